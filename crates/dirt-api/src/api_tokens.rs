@@ -0,0 +1,375 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use dirt_core::db::{AccessTokenRepository, LibSqlAccessTokenRepository};
+use dirt_core::models::{AccessScope, AccessToken};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::managed_db::ManagedDatabase;
+
+/// Prefix that distinguishes Dirt API tokens from Supabase access tokens.
+pub const API_TOKEN_PREFIX: &str = "dirt_";
+
+pub const MAX_API_TOKEN_NAME_LEN: usize = 100;
+pub const MAX_API_TOKEN_EXPIRY_DAYS: u32 = 365;
+
+const API_TOKEN_ISSUER: &str = "dirt-api";
+
+/// Minimum seconds between `last_used_at` writes for the same token.
+const LAST_USED_TOUCH_INTERVAL_SECS: i64 = 300;
+
+/// What an authenticated request is allowed to do.
+///
+/// Supabase sessions carry the user's full access; API tokens are limited to
+/// the scopes they were issued with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Grant {
+    Session,
+    ApiToken(Vec<AccessScope>),
+}
+
+impl Grant {
+    pub fn require(&self, scope: AccessScope) -> Result<(), AppError> {
+        match self {
+            Self::Session => Ok(()),
+            Self::ApiToken(scopes) if scopes.contains(&scope) => Ok(()),
+            Self::ApiToken(_) => Err(AppError::forbidden(format!(
+                "API token is missing the `{scope}` scope"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ApiTokenClaims {
+    jti: String,
+    sub: String,
+    iss: String,
+    iat: i64,
+    exp: i64,
+    scopes: Vec<AccessScope>,
+}
+
+/// Token metadata returned to the owner (never includes the secret).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiTokenSummary {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<AccessScope>,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub last_used_at: Option<i64>,
+    pub revoked_at: Option<i64>,
+}
+
+impl From<AccessToken> for ApiTokenSummary {
+    fn from(token: AccessToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            scopes: token.scopes,
+            created_at: token.created_at,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            revoked_at: token.revoked_at,
+        }
+    }
+}
+
+/// A freshly signed token and the record to store for it.
+#[derive(Debug, Clone)]
+pub struct IssuedApiToken {
+    pub token: String,
+    pub record: AccessToken,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedApiToken {
+    pub token_id: String,
+    pub user_id: String,
+    pub scopes: Vec<AccessScope>,
+}
+
+/// Issues and verifies HMAC-signed personal access tokens.
+#[derive(Clone)]
+pub struct ApiTokenService {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    default_ttl: Duration,
+    clock_skew: Duration,
+}
+
+impl ApiTokenService {
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        let secret = config.api_token_signing_secret.as_deref()?;
+        Some(Self {
+            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            default_ttl: config.api_token_ttl,
+            clock_skew: config.auth_clock_skew,
+        })
+    }
+
+    pub fn issue(
+        &self,
+        user_id: &str,
+        name: &str,
+        scopes: &[AccessScope],
+        expires_in_days: Option<u32>,
+    ) -> Result<IssuedApiToken, AppError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_API_TOKEN_NAME_LEN {
+            return Err(AppError::bad_request(format!(
+                "API token name must be 1-{MAX_API_TOKEN_NAME_LEN} characters"
+            )));
+        }
+
+        let mut scopes = scopes.to_vec();
+        scopes.sort();
+        scopes.dedup();
+        if scopes.is_empty() {
+            return Err(AppError::bad_request(
+                "API token must request at least one scope",
+            ));
+        }
+
+        let ttl_secs = match expires_in_days {
+            Some(days) if (1..=MAX_API_TOKEN_EXPIRY_DAYS).contains(&days) => {
+                i64::from(days) * 86_400
+            }
+            Some(_) => {
+                return Err(AppError::bad_request(format!(
+                    "expires_in_days must be in [1, {MAX_API_TOKEN_EXPIRY_DAYS}]"
+                )))
+            }
+            None => i64::try_from(self.default_ttl.as_secs()).unwrap_or(i64::MAX),
+        };
+
+        let issued_at = Utc::now().timestamp();
+        let expires_at = issued_at.saturating_add(ttl_secs);
+        let id = Uuid::now_v7().to_string();
+        let claims = ApiTokenClaims {
+            jti: id.clone(),
+            sub: user_id.to_string(),
+            iss: API_TOKEN_ISSUER.to_string(),
+            iat: issued_at,
+            exp: expires_at,
+            scopes: scopes.clone(),
+        };
+        let jwt = encode(&Header::new(Algorithm::HS256), &claims, &self.encoding_key)
+            .map_err(|error| AppError::internal(format!("Failed to sign API token: {error}")))?;
+
+        Ok(IssuedApiToken {
+            token: format!("{API_TOKEN_PREFIX}{jwt}"),
+            record: AccessToken {
+                id,
+                user_id: user_id.to_string(),
+                name: name.to_string(),
+                scopes,
+                created_at: issued_at,
+                expires_at,
+                last_used_at: None,
+                revoked_at: None,
+            },
+        })
+    }
+
+    /// Checks the token signature and expiry. Revocation is checked by [`ApiTokenRegistry`].
+    pub fn verify(&self, token: &str) -> Result<VerifiedApiToken, AppError> {
+        let jwt = token
+            .strip_prefix(API_TOKEN_PREFIX)
+            .ok_or_else(|| AppError::unauthorized("Token is not a Dirt API token"))?;
+
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.validate_aud = false;
+        validation.leeway = self.clock_skew.as_secs();
+        validation.set_issuer(&[API_TOKEN_ISSUER]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+
+        let claims = decode::<ApiTokenClaims>(jwt, &self.decoding_key, &validation)
+            .map_err(|_| AppError::unauthorized("API token is invalid or expired"))?
+            .claims;
+        if claims.sub.trim().is_empty() || claims.jti.trim().is_empty() {
+            return Err(AppError::unauthorized(
+                "API token is missing required claims",
+            ));
+        }
+
+        Ok(VerifiedApiToken {
+            token_id: claims.jti,
+            user_id: claims.sub,
+            scopes: claims.scopes,
+        })
+    }
+}
+
+/// Stored token records, used for listing, revocation, and last-use tracking.
+pub struct ApiTokenRegistry {
+    db: Arc<ManagedDatabase>,
+}
+
+impl ApiTokenRegistry {
+    pub const fn new(db: Arc<ManagedDatabase>) -> Self {
+        Self { db }
+    }
+
+    pub async fn record(&self, token: &AccessToken) -> Result<(), AppError> {
+        let db = self.db.connect().await?;
+        LibSqlAccessTokenRepository::new(db.connection())
+            .create_access_token(token)
+            .await
+            .map_err(AppError::core("API token registry"))
+    }
+
+    pub async fn list(&self, user_id: &str) -> Result<Vec<AccessToken>, AppError> {
+        let db = self.db.connect().await?;
+        LibSqlAccessTokenRepository::new(db.connection())
+            .list_access_tokens(user_id)
+            .await
+            .map_err(AppError::core("API token registry"))
+    }
+
+    pub async fn revoke(&self, user_id: &str, id: &str) -> Result<(), AppError> {
+        let db = self.db.connect().await?;
+        let revoked = LibSqlAccessTokenRepository::new(db.connection())
+            .revoke_access_token(user_id, id, Utc::now().timestamp())
+            .await
+            .map_err(AppError::core("API token registry"))?;
+        if revoked {
+            Ok(())
+        } else {
+            Err(AppError::not_found(format!(
+                "API token {id} does not exist or is already revoked"
+            )))
+        }
+    }
+
+    /// Rejects tokens that were revoked or never recorded, and notes the use.
+    pub async fn authorize(&self, verified: &VerifiedApiToken) -> Result<(), AppError> {
+        let db = self.db.connect().await?;
+        let repo = LibSqlAccessTokenRepository::new(db.connection());
+        let now = Utc::now().timestamp();
+        let record = repo
+            .get_access_token(&verified.token_id)
+            .await
+            .map_err(AppError::core("API token registry"))?
+            .filter(|record| record.user_id == verified.user_id && record.is_active(now))
+            .ok_or_else(|| AppError::unauthorized("API token has been revoked or has expired"))?;
+
+        repo.touch_access_token(&record.id, now, LAST_USED_TOUCH_INTERVAL_SECS)
+            .await
+            .map_err(AppError::core("API token registry"))
+    }
+}
+
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(API_TOKEN_PREFIX)
+}
+
+pub fn parse_scopes(raw: &[String]) -> Result<Vec<AccessScope>, AppError> {
+    raw.iter()
+        .map(|scope| {
+            scope
+                .parse::<AccessScope>()
+                .map_err(|_| AppError::bad_request(format!("Unknown API token scope `{scope}`")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(secret: &str) -> ApiTokenService {
+        ApiTokenService {
+            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            default_ttl: Duration::from_secs(3_600),
+            clock_skew: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn issued_token_round_trips_scopes() {
+        let service = service("0123456789abcdef0123456789abcdef");
+        let issued = service
+            .issue(
+                "user-a",
+                "backup script",
+                &[
+                    AccessScope::MediaRead,
+                    AccessScope::NotesRead,
+                    AccessScope::NotesRead,
+                ],
+                None,
+            )
+            .unwrap();
+        assert!(is_api_token(&issued.token));
+        assert_eq!(
+            issued.record.scopes,
+            vec![AccessScope::NotesRead, AccessScope::MediaRead]
+        );
+        assert_eq!(issued.record.name, "backup script");
+        assert_eq!(issued.record.expires_at - issued.record.created_at, 3_600);
+
+        let verified = service.verify(&issued.token).unwrap();
+        assert_eq!(verified.token_id, issued.record.id);
+        assert_eq!(verified.user_id, "user-a");
+        assert_eq!(verified.scopes, issued.record.scopes);
+    }
+
+    #[test]
+    fn verify_rejects_tokens_signed_with_another_secret() {
+        let issued = service("0123456789abcdef0123456789abcdef")
+            .issue("user-a", "script", &[AccessScope::NotesRead], None)
+            .unwrap();
+        let err = service("fedcba9876543210fedcba9876543210")
+            .verify(&issued.token)
+            .unwrap_err();
+        assert!(matches!(err, AppError::Unauthorized(_)));
+    }
+
+    #[test]
+    fn issue_validates_name_scopes_and_expiry() {
+        let service = service("0123456789abcdef0123456789abcdef");
+        assert!(service.issue("user-a", "script", &[], None).is_err());
+        assert!(service
+            .issue("user-a", "  ", &[AccessScope::NotesRead], None)
+            .is_err());
+        assert!(service
+            .issue("user-a", "script", &[AccessScope::NotesRead], Some(0))
+            .is_err());
+
+        let issued = service
+            .issue("user-a", "script", &[AccessScope::NotesRead], Some(7))
+            .unwrap();
+        assert_eq!(
+            issued.record.expires_at - issued.record.created_at,
+            7 * 86_400
+        );
+    }
+
+    #[test]
+    fn grant_enforces_token_scopes() {
+        assert!(Grant::Session.require(AccessScope::NotesWrite).is_ok());
+        let grant = Grant::ApiToken(vec![AccessScope::NotesRead]);
+        assert!(grant.require(AccessScope::NotesRead).is_ok());
+        let err = grant.require(AccessScope::MediaWrite).unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+    }
+
+    #[test]
+    fn scopes_parse_from_wire_names() {
+        assert_eq!(
+            parse_scopes(&["notes:write".to_string(), "media:read".to_string()]).unwrap(),
+            vec![AccessScope::NotesWrite, AccessScope::MediaRead]
+        );
+        let err = parse_scopes(&["admin".to_string()]).unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+    }
+}
@@ -16,6 +16,7 @@ use crate::error::AppError;
 pub struct AuthenticatedUser {
     pub user_id: String,
     pub session_id: Option<String>,
+    pub email: Option<String>,
 }
 
 #[derive(Clone)]
@@ -73,6 +74,10 @@ impl SupabaseJwtVerifier {
         Ok(AuthenticatedUser {
             user_id: decoded.claims.sub,
             session_id: decoded.claims.session_id.or(decoded.claims.jti),
+            email: decoded
+                .claims
+                .email
+                .filter(|email| !email.trim().is_empty()),
         })
     }
 
@@ -151,6 +156,7 @@ struct SupabaseClaims {
     nbf: Option<i64>,
     jti: Option<String>,
     session_id: Option<String>,
+    email: Option<String>,
 }
 
 fn validate_temporal_claims(
@@ -336,6 +342,7 @@ mod tests {
             nbf: None,
             jti: None,
             session_id: None,
+            email: None,
         };
         let err =
             validate_temporal_claims(&claims, std::time::Duration::from_secs(60)).unwrap_err();
@@ -354,6 +361,7 @@ mod tests {
             nbf: None,
             jti: None,
             session_id: None,
+            email: None,
         };
         let err =
             validate_temporal_claims(&claims, std::time::Duration::from_secs(30)).unwrap_err();
@@ -1,10 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fmt;
 use std::time::Duration;
 
+use dirt_core::config::{ClientPlatform, ReleaseChannel};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::scanner::CLAMD_SCHEME;
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("Missing required environment variable: {0}")]
@@ -25,6 +29,9 @@ pub struct AppConfig {
     pub bootstrap_manifest_version: String,
     pub bootstrap_cache_max_age_secs: u64,
     pub bootstrap_public_api_base_url: Option<String>,
+    pub bootstrap_min_client_version: Option<String>,
+    pub bootstrap_channels: BTreeMap<String, BootstrapOverrides>,
+    pub bootstrap_platforms: BTreeMap<String, BootstrapOverrides>,
     pub turso_api_url: String,
     pub turso_organization_slug: String,
     pub turso_database_name: String,
@@ -32,12 +39,37 @@ pub struct AppConfig {
     pub turso_platform_api_token: Option<String>,
     pub turso_static_auth_token: Option<String>,
     pub turso_token_ttl: Duration,
+    pub sync_credential_epoch: u64,
     pub media_url_ttl: Duration,
+    pub media_quota_bytes: Option<i64>,
     pub auth_clock_skew: Duration,
     pub rate_limit_window: Duration,
     pub sync_token_rate_limit_per_window: u32,
     pub media_presign_rate_limit_per_window: u32,
+    pub notes_api_rate_limit_per_window: u32,
+    pub api_token_signing_secret: Option<String>,
+    pub api_token_ttl: Duration,
+    pub cors_allowed_origins: Vec<String>,
+    pub cors_max_age: Duration,
     pub r2: Option<R2RuntimeConfig>,
+    pub media_scanner: Option<MediaScannerConfig>,
+    pub digest_email: Option<DigestEmailConfig>,
+}
+
+/// Bootstrap manifest values replaced for one release channel or platform
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootstrapOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supabase_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supabase_anon_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_base_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turso_sync_token_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_client_version: Option<String>,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -60,6 +92,49 @@ impl fmt::Debug for R2RuntimeConfig {
     }
 }
 
+#[derive(Clone, PartialEq, Eq)]
+pub struct MediaScannerConfig {
+    pub url: String,
+    pub auth_token: Option<String>,
+    pub timeout: Duration,
+    pub max_bytes: u64,
+}
+
+impl fmt::Debug for MediaScannerConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("MediaScannerConfig")
+            .field("url", &self.url)
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("timeout", &self.timeout)
+            .field("max_bytes", &self.max_bytes)
+            .finish()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct DigestEmailConfig {
+    pub api_url: String,
+    pub api_key: String,
+    pub from: String,
+    pub timeout: Duration,
+}
+
+impl fmt::Debug for DigestEmailConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("DigestEmailConfig")
+            .field("api_url", &self.api_url)
+            .field("api_key", &"[REDACTED]")
+            .field("from", &self.from)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
 impl fmt::Debug for AppConfig {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter
@@ -83,6 +158,12 @@ impl fmt::Debug for AppConfig {
                 "bootstrap_public_api_base_url",
                 &self.bootstrap_public_api_base_url,
             )
+            .field(
+                "bootstrap_min_client_version",
+                &self.bootstrap_min_client_version,
+            )
+            .field("bootstrap_channels", &self.bootstrap_channels)
+            .field("bootstrap_platforms", &self.bootstrap_platforms)
             .field("turso_api_url", &self.turso_api_url)
             .field("turso_organization_slug", &self.turso_organization_slug)
             .field("turso_database_name", &self.turso_database_name)
@@ -96,7 +177,9 @@ impl fmt::Debug for AppConfig {
                 &self.turso_static_auth_token.as_ref().map(|_| "[REDACTED]"),
             )
             .field("turso_token_ttl", &self.turso_token_ttl)
+            .field("sync_credential_epoch", &self.sync_credential_epoch)
             .field("media_url_ttl", &self.media_url_ttl)
+            .field("media_quota_bytes", &self.media_quota_bytes)
             .field("auth_clock_skew", &self.auth_clock_skew)
             .field("rate_limit_window", &self.rate_limit_window)
             .field(
@@ -107,7 +190,20 @@ impl fmt::Debug for AppConfig {
                 "media_presign_rate_limit_per_window",
                 &self.media_presign_rate_limit_per_window,
             )
+            .field(
+                "notes_api_rate_limit_per_window",
+                &self.notes_api_rate_limit_per_window,
+            )
+            .field(
+                "api_token_signing_secret",
+                &self.api_token_signing_secret.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("api_token_ttl", &self.api_token_ttl)
+            .field("cors_allowed_origins", &self.cors_allowed_origins)
+            .field("cors_max_age", &self.cors_max_age)
             .field("r2", &self.r2)
+            .field("media_scanner", &self.media_scanner)
+            .field("digest_email", &self.digest_email)
             .finish()
     }
 }
@@ -186,6 +282,19 @@ impl AppConfig {
             }
         }
 
+        let bootstrap_min_client_version =
+            optional_trimmed(&lookup, "BOOTSTRAP_MIN_CLIENT_VERSION");
+        if let Some(version) = bootstrap_min_client_version.as_deref() {
+            validate_client_version(version, "BOOTSTRAP_MIN_CLIENT_VERSION")?;
+        }
+        let bootstrap_channels = parse_bootstrap_overrides(&lookup, "BOOTSTRAP_CHANNELS", |key| {
+            ReleaseChannel::parse(key).is_some()
+        })?;
+        let bootstrap_platforms =
+            parse_bootstrap_overrides(&lookup, "BOOTSTRAP_PLATFORMS", |key| {
+                ClientPlatform::parse(key).is_some()
+            })?;
+
         let turso_api_url = value_or_default(&lookup, "TURSO_API_URL", "https://api.turso.tech");
         if !is_http_url(&turso_api_url) {
             return Err(ConfigError::Invalid(
@@ -218,6 +327,14 @@ impl AppConfig {
             ));
         }
 
+        let sync_credential_epoch = value_or_default(&lookup, "SYNC_CREDENTIAL_EPOCH", "0")
+            .parse::<u64>()
+            .map_err(|_| {
+                ConfigError::Invalid(
+                    "SYNC_CREDENTIAL_EPOCH must be a non-negative integer".to_string(),
+                )
+            })?;
+
         let media_ttl_secs = value_or_default(&lookup, "MEDIA_SIGNED_URL_TTL_SECS", "600")
             .parse::<u64>()
             .map_err(|_| {
@@ -231,6 +348,19 @@ impl AppConfig {
             ));
         }
 
+        let media_quota_bytes = optional_trimmed(&lookup, "MEDIA_QUOTA_BYTES")
+            .map(|raw| {
+                raw.parse::<i64>()
+                    .ok()
+                    .filter(|bytes| *bytes > 0)
+                    .ok_or_else(|| {
+                        ConfigError::Invalid(
+                            "MEDIA_QUOTA_BYTES must be a positive integer".to_string(),
+                        )
+                    })
+            })
+            .transpose()?;
+
         let auth_clock_skew_secs = value_or_default(&lookup, "AUTH_CLOCK_SKEW_SECS", "60")
             .parse::<u64>()
             .map_err(|_| {
@@ -287,7 +417,62 @@ impl AppConfig {
             ));
         }
 
+        let notes_api_rate_limit_per_window =
+            value_or_default(&lookup, "NOTES_API_RATE_LIMIT_PER_WINDOW", "120")
+                .parse::<u32>()
+                .map_err(|_| {
+                    ConfigError::Invalid(
+                        "NOTES_API_RATE_LIMIT_PER_WINDOW must be an integer in [1, 5000]"
+                            .to_string(),
+                    )
+                })?;
+        if !(1..=5_000).contains(&notes_api_rate_limit_per_window) {
+            return Err(ConfigError::Invalid(
+                "NOTES_API_RATE_LIMIT_PER_WINDOW must be in [1, 5000]".to_string(),
+            ));
+        }
+
+        let api_token_signing_secret = optional_trimmed(&lookup, "API_TOKEN_SIGNING_SECRET");
+        if api_token_signing_secret
+            .as_ref()
+            .is_some_and(|secret| secret.len() < 32)
+        {
+            return Err(ConfigError::Invalid(
+                "API_TOKEN_SIGNING_SECRET must be at least 32 characters".to_string(),
+            ));
+        }
+
+        let api_token_ttl_secs = value_or_default(&lookup, "API_TOKEN_TTL_SECS", "2592000")
+            .parse::<u64>()
+            .map_err(|_| {
+                ConfigError::Invalid(
+                    "API_TOKEN_TTL_SECS must be an integer in [300, 31536000]".to_string(),
+                )
+            })?;
+        if !(300..=31_536_000).contains(&api_token_ttl_secs) {
+            return Err(ConfigError::Invalid(
+                "API_TOKEN_TTL_SECS must be in [300, 31536000]".to_string(),
+            ));
+        }
+
+        let cors_allowed_origins = parse_cors_allowed_origins(&lookup)?;
+
+        let cors_max_age_secs = value_or_default(&lookup, "CORS_MAX_AGE_SECS", "600")
+            .parse::<u64>()
+            .map_err(|_| {
+                ConfigError::Invalid(
+                    "CORS_MAX_AGE_SECS must be an integer in [0, 86400]".to_string(),
+                )
+            })?;
+        if cors_max_age_secs > 86_400 {
+            return Err(ConfigError::Invalid(
+                "CORS_MAX_AGE_SECS must be in [0, 86400]".to_string(),
+            ));
+        }
+
         let r2 = parse_r2_config(&lookup)?;
+        let media_scanner = parse_media_scanner_config(&lookup)?;
+        let digest_email = parse_digest_email_config(&lookup)?;
 
         Ok(Self {
             bind_addr,
@@ -300,6 +485,9 @@ impl AppConfig {
             bootstrap_manifest_version,
             bootstrap_cache_max_age_secs,
             bootstrap_public_api_base_url,
+            bootstrap_min_client_version,
+            bootstrap_channels,
+            bootstrap_platforms,
             turso_api_url,
             turso_organization_slug,
             turso_database_name,
@@ -307,16 +495,126 @@ impl AppConfig {
             turso_platform_api_token,
             turso_static_auth_token,
             turso_token_ttl: Duration::from_secs(turso_ttl_secs),
+            sync_credential_epoch,
             media_url_ttl: Duration::from_secs(media_ttl_secs),
+            media_quota_bytes,
             auth_clock_skew: Duration::from_secs(auth_clock_skew_secs),
             rate_limit_window: Duration::from_secs(rate_limit_window_secs),
             sync_token_rate_limit_per_window,
             media_presign_rate_limit_per_window,
+            notes_api_rate_limit_per_window,
+            api_token_signing_secret,
+            api_token_ttl: Duration::from_secs(api_token_ttl_secs),
+            cors_allowed_origins,
+            cors_max_age: Duration::from_secs(cors_max_age_secs),
             r2,
+            media_scanner,
+            digest_email,
         })
     }
 }
 
+/// Parse a JSON object of bootstrap manifest overrides keyed by release
+/// channel or platform, e.g. `{"beta": {"api_base_url": "https://beta.example.com"}}`.
+fn parse_bootstrap_overrides(
+    lookup: impl Fn(&str) -> Option<String>,
+    name: &str,
+    is_known_key: impl Fn(&str) -> bool,
+) -> Result<BTreeMap<String, BootstrapOverrides>, ConfigError> {
+    let Some(raw) = optional_trimmed(lookup, name) else {
+        return Ok(BTreeMap::new());
+    };
+    let parsed: BTreeMap<String, BootstrapOverrides> = serde_json::from_str(&raw)
+        .map_err(|error| ConfigError::Invalid(format!("{name} must be a JSON object: {error}")))?;
+
+    let mut overrides = BTreeMap::new();
+    for (key, mut value) in parsed {
+        let key = key.trim().to_ascii_lowercase();
+        if !is_known_key(&key) {
+            return Err(ConfigError::Invalid(format!(
+                "{name} has unknown key '{key}'"
+            )));
+        }
+        for (field, url) in [
+            ("supabase_url", &mut value.supabase_url),
+            ("api_base_url", &mut value.api_base_url),
+            (
+                "turso_sync_token_endpoint",
+                &mut value.turso_sync_token_endpoint,
+            ),
+        ] {
+            if let Some(current) = url.as_deref() {
+                if !is_http_url(current.trim()) {
+                    return Err(ConfigError::Invalid(format!(
+                        "{name}.{key}.{field} must start with http:// or https://"
+                    )));
+                }
+                *url = Some(trim_trailing(current.trim()).to_string());
+            }
+        }
+        if let Some(version) = value.min_client_version.as_deref() {
+            validate_client_version(version, &format!("{name}.{key}.min_client_version"))?;
+        }
+        overrides.insert(key, value);
+    }
+    Ok(overrides)
+}
+
+/// Minimum client versions must be dotted numbers such as `0.4.0`.
+fn validate_client_version(version: &str, name: &str) -> Result<(), ConfigError> {
+    let numeric = version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .all(|part| !part.is_empty() && part.chars().all(|ch| ch.is_ascii_digit()));
+    if numeric {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid(format!(
+            "{name} must be a version such as 0.4.0"
+        )))
+    }
+}
+
+/// Parse the comma-separated browser origins allowed to call the API.
+///
+/// `*` allows any origin and must be the only entry. Unset means no
+/// cross-origin browser access.
+fn parse_cors_allowed_origins(
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<String>, ConfigError> {
+    let Some(raw) = optional_trimmed(lookup, "CORS_ALLOWED_ORIGINS") else {
+        return Ok(Vec::new());
+    };
+
+    let origins: Vec<String> = raw
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(|origin| trim_trailing(origin).to_string())
+        .collect();
+
+    if origins.iter().any(|origin| origin == "*") {
+        if origins.len() > 1 {
+            return Err(ConfigError::Invalid(
+                "CORS_ALLOWED_ORIGINS cannot combine '*' with explicit origins".to_string(),
+            ));
+        }
+        return Ok(origins);
+    }
+
+    for origin in &origins {
+        let scheme_end = origin.find("://").map_or(0, |index| index + 3);
+        if !is_http_url(origin) || origin[scheme_end..].contains('/') {
+            return Err(ConfigError::Invalid(format!(
+                "CORS_ALLOWED_ORIGINS entry '{origin}' must be an http(s) origin without a path"
+            )));
+        }
+    }
+
+    Ok(origins)
+}
+
 fn parse_r2_config(
     lookup: impl Fn(&str) -> Option<String>,
 ) -> Result<Option<R2RuntimeConfig>, ConfigError> {
@@ -347,6 +645,113 @@ fn parse_r2_config(
     }))
 }
 
+/// Parse the optional external scanner used by `/v1/media/commit`.
+///
+/// Unset `MEDIA_SCANNER_URL` disables scanning; committed uploads then stay unscanned.
+/// A `clamd://host:port` URL streams uploads to a ClamAV daemon instead of a
+/// webhook.
+fn parse_media_scanner_config(
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<Option<MediaScannerConfig>, ConfigError> {
+    let Some(url) = optional_trimmed(&lookup, "MEDIA_SCANNER_URL") else {
+        return Ok(None);
+    };
+    if !is_http_url(&url) && !is_clamd_url(&url) {
+        return Err(ConfigError::Invalid(
+            "MEDIA_SCANNER_URL must start with http://, https://, or clamd://".to_string(),
+        ));
+    }
+    let auth_token = optional_trimmed(&lookup, "MEDIA_SCANNER_TOKEN");
+
+    let timeout_secs = value_or_default(&lookup, "MEDIA_SCANNER_TIMEOUT_SECS", "30")
+        .parse::<u64>()
+        .map_err(|_| {
+            ConfigError::Invalid(
+                "MEDIA_SCANNER_TIMEOUT_SECS must be an integer in [1, 300]".to_string(),
+            )
+        })?;
+    if !(1..=300).contains(&timeout_secs) {
+        return Err(ConfigError::Invalid(
+            "MEDIA_SCANNER_TIMEOUT_SECS must be in [1, 300]".to_string(),
+        ));
+    }
+
+    let max_bytes = value_or_default(&lookup, "MEDIA_SCANNER_MAX_BYTES", "52428800")
+        .parse::<u64>()
+        .map_err(|_| {
+            ConfigError::Invalid(
+                "MEDIA_SCANNER_MAX_BYTES must be an integer in [1024, 1073741824]".to_string(),
+            )
+        })?;
+    if !(1_024..=1_073_741_824).contains(&max_bytes) {
+        return Err(ConfigError::Invalid(
+            "MEDIA_SCANNER_MAX_BYTES must be in [1024, 1073741824]".to_string(),
+        ));
+    }
+
+    Ok(Some(MediaScannerConfig {
+        url,
+        auth_token,
+        timeout: Duration::from_secs(timeout_secs),
+        max_bytes,
+    }))
+}
+
+fn is_clamd_url(value: &str) -> bool {
+    value.strip_prefix(CLAMD_SCHEME).is_some_and(|address| {
+        address
+            .rsplit_once(':')
+            .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok())
+    })
+}
+
+/// Parse the optional email API used by `/v1/digest/email`.
+///
+/// Digest email stays disabled unless both `DIGEST_EMAIL_API_KEY` and
+/// `DIGEST_EMAIL_FROM` are set.
+fn parse_digest_email_config(
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<Option<DigestEmailConfig>, ConfigError> {
+    let api_key = optional_trimmed(&lookup, "DIGEST_EMAIL_API_KEY");
+    let from = optional_trimmed(&lookup, "DIGEST_EMAIL_FROM");
+    if api_key.is_none() && from.is_none() {
+        return Ok(None);
+    }
+    let api_key = api_key.ok_or(ConfigError::MissingVar("DIGEST_EMAIL_API_KEY"))?;
+    let from = from.ok_or(ConfigError::MissingVar("DIGEST_EMAIL_FROM"))?;
+
+    let api_url = value_or_default(
+        &lookup,
+        "DIGEST_EMAIL_API_URL",
+        "https://api.resend.com/emails",
+    );
+    if !is_http_url(&api_url) {
+        return Err(ConfigError::Invalid(
+            "DIGEST_EMAIL_API_URL must start with http:// or https://".to_string(),
+        ));
+    }
+
+    let timeout_secs = value_or_default(&lookup, "DIGEST_EMAIL_TIMEOUT_SECS", "15")
+        .parse::<u64>()
+        .map_err(|_| {
+            ConfigError::Invalid(
+                "DIGEST_EMAIL_TIMEOUT_SECS must be an integer in [1, 120]".to_string(),
+            )
+        })?;
+    if !(1..=120).contains(&timeout_secs) {
+        return Err(ConfigError::Invalid(
+            "DIGEST_EMAIL_TIMEOUT_SECS must be in [1, 120]".to_string(),
+        ));
+    }
+
+    Ok(Some(DigestEmailConfig {
+        api_url,
+        api_key,
+        from,
+        timeout: Duration::from_secs(timeout_secs),
+    }))
+}
+
 fn value_or_default(lookup: impl Fn(&str) -> Option<String>, name: &str, default: &str) -> String {
     optional_trimmed(lookup, name).unwrap_or_else(|| default.to_string())
 }
@@ -383,6 +788,18 @@ mod tests {
 
     use super::*;
 
+    /// Settings every config needs, with a static Turso token
+    fn required_config_map() -> HashMap<&'static str, &'static str> {
+        HashMap::from([
+            ("SUPABASE_URL", "https://project.supabase.co"),
+            ("SUPABASE_ANON_KEY", "public-anon-key"),
+            ("TURSO_ORGANIZATION_SLUG", "org"),
+            ("TURSO_DATABASE_NAME", "db"),
+            ("TURSO_DATABASE_URL", "libsql://db.turso.io"),
+            ("TURSO_AUTH_TOKEN", "static-db-token"),
+        ])
+    }
+
     #[test]
     fn config_requires_minimum_secrets() {
         let map: HashMap<&str, &str> = HashMap::new();
@@ -393,12 +810,7 @@ mod tests {
 
     #[test]
     fn config_redacts_sensitive_debug_fields() {
-        let mut map = HashMap::new();
-        map.insert("SUPABASE_URL", "https://project.supabase.co");
-        map.insert("SUPABASE_ANON_KEY", "public-anon-key");
-        map.insert("TURSO_ORGANIZATION_SLUG", "org");
-        map.insert("TURSO_DATABASE_NAME", "db");
-        map.insert("TURSO_DATABASE_URL", "libsql://db.turso.io");
+        let mut map = required_config_map();
         map.insert("TURSO_PLATFORM_API_TOKEN", "sensitive-platform-token");
         map.insert("TURSO_AUTH_TOKEN", "sensitive-static-token");
         map.insert("R2_ACCOUNT_ID", "acc");
@@ -418,13 +830,7 @@ mod tests {
 
     #[test]
     fn config_allows_static_turso_token_without_platform_token() {
-        let mut map = HashMap::new();
-        map.insert("SUPABASE_URL", "https://project.supabase.co");
-        map.insert("SUPABASE_ANON_KEY", "public-anon-key");
-        map.insert("TURSO_ORGANIZATION_SLUG", "org");
-        map.insert("TURSO_DATABASE_NAME", "db");
-        map.insert("TURSO_DATABASE_URL", "libsql://db.turso.io");
-        map.insert("TURSO_AUTH_TOKEN", "static-db-token");
+        let map = required_config_map();
 
         let config =
             AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
@@ -434,4 +840,225 @@ mod tests {
             Some("static-db-token")
         );
     }
+
+    #[test]
+    fn config_parses_cors_allowed_origins() {
+        let mut map = required_config_map();
+
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert!(config.cors_allowed_origins.is_empty());
+        assert_eq!(config.cors_max_age, Duration::from_secs(600));
+
+        map.insert(
+            "CORS_ALLOWED_ORIGINS",
+            " https://app.dirt.dev/ , http://localhost:5173",
+        );
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert_eq!(
+            config.cors_allowed_origins,
+            vec![
+                "https://app.dirt.dev".to_string(),
+                "http://localhost:5173".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn config_rejects_invalid_cors_origins() {
+        let mut map = required_config_map();
+
+        for invalid in [
+            "*, https://app.dirt.dev",
+            "app.dirt.dev",
+            "https://app.dirt.dev/web",
+        ] {
+            map.insert("CORS_ALLOWED_ORIGINS", invalid);
+            let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("CORS_ALLOWED_ORIGINS"),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn config_validates_api_token_settings() {
+        let mut map = required_config_map();
+
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert!(config.api_token_signing_secret.is_none());
+        assert_eq!(config.api_token_ttl, Duration::from_secs(2_592_000));
+        assert_eq!(config.notes_api_rate_limit_per_window, 120);
+
+        map.insert("API_TOKEN_SIGNING_SECRET", "too-short");
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("API_TOKEN_SIGNING_SECRET"));
+
+        map.insert(
+            "API_TOKEN_SIGNING_SECRET",
+            "sensitive-api-token-signing-secret-value",
+        );
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert!(!format!("{config:?}").contains("sensitive-api-token-signing-secret-value"));
+    }
+
+    #[test]
+    fn config_validates_media_scanner_settings() {
+        let mut map = required_config_map();
+
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert!(config.media_scanner.is_none());
+
+        map.insert("MEDIA_SCANNER_URL", "scanner.internal/scan");
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("MEDIA_SCANNER_URL"));
+
+        map.insert("MEDIA_SCANNER_URL", "clamd://clamav.internal");
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("MEDIA_SCANNER_URL"));
+
+        map.insert("MEDIA_SCANNER_URL", "clamd://clamav.internal:3310");
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert_eq!(
+            config.media_scanner.as_ref().unwrap().url,
+            "clamd://clamav.internal:3310"
+        );
+
+        map.insert("MEDIA_SCANNER_URL", "https://scanner.internal/scan");
+        map.insert("MEDIA_SCANNER_TIMEOUT_SECS", "0");
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("MEDIA_SCANNER_TIMEOUT_SECS"));
+
+        map.remove("MEDIA_SCANNER_TIMEOUT_SECS");
+        map.insert("MEDIA_SCANNER_TOKEN", "sensitive-scanner-token");
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        let scanner = config.media_scanner.as_ref().unwrap();
+        assert_eq!(scanner.timeout, Duration::from_secs(30));
+        assert_eq!(scanner.max_bytes, 52_428_800);
+        assert!(!format!("{config:?}").contains("sensitive-scanner-token"));
+    }
+
+    #[test]
+    fn config_validates_digest_email_settings() {
+        let mut map = required_config_map();
+
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert!(config.digest_email.is_none());
+
+        map.insert("DIGEST_EMAIL_API_KEY", "sensitive-email-api-key");
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("DIGEST_EMAIL_FROM"));
+
+        map.insert("DIGEST_EMAIL_FROM", "Dirt <digest@dirt.dev>");
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        let email = config.digest_email.as_ref().unwrap();
+        assert_eq!(email.api_url, "https://api.resend.com/emails");
+        assert_eq!(email.timeout, Duration::from_secs(15));
+        assert!(!format!("{config:?}").contains("sensitive-email-api-key"));
+
+        map.insert("DIGEST_EMAIL_TIMEOUT_SECS", "0");
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("DIGEST_EMAIL_TIMEOUT_SECS"));
+    }
+
+    #[test]
+    fn config_parses_sync_credential_epoch() {
+        let mut map = required_config_map();
+
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert_eq!(config.sync_credential_epoch, 0);
+
+        map.insert("SYNC_CREDENTIAL_EPOCH", "3");
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert_eq!(config.sync_credential_epoch, 3);
+
+        map.insert("SYNC_CREDENTIAL_EPOCH", "-1");
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("SYNC_CREDENTIAL_EPOCH"));
+    }
+
+    #[test]
+    fn config_parses_media_quota() {
+        let mut map = required_config_map();
+
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert_eq!(config.media_quota_bytes, None);
+
+        map.insert("MEDIA_QUOTA_BYTES", "5368709120");
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert_eq!(config.media_quota_bytes, Some(5_368_709_120));
+
+        map.insert("MEDIA_QUOTA_BYTES", "0");
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("MEDIA_QUOTA_BYTES"));
+    }
+
+    #[test]
+    fn config_parses_bootstrap_rollout_settings() {
+        let mut map = required_config_map();
+
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert!(config.bootstrap_min_client_version.is_none());
+        assert!(config.bootstrap_channels.is_empty());
+
+        map.insert("BOOTSTRAP_MIN_CLIENT_VERSION", "0.4.0");
+        map.insert(
+            "BOOTSTRAP_CHANNELS",
+            r#"{"Beta": {"api_base_url": "https://beta.dirt.dev/"}}"#,
+        );
+        map.insert(
+            "BOOTSTRAP_PLATFORMS",
+            r#"{"mobile": {"min_client_version": "0.5.0"}}"#,
+        );
+        let config =
+            AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string())).unwrap();
+        assert_eq!(
+            config.bootstrap_min_client_version.as_deref(),
+            Some("0.4.0")
+        );
+        assert_eq!(
+            config.bootstrap_channels["beta"].api_base_url.as_deref(),
+            Some("https://beta.dirt.dev")
+        );
+        assert_eq!(
+            config.bootstrap_platforms["mobile"]
+                .min_client_version
+                .as_deref(),
+            Some("0.5.0")
+        );
+
+        map.insert("BOOTSTRAP_PLATFORMS", r#"{"watch": {}}"#);
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("BOOTSTRAP_PLATFORMS"));
+
+        map.remove("BOOTSTRAP_PLATFORMS");
+        map.insert("BOOTSTRAP_MIN_CLIENT_VERSION", "latest");
+        let err = AppConfig::from_lookup(|key| map.get(key).map(|value| (*value).to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("BOOTSTRAP_MIN_CLIENT_VERSION"));
+    }
 }
@@ -1,18 +1,25 @@
-use axum::http::header::{HeaderName, HeaderValue};
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::rate_limit::RateLimitStatus;
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("Invalid request: {0}")]
     BadRequest(String),
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
     #[error("Too many requests: {0}")]
-    TooManyRequests(String, u64),
+    TooManyRequests(String, RateLimitStatus),
     #[error("Configuration error: {0}")]
     Config(String),
     #[error("External dependency error: {0}")]
@@ -35,8 +42,20 @@ impl AppError {
         Self::Unauthorized(message.into())
     }
 
-    pub fn too_many_requests(message: impl Into<String>, retry_after_secs: u64) -> Self {
-        Self::TooManyRequests(message.into(), retry_after_secs)
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::Forbidden(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::PayloadTooLarge(message.into())
+    }
+
+    pub fn too_many_requests(message: impl Into<String>, status: RateLimitStatus) -> Self {
+        Self::TooManyRequests(message.into(), status)
     }
 
     pub fn external(message: impl Into<String>) -> Self {
@@ -46,6 +65,27 @@ impl AppError {
     pub fn internal(message: impl Into<String>) -> Self {
         Self::Internal(message.into())
     }
+
+    /// Converter for `map_err` that labels unexpected core failures with the
+    /// component they came from, e.g. `AppError::core("Notes database")`.
+    pub fn core(context: &'static str) -> impl Fn(dirt_core::Error) -> Self {
+        move |error| match Self::from(error) {
+            Self::External(message) => Self::External(format!("{context} error: {message}")),
+            other => other,
+        }
+    }
+}
+
+/// Missing records become 404s and rejected input 400s; any other failure is
+/// reported as an error of the database behind the API.
+impl From<dirt_core::Error> for AppError {
+    fn from(error: dirt_core::Error) -> Self {
+        match error {
+            dirt_core::Error::NotFound(id) => Self::not_found(format!("{id} not found")),
+            dirt_core::Error::InvalidInput(message) => Self::bad_request(message),
+            other => Self::external(other.to_string()),
+        }
+    }
 }
 
 impl IntoResponse for AppError {
@@ -53,6 +93,9 @@ impl IntoResponse for AppError {
         let status = match self {
             Self::BadRequest(_) => StatusCode::BAD_REQUEST,
             Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
             Self::TooManyRequests(_, _) => StatusCode::TOO_MANY_REQUESTS,
             Self::External(_) => StatusCode::BAD_GATEWAY,
             Self::Config(_) | Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -61,12 +104,42 @@ impl IntoResponse for AppError {
             error: self.to_string(),
         };
         let mut response = (status, Json(body)).into_response();
-        if let Self::TooManyRequests(_, retry_after_secs) = self {
-            let header_name = HeaderName::from_static("retry-after");
-            if let Ok(value) = HeaderValue::from_str(&retry_after_secs.to_string()) {
-                response.headers_mut().insert(header_name, value);
-            }
+        if let Self::TooManyRequests(_, status) = self {
+            status.write_headers(response.headers_mut());
         }
         response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn core_errors_map_to_http_errors() {
+        assert!(matches!(
+            AppError::from(dirt_core::Error::NotFound("x".to_string())),
+            AppError::NotFound(_)
+        ));
+        assert!(matches!(
+            AppError::from(dirt_core::Error::InvalidInput("bad".to_string())),
+            AppError::BadRequest(_)
+        ));
+        assert!(matches!(
+            AppError::from(dirt_core::Error::Database("boom".to_string())),
+            AppError::External(_)
+        ));
+
+        let labelled =
+            AppError::core("Share link registry")(dirt_core::Error::Database("boom".to_string()));
+        assert_eq!(
+            labelled.to_string(),
+            "External dependency error: Share link registry error: Database error: boom"
+        );
+        assert_eq!(
+            AppError::core("Notes database")(dirt_core::Error::NotFound("abc".to_string()))
+                .to_string(),
+            "Not found: abc not found"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::config::{AppConfig, DigestEmailConfig};
+use crate::error::AppError;
+
+/// Largest digest body accepted for delivery.
+pub const MAX_DIGEST_EMAIL_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Serialize)]
+struct EmailPayload<'a> {
+    from: &'a str,
+    to: [&'a str; 1],
+    subject: &'a str,
+    text: &'a str,
+}
+
+/// Sends weekly digests through an HTTP email API (`DIGEST_EMAIL_API_URL`).
+///
+/// The payload follows the Resend `POST /emails` shape:
+/// `{"from", "to": [...], "subject", "text"}` with a bearer API key.
+#[derive(Clone)]
+pub struct DigestMailer {
+    config: DigestEmailConfig,
+    client: reqwest::Client,
+}
+
+impl DigestMailer {
+    pub fn from_config(config: &Arc<AppConfig>) -> Option<Self> {
+        config.digest_email.clone().map(Self::new)
+    }
+
+    pub fn new(config: DigestEmailConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { config, client }
+    }
+
+    pub async fn send(&self, to: &str, subject: &str, text: &str) -> Result<(), AppError> {
+        let payload = EmailPayload {
+            from: &self.config.from,
+            to: [to],
+            subject,
+            text,
+        };
+        let response = self
+            .client
+            .post(&self.config.api_url)
+            .bearer_auth(&self.config.api_key)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|error| AppError::external(format!("Email API request failed: {error}")))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::external(format!(
+                "Email API returned HTTP {}: {}",
+                status.as_u16(),
+                body.trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Validates a digest email request before it is handed to the mailer.
+pub fn validate_digest_email(subject: &str, markdown: &str) -> Result<(), AppError> {
+    if subject.trim().is_empty() || subject.contains(['\r', '\n']) {
+        return Err(AppError::bad_request(
+            "Digest subject must be a single non-empty line",
+        ));
+    }
+    if markdown.trim().is_empty() {
+        return Err(AppError::bad_request("Digest body must not be empty"));
+    }
+    if markdown.len() > MAX_DIGEST_EMAIL_BYTES {
+        return Err(AppError::bad_request(format!(
+            "Digest body must be at most {MAX_DIGEST_EMAIL_BYTES} bytes"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_digest_email_rejects_bad_input() {
+        assert!(validate_digest_email("Weekly digest", "- Notes captured: 3").is_ok());
+        assert!(validate_digest_email(" ", "body").is_err());
+        assert!(validate_digest_email("Subject\r\nBcc: x@example.com", "body").is_err());
+        assert!(validate_digest_email("Weekly digest", "  ").is_err());
+        assert!(
+            validate_digest_email("Weekly digest", &"x".repeat(MAX_DIGEST_EMAIL_BYTES + 1))
+                .is_err()
+        );
+    }
+}
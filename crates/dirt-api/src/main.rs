@@ -1,14 +1,23 @@
+mod api_tokens;
 mod auth;
 mod config;
 mod error;
+mod mailer;
+mod managed_db;
 mod media;
+mod notes;
+mod quota;
 mod rate_limit;
 mod routes;
+mod scanner;
+mod shares;
 mod turso;
+mod validation;
 
 use std::sync::Arc;
 
 use config::AppConfig;
+use dirt_core::logging::LogFormat;
 use routes::{app_router, AppState};
 
 /// Load .env.server for local development.
@@ -20,17 +29,27 @@ fn load_dev_dotenv() {
     }
 }
 
+/// Log to stdout, as one JSON object per event when `DIRT_LOG_FORMAT=json`.
+fn init_tracing(format: LogFormat) {
+    let filter = tracing_subscriber::EnvFilter::from_default_env()
+        .add_directive("dirt_api=info".parse().expect("valid directive"));
+    match format {
+        LogFormat::Text => tracing_subscriber::fmt().with_env_filter(filter).init(),
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_span_list(false)
+            .with_env_filter(filter)
+            .init(),
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(debug_assertions)]
     load_dev_dotenv();
 
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive("dirt_api=info".parse().expect("valid directive")),
-        )
-        .init();
+    init_tracing(LogFormat::from_env());
 
     let config = Arc::new(AppConfig::from_env()?);
     tracing::info!("Starting dirt-api with config: {:?}", config);
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use dirt_core::db::Database;
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+
+use crate::error::AppError;
+use crate::turso::TursoTokenBroker;
+
+/// Seconds before token expiry at which the remote connection is reopened.
+const CONNECTION_REFRESH_MARGIN_SECS: i64 = 60;
+
+/// Token subject recorded when the API mints credentials for its own connection.
+const MANAGED_DB_TOKEN_SUBJECT: &str = "dirt-api";
+
+/// Shared remote-only connection to the managed Turso database.
+///
+/// The connection is opened lazily with a broker-minted token and reopened
+/// shortly before that token expires.
+pub struct ManagedDatabase {
+    broker: Arc<TursoTokenBroker>,
+    connection: Mutex<Option<RemoteConnection>>,
+}
+
+struct RemoteConnection {
+    db: Database,
+    expires_at: i64,
+}
+
+impl ManagedDatabase {
+    pub fn new(broker: Arc<TursoTokenBroker>) -> Self {
+        Self {
+            broker,
+            connection: Mutex::new(None),
+        }
+    }
+
+    /// Wraps an already-open database that never needs reopening.
+    #[cfg(test)]
+    pub fn with_database(broker: Arc<TursoTokenBroker>, db: Database) -> Self {
+        Self {
+            broker,
+            connection: Mutex::new(Some(RemoteConnection {
+                db,
+                expires_at: i64::MAX,
+            })),
+        }
+    }
+
+    /// Locks the shared connection, (re)opening it when missing or near token expiry.
+    pub async fn connect(&self) -> Result<MappedMutexGuard<'_, Database>, AppError> {
+        let mut guard = self.connection.lock().await;
+        let now = Utc::now().timestamp();
+        let is_fresh = guard
+            .as_ref()
+            .is_some_and(|conn| conn.expires_at - CONNECTION_REFRESH_MARGIN_SECS > now);
+        if !is_fresh {
+            *guard = Some(self.open_connection().await?);
+        }
+
+        Ok(MutexGuard::map(guard, |slot| {
+            &mut slot.as_mut().expect("connection opened above").db
+        }))
+    }
+
+    async fn open_connection(&self) -> Result<RemoteConnection, AppError> {
+        let token = self
+            .broker
+            .mint_sync_token(MANAGED_DB_TOKEN_SUBJECT)
+            .await?;
+        let db = Database::open_remote(token.database_url, token.auth_token)
+            .await
+            .map_err(|error| {
+                AppError::external(format!("Failed to open managed database: {error}"))
+            })?;
+        tracing::info!(
+            expires_at = token.expires_at,
+            "Opened remote managed database connection"
+        );
+        Ok(RemoteConnection {
+            db,
+            expires_at: token.expires_at,
+        })
+    }
+}
@@ -1,15 +1,27 @@
+use std::fmt::Write;
 use std::sync::Arc;
 use std::time::Duration;
 
 use aws_credential_types::Credentials;
 use aws_sdk_s3::presigning::PresigningConfig;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
 use aws_sdk_s3::Client;
 use aws_types::region::Region;
+use dirt_core::media::{StoredObject, StoredObjectPage, UploadedPart, ATTACHMENT_OBJECT_PREFIX};
 use serde::Serialize;
 
 use crate::config::{AppConfig, R2RuntimeConfig};
 use crate::error::AppError;
 
+/// Key prefix that flagged uploads are moved under; clients cannot sign URLs for it.
+pub const QUARANTINE_PREFIX: &str = "quarantine/";
+
+/// Objects returned per page of `GET /v1/media/objects`.
+const OBJECT_LIST_PAGE_SIZE: i32 = 1_000;
+
+/// Most parts storage accepts in one multipart upload.
+const MAX_UPLOAD_PARTS: i32 = 10_000;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct PresignedOperation {
     pub method: String,
@@ -17,6 +29,15 @@ pub struct PresignedOperation {
     pub headers: Vec<(String, String)>,
 }
 
+/// Size, content type, and leading bytes of a stored object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectPreview {
+    pub size_bytes: i64,
+    pub content_type: Option<String>,
+    /// Up to the first `prefix_bytes` requested from [`R2PresignService::preview_object`]
+    pub prefix: Vec<u8>,
+}
+
 #[derive(Clone)]
 pub struct R2PresignService {
     bucket: String,
@@ -88,6 +109,131 @@ impl R2PresignService {
         ))
     }
 
+    /// Starts a multipart upload and returns its upload ID.
+    pub async fn start_multipart_upload(
+        &self,
+        object_key: &str,
+        content_type: Option<&str>,
+    ) -> Result<String, AppError> {
+        let object_key = normalize_object_key(object_key)?;
+        let output = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&object_key)
+            .set_content_type(content_type.and_then(normalize_content_type))
+            .send()
+            .await
+            .map_err(|error| {
+                AppError::external(format!(
+                    "Failed to start multipart upload: {}",
+                    sanitize(&error)
+                ))
+            })?;
+        output
+            .upload_id()
+            .map(ToString::to_string)
+            .ok_or_else(|| AppError::external("Storage returned no multipart upload ID"))
+    }
+
+    pub async fn presign_upload_part(
+        &self,
+        object_key: &str,
+        upload_id: &str,
+        part_number: i32,
+    ) -> Result<PresignedOperation, AppError> {
+        let object_key = normalize_object_key(object_key)?;
+        if !(1..=MAX_UPLOAD_PARTS).contains(&part_number) {
+            return Err(AppError::bad_request(format!(
+                "part_number must be between 1 and {MAX_UPLOAD_PARTS}"
+            )));
+        }
+        let operation = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(&object_key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .presigned(presign_config(self.ttl)?)
+            .await
+            .map_err(|error| {
+                AppError::external(format!(
+                    "Failed to presign upload part URL: {}",
+                    sanitize(&error)
+                ))
+            })?;
+        Ok(map_presigned(
+            operation.method().to_string(),
+            operation.uri().to_string(),
+            operation.headers(),
+        ))
+    }
+
+    /// Assembles the uploaded parts into the final object.
+    pub async fn complete_multipart_upload(
+        &self,
+        object_key: &str,
+        upload_id: &str,
+        parts: &[UploadedPart],
+    ) -> Result<(), AppError> {
+        let object_key = normalize_object_key(object_key)?;
+        if parts.is_empty() {
+            return Err(AppError::bad_request("parts must not be empty"));
+        }
+        let parts = parts
+            .iter()
+            .map(|part| {
+                CompletedPart::builder()
+                    .part_number(part.part_number)
+                    .e_tag(&part.etag)
+                    .build()
+            })
+            .collect();
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&object_key)
+            .upload_id(upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|error| {
+                AppError::external(format!(
+                    "Failed to complete multipart upload: {}",
+                    sanitize(&error)
+                ))
+            })?;
+        Ok(())
+    }
+
+    /// Discards a multipart upload and the parts stored so far.
+    pub async fn abort_multipart_upload(
+        &self,
+        object_key: &str,
+        upload_id: &str,
+    ) -> Result<(), AppError> {
+        let object_key = normalize_object_key(object_key)?;
+        self.client
+            .abort_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&object_key)
+            .upload_id(upload_id)
+            .send()
+            .await
+            .map_err(|error| {
+                AppError::external(format!(
+                    "Failed to abort multipart upload: {}",
+                    sanitize(&error)
+                ))
+            })?;
+        Ok(())
+    }
+
     pub async fn presign_download(&self, object_key: &str) -> Result<PresignedOperation, AppError> {
         let object_key = normalize_object_key(object_key)?;
         let operation = self
@@ -131,6 +277,176 @@ impl R2PresignService {
             operation.headers(),
         ))
     }
+
+    /// Lists one page of attachment objects, starting after `cursor`.
+    ///
+    /// Quarantined objects live outside [`ATTACHMENT_OBJECT_PREFIX`] and are
+    /// never listed.
+    pub async fn list_objects(&self, cursor: Option<&str>) -> Result<StoredObjectPage, AppError> {
+        let output = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(ATTACHMENT_OBJECT_PREFIX)
+            .max_keys(OBJECT_LIST_PAGE_SIZE)
+            .set_continuation_token(cursor.map(ToString::to_string))
+            .send()
+            .await
+            .map_err(|error| {
+                AppError::external(format!("Failed to list objects: {}", sanitize(&error)))
+            })?;
+        let objects = output
+            .contents()
+            .iter()
+            .filter_map(|object| {
+                Some(StoredObject {
+                    key: object.key()?.to_string(),
+                    size_bytes: object.size().unwrap_or(0),
+                    last_modified: object.last_modified().map(|time| time.secs()),
+                })
+            })
+            .collect();
+        Ok(StoredObjectPage {
+            objects,
+            next_cursor: output.next_continuation_token().map(ToString::to_string),
+        })
+    }
+
+    /// Reads a stored object for scanning, refusing objects over `max_bytes`.
+    ///
+    /// Returns the bytes and the stored content type.
+    pub async fn fetch_object(
+        &self,
+        object_key: &str,
+        max_bytes: u64,
+    ) -> Result<(Vec<u8>, Option<String>), AppError> {
+        let object_key = normalize_object_key(object_key)?;
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&object_key)
+            .send()
+            .await
+            .map_err(|error| {
+                AppError::external(format!("Failed to read object: {}", sanitize(&error)))
+            })?;
+        let size = output
+            .content_length()
+            .and_then(|length| u64::try_from(length).ok())
+            .unwrap_or(0);
+        if size > max_bytes {
+            return Err(AppError::external(format!(
+                "Object is {size} bytes, above the {max_bytes} byte scan limit"
+            )));
+        }
+
+        let content_type = output.content_type().map(ToString::to_string);
+        let bytes = output.body.collect().await.map_err(|error| {
+            AppError::external(format!("Failed to read object body: {}", sanitize(&error)))
+        })?;
+        Ok((bytes.into_bytes().to_vec(), content_type))
+    }
+
+    /// Reads a stored object's size and content type plus its first
+    /// `prefix_bytes`, without downloading the rest.
+    pub async fn preview_object(
+        &self,
+        object_key: &str,
+        prefix_bytes: usize,
+    ) -> Result<ObjectPreview, AppError> {
+        let object_key = normalize_object_key(object_key)?;
+        let head = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(&object_key)
+            .send()
+            .await
+            .map_err(|error| {
+                AppError::external(format!(
+                    "Failed to read object metadata: {}",
+                    sanitize(&error)
+                ))
+            })?;
+        let size_bytes = head.content_length().unwrap_or(0);
+        let content_type = head.content_type().map(ToString::to_string);
+        if size_bytes == 0 || prefix_bytes == 0 {
+            return Ok(ObjectPreview {
+                size_bytes,
+                content_type,
+                prefix: Vec::new(),
+            });
+        }
+
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&object_key)
+            .range(format!("bytes=0-{}", prefix_bytes - 1))
+            .send()
+            .await
+            .map_err(|error| {
+                AppError::external(format!("Failed to read object: {}", sanitize(&error)))
+            })?;
+        let prefix = output.body.collect().await.map_err(|error| {
+            AppError::external(format!("Failed to read object body: {}", sanitize(&error)))
+        })?;
+        Ok(ObjectPreview {
+            size_bytes,
+            content_type,
+            prefix: prefix.into_bytes().to_vec(),
+        })
+    }
+
+    /// Moves an object under [`QUARANTINE_PREFIX`] and returns its new key.
+    pub async fn quarantine_object(&self, object_key: &str) -> Result<String, AppError> {
+        let object_key = normalize_object_key(object_key)?;
+        let quarantine_key = quarantine_key(&object_key);
+        self.client
+            .copy_object()
+            .bucket(&self.bucket)
+            .copy_source(copy_source(&self.bucket, &object_key))
+            .key(&quarantine_key)
+            .send()
+            .await
+            .map_err(|error| {
+                AppError::external(format!("Failed to quarantine object: {}", sanitize(&error)))
+            })?;
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(&object_key)
+            .send()
+            .await
+            .map_err(|error| {
+                AppError::external(format!(
+                    "Failed to remove quarantined object: {}",
+                    sanitize(&error)
+                ))
+            })?;
+        Ok(quarantine_key)
+    }
+}
+
+fn quarantine_key(object_key: &str) -> String {
+    format!("{QUARANTINE_PREFIX}{object_key}")
+}
+
+/// `CopySource` is `bucket/key` with the key URL-encoded, keeping `/` separators.
+fn copy_source(bucket: &str, object_key: &str) -> String {
+    let mut encoded = String::with_capacity(bucket.len() + object_key.len() + 1);
+    encoded.push_str(bucket);
+    encoded.push('/');
+    for byte in object_key.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'/' | b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
 }
 
 fn normalize_content_type(raw: &str) -> Option<String> {
@@ -157,7 +473,7 @@ fn map_presigned<'a>(
     }
 }
 
-fn normalize_object_key(raw: &str) -> Result<String, AppError> {
+pub fn normalize_object_key(raw: &str) -> Result<String, AppError> {
     let key = raw.trim().trim_start_matches('/').to_string();
     if key.is_empty() {
         return Err(AppError::bad_request("object_key is required"));
@@ -167,6 +483,11 @@ fn normalize_object_key(raw: &str) -> Result<String, AppError> {
             "object_key must not contain path traversal segments",
         ));
     }
+    if key.starts_with(QUARANTINE_PREFIX) {
+        return Err(AppError::forbidden(
+            "object_key refers to a quarantined object",
+        ));
+    }
     Ok(key)
 }
 
@@ -196,4 +517,21 @@ mod tests {
             "notes/file.png"
         );
     }
+
+    #[test]
+    fn normalize_object_key_rejects_quarantined_objects() {
+        assert!(normalize_object_key("quarantine/notes/file.png").is_err());
+        assert_eq!(
+            quarantine_key("notes/file.png"),
+            "quarantine/notes/file.png"
+        );
+    }
+
+    #[test]
+    fn copy_source_encodes_key_segments() {
+        assert_eq!(
+            copy_source("bucket", "notes/a b+c.png"),
+            "bucket/notes/a%20b%2Bc.png"
+        );
+    }
 }
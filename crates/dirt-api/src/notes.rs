@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use dirt_core::db::NoteRepository;
+use dirt_core::models::NoteSource;
+use dirt_core::{Attachment, Note, NoteId};
+
+use crate::error::AppError;
+use crate::managed_db::ManagedDatabase;
+
+pub const DEFAULT_NOTES_PAGE_SIZE: usize = 50;
+pub const MAX_NOTES_PAGE_SIZE: usize = 200;
+
+/// Server-side access to notes in the managed Turso database.
+///
+/// Integrations can read and write notes over HTTP without embedding libSQL.
+pub struct NotesStore {
+    db: Arc<ManagedDatabase>,
+}
+
+impl NotesStore {
+    pub const fn new(db: Arc<ManagedDatabase>) -> Self {
+        Self { db }
+    }
+
+    pub async fn list(&self, limit: usize, offset: usize) -> Result<Vec<Note>, AppError> {
+        let db = self.db.connect().await?;
+        db.note_repository()
+            .list(limit, offset)
+            .await
+            .map_err(AppError::core("Notes database"))
+    }
+
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Note>, AppError> {
+        let db = self.db.connect().await?;
+        db.note_repository()
+            .search(query, limit)
+            .await
+            .map_err(AppError::core("Notes database"))
+    }
+
+    pub async fn get(&self, id: &NoteId) -> Result<Note, AppError> {
+        let db = self.db.connect().await?;
+        db.note_repository()
+            .get(id)
+            .await
+            .map_err(AppError::core("Notes database"))?
+            .filter(|note| !note.is_deleted)
+            .ok_or_else(|| AppError::not_found(format!("Note {id} does not exist")))
+    }
+
+    pub async fn attachments(&self, id: &NoteId) -> Result<Vec<Attachment>, AppError> {
+        let db = self.db.connect().await?;
+        db.note_repository()
+            .list_attachments(id)
+            .await
+            .map_err(AppError::core("Notes database"))
+    }
+
+    pub async fn create(&self, content: &str) -> Result<Note, AppError> {
+        let db = self.db.connect().await?;
+        db.note_repository()
+            .create_with_source(&Note::new(content), NoteSource::Api)
+            .await
+            .map_err(AppError::core("Notes database"))
+    }
+
+    pub async fn update(&self, id: &NoteId, content: &str) -> Result<Note, AppError> {
+        let db = self.db.connect().await?;
+        db.note_repository()
+            .update(id, content)
+            .await
+            .map_err(AppError::core("Notes database"))
+    }
+
+    pub async fn delete(&self, id: &NoteId) -> Result<(), AppError> {
+        let db = self.db.connect().await?;
+        db.note_repository()
+            .delete(id)
+            .await
+            .map_err(AppError::core("Notes database"))
+    }
+}
+
+pub fn clamp_page_size(limit: Option<usize>) -> usize {
+    limit
+        .unwrap_or(DEFAULT_NOTES_PAGE_SIZE)
+        .clamp(1, MAX_NOTES_PAGE_SIZE)
+}
+
+pub fn parse_note_id(raw: &str) -> Result<NoteId, AppError> {
+    raw.parse::<NoteId>()
+        .map_err(|_| AppError::bad_request(format!("`{raw}` is not a valid note id")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_size_is_clamped() {
+        assert_eq!(clamp_page_size(None), DEFAULT_NOTES_PAGE_SIZE);
+        assert_eq!(clamp_page_size(Some(0)), 1);
+        assert_eq!(clamp_page_size(Some(10_000)), MAX_NOTES_PAGE_SIZE);
+    }
+
+    #[test]
+    fn note_id_parse_rejects_garbage() {
+        let err = parse_note_id("not-a-uuid").unwrap_err();
+        assert!(matches!(err, AppError::BadRequest(_)));
+        assert!(parse_note_id(&NoteId::new().to_string()).is_ok());
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use dirt_core::db::{LibSqlMediaUsageRepository, MediaUsageRepository};
+use dirt_core::models::{format_byte_count, MediaUsage};
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::managed_db::ManagedDatabase;
+use crate::media::normalize_object_key;
+
+/// Per-user attachment storage accounting.
+///
+/// Uploads record their declared size when they are signed, and signed
+/// deletes forget it, so usage reflects what clients asked to store. With
+/// `MEDIA_QUOTA_BYTES` set, uploads that would go over the quota are refused
+/// before a URL is signed.
+pub struct MediaQuota {
+    db: Arc<ManagedDatabase>,
+    quota_bytes: Option<i64>,
+}
+
+impl MediaQuota {
+    pub fn new(db: Arc<ManagedDatabase>, config: &AppConfig) -> Self {
+        Self {
+            db,
+            quota_bytes: config.media_quota_bytes,
+        }
+    }
+
+    pub async fn usage(&self, user_id: &str) -> Result<MediaUsage, AppError> {
+        let db = self.db.connect().await?;
+        let usage = LibSqlMediaUsageRepository::new(db.connection())
+            .get_media_usage(user_id)
+            .await
+            .map_err(AppError::core("Media usage registry"))?;
+        Ok(MediaUsage {
+            quota_bytes: self.quota_bytes,
+            ..usage
+        })
+    }
+
+    /// Records an upload of `size_bytes` under `object_key`, replacing the
+    /// size of an earlier upload to the same key.
+    ///
+    /// Uploads without a size are only accepted while no quota is set.
+    pub async fn reserve(
+        &self,
+        user_id: &str,
+        object_key: &str,
+        size_bytes: Option<i64>,
+    ) -> Result<(), AppError> {
+        let Some(size_bytes) = size_bytes else {
+            return if self.quota_bytes.is_some() {
+                Err(AppError::bad_request(
+                    "size_bytes is required while storage quotas are enabled",
+                ))
+            } else {
+                Ok(())
+            };
+        };
+        if size_bytes < 0 {
+            return Err(AppError::bad_request("size_bytes must not be negative"));
+        }
+        let object_key = normalize_object_key(object_key)?;
+
+        // The connection lock is held across the check and the record, so
+        // concurrent uploads cannot both slip under the quota.
+        let db = self.db.connect().await?;
+        let repo = LibSqlMediaUsageRepository::new(db.connection());
+        if self.quota_bytes.is_some() {
+            let replaced = repo
+                .get_media_object_size(user_id, &object_key)
+                .await
+                .map_err(AppError::core("Media usage registry"))?
+                .unwrap_or(0);
+            let usage = MediaUsage {
+                quota_bytes: self.quota_bytes,
+                ..repo
+                    .get_media_usage(user_id)
+                    .await
+                    .map_err(AppError::core("Media usage registry"))?
+            };
+            if usage.exceeds_quota(size_bytes.saturating_sub(replaced)) {
+                return Err(AppError::payload_too_large(format!(
+                    "Storage quota exceeded: {}; this upload needs {}",
+                    usage.summary(),
+                    format_byte_count(size_bytes)
+                )));
+            }
+        }
+        repo.record_media_object(user_id, &object_key, size_bytes, Utc::now().timestamp())
+            .await
+            .map_err(AppError::core("Media usage registry"))
+    }
+
+    /// Size declared when `object_key`'s upload was signed, if it was recorded.
+    pub async fn recorded_size(
+        &self,
+        user_id: &str,
+        object_key: &str,
+    ) -> Result<Option<i64>, AppError> {
+        let object_key = normalize_object_key(object_key)?;
+        let db = self.db.connect().await?;
+        LibSqlMediaUsageRepository::new(db.connection())
+            .get_media_object_size(user_id, &object_key)
+            .await
+            .map_err(AppError::core("Media usage registry"))
+    }
+
+    /// Forgets `object_key` once its upload is deleted or abandoned.
+    pub async fn release(&self, user_id: &str, object_key: &str) -> Result<(), AppError> {
+        let object_key = normalize_object_key(object_key)?;
+        let db = self.db.connect().await?;
+        LibSqlMediaUsageRepository::new(db.connection())
+            .remove_media_object(user_id, &object_key)
+            .await
+            .map_err(AppError::core("Media usage registry"))?;
+        Ok(())
+    }
+}
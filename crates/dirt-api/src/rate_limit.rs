@@ -4,6 +4,8 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use axum::http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use axum::response::{IntoResponseParts, ResponseParts};
 use tokio::sync::Mutex;
 
 use crate::config::AppConfig;
@@ -15,6 +17,7 @@ pub struct EndpointRateLimiter {
     window: Duration,
     sync_limit: u32,
     media_limit: u32,
+    notes_limit: u32,
     metrics: Arc<RateLimitMetrics>,
 }
 
@@ -22,6 +25,7 @@ pub struct EndpointRateLimiter {
 pub enum ProtectedEndpoint {
     SyncToken,
     MediaPresign,
+    NotesApi,
 }
 
 #[derive(Default)]
@@ -30,6 +34,8 @@ struct RateLimitMetrics {
     sync_limited: AtomicU64,
     media_allowed: AtomicU64,
     media_limited: AtomicU64,
+    notes_allowed: AtomicU64,
+    notes_limited: AtomicU64,
 }
 
 #[derive(Debug, Clone, Copy, serde::Serialize)]
@@ -38,8 +44,23 @@ pub struct RateLimitMetricsSnapshot {
     pub sync_limited: u64,
     pub media_allowed: u64,
     pub media_limited: u64,
+    pub notes_allowed: u64,
+    pub notes_limited: u64,
 }
 
+/// Per-user budget reported to clients via `x-ratelimit-*` headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_after_secs: u64,
+}
+
+pub const RATE_LIMIT_LIMIT_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+pub const RATE_LIMIT_REMAINING_HEADER: HeaderName =
+    HeaderName::from_static("x-ratelimit-remaining");
+pub const RATE_LIMIT_RESET_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
 #[derive(Debug, Clone, Copy)]
 struct RateWindow {
     started_at: Instant,
@@ -53,14 +74,20 @@ impl EndpointRateLimiter {
             window: config.rate_limit_window,
             sync_limit: config.sync_token_rate_limit_per_window,
             media_limit: config.media_presign_rate_limit_per_window,
+            notes_limit: config.notes_api_rate_limit_per_window,
             metrics: Arc::new(RateLimitMetrics::default()),
         }
     }
 
-    pub async fn check(&self, endpoint: ProtectedEndpoint, user_id: &str) -> Result<(), AppError> {
+    pub async fn check(
+        &self,
+        endpoint: ProtectedEndpoint,
+        user_id: &str,
+    ) -> Result<RateLimitStatus, AppError> {
         let limit = match endpoint {
             ProtectedEndpoint::SyncToken => self.sync_limit,
             ProtectedEndpoint::MediaPresign => self.media_limit,
+            ProtectedEndpoint::NotesApi => self.notes_limit,
         };
 
         let key = format!("{}:{user_id}", endpoint.label());
@@ -76,27 +103,37 @@ impl EndpointRateLimiter {
             entry.count = 0;
         }
 
+        let reset_after = self
+            .window
+            .saturating_sub(now.duration_since(entry.started_at));
+        // Round up so clients never retry before the window actually resets.
+        let reset_after_secs = reset_after.as_secs() + u64::from(reset_after.subsec_nanos() > 0);
+
         if entry.count >= limit {
-            let retry_after_secs = self
-                .window
-                .saturating_sub(now.duration_since(entry.started_at))
-                .as_secs();
             self.mark_limited(endpoint);
             tracing::warn!(
                 endpoint = endpoint.label(),
                 user = user_fingerprint(user_id),
-                retry_after_secs,
+                retry_after_secs = reset_after_secs,
                 "Rate limit exceeded"
             );
             return Err(AppError::too_many_requests(
                 "Rate limit exceeded for protected endpoint",
-                retry_after_secs,
+                RateLimitStatus {
+                    limit,
+                    remaining: 0,
+                    reset_after_secs,
+                },
             ));
         }
 
         entry.count += 1;
         self.mark_allowed(endpoint);
-        Ok(())
+        Ok(RateLimitStatus {
+            limit,
+            remaining: limit - entry.count,
+            reset_after_secs,
+        })
     }
 
     pub fn metrics_snapshot(&self) -> RateLimitMetricsSnapshot {
@@ -105,6 +142,8 @@ impl EndpointRateLimiter {
             sync_limited: self.metrics.sync_limited.load(Ordering::Relaxed),
             media_allowed: self.metrics.media_allowed.load(Ordering::Relaxed),
             media_limited: self.metrics.media_limited.load(Ordering::Relaxed),
+            notes_allowed: self.metrics.notes_allowed.load(Ordering::Relaxed),
+            notes_limited: self.metrics.notes_limited.load(Ordering::Relaxed),
         }
     }
 
@@ -116,6 +155,9 @@ impl EndpointRateLimiter {
             ProtectedEndpoint::MediaPresign => {
                 self.metrics.media_allowed.fetch_add(1, Ordering::Relaxed);
             }
+            ProtectedEndpoint::NotesApi => {
+                self.metrics.notes_allowed.fetch_add(1, Ordering::Relaxed);
+            }
         }
     }
 
@@ -127,15 +169,46 @@ impl EndpointRateLimiter {
             ProtectedEndpoint::MediaPresign => {
                 self.metrics.media_limited.fetch_add(1, Ordering::Relaxed);
             }
+            ProtectedEndpoint::NotesApi => {
+                self.metrics.notes_limited.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl RateLimitStatus {
+    /// Writes the `x-ratelimit-*` headers, plus `Retry-After` once exhausted.
+    pub fn write_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(RATE_LIMIT_LIMIT_HEADER, HeaderValue::from(self.limit));
+        headers.insert(
+            RATE_LIMIT_REMAINING_HEADER,
+            HeaderValue::from(self.remaining),
+        );
+        headers.insert(
+            RATE_LIMIT_RESET_HEADER,
+            HeaderValue::from(self.reset_after_secs),
+        );
+        if self.remaining == 0 {
+            headers.insert(RETRY_AFTER, HeaderValue::from(self.reset_after_secs));
         }
     }
 }
 
+impl IntoResponseParts for RateLimitStatus {
+    type Error = std::convert::Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        self.write_headers(res.headers_mut());
+        Ok(res)
+    }
+}
+
 impl ProtectedEndpoint {
     pub const fn label(self) -> &'static str {
         match self {
             Self::SyncToken => "sync_token",
             Self::MediaPresign => "media_presign",
+            Self::NotesApi => "notes_api",
         }
     }
 }
@@ -157,26 +230,58 @@ mod tests {
             window: Duration::from_secs(60),
             sync_limit: 2,
             media_limit: 2,
+            notes_limit: 2,
             metrics: Arc::new(RateLimitMetrics::default()),
         };
 
-        limiter
+        let first = limiter
             .check(ProtectedEndpoint::SyncToken, "user-a")
             .await
             .unwrap();
-        limiter
+        assert_eq!(first.limit, 2);
+        assert_eq!(first.remaining, 1);
+        let second = limiter
             .check(ProtectedEndpoint::SyncToken, "user-a")
             .await
             .unwrap();
+        assert_eq!(second.remaining, 0);
 
         let err = limiter
             .check(ProtectedEndpoint::SyncToken, "user-a")
             .await
             .unwrap_err();
-        assert!(matches!(err, AppError::TooManyRequests(_, _)));
+        let AppError::TooManyRequests(_, status) = err else {
+            panic!("expected rate limit error, got {err:?}");
+        };
+        assert_eq!(status.remaining, 0);
+        assert!(status.reset_after_secs > 0 && status.reset_after_secs <= 60);
 
         let metrics = limiter.metrics_snapshot();
         assert_eq!(metrics.sync_allowed, 2);
         assert_eq!(metrics.sync_limited, 1);
     }
+
+    #[test]
+    fn rate_limit_status_writes_headers() {
+        let mut headers = HeaderMap::new();
+        RateLimitStatus {
+            limit: 20,
+            remaining: 5,
+            reset_after_secs: 30,
+        }
+        .write_headers(&mut headers);
+        assert_eq!(headers[RATE_LIMIT_LIMIT_HEADER], "20");
+        assert_eq!(headers[RATE_LIMIT_REMAINING_HEADER], "5");
+        assert_eq!(headers[RATE_LIMIT_RESET_HEADER], "30");
+        assert!(headers.get(RETRY_AFTER).is_none());
+
+        let mut headers = HeaderMap::new();
+        RateLimitStatus {
+            limit: 20,
+            remaining: 0,
+            reset_after_secs: 30,
+        }
+        .write_headers(&mut headers);
+        assert_eq!(headers[RETRY_AFTER], "30");
+    }
 }
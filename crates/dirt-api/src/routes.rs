@@ -1,26 +1,47 @@
+use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::time::Instant;
 
-use axum::extract::{Query, Request, State};
+use axum::extract::{Path, Query, Request, State};
 use axum::http::header::{self, HeaderValue};
-use axum::http::{HeaderMap, StatusCode};
+use axum::http::{HeaderMap, Method, StatusCode};
 use axum::middleware::{self, Next};
 use axum::response::{IntoResponse, Response};
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post};
 use axum::{Extension, Json, Router};
 use chrono::Utc;
+use dirt_core::crypto::is_encrypted;
+use dirt_core::logging::{new_request_id, REQUEST_ID_HEADER};
+use dirt_core::media::{MediaCommitResult, MultipartUploadStart, StoredObjectPage, UploadedPart};
+use dirt_core::models::{AccessScope, AttachmentScanStatus, MediaUsage};
+use dirt_core::share::{ShareLink, SharedAttachment, SharedNote, SHARED_NOTE_PATH};
+use dirt_core::Note;
 use serde::{Deserialize, Serialize};
-use tower_http::cors::{Any, CorsLayer};
-use tower_http::trace::TraceLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::Instrument;
 
+use crate::api_tokens::{
+    is_api_token, parse_scopes, ApiTokenRegistry, ApiTokenService, ApiTokenSummary, Grant,
+};
 use crate::auth::{extract_bearer_token, AuthenticatedUser, SupabaseJwtVerifier};
-use crate::config::AppConfig;
+use crate::config::{AppConfig, BootstrapOverrides};
 use crate::error::AppError;
+use crate::mailer::{validate_digest_email, DigestMailer};
+use crate::managed_db::ManagedDatabase;
 use crate::media::{PresignedOperation, R2PresignService};
-use crate::rate_limit::{EndpointRateLimiter, ProtectedEndpoint, RateLimitMetricsSnapshot};
+use crate::notes::{clamp_page_size, parse_note_id, NotesStore};
+use crate::quota::MediaQuota;
+use crate::rate_limit::{
+    EndpointRateLimiter, ProtectedEndpoint, RateLimitMetricsSnapshot, RateLimitStatus,
+    RATE_LIMIT_LIMIT_HEADER, RATE_LIMIT_REMAINING_HEADER, RATE_LIMIT_RESET_HEADER,
+};
+use crate::scanner::{MediaScanner, ScanVerdict};
+use crate::shares::{share_link, ShareRegistry, ShareTokenService};
 use crate::turso::{MintedSyncToken, TursoTokenBroker};
+use crate::validation::{validate_upload, SNIFF_BYTES};
 
-const BOOTSTRAP_SCHEMA_VERSION: u32 = 1;
+const BOOTSTRAP_SCHEMA_VERSION: u32 = 2;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -28,15 +49,33 @@ pub struct AppState {
     jwt_verifier: Arc<SupabaseJwtVerifier>,
     turso_broker: Arc<TursoTokenBroker>,
     r2_presign: Option<Arc<R2PresignService>>,
+    media_scanner: Option<Arc<MediaScanner>>,
+    digest_mailer: Option<Arc<DigestMailer>>,
     endpoint_rate_limiter: Arc<EndpointRateLimiter>,
+    api_tokens: Option<Arc<ApiTokenService>>,
+    token_registry: Arc<ApiTokenRegistry>,
+    share_tokens: Option<Arc<ShareTokenService>>,
+    share_registry: Arc<ShareRegistry>,
+    media_quota: Arc<MediaQuota>,
+    notes: Arc<NotesStore>,
 }
 
 impl AppState {
     pub fn from_config(config: Arc<AppConfig>) -> Self {
+        let turso_broker = Arc::new(TursoTokenBroker::new(config.clone()));
+        let managed_db = Arc::new(ManagedDatabase::new(turso_broker.clone()));
         Self {
             jwt_verifier: Arc::new(SupabaseJwtVerifier::new(config.clone())),
-            turso_broker: Arc::new(TursoTokenBroker::new(config.clone())),
+            notes: Arc::new(NotesStore::new(managed_db.clone())),
+            token_registry: Arc::new(ApiTokenRegistry::new(managed_db.clone())),
+            media_quota: Arc::new(MediaQuota::new(managed_db.clone(), &config)),
+            share_registry: Arc::new(ShareRegistry::new(managed_db)),
+            turso_broker,
             r2_presign: R2PresignService::from_config(&config).map(Arc::new),
+            media_scanner: MediaScanner::from_config(&config).map(Arc::new),
+            digest_mailer: DigestMailer::from_config(&config).map(Arc::new),
+            api_tokens: ApiTokenService::from_config(&config).map(Arc::new),
+            share_tokens: ShareTokenService::from_config(&config).map(Arc::new),
             endpoint_rate_limiter: Arc::new(EndpointRateLimiter::from_config(config.as_ref())),
             config,
         }
@@ -46,25 +85,86 @@ impl AppState {
 pub fn app_router(state: AppState) -> Router {
     let protected_routes = Router::new()
         .route("/sync/token", post(mint_sync_token))
+        .route("/tokens", get(list_api_tokens).post(create_api_token))
+        .route("/tokens/{id}", delete(revoke_api_token))
+        .route("/digest/email", post(email_digest))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    // Notes and media routes also accept personal access tokens, limited by scope.
+    let scoped_routes = Router::new()
         .route("/media/presign/upload", post(presign_upload))
         .route("/media/presign/download", get(presign_download))
         .route("/media/presign/delete", post(presign_delete))
-        .route_layer(middleware::from_fn_with_state(state.clone(), require_auth));
+        .route("/media/multipart/start", post(start_multipart_upload))
+        .route("/media/multipart/part", post(presign_upload_part))
+        .route("/media/multipart/complete", post(complete_multipart_upload))
+        .route("/media/multipart/abort", post(abort_multipart_upload))
+        .route("/media/commit", post(commit_media))
+        .route("/media/objects", get(list_media_objects))
+        .route("/media/usage", get(media_usage))
+        .route("/notes", get(list_notes).post(create_note))
+        .route(
+            "/notes/{id}",
+            get(get_note).patch(update_note).delete(delete_note),
+        )
+        .route("/notes/{id}/share", post(create_note_share))
+        .route("/notes/{id}/shares", get(list_note_shares))
+        .route("/shares/{id}", delete(revoke_note_share))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_scoped_auth,
+        ));
 
     Router::new()
         .route("/healthz", get(healthz))
         .route("/v1/bootstrap", get(bootstrap_manifest))
+        // Share links authenticate with the token in the path.
+        .route("/v1/shared/{token}", get(open_shared_note))
         .nest("/v1", protected_routes)
-        .layer(TraceLayer::new_for_http())
-        .layer(
-            CorsLayer::new()
-                .allow_origin(Any)
-                .allow_headers(Any)
-                .allow_methods(Any),
-        )
+        .nest("/v1", scoped_routes)
+        .layer(middleware::from_fn(log_request))
+        .layer(build_cors_layer(state.config.as_ref()))
         .with_state(state)
 }
 
+/// Browser CORS policy. Preflight requests are answered here, before auth runs.
+fn build_cors_layer(config: &AppConfig) -> CorsLayer {
+    let allow_origin = if config
+        .cors_allowed_origins
+        .iter()
+        .any(|origin| origin == "*")
+    {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(
+            config
+                .cors_allowed_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        )
+    };
+
+    // Wildcard `Access-Control-Allow-Headers` does not cover `Authorization`,
+    // so request headers are listed explicitly.
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::IF_NONE_MATCH,
+        ])
+        .expose_headers([
+            header::ETAG,
+            header::CACHE_CONTROL,
+            header::RETRY_AFTER,
+            RATE_LIMIT_LIMIT_HEADER,
+            RATE_LIMIT_REMAINING_HEADER,
+            RATE_LIMIT_RESET_HEADER,
+        ])
+        .max_age(config.cors_max_age)
+}
+
 #[derive(Debug, Serialize)]
 struct HealthResponse {
     status: &'static str,
@@ -95,6 +195,12 @@ struct BootstrapManifest {
     api_base_url: String,
     turso_sync_token_endpoint: String,
     feature_flags: BootstrapFeatureFlags,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_client_version: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    channels: BTreeMap<String, BootstrapOverrides>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    platforms: BTreeMap<String, BootstrapOverrides>,
 }
 
 async fn bootstrap_manifest(
@@ -113,6 +219,9 @@ async fn bootstrap_manifest(
             managed_sync: true,
             managed_media: state.r2_presign.is_some(),
         },
+        min_client_version: state.config.bootstrap_min_client_version.clone(),
+        channels: state.config.bootstrap_channels.clone(),
+        platforms: state.config.bootstrap_platforms.clone(),
     };
 
     let payload = serde_json::to_vec(&manifest).map_err(|error| {
@@ -202,6 +311,37 @@ fn apply_bootstrap_cache_headers(headers: &mut HeaderMap, etag: &str, max_age_se
     }
 }
 
+/// Log one line per request, keyed by the caller's `x-request-id` (or a
+/// fresh one) so client and server logs can be joined.
+async fn log_request(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty())
+        .map_or_else(new_request_id, str::to_string);
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let span = tracing::info_span!("request", request_id = %request_id);
+    let started = Instant::now();
+
+    let mut response = next.run(request).instrument(span).await;
+
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    tracing::info!(
+        request_id = %request_id,
+        method = %method,
+        path = %path,
+        status = response.status().as_u16(),
+        latency_ms,
+        "Handled request"
+    );
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
 async fn require_auth(
     State(state): State<AppState>,
     mut request: Request,
@@ -216,8 +356,8 @@ async fn require_auth(
 async fn mint_sync_token(
     State(state): State<AppState>,
     Extension(user): Extension<AuthenticatedUser>,
-) -> Result<Json<MintedSyncToken>, AppError> {
-    state
+) -> Result<(RateLimitStatus, Json<MintedSyncToken>), AppError> {
+    let rate_limit = state
         .endpoint_rate_limiter
         .check(ProtectedEndpoint::SyncToken, &user.user_id)
         .await?;
@@ -229,15 +369,18 @@ async fn mint_sync_token(
         user = user_hash,
         session = user.session_id.as_deref().unwrap_or("none"),
         expires_at = token.expires_at,
+        credential_epoch = token.credential_epoch,
         "Issued managed sync token"
     );
-    Ok(Json(token))
+    Ok((rate_limit, Json(token)))
 }
 
 #[derive(Debug, Deserialize)]
 struct UploadPresignRequest {
     object_key: String,
     content_type: Option<String>,
+    /// Size of the upload, counted against the user's storage quota
+    size_bytes: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -258,17 +401,23 @@ struct PresignResponse {
 async fn presign_upload(
     State(state): State<AppState>,
     Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
     Json(request): Json<UploadPresignRequest>,
-) -> Result<Json<PresignResponse>, AppError> {
-    state
+) -> Result<(RateLimitStatus, Json<PresignResponse>), AppError> {
+    let rate_limit = state
         .endpoint_rate_limiter
         .check(ProtectedEndpoint::MediaPresign, &user.user_id)
         .await?;
+    grant.require(AccessScope::MediaWrite)?;
 
     let user_hash = user_fingerprint(&user.user_id);
     let signer = state.r2_presign.as_ref().ok_or_else(|| {
         AppError::Config("R2 presign service is not configured on the backend".to_string())
     })?;
+    state
+        .media_quota
+        .reserve(&user.user_id, &request.object_key, request.size_bytes)
+        .await?;
     let operation = signer
         .presign_upload(&request.object_key, request.content_type.as_deref())
         .await?;
@@ -278,18 +427,20 @@ async fn presign_upload(
         object_key_len = request.object_key.len(),
         "Issued presigned upload URL"
     );
-    Ok(Json(PresignResponse { operation }))
+    Ok((rate_limit, Json(PresignResponse { operation })))
 }
 
 async fn presign_download(
     State(state): State<AppState>,
     Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
     Query(query): Query<DownloadPresignQuery>,
-) -> Result<Json<PresignResponse>, AppError> {
-    state
+) -> Result<(RateLimitStatus, Json<PresignResponse>), AppError> {
+    let rate_limit = state
         .endpoint_rate_limiter
         .check(ProtectedEndpoint::MediaPresign, &user.user_id)
         .await?;
+    grant.require(AccessScope::MediaRead)?;
 
     let user_hash = user_fingerprint(&user.user_id);
     let signer = state.r2_presign.as_ref().ok_or_else(|| {
@@ -302,31 +453,817 @@ async fn presign_download(
         object_key_len = query.object_key.len(),
         "Issued presigned download URL"
     );
-    Ok(Json(PresignResponse { operation }))
+    Ok((rate_limit, Json(PresignResponse { operation })))
 }
 
 async fn presign_delete(
     State(state): State<AppState>,
     Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
     Json(request): Json<DeletePresignRequest>,
-) -> Result<Json<PresignResponse>, AppError> {
-    state
+) -> Result<(RateLimitStatus, Json<PresignResponse>), AppError> {
+    let rate_limit = state
         .endpoint_rate_limiter
         .check(ProtectedEndpoint::MediaPresign, &user.user_id)
         .await?;
+    grant.require(AccessScope::MediaWrite)?;
 
     let user_hash = user_fingerprint(&user.user_id);
     let signer = state.r2_presign.as_ref().ok_or_else(|| {
         AppError::Config("R2 presign service is not configured on the backend".to_string())
     })?;
     let operation = signer.presign_delete(&request.object_key).await?;
+    state
+        .media_quota
+        .release(&user.user_id, &request.object_key)
+        .await?;
     tracing::info!(
         endpoint = "media_presign_delete",
         user = user_hash,
         object_key_len = request.object_key.len(),
         "Issued presigned delete URL"
     );
-    Ok(Json(PresignResponse { operation }))
+    Ok((rate_limit, Json(PresignResponse { operation })))
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadPartPresignRequest {
+    object_key: String,
+    upload_id: String,
+    part_number: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompleteMultipartRequest {
+    object_key: String,
+    upload_id: String,
+    parts: Vec<UploadedPart>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbortMultipartRequest {
+    object_key: String,
+    upload_id: String,
+}
+
+/// Starts a chunked upload; each part is then signed separately so clients
+/// can resume after a dropped connection.
+async fn start_multipart_upload(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Json(request): Json<UploadPresignRequest>,
+) -> Result<(RateLimitStatus, Json<MultipartUploadStart>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::MediaPresign, &user.user_id)
+        .await?;
+    grant.require(AccessScope::MediaWrite)?;
+
+    let user_hash = user_fingerprint(&user.user_id);
+    let storage = state.r2_presign.as_ref().ok_or_else(|| {
+        AppError::Config("R2 presign service is not configured on the backend".to_string())
+    })?;
+    state
+        .media_quota
+        .reserve(&user.user_id, &request.object_key, request.size_bytes)
+        .await?;
+    let upload_id = storage
+        .start_multipart_upload(&request.object_key, request.content_type.as_deref())
+        .await?;
+    tracing::info!(
+        endpoint = "media_multipart_start",
+        user = user_hash,
+        object_key_len = request.object_key.len(),
+        "Started multipart upload"
+    );
+    Ok((rate_limit, Json(MultipartUploadStart { upload_id })))
+}
+
+async fn presign_upload_part(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Json(request): Json<UploadPartPresignRequest>,
+) -> Result<(RateLimitStatus, Json<PresignResponse>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::MediaPresign, &user.user_id)
+        .await?;
+    grant.require(AccessScope::MediaWrite)?;
+
+    let user_hash = user_fingerprint(&user.user_id);
+    let signer = state.r2_presign.as_ref().ok_or_else(|| {
+        AppError::Config("R2 presign service is not configured on the backend".to_string())
+    })?;
+    let operation = signer
+        .presign_upload_part(&request.object_key, &request.upload_id, request.part_number)
+        .await?;
+    tracing::info!(
+        endpoint = "media_multipart_part",
+        user = user_hash,
+        part_number = request.part_number,
+        "Issued presigned upload part URL"
+    );
+    Ok((rate_limit, Json(PresignResponse { operation })))
+}
+
+async fn complete_multipart_upload(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Json(request): Json<CompleteMultipartRequest>,
+) -> Result<(StatusCode, RateLimitStatus, ()), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::MediaPresign, &user.user_id)
+        .await?;
+    grant.require(AccessScope::MediaWrite)?;
+
+    let user_hash = user_fingerprint(&user.user_id);
+    let storage = state.r2_presign.as_ref().ok_or_else(|| {
+        AppError::Config("R2 presign service is not configured on the backend".to_string())
+    })?;
+    storage
+        .complete_multipart_upload(&request.object_key, &request.upload_id, &request.parts)
+        .await?;
+    tracing::info!(
+        endpoint = "media_multipart_complete",
+        user = user_hash,
+        parts = request.parts.len(),
+        "Completed multipart upload"
+    );
+    Ok((StatusCode::NO_CONTENT, rate_limit, ()))
+}
+
+async fn abort_multipart_upload(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Json(request): Json<AbortMultipartRequest>,
+) -> Result<(StatusCode, RateLimitStatus, ()), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::MediaPresign, &user.user_id)
+        .await?;
+    grant.require(AccessScope::MediaWrite)?;
+
+    let user_hash = user_fingerprint(&user.user_id);
+    let storage = state.r2_presign.as_ref().ok_or_else(|| {
+        AppError::Config("R2 presign service is not configured on the backend".to_string())
+    })?;
+    storage
+        .abort_multipart_upload(&request.object_key, &request.upload_id)
+        .await?;
+    state
+        .media_quota
+        .release(&user.user_id, &request.object_key)
+        .await?;
+    tracing::info!(
+        endpoint = "media_multipart_abort",
+        user = user_hash,
+        "Aborted multipart upload"
+    );
+    Ok((StatusCode::NO_CONTENT, rate_limit, ()))
+}
+
+#[derive(Debug, Deserialize)]
+struct ListMediaObjectsQuery {
+    cursor: Option<String>,
+}
+
+/// Lists stored attachment objects so clients can audit them against their
+/// attachment records.
+async fn list_media_objects(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Query(query): Query<ListMediaObjectsQuery>,
+) -> Result<(RateLimitStatus, Json<StoredObjectPage>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::MediaPresign, &user.user_id)
+        .await?;
+    grant.require(AccessScope::MediaRead)?;
+
+    let user_hash = user_fingerprint(&user.user_id);
+    let storage = state.r2_presign.as_ref().ok_or_else(|| {
+        AppError::Config("R2 presign service is not configured on the backend".to_string())
+    })?;
+    let page = storage.list_objects(query.cursor.as_deref()).await?;
+    tracing::info!(
+        endpoint = "media_list_objects",
+        user = user_hash,
+        objects = page.objects.len(),
+        "Listed stored media objects"
+    );
+    Ok((rate_limit, Json(page)))
+}
+
+/// Reports the caller's attachment storage use and quota.
+async fn media_usage(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+) -> Result<(RateLimitStatus, Json<MediaUsage>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::MediaPresign, &user.user_id)
+        .await?;
+    grant.require(AccessScope::MediaRead)?;
+
+    let usage = state.media_quota.usage(&user.user_id).await?;
+    tracing::info!(
+        endpoint = "media_usage",
+        user = user_fingerprint(&user.user_id),
+        objects = usage.object_count,
+        "Reported media usage"
+    );
+    Ok((rate_limit, Json(usage)))
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitMediaRequest {
+    object_key: String,
+}
+
+/// Validates a finished upload and scans it, quarantining it when either the
+/// checks in [`validate_upload`] reject it or the scanner flags it.
+///
+/// Without a configured scanner a valid object is reported as unscanned.
+/// Storage and scanner outages are reported as a failed scan rather than an
+/// error so uploads still complete.
+async fn commit_media(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Json(request): Json<CommitMediaRequest>,
+) -> Result<(RateLimitStatus, Json<MediaCommitResult>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::MediaPresign, &user.user_id)
+        .await?;
+    grant.require(AccessScope::MediaWrite)?;
+
+    let user_hash = user_fingerprint(&user.user_id);
+    let unscanned = MediaCommitResult {
+        scan_status: AttachmentScanStatus::Unscanned,
+        reason: None,
+    };
+    if state.media_scanner.is_none() && state.r2_presign.is_none() {
+        return Ok((rate_limit, Json(unscanned)));
+    }
+    let storage = state.r2_presign.as_ref().ok_or_else(|| {
+        AppError::Config("R2 presign service is not configured on the backend".to_string())
+    })?;
+
+    // Size and file-signature checks run before any scanner sees the upload;
+    // a rejected upload is quarantined like a flagged one.
+    let declared_size = state
+        .media_quota
+        .recorded_size(&user.user_id, &request.object_key)
+        .await?;
+    let checked = match storage
+        .preview_object(&request.object_key, SNIFF_BYTES)
+        .await
+    {
+        Ok(preview) => Ok(validate_upload(&preview, declared_size)),
+        // Invalid keys are the caller's mistake, not a scan failure.
+        Err(error @ (AppError::BadRequest(_) | AppError::Forbidden(_))) => return Err(error),
+        Err(error) => Err(error),
+    };
+    let verdict = match (checked, state.media_scanner.as_ref()) {
+        (Ok(Err(reason)), _) => Ok(Some(ScanVerdict::Flagged(Some(reason)))),
+        (Ok(Ok(())), Some(scanner)) => scan_stored_object(storage, scanner, &request.object_key)
+            .await
+            .map(Some),
+        (Ok(Ok(())), None) => Ok(None),
+        (Err(error), _) => Err(error),
+    };
+
+    let result = match verdict {
+        Ok(None) => unscanned,
+        Ok(Some(ScanVerdict::Clean)) => MediaCommitResult {
+            scan_status: AttachmentScanStatus::Clean,
+            reason: None,
+        },
+        Ok(Some(ScanVerdict::Flagged(reason))) => {
+            storage.quarantine_object(&request.object_key).await?;
+            MediaCommitResult {
+                scan_status: AttachmentScanStatus::Quarantined,
+                reason,
+            }
+        }
+        Err(error) => {
+            tracing::warn!(
+                endpoint = "media_commit",
+                user = user_hash,
+                "Media scan failed: {}",
+                error
+            );
+            MediaCommitResult {
+                scan_status: AttachmentScanStatus::Failed,
+                reason: None,
+            }
+        }
+    };
+
+    tracing::info!(
+        endpoint = "media_commit",
+        user = user_hash,
+        object_key_len = request.object_key.len(),
+        scan_status = result.scan_status.as_str(),
+        "Committed media upload"
+    );
+    Ok((rate_limit, Json(result)))
+}
+
+async fn scan_stored_object(
+    storage: &R2PresignService,
+    scanner: &MediaScanner,
+    object_key: &str,
+) -> Result<ScanVerdict, AppError> {
+    let (bytes, content_type) = storage
+        .fetch_object(object_key, scanner.max_bytes())
+        .await?;
+    scanner
+        .scan(object_key, content_type.as_deref(), bytes)
+        .await
+}
+
+/// Accepts either a Supabase session or a personal access token and records the grant.
+async fn require_scoped_auth(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let token = extract_bearer_token(request.headers())?;
+    let (user, grant) = if is_api_token(token) {
+        let service = state
+            .api_tokens
+            .as_ref()
+            .ok_or_else(|| AppError::unauthorized("API tokens are not enabled on this server"))?;
+        let verified = service.verify(token)?;
+        state.token_registry.authorize(&verified).await?;
+        (
+            AuthenticatedUser {
+                user_id: verified.user_id,
+                session_id: None,
+                email: None,
+            },
+            Grant::ApiToken(verified.scopes),
+        )
+    } else {
+        let user = state.jwt_verifier.verify_access_token(token).await?;
+        (user, Grant::Session)
+    };
+    request.extensions_mut().insert(user);
+    request.extensions_mut().insert(grant);
+    Ok(next.run(request).await)
+}
+
+#[derive(Debug, Deserialize)]
+struct DigestEmailRequest {
+    subject: String,
+    markdown: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DigestEmailResponse {
+    sent: bool,
+}
+
+/// Emails a client-rendered digest to the signed-in user's account address.
+async fn email_digest(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<DigestEmailRequest>,
+) -> Result<(RateLimitStatus, Json<DigestEmailResponse>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+
+    let mailer = state.digest_mailer.as_ref().ok_or_else(|| {
+        AppError::Config("Digest email is not configured on the backend".to_string())
+    })?;
+    let recipient = user
+        .email
+        .as_deref()
+        .ok_or_else(|| AppError::bad_request("Session has no email address for digest delivery"))?;
+    validate_digest_email(&request.subject, &request.markdown)?;
+
+    mailer
+        .send(recipient, request.subject.trim(), &request.markdown)
+        .await?;
+    tracing::info!(
+        endpoint = "digest_email",
+        user = user_fingerprint(&user.user_id),
+        body_len = request.markdown.len(),
+        "Sent weekly digest email"
+    );
+    Ok((rate_limit, Json(DigestEmailResponse { sent: true })))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateApiTokenRequest {
+    name: String,
+    scopes: Vec<String>,
+    expires_in_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreatedApiTokenResponse {
+    token: String,
+    #[serde(flatten)]
+    summary: ApiTokenSummary,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiTokensResponse {
+    tokens: Vec<ApiTokenSummary>,
+}
+
+async fn create_api_token(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateApiTokenRequest>,
+) -> Result<(StatusCode, RateLimitStatus, Json<CreatedApiTokenResponse>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+
+    let service = state.api_tokens.as_ref().ok_or_else(|| {
+        AppError::Config("API tokens are not enabled; set API_TOKEN_SIGNING_SECRET".to_string())
+    })?;
+    let scopes = parse_scopes(&request.scopes)?;
+    let issued = service.issue(
+        &user.user_id,
+        &request.name,
+        &scopes,
+        request.expires_in_days,
+    )?;
+    state.token_registry.record(&issued.record).await?;
+    tracing::info!(
+        endpoint = "tokens_create",
+        user = user_fingerprint(&user.user_id),
+        scopes = ?issued.record.scopes,
+        expires_at = issued.record.expires_at,
+        "Issued personal access token"
+    );
+    Ok((
+        StatusCode::CREATED,
+        rate_limit,
+        Json(CreatedApiTokenResponse {
+            token: issued.token,
+            summary: issued.record.into(),
+        }),
+    ))
+}
+
+async fn list_api_tokens(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<(RateLimitStatus, Json<ApiTokensResponse>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+
+    let tokens = state
+        .token_registry
+        .list(&user.user_id)
+        .await?
+        .into_iter()
+        .map(ApiTokenSummary::from)
+        .collect();
+    Ok((rate_limit, Json(ApiTokensResponse { tokens })))
+}
+
+async fn revoke_api_token(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, RateLimitStatus, ()), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+
+    state.token_registry.revoke(&user.user_id, &id).await?;
+    tracing::info!(
+        endpoint = "tokens_revoke",
+        user = user_fingerprint(&user.user_id),
+        "Revoked personal access token"
+    );
+    Ok((StatusCode::NO_CONTENT, rate_limit, ()))
+}
+
+#[derive(Debug, Deserialize)]
+struct ListNotesQuery {
+    q: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NoteContentRequest {
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct NotesResponse {
+    notes: Vec<Note>,
+}
+
+#[derive(Debug, Serialize)]
+struct NoteResponse {
+    note: Note,
+}
+
+async fn list_notes(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Query(query): Query<ListNotesQuery>,
+) -> Result<(RateLimitStatus, Json<NotesResponse>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+    grant.require(AccessScope::NotesRead)?;
+
+    let limit = clamp_page_size(query.limit);
+    let notes = match query.q.as_deref().map(str::trim) {
+        Some(search) if !search.is_empty() => state.notes.search(search, limit).await?,
+        _ => {
+            state
+                .notes
+                .list(limit, query.offset.unwrap_or_default())
+                .await?
+        }
+    };
+    Ok((rate_limit, Json(NotesResponse { notes })))
+}
+
+async fn get_note(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Path(id): Path<String>,
+) -> Result<(RateLimitStatus, Json<NoteResponse>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+    grant.require(AccessScope::NotesRead)?;
+
+    let note = state.notes.get(&parse_note_id(&id)?).await?;
+    Ok((rate_limit, Json(NoteResponse { note })))
+}
+
+async fn create_note(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Json(request): Json<NoteContentRequest>,
+) -> Result<(StatusCode, RateLimitStatus, Json<NoteResponse>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+    grant.require(AccessScope::NotesWrite)?;
+    require_note_content(&request.content)?;
+
+    let note = state.notes.create(&request.content).await?;
+    tracing::info!(
+        endpoint = "notes_create",
+        user = user_fingerprint(&user.user_id),
+        content_len = request.content.len(),
+        "Created note via API"
+    );
+    Ok((StatusCode::CREATED, rate_limit, Json(NoteResponse { note })))
+}
+
+async fn update_note(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Path(id): Path<String>,
+    Json(request): Json<NoteContentRequest>,
+) -> Result<(RateLimitStatus, Json<NoteResponse>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+    grant.require(AccessScope::NotesWrite)?;
+    require_note_content(&request.content)?;
+
+    let note = state
+        .notes
+        .update(&parse_note_id(&id)?, &request.content)
+        .await?;
+    tracing::info!(
+        endpoint = "notes_update",
+        user = user_fingerprint(&user.user_id),
+        content_len = request.content.len(),
+        "Updated note via API"
+    );
+    Ok((rate_limit, Json(NoteResponse { note })))
+}
+
+async fn delete_note(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, RateLimitStatus, ()), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+    grant.require(AccessScope::NotesWrite)?;
+
+    state.notes.delete(&parse_note_id(&id)?).await?;
+    tracing::info!(
+        endpoint = "notes_delete",
+        user = user_fingerprint(&user.user_id),
+        "Deleted note via API"
+    );
+    Ok((StatusCode::NO_CONTENT, rate_limit, ()))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CreateShareRequest {
+    expires_in_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShareLinksResponse {
+    shares: Vec<ShareLink>,
+}
+
+/// Signs a public read-only link to a note.
+///
+/// Encrypted notes are refused: the server only holds their ciphertext.
+async fn create_note_share(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    request: Option<Json<CreateShareRequest>>,
+) -> Result<(StatusCode, RateLimitStatus, Json<ShareLink>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+    grant.require(AccessScope::NotesWrite)?;
+
+    let service = state.share_tokens.as_ref().ok_or_else(|| {
+        AppError::Config("Share links are not enabled; set API_TOKEN_SIGNING_SECRET".to_string())
+    })?;
+    let note = state.notes.get(&parse_note_id(&id)?).await?;
+    if is_encrypted(&note.content) {
+        return Err(AppError::bad_request(
+            "Encrypted notes cannot be shared; the server cannot read them",
+        ));
+    }
+    let Json(request) = request.unwrap_or_default();
+    let issued = service.issue(&user.user_id, &note.id, request.expires_in_secs)?;
+    state.share_registry.record(&issued.record).await?;
+    tracing::info!(
+        endpoint = "notes_share",
+        user = user_fingerprint(&user.user_id),
+        expires_at = issued.record.expires_at,
+        "Created note share link"
+    );
+
+    let url = format!(
+        "{}{SHARED_NOTE_PATH}{}",
+        resolve_public_api_base_url(state.config.as_ref(), &headers),
+        issued.token
+    );
+    Ok((
+        StatusCode::CREATED,
+        rate_limit,
+        Json(share_link(issued.record, Some(url))),
+    ))
+}
+
+async fn list_note_shares(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Path(id): Path<String>,
+) -> Result<(RateLimitStatus, Json<ShareLinksResponse>), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+    grant.require(AccessScope::NotesRead)?;
+
+    let shares = state
+        .share_registry
+        .list(&user.user_id, &parse_note_id(&id)?)
+        .await?
+        .into_iter()
+        .map(|record| share_link(record, None))
+        .collect();
+    Ok((rate_limit, Json(ShareLinksResponse { shares })))
+}
+
+async fn revoke_note_share(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Extension(grant): Extension<Grant>,
+    Path(id): Path<String>,
+) -> Result<(StatusCode, RateLimitStatus, ()), AppError> {
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &user.user_id)
+        .await?;
+    grant.require(AccessScope::NotesWrite)?;
+
+    state.share_registry.revoke(&user.user_id, &id).await?;
+    tracing::info!(
+        endpoint = "shares_revoke",
+        user = user_fingerprint(&user.user_id),
+        "Revoked note share link"
+    );
+    Ok((StatusCode::NO_CONTENT, rate_limit, ()))
+}
+
+/// Opens a share link without authentication.
+///
+/// A bad signature, expiry, revocation, or deleted note is a 404 so the
+/// response does not reveal whether a link ever existed. Requests count
+/// against the link owner's notes rate limit.
+async fn open_shared_note(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<(RateLimitStatus, Json<SharedNote>), AppError> {
+    let service = state
+        .share_tokens
+        .as_ref()
+        .ok_or_else(|| AppError::not_found("Share links are not enabled on this server"))?;
+    let verified = service.verify(&token)?;
+    let rate_limit = state
+        .endpoint_rate_limiter
+        .check(ProtectedEndpoint::NotesApi, &verified.user_id)
+        .await?;
+    let share = state.share_registry.authorize(&verified).await?;
+    let note = match state.notes.get(&share.note_id).await {
+        Ok(note) if !is_encrypted(&note.content) => note,
+        Ok(_) | Err(AppError::NotFound(_)) => {
+            return Err(AppError::not_found("Shared note is no longer available"))
+        }
+        Err(error) => return Err(error),
+    };
+
+    let mut attachments = Vec::new();
+    for attachment in state.notes.attachments(&note.id).await? {
+        if !attachment.scan_status.is_downloadable() {
+            continue;
+        }
+        let url = match state.r2_presign.as_ref() {
+            Some(signer) => Some(signer.presign_download(&attachment.r2_key).await?.url),
+            None => None,
+        };
+        attachments.push(SharedAttachment {
+            filename: attachment.filename,
+            mime_type: attachment.mime_type,
+            size_bytes: attachment.size_bytes,
+            url,
+        });
+    }
+    tracing::info!(
+        endpoint = "shared_note",
+        user = user_fingerprint(&verified.user_id),
+        attachments = attachments.len(),
+        "Opened note share link"
+    );
+
+    Ok((
+        rate_limit,
+        Json(SharedNote {
+            note_id: note.id,
+            tags: note.tags(),
+            content: note.content,
+            created_at: note.created_at,
+            updated_at: note.updated_at,
+            expires_at: share.expires_at,
+            attachments,
+        }),
+    ))
+}
+
+fn require_note_content(content: &str) -> Result<(), AppError> {
+    if content.trim().is_empty() {
+        return Err(AppError::bad_request("Note content must not be empty"));
+    }
+    Ok(())
 }
 
 fn user_fingerprint(user_id: &str) -> u64 {
@@ -340,9 +1277,10 @@ mod tests {
     use std::sync::Arc;
     use std::time::Duration;
 
-    use axum::body::to_bytes;
+    use axum::body::{to_bytes, Body};
     use axum::http::header;
     use axum::http::HeaderMap;
+    use tower::ServiceExt;
 
     use super::*;
     use crate::config::{AppConfig, R2RuntimeConfig};
@@ -360,6 +1298,9 @@ mod tests {
             bootstrap_manifest_version: "v1".to_string(),
             bootstrap_cache_max_age_secs: 300,
             bootstrap_public_api_base_url: Some("https://api.example.com".to_string()),
+            bootstrap_min_client_version: None,
+            bootstrap_channels: BTreeMap::new(),
+            bootstrap_platforms: BTreeMap::new(),
             turso_api_url: "https://api.turso.tech".to_string(),
             turso_organization_slug: "org".to_string(),
             turso_database_name: "db".to_string(),
@@ -367,15 +1308,459 @@ mod tests {
             turso_platform_api_token: Some("secret".to_string()),
             turso_static_auth_token: None,
             turso_token_ttl: Duration::from_secs(900),
+            sync_credential_epoch: 0,
             media_url_ttl: Duration::from_secs(600),
+            media_quota_bytes: None,
             auth_clock_skew: Duration::from_secs(60),
             rate_limit_window: Duration::from_secs(60),
             sync_token_rate_limit_per_window: 20,
             media_presign_rate_limit_per_window: 120,
+            notes_api_rate_limit_per_window: 120,
+            api_token_signing_secret: Some("test-api-token-signing-secret-0123".to_string()),
+            api_token_ttl: Duration::from_secs(3_600),
+            cors_allowed_origins: vec!["https://app.example.com".to_string()],
+            cors_max_age: Duration::from_secs(600),
             r2: None,
+            media_scanner: None,
+            digest_email: None,
         }
     }
 
+    async fn send(router: Router, request: Request<Body>) -> Response {
+        router.oneshot(request).await.expect("router response")
+    }
+
+    fn preflight(path: &str, origin: &str) -> Request<Body> {
+        axum::http::Request::builder()
+            .method(Method::OPTIONS)
+            .uri(path)
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(
+                header::ACCESS_CONTROL_REQUEST_HEADERS,
+                "authorization,content-type",
+            )
+            .body(Body::empty())
+            .expect("preflight request")
+    }
+
+    fn header_str(response: &Response, name: header::HeaderName) -> Option<&str> {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    }
+
+    #[tokio::test]
+    async fn cors_preflight_allows_configured_origin_on_protected_routes() {
+        let router = app_router(AppState::from_config(Arc::new(test_config())));
+
+        for path in [
+            "/v1/sync/token",
+            "/v1/media/presign/upload",
+            "/v1/media/presign/download",
+            "/v1/media/presign/delete",
+            "/v1/media/commit",
+            "/v1/media/objects",
+            "/v1/media/usage",
+            "/v1/media/multipart/start",
+            "/v1/media/multipart/part",
+            "/v1/bootstrap",
+            "/v1/notes",
+            "/v1/tokens",
+            "/v1/digest/email",
+        ] {
+            let response = send(router.clone(), preflight(path, "https://app.example.com")).await;
+            assert_eq!(response.status(), StatusCode::OK, "{path}");
+            assert_eq!(
+                header_str(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+                Some("https://app.example.com"),
+                "{path}"
+            );
+            let allowed_headers = header_str(&response, header::ACCESS_CONTROL_ALLOW_HEADERS)
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            assert!(allowed_headers.contains("authorization"), "{path}");
+            assert_eq!(
+                header_str(&response, header::ACCESS_CONTROL_MAX_AGE),
+                Some("600")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn cors_preflight_rejects_unknown_origin() {
+        let router = app_router(AppState::from_config(Arc::new(test_config())));
+        let response = send(router, preflight("/v1/sync/token", "https://evil.example")).await;
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn cors_wildcard_allows_any_origin() {
+        let mut config = test_config();
+        config.cors_allowed_origins = vec!["*".to_string()];
+        let router = app_router(AppState::from_config(Arc::new(config)));
+        let response = send(router, preflight("/v1/sync/token", "https://anything.dev")).await;
+        assert_eq!(
+            header_str(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("*")
+        );
+    }
+
+    #[tokio::test]
+    async fn cors_bootstrap_response_exposes_etag() {
+        let router = app_router(AppState::from_config(Arc::new(test_config())));
+        let request = Request::builder()
+            .uri("/v1/bootstrap")
+            .header(header::ORIGIN, "https://app.example.com")
+            .body(Body::empty())
+            .expect("bootstrap request");
+        let response = send(router, request).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            header_str(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some("https://app.example.com")
+        );
+        let exposed = header_str(&response, header::ACCESS_CONTROL_EXPOSE_HEADERS)
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        assert!(exposed.contains("etag"));
+        assert!(exposed.contains("retry-after"));
+        assert!(exposed.contains("x-ratelimit-remaining"));
+    }
+
+    fn bearer_request(method: Method, path: &str, token: &str, body: &str) -> Request<Body> {
+        axum::http::Request::builder()
+            .method(method)
+            .uri(path)
+            .header(header::AUTHORIZATION, format!("Bearer {token}"))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .expect("bearer request")
+    }
+
+    #[tokio::test]
+    async fn notes_routes_require_authentication() {
+        let router = app_router(AppState::from_config(Arc::new(test_config())));
+        let request = axum::http::Request::builder()
+            .uri("/v1/notes")
+            .body(Body::empty())
+            .expect("notes request");
+        let response = send(router, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    /// State whose notes and token registry share an in-memory database.
+    async fn state_with_memory_db(config: AppConfig) -> AppState {
+        let mut state = AppState::from_config(Arc::new(config));
+        let db = dirt_core::db::Database::open_in_memory()
+            .await
+            .expect("in-memory database");
+        let managed_db = Arc::new(ManagedDatabase::with_database(
+            state.turso_broker.clone(),
+            db,
+        ));
+        state.notes = Arc::new(NotesStore::new(managed_db.clone()));
+        state.token_registry = Arc::new(ApiTokenRegistry::new(managed_db.clone()));
+        state.media_quota = Arc::new(MediaQuota::new(managed_db.clone(), &state.config));
+        state.share_registry = Arc::new(ShareRegistry::new(managed_db));
+        state
+    }
+
+    async fn issue_recorded_token(state: &AppState, scopes: &[AccessScope]) -> (String, String) {
+        let service = state.api_tokens.as_ref().expect("api tokens enabled");
+        let issued = service.issue("user-a", "script", scopes, None).unwrap();
+        state.token_registry.record(&issued.record).await.unwrap();
+        (issued.record.id, issued.token)
+    }
+
+    #[tokio::test]
+    async fn notes_routes_enforce_api_token_scopes() {
+        let state = state_with_memory_db(test_config()).await;
+        let (_, read_only) = issue_recorded_token(&state, &[AccessScope::NotesRead]).await;
+        let router = app_router(state);
+
+        let response = send(
+            router.clone(),
+            bearer_request(Method::GET, "/v1/notes", &read_only, ""),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for (method, path, body) in [
+            (Method::POST, "/v1/notes", r#"{"content":"hello"}"#),
+            (
+                Method::PATCH,
+                "/v1/notes/0190a6b4-0000-7000-8000-000000000000",
+                r#"{"content":"hello"}"#,
+            ),
+            (
+                Method::DELETE,
+                "/v1/notes/0190a6b4-0000-7000-8000-000000000000",
+                "",
+            ),
+            (
+                Method::POST,
+                "/v1/media/presign/upload",
+                r#"{"object_key":"a.png"}"#,
+            ),
+            (
+                Method::GET,
+                "/v1/media/presign/download?object_key=a.png",
+                "",
+            ),
+            (
+                Method::POST,
+                "/v1/media/commit",
+                r#"{"object_key":"a.png"}"#,
+            ),
+            (Method::GET, "/v1/media/objects", ""),
+            (
+                Method::POST,
+                "/v1/media/multipart/start",
+                r#"{"object_key":"a.mp4"}"#,
+            ),
+        ] {
+            let response = send(
+                router.clone(),
+                bearer_request(method, path, &read_only, body),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn media_usage_enforces_quota_and_reports_recorded_uploads() {
+        let mut config = test_config();
+        config.media_quota_bytes = Some(1_000);
+        let state = state_with_memory_db(config).await;
+        let (_, token) = issue_recorded_token(&state, &[AccessScope::MediaRead]).await;
+        let quota = state.media_quota.clone();
+
+        quota
+            .reserve("user-a", "/notes/a.png", Some(600))
+            .await
+            .unwrap();
+        let err = quota
+            .reserve("user-a", "notes/b.png", Some(500))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::PayloadTooLarge(_)));
+        // Replacing an object only counts the difference.
+        quota
+            .reserve("user-a", "notes/a.png", Some(900))
+            .await
+            .unwrap();
+        quota
+            .reserve("user-b", "notes/c.png", Some(800))
+            .await
+            .unwrap();
+        assert!(matches!(
+            quota.reserve("user-a", "notes/d.png", None).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let response = send(
+            app_router(state),
+            bearer_request(Method::GET, "/v1/media/usage", &token, ""),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body_bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let usage: MediaUsage = serde_json::from_slice(&body_bytes).expect("usage JSON");
+        assert_eq!(
+            usage,
+            MediaUsage {
+                used_bytes: 900,
+                object_count: 1,
+                quota_bytes: Some(1_000),
+            }
+        );
+
+        quota.release("user-a", "notes/a.png").await.unwrap();
+        assert_eq!(quota.usage("user-a").await.unwrap().used_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn media_commit_reports_unscanned_without_scanner() {
+        let state = state_with_memory_db(test_config()).await;
+        let (_, token) = issue_recorded_token(&state, &[AccessScope::MediaWrite]).await;
+        let router = app_router(state);
+
+        let response = send(
+            router,
+            bearer_request(
+                Method::POST,
+                "/v1/media/commit",
+                &token,
+                r#"{"object_key":"notes/a.png"}"#,
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body_bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body bytes");
+        let result: MediaCommitResult =
+            serde_json::from_slice(&body_bytes).expect("valid commit JSON");
+        assert_eq!(result.scan_status, AttachmentScanStatus::Unscanned);
+    }
+
+    #[tokio::test]
+    async fn share_links_open_notes_until_revoked() {
+        let state = state_with_memory_db(test_config()).await;
+        let (_, token) =
+            issue_recorded_token(&state, &[AccessScope::NotesRead, AccessScope::NotesWrite]).await;
+        let note = state.notes.create("Trip plan #travel").await.unwrap();
+        let router = app_router(state);
+
+        let response = send(
+            router.clone(),
+            bearer_request(
+                Method::POST,
+                &format!("/v1/notes/{}/share", note.id),
+                &token,
+                r#"{"expires_in_secs":3600}"#,
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body_bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body bytes");
+        let link: ShareLink = serde_json::from_slice(&body_bytes).expect("valid share JSON");
+        assert_eq!(link.expires_at - link.created_at, 3_600);
+        let url = link.url.expect("share url");
+        let path = url
+            .strip_prefix("https://api.example.com")
+            .expect("public base url");
+
+        let open = || {
+            axum::http::Request::builder()
+                .uri(path)
+                .body(Body::empty())
+                .expect("shared note request")
+        };
+        let response = send(router.clone(), open()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body_bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body bytes");
+        let shared: SharedNote = serde_json::from_slice(&body_bytes).expect("valid shared note");
+        assert_eq!(shared.content, "Trip plan #travel");
+        assert_eq!(shared.tags, vec!["travel".to_string()]);
+
+        let response = send(
+            router.clone(),
+            bearer_request(
+                Method::DELETE,
+                &format!("/v1/shares/{}", link.id),
+                &token,
+                "",
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = send(router.clone(), open()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = send(
+            router,
+            axum::http::Request::builder()
+                .uri("/v1/shared/dirt_share_not-a-token")
+                .body(Body::empty())
+                .expect("shared note request"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn revoked_api_tokens_are_rejected() {
+        let state = state_with_memory_db(test_config()).await;
+        let (id, token) = issue_recorded_token(&state, &[AccessScope::NotesRead]).await;
+        state.token_registry.revoke("user-a", &id).await.unwrap();
+        let router = app_router(state);
+
+        let response = send(router, bearer_request(Method::GET, "/v1/notes", &token, "")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn unrecorded_api_tokens_are_rejected() {
+        let state = state_with_memory_db(test_config()).await;
+        let service = state.api_tokens.clone().expect("api tokens enabled");
+        let issued = service
+            .issue("user-a", "script", &[AccessScope::NotesRead], None)
+            .unwrap();
+        let router = app_router(state);
+
+        let response = send(
+            router,
+            bearer_request(Method::GET, "/v1/notes", &issued.token, ""),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn api_tokens_cannot_mint_sync_credentials_or_tokens() {
+        let state = state_with_memory_db(test_config()).await;
+        let (_, token) = issue_recorded_token(&state, &AccessScope::ALL).await;
+        let router = app_router(state);
+
+        for (method, path, body) in [
+            (Method::POST, "/v1/sync/token", ""),
+            (Method::GET, "/v1/tokens", ""),
+            (
+                Method::POST,
+                "/v1/tokens",
+                r#"{"name":"escalate","scopes":["notes:write"]}"#,
+            ),
+            (
+                Method::POST,
+                "/v1/digest/email",
+                r#"{"subject":"Weekly digest","markdown":"- Notes captured: 1"}"#,
+            ),
+        ] {
+            let response = send(router.clone(), bearer_request(method, path, &token, body)).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn responses_echo_or_assign_request_ids() {
+        let router = app_router(AppState::from_config(Arc::new(test_config())));
+
+        let request = axum::http::Request::builder()
+            .uri("/healthz")
+            .header(REQUEST_ID_HEADER, "cli-1234")
+            .body(Body::empty())
+            .expect("healthz request");
+        let response = send(router.clone(), request).await;
+        assert_eq!(
+            response
+                .headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|value| value.to_str().ok()),
+            Some("cli-1234")
+        );
+
+        let request = axum::http::Request::builder()
+            .uri("/healthz")
+            .body(Body::empty())
+            .expect("healthz request");
+        let response = send(router, request).await;
+        assert!(response.headers().contains_key(REQUEST_ID_HEADER));
+    }
+
     #[tokio::test]
     async fn bootstrap_manifest_returns_schema_and_cache_headers() {
         let state = AppState::from_config(Arc::new(test_config()));
@@ -402,7 +1787,7 @@ mod tests {
             payload
                 .get("schema_version")
                 .and_then(serde_json::Value::as_u64),
-            Some(1)
+            Some(2)
         );
         assert_eq!(
             payload
@@ -410,6 +1795,37 @@ mod tests {
                 .and_then(|v| v.as_str()),
             Some("https://api.example.com/v1/sync/token")
         );
+        assert!(payload.get("channels").is_none());
+    }
+
+    #[tokio::test]
+    async fn bootstrap_manifest_serves_rollout_settings() {
+        let mut config = test_config();
+        config.bootstrap_min_client_version = Some("0.4.0".to_string());
+        config.bootstrap_channels.insert(
+            "beta".to_string(),
+            BootstrapOverrides {
+                api_base_url: Some("https://beta.example.com".to_string()),
+                ..BootstrapOverrides::default()
+            },
+        );
+        let state = AppState::from_config(Arc::new(config));
+        let response = bootstrap_manifest(State(state), HeaderMap::new())
+            .await
+            .expect("bootstrap response");
+
+        let body_bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        let payload: serde_json::Value =
+            serde_json::from_slice(&body_bytes).expect("valid bootstrap JSON");
+        assert_eq!(payload["min_client_version"], "0.4.0");
+        assert_eq!(
+            payload["channels"]["beta"]["api_base_url"],
+            "https://beta.example.com"
+        );
+        assert!(payload["channels"]["beta"].get("supabase_url").is_none());
+        assert!(payload.get("platforms").is_none());
     }
 
     #[tokio::test]
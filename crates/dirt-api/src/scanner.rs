@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::config::{AppConfig, MediaScannerConfig};
+use crate::error::AppError;
+
+/// `MEDIA_SCANNER_URL` scheme that selects a ClamAV daemon over TCP.
+pub const CLAMD_SCHEME: &str = "clamd://";
+
+/// Largest chunk sent in one clamd `INSTREAM` frame.
+const CLAMD_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Outcome of scanning one stored object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    Flagged(Option<String>),
+}
+
+#[derive(Debug, Deserialize)]
+struct ScannerResponse {
+    verdict: String,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Client for the external content scanner configured with `MEDIA_SCANNER_URL`.
+///
+/// A webhook scanner receives the raw object bytes in a POST body and answers
+/// with `{"verdict": "clean" | "flagged", "reason": "..."}`. A
+/// `clamd://host:port` URL streams the bytes to a ClamAV daemon instead.
+#[derive(Clone)]
+pub struct MediaScanner {
+    config: MediaScannerConfig,
+    client: reqwest::Client,
+}
+
+impl MediaScanner {
+    pub fn from_config(config: &Arc<AppConfig>) -> Option<Self> {
+        config.media_scanner.clone().map(Self::new)
+    }
+
+    pub fn new(config: MediaScannerConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        Self { config, client }
+    }
+
+    pub const fn max_bytes(&self) -> u64 {
+        self.config.max_bytes
+    }
+
+    pub async fn scan(
+        &self,
+        object_key: &str,
+        content_type: Option<&str>,
+        bytes: Vec<u8>,
+    ) -> Result<ScanVerdict, AppError> {
+        if let Some(address) = self.config.url.strip_prefix(CLAMD_SCHEME) {
+            return tokio::time::timeout(self.config.timeout, scan_with_clamd(address, &bytes))
+                .await
+                .map_err(|_| AppError::external("ClamAV scan timed out"))?;
+        }
+
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .header("Accept", "application/json")
+            .header("X-Dirt-Object-Key", object_key)
+            .header(
+                "Content-Type",
+                content_type.unwrap_or("application/octet-stream"),
+            );
+        if let Some(token) = self.config.auth_token.as_deref() {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.body(bytes).send().await.map_err(|error| {
+            AppError::external(format!("Media scanner request failed: {error}"))
+        })?;
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(AppError::external(format!(
+                "Media scanner returned HTTP {}: {}",
+                status.as_u16(),
+                body.trim()
+            )));
+        }
+        parse_verdict(&body)
+    }
+}
+
+/// Streams `bytes` to clamd with the `INSTREAM` command: length-prefixed
+/// chunks ended by a zero-length chunk.
+async fn scan_with_clamd(address: &str, bytes: &[u8]) -> Result<ScanVerdict, AppError> {
+    let clamd_error =
+        |error: std::io::Error| AppError::external(format!("ClamAV request failed: {error}"));
+    let mut stream = TcpStream::connect(address).await.map_err(clamd_error)?;
+    stream
+        .write_all(b"zINSTREAM\0")
+        .await
+        .map_err(clamd_error)?;
+    for chunk in bytes.chunks(CLAMD_CHUNK_BYTES) {
+        let length = u32::try_from(chunk.len()).unwrap_or(u32::MAX);
+        stream
+            .write_all(&length.to_be_bytes())
+            .await
+            .map_err(clamd_error)?;
+        stream.write_all(chunk).await.map_err(clamd_error)?;
+    }
+    stream
+        .write_all(&0_u32.to_be_bytes())
+        .await
+        .map_err(clamd_error)?;
+
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply).await.map_err(clamd_error)?;
+    parse_clamd_reply(&String::from_utf8_lossy(&reply))
+}
+
+fn parse_clamd_reply(reply: &str) -> Result<ScanVerdict, AppError> {
+    let reply = reply.trim_end_matches('\0').trim();
+    let result = reply.strip_prefix("stream:").map_or(reply, str::trim);
+
+    if result == "OK" {
+        return Ok(ScanVerdict::Clean);
+    }
+    if let Some(signature) = result.strip_suffix("FOUND") {
+        let signature = signature.trim();
+        return Ok(ScanVerdict::Flagged(
+            (!signature.is_empty()).then(|| signature.to_string()),
+        ));
+    }
+    Err(AppError::external(format!("ClamAV returned '{reply}'")))
+}
+
+fn parse_verdict(body: &str) -> Result<ScanVerdict, AppError> {
+    let response: ScannerResponse = serde_json::from_str(body).map_err(|error| {
+        AppError::external(format!("Media scanner returned invalid JSON: {error}"))
+    })?;
+    let reason = response
+        .reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+
+    match response.verdict.trim().to_ascii_lowercase().as_str() {
+        "clean" => Ok(ScanVerdict::Clean),
+        "flagged" => Ok(ScanVerdict::Flagged(reason)),
+        other => Err(AppError::external(format!(
+            "Media scanner returned unknown verdict '{other}'"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_verdict_reads_clean_and_flagged_responses() {
+        assert_eq!(
+            parse_verdict(r#"{"verdict":"clean"}"#).unwrap(),
+            ScanVerdict::Clean
+        );
+        assert_eq!(
+            parse_verdict(r#"{"verdict":"FLAGGED","reason":" EICAR test file "}"#).unwrap(),
+            ScanVerdict::Flagged(Some("EICAR test file".to_string()))
+        );
+        assert_eq!(
+            parse_verdict(r#"{"verdict":"flagged","reason":""}"#).unwrap(),
+            ScanVerdict::Flagged(None)
+        );
+    }
+
+    #[test]
+    fn parse_verdict_rejects_unknown_or_malformed_responses() {
+        assert!(parse_verdict(r#"{"verdict":"maybe"}"#).is_err());
+        assert!(parse_verdict("not json").is_err());
+    }
+
+    #[test]
+    fn parse_clamd_reply_reads_ok_found_and_errors() {
+        assert_eq!(
+            parse_clamd_reply("stream: OK\0").unwrap(),
+            ScanVerdict::Clean
+        );
+        assert_eq!(
+            parse_clamd_reply("stream: Win.Test.EICAR_HDB-1 FOUND\0").unwrap(),
+            ScanVerdict::Flagged(Some("Win.Test.EICAR_HDB-1".to_string()))
+        );
+        assert!(parse_clamd_reply("INSTREAM size limit exceeded. ERROR\0").is_err());
+        assert!(parse_clamd_reply("").is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use dirt_core::db::{LibSqlNoteShareRepository, NoteShareRepository};
+use dirt_core::models::NoteShare;
+use dirt_core::share::{
+    ShareLink, DEFAULT_SHARE_EXPIRY_SECS, MAX_SHARE_EXPIRY_SECS, MIN_SHARE_EXPIRY_SECS,
+};
+use dirt_core::NoteId;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::managed_db::ManagedDatabase;
+
+/// Prefix that distinguishes share link tokens from API tokens.
+pub const SHARE_TOKEN_PREFIX: &str = "dirt_share_";
+
+const SHARE_TOKEN_ISSUER: &str = "dirt-api-share";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ShareClaims {
+    jti: String,
+    sub: String,
+    note: String,
+    iss: String,
+    iat: i64,
+    exp: i64,
+}
+
+/// A freshly signed share link token and the record to store for it.
+#[derive(Debug, Clone)]
+pub struct IssuedShare {
+    pub token: String,
+    pub record: NoteShare,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedShare {
+    pub share_id: String,
+    pub user_id: String,
+    pub note_id: NoteId,
+}
+
+/// Share link metadata returned to the owner; `url` is only known at creation.
+pub fn share_link(record: NoteShare, url: Option<String>) -> ShareLink {
+    ShareLink {
+        id: record.id,
+        note_id: record.note_id,
+        url,
+        created_at: record.created_at,
+        expires_at: record.expires_at,
+        revoked_at: record.revoked_at,
+    }
+}
+
+/// Issues and verifies HMAC-signed share link tokens.
+///
+/// Shares the API token signing secret, under a different issuer so neither
+/// kind of token is accepted as the other.
+#[derive(Clone)]
+pub struct ShareTokenService {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    clock_skew: Duration,
+}
+
+impl ShareTokenService {
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        let secret = config.api_token_signing_secret.as_deref()?;
+        Some(Self {
+            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            clock_skew: config.auth_clock_skew,
+        })
+    }
+
+    pub fn issue(
+        &self,
+        user_id: &str,
+        note_id: &NoteId,
+        expires_in_secs: Option<i64>,
+    ) -> Result<IssuedShare, AppError> {
+        let ttl_secs = expires_in_secs.unwrap_or(DEFAULT_SHARE_EXPIRY_SECS);
+        if !(MIN_SHARE_EXPIRY_SECS..=MAX_SHARE_EXPIRY_SECS).contains(&ttl_secs) {
+            return Err(AppError::bad_request(format!(
+                "expires_in_secs must be in [{MIN_SHARE_EXPIRY_SECS}, {MAX_SHARE_EXPIRY_SECS}]"
+            )));
+        }
+
+        let issued_at = Utc::now().timestamp();
+        let expires_at = issued_at.saturating_add(ttl_secs);
+        let id = Uuid::now_v7().to_string();
+        let claims = ShareClaims {
+            jti: id.clone(),
+            sub: user_id.to_string(),
+            note: note_id.to_string(),
+            iss: SHARE_TOKEN_ISSUER.to_string(),
+            iat: issued_at,
+            exp: expires_at,
+        };
+        let jwt = encode(&Header::new(Algorithm::HS256), &claims, &self.encoding_key)
+            .map_err(|error| AppError::internal(format!("Failed to sign share link: {error}")))?;
+
+        Ok(IssuedShare {
+            token: format!("{SHARE_TOKEN_PREFIX}{jwt}"),
+            record: NoteShare {
+                id,
+                user_id: user_id.to_string(),
+                note_id: *note_id,
+                created_at: issued_at,
+                expires_at,
+                revoked_at: None,
+            },
+        })
+    }
+
+    /// Checks the token signature and expiry. Revocation is checked by [`ShareRegistry`].
+    pub fn verify(&self, token: &str) -> Result<VerifiedShare, AppError> {
+        let invalid = || AppError::not_found("Share link is invalid or has expired");
+        let jwt = token.strip_prefix(SHARE_TOKEN_PREFIX).ok_or_else(invalid)?;
+
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.validate_aud = false;
+        validation.leeway = self.clock_skew.as_secs();
+        validation.set_issuer(&[SHARE_TOKEN_ISSUER]);
+        validation.set_required_spec_claims(&["exp", "iss", "sub"]);
+
+        let claims = decode::<ShareClaims>(jwt, &self.decoding_key, &validation)
+            .map_err(|_| invalid())?
+            .claims;
+        let note_id = claims.note.parse::<NoteId>().map_err(|_| invalid())?;
+        if claims.sub.trim().is_empty() || claims.jti.trim().is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(VerifiedShare {
+            share_id: claims.jti,
+            user_id: claims.sub,
+            note_id,
+        })
+    }
+}
+
+/// Stored share link records, used for listing, revocation, and validation.
+pub struct ShareRegistry {
+    db: Arc<ManagedDatabase>,
+}
+
+impl ShareRegistry {
+    pub const fn new(db: Arc<ManagedDatabase>) -> Self {
+        Self { db }
+    }
+
+    pub async fn record(&self, share: &NoteShare) -> Result<(), AppError> {
+        let db = self.db.connect().await?;
+        LibSqlNoteShareRepository::new(db.connection())
+            .create_note_share(share)
+            .await
+            .map_err(AppError::core("Share link registry"))
+    }
+
+    pub async fn list(&self, user_id: &str, note_id: &NoteId) -> Result<Vec<NoteShare>, AppError> {
+        let db = self.db.connect().await?;
+        LibSqlNoteShareRepository::new(db.connection())
+            .list_note_shares(user_id, note_id)
+            .await
+            .map_err(AppError::core("Share link registry"))
+    }
+
+    pub async fn revoke(&self, user_id: &str, id: &str) -> Result<(), AppError> {
+        let db = self.db.connect().await?;
+        let revoked = LibSqlNoteShareRepository::new(db.connection())
+            .revoke_note_share(user_id, id, Utc::now().timestamp())
+            .await
+            .map_err(AppError::core("Share link registry"))?;
+        if revoked {
+            Ok(())
+        } else {
+            Err(AppError::not_found(format!(
+                "Share link {id} does not exist or is already revoked"
+            )))
+        }
+    }
+
+    /// Rejects links that were revoked or never recorded.
+    pub async fn authorize(&self, verified: &VerifiedShare) -> Result<NoteShare, AppError> {
+        let db = self.db.connect().await?;
+        let now = Utc::now().timestamp();
+        LibSqlNoteShareRepository::new(db.connection())
+            .get_note_share(&verified.share_id)
+            .await
+            .map_err(AppError::core("Share link registry"))?
+            .filter(|record| {
+                record.user_id == verified.user_id
+                    && record.note_id == verified.note_id
+                    && record.is_active(now)
+            })
+            .ok_or_else(|| AppError::not_found("Share link has been revoked or has expired"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(secret: &str) -> ShareTokenService {
+        ShareTokenService {
+            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
+            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            clock_skew: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn issued_share_round_trips_note() {
+        let service = service("0123456789abcdef0123456789abcdef");
+        let note_id = NoteId::new();
+        let issued = service.issue("user-a", &note_id, None).unwrap();
+        assert!(issued.token.starts_with(SHARE_TOKEN_PREFIX));
+        assert_eq!(
+            issued.record.expires_at - issued.record.created_at,
+            DEFAULT_SHARE_EXPIRY_SECS
+        );
+
+        let verified = service.verify(&issued.token).unwrap();
+        assert_eq!(verified.share_id, issued.record.id);
+        assert_eq!(verified.user_id, "user-a");
+        assert_eq!(verified.note_id, note_id);
+    }
+
+    #[test]
+    fn verify_rejects_foreign_and_api_tokens() {
+        let issued = service("0123456789abcdef0123456789abcdef")
+            .issue("user-a", &NoteId::new(), Some(3_600))
+            .unwrap();
+        let err = service("fedcba9876543210fedcba9876543210")
+            .verify(&issued.token)
+            .unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+
+        let as_api_token = issued.token.replacen(SHARE_TOKEN_PREFIX, "dirt_", 1);
+        assert!(service("0123456789abcdef0123456789abcdef")
+            .verify(&as_api_token)
+            .is_err());
+    }
+
+    #[test]
+    fn issue_validates_expiry() {
+        let service = service("0123456789abcdef0123456789abcdef");
+        let note_id = NoteId::new();
+        assert!(service.issue("user-a", &note_id, Some(0)).is_err());
+        assert!(service
+            .issue("user-a", &note_id, Some(MAX_SHARE_EXPIRY_SECS + 1))
+            .is_err());
+        let issued = service.issue("user-a", &note_id, Some(600)).unwrap();
+        assert_eq!(issued.record.expires_at - issued.record.created_at, 600);
+    }
+}
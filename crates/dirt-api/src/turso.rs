@@ -18,6 +18,8 @@ pub struct MintedSyncToken {
     pub auth_token: String,
     pub expires_at: i64,
     pub database_url: String,
+    /// Bumped by operators to make clients reopen their replicas with new credentials.
+    pub credential_epoch: u64,
 }
 
 impl TursoTokenBroker {
@@ -119,6 +121,7 @@ impl TursoTokenBroker {
             auth_token: token,
             expires_at,
             database_url: self.config.turso_database_url.clone(),
+            credential_epoch: self.config.sync_credential_epoch,
         })
     }
 
@@ -143,6 +146,7 @@ impl TursoTokenBroker {
             auth_token: token.to_string(),
             expires_at,
             database_url: self.config.turso_database_url.clone(),
+            credential_epoch: self.config.sync_credential_epoch,
         })
     }
 }
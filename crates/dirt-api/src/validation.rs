@@ -0,0 +1,253 @@
+use crate::media::ObjectPreview;
+
+/// Leading bytes read from a committed upload to check its file signature.
+pub const SNIFF_BYTES: usize = 512;
+
+/// Broad kinds of file a signature identifies; a declared content type only
+/// has to agree with the sniffed one on the kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    Image,
+    Audio,
+    Video,
+    /// Containers (webm, ogg, mp4, 3gp) that hold audio or video alike.
+    AudioOrVideo,
+    Document,
+    Archive,
+    Executable,
+}
+
+impl FileKind {
+    /// Whether a file of this sniffed kind may carry the `declared` kind.
+    fn admits(self, declared: Self) -> bool {
+        match self {
+            Self::AudioOrVideo => matches!(declared, Self::Audio | Self::Video),
+            sniffed => sniffed == declared,
+        }
+    }
+}
+
+struct Signature {
+    offset: usize,
+    magic: &'static [u8],
+    mime: &'static str,
+    kind: FileKind,
+}
+
+const fn signature(
+    offset: usize,
+    magic: &'static [u8],
+    mime: &'static str,
+    kind: FileKind,
+) -> Signature {
+    Signature {
+        offset,
+        magic,
+        mime,
+        kind,
+    }
+}
+
+/// Checked in order, so container formats sharing a prefix (`RIFF`, `ftyp`)
+/// list their specific brands first.
+const SIGNATURES: &[Signature] = &[
+    signature(0, b"\x89PNG\r\n\x1a\n", "image/png", FileKind::Image),
+    signature(0, b"\xff\xd8\xff", "image/jpeg", FileKind::Image),
+    signature(0, b"GIF87a", "image/gif", FileKind::Image),
+    signature(0, b"GIF89a", "image/gif", FileKind::Image),
+    signature(8, b"WEBP", "image/webp", FileKind::Image),
+    signature(8, b"WAVE", "audio/wav", FileKind::Audio),
+    signature(8, b"AVI ", "video/x-msvideo", FileKind::Video),
+    signature(4, b"ftypheic", "image/heic", FileKind::Image),
+    signature(4, b"ftypheix", "image/heic", FileKind::Image),
+    signature(4, b"ftypmif1", "image/heif", FileKind::Image),
+    signature(4, b"ftypavif", "image/avif", FileKind::Image),
+    signature(4, b"ftypM4A ", "audio/mp4", FileKind::Audio),
+    signature(4, b"ftypqt  ", "video/quicktime", FileKind::Video),
+    signature(4, b"ftyp3gp", "video/3gpp", FileKind::AudioOrVideo),
+    signature(4, b"ftyp", "video/mp4", FileKind::AudioOrVideo),
+    signature(0, b"\x1a\x45\xdf\xa3", "video/webm", FileKind::AudioOrVideo),
+    signature(0, b"OggS", "audio/ogg", FileKind::AudioOrVideo),
+    signature(0, b"ID3", "audio/mpeg", FileKind::Audio),
+    signature(0, b"\xff\xfb", "audio/mpeg", FileKind::Audio),
+    signature(0, b"\xff\xfa", "audio/mpeg", FileKind::Audio),
+    signature(0, b"\xff\xf3", "audio/mpeg", FileKind::Audio),
+    signature(0, b"\xff\xf2", "audio/mpeg", FileKind::Audio),
+    signature(0, b"fLaC", "audio/flac", FileKind::Audio),
+    signature(0, b"%PDF-", "application/pdf", FileKind::Document),
+    signature(0, b"PK\x03\x04", "application/zip", FileKind::Archive),
+    signature(0, b"MZ", "application/x-msdownload", FileKind::Executable),
+    signature(0, b"\x7fELF", "application/x-elf", FileKind::Executable),
+];
+
+/// Identifies a file from its leading bytes.
+fn sniff(prefix: &[u8]) -> Option<&'static Signature> {
+    SIGNATURES.iter().find(|signature| {
+        prefix
+            .get(signature.offset..signature.offset + signature.magic.len())
+            .is_some_and(|bytes| bytes == signature.magic)
+    })
+}
+
+/// Kind a declared content type claims, or `None` when it claims nothing
+/// checkable (text, octet-stream, or unknown types).
+fn declared_kind(content_type: &str) -> Option<FileKind> {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    let (top, sub) = mime.split_once('/')?;
+    match (top, sub) {
+        ("image", _) => Some(FileKind::Image),
+        ("audio", _) => Some(FileKind::Audio),
+        ("video", _) => Some(FileKind::Video),
+        ("application", "pdf") => Some(FileKind::Document),
+        ("application", "zip") => Some(FileKind::Archive),
+        _ => None,
+    }
+}
+
+/// Checks a stored upload before it is committed: it must not be empty, must
+/// match the size declared when its URL was signed, must not be an
+/// executable, and a media content type must agree with its file signature.
+///
+/// Returns the reason for rejecting it.
+pub fn validate_upload(preview: &ObjectPreview, declared_size: Option<i64>) -> Result<(), String> {
+    if preview.size_bytes <= 0 {
+        return Err("Upload is empty".to_string());
+    }
+    if let Some(declared) = declared_size.filter(|declared| *declared != preview.size_bytes) {
+        return Err(format!(
+            "Upload is {} bytes but {declared} bytes were declared",
+            preview.size_bytes
+        ));
+    }
+
+    let sniffed = sniff(&preview.prefix);
+    if let Some(signature) = sniffed.filter(|signature| signature.kind == FileKind::Executable) {
+        return Err(format!(
+            "Executable uploads are not allowed ({})",
+            signature.mime
+        ));
+    }
+
+    let Some(declared) = preview.content_type.as_deref() else {
+        return Ok(());
+    };
+    let Some(kind) = declared_kind(declared) else {
+        return Ok(());
+    };
+    match sniffed {
+        Some(signature) if signature.kind.admits(kind) => Ok(()),
+        Some(signature) => Err(format!(
+            "Upload declared as {} but its contents are {}",
+            declared.trim(),
+            signature.mime
+        )),
+        // SVG and other text-based images carry no binary signature.
+        None if kind == FileKind::Image && declared.contains("svg") => Ok(()),
+        None => Err(format!(
+            "Upload declared as {} but its contents are not recognised",
+            declared.trim()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview(size_bytes: i64, content_type: Option<&str>, prefix: &[u8]) -> ObjectPreview {
+        ObjectPreview {
+            size_bytes,
+            content_type: content_type.map(ToString::to_string),
+            prefix: prefix.to_vec(),
+        }
+    }
+
+    fn sniffed_mime(prefix: &[u8]) -> Option<&'static str> {
+        sniff(prefix).map(|signature| signature.mime)
+    }
+
+    #[test]
+    fn sniff_identifies_common_signatures() {
+        assert_eq!(sniffed_mime(b"\x89PNG\r\n\x1a\nrest"), Some("image/png"));
+        assert_eq!(sniffed_mime(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniffed_mime(b"\0\0\0\x18ftypheic"), Some("image/heic"));
+        assert_eq!(sniffed_mime(b"\0\0\0\x20ftypisom"), Some("video/mp4"));
+        assert_eq!(sniffed_mime(b"%PDF-1.7"), Some("application/pdf"));
+        assert_eq!(sniffed_mime(b"MZ\x90\0"), Some("application/x-msdownload"));
+        assert_eq!(sniffed_mime(b"plain text"), None);
+        assert_eq!(sniffed_mime(b""), None);
+    }
+
+    #[test]
+    fn validate_upload_accepts_matching_and_untyped_uploads() {
+        assert!(validate_upload(
+            &preview(10, Some("image/png"), b"\x89PNG\r\n\x1a\n"),
+            Some(10)
+        )
+        .is_ok());
+        assert!(
+            validate_upload(&preview(10, Some("image/jpg"), b"\xff\xd8\xff\xe0"), None).is_ok()
+        );
+        assert!(validate_upload(
+            &preview(10, Some("application/octet-stream"), b"anything"),
+            None
+        )
+        .is_ok());
+        assert!(validate_upload(&preview(10, Some("text/markdown"), b"# Notes"), None).is_ok());
+        assert!(validate_upload(&preview(10, Some("image/svg+xml"), b"<svg"), None).is_ok());
+        assert!(validate_upload(&preview(10, None, b"\0\0\0\x18ftypmp42"), None).is_ok());
+    }
+
+    #[test]
+    fn validate_upload_accepts_audio_in_shared_containers() {
+        let webm = b"\x1a\x45\xdf\xa3\x9f\x42\x86\x81";
+        assert!(validate_upload(&preview(10, Some("audio/webm;codecs=opus"), webm), None).is_ok());
+        assert!(validate_upload(&preview(10, Some("video/webm"), webm), None).is_ok());
+        assert!(
+            validate_upload(&preview(10, Some("audio/mp4"), b"\0\0\0\x1cftypisom"), None).is_ok()
+        );
+        assert!(validate_upload(
+            &preview(10, Some("audio/3gpp"), b"\0\0\0\x18ftyp3gp4"),
+            None
+        )
+        .is_ok());
+        assert!(validate_upload(&preview(10, Some("video/ogg"), b"OggS\0\x02"), None).is_ok());
+        for frame_sync in [b"\xff\xfb", b"\xff\xfa", b"\xff\xf3", b"\xff\xf2"] {
+            assert!(validate_upload(&preview(10, Some("audio/mpeg"), frame_sync), None).is_ok());
+        }
+        assert!(validate_upload(&preview(10, Some("image/png"), webm), None)
+            .unwrap_err()
+            .contains("video/webm"));
+    }
+
+    #[test]
+    fn validate_upload_rejects_size_mismatches_spoofed_types_and_executables() {
+        assert!(validate_upload(&preview(0, Some("image/png"), b""), None)
+            .unwrap_err()
+            .contains("empty"));
+        assert!(validate_upload(&preview(12, None, b"data"), Some(10))
+            .unwrap_err()
+            .contains("10 bytes were declared"));
+        assert!(
+            validate_upload(&preview(10, Some("image/png"), b"%PDF-1.4"), None)
+                .unwrap_err()
+                .contains("application/pdf")
+        );
+        assert!(
+            validate_upload(&preview(10, Some("video/mp4"), b"not a video"), None)
+                .unwrap_err()
+                .contains("not recognised")
+        );
+        assert!(validate_upload(
+            &preview(10, Some("application/octet-stream"), b"\x7fELF\x02"),
+            None
+        )
+        .unwrap_err()
+        .contains("Executable"));
+    }
+}
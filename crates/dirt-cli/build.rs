@@ -0,0 +1,48 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=DIRT_EMBEDDED_BOOTSTRAP_MANIFEST");
+
+    if let Err(error) = write_embedded_bootstrap_manifest() {
+        panic!("failed to embed bootstrap manifest: {error}");
+    }
+}
+
+/// Copy the manifest named by `DIRT_EMBEDDED_BOOTSTRAP_MANIFEST` into `OUT_DIR`.
+///
+/// Packagers point the variable at a bootstrap manifest JSON file (relative
+/// paths resolve from the workspace root); without it an empty file is
+/// written and the binary ships without an embedded manifest.
+fn write_embedded_bootstrap_manifest() -> io::Result<()> {
+    let out_dir = env::var_os("OUT_DIR")
+        .map(PathBuf::from)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "OUT_DIR is not set"))?;
+    fs::create_dir_all(&out_dir)?;
+
+    let content = match env::var("DIRT_EMBEDDED_BOOTSTRAP_MANIFEST")
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+    {
+        Some(path) => {
+            let manifest_dir =
+                env::var_os("CARGO_MANIFEST_DIR").map_or_else(|| PathBuf::from("."), PathBuf::from);
+            let path = manifest_dir.join("..").join("..").join(path.trim());
+            println!("cargo:rerun-if-changed={}", path.display());
+            let content = fs::read_to_string(&path).map_err(|error| {
+                io::Error::new(error.kind(), format!("{}: {error}", path.display()))
+            })?;
+            serde_json::from_str::<serde_json::Value>(&content).map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} is not valid JSON: {error}", path.display()),
+                )
+            })?;
+            content
+        }
+        None => String::new(),
+    };
+    fs::write(out_dir.join("embedded-bootstrap-manifest.json"), content)
+}
@@ -2,8 +2,9 @@
 
 use std::time::Duration;
 
+use dirt_core::changelog::CURRENT_VERSION;
 use dirt_core::config::{
-    parse_bootstrap_manifest as parse_core_bootstrap_manifest, BootstrapConfig,
+    parse_bootstrap_manifest_for, BootstrapConfig, ClientPlatform, ManifestTarget,
 };
 use thiserror::Error;
 
@@ -35,6 +36,10 @@ pub enum ManagedBootstrapError {
     HttpStatus { status: u16, body: String },
     #[error("Invalid bootstrap payload: {0}")]
     InvalidPayload(String),
+    #[error(
+        "Update required: this backend needs Dirt {minimum} or newer (this is {CURRENT_VERSION})"
+    )]
+    UpdateRequired { minimum: String },
 }
 
 /// Fetches and validates a managed bootstrap manifest from the given URL.
@@ -78,8 +83,17 @@ fn parse_bootstrap_manifest_with_url(
     payload: &str,
     manifest_url: &str,
 ) -> Result<ManagedBootstrapConfig, ManagedBootstrapError> {
-    let config = parse_core_bootstrap_manifest(payload, manifest_url)
-        .map_err(ManagedBootstrapError::InvalidPayload)?;
+    let config = parse_bootstrap_manifest_for(
+        payload,
+        manifest_url,
+        ManifestTarget::current(ClientPlatform::Cli),
+    )
+    .map_err(ManagedBootstrapError::InvalidPayload)?;
+    if let Some(minimum) = config.update_required(CURRENT_VERSION) {
+        return Err(ManagedBootstrapError::UpdateRequired {
+            minimum: minimum.to_string(),
+        });
+    }
     managed_bootstrap_from_core(config)
 }
 
@@ -207,7 +221,7 @@ mod tests {
     fn parse_bootstrap_manifest_rejects_invalid_schema() {
         let payload = r#"
         {
-          "schema_version": 2,
+          "schema_version": 9,
           "manifest_version": "v1",
           "supabase_url": "https://project.supabase.co",
           "supabase_anon_key": "anon",
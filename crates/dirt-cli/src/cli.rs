@@ -1,11 +1,14 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand, ValueEnum};
+use dirt_core::logging::LogFormat;
+use dirt_core::models::{AccessScope, DEFAULT_TRASH_RETENTION_DAYS};
 
 #[derive(Parser)]
 #[command(name = "dirt")]
 #[command(about = "Capture fleeting thoughts from the command line")]
 #[command(version)]
+#[command(disable_help_subcommand = true)]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -18,6 +21,15 @@ pub struct Cli {
     #[arg(long, global = true, value_name = "NAME")]
     pub profile: Option<String>,
 
+    /// Apply destructive changes without prompting, even to many notes
+    #[arg(long, global = true)]
+    pub force: bool,
+
+    /// Log format: text, or json for one JSON object per event on stderr
+    /// (defaults to $DIRT_LOG_FORMAT, then text)
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub log_format: Option<LogFormat>,
+
     /// Quick capture: dirt "my thought here"
     #[arg(trailing_var_arg = true)]
     pub note: Vec<String>,
@@ -29,7 +41,14 @@ pub enum Commands {
     #[command(alias = "new")]
     Add {
         /// Note content
+        #[arg(conflicts_with = "template")]
         content: Vec<String>,
+        /// Fill in a capture template (meeting, standup, reading)
+        #[arg(long)]
+        template: Option<String>,
+        /// Template field value, repeatable
+        #[arg(long = "field", value_name = "KEY=VALUE", requires = "template")]
+        fields: Vec<String>,
     },
     /// List recent notes
     List {
@@ -39,39 +58,340 @@ pub enum Commands {
         /// Filter notes by tag name
         #[arg(long)]
         tag: Option<String>,
+        /// Show archived notes instead
+        #[arg(long, conflicts_with = "tag")]
+        archived: bool,
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Columns to show, e.g. `id,preview,tags,created,updated`
+        #[arg(long, value_name = "COLUMNS")]
+        columns: Option<String>,
+        /// Show full ids and never clip previews or tags
+        #[arg(long)]
+        no_truncate: bool,
     },
     /// Search notes
     Search {
-        /// Search query
+        /// Search query (`source:<name>` filters by capture source, e.g. `source:cli`;
+        /// `url:<text>` keeps notes linking a URL containing the text)
         query: String,
         /// Number of notes to show
         #[arg(short, long, default_value = "10")]
         limit: usize,
+        /// Also match attachment filenames and the text of text and PDF attachments
+        #[arg(long = "attachments")]
+        include_attachments: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+        /// Columns to show, e.g. `id,preview,tags,created,updated`
+        #[arg(long, value_name = "COLUMNS")]
+        columns: Option<String>,
+        /// Show full ids and never clip previews or tags
+        #[arg(long)]
+        no_truncate: bool,
+    },
+    /// List links captured in notes, newest notes first
+    Urls {
+        /// Number of links to show
+        #[arg(short, long, default_value = "50")]
+        limit: usize,
+        /// Only links from notes with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List notes that link to a note with [[title]] or [[id]]
+    Links {
+        /// Note ID or unique ID prefix
+        id: String,
         /// Output as JSON
         #[arg(long)]
         json: bool,
     },
+    /// Open today's daily note in the editor, creating it when missing
+    Today,
+    /// Open yesterday's daily note in the editor, creating it when missing
+    Yesterday,
     /// Edit an existing note
     Edit {
         /// Note ID or unique ID prefix
         id: String,
     },
-    /// Delete an existing note
+    /// Translate a note into another language
+    Translate {
+        /// Note ID or unique ID prefix
+        id: String,
+        /// Target language code (e.g. ja, de, pt-BR)
+        #[arg(long, value_name = "LANG")]
+        to: String,
+        /// Append the translation to the note instead of creating a linked note
+        #[arg(long)]
+        append: bool,
+    },
+    /// Transcribe an audio attachment, such as a voice memo
+    Transcribe {
+        /// Attachment ID or unique ID prefix
+        #[arg(value_name = "ATTACHMENT_ID")]
+        attachment_id: String,
+        /// Append the transcript to the attachment's note
+        #[arg(long)]
+        append: bool,
+        /// Use this provider instead of the profile's setting (openai or whisper)
+        #[arg(long, value_name = "PROVIDER")]
+        provider: Option<String>,
+    },
+    /// Manage offline Whisper transcription models
+    Whisper {
+        #[command(subcommand)]
+        command: WhisperCommands,
+    },
+    /// Summarize a note and keep the summary for list previews
+    Summarize {
+        /// Note ID or unique ID prefix
+        id: String,
+        /// Print the stored summary as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete an existing note, or every note with a tag
     Delete {
+        /// Note ID or unique ID prefix
+        #[arg(required_unless_present = "tag", conflicts_with = "tag")]
+        id: Option<String>,
+        /// Delete all notes carrying this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+        /// Show what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List open `- [ ]` tasks across notes
+    Tasks {
+        #[command(subcommand)]
+        command: TaskCommands,
+    },
+    /// List, restore, or permanently remove deleted notes
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
+    /// Encrypt a note so it cannot be read until a date
+    Seal {
         /// Note ID or unique ID prefix
         id: String,
+        /// Date the note opens (YYYY-MM-DD)
+        #[arg(long)]
+        until: String,
+    },
+    /// Decrypt a sealed note whose date has passed
+    Unseal {
+        /// Note ID or unique ID prefix
+        id: String,
+    },
+    /// Keep a note at the top of the note list
+    Pin {
+        /// Note ID or unique ID prefix
+        id: String,
+        /// Unpin the note instead
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Hide a note from the note list; search and exports still include it
+    Archive {
+        /// Note ID or unique ID prefix
+        id: String,
+        /// Restore the note to the note list instead
+        #[arg(long)]
+        clear: bool,
+    },
+    /// List the saved revisions of a note, newest first
+    History {
+        /// Note ID or unique ID prefix
+        id: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Restore a note to one of its saved revisions
+    Restore {
+        /// Note ID or unique ID prefix
+        id: String,
+        /// Revision number, as listed by `dirt history`
+        #[arg(long, value_name = "N")]
+        revision: i64,
+    },
+    /// Keep a note, or every note with a tag, out of exports, digests, and shares
+    NoExport {
+        /// Note ID or unique ID prefix
+        #[arg(required_unless_present = "tag", conflicts_with = "tag")]
+        id: Option<String>,
+        /// Apply the rule to every note carrying this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Remove the flag or tag rule instead
+        #[arg(long)]
+        clear: bool,
     },
     /// Export notes
     Export {
         /// Export format
         #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
         format: ExportFormat,
-        /// Optional output path (stdout when omitted)
+        /// Optional output path (stdout when omitted; a directory with --incremental)
         #[arg(short, long, value_name = "PATH")]
         output: Option<PathBuf>,
+        /// Include notes marked no-export
+        #[arg(long)]
+        include_private: bool,
+        /// Mirror notes into the output directory, one file per note in the
+        /// chosen format, writing only notes changed since the last run and
+        /// removing deleted ones
+        #[arg(long, requires = "output")]
+        incremental: bool,
+        /// Directory filled by `dirt attachments pull`; Markdown exports embed
+        /// the pulled files where notes reference them inline
+        #[arg(long, value_name = "DIR", conflicts_with = "incremental")]
+        media_dir: Option<PathBuf>,
+    },
+    /// Import notes exported from another app, or a Dirt export
+    Import {
+        /// App or format the export comes from
+        #[arg(long, visible_alias = "format", value_enum)]
+        from: ImportSource,
+        /// Export folder, zip archive, or file
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+        /// Preview what would be imported without saving anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip uploading media attachments
+        #[arg(long)]
+        no_media: bool,
+    },
+    /// Create a public read-only link to a note and its attachments
+    Share {
+        /// Note ID or unique ID prefix
+        id: String,
+        /// How long the link works: 30m, 12h, 7d, or 2w (default 7d, at most 90d)
+        #[arg(long, value_name = "DURATION", conflicts_with_all = ["list", "revoke"])]
+        expires: Option<String>,
+        /// List the note's links instead of creating one
+        #[arg(long, conflicts_with = "revoke")]
+        list: bool,
+        /// Revoke every active link to the note
+        #[arg(long)]
+        revoke: bool,
+        /// Share the note even if it is marked no-export
+        #[arg(long, conflicts_with_all = ["list", "revoke"])]
+        include_private: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Upload a file as an attachment
+    Attach {
+        /// File to upload
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Create a new #inbox note titled from the file name (required)
+        #[arg(long, required = true)]
+        new: bool,
+        /// Print the created note and attachment as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Download or manage attachments in bulk
+    Attachments {
+        #[command(subcommand)]
+        command: AttachmentCommands,
+    },
+    /// Show or clear the local cache of downloaded attachments
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+    /// Summarize recent captures as a Markdown digest
+    Digest {
+        /// Summarize the past 7 days (the default period)
+        #[arg(long)]
+        week: bool,
+        /// Save the digest as a new note tagged #digest
+        #[arg(long)]
+        save: bool,
+        /// Email the digest to the signed-in account via the Dirt API
+        #[arg(long)]
+        email: bool,
+        /// Write the Markdown digest to a file instead of stdout
+        #[arg(short, long, value_name = "PATH", conflicts_with = "json")]
+        output: Option<PathBuf>,
+        /// Print the digest summary as JSON
+        #[arg(long)]
+        json: bool,
+        /// Include notes marked no-export
+        #[arg(long)]
+        include_private: bool,
+    },
+    /// Compile notes from a date range into a printable journal
+    Journal {
+        /// First day to include (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        from: String,
+        /// Last day to include (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        to: String,
+        /// Journal format
+        #[arg(long, value_enum, default_value_t = JournalFormat::Markdown)]
+        format: JournalFormat,
+        /// Optional output path (stdout when omitted; required for PDF)
+        #[arg(short, long, value_name = "PATH", required_if_eq("format", "pdf"))]
+        output: Option<PathBuf>,
+        /// Include notes marked no-export
+        #[arg(long)]
+        include_private: bool,
+    },
+    /// Database maintenance
+    #[command(
+        arg_required_else_help = true,
+        args_conflicts_with_subcommands = true,
+        visible_alias = "maintenance"
+    )]
+    Maintain {
+        #[command(subcommand)]
+        command: Option<MaintainCommands>,
+        /// Refresh planner statistics and report index usage
+        #[arg(long)]
+        analyze: bool,
+        /// Show database size and note counts against the warning thresholds
+        #[arg(long)]
+        usage: bool,
+        /// Permanently remove deleted notes and compact the database (local-only)
+        #[arg(long)]
+        purge_deleted: bool,
+        /// Warn when there are more notes than this (0 turns the warning off)
+        #[arg(long, value_name = "COUNT")]
+        note_limit: Option<u32>,
+        /// Warn when the database grows past this many MB (0 turns the warning off)
+        #[arg(long, value_name = "MB")]
+        size_limit_mb: Option<u32>,
+        /// Keep this many revisions of each note (0 keeps every revision)
+        #[arg(long, value_name = "COUNT")]
+        history_limit: Option<u32>,
+        /// Skip the purge confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Show how notes were captured, broken down by source
+    Stats {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
     /// Generate shell completion scripts
     Completions {
@@ -84,6 +404,18 @@ pub enum Commands {
     },
     /// Sync local replica with remote Turso database
     Sync {
+        /// Keep syncing until interrupted, refreshing credentials before they expire
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between syncs with --watch
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value = "30",
+            value_parser = clap::value_parser!(u64).range(1..),
+            requires = "watch"
+        )]
+        interval: u64,
         #[command(subcommand)]
         command: Option<SyncCommands>,
     },
@@ -97,14 +429,88 @@ pub enum Commands {
         #[command(subcommand)]
         command: AuthCommands,
     },
-    /// Open TUI interface
+    /// Manage personal access tokens for the Dirt API
+    Tokens {
+        #[command(subcommand)]
+        command: TokenCommands,
+    },
+    /// List, rename, merge, and remove tags across notes
+    Tag {
+        #[command(subcommand)]
+        command: TagCommands,
+    },
+    /// Manage routing rules that tag new and edited notes
+    Rules {
+        #[command(subcommand)]
+        command: RuleCommands,
+    },
+    /// Encrypt note content and attachments with a passphrase
+    Encryption {
+        #[command(subcommand)]
+        command: EncryptionCommands,
+    },
+    /// Show the release notes of every version up to this one
+    Changelog {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Browse, search, and edit notes in a full-screen terminal interface
     Tui,
+    /// Show help for a command, or read the offline user guide
+    Help {
+        #[command(subcommand)]
+        command: Option<HelpCommands>,
+    },
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
 pub enum ExportFormat {
     Json,
     Markdown,
+    /// Org-mode, one heading per note
+    Org,
+    /// Plain text
+    Text,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ConflictFormat {
+    Text,
+    Json,
+    Jsonl,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ConflictStrategy {
+    /// Keep the version last-write-wins chose
+    KeepLocal,
+    /// Bring back the version last-write-wins rejected
+    KeepRemote,
+    /// Keep the lines of both versions
+    MergeByLine,
+    /// Edit both versions between conflict markers in $EDITOR
+    Manual,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum JournalFormat {
+    Markdown,
+    Pdf,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ImportSource {
+    /// Google Keep (Google Takeout folder or zip)
+    Keep,
+    /// Simplenote export zip
+    Simplenote,
+    /// Apple Notes exported to a folder
+    AppleNotes,
+    /// Markdown files or an Obsidian vault, with optional YAML frontmatter
+    Markdown,
+    /// Dirt JSON export (one file, or a folder of per-note files)
+    Json,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
@@ -114,17 +520,93 @@ pub enum CompletionShell {
     Fish,
 }
 
+#[derive(Subcommand)]
+pub enum HelpCommands {
+    /// Read the user guide: capture workflows, sync setup, and profiles
+    Guide {
+        /// Page to open (capture, sync, profiles); a menu when omitted
+        page: Option<String>,
+        /// List the guide pages
+        #[arg(long, conflicts_with = "page")]
+        list: bool,
+        /// Print to stdout instead of opening a pager
+        #[arg(long)]
+        no_pager: bool,
+    },
+    /// Help for another command, e.g. `dirt help sync conflicts`
+    #[command(external_subcommand)]
+    Command(Vec<String>),
+}
+
 #[derive(Subcommand)]
 pub enum SyncCommands {
     /// List recently resolved sync conflicts
     Conflicts {
-        /// Number of conflicts to show
+        /// Number of conflicts to show (default 10; unlimited with --since)
+        #[arg(short, long)]
+        limit: Option<usize>,
+        /// Only include conflicts resolved at or after this time
+        /// (YYYY-MM-DD, RFC 3339, or Unix milliseconds)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+        /// Output format; `jsonl` writes one conflict per line with both versions
+        #[arg(long, value_enum, conflicts_with = "json")]
+        format: Option<ConflictFormat>,
+        /// Output as JSON (same as `--format json`)
+        #[arg(long)]
+        json: bool,
+        /// Optional output path (stdout when omitted)
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+        #[command(subcommand)]
+        command: Option<ConflictCommands>,
+    },
+    /// Keep syncing in the background, reporting progress to `dirt sync status`
+    Daemon {
+        /// Seconds between syncs
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value = "30",
+            value_parser = clap::value_parser!(u64).range(1..)
+        )]
+        interval: u64,
+    },
+    /// Show whether a sync daemon is running and when it last synced
+    Status {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show recent syncs with bytes transferred and notes changed
+    History {
+        /// Number of syncs to show
         #[arg(short, long, default_value = "10")]
         limit: usize,
         /// Output as JSON
         #[arg(long)]
         json: bool,
     },
+    /// Keep every note with a tag readable on this device only
+    ExcludeTag {
+        /// Tag whose notes stay off the sync server
+        tag: String,
+        /// Sync notes with the tag again instead
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConflictCommands {
+    /// Settle a conflict again, restoring or merging the rejected version
+    Resolve {
+        /// Conflict id, as listed by `dirt sync conflicts`
+        id: i64,
+        /// How to combine the kept and the rejected version
+        #[arg(long, value_enum)]
+        strategy: ConflictStrategy,
+    },
 }
 
 #[derive(Subcommand)]
@@ -153,6 +635,16 @@ pub enum ConfigCommands {
         #[arg(long)]
         no_activate: bool,
     },
+    /// Show the active profile and each profile's database path
+    Show,
+    /// Show or set the default columns of `dirt list` and `dirt search`
+    Columns {
+        /// Comma-separated columns, e.g. `id,preview,tags`
+        columns: Option<String>,
+        /// Go back to the built-in columns
+        #[arg(long, conflicts_with = "columns")]
+        reset: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -182,3 +674,259 @@ pub enum AuthCommands {
         profile: Option<String>,
     },
 }
+
+#[derive(Subcommand)]
+pub enum AttachmentCommands {
+    /// Download attachments into <DIR>/<note-id>/<filename>, skipping files already pulled
+    Pull {
+        /// Directory to download into
+        #[arg(short, long, value_name = "DIR")]
+        output: PathBuf,
+        /// Only attachments of notes with this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only attachments added on or after this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+        /// Only attachments added on or before this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        until: Option<String>,
+        /// Number of downloads to run at once
+        #[arg(
+            long,
+            value_name = "N",
+            default_value = "4",
+            value_parser = clap::value_parser!(u16).range(1..=16)
+        )]
+        jobs: u16,
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Show how many attachments are cached and how much space they use
+    Stats {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Remove every cached attachment
+    Clear,
+}
+
+#[derive(Subcommand)]
+pub enum TaskCommands {
+    /// List unchecked tasks, grouped by note
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommands {
+    /// List deleted notes, most recently deleted first
+    List {
+        /// Maximum number of notes to show
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Move a deleted note back to the note list
+    Restore {
+        /// Note ID or unique ID prefix
+        id: String,
+    },
+    /// Permanently remove deleted notes and their attachments (local-only)
+    Empty {
+        /// Only remove notes deleted more than this many days ago (0 removes all)
+        #[arg(long, value_name = "DAYS", default_value_t = DEFAULT_TRASH_RETENTION_DAYS)]
+        older_than: u32,
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+        /// Show what would be removed without removing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WhisperCommands {
+    /// List the available models and which are downloaded
+    Models {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Download a model
+    Download {
+        /// Model name (e.g. base, small.en)
+        #[arg(value_name = "MODEL")]
+        model: String,
+    },
+    /// Delete a downloaded model
+    Remove {
+        /// Model name
+        #[arg(value_name = "MODEL")]
+        model: String,
+    },
+    /// Transcribe this profile's voice memos offline with a downloaded model
+    Use {
+        /// Model name
+        #[arg(value_name = "MODEL")]
+        model: String,
+    },
+    /// Go back to transcribing with OpenAI for this profile
+    Off,
+}
+
+#[derive(Subcommand)]
+pub enum MaintainCommands {
+    /// Find attachment records without a stored file, and stored files no note uses
+    Attachments {
+        /// Delete attachment records whose file is missing from storage
+        #[arg(long)]
+        repair: bool,
+        /// Delete stored files that no attachment refers to
+        #[arg(long)]
+        purge: bool,
+        /// Show what --repair and --purge would delete without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+        /// Output the audit as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check database integrity and how much space compacting would reclaim
+    Db {
+        /// Rebuild search indexes, vacuum, and checkpoint the database file
+        #[arg(long)]
+        compact: bool,
+        /// Output the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TagCommands {
+    /// List tags in use, most used first
+    List {
+        /// Show how many notes carry each tag
+        #[arg(long)]
+        counts: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rename a tag in every note (merges into NEW if it is already in use)
+    Rename {
+        /// Tag to rename, e.g. #work
+        old: String,
+        /// New name
+        new: String,
+    },
+    /// Replace several tags with one in every note
+    Merge {
+        /// Tags to fold into the target
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Tag to keep
+        #[arg(long, value_name = "TAG")]
+        into: String,
+    },
+    /// Remove a tag from every note
+    Remove {
+        /// Tag to remove
+        tag: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RuleCommands {
+    /// Add a rule that runs after the existing ones
+    Add {
+        /// Condition: #tag, source:<name>, or contains:<text>
+        #[arg(long = "if", value_name = "CONDITION")]
+        condition: String,
+        /// Tag to add to matching notes, e.g. #inbox
+        #[arg(long = "then", value_name = "ACTION")]
+        action: String,
+    },
+    /// List rules in the order they run
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Remove a rule
+    Remove {
+        /// Rule ID, as shown by `dirt rules list`
+        id: i64,
+    },
+    /// Show what the rules would do to a note, without saving anything
+    Test {
+        /// Note text to route
+        text: String,
+        /// Capture source to assume, e.g. email-in
+        #[arg(long, value_name = "NAME")]
+        source: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EncryptionCommands {
+    /// Show whether notes are encrypted and unlocked
+    Status,
+    /// Encrypt every note with a passphrase (it cannot be recovered)
+    Enable {
+        /// Passphrase to encrypt with; defaults to $DIRT_PASSPHRASE
+        #[arg(long, value_name = "PASSPHRASE")]
+        passphrase: Option<String>,
+    },
+    /// Check a passphrase and encrypt notes still stored in plaintext
+    Unlock {
+        /// Passphrase to unlock with; defaults to $DIRT_PASSPHRASE
+        #[arg(long, value_name = "PASSPHRASE")]
+        passphrase: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TokenCommands {
+    /// Create a personal access token (printed once)
+    Create {
+        /// Label to identify the token later
+        name: String,
+        /// Granted scope: notes:read, notes:write, media:read, media:write (repeatable)
+        #[arg(long = "scope", value_name = "SCOPE", required = true)]
+        scopes: Vec<AccessScope>,
+        /// Days until the token expires (server default when omitted)
+        #[arg(long, value_name = "DAYS")]
+        expires_in_days: Option<u32>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List personal access tokens
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Revoke a personal access token
+    Revoke {
+        /// Token ID
+        id: String,
+    },
+}
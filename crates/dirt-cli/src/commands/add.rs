@@ -1,14 +1,54 @@
 use std::path::Path;
 
+use dirt_core::capture::{run_post_capture, PostCaptureActions};
+use dirt_core::models::{builtin_templates, find_template, parse_field_assignment, NoteSource};
+
 use crate::commands::common::{open_database, resolve_note_content};
 use crate::error::CliError;
 
-pub async fn run_add(content_parts: &[String], db_path: &Path) -> Result<(), CliError> {
-    let content = resolve_note_content(content_parts)?;
+pub async fn run_add(
+    content_parts: &[String],
+    template: Option<&str>,
+    fields: &[String],
+    db_path: &Path,
+) -> Result<(), CliError> {
+    let content = match template {
+        Some(template) => render_template(template, fields)?,
+        None => resolve_note_content(content_parts)?,
+    };
 
     let db = open_database(db_path).await?;
-    let note = db.create_note(&content).await?;
+    let note = db.create_note_from(&content, NoteSource::Cli).await?;
 
     println!("{}", note.id);
+
+    // The id is printed above, so the CLI skips the copy-to-clipboard action.
+    let actions = PostCaptureActions::from_settings(&db.load_settings().await?);
+    if !actions.is_empty() {
+        let today = chrono::Local::now().date_naive();
+        let report = run_post_capture(&db, &actions, note, NoteSource::Cli, today, None).await;
+        for failure in &report.failures {
+            eprintln!("Warning: {failure}");
+        }
+    }
     Ok(())
 }
+
+pub fn render_template(template_id: &str, fields: &[String]) -> Result<String, CliError> {
+    let Some(template) = find_template(template_id) else {
+        let available = builtin_templates()
+            .into_iter()
+            .map(|template| template.id)
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(CliError::UnknownTemplate {
+            name: template_id.to_string(),
+            available,
+        });
+    };
+    let values = fields
+        .iter()
+        .map(|field| parse_field_assignment(field))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(template.render(&values)?)
+}
@@ -0,0 +1,121 @@
+use std::time::Instant;
+
+use dirt_core::http::RateLimited;
+use dirt_core::logging::{new_request_id, REQUEST_ID_HEADER};
+use dirt_core::media::MediaApiClient;
+use dirt_core::share::ShareApiClient;
+use reqwest::{Client, Method, RequestBuilder, Response};
+use serde::Deserialize;
+
+use crate::commands::common::restore_profile_session;
+use crate::config_profiles::CliProfilesConfig;
+use crate::error::CliError;
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    error: String,
+}
+
+/// Authenticated client for session-only Dirt API endpoints under `/v1`.
+pub struct DirtApi {
+    client: Client,
+    base_url: String,
+    access_token: String,
+}
+
+impl DirtApi {
+    pub async fn for_profile(global_profile: Option<&str>) -> Result<Self, CliError> {
+        let config = CliProfilesConfig::load().map_err(CliError::Config)?;
+        let profile_name = config.resolve_profile_name(global_profile);
+        let profile = config.profile(&profile_name).ok_or_else(|| {
+            CliError::Config(format!(
+                "Profile '{profile_name}' is not configured. Run `dirt config init --profile {profile_name}` first."
+            ))
+        })?;
+        let base_url = profile.api_base_url().ok_or_else(|| {
+            CliError::Config(format!(
+                "Profile '{profile_name}' has no Dirt API URL. Set one with `dirt config init --api-base-url <URL>`."
+            ))
+        })?;
+        let session = restore_profile_session(&profile_name, profile)
+            .await?
+            .ok_or_else(|| {
+                CliError::Auth(format!(
+                    "Profile '{profile_name}' is not signed in. Run `dirt auth login` first."
+                ))
+            })?;
+
+        Ok(Self {
+            client: Client::new(),
+            base_url,
+            access_token: session.access_token,
+        })
+    }
+
+    /// Media client for the profile's API, with the session access token.
+    pub fn media_client(&self) -> Result<(MediaApiClient, &str), CliError> {
+        let client = MediaApiClient::new(self.base_url.as_str()).map_err(CliError::Api)?;
+        Ok((client, self.access_token.as_str()))
+    }
+
+    /// Share link client for the profile's API, with the session access token.
+    pub fn share_client(&self) -> Result<(ShareApiClient, &str), CliError> {
+        let client = ShareApiClient::new(self.base_url.as_str()).map_err(CliError::Api)?;
+        Ok((client, self.access_token.as_str()))
+    }
+
+    pub fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client
+            .request(method, format!("{}/v1{path}", self.base_url))
+            .bearer_auth(&self.access_token)
+            .header("Accept", "application/json")
+    }
+}
+
+/// Send `request` tagged with a fresh `x-request-id`, logging it with the
+/// field names the Dirt API uses for the same request.
+pub async fn send(request: RequestBuilder) -> Result<Response, CliError> {
+    let request_id = new_request_id();
+    let (client, request) = request
+        .header(REQUEST_ID_HEADER, request_id.as_str())
+        .build_split();
+    let request = request.map_err(|error| CliError::Api(error.to_string()))?;
+    let method = request.method().clone();
+    let path = request.url().path().to_string();
+    let started = Instant::now();
+    let response = client
+        .execute(request)
+        .await
+        .map_err(|error| CliError::Api(error.to_string()))?;
+    let status = response.status();
+    tracing::debug!(
+        request_id = %request_id,
+        method = %method,
+        path = %path,
+        status = status.as_u16(),
+        latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        "Dirt API request"
+    );
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    if let Some(rate_limited) = RateLimited::from_response(status, response.headers()) {
+        return Err(CliError::Api(rate_limited.to_string()));
+    }
+    let message = response
+        .json::<ApiErrorBody>()
+        .await
+        .map_or_else(|_| status.to_string(), |body| body.error);
+    Err(CliError::Api(format!(
+        "HTTP {}: {message}",
+        status.as_u16()
+    )))
+}
+
+pub async fn parse_json<T: for<'de> Deserialize<'de>>(response: Response) -> Result<T, CliError> {
+    response
+        .json::<T>()
+        .await
+        .map_err(|error| CliError::Api(format!("Invalid response: {error}")))
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use dirt_core::media::{capture_attachment_file, CapturedAttachment};
+use dirt_core::models::{AttachmentScanStatus, NoteSource};
+use serde::Serialize;
+
+use crate::commands::api_client::DirtApi;
+use crate::commands::common::open_database;
+use crate::error::CliError;
+
+#[derive(Debug, Serialize)]
+pub struct CapturedAttachmentItem {
+    pub note_id: String,
+    pub title: String,
+    pub attachment_id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub scan_status: AttachmentScanStatus,
+}
+
+/// Upload `file` as the attachment of a new `#inbox` note.
+pub async fn run_attach_new(
+    file: &Path,
+    as_json: bool,
+    db_path: &Path,
+    global_profile: Option<&str>,
+) -> Result<(), CliError> {
+    if !file.is_file() {
+        return Err(CliError::Core(dirt_core::Error::InvalidInput(format!(
+            "{} is not a file",
+            file.display()
+        ))));
+    }
+
+    let api = DirtApi::for_profile(global_profile).await?;
+    let (client, access_token) = api.media_client()?;
+    let db = open_database(db_path).await?;
+    let captured =
+        capture_attachment_file(&db, &client, access_token, file, NoteSource::Cli).await?;
+
+    if as_json {
+        let item = captured_attachment_to_item(&captured);
+        println!("{}", serde_json::to_string_pretty(&item)?);
+    } else {
+        println!("{}", captured.note.id);
+    }
+    if captured.attachment.scan_status == AttachmentScanStatus::Quarantined {
+        eprintln!(
+            "Warning: {} was quarantined by the content scanner",
+            captured.attachment.filename
+        );
+    }
+    Ok(())
+}
+
+pub fn captured_attachment_to_item(captured: &CapturedAttachment) -> CapturedAttachmentItem {
+    CapturedAttachmentItem {
+        note_id: captured.note.id.to_string(),
+        title: captured.note.title_preview(usize::MAX),
+        attachment_id: captured.attachment.id.as_str(),
+        filename: captured.attachment.filename.clone(),
+        mime_type: captured.attachment.mime_type.clone(),
+        size_bytes: captured.attachment.size_bytes,
+        scan_status: captured.attachment.scan_status,
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate};
+use dirt_core::journal::parse_journal_date;
+use dirt_core::models::Attachment;
+use dirt_core::services::DatabaseService;
+use dirt_core::util::fs::{sanitize_file_name, write_atomic};
+use dirt_core::NoteId;
+use serde::Serialize;
+use tokio::task::JoinSet;
+
+use crate::commands::api_client::DirtApi;
+use crate::commands::common::open_database;
+use crate::error::CliError;
+
+/// What `dirt attachments pull` should download and where to put it.
+pub struct PullOptions<'a> {
+    pub output: &'a Path,
+    pub tag: Option<&'a str>,
+    pub since: Option<&'a str>,
+    pub until: Option<&'a str>,
+    pub jobs: usize,
+    pub json: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct PullSummary {
+    pub downloaded: usize,
+    pub already_present: usize,
+    pub quarantined: usize,
+    pub failed: usize,
+    pub bytes_downloaded: u64,
+}
+
+/// One attachment to download and the file it lands in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedDownload {
+    pub attachment: Attachment,
+    pub destination: PathBuf,
+}
+
+/// Download every matching attachment into `<output>/<note-id>/<filename>`.
+///
+/// Files already on disk with the recorded size are skipped, so an
+/// interrupted pull can simply be run again.
+pub async fn run_attachments_pull(
+    options: PullOptions<'_>,
+    db_path: &Path,
+    global_profile: Option<&str>,
+) -> Result<PullSummary, CliError> {
+    let since = options.since.map(parse_journal_date).transpose()?;
+    let until = options.until.map(parse_journal_date).transpose()?;
+    if let (Some(since), Some(until)) = (since, until) {
+        if since > until {
+            return Err(dirt_core::Error::InvalidInput(format!(
+                "--since {since} is after --until {until}"
+            ))
+            .into());
+        }
+    }
+
+    let db = open_database(db_path).await?;
+    let mut attachments: Vec<Attachment> = db
+        .list_all_attachments()
+        .await?
+        .into_iter()
+        .filter(|attachment| created_within(attachment.created_at, since, until))
+        .collect();
+
+    if let Some(tag) = options.tag {
+        let tagged = tagged_note_ids(&db, &attachments, tag).await?;
+        attachments.retain(|attachment| tagged.contains(&attachment.note_id));
+    }
+
+    let mut summary = PullSummary::default();
+    let mut pending = Vec::new();
+    for planned in plan_downloads(options.output, attachments) {
+        if !planned.attachment.scan_status.is_downloadable() {
+            summary.quarantined += 1;
+        } else if is_already_pulled(&planned.destination, planned.attachment.size_bytes) {
+            summary.already_present += 1;
+        } else {
+            pending.push(planned);
+        }
+    }
+
+    if !pending.is_empty() {
+        let api = DirtApi::for_profile(global_profile).await?;
+        let (client, access_token) = api.media_client()?;
+        let total = pending.len();
+        let jobs = options.jobs.max(1);
+        let mut queue = pending.into_iter();
+        let mut in_flight = JoinSet::new();
+        let mut finished = 0;
+
+        loop {
+            while in_flight.len() < jobs {
+                let Some(planned) = queue.next() else {
+                    break;
+                };
+                let client = client.clone();
+                let access_token = access_token.to_string();
+                let db = db.clone();
+                in_flight.spawn(async move {
+                    let result = async {
+                        let (bytes, _content_type) = client
+                            .download(&access_token, &planned.attachment.r2_key)
+                            .await
+                            .map_err(String::from)?;
+                        let bytes = db
+                            .decrypt_attachment_bytes(bytes)
+                            .await
+                            .map_err(|error| error.to_string())?;
+                        save_download(&planned.destination, &bytes)
+                            .map_err(|error| error.to_string())
+                    }
+                    .await;
+                    (planned, result)
+                });
+            }
+
+            let Some(joined) = in_flight.join_next().await else {
+                break;
+            };
+            finished += 1;
+            match joined {
+                Ok((planned, Ok(bytes))) => {
+                    summary.downloaded += 1;
+                    summary.bytes_downloaded += bytes;
+                    eprintln!("[{finished}/{total}] {}", planned.destination.display());
+                }
+                Ok((planned, Err(error))) => {
+                    summary.failed += 1;
+                    eprintln!(
+                        "[{finished}/{total}] Failed to download {} ({}): {error}",
+                        planned.attachment.filename, planned.attachment.id
+                    );
+                }
+                Err(join_error) => {
+                    summary.failed += 1;
+                    eprintln!("[{finished}/{total}] Download task failed: {join_error}");
+                }
+            }
+        }
+    }
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        println!("{}", format_pull_summary(&summary));
+    }
+
+    if summary.failed > 0 {
+        return Err(CliError::Api(format!(
+            "{} attachment(s) failed to download; run the pull again to retry",
+            summary.failed
+        )));
+    }
+    Ok(summary)
+}
+
+/// Assign each attachment a destination under `output/<note-id>/`.
+///
+/// File names are sanitized; when two attachments of one note would share a
+/// name, the later one is prefixed with its attachment id so the layout stays
+/// stable across runs.
+pub fn plan_downloads(output: &Path, attachments: Vec<Attachment>) -> Vec<PlannedDownload> {
+    let mut taken = HashSet::new();
+    attachments
+        .into_iter()
+        .map(|attachment| {
+            let note_dir = output.join(attachment.note_id.to_string());
+            let file_name = sanitize_file_name(&attachment.filename);
+            let mut destination = note_dir.join(&file_name);
+            if !taken.insert(destination.clone()) {
+                destination = note_dir.join(format!("{}-{file_name}", attachment.id));
+                taken.insert(destination.clone());
+            }
+            PlannedDownload {
+                attachment,
+                destination,
+            }
+        })
+        .collect()
+}
+
+/// Whether `path` already holds a complete copy of an attachment.
+pub fn is_already_pulled(path: &Path, size_bytes: i64) -> bool {
+    let Ok(expected) = u64::try_from(size_bytes) else {
+        return false;
+    };
+    std::fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.len() == expected)
+}
+
+pub fn format_pull_summary(summary: &PullSummary) -> String {
+    format!(
+        "Downloaded {} attachment(s) ({} bytes), {} already present, {} quarantined, {} failed",
+        summary.downloaded,
+        summary.bytes_downloaded,
+        summary.already_present,
+        summary.quarantined,
+        summary.failed
+    )
+}
+
+fn created_within(created_at: i64, since: Option<NaiveDate>, until: Option<NaiveDate>) -> bool {
+    let Some(date) = DateTime::from_timestamp_millis(created_at).map(|time| time.date_naive())
+    else {
+        return false;
+    };
+    since.map_or(true, |since| date >= since) && until.map_or(true, |until| date <= until)
+}
+
+async fn tagged_note_ids(
+    db: &DatabaseService,
+    attachments: &[Attachment],
+    tag: &str,
+) -> Result<HashSet<NoteId>, CliError> {
+    let tag = tag.trim().trim_start_matches('#').to_lowercase();
+    let mut checked: HashMap<NoteId, bool> = HashMap::new();
+    for attachment in attachments {
+        if checked.contains_key(&attachment.note_id) {
+            continue;
+        }
+        let has_tag = db
+            .get_note(&attachment.note_id)
+            .await?
+            .is_some_and(|note| note.tags().iter().any(|note_tag| *note_tag == tag));
+        checked.insert(attachment.note_id, has_tag);
+    }
+
+    Ok(checked
+        .into_iter()
+        .filter_map(|(note_id, has_tag)| has_tag.then_some(note_id))
+        .collect())
+}
+
+/// Write downloaded bytes, creating the note directory on first use.
+fn save_download(destination: &Path, bytes: &[u8]) -> std::io::Result<u64> {
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_atomic(destination, bytes)?;
+    Ok(std::fs::metadata(destination)?.len())
+}
@@ -0,0 +1,62 @@
+use std::path::Path;
+
+use dirt_core::media::{AttachmentCache, CacheStats};
+use dirt_core::models::format_byte_count;
+
+use crate::cli::CacheCommands;
+use crate::commands::common::open_database;
+use crate::error::CliError;
+
+/// Show or empty the local attachment cache next to the database.
+pub async fn run_cache(command: CacheCommands, db_path: &Path) -> Result<CacheStats, CliError> {
+    let db = open_database(db_path).await?;
+    let settings = db.load_settings().await?;
+    let cache =
+        AttachmentCache::beside_database(db_path, settings.attachment_cache_mb.unwrap_or(0))?;
+    match command {
+        CacheCommands::Stats { json } => {
+            let stats = cache.stats();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                println!("Attachment cache: {}", cache.dir().display());
+                for line in format_cache_stats_lines(&stats) {
+                    println!("{line}");
+                }
+            }
+            Ok(stats)
+        }
+        CacheCommands::Clear => {
+            let before = cache.stats();
+            cache.clear()?;
+            println!(
+                "Removed {} cached attachment(s) ({}).",
+                before.entries,
+                format_byte_count(byte_count(before.bytes))
+            );
+            Ok(cache.stats())
+        }
+    }
+}
+
+pub fn format_cache_stats_lines(stats: &CacheStats) -> Vec<String> {
+    let limit = if stats.max_bytes == 0 {
+        "cache disabled".to_string()
+    } else {
+        format!("limit {}", format_byte_count(byte_count(stats.max_bytes)))
+    };
+    vec![
+        format!(
+            "Cached attachments: {} in {} file(s)",
+            stats.entries, stats.files
+        ),
+        format!(
+            "Size: {} ({limit})",
+            format_byte_count(byte_count(stats.bytes))
+        ),
+    ]
+}
+
+fn byte_count(bytes: u64) -> i64 {
+    i64::try_from(bytes).unwrap_or(i64::MAX)
+}
@@ -0,0 +1,42 @@
+use dirt_core::changelog::{releases, Release, CURRENT_VERSION};
+
+use crate::error::CliError;
+
+pub fn run_changelog(as_json: bool) -> Result<(), CliError> {
+    let releases = releases();
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&releases)?);
+    } else {
+        print!("{}", format_changelog(&releases));
+    }
+    Ok(())
+}
+
+pub fn format_changelog(releases: &[Release]) -> String {
+    let mut output = String::new();
+    for (index, release) in releases.iter().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        match &release.date {
+            Some(date) => output.push_str(&format!("{} ({date})\n", release.version)),
+            None => output.push_str(&format!("{}\n", release.version)),
+        }
+        for note in &release.notes {
+            output.push_str(&format!("  - {note}\n"));
+        }
+    }
+    output
+}
+
+/// One-line notice for releases the user has not seen, `None` when there are none.
+pub fn format_update_notice(unseen: &[Release]) -> Option<String> {
+    let changes: usize = unseen.iter().map(|release| release.notes.len()).sum();
+    if changes == 0 {
+        return None;
+    }
+    let noun = if changes == 1 { "change" } else { "changes" };
+    Some(format!(
+        "Dirt was updated to {CURRENT_VERSION} with {changes} new {noun}. Run `dirt changelog` to see what's new."
+    ))
+}
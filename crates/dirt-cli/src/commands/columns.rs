@@ -0,0 +1,275 @@
+//! Column layout for the text output of `dirt list` and `dirt search`.
+//!
+//! Columns come from `--columns`, then the `list_columns` config default.
+//! The id and time columns keep their width; preview and tags share what is
+//! left of the terminal line, so nothing is clipped when it fits. Output that
+//! is not a terminal keeps the historical 40-character preview unless
+//! `--no-truncate` is passed.
+
+use std::env;
+use std::io::{self, IsTerminal};
+use std::str::FromStr;
+
+use chrono::Utc;
+use dirt_core::models::short_note_id;
+use dirt_core::Note;
+
+use crate::commands::common::{format_relative_time, note_preview, render_tags, truncate_chars};
+use crate::config_profiles::CliProfilesConfig;
+use crate::error::CliError;
+
+/// Columns shown when neither `--columns` nor the config picks any
+pub const DEFAULT_LIST_COLUMNS: &[ListColumn] = &[
+    ListColumn::Id,
+    ListColumn::Preview,
+    ListColumn::Updated,
+    ListColumn::Tags,
+];
+
+/// Preview width when stdout is not a terminal
+const PIPED_PREVIEW_CHARS: usize = 40;
+
+/// Narrowest a preview or tags column is squeezed to on small terminals
+const MIN_FLEXIBLE_CHARS: usize = 12;
+
+/// Line width assumed for a terminal that does not report `COLUMNS`
+const FALLBACK_TERMINAL_WIDTH: usize = 80;
+
+const COLUMN_GAP: &str = "  ";
+
+/// One column of the note table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListColumn {
+    Id,
+    Preview,
+    Tags,
+    Created,
+    Updated,
+}
+
+impl ListColumn {
+    pub const ALL: [Self; 5] = [
+        Self::Id,
+        Self::Preview,
+        Self::Tags,
+        Self::Created,
+        Self::Updated,
+    ];
+
+    /// Name used in `--columns` and the config file.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Preview => "preview",
+            Self::Tags => "tags",
+            Self::Created => "created",
+            Self::Updated => "updated",
+        }
+    }
+
+    /// Whether the column shrinks to fit the terminal.
+    const fn is_flexible(self) -> bool {
+        matches!(self, Self::Preview | Self::Tags)
+    }
+
+    fn cell(self, note: &Note, now_ms: i64, id_len: Option<usize>) -> String {
+        match self {
+            Self::Id => {
+                let id = note.id.to_string();
+                match id_len {
+                    Some(len) => short_note_id(&id, len).to_string(),
+                    None => id,
+                }
+            }
+            Self::Preview => note_preview(note, usize::MAX),
+            Self::Tags => render_tags(note),
+            Self::Created => format_relative_time(note.created_at, now_ms),
+            Self::Updated => format_relative_time(note.updated_at, now_ms),
+        }
+    }
+}
+
+impl FromStr for ListColumn {
+    type Err = CliError;
+
+    fn from_str(raw: &str) -> Result<Self, CliError> {
+        let name = raw.trim().to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|column| column.name() == name)
+            .ok_or_else(|| CliError::UnknownListColumn {
+                name,
+                available: available_columns(),
+            })
+    }
+}
+
+fn available_columns() -> String {
+    ListColumn::ALL
+        .iter()
+        .map(|column| column.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse a comma-separated column list such as `id,preview,tags`.
+pub fn parse_list_columns(raw: &str) -> Result<Vec<ListColumn>, CliError> {
+    let columns = raw
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(ListColumn::from_str)
+        .collect::<Result<Vec<_>, _>>()?;
+    if columns.is_empty() {
+        return Err(CliError::EmptyListColumns(available_columns()));
+    }
+    Ok(columns)
+}
+
+/// Format a column list back into its `--columns` form.
+pub fn format_list_columns(columns: &[ListColumn]) -> String {
+    columns
+        .iter()
+        .map(|column| column.name())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Which columns to print and how much room they have.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListLayout {
+    pub columns: Vec<ListColumn>,
+    /// Line width to fit, or `None` when stdout is not a terminal
+    pub width: Option<usize>,
+    /// Shorten ids and clip long cells; off with `--no-truncate`
+    pub truncate: bool,
+}
+
+impl ListLayout {
+    /// Layout for the current invocation: `--columns` wins over the config
+    /// default, and the width comes from the terminal.
+    pub fn resolve(columns: Option<&str>, no_truncate: bool) -> Result<Self, CliError> {
+        let configured = match columns {
+            Some(raw) => Some(raw.to_string()),
+            None => {
+                CliProfilesConfig::load()
+                    .map_err(CliError::Config)?
+                    .list_columns
+            }
+        };
+        let columns = match configured {
+            Some(raw) => parse_list_columns(&raw)?,
+            None => DEFAULT_LIST_COLUMNS.to_vec(),
+        };
+        Ok(Self {
+            columns,
+            width: terminal_width(),
+            truncate: !no_truncate,
+        })
+    }
+
+    /// Width of each column for the given cells.
+    ///
+    /// Flexible columns are filled narrowest first, so a short tags column
+    /// keeps its full width and the preview takes the rest of the line.
+    fn column_widths(&self, rows: &[Vec<String>]) -> Vec<usize> {
+        let natural = (0..self.columns.len())
+            .map(|index| {
+                rows.iter()
+                    .map(|cells| cells[index].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        if !self.truncate {
+            return natural;
+        }
+
+        let Some(line_width) = self.width else {
+            return self
+                .columns
+                .iter()
+                .zip(&natural)
+                .map(|(column, width)| match column {
+                    ListColumn::Preview => (*width).min(PIPED_PREVIEW_CHARS),
+                    _ => *width,
+                })
+                .collect();
+        };
+
+        let gaps = COLUMN_GAP.len() * self.columns.len().saturating_sub(1);
+        let fixed = self
+            .columns
+            .iter()
+            .zip(&natural)
+            .filter(|(column, _)| !column.is_flexible())
+            .map(|(_, width)| width)
+            .sum::<usize>();
+        let mut remaining = line_width.saturating_sub(fixed + gaps);
+
+        let mut flexible = (0..self.columns.len())
+            .filter(|index| self.columns[*index].is_flexible())
+            .collect::<Vec<_>>();
+        flexible.sort_by_key(|index| natural[*index]);
+
+        let mut widths = natural.clone();
+        let mut left = flexible.len();
+        for index in flexible {
+            let share = (remaining / left).max(MIN_FLEXIBLE_CHARS);
+            widths[index] = natural[index].min(share);
+            remaining = remaining.saturating_sub(widths[index]);
+            left -= 1;
+        }
+        widths
+    }
+}
+
+/// Terminal line width, or `None` when stdout is piped.
+fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    let width = env::var("COLUMNS")
+        .ok()
+        .and_then(|raw| raw.trim().parse::<usize>().ok())
+        .filter(|width| *width > 0);
+    Some(width.unwrap_or(FALLBACK_TERMINAL_WIDTH))
+}
+
+/// One aligned line per note.
+///
+/// Ids are cut to `id_len` characters, the shortest prefix that is unique
+/// across the database, unless truncation is off.
+pub fn format_note_lines(notes: &[Note], layout: &ListLayout, id_len: usize) -> Vec<String> {
+    let id_len = layout.truncate.then_some(id_len);
+    let now_ms = Utc::now().timestamp_millis();
+    let rows = notes
+        .iter()
+        .map(|note| {
+            layout
+                .columns
+                .iter()
+                .map(|column| column.cell(note, now_ms, id_len))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let widths = layout.column_widths(&rows);
+
+    rows.iter()
+        .map(|cells| {
+            let line = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| {
+                    let cell = if layout.truncate {
+                        truncate_chars(cell, *width)
+                    } else {
+                        cell.clone()
+                    };
+                    format!("{cell:<width$}")
+                })
+                .collect::<Vec<_>>()
+                .join(COLUMN_GAP);
+            line.trim_end().to_string()
+        })
+        .collect()
+}
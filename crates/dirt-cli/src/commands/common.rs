@@ -1,17 +1,25 @@
 use std::env;
+use std::future::Future;
 use std::io::{self, IsTerminal, Read};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use chrono::Utc;
-use dirt_core::db::SyncConfig;
+use dirt_core::auth::AuthSession;
+use dirt_core::db::{subscribe_migration_progress, MigrationProgress, SyncConfig};
+use dirt_core::models::short_note_id;
+use dirt_core::quota::QUOTA_MAINTENANCE_HINTS;
 use dirt_core::services::DatabaseService;
+use dirt_core::sync::SyncToken;
+use dirt_core::util::editor::preferred_editor;
 use dirt_core::{Note, NoteId, SyncConflict};
 use serde::Serialize;
 
 use crate::auth::{clear_stored_session, load_stored_session, SupabaseAuthService};
-use crate::config_profiles::CliProfilesConfig;
+use crate::commands::changelog::format_update_notice;
+use crate::config_profiles::{CliProfile, CliProfilesConfig};
 use crate::error::CliError;
 use crate::managed_sync::ManagedSyncAuthClient;
 
@@ -24,6 +32,8 @@ pub struct NoteListItem {
     pub updated_at: i64,
     pub relative_time: String,
     pub tags: Vec<String>,
+    pub pinned: bool,
+    pub archived: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -35,6 +45,10 @@ pub struct SyncConflictItem {
     pub resolved_at: i64,
     pub resolved_at_iso: String,
     pub strategy: String,
+    /// Which version the resolution kept: `local` or `incoming`
+    pub kept: &'static str,
+    pub local_content: Option<String>,
+    pub incoming_content: Option<String>,
 }
 
 pub async fn list_notes(
@@ -43,6 +57,14 @@ pub async fn list_notes(
     db_path: &Path,
 ) -> Result<Vec<Note>, CliError> {
     let db = open_database(db_path).await?;
+    list_notes_in(&db, limit, tag).await
+}
+
+pub async fn list_notes_in(
+    db: &DatabaseService,
+    limit: usize,
+    tag: Option<&str>,
+) -> Result<Vec<Note>, CliError> {
     if let Some(tag_name) = tag {
         Ok(db.list_notes_by_tag(tag_name, limit, 0).await?)
     } else {
@@ -50,28 +72,6 @@ pub async fn list_notes(
     }
 }
 
-pub async fn list_all_notes(db_path: &Path) -> Result<Vec<Note>, CliError> {
-    const PAGE_SIZE: usize = 500;
-
-    let db = open_database(db_path).await?;
-
-    let mut notes = Vec::new();
-    let mut offset = 0usize;
-
-    loop {
-        let batch = db.list_notes(PAGE_SIZE, offset).await?;
-        let count = batch.len();
-        notes.extend(batch);
-
-        if count < PAGE_SIZE {
-            break;
-        }
-        offset += count;
-    }
-
-    Ok(notes)
-}
-
 pub async fn search_notes(
     query: &str,
     limit: usize,
@@ -82,11 +82,14 @@ pub async fn search_notes(
 }
 
 pub async fn list_sync_conflicts(
+    db: &DatabaseService,
+    since: Option<i64>,
     limit: usize,
-    db_path: &Path,
 ) -> Result<Vec<SyncConflict>, CliError> {
-    let db = open_database(db_path).await?;
-    Ok(db.list_conflicts(limit).await?)
+    match since {
+        Some(since) => Ok(db.list_conflicts_since(since, limit).await?),
+        None => Ok(db.list_conflicts(limit).await?),
+    }
 }
 
 pub async fn resolve_note_for_edit(
@@ -100,6 +103,7 @@ pub async fn resolve_note_for_edit(
     }
 
     let matching_ids = db.list_note_ids_by_prefix(note_query, 3).await?;
+    let id_len = db.short_note_id_len().await?;
 
     match matching_ids.len() {
         0 => Err(CliError::NoteNotFound(note_query.to_string())),
@@ -115,7 +119,7 @@ pub async fn resolve_note_for_edit(
             let options = matching_ids
                 .iter()
                 .take(3)
-                .map(|id| id.chars().take(13).collect::<String>())
+                .map(|id| short_note_id(id, id_len))
                 .collect::<Vec<_>>()
                 .join(", ");
 
@@ -126,26 +130,6 @@ pub async fn resolve_note_for_edit(
     }
 }
 
-pub fn format_note_lines(notes: &[Note]) -> Vec<String> {
-    let now_ms = Utc::now().timestamp_millis();
-    notes
-        .iter()
-        .map(|note| {
-            let id = note.id.to_string();
-            let short_id = id.chars().take(13).collect::<String>();
-            let preview = note_preview(note, 40);
-            let relative_time = format_relative_time(note.updated_at, now_ms);
-            let tags = render_tags(note);
-
-            if tags.is_empty() {
-                format!("{short_id:<13}  {preview:<40}  {relative_time}")
-            } else {
-                format!("{short_id:<13}  {preview:<40}  {relative_time:<10}  {tags}")
-            }
-        })
-        .collect()
-}
-
 pub fn note_to_list_item(note: &Note) -> NoteListItem {
     let now_ms = Utc::now().timestamp_millis();
     let mut tags = note.tags();
@@ -159,6 +143,8 @@ pub fn note_to_list_item(note: &Note) -> NoteListItem {
         updated_at: note.updated_at,
         relative_time: format_relative_time(note.updated_at, now_ms),
         tags,
+        pinned: note.is_pinned,
+        archived: note.is_archived,
     }
 }
 
@@ -171,18 +157,29 @@ pub fn sync_conflict_to_item(conflict: &SyncConflict) -> SyncConflictItem {
         resolved_at: conflict.resolved_at,
         resolved_at_iso: format_sync_timestamp(conflict.resolved_at),
         strategy: conflict.strategy.clone(),
+        kept: if conflict.local_updated_at >= conflict.incoming_updated_at {
+            "local"
+        } else {
+            "incoming"
+        },
+        local_content: conflict.local_content.clone(),
+        incoming_content: conflict.incoming_content.clone(),
     }
 }
 
 pub fn note_preview(note: &Note, max_chars: usize) -> String {
     let first_line = note.content.lines().next().unwrap_or("").trim();
     let collapsed = first_line.split_whitespace().collect::<Vec<_>>().join(" ");
+    truncate_chars(&collapsed, max_chars)
+}
 
-    if collapsed.chars().count() <= max_chars {
-        collapsed
+/// Shorten `text` to at most `max_chars`, marking the cut with `...`.
+pub fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
     } else {
         let take_len = max_chars.saturating_sub(3);
-        let mut truncated = collapsed.chars().take(take_len).collect::<String>();
+        let mut truncated = text.chars().take(take_len).collect::<String>();
         truncated.push_str("...");
         truncated
     }
@@ -197,15 +194,16 @@ pub fn render_tags(note: &Note) -> String {
         .join(" ")
 }
 
-pub fn format_sync_conflict_lines(conflicts: &[SyncConflict]) -> Vec<String> {
+pub fn format_sync_conflict_lines(conflicts: &[SyncConflict], id_len: usize) -> Vec<String> {
     conflicts
         .iter()
         .map(|conflict| {
             format!(
-                "{}  {:<4}  note={}  local={} incoming={}",
+                "{}  #{:<4}  {:<13}  note={}  local={} incoming={}",
                 format_sync_timestamp(conflict.resolved_at),
+                conflict.id,
                 conflict.strategy,
-                conflict.note_id,
+                short_note_id(&conflict.note_id, id_len),
                 conflict.local_updated_at,
                 conflict.incoming_updated_at
             )
@@ -320,50 +318,12 @@ pub fn capture_editor_input_with_initial(
 }
 
 pub fn launch_editor(editor: &str, file_path: &Path) -> Result<(), CliError> {
-    match Command::new(editor).arg(file_path).status() {
-        Ok(status) => {
-            if status.success() {
-                Ok(())
-            } else {
-                Err(CliError::EditorFailed(format!(
-                    "`{editor}` exited with status {status}"
-                )))
-            }
+    dirt_core::util::editor::launch_editor(editor, file_path).map_err(|err| match err.kind() {
+        io::ErrorKind::Other | io::ErrorKind::InvalidInput => {
+            CliError::EditorFailed(err.to_string())
         }
-        Err(err) if err.kind() == io::ErrorKind::NotFound => {
-            let mut parts = editor.split_whitespace();
-            let Some(program) = parts.next() else {
-                return Err(CliError::EditorFailed("empty EDITOR command".into()));
-            };
-
-            let mut command = Command::new(program);
-            command.args(parts).arg(file_path);
-
-            let status = command.status()?;
-            if status.success() {
-                Ok(())
-            } else {
-                Err(CliError::EditorFailed(format!(
-                    "`{editor}` exited with status {status}"
-                )))
-            }
-        }
-        Err(err) => Err(CliError::Io(err)),
-    }
-}
-
-pub fn preferred_editor() -> String {
-    env::var("VISUAL")
-        .or_else(|_| env::var("EDITOR"))
-        .unwrap_or_else(|_| default_editor().to_string())
-}
-
-pub const fn default_editor() -> &'static str {
-    if cfg!(windows) {
-        "notepad"
-    } else {
-        "vi"
-    }
+        _ => CliError::Io(err),
+    })
 }
 
 pub fn create_temp_note_file_path() -> PathBuf {
@@ -373,19 +333,6 @@ pub fn create_temp_note_file_path() -> PathBuf {
     env::temp_dir().join(format!("dirt-note-{}-{now}.md", std::process::id()))
 }
 
-pub fn resolve_db_path(cli_db_path: Option<PathBuf>) -> PathBuf {
-    cli_db_path
-        .or_else(|| env::var_os("DIRT_DB_PATH").map(PathBuf::from))
-        .unwrap_or_else(default_db_path)
-}
-
-pub fn default_db_path() -> PathBuf {
-    dirs::data_dir()
-        .unwrap_or_else(|| panic!("Failed to resolve CLI data directory"))
-        .join("dirt")
-        .join("dirt.db")
-}
-
 #[derive(Clone, Copy)]
 enum OpenDatabaseMode {
     Standard,
@@ -398,6 +345,9 @@ impl OpenDatabaseMode {
     }
 }
 
+/// Environment variable holding the passphrase of encrypted notes
+pub const PASSPHRASE_ENV: &str = "DIRT_PASSPHRASE";
+
 pub async fn open_database(path: &Path) -> Result<DatabaseService, CliError> {
     open_database_with_mode(path, OpenDatabaseMode::Standard).await
 }
@@ -414,16 +364,158 @@ async fn open_database_with_mode(
         std::fs::create_dir_all(parent)?;
     }
 
-    let sync_config = sync_config_from_profile(mode).await?;
+    let sync_config = managed_sync_token(mode)
+        .await?
+        .map(|token| sync_config_for(&token));
+    let db = with_migration_progress(DatabaseService::open_path(path.to_path_buf(), sync_config))
+        .await?;
+    if db.is_read_only().await {
+        eprintln!(
+            "Warning: a newer version of Dirt upgraded this database; it is read-only until you update the CLI."
+        );
+    }
+    unlock_encrypted_notes(&db).await?;
+    warn_storage_quotas(&db).await;
+    announce_release_notes(&db).await;
+    Ok(db)
+}
 
-    if let Some(sync_config) = sync_config {
-        Ok(DatabaseService::open_sync_path(path.to_path_buf(), sync_config).await?)
-    } else {
-        Ok(DatabaseService::open_local_path(path.to_path_buf()).await?)
+/// Unlock encrypted notes with `$DIRT_PASSPHRASE`, or say how to.
+async fn unlock_encrypted_notes(db: &DatabaseService) -> Result<(), CliError> {
+    if !db.is_encrypted().await || db.is_unlocked().await {
+        return Ok(());
     }
+    match env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) if !passphrase.is_empty() => {
+            db.unlock_encryption(&passphrase).await?;
+        }
+        _ => eprintln!("Warning: notes are encrypted; set {PASSPHRASE_ENV} to read and edit them."),
+    }
+    Ok(())
 }
 
-async fn sync_config_from_profile(mode: OpenDatabaseMode) -> Result<Option<SyncConfig>, CliError> {
+/// Set once the storage quotas were checked, so a run warns at most once.
+static QUOTAS_CHECKED: AtomicBool = AtomicBool::new(false);
+
+/// Print soft storage quota warnings on stderr when it is a terminal.
+async fn warn_storage_quotas(db: &DatabaseService) {
+    if !io::stderr().is_terminal() || QUOTAS_CHECKED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    match db.check_storage_quotas().await {
+        Ok((_, warnings)) if !warnings.is_empty() => {
+            for warning in &warnings {
+                eprintln!("Warning: {warning}.");
+            }
+            for hint in QUOTA_MAINTENANCE_HINTS {
+                eprintln!("  {hint}");
+            }
+        }
+        Ok(_) => {}
+        Err(error) => tracing::debug!("Storage quota check failed: {error}"),
+    }
+}
+
+/// Set once release notes were checked, so a run announces them at most once.
+static RELEASE_NOTES_CHECKED: AtomicBool = AtomicBool::new(false);
+
+/// Point at `dirt changelog` on stderr after an upgrade. Scripts without a
+/// terminal leave the notice pending for the next interactive run.
+async fn announce_release_notes(db: &DatabaseService) {
+    if !io::stderr().is_terminal() || RELEASE_NOTES_CHECKED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    match db.take_unseen_release_notes().await {
+        Ok(unseen) => {
+            if let Some(notice) = format_update_notice(&unseen) {
+                eprintln!("{notice}");
+            }
+        }
+        Err(error) => tracing::debug!("Release notes check failed: {error}"),
+    }
+}
+
+/// Open the sync replica with an already exchanged managed token.
+pub async fn open_sync_database_with_token(
+    path: &Path,
+    token: &SyncToken,
+) -> Result<DatabaseService, CliError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    Ok(with_migration_progress(DatabaseService::open_sync_path(
+        path.to_path_buf(),
+        sync_config_for(token),
+    ))
+    .await?)
+}
+
+/// Await a database open, drawing a progress bar on stderr while it applies
+/// schema migrations.
+///
+/// Opening a sync replica blocks its task, so the bar is drawn from a
+/// separate task.
+async fn with_migration_progress<T>(open: impl Future<Output = T>) -> T {
+    if !io::stderr().is_terminal() {
+        return open.await;
+    }
+
+    let mut progress = subscribe_migration_progress();
+    progress.mark_unchanged();
+    let drawn = Arc::new(AtomicBool::new(false));
+    let reporter = tokio::spawn({
+        let drawn = Arc::clone(&drawn);
+        async move {
+            while progress.changed().await.is_ok() {
+                let current = *progress.borrow_and_update();
+                if let Some(current) = current {
+                    eprint!("\r{}\x1b[K", format_migration_progress(&current));
+                    drawn.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+    });
+
+    let result = open.await;
+    reporter.abort();
+    if drawn.load(Ordering::Relaxed) {
+        if let Some(last) = *subscribe_migration_progress().borrow() {
+            eprint!("\r{}\x1b[K", format_migration_progress(&last));
+        }
+        eprintln!();
+    }
+    result
+}
+
+/// One-line progress bar for a migration run.
+pub fn format_migration_progress(progress: &MigrationProgress) -> String {
+    const BAR_WIDTH: usize = 24;
+    let percent = usize::from(progress.percent());
+    let filled = BAR_WIDTH * percent / 100;
+    let mut line = format!(
+        "Migrating database [{}{}] {percent:>3}% {}",
+        "#".repeat(filled),
+        "-".repeat(BAR_WIDTH - filled),
+        progress.description()
+    );
+    if let Some((done, total)) = progress.rows.filter(|_| !progress.is_finished()) {
+        line.push_str(&format!(" ({done}/{total} notes)"));
+    }
+    line
+}
+
+/// Exchange a fresh managed sync token for the active profile.
+pub async fn exchange_managed_sync_token() -> Result<SyncToken, CliError> {
+    managed_sync_token(OpenDatabaseMode::RequireSync)
+        .await?
+        .ok_or(CliError::SyncNotConfigured)
+}
+
+fn sync_config_for(token: &SyncToken) -> SyncConfig {
+    SyncConfig::new(token.database_url.clone(), token.token.clone())
+}
+
+async fn managed_sync_token(mode: OpenDatabaseMode) -> Result<Option<SyncToken>, CliError> {
     let config = CliProfilesConfig::load().map_err(CliError::Config)?;
     let profile_name = config.resolve_profile_name(None);
     let Some(profile) = config.profile(&profile_name) else {
@@ -439,7 +531,29 @@ async fn sync_config_from_profile(mode: OpenDatabaseMode) -> Result<Option<SyncC
         return Ok(None);
     };
 
-    let maybe_auth_service = SupabaseAuthService::new_for_profile(&profile_name, profile)
+    let Some(session) = restore_profile_session(&profile_name, profile).await? else {
+        return Err(CliError::SyncNotConfigured);
+    };
+
+    let sync_auth_client = ManagedSyncAuthClient::new(endpoint)
+        .map_err(|error| CliError::ManagedSync(error.to_string()))?;
+    let managed_token = sync_auth_client
+        .exchange_token(&session.access_token)
+        .await
+        .map_err(|error| CliError::ManagedSync(error.to_string()))?;
+
+    tracing::info!("Managed sync enabled via profile '{}'", profile_name);
+    Ok(Some(managed_token))
+}
+
+/// Loads the profile's stored Supabase session, refreshing it when expired.
+///
+/// Returns `None` when the profile is not signed in.
+pub async fn restore_profile_session(
+    profile_name: &str,
+    profile: &CliProfile,
+) -> Result<Option<AuthSession>, CliError> {
+    let maybe_auth_service = SupabaseAuthService::new_for_profile(profile_name, profile)
         .map_err(|error| CliError::Auth(error.to_string()))?;
     let mut session = if let Some(service) = maybe_auth_service.as_ref() {
         service
@@ -447,7 +561,7 @@ async fn sync_config_from_profile(mode: OpenDatabaseMode) -> Result<Option<SyncC
             .await
             .map_err(|error| CliError::Auth(error.to_string()))?
     } else {
-        load_stored_session(&profile_name).map_err(|error| CliError::Auth(error.to_string()))?
+        load_stored_session(profile_name).map_err(|error| CliError::Auth(error.to_string()))?
     };
 
     if let Some(stored) = session.as_ref() {
@@ -459,27 +573,12 @@ async fn sync_config_from_profile(mode: OpenDatabaseMode) -> Result<Option<SyncC
                     .map(Some)
                     .map_err(|error| CliError::Auth(error.to_string()))?;
             } else {
-                clear_stored_session(&profile_name)
+                clear_stored_session(profile_name)
                     .map_err(|error| CliError::Auth(error.to_string()))?;
                 session = None;
             }
         }
     }
 
-    let Some(session) = session else {
-        return Err(CliError::SyncNotConfigured);
-    };
-
-    let sync_auth_client = ManagedSyncAuthClient::new(endpoint)
-        .map_err(|error| CliError::ManagedSync(error.to_string()))?;
-    let managed_token = sync_auth_client
-        .exchange_token(&session.access_token)
-        .await
-        .map_err(|error| CliError::ManagedSync(error.to_string()))?;
-
-    tracing::info!("Managed sync enabled via profile '{}'", profile_name);
-    Ok(Some(SyncConfig::new(
-        managed_token.database_url,
-        managed_token.token,
-    )))
+    Ok(session)
 }
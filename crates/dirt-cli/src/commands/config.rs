@@ -1,13 +1,21 @@
-use std::env;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use dirt_core::config::{ConfigSource, EnvConfig};
 
 use crate::bootstrap_manifest::fetch_bootstrap_manifest;
 use crate::cli::ConfigCommands;
-use crate::config_profiles::{is_http_url, normalize_text_option, CliProfile, CliProfilesConfig};
+use crate::commands::columns::{format_list_columns, parse_list_columns, DEFAULT_LIST_COLUMNS};
+use crate::commands::profile_db::{data_root, legacy_db_path, profile_db_path_in};
+use crate::config_profiles::{
+    default_config_path, is_http_url, normalize_text_option, CliProfile, CliProfilesConfig,
+};
 use crate::error::CliError;
 
 pub async fn run_config(
     command: ConfigCommands,
     global_profile: Option<&str>,
+    db_override: Option<&Path>,
 ) -> Result<(), CliError> {
     match command {
         ConfigCommands::Init {
@@ -30,7 +38,97 @@ pub async fn run_config(
             )
             .await
         }
+        ConfigCommands::Show => run_config_show(global_profile, db_override),
+        ConfigCommands::Columns { columns, reset } => run_config_columns(columns.as_deref(), reset),
+    }
+}
+
+pub fn run_config_show(
+    global_profile: Option<&str>,
+    db_override: Option<&Path>,
+) -> Result<(), CliError> {
+    let config = CliProfilesConfig::load().map_err(CliError::Config)?;
+    println!("Config: {}", default_config_path().display());
+    for line in format_profile_db_lines(&config, global_profile, db_override, &data_root()) {
+        println!("{line}");
+    }
+
+    let legacy_path = legacy_db_path();
+    if legacy_path.exists() {
+        println!(
+            "Shared database from before per-profile paths: {}",
+            legacy_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Show or change the default list columns.
+pub fn run_config_columns(columns: Option<&str>, reset: bool) -> Result<(), CliError> {
+    let mut config =
+        CliProfilesConfig::load_from_path(&default_config_path()).map_err(CliError::Config)?;
+
+    if !reset && columns.is_none() {
+        let current = match config.list_columns.as_deref() {
+            Some(raw) => parse_list_columns(raw)?,
+            None => DEFAULT_LIST_COLUMNS.to_vec(),
+        };
+        println!("{}", format_list_columns(&current));
+        return Ok(());
+    }
+
+    config.list_columns = match columns {
+        Some(raw) => Some(format_list_columns(&parse_list_columns(raw)?)),
+        None => None,
+    };
+    config.save().map_err(CliError::Config)?;
+    match &config.list_columns {
+        Some(columns) => println!("Default list columns set to {columns}"),
+        None => println!(
+            "Default list columns reset to {}",
+            format_list_columns(DEFAULT_LIST_COLUMNS)
+        ),
+    }
+    Ok(())
+}
+
+/// One line per profile with its database path; the active profile is
+/// marked with `*`.
+pub fn format_profile_db_lines(
+    config: &CliProfilesConfig,
+    global_profile: Option<&str>,
+    db_override: Option<&Path>,
+    data_root: &Path,
+) -> Vec<String> {
+    let active = config.resolve_profile_name(global_profile);
+    let mut names = config.profiles.keys().cloned().collect::<BTreeSet<_>>();
+    names.insert(active.clone());
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+
+    let mut lines = vec![
+        format!("Active profile: {active}"),
+        "Databases:".to_string(),
+    ];
+    for name in &names {
+        let marker = if *name == active { '*' } else { ' ' };
+        let path = profile_db_path_in(data_root, name);
+        let status = if path.exists() {
+            ""
+        } else {
+            " (not created yet)"
+        };
+        lines.push(format!(
+            "{marker} {name:<width$}  {}{status}",
+            path.display()
+        ));
+    }
+    if let Some(path) = db_override {
+        lines.push(format!(
+            "Overridden by --db-path / DIRT_DB_PATH: {}",
+            path.display()
+        ));
     }
+    lines
 }
 
 #[allow(clippy::needless_pass_by_value)]
@@ -53,7 +151,9 @@ pub async fn run_config_init(
     let explicit_api_base_url = normalize_text_option(api_base_url);
     let explicit_bootstrap_url = normalize_text_option(bootstrap_url);
 
+    let env = EnvConfig;
     let bootstrap_url = resolve_bootstrap_url(
+        &env,
         explicit_bootstrap_url.clone(),
         explicit_api_base_url.clone(),
         existing_profile.dirt_api_base_url.clone(),
@@ -90,7 +190,7 @@ pub async fn run_config_init(
                 .as_ref()
                 .map(|manifest| manifest.supabase_url.clone())
         })
-        .or_else(|| normalize_text_option(env::var("SUPABASE_URL").ok()))
+        .or_else(|| env.text_var("SUPABASE_URL"))
         .or_else(|| existing_profile.supabase_url());
     let merged_supabase_anon_key = explicit_supabase_anon_key
         .or_else(|| {
@@ -98,7 +198,7 @@ pub async fn run_config_init(
                 .as_ref()
                 .map(|manifest| manifest.supabase_anon_key.clone())
         })
-        .or_else(|| normalize_text_option(env::var("SUPABASE_ANON_KEY").ok()))
+        .or_else(|| env.text_var("SUPABASE_ANON_KEY"))
         .or_else(|| existing_profile.supabase_anon_key());
     let merged_sync_token_endpoint = explicit_sync_token_endpoint
         .or_else(|| {
@@ -106,7 +206,7 @@ pub async fn run_config_init(
                 .as_ref()
                 .and_then(|manifest| manifest.sync_token_endpoint.clone())
         })
-        .or_else(|| normalize_text_option(env::var("TURSO_SYNC_TOKEN_ENDPOINT").ok()))
+        .or_else(|| env.text_var("TURSO_SYNC_TOKEN_ENDPOINT"))
         .or_else(|| existing_profile.managed_sync_endpoint());
     let merged_api_base_url = explicit_api_base_url
         .or_else(|| {
@@ -114,7 +214,7 @@ pub async fn run_config_init(
                 .as_ref()
                 .map(|manifest| manifest.api_base_url.clone())
         })
-        .or_else(|| normalize_text_option(env::var("DIRT_API_BASE_URL").ok()))
+        .or_else(|| env.text_var("DIRT_API_BASE_URL"))
         .or_else(|| normalize_text_option(existing_profile.dirt_api_base_url.clone()));
 
     let profile = config.profile_mut_or_default(&profile_name);
@@ -174,6 +274,7 @@ pub async fn run_config_init(
 }
 
 pub fn resolve_bootstrap_url(
+    env: &impl ConfigSource,
     explicit_bootstrap_url: Option<String>,
     explicit_api_base_url: Option<String>,
     existing_api_base_url: Option<String>,
@@ -182,12 +283,12 @@ pub fn resolve_bootstrap_url(
         return normalize_bootstrap_url(url).map(Some);
     }
 
-    if let Some(url) = normalize_text_option(env::var("DIRT_BOOTSTRAP_URL").ok()) {
+    if let Some(url) = env.text_var("DIRT_BOOTSTRAP_URL") {
         return normalize_bootstrap_url(url).map(Some);
     }
 
     let api_base_url = explicit_api_base_url
-        .or_else(|| normalize_text_option(env::var("DIRT_API_BASE_URL").ok()))
+        .or_else(|| env.text_var("DIRT_API_BASE_URL"))
         .or_else(|| normalize_text_option(existing_api_base_url));
     Ok(api_base_url.map(|base| format!("{}/v1/bootstrap", base.trim_end_matches('/'))))
 }
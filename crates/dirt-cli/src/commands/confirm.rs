@@ -0,0 +1,96 @@
+//! Shared confirmation checks for destructive commands.
+//!
+//! Every destructive command describes what it is about to do and asks
+//! [`confirm_destructive`] whether to go ahead. Operations touching more than
+//! [`BULK_CONFIRM_LIMIT`] notes default to a dry run unless the global
+//! `--force` flag is given; smaller ones need `--yes` or an interactive "y".
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use crate::error::CliError;
+
+/// Notes an operation may touch before it defaults to a dry run.
+pub const BULK_CONFIRM_LIMIT: usize = 20;
+
+/// Confirmation flags shared by destructive commands.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConfirmOptions {
+    /// `--yes`: skip the interactive prompt
+    pub yes: bool,
+    /// Global `--force`: skip the prompt and the bulk dry-run default
+    pub force: bool,
+    /// `--dry-run`: only show what would change
+    pub dry_run: bool,
+}
+
+/// Outcome of a confirmation check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirmation {
+    Proceed,
+    DryRun,
+    Declined,
+}
+
+/// Decide whether to run `action` (e.g. "Delete 3 notes tagged #old"),
+/// prompting on the terminal when needed.
+pub fn confirm_destructive(
+    action: &str,
+    affected: usize,
+    options: ConfirmOptions,
+) -> Result<Confirmation, CliError> {
+    decide(
+        action,
+        affected,
+        options,
+        io::stdin().is_terminal(),
+        prompt_yes_no,
+    )
+}
+
+/// Decision logic behind [`confirm_destructive`], with the prompt injected.
+pub fn decide(
+    action: &str,
+    affected: usize,
+    options: ConfirmOptions,
+    interactive: bool,
+    prompt: impl FnOnce(&str) -> io::Result<bool>,
+) -> Result<Confirmation, CliError> {
+    if options.dry_run || affected == 0 {
+        return Ok(Confirmation::DryRun);
+    }
+    if options.force {
+        return Ok(Confirmation::Proceed);
+    }
+    if affected > BULK_CONFIRM_LIMIT {
+        println!(
+            "{action} touches more than {BULK_CONFIRM_LIMIT} notes; showing a dry run. Pass --force to apply."
+        );
+        return Ok(Confirmation::DryRun);
+    }
+    if options.yes {
+        return Ok(Confirmation::Proceed);
+    }
+    if !interactive {
+        return Err(CliError::ConfirmationRequired(action.to_string()));
+    }
+
+    if prompt(&format!("{action}? [y/N] "))? {
+        Ok(Confirmation::Proceed)
+    } else {
+        Ok(Confirmation::Declined)
+    }
+}
+
+/// Ask a yes/no question on the terminal; anything but "y"/"yes" is no.
+pub fn prompt_yes_no(question: &str) -> io::Result<bool> {
+    let mut stdout = io::stdout();
+    write!(stdout, "{question}")?;
+    stdout.flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use chrono::{Days, NaiveDate};
+use dirt_core::models::NoteSource;
+
+use crate::commands::common::open_database;
+use crate::commands::edit::edit_note;
+use crate::error::CliError;
+
+/// Open the daily note from `days_ago` days before today in the editor,
+/// creating it when missing.
+pub async fn run_daily(days_ago: u64, db_path: &Path) -> Result<(), CliError> {
+    let db = open_database(db_path).await?;
+    let date = daily_note_date(chrono::Local::now().date_naive(), days_ago);
+    let note = db.get_or_create_daily_note(date, NoteSource::Cli).await?;
+    edit_note(&db, note).await
+}
+
+/// The calendar day `days_ago` days before `today`.
+pub fn daily_note_date(today: NaiveDate, days_ago: u64) -> NaiveDate {
+    today.checked_sub_days(Days::new(days_ago)).unwrap_or(today)
+}
@@ -1,8 +1,15 @@
 use std::path::Path;
 
-use crate::commands::common::{normalize_note_identifier, open_database, resolve_note_for_edit};
+use dirt_core::Note;
+
+use crate::commands::common::{
+    normalize_note_identifier, note_preview, open_database, resolve_note_for_edit,
+};
+use crate::commands::confirm::{confirm_destructive, ConfirmOptions, Confirmation};
 use crate::error::CliError;
 
+const PREVIEW_CHARS: usize = 60;
+
 pub async fn run_delete(id: &str, db_path: &Path) -> Result<(), CliError> {
     let normalized_id = normalize_note_identifier(id)?;
     let db = open_database(db_path).await?;
@@ -12,3 +19,53 @@ pub async fn run_delete(id: &str, db_path: &Path) -> Result<(), CliError> {
     println!("{}", note.id);
     Ok(())
 }
+
+/// Delete every note carrying `tag`; returns the number of notes deleted.
+pub async fn run_delete_by_tag(
+    tag: &str,
+    options: ConfirmOptions,
+    db_path: &Path,
+) -> Result<usize, CliError> {
+    const PAGE_SIZE: usize = 500;
+
+    let tag = tag.trim().trim_start_matches('#');
+    let db = open_database(db_path).await?;
+
+    let mut notes: Vec<Note> = Vec::new();
+    loop {
+        let batch = db.list_notes_by_tag(tag, PAGE_SIZE, notes.len()).await?;
+        let count = batch.len();
+        notes.extend(batch);
+        if count < PAGE_SIZE {
+            break;
+        }
+    }
+
+    if notes.is_empty() {
+        println!("No notes tagged #{tag}");
+        return Ok(0);
+    }
+
+    let action = format!("Delete {} notes tagged #{tag}", notes.len());
+    match confirm_destructive(&action, notes.len(), options)? {
+        Confirmation::DryRun => {
+            println!("Would delete {} notes tagged #{tag}:", notes.len());
+            for note in &notes {
+                println!("  {}  {}", note.id, note_preview(note, PREVIEW_CHARS));
+            }
+            println!("Dry run: nothing was deleted.");
+            Ok(0)
+        }
+        Confirmation::Declined => {
+            println!("Aborted: nothing was deleted.");
+            Ok(0)
+        }
+        Confirmation::Proceed => {
+            for note in &notes {
+                db.delete_note(&note.id).await?;
+                println!("{}", note.id);
+            }
+            Ok(notes.len())
+        }
+    }
+}
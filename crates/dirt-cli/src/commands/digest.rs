@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use chrono::Utc;
+use dirt_core::digest::{DigestPeriod, WeeklyDigest};
+use reqwest::Method;
+use serde::Serialize;
+
+use crate::commands::api_client::{send, DirtApi};
+use crate::commands::common::open_database;
+use crate::error::CliError;
+
+/// Where `dirt digest` delivers the rendered digest besides stdout.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DigestDelivery<'a> {
+    pub output: Option<&'a Path>,
+    pub save: bool,
+    pub email: bool,
+    pub json: bool,
+    pub include_private: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct DigestEmailRequest<'a> {
+    subject: &'a str,
+    markdown: &'a str,
+}
+
+pub async fn run_digest(
+    delivery: DigestDelivery<'_>,
+    db_path: &Path,
+    global_profile: Option<&str>,
+) -> Result<WeeklyDigest, CliError> {
+    let db = open_database(db_path).await?;
+    let period = DigestPeriod::week_ending(Utc::now().timestamp_millis());
+    let digest = db.weekly_digest(period, delivery.include_private).await?;
+    let markdown = digest.render_markdown();
+
+    if let Some(path) = delivery.output {
+        std::fs::write(path, &markdown)?;
+        println!("{}", path.display());
+    } else if delivery.json {
+        println!("{}", serde_json::to_string_pretty(&digest)?);
+    } else {
+        print!("{markdown}");
+    }
+
+    if delivery.save {
+        let note = db.create_note(&markdown).await?;
+        println!("Saved digest note {}", note.id);
+    }
+
+    if delivery.email {
+        let api = DirtApi::for_profile(global_profile).await?;
+        let title = digest.title();
+        let request = DigestEmailRequest {
+            subject: &title,
+            markdown: &markdown,
+        };
+        send(api.request(Method::POST, "/digest/email").json(&request)).await?;
+        println!("Emailed digest for {}", period.label());
+    }
+
+    Ok(digest)
+}
@@ -1,5 +1,9 @@
 use std::path::Path;
 
+use dirt_core::models::Note;
+use dirt_core::seal::{format_seal_date, sealed_until};
+use dirt_core::services::DatabaseService;
+
 use crate::commands::common::{
     capture_editor_input_with_initial, normalize_note_identifier, open_database,
     resolve_note_for_edit,
@@ -10,6 +14,17 @@ pub async fn run_edit(id: &str, db_path: &Path) -> Result<(), CliError> {
     let normalized_id = normalize_note_identifier(id)?;
     let db = open_database(db_path).await?;
     let note = resolve_note_for_edit(&normalized_id, &db).await?;
+    edit_note(&db, note).await
+}
+
+/// Open `note` in the editor and save the result, printing the note id.
+pub async fn edit_note(db: &DatabaseService, note: Note) -> Result<(), CliError> {
+    if let Some(until) = sealed_until(&note.content) {
+        return Err(CliError::NoteSealed {
+            id: note.id.to_string(),
+            until: format_seal_date(until),
+        });
+    }
 
     let Some(edited_content) = capture_editor_input_with_initial(&note.content)? else {
         return Err(CliError::EmptyEditedContent);
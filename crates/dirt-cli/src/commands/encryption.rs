@@ -0,0 +1,61 @@
+use std::env;
+use std::path::Path;
+
+use crate::cli::EncryptionCommands;
+use crate::commands::common::{open_database, PASSPHRASE_ENV};
+use crate::error::CliError;
+
+pub async fn run_encryption(command: EncryptionCommands, db_path: &Path) -> Result<(), CliError> {
+    match command {
+        EncryptionCommands::Status => {
+            let db = open_database(db_path).await?;
+            let status = if !db.is_encrypted().await {
+                "off"
+            } else if db.is_unlocked().await {
+                "on (unlocked)"
+            } else {
+                "on (locked)"
+            };
+            println!("Encryption: {status}");
+        }
+        EncryptionCommands::Enable { passphrase } => {
+            let encrypted = run_enable(&resolve_passphrase(passphrase)?, db_path).await?;
+            println!("Encrypted {encrypted} note(s).");
+            println!(
+                "Keep your passphrase safe: it cannot be recovered, and without it your notes cannot be read."
+            );
+            println!("Set {PASSPHRASE_ENV} to unlock them in later commands.");
+        }
+        EncryptionCommands::Unlock { passphrase } => {
+            let encrypted = run_unlock(&resolve_passphrase(passphrase)?, db_path).await?;
+            if encrypted > 0 {
+                println!("Unlocked; encrypted {encrypted} note(s) that were still in plaintext.");
+            } else {
+                println!("Unlocked.");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Turn on encryption; returns how many notes were encrypted.
+pub async fn run_enable(passphrase: &str, db_path: &Path) -> Result<u64, CliError> {
+    let db = open_database(db_path).await?;
+    Ok(db.enable_encryption(passphrase).await?)
+}
+
+/// Check `passphrase`; returns how many plaintext notes it then encrypted.
+pub async fn run_unlock(passphrase: &str, db_path: &Path) -> Result<u64, CliError> {
+    let db = open_database(db_path).await?;
+    Ok(db.unlock_encryption(passphrase).await?)
+}
+
+fn resolve_passphrase(passphrase: Option<String>) -> Result<String, CliError> {
+    passphrase
+        .or_else(|| env::var(PASSPHRASE_ENV).ok())
+        .filter(|passphrase| !passphrase.is_empty())
+        .ok_or_else(|| {
+            dirt_core::Error::InvalidInput(format!("Pass --passphrase or set {PASSPHRASE_ENV}"))
+                .into()
+        })
+}
@@ -1,25 +1,56 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
 use std::path::Path;
 
-use dirt_core::export::{render_notes_export, ExportFormat as CoreExportFormat};
+use chrono::Utc;
+use dirt_core::export::{
+    note_export_file_name, plan_incremental_export, render_json_export,
+    render_markdown_export_with_media, render_note_file, render_org_export,
+    render_plaintext_export, BrokenLink, ExportFormat as CoreExportFormat,
+};
+use dirt_core::models::{Attachment, ExportState};
+use dirt_core::util::fs::write_atomic;
+use dirt_core::NoteId;
+use serde::Serialize;
 
 use crate::cli::ExportFormat;
-use crate::commands::common::list_all_notes;
+use crate::commands::attachments::{is_already_pulled, plan_downloads};
+use crate::commands::common::open_database;
 use crate::error::CliError;
 
+/// Export every exportable note at once.
+///
+/// With `media_dir`, inline `!attach[..](id)` references in a Markdown export
+/// embed the copies `dirt attachments pull` left in that directory.
 pub async fn run_export(
     format: ExportFormat,
     output_path: Option<&Path>,
+    include_private: bool,
+    media_dir: Option<&Path>,
     db_path: &Path,
 ) -> Result<(), CliError> {
-    let notes = list_all_notes(db_path).await?;
-    let core_format = match format {
-        ExportFormat::Json => CoreExportFormat::Json,
-        ExportFormat::Markdown => CoreExportFormat::Markdown,
+    let db = open_database(db_path).await?;
+    let notes = db.list_exportable_notes(include_private).await?;
+    let rendered = match format {
+        ExportFormat::Json => render_json_export(&notes)?,
+        ExportFormat::Markdown => {
+            let mut attachments = Vec::new();
+            for note in &notes {
+                attachments.extend(db.list_attachments(&note.id).await?);
+            }
+            let media_urls = media_dir.map_or_else(HashMap::new, |media_dir| {
+                pulled_media_paths(media_dir, output_path, &attachments)
+            });
+            let export = render_markdown_export_with_media(&notes, &attachments, media_urls);
+            report_broken_links(&export.broken_links);
+            export.markdown
+        }
+        ExportFormat::Org => render_org_export(&notes),
+        ExportFormat::Text => render_plaintext_export(&notes),
     };
-    let rendered = render_notes_export(&notes, core_format)?;
 
     if let Some(path) = output_path {
-        std::fs::write(path, rendered)?;
+        write_atomic(path, rendered)?;
         println!("{}", path.display());
     } else {
         println!("{rendered}");
@@ -27,3 +58,125 @@ pub async fn run_export(
 
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct IncrementalExportReport {
+    pub written: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// Mirror exportable notes into `output_dir` as one file per note.
+///
+/// Only notes edited since the previous run into the same directory (or
+/// whose file is missing) are written; files of deleted or newly private
+/// notes are removed. Switching formats rewrites every note.
+pub async fn run_incremental_export(
+    format: ExportFormat,
+    output_dir: &Path,
+    include_private: bool,
+    db_path: &Path,
+) -> Result<IncrementalExportReport, CliError> {
+    let format = match format {
+        ExportFormat::Json => CoreExportFormat::Json,
+        ExportFormat::Markdown => CoreExportFormat::Markdown,
+        ExportFormat::Org => CoreExportFormat::Org,
+        ExportFormat::Text => CoreExportFormat::PlainText,
+    };
+    std::fs::create_dir_all(output_dir)?;
+    let destination = output_dir.canonicalize()?.to_string_lossy().into_owned();
+
+    let db = open_database(db_path).await?;
+    let notes = db.list_exportable_notes(include_private).await?;
+    let previous_cursor = db
+        .get_export_state(&destination)
+        .await?
+        .filter(|state| state.format == format)
+        .map(|state| state.cursor);
+    let exported = exported_note_ids(output_dir, format)?;
+    let plan = plan_incremental_export(&notes, previous_cursor, &exported);
+
+    for note in &plan.changed {
+        let path = output_dir.join(note_export_file_name(&note.id, format));
+        write_atomic(&path, render_note_file(note, format)?)?;
+    }
+    for note_id in &plan.removed {
+        let path = output_dir.join(note_export_file_name(note_id, format));
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    db.save_export_state(&ExportState {
+        destination,
+        format,
+        cursor: plan.cursor,
+        exported_at: Utc::now().timestamp_millis(),
+    })
+    .await?;
+
+    let report = IncrementalExportReport {
+        written: plan.changed.len(),
+        removed: plan.removed.len(),
+        unchanged: notes.len() - plan.changed.len(),
+    };
+    println!(
+        "{}: wrote {}, removed {}, unchanged {}",
+        output_dir.display(),
+        report.written,
+        report.removed,
+        report.unchanged
+    );
+    Ok(report)
+}
+
+/// Notes that already have a file of `format` in `output_dir`.
+fn exported_note_ids(output_dir: &Path, format: CoreExportFormat) -> io::Result<HashSet<NoteId>> {
+    let mut note_ids = HashSet::new();
+    for entry in std::fs::read_dir(output_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(format.extension()) {
+            continue;
+        }
+        if let Some(note_id) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<NoteId>().ok())
+        {
+            note_ids.insert(note_id);
+        }
+    }
+    Ok(note_ids)
+}
+
+/// Paths of the attachments already pulled into `media_dir`, keyed by
+/// attachment id and relative to the export file where possible.
+pub fn pulled_media_paths(
+    media_dir: &Path,
+    output_path: Option<&Path>,
+    attachments: &[Attachment],
+) -> HashMap<String, String> {
+    let base = output_path.and_then(Path::parent);
+    plan_downloads(media_dir, attachments.to_vec())
+        .into_iter()
+        .filter(|planned| is_already_pulled(&planned.destination, planned.attachment.size_bytes))
+        .map(|planned| {
+            let path = base
+                .and_then(|base| planned.destination.strip_prefix(base).ok())
+                .unwrap_or(&planned.destination);
+            let path = path.to_string_lossy().replace('\\', "/");
+            (planned.attachment.id.to_string(), path)
+        })
+        .collect()
+}
+
+fn report_broken_links(broken_links: &[BrokenLink]) {
+    for link in broken_links {
+        eprintln!(
+            "Warning: note {} links to missing [[{}]]",
+            link.note_id, link.target
+        );
+    }
+}
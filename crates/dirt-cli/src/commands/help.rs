@@ -0,0 +1,260 @@
+//! `dirt help`: command help and the offline user guide.
+//!
+//! The guide pages are Markdown files under `guide/` compiled into the binary,
+//! so they are available on servers without a browser. Pages are rendered for
+//! the terminal and shown through `$PAGER` (default `less -R`) when stdout is
+//! a terminal.
+
+use std::env;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use clap::CommandFactory;
+
+use crate::cli::Cli;
+use crate::error::CliError;
+
+/// One page of the embedded user guide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuidePage {
+    pub name: &'static str,
+    pub title: &'static str,
+    pub markdown: &'static str,
+}
+
+/// Guide pages in reading order.
+pub const GUIDE_PAGES: &[GuidePage] = &[
+    GuidePage {
+        name: "capture",
+        title: "Capturing notes",
+        markdown: include_str!("../../guide/capture.md"),
+    },
+    GuidePage {
+        name: "sync",
+        title: "Setting up sync",
+        markdown: include_str!("../../guide/sync.md"),
+    },
+    GuidePage {
+        name: "profiles",
+        title: "Profiles",
+        markdown: include_str!("../../guide/profiles.md"),
+    },
+];
+
+const DEFAULT_PAGER: &str = "less -R";
+
+const BOLD: &str = "\x1b[1m";
+const UNDERLINE: &str = "\x1b[4m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Print long help for a command path such as `["sync", "conflicts"]`.
+pub fn run_help_command(path: &[String]) -> Result<(), CliError> {
+    let mut command = Cli::command();
+    // Building names each subcommand `dirt <path>` in its usage line.
+    command.build();
+    for name in path {
+        command = command
+            .find_subcommand(name)
+            .cloned()
+            .ok_or_else(|| CliError::UnknownCommand(path.join(" ")))?;
+    }
+    command.print_long_help()?;
+    Ok(())
+}
+
+/// Show the user guide: one page, the page list, or an interactive menu.
+pub fn run_help_guide(page: Option<&str>, list: bool, no_pager: bool) -> Result<(), CliError> {
+    let styled = !no_pager && io::stdout().is_terminal();
+
+    if list {
+        for line in format_guide_index() {
+            println!("{line}");
+        }
+        return Ok(());
+    }
+
+    if let Some(name) = page {
+        return show(
+            &render_markdown(find_guide_page(name)?.markdown, styled),
+            styled,
+        );
+    }
+
+    if styled && io::stdin().is_terminal() {
+        return run_guide_menu();
+    }
+
+    let all_pages = GUIDE_PAGES
+        .iter()
+        .map(|page| render_markdown(page.markdown, styled))
+        .collect::<Vec<_>>()
+        .join("\n");
+    show(&all_pages, styled)
+}
+
+/// Find a page by name or unambiguous name prefix, ignoring case.
+pub fn find_guide_page(name: &str) -> Result<&'static GuidePage, CliError> {
+    let name = name.trim().to_ascii_lowercase();
+    if let Some(page) = GUIDE_PAGES.iter().find(|page| page.name == name) {
+        return Ok(page);
+    }
+
+    let mut matches = GUIDE_PAGES
+        .iter()
+        .filter(|page| !name.is_empty() && page.name.starts_with(&name));
+    match (matches.next(), matches.next()) {
+        (Some(page), None) => Ok(page),
+        _ => Err(unknown_guide_page(name)),
+    }
+}
+
+fn unknown_guide_page(name: String) -> CliError {
+    CliError::UnknownGuidePage {
+        name,
+        available: GUIDE_PAGES
+            .iter()
+            .map(|page| page.name)
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Numbered table of contents, as shown by `--list` and the menu.
+pub fn format_guide_index() -> Vec<String> {
+    let width = GUIDE_PAGES
+        .iter()
+        .map(|page| page.name.len())
+        .max()
+        .unwrap_or(0);
+    GUIDE_PAGES
+        .iter()
+        .enumerate()
+        .map(|(index, page)| format!("{:>2}. {:<width$}  {}", index + 1, page.name, page.title))
+        .collect()
+}
+
+/// Render guide Markdown for a terminal.
+///
+/// Only the subset the guide uses is handled: headings, fenced code blocks,
+/// bullet lists, and inline code. Without `styled`, markup is stripped and no
+/// escape sequences are written, so the output stays readable in files.
+pub fn render_markdown(markdown: &str, styled: bool) -> String {
+    let mut out = String::new();
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            out.push_str("    ");
+            out.push_str(&paint(line, CYAN, styled));
+        } else if let Some(title) = line.strip_prefix("# ") {
+            let title = title.to_uppercase();
+            if styled {
+                out.push_str(&format!("{BOLD}{UNDERLINE}{title}{RESET}"));
+            } else {
+                out.push_str(&format!("{title}\n{}", "=".repeat(title.chars().count())));
+            }
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            out.push_str(&paint(heading, BOLD, styled));
+        } else if let Some(item) = line.strip_prefix("- ") {
+            out.push_str("  * ");
+            out.push_str(&render_inline(item, styled));
+        } else if let Some(continued) = line.strip_prefix("  ") {
+            out.push_str("    ");
+            out.push_str(&render_inline(continued, styled));
+        } else {
+            out.push_str(&render_inline(line, styled));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Replace `inline code` spans with highlighted text.
+fn render_inline(line: &str, styled: bool) -> String {
+    let mut out = String::new();
+    for (index, part) in line.split('`').enumerate() {
+        if index % 2 == 1 {
+            out.push_str(&paint(part, CYAN, styled));
+        } else {
+            out.push_str(part);
+        }
+    }
+    out
+}
+
+fn paint(text: &str, style: &str, styled: bool) -> String {
+    if styled {
+        format!("{style}{text}{RESET}")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Let the user pick pages until they quit.
+fn run_guide_menu() -> Result<(), CliError> {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    loop {
+        println!("{}", paint("Dirt user guide", BOLD, true));
+        for line in format_guide_index() {
+            println!("{line}");
+        }
+        print!("Page number or name (q to quit): ");
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Ok(());
+        }
+        let answer = answer.trim();
+        if answer.is_empty() || answer.eq_ignore_ascii_case("q") {
+            return Ok(());
+        }
+
+        let page = match answer.parse::<usize>() {
+            Ok(number) => number
+                .checked_sub(1)
+                .and_then(|index| GUIDE_PAGES.get(index))
+                .ok_or_else(|| unknown_guide_page(number.to_string())),
+            Err(_) => find_guide_page(answer),
+        };
+        match page {
+            Ok(page) => show(&render_markdown(page.markdown, true), true)?,
+            Err(error) => eprintln!("{error}"),
+        }
+        println!();
+    }
+}
+
+/// Write `text` through the pager when paging, falling back to stdout.
+fn show(text: &str, paged: bool) -> Result<(), CliError> {
+    if paged && page_through(text).is_ok() {
+        return Ok(());
+    }
+    io::stdout().write_all(text.as_bytes())?;
+    Ok(())
+}
+
+fn page_through(text: &str) -> io::Result<()> {
+    let pager = env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let mut parts = pager.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "PAGER is empty"))?;
+
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closing early (e.g. `q` in less) is not an error.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
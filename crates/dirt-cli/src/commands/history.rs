@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use dirt_core::device::load_or_create_device_id;
+use dirt_core::models::NoteRevision;
+use serde::Serialize;
+
+use crate::commands::common::{
+    format_sync_timestamp, normalize_note_identifier, open_database, resolve_note_for_edit,
+    truncate_chars,
+};
+use crate::error::CliError;
+
+/// Characters of a device id shown in `dirt history`
+const DEVICE_ID_CHARS: usize = 8;
+
+#[derive(Debug, Serialize)]
+pub struct NoteRevisionItem {
+    pub revision: i64,
+    pub saved_at: i64,
+    pub saved_at_iso: String,
+    pub device_id: Option<String>,
+    pub content: String,
+}
+
+pub async fn run_history(id: &str, as_json: bool, db_path: &Path) -> Result<(), CliError> {
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+    let revisions = db.list_note_revisions(&note.id).await?;
+
+    if as_json {
+        let json_items = revisions.iter().map(revision_to_item).collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&json_items)?);
+    } else if revisions.is_empty() {
+        println!("No earlier versions of {} yet", note.id);
+    } else {
+        let this_device = load_or_create_device_id(db_path).ok();
+        for line in format_revision_lines(&revisions, this_device.as_deref()) {
+            println!("{line}");
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn run_restore(id: &str, revision: i64, db_path: &Path) -> Result<(), CliError> {
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+
+    let restored = db.restore_note_revision(&note.id, revision).await?;
+    println!("Restored revision {revision} of {}", restored.id);
+    Ok(())
+}
+
+/// One line per revision, newest first: number, save time, device, preview.
+pub fn format_revision_lines(revisions: &[NoteRevision], this_device: Option<&str>) -> Vec<String> {
+    revisions
+        .iter()
+        .map(|revision| {
+            let device = match revision.device_id.as_deref() {
+                None => "unknown".to_string(),
+                Some(device_id) if Some(device_id) == this_device => "this device".to_string(),
+                Some(device_id) => device_id.chars().take(DEVICE_ID_CHARS).collect(),
+            };
+            let first_line = revision.content.lines().next().unwrap_or("").trim();
+            let preview = truncate_chars(first_line, 50);
+            format!(
+                "{:>4}  {}  {device:<11}  {preview}",
+                revision.revision,
+                format_sync_timestamp(revision.saved_at)
+            )
+        })
+        .collect()
+}
+
+pub fn revision_to_item(revision: &NoteRevision) -> NoteRevisionItem {
+    NoteRevisionItem {
+        revision: revision.revision,
+        saved_at: revision.saved_at,
+        saved_at_iso: format_sync_timestamp(revision.saved_at),
+        device_id: revision.device_id.clone(),
+        content: revision.content.clone(),
+    }
+}
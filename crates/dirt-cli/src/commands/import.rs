@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use dirt_core::import::{
+    import_batch, read_import, ImportBatch, ImportMediaTarget, ImportSource as CoreImportSource,
+    ImportSummary,
+};
+
+use crate::cli::ImportSource;
+use crate::commands::api_client::DirtApi;
+use crate::commands::common::open_database;
+use crate::error::CliError;
+
+const PREVIEW_LIMIT: usize = 20;
+const PREVIEW_TITLE_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    pub dry_run: bool,
+    pub no_media: bool,
+}
+
+pub async fn run_import(
+    source: ImportSource,
+    path: &Path,
+    options: ImportOptions,
+    db_path: &Path,
+    global_profile: Option<&str>,
+) -> Result<ImportSummary, CliError> {
+    let core_source = match source {
+        ImportSource::Keep => CoreImportSource::Keep,
+        ImportSource::Simplenote => CoreImportSource::Simplenote,
+        ImportSource::AppleNotes => CoreImportSource::AppleNotes,
+        ImportSource::Markdown => CoreImportSource::Markdown,
+        ImportSource::Json => CoreImportSource::Json,
+    };
+    let batch = read_import(core_source, path)?;
+    print_preview(&batch);
+
+    if options.dry_run {
+        println!("Dry run: nothing was imported.");
+        return Ok(ImportSummary::default());
+    }
+
+    let api = if batch.media_count() > 0 && !options.no_media {
+        match DirtApi::for_profile(global_profile).await {
+            Ok(api) => Some(api),
+            Err(error) => {
+                println!("Media will not be uploaded: {error}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let media = api.as_ref().map(DirtApi::media_client).transpose()?;
+    let media_target = media
+        .as_ref()
+        .map(|(client, access_token)| ImportMediaTarget {
+            client,
+            access_token,
+        });
+
+    let db = open_database(db_path).await?;
+    let summary = import_batch(&db, &batch, media_target).await?;
+
+    println!(
+        "Imported {} notes, uploaded {} attachments",
+        summary.notes_imported, summary.media_uploaded
+    );
+    if summary.media_skipped > 0 {
+        println!("Skipped {} media files", summary.media_skipped);
+    }
+    for failure in &summary.failures {
+        println!("  {failure}");
+    }
+    Ok(summary)
+}
+
+fn print_preview(batch: &ImportBatch) {
+    println!(
+        "Found {} notes and {} media files in {} export",
+        batch.notes.len(),
+        batch.media_count(),
+        batch.source.label()
+    );
+    for note in batch.notes.iter().take(PREVIEW_LIMIT) {
+        let title: String = note
+            .content
+            .lines()
+            .next()
+            .unwrap_or("")
+            .chars()
+            .take(PREVIEW_TITLE_CHARS)
+            .collect();
+        println!("  {title}");
+    }
+    if batch.notes.len() > PREVIEW_LIMIT {
+        println!("  ... and {} more", batch.notes.len() - PREVIEW_LIMIT);
+    }
+    for skip in &batch.skipped {
+        println!("Skipping {}: {}", skip.entry, skip.reason);
+    }
+}
@@ -0,0 +1,36 @@
+use std::io::Write as _;
+use std::path::Path;
+
+use dirt_core::journal::{compile_journal, Journal, JournalRange};
+use dirt_core::util::fs::write_atomic;
+
+use crate::cli::JournalFormat;
+use crate::commands::common::open_database;
+use crate::error::CliError;
+
+pub async fn run_journal(
+    from: &str,
+    to: &str,
+    format: JournalFormat,
+    output_path: Option<&Path>,
+    include_private: bool,
+    db_path: &Path,
+) -> Result<Journal, CliError> {
+    let range = JournalRange::parse(from, to)?;
+    let db = open_database(db_path).await?;
+    let notes = db.list_exportable_notes(include_private).await?;
+    let journal = compile_journal(&notes, range);
+    let rendered = match format {
+        JournalFormat::Markdown => journal.render_markdown().into_bytes(),
+        JournalFormat::Pdf => journal.render_pdf(),
+    };
+
+    if let Some(path) = output_path {
+        write_atomic(path, rendered)?;
+        println!("{}", path.display());
+    } else {
+        std::io::stdout().write_all(&rendered)?;
+    }
+
+    Ok(journal)
+}
@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use dirt_core::models::{short_note_id, Note};
+
+use crate::commands::common::{
+    normalize_note_identifier, note_preview, note_to_list_item, open_database,
+    resolve_note_for_edit,
+};
+use crate::error::CliError;
+
+/// Print the notes that link to a note with `[[title]]` or `[[id]]`.
+pub async fn run_links(id: &str, as_json: bool, db_path: &Path) -> Result<(), CliError> {
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+    let backlinks = db.backlinks(&note.id).await?;
+
+    if as_json {
+        let json_items = backlinks.iter().map(note_to_list_item).collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&json_items)?);
+    } else if backlinks.is_empty() {
+        println!("No notes link to {}", note_preview(&note, 40));
+    } else {
+        for line in format_backlink_lines(&backlinks, db.short_note_id_len().await?) {
+            println!("{line}");
+        }
+    }
+
+    Ok(())
+}
+
+pub fn format_backlink_lines(notes: &[Note], id_len: usize) -> Vec<String> {
+    notes
+        .iter()
+        .map(|note| {
+            let id = note.id.to_string();
+            let short_id = short_note_id(&id, id_len);
+            format!("{short_id:<id_len$}  {}", note_preview(note, 60))
+        })
+        .collect()
+}
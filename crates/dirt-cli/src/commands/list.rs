@@ -1,15 +1,23 @@
 use std::path::Path;
 
-use crate::commands::common::{format_note_lines, list_notes, note_to_list_item, NoteListItem};
+use crate::commands::columns::{format_note_lines, ListLayout};
+use crate::commands::common::{list_notes_in, note_to_list_item, open_database, NoteListItem};
 use crate::error::CliError;
 
 pub async fn run_list(
     limit: usize,
     tag: Option<&str>,
+    archived: bool,
     as_json: bool,
+    layout: &ListLayout,
     db_path: &Path,
 ) -> Result<(), CliError> {
-    let notes = list_notes(limit, tag, db_path).await?;
+    let db = open_database(db_path).await?;
+    let notes = if archived {
+        db.list_archived_notes(limit, 0).await?
+    } else {
+        list_notes_in(&db, limit, tag).await?
+    };
 
     if as_json {
         let json_items = notes
@@ -18,7 +26,7 @@ pub async fn run_list(
             .collect::<Vec<NoteListItem>>();
         println!("{}", serde_json::to_string_pretty(&json_items)?);
     } else {
-        for line in format_note_lines(&notes) {
+        for line in format_note_lines(&notes, layout, db.short_note_id_len().await?) {
             println!("{line}");
         }
     }
@@ -0,0 +1,395 @@
+use std::path::Path;
+
+use dirt_core::db::{AnalyzeReport, CompactReport, DatabaseHealth, SLOW_QUERY_ENV};
+use dirt_core::media::{purge_orphaned_objects, AttachmentAudit, AttachmentCache};
+use dirt_core::models::{format_byte_count, Settings};
+use dirt_core::quota::{quota_warnings, StorageUsage, QUOTA_MAINTENANCE_HINTS};
+use serde::Serialize;
+
+use crate::commands::api_client::DirtApi;
+use crate::commands::common::open_database;
+use crate::commands::confirm::{confirm_destructive, ConfirmOptions, Confirmation};
+use crate::error::CliError;
+
+pub async fn run_analyze(db_path: &Path) -> Result<AnalyzeReport, CliError> {
+    let db = open_database(db_path).await?;
+    let report = db.analyze().await?;
+
+    for line in format_analyze_lines(&report) {
+        println!("{line}");
+    }
+
+    Ok(report)
+}
+
+pub fn format_analyze_lines(report: &AnalyzeReport) -> Vec<String> {
+    let mut lines = vec!["Indexes:".to_string()];
+    if report.indexes.is_empty() {
+        lines.push("  (no statistics yet; the database is empty)".to_string());
+    }
+    for stat in &report.indexes {
+        lines.push(format!(
+            "  {}.{}  {} rows",
+            stat.table, stat.index, stat.rows
+        ));
+    }
+
+    lines.push("Query plans:".to_string());
+    for plan in &report.plans {
+        let marker = if plan.full_scans().next().is_some() {
+            "  [full scan]"
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "  {}: {}{marker}",
+            plan.name,
+            plan.steps.join(" / ")
+        ));
+    }
+
+    lines.push(format!(
+        "Set {SLOW_QUERY_ENV}=<ms> to log queries slower than the threshold."
+    ));
+    lines
+}
+
+pub async fn run_usage(db_path: &Path) -> Result<StorageUsage, CliError> {
+    let db = open_database(db_path).await?;
+    let usage = db.storage_usage().await?;
+    let settings = db.load_settings().await?;
+
+    for line in format_usage_lines(&usage, &settings) {
+        println!("{line}");
+    }
+
+    Ok(usage)
+}
+
+pub fn format_usage_lines(usage: &StorageUsage, settings: &Settings) -> Vec<String> {
+    let limit = |value: Option<u32>, unit: &str| {
+        value.map_or_else(
+            || "no warning".to_string(),
+            |value| format!("warn above {value}{unit}"),
+        )
+    };
+    let mut lines = vec![
+        format!(
+            "Database size: {} ({})",
+            format_byte_count(usage.database_bytes),
+            limit(settings.database_size_warning_mb, " MB")
+        ),
+        format!(
+            "Notes: {} ({})",
+            usage.notes,
+            limit(settings.note_count_warning, "")
+        ),
+        format!("Deleted notes awaiting purge: {}", usage.deleted_notes),
+    ];
+
+    let warnings = quota_warnings(usage, settings);
+    for warning in &warnings {
+        lines.push(format!("Warning: {warning}."));
+    }
+    if !warnings.is_empty() {
+        lines.extend(
+            QUOTA_MAINTENANCE_HINTS
+                .iter()
+                .map(|hint| format!("  {hint}")),
+        );
+    }
+    lines
+}
+
+/// Save the storage warning thresholds; `0` turns a warning off.
+pub async fn run_set_quota_limits(
+    note_limit: Option<u32>,
+    size_limit_mb: Option<u32>,
+    db_path: &Path,
+) -> Result<Settings, CliError> {
+    let db = open_database(db_path).await?;
+    let mut settings = db.load_settings().await?;
+    if let Some(limit) = note_limit {
+        settings.note_count_warning = (limit > 0).then_some(limit);
+    }
+    if let Some(limit) = size_limit_mb {
+        settings.database_size_warning_mb = (limit > 0).then_some(limit);
+    }
+    db.save_settings(&settings).await?;
+    println!("Saved storage warning thresholds.");
+    Ok(settings)
+}
+
+/// Save how many revisions to keep per note and prune older ones now;
+/// returns the number of revisions removed.
+pub async fn run_set_history_limit(limit: u32, db_path: &Path) -> Result<u64, CliError> {
+    let db = open_database(db_path).await?;
+    let mut settings = db.load_settings().await?;
+    settings.revision_retention = (limit > 0).then_some(limit);
+    db.save_settings(&settings).await?;
+
+    let pruned = db.prune_note_revisions().await?;
+    match settings.revision_retention {
+        Some(limit) => println!("Keeping the last {limit} revisions of each note."),
+        None => println!("Keeping every revision of each note."),
+    }
+    if pruned > 0 {
+        println!("Removed {pruned} older revisions.");
+    }
+    Ok(pruned)
+}
+
+/// Permanently remove deleted notes; returns the number purged.
+pub async fn run_purge_deleted(options: ConfirmOptions, db_path: &Path) -> Result<u64, CliError> {
+    let db = open_database(db_path).await?;
+    let usage = db.storage_usage().await?;
+    let deleted = usize::try_from(usage.deleted_notes).unwrap_or(usize::MAX);
+    if deleted == 0 {
+        println!("No deleted notes to purge.");
+        return Ok(0);
+    }
+
+    let action = format!("Permanently remove {deleted} deleted notes");
+    match confirm_destructive(&action, deleted, options)? {
+        Confirmation::DryRun => {
+            println!("Would permanently remove {deleted} deleted notes.");
+            println!("Dry run: nothing was purged.");
+            Ok(0)
+        }
+        Confirmation::Declined => {
+            println!("Aborted: nothing was purged.");
+            Ok(0)
+        }
+        Confirmation::Proceed => {
+            let purged = db.purge_deleted_notes().await?;
+            let after = db.storage_usage().await?;
+            println!(
+                "Purged {purged} deleted notes; the database now uses {}.",
+                format_byte_count(after.database_bytes)
+            );
+            Ok(purged)
+        }
+    }
+}
+
+/// Cleanup `dirt maintenance attachments` may do after reporting
+#[derive(Debug, Clone, Copy)]
+pub struct AttachmentAuditOptions {
+    /// Delete attachment records whose file is missing
+    pub repair: bool,
+    /// Delete stored files no attachment refers to
+    pub purge: bool,
+    pub json: bool,
+    pub confirm: ConfirmOptions,
+}
+
+/// Compare attachment records with the files in managed storage, then repair
+/// or purge as asked; returns the audit as found.
+pub async fn run_attachment_audit(
+    options: AttachmentAuditOptions,
+    db_path: &Path,
+    global_profile: Option<&str>,
+) -> Result<AttachmentAudit, CliError> {
+    let db = open_database(db_path).await?;
+    let api = DirtApi::for_profile(global_profile).await?;
+    let (media_api, access_token) = api.media_client()?;
+    let objects = media_api
+        .list_objects(access_token)
+        .await
+        .map_err(|error| CliError::Api(error.to_string()))?;
+    let audit = db.audit_attachments(&objects).await?;
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&audit)?);
+    } else {
+        for line in format_attachment_audit_lines(&audit) {
+            println!("{line}");
+        }
+    }
+
+    let repair = options.repair && !audit.missing.is_empty();
+    let purge = options.purge && !audit.orphaned.is_empty();
+    if !repair && !purge {
+        if !options.json && !audit.is_clean() && !options.repair && !options.purge {
+            println!("Run with --repair or --purge to clean up.");
+        }
+        return Ok(audit);
+    }
+
+    let mut steps = Vec::new();
+    let mut affected = 0;
+    if repair {
+        steps.push(format!(
+            "remove {} attachment record(s)",
+            audit.missing.len()
+        ));
+        affected += audit.missing.len();
+    }
+    if purge {
+        steps.push(format!(
+            "delete {} stored file(s) ({})",
+            audit.orphaned.len(),
+            format_byte_count(audit.orphaned_bytes())
+        ));
+        affected += audit.orphaned.len();
+    }
+    let steps = steps.join(" and ");
+    match confirm_destructive(
+        &format!("Attachment cleanup: {steps}"),
+        affected,
+        options.confirm,
+    )? {
+        Confirmation::DryRun => {
+            println!("Would {steps}.");
+            println!("Dry run: nothing was deleted.");
+        }
+        Confirmation::Declined => println!("Aborted: nothing was deleted."),
+        Confirmation::Proceed => {
+            if repair {
+                let removed = db.repair_attachments(&audit).await?;
+                println!("Removed {removed} attachment record(s).");
+            }
+            if purge {
+                let deleted = purge_orphaned_objects(&media_api, access_token, &audit)
+                    .await
+                    .map_err(|error| CliError::Api(error.to_string()))?;
+                println!("Deleted {deleted} stored file(s).");
+                forget_cached_objects(&audit, db_path);
+            }
+        }
+    }
+    Ok(audit)
+}
+
+/// Drop purged objects from the local attachment cache.
+fn forget_cached_objects(audit: &AttachmentAudit, db_path: &Path) {
+    let removed = AttachmentCache::beside_database(db_path, 0).and_then(|cache| {
+        audit
+            .orphaned
+            .iter()
+            .try_for_each(|object| cache.remove(&object.key))
+    });
+    if let Err(error) = removed {
+        tracing::warn!("Failed to update the attachment cache: {error}");
+    }
+}
+
+pub fn format_attachment_audit_lines(audit: &AttachmentAudit) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Checked {} attachment record(s) against {} stored file(s).",
+        audit.checked_attachments, audit.checked_objects
+    )];
+    if audit.is_clean() {
+        lines.push("Attachments and storage match.".to_string());
+        return lines;
+    }
+    if !audit.missing.is_empty() {
+        lines.push(format!(
+            "{} record(s) missing their file:",
+            audit.missing.len()
+        ));
+        lines.extend(audit.missing.iter().map(|attachment| {
+            format!(
+                "  {}  {}  {}",
+                attachment.note_id, attachment.filename, attachment.r2_key
+            )
+        }));
+    }
+    if !audit.orphaned.is_empty() {
+        lines.push(format!(
+            "{} stored file(s) no attachment uses ({}):",
+            audit.orphaned.len(),
+            format_byte_count(audit.orphaned_bytes())
+        ));
+        lines.extend(
+            audit.orphaned.iter().map(|object| {
+                format!("  {}  {}", object.key, format_byte_count(object.size_bytes))
+            }),
+        );
+    }
+    lines
+}
+
+/// Result of `dirt maintenance db`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DbMaintenanceReport {
+    pub health: DatabaseHealth,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compact: Option<CompactReport>,
+}
+
+/// Check database health and, with `compact`, reclaim space; the check runs
+/// first so a damaged file is never vacuumed.
+pub async fn run_db_maintenance(
+    compact: bool,
+    json: bool,
+    db_path: &Path,
+) -> Result<DbMaintenanceReport, CliError> {
+    let db = open_database(db_path).await?;
+    let health = db.health_check().await?;
+    let compact = if compact && health.integrity_errors.is_empty() {
+        Some(db.compact().await?)
+    } else {
+        None
+    };
+    let report = DbMaintenanceReport { health, compact };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for line in format_db_maintenance_lines(&report) {
+            println!("{line}");
+        }
+    }
+    if report.health.integrity_errors.is_empty() {
+        Ok(report)
+    } else {
+        Err(CliError::DatabaseDamaged(db_path.display().to_string()))
+    }
+}
+
+pub fn format_db_maintenance_lines(report: &DbMaintenanceReport) -> Vec<String> {
+    let health = &report.health;
+    let mut lines = vec![format!(
+        "Database: {} ({} free)",
+        format_byte_count(health.database_bytes),
+        format_byte_count(health.free_bytes)
+    )];
+    if health.is_healthy() {
+        lines.push("Integrity check: ok".to_string());
+    }
+    lines.extend(
+        health
+            .integrity_errors
+            .iter()
+            .map(|error| format!("Integrity problem: {error}")),
+    );
+    lines.extend(
+        health
+            .fts_errors
+            .iter()
+            .map(|error| format!("Search index problem: {error}")),
+    );
+
+    match report.compact {
+        Some(compact) if compact.vacuumed => lines.push(format!(
+            "Compacted: {} -> {} ({} reclaimed); search indexes rebuilt.",
+            format_byte_count(compact.bytes_before),
+            format_byte_count(compact.bytes_after),
+            format_byte_count(compact.reclaimed_bytes())
+        )),
+        Some(compact) => lines.push(format!(
+            "Checkpointed {} WAL frame(s); synced replicas are not vacuumed.",
+            compact.checkpointed_frames
+        )),
+        None if health.integrity_errors.is_empty()
+            && (!health.fts_errors.is_empty() || health.free_bytes > 0) =>
+        {
+            lines.push(
+                "Run with --compact to rebuild search indexes and reclaim space.".to_string(),
+            );
+        }
+        _ => {}
+    }
+    lines
+}
@@ -1,11 +1,43 @@
 pub mod add;
+pub mod api_client;
+pub mod attach;
+pub mod attachments;
 pub mod auth_cmd;
+pub mod cache;
+pub mod changelog;
+pub mod columns;
 pub mod common;
 pub mod completions;
 pub mod config;
+pub mod confirm;
+pub mod daily;
 pub mod delete;
+pub mod digest;
 pub mod edit;
+pub mod encryption;
 pub mod export;
+pub mod help;
+pub mod history;
+pub mod import;
+pub mod journal;
+pub mod links;
 pub mod list;
+pub mod maintain;
+pub mod no_export;
+pub mod pin;
+pub mod profile_db;
+pub mod rules;
+pub mod seal;
 pub mod search;
+pub mod share;
+pub mod stats;
+pub mod summarize;
 pub mod sync;
+pub mod tag;
+pub mod tasks;
+pub mod tokens;
+pub mod transcribe;
+pub mod translate;
+pub mod trash;
+pub mod urls;
+pub mod whisper;
@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use dirt_core::export::parse_no_export_tags;
+
+use crate::commands::common::{normalize_note_identifier, open_database, resolve_note_for_edit};
+use crate::error::CliError;
+
+pub async fn run_no_export(id: &str, clear: bool, db_path: &Path) -> Result<(), CliError> {
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+
+    db.set_note_export_excluded(&note.id, !clear).await?;
+
+    if clear {
+        println!("{} can be exported again", note.id);
+    } else {
+        println!("{} is now left out of exports", note.id);
+    }
+    Ok(())
+}
+
+pub async fn run_no_export_tag(tag: &str, clear: bool, db_path: &Path) -> Result<(), CliError> {
+    let tags = parse_no_export_tags(tag);
+    let [tag] = tags.as_slice() else {
+        return Err(dirt_core::Error::InvalidInput(format!("Invalid tag '{tag}'")).into());
+    };
+    let db = open_database(db_path).await?;
+    let mut settings = db.load_settings().await?;
+
+    settings.no_export_tags.retain(|existing| existing != tag);
+    if !clear {
+        settings.no_export_tags.push(tag.clone());
+    }
+    db.save_settings(&settings).await?;
+
+    if clear {
+        println!("Notes tagged #{tag} can be exported again");
+    } else {
+        println!("Notes tagged #{tag} are now left out of exports");
+    }
+    Ok(())
+}
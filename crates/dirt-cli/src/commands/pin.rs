@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use crate::commands::common::{normalize_note_identifier, open_database, resolve_note_for_edit};
+use crate::error::CliError;
+
+pub async fn run_pin(id: &str, clear: bool, db_path: &Path) -> Result<(), CliError> {
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+
+    db.set_note_pinned(&note.id, !clear).await?;
+
+    if clear {
+        println!("{} is no longer pinned", note.id);
+    } else {
+        println!("{} is now pinned", note.id);
+    }
+    Ok(())
+}
+
+pub async fn run_archive(id: &str, clear: bool, db_path: &Path) -> Result<(), CliError> {
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+
+    db.set_note_archived(&note.id, !clear).await?;
+
+    if clear {
+        println!("{} is back in the note list", note.id);
+    } else {
+        println!(
+            "{} is now archived; see it with `dirt list --archived`",
+            note.id
+        );
+    }
+    Ok(())
+}
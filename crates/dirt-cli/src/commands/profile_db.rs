@@ -0,0 +1,171 @@
+//! Per-profile local database paths.
+//!
+//! Each CLI profile keeps its notes in `<data_dir>/dirt/<profile>/dirt.db`, so
+//! signing in to two accounts never mixes their notes. Databases created
+//! before profiles had their own paths live at `<data_dir>/dirt/dirt.db`
+//! (shared with the desktop app); [`resolve_db_path`] offers to copy that
+//! database into the active profile the first time the profile is used.
+
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use dirt_core::workspace::workspace_db_path;
+
+use crate::commands::confirm::prompt_yes_no;
+use crate::config_profiles::CliProfilesConfig;
+use crate::error::CliError;
+
+const DB_FILE_NAME: &str = "dirt.db";
+const SEAL_KEYS_DIR: &str = "seal-keys";
+
+/// Database path given by `--db-path` or `DIRT_DB_PATH`, which bypasses
+/// profile paths entirely.
+pub fn db_path_override(cli_db_path: Option<PathBuf>) -> Option<PathBuf> {
+    cli_db_path.or_else(|| env::var_os("DIRT_DB_PATH").map(PathBuf::from))
+}
+
+/// Data directory shared by all profiles.
+pub fn data_root() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| panic!("Failed to resolve CLI data directory"))
+        .join("dirt")
+}
+
+/// Database used before profiles had their own paths.
+pub fn legacy_db_path() -> PathBuf {
+    data_root().join(DB_FILE_NAME)
+}
+
+/// Default database path for `profile_name`.
+pub fn profile_db_path(profile_name: &str) -> PathBuf {
+    profile_db_path_in(&data_root(), profile_name)
+}
+
+/// Database path for `profile_name` under `root`; the profile name is
+/// sanitized so it cannot escape `root`.
+pub fn profile_db_path_in(root: &Path, profile_name: &str) -> PathBuf {
+    workspace_db_path(root, profile_name)
+}
+
+/// Database path for this run: the override when given, otherwise the active
+/// profile's path.
+///
+/// With `offer_migration`, a profile without a database yet is offered a
+/// copy of the legacy shared database.
+pub fn resolve_db_path(
+    db_override: Option<PathBuf>,
+    offer_migration: bool,
+) -> Result<PathBuf, CliError> {
+    if let Some(path) = db_override {
+        return Ok(path);
+    }
+
+    let config = CliProfilesConfig::load().map_err(CliError::Config)?;
+    let profile_name = config.resolve_profile_name(None);
+    let profile_path = profile_db_path(&profile_name);
+    if !offer_migration {
+        return Ok(profile_path);
+    }
+
+    choose_db_path(
+        &profile_name,
+        profile_path,
+        &legacy_db_path(),
+        io::stdin().is_terminal(),
+        prompt_yes_no,
+    )
+}
+
+/// Migration logic behind [`resolve_db_path`], with the prompt injected.
+///
+/// Returns the legacy path when the copy is declined or cannot be asked for,
+/// so existing notes stay reachable until the user opts in.
+pub fn choose_db_path(
+    profile_name: &str,
+    profile_path: PathBuf,
+    legacy_path: &Path,
+    interactive: bool,
+    prompt: impl FnOnce(&str) -> io::Result<bool>,
+) -> Result<PathBuf, CliError> {
+    if profile_path.exists() || !legacy_path.exists() {
+        return Ok(profile_path);
+    }
+
+    let question = format!(
+        "Profile '{profile_name}' now keeps its notes in {}. Copy the shared database at {} there? [y/N] ",
+        profile_path.display(),
+        legacy_path.display()
+    );
+    if interactive && prompt(&question)? {
+        copy_legacy_db(legacy_path, &profile_path)?;
+        println!(
+            "Copied {} to {}",
+            legacy_path.display(),
+            profile_path.display()
+        );
+        return Ok(profile_path);
+    }
+
+    eprintln!(
+        "Using the shared database at {} for profile '{profile_name}'. Run dirt interactively to copy it into {}, or pass --db-path.",
+        legacy_path.display(),
+        profile_path.display()
+    );
+    Ok(legacy_path.to_path_buf())
+}
+
+/// Copy the legacy database, its WAL and sync metadata, and its seal keys.
+///
+/// Only `dirt.db` and `dirt.db-*` files are copied; the shared-memory index
+/// (`-shm`) is rebuilt by SQLite and is skipped.
+fn copy_legacy_db(legacy_path: &Path, profile_path: &Path) -> io::Result<()> {
+    let (Some(legacy_dir), Some(profile_dir)) = (legacy_path.parent(), profile_path.parent())
+    else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "database path has no parent directory",
+        ));
+    };
+    let legacy_name = legacy_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(DB_FILE_NAME);
+    let profile_name = profile_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(DB_FILE_NAME);
+    std::fs::create_dir_all(profile_dir)?;
+
+    for entry in std::fs::read_dir(legacy_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(suffix) = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix(legacy_name))
+        else {
+            continue;
+        };
+        let is_sidecar = suffix.is_empty() || suffix.starts_with('-');
+        if !is_sidecar || suffix == "-shm" || !entry.file_type()?.is_file() {
+            continue;
+        }
+        std::fs::copy(
+            entry.path(),
+            profile_dir.join(format!("{profile_name}{suffix}")),
+        )?;
+    }
+
+    let legacy_keys = legacy_dir.join(SEAL_KEYS_DIR);
+    if legacy_keys.is_dir() {
+        let profile_keys = profile_dir.join(SEAL_KEYS_DIR);
+        std::fs::create_dir_all(&profile_keys)?;
+        for entry in std::fs::read_dir(legacy_keys)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                std::fs::copy(entry.path(), profile_keys.join(entry.file_name()))?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use dirt_core::models::NoteSource;
+use dirt_core::rules::{route_note, RoutingRule, RuleAction, RuleCondition};
+
+use crate::cli::RuleCommands;
+use crate::commands::common::open_database;
+use crate::error::CliError;
+
+pub async fn run_rules(command: RuleCommands, db_path: &Path) -> Result<(), CliError> {
+    match command {
+        RuleCommands::Add { condition, action } => {
+            let condition: RuleCondition = condition.parse()?;
+            let action: RuleAction = action.parse()?;
+            let db = open_database(db_path).await?;
+            let rule = db.add_routing_rule(&condition, &action).await?;
+            println!("Added rule {}: {rule}", rule.id);
+        }
+        RuleCommands::List { json } => {
+            let db = open_database(db_path).await?;
+            let rules = db.list_routing_rules().await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&rules)?);
+                return Ok(());
+            }
+            if rules.is_empty() {
+                println!("No routing rules.");
+                return Ok(());
+            }
+            for line in format_rule_lines(&rules) {
+                println!("{line}");
+            }
+        }
+        RuleCommands::Remove { id } => {
+            let db = open_database(db_path).await?;
+            if !db.remove_routing_rule(id).await? {
+                return Err(CliError::RoutingRuleNotFound(id));
+            }
+            println!("Removed rule {id}");
+        }
+        RuleCommands::Test { text, source } => {
+            let source = source
+                .as_deref()
+                .map(str::parse::<NoteSource>)
+                .transpose()?;
+            let db = open_database(db_path).await?;
+            let rules = db.list_routing_rules().await?;
+            let outcome = route_note(&rules, &text, source);
+            if !outcome.changed() {
+                println!("No rule applies.");
+                return Ok(());
+            }
+            let applied: Vec<_> = rules
+                .into_iter()
+                .filter(|rule| outcome.applied.contains(&rule.id))
+                .collect();
+            for line in format_rule_lines(&applied) {
+                println!("{line}");
+            }
+            println!();
+            println!("{}", outcome.content);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn format_rule_lines(rules: &[RoutingRule]) -> Vec<String> {
+    rules
+        .iter()
+        .map(|rule| format!("{:>4}  {rule}", rule.id))
+        .collect()
+}
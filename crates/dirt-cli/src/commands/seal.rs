@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use dirt_core::seal::{format_seal_date, parse_seal_date, seal_note, unseal_note, SealKeyStore};
+
+use crate::commands::common::{normalize_note_identifier, open_database, resolve_note_for_edit};
+use crate::error::CliError;
+
+pub async fn run_seal(id: &str, until: &str, db_path: &Path) -> Result<(), CliError> {
+    let until_ms = parse_seal_date(until)?;
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+
+    let keys = SealKeyStore::beside_database(db_path);
+    let now = chrono::Utc::now().timestamp_millis();
+    seal_note(&db, &keys, &note.id, until_ms, now).await?;
+
+    println!("{} sealed until {}", note.id, format_seal_date(until_ms));
+    Ok(())
+}
+
+pub async fn run_unseal(id: &str, db_path: &Path) -> Result<(), CliError> {
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+
+    let keys = SealKeyStore::beside_database(db_path);
+    let now = chrono::Utc::now().timestamp_millis();
+    let note = unseal_note(&db, &keys, &note.id, now).await?;
+
+    println!("{}", note.id);
+    Ok(())
+}
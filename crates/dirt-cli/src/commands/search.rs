@@ -1,30 +1,69 @@
 use std::path::Path;
 
+use dirt_core::search::SearchHit;
+use dirt_core::Note;
+use serde::Serialize;
+
+use crate::commands::columns::{format_note_lines, ListLayout};
 use crate::commands::common::{
-    format_note_lines, normalize_search_query, note_to_list_item, search_notes, NoteListItem,
+    normalize_search_query, note_to_list_item, open_database, NoteListItem,
 };
 use crate::error::CliError;
 
+#[derive(Debug, Serialize)]
+pub struct SearchListItem {
+    #[serde(flatten)]
+    pub note: NoteListItem,
+    /// Attachment whose filename or text matched, when the note's content did not
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_attachment: Option<String>,
+}
+
 pub async fn run_search(
     query: &str,
     limit: usize,
+    include_attachments: bool,
     as_json: bool,
+    layout: &ListLayout,
     db_path: &Path,
 ) -> Result<(), CliError> {
     let normalized_query = normalize_search_query(query)?;
-    let notes = search_notes(&normalized_query, limit, db_path).await?;
+    let db = open_database(db_path).await?;
+    let hits = db
+        .search_note_hits(&normalized_query, limit, include_attachments)
+        .await?;
 
     if as_json {
-        let json_items = notes
+        let json_items = hits
             .iter()
-            .map(note_to_list_item)
-            .collect::<Vec<NoteListItem>>();
+            .map(search_hit_to_item)
+            .collect::<Vec<SearchListItem>>();
         println!("{}", serde_json::to_string_pretty(&json_items)?);
     } else {
-        for line in format_note_lines(&notes) {
+        for line in format_search_lines(&hits, layout, db.short_note_id_len().await?) {
             println!("{line}");
         }
     }
 
     Ok(())
 }
+
+pub fn search_hit_to_item(hit: &SearchHit) -> SearchListItem {
+    SearchListItem {
+        note: note_to_list_item(&hit.note),
+        matched_attachment: hit.matched_attachment.clone(),
+    }
+}
+
+/// Note lines for search results, marking notes found through an attachment.
+pub fn format_search_lines(hits: &[SearchHit], layout: &ListLayout, id_len: usize) -> Vec<String> {
+    let notes: Vec<Note> = hits.iter().map(|hit| hit.note.clone()).collect();
+    format_note_lines(&notes, layout, id_len)
+        .into_iter()
+        .zip(hits)
+        .map(|(line, hit)| match &hit.matched_attachment {
+            Some(filename) => format!("{line}  [attachment: {filename}]"),
+            None => line,
+        })
+        .collect()
+}
@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use dirt_core::share::{parse_share_expiry, ShareLink};
+
+use crate::commands::api_client::DirtApi;
+use crate::commands::common::{normalize_note_identifier, open_database, resolve_note_for_edit};
+use crate::commands::tokens::format_unix_seconds;
+use crate::error::CliError;
+
+/// What `dirt share` does with the note
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareAction {
+    /// Create a link; `None` uses the server's default lifetime, and notes
+    /// marked no-export are refused unless `include_private` is set
+    Create {
+        expires_in_secs: Option<i64>,
+        include_private: bool,
+    },
+    List,
+    Revoke,
+}
+
+impl ShareAction {
+    pub fn from_flags(
+        expires: Option<&str>,
+        include_private: bool,
+        list: bool,
+        revoke: bool,
+    ) -> Result<Self, CliError> {
+        Ok(if list {
+            Self::List
+        } else if revoke {
+            Self::Revoke
+        } else {
+            Self::Create {
+                expires_in_secs: expires.map(parse_share_expiry).transpose()?,
+                include_private,
+            }
+        })
+    }
+}
+
+pub async fn run_share(
+    id: &str,
+    action: ShareAction,
+    as_json: bool,
+    db_path: &Path,
+    global_profile: Option<&str>,
+) -> Result<(), CliError> {
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+    if let ShareAction::Create {
+        include_private, ..
+    } = action
+    {
+        if !db.export_privacy(include_private).await?.allows(&note) {
+            return Err(CliError::PrivateNote(note.id.to_string()));
+        }
+    }
+    let api = DirtApi::for_profile(global_profile).await?;
+    let (client, access_token) = api.share_client()?;
+
+    match action {
+        ShareAction::Create {
+            expires_in_secs, ..
+        } => {
+            let link = client
+                .create(access_token, &note.id, expires_in_secs)
+                .await
+                .map_err(CliError::Api)?;
+            if as_json {
+                println!("{}", serde_json::to_string_pretty(&link)?);
+                return Ok(());
+            }
+            println!("{}", link.url.as_deref().unwrap_or_default());
+            eprintln!(
+                "Anyone with this link can read the note until {}; revoke it with `dirt share {} --revoke`",
+                format_unix_seconds(link.expires_at),
+                note.id
+            );
+        }
+        ShareAction::List => {
+            let links = client
+                .list(access_token, &note.id)
+                .await
+                .map_err(CliError::Api)?;
+            if as_json {
+                println!("{}", serde_json::to_string_pretty(&links)?);
+                return Ok(());
+            }
+            if links.is_empty() {
+                println!("No share links for {}.", note.id);
+                return Ok(());
+            }
+            let now = chrono::Utc::now().timestamp();
+            for line in format_share_lines(&links, now) {
+                println!("{line}");
+            }
+        }
+        ShareAction::Revoke => {
+            let now = chrono::Utc::now().timestamp();
+            let active: Vec<ShareLink> = client
+                .list(access_token, &note.id)
+                .await
+                .map_err(CliError::Api)?
+                .into_iter()
+                .filter(|link| share_status(link, now) == "active")
+                .collect();
+            for link in &active {
+                client
+                    .revoke(access_token, &link.id)
+                    .await
+                    .map_err(CliError::Api)?;
+            }
+            if as_json {
+                let ids: Vec<&str> = active.iter().map(|link| link.id.as_str()).collect();
+                println!("{}", serde_json::to_string_pretty(&ids)?);
+            } else {
+                println!("Revoked {} share link(s) for {}", active.len(), note.id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn format_share_lines(links: &[ShareLink], now: i64) -> Vec<String> {
+    links
+        .iter()
+        .map(|link| {
+            format!(
+                "{}  {:<8}  created={}  expires={}",
+                link.id,
+                share_status(link, now),
+                format_unix_seconds(link.created_at),
+                format_unix_seconds(link.expires_at)
+            )
+        })
+        .collect()
+}
+
+pub const fn share_status(link: &ShareLink, now: i64) -> &'static str {
+    if link.revoked_at.is_some() {
+        "revoked"
+    } else if link.expires_at <= now {
+        "expired"
+    } else {
+        "active"
+    }
+}
@@ -0,0 +1,17 @@
+use std::path::Path;
+
+use crate::commands::common::open_database;
+use crate::error::CliError;
+
+pub async fn run_stats(as_json: bool, db_path: &Path) -> Result<(), CliError> {
+    let db = open_database(db_path).await?;
+    let stats = db.capture_stats().await?;
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        println!("{}", stats.render_text());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use dirt_core::models::{NoteSummary, SummaryMethod};
+use dirt_core::seal::{format_seal_date, sealed_until};
+use dirt_core::summarize::{summarize_note, SummaryConfig};
+use serde::Serialize;
+
+use crate::commands::common::{normalize_note_identifier, open_database, resolve_note_for_edit};
+use crate::error::CliError;
+
+#[derive(Debug, Serialize)]
+pub struct NoteSummaryItem {
+    pub note_id: String,
+    pub summary: String,
+    pub method: SummaryMethod,
+    pub created_at: i64,
+}
+
+/// Summarize a note with `OpenAI` (when `OPENAI_API_KEY` is set) or locally,
+/// store the summary, and print it.
+pub async fn run_summarize(id: &str, as_json: bool, db_path: &Path) -> Result<(), CliError> {
+    let config = SummaryConfig::from_env()?;
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+    if let Some(until) = sealed_until(&note.content) {
+        return Err(CliError::NoteSealed {
+            id: note.id.to_string(),
+            until: format_seal_date(until),
+        });
+    }
+
+    let summary = summarize_note(&db, config.as_ref(), &note).await?;
+    if as_json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&note_summary_to_item(&summary))?
+        );
+    } else {
+        println!("{}", summary.summary);
+    }
+    Ok(())
+}
+
+pub fn note_summary_to_item(summary: &NoteSummary) -> NoteSummaryItem {
+    NoteSummaryItem {
+        note_id: summary.note_id.to_string(),
+        summary: summary.summary.clone(),
+        method: summary.method,
+        created_at: summary.created_at,
+    }
+}
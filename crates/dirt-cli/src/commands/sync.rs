@@ -1,11 +1,37 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use dirt_core::export::parse_no_export_tags;
+use dirt_core::models::{format_byte_count, SyncHistoryEntry};
+use dirt_core::services::DatabaseService;
+use dirt_core::sync::{
+    ConflictResolver, ConflictStrategy as CoreConflictStrategy, Manual, SyncAction, SyncScheduler,
+    SyncToken,
+};
+use dirt_core::util::fs::write_atomic;
+use dirt_core::util::unix_timestamp_now;
+use dirt_core::SyncConflict;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{ConflictFormat, ConflictStrategy};
 use crate::commands::common::{
-    format_sync_conflict_lines, list_sync_conflicts, open_sync_database, sync_conflict_to_item,
-    SyncConflictItem,
+    capture_editor_input_with_initial, exchange_managed_sync_token, format_relative_time,
+    format_sync_conflict_lines, format_sync_timestamp, list_sync_conflicts, open_database,
+    open_sync_database, open_sync_database_with_token, sync_conflict_to_item, SyncConflictItem,
 };
 use crate::error::CliError;
 
+#[derive(Debug, Serialize)]
+pub struct SyncHistoryItem {
+    pub id: i64,
+    pub synced_at: i64,
+    pub synced_at_iso: String,
+    pub duration_ms: i64,
+    pub frames_synced: i64,
+    pub bytes_transferred: i64,
+    pub rows_changed: i64,
+}
+
 pub async fn run_sync(db_path: &Path) -> Result<(), CliError> {
     let db = open_sync_database(db_path).await?;
     if !db.is_sync_enabled().await {
@@ -13,33 +39,490 @@ pub async fn run_sync(db_path: &Path) -> Result<(), CliError> {
     }
 
     db.sync().await?;
-    println!("Sync completed");
+    print_sync_summary(&db).await
+}
+
+/// Sync every `interval` until interrupted.
+pub async fn run_sync_watch(interval: Duration, db_path: &Path) -> Result<(), CliError> {
+    sync_periodically(interval, db_path, None).await
+}
+
+/// `dirt sync daemon`: sync every `interval` like `--watch`, recording progress
+/// in a status file that `dirt sync status` reads.
+///
+/// The status file is removed on Ctrl-C; one left behind by a killed daemon
+/// is recognised as stale once its heartbeat stops.
+pub async fn run_sync_daemon(interval: Duration, db_path: &Path) -> Result<(), CliError> {
+    let status_path = sync_daemon_status_path(db_path);
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    if let Some(status) = read_sync_daemon_status(&status_path)? {
+        if status.is_running(now_ms) {
+            return Err(CliError::SyncDaemonRunning(status.pid));
+        }
+    }
+
+    let mut status_file = SyncDaemonStatusFile {
+        path: status_path,
+        status: SyncDaemonStatus {
+            pid: std::process::id(),
+            interval_secs: interval.as_secs(),
+            started_at: now_ms,
+            heartbeat_at: now_ms,
+            last_synced_at: None,
+            last_error: None,
+            pending_changes: 0,
+        },
+    };
+    status_file.write()?;
+
+    let result = tokio::select! {
+        result = sync_periodically(interval, db_path, Some(&mut status_file)) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    };
+    if let Err(error) = std::fs::remove_file(&status_file.path) {
+        tracing::warn!(error = %error, "Failed to remove sync daemon status file");
+    }
+    result
+}
+
+/// Sync loop shared by `--watch` and `dirt sync daemon`.
+///
+/// Managed tokens are short-lived, so the token is exchanged again shortly
+/// before it expires (or when the backend rotates credentials) and the
+/// replica is reopened whenever the database URL or token changes.
+async fn sync_periodically(
+    interval: Duration,
+    db_path: &Path,
+    mut status_file: Option<&mut SyncDaemonStatusFile>,
+) -> Result<(), CliError> {
+    let mut scheduler = SyncScheduler::new();
+    let mut token = exchange_managed_sync_token().await?;
+    let mut db = open_sync_database_with_token(db_path, &token).await?;
+    scheduler.credentials_opened(&token, unix_timestamp_now());
+    println!(
+        "Syncing every {}s; press Ctrl-C to stop",
+        interval.as_secs()
+    );
+
+    loop {
+        let result = db.sync().await;
+        match &result {
+            Ok(()) => {
+                tracing::debug!("Sync completed");
+                print_sync_summary(&db).await?;
+            }
+            Err(error) => {
+                tracing::debug!(error = %error, "Sync failed");
+                eprintln!("Sync failed: {error}");
+            }
+        }
+        if let Some(status_file) = status_file.as_deref_mut() {
+            let pending_changes = db.pending_sync_count().await?;
+            status_file
+                .record_sync(result.err().map(|error| error.to_string()), pending_changes)?;
+        }
+        tokio::time::sleep(interval).await;
+
+        let now = unix_timestamp_now();
+        let fresh_token = match scheduler.next_action(now) {
+            SyncAction::Sync => None,
+            SyncAction::CheckRotation => {
+                scheduler.begin_rotation_check(now);
+                match exchange_managed_sync_token().await {
+                    Ok(fresh) => scheduler.observe_exchange(fresh, now).and_then(|event| {
+                        println!("{event}");
+                        scheduler.take_pending_token(now)
+                    }),
+                    Err(error) => {
+                        // The open replica's credentials still work.
+                        eprintln!("Sync credential rotation check failed: {error}");
+                        None
+                    }
+                }
+            }
+            SyncAction::Reopen(reason) => match scheduler.take_pending_token(now) {
+                Some(pending) => Some(pending),
+                None => match exchange_managed_sync_token().await {
+                    Ok(fresh) => Some(fresh),
+                    Err(error) => {
+                        eprintln!("Sync token refresh failed ({reason}): {error}");
+                        None
+                    }
+                },
+            },
+        };
+
+        let Some(fresh_token) = fresh_token else {
+            continue;
+        };
+        if sync_credentials_changed(&token, &fresh_token) {
+            // Release the replica file before libSQL opens it again.
+            drop(db);
+            db = open_sync_database_with_token(db_path, &fresh_token).await?;
+            println!("Reopened sync replica with refreshed credentials");
+        }
+        scheduler.credentials_opened(&fresh_token, now);
+        token = fresh_token;
+    }
+}
+
+/// Extra seconds past two intervals before a daemon's heartbeat counts as
+/// stale, covering slow syncs and token exchanges.
+const DAEMON_HEARTBEAT_GRACE_SECS: u64 = 60;
+
+/// What `dirt sync daemon` last reported, kept next to the database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncDaemonStatus {
+    pub pid: u32,
+    pub interval_secs: u64,
+    /// Unix milliseconds
+    pub started_at: i64,
+    /// Unix milliseconds of the last sync attempt
+    pub heartbeat_at: i64,
+    /// Unix milliseconds of the last successful sync
+    pub last_synced_at: Option<i64>,
+    /// Error from the last sync attempt, if it failed
+    pub last_error: Option<String>,
+    /// Notes in the sync outbox after the last attempt
+    #[serde(default)]
+    pub pending_changes: usize,
+}
+
+impl SyncDaemonStatus {
+    /// Whether the daemon has synced recently enough to still be running.
+    pub fn is_running(&self, now_ms: i64) -> bool {
+        let stale_after_secs = self
+            .interval_secs
+            .saturating_mul(2)
+            .saturating_add(DAEMON_HEARTBEAT_GRACE_SECS);
+        let stale_after_ms =
+            i64::try_from(stale_after_secs.saturating_mul(1_000)).unwrap_or(i64::MAX);
+        now_ms.saturating_sub(self.heartbeat_at) <= stale_after_ms
+    }
+}
+
+struct SyncDaemonStatusFile {
+    path: PathBuf,
+    status: SyncDaemonStatus,
+}
+
+impl SyncDaemonStatusFile {
+    fn write(&self) -> Result<(), CliError> {
+        write_atomic(&self.path, serde_json::to_string_pretty(&self.status)?)?;
+        Ok(())
+    }
+
+    fn record_sync(
+        &mut self,
+        error: Option<String>,
+        pending_changes: usize,
+    ) -> Result<(), CliError> {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        self.status.heartbeat_at = now_ms;
+        if error.is_none() {
+            self.status.last_synced_at = Some(now_ms);
+        }
+        self.status.last_error = error;
+        self.status.pending_changes = pending_changes;
+        self.write()
+    }
+}
+
+/// Status file of the sync daemon for the database at `db_path`
+pub fn sync_daemon_status_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("sync-daemon.json")
+}
+
+pub fn read_sync_daemon_status(path: &Path) -> Result<Option<SyncDaemonStatus>, CliError> {
+    match std::fs::read_to_string(path) {
+        Ok(raw) => Ok(Some(serde_json::from_str(&raw)?)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+pub fn run_sync_status(as_json: bool, db_path: &Path) -> Result<(), CliError> {
+    let status = read_sync_daemon_status(&sync_daemon_status_path(db_path))?;
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    if as_json {
+        let item = SyncStatusItem {
+            running: status
+                .as_ref()
+                .is_some_and(|status| status.is_running(now_ms)),
+            daemon: status,
+        };
+        println!("{}", serde_json::to_string_pretty(&item)?);
+        return Ok(());
+    }
+    for line in format_sync_status_lines(status.as_ref(), now_ms) {
+        println!("{line}");
+    }
     Ok(())
 }
 
+#[derive(Debug, Serialize)]
+struct SyncStatusItem {
+    running: bool,
+    daemon: Option<SyncDaemonStatus>,
+}
+
+pub fn format_sync_status_lines(status: Option<&SyncDaemonStatus>, now_ms: i64) -> Vec<String> {
+    let Some(status) = status else {
+        return vec!["Sync daemon is not running; start it with `dirt sync daemon`".to_string()];
+    };
+    let mut lines = vec![if status.is_running(now_ms) {
+        format!(
+            "Sync daemon running (pid {}), syncing every {}s since {}",
+            status.pid,
+            status.interval_secs,
+            format_sync_timestamp(status.started_at)
+        )
+    } else {
+        format!(
+            "Sync daemon not running (pid {} last seen {})",
+            status.pid,
+            format_relative_time(status.heartbeat_at, now_ms)
+        )
+    }];
+    lines.push(match status.last_synced_at {
+        Some(synced_at) => format!(
+            "Last synced {} ({})",
+            format_relative_time(synced_at, now_ms),
+            format_sync_timestamp(synced_at)
+        ),
+        None => "Not synced yet".to_string(),
+    });
+    if let Some(error) = &status.last_error {
+        lines.push(format!("Last sync failed: {error}"));
+    }
+    if status.pending_changes > 0 {
+        lines.push(format!(
+            "{} note(s) waiting to sync",
+            status.pending_changes
+        ));
+    }
+    lines
+}
+
+/// Whether `fresh` needs a new replica connection; libSQL bakes the URL and
+/// token into the connection when it is opened.
+pub fn sync_credentials_changed(current: &SyncToken, fresh: &SyncToken) -> bool {
+    current.database_url != fresh.database_url || current.token != fresh.token
+}
+
+async fn print_sync_summary(db: &DatabaseService) -> Result<(), CliError> {
+    match db.list_sync_history(1).await?.first() {
+        Some(entry) => println!(
+            "Sync completed: {} transferred, {} notes changed",
+            format_byte_count(entry.bytes_transferred),
+            entry.rows_changed
+        ),
+        None => println!("Sync completed"),
+    }
+    Ok(())
+}
+
+/// Conflicts listed when neither `--limit` nor `--since` is given.
+const DEFAULT_CONFLICT_LIMIT: usize = 10;
+
 pub async fn run_sync_conflicts(
-    limit: usize,
-    as_json: bool,
+    limit: Option<usize>,
+    since: Option<&str>,
+    format: ConflictFormat,
+    output_path: Option<&Path>,
+    db_path: &Path,
+) -> Result<(), CliError> {
+    let since_ms = since.map(parse_conflict_since).transpose()?;
+    let limit = limit.unwrap_or(if since_ms.is_some() {
+        u32::MAX as usize
+    } else {
+        DEFAULT_CONFLICT_LIMIT
+    });
+    let db = open_database(db_path).await?;
+    let conflicts = list_sync_conflicts(&db, since_ms, limit).await?;
+    let rendered = render_sync_conflicts(&conflicts, format, db.short_note_id_len().await?)?;
+
+    if let Some(path) = output_path {
+        write_atomic(path, rendered)?;
+        println!("{}", path.display());
+    } else if !rendered.is_empty() {
+        println!("{}", rendered.trim_end_matches('\n'));
+    }
+    Ok(())
+}
+
+/// Content typed into `$EDITOR` for a manual resolution
+struct EditedResolution(String);
+
+impl ConflictResolver for EditedResolution {
+    fn name(&self) -> &'static str {
+        Manual.name()
+    }
+
+    fn resolve(&self, _local: &str, _remote: &str) -> String {
+        self.0.clone()
+    }
+}
+
+pub async fn run_resolve_conflict(
+    id: i64,
+    strategy: ConflictStrategy,
     db_path: &Path,
 ) -> Result<(), CliError> {
-    let conflicts = list_sync_conflicts(limit, db_path).await?;
+    let strategy = match strategy {
+        ConflictStrategy::KeepLocal => CoreConflictStrategy::KeepLocal,
+        ConflictStrategy::KeepRemote => CoreConflictStrategy::KeepRemote,
+        ConflictStrategy::MergeByLine => CoreConflictStrategy::MergeByLine,
+        ConflictStrategy::Manual => CoreConflictStrategy::Manual,
+    };
+    let db = open_database(db_path).await?;
+
+    let note = if strategy == CoreConflictStrategy::Manual {
+        let conflict = db
+            .get_conflict(id)
+            .await?
+            .ok_or_else(|| dirt_core::Error::NotFound(format!("Sync conflict {id}")))?;
+        let (local, remote) = conflict.versions()?;
+        let draft = Manual.resolve(local, remote);
+        let Some(edited) = capture_editor_input_with_initial(&draft)? else {
+            return Err(CliError::EmptyEditedContent);
+        };
+        db.resolve_conflict(id, &EditedResolution(edited)).await?
+    } else {
+        db.resolve_conflict(id, strategy.resolver()).await?
+    };
+
+    println!("Resolved conflict {id}; {} updated", note.id);
+    Ok(())
+}
+
+/// Render conflicts; `jsonl` writes one compact object per line so external
+/// tools can stream and append exports.
+pub fn render_sync_conflicts(
+    conflicts: &[SyncConflict],
+    format: ConflictFormat,
+    id_len: usize,
+) -> Result<String, CliError> {
+    let items = || conflicts.iter().map(sync_conflict_to_item);
+    Ok(match format {
+        ConflictFormat::Json => {
+            serde_json::to_string_pretty(&items().collect::<Vec<SyncConflictItem>>())?
+        }
+        ConflictFormat::Jsonl => {
+            let mut rendered = String::new();
+            for item in items() {
+                rendered.push_str(&serde_json::to_string(&item)?);
+                rendered.push('\n');
+            }
+            rendered
+        }
+        ConflictFormat::Text if conflicts.is_empty() => "No sync conflicts recorded.".to_string(),
+        ConflictFormat::Text => format_sync_conflict_lines(conflicts, id_len).join("\n"),
+    })
+}
+
+/// Parse `--since`: `YYYY-MM-DD` (start of day, UTC), RFC 3339, or Unix ms
+/// such as the `resolved_at` of the last exported conflict.
+pub fn parse_conflict_since(input: &str) -> Result<i64, CliError> {
+    let input = input.trim();
+    if let Ok(timestamp_ms) = input.parse::<i64>() {
+        return Ok(timestamp_ms);
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let start_of_day = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        return Ok(start_of_day.and_utc().timestamp_millis());
+    }
+    chrono::DateTime::parse_from_rfc3339(input)
+        .map(|date_time| date_time.timestamp_millis())
+        .map_err(|_| {
+            dirt_core::Error::InvalidInput(format!(
+                "Invalid --since value '{input}' (expected YYYY-MM-DD, RFC 3339, or Unix milliseconds)"
+            ))
+            .into()
+        })
+}
+
+pub async fn run_sync_history(limit: usize, as_json: bool, db_path: &Path) -> Result<(), CliError> {
+    let db = open_database(db_path).await?;
+    let entries = db.list_sync_history(limit).await?;
 
     if as_json {
-        let json_items = conflicts
+        let json_items = entries
             .iter()
-            .map(sync_conflict_to_item)
-            .collect::<Vec<SyncConflictItem>>();
+            .map(sync_history_to_item)
+            .collect::<Vec<SyncHistoryItem>>();
         println!("{}", serde_json::to_string_pretty(&json_items)?);
         return Ok(());
     }
 
-    if conflicts.is_empty() {
-        println!("No sync conflicts recorded.");
+    if entries.is_empty() {
+        println!("No syncs recorded.");
         return Ok(());
     }
 
-    for line in format_sync_conflict_lines(&conflicts) {
+    for line in format_sync_history_lines(&entries) {
         println!("{line}");
     }
     Ok(())
 }
+
+/// Keep notes tagged `tag` off the sync server, or sync them again with
+/// `clear`; returns the excluded tags.
+pub async fn run_sync_exclude_tag(
+    tag: &str,
+    clear: bool,
+    db_path: &Path,
+) -> Result<Vec<String>, CliError> {
+    let tags = parse_no_export_tags(tag);
+    let [tag] = tags.as_slice() else {
+        return Err(dirt_core::Error::InvalidInput(format!("Invalid tag '{tag}'")).into());
+    };
+    let db = open_database(db_path).await?;
+    let mut excluded = db.load_settings().await?.sync_excluded_tags;
+
+    excluded.retain(|existing| existing != tag);
+    if !clear {
+        excluded.push(tag.clone());
+    }
+    let changed = db.set_sync_excluded_tags(excluded.clone()).await?;
+
+    if clear {
+        println!("Notes tagged #{tag} sync again");
+    } else {
+        println!("Notes tagged #{tag} are now kept on this device");
+    }
+    if changed > 0 {
+        println!("Updated {changed} note(s).");
+    }
+    if !db.is_sync_enabled().await {
+        println!("Sync is not set up yet; the rule applies once it is.");
+    }
+    Ok(excluded)
+}
+
+pub fn sync_history_to_item(entry: &SyncHistoryEntry) -> SyncHistoryItem {
+    SyncHistoryItem {
+        id: entry.id,
+        synced_at: entry.synced_at,
+        synced_at_iso: format_sync_timestamp(entry.synced_at),
+        duration_ms: entry.duration_ms,
+        frames_synced: entry.frames_synced,
+        bytes_transferred: entry.bytes_transferred,
+        rows_changed: entry.rows_changed,
+    }
+}
+
+pub fn format_sync_history_lines(entries: &[SyncHistoryEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}  {:>9}  {:>5} notes changed  {} ms",
+                format_sync_timestamp(entry.synced_at),
+                format_byte_count(entry.bytes_transferred),
+                entry.rows_changed,
+                entry.duration_ms
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::cli::TagCommands;
+use crate::commands::common::open_database;
+use crate::error::CliError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagItem {
+    pub name: String,
+    pub notes: usize,
+}
+
+pub async fn run_tag(command: TagCommands, db_path: &Path) -> Result<(), CliError> {
+    let db = open_database(db_path).await?;
+    match command {
+        TagCommands::List { counts, json } => {
+            let tags: Vec<TagItem> = db
+                .list_tags()
+                .await?
+                .into_iter()
+                .map(|(name, notes)| TagItem { name, notes })
+                .collect();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&tags)?);
+                return Ok(());
+            }
+            if tags.is_empty() {
+                println!("No tags.");
+                return Ok(());
+            }
+            for line in format_tag_lines(&tags, counts) {
+                println!("{line}");
+            }
+        }
+        TagCommands::Rename { old, new } => {
+            let changed = db.rename_tag(&old, &new).await?;
+            println!("Renamed {} in {changed} note(s).", display_tag(&old));
+        }
+        TagCommands::Merge { tags, into } => {
+            let changed = db.merge_tags(&tags, &into).await?;
+            println!("Merged into {} in {changed} note(s).", display_tag(&into));
+        }
+        TagCommands::Remove { tag } => {
+            let changed = db.remove_tag(&tag).await?;
+            println!("Removed {} from {changed} note(s).", display_tag(&tag));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn format_tag_lines(tags: &[TagItem], counts: bool) -> Vec<String> {
+    if !counts {
+        return tags.iter().map(|tag| format!("#{}", tag.name)).collect();
+    }
+    let width = tags
+        .iter()
+        .map(|tag| tag.notes.to_string().len())
+        .max()
+        .unwrap_or(1);
+    tags.iter()
+        .map(|tag| format!("{:>width$}  #{}", tag.notes, tag.name))
+        .collect()
+}
+
+fn display_tag(raw: &str) -> String {
+    format!("#{}", raw.trim().trim_start_matches('#').to_lowercase())
+}
@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use dirt_core::models::{short_note_id, Note, NoteTask};
+use serde::Serialize;
+
+use crate::commands::common::{note_preview, open_database};
+use crate::error::CliError;
+
+const PREVIEW_CHARS: usize = 60;
+
+#[derive(Debug, Serialize)]
+pub struct OpenTaskItem {
+    pub note_id: String,
+    pub note_preview: String,
+    /// Zero-based line of the task in the note content
+    pub line_index: usize,
+    pub text: String,
+}
+
+/// Print unchecked `- [ ]` tasks grouped by note, newest note first.
+pub async fn run_tasks_list(
+    as_json: bool,
+    db_path: &Path,
+) -> Result<Vec<(Note, Vec<NoteTask>)>, CliError> {
+    let db = open_database(db_path).await?;
+    let open = db.list_open_tasks().await?;
+
+    if as_json {
+        let json_items = open_task_items(&open);
+        println!("{}", serde_json::to_string_pretty(&json_items)?);
+    } else if open.is_empty() {
+        println!("No open tasks.");
+    } else {
+        for line in format_task_lines(&open, db.short_note_id_len().await?) {
+            println!("{line}");
+        }
+    }
+
+    Ok(open)
+}
+
+/// One header line per note, then each task with its 1-based line number.
+pub fn format_task_lines(open: &[(Note, Vec<NoteTask>)], id_len: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (note, tasks) in open {
+        let id = note.id.to_string();
+        lines.push(format!(
+            "{:<id_len$}  {}",
+            short_note_id(&id, id_len),
+            note_preview(note, PREVIEW_CHARS)
+        ));
+        for task in tasks {
+            lines.push(format!("  {:>4}  [ ] {}", task.line_index + 1, task.text));
+        }
+    }
+    lines
+}
+
+pub fn open_task_items(open: &[(Note, Vec<NoteTask>)]) -> Vec<OpenTaskItem> {
+    open.iter()
+        .flat_map(|(note, tasks)| {
+            tasks.iter().map(|task| OpenTaskItem {
+                note_id: note.id.to_string(),
+                note_preview: note_preview(note, PREVIEW_CHARS),
+                line_index: task.line_index,
+                text: task.text.clone(),
+            })
+        })
+        .collect()
+}
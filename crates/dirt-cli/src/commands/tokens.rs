@@ -0,0 +1,183 @@
+use dirt_core::models::AccessScope;
+use reqwest::{Method, RequestBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::TokenCommands;
+use crate::commands::api_client::{parse_json, send, DirtApi};
+use crate::error::CliError;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TokenItem {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<AccessScope>,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub last_used_at: Option<i64>,
+    pub revoked_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CreatedToken {
+    token: String,
+    #[serde(flatten)]
+    item: TokenItem,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenList {
+    tokens: Vec<TokenItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateTokenRequest<'a> {
+    name: &'a str,
+    scopes: &'a [AccessScope],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_in_days: Option<u32>,
+}
+
+/// Dirt API `/v1/tokens` endpoints.
+struct TokensApi {
+    api: DirtApi,
+}
+
+impl TokensApi {
+    async fn for_profile(global_profile: Option<&str>) -> Result<Self, CliError> {
+        Ok(Self {
+            api: DirtApi::for_profile(global_profile).await?,
+        })
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.api.request(method, &format!("/tokens{path}"))
+    }
+
+    async fn create(
+        &self,
+        name: &str,
+        scopes: &[AccessScope],
+        expires_in_days: Option<u32>,
+    ) -> Result<CreatedToken, CliError> {
+        let request = CreateTokenRequest {
+            name,
+            scopes,
+            expires_in_days,
+        };
+        let response = send(self.request(Method::POST, "").json(&request)).await?;
+        parse_json(response).await
+    }
+
+    async fn list(&self) -> Result<Vec<TokenItem>, CliError> {
+        let response = send(self.request(Method::GET, "")).await?;
+        Ok(parse_json::<TokenList>(response).await?.tokens)
+    }
+
+    async fn revoke(&self, id: &str) -> Result<(), CliError> {
+        send(self.request(Method::DELETE, &format!("/{id}"))).await?;
+        Ok(())
+    }
+}
+
+pub async fn run_tokens(
+    command: TokenCommands,
+    global_profile: Option<&str>,
+) -> Result<(), CliError> {
+    let api = TokensApi::for_profile(global_profile).await?;
+
+    match command {
+        TokenCommands::Create {
+            name,
+            scopes,
+            expires_in_days,
+            json,
+        } => {
+            let created = api.create(&name, &scopes, expires_in_days).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&created)?);
+                return Ok(());
+            }
+            println!(
+                "Created token '{}' ({}), expires {}",
+                created.item.name,
+                created.item.id,
+                format_unix_seconds(created.item.expires_at)
+            );
+            println!("Scopes: {}", render_scopes(&created.item.scopes));
+            println!();
+            println!("{}", created.token);
+            println!();
+            println!("Copy this token now; it will not be shown again.");
+        }
+        TokenCommands::List { json } => {
+            let tokens = api.list().await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&tokens)?);
+                return Ok(());
+            }
+            if tokens.is_empty() {
+                println!("No personal access tokens.");
+                return Ok(());
+            }
+            let now = chrono::Utc::now().timestamp();
+            for line in format_token_lines(&tokens, now) {
+                println!("{line}");
+            }
+        }
+        TokenCommands::Revoke { id } => {
+            let id = id.trim();
+            if id.is_empty() {
+                return Err(CliError::Api("Token ID cannot be empty".to_string()));
+            }
+            api.revoke(id).await?;
+            println!("Revoked token {id}");
+        }
+    }
+
+    Ok(())
+}
+
+pub fn format_token_lines(tokens: &[TokenItem], now: i64) -> Vec<String> {
+    tokens
+        .iter()
+        .map(|token| {
+            let last_used = token
+                .last_used_at
+                .map_or_else(|| "never".to_string(), format_unix_seconds);
+            format!(
+                "{}  {:<8}  {:<24}  {}  expires={}  last_used={}",
+                token.id,
+                token_status(token, now),
+                token.name,
+                render_scopes(&token.scopes),
+                format_unix_seconds(token.expires_at),
+                last_used
+            )
+        })
+        .collect()
+}
+
+pub const fn token_status(token: &TokenItem, now: i64) -> &'static str {
+    if token.revoked_at.is_some() {
+        "revoked"
+    } else if token.expires_at <= now {
+        "expired"
+    } else {
+        "active"
+    }
+}
+
+fn render_scopes(scopes: &[AccessScope]) -> String {
+    scopes
+        .iter()
+        .map(|scope| scope.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn format_unix_seconds(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0).map_or_else(
+        || timestamp.to_string(),
+        |date_time| date_time.format("%Y-%m-%d %H:%M UTC").to_string(),
+    )
+}
@@ -0,0 +1,143 @@
+use std::path::Path;
+
+use dirt_core::config::{ConfigSource, EnvConfig};
+use dirt_core::media::AttachmentCache;
+use dirt_core::models::Attachment;
+use dirt_core::transcription::{
+    append_voice_memo_transcript, configured_transcriber, transcribe_audio, AudioClip,
+    TranscriptionProvider, WhisperModelStore, ENV_OPENAI_API_KEY,
+};
+
+use crate::commands::api_client::DirtApi;
+use crate::commands::common::open_database;
+use crate::commands::profile_db::data_root;
+use crate::error::CliError;
+
+/// Transcribe an audio attachment and print the transcript; with `append`,
+/// also add it to the attachment's note.
+///
+/// The profile's settings pick the provider unless `provider` overrides it.
+pub async fn run_transcribe(
+    attachment_id: &str,
+    append: bool,
+    provider: Option<&str>,
+    db_path: &Path,
+    global_profile: Option<&str>,
+) -> Result<String, CliError> {
+    let db = open_database(db_path).await?;
+    let mut settings = db.load_settings().await?;
+    if let Some(provider) = provider {
+        settings.transcription_provider = parse_provider(provider)?;
+    }
+    let transcriber = configured_transcriber(
+        &settings,
+        EnvConfig.text_var(ENV_OPENAI_API_KEY),
+        &EnvConfig,
+        &WhisperModelStore::in_dir(&data_root()),
+    )?
+    .ok_or_else(|| {
+        CliError::Config(
+            "Transcription is not configured. Set OPENAI_API_KEY or use `dirt whisper use <MODEL>`."
+                .to_string(),
+        )
+    })?;
+
+    let attachments = db.list_all_attachments().await?;
+    let attachment = resolve_audio_attachment(&attachments, attachment_id)?;
+
+    let cache =
+        AttachmentCache::beside_database(db_path, settings.attachment_cache_mb.unwrap_or(0))
+            .map_err(|error| tracing::warn!("Failed to open the attachment cache: {error}"))
+            .ok();
+    let api = DirtApi::for_profile(global_profile).await?;
+    let (client, access_token) = api.media_client()?;
+    let (bytes, _content_type) = client
+        .download_cached(cache.as_ref(), access_token, &attachment.r2_key)
+        .await
+        .map_err(|error| CliError::Api(String::from(error)))?;
+    let bytes = db.decrypt_attachment_bytes(bytes).await?;
+
+    let clip = AudioClip {
+        file_name: &attachment.filename,
+        mime_type: &attachment.mime_type,
+        bytes: &bytes,
+    };
+    let transcript = transcribe_audio(&transcriber, &clip).await?;
+    println!("{transcript}");
+
+    if append {
+        let updated = append_voice_memo_transcript(
+            &db,
+            &attachment.note_id,
+            &attachment.filename,
+            &transcript,
+            None,
+        )
+        .await?;
+        if updated.is_some() {
+            eprintln!("Appended the transcript to note {}", attachment.note_id);
+        }
+    }
+    Ok(transcript)
+}
+
+/// The audio attachment whose id is `query` or starts with it.
+pub fn resolve_audio_attachment<'a>(
+    attachments: &'a [Attachment],
+    query: &str,
+) -> Result<&'a Attachment, CliError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(invalid_input("Attachment ID cannot be empty".to_string()));
+    }
+    let matches = attachments
+        .iter()
+        .filter(|attachment| attachment.id.as_str().starts_with(query))
+        .collect::<Vec<_>>();
+    let attachment = match matches.as_slice() {
+        [] => {
+            return Err(CliError::Core(dirt_core::Error::NotFound(format!(
+                "Attachment not found for id/prefix: {query}"
+            ))))
+        }
+        [attachment] => *attachment,
+        _ => {
+            return Err(invalid_input(format!(
+                "Attachment ID prefix '{query}' is ambiguous; matches {} attachments",
+                matches.len()
+            )))
+        }
+    };
+
+    if !attachment
+        .mime_type
+        .to_ascii_lowercase()
+        .starts_with("audio/")
+    {
+        return Err(invalid_input(format!(
+            "{} is {}, not audio",
+            attachment.filename, attachment.mime_type
+        )));
+    }
+    if !attachment.scan_status.is_downloadable() {
+        return Err(invalid_input(format!(
+            "{} was quarantined by the content scanner",
+            attachment.filename
+        )));
+    }
+    Ok(attachment)
+}
+
+/// Provider named on the command line.
+pub fn parse_provider(value: &str) -> Result<TranscriptionProvider, CliError> {
+    TranscriptionProvider::parse(value).ok_or_else(|| {
+        invalid_input(format!(
+            "Unknown transcription provider '{}' (use openai or whisper)",
+            value.trim()
+        ))
+    })
+}
+
+fn invalid_input(message: String) -> CliError {
+    CliError::Core(dirt_core::Error::InvalidInput(message))
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use dirt_core::models::NoteSource;
+use dirt_core::seal::{format_seal_date, sealed_until};
+use dirt_core::translate::{
+    normalize_language, translate_note_with_config, TranslationConfig, TranslationOutput,
+};
+
+use crate::commands::common::{normalize_note_identifier, open_database, resolve_note_for_edit};
+use crate::error::CliError;
+
+/// Translate a note into `to`, as a new linked note or (with `append`) a
+/// section of the original. Prints the id of the note holding the translation.
+pub async fn run_translate(
+    id: &str,
+    to: &str,
+    append: bool,
+    db_path: &Path,
+) -> Result<(), CliError> {
+    let language = normalize_language(to).ok_or_else(|| {
+        CliError::Core(dirt_core::Error::InvalidInput(format!(
+            "'{to}' is not a language code (e.g. ja, de, pt-BR)"
+        )))
+    })?;
+    let config = TranslationConfig::from_env()?.ok_or_else(|| {
+        CliError::Config(
+            "Translation is not configured. Set OPENAI_API_KEY or DEEPL_API_KEY.".to_string(),
+        )
+    })?;
+
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let note = resolve_note_for_edit(&normalized_id, &db).await?;
+    if let Some(until) = sealed_until(&note.content) {
+        return Err(CliError::NoteSealed {
+            id: note.id.to_string(),
+            until: format_seal_date(until),
+        });
+    }
+
+    let output = if append {
+        TranslationOutput::AppendSection
+    } else {
+        TranslationOutput::LinkedNote
+    };
+    let translated =
+        translate_note_with_config(&db, &config, &note, &language, output, NoteSource::Cli).await?;
+    println!("{}", translated.id);
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use std::path::Path;
+
+use chrono::Utc;
+use dirt_core::media::{delete_attachment_objects, AttachmentCache};
+use dirt_core::models::{short_note_id, trash_cutoff, Note, TrashPurge};
+use dirt_core::services::DatabaseService;
+
+use crate::commands::api_client::DirtApi;
+use crate::commands::common::{
+    format_relative_time, normalize_note_identifier, note_preview, note_to_list_item, open_database,
+};
+use crate::commands::confirm::{confirm_destructive, ConfirmOptions, Confirmation};
+use crate::error::CliError;
+
+const PAGE_SIZE: usize = 500;
+const PREVIEW_CHARS: usize = 60;
+
+/// Print deleted notes that can still be restored, most recently deleted first.
+pub async fn run_trash_list(
+    limit: usize,
+    as_json: bool,
+    db_path: &Path,
+) -> Result<Vec<Note>, CliError> {
+    let db = open_database(db_path).await?;
+    let notes = db.list_trashed_notes(limit, 0).await?;
+
+    if as_json {
+        let json_items = notes.iter().map(note_to_list_item).collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&json_items)?);
+    } else if notes.is_empty() {
+        println!("Trash is empty.");
+    } else {
+        let now_ms = Utc::now().timestamp_millis();
+        for line in format_trash_lines(&notes, db.short_note_id_len().await?, now_ms) {
+            println!("{line}");
+        }
+    }
+
+    Ok(notes)
+}
+
+/// Move a deleted note back to the note list and print its id.
+pub async fn run_trash_restore(id: &str, db_path: &Path) -> Result<Note, CliError> {
+    let normalized_id = normalize_note_identifier(id)?;
+    let db = open_database(db_path).await?;
+    let trashed = list_all_trashed(&db).await?;
+    let note = resolve_trashed_note(&trashed, &normalized_id)?;
+
+    let restored = db.restore_note(&note.id).await?;
+    println!("{}", restored.id);
+    Ok(restored)
+}
+
+/// Permanently remove notes deleted more than `older_than_days` days ago
+/// (`0` empties the trash), then delete their attachments from storage.
+pub async fn run_trash_empty(
+    older_than_days: u32,
+    options: ConfirmOptions,
+    db_path: &Path,
+    global_profile: Option<&str>,
+) -> Result<TrashPurge, CliError> {
+    let db = open_database(db_path).await?;
+    let cutoff = trash_cutoff(Utc::now().timestamp_millis(), older_than_days);
+    let expired = list_all_trashed(&db)
+        .await?
+        .into_iter()
+        .filter(|note| note.updated_at < cutoff)
+        .collect::<Vec<_>>();
+    let scope = if older_than_days == 0 {
+        "deleted notes".to_string()
+    } else {
+        format!("notes deleted more than {older_than_days} days ago")
+    };
+    if expired.is_empty() {
+        println!("No {scope} in the trash.");
+        return Ok(TrashPurge::default());
+    }
+
+    let action = format!("Permanently remove {} {scope}", expired.len());
+    match confirm_destructive(&action, expired.len(), options)? {
+        Confirmation::DryRun => {
+            println!("Would permanently remove {} {scope}:", expired.len());
+            for note in &expired {
+                println!("  {}  {}", note.id, note_preview(note, PREVIEW_CHARS));
+            }
+            println!("Dry run: nothing was removed.");
+            Ok(TrashPurge::default())
+        }
+        Confirmation::Declined => {
+            println!("Aborted: nothing was removed.");
+            Ok(TrashPurge::default())
+        }
+        Confirmation::Proceed => {
+            let purge = db.purge_trash(older_than_days).await?;
+            println!("Removed {} notes from the trash.", purge.notes);
+            if !purge.attachments.is_empty() {
+                delete_purged_attachments(&purge, db_path, global_profile).await;
+            }
+            Ok(purge)
+        }
+    }
+}
+
+pub fn format_trash_lines(notes: &[Note], id_len: usize, now_ms: i64) -> Vec<String> {
+    notes
+        .iter()
+        .map(|note| {
+            let id = note.id.to_string();
+            let short_id = short_note_id(&id, id_len);
+            format!(
+                "{short_id:<id_len$}  {:<10}  {}",
+                format_relative_time(note.updated_at, now_ms),
+                note_preview(note, PREVIEW_CHARS)
+            )
+        })
+        .collect()
+}
+
+/// The trashed note whose id is `query` or starts with it.
+pub fn resolve_trashed_note<'a>(notes: &'a [Note], query: &str) -> Result<&'a Note, CliError> {
+    let matches = notes
+        .iter()
+        .filter(|note| note.id.as_str().starts_with(query))
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [] => Err(CliError::NoteNotFound(query.to_string())),
+        [note] => Ok(*note),
+        _ => Err(CliError::AmbiguousNoteId(format!(
+            "ID prefix '{query}' is ambiguous; matches {} notes in the trash",
+            matches.len()
+        ))),
+    }
+}
+
+async fn list_all_trashed(db: &DatabaseService) -> Result<Vec<Note>, CliError> {
+    let mut notes = Vec::new();
+    loop {
+        let batch = db.list_trashed_notes(PAGE_SIZE, notes.len()).await?;
+        let count = batch.len();
+        notes.extend(batch);
+        if count < PAGE_SIZE {
+            return Ok(notes);
+        }
+    }
+}
+
+/// Delete the stored files of purged attachments. Failures only warn: the
+/// files show up as orphans in `dirt maintain attachments --purge`.
+async fn delete_purged_attachments(
+    purge: &TrashPurge,
+    db_path: &Path,
+    global_profile: Option<&str>,
+) {
+    let removed = AttachmentCache::beside_database(db_path, 0).and_then(|cache| {
+        purge
+            .attachments
+            .iter()
+            .try_for_each(|attachment| cache.remove(&attachment.r2_key))
+    });
+    if let Err(error) = removed {
+        tracing::warn!("Failed to update the attachment cache: {error}");
+    }
+
+    let deleted = match DirtApi::for_profile(global_profile).await {
+        Ok(api) => match api.media_client() {
+            Ok((media_api, access_token)) => {
+                delete_attachment_objects(&media_api, access_token, &purge.attachments)
+                    .await
+                    .map_err(|error| error.to_string())
+            }
+            Err(error) => Err(error.to_string()),
+        },
+        Err(error) => Err(error.to_string()),
+    };
+    match deleted {
+        Ok(deleted) => println!("Deleted {deleted} stored attachment file(s)."),
+        Err(error) => eprintln!(
+            "Warning: {} attachment file(s) were not deleted from storage ({error}). Run `dirt maintain attachments --purge` later.",
+            purge.attachments.len()
+        ),
+    }
+}
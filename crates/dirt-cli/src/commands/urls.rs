@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use dirt_core::models::{short_note_id, NoteUrl};
+use serde::Serialize;
+
+use crate::commands::common::{note_preview, open_database};
+use crate::error::CliError;
+
+#[derive(Debug, Serialize)]
+pub struct NoteUrlItem {
+    pub url: String,
+    pub note_id: String,
+    pub note_preview: String,
+    pub note_updated_at: i64,
+}
+
+pub async fn run_urls(
+    limit: usize,
+    tag: Option<&str>,
+    as_json: bool,
+    db_path: &Path,
+) -> Result<(), CliError> {
+    let db = open_database(db_path).await?;
+    let urls = db.list_urls(tag, limit).await?;
+
+    if as_json {
+        let json_items = urls.iter().map(note_url_to_item).collect::<Vec<_>>();
+        println!("{}", serde_json::to_string_pretty(&json_items)?);
+    } else {
+        for line in format_url_lines(&urls, db.short_note_id_len().await?) {
+            println!("{line}");
+        }
+    }
+
+    Ok(())
+}
+
+pub fn format_url_lines(urls: &[NoteUrl], id_len: usize) -> Vec<String> {
+    urls.iter()
+        .map(|note_url| {
+            let id = note_url.note.id.to_string();
+            let short_id = short_note_id(&id, id_len);
+            let preview = note_preview(&note_url.note, 30);
+            format!("{short_id:<id_len$}  {preview:<30}  {}", note_url.url)
+        })
+        .collect()
+}
+
+pub fn note_url_to_item(note_url: &NoteUrl) -> NoteUrlItem {
+    NoteUrlItem {
+        url: note_url.url.clone(),
+        note_id: note_url.note.id.to_string(),
+        note_preview: note_preview(&note_url.note, 80),
+        note_updated_at: note_url.note.updated_at,
+    }
+}
@@ -0,0 +1,150 @@
+use std::io::Write;
+use std::path::Path;
+
+use dirt_core::models::format_byte_count;
+use dirt_core::transcription::{
+    find_whisper_model, InstalledWhisperModel, TranscriptionProvider, WhisperModelStore,
+    DEFAULT_WHISPER_MODEL, WHISPER_MODELS,
+};
+use serde::Serialize;
+
+use crate::cli::WhisperCommands;
+use crate::commands::common::open_database;
+use crate::commands::profile_db::data_root;
+use crate::error::CliError;
+
+/// A catalog model and whether it is downloaded, as printed by `dirt whisper models`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WhisperModelRow {
+    pub name: &'static str,
+    pub size_mb: u32,
+    pub english_only: bool,
+    pub installed: bool,
+    pub selected: bool,
+}
+
+/// Manage the shared Whisper model folder and this profile's provider setting.
+pub async fn run_whisper(command: WhisperCommands, db_path: &Path) -> Result<(), CliError> {
+    let store = WhisperModelStore::in_dir(&data_root());
+    match command {
+        WhisperCommands::Models { json } => {
+            let db = open_database(db_path).await?;
+            let settings = db.load_settings().await?;
+            let selected = (settings.transcription_provider == TranscriptionProvider::Whisper)
+                .then(|| {
+                    settings
+                        .whisper_model
+                        .unwrap_or_else(|| DEFAULT_WHISPER_MODEL.to_string())
+                });
+            let rows = whisper_model_rows(&store.installed()?, selected.as_deref());
+            if json {
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+            } else {
+                println!("Whisper models: {}", store.dir().display());
+                for line in format_whisper_model_lines(&rows) {
+                    println!("{line}");
+                }
+            }
+        }
+        WhisperCommands::Download { model } => {
+            let path = store
+                .download(&model, |received, total| {
+                    eprint!("\r{}", format_download_progress(received, total));
+                    let _ = std::io::stderr().flush();
+                })
+                .await?;
+            eprintln!();
+            println!("Downloaded {}", path.display());
+        }
+        WhisperCommands::Remove { model } => {
+            if store.remove(&model)? {
+                println!("Removed Whisper model {}", model.trim());
+            } else {
+                println!("Whisper model {} is not downloaded", model.trim());
+            }
+        }
+        WhisperCommands::Use { model } => {
+            let name = find_whisper_model(&model)
+                .map(|entry| entry.name)
+                .ok_or_else(|| {
+                    CliError::Core(dirt_core::Error::InvalidInput(format!(
+                        "Unknown Whisper model '{}'",
+                        model.trim()
+                    )))
+                })?;
+            if store.installed_path(name)?.is_none() {
+                return Err(CliError::Config(format!(
+                    "Whisper model {name} is not downloaded. Run `dirt whisper download {name}` first."
+                )));
+            }
+            let db = open_database(db_path).await?;
+            let mut settings = db.load_settings().await?;
+            settings.transcription_provider = TranscriptionProvider::Whisper;
+            settings.whisper_model = Some(name.to_string());
+            db.save_settings(&settings).await?;
+            println!("Voice memos in this profile are now transcribed offline with {name}.");
+        }
+        WhisperCommands::Off => {
+            let db = open_database(db_path).await?;
+            let mut settings = db.load_settings().await?;
+            settings.transcription_provider = TranscriptionProvider::OpenAi;
+            db.save_settings(&settings).await?;
+            println!("Voice memos in this profile are now transcribed with OpenAI.");
+        }
+    }
+    Ok(())
+}
+
+pub fn whisper_model_rows(
+    installed: &[InstalledWhisperModel],
+    selected: Option<&str>,
+) -> Vec<WhisperModelRow> {
+    WHISPER_MODELS
+        .iter()
+        .map(|model| WhisperModelRow {
+            name: model.name,
+            size_mb: model.size_mb,
+            english_only: model.english_only,
+            installed: installed.iter().any(|entry| entry.name == model.name),
+            selected: selected.is_some_and(|name| name.eq_ignore_ascii_case(model.name)),
+        })
+        .collect()
+}
+
+pub fn format_whisper_model_lines(rows: &[WhisperModelRow]) -> Vec<String> {
+    rows.iter()
+        .map(|row| {
+            let marker = if row.selected { "*" } else { " " };
+            let language = if row.english_only {
+                "English"
+            } else {
+                "multilingual"
+            };
+            let status = if row.installed {
+                "downloaded"
+            } else {
+                "not downloaded"
+            };
+            format!(
+                "{marker} {:<16} {:>6} MB  {language:<12}  {status}",
+                row.name, row.size_mb
+            )
+        })
+        .collect()
+}
+
+fn format_download_progress(received: u64, total: Option<u64>) -> String {
+    let received_text = format_byte_count(byte_count(received));
+    match total.filter(|total| *total > 0) {
+        Some(total) => format!(
+            "Downloading {received_text} of {} ({}%)",
+            format_byte_count(byte_count(total)),
+            received.saturating_mul(100) / total
+        ),
+        None => format!("Downloading {received_text}"),
+    }
+}
+
+fn byte_count(bytes: u64) -> i64 {
+    i64::try_from(bytes).unwrap_or(i64::MAX)
+}
@@ -3,9 +3,14 @@
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+use dirt_core::config::{ConfigSource, EnvConfig};
 use serde::{Deserialize, Serialize};
 
+use crate::embedded_bootstrap::embedded_profile;
+
 const CONFIG_FILE_NAME: &str = "cli-config.json";
+const SYNC_TOKEN_ENDPOINT_PATH: &str = "/v1/sync/token";
+const DEFAULT_PROFILE_NAME: &str = "default";
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CliProfilesConfig {
@@ -15,6 +20,9 @@ pub struct CliProfilesConfig {
     pub active_profile: Option<String>,
     #[serde(default)]
     pub profiles: BTreeMap<String, CliProfile>,
+    /// Default `--columns` of `dirt list` and `dirt search`
+    #[serde(default)]
+    pub list_columns: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -61,7 +69,19 @@ pub fn is_http_url(value: &str) -> bool {
 
 impl CliProfilesConfig {
     pub fn load() -> Result<Self, String> {
-        Self::load_from_path(&default_config_path())
+        let config = Self::load_from_path(&default_config_path())?;
+        Ok(config.with_embedded_profile(embedded_profile()?))
+    }
+
+    /// Fill the `default` profile with values from a packager-embedded
+    /// bootstrap manifest; anything the user configured takes precedence.
+    #[must_use]
+    pub fn with_embedded_profile(mut self, embedded: Option<CliProfile>) -> Self {
+        if let Some(embedded) = embedded {
+            self.profile_mut_or_default(DEFAULT_PROFILE_NAME)
+                .fill_missing_from(embedded);
+        }
+        self
     }
 
     pub fn load_from_path(path: &Path) -> Result<Self, String> {
@@ -103,17 +123,26 @@ impl CliProfilesConfig {
     }
 
     pub fn resolve_profile_name(&self, explicit: Option<&str>) -> String {
+        self.resolve_profile_name_from(explicit, &EnvConfig)
+    }
+
+    /// Profile to use: `explicit`, then `DIRT_PROFILE` from `env`, then the
+    /// active profile, then `default`.
+    pub fn resolve_profile_name_from(
+        &self,
+        explicit: Option<&str>,
+        env: &impl ConfigSource,
+    ) -> String {
         if let Some(profile) = normalize_profile_name(explicit) {
             return profile;
         }
-        if let Some(profile) = normalize_profile_name(std::env::var("DIRT_PROFILE").ok().as_deref())
-        {
+        if let Some(profile) = normalize_profile_name(env.var("DIRT_PROFILE").as_deref()) {
             return profile;
         }
         if let Some(profile) = normalize_profile_name(self.active_profile.as_deref()) {
             return profile;
         }
-        "default".to_string()
+        DEFAULT_PROFILE_NAME.to_string()
     }
 
     pub fn profile(&self, name: &str) -> Option<&CliProfile> {
@@ -137,6 +166,18 @@ impl CliProfile {
         normalize_text_option(self.turso_sync_token_endpoint.clone())
     }
 
+    /// Dirt API base URL, derived from the sync token endpoint when not set explicitly.
+    pub fn api_base_url(&self) -> Option<String> {
+        if let Some(base_url) = normalize_text_option(self.dirt_api_base_url.clone()) {
+            return Some(base_url.trim_end_matches('/').to_string());
+        }
+        let endpoint = self.managed_sync_endpoint()?;
+        endpoint
+            .trim_end_matches('/')
+            .strip_suffix(SYNC_TOKEN_ENDPOINT_PATH)
+            .map(str::to_string)
+    }
+
     pub fn supabase_url(&self) -> Option<String> {
         normalize_text_option(self.supabase_url.clone())
     }
@@ -145,6 +186,17 @@ impl CliProfile {
         normalize_text_option(self.supabase_anon_key.clone())
     }
 
+    /// Set every field that is still empty from `fallback`.
+    pub fn fill_missing_from(&mut self, fallback: Self) {
+        self.supabase_url = self.supabase_url().or(fallback.supabase_url);
+        self.supabase_anon_key = self.supabase_anon_key().or(fallback.supabase_anon_key);
+        self.turso_sync_token_endpoint = self
+            .managed_sync_endpoint()
+            .or(fallback.turso_sync_token_endpoint);
+        self.dirt_api_base_url =
+            normalize_text_option(self.dirt_api_base_url.clone()).or(fallback.dirt_api_base_url);
+    }
+
     fn normalize(&mut self) {
         self.supabase_url = normalize_text_option(self.supabase_url.clone());
         self.supabase_anon_key = normalize_text_option(self.supabase_anon_key.clone());
@@ -156,6 +208,8 @@ impl CliProfile {
 
 #[cfg(test)]
 mod tests {
+    use dirt_core::config::MapConfig;
+
     use super::*;
 
     #[test]
@@ -183,6 +237,7 @@ mod tests {
             version: 1,
             active_profile: Some("default".to_string()),
             profiles: BTreeMap::new(),
+            list_columns: None,
         };
         config.profiles.insert(
             "default".to_string(),
@@ -212,14 +267,87 @@ mod tests {
         let _ = std::fs::remove_file(path);
     }
 
+    #[test]
+    fn api_base_url_prefers_explicit_then_sync_endpoint() {
+        let mut profile = CliProfile {
+            turso_sync_token_endpoint: Some("https://api.example.com/v1/sync/token".to_string()),
+            ..CliProfile::default()
+        };
+        assert_eq!(
+            profile.api_base_url().as_deref(),
+            Some("https://api.example.com")
+        );
+
+        profile.dirt_api_base_url = Some("https://dirt.example.com/".to_string());
+        assert_eq!(
+            profile.api_base_url().as_deref(),
+            Some("https://dirt.example.com")
+        );
+
+        let custom = CliProfile {
+            turso_sync_token_endpoint: Some("https://sync.example.com/token".to_string()),
+            ..CliProfile::default()
+        };
+        assert_eq!(custom.api_base_url(), None);
+    }
+
     #[test]
     fn resolve_profile_name_prefers_explicit_then_active() {
         let config = CliProfilesConfig {
             version: 1,
             active_profile: Some("work".to_string()),
             profiles: BTreeMap::new(),
+            list_columns: None,
+        };
+        let no_env = MapConfig::new();
+        assert_eq!(
+            config.resolve_profile_name_from(Some("mobile"), &no_env),
+            "mobile"
+        );
+        assert_eq!(config.resolve_profile_name_from(None, &no_env), "work");
+
+        let env = MapConfig::new().with("DIRT_PROFILE", "ci");
+        assert_eq!(config.resolve_profile_name_from(None, &env), "ci");
+        assert_eq!(
+            config.resolve_profile_name_from(Some("mobile"), &env),
+            "mobile"
+        );
+    }
+
+    #[test]
+    fn embedded_profile_fills_default_without_overriding_user_values() {
+        let mut config = CliProfilesConfig::default();
+        config.profiles.insert(
+            "default".to_string(),
+            CliProfile {
+                supabase_url: Some("https://mine.supabase.co".to_string()),
+                ..CliProfile::default()
+            },
+        );
+        let embedded = CliProfile {
+            supabase_url: Some("https://acme.supabase.co".to_string()),
+            supabase_anon_key: Some("acme-anon".to_string()),
+            turso_sync_token_endpoint: Some("https://api.acme.example/v1/sync/token".to_string()),
+            dirt_api_base_url: Some("https://api.acme.example".to_string()),
         };
-        assert_eq!(config.resolve_profile_name(Some("mobile")), "mobile");
-        assert_eq!(config.resolve_profile_name(None), "work");
+
+        let config = config.with_embedded_profile(Some(embedded.clone()));
+        let profile = config.profile("default").unwrap();
+        assert_eq!(
+            profile.supabase_url(),
+            Some("https://mine.supabase.co".to_string())
+        );
+        assert_eq!(profile.supabase_anon_key(), Some("acme-anon".to_string()));
+        assert_eq!(
+            profile.api_base_url().as_deref(),
+            Some("https://api.acme.example")
+        );
+
+        let fresh = CliProfilesConfig::default().with_embedded_profile(Some(embedded.clone()));
+        assert_eq!(fresh.profile("default"), Some(&embedded));
+        assert!(CliProfilesConfig::default()
+            .with_embedded_profile(None)
+            .profiles
+            .is_empty());
     }
 }
@@ -0,0 +1,35 @@
+//! Bootstrap manifest baked into the binary by packagers.
+//!
+//! Builds made with `DIRT_EMBEDDED_BOOTSTRAP_MANIFEST` pointing at a manifest
+//! file ship preconfigured, so users can sign in without `dirt config init`.
+
+use dirt_core::config::{
+    parse_embedded_bootstrap_manifest, BootstrapConfig, ClientPlatform, ManifestTarget,
+};
+
+use crate::config_profiles::CliProfile;
+
+const EMBEDDED_MANIFEST: &str = include_str!(concat!(
+    env!("OUT_DIR"),
+    "/embedded-bootstrap-manifest.json"
+));
+
+/// Profile values from the embedded manifest, if this build carries one.
+pub fn embedded_profile() -> Result<Option<CliProfile>, String> {
+    parse_embedded_bootstrap_manifest(
+        EMBEDDED_MANIFEST,
+        ManifestTarget::current(ClientPlatform::Cli),
+    )
+    .map(|config| config.map(profile_from_bootstrap))
+    .map_err(|error| format!("Embedded bootstrap manifest is invalid: {error}"))
+}
+
+/// Convert resolved bootstrap values into CLI profile fields.
+pub fn profile_from_bootstrap(config: BootstrapConfig) -> CliProfile {
+    CliProfile {
+        dirt_api_base_url: config.managed_api_base_url(),
+        supabase_url: config.supabase_url,
+        supabase_anon_key: config.supabase_anon_key,
+        turso_sync_token_endpoint: config.turso_sync_token_endpoint,
+    }
+}
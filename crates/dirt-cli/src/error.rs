@@ -12,10 +12,28 @@ pub enum CliError {
     Io(#[from] io::Error),
     #[error(transparent)]
     Serialization(#[from] serde_json::Error),
+    #[error(transparent)]
+    Transcription(#[from] dirt_core::transcription::TranscriptionError),
     #[error("No note content provided")]
     EmptyContent,
     #[error("Edited note content cannot be empty")]
     EmptyEditedContent,
+    #[error("Unknown template '{name}' (available: {available})")]
+    UnknownTemplate { name: String, available: String },
+    #[error("Unknown guide page '{name}' (available: {available})")]
+    UnknownGuidePage { name: String, available: String },
+    #[error("Unknown list column '{name}' (available: {available})")]
+    UnknownListColumn { name: String, available: String },
+    #[error("No list columns given (available: {0})")]
+    EmptyListColumns(String),
+    #[error("Unknown command: {0}; run `dirt help` to list commands")]
+    UnknownCommand(String),
+    #[error("Note {id} is sealed until {until}; run `dirt unseal` after that date")]
+    NoteSealed { id: String, until: String },
+    #[error("Note {0} is marked no-export; pass --include-private to share it")]
+    PrivateNote(String),
+    #[error("{0} needs confirmation; pass --yes to run it non-interactively")]
+    ConfirmationRequired(String),
     #[error("Note ID cannot be empty")]
     EmptyNoteId,
     #[error("Search query cannot be empty")]
@@ -24,6 +42,8 @@ pub enum CliError {
     NoteNotFound(String),
     #[error("{0}")]
     AmbiguousNoteId(String),
+    #[error("Routing rule not found: {0}")]
+    RoutingRuleNotFound(i64),
     #[error("Editor command failed: {0}")]
     EditorFailed(String),
     #[error("Configuration error: {0}")]
@@ -32,8 +52,16 @@ pub enum CliError {
     Auth(String),
     #[error("Managed sync error: {0}")]
     ManagedSync(String),
+    #[error("Dirt API error: {0}")]
+    Api(String),
     #[error(
         "Sync is not configured. Run `dirt config init` + `dirt auth login`, or set TURSO_DATABASE_URL and TURSO_AUTH_TOKEN for advanced env mode."
     )]
     SyncNotConfigured,
+    #[error(
+        "A sync daemon (pid {0}) is already running for this database; see `dirt sync status`"
+    )]
+    SyncDaemonRunning(u32),
+    #[error("Database integrity check failed for {0}; restore it from a backup or sync it again")]
+    DatabaseDamaged(String),
 }
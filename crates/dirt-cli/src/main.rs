@@ -7,16 +7,24 @@ mod bootstrap_manifest;
 mod cli;
 mod commands;
 mod config_profiles;
+mod embedded_bootstrap;
 mod error;
 mod managed_sync;
 #[cfg(test)]
 mod tests;
+mod tui;
 
 use std::env;
+use std::time::Duration;
 
 use clap::{CommandFactory, Parser};
+use dirt_core::logging::LogFormat;
 
-use crate::cli::{Cli, Commands, SyncCommands};
+use crate::cli::{
+    AttachmentCommands, Cli, Commands, ConflictCommands, ConflictFormat, HelpCommands,
+    MaintainCommands, SyncCommands, TaskCommands, TrashCommands,
+};
+use crate::commands::columns::ListLayout;
 use crate::error::CliError;
 
 #[tokio::main]
@@ -27,61 +35,479 @@ async fn main() {
     }
 }
 
-async fn run() -> Result<(), CliError> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive("dirt=info".parse().expect("valid directive")),
-        )
-        .init();
+/// Text logs keep going to stdout; JSON logs go to stderr, away from command
+/// output, and include debug events such as each Dirt API request.
+fn init_tracing(format: LogFormat) {
+    match format {
+        LogFormat::Text => tracing_subscriber::fmt()
+            .with_env_filter(
+                tracing_subscriber::EnvFilter::from_default_env()
+                    .add_directive("dirt=info".parse().expect("valid directive")),
+            )
+            .init(),
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_span_list(false)
+            .with_writer(std::io::stderr)
+            .with_env_filter(
+                tracing_subscriber::EnvFilter::from_default_env()
+                    .add_directive("dirt=debug".parse().expect("valid directive")),
+            )
+            .init(),
+    }
+}
 
+async fn run() -> Result<(), CliError> {
     let cli = Cli::parse();
-    let db_path = commands::common::resolve_db_path(cli.db_path);
+    init_tracing(cli.log_format.unwrap_or_else(LogFormat::from_env));
     let global_profile = config_profiles::normalize_profile_name(cli.profile.as_deref());
     if let Some(profile) = &global_profile {
         env::set_var("DIRT_PROFILE", profile);
     }
+    let db_override = commands::profile_db::db_path_override(cli.db_path);
+    let db_path = commands::profile_db::resolve_db_path(
+        db_override.clone(),
+        command_uses_database(cli.command.as_ref()),
+    )?;
 
     match cli.command {
-        Some(Commands::Add { content }) => commands::add::run_add(&content, &db_path).await?,
-        Some(Commands::List { limit, tag, json }) => {
-            commands::list::run_list(limit, tag.as_deref(), json, &db_path).await?;
+        Some(Commands::Add {
+            content,
+            template,
+            fields,
+        }) => {
+            commands::add::run_add(&content, template.as_deref(), &fields, &db_path).await?;
+        }
+        Some(Commands::List {
+            limit,
+            tag,
+            archived,
+            json,
+            columns,
+            no_truncate,
+        }) => {
+            let layout = ListLayout::resolve(columns.as_deref(), no_truncate)?;
+            commands::list::run_list(limit, tag.as_deref(), archived, json, &layout, &db_path)
+                .await?;
+        }
+        Some(Commands::Search {
+            query,
+            limit,
+            include_attachments,
+            json,
+            columns,
+            no_truncate,
+        }) => {
+            let layout = ListLayout::resolve(columns.as_deref(), no_truncate)?;
+            commands::search::run_search(
+                &query,
+                limit,
+                include_attachments,
+                json,
+                &layout,
+                &db_path,
+            )
+            .await?;
+        }
+        Some(Commands::Urls { limit, tag, json }) => {
+            commands::urls::run_urls(limit, tag.as_deref(), json, &db_path).await?;
         }
-        Some(Commands::Search { query, limit, json }) => {
-            commands::search::run_search(&query, limit, json, &db_path).await?;
+        Some(Commands::Links { id, json }) => {
+            commands::links::run_links(&id, json, &db_path).await?;
         }
+        Some(Commands::Today) => commands::daily::run_daily(0, &db_path).await?,
+        Some(Commands::Yesterday) => commands::daily::run_daily(1, &db_path).await?,
         Some(Commands::Edit { id }) => commands::edit::run_edit(&id, &db_path).await?,
-        Some(Commands::Delete { id }) => commands::delete::run_delete(&id, &db_path).await?,
-        Some(Commands::Export { format, output }) => {
-            commands::export::run_export(format, output.as_deref(), &db_path).await?;
+        Some(Commands::Translate { id, to, append }) => {
+            commands::translate::run_translate(&id, &to, append, &db_path).await?;
+        }
+        Some(Commands::Transcribe {
+            attachment_id,
+            append,
+            provider,
+        }) => {
+            commands::transcribe::run_transcribe(
+                &attachment_id,
+                append,
+                provider.as_deref(),
+                &db_path,
+                global_profile.as_deref(),
+            )
+            .await?;
+        }
+        Some(Commands::Whisper { command }) => {
+            commands::whisper::run_whisper(command, &db_path).await?;
+        }
+        Some(Commands::Summarize { id, json }) => {
+            commands::summarize::run_summarize(&id, json, &db_path).await?;
+        }
+        Some(Commands::Delete {
+            id,
+            tag,
+            yes,
+            dry_run,
+        }) => match (id, tag) {
+            (_, Some(tag)) => {
+                let options = commands::confirm::ConfirmOptions {
+                    yes,
+                    force: cli.force,
+                    dry_run,
+                };
+                commands::delete::run_delete_by_tag(&tag, options, &db_path).await?;
+            }
+            (Some(id), None) => commands::delete::run_delete(&id, &db_path).await?,
+            (None, None) => unreachable!("clap requires an id or --tag"),
+        },
+        Some(Commands::Tasks { command }) => match command {
+            TaskCommands::List { json } => {
+                commands::tasks::run_tasks_list(json, &db_path).await?;
+            }
+        },
+        Some(Commands::Trash { command }) => match command {
+            TrashCommands::List { limit, json } => {
+                commands::trash::run_trash_list(limit, json, &db_path).await?;
+            }
+            TrashCommands::Restore { id } => {
+                commands::trash::run_trash_restore(&id, &db_path).await?;
+            }
+            TrashCommands::Empty {
+                older_than,
+                yes,
+                dry_run,
+            } => {
+                let options = commands::confirm::ConfirmOptions {
+                    yes,
+                    force: cli.force,
+                    dry_run,
+                };
+                commands::trash::run_trash_empty(
+                    older_than,
+                    options,
+                    &db_path,
+                    global_profile.as_deref(),
+                )
+                .await?;
+            }
+        },
+        Some(Commands::Seal { id, until }) => {
+            commands::seal::run_seal(&id, &until, &db_path).await?;
+        }
+        Some(Commands::Unseal { id }) => commands::seal::run_unseal(&id, &db_path).await?,
+        Some(Commands::Pin { id, clear }) => commands::pin::run_pin(&id, clear, &db_path).await?,
+        Some(Commands::Archive { id, clear }) => {
+            commands::pin::run_archive(&id, clear, &db_path).await?;
+        }
+        Some(Commands::History { id, json }) => {
+            commands::history::run_history(&id, json, &db_path).await?;
+        }
+        Some(Commands::Restore { id, revision }) => {
+            commands::history::run_restore(&id, revision, &db_path).await?;
+        }
+        Some(Commands::NoExport { id, tag, clear }) => match (id, tag) {
+            (_, Some(tag)) => commands::no_export::run_no_export_tag(&tag, clear, &db_path).await?,
+            (Some(id), None) => commands::no_export::run_no_export(&id, clear, &db_path).await?,
+            (None, None) => unreachable!("clap requires an id or --tag"),
+        },
+        Some(Commands::Export {
+            format,
+            output,
+            include_private,
+            incremental,
+            media_dir,
+        }) => match output {
+            Some(output_dir) if incremental => {
+                commands::export::run_incremental_export(
+                    format,
+                    &output_dir,
+                    include_private,
+                    &db_path,
+                )
+                .await?;
+            }
+            output => {
+                commands::export::run_export(
+                    format,
+                    output.as_deref(),
+                    include_private,
+                    media_dir.as_deref(),
+                    &db_path,
+                )
+                .await?;
+            }
+        },
+        Some(Commands::Import {
+            from,
+            path,
+            dry_run,
+            no_media,
+        }) => {
+            let options = commands::import::ImportOptions { dry_run, no_media };
+            commands::import::run_import(from, &path, options, &db_path, global_profile.as_deref())
+                .await?;
+        }
+        Some(Commands::Share {
+            id,
+            expires,
+            list,
+            revoke,
+            include_private,
+            json,
+        }) => {
+            let action = commands::share::ShareAction::from_flags(
+                expires.as_deref(),
+                include_private,
+                list,
+                revoke,
+            )?;
+            commands::share::run_share(&id, action, json, &db_path, global_profile.as_deref())
+                .await?;
         }
+        Some(Commands::Attach { file, new: _, json }) => {
+            commands::attach::run_attach_new(&file, json, &db_path, global_profile.as_deref())
+                .await?;
+        }
+        Some(Commands::Attachments {
+            command:
+                AttachmentCommands::Pull {
+                    output,
+                    tag,
+                    since,
+                    until,
+                    jobs,
+                    json,
+                },
+        }) => {
+            let options = commands::attachments::PullOptions {
+                output: &output,
+                tag: tag.as_deref(),
+                since: since.as_deref(),
+                until: until.as_deref(),
+                jobs: usize::from(jobs),
+                json,
+            };
+            commands::attachments::run_attachments_pull(
+                options,
+                &db_path,
+                global_profile.as_deref(),
+            )
+            .await?;
+        }
+        Some(Commands::Cache { command }) => {
+            commands::cache::run_cache(command, &db_path).await?;
+        }
+        Some(Commands::Digest {
+            week: _,
+            save,
+            email,
+            output,
+            json,
+            include_private,
+        }) => {
+            let delivery = commands::digest::DigestDelivery {
+                output: output.as_deref(),
+                save,
+                email,
+                json,
+                include_private,
+            };
+            commands::digest::run_digest(delivery, &db_path, global_profile.as_deref()).await?;
+        }
+        Some(Commands::Journal {
+            from,
+            to,
+            format,
+            output,
+            include_private,
+        }) => {
+            commands::journal::run_journal(
+                &from,
+                &to,
+                format,
+                output.as_deref(),
+                include_private,
+                &db_path,
+            )
+            .await?;
+        }
+        Some(Commands::Maintain {
+            command:
+                Some(MaintainCommands::Attachments {
+                    repair,
+                    purge,
+                    dry_run,
+                    yes,
+                    json,
+                }),
+            ..
+        }) => {
+            let options = commands::maintain::AttachmentAuditOptions {
+                repair,
+                purge,
+                json,
+                confirm: commands::confirm::ConfirmOptions {
+                    yes,
+                    force: cli.force,
+                    dry_run,
+                },
+            };
+            commands::maintain::run_attachment_audit(options, &db_path, global_profile.as_deref())
+                .await?;
+        }
+        Some(Commands::Maintain {
+            command: Some(MaintainCommands::Db { compact, json }),
+            ..
+        }) => {
+            commands::maintain::run_db_maintenance(compact, json, &db_path).await?;
+        }
+        Some(Commands::Maintain {
+            command: None,
+            analyze,
+            usage,
+            purge_deleted,
+            note_limit,
+            size_limit_mb,
+            history_limit,
+            yes,
+        }) => {
+            if note_limit.is_some() || size_limit_mb.is_some() {
+                commands::maintain::run_set_quota_limits(note_limit, size_limit_mb, &db_path)
+                    .await?;
+            }
+            if let Some(limit) = history_limit {
+                commands::maintain::run_set_history_limit(limit, &db_path).await?;
+            }
+            if purge_deleted {
+                let options = commands::confirm::ConfirmOptions {
+                    yes,
+                    force: cli.force,
+                    dry_run: false,
+                };
+                commands::maintain::run_purge_deleted(options, &db_path).await?;
+            }
+            if analyze {
+                commands::maintain::run_analyze(&db_path).await?;
+            }
+            if usage {
+                commands::maintain::run_usage(&db_path).await?;
+            }
+        }
+        Some(Commands::Stats { json }) => commands::stats::run_stats(json, &db_path).await?,
         Some(Commands::Completions { shell, output }) => {
             commands::completions::run_completions(shell, output.as_deref())?;
         }
-        Some(Commands::Sync { command }) => match command {
-            Some(SyncCommands::Conflicts { limit, json }) => {
-                commands::sync::run_sync_conflicts(limit, json, &db_path).await?;
+        Some(Commands::Sync {
+            command,
+            watch,
+            interval,
+        }) => match command {
+            Some(SyncCommands::Conflicts {
+                command: Some(ConflictCommands::Resolve { id, strategy }),
+                ..
+            }) => {
+                commands::sync::run_resolve_conflict(id, strategy, &db_path).await?;
+            }
+            Some(SyncCommands::Conflicts {
+                limit,
+                since,
+                format,
+                json,
+                output,
+                command: None,
+            }) => {
+                let format = if json {
+                    ConflictFormat::Json
+                } else {
+                    format.unwrap_or(ConflictFormat::Text)
+                };
+                commands::sync::run_sync_conflicts(
+                    limit,
+                    since.as_deref(),
+                    format,
+                    output.as_deref(),
+                    &db_path,
+                )
+                .await?;
+            }
+            Some(SyncCommands::Daemon { interval }) => {
+                commands::sync::run_sync_daemon(Duration::from_secs(interval), &db_path).await?;
+            }
+            Some(SyncCommands::Status { json }) => {
+                commands::sync::run_sync_status(json, &db_path)?;
+            }
+            Some(SyncCommands::History { limit, json }) => {
+                commands::sync::run_sync_history(limit, json, &db_path).await?;
+            }
+            Some(SyncCommands::ExcludeTag { tag, clear }) => {
+                commands::sync::run_sync_exclude_tag(&tag, clear, &db_path).await?;
+            }
+            None if watch => {
+                commands::sync::run_sync_watch(Duration::from_secs(interval), &db_path).await?;
             }
             None => commands::sync::run_sync(&db_path).await?,
         },
         Some(Commands::Config { command }) => {
-            commands::config::run_config(command, global_profile.as_deref()).await?;
+            commands::config::run_config(
+                command,
+                global_profile.as_deref(),
+                db_override.as_deref(),
+            )
+            .await?;
         }
         Some(Commands::Auth { command }) => {
             commands::auth_cmd::run_auth(command, global_profile.as_deref()).await?;
         }
-        Some(Commands::Tui) => {
-            println!("Opening TUI...");
+        Some(Commands::Tokens { command }) => {
+            commands::tokens::run_tokens(command, global_profile.as_deref()).await?;
+        }
+        Some(Commands::Tag { command }) => {
+            commands::tag::run_tag(command, &db_path).await?;
+        }
+        Some(Commands::Rules { command }) => {
+            commands::rules::run_rules(command, &db_path).await?;
         }
+        Some(Commands::Encryption { command }) => {
+            commands::encryption::run_encryption(command, &db_path).await?;
+        }
+        Some(Commands::Changelog { json }) => commands::changelog::run_changelog(json)?,
+        Some(Commands::Tui) => tui::run_tui(&db_path).await?,
+        Some(Commands::Help { command }) => match command {
+            Some(HelpCommands::Guide {
+                page,
+                list,
+                no_pager,
+            }) => commands::help::run_help_guide(page.as_deref(), list, no_pager)?,
+            Some(HelpCommands::Command(path)) => commands::help::run_help_command(&path)?,
+            None => {
+                Cli::command().print_long_help().map_err(CliError::Io)?;
+                println!();
+            }
+        },
         None => {
             if cli.note.is_empty() {
                 Cli::command().print_help().map_err(CliError::Io)?;
                 println!();
             } else {
-                commands::add::run_add(&cli.note, &db_path).await?;
+                commands::add::run_add(&cli.note, None, &[], &db_path).await?;
             }
         }
     }
 
     Ok(())
 }
+
+/// Whether `command` reads or writes notes; profile, auth, and token
+/// management never open the local database.
+const fn command_uses_database(command: Option<&Commands>) -> bool {
+    !matches!(
+        command,
+        Some(
+            Commands::Config { .. }
+                | Commands::Auth { .. }
+                | Commands::Tokens { .. }
+                | Commands::Completions { .. }
+                | Commands::Changelog { .. }
+                | Commands::Help { .. }
+        )
+    )
+}
@@ -1,23 +1,78 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use dirt_core::config::MapConfig;
 use dirt_core::db::{Database, LibSqlNoteRepository, NoteRepository};
 use dirt_core::export::render_markdown_export;
+use dirt_core::models::{AccessScope, NoteSource, SyncHistoryEntry, MIN_SHORT_ID_LEN};
+use dirt_core::util::editor::default_editor;
 use dirt_core::{Note, SyncConflict};
 use tokio::time::sleep;
 
-use crate::cli::{CompletionShell, ExportFormat};
+use crate::cli::{
+    CompletionShell, ConflictFormat, ConflictStrategy, ExportFormat, ImportSource, JournalFormat,
+};
+use crate::commands::add::{render_template, run_add};
+use crate::commands::attach::run_attach_new;
+use crate::commands::attachments::{
+    format_pull_summary, is_already_pulled, plan_downloads, run_attachments_pull, PullOptions,
+    PullSummary,
+};
+use crate::commands::changelog::{format_changelog, format_update_notice};
+use crate::commands::columns::{
+    format_list_columns, format_note_lines, parse_list_columns, ListColumn, ListLayout,
+    DEFAULT_LIST_COLUMNS,
+};
 use crate::commands::common::{
-    default_editor, format_relative_time, format_sync_conflict_lines, format_sync_timestamp,
-    list_notes, normalize_content, normalize_note_identifier, normalize_search_query, note_preview,
-    open_database, resolve_note_for_edit, search_notes,
+    format_migration_progress, format_relative_time, format_sync_conflict_lines,
+    format_sync_timestamp, list_notes, normalize_content, normalize_note_identifier,
+    normalize_search_query, note_preview, open_database, resolve_note_for_edit, search_notes,
 };
 use crate::commands::completions::run_completions;
-use crate::commands::config::{normalize_bootstrap_url, resolve_bootstrap_url};
-use crate::commands::delete::run_delete;
-use crate::commands::export::run_export;
-use crate::commands::sync::run_sync;
+use crate::commands::config::{
+    format_profile_db_lines, normalize_bootstrap_url, resolve_bootstrap_url,
+};
+use crate::commands::confirm::{decide, ConfirmOptions, Confirmation, BULK_CONFIRM_LIMIT};
+use crate::commands::daily::daily_note_date;
+use crate::commands::delete::{run_delete, run_delete_by_tag};
+use crate::commands::digest::{run_digest, DigestDelivery};
+use crate::commands::encryption::{run_enable, run_unlock};
+use crate::commands::export::{
+    pulled_media_paths, run_export, run_incremental_export, IncrementalExportReport,
+};
+use crate::commands::help::{find_guide_page, format_guide_index, render_markdown, GUIDE_PAGES};
+use crate::commands::history::{format_revision_lines, run_restore};
+use crate::commands::import::{run_import, ImportOptions};
+use crate::commands::journal::run_journal;
+use crate::commands::links::format_backlink_lines;
+use crate::commands::maintain::{
+    format_analyze_lines, format_attachment_audit_lines, format_db_maintenance_lines,
+    format_usage_lines, run_analyze, run_db_maintenance, run_purge_deleted, run_set_history_limit,
+    run_set_quota_limits, run_usage,
+};
+use crate::commands::no_export::{run_no_export, run_no_export_tag};
+use crate::commands::pin::{run_archive, run_pin};
+use crate::commands::profile_db::{choose_db_path, profile_db_path_in};
+use crate::commands::rules::{format_rule_lines, run_rules};
+use crate::commands::seal::{run_seal, run_unseal};
+use crate::commands::search::{format_search_lines, search_hit_to_item};
+use crate::commands::share::{format_share_lines, run_share, share_status, ShareAction};
+use crate::commands::summarize::note_summary_to_item;
+use crate::commands::sync::{
+    format_sync_history_lines, parse_conflict_since, render_sync_conflicts, run_resolve_conflict,
+    run_sync, run_sync_conflicts, sync_history_to_item,
+};
+use crate::commands::tag::{format_tag_lines, run_tag, TagItem};
+use crate::commands::tasks::{format_task_lines, open_task_items, run_tasks_list};
+use crate::commands::tokens::{format_token_lines, token_status, TokenItem};
+use crate::commands::translate::run_translate;
+use crate::commands::trash::{
+    format_trash_lines, resolve_trashed_note, run_trash_empty, run_trash_list, run_trash_restore,
+};
+use crate::commands::urls::{format_url_lines, note_url_to_item};
+use crate::config_profiles::{CliProfile, CliProfilesConfig};
 use crate::error::CliError;
 
 #[test]
@@ -48,6 +103,7 @@ fn normalize_bootstrap_url_requires_http_scheme() {
 #[test]
 fn resolve_bootstrap_url_prefers_explicit_manifest_url() {
     let resolved = resolve_bootstrap_url(
+        &MapConfig::new(),
         Some("https://api.example.com/v1/bootstrap".to_string()),
         Some("https://ignored.example.com".to_string()),
         Some("https://also-ignored.example.com".to_string()),
@@ -61,8 +117,35 @@ fn resolve_bootstrap_url_prefers_explicit_manifest_url() {
 
 #[test]
 fn resolve_bootstrap_url_derives_from_api_base() {
-    let resolved =
-        resolve_bootstrap_url(None, Some("https://api.example.com/".to_string()), None).unwrap();
+    let resolved = resolve_bootstrap_url(
+        &MapConfig::new(),
+        None,
+        Some("https://api.example.com/".to_string()),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        resolved.as_deref(),
+        Some("https://api.example.com/v1/bootstrap")
+    );
+}
+
+#[test]
+fn resolve_bootstrap_url_reads_injected_env() {
+    let env = MapConfig::new()
+        .with(
+            "DIRT_BOOTSTRAP_URL",
+            "https://env.example.com/v1/bootstrap/",
+        )
+        .with("DIRT_API_BASE_URL", "https://ignored.example.com");
+    let resolved = resolve_bootstrap_url(&env, None, None, None).unwrap();
+    assert_eq!(
+        resolved.as_deref(),
+        Some("https://env.example.com/v1/bootstrap")
+    );
+
+    let api_only = MapConfig::new().with("DIRT_API_BASE_URL", "https://api.example.com");
+    let resolved = resolve_bootstrap_url(&api_only, None, None, None).unwrap();
     assert_eq!(
         resolved.as_deref(),
         Some("https://api.example.com/v1/bootstrap")
@@ -84,6 +167,50 @@ fn note_preview_truncates_with_ellipsis() {
     assert_eq!(preview, "This is a very lo...");
 }
 
+#[test]
+fn parse_list_columns_accepts_known_names_only() {
+    assert_eq!(
+        parse_list_columns(" ID, preview ,,Updated").unwrap(),
+        vec![ListColumn::Id, ListColumn::Preview, ListColumn::Updated]
+    );
+    assert_eq!(
+        format_list_columns(DEFAULT_LIST_COLUMNS),
+        "id,preview,updated,tags"
+    );
+    assert!(matches!(
+        parse_list_columns("id,title"),
+        Err(CliError::UnknownListColumn { name, .. }) if name == "title"
+    ));
+    assert!(matches!(
+        parse_list_columns(" , "),
+        Err(CliError::EmptyListColumns(_))
+    ));
+}
+
+#[test]
+fn format_note_lines_fits_flexible_columns_to_the_terminal() {
+    let long = "A long first line that describes the whole plan for the quarter #work";
+    let notes = vec![Note::new(long), Note::new("Short #home #errands")];
+    let layout = |width: Option<usize>, truncate: bool| ListLayout {
+        columns: vec![ListColumn::Id, ListColumn::Preview, ListColumn::Tags],
+        width,
+        truncate,
+    };
+
+    let fitted = format_note_lines(&notes, &layout(Some(60), true), MIN_SHORT_ID_LEN);
+    assert!(fitted.iter().all(|line| line.chars().count() <= 60));
+    assert!(fitted[0].contains("..."));
+    assert!(fitted[1].ends_with("#errands #home"));
+
+    let piped = format_note_lines(&notes, &layout(None, true), MIN_SHORT_ID_LEN);
+    assert!(piped[0].contains(&long[..37]));
+    assert!(!piped[0].contains(&long[..41]));
+
+    let full = format_note_lines(&notes, &layout(Some(60), false), MIN_SHORT_ID_LEN);
+    assert!(full[0].starts_with(&notes[0].id.to_string()));
+    assert!(full[0].contains(long));
+}
+
 #[test]
 fn format_sync_timestamp_returns_utc_label() {
     assert_eq!(format_sync_timestamp(0), "1970-01-01 00:00:00 UTC");
@@ -98,287 +225,2518 @@ fn format_sync_conflict_lines_include_key_fields() {
         incoming_updated_at: 100,
         resolved_at: 300,
         strategy: "lww".to_string(),
+        local_content: None,
+        incoming_content: None,
     }];
 
-    let rendered = format_sync_conflict_lines(&conflicts);
+    let rendered = format_sync_conflict_lines(&conflicts, MIN_SHORT_ID_LEN);
     assert_eq!(rendered.len(), 1);
+    assert!(rendered[0].contains("#1 "));
     assert!(rendered[0].contains("lww"));
-    assert!(rendered[0].contains("note=11111111-1111-7111-8111-111111111111"));
+    assert!(rendered[0].contains("note=11111111 "));
     assert!(rendered[0].contains("local=200"));
     assert!(rendered[0].contains("incoming=100"));
 }
 
-#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
-#[tokio::test(flavor = "current_thread")]
-async fn list_notes_respects_limit_and_tag_filter() {
-    let db_path = unique_test_db_path();
-    {
-        let db = Database::open(&db_path).await.unwrap();
-        let repo = LibSqlNoteRepository::new(db.connection());
-
-        repo.create("First #work").await.unwrap();
-        sleep(Duration::from_millis(2)).await;
-        repo.create("Second #personal").await.unwrap();
-        sleep(Duration::from_millis(2)).await;
-        repo.create("Third #work").await.unwrap();
-    }
-
-    let recent = list_notes(2, None, &db_path).await.unwrap();
-    assert_eq!(recent.len(), 2);
-    assert_eq!(recent[0].content, "Third #work");
-    assert_eq!(recent[1].content, "Second #personal");
-
-    let work_only = list_notes(10, Some("work"), &db_path).await.unwrap();
-    assert_eq!(work_only.len(), 2);
-    assert!(work_only.iter().all(|note| note.content.contains("#work")));
+#[test]
+fn render_sync_conflicts_jsonl_includes_both_versions() {
+    let conflicts = vec![SyncConflict {
+        id: 4,
+        note_id: "11111111-1111-7111-8111-111111111111".to_string(),
+        local_updated_at: 200,
+        incoming_updated_at: 100,
+        resolved_at: 300,
+        strategy: "lww".to_string(),
+        local_content: Some("kept\nversion".to_string()),
+        incoming_content: Some("stale".to_string()),
+    }];
 
-    cleanup_db_files(&db_path);
+    let rendered =
+        render_sync_conflicts(&conflicts, ConflictFormat::Jsonl, MIN_SHORT_ID_LEN).unwrap();
+    let lines = rendered.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1);
+    let item: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(item["kept"], "local");
+    assert_eq!(item["local_content"], "kept\nversion");
+    assert_eq!(item["incoming_content"], "stale");
+    assert_eq!(item["resolved_at"], 300);
+
+    assert!(
+        render_sync_conflicts(&[], ConflictFormat::Jsonl, MIN_SHORT_ID_LEN)
+            .unwrap()
+            .is_empty()
+    );
 }
 
-#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
-#[tokio::test(flavor = "current_thread")]
-async fn search_notes_finds_matches_with_limit() {
-    let db_path = unique_test_db_path();
-    {
-        let db = Database::open(&db_path).await.unwrap();
-        let repo = LibSqlNoteRepository::new(db.connection());
-
-        repo.create("Milk and eggs").await.unwrap();
-        sleep(Duration::from_millis(2)).await;
-        repo.create("Milkshake recipe").await.unwrap();
-        sleep(Duration::from_millis(2)).await;
-        repo.create("Unrelated note").await.unwrap();
-    }
+#[test]
+fn parse_conflict_since_accepts_dates_and_timestamps() {
+    assert_eq!(parse_conflict_since("1970-01-02").unwrap(), 86_400_000);
+    assert_eq!(parse_conflict_since("1970-01-01T00:00:01Z").unwrap(), 1_000);
+    assert_eq!(parse_conflict_since("1234").unwrap(), 1_234);
+    assert!(parse_conflict_since("yesterday").is_err());
+}
 
-    let matches = search_notes("milk", 1, &db_path).await.unwrap();
-    assert_eq!(matches.len(), 1);
-    assert!(matches[0].content.to_lowercase().contains("milk"));
+#[test]
+fn sync_conflicts_format_conflicts_with_json_flag() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, SyncCommands};
+
+    let cli = Cli::try_parse_from([
+        "dirt",
+        "sync",
+        "conflicts",
+        "--format",
+        "jsonl",
+        "--output",
+        "log.jsonl",
+        "--since",
+        "2026-01-01",
+    ])
+    .unwrap();
+    let Some(Commands::Sync {
+        command:
+            Some(SyncCommands::Conflicts {
+                format,
+                output,
+                since,
+                ..
+            }),
+        ..
+    }) = cli.command
+    else {
+        panic!("expected sync conflicts");
+    };
+    assert_eq!(format, Some(ConflictFormat::Jsonl));
+    assert_eq!(output, Some(PathBuf::from("log.jsonl")));
+    assert_eq!(since.as_deref(), Some("2026-01-01"));
 
-    cleanup_db_files(&db_path);
+    assert!(
+        Cli::try_parse_from(["dirt", "sync", "conflicts", "--json", "--format", "jsonl"]).is_err()
+    );
 }
 
 #[test]
-fn normalize_search_query_rejects_empty() {
-    assert!(normalize_search_query(" \n\t ").is_err());
+fn migration_progress_bar_shows_step_and_rows() {
+    use dirt_core::db::MigrationProgress;
+
+    let line = format_migration_progress(&MigrationProgress {
+        version: 12,
+        completed: 1,
+        total: 4,
+        rows: Some((50, 100)),
+    });
     assert_eq!(
-        normalize_search_query("  exact phrase  ").unwrap(),
-        "exact phrase"
+        line,
+        "Migrating database [########----------------]  37% Indexing links in notes (50/100 notes)"
     );
+
+    let done = format_migration_progress(&MigrationProgress {
+        version: 15,
+        completed: 4,
+        total: 4,
+        rows: Some((100, 100)),
+    });
+    assert!(done.contains("[########################] 100% Database is up to date"));
+    assert!(!done.contains("notes)"));
 }
 
 #[test]
-fn normalize_note_identifier_rejects_empty() {
+fn help_subcommand_routes_guide_and_command_help() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, HelpCommands};
+
+    let cli = Cli::try_parse_from(["dirt", "help", "guide", "sync", "--no-pager"]).unwrap();
+    let Some(Commands::Help {
+        command:
+            Some(HelpCommands::Guide {
+                page,
+                list,
+                no_pager,
+            }),
+    }) = cli.command
+    else {
+        panic!("expected help guide");
+    };
+    assert_eq!(page.as_deref(), Some("sync"));
+    assert!(!list);
+    assert!(no_pager);
+
+    let cli = Cli::try_parse_from(["dirt", "help", "sync", "conflicts"]).unwrap();
+    let Some(Commands::Help {
+        command: Some(HelpCommands::Command(path)),
+    }) = cli.command
+    else {
+        panic!("expected command help");
+    };
+    assert_eq!(path, vec!["sync".to_string(), "conflicts".to_string()]);
+
     assert!(matches!(
-        normalize_note_identifier(" \n "),
-        Err(CliError::EmptyNoteId)
+        Cli::try_parse_from(["dirt", "help"]).unwrap().command,
+        Some(Commands::Help { command: None })
     ));
+    assert!(Cli::try_parse_from(["dirt", "help", "guide", "sync", "--list"]).is_err());
+}
+
+#[test]
+fn guide_pages_resolve_by_name_or_prefix() {
+    assert_eq!(find_guide_page("SYNC").unwrap().name, "sync");
+    assert_eq!(find_guide_page("prof").unwrap().name, "profiles");
+    let error = find_guide_page("billing").unwrap_err().to_string();
+    assert!(error.contains("capture, sync, profiles"));
+
+    let index = format_guide_index();
+    assert_eq!(index.len(), GUIDE_PAGES.len());
+    assert!(index[0].starts_with(" 1. capture"));
+}
+
+#[test]
+fn guide_renders_plain_text_without_markup() {
+    let rendered = render_markdown(
+        "# Title\n\nRun `dirt sync` now.\n\n```\ndirt sync --watch\n```\n- item\n",
+        false,
+    );
+
     assert_eq!(
-        normalize_note_identifier("  abc123  ").unwrap(),
-        "abc123".to_string()
+        rendered,
+        "TITLE\n=====\n\nRun dirt sync now.\n\n    dirt sync --watch\n  * item\n"
     );
+    assert!(!render_markdown(GUIDE_PAGES[0].markdown, false).contains('\x1b'));
+    assert!(render_markdown("`code`", true).contains("\x1b[36mcode"));
+}
+
+#[test]
+fn guide_only_mentions_existing_commands() {
+    use clap::CommandFactory;
+
+    let command = crate::cli::Cli::command();
+    for page in GUIDE_PAGES {
+        for mention in page.markdown.split("dirt ").skip(1) {
+            let name: String = mention
+                .chars()
+                .take_while(|ch| ch.is_ascii_lowercase() || *ch == '-')
+                .collect();
+            if name.is_empty() || name.starts_with('-') {
+                continue;
+            }
+            assert!(
+                command.find_subcommand(&name).is_some(),
+                "guide page '{}' mentions unknown command `dirt {name}`",
+                page.name
+            );
+        }
+    }
 }
 
-#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
 #[tokio::test(flavor = "current_thread")]
-async fn resolve_note_for_edit_supports_exact_and_prefix_id() {
-    let db_path = unique_test_db_path();
-    let db = Database::open(&db_path).await.unwrap();
-    let repo = LibSqlNoteRepository::new(db.connection());
+async fn attach_requires_new_and_rejects_missing_files() {
+    use clap::Parser;
 
-    let note_a = Note {
-        id: "11111111-1111-7111-8111-111111111111".parse().unwrap(),
-        content: "Note A".to_string(),
-        created_at: 1000,
-        updated_at: 1000,
-        is_deleted: false,
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["dirt", "attach", "--new", "receipt.pdf"]).unwrap();
+    let Some(Commands::Attach { file, new, json }) = cli.command else {
+        panic!("expected attach");
     };
-    let note_b = Note {
-        id: "11111111-1111-7111-8111-222222222222".parse().unwrap(),
-        content: "Note B".to_string(),
-        created_at: 1001,
-        updated_at: 1001,
-        is_deleted: false,
+    assert_eq!(file, PathBuf::from("receipt.pdf"));
+    assert!(new);
+    assert!(!json);
+    assert!(Cli::try_parse_from(["dirt", "attach", "receipt.pdf"]).is_err());
+
+    let missing = std::env::temp_dir().join("dirt-cli-attach-missing.pdf");
+    let error = run_attach_new(&missing, false, &unique_test_db_path(), None)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("is not a file"));
+}
+
+#[test]
+fn summarize_parses_and_serializes_method() {
+    use clap::Parser;
+    use dirt_core::models::{NoteSummary, SummaryMethod};
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["dirt", "summarize", "0190abcd", "--json"]).unwrap();
+    let Some(Commands::Summarize { id, json }) = cli.command else {
+        panic!("expected summarize");
     };
-    repo.create_with_note(&note_a).await.unwrap();
-    repo.create_with_note(&note_b).await.unwrap();
-    drop(db);
+    assert_eq!(id, "0190abcd");
+    assert!(json);
+
+    let note = Note::new("Long note");
+    let item = note_summary_to_item(&NoteSummary {
+        note_id: note.id,
+        summary: "Gist".to_string(),
+        method: SummaryMethod::Extractive,
+        note_updated_at: note.updated_at,
+        created_at: 42,
+    });
+    let value = serde_json::to_value(&item).unwrap();
+    assert_eq!(value["note_id"], note.id.to_string());
+    assert_eq!(value["summary"], "Gist");
+    assert_eq!(value["method"], "extractive");
+}
 
-    let service = open_database(&db_path).await.unwrap();
+#[tokio::test(flavor = "current_thread")]
+async fn translate_parses_target_and_rejects_unknown_languages() {
+    use clap::Parser;
 
-    let by_exact = resolve_note_for_edit("11111111-1111-7111-8111-111111111111", &service)
-        .await
-        .unwrap();
-    assert_eq!(by_exact.content, "Note A");
+    use crate::cli::{Cli, Commands};
 
-    let by_prefix = resolve_note_for_edit("11111111-1111-7111-8111-2", &service)
+    let cli = Cli::try_parse_from(["dirt", "translate", "0190abcd", "--to", "ja"]).unwrap();
+    let Some(Commands::Translate { id, to, append }) = cli.command else {
+        panic!("expected translate");
+    };
+    assert_eq!(id, "0190abcd");
+    assert_eq!(to, "ja");
+    assert!(!append);
+    assert!(Cli::try_parse_from(["dirt", "translate", "0190abcd"]).is_err());
+
+    let error = run_translate("0190abcd", "Japanese", false, &unique_test_db_path())
         .await
-        .unwrap();
-    assert_eq!(by_prefix.content, "Note B");
+        .unwrap_err();
+    assert!(error.to_string().contains("not a language code"));
+}
 
-    cleanup_db_files(&db_path);
+#[test]
+fn transcribe_parses_attachment_id_and_resolves_audio_attachments() {
+    use clap::Parser;
+    use dirt_core::models::Attachment;
+    use dirt_core::NoteId;
+
+    use crate::cli::{Cli, Commands};
+    use crate::commands::transcribe::resolve_audio_attachment;
+
+    let cli = Cli::try_parse_from(["dirt", "transcribe", "0190abcd", "--append"]).unwrap();
+    let Some(Commands::Transcribe {
+        attachment_id,
+        append,
+        provider,
+    }) = cli.command
+    else {
+        panic!("expected transcribe");
+    };
+    assert_eq!(attachment_id, "0190abcd");
+    assert!(append);
+    assert_eq!(provider, None);
+    assert!(Cli::try_parse_from(["dirt", "transcribe"]).is_err());
+
+    let note_id = NoteId::new();
+    let memo = Attachment::new(note_id, "memo.wav", "audio/wav", 10, "notes/a/memo.wav").unwrap();
+    let photo =
+        Attachment::new(note_id, "photo.png", "image/png", 10, "notes/a/photo.png").unwrap();
+    let attachments = vec![memo.clone(), photo.clone()];
+
+    let resolved = resolve_audio_attachment(&attachments, &memo.id.as_str()).unwrap();
+    assert_eq!(resolved.id, memo.id);
+    let error = resolve_audio_attachment(&attachments, &photo.id.as_str()).unwrap_err();
+    assert!(error.to_string().contains("not audio"));
+    assert!(resolve_audio_attachment(&attachments, "zzzz").is_err());
+    assert!(resolve_audio_attachment(&attachments, " ").is_err());
+
+    let memo_id = memo.id.as_str();
+    let photo_id = photo.id.as_str();
+    let shared = memo_id
+        .chars()
+        .zip(photo_id.chars())
+        .take_while(|(left, right)| left == right)
+        .count();
+    if shared > 0 {
+        let error = resolve_audio_attachment(&attachments, &memo_id[..shared]).unwrap_err();
+        assert!(error.to_string().contains("ambiguous"));
+    }
 }
 
-#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
-#[tokio::test(flavor = "current_thread")]
-async fn resolve_note_for_edit_rejects_ambiguous_prefix() {
-    let db_path = unique_test_db_path();
-    let db = Database::open(&db_path).await.unwrap();
-    let repo = LibSqlNoteRepository::new(db.connection());
+#[test]
+fn whisper_commands_parse_and_list_models() {
+    use clap::Parser;
+    use dirt_core::transcription::{InstalledWhisperModel, TranscriptionProvider};
+
+    use crate::cli::{Cli, Commands, WhisperCommands};
+    use crate::commands::transcribe::parse_provider;
+    use crate::commands::whisper::{format_whisper_model_lines, whisper_model_rows};
+
+    let cli = Cli::try_parse_from(["dirt", "whisper", "download", "base.en"]).unwrap();
+    let Some(Commands::Whisper {
+        command: WhisperCommands::Download { model },
+    }) = cli.command
+    else {
+        panic!("expected whisper download");
+    };
+    assert_eq!(model, "base.en");
+    assert!(Cli::try_parse_from(["dirt", "whisper", "use"]).is_err());
 
-    let note_a = Note {
-        id: "aaaaaaaa-aaaa-7aaa-8aaa-aaaaaaaaaaaa".parse().unwrap(),
-        content: "Left".to_string(),
-        created_at: 1000,
-        updated_at: 1000,
-        is_deleted: false,
+    let cli = Cli::try_parse_from(["dirt", "transcribe", "0190", "--provider", "whisper"]).unwrap();
+    let Some(Commands::Transcribe { provider, .. }) = cli.command else {
+        panic!("expected transcribe");
     };
-    let note_b = Note {
-        id: "aaaaaaaa-aaaa-7aaa-8aaa-bbbbbbbbbbbb".parse().unwrap(),
-        content: "Right".to_string(),
-        created_at: 1001,
-        updated_at: 1001,
-        is_deleted: false,
+    assert_eq!(
+        parse_provider(provider.as_deref().unwrap()).unwrap(),
+        TranscriptionProvider::Whisper
+    );
+    assert!(parse_provider("azure").is_err());
+
+    let installed = vec![InstalledWhisperModel {
+        name: "tiny".to_string(),
+        path: PathBuf::from("/data/whisper-models/ggml-tiny.bin"),
+        size_bytes: 75_000_000,
+    }];
+    let rows = whisper_model_rows(&installed, Some("tiny"));
+    assert!(rows[0].installed && rows[0].selected);
+    assert!(rows[1..].iter().all(|row| !row.installed && !row.selected));
+    let lines = format_whisper_model_lines(&rows);
+    assert!(lines[0].starts_with("* tiny"));
+    assert!(lines[0].ends_with("downloaded"));
+    assert!(lines[1].ends_with("not downloaded"));
+}
+
+#[test]
+fn sync_watch_parses_interval_and_detects_changed_credentials() {
+    use clap::Parser;
+    use dirt_core::sync::SyncToken;
+
+    use crate::cli::{Cli, Commands};
+    use crate::commands::sync::sync_credentials_changed;
+
+    let cli = Cli::try_parse_from(["dirt", "sync", "--watch", "--interval", "5"]).unwrap();
+    let Some(Commands::Sync {
+        watch, interval, ..
+    }) = cli.command
+    else {
+        panic!("expected sync");
     };
-    repo.create_with_note(&note_a).await.unwrap();
-    repo.create_with_note(&note_b).await.unwrap();
-    drop(db);
+    assert!(watch);
+    assert_eq!(interval, 5);
+    assert!(Cli::try_parse_from(["dirt", "sync", "--interval", "5"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "sync", "--watch", "--interval", "0"]).is_err());
+
+    let current = SyncToken {
+        token: "token-a".to_string(),
+        expires_at: 1_000,
+        database_url: "libsql://db-a.turso.io".to_string(),
+        credential_epoch: Some(1),
+    };
+    let renewed_expiry = SyncToken {
+        expires_at: 2_000,
+        ..current.clone()
+    };
+    let new_token = SyncToken {
+        token: "token-b".to_string(),
+        ..current.clone()
+    };
+    let moved_database = SyncToken {
+        database_url: "libsql://db-b.turso.io".to_string(),
+        ..current.clone()
+    };
+    assert!(!sync_credentials_changed(&current, &renewed_expiry));
+    assert!(sync_credentials_changed(&current, &new_token));
+    assert!(sync_credentials_changed(&current, &moved_database));
+}
 
-    let service = open_database(&db_path).await.unwrap();
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn sync_exclude_tag_parses_and_saves_tags() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, SyncCommands};
+    use crate::commands::sync::run_sync_exclude_tag;
+
+    let cli = Cli::try_parse_from(["dirt", "sync", "exclude-tag", "private", "--clear"]).unwrap();
+    let Some(Commands::Sync {
+        command: Some(SyncCommands::ExcludeTag { tag, clear }),
+        ..
+    }) = cli.command
+    else {
+        panic!("expected sync exclude-tag");
+    };
+    assert_eq!(tag, "private");
+    assert!(clear);
 
-    let error = resolve_note_for_edit("aaaaaaaa-aaaa-7aaa-8aaa", &service)
+    let db_path = unique_test_db_path();
+    assert_eq!(
+        run_sync_exclude_tag("#Private", false, &db_path)
+            .await
+            .unwrap(),
+        ["private"]
+    );
+    assert_eq!(
+        run_sync_exclude_tag("journal", false, &db_path)
+            .await
+            .unwrap(),
+        ["private", "journal"]
+    );
+    assert_eq!(
+        run_sync_exclude_tag("private", true, &db_path)
+            .await
+            .unwrap(),
+        ["journal"]
+    );
+    assert!(run_sync_exclude_tag("2fast", false, &db_path)
         .await
-        .unwrap_err();
-    assert!(matches!(error, CliError::AmbiguousNoteId(_)));
-
+        .is_err());
     cleanup_db_files(&db_path);
 }
 
+#[test]
+fn sync_daemon_status_reports_stale_heartbeats() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, SyncCommands};
+    use crate::commands::sync::{
+        format_sync_status_lines, read_sync_daemon_status, sync_daemon_status_path,
+        SyncDaemonStatus,
+    };
+
+    let cli = Cli::try_parse_from(["dirt", "sync", "daemon", "--interval", "10"]).unwrap();
+    let Some(Commands::Sync {
+        command: Some(SyncCommands::Daemon { interval }),
+        ..
+    }) = cli.command
+    else {
+        panic!("expected sync daemon");
+    };
+    assert_eq!(interval, 10);
+    assert!(Cli::try_parse_from(["dirt", "sync", "daemon", "--interval", "0"]).is_err());
+
+    let db_path = unique_test_db_path();
+    let status_path = sync_daemon_status_path(&db_path);
+    assert_eq!(status_path.extension().unwrap(), "json");
+    assert!(read_sync_daemon_status(&status_path).unwrap().is_none());
+    assert!(format_sync_status_lines(None, 0)[0].contains("not running"));
+
+    let status = SyncDaemonStatus {
+        pid: 42,
+        interval_secs: 30,
+        started_at: 0,
+        heartbeat_at: 60_000,
+        last_synced_at: None,
+        last_error: Some("offline".to_string()),
+        pending_changes: 2,
+    };
+    assert!(status.is_running(60_000 + 120_000));
+    assert!(!status.is_running(60_000 + 121_000));
+
+    let lines = format_sync_status_lines(Some(&status), 90_000);
+    assert!(lines[0].starts_with("Sync daemon running (pid 42)"));
+    assert_eq!(lines[1], "Not synced yet");
+    assert_eq!(lines[2], "Last sync failed: offline");
+    assert_eq!(lines[3], "2 note(s) waiting to sync");
+    let lines = format_sync_status_lines(Some(&status), 3_660_000);
+    assert_eq!(
+        lines[0],
+        "Sync daemon not running (pid 42 last seen 1h ago)"
+    );
+}
+
 #[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
 #[tokio::test(flavor = "current_thread")]
-async fn resolve_note_for_edit_rejects_missing_note() {
+async fn run_sync_conflicts_writes_jsonl_export() {
     let db_path = unique_test_db_path();
-    let service = open_database(&db_path).await.unwrap();
+    let db = open_database(&db_path).await.unwrap();
+    let note = db.create_note("Local edit").await.unwrap();
+    drop(db);
+    {
+        let raw = Database::open(&db_path).await.unwrap();
+        raw.connection()
+            .execute(
+                "UPDATE notes SET content = ?, updated_at = ? WHERE id = ?",
+                libsql::params![
+                    "Older remote edit",
+                    note.updated_at - 1_000,
+                    note.id.as_str()
+                ],
+            )
+            .await
+            .unwrap();
+    }
 
-    let error = resolve_note_for_edit("does-not-exist", &service)
-        .await
-        .unwrap_err();
-    assert!(matches!(error, CliError::NoteNotFound(_)));
+    let output = db_path.with_extension("jsonl");
+    run_sync_conflicts(
+        None,
+        Some("0"),
+        ConflictFormat::Jsonl,
+        Some(&output),
+        &db_path,
+    )
+    .await
+    .unwrap();
+    let exported = std::fs::read_to_string(&output).unwrap();
+    let item: serde_json::Value = serde_json::from_str(exported.trim_end()).unwrap();
+    assert_eq!(item["note_id"], note.id.to_string());
+    assert_eq!(item["local_content"], "Local edit");
+    assert_eq!(item["incoming_content"], "Older remote edit");
 
+    let _ = std::fs::remove_file(&output);
     cleanup_db_files(&db_path);
 }
 
 #[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
 #[tokio::test(flavor = "current_thread")]
-async fn run_delete_soft_deletes_note_by_exact_and_prefix_id() {
+async fn resolve_conflict_restores_the_rejected_version() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, ConflictCommands, SyncCommands};
+
+    let cli = Cli::try_parse_from([
+        "dirt",
+        "sync",
+        "conflicts",
+        "resolve",
+        "7",
+        "--strategy",
+        "merge-by-line",
+    ])
+    .unwrap();
+    let Some(Commands::Sync {
+        command:
+            Some(SyncCommands::Conflicts {
+                command: Some(ConflictCommands::Resolve { id, strategy }),
+                ..
+            }),
+        ..
+    }) = cli.command
+    else {
+        panic!("expected sync conflicts resolve");
+    };
+    assert_eq!((id, strategy), (7, ConflictStrategy::MergeByLine));
+    assert!(Cli::try_parse_from(["dirt", "sync", "conflicts", "resolve", "7"]).is_err());
+
     let db_path = unique_test_db_path();
-    let db = Database::open(&db_path).await.unwrap();
-    let repo = LibSqlNoteRepository::new(db.connection());
+    let db = open_database(&db_path).await.unwrap();
+    let note = db.create_note("Local edit").await.unwrap();
+    drop(db);
+    {
+        let raw = Database::open(&db_path).await.unwrap();
+        raw.connection()
+            .execute(
+                "UPDATE notes SET content = ?, updated_at = ? WHERE id = ?",
+                libsql::params![
+                    "Older remote edit",
+                    note.updated_at - 1_000,
+                    note.id.as_str()
+                ],
+            )
+            .await
+            .unwrap();
+    }
 
-    let note_a = Note {
-        id: "bbbbbbbb-bbbb-7bbb-8bbb-111111111111".parse().unwrap(),
-        content: "Keep me".to_string(),
-        created_at: 1000,
-        updated_at: 1000,
+    let db = open_database(&db_path).await.unwrap();
+    let conflict_id = db.list_conflicts(1).await.unwrap()[0].id;
+    drop(db);
+    run_resolve_conflict(conflict_id, ConflictStrategy::KeepRemote, &db_path)
+        .await
+        .unwrap();
+
+    let db = open_database(&db_path).await.unwrap();
+    let restored = db.get_note(&note.id).await.unwrap().unwrap();
+    assert_eq!(restored.content, "Older remote edit");
+    let conflict = db.get_conflict(conflict_id).await.unwrap().unwrap();
+    assert_eq!(conflict.strategy, "keep-remote");
+    drop(db);
+
+    assert!(
+        run_resolve_conflict(conflict_id + 1, ConflictStrategy::KeepLocal, &db_path)
+            .await
+            .is_err()
+    );
+
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn format_sync_history_lines_show_size_and_changes() {
+    let entries = vec![SyncHistoryEntry {
+        id: 7,
+        synced_at: 300,
+        duration_ms: 850,
+        frames_synced: 512,
+        bytes_transferred: 2 * 1024 * 1024,
+        rows_changed: 42,
+    }];
+
+    let rendered = format_sync_history_lines(&entries);
+    assert_eq!(rendered.len(), 1);
+    assert!(rendered[0].contains("2.0 MiB"));
+    assert!(rendered[0].contains("42 notes changed"));
+    assert!(rendered[0].contains("850 ms"));
+
+    let item = sync_history_to_item(&entries[0]);
+    assert_eq!(item.synced_at_iso, "1970-01-01 00:00:00 UTC");
+    assert_eq!(item.rows_changed, 42);
+}
+
+#[test]
+fn changelog_lists_releases_and_announces_unseen_changes() {
+    use dirt_core::changelog::{parse_changelog, CURRENT_VERSION};
+
+    let releases =
+        parse_changelog("## 0.2.0 - 2026-11-01\n- Profiles\n- Exports\n\n## 0.1.0\n- Rules\n");
+    assert_eq!(
+        format_changelog(&releases),
+        "0.2.0 (2026-11-01)\n  - Profiles\n  - Exports\n\n0.1.0\n  - Rules\n"
+    );
+    assert_eq!(
+        format_update_notice(&releases[..1]),
+        Some(format!(
+            "Dirt was updated to {CURRENT_VERSION} with 2 new changes. Run `dirt changelog` to see what's new."
+        ))
+    );
+    assert_eq!(format_update_notice(&[]), None);
+}
+
+#[test]
+fn log_format_is_a_global_flag() {
+    use clap::Parser;
+    use dirt_core::logging::LogFormat;
+
+    use crate::cli::Cli;
+
+    let cli = Cli::try_parse_from(["dirt", "sync", "--watch", "--log-format", "json"]).unwrap();
+    assert_eq!(cli.log_format, Some(LogFormat::Json));
+    assert_eq!(
+        Cli::try_parse_from(["dirt", "list"]).unwrap().log_format,
+        None
+    );
+    assert!(Cli::try_parse_from(["dirt", "--log-format", "xml", "list"]).is_err());
+}
+
+#[test]
+fn tokens_create_parses_repeated_scopes() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, TokenCommands};
+
+    let cli = Cli::try_parse_from([
+        "dirt",
+        "tokens",
+        "create",
+        "backup",
+        "--scope",
+        "notes:read",
+        "--scope",
+        "media:read",
+    ])
+    .unwrap();
+    let Some(Commands::Tokens {
+        command: TokenCommands::Create { name, scopes, .. },
+    }) = cli.command
+    else {
+        panic!("expected tokens create");
+    };
+    assert_eq!(name, "backup");
+    assert_eq!(scopes, vec![AccessScope::NotesRead, AccessScope::MediaRead]);
+
+    assert!(Cli::try_parse_from(["dirt", "tokens", "create", "backup"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "tokens", "create", "x", "--scope", "admin"]).is_err());
+}
+
+#[test]
+fn format_token_lines_show_status_and_scopes() {
+    let active = TokenItem {
+        id: "0190a6b4-0000-7000-8000-000000000001".to_string(),
+        name: "backup".to_string(),
+        scopes: vec![AccessScope::NotesRead, AccessScope::NotesWrite],
+        created_at: 0,
+        expires_at: 2_000,
+        last_used_at: None,
+        revoked_at: None,
+    };
+    let revoked = TokenItem {
+        revoked_at: Some(500),
+        ..active.clone()
+    };
+
+    assert_eq!(token_status(&active, 1_000), "active");
+    assert_eq!(token_status(&active, 2_000), "expired");
+    assert_eq!(token_status(&revoked, 1_000), "revoked");
+
+    let rendered = format_token_lines(&[active], 1_000);
+    assert!(rendered[0].contains("notes:read,notes:write"));
+    assert!(rendered[0].contains("last_used=never"));
+    assert!(rendered[0].contains("active"));
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn list_notes_respects_limit_and_tag_filter() {
+    let db_path = unique_test_db_path();
+    {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        repo.create("First #work").await.unwrap();
+        sleep(Duration::from_millis(2)).await;
+        repo.create("Second #personal").await.unwrap();
+        sleep(Duration::from_millis(2)).await;
+        repo.create("Third #work").await.unwrap();
+    }
+
+    let recent = list_notes(2, None, &db_path).await.unwrap();
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[0].content, "Third #work");
+    assert_eq!(recent[1].content, "Second #personal");
+
+    let work_only = list_notes(10, Some("work"), &db_path).await.unwrap();
+    assert_eq!(work_only.len(), 2);
+    assert!(work_only.iter().all(|note| note.content.contains("#work")));
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn list_urls_filters_by_tag_and_formats_lines() {
+    let db_path = unique_test_db_path();
+    {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        repo.create("Reading https://example.com/paper #research")
+            .await
+            .unwrap();
+        sleep(Duration::from_millis(2)).await;
+        repo.create("Tool https://example.com/tool").await.unwrap();
+    }
+
+    let db = open_database(&db_path).await.unwrap();
+    let all = db.list_urls(None, 10).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].url, "https://example.com/tool");
+
+    let research = db.list_urls(Some("research"), 10).await.unwrap();
+    let lines = format_url_lines(&research, MIN_SHORT_ID_LEN);
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].contains("Reading"));
+    assert!(lines[0].ends_with("  https://example.com/paper"));
+    assert_eq!(
+        note_url_to_item(&research[0]).note_id,
+        research[0].note.id.to_string()
+    );
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn backlinks_list_linking_notes() {
+    let db_path = unique_test_db_path();
+    let (plan, linking) = {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let plan = repo.create("Reading list\n* Dune").await.unwrap();
+        let linking = repo.create("Weekend: see [[Reading List]]").await.unwrap();
+        repo.create("No links").await.unwrap();
+        (plan, linking)
+    };
+
+    let db = open_database(&db_path).await.unwrap();
+    let backlinks = db.backlinks(&plan.id).await.unwrap();
+    assert_eq!(backlinks.len(), 1);
+    assert_eq!(backlinks[0].id, linking.id);
+    let lines = format_backlink_lines(&backlinks, MIN_SHORT_ID_LEN);
+    assert!(lines[0].starts_with(&linking.id.to_string()[..MIN_SHORT_ID_LEN]));
+    assert!(lines[0].contains("Weekend: see [[Reading List]]"));
+    assert!(db.backlinks(&linking.id).await.unwrap().is_empty());
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn daily_commands_open_one_note_per_day() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["dirt", "today"]).unwrap();
+    assert!(matches!(cli.command, Some(Commands::Today)));
+    let cli = Cli::try_parse_from(["dirt", "yesterday"]).unwrap();
+    assert!(matches!(cli.command, Some(Commands::Yesterday)));
+
+    let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+    assert_eq!(daily_note_date(today, 0), today);
+    assert_eq!(
+        daily_note_date(today, 1),
+        chrono::NaiveDate::from_ymd_opt(2026, 2, 28).unwrap()
+    );
+
+    let db_path = unique_test_db_path();
+    let db = open_database(&db_path).await.unwrap();
+    let first = db
+        .get_or_create_daily_note(today, NoteSource::Cli)
+        .await
+        .unwrap();
+    let again = db
+        .get_or_create_daily_note(today, NoteSource::Cli)
+        .await
+        .unwrap();
+    assert_eq!(first.id, again.id);
+    assert_eq!(first.content, "2026-03-01\n#daily");
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn trash_lists_restores_and_empties_deleted_notes() {
+    let db_path = unique_test_db_path();
+    let (first, second) = {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let first = repo.create("Old shopping list").await.unwrap();
+        let second = repo.create("Draft letter").await.unwrap();
+        repo.create("Still here").await.unwrap();
+        repo.delete(&first.id).await.unwrap();
+        sleep(Duration::from_millis(2)).await;
+        repo.delete(&second.id).await.unwrap();
+        (first, second)
+    };
+
+    let trashed = run_trash_list(10, false, &db_path).await.unwrap();
+    assert_eq!(
+        trashed.iter().map(|note| note.id).collect::<Vec<_>>(),
+        vec![second.id, first.id]
+    );
+    let lines = format_trash_lines(&trashed, MIN_SHORT_ID_LEN, trashed[0].updated_at);
+    assert!(lines[0].contains("just now"));
+    assert!(lines[0].ends_with("Draft letter"));
+    assert!(matches!(
+        resolve_trashed_note(&trashed, "zzzz"),
+        Err(CliError::NoteNotFound(_))
+    ));
+
+    let prefix = &first.id.to_string()[..MIN_SHORT_ID_LEN + 4];
+    let restored = run_trash_restore(prefix, &db_path).await.unwrap();
+    assert_eq!(restored.id, first.id);
+    assert!(run_trash_restore(prefix, &db_path).await.is_err());
+
+    let options = ConfirmOptions {
+        yes: true,
+        ..ConfirmOptions::default()
+    };
+    let kept = run_trash_empty(30, options, &db_path, None).await.unwrap();
+    assert_eq!(kept.notes, 0);
+    let purge = run_trash_empty(0, options, &db_path, None).await.unwrap();
+    assert_eq!(purge.notes, 1);
+    assert!(purge.attachments.is_empty());
+    assert!(run_trash_list(10, true, &db_path).await.unwrap().is_empty());
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn tasks_list_shows_open_tasks_by_note() {
+    let db_path = unique_test_db_path();
+    let errands = {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        repo.create("Done\n- [x] filed taxes").await.unwrap();
+        repo.create("Errands\n- [ ] post office\n- [x] bank\n  - [ ] buy stamps")
+            .await
+            .unwrap()
+    };
+
+    let open = run_tasks_list(false, &db_path).await.unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].0.id, errands.id);
+
+    let lines = format_task_lines(&open, MIN_SHORT_ID_LEN);
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].ends_with("  Errands"));
+    assert_eq!(lines[1], "     2  [ ] post office");
+    assert_eq!(lines[2], "     4  [ ] buy stamps");
+
+    let items = open_task_items(&open);
+    assert_eq!(
+        items
+            .iter()
+            .map(|item| (item.line_index, item.text.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(1, "post office"), (3, "buy stamps")]
+    );
+    assert_eq!(items[0].note_id, errands.id.to_string());
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn search_notes_finds_matches_with_limit() {
+    let db_path = unique_test_db_path();
+    {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        repo.create("Milk and eggs").await.unwrap();
+        sleep(Duration::from_millis(2)).await;
+        repo.create("Milkshake recipe").await.unwrap();
+        sleep(Duration::from_millis(2)).await;
+        repo.create("Unrelated note").await.unwrap();
+    }
+
+    let matches = search_notes("milk", 1, &db_path).await.unwrap();
+    assert_eq!(matches.len(), 1);
+    assert!(matches[0].content.to_lowercase().contains("milk"));
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn search_marks_notes_found_through_attachments() {
+    let db_path = unique_test_db_path();
+    let db = open_database(&db_path).await.unwrap();
+    db.create_note("Receipts #taxes").await.unwrap();
+    let scan = db.create_note("Scanned mail").await.unwrap();
+    let attachment = db
+        .create_attachment(
+            &scan.id,
+            "receipts-march.pdf",
+            "application/pdf",
+            4,
+            "notes/s/receipts-march.pdf",
+        )
+        .await
+        .unwrap();
+    db.set_attachment_text(&attachment.id, Some("Hardware store receipts"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        db.search_note_hits("receipts", 10, false)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    let hits = db.search_note_hits("hardware", 10, true).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].note.id, scan.id);
+
+    let layout = ListLayout {
+        columns: vec![ListColumn::Id, ListColumn::Preview],
+        width: None,
+        truncate: true,
+    };
+    let lines = format_search_lines(&hits, &layout, MIN_SHORT_ID_LEN);
+    assert!(lines[0].ends_with("[attachment: receipts-march.pdf]"));
+    let item = serde_json::to_value(search_hit_to_item(&hits[0])).unwrap();
+    assert_eq!(item["matched_attachment"], "receipts-march.pdf");
+    assert_eq!(item["preview"], "Scanned mail");
+
+    drop(db);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn normalize_search_query_rejects_empty() {
+    assert!(normalize_search_query(" \n\t ").is_err());
+    assert_eq!(
+        normalize_search_query("  exact phrase  ").unwrap(),
+        "exact phrase"
+    );
+}
+
+#[test]
+fn normalize_note_identifier_rejects_empty() {
+    assert!(matches!(
+        normalize_note_identifier(" \n "),
+        Err(CliError::EmptyNoteId)
+    ));
+    assert_eq!(
+        normalize_note_identifier("  abc123  ").unwrap(),
+        "abc123".to_string()
+    );
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn resolve_note_for_edit_supports_exact_and_prefix_id() {
+    let db_path = unique_test_db_path();
+    let db = Database::open(&db_path).await.unwrap();
+    let repo = LibSqlNoteRepository::new(db.connection());
+
+    let note_a = Note {
+        id: "11111111-1111-7111-8111-111111111111".parse().unwrap(),
+        content: "Note A".to_string(),
+        created_at: 1000,
+        updated_at: 1000,
         is_deleted: false,
+        is_pinned: false,
+        is_archived: false,
     };
     let note_b = Note {
-        id: "bbbbbbbb-bbbb-7bbb-8bbb-222222222222".parse().unwrap(),
-        content: "Delete me".to_string(),
+        id: "11111111-1111-7111-8111-222222222222".parse().unwrap(),
+        content: "Note B".to_string(),
         created_at: 1001,
         updated_at: 1001,
         is_deleted: false,
+        is_pinned: false,
+        is_archived: false,
     };
     repo.create_with_note(&note_a).await.unwrap();
     repo.create_with_note(&note_b).await.unwrap();
     drop(db);
 
-    run_delete("bbbbbbbb-bbbb-7bbb-8bbb-2", &db_path)
+    let service = open_database(&db_path).await.unwrap();
+
+    let by_exact = resolve_note_for_edit("11111111-1111-7111-8111-111111111111", &service)
+        .await
+        .unwrap();
+    assert_eq!(by_exact.content, "Note A");
+
+    let by_prefix = resolve_note_for_edit("11111111-1111-7111-8111-2", &service)
+        .await
+        .unwrap();
+    assert_eq!(by_prefix.content, "Note B");
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn resolve_note_for_edit_rejects_ambiguous_prefix() {
+    let db_path = unique_test_db_path();
+    let db = Database::open(&db_path).await.unwrap();
+    let repo = LibSqlNoteRepository::new(db.connection());
+
+    let note_a = Note {
+        id: "aaaaaaaa-aaaa-7aaa-8aaa-aaaaaaaaaaaa".parse().unwrap(),
+        content: "Left".to_string(),
+        created_at: 1000,
+        updated_at: 1000,
+        is_deleted: false,
+        is_pinned: false,
+        is_archived: false,
+    };
+    let note_b = Note {
+        id: "aaaaaaaa-aaaa-7aaa-8aaa-bbbbbbbbbbbb".parse().unwrap(),
+        content: "Right".to_string(),
+        created_at: 1001,
+        updated_at: 1001,
+        is_deleted: false,
+        is_pinned: false,
+        is_archived: false,
+    };
+    repo.create_with_note(&note_a).await.unwrap();
+    repo.create_with_note(&note_b).await.unwrap();
+    drop(db);
+
+    let service = open_database(&db_path).await.unwrap();
+
+    let error = resolve_note_for_edit("aaaaaaaa-aaaa-7aaa-8aaa", &service)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, CliError::AmbiguousNoteId(_)));
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn resolve_note_for_edit_rejects_missing_note() {
+    let db_path = unique_test_db_path();
+    let service = open_database(&db_path).await.unwrap();
+
+    let error = resolve_note_for_edit("does-not-exist", &service)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, CliError::NoteNotFound(_)));
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_delete_soft_deletes_note_by_exact_and_prefix_id() {
+    let db_path = unique_test_db_path();
+    let db = Database::open(&db_path).await.unwrap();
+    let repo = LibSqlNoteRepository::new(db.connection());
+
+    let note_a = Note {
+        id: "bbbbbbbb-bbbb-7bbb-8bbb-111111111111".parse().unwrap(),
+        content: "Keep me".to_string(),
+        created_at: 1000,
+        updated_at: 1000,
+        is_deleted: false,
+        is_pinned: false,
+        is_archived: false,
+    };
+    let note_b = Note {
+        id: "bbbbbbbb-bbbb-7bbb-8bbb-222222222222".parse().unwrap(),
+        content: "Delete me".to_string(),
+        created_at: 1001,
+        updated_at: 1001,
+        is_deleted: false,
+        is_pinned: false,
+        is_archived: false,
+    };
+    repo.create_with_note(&note_a).await.unwrap();
+    repo.create_with_note(&note_b).await.unwrap();
+    drop(db);
+
+    run_delete("bbbbbbbb-bbbb-7bbb-8bbb-2", &db_path)
+        .await
+        .unwrap();
+
+    let db = Database::open(&db_path).await.unwrap();
+    let repo = LibSqlNoteRepository::new(db.connection());
+    assert!(repo.get(&note_b.id).await.unwrap().is_none());
+    assert!(repo.get(&note_a.id).await.unwrap().is_some());
+    drop(db);
+
+    run_delete("bbbbbbbb-bbbb-7bbb-8bbb-111111111111", &db_path)
+        .await
+        .unwrap();
+
+    let db = Database::open(&db_path).await.unwrap();
+    let repo = LibSqlNoteRepository::new(db.connection());
+    assert!(repo.get(&note_a.id).await.unwrap().is_none());
+
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn decide_requires_confirmation_and_dry_runs_bulk_changes() {
+    let no_prompt = |_: &str| -> std::io::Result<bool> { panic!("unexpected prompt") };
+    let yes = ConfirmOptions {
+        yes: true,
+        ..ConfirmOptions::default()
+    };
+    let force = ConfirmOptions {
+        force: true,
+        ..ConfirmOptions::default()
+    };
+
+    assert_eq!(
+        decide("Delete", 3, yes, false, no_prompt).unwrap(),
+        Confirmation::Proceed
+    );
+    assert!(matches!(
+        decide("Delete", 3, ConfirmOptions::default(), false, no_prompt),
+        Err(CliError::ConfirmationRequired(_))
+    ));
+    assert_eq!(
+        decide("Delete", 3, ConfirmOptions::default(), true, |_| Ok(false)).unwrap(),
+        Confirmation::Declined
+    );
+
+    let bulk = BULK_CONFIRM_LIMIT + 1;
+    assert_eq!(
+        decide("Delete", bulk, yes, true, no_prompt).unwrap(),
+        Confirmation::DryRun
+    );
+    assert_eq!(
+        decide("Delete", bulk, force, false, no_prompt).unwrap(),
+        Confirmation::Proceed
+    );
+    let forced_dry_run = ConfirmOptions {
+        dry_run: true,
+        ..force
+    };
+    assert_eq!(
+        decide("Delete", 3, forced_dry_run, false, no_prompt).unwrap(),
+        Confirmation::DryRun
+    );
+}
+
+#[test]
+fn delete_parses_tag_mode_and_global_force() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli =
+        Cli::try_parse_from(["dirt", "delete", "--tag", "stale", "--yes", "--force"]).unwrap();
+    assert!(cli.force);
+    let Some(Commands::Delete { id, tag, yes, .. }) = cli.command else {
+        panic!("expected delete");
+    };
+    assert_eq!(id, None);
+    assert_eq!(tag.as_deref(), Some("stale"));
+    assert!(yes);
+
+    assert!(Cli::try_parse_from(["dirt", "delete"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "delete", "abc", "--tag", "stale"]).is_err());
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_delete_by_tag_deletes_only_tagged_notes_once_confirmed() {
+    let db_path = unique_test_db_path();
+    let db = open_database(&db_path).await.unwrap();
+    db.create_note("Old idea #stale").await.unwrap();
+    db.create_note("Another #stale one").await.unwrap();
+    let kept = db.create_note("Fresh #ideas").await.unwrap();
+    drop(db);
+
+    let dry_run = ConfirmOptions {
+        yes: true,
+        dry_run: true,
+        ..ConfirmOptions::default()
+    };
+    assert_eq!(
+        run_delete_by_tag("#stale", dry_run, &db_path)
+            .await
+            .unwrap(),
+        0
+    );
+
+    let yes = ConfirmOptions {
+        yes: true,
+        ..ConfirmOptions::default()
+    };
+    assert_eq!(run_delete_by_tag("stale", yes, &db_path).await.unwrap(), 2);
+
+    let notes = list_notes(10, None, &db_path).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].id, kept.id);
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_add_applies_configured_post_capture_tag() {
+    let db_path = unique_test_db_path();
+    {
+        let db = open_database(&db_path).await.unwrap();
+        let mut settings = db.load_settings().await.unwrap();
+        settings.capture_default_tag = Some("#inbox".to_string());
+        db.save_settings(&settings).await.unwrap();
+    }
+
+    run_add(&["Call the bank".to_string()], None, &[], &db_path)
+        .await
+        .unwrap();
+
+    let notes = list_notes(10, Some("inbox"), &db_path).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].content, "Call the bank\n\n#inbox");
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_add_records_cli_source() {
+    let db_path = unique_test_db_path();
+
+    run_add(
+        &["Captured from the shell".to_string()],
+        None,
+        &[],
+        &db_path,
+    )
+    .await
+    .unwrap();
+
+    let notes = search_notes("source:cli", 10, &db_path).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert!(search_notes("source:api", 10, &db_path)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let db = open_database(&db_path).await.unwrap();
+    let stats = db.capture_stats().await.unwrap();
+    assert_eq!(stats.total_notes, 1);
+    assert_eq!(stats.percent(NoteSource::Cli), 100);
+    drop(db);
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_seal_encrypts_note_until_date() {
+    let db_path = unique_test_db_path();
+    let db = Database::open(&db_path).await.unwrap();
+    let repo = LibSqlNoteRepository::new(db.connection());
+    let note = Note {
+        id: "cccccccc-cccc-7ccc-8ccc-111111111111".parse().unwrap(),
+        content: "Open on new year's day".to_string(),
+        created_at: 1000,
+        updated_at: 1000,
+        is_deleted: false,
+        is_pinned: false,
+        is_archived: false,
+    };
+    repo.create_with_note(&note).await.unwrap();
+    drop(db);
+
+    run_seal("cccccccc", "2999-01-01", &db_path).await.unwrap();
+
+    let db = Database::open(&db_path).await.unwrap();
+    let repo = LibSqlNoteRepository::new(db.connection());
+    let sealed = repo.get(&note.id).await.unwrap().unwrap();
+    assert!(sealed.content.starts_with("Sealed until 2999-01-01"));
+    assert!(!sealed.content.contains("new year"));
+    drop(db);
+
+    let error = run_unseal("cccccccc", &db_path).await.unwrap_err();
+    assert!(error.to_string().contains("sealed until 2999-01-01"));
+
+    let keys_dir = db_path.parent().unwrap().join("seal-keys");
+    let _ = std::fs::remove_file(keys_dir.join(format!("{}.key", note.id)));
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_sync_requires_sync_configuration() {
+    let db_path = unique_test_db_path();
+
+    let error = run_sync(&db_path).await.unwrap_err();
+    assert!(matches!(error, CliError::SyncNotConfigured));
+
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn digest_parses_week_and_delivery_flags() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["dirt", "digest", "--week", "--save", "--email"]).unwrap();
+    let Some(Commands::Digest {
+        week, save, email, ..
+    }) = cli.command
+    else {
+        panic!("expected digest");
+    };
+    assert!(week && save && email);
+
+    assert!(Cli::try_parse_from(["dirt", "digest", "--json", "--output", "digest.md"]).is_err());
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_digest_saves_digest_note() {
+    let db_path = unique_test_db_path();
+    {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        repo.create("Plan #work\n- [ ] ship digest").await.unwrap();
+    }
+
+    let output_path = std::env::temp_dir().join(format!(
+        "dirt-digest-test-{}.md",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos())
+    ));
+    let delivery = DigestDelivery {
+        output: Some(&output_path),
+        save: true,
+        ..DigestDelivery::default()
+    };
+    let digest = run_digest(delivery, &db_path, None).await.unwrap();
+    assert_eq!(digest.created, 1);
+    assert_eq!(digest.open_tasks.len(), 1);
+
+    let rendered = std::fs::read_to_string(&output_path).unwrap();
+    assert!(rendered.contains("- [ ] ship digest"));
+
+    let notes = list_notes(10, Some("digest"), &db_path).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert!(notes[0].content.starts_with("Weekly digest: "));
+
+    let _ = std::fs::remove_file(output_path);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn journal_requires_output_for_pdf() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from([
+        "dirt",
+        "journal",
+        "--from",
+        "2024-06-01",
+        "--to",
+        "2024-06-30",
+    ])
+    .unwrap();
+    let Some(Commands::Journal { format, output, .. }) = cli.command else {
+        panic!("expected journal");
+    };
+    assert_eq!(format, JournalFormat::Markdown);
+    assert!(output.is_none());
+
+    assert!(Cli::try_parse_from([
+        "dirt",
+        "journal",
+        "--from",
+        "2024-06-01",
+        "--to",
+        "2024-06-30",
+        "--format",
+        "pdf",
+    ])
+    .is_err());
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_journal_writes_pdf_for_range() {
+    let db_path = unique_test_db_path();
+    {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        repo.create("Today's entry #journal").await.unwrap();
+    }
+
+    let today = chrono::Utc::now().date_naive().to_string();
+    let output_path = std::env::temp_dir().join(format!(
+        "dirt-journal-test-{}.pdf",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos())
+    ));
+    let journal = run_journal(
+        &today,
+        &today,
+        JournalFormat::Pdf,
+        Some(&output_path),
+        false,
+        &db_path,
+    )
+    .await
+    .unwrap();
+    assert_eq!(journal.entry_count(), 1);
+
+    let rendered = std::fs::read(&output_path).unwrap();
+    assert!(rendered.starts_with(b"%PDF-"));
+
+    let reversed = run_journal(
+        "2024-06-30",
+        "2024-06-01",
+        JournalFormat::Markdown,
+        None,
+        false,
+        &db_path,
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(reversed, CliError::Core(_)));
+
+    let _ = std::fs::remove_file(output_path);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn maintain_requires_an_action() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["dirt", "maintain", "--analyze"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Maintain { analyze: true, .. })
+    ));
+    assert!(Cli::try_parse_from(["dirt", "maintain"]).is_err());
+
+    let cli = Cli::try_parse_from(["dirt", "maintain", "--note-limit", "0", "--usage"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Maintain {
+            usage: true,
+            note_limit: Some(0),
+            size_limit_mb: None,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn maintenance_attachments_parses_and_formats_audit() {
+    use clap::Parser;
+    use dirt_core::media::{AttachmentAudit, StoredObject};
+    use dirt_core::models::Attachment;
+    use dirt_core::NoteId;
+
+    use crate::cli::{Cli, Commands, MaintainCommands};
+
+    let cli = Cli::try_parse_from([
+        "dirt",
+        "maintenance",
+        "attachments",
+        "--repair",
+        "--dry-run",
+    ])
+    .unwrap();
+    let Some(Commands::Maintain {
+        command:
+            Some(MaintainCommands::Attachments {
+                repair,
+                purge,
+                dry_run,
+                ..
+            }),
+        ..
+    }) = cli.command
+    else {
+        panic!("expected maintain attachments command");
+    };
+    assert!(repair && dry_run && !purge);
+    assert!(Cli::try_parse_from(["dirt", "maintain", "--usage", "attachments"]).is_err());
+
+    let mut audit = AttachmentAudit {
+        checked_attachments: 2,
+        checked_objects: 2,
+        ..AttachmentAudit::default()
+    };
+    assert_eq!(
+        format_attachment_audit_lines(&audit),
+        [
+            "Checked 2 attachment record(s) against 2 stored file(s).",
+            "Attachments and storage match.",
+        ]
+    );
+
+    let note_id = NoteId::new();
+    audit.missing.push(
+        Attachment::new(
+            note_id,
+            "scan.pdf",
+            "application/pdf",
+            10,
+            "notes/a/scan.pdf",
+        )
+        .unwrap(),
+    );
+    audit.orphaned.push(StoredObject {
+        key: "notes/b/old.png".to_string(),
+        size_bytes: 2_048,
+        last_modified: Some(0),
+    });
+    let lines = format_attachment_audit_lines(&audit);
+    assert_eq!(lines[1], "1 record(s) missing their file:");
+    assert_eq!(lines[2], format!("  {note_id}  scan.pdf  notes/a/scan.pdf"));
+    assert!(lines[3].starts_with("1 stored file(s) no attachment uses"));
+    assert!(lines[4].starts_with("  notes/b/old.png  "));
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_analyze_reports_index_usage() {
+    let db_path = unique_test_db_path();
+    {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        repo.create("Indexed #maintenance").await.unwrap();
+    }
+
+    let report = run_analyze(&db_path).await.unwrap();
+    assert!(report
+        .indexes
+        .iter()
+        .any(|stat| stat.table == "notes" && stat.rows == 1));
+
+    let lines = format_analyze_lines(&report);
+    assert_eq!(lines[0], "Indexes:");
+    assert!(lines.iter().any(|line| line.starts_with("  list notes: ")));
+    assert!(lines.last().unwrap().contains("DIRT_SLOW_QUERY_MS"));
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn storage_quotas_warn_and_purge_reclaims_deleted_notes() {
+    let db_path = unique_test_db_path();
+    {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        repo.create("Kept").await.unwrap();
+        repo.create("Also kept").await.unwrap();
+        let gone = repo.create("Deleted").await.unwrap();
+        repo.delete(&gone.id).await.unwrap();
+    }
+
+    let settings = run_set_quota_limits(Some(1), Some(0), &db_path)
+        .await
+        .unwrap();
+    assert_eq!(settings.note_count_warning, Some(1));
+    assert_eq!(settings.database_size_warning_mb, None);
+
+    let usage = run_usage(&db_path).await.unwrap();
+    assert_eq!((usage.notes, usage.deleted_notes), (2, 1));
+    let lines = format_usage_lines(&usage, &settings);
+    assert!(lines[0].ends_with("(no warning)"));
+    assert_eq!(lines[1], "Notes: 2 (warn above 1)");
+    assert!(lines.contains(&"Warning: 2 notes exceed the warning threshold of 1.".to_string()));
+    assert!(lines
+        .iter()
+        .any(|line| line.contains("dirt maintain --purge-deleted")));
+
+    let options = ConfirmOptions {
+        yes: true,
+        ..ConfirmOptions::default()
+    };
+    assert_eq!(run_purge_deleted(options, &db_path).await.unwrap(), 1);
+    assert_eq!(run_purge_deleted(options, &db_path).await.unwrap(), 0);
+    assert_eq!(run_usage(&db_path).await.unwrap().deleted_notes, 0);
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn maintenance_db_checks_and_compacts() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, MaintainCommands};
+
+    let cli = Cli::try_parse_from(["dirt", "maintenance", "db", "--compact", "--json"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Maintain {
+            command: Some(MaintainCommands::Db {
+                compact: true,
+                json: true
+            }),
+            ..
+        })
+    ));
+
+    let db_path = unique_test_db_path();
+    {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        let filler = "padding ".repeat(4_000);
+        for index in 0..10 {
+            repo.create(&format!("Old {index} {filler}")).await.unwrap();
+        }
+        db.connection()
+            .execute("DELETE FROM notes", ())
+            .await
+            .unwrap();
+        repo.create("Kept #maintenance").await.unwrap();
+    }
+
+    let report = run_db_maintenance(false, false, &db_path).await.unwrap();
+    assert!(report.health.is_healthy());
+    assert_eq!(report.compact, None);
+    assert!(report.health.free_bytes > 0);
+    let lines = format_db_maintenance_lines(&report);
+    assert_eq!(lines[1], "Integrity check: ok");
+    assert!(lines[2].starts_with("Run with --compact"));
+
+    let report = run_db_maintenance(true, false, &db_path).await.unwrap();
+    let compact = report.compact.unwrap();
+    assert!(compact.vacuumed);
+    assert!(compact.reclaimed_bytes() > 0);
+    let lines = format_db_maintenance_lines(&report);
+    assert!(lines[2].starts_with("Compacted: "));
+
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn rules_add_parses_condition_and_action_flags() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, RuleCommands};
+
+    let cli = Cli::try_parse_from([
+        "dirt",
+        "rules",
+        "add",
+        "--if",
+        "source:email-in",
+        "--then",
+        "#inbox",
+    ])
+    .unwrap();
+    let Some(Commands::Rules {
+        command: RuleCommands::Add { condition, action },
+    }) = cli.command
+    else {
+        panic!("expected rules add");
+    };
+    assert_eq!(condition, "source:email-in");
+    assert_eq!(action, "#inbox");
+
+    assert!(Cli::try_parse_from(["dirt", "rules", "add", "--if", "#work"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "rules", "remove", "abc"]).is_err());
+}
+
+#[test]
+fn tag_merge_requires_sources_and_target() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, TagCommands};
+
+    let cli =
+        Cli::try_parse_from(["dirt", "tag", "merge", "job", "#office", "--into", "work"]).unwrap();
+    let Some(Commands::Tag {
+        command: TagCommands::Merge { tags, into },
+    }) = cli.command
+    else {
+        panic!("expected tag merge");
+    };
+    assert_eq!(tags, ["job", "#office"]);
+    assert_eq!(into, "work");
+
+    assert!(Cli::try_parse_from(["dirt", "tag", "merge", "--into", "work"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "tag", "merge", "job"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "tag", "rename", "job"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "tag", "list", "--counts", "--json"]).is_ok());
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_tag_renames_and_removes_tags_in_notes() {
+    use crate::cli::TagCommands;
+
+    let db_path = unique_test_db_path();
+    let db = open_database(&db_path).await.unwrap();
+    let note = db.create_note("Standup #Work #todo").await.unwrap();
+    db.create_note("Plan #work").await.unwrap();
+    drop(db);
+
+    run_tag(
+        TagCommands::Rename {
+            old: "#work".to_string(),
+            new: "office".to_string(),
+        },
+        &db_path,
+    )
+    .await
+    .unwrap();
+    run_tag(
+        TagCommands::Remove {
+            tag: "todo".to_string(),
+        },
+        &db_path,
+    )
+    .await
+    .unwrap();
+    assert!(run_tag(
+        TagCommands::Remove {
+            tag: "not a tag".to_string(),
+        },
+        &db_path,
+    )
+    .await
+    .is_err());
+
+    let db = open_database(&db_path).await.unwrap();
+    let updated = db.get_note(&note.id).await.unwrap().unwrap();
+    assert_eq!(updated.content, "Standup #office");
+    let tags: Vec<TagItem> = db
+        .list_tags()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(name, notes)| TagItem { name, notes })
+        .collect();
+    assert_eq!(format_tag_lines(&tags, true), ["2  #office"]);
+    assert_eq!(format_tag_lines(&tags, false), ["#office"]);
+    drop(db);
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_rules_adds_lists_removes_and_rejects_invalid_rules() {
+    use crate::cli::RuleCommands;
+
+    let db_path = unique_test_db_path();
+    run_rules(
+        RuleCommands::Add {
+            condition: "contains:invoice".to_string(),
+            action: "#finance".to_string(),
+        },
+        &db_path,
+    )
+    .await
+    .unwrap();
+    assert!(run_rules(
+        RuleCommands::Add {
+            condition: "invoice".to_string(),
+            action: "#finance".to_string(),
+        },
+        &db_path,
+    )
+    .await
+    .is_err());
+
+    let db = open_database(&db_path).await.unwrap();
+    let rules = db.list_routing_rules().await.unwrap();
+    let id = rules[0].id;
+    assert_eq!(
+        format_rule_lines(&rules),
+        [format!("{id:>4}  if contains:invoice then #finance")]
+    );
+    let note = db.create_note("Invoice for March").await.unwrap();
+    assert_eq!(note.content, "Invoice for March\n\n#finance");
+    drop(db);
+
+    run_rules(
+        RuleCommands::Test {
+            text: "invoice".to_string(),
+            source: Some("email-in".to_string()),
+        },
+        &db_path,
+    )
+    .await
+    .unwrap();
+    assert!(run_rules(
+        RuleCommands::Test {
+            text: "invoice".to_string(),
+            source: Some("fax".to_string()),
+        },
+        &db_path,
+    )
+    .await
+    .is_err());
+
+    run_rules(RuleCommands::Remove { id }, &db_path)
+        .await
+        .unwrap();
+    assert!(matches!(
+        run_rules(RuleCommands::Remove { id }, &db_path).await,
+        Err(CliError::RoutingRuleNotFound(missing)) if missing == id
+    ));
+
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn note_to_export_item_sorts_tags() {
+    let note = Note::new("#zeta test #alpha #beta");
+    let export = dirt_core::export::note_to_export_item(&note);
+
+    assert_eq!(export.tags, vec!["alpha", "beta", "zeta"]);
+}
+
+#[test]
+fn render_markdown_export_includes_frontmatter_and_content() {
+    let note = Note {
+        id: "cccccccc-cccc-7ccc-8ccc-111111111111".parse().unwrap(),
+        content: "Hello export #tag".to_string(),
+        created_at: 123,
+        updated_at: 456,
+        is_deleted: false,
+        is_pinned: false,
+        is_archived: false,
+    };
+
+    let rendered = render_markdown_export(&[note]);
+    assert!(rendered.contains("id: cccccccc-cccc-7ccc-8ccc-111111111111"));
+    assert!(rendered.contains("created_at: 123"));
+    assert!(rendered.contains("updated_at: 456"));
+    assert!(rendered.contains("tags:\n  - tag"));
+    assert!(rendered.contains("Hello export #tag"));
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_export_writes_json_file() {
+    let db_path = unique_test_db_path();
+    {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        repo.create("Export me #one").await.unwrap();
+    }
+
+    let output_path = std::env::temp_dir().join(format!(
+        "dirt-export-test-{}.json",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos())
+    ));
+
+    run_export(
+        ExportFormat::Json,
+        Some(&output_path),
+        false,
+        None,
+        &db_path,
+    )
+    .await
+    .unwrap();
+
+    let exported = std::fs::read_to_string(&output_path).unwrap();
+    assert!(exported.contains("\"content\": \"Export me #one\""));
+    assert!(exported.contains("\"tags\": [\n      \"one\"\n    ]"));
+
+    let _ = std::fs::remove_file(output_path);
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_export_resolves_markdown_links() {
+    let db_path = unique_test_db_path();
+    let target_id = {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        let target = repo.create("Reading list").await.unwrap();
+        repo.create("See [[reading list]] and [[Nowhere]]")
+            .await
+            .unwrap();
+        target.id
+    };
+
+    let output_path = std::env::temp_dir().join(format!(
+        "dirt-export-links-test-{}.md",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos())
+    ));
+
+    run_export(
+        ExportFormat::Markdown,
+        Some(&output_path),
+        false,
+        None,
+        &db_path,
+    )
+    .await
+    .unwrap();
+
+    let exported = std::fs::read_to_string(&output_path).unwrap();
+    assert!(exported.contains(&format!("<a id=\"note-{target_id}\"></a>")));
+    assert!(exported.contains(&format!("[reading list](#note-{target_id})")));
+    assert!(exported.contains("[[Nowhere]]"));
+
+    let _ = std::fs::remove_file(output_path);
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_export_writes_org_and_text_files() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["dirt", "export", "--format", "org"]).unwrap();
+    let Some(Commands::Export { format, .. }) = cli.command else {
+        panic!("expected export");
+    };
+    assert_eq!(format, ExportFormat::Org);
+
+    let db_path = unique_test_db_path();
+    let note = {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        repo.create("Garden plan #home\n* tomatoes").await.unwrap()
+    };
+
+    let output_dir = db_path.with_extension("plain");
+    std::fs::create_dir_all(&output_dir).unwrap();
+    let org_path = output_dir.join("notes.org");
+    run_export(ExportFormat::Org, Some(&org_path), false, None, &db_path)
+        .await
+        .unwrap();
+    let exported = std::fs::read_to_string(&org_path).unwrap();
+    assert!(exported.starts_with("* Garden plan #home :home:\n:PROPERTIES:\n"));
+    assert!(exported.contains(&format!(":ID: {}\n", note.id)));
+    assert!(exported.ends_with(":END:\n * tomatoes\n"));
+
+    let text_path = output_dir.join("notes.txt");
+    run_export(ExportFormat::Text, Some(&text_path), false, None, &db_path)
+        .await
+        .unwrap();
+    let exported = std::fs::read_to_string(&text_path).unwrap();
+    assert!(exported.starts_with(&format!("ID: {}\n", note.id)));
+    assert!(exported.ends_with("Tags: home\n\nGarden plan #home\n* tomatoes\n"));
+
+    let mirror_dir = output_dir.join("mirror");
+    let report = run_incremental_export(ExportFormat::Org, &mirror_dir, false, &db_path)
+        .await
+        .unwrap();
+    assert_eq!(report.written, 1);
+    assert!(mirror_dir.join(format!("{}.org", note.id)).is_file());
+
+    let _ = std::fs::remove_dir_all(output_dir);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn no_export_parses_id_or_tag_and_include_private_flags() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["dirt", "no-export", "--tag", "private", "--clear"]).unwrap();
+    let Some(Commands::NoExport { id, tag, clear }) = cli.command else {
+        panic!("expected no-export");
+    };
+    assert!(id.is_none());
+    assert_eq!(tag.as_deref(), Some("private"));
+    assert!(clear);
+
+    assert!(Cli::try_parse_from(["dirt", "no-export"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "no-export", "abc", "--tag", "private"]).is_err());
+
+    let cli = Cli::try_parse_from(["dirt", "export", "--include-private"]).unwrap();
+    let Some(Commands::Export {
+        include_private, ..
+    }) = cli.command
+    else {
+        panic!("expected export");
+    };
+    assert!(include_private);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_incremental_export_writes_only_changes_and_prunes_deleted_notes() {
+    use clap::Parser;
+
+    use crate::cli::Cli;
+
+    assert!(Cli::try_parse_from(["dirt", "export", "--incremental"]).is_err());
+
+    let db_path = unique_test_db_path();
+    let (kept, edited, deleted) = {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        (
+            repo.create("Kept note").await.unwrap(),
+            repo.create("Draft plan").await.unwrap(),
+            repo.create("Scratch").await.unwrap(),
+        )
+    };
+    let output_dir = db_path.with_extension("mirror");
+
+    let report = run_incremental_export(ExportFormat::Markdown, &output_dir, false, &db_path)
+        .await
+        .unwrap();
+    assert_eq!(
+        report,
+        IncrementalExportReport {
+            written: 3,
+            removed: 0,
+            unchanged: 0,
+        }
+    );
+    assert!(output_dir.join(format!("{}.md", kept.id)).is_file());
+
+    let report = run_incremental_export(ExportFormat::Markdown, &output_dir, false, &db_path)
+        .await
+        .unwrap();
+    assert_eq!(report.written, 0);
+    assert_eq!(report.unchanged, 3);
+
+    sleep(Duration::from_millis(2)).await;
+    {
+        let db = open_database(&db_path).await.unwrap();
+        db.update_note(&edited.id, "Final plan").await.unwrap();
+        db.delete_note(&deleted.id).await.unwrap();
+    }
+
+    let report = run_incremental_export(ExportFormat::Markdown, &output_dir, false, &db_path)
+        .await
+        .unwrap();
+    assert_eq!(
+        report,
+        IncrementalExportReport {
+            written: 1,
+            removed: 1,
+            unchanged: 1,
+        }
+    );
+    let edited_file =
+        std::fs::read_to_string(output_dir.join(format!("{}.md", edited.id))).unwrap();
+    assert!(edited_file.contains("Final plan"));
+    assert!(!output_dir.join(format!("{}.md", deleted.id)).exists());
+
+    let report = run_incremental_export(ExportFormat::Json, &output_dir, false, &db_path)
+        .await
+        .unwrap();
+    assert_eq!(report.written, 2);
+
+    let _ = std::fs::remove_dir_all(output_dir);
+    cleanup_db_files(&db_path);
+}
+
+#[test]
+fn attachments_pull_parses_filters_and_limits_jobs() {
+    use clap::Parser;
+
+    use crate::cli::{AttachmentCommands, Cli, Commands};
+
+    let cli = Cli::try_parse_from([
+        "dirt",
+        "attachments",
+        "pull",
+        "--output",
+        "media",
+        "--tag",
+        "trip",
+        "--since",
+        "2024-06-01",
+        "--jobs",
+        "8",
+    ])
+    .unwrap();
+    let Some(Commands::Attachments {
+        command:
+            AttachmentCommands::Pull {
+                output,
+                tag,
+                since,
+                until,
+                jobs,
+                json,
+            },
+    }) = cli.command
+    else {
+        panic!("expected attachments pull");
+    };
+    assert_eq!(output, PathBuf::from("media"));
+    assert_eq!(tag.as_deref(), Some("trip"));
+    assert_eq!(since.as_deref(), Some("2024-06-01"));
+    assert!(until.is_none());
+    assert_eq!(jobs, 8);
+    assert!(!json);
+
+    assert!(Cli::try_parse_from(["dirt", "attachments", "pull"]).is_err());
+    assert!(
+        Cli::try_parse_from(["dirt", "attachments", "pull", "-o", "x", "--jobs", "0"]).is_err()
+    );
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn cache_stats_and_clear_report_the_attachment_cache() {
+    use clap::Parser;
+    use dirt_core::media::{AttachmentCache, DEFAULT_ATTACHMENT_CACHE_MB};
+
+    use crate::cli::{CacheCommands, Cli, Commands};
+    use crate::commands::cache::{format_cache_stats_lines, run_cache};
+
+    let cli = Cli::try_parse_from(["dirt", "cache", "stats", "--json"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Cache {
+            command: CacheCommands::Stats { json: true }
+        })
+    ));
+    assert!(Cli::try_parse_from(["dirt", "cache"]).is_err());
+
+    let dir = unique_test_db_path().with_extension("");
+    let db_path = dir.join("dirt.db");
+    let empty = run_cache(CacheCommands::Stats { json: false }, &db_path)
         .await
         .unwrap();
+    assert_eq!(empty.entries, 0);
+    assert_eq!(
+        empty.max_bytes,
+        u64::from(DEFAULT_ATTACHMENT_CACHE_MB) * 1024 * 1024
+    );
 
-    let db = Database::open(&db_path).await.unwrap();
-    let repo = LibSqlNoteRepository::new(db.connection());
-    assert!(repo.get(&note_b.id).await.unwrap().is_none());
-    assert!(repo.get(&note_a.id).await.unwrap().is_some());
-    drop(db);
-
-    run_delete("bbbbbbbb-bbbb-7bbb-8bbb-111111111111", &db_path)
+    let cache = AttachmentCache::beside_database(&db_path, DEFAULT_ATTACHMENT_CACHE_MB).unwrap();
+    cache.put("notes/a/photo.png", &[7; 2048], None).unwrap();
+    let stats = run_cache(CacheCommands::Stats { json: false }, &db_path)
         .await
         .unwrap();
+    assert_eq!(
+        format_cache_stats_lines(&stats),
+        [
+            "Cached attachments: 1 in 1 file(s)",
+            "Size: 2.0 KiB (limit 256.0 MiB)"
+        ]
+    );
 
-    let db = Database::open(&db_path).await.unwrap();
-    let repo = LibSqlNoteRepository::new(db.connection());
-    assert!(repo.get(&note_a.id).await.unwrap().is_none());
-
-    cleanup_db_files(&db_path);
+    let cleared = run_cache(CacheCommands::Clear, &db_path).await.unwrap();
+    assert_eq!(cleared.entries, 0);
+    let _ = std::fs::remove_dir_all(dir);
 }
 
-#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
-#[tokio::test(flavor = "current_thread")]
-async fn run_sync_requires_sync_configuration() {
-    let db_path = unique_test_db_path();
-
-    let error = run_sync(&db_path).await.unwrap_err();
-    assert!(matches!(error, CliError::SyncNotConfigured));
+#[test]
+fn plan_downloads_groups_by_note_and_disambiguates_names() {
+    let note_id = dirt_core::NoteId::new();
+    let first =
+        dirt_core::models::Attachment::new(note_id, "Photo 1.PNG", "image/png", 3, "k/1").unwrap();
+    let second =
+        dirt_core::models::Attachment::new(note_id, "photo-1.png", "image/png", 3, "k/2").unwrap();
+    let second_id = second.id;
+    let output = std::env::temp_dir().join("dirt-pull-plan-test");
+
+    let planned = plan_downloads(&output, vec![first, second]);
+    let note_dir = output.join(note_id.to_string());
+    assert_eq!(planned[0].destination, note_dir.join("photo-1.png"));
+    assert_eq!(
+        planned[1].destination,
+        note_dir.join(format!("{second_id}-photo-1.png"))
+    );
 
-    cleanup_db_files(&db_path);
+    assert!(!is_already_pulled(&planned[0].destination, 3));
+    assert_eq!(
+        format_pull_summary(&PullSummary {
+            downloaded: 2,
+            bytes_downloaded: 10,
+            already_present: 1,
+            quarantined: 0,
+            failed: 0,
+        }),
+        "Downloaded 2 attachment(s) (10 bytes), 1 already present, 0 quarantined, 0 failed"
+    );
 }
 
 #[test]
-fn note_to_export_item_sorts_tags() {
-    let note = Note::new("#zeta test #alpha #beta");
-    let export = dirt_core::export::note_to_export_item(&note);
+fn pulled_media_paths_point_at_complete_copies_next_to_the_export() {
+    let note_id = dirt_core::NoteId::new();
+    let pulled =
+        dirt_core::models::Attachment::new(note_id, "sketch.png", "image/png", 3, "k/1").unwrap();
+    let missing =
+        dirt_core::models::Attachment::new(note_id, "spec.pdf", "application/pdf", 9, "k/2")
+            .unwrap();
+    let export_dir = std::env::temp_dir().join(format!(
+        "dirt-media-paths-test-{}",
+        dirt_core::NoteId::new()
+    ));
+    let media_dir = export_dir.join("media");
+    std::fs::create_dir_all(media_dir.join(note_id.to_string())).unwrap();
+    std::fs::write(
+        media_dir.join(note_id.to_string()).join("sketch.png"),
+        b"png",
+    )
+    .unwrap();
 
-    assert_eq!(export.tags, vec!["alpha", "beta", "zeta"]);
+    let attachments = [pulled.clone(), missing];
+    let paths = pulled_media_paths(&media_dir, Some(&export_dir.join("notes.md")), &attachments);
+    assert_eq!(
+        paths,
+        HashMap::from([(pulled.id.to_string(), format!("media/{note_id}/sketch.png"))])
+    );
+
+    let _ = std::fs::remove_dir_all(&export_dir);
 }
 
-#[test]
-fn render_markdown_export_includes_frontmatter_and_content() {
-    let note = Note {
-        id: "cccccccc-cccc-7ccc-8ccc-111111111111".parse().unwrap(),
-        content: "Hello export #tag".to_string(),
-        created_at: 123,
-        updated_at: 456,
-        is_deleted: false,
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_attachments_pull_resumes_and_applies_filters() {
+    use dirt_core::models::AttachmentScanStatus;
+
+    let db_path = unique_test_db_path();
+    let (trip_note, present, quarantined) = {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        let trip = repo.create("Beach day #Trip").await.unwrap();
+        let other = repo.create("Receipts").await.unwrap();
+        let present = repo
+            .create_attachment(&trip.id, "beach.jpg", "image/jpeg", 3, "notes/t/beach.jpg")
+            .await
+            .unwrap();
+        let quarantined = repo
+            .create_attachment(&trip.id, "bad.pdf", "application/pdf", 9, "notes/t/bad.pdf")
+            .await
+            .unwrap();
+        repo.set_attachment_scan_status(&quarantined.id, AttachmentScanStatus::Quarantined)
+            .await
+            .unwrap();
+        repo.create_attachment(&other.id, "r.pdf", "application/pdf", 5, "notes/o/r.pdf")
+            .await
+            .unwrap();
+        (trip.id, present, quarantined)
     };
 
-    let rendered = render_markdown_export(&[note]);
-    assert!(rendered.contains("id: cccccccc-cccc-7ccc-8ccc-111111111111"));
-    assert!(rendered.contains("created_at: 123"));
-    assert!(rendered.contains("updated_at: 456"));
-    assert!(rendered.contains("tags:\n  - tag"));
-    assert!(rendered.contains("Hello export #tag"));
+    let output = db_path.with_extension("media");
+    let note_dir = output.join(trip_note.to_string());
+    std::fs::create_dir_all(&note_dir).unwrap();
+    std::fs::write(note_dir.join("beach.jpg"), b"abc").unwrap();
+    assert!(is_already_pulled(
+        &note_dir.join("beach.jpg"),
+        present.size_bytes
+    ));
+
+    let options = PullOptions {
+        output: &output,
+        tag: Some("#trip"),
+        since: None,
+        until: None,
+        jobs: 4,
+        json: false,
+    };
+    let summary = run_attachments_pull(options, &db_path, None).await.unwrap();
+    assert_eq!(summary.already_present, 1);
+    assert_eq!(summary.quarantined, 1);
+    assert_eq!(summary.downloaded, 0);
+    assert!(!note_dir.join(&quarantined.filename).exists());
+
+    let options = PullOptions {
+        output: &output,
+        tag: None,
+        since: Some("2999-01-01"),
+        until: None,
+        jobs: 4,
+        json: true,
+    };
+    let summary = run_attachments_pull(options, &db_path, None).await.unwrap();
+    assert_eq!(summary, PullSummary::default());
+
+    let options = PullOptions {
+        output: &output,
+        tag: None,
+        since: Some("2024-06-02"),
+        until: Some("2024-06-01"),
+        jobs: 4,
+        json: false,
+    };
+    assert!(run_attachments_pull(options, &db_path, None).await.is_err());
+
+    let _ = std::fs::remove_dir_all(output);
+    cleanup_db_files(&db_path);
 }
 
 #[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
 #[tokio::test(flavor = "current_thread")]
-async fn run_export_writes_json_file() {
+async fn run_export_skips_private_notes_unless_included() {
     let db_path = unique_test_db_path();
-    {
+    let flagged_id = {
         let db = Database::open(&db_path).await.unwrap();
         let repo = LibSqlNoteRepository::new(db.connection());
-        repo.create("Export me #one").await.unwrap();
-    }
+        repo.create("Shared plan").await.unwrap();
+        repo.create("Salary review #private").await.unwrap();
+        repo.create("Diary entry").await.unwrap().id
+    };
+    run_no_export(&flagged_id.to_string(), false, &db_path)
+        .await
+        .unwrap();
+    run_no_export_tag("#Private", false, &db_path)
+        .await
+        .unwrap();
+    assert!(run_no_export_tag("2fast", false, &db_path).await.is_err());
 
     let output_path = std::env::temp_dir().join(format!(
-        "dirt-export-test-{}.json",
+        "dirt-export-private-test-{}.json",
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map_or(0, |duration| duration.as_nanos())
     ));
 
-    run_export(ExportFormat::Json, Some(&output_path), &db_path)
+    run_export(
+        ExportFormat::Json,
+        Some(&output_path),
+        false,
+        None,
+        &db_path,
+    )
+    .await
+    .unwrap();
+    let exported = std::fs::read_to_string(&output_path).unwrap();
+    assert!(exported.contains("Shared plan"));
+    assert!(!exported.contains("Salary review"));
+    assert!(!exported.contains("Diary entry"));
+
+    run_export(ExportFormat::Json, Some(&output_path), true, None, &db_path)
         .await
         .unwrap();
-
     let exported = std::fs::read_to_string(&output_path).unwrap();
-    assert!(exported.contains("\"content\": \"Export me #one\""));
-    assert!(exported.contains("\"tags\": [\n      \"one\"\n    ]"));
+    assert!(exported.contains("Salary review"));
+    assert!(exported.contains("Diary entry"));
 
     let _ = std::fs::remove_file(output_path);
     cleanup_db_files(&db_path);
 }
 
+#[test]
+fn import_parses_source_and_path() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from([
+        "dirt",
+        "import",
+        "--from",
+        "apple-notes",
+        "export-folder",
+        "--dry-run",
+    ])
+    .unwrap();
+    let Some(Commands::Import {
+        from,
+        path,
+        dry_run,
+        no_media,
+    }) = cli.command
+    else {
+        panic!("expected import");
+    };
+    assert_eq!(from, ImportSource::AppleNotes);
+    assert_eq!(path, PathBuf::from("export-folder"));
+    assert!(dry_run && !no_media);
+    let cli = Cli::try_parse_from(["dirt", "import", "vault", "--format", "markdown"]).unwrap();
+    let Some(Commands::Import { from, .. }) = cli.command else {
+        panic!("expected import");
+    };
+    assert_eq!(from, ImportSource::Markdown);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_import_saves_simplenote_notes() {
+    let db_path = unique_test_db_path();
+    let export_path = std::env::temp_dir().join(format!(
+        "dirt-import-test-{}.json",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos())
+    ));
+    std::fs::write(
+        &export_path,
+        r#"{"activeNotes": [{"id": "a", "content": "From Simplenote", "tags": ["Inbox"]}]}"#,
+    )
+    .unwrap();
+
+    let preview = run_import(
+        ImportSource::Simplenote,
+        &export_path,
+        ImportOptions {
+            dry_run: true,
+            ..ImportOptions::default()
+        },
+        &db_path,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(preview.notes_imported, 0);
+
+    let summary = run_import(
+        ImportSource::Simplenote,
+        &export_path,
+        ImportOptions::default(),
+        &db_path,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(summary.notes_imported, 1);
+
+    let notes = list_notes(10, Some("inbox"), &db_path).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].content, "From Simplenote\n\n#inbox");
+
+    let _ = std::fs::remove_file(export_path);
+    cleanup_db_files(&db_path);
+}
+
 #[test]
 fn run_completions_writes_bash_script_file() {
     let output_path = std::env::temp_dir().join(format!(
@@ -398,6 +2756,87 @@ fn run_completions_writes_bash_script_file() {
     let _ = std::fs::remove_file(output_path);
 }
 
+#[test]
+fn profile_db_path_is_scoped_and_sanitized() {
+    let root = PathBuf::from("data");
+    assert_eq!(
+        profile_db_path_in(&root, "work"),
+        root.join("work").join("dirt.db")
+    );
+    assert!(profile_db_path_in(&root, "../escape").starts_with(&root));
+    assert_ne!(
+        profile_db_path_in(&root, "work"),
+        profile_db_path_in(&root, "personal")
+    );
+}
+
+#[test]
+fn choose_db_path_copies_legacy_database_once_confirmed() {
+    let root = unique_test_db_path().with_extension("profiles");
+    let legacy = root.join("dirt.db");
+    std::fs::create_dir_all(root.join("seal-keys")).unwrap();
+    std::fs::write(&legacy, "db").unwrap();
+    std::fs::write(root.join("dirt.db-wal"), "wal").unwrap();
+    std::fs::write(root.join("dirt.db-shm"), "shm").unwrap();
+    std::fs::write(root.join("seal-keys").join("note.key"), "key").unwrap();
+    let profile_path = profile_db_path_in(&root, "work");
+
+    let declined = choose_db_path("work", profile_path.clone(), &legacy, true, |_| Ok(false));
+    assert_eq!(declined.unwrap(), legacy);
+    let scripted = choose_db_path("work", profile_path.clone(), &legacy, false, |_| {
+        panic!("non-interactive runs must not prompt")
+    });
+    assert_eq!(scripted.unwrap(), legacy);
+    assert!(!profile_path.exists());
+
+    let chosen = choose_db_path("work", profile_path.clone(), &legacy, true, |_| Ok(true));
+    assert_eq!(chosen.unwrap(), profile_path);
+    let profile_dir = profile_path.parent().unwrap();
+    assert_eq!(std::fs::read_to_string(&profile_path).unwrap(), "db");
+    assert!(profile_dir.join("dirt.db-wal").exists());
+    assert!(!profile_dir.join("dirt.db-shm").exists());
+    assert!(profile_dir.join("seal-keys").join("note.key").exists());
+    assert!(legacy.exists());
+
+    let again = choose_db_path("work", profile_path.clone(), &legacy, true, |_| {
+        panic!("an existing profile database must not prompt")
+    });
+    assert_eq!(again.unwrap(), profile_path);
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn format_profile_db_lines_mark_active_profile() {
+    let mut config = CliProfilesConfig {
+        version: 1,
+        active_profile: Some("work".to_string()),
+        ..CliProfilesConfig::default()
+    };
+    config
+        .profiles
+        .insert("personal".to_string(), CliProfile::default());
+    config
+        .profiles
+        .insert("work".to_string(), CliProfile::default());
+    let root = PathBuf::from("data");
+
+    let lines = format_profile_db_lines(&config, Some("personal"), None, &root);
+    assert_eq!(lines[0], "Active profile: personal");
+    assert!(lines[2].starts_with("* personal"));
+    assert!(lines[2].contains(&root.join("personal").join("dirt.db").display().to_string()));
+    assert!(lines[2].ends_with("(not created yet)"));
+    assert!(lines[3].starts_with("  work"));
+
+    let overridden = format_profile_db_lines(
+        &config,
+        None,
+        Some(std::path::Path::new("custom.db")),
+        &root,
+    );
+    assert!(overridden.last().unwrap().ends_with("custom.db"));
+}
+
 fn unique_test_db_path() -> PathBuf {
     static NEXT_TEST_DB_ID: AtomicU64 = AtomicU64::new(0);
 
@@ -419,3 +2858,379 @@ fn cleanup_db_files(path: &PathBuf) {
     let _ = std::fs::remove_file(path.with_extension("db-shm"));
     let _ = std::fs::remove_file(path.with_extension("db-wal"));
 }
+
+#[test]
+fn add_parses_template_fields() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from([
+        "dirt",
+        "add",
+        "--template",
+        "meeting",
+        "--field",
+        "attendees=Ana, Bo",
+        "--field",
+        "actions=Send recap",
+    ])
+    .unwrap();
+    let Some(Commands::Add {
+        content,
+        template,
+        fields,
+    }) = cli.command
+    else {
+        panic!("expected add");
+    };
+    assert!(content.is_empty());
+    assert_eq!(template.as_deref(), Some("meeting"));
+    assert_eq!(fields, vec!["attendees=Ana, Bo", "actions=Send recap"]);
+
+    assert!(Cli::try_parse_from(["dirt", "add", "--field", "a=b"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "add", "text", "--template", "meeting"]).is_err());
+}
+
+#[test]
+fn render_template_builds_note_and_rejects_unknown_names() {
+    let content = render_template(
+        "meeting",
+        &[
+            "attendees=Ana, Bo".to_string(),
+            "decisions=Ship Friday".to_string(),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        content,
+        "Meeting\nAttendees: Ana, Bo\nDecisions:\n- Ship Friday\n\n#meeting"
+    );
+
+    assert!(matches!(
+        render_template("retro", &[]),
+        Err(CliError::UnknownTemplate { .. })
+    ));
+    assert!(render_template("meeting", &["mood=ok".to_string()]).is_err());
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn pin_and_archive_update_the_note_list() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["dirt", "list", "--archived"]).unwrap();
+    let Some(Commands::List { archived, .. }) = cli.command else {
+        panic!("expected list");
+    };
+    assert!(archived);
+    assert!(Cli::try_parse_from(["dirt", "list", "--archived", "--tag", "work"]).is_err());
+    let cli = Cli::try_parse_from(["dirt", "pin", "abc", "--clear"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Pin { clear: true, .. })
+    ));
+
+    let db_path = unique_test_db_path();
+    let (pinned_id, archived_id) = {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        let pinned = repo.create("Pin me").await.unwrap();
+        let archived = repo.create("Archive me").await.unwrap();
+        repo.create("Leave me").await.unwrap();
+        (pinned.id, archived.id)
+    };
+
+    run_pin(&pinned_id.to_string(), false, &db_path)
+        .await
+        .unwrap();
+    run_archive(&archived_id.to_string(), false, &db_path)
+        .await
+        .unwrap();
+
+    let listed = list_notes(10, None, &db_path).await.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert!(listed.iter().all(|note| note.id != archived_id));
+    assert!(listed
+        .iter()
+        .any(|note| note.id == pinned_id && note.is_pinned));
+
+    let db = open_database(&db_path).await.unwrap();
+    let archived = db.list_archived_notes(10, 0).await.unwrap();
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived[0].id, archived_id);
+    drop(db);
+
+    run_archive(&archived_id.to_string(), true, &db_path)
+        .await
+        .unwrap();
+    assert_eq!(list_notes(10, None, &db_path).await.unwrap().len(), 3);
+
+    cleanup_db_files(&db_path);
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn history_lists_revisions_and_restore_brings_one_back() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    let cli = Cli::try_parse_from(["dirt", "restore", "abc", "--revision", "2"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Restore { revision: 2, .. })
+    ));
+    assert!(Cli::try_parse_from(["dirt", "restore", "abc"]).is_err());
+    let cli = Cli::try_parse_from(["dirt", "maintain", "--history-limit", "0"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Maintain {
+            history_limit: Some(0),
+            ..
+        })
+    ));
+
+    let db_path = unique_test_db_path();
+    let db = open_database(&db_path).await.unwrap();
+    let note = db.create_note("First draft").await.unwrap();
+    db.update_note(&note.id, "Second draft").await.unwrap();
+    db.update_note(&note.id, "Third draft\nwith details")
+        .await
+        .unwrap();
+    let revisions = db.list_note_revisions(&note.id).await.unwrap();
+    drop(db);
+
+    assert_eq!(revisions.len(), 3);
+    let lines = format_revision_lines(&revisions, revisions[0].device_id.as_deref());
+    assert!(lines[0].trim_start().starts_with('3'));
+    assert!(lines[0].contains("this device"));
+    assert!(lines[0].ends_with("Third draft"));
+    assert!(lines[2].contains("unknown"));
+    assert!(lines[2].ends_with("First draft"));
+
+    run_restore(&note.id.to_string(), 1, &db_path)
+        .await
+        .unwrap();
+    let db = open_database(&db_path).await.unwrap();
+    let restored = db.get_note(&note.id).await.unwrap().unwrap();
+    assert_eq!(restored.content, "First draft");
+    assert_eq!(db.list_note_revisions(&note.id).await.unwrap().len(), 4);
+    drop(db);
+
+    assert!(run_restore(&note.id.to_string(), 9, &db_path)
+        .await
+        .is_err());
+    assert_eq!(run_set_history_limit(2, &db_path).await.unwrap(), 2);
+
+    cleanup_db_files(&db_path);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn encryption_enable_locks_notes_until_unlocked() {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands, EncryptionCommands};
+
+    let cli =
+        Cli::try_parse_from(["dirt", "encryption", "unlock", "--passphrase", "secret"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Encryption {
+            command: EncryptionCommands::Unlock {
+                passphrase: Some(_)
+            }
+        })
+    ));
+
+    let db_path = unique_test_db_path();
+    let db = open_database(&db_path).await.unwrap();
+    let note = db.create_note("Bank PIN hint #private").await.unwrap();
+    drop(db);
+
+    assert_eq!(run_enable("correct horse", &db_path).await.unwrap(), 1);
+    assert!(run_enable("correct horse", &db_path).await.is_err());
+
+    // Every new process starts locked.
+    let db = open_database(&db_path).await.unwrap();
+    assert!(db.is_encrypted().await);
+    assert!(!db.is_unlocked().await);
+    let locked = db.get_note(&note.id).await.unwrap().unwrap();
+    assert!(!locked.content.contains("Bank PIN"));
+    assert!(db.create_note("Refused while locked").await.is_err());
+    drop(db);
+
+    assert!(run_unlock("battery staple", &db_path).await.is_err());
+    assert_eq!(run_unlock("correct horse", &db_path).await.unwrap(), 0);
+
+    cleanup_db_files(&db_path);
+}
+
+fn tui_key(code: ratatui::crossterm::event::KeyCode) -> ratatui::crossterm::event::KeyEvent {
+    ratatui::crossterm::event::KeyEvent::from(code)
+}
+
+fn tui_type(app: &mut crate::tui::app::App, text: &str) {
+    for ch in text.chars() {
+        app.handle_key(tui_key(ratatui::crossterm::event::KeyCode::Char(ch)));
+    }
+}
+
+#[test]
+fn tui_keys_drive_search_tags_editing_and_delete() {
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::tui::app::{Action, App, Mode, SyncState};
+
+    let save = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    let first = Note::new("Groceries #errands");
+    let second = Note::new("Call the bank");
+    let mut app = App::new(SyncState::Disabled);
+    app.set_notes(vec![first.clone(), second.clone()]);
+
+    assert_eq!(app.handle_key(tui_key(KeyCode::Char('/'))), Action::None);
+    assert_eq!(app.mode, Mode::Search);
+    assert_eq!(app.handle_key(tui_key(KeyCode::Char('b'))), Action::Reload);
+    assert_eq!(app.search, "b");
+    assert_eq!(app.handle_key(tui_key(KeyCode::Esc)), Action::Reload);
+    assert!(app.search.is_empty());
+
+    app.handle_key(tui_key(KeyCode::Char('t')));
+    tui_type(&mut app, "#Errands");
+    assert_eq!(app.handle_key(tui_key(KeyCode::Enter)), Action::Reload);
+    assert_eq!(app.tag_filter.as_deref(), Some("errands"));
+    // Esc clears the filter before it quits.
+    assert_eq!(app.handle_key(tui_key(KeyCode::Esc)), Action::Reload);
+    assert_eq!(app.tag_filter, None);
+
+    app.handle_key(tui_key(KeyCode::Char('j')));
+    app.handle_key(tui_key(KeyCode::Char('e')));
+    assert_eq!(app.mode, Mode::Edit(Some(second.id)));
+    tui_type(&mut app, " today");
+    assert_eq!(
+        app.handle_key(save),
+        Action::Update(second.id, "Call the bank today".to_string())
+    );
+    assert_eq!(app.mode, Mode::List);
+
+    app.handle_key(tui_key(KeyCode::Char('e')));
+    assert_eq!(app.handle_key(save), Action::None);
+
+    app.handle_key(tui_key(KeyCode::Char('a')));
+    tui_type(&mut app, "New idea");
+    app.handle_key(tui_key(KeyCode::Enter));
+    tui_type(&mut app, "#work");
+    assert_eq!(
+        app.handle_key(save),
+        Action::Create("New idea\n#work".to_string())
+    );
+
+    app.handle_key(tui_key(KeyCode::Char('d')));
+    assert_eq!(app.handle_key(tui_key(KeyCode::Char('n'))), Action::None);
+    app.handle_key(tui_key(KeyCode::Char('d')));
+    assert_eq!(
+        app.handle_key(tui_key(KeyCode::Char('y'))),
+        Action::Delete(second.id)
+    );
+
+    app.handle_key(tui_key(KeyCode::Char('s')));
+    assert!(app.message.as_deref().unwrap().contains("not configured"));
+    assert_eq!(app.handle_key(tui_key(KeyCode::Char('q'))), Action::Quit);
+}
+
+#[test]
+fn tui_editor_joins_and_splits_lines() {
+    use ratatui::crossterm::event::KeyCode;
+
+    use crate::tui::app::Editor;
+
+    let mut editor = Editor::new("one\ntwo");
+    assert_eq!(editor.cursor(), (1, 3));
+    editor.handle_key(tui_key(KeyCode::Home));
+    editor.handle_key(tui_key(KeyCode::Backspace));
+    assert_eq!(editor.text(), "onetwo");
+    assert_eq!(editor.cursor(), (0, 3));
+    editor.handle_key(tui_key(KeyCode::Enter));
+    editor.handle_key(tui_key(KeyCode::Char('é')));
+    assert_eq!(editor.text(), "one\nétwo");
+    editor.handle_key(tui_key(KeyCode::Up));
+    editor.handle_key(tui_key(KeyCode::End));
+    editor.handle_key(tui_key(KeyCode::Delete));
+    assert_eq!(editor.text(), "oneétwo");
+}
+
+#[test]
+fn share_parses_flags_and_formats_links() {
+    let cli = Cli::try_parse_from(["dirt", "share", "abc", "--expires", "2d"]).unwrap();
+    let Some(Commands::Share {
+        id,
+        expires,
+        list,
+        revoke,
+        include_private,
+        ..
+    }) = cli.command
+    else {
+        panic!("expected share");
+    };
+    assert_eq!(id, "abc");
+    assert_eq!(
+        ShareAction::from_flags(expires.as_deref(), include_private, list, revoke).unwrap(),
+        ShareAction::Create {
+            expires_in_secs: Some(2 * 86_400),
+            include_private: false,
+        }
+    );
+    assert!(Cli::try_parse_from(["dirt", "share", "abc", "--include-private", "--list"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "share", "abc", "--expires", "2d", "--list"]).is_err());
+    assert!(Cli::try_parse_from(["dirt", "share", "abc", "--list", "--revoke"]).is_err());
+    assert!(ShareAction::from_flags(Some("forever"), false, false, false).is_err());
+    assert_eq!(
+        ShareAction::from_flags(None, false, false, true).unwrap(),
+        ShareAction::Revoke
+    );
+
+    let link = dirt_core::share::ShareLink {
+        id: "share-1".to_string(),
+        note_id: Note::new("shared").id,
+        url: None,
+        created_at: 0,
+        expires_at: 3_600,
+        revoked_at: None,
+    };
+    assert_eq!(share_status(&link, 60), "active");
+    assert_eq!(share_status(&link, 3_600), "expired");
+    let revoked = dirt_core::share::ShareLink {
+        revoked_at: Some(120),
+        ..link.clone()
+    };
+    assert_eq!(share_status(&revoked, 60), "revoked");
+    assert_eq!(
+        format_share_lines(&[link], 60),
+        vec!["share-1  active    created=1970-01-01 00:00 UTC  expires=1970-01-01 01:00 UTC"]
+    );
+}
+
+#[cfg_attr(windows, ignore = "libsql integration is flaky on windows CI")]
+#[tokio::test(flavor = "current_thread")]
+async fn run_share_refuses_private_notes_without_include_private() {
+    let db_path = unique_test_db_path();
+    let private_id = {
+        let db = Database::open(&db_path).await.unwrap();
+        let repo = LibSqlNoteRepository::new(db.connection());
+        repo.create("Diary entry").await.unwrap().id
+    };
+    run_no_export(&private_id.to_string(), false, &db_path)
+        .await
+        .unwrap();
+
+    let action = ShareAction::from_flags(None, false, false, false).unwrap();
+    let error = run_share(&private_id.to_string(), action, false, &db_path, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, CliError::PrivateNote(id) if id == private_id.to_string()));
+
+    cleanup_db_files(&db_path);
+}
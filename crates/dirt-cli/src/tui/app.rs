@@ -0,0 +1,386 @@
+//! TUI state and key handling, kept free of I/O so it can be tested.
+
+use dirt_core::seal::{format_seal_date, sealed_until};
+use dirt_core::{Note, NoteId};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::commands::common::normalize_content;
+
+/// What the keyboard currently drives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Browsing the note list
+    List,
+    /// Typing a search query; results update as you type
+    Search,
+    /// Typing the tag to filter by
+    TagFilter,
+    /// Editing a note; `None` is a new note
+    Edit(Option<NoteId>),
+    /// Waiting for `y` before deleting the selected note
+    ConfirmDelete,
+}
+
+/// Work the event loop does after a key press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    /// Reload notes for the current search and tag filter
+    Reload,
+    Create(String),
+    Update(NoteId, String),
+    Delete(NoteId),
+    Sync,
+}
+
+/// Sync state shown in the status bar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncState {
+    /// No managed sync profile or Turso env; notes stay local
+    Disabled,
+    Idle {
+        last_synced_at: Option<i64>,
+    },
+    Syncing,
+    Failed(String),
+}
+
+/// State of `dirt tui`
+#[derive(Debug)]
+pub struct App {
+    pub notes: Vec<Note>,
+    pub selected: usize,
+    pub mode: Mode,
+    pub search: String,
+    pub tag_filter: Option<String>,
+    /// Tag typed so far in [`Mode::TagFilter`]
+    pub tag_input: String,
+    pub editor: Editor,
+    pub sync: SyncState,
+    /// Outcome of the last action, shown in the status bar
+    pub message: Option<String>,
+}
+
+impl App {
+    pub fn new(sync: SyncState) -> Self {
+        Self {
+            notes: Vec::new(),
+            selected: 0,
+            mode: Mode::List,
+            search: String::new(),
+            tag_filter: None,
+            tag_input: String::new(),
+            editor: Editor::default(),
+            sync,
+            message: None,
+        }
+    }
+
+    pub fn selected_note(&self) -> Option<&Note> {
+        self.notes.get(self.selected)
+    }
+
+    /// Replace the listed notes, keeping the selection in range.
+    pub fn set_notes(&mut self, notes: Vec<Note>) {
+        self.notes = notes;
+        self.selected = self.selected.min(self.notes.len().saturating_sub(1));
+    }
+
+    /// Select the note with `id` if it is listed.
+    pub fn select(&mut self, id: &NoteId) {
+        if let Some(index) = self.notes.iter().position(|note| &note.id == id) {
+            self.selected = index;
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        match self.mode.clone() {
+            Mode::List => self.handle_list_key(key),
+            Mode::Search => self.handle_search_key(key),
+            Mode::TagFilter => self.handle_tag_key(key),
+            Mode::Edit(id) => self.handle_edit_key(id, key),
+            Mode::ConfirmDelete => self.handle_confirm_delete_key(key),
+        }
+    }
+
+    fn handle_list_key(&mut self, key: KeyEvent) -> Action {
+        self.message = None;
+        match key.code {
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Esc if self.search.is_empty() && self.tag_filter.is_none() => {
+                return Action::Quit;
+            }
+            KeyCode::Esc => {
+                self.search.clear();
+                self.tag_filter = None;
+                return Action::Reload;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                if self.selected + 1 < self.notes.len() {
+                    self.selected += 1;
+                }
+            }
+            KeyCode::Char('k') | KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Char('g') | KeyCode::Home => self.selected = 0,
+            KeyCode::Char('G') | KeyCode::End => {
+                self.selected = self.notes.len().saturating_sub(1);
+            }
+            KeyCode::Char('/') => self.mode = Mode::Search,
+            KeyCode::Char('t') => {
+                self.tag_input = self.tag_filter.clone().unwrap_or_default();
+                self.mode = Mode::TagFilter;
+            }
+            KeyCode::Char('a' | 'n') => {
+                self.editor = Editor::default();
+                self.mode = Mode::Edit(None);
+            }
+            KeyCode::Char('e') | KeyCode::Enter => self.start_editing(),
+            KeyCode::Char('d') if self.selected_note().is_some() => {
+                self.message = Some("Delete this note? Press y to confirm".to_string());
+                self.mode = Mode::ConfirmDelete;
+            }
+            KeyCode::Char('s') if self.sync == SyncState::Disabled => {
+                self.message = Some("Sync is not configured for this profile".to_string());
+            }
+            KeyCode::Char('s') => return Action::Sync,
+            KeyCode::Char('r') => return Action::Reload,
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn start_editing(&mut self) {
+        let Some(note) = self.selected_note() else {
+            return;
+        };
+        if let Some(until) = sealed_until(&note.content) {
+            self.message = Some(format!("Note is sealed until {}", format_seal_date(until)));
+            return;
+        }
+        let editor = Editor::new(&note.content);
+        let id = note.id;
+        self.editor = editor;
+        self.mode = Mode::Edit(Some(id));
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Enter => {
+                self.mode = Mode::List;
+                Action::None
+            }
+            KeyCode::Esc => {
+                self.mode = Mode::List;
+                self.search.clear();
+                Action::Reload
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+                Action::Reload
+            }
+            KeyCode::Char(ch) => {
+                self.search.push(ch);
+                Action::Reload
+            }
+            _ => Action::None,
+        }
+    }
+
+    fn handle_tag_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Enter => {
+                self.mode = Mode::List;
+                let tag = self.tag_input.trim().trim_start_matches('#').to_lowercase();
+                self.tag_filter = (!tag.is_empty()).then_some(tag);
+                Action::Reload
+            }
+            KeyCode::Esc => {
+                self.mode = Mode::List;
+                Action::None
+            }
+            KeyCode::Backspace => {
+                self.tag_input.pop();
+                Action::None
+            }
+            KeyCode::Char(ch) if !ch.is_whitespace() => {
+                self.tag_input.push(ch);
+                Action::None
+            }
+            _ => Action::None,
+        }
+    }
+
+    fn handle_edit_key(&mut self, id: Option<NoteId>, key: KeyEvent) -> Action {
+        let original = id
+            .and_then(|id| self.notes.iter().find(|note| note.id == id))
+            .map_or("", |note| note.content.as_str());
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('s') {
+            self.mode = Mode::List;
+            let Some(content) = normalize_content(&self.editor.text()) else {
+                self.message = Some("Empty note discarded".to_string());
+                return Action::None;
+            };
+            return match id {
+                Some(_) if content == original => Action::None,
+                Some(id) => Action::Update(id, content),
+                None => Action::Create(content),
+            };
+        }
+        if key.code == KeyCode::Esc {
+            if self.editor.text() != original {
+                self.message = Some("Changes discarded".to_string());
+            }
+            self.mode = Mode::List;
+            return Action::None;
+        }
+        self.editor.handle_key(key);
+        Action::None
+    }
+
+    fn handle_confirm_delete_key(&mut self, key: KeyEvent) -> Action {
+        self.mode = Mode::List;
+        let selected = self.selected_note().map(|note| note.id);
+        match (key.code, selected) {
+            (KeyCode::Char('y' | 'Y'), Some(id)) => {
+                self.message = None;
+                Action::Delete(id)
+            }
+            _ => {
+                self.message = Some("Delete cancelled".to_string());
+                Action::None
+            }
+        }
+    }
+}
+
+/// Multi-line text buffer with a cursor, in characters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Editor {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Editor {
+    /// Buffer holding `text`, with the cursor at its end.
+    pub fn new(text: &str) -> Self {
+        let lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        Self { lines, row, col }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Cursor as (line, character)
+    pub const fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.insert(ch);
+            }
+            KeyCode::Enter => self.newline(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left => self.move_left(),
+            KeyCode::Right => self.move_right(),
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(self.row),
+            _ => {}
+        }
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    /// Byte offset of the cursor in its line
+    fn byte_index(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.col)
+            .map_or(line.len(), |(index, _)| index)
+    }
+
+    fn insert(&mut self, ch: char) {
+        let index = self.byte_index();
+        self.lines[self.row].insert(index, ch);
+        self.col += 1;
+    }
+
+    fn newline(&mut self) {
+        let index = self.byte_index();
+        let rest = self.lines[self.row].split_off(index);
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let index = self.byte_index();
+            self.lines[self.row].remove(index);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len(self.row);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.col < self.line_len(self.row) {
+            let index = self.byte_index();
+            self.lines[self.row].remove(index);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        }
+    }
+
+    fn move_left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len(self.row);
+        }
+    }
+
+    fn move_right(&mut self) {
+        if self.col < self.line_len(self.row) {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! `dirt tui`: a full-screen terminal interface.
+//!
+//! Opens the database the same way every other command does, so the managed
+//! sync profile (or Turso env) is picked up, then drives [`app::App`] with
+//! key presses and renders it with ratatui.
+
+pub mod app;
+mod ui;
+
+use std::path::Path;
+
+use dirt_core::models::NoteSource;
+use dirt_core::services::DatabaseService;
+use dirt_core::Note;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+
+use crate::commands::common::{list_notes_in, open_database};
+use crate::error::CliError;
+use app::{Action, App, Mode, SyncState};
+
+/// Notes loaded into the list at once
+const NOTE_LIMIT: usize = 500;
+
+pub async fn run_tui(db_path: &Path) -> Result<(), CliError> {
+    // Open before taking over the terminal so migration progress and
+    // warnings still print normally.
+    let db = open_database(db_path).await?;
+    let mut app = App::new(initial_sync_state(&db).await);
+    reload(&db, &mut app).await;
+
+    let mut terminal = ratatui::init();
+    let result = run_loop(&mut terminal, &db, &mut app).await;
+    ratatui::restore();
+    result
+}
+
+async fn run_loop(
+    terminal: &mut DefaultTerminal,
+    db: &DatabaseService,
+    app: &mut App,
+) -> Result<(), CliError> {
+    loop {
+        terminal.draw(|frame| ui::render(frame, app))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match app.handle_key(key) {
+            Action::None => {}
+            Action::Quit => return Ok(()),
+            Action::Reload => reload(db, app).await,
+            Action::Create(content) => match db.create_note_from(&content, NoteSource::Cli).await {
+                Ok(note) => {
+                    app.message = Some("Note added".to_string());
+                    reload(db, app).await;
+                    app.select(&note.id);
+                }
+                Err(error) => {
+                    app.message = Some(format!("Failed to add note: {error}"));
+                    app.mode = Mode::Edit(None);
+                }
+            },
+            Action::Update(id, content) => match db.update_note(&id, &content).await {
+                Ok(_) => {
+                    app.message = Some("Note saved".to_string());
+                    reload(db, app).await;
+                }
+                Err(error) => {
+                    app.message = Some(format!("Failed to save note: {error}"));
+                    app.mode = Mode::Edit(Some(id));
+                }
+            },
+            Action::Delete(id) => {
+                app.message = Some(match db.delete_note(&id).await {
+                    Ok(()) => "Note deleted".to_string(),
+                    Err(error) => format!("Failed to delete note: {error}"),
+                });
+                reload(db, app).await;
+            }
+            Action::Sync => {
+                app.sync = SyncState::Syncing;
+                terminal.draw(|frame| ui::render(frame, app))?;
+                match db.sync().await {
+                    Ok(()) => {
+                        app.sync = SyncState::Idle {
+                            last_synced_at: Some(chrono::Utc::now().timestamp_millis()),
+                        };
+                        reload(db, app).await;
+                    }
+                    Err(error) => app.sync = SyncState::Failed(error.to_string()),
+                }
+            }
+        }
+    }
+}
+
+async fn initial_sync_state(db: &DatabaseService) -> SyncState {
+    if !db.is_sync_enabled().await {
+        return SyncState::Disabled;
+    }
+    let last_synced_at = db
+        .list_sync_history(1)
+        .await
+        .ok()
+        .and_then(|history| history.first().map(|entry| entry.synced_at));
+    SyncState::Idle { last_synced_at }
+}
+
+/// Load notes for the current search and tag filter; failures, such as a
+/// half-typed search query, show in the status bar.
+async fn reload(db: &DatabaseService, app: &mut App) {
+    match load_notes(db, app.search.trim(), app.tag_filter.as_deref()).await {
+        Ok(notes) => app.set_notes(notes),
+        Err(error) => app.message = Some(error.to_string()),
+    }
+}
+
+async fn load_notes(
+    db: &DatabaseService,
+    query: &str,
+    tag: Option<&str>,
+) -> Result<Vec<Note>, CliError> {
+    if query.is_empty() {
+        return list_notes_in(db, NOTE_LIMIT, tag).await;
+    }
+    let mut notes = db.search_notes(query, NOTE_LIMIT).await?;
+    if let Some(tag) = tag {
+        notes.retain(|note| {
+            note.tags()
+                .iter()
+                .any(|note_tag| note_tag.eq_ignore_ascii_case(tag))
+        });
+    }
+    Ok(notes)
+}
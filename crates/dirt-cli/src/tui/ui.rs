@@ -0,0 +1,182 @@
+//! Rendering of `dirt tui`.
+
+use dirt_core::models::{short_note_id, MIN_SHORT_ID_LEN};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::app::{App, Mode, SyncState};
+use crate::commands::common::{
+    format_relative_time, format_sync_timestamp, note_preview, render_tags,
+};
+
+/// Characters of a note's first line shown in the list
+const PREVIEW_CHARS: usize = 60;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let [main, status, hints] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [list_area, detail_area] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(main);
+
+    render_list(frame, app, list_area);
+    render_detail(frame, app, detail_area);
+    render_status(frame, app, status);
+    frame.render_widget(Paragraph::new(key_hints(&app.mode)).dim(), hints);
+}
+
+fn render_list(frame: &mut Frame, app: &App, area: Rect) {
+    let [prompt_area, notes_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(area);
+
+    let (label, value) = if app.mode == Mode::TagFilter {
+        ("Tag", format!("#{}", app.tag_input))
+    } else {
+        ("Search", app.search.clone())
+    };
+    let prompting = matches!(app.mode, Mode::Search | Mode::TagFilter);
+    let prompt_style = if prompting {
+        Style::new().fg(Color::Yellow)
+    } else {
+        Style::new()
+    };
+    if prompting {
+        frame.set_cursor_position(Position::new(
+            prompt_area
+                .x
+                .saturating_add(1)
+                .saturating_add(to_u16(value.chars().count())),
+            prompt_area.y.saturating_add(1),
+        ));
+    }
+    frame.render_widget(
+        Paragraph::new(value).block(Block::bordered().title(label).border_style(prompt_style)),
+        prompt_area,
+    );
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let items: Vec<ListItem> = app
+        .notes
+        .iter()
+        .map(|note| {
+            let details = format!(
+                "{}  {}",
+                format_relative_time(note.updated_at, now),
+                render_tags(note)
+            );
+            ListItem::new(vec![
+                Line::raw(note_preview(note, PREVIEW_CHARS)),
+                Line::from(Span::raw(details).dim()),
+            ])
+        })
+        .collect();
+    let title = match &app.tag_filter {
+        Some(tag) => format!("Notes ({}) #{tag}", app.notes.len()),
+        None => format!("Notes ({})", app.notes.len()),
+    };
+    let list = List::new(items)
+        .block(Block::bordered().title(title))
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ");
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(list, notes_area, &mut state);
+}
+
+fn render_detail(frame: &mut Frame, app: &App, area: Rect) {
+    if let Mode::Edit(id) = &app.mode {
+        let title = if id.is_some() {
+            "Edit note"
+        } else {
+            "New note"
+        };
+        let block = Block::bordered()
+            .title(title)
+            .border_style(Style::new().fg(Color::Yellow));
+        let visible_rows = usize::from(area.height.saturating_sub(2)).max(1);
+        let (row, col) = app.editor.cursor();
+        let scroll = row.saturating_sub(visible_rows - 1);
+        let lines: Vec<Line> = app
+            .editor
+            .lines()
+            .iter()
+            .map(|line| Line::raw(line.as_str()))
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .scroll((to_u16(scroll), 0)),
+            area,
+        );
+        frame.set_cursor_position(Position::new(
+            area.x.saturating_add(1).saturating_add(to_u16(col)),
+            area.y
+                .saturating_add(1)
+                .saturating_add(to_u16(row - scroll)),
+        ));
+        return;
+    }
+
+    let Some(note) = app.selected_note() else {
+        let empty = if app.search.is_empty() && app.tag_filter.is_none() {
+            "No notes yet. Press a to add one."
+        } else {
+            "No notes match. Press Esc to clear the filter."
+        };
+        frame.render_widget(Paragraph::new(empty).block(Block::bordered()), area);
+        return;
+    };
+    let title = format!(
+        "{}  {}",
+        short_note_id(&note.id.as_str(), MIN_SHORT_ID_LEN),
+        format_sync_timestamp(note.updated_at)
+    );
+    frame.render_widget(
+        Paragraph::new(note.content.as_str())
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(title)),
+        area,
+    );
+}
+
+fn render_status(frame: &mut Frame, app: &App, area: Rect) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let sync = match &app.sync {
+        SyncState::Disabled => Span::raw("Local only").dim(),
+        SyncState::Idle {
+            last_synced_at: None,
+        } => Span::raw("Sync on, not synced yet"),
+        SyncState::Idle {
+            last_synced_at: Some(synced_at),
+        } => Span::raw(format!("Synced {}", format_relative_time(*synced_at, now))).green(),
+        SyncState::Syncing => Span::raw("Syncing...").yellow(),
+        SyncState::Failed(error) => Span::raw(format!("Sync failed: {error}")).red(),
+    };
+    let mut spans = vec![sync];
+    if let Some(message) = &app.message {
+        spans.push(Span::raw("  |  "));
+        spans.push(Span::raw(message.as_str()).bold());
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+const fn key_hints(mode: &Mode) -> &'static str {
+    match mode {
+        Mode::List => {
+            "j/k move  / search  t tag  a add  e edit  d delete  s sync  r reload  Esc clear  q quit"
+        }
+        Mode::Search => "Type to search  Enter keep  Esc clear",
+        Mode::TagFilter => "Type a tag  Enter apply (empty clears)  Esc cancel",
+        Mode::Edit(_) => "Ctrl+S save  Esc discard",
+        Mode::ConfirmDelete => "y delete  any other key cancels",
+    }
+}
+
+fn to_u16(value: usize) -> u16 {
+    u16::try_from(value).unwrap_or(u16::MAX)
+}
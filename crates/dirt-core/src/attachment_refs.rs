@@ -0,0 +1,149 @@
+//! Inline attachment references
+//!
+//! `!attach[diagram.png](<attachment-id>)` places an attachment at a chosen
+//! spot in a note. The id is what a renderer resolves; the label is what
+//! readers see wherever the media cannot be reached, so renaming a file never
+//! breaks the reference. References are only ever written by the user (or by
+//! an explicit "insert" action); attachments are never injected into note
+//! content on their own.
+
+use regex::{Captures, Regex};
+
+use crate::models::Attachment;
+
+/// Start of every inline attachment reference.
+pub const ATTACHMENT_REF_PREFIX: &str = "!attach[";
+
+/// One `!attach[label](id)` reference in a note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachmentRef {
+    /// Text between the brackets, normally the file name
+    pub label: String,
+    /// Id of the referenced attachment
+    pub attachment_id: String,
+}
+
+/// Reference to `attachment`, labeled with its file name.
+#[must_use]
+pub fn format_attachment_ref(attachment: &Attachment) -> String {
+    let label: String = attachment
+        .filename
+        .chars()
+        .filter(|ch| !matches!(ch, '[' | ']' | '\n' | '\r'))
+        .collect();
+    format!("{ATTACHMENT_REF_PREFIX}{label}]({})", attachment.id)
+}
+
+/// References in `content`, in order of appearance.
+#[must_use]
+pub fn parse_attachment_refs(content: &str) -> Vec<AttachmentRef> {
+    ref_pattern()
+        .captures_iter(content)
+        .map(|caps| attachment_ref(&caps))
+        .collect()
+}
+
+/// Replace every reference `resolve` returns text for; the others are left
+/// as written.
+pub fn replace_attachment_refs(
+    content: &str,
+    mut resolve: impl FnMut(&AttachmentRef) -> Option<String>,
+) -> String {
+    ref_pattern()
+        .replace_all(content, |caps: &Captures<'_>| {
+            resolve(&attachment_ref(caps)).unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Replace every reference with a plain `[attachment: label]` marker, for
+/// renderers without access to the media.
+#[must_use]
+pub fn attachment_refs_as_labels(content: &str) -> String {
+    replace_attachment_refs(content, |reference| {
+        Some(format!("[attachment: {}]", reference.label))
+    })
+}
+
+/// Markdown for a resolved reference: an image embed for images, a link
+/// otherwise. URLs with spaces (local file names) are wrapped in `<>`.
+#[must_use]
+pub fn render_attachment_embed(label: &str, mime_type: &str, url: &str) -> String {
+    let url = if url.contains(char::is_whitespace) {
+        format!("<{url}>")
+    } else {
+        url.to_string()
+    };
+    if mime_type.starts_with("image/") {
+        format!("![{label}]({url})")
+    } else {
+        format!("[{label}]({url})")
+    }
+}
+
+fn ref_pattern() -> Regex {
+    Regex::new(r"!attach\[([^\[\]\n]*)\]\(([0-9A-Za-z-]+)\)").expect("Invalid regex")
+}
+
+fn attachment_ref(caps: &Captures<'_>) -> AttachmentRef {
+    AttachmentRef {
+        label: caps[1].trim().to_string(),
+        attachment_id: caps[2].to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NoteId;
+
+    #[test]
+    fn references_round_trip_and_resolve() {
+        let attachment = Attachment::new(
+            NoteId::new(),
+            "plan [v2].png",
+            "image/png",
+            42,
+            "notes/plan",
+        )
+        .unwrap();
+        let reference = format_attachment_ref(&attachment);
+        assert_eq!(
+            reference,
+            format!("!attach[plan v2.png]({})", attachment.id)
+        );
+
+        let content = format!("Before\n{reference}\nand !attach[gone.pdf](missing-id) after");
+        assert_eq!(
+            parse_attachment_refs(&content),
+            [
+                AttachmentRef {
+                    label: "plan v2.png".to_string(),
+                    attachment_id: attachment.id.to_string(),
+                },
+                AttachmentRef {
+                    label: "gone.pdf".to_string(),
+                    attachment_id: "missing-id".to_string(),
+                },
+            ]
+        );
+
+        let resolved = replace_attachment_refs(&content, |reference| {
+            (reference.attachment_id == attachment.id.to_string())
+                .then(|| render_attachment_embed(&reference.label, "image/png", "media/plan.png"))
+        });
+        assert_eq!(
+            resolved,
+            "Before\n![plan v2.png](media/plan.png)\nand !attach[gone.pdf](missing-id) after"
+        );
+        assert_eq!(
+            attachment_refs_as_labels("See !attach[a.pdf](abc)."),
+            "See [attachment: a.pdf]."
+        );
+        assert!(parse_attachment_refs("![image](https://example.com/a.png)").is_empty());
+        assert_eq!(
+            render_attachment_embed("Spec", "application/pdf", "media/my spec.pdf"),
+            "[Spec](<media/my spec.pdf>)"
+        );
+    }
+}
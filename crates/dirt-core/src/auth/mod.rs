@@ -7,6 +7,7 @@ use reqwest::{Client, RequestBuilder, StatusCode};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::util::retry::{Idempotency, RetryPolicy};
 use crate::util::unix_timestamp_now;
 
 const EXPIRY_SKEW_SECONDS: i64 = 60;
@@ -61,6 +62,28 @@ impl std::fmt::Debug for AuthSession {
     }
 }
 
+/// Session lifecycle state reported to UI watchers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// No session is stored.
+    SignedOut,
+    /// The session is usable, refreshed if it was near expiry.
+    Active(AuthSession),
+    /// The refresh token was rejected; the user must sign in again.
+    Expired(AuthUser),
+}
+
+impl SessionEvent {
+    /// The usable session, if any.
+    #[must_use]
+    pub fn into_session(self) -> Option<AuthSession> {
+        match self {
+            Self::Active(session) => Some(session),
+            Self::SignedOut | Self::Expired(_) => None,
+        }
+    }
+}
+
 /// Sign-up result from provider.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SignUpOutcome {
@@ -146,6 +169,7 @@ pub struct SupabaseAuthService<S: SessionPersistence = NoopSessionStore> {
     anon_key: String,
     client: Client,
     session_store: S,
+    retry: RetryPolicy,
 }
 
 impl SupabaseAuthService<NoopSessionStore> {
@@ -177,15 +201,29 @@ impl<S: SessionPersistence> SupabaseAuthService<S> {
             anon_key,
             client,
             session_store,
+            retry: RetryPolicy::DEFAULT,
         })
     }
 
+    /// Retries transient auth API failures with `policy` instead of
+    /// [`RetryPolicy::DEFAULT`].
+    #[must_use]
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
     /// Restore session from secure storage. If expired, refresh automatically.
-    #[allow(clippy::cognitive_complexity)]
     pub async fn restore_session(&self) -> AuthResult<Option<AuthSession>> {
+        Ok(self.restore_session_event().await?.into_session())
+    }
+
+    /// Restore session from secure storage, reporting whether a stored session
+    /// expired so the UI can prompt the user to sign in again.
+    pub async fn restore_session_event(&self) -> AuthResult<SessionEvent> {
         let Some(stored_session) = self.session_store.load()? else {
             tracing::info!("No persisted auth session found in secure storage");
-            return Ok(None);
+            return Ok(SessionEvent::SignedOut);
         };
 
         tracing::info!(
@@ -193,36 +231,43 @@ impl<S: SessionPersistence> SupabaseAuthService<S> {
             stored_session.user.email.as_deref().unwrap_or("unknown"),
             stored_session.expires_at,
         );
+        self.check_session(stored_session).await
+    }
 
-        if !stored_session.is_expired() {
-            tracing::info!("Persisted session is still valid, restoring");
-            return Ok(Some(stored_session));
+    /// Refresh `session` if it is about to expire.
+    ///
+    /// A refresh rejected by the server clears stored credentials and yields
+    /// [`SessionEvent::Expired`]; transient failures keep the current session.
+    #[allow(clippy::cognitive_complexity)]
+    pub async fn check_session(&self, session: AuthSession) -> AuthResult<SessionEvent> {
+        if !session.is_expired() {
+            tracing::debug!("Auth session is still valid");
+            return Ok(SessionEvent::Active(session));
         }
 
-        tracing::info!("Persisted session has expired, attempting token refresh");
-        match self.refresh_session(&stored_session.refresh_token).await {
+        tracing::info!("Auth session has expired, attempting token refresh");
+        match self.refresh_session(&session.refresh_token).await {
             Ok(refreshed) => {
                 tracing::info!(
                     "Session refresh succeeded, new expires_at={}",
                     refreshed.expires_at
                 );
-                self.session_store.save(&refreshed)?;
-                Ok(Some(refreshed))
+                Ok(SessionEvent::Active(refreshed))
             }
             Err(AuthError::Api(_)) => {
                 tracing::warn!(
                     "Session refresh rejected by server, clearing stored credentials: {}",
-                    stored_session.user.email.as_deref().unwrap_or("unknown"),
+                    session.user.email.as_deref().unwrap_or("unknown"),
                 );
                 self.session_store.clear()?;
-                Ok(None)
+                Ok(SessionEvent::Expired(session.user))
             }
             Err(error) => {
                 tracing::warn!(
                     "Session refresh failed due to transient error, keeping stored credentials: {}",
                     error,
                 );
-                Ok(Some(stored_session))
+                Ok(SessionEvent::Active(session))
             }
         }
     }
@@ -240,7 +285,10 @@ impl<S: SessionPersistence> SupabaseAuthService<S> {
                 .post(format!("{}/signup", self.auth_url))
                 .json(&payload),
         );
-        let response = self.send_auth_request(request).await?;
+        // A repeated sign-up would send a second confirmation email.
+        let response = self
+            .send_auth_request(request, Idempotency::NonIdempotent)
+            .await?;
         match response.into_session()? {
             Some(session) => {
                 self.session_store.save(&session)?;
@@ -264,7 +312,9 @@ impl<S: SessionPersistence> SupabaseAuthService<S> {
                 .query(&[("grant_type", "password")])
                 .json(&payload),
         );
-        let response = self.send_auth_request(request).await?;
+        let response = self
+            .send_auth_request(request, Idempotency::Idempotent)
+            .await?;
         let session = response.into_session()?.ok_or_else(|| {
             AuthError::Api("Sign-in response did not include an active session".to_string())
         })?;
@@ -289,7 +339,11 @@ impl<S: SessionPersistence> SupabaseAuthService<S> {
                 .query(&[("grant_type", "refresh_token")])
                 .json(&payload),
         );
-        let response = self.send_auth_request(request).await?;
+        // Refresh tokens are single use, so a refresh the server may have
+        // seen is not repeated.
+        let response = self
+            .send_auth_request(request, Idempotency::NonIdempotent)
+            .await?;
         let session = response.into_session()?.ok_or_else(|| {
             AuthError::Api("Refresh response did not include an active session".to_string())
         })?;
@@ -307,7 +361,7 @@ impl<S: SessionPersistence> SupabaseAuthService<S> {
                 .post(format!("{}/logout", self.auth_url))
                 .header("apikey", &self.anon_key)
                 .bearer_auth(access_token);
-            let response = request.send().await?;
+            let response = self.retry.send(request, Idempotency::Idempotent).await?;
             if response.status().is_success() || response.status() == StatusCode::UNAUTHORIZED {
                 Ok(())
             } else {
@@ -334,7 +388,7 @@ impl<S: SessionPersistence> SupabaseAuthService<S> {
     /// Verify Supabase auth configuration and return a summary for UI diagnostics.
     pub async fn verify_configuration(&self) -> AuthResult<AuthConfigStatus> {
         let request = self.public_request(self.client.get(format!("{}/settings", self.auth_url)));
-        let response = request.send().await?;
+        let response = self.retry.send(request, Idempotency::Idempotent).await?;
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -357,8 +411,12 @@ impl<S: SessionPersistence> SupabaseAuthService<S> {
             .header("Authorization", format!("Bearer {}", self.anon_key))
     }
 
-    async fn send_auth_request(&self, request: RequestBuilder) -> AuthResult<SupabaseAuthResponse> {
-        let response = request.send().await?;
+    async fn send_auth_request(
+        &self,
+        request: RequestBuilder,
+        idempotency: Idempotency,
+    ) -> AuthResult<SupabaseAuthResponse> {
+        let response = self.retry.send(request, idempotency).await?;
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -567,4 +625,53 @@ mod tests {
         assert!(session.is_expired_at(940));
         assert!(!session.is_expired_at(900));
     }
+
+    struct FixedSessionStore(Option<AuthSession>);
+
+    impl SessionPersistence for FixedSessionStore {
+        fn load(&self) -> AuthResult<Option<AuthSession>> {
+            Ok(self.0.clone())
+        }
+        fn save(&self, _session: &AuthSession) -> AuthResult<()> {
+            Ok(())
+        }
+        fn clear(&self) -> AuthResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn restore_session_event_reports_stored_session_state() {
+        let session = AuthSession {
+            access_token: "access".to_string(),
+            refresh_token: "refresh".to_string(),
+            expires_at: unix_timestamp_now() + 3_600,
+            user: AuthUser {
+                id: "user".to_string(),
+                email: Some("test@example.com".to_string()),
+            },
+        };
+        let signed_in = SupabaseAuthService::with_session_store(
+            "https://example.supabase.co",
+            "anon",
+            FixedSessionStore(Some(session.clone())),
+        )
+        .unwrap();
+        let signed_out = SupabaseAuthService::with_session_store(
+            "https://example.supabase.co",
+            "anon",
+            FixedSessionStore(None),
+        )
+        .unwrap();
+
+        let session_user = session.user.clone();
+        let event = signed_in.restore_session_event().await.unwrap();
+        assert_eq!(event, SessionEvent::Active(session.clone()));
+        assert_eq!(event.into_session(), Some(session));
+        assert_eq!(
+            signed_out.restore_session_event().await.unwrap(),
+            SessionEvent::SignedOut
+        );
+        assert_eq!(SessionEvent::Expired(session_user).into_session(), None);
+    }
 }
@@ -0,0 +1,384 @@
+//! Post-capture pipeline shared by quick-capture surfaces.
+//!
+//! The desktop hotkey window and `dirt add` hand each new note to
+//! [`run_post_capture`], which applies the actions enabled in [`Settings`]:
+//! adding a default tag, linking the note from today's daily note, copying the
+//! note id, and posting the note to a webhook unless it is private. A failed
+//! action is reported in the [`PostCaptureReport`]; it never undoes the
+//! capture or skips the rest.
+
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::models::{daily_note_with_entry, extract_tags, Note, NoteId, NoteSource, Settings};
+use crate::services::DatabaseService;
+use crate::util::{compact_text, is_http_url, normalize_text_option};
+
+/// Event name sent in capture webhook payloads.
+pub const CAPTURE_WEBHOOK_EVENT: &str = "note.captured";
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+const LINK_TITLE_MAX_CHARS: usize = 80;
+
+/// Actions to run after a quick capture, read from [`Settings`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PostCaptureActions {
+    /// Tag added to captures that do not already carry it.
+    pub default_tag: Option<String>,
+    /// Link each capture from today's daily note, creating it when missing.
+    pub link_daily_note: bool,
+    /// Copy the new note id to the clipboard.
+    pub copy_note_id: bool,
+    /// URL that receives a JSON POST for each capture that is not private.
+    pub webhook_url: Option<String>,
+}
+
+impl PostCaptureActions {
+    /// Actions configured in `settings`; invalid tags and URLs are ignored.
+    #[must_use]
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            default_tag: settings
+                .capture_default_tag
+                .as_deref()
+                .and_then(normalize_capture_tag),
+            link_daily_note: settings.capture_link_daily_note,
+            copy_note_id: settings.capture_copy_note_id,
+            webhook_url: normalize_text_option(settings.capture_webhook_url.clone())
+                .filter(|url| is_http_url(url)),
+        }
+    }
+
+    /// Whether no action is enabled.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.default_tag.is_none()
+            && !self.link_daily_note
+            && !self.copy_note_id
+            && self.webhook_url.is_none()
+    }
+}
+
+/// Destination for the copy-note-id action.
+pub trait Clipboard {
+    /// Replace the clipboard contents with `text`.
+    fn set_text(&mut self, text: &str) -> Result<(), String>;
+}
+
+/// What the pipeline did for one capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostCaptureReport {
+    /// The captured note, including any default tag that was added.
+    pub note: Note,
+    /// Daily note the capture was linked from.
+    pub daily_note_id: Option<NoteId>,
+    /// Whether the note id was copied to the clipboard.
+    pub copied_note_id: bool,
+    /// Whether the webhook accepted the capture.
+    pub webhook_delivered: bool,
+    /// One message per failed action.
+    pub failures: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CaptureWebhookPayload<'a> {
+    event: &'a str,
+    note_id: String,
+    content: &'a str,
+    tags: Vec<String>,
+    created_at: i64,
+    source: &'a str,
+}
+
+/// Run `actions` for a freshly captured `note`.
+///
+/// `today` picks the daily note; `source` is recorded for a daily note the
+/// pipeline creates and sent to the webhook. Without a `clipboard`, the copy
+/// action is skipped silently (e.g. in the CLI, which prints the id instead).
+pub async fn run_post_capture(
+    db: &DatabaseService,
+    actions: &PostCaptureActions,
+    note: Note,
+    source: NoteSource,
+    today: NaiveDate,
+    clipboard: Option<&mut dyn Clipboard>,
+) -> PostCaptureReport {
+    let mut report = PostCaptureReport {
+        note,
+        daily_note_id: None,
+        copied_note_id: false,
+        webhook_delivered: false,
+        failures: Vec::new(),
+    };
+
+    if let Some(tag) = actions.default_tag.as_deref() {
+        if let Some(content) = content_with_tag(&report.note.content, tag) {
+            match db.update_note(&report.note.id, &content).await {
+                Ok(note) => report.note = note,
+                Err(error) => report
+                    .failures
+                    .push(format!("Failed to add #{tag}: {error}")),
+            }
+        }
+    }
+
+    if actions.link_daily_note {
+        match link_from_daily_note(db, &report.note, source, today).await {
+            Ok(daily_note_id) => report.daily_note_id = Some(daily_note_id),
+            Err(error) => report
+                .failures
+                .push(format!("Failed to link daily note: {error}")),
+        }
+    }
+
+    if actions.copy_note_id {
+        if let Some(clipboard) = clipboard {
+            match clipboard.set_text(&report.note.id.to_string()) {
+                Ok(()) => report.copied_note_id = true,
+                Err(error) => report
+                    .failures
+                    .push(format!("Failed to copy note id: {error}")),
+            }
+        }
+    }
+
+    if let Some(url) = actions.webhook_url.as_deref() {
+        // Private notes never leave the app through the webhook.
+        let shareable = match db.export_privacy(false).await {
+            Ok(privacy) => privacy.allows(&report.note),
+            Err(error) => {
+                report.failures.push(format!("Webhook skipped: {error}"));
+                false
+            }
+        };
+        if shareable {
+            match send_capture_webhook(url, &report.note, source).await {
+                Ok(()) => report.webhook_delivered = true,
+                Err(error) => report.failures.push(format!("Webhook failed: {error}")),
+            }
+        }
+    }
+
+    report
+}
+
+/// Normalize a configured tag: trims a leading `#` and lowercases it.
+///
+/// Returns `None` when the result is not a valid tag name.
+#[must_use]
+pub fn normalize_capture_tag(raw: &str) -> Option<String> {
+    let name = raw.trim().trim_start_matches('#');
+    extract_tags(&format!("#{name}"))
+        .into_iter()
+        .find(|tag| tag.eq_ignore_ascii_case(name))
+}
+
+/// `content` with `#tag` appended, or `None` when the tag is already present.
+#[must_use]
+pub fn content_with_tag(content: &str, tag: &str) -> Option<String> {
+    if extract_tags(content).iter().any(|existing| existing == tag) {
+        return None;
+    }
+    let content = content.trim_end();
+    Some(if content.is_empty() {
+        format!("#{tag}")
+    } else {
+        format!("{content}\n\n#{tag}")
+    })
+}
+
+/// List line linking to `note` from a daily note.
+///
+/// Links by title when the first line is short and plain; otherwise by id, so
+/// the daily note never picks up the capture's tags.
+#[must_use]
+pub fn daily_note_link(note: &Note) -> String {
+    let title = note.title_preview(usize::MAX);
+    let title = title.trim();
+    let linkable = !title.is_empty()
+        && title.chars().count() <= LINK_TITLE_MAX_CHARS
+        && extract_tags(title).is_empty()
+        && !title.contains("[[")
+        && !title.contains("]]");
+    if linkable {
+        format!("- [[{title}]]")
+    } else {
+        format!("- [[{}]]", note.id)
+    }
+}
+
+/// Append `text` to the daily note for `today`, creating it when missing.
+///
+/// Used instead of creating a standalone note when quick capture is set to
+/// journal into the daily note.
+pub async fn append_to_daily_note(
+    db: &DatabaseService,
+    text: &str,
+    source: NoteSource,
+    today: NaiveDate,
+) -> crate::Result<Note> {
+    let daily = db.get_or_create_daily_note(today, source).await?;
+    db.update_note(&daily.id, &daily_note_with_entry(&daily.content, text))
+        .await
+}
+
+async fn link_from_daily_note(
+    db: &DatabaseService,
+    note: &Note,
+    source: NoteSource,
+    today: NaiveDate,
+) -> crate::Result<NoteId> {
+    let link = daily_note_link(note);
+    let daily = db.get_or_create_daily_note(today, source).await?;
+    if daily.content.lines().any(|line| line.trim() == link) {
+        return Ok(daily.id);
+    }
+    let content = daily_note_with_entry(&daily.content, &link);
+    Ok(db.update_note(&daily.id, &content).await?.id)
+}
+
+async fn send_capture_webhook(url: &str, note: &Note, source: NoteSource) -> Result<(), String> {
+    let payload = CaptureWebhookPayload {
+        event: CAPTURE_WEBHOOK_EVENT,
+        note_id: note.id.to_string(),
+        content: &note.content,
+        tags: note.tags(),
+        created_at: note.created_at,
+        source: source.as_str(),
+    };
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|error| format!("Failed to construct HTTP client: {error}"))?;
+    let response = client
+        .post(url)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|error| error.to_string())?;
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("HTTP {status}: {}", compact_text(&body)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{daily_note_title, is_daily_note};
+
+    struct RecordingClipboard(Vec<String>);
+
+    impl Clipboard for RecordingClipboard {
+        fn set_text(&mut self, text: &str) -> Result<(), String> {
+            self.0.push(text.to_string());
+            Ok(())
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn actions_from_settings_ignore_invalid_values() {
+        let settings = Settings {
+            capture_default_tag: Some(" #Inbox ".to_string()),
+            capture_webhook_url: Some("ftp://example.com/hook".to_string()),
+            ..Settings::default()
+        };
+        let actions = PostCaptureActions::from_settings(&settings);
+        assert_eq!(actions.default_tag.as_deref(), Some("inbox"));
+        assert_eq!(actions.webhook_url, None);
+        assert!(PostCaptureActions::from_settings(&Settings::default()).is_empty());
+
+        assert_eq!(normalize_capture_tag("2fast"), None);
+        assert_eq!(normalize_capture_tag("two words"), None);
+        assert_eq!(content_with_tag("Milk #inbox", "inbox"), None);
+        assert_eq!(
+            content_with_tag("Milk\n", "inbox").as_deref(),
+            Some("Milk\n\n#inbox")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pipeline_tags_links_daily_note_and_copies_id() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        let actions = PostCaptureActions {
+            default_tag: Some("inbox".to_string()),
+            link_daily_note: true,
+            copy_note_id: true,
+            webhook_url: None,
+        };
+        let mut clipboard = RecordingClipboard(Vec::new());
+        let today = date("2026-03-14");
+
+        let first = db.create_note("Call the bank").await.unwrap();
+        let report = run_post_capture(
+            &db,
+            &actions,
+            first,
+            NoteSource::Cli,
+            today,
+            Some(&mut clipboard),
+        )
+        .await;
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+        assert_eq!(report.note.content, "Call the bank\n\n#inbox");
+        assert!(report.copied_note_id);
+        assert_eq!(clipboard.0, vec![report.note.id.to_string()]);
+
+        let second = db.create_note("Buy stamps #inbox").await.unwrap();
+        let second_report =
+            run_post_capture(&db, &actions, second, NoteSource::Cli, today, None).await;
+        assert_eq!(second_report.note.content, "Buy stamps #inbox");
+        assert!(!second_report.copied_note_id);
+        assert_eq!(second_report.daily_note_id, report.daily_note_id);
+
+        let daily = db
+            .get_note(&report.daily_note_id.unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            daily.content,
+            format!(
+                "2026-03-14\n#daily\n\n- [[Call the bank]]\n- [[{}]]",
+                second_report.note.id
+            )
+        );
+        assert!(is_daily_note(&daily, &daily_note_title(today)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pipeline_does_not_post_private_notes() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        db.save_settings(&Settings {
+            no_export_tags: vec!["private".to_string()],
+            ..Settings::default()
+        })
+        .await
+        .unwrap();
+        let actions = PostCaptureActions {
+            webhook_url: Some("http://127.0.0.1:9/hook".to_string()),
+            ..PostCaptureActions::default()
+        };
+
+        let note = db.create_note("Therapy notes #private").await.unwrap();
+        let report = run_post_capture(
+            &db,
+            &actions,
+            note,
+            NoteSource::Cli,
+            date("2026-03-14"),
+            None,
+        )
+        .await;
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+        assert!(!report.webhook_delivered);
+    }
+}
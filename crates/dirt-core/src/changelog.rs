@@ -0,0 +1,166 @@
+//! Release notes compiled into every Dirt client
+//!
+//! `CHANGELOG.md` at the repository root is embedded at build time, so a
+//! client can tell users what changed since the version they last ran without
+//! going online. Each release is a `## <version> - <date>` heading followed by
+//! `- ` bullets, newest release first.
+//!
+//! The last version a user saw is kept in [`Settings`]. A database without one
+//! is treated as a fresh install: the running version is recorded and no notes
+//! are shown.
+//!
+//! [`Settings`]: crate::models::Settings
+
+use std::cmp::Ordering;
+
+use serde::Serialize;
+
+/// Release notes of every version, as written in `CHANGELOG.md`.
+pub const CHANGELOG: &str = include_str!("../../../CHANGELOG.md");
+
+/// Version of the running client.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// One `## <version>` section of the changelog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Release {
+    pub version: String,
+    /// Release date as written in the heading
+    pub date: Option<String>,
+    /// One entry per user-facing change
+    pub notes: Vec<String>,
+}
+
+/// Releases in `text`, in the order they appear.
+#[must_use]
+pub fn parse_changelog(text: &str) -> Vec<Release> {
+    let mut releases: Vec<Release> = Vec::new();
+    for line in text.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            let (version, date) = heading
+                .split_once(" - ")
+                .map_or((heading, None), |(version, date)| {
+                    (version, Some(date.trim().to_string()))
+                });
+            releases.push(Release {
+                version: version.trim().to_string(),
+                date,
+                notes: Vec::new(),
+            });
+            continue;
+        }
+        let Some(release) = releases.last_mut() else {
+            continue;
+        };
+        if let Some(note) = line.strip_prefix("- ") {
+            release.notes.push(note.trim().to_string());
+        } else if line.starts_with("  ") && !line.trim().is_empty() {
+            // Indented lines continue the previous bullet.
+            if let Some(note) = release.notes.last_mut() {
+                note.push(' ');
+                note.push_str(line.trim());
+            }
+        }
+    }
+    releases
+}
+
+/// Every release up to and including the running version, newest first.
+#[must_use]
+pub fn releases() -> Vec<Release> {
+    let mut releases: Vec<_> = parse_changelog(CHANGELOG)
+        .into_iter()
+        .filter(|release| compare_versions(&release.version, CURRENT_VERSION).is_le())
+        .collect();
+    releases.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    releases
+}
+
+/// Releases newer than `last_seen`, newest first. Empty when no version was
+/// recorded yet.
+#[must_use]
+pub fn unseen_releases(last_seen: Option<&str>) -> Vec<Release> {
+    let Some(last_seen) = last_seen else {
+        return Vec::new();
+    };
+    releases()
+        .into_iter()
+        .filter(|release| compare_versions(&release.version, last_seen).is_gt())
+        .collect()
+}
+
+/// Whether the running version should be recorded as seen: nothing was
+/// recorded yet, or an older version was.
+#[must_use]
+pub fn should_record_current_version(last_seen: Option<&str>) -> bool {
+    last_seen.map_or(true, |last_seen| {
+        compare_versions(last_seen, CURRENT_VERSION).is_lt()
+    })
+}
+
+/// Compare dotted version numbers component by component; missing
+/// components count as zero and a leading `v` is ignored.
+#[must_use]
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (version_parts(a), version_parts(b));
+    (0..a.len().max(b.len()))
+        .map(|index| {
+            let left = a.get(index).copied().unwrap_or(0);
+            left.cmp(&b.get(index).copied().unwrap_or(0))
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn version_parts(version: &str) -> Vec<u64> {
+    let version = version.trim();
+    version
+        .strip_prefix('v')
+        .unwrap_or(version)
+        .split(['.', '-', '+'])
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changelog_parses_releases_and_orders_versions() {
+        let releases = parse_changelog(
+            "# Changelog\n\nIntro.\n\n## 0.3.0 - 2026-11-01\n\n- Profiles\n- Exports that\n  wrap\n\n## 0.2.0\n- Rules\n",
+        );
+        assert_eq!(
+            releases,
+            [
+                Release {
+                    version: "0.3.0".to_string(),
+                    date: Some("2026-11-01".to_string()),
+                    notes: vec!["Profiles".to_string(), "Exports that wrap".to_string()],
+                },
+                Release {
+                    version: "0.2.0".to_string(),
+                    date: None,
+                    notes: vec!["Rules".to_string()],
+                },
+            ]
+        );
+
+        assert!(compare_versions("0.10.0", "0.9.1").is_gt());
+        assert!(compare_versions("v1.2", "1.2.0").is_eq());
+        assert!(compare_versions("1.2.0-beta.1", "1.2.1").is_lt());
+
+        let embedded = releases();
+        assert!(embedded
+            .iter()
+            .any(|release| release.version == CURRENT_VERSION));
+        assert!(unseen_releases(None).is_empty());
+        assert!(unseen_releases(Some(CURRENT_VERSION)).is_empty());
+        assert!(!unseen_releases(Some("0.0.1")).is_empty());
+        assert!(should_record_current_version(None));
+        assert!(should_record_current_version(Some("0.0.1")));
+        assert!(!should_record_current_version(Some(CURRENT_VERSION)));
+        assert!(!should_record_current_version(Some("999.0.0")));
+    }
+}
@@ -2,14 +2,24 @@
 //!
 //! Provides a unified `BootstrapConfig` struct used by desktop, mobile, and CLI
 //! to discover Supabase auth, Turso sync, and media API endpoints.
+//!
+//! Schema 2 manifests can override values per release channel and platform
+//! and name the oldest client version the backend still supports.
+
+mod source;
 
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
+pub use source::{ConfigSource, EnvConfig, MapConfig};
+
+use crate::changelog::compare_versions;
 use crate::util::{compact_text, is_http_url, normalize_text_option};
 
-const BOOTSTRAP_SCHEMA_VERSION: u32 = 1;
+const BOOTSTRAP_SCHEMA_VERSION: u32 = 2;
+const LEGACY_BOOTSTRAP_SCHEMA_VERSION: u32 = 1;
 const BOOTSTRAP_HTTP_TIMEOUT_SECS: u64 = 4;
 
 /// Build-provisioned client configuration.
@@ -29,6 +39,9 @@ pub struct BootstrapConfig {
     pub turso_sync_token_endpoint: Option<String>,
     #[serde(default)]
     pub dirt_api_base_url: Option<String>,
+    /// Oldest client version the backend supports, from a schema 2 manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_client_version: Option<String>,
 }
 
 impl BootstrapConfig {
@@ -46,6 +59,122 @@ impl BootstrapConfig {
             .strip_suffix("/v1/sync/token")
             .map(std::string::ToString::to_string)
     }
+
+    /// Fill fields missing from `self` with the values of `fallback`.
+    #[must_use]
+    pub fn or_fallback(self, fallback: Self) -> Self {
+        Self {
+            bootstrap_manifest_url: normalize_text_option(self.bootstrap_manifest_url)
+                .or(fallback.bootstrap_manifest_url),
+            supabase_url: normalize_text_option(self.supabase_url).or(fallback.supabase_url),
+            supabase_anon_key: normalize_text_option(self.supabase_anon_key)
+                .or(fallback.supabase_anon_key),
+            turso_sync_token_endpoint: normalize_text_option(self.turso_sync_token_endpoint)
+                .or(fallback.turso_sync_token_endpoint),
+            dirt_api_base_url: normalize_text_option(self.dirt_api_base_url)
+                .or(fallback.dirt_api_base_url),
+            min_client_version: normalize_text_option(self.min_client_version)
+                .or(fallback.min_client_version),
+        }
+    }
+
+    /// The minimum version when `client_version` is older than the backend
+    /// supports, meaning the client must be updated.
+    #[must_use]
+    pub fn update_required(&self, client_version: &str) -> Option<&str> {
+        self.min_client_version
+            .as_deref()
+            .filter(|minimum| compare_versions(client_version, minimum).is_lt())
+    }
+}
+
+/// Release channel a client build follows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl ReleaseChannel {
+    /// Parse a channel name (case-insensitive).
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "stable" => Some(Self::Stable),
+            "beta" => Some(Self::Beta),
+            _ => None,
+        }
+    }
+
+    /// Channel this build was made for, from `DIRT_RELEASE_CHANNEL` at build
+    /// time; stable when unset.
+    #[must_use]
+    pub fn current() -> Self {
+        option_env!("DIRT_RELEASE_CHANNEL")
+            .and_then(Self::parse)
+            .unwrap_or_default()
+    }
+
+    /// Name used as the key in manifest `channels`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+        }
+    }
+}
+
+/// Client kind, used as the key in manifest `platforms`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientPlatform {
+    Desktop,
+    Mobile,
+    Cli,
+}
+
+impl ClientPlatform {
+    /// Parse a platform name (case-insensitive).
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "desktop" => Some(Self::Desktop),
+            "mobile" => Some(Self::Mobile),
+            "cli" => Some(Self::Cli),
+            _ => None,
+        }
+    }
+
+    /// Name used as the key in manifest `platforms`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Desktop => "desktop",
+            Self::Mobile => "mobile",
+            Self::Cli => "cli",
+        }
+    }
+}
+
+/// Which channel and platform overrides of a manifest a client applies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ManifestTarget {
+    pub channel: ReleaseChannel,
+    pub platform: Option<ClientPlatform>,
+}
+
+impl ManifestTarget {
+    /// This build's channel on `platform`.
+    #[must_use]
+    pub fn current(platform: ClientPlatform) -> Self {
+        Self {
+            channel: ReleaseChannel::current(),
+            platform: Some(platform),
+        }
+    }
 }
 
 /// Resolve runtime bootstrap config by fetching the manifest URL.
@@ -54,24 +183,60 @@ impl BootstrapConfig {
 /// returned as errors instead of falling back to embedded values.
 pub async fn resolve_bootstrap_config(
     fallback: BootstrapConfig,
+) -> Result<BootstrapConfig, String> {
+    resolve_bootstrap_config_for(fallback, ManifestTarget::default()).await
+}
+
+/// Like [`resolve_bootstrap_config`], applying the manifest's overrides for
+/// `target`.
+pub async fn resolve_bootstrap_config_for(
+    fallback: BootstrapConfig,
+    target: ManifestTarget,
 ) -> Result<BootstrapConfig, String> {
     let Some(manifest_url) = normalize_text_option(fallback.bootstrap_manifest_url.clone()) else {
         return Ok(fallback);
     };
 
-    fetch_bootstrap_manifest(&manifest_url).await
+    fetch_bootstrap_manifest(&manifest_url, target).await
 }
 
-/// Parse a bootstrap manifest from a raw JSON payload.
+/// Parse a bootstrap manifest (schema 1 or 2) from a raw JSON payload, for
+/// the stable channel without platform overrides.
 ///
 /// Public for testability — callers can exercise parsing without network access.
 pub fn parse_bootstrap_manifest(
     payload: &str,
     manifest_url: &str,
+) -> Result<BootstrapConfig, String> {
+    parse_bootstrap_manifest_for(payload, manifest_url, ManifestTarget::default())
+}
+
+/// Parse a bootstrap manifest, applying its overrides for `target`.
+pub fn parse_bootstrap_manifest_for(
+    payload: &str,
+    manifest_url: &str,
+    target: ManifestTarget,
 ) -> Result<BootstrapConfig, String> {
     let manifest: ManagedBootstrapManifest = serde_json::from_str(payload)
         .map_err(|error| format!("invalid bootstrap manifest JSON: {error}"))?;
-    manifest.into_runtime_config(manifest_url)
+    manifest.into_runtime_config(Some(manifest_url), target)
+}
+
+/// Parse a bootstrap manifest baked into a binary at build time.
+///
+/// Packagers embed the same manifest JSON the backend serves; an empty payload
+/// means no manifest was embedded. The result carries no manifest URL, so it
+/// only fills gaps left by build-time values and never triggers a fetch.
+pub fn parse_embedded_bootstrap_manifest(
+    payload: &str,
+    target: ManifestTarget,
+) -> Result<Option<BootstrapConfig>, String> {
+    if payload.trim().is_empty() {
+        return Ok(None);
+    }
+    let manifest: ManagedBootstrapManifest = serde_json::from_str(payload)
+        .map_err(|error| format!("invalid embedded bootstrap manifest JSON: {error}"))?;
+    manifest.into_runtime_config(None, target).map(Some)
 }
 
 // ---------------------------------------------------------------------------
@@ -89,26 +254,69 @@ struct ManagedBootstrapManifest {
     #[serde(default)]
     turso_sync_token_endpoint: Option<String>,
     feature_flags: ManagedFeatureFlags,
+    #[serde(default)]
+    min_client_version: Option<String>,
+    /// Overrides keyed by [`ReleaseChannel::as_str`]; unknown channels are ignored
+    #[serde(default)]
+    channels: BTreeMap<String, ManifestOverrides>,
+    /// Overrides keyed by [`ClientPlatform::as_str`], applied after the channel's
+    #[serde(default)]
+    platforms: BTreeMap<String, ManifestOverrides>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
 struct ManagedFeatureFlags {
     managed_sync: bool,
     managed_media: bool,
 }
 
+/// Values a schema 2 manifest replaces for one channel or platform
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct ManifestOverrides {
+    #[serde(default)]
+    supabase_url: Option<String>,
+    #[serde(default)]
+    supabase_anon_key: Option<String>,
+    #[serde(default)]
+    api_base_url: Option<String>,
+    #[serde(default)]
+    turso_sync_token_endpoint: Option<String>,
+    #[serde(default)]
+    feature_flags: Option<ManagedFeatureFlags>,
+    #[serde(default)]
+    min_client_version: Option<String>,
+}
+
 impl ManagedBootstrapManifest {
-    fn into_runtime_config(self, manifest_url: &str) -> Result<BootstrapConfig, String> {
-        if self.schema_version != BOOTSTRAP_SCHEMA_VERSION {
-            return Err(format!(
-                "unsupported bootstrap schema_version {} (expected {})",
-                self.schema_version, BOOTSTRAP_SCHEMA_VERSION
-            ));
+    fn into_runtime_config(
+        mut self,
+        manifest_url: Option<&str>,
+        target: ManifestTarget,
+    ) -> Result<BootstrapConfig, String> {
+        match self.schema_version {
+            BOOTSTRAP_SCHEMA_VERSION => self.apply_overrides(target),
+            LEGACY_BOOTSTRAP_SCHEMA_VERSION => {
+                if self.min_client_version.is_some()
+                    || !self.channels.is_empty()
+                    || !self.platforms.is_empty()
+                {
+                    return Err(format!(
+                        "bootstrap schema_version {LEGACY_BOOTSTRAP_SCHEMA_VERSION} does not support min_client_version, channels, or platforms (use {BOOTSTRAP_SCHEMA_VERSION})"
+                    ));
+                }
+            }
+            other => {
+                return Err(format!(
+                    "unsupported bootstrap schema_version {other} (expected {LEGACY_BOOTSTRAP_SCHEMA_VERSION} or {BOOTSTRAP_SCHEMA_VERSION})"
+                ));
+            }
         }
         if self.manifest_version.trim().is_empty() {
             return Err("bootstrap manifest_version must not be empty".to_string());
         }
+        let min_client_version = normalize_text_option(self.min_client_version.take());
 
         let supabase_url = normalize_required_http_url(self.supabase_url, "supabase_url")?;
         let supabase_anon_key =
@@ -135,16 +343,48 @@ impl ManagedBootstrapManifest {
             };
 
         Ok(BootstrapConfig {
-            bootstrap_manifest_url: Some(manifest_url.to_string()),
+            bootstrap_manifest_url: manifest_url.map(str::to_string),
             supabase_url: Some(supabase_url),
             supabase_anon_key: Some(supabase_anon_key),
             turso_sync_token_endpoint: sync_endpoint,
             dirt_api_base_url: api_base_for_clients,
+            min_client_version,
         })
     }
+
+    /// Replace base values with the target channel's, then the platform's.
+    fn apply_overrides(&mut self, target: ManifestTarget) {
+        let channel = self.channels.remove(target.channel.as_str());
+        let platform = target
+            .platform
+            .and_then(|platform| self.platforms.remove(platform.as_str()));
+        for overrides in [channel, platform].into_iter().flatten() {
+            if let Some(value) = overrides.supabase_url {
+                self.supabase_url = value;
+            }
+            if let Some(value) = overrides.supabase_anon_key {
+                self.supabase_anon_key = value;
+            }
+            if let Some(value) = overrides.api_base_url {
+                self.api_base_url = value;
+            }
+            if let Some(value) = overrides.turso_sync_token_endpoint {
+                self.turso_sync_token_endpoint = Some(value);
+            }
+            if let Some(value) = overrides.feature_flags {
+                self.feature_flags = value;
+            }
+            if let Some(value) = overrides.min_client_version {
+                self.min_client_version = Some(value);
+            }
+        }
+    }
 }
 
-async fn fetch_bootstrap_manifest(url: &str) -> Result<BootstrapConfig, String> {
+async fn fetch_bootstrap_manifest(
+    url: &str,
+    target: ManifestTarget,
+) -> Result<BootstrapConfig, String> {
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(BOOTSTRAP_HTTP_TIMEOUT_SECS))
         .build()
@@ -173,7 +413,7 @@ async fn fetch_bootstrap_manifest(url: &str) -> Result<BootstrapConfig, String>
         .text()
         .await
         .map_err(|error| format!("failed to read bootstrap response body: {error}"))?;
-    parse_bootstrap_manifest(&body, url)
+    parse_bootstrap_manifest_for(&body, url, target)
 }
 
 fn normalize_required_value(raw: String, field: &str) -> Result<String, String> {
@@ -277,4 +517,99 @@ mod tests {
             Some("https://api.example.com")
         );
     }
+
+    #[test]
+    fn parse_manifest_applies_channel_and_platform_overrides() {
+        let payload = r#"
+        {
+          "schema_version": 2,
+          "manifest_version": "v3",
+          "supabase_url": "https://project.supabase.co",
+          "supabase_anon_key": "anon",
+          "api_base_url": "https://api.example.com",
+          "feature_flags": {
+            "managed_sync": true,
+            "managed_media": true
+          },
+          "min_client_version": "0.4.0",
+          "channels": {
+            "beta": { "api_base_url": "https://beta.example.com" },
+            "nightly": { "api_base_url": "https://nightly.example.com" }
+          },
+          "platforms": {
+            "mobile": { "min_client_version": "0.5.0" }
+          }
+        }
+        "#;
+        let url = "https://api.example.com/v1/bootstrap";
+
+        let stable = parse_bootstrap_manifest_for(payload, url, ManifestTarget::default())
+            .expect("manifest should parse");
+        assert_eq!(
+            stable.dirt_api_base_url.as_deref(),
+            Some("https://api.example.com")
+        );
+        assert_eq!(stable.update_required("0.3.9"), Some("0.4.0"));
+        assert_eq!(stable.update_required("0.4.0"), None);
+
+        let beta_mobile = ManifestTarget {
+            channel: ReleaseChannel::Beta,
+            platform: Some(ClientPlatform::Mobile),
+        };
+        let beta =
+            parse_bootstrap_manifest_for(payload, url, beta_mobile).expect("manifest should parse");
+        assert_eq!(
+            beta.turso_sync_token_endpoint.as_deref(),
+            Some("https://beta.example.com/v1/sync/token")
+        );
+        assert_eq!(beta.update_required("0.4.2"), Some("0.5.0"));
+
+        let legacy = payload.replace("\"schema_version\": 2", "\"schema_version\": 1");
+        let error = parse_bootstrap_manifest(&legacy, url).unwrap_err();
+        assert!(error.contains("schema_version"));
+    }
+
+    #[test]
+    fn embedded_manifest_fills_gaps_without_a_manifest_url() {
+        assert_eq!(
+            parse_embedded_bootstrap_manifest("  \n", ManifestTarget::default()).unwrap(),
+            None
+        );
+        assert!(parse_embedded_bootstrap_manifest("{}", ManifestTarget::default()).is_err());
+
+        let payload = r#"
+        {
+          "schema_version": 1,
+          "manifest_version": "acme-1",
+          "supabase_url": "https://acme.supabase.co",
+          "supabase_anon_key": "acme-anon",
+          "api_base_url": "https://api.acme.example",
+          "feature_flags": {
+            "managed_sync": true,
+            "managed_media": true
+          }
+        }
+        "#;
+        let embedded = parse_embedded_bootstrap_manifest(payload, ManifestTarget::default())
+            .unwrap()
+            .expect("manifest should be embedded");
+        assert_eq!(embedded.bootstrap_manifest_url, None);
+
+        let build_time = BootstrapConfig {
+            supabase_url: Some("https://override.supabase.co".to_string()),
+            supabase_anon_key: Some("  ".to_string()),
+            ..BootstrapConfig::default()
+        };
+        let resolved = build_time.or_fallback(embedded);
+        assert_eq!(
+            resolved.supabase_url.as_deref(),
+            Some("https://override.supabase.co")
+        );
+        assert_eq!(resolved.supabase_anon_key.as_deref(), Some("acme-anon"));
+        assert_eq!(
+            resolved.turso_sync_token_endpoint.as_deref(),
+            Some("https://api.acme.example/v1/sync/token")
+        );
+        assert_eq!(resolved.bootstrap_manifest_url, None);
+    }
 }
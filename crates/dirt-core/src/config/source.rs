@@ -0,0 +1,94 @@
+//! Injectable lookups for environment-style configuration.
+//!
+//! Code that used to read `std::env` directly takes a [`ConfigSource`]
+//! instead. Production callers pass [`EnvConfig`]; tests pass a [`MapConfig`]
+//! so they never mutate the (process-global) environment.
+
+use std::collections::HashMap;
+
+use crate::util::normalize_text_option;
+
+/// Source of configuration values keyed by environment variable name.
+pub trait ConfigSource {
+    /// Raw value for `key`, or `None` when unset.
+    fn var(&self, key: &str) -> Option<String>;
+
+    /// Trimmed value for `key`; blank values count as unset.
+    fn text_var(&self, key: &str) -> Option<String> {
+        normalize_text_option(self.var(key))
+    }
+}
+
+impl<T: ConfigSource + ?Sized> ConfigSource for &T {
+    fn var(&self, key: &str) -> Option<String> {
+        (**self).var(key)
+    }
+}
+
+/// Reads the process environment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EnvConfig;
+
+impl ConfigSource for EnvConfig {
+    fn var(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+}
+
+/// Fixed set of values, for tests and embedders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MapConfig {
+    values: HashMap<String, String>,
+}
+
+impl MapConfig {
+    /// Empty source; every lookup returns `None`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace `key`.
+    #[must_use]
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.values.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for MapConfig {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            values: iter
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        }
+    }
+}
+
+impl ConfigSource for MapConfig {
+    fn var(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_config_looks_up_and_trims_values() {
+        let source = MapConfig::new()
+            .with("DIRT_SET", "  value ")
+            .with("DIRT_BLANK", "   ");
+
+        assert_eq!(source.var("DIRT_SET").as_deref(), Some("  value "));
+        assert_eq!(source.text_var("DIRT_SET").as_deref(), Some("value"));
+        assert_eq!(source.text_var("DIRT_BLANK"), None);
+        assert_eq!(source.var("DIRT_MISSING"), None);
+
+        let collected: MapConfig = [("DIRT_SET", "other")].into_iter().collect();
+        assert_eq!((&collected).var("DIRT_SET").as_deref(), Some("other"));
+    }
+}
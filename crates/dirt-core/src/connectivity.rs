@@ -0,0 +1,256 @@
+//! Offline detection for periodic network work.
+//!
+//! Clients share one [`ConnectivityMonitor`] between their sync loop,
+//! session refresh, and media operations. It learns the network state from
+//! platform signals (an OS "network changed" callback) and from probing a
+//! lightweight endpoint after requests fail. While offline, periodic work
+//! asks [`ConnectivityMonitor::should_attempt`] and skips its tick instead of
+//! retrying and logging the same error every interval; probes back off from
+//! [`MIN_OFFLINE_PROBE_SECS`] to [`MAX_OFFLINE_PROBE_SECS`] until the
+//! network returns.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use crate::config::BootstrapConfig;
+use crate::util::normalize_text_option;
+
+/// Wait before the first probe after going offline.
+pub const MIN_OFFLINE_PROBE_SECS: i64 = 15;
+/// Longest wait between probes while offline.
+pub const MAX_OFFLINE_PROBE_SECS: i64 = 300;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whether the backend can currently be reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Nothing has been observed yet; work is attempted.
+    Unknown,
+    Online,
+    Offline,
+}
+
+impl fmt::Display for Connectivity {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Unknown => "unknown",
+            Self::Online => "online",
+            Self::Offline => "offline",
+        })
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    status: Connectivity,
+    /// Unix timestamp (seconds) of the next probe while offline.
+    next_probe_at: i64,
+    /// Wait before the probe after next, doubled after each failed probe.
+    probe_interval_secs: i64,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    probe_url: Mutex<Option<String>>,
+    client: reqwest::Client,
+}
+
+/// Shared network state; clones observe and update the same state.
+#[derive(Debug, Clone)]
+pub struct ConnectivityMonitor {
+    shared: Arc<Shared>,
+}
+
+impl Default for ConnectivityMonitor {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl ConnectivityMonitor {
+    /// Creates a monitor that probes `probe_url`; without one, only platform
+    /// signals and recorded outcomes change the state.
+    #[must_use]
+    pub fn new(probe_url: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(PROBE_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(State {
+                    status: Connectivity::Unknown,
+                    next_probe_at: 0,
+                    probe_interval_secs: MIN_OFFLINE_PROBE_SECS,
+                }),
+                probe_url: Mutex::new(normalize_text_option(probe_url)),
+                client,
+            }),
+        }
+    }
+
+    /// Probes the Dirt API health check, or the Supabase URL when no managed
+    /// API is configured.
+    pub fn set_probe_url_from_bootstrap(&self, bootstrap: &BootstrapConfig) {
+        let url = bootstrap
+            .managed_api_base_url()
+            .map(|base_url| format!("{}/healthz", base_url.trim_end_matches('/')))
+            .or_else(|| normalize_text_option(bootstrap.supabase_url.clone()));
+        *self
+            .shared
+            .probe_url
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = url;
+    }
+
+    /// The last observed network state.
+    #[must_use]
+    pub fn status(&self) -> Connectivity {
+        self.lock().status
+    }
+
+    /// Whether the network was last seen unavailable.
+    #[must_use]
+    pub fn is_offline(&self) -> bool {
+        self.status() == Connectivity::Offline
+    }
+
+    /// Whether periodic network work should run at `now` (Unix seconds).
+    ///
+    /// Always `true` unless offline; while offline, `true` once each probe
+    /// interval so the caller can [`Self::probe`] before working.
+    #[must_use]
+    pub fn should_attempt(&self, now: i64) -> bool {
+        let state = self.lock();
+        state.status != Connectivity::Offline || now >= state.next_probe_at
+    }
+
+    /// Records a platform signal that the network became available or went
+    /// away.
+    pub fn set_platform_status(&self, online: bool, now: i64) {
+        if online {
+            self.record_online();
+        } else {
+            self.record_offline(now);
+        }
+    }
+
+    /// Records that a request reached the server.
+    pub fn record_online(&self) {
+        let mut state = self.lock();
+        if state.status == Connectivity::Offline {
+            tracing::info!("Network is reachable again");
+        }
+        state.status = Connectivity::Online;
+        state.probe_interval_secs = MIN_OFFLINE_PROBE_SECS;
+    }
+
+    /// Records that the network is unreachable; the next probe is due after
+    /// the current probe interval, which then doubles.
+    pub fn record_offline(&self, now: i64) {
+        let mut state = self.lock();
+        if state.status != Connectivity::Offline {
+            tracing::info!("Network is unreachable; pausing network work");
+            state.probe_interval_secs = MIN_OFFLINE_PROBE_SECS;
+        }
+        state.status = Connectivity::Offline;
+        state.next_probe_at = now.saturating_add(state.probe_interval_secs);
+        state.probe_interval_secs = state
+            .probe_interval_secs
+            .saturating_mul(2)
+            .min(MAX_OFFLINE_PROBE_SECS);
+    }
+
+    /// Checks whether the probe URL answers. Any HTTP response counts as
+    /// online; only a failure to connect counts as offline. Without a probe
+    /// URL the state is left as it is.
+    pub async fn probe(&self, now: i64) -> Connectivity {
+        let url = self
+            .shared
+            .probe_url
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let Some(url) = url else {
+            return self.status();
+        };
+
+        match self.shared.client.get(&url).send().await {
+            Ok(_) => self.record_online(),
+            Err(error) => {
+                tracing::debug!("Connectivity probe failed: {error}");
+                self.record_offline(now);
+            }
+        }
+        self.status()
+    }
+
+    /// Probes first when offline, for work the user asked for: returns
+    /// whether to go ahead.
+    pub async fn ensure_online(&self, now: i64) -> bool {
+        !self.is_offline() || self.probe(now).await != Connectivity::Offline
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_skips_work_until_the_next_probe_with_backoff() {
+        let monitor = ConnectivityMonitor::default();
+        assert_eq!(monitor.status(), Connectivity::Unknown);
+        assert!(monitor.should_attempt(0));
+
+        monitor.record_offline(100);
+        assert!(monitor.is_offline());
+        assert!(!monitor.should_attempt(100));
+        assert!(monitor.should_attempt(100 + MIN_OFFLINE_PROBE_SECS));
+
+        // A failed probe doubles the wait.
+        monitor.record_offline(200);
+        assert!(!monitor.should_attempt(200 + MIN_OFFLINE_PROBE_SECS));
+        assert!(monitor.should_attempt(200 + MIN_OFFLINE_PROBE_SECS * 2));
+
+        for _ in 0..10 {
+            monitor.record_offline(300);
+        }
+        assert!(monitor.should_attempt(300 + MAX_OFFLINE_PROBE_SECS));
+
+        monitor.set_platform_status(true, 400);
+        assert_eq!(monitor.status(), Connectivity::Online);
+        assert!(monitor.should_attempt(400));
+
+        // Going offline again starts from the shortest wait.
+        monitor.record_offline(500);
+        assert!(monitor.should_attempt(500 + MIN_OFFLINE_PROBE_SECS));
+    }
+
+    #[test]
+    fn clones_share_state() {
+        let monitor = ConnectivityMonitor::default();
+        let clone = monitor.clone();
+        clone.set_platform_status(false, 0);
+        assert!(monitor.is_offline());
+    }
+
+    #[tokio::test]
+    async fn probe_without_url_keeps_state() {
+        let monitor = ConnectivityMonitor::default();
+        assert_eq!(monitor.probe(0).await, Connectivity::Unknown);
+        assert!(monitor.ensure_online(0).await);
+
+        monitor.record_offline(0);
+        assert!(!monitor.ensure_online(0).await);
+    }
+}
@@ -0,0 +1,293 @@
+//! Client-side encryption of note content and attachment bytes.
+//!
+//! A 256-bit key is derived from the user's passphrase with Argon2id and a
+//! random salt. Note content is stored as a ChaCha20-Poly1305 envelope, so the
+//! database, and everything sync carries, only ever holds ciphertext. The
+//! salt and a verifier live in the `encryption_params` table and sync with the
+//! notes, letting another device unlock with the same passphrase.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::error::{Error, Result};
+use crate::util::{decode_hex, encode_hex};
+
+/// Prefix of note content encrypted with a [`ContentKey`].
+pub const ENCRYPTED_MARKER: &str = "dirt-enc:v1:";
+
+/// Header of attachment bytes encrypted with a [`ContentKey`].
+const ENCRYPTED_BYTES_MAGIC: &[u8] = b"DIRTENC1";
+
+/// Plaintext of the verifier that proves a passphrase is right.
+const VERIFIER_PLAINTEXT: &str = "dirt-passphrase-check";
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+
+/// Whether stored note content is an encrypted envelope.
+#[must_use]
+pub fn is_encrypted(content: &str) -> bool {
+    content.starts_with(ENCRYPTED_MARKER)
+}
+
+/// Whether attachment bytes were encrypted with a [`ContentKey`].
+#[must_use]
+pub fn is_encrypted_bytes(bytes: &[u8]) -> bool {
+    bytes.starts_with(ENCRYPTED_BYTES_MAGIC)
+}
+
+/// Key derived from the user's passphrase.
+#[derive(Clone)]
+pub struct ContentKey {
+    key: Key,
+}
+
+impl std::fmt::Debug for ContentKey {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter
+            .debug_struct("ContentKey")
+            .field("key", &"[REDACTED]")
+            .finish()
+    }
+}
+
+impl ContentKey {
+    /// Derive the key for `passphrase` and `salt`.
+    pub fn derive(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(Error::InvalidInput("Passphrase cannot be empty".into()));
+        }
+        let mut key = [0_u8; KEY_LEN];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|error| Error::InvalidInput(format!("Failed to derive key: {error}")))?;
+        Ok(Self {
+            key: Key::clone_from_slice(&key),
+        })
+    }
+
+    /// Generate a random key, for content only this device decrypts.
+    pub(crate) fn generate() -> Self {
+        Self {
+            key: ChaCha20Poly1305::generate_key(&mut OsRng),
+        }
+    }
+
+    /// Key from raw bytes, or `None` when they are not a key.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        (bytes.len() == KEY_LEN).then(|| Self {
+            key: Key::clone_from_slice(bytes),
+        })
+    }
+
+    /// Raw key bytes, for storing a generated key.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        self.key.as_slice()
+    }
+
+    /// Encrypt note content into an envelope starting with
+    /// [`ENCRYPTED_MARKER`].
+    pub fn encrypt_text(&self, plaintext: &str) -> Result<String> {
+        let (nonce, ciphertext) = self.seal(plaintext.as_bytes())?;
+        Ok(format!(
+            "{ENCRYPTED_MARKER}{}:{}",
+            encode_hex(&nonce),
+            encode_hex(&ciphertext)
+        ))
+    }
+
+    /// Decrypt an envelope produced by [`Self::encrypt_text`].
+    pub fn decrypt_text(&self, content: &str) -> Result<String> {
+        let (nonce, ciphertext) = content
+            .strip_prefix(ENCRYPTED_MARKER)
+            .and_then(|payload| payload.split_once(':'))
+            .and_then(|(nonce, ciphertext)| Some((decode_hex(nonce)?, decode_hex(ciphertext)?)))
+            .filter(|(nonce, _)| nonce.len() == NONCE_LEN)
+            .ok_or_else(|| Error::InvalidInput("Malformed encrypted note".into()))?;
+        let plaintext = self.open(&nonce, &ciphertext)?;
+        String::from_utf8(plaintext)
+            .map_err(|_| Error::InvalidInput("Decrypted note is not valid UTF-8".into()))
+    }
+
+    /// Encrypt attachment bytes: magic header, nonce, then ciphertext.
+    pub fn encrypt_bytes(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let (nonce, ciphertext) = self.seal(plaintext)?;
+        let mut out =
+            Vec::with_capacity(ENCRYPTED_BYTES_MAGIC.len() + NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(ENCRYPTED_BYTES_MAGIC);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// Decrypt bytes produced by [`Self::encrypt_bytes`].
+    pub fn decrypt_bytes(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        let payload = bytes
+            .strip_prefix(ENCRYPTED_BYTES_MAGIC)
+            .filter(|payload| payload.len() >= NONCE_LEN)
+            .ok_or_else(|| Error::InvalidInput("Malformed encrypted attachment".into()))?;
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        self.open(nonce, ciphertext)
+    }
+
+    fn seal(&self, plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = ChaCha20Poly1305::new(&self.key)
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| Error::InvalidInput("Failed to encrypt content".into()))?;
+        Ok((nonce.to_vec(), ciphertext))
+    }
+
+    fn open(&self, nonce: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        ChaCha20Poly1305::new(&self.key)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| Error::InvalidInput("Wrong passphrase or corrupted content".into()))
+    }
+}
+
+/// Salt and passphrase verifier shared by every device of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptionParams {
+    /// Argon2 salt, hex encoded
+    pub salt: String,
+    /// [`VERIFIER_PLAINTEXT`] encrypted with the derived key
+    pub verifier: String,
+    /// When encryption was turned on (Unix ms)
+    pub created_at: i64,
+}
+
+impl EncryptionParams {
+    /// Fresh parameters for `passphrase`, with the key they unlock.
+    pub fn create(passphrase: &str, created_at: i64) -> Result<(Self, ContentKey)> {
+        let mut salt = [0_u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = ContentKey::derive(passphrase, &salt)?;
+        let params = Self {
+            salt: encode_hex(&salt),
+            verifier: key.encrypt_text(VERIFIER_PLAINTEXT)?,
+            created_at,
+        };
+        Ok((params, key))
+    }
+
+    /// Derive the key for `passphrase`, failing when it is not the one
+    /// these parameters were created with.
+    pub fn unlock(&self, passphrase: &str) -> Result<ContentKey> {
+        let salt = decode_hex(&self.salt)
+            .ok_or_else(|| Error::InvalidInput("Corrupt encryption salt".into()))?;
+        let key = ContentKey::derive(passphrase, &salt)?;
+        match key.decrypt_text(&self.verifier) {
+            Ok(check) if check == VERIFIER_PLAINTEXT => Ok(key),
+            _ => Err(Error::InvalidInput("Wrong passphrase".into())),
+        }
+    }
+}
+
+/// Encryption state of an open database.
+#[derive(Debug, Clone, Default)]
+pub enum ContentEncryption {
+    /// Notes are stored in plaintext
+    #[default]
+    Off,
+    /// Notes are encrypted and no key has been entered; reads return the
+    /// envelopes and writes are refused
+    Locked,
+    /// Notes are encrypted and decrypted transparently with this key
+    Unlocked(ContentKey),
+}
+
+impl ContentEncryption {
+    /// Note content as it should be stored.
+    pub fn encrypt_content(&self, content: &str) -> Result<String> {
+        match self {
+            Self::Off => Ok(content.to_string()),
+            Self::Locked => Err(Error::InvalidInput(
+                "Notes are encrypted; unlock them with your passphrase first".into(),
+            )),
+            Self::Unlocked(key) => key.encrypt_text(content),
+        }
+    }
+
+    /// Stored note content as it should be shown; envelopes stay as they are
+    /// while locked.
+    pub fn decrypt_content(&self, stored: String) -> Result<String> {
+        match self {
+            Self::Unlocked(key) if is_encrypted(&stored) => key.decrypt_text(&stored),
+            _ => Ok(stored),
+        }
+    }
+
+    /// Whether notes are encrypted (locked or not).
+    pub const fn is_enabled(&self) -> bool {
+        !matches!(self, Self::Off)
+    }
+
+    /// Whether encrypted notes can be read and written.
+    pub const fn is_unlocked(&self) -> bool {
+        matches!(self, Self::Unlocked(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_and_bytes_round_trip() {
+        let (params, key) = EncryptionParams::create("correct horse", 1).unwrap();
+
+        let envelope = key.encrypt_text("Secret plans #work").unwrap();
+        assert!(is_encrypted(&envelope));
+        assert!(!envelope.contains("Secret"));
+        assert_eq!(key.decrypt_text(&envelope).unwrap(), "Secret plans #work");
+
+        let bytes = key.encrypt_bytes(b"\x89PNG").unwrap();
+        assert!(is_encrypted_bytes(&bytes));
+        assert_eq!(key.decrypt_bytes(&bytes).unwrap(), b"\x89PNG");
+
+        // Another device derives the same key from the synced parameters.
+        let unlocked = params.unlock("correct horse").unwrap();
+        assert_eq!(
+            unlocked.decrypt_text(&envelope).unwrap(),
+            "Secret plans #work"
+        );
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let (params, key) = EncryptionParams::create("correct horse", 1).unwrap();
+        assert!(params.unlock("battery staple").is_err());
+        assert!(ContentKey::derive("", b"saltsaltsalt").is_err());
+
+        let other = ContentKey::derive("battery staple", b"saltsaltsalt").unwrap();
+        let envelope = key.encrypt_text("Secret").unwrap();
+        assert!(other.decrypt_text(&envelope).is_err());
+    }
+
+    #[test]
+    fn locked_state_refuses_writes_and_passes_envelopes_through() {
+        let (_, key) = EncryptionParams::create("correct horse", 1).unwrap();
+        let envelope = key.encrypt_text("Secret").unwrap();
+
+        assert!(ContentEncryption::Locked.encrypt_content("New").is_err());
+        assert_eq!(
+            ContentEncryption::Locked
+                .decrypt_content(envelope.clone())
+                .unwrap(),
+            envelope
+        );
+        assert_eq!(
+            ContentEncryption::Unlocked(key)
+                .decrypt_content(envelope)
+                .unwrap(),
+            "Secret"
+        );
+        assert_eq!(
+            ContentEncryption::Off.encrypt_content("Plain").unwrap(),
+            "Plain"
+        );
+    }
+}
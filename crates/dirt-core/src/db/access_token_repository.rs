@@ -0,0 +1,224 @@
+//! Personal access token registry implementation
+
+use crate::error::Result;
+use crate::models::{AccessScope, AccessToken};
+use libsql::Connection;
+
+/// Trait for personal access token registry operations (async)
+#[allow(async_fn_in_trait)]
+pub trait AccessTokenRepository {
+    /// Record a newly issued token
+    async fn create_access_token(&self, token: &AccessToken) -> Result<()>;
+
+    /// Look up a token by ID
+    async fn get_access_token(&self, id: &str) -> Result<Option<AccessToken>>;
+
+    /// List a user's tokens, newest first (including revoked and expired ones)
+    async fn list_access_tokens(&self, user_id: &str) -> Result<Vec<AccessToken>>;
+
+    /// Revoke one of a user's tokens. Returns `false` if no active token matched.
+    async fn revoke_access_token(&self, user_id: &str, id: &str, now: i64) -> Result<bool>;
+
+    /// Record a use of the token, at most once per `min_interval` seconds
+    async fn touch_access_token(&self, id: &str, now: i64, min_interval: i64) -> Result<()>;
+}
+
+/// libSQL implementation of `AccessTokenRepository`
+pub struct LibSqlAccessTokenRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlAccessTokenRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Parse a token from a database row
+    fn parse_token(row: &libsql::Row) -> Result<AccessToken> {
+        let scopes: String = row.get(3)?;
+        Ok(AccessToken {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            name: row.get(2)?,
+            scopes: scopes
+                .split_whitespace()
+                .map(str::parse::<AccessScope>)
+                .collect::<Result<_>>()?,
+            created_at: row.get(4)?,
+            expires_at: row.get(5)?,
+            last_used_at: row.get(6)?,
+            revoked_at: row.get(7)?,
+        })
+    }
+}
+
+impl AccessTokenRepository for LibSqlAccessTokenRepository<'_> {
+    async fn create_access_token(&self, token: &AccessToken) -> Result<()> {
+        let scopes = token
+            .scopes
+            .iter()
+            .map(|scope| scope.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        self.conn
+            .execute(
+                "INSERT INTO access_tokens
+                    (id, user_id, name, scopes, created_at, expires_at, last_used_at, revoked_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                libsql::params![
+                    token.id.as_str(),
+                    token.user_id.as_str(),
+                    token.name.as_str(),
+                    scopes,
+                    token.created_at,
+                    token.expires_at,
+                    token.last_used_at,
+                    token.revoked_at
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_access_token(&self, id: &str) -> Result<Option<AccessToken>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT id, user_id, name, scopes, created_at, expires_at, last_used_at, revoked_at
+                 FROM access_tokens WHERE id = ?",
+                [id],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(Self::parse_token(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list_access_tokens(&self, user_id: &str) -> Result<Vec<AccessToken>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT id, user_id, name, scopes, created_at, expires_at, last_used_at, revoked_at
+                 FROM access_tokens WHERE user_id = ?
+                 ORDER BY created_at DESC, id DESC",
+                [user_id],
+            )
+            .await?;
+
+        let mut tokens = Vec::new();
+        while let Some(row) = rows.next().await? {
+            tokens.push(Self::parse_token(&row)?);
+        }
+
+        Ok(tokens)
+    }
+
+    async fn revoke_access_token(&self, user_id: &str, id: &str, now: i64) -> Result<bool> {
+        let rows_affected = self
+            .conn
+            .execute(
+                "UPDATE access_tokens SET revoked_at = ?
+                 WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
+                libsql::params![now, id, user_id],
+            )
+            .await?;
+        Ok(rows_affected > 0)
+    }
+
+    async fn touch_access_token(&self, id: &str, now: i64, min_interval: i64) -> Result<()> {
+        self.conn
+            .execute(
+                "UPDATE access_tokens SET last_used_at = ?
+                 WHERE id = ? AND (last_used_at IS NULL OR last_used_at <= ?)",
+                libsql::params![now, id, now - min_interval],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    async fn setup() -> Database {
+        Database::open_in_memory().await.unwrap()
+    }
+
+    fn token(id: &str, user_id: &str, created_at: i64) -> AccessToken {
+        AccessToken {
+            id: id.to_string(),
+            user_id: user_id.to_string(),
+            name: format!("{id} script"),
+            scopes: vec![AccessScope::NotesRead, AccessScope::MediaRead],
+            created_at,
+            expires_at: created_at + 3_600,
+            last_used_at: None,
+            revoked_at: None,
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_create_get_and_list_tokens() {
+        let db = setup().await;
+        let repo = LibSqlAccessTokenRepository::new(db.connection());
+
+        repo.create_access_token(&token("a", "user-1", 100))
+            .await
+            .unwrap();
+        repo.create_access_token(&token("b", "user-1", 200))
+            .await
+            .unwrap();
+        repo.create_access_token(&token("c", "user-2", 300))
+            .await
+            .unwrap();
+
+        let fetched = repo.get_access_token("a").await.unwrap().unwrap();
+        assert_eq!(fetched, token("a", "user-1", 100));
+        assert!(repo.get_access_token("missing").await.unwrap().is_none());
+
+        let listed = repo.list_access_tokens("user-1").await.unwrap();
+        let ids: Vec<_> = listed.iter().map(|token| token.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a"]);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_revoke_is_scoped_to_owner() {
+        let db = setup().await;
+        let repo = LibSqlAccessTokenRepository::new(db.connection());
+        repo.create_access_token(&token("a", "user-1", 100))
+            .await
+            .unwrap();
+
+        assert!(!repo.revoke_access_token("user-2", "a", 150).await.unwrap());
+        assert!(repo.revoke_access_token("user-1", "a", 150).await.unwrap());
+        assert!(!repo.revoke_access_token("user-1", "a", 160).await.unwrap());
+
+        let revoked = repo.get_access_token("a").await.unwrap().unwrap();
+        assert_eq!(revoked.revoked_at, Some(150));
+        assert!(!revoked.is_active(150));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_touch_is_throttled() {
+        let db = setup().await;
+        let repo = LibSqlAccessTokenRepository::new(db.connection());
+        repo.create_access_token(&token("a", "user-1", 100))
+            .await
+            .unwrap();
+
+        repo.touch_access_token("a", 200, 300).await.unwrap();
+        repo.touch_access_token("a", 250, 300).await.unwrap();
+        let touched = repo.get_access_token("a").await.unwrap().unwrap();
+        assert_eq!(touched.last_used_at, Some(200));
+
+        repo.touch_access_token("a", 500, 300).await.unwrap();
+        let touched = repo.get_access_token("a").await.unwrap().unwrap();
+        assert_eq!(touched.last_used_at, Some(500));
+    }
+}
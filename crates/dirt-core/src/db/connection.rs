@@ -1,14 +1,22 @@
 //! Database connection management
 
-use crate::error::Result;
+use crate::crypto::ContentEncryption;
+use crate::error::{Error, Result};
+use crate::sync::SyncFilter;
 #[cfg(target_os = "android")]
 use hyper_rustls::HttpsConnectorBuilder;
 use libsql::{Builder, Connection, Database as LibSqlDatabase};
+use serde::Serialize;
 use std::fmt;
 use std::path::Path;
 use std::time::Duration;
 
-use super::migrations;
+use super::migrations::{self, SchemaCompatibility};
+use super::statements::StatementCache;
+use super::{
+    EncryptionParamsRepository, LibSqlEncryptionParamsRepository, LibSqlNoteRepository,
+    LibSqlNoteRevisionRepository,
+};
 
 /// Configuration for database sync
 #[derive(Clone, Default)]
@@ -65,11 +73,58 @@ impl SyncConfig {
     }
 }
 
+/// Full-text indexes kept in step with their tables by triggers
+const FTS_TABLES: &[&str] = &["notes_fts", "attachments_fts"];
+
+/// Result of [`Database::health_check`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DatabaseHealth {
+    /// `PRAGMA integrity_check` problems; empty when the file is sound
+    pub integrity_errors: Vec<String>,
+    /// Full-text indexes that no longer match their table. Only checked on
+    /// writable local-only databases.
+    pub fts_errors: Vec<String>,
+    /// Size of the database file
+    pub database_bytes: i64,
+    /// Space held by free pages, which [`Database::compact`] gives back
+    pub free_bytes: i64,
+}
+
+impl DatabaseHealth {
+    /// Whether no problems were found.
+    pub fn is_healthy(&self) -> bool {
+        self.integrity_errors.is_empty() && self.fts_errors.is_empty()
+    }
+}
+
+/// Result of [`Database::compact`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CompactReport {
+    pub bytes_before: i64,
+    pub bytes_after: i64,
+    /// WAL frames copied back into the database file
+    pub checkpointed_frames: i64,
+    /// Whether full-text indexes were rebuilt and the file vacuumed; sync
+    /// replicas only checkpoint, since their pages come from the server
+    pub vacuumed: bool,
+}
+
+impl CompactReport {
+    /// Bytes the database file shrank by.
+    pub const fn reclaimed_bytes(&self) -> i64 {
+        self.bytes_before.saturating_sub(self.bytes_after)
+    }
+}
+
 /// Database wrapper for libSQL connections
 pub struct Database {
     db: LibSqlDatabase,
     conn: Connection,
+    statements: StatementCache,
     sync_config: Option<SyncConfig>,
+    compatibility: SchemaCompatibility,
+    encryption: ContentEncryption,
+    sync_filter: Option<SyncFilter>,
 }
 
 impl Database {
@@ -81,10 +136,14 @@ impl Database {
         let db = Builder::new_local(&path_str).build().await?;
         let conn = db.connect()?;
 
-        let database = Self {
+        let mut database = Self {
             db,
             conn,
+            statements: StatementCache::from_env(),
             sync_config: None,
+            compatibility: SchemaCompatibility::Current,
+            encryption: ContentEncryption::Off,
+            sync_filter: None,
         };
         database.configure().await?;
         database.migrate().await?;
@@ -96,10 +155,14 @@ impl Database {
         let db = Builder::new_local(":memory:").build().await?;
         let conn = db.connect()?;
 
-        let database = Self {
+        let mut database = Self {
             db,
             conn,
+            statements: StatementCache::from_env(),
             sync_config: None,
+            compatibility: SchemaCompatibility::Current,
+            encryption: ContentEncryption::Off,
+            sync_filter: None,
         };
         database.configure().await?;
         database.migrate().await?;
@@ -146,10 +209,14 @@ impl Database {
         let db = builder.build().await?;
         let conn = db.connect()?;
 
-        let database = Self {
+        let mut database = Self {
             db,
             conn,
+            statements: StatementCache::from_env(),
             sync_config: Some(sync_config),
+            compatibility: SchemaCompatibility::Current,
+            encryption: ContentEncryption::Off,
+            sync_filter: None,
         };
 
         // Sync first to pull remote schema if it exists
@@ -163,6 +230,33 @@ impl Database {
         Ok(database)
     }
 
+    /// Open a remote-only connection to a Turso database (no local replica)
+    ///
+    /// Every statement is sent to the remote database. Intended for server-side
+    /// callers that should not keep a local copy. Runs migrations automatically.
+    pub async fn open_remote(
+        url: impl Into<String>,
+        auth_token: impl Into<String>,
+    ) -> Result<Self> {
+        let db = Builder::new_remote(url.into(), auth_token.into())
+            .build()
+            .await?;
+        let conn = db.connect()?;
+
+        let mut database = Self {
+            db,
+            conn,
+            statements: StatementCache::from_env(),
+            sync_config: None,
+            compatibility: SchemaCompatibility::Current,
+            encryption: ContentEncryption::Off,
+            sync_filter: None,
+        };
+        database.configure().await?;
+        database.migrate().await?;
+        Ok(database)
+    }
+
     /// Configure `SQLite` for optimal performance
     async fn configure(&self) -> Result<()> {
         // Enable WAL mode for better concurrency (local databases only)
@@ -184,19 +278,69 @@ impl Database {
     }
 
     /// Run database migrations
-    async fn migrate(&self) -> Result<()> {
-        migrations::run(&self.conn).await
+    ///
+    /// A database already migrated by a newer build is opened read-only
+    /// (`PRAGMA query_only`) when that build only added to the schema.
+    async fn migrate(&mut self) -> Result<()> {
+        self.compatibility = migrations::run(&self.conn).await?;
+        if let SchemaCompatibility::ReadOnly {
+            database_version,
+            supported_version,
+        } = self.compatibility
+        {
+            self.conn.execute("PRAGMA query_only = ON;", ()).await?;
+            tracing::warn!(
+                "Database schema {} is newer than this build supports ({}); opened read-only",
+                database_version,
+                supported_version
+            );
+        }
+        self.detect_encryption().await
+    }
+
+    /// Lock the database once encryption parameters exist, e.g. after another
+    /// device turned encryption on and they arrived with a sync
+    pub async fn detect_encryption(&mut self) -> Result<()> {
+        if !self.encryption.is_enabled()
+            && LibSqlEncryptionParamsRepository::new(&self.conn)
+                .load()
+                .await?
+                .is_some()
+        {
+            self.encryption = ContentEncryption::Locked;
+        }
+        Ok(())
+    }
+
+    /// Whether the schema is current, or newer and therefore read-only
+    pub const fn schema_compatibility(&self) -> SchemaCompatibility {
+        self.compatibility
+    }
+
+    /// Whether writes are blocked because a newer build migrated the database
+    pub const fn is_read_only(&self) -> bool {
+        self.compatibility.is_read_only()
     }
 
     /// Sync with remote database (if configured)
     ///
     /// For embedded replicas, this pulls changes from the remote database.
     pub async fn sync(&self) -> Result<()> {
-        if self.sync_config.is_some() {
-            self.db.sync().await?;
-            tracing::debug!("Database synced with remote");
+        self.sync_frames().await.map(|_| ())
+    }
+
+    /// Sync with remote database (if configured), returning the number of
+    /// WAL frames pulled (0 when sync is not configured)
+    pub async fn sync_frames(&self) -> Result<usize> {
+        if self.sync_config.is_none() {
+            return Ok(0);
         }
-        Ok(())
+        let replicated = self.db.sync().await?;
+        tracing::debug!(
+            "Database synced with remote ({} frames)",
+            replicated.frames_synced()
+        );
+        Ok(replicated.frames_synced())
     }
 
     /// Check if sync is configured
@@ -208,11 +352,131 @@ impl Database {
     pub const fn connection(&self) -> &Connection {
         &self.conn
     }
+
+    /// Note repository reusing this connection's prepared statements,
+    /// encrypting content when encryption is on and holding back notes the
+    /// sync filter excludes
+    pub const fn note_repository(&self) -> LibSqlNoteRepository<'_> {
+        LibSqlNoteRepository::with_statements(&self.conn, &self.statements)
+            .with_encryption(&self.encryption)
+            .with_sync_filter(self.sync_filter.as_ref())
+    }
+
+    /// Note revision repository storing content like [`Self::note_repository`]
+    pub const fn revision_repository(&self) -> LibSqlNoteRevisionRepository<'_> {
+        LibSqlNoteRevisionRepository::new(&self.conn)
+            .with_encryption(&self.encryption)
+            .with_sync_filter(self.sync_filter.as_ref())
+    }
+
+    /// Encryption state of note content
+    pub const fn encryption(&self) -> &ContentEncryption {
+        &self.encryption
+    }
+
+    /// Replace the encryption state, e.g. after unlocking with a passphrase
+    pub fn set_encryption(&mut self, encryption: ContentEncryption) {
+        self.encryption = encryption;
+    }
+
+    /// Which notes this device keeps off the sync server, if any
+    pub const fn sync_filter(&self) -> Option<&SyncFilter> {
+        self.sync_filter.as_ref()
+    }
+
+    /// Replace the sync filter, e.g. once the excluded tags are known
+    pub fn set_sync_filter(&mut self, sync_filter: Option<SyncFilter>) {
+        self.sync_filter = sync_filter;
+    }
+
+    /// Check the file's integrity and that full-text indexes match their
+    /// tables, and measure how much space compacting would reclaim
+    pub async fn health_check(&self) -> Result<DatabaseHealth> {
+        let mut integrity_errors = Vec::new();
+        let mut rows = self.conn.query("PRAGMA integrity_check", ()).await?;
+        while let Some(row) = rows.next().await? {
+            let message: String = row.get(0)?;
+            if message != "ok" {
+                integrity_errors.push(message);
+            }
+        }
+
+        // FTS5 runs its check as a write, which replicas would forward to
+        // the server and read-only databases reject.
+        let mut fts_errors = Vec::new();
+        if !self.is_sync_enabled() && !self.is_read_only() {
+            for table in FTS_TABLES {
+                let check = format!("INSERT INTO {table}({table}) VALUES ('integrity-check')");
+                if let Err(error) = self.conn.execute(&check, ()).await {
+                    fts_errors.push(format!("{table}: {error}"));
+                }
+            }
+        }
+
+        let page_size = self.pragma_value("page_size").await?;
+        Ok(DatabaseHealth {
+            integrity_errors,
+            fts_errors,
+            database_bytes: self.pragma_value("page_count").await? * page_size,
+            free_bytes: self.pragma_value("freelist_count").await? * page_size,
+        })
+    }
+
+    /// Rebuild full-text indexes, vacuum, and checkpoint the WAL into the
+    /// database file
+    ///
+    /// Sync replicas are only checkpointed: rewriting their pages locally
+    /// would leave them out of step with the server.
+    pub async fn compact(&self) -> Result<CompactReport> {
+        if self.is_read_only() {
+            return Err(Error::InvalidInput(
+                "A database opened read-only cannot be compacted".to_string(),
+            ));
+        }
+        let bytes_before = self.storage_usage().await?.database_bytes;
+
+        let vacuumed = !self.is_sync_enabled();
+        if vacuumed {
+            for table in FTS_TABLES {
+                self.conn
+                    .execute(
+                        &format!("INSERT INTO {table}({table}) VALUES ('rebuild')"),
+                        (),
+                    )
+                    .await?;
+            }
+            self.conn.execute("VACUUM", ()).await?;
+        }
+
+        let mut rows = self
+            .conn
+            .query("PRAGMA wal_checkpoint(TRUNCATE)", ())
+            .await?;
+        // In-memory and rollback-journal databases report -1 frames.
+        let checkpointed_frames = match rows.next().await? {
+            Some(row) => row.get::<i64>(2)?.max(0),
+            None => 0,
+        };
+
+        Ok(CompactReport {
+            bytes_before,
+            bytes_after: self.storage_usage().await?.database_bytes,
+            checkpointed_frames,
+            vacuumed,
+        })
+    }
+
+    /// Log statements taking at least `threshold`, overriding
+    /// `DIRT_SLOW_QUERY_MS` (`None` disables the slow query log)
+    pub const fn set_slow_query_threshold(&mut self, threshold: Option<Duration>) {
+        self.statements.set_slow_query_threshold(threshold);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::NoteRepository;
     use std::env;
     use tempfile::tempdir;
 
@@ -244,6 +508,78 @@ mod tests {
         assert!(debug_output.contains("[REDACTED]"));
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_newer_additive_schema_opens_read_only() {
+        let tmp = tempdir().unwrap();
+        let db_path = tmp.path().join("newer.db");
+        {
+            let db = Database::open(&db_path).await.unwrap();
+            assert!(!db.is_read_only());
+            db.connection()
+                .execute(
+                    "INSERT INTO schema_version (version, min_reader_version) VALUES (99, 17)",
+                    (),
+                )
+                .await
+                .unwrap();
+        }
+
+        let db = Database::open(&db_path).await.unwrap();
+        assert!(db.is_read_only());
+        assert!(db.note_repository().list(10, 0).await.unwrap().is_empty());
+        assert!(db.note_repository().create("Blocked").await.is_err());
+        drop(db);
+
+        let conn = Builder::new_local(db_path.to_string_lossy().as_ref())
+            .build()
+            .await
+            .unwrap()
+            .connect()
+            .unwrap();
+        conn.execute(
+            "INSERT INTO schema_version (version, min_reader_version) VALUES (100, 100)",
+            (),
+        )
+        .await
+        .unwrap();
+        drop(conn);
+        assert!(Database::open(&db_path).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_compact_reclaims_space_and_keeps_search_working() {
+        let tmp = tempdir().unwrap();
+        let db = Database::open(tmp.path().join("compact.db")).await.unwrap();
+        let notes = db.note_repository();
+        let filler = "lorem ipsum ".repeat(2_000);
+        for index in 0..20 {
+            notes
+                .create(&format!("Draft {index} #bulk {filler}"))
+                .await
+                .unwrap();
+        }
+        let kept = notes.create("Keep the zeppelin note").await.unwrap();
+        db.connection()
+            .execute("DELETE FROM notes WHERE content LIKE 'Draft %'", ())
+            .await
+            .unwrap();
+
+        let health = db.health_check().await.unwrap();
+        assert!(health.is_healthy(), "{health:?}");
+        assert!(health.free_bytes > 0);
+
+        let report = db.compact().await.unwrap();
+        assert!(report.vacuumed);
+        assert!(report.reclaimed_bytes() > 0, "{report:?}");
+
+        let health = db.health_check().await.unwrap();
+        assert!(health.is_healthy());
+        assert_eq!(health.free_bytes, 0);
+        let found = notes.search("zeppelin", 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, kept.id);
+    }
+
     /// Integration test for Turso sync - only runs if env vars are set
     /// Run with: `TURSO_DATABASE_URL=... TURSO_AUTH_TOKEN=... cargo test test_sync_with_turso -- --ignored`
     #[tokio::test(flavor = "multi_thread")]
@@ -0,0 +1,155 @@
+//! Draft journal repository implementation
+
+use crate::error::{Error, Result};
+use crate::models::{NoteDraft, NoteId};
+use libsql::Connection;
+
+/// Trait for editor draft journal operations (async)
+#[allow(async_fn_in_trait)]
+pub trait DraftRepository {
+    /// Write (or overwrite) the journaled draft for a note
+    async fn save_draft(&self, note_id: &NoteId, content: &str) -> Result<NoteDraft>;
+
+    /// List drafts that differ from, and are newer than, their saved note
+    async fn list_recoverable_drafts(&self) -> Result<Vec<NoteDraft>>;
+
+    /// Remove the journaled draft for a note
+    async fn discard_draft(&self, note_id: &NoteId) -> Result<()>;
+}
+
+/// libSQL implementation of `DraftRepository`
+pub struct LibSqlDraftRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlDraftRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Parse a draft from a database row
+    fn parse_draft(row: &libsql::Row) -> Result<NoteDraft> {
+        let note_id: String = row.get(0)?;
+        Ok(NoteDraft {
+            note_id: note_id
+                .parse()
+                .map_err(|_| Error::InvalidInput("Invalid note ID".into()))?,
+            content: row.get(1)?,
+            updated_at: row.get(2)?,
+        })
+    }
+}
+
+impl DraftRepository for LibSqlDraftRepository<'_> {
+    async fn save_draft(&self, note_id: &NoteId, content: &str) -> Result<NoteDraft> {
+        let draft = NoteDraft {
+            note_id: *note_id,
+            content: content.to_string(),
+            updated_at: chrono::Utc::now().timestamp_millis(),
+        };
+
+        self.conn
+            .execute(
+                "INSERT INTO note_drafts (note_id, content, updated_at) VALUES (?, ?, ?)
+                 ON CONFLICT(note_id) DO UPDATE SET
+                    content = excluded.content,
+                    updated_at = excluded.updated_at",
+                libsql::params![note_id.as_str(), draft.content.as_str(), draft.updated_at],
+            )
+            .await?;
+
+        Ok(draft)
+    }
+
+    async fn list_recoverable_drafts(&self) -> Result<Vec<NoteDraft>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT d.note_id, d.content, d.updated_at
+                 FROM note_drafts d
+                 JOIN notes n ON n.id = d.note_id
+                 WHERE n.is_deleted = 0
+                   AND d.content != n.content
+                   AND d.updated_at >= n.updated_at
+                 ORDER BY d.updated_at DESC",
+                (),
+            )
+            .await?;
+
+        let mut drafts = Vec::new();
+        while let Some(row) = rows.next().await? {
+            drafts.push(Self::parse_draft(&row)?);
+        }
+
+        Ok(drafts)
+    }
+
+    async fn discard_draft(&self, note_id: &NoteId) -> Result<()> {
+        self.conn
+            .execute(
+                "DELETE FROM note_drafts WHERE note_id = ?",
+                [note_id.as_str()],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, LibSqlNoteRepository, NoteRepository};
+
+    async fn setup() -> Database {
+        Database::open_in_memory().await.unwrap()
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_unsaved_draft_is_recoverable() {
+        let db = setup().await;
+        let notes = LibSqlNoteRepository::new(db.connection());
+        let drafts = LibSqlDraftRepository::new(db.connection());
+
+        let note = notes.create("Saved text").await.unwrap();
+        drafts.save_draft(&note.id, "Saved text").await.unwrap();
+        drafts
+            .save_draft(&note.id, "Saved text plus more")
+            .await
+            .unwrap();
+
+        let recoverable = drafts.list_recoverable_drafts().await.unwrap();
+        assert_eq!(recoverable.len(), 1);
+        assert_eq!(recoverable[0].note_id, note.id);
+        assert_eq!(recoverable[0].content, "Saved text plus more");
+
+        drafts.discard_draft(&note.id).await.unwrap();
+        assert!(drafts.list_recoverable_drafts().await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_draft_matching_saved_note_is_not_recoverable() {
+        let db = setup().await;
+        let notes = LibSqlNoteRepository::new(db.connection());
+        let drafts = LibSqlDraftRepository::new(db.connection());
+
+        let note = notes.create("Original").await.unwrap();
+        drafts.save_draft(&note.id, "Edited").await.unwrap();
+        notes.update(&note.id, "Edited").await.unwrap();
+
+        assert!(drafts.list_recoverable_drafts().await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_draft_for_deleted_note_is_not_recoverable() {
+        let db = setup().await;
+        let notes = LibSqlNoteRepository::new(db.connection());
+        let drafts = LibSqlDraftRepository::new(db.connection());
+
+        let note = notes.create("Doomed").await.unwrap();
+        drafts.save_draft(&note.id, "Doomed edit").await.unwrap();
+        notes.delete(&note.id).await.unwrap();
+
+        assert!(drafts.list_recoverable_drafts().await.unwrap().is_empty());
+    }
+}
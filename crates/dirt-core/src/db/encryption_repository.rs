@@ -0,0 +1,82 @@
+//! Encryption parameters repository implementation
+
+use crate::crypto::EncryptionParams;
+use crate::error::Result;
+use libsql::Connection;
+
+/// Trait for the passphrase parameters of encrypted notes (async)
+#[allow(async_fn_in_trait)]
+pub trait EncryptionParamsRepository {
+    /// Load the parameters; `None` while notes are not encrypted
+    async fn load(&self) -> Result<Option<EncryptionParams>>;
+
+    /// Store the parameters when encryption is turned on
+    async fn save(&self, params: &EncryptionParams) -> Result<()>;
+}
+
+/// libSQL implementation of `EncryptionParamsRepository`
+pub struct LibSqlEncryptionParamsRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlEncryptionParamsRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+}
+
+impl EncryptionParamsRepository for LibSqlEncryptionParamsRepository<'_> {
+    async fn load(&self) -> Result<Option<EncryptionParams>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT salt, verifier, created_at FROM encryption_params WHERE id = 1",
+                (),
+            )
+            .await?;
+
+        let Some(row) = rows.next().await? else {
+            return Ok(None);
+        };
+        Ok(Some(EncryptionParams {
+            salt: row.get(0)?,
+            verifier: row.get(1)?,
+            created_at: row.get(2)?,
+        }))
+    }
+
+    async fn save(&self, params: &EncryptionParams) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO encryption_params (id, salt, verifier, created_at)
+                 VALUES (1, ?, ?, ?)",
+                libsql::params![
+                    params.salt.as_str(),
+                    params.verifier.as_str(),
+                    params.created_at
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_save_and_load_params() {
+        let db = Database::open_in_memory().await.unwrap();
+        let repo = LibSqlEncryptionParamsRepository::new(db.connection());
+        assert!(repo.load().await.unwrap().is_none());
+
+        let (params, _) = EncryptionParams::create("correct horse", 42).unwrap();
+        repo.save(&params).await.unwrap();
+        assert_eq!(repo.load().await.unwrap(), Some(params.clone()));
+        // Encryption is turned on once; a second set of parameters is refused.
+        assert!(repo.save(&params).await.is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Per-note export exclusion repository implementation
+
+use crate::error::{Error, Result};
+use crate::models::NoteId;
+use libsql::Connection;
+
+/// Trait for per-note `no_export` flags (async)
+#[allow(async_fn_in_trait)]
+pub trait ExportExclusionRepository {
+    /// Flag or unflag a note as `no_export`
+    async fn set_excluded(&self, note_id: &NoteId, excluded: bool) -> Result<()>;
+
+    /// Whether a note is flagged `no_export`
+    async fn is_excluded(&self, note_id: &NoteId) -> Result<bool>;
+
+    /// List notes flagged `no_export`
+    async fn list_excluded(&self) -> Result<Vec<NoteId>>;
+}
+
+/// libSQL implementation of `ExportExclusionRepository`
+pub struct LibSqlExportExclusionRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlExportExclusionRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+}
+
+impl ExportExclusionRepository for LibSqlExportExclusionRepository<'_> {
+    async fn set_excluded(&self, note_id: &NoteId, excluded: bool) -> Result<()> {
+        if excluded {
+            self.conn
+                .execute(
+                    "INSERT OR IGNORE INTO note_export_exclusions (note_id, created_at)
+                     VALUES (?, ?)",
+                    libsql::params![note_id.as_str(), chrono::Utc::now().timestamp_millis()],
+                )
+                .await?;
+        } else {
+            self.conn
+                .execute(
+                    "DELETE FROM note_export_exclusions WHERE note_id = ?",
+                    [note_id.as_str()],
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn is_excluded(&self, note_id: &NoteId) -> Result<bool> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT 1 FROM note_export_exclusions WHERE note_id = ?",
+                [note_id.as_str()],
+            )
+            .await?;
+        Ok(rows.next().await?.is_some())
+    }
+
+    async fn list_excluded(&self) -> Result<Vec<NoteId>> {
+        let mut rows = self
+            .conn
+            .query("SELECT note_id FROM note_export_exclusions", ())
+            .await?;
+
+        let mut note_ids = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let note_id: String = row.get(0)?;
+            note_ids.push(
+                note_id
+                    .parse()
+                    .map_err(|_| Error::InvalidInput("Invalid note ID".into()))?,
+            );
+        }
+
+        Ok(note_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, LibSqlNoteRepository, NoteRepository};
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_flag_and_unflag_notes() {
+        let db = Database::open_in_memory().await.unwrap();
+        let notes = LibSqlNoteRepository::new(db.connection());
+        let exclusions = LibSqlExportExclusionRepository::new(db.connection());
+
+        let private = notes.create("Diary").await.unwrap();
+        let public = notes.create("Release notes").await.unwrap();
+        exclusions.set_excluded(&private.id, true).await.unwrap();
+        exclusions.set_excluded(&private.id, true).await.unwrap();
+
+        assert!(exclusions.is_excluded(&private.id).await.unwrap());
+        assert!(!exclusions.is_excluded(&public.id).await.unwrap());
+        assert_eq!(exclusions.list_excluded().await.unwrap(), vec![private.id]);
+
+        exclusions.set_excluded(&private.id, false).await.unwrap();
+        assert!(exclusions.list_excluded().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+//! Incremental export state repository implementation
+
+use crate::error::{Error, Result};
+use crate::export::ExportFormat;
+use crate::models::ExportState;
+use libsql::Connection;
+
+/// Trait for incremental export cursors (async)
+#[allow(async_fn_in_trait)]
+pub trait ExportStateRepository {
+    /// Get the export state of a destination
+    async fn get_state(&self, destination: &str) -> Result<Option<ExportState>>;
+
+    /// Store (or replace) the export state of a destination
+    async fn save_state(&self, state: &ExportState) -> Result<()>;
+}
+
+/// libSQL implementation of `ExportStateRepository`
+pub struct LibSqlExportStateRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlExportStateRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+}
+
+impl ExportStateRepository for LibSqlExportStateRepository<'_> {
+    async fn get_state(&self, destination: &str) -> Result<Option<ExportState>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT destination, format, cursor, exported_at
+                 FROM export_state WHERE destination = ?",
+                [destination],
+            )
+            .await?;
+
+        let Some(row) = rows.next().await? else {
+            return Ok(None);
+        };
+        let format: String = row.get(1)?;
+        Ok(Some(ExportState {
+            destination: row.get(0)?,
+            format: ExportFormat::from_extension(&format)
+                .ok_or_else(|| Error::InvalidInput(format!("Invalid export format '{format}'")))?,
+            cursor: row.get(2)?,
+            exported_at: row.get(3)?,
+        }))
+    }
+
+    async fn save_state(&self, state: &ExportState) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO export_state (destination, format, cursor, exported_at)
+                 VALUES (?, ?, ?, ?)
+                 ON CONFLICT(destination) DO UPDATE SET
+                    format = excluded.format,
+                    cursor = excluded.cursor,
+                    exported_at = excluded.exported_at",
+                libsql::params![
+                    state.destination.as_str(),
+                    state.format.extension(),
+                    state.cursor,
+                    state.exported_at
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_save_replaces_state_per_destination() {
+        let db = Database::open_in_memory().await.unwrap();
+        let repo = LibSqlExportStateRepository::new(db.connection());
+
+        assert!(repo.get_state("/backups/notes").await.unwrap().is_none());
+
+        let mut state = ExportState {
+            destination: "/backups/notes".to_string(),
+            format: ExportFormat::Json,
+            cursor: 1_000,
+            exported_at: 2_000,
+        };
+        repo.save_state(&state).await.unwrap();
+        state.format = ExportFormat::Markdown;
+        state.cursor = 3_000;
+        repo.save_state(&state).await.unwrap();
+
+        assert_eq!(repo.get_state("/backups/notes").await.unwrap(), Some(state));
+        assert!(repo.get_state("/backups/other").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,216 @@
+//! Query planner statistics, index usage reporting, and storage upkeep
+
+use crate::error::Result;
+use crate::quota::StorageUsage;
+
+use super::Database;
+
+/// Representative repository queries whose plans `analyze` reports
+const PLANNED_QUERIES: &[(&str, &str)] = &[
+    (
+        "list notes",
+        "SELECT id FROM notes WHERE is_deleted = 0 ORDER BY updated_at DESC LIMIT 50",
+    ),
+    (
+        "list notes by tag",
+        "SELECT n.id FROM notes n
+         JOIN note_tags nt ON n.id = nt.note_id
+         JOIN tags t ON nt.tag_id = t.id
+         WHERE t.name = 'tag' COLLATE NOCASE AND n.is_deleted = 0
+         ORDER BY n.updated_at DESC LIMIT 50",
+    ),
+    (
+        "list notes by source",
+        "SELECT id FROM notes WHERE source = 'cli' AND is_deleted = 0
+         ORDER BY updated_at DESC LIMIT 50",
+    ),
+    (
+        "list attachments",
+        "SELECT id FROM attachments WHERE note_id = 'note' AND is_deleted = 0
+         ORDER BY created_at DESC",
+    ),
+    (
+        "list sync conflicts",
+        "SELECT id FROM sync_conflicts ORDER BY resolved_at DESC, id DESC LIMIT 10",
+    ),
+];
+
+/// Row estimate `ANALYZE` recorded for one index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexStat {
+    pub table: String,
+    pub index: String,
+    /// Rows covered by the index
+    pub rows: i64,
+}
+
+/// How `SQLite` plans one of the hot repository queries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryPlan {
+    pub name: &'static str,
+    /// `EXPLAIN QUERY PLAN` detail lines, outermost first
+    pub steps: Vec<String>,
+}
+
+impl QueryPlan {
+    /// Plan steps that read a whole table without an index
+    pub fn full_scans(&self) -> impl Iterator<Item = &str> {
+        self.steps
+            .iter()
+            .map(String::as_str)
+            .filter(|step| step.starts_with("SCAN ") && !step.contains(" USING "))
+    }
+}
+
+/// Result of `dirt maintain --analyze`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnalyzeReport {
+    pub indexes: Vec<IndexStat>,
+    pub plans: Vec<QueryPlan>,
+}
+
+impl Database {
+    /// Refresh planner statistics with `ANALYZE` and report index usage
+    pub async fn analyze(&self) -> Result<AnalyzeReport> {
+        let conn = self.connection();
+        conn.execute("ANALYZE", ()).await?;
+
+        let mut rows = conn
+            .query(
+                "SELECT tbl, idx, stat FROM sqlite_stat1
+                 WHERE idx IS NOT NULL
+                 ORDER BY tbl, idx",
+                (),
+            )
+            .await?;
+        let mut indexes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let stat: String = row.get(2)?;
+            indexes.push(IndexStat {
+                table: row.get(0)?,
+                index: row.get(1)?,
+                rows: parse_row_estimate(&stat),
+            });
+        }
+
+        let mut plans = Vec::with_capacity(PLANNED_QUERIES.len());
+        for &(name, sql) in PLANNED_QUERIES {
+            let mut rows = conn.query(&format!("EXPLAIN QUERY PLAN {sql}"), ()).await?;
+            let mut steps = Vec::new();
+            while let Some(row) = rows.next().await? {
+                steps.push(row.get::<String>(3)?);
+            }
+            plans.push(QueryPlan { name, steps });
+        }
+
+        Ok(AnalyzeReport { indexes, plans })
+    }
+
+    /// Measure the database file and count live and deleted notes
+    pub async fn storage_usage(&self) -> Result<StorageUsage> {
+        let conn = self.connection();
+        let database_bytes =
+            self.pragma_value("page_count").await? * self.pragma_value("page_size").await?;
+
+        let mut rows = conn
+            .query(
+                "SELECT COUNT(*) - COALESCE(SUM(is_deleted), 0), COALESCE(SUM(is_deleted), 0)
+                 FROM notes",
+                (),
+            )
+            .await?;
+        let (notes, deleted_notes) = match rows.next().await? {
+            Some(row) => (row.get::<i64>(0)?, row.get::<i64>(1)?),
+            None => (0, 0),
+        };
+
+        Ok(StorageUsage {
+            database_bytes,
+            notes,
+            deleted_notes,
+        })
+    }
+
+    /// Permanently remove deleted notes and compact the file
+    ///
+    /// Tags, attachments, and other per-note rows go with them through
+    /// `ON DELETE CASCADE`. Returns the number of notes removed.
+    pub async fn purge_deleted_notes(&self) -> Result<u64> {
+        let conn = self.connection();
+        let purged = conn
+            .execute("DELETE FROM notes WHERE is_deleted = 1", ())
+            .await?;
+        conn.execute("VACUUM", ()).await?;
+        Ok(purged)
+    }
+
+    pub(super) async fn pragma_value(&self, name: &str) -> Result<i64> {
+        let mut rows = self
+            .connection()
+            .query(&format!("PRAGMA {name}"), ())
+            .await?;
+        Ok(match rows.next().await? {
+            Some(row) => row.get::<i64>(0)?,
+            None => 0,
+        })
+    }
+}
+
+/// First number of a `sqlite_stat1.stat` value, the row count
+fn parse_row_estimate(stat: &str) -> i64 {
+    stat.split_whitespace()
+        .next()
+        .and_then(|rows| rows.parse().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::NoteRepository;
+
+    #[test]
+    fn test_parse_row_estimate() {
+        assert_eq!(parse_row_estimate("1200 2 1"), 1200);
+        assert_eq!(parse_row_estimate(""), 0);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_analyze_reports_indexes_and_plans() {
+        let db = Database::open_in_memory().await.unwrap();
+        let notes = db.note_repository();
+        for index in 0..5 {
+            notes
+                .create(&format!("Note {index} #tagged"))
+                .await
+                .unwrap();
+        }
+
+        let report = db.analyze().await.unwrap();
+
+        assert!(report
+            .indexes
+            .iter()
+            .any(|stat| stat.index == "idx_notes_updated" && stat.rows == 5));
+        assert_eq!(report.plans.len(), PLANNED_QUERIES.len());
+        assert!(report.plans.iter().all(|plan| !plan.steps.is_empty()));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_purge_deleted_notes_updates_storage_usage() {
+        let db = Database::open_in_memory().await.unwrap();
+        let notes = db.note_repository();
+        let kept = notes.create("Keep me #tagged").await.unwrap();
+        let gone = notes.create("Delete me #tagged").await.unwrap();
+        notes.delete(&gone.id).await.unwrap();
+
+        let usage = db.storage_usage().await.unwrap();
+        assert_eq!((usage.notes, usage.deleted_notes), (1, 1));
+        assert!(usage.database_bytes > 0);
+
+        assert_eq!(db.purge_deleted_notes().await.unwrap(), 1);
+        let usage = db.storage_usage().await.unwrap();
+        assert_eq!((usage.notes, usage.deleted_notes), (1, 0));
+        assert!(notes.get(&kept.id).await.unwrap().is_some());
+    }
+}
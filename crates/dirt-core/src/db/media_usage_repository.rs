@@ -0,0 +1,162 @@
+//! Per-user attachment storage records
+
+use crate::error::Result;
+use crate::models::MediaUsage;
+use libsql::Connection;
+
+/// Trait for attachment storage accounting (async)
+#[allow(async_fn_in_trait)]
+pub trait MediaUsageRepository {
+    /// Record an object stored for a user, replacing the size of an object
+    /// already recorded under the same key
+    async fn record_media_object(
+        &self,
+        user_id: &str,
+        object_key: &str,
+        size_bytes: i64,
+        recorded_at: i64,
+    ) -> Result<()>;
+
+    /// Recorded size of one of a user's objects
+    async fn get_media_object_size(&self, user_id: &str, object_key: &str) -> Result<Option<i64>>;
+
+    /// Forget one of a user's objects. Returns `false` if it was not recorded.
+    async fn remove_media_object(&self, user_id: &str, object_key: &str) -> Result<bool>;
+
+    /// Total size and count of a user's recorded objects (quota left unset)
+    async fn get_media_usage(&self, user_id: &str) -> Result<MediaUsage>;
+}
+
+/// libSQL implementation of `MediaUsageRepository`
+pub struct LibSqlMediaUsageRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlMediaUsageRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+}
+
+impl MediaUsageRepository for LibSqlMediaUsageRepository<'_> {
+    async fn record_media_object(
+        &self,
+        user_id: &str,
+        object_key: &str,
+        size_bytes: i64,
+        recorded_at: i64,
+    ) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO media_usage (user_id, object_key, size_bytes, recorded_at)
+                 VALUES (?, ?, ?, ?)
+                 ON CONFLICT(user_id, object_key) DO UPDATE SET
+                     size_bytes = excluded.size_bytes,
+                     recorded_at = excluded.recorded_at",
+                libsql::params![user_id, object_key, size_bytes, recorded_at],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_media_object_size(&self, user_id: &str, object_key: &str) -> Result<Option<i64>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT size_bytes FROM media_usage WHERE user_id = ? AND object_key = ?",
+                libsql::params![user_id, object_key],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(row.get(0)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn remove_media_object(&self, user_id: &str, object_key: &str) -> Result<bool> {
+        let rows_affected = self
+            .conn
+            .execute(
+                "DELETE FROM media_usage WHERE user_id = ? AND object_key = ?",
+                libsql::params![user_id, object_key],
+            )
+            .await?;
+        Ok(rows_affected > 0)
+    }
+
+    async fn get_media_usage(&self, user_id: &str) -> Result<MediaUsage> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT COALESCE(SUM(size_bytes), 0), COUNT(*)
+                 FROM media_usage WHERE user_id = ?",
+                [user_id],
+            )
+            .await?;
+
+        let usage = match rows.next().await? {
+            Some(row) => MediaUsage {
+                used_bytes: row.get(0)?,
+                object_count: row.get(1)?,
+                quota_bytes: None,
+            },
+            None => MediaUsage::default(),
+        };
+        Ok(usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_usage_is_summed_per_user() {
+        let db = Database::open_in_memory().await.unwrap();
+        let repo = LibSqlMediaUsageRepository::new(db.connection());
+
+        repo.record_media_object("user-1", "notes/a.png", 100, 1)
+            .await
+            .unwrap();
+        repo.record_media_object("user-1", "notes/b.mp4", 400, 2)
+            .await
+            .unwrap();
+        repo.record_media_object("user-2", "notes/c.png", 50, 3)
+            .await
+            .unwrap();
+        // Re-uploading a key replaces its size.
+        repo.record_media_object("user-1", "notes/a.png", 250, 4)
+            .await
+            .unwrap();
+
+        let usage = repo.get_media_usage("user-1").await.unwrap();
+        assert_eq!(usage.used_bytes, 650);
+        assert_eq!(usage.object_count, 2);
+        assert_eq!(
+            repo.get_media_object_size("user-1", "notes/a.png")
+                .await
+                .unwrap(),
+            Some(250)
+        );
+
+        assert!(repo
+            .remove_media_object("user-1", "notes/b.mp4")
+            .await
+            .unwrap());
+        assert!(!repo
+            .remove_media_object("user-1", "notes/c.png")
+            .await
+            .unwrap());
+        assert_eq!(
+            repo.get_media_usage("user-1").await.unwrap().used_bytes,
+            250
+        );
+        assert_eq!(
+            repo.get_media_usage("nobody").await.unwrap(),
+            MediaUsage::default()
+        );
+    }
+}
@@ -1,25 +1,280 @@
 //! Database migrations
+//!
+//! Every migration commits atomically together with its `schema_version` row,
+//! so an interrupted startup simply reruns it. Steps that touch every note
+//! commit in batches instead and record a checkpoint with each batch, so a
+//! large migration that is cancelled resumes where it stopped rather than
+//! starting over. Progress is published through
+//! [`subscribe_migration_progress`] for clients to show while they open.
+//!
+//! Clients are updated at different times, so a build may meet a database
+//! migrated by a newer one. From version 17 every `schema_version` row
+//! records `min_reader_version`: the oldest schema whose clients can still
+//! read the database. Additive migrations (new tables, columns or indexes)
+//! keep the previous value; migrations that change or drop existing
+//! structures raise it to their own version. A build whose latest version is
+//! at least that minimum opens the newer database read-only instead of
+//! failing; any other build refuses to open it.
+
+use std::sync::OnceLock;
 
 use crate::error::Result;
+use crate::models::{extract_note_links, extract_urls};
 use libsql::Connection;
+use tokio::sync::watch;
+
+/// Newest schema version this build applies
+const LATEST_VERSION: i32 = 26;
+
+/// Notes a resumable step processes per transaction
+const RESUMABLE_BATCH_SIZE: i64 = 500;
+
+/// Progress of a database migration run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationProgress {
+    /// Schema version being applied (the latest version once finished)
+    pub version: i32,
+    /// Migrations finished so far in this run
+    pub completed: usize,
+    /// Migrations that were pending when the run started
+    pub total: usize,
+    /// Notes processed and total notes, for steps that walk every note
+    pub rows: Option<(usize, usize)>,
+}
+
+impl MigrationProgress {
+    /// Whether every pending migration has been applied.
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.completed >= self.total
+    }
+
+    /// Overall completion from 0 to 100, counting partial row progress.
+    #[must_use]
+    pub fn percent(&self) -> u8 {
+        if self.total == 0 || self.is_finished() {
+            return 100;
+        }
+        let step_percent = self
+            .rows
+            .filter(|(_, total)| *total > 0)
+            .map_or(0, |(done, total)| done.min(total) * 100 / total);
+        let percent = (self.completed * 100 + step_percent) / self.total;
+        u8::try_from(percent.min(100)).unwrap_or(100)
+    }
+
+    /// Short description of the migration being applied.
+    #[must_use]
+    pub const fn description(&self) -> &'static str {
+        if self.is_finished() {
+            return "Database is up to date";
+        }
+        match self.version {
+            1 => "Creating the notes database",
+            11 => "Keeping both versions of sync conflicts",
+            12 => "Indexing links in notes",
+            21 => "Indexing attachments for search",
+            24 => "Indexing links between notes",
+            _ => "Updating the database schema",
+        }
+    }
+}
 
-/// Run all pending migrations
-pub async fn run(conn: &Connection) -> Result<()> {
+/// Follow migration progress.
+///
+/// The receiver holds `None` until a database with pending migrations is
+/// opened in this process, then the latest [`MigrationProgress`].
+#[must_use]
+pub fn subscribe_migration_progress() -> watch::Receiver<Option<MigrationProgress>> {
+    progress_channel().subscribe()
+}
+
+fn progress_channel() -> &'static watch::Sender<Option<MigrationProgress>> {
+    static CHANNEL: OnceLock<watch::Sender<Option<MigrationProgress>>> = OnceLock::new();
+    CHANNEL.get_or_init(|| watch::channel(None).0)
+}
+
+/// A migration being applied as part of a run, for progress reports
+#[derive(Debug, Clone, Copy)]
+struct MigrationStep {
+    version: i32,
+    completed: usize,
+    total: usize,
+}
+
+impl MigrationStep {
+    fn report(self, rows: Option<(usize, usize)>) {
+        progress_channel().send_replace(Some(MigrationProgress {
+            version: self.version,
+            completed: self.completed,
+            total: self.total,
+            rows,
+        }));
+    }
+}
+
+/// How this build may use a database after opening it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaCompatibility {
+    /// The schema is the one this build writes
+    Current,
+    /// A newer build migrated the database additively; it can be read but
+    /// not written until this client is updated
+    ReadOnly {
+        database_version: i32,
+        supported_version: i32,
+    },
+}
+
+impl SchemaCompatibility {
+    /// Whether writes are blocked.
+    #[must_use]
+    pub const fn is_read_only(&self) -> bool {
+        matches!(self, Self::ReadOnly { .. })
+    }
+}
+
+/// Run all pending migrations, or work out whether a database migrated by a
+/// newer build can still be read
+pub async fn run(conn: &Connection) -> Result<SchemaCompatibility> {
     let version = get_version(conn).await?;
+    if version > LATEST_VERSION {
+        return newer_schema_compatibility(conn, version).await;
+    }
+    if version == LATEST_VERSION {
+        return Ok(SchemaCompatibility::Current);
+    }
+    ensure_checkpoint_table(conn).await?;
 
-    if version < 1 {
-        migrate_v1(conn).await?;
+    let total = usize::try_from(LATEST_VERSION - version).unwrap_or(0);
+    for (completed, next) in (version + 1..=LATEST_VERSION).enumerate() {
+        let step = MigrationStep {
+            version: next,
+            completed,
+            total,
+        };
+        step.report(None);
+        apply(conn, step).await?;
     }
-    if version < 2 {
-        migrate_v2(conn).await?;
+
+    MigrationStep {
+        version: LATEST_VERSION,
+        completed: total,
+        total,
     }
-    if version < 3 {
-        migrate_v3(conn).await?;
+    .report(None);
+    Ok(SchemaCompatibility::Current)
+}
+
+async fn newer_schema_compatibility(
+    conn: &Connection,
+    database_version: i32,
+) -> Result<SchemaCompatibility> {
+    match get_min_reader_version(conn).await? {
+        Some(min_reader_version) if min_reader_version <= LATEST_VERSION => {
+            Ok(SchemaCompatibility::ReadOnly {
+                database_version,
+                supported_version: LATEST_VERSION,
+            })
+        }
+        _ => Err(crate::Error::Database(format!(
+            "The database uses schema version {database_version}, which this version of Dirt \
+             (schema {LATEST_VERSION}) cannot read; update Dirt to open it"
+        ))),
     }
+}
 
+async fn apply(conn: &Connection, step: MigrationStep) -> Result<()> {
+    match step.version {
+        1 => migrate_v1(conn).await,
+        2 => migrate_v2(conn).await,
+        3 => migrate_v3(conn).await,
+        4 => migrate_v4(conn).await,
+        5 => migrate_v5(conn).await,
+        6 => migrate_v6(conn).await,
+        7 => migrate_v7(conn).await,
+        8 => migrate_v8(conn).await,
+        9 => migrate_v9(conn).await,
+        10 => migrate_v10(conn).await,
+        11 => migrate_v11(conn).await,
+        12 => migrate_v12(conn, step).await,
+        13 => migrate_v13(conn).await,
+        14 => migrate_v14(conn).await,
+        15 => migrate_v15(conn).await,
+        16 => migrate_v16(conn).await,
+        17 => migrate_v17(conn).await,
+        18 => migrate_v18(conn).await,
+        19 => migrate_v19(conn).await,
+        20 => migrate_v20(conn).await,
+        21 => migrate_v21(conn).await,
+        22 => migrate_v22(conn).await,
+        23 => migrate_v23(conn).await,
+        24 => migrate_v24(conn, step).await,
+        25 => migrate_v25(conn).await,
+        26 => migrate_v26(conn).await,
+        version => Err(crate::Error::Database(format!(
+            "No migration to schema version {version}"
+        ))),
+    }
+}
+
+/// Checkpoints of resumable steps, kept outside the versioned schema so they
+/// exist before any migration runs
+async fn ensure_checkpoint_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS migration_checkpoints (
+            version INTEGER PRIMARY KEY,
+            cursor TEXT NOT NULL
+        )",
+        (),
+    )
+    .await?;
     Ok(())
 }
 
+async fn load_checkpoint(conn: &Connection, version: i32) -> Result<Option<String>> {
+    let mut rows = conn
+        .query(
+            "SELECT cursor FROM migration_checkpoints WHERE version = ?",
+            libsql::params![version],
+        )
+        .await?;
+    match rows.next().await? {
+        Some(row) => Ok(Some(row.get(0)?)),
+        None => Ok(None),
+    }
+}
+
+/// Run `statements` in one transaction, rolling back on failure
+async fn execute_in_transaction(
+    conn: &Connection,
+    statements: &[(&str, Vec<libsql::Value>)],
+) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    for (stmt, params) in statements {
+        if let Err(e) = conn.execute(*stmt, params.clone()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+async fn count_rows(conn: &Connection, sql: &str, params: Vec<libsql::Value>) -> Result<usize> {
+    let mut rows = conn.query(sql, params).await?;
+    let count = match rows.next().await? {
+        Some(row) => row.get::<i64>(0)?,
+        None => 0,
+    };
+    Ok(usize::try_from(count).unwrap_or(0))
+}
+
 /// Get the current schema version
 async fn get_version(conn: &Connection) -> Result<i32> {
     // Check if schema_version table exists
@@ -53,6 +308,35 @@ async fn get_version(conn: &Connection) -> Result<i32> {
     Ok(version)
 }
 
+/// Oldest schema version able to read the database, if any migration
+/// recorded one
+async fn get_min_reader_version(conn: &Connection) -> Result<Option<i32>> {
+    let mut rows = conn
+        .query(
+            "SELECT EXISTS(
+                SELECT 1 FROM pragma_table_info('schema_version')
+                WHERE name = 'min_reader_version'
+            )",
+            (),
+        )
+        .await?;
+    let exists = match rows.next().await? {
+        Some(row) => row.get::<i32>(0)? != 0,
+        None => false,
+    };
+    if !exists {
+        return Ok(None);
+    }
+
+    let mut rows = conn
+        .query("SELECT MAX(min_reader_version) FROM schema_version", ())
+        .await?;
+    match rows.next().await? {
+        Some(row) => Ok(row.get::<Option<i32>>(0)?),
+        None => Ok(None),
+    }
+}
+
 /// Migration to version 1: Initial schema
 async fn migrate_v1(conn: &Connection) -> Result<()> {
     // libsql doesn't have execute_batch, so we run each statement separately
@@ -221,6 +505,850 @@ async fn migrate_v3(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Migration to version 4: Tag completion frequency for autocomplete ranking
+async fn migrate_v4(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "ALTER TABLE tags ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0",
+        "INSERT INTO schema_version (version) VALUES (4)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 4");
+    Ok(())
+}
+
+/// Migration to version 5: Editor draft journal for crash recovery
+async fn migrate_v5(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "CREATE TABLE IF NOT EXISTS note_drafts (
+            note_id TEXT PRIMARY KEY REFERENCES notes(id) ON DELETE CASCADE,
+            content TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        "INSERT INTO schema_version (version) VALUES (5)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 5");
+    Ok(())
+}
+
+/// Migration to version 6: Personal access token registry for the notes API
+async fn migrate_v6(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "CREATE TABLE IF NOT EXISTS access_tokens (
+            id TEXT PRIMARY KEY,
+            user_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            scopes TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL,
+            last_used_at INTEGER,
+            revoked_at INTEGER
+        )",
+        "CREATE INDEX IF NOT EXISTS idx_access_tokens_user_id ON access_tokens(user_id)",
+        "INSERT INTO schema_version (version) VALUES (6)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 6");
+    Ok(())
+}
+
+/// Migration to version 7: Server-side scan status for attachments
+async fn migrate_v7(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "ALTER TABLE attachments ADD COLUMN scan_status TEXT NOT NULL DEFAULT 'unscanned'",
+        "INSERT INTO schema_version (version) VALUES (7)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 7");
+    Ok(())
+}
+
+/// Migration to version 8: Manual note ordering within a tag scope
+async fn migrate_v8(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "CREATE TABLE IF NOT EXISTS note_positions (
+            scope TEXT NOT NULL,
+            note_id TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+            position INTEGER NOT NULL,
+            PRIMARY KEY (scope, note_id)
+        )",
+        "CREATE INDEX IF NOT EXISTS idx_note_positions_scope ON note_positions(scope, position)",
+        "INSERT INTO schema_version (version) VALUES (8)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 8");
+    Ok(())
+}
+
+/// Migration to version 9: Capture source attribution for notes
+async fn migrate_v9(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "ALTER TABLE notes ADD COLUMN source TEXT NOT NULL DEFAULT 'unknown'",
+        "CREATE INDEX IF NOT EXISTS idx_notes_source ON notes(source)",
+        "INSERT INTO schema_version (version) VALUES (9)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 9");
+    Ok(())
+}
+
+/// Migration to version 10: Per-sync transfer history
+async fn migrate_v10(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "CREATE TABLE IF NOT EXISTS sync_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            synced_at INTEGER NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            frames_synced INTEGER NOT NULL,
+            bytes_transferred INTEGER NOT NULL,
+            rows_changed INTEGER NOT NULL
+        )",
+        "CREATE INDEX IF NOT EXISTS idx_sync_history_synced_at ON sync_history(synced_at DESC)",
+        "INSERT INTO schema_version (version) VALUES (10)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 10");
+    Ok(())
+}
+
+/// Migration to version 11: retain both note versions on sync conflicts
+async fn migrate_v11(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "ALTER TABLE sync_conflicts ADD COLUMN local_content TEXT",
+        "ALTER TABLE sync_conflicts ADD COLUMN incoming_content TEXT",
+        "DROP TRIGGER IF EXISTS notes_lww_conflict_guard",
+        "CREATE TRIGGER notes_lww_conflict_guard BEFORE UPDATE ON notes
+         FOR EACH ROW
+         WHEN NEW.updated_at < OLD.updated_at
+         BEGIN
+             INSERT INTO sync_conflicts (
+                 note_id,
+                 local_updated_at,
+                 incoming_updated_at,
+                 resolved_at,
+                 strategy,
+                 local_content,
+                 incoming_content
+             ) VALUES (
+                 OLD.id,
+                 OLD.updated_at,
+                 NEW.updated_at,
+                 CAST(strftime('%s','now') AS INTEGER) * 1000,
+                 'lww',
+                 OLD.content,
+                 NEW.content
+             );
+             SELECT RAISE(IGNORE);
+         END",
+        "INSERT INTO schema_version (version) VALUES (11)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 11");
+    Ok(())
+}
+
+/// Migration to version 12: URLs captured from note content
+///
+/// The table is created first and committed on its own; the backfill then
+/// commits in checkpointed batches so a cancelled run resumes mid-way.
+async fn migrate_v12(conn: &Connection, step: MigrationStep) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "CREATE TABLE IF NOT EXISTS note_urls (
+                    note_id TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+                    url TEXT NOT NULL,
+                    position INTEGER NOT NULL,
+                    PRIMARY KEY (note_id, url)
+                )",
+                Vec::new(),
+            ),
+            (
+                "CREATE INDEX IF NOT EXISTS idx_note_urls_url ON note_urls(url)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    backfill_note_urls(conn, step).await?;
+
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "INSERT INTO schema_version (version) VALUES (12)",
+                Vec::new(),
+            ),
+            (
+                "DELETE FROM migration_checkpoints WHERE version = 12",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 12");
+    Ok(())
+}
+
+/// Capture URLs from notes written before `note_urls` existed
+async fn backfill_note_urls(conn: &Connection, step: MigrationStep) -> Result<()> {
+    backfill_note_values(
+        conn,
+        step,
+        "INSERT OR IGNORE INTO note_urls (note_id, url, position) VALUES (?, ?, ?)",
+        extract_urls,
+    )
+    .await
+}
+
+/// Capture `[[links]]` from notes written before `note_links` existed
+///
+/// Targets are stored lowercased so backlinks match titles case-insensitively.
+async fn backfill_note_links(conn: &Connection, step: MigrationStep) -> Result<()> {
+    backfill_note_values(
+        conn,
+        step,
+        "INSERT OR IGNORE INTO note_links (note_id, target, position) VALUES (?, ?, ?)",
+        |content| {
+            extract_note_links(content)
+                .into_iter()
+                .map(|target| target.to_lowercase())
+                .collect()
+        },
+    )
+    .await
+}
+
+/// Insert `(note_id, value, position)` rows with `insert_sql` for every value
+/// `extract` finds in each note
+///
+/// Notes are walked in id order; each batch commits with a checkpoint of the
+/// last id so an interrupted backfill continues after it. Notes encrypted at
+/// rest yield nothing here and are indexed the next time they are saved.
+async fn backfill_note_values(
+    conn: &Connection,
+    step: MigrationStep,
+    insert_sql: &'static str,
+    extract: impl Fn(&str) -> Vec<String>,
+) -> Result<()> {
+    let total = count_rows(conn, "SELECT COUNT(*) FROM notes", Vec::new()).await?;
+    let mut cursor = load_checkpoint(conn, step.version)
+        .await?
+        .unwrap_or_default();
+    let mut done = count_rows(
+        conn,
+        "SELECT COUNT(*) FROM notes WHERE id <= ?",
+        vec![cursor.clone().into()],
+    )
+    .await?;
+    if done > 0 {
+        tracing::info!(
+            "Resuming migration to version {} after {done} of {total} notes",
+            step.version
+        );
+    }
+    step.report(Some((done, total)));
+
+    loop {
+        let mut rows = conn
+            .query(
+                "SELECT id, content FROM notes WHERE id > ? ORDER BY id LIMIT ?",
+                libsql::params![cursor.as_str(), RESUMABLE_BATCH_SIZE],
+            )
+            .await?;
+        let mut notes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            notes.push((row.get::<String>(0)?, row.get::<String>(1)?));
+        }
+        let Some((last_id, _)) = notes.last() else {
+            break;
+        };
+        let last_id = last_id.clone();
+
+        let mut statements = Vec::new();
+        for (note_id, content) in &notes {
+            for (position, value) in extract(content).into_iter().enumerate() {
+                statements.push((
+                    insert_sql,
+                    vec![
+                        note_id.clone().into(),
+                        value.into(),
+                        i64::try_from(position).unwrap_or(i64::MAX).into(),
+                    ],
+                ));
+            }
+        }
+        statements.push((
+            "INSERT INTO migration_checkpoints (version, cursor) VALUES (?, ?)
+             ON CONFLICT(version) DO UPDATE SET cursor = excluded.cursor",
+            vec![step.version.into(), last_id.clone().into()],
+        ));
+        execute_in_transaction(conn, &statements).await?;
+
+        done += notes.len();
+        cursor = last_id;
+        step.report(Some((done, total)));
+    }
+    Ok(())
+}
+
+/// Migration to version 13: stored summaries of long notes
+async fn migrate_v13(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "CREATE TABLE IF NOT EXISTS note_summaries (
+            note_id TEXT PRIMARY KEY REFERENCES notes(id) ON DELETE CASCADE,
+            summary TEXT NOT NULL,
+            method TEXT NOT NULL,
+            note_updated_at INTEGER NOT NULL,
+            created_at INTEGER NOT NULL
+        )",
+        "INSERT INTO schema_version (version) VALUES (13)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 13");
+    Ok(())
+}
+
+/// Migration to version 14: per-note `no_export` flags
+async fn migrate_v14(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "CREATE TABLE IF NOT EXISTS note_export_exclusions (
+            note_id TEXT PRIMARY KEY REFERENCES notes(id) ON DELETE CASCADE,
+            created_at INTEGER NOT NULL
+        )",
+        "INSERT INTO schema_version (version) VALUES (14)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 14");
+    Ok(())
+}
+
+/// Migration to version 15: incremental export cursors per destination
+async fn migrate_v15(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "CREATE TABLE IF NOT EXISTS export_state (
+            destination TEXT PRIMARY KEY,
+            format TEXT NOT NULL,
+            cursor INTEGER NOT NULL,
+            exported_at INTEGER NOT NULL
+        )",
+        "INSERT INTO schema_version (version) VALUES (15)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 15");
+    Ok(())
+}
+
+/// Migration to version 16: routing rules applied to new and edited notes
+async fn migrate_v16(conn: &Connection) -> Result<()> {
+    conn.execute("BEGIN TRANSACTION", ()).await?;
+
+    let statements = [
+        "CREATE TABLE IF NOT EXISTS routing_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            condition TEXT NOT NULL,
+            action TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )",
+        "INSERT INTO schema_version (version) VALUES (16)",
+    ];
+
+    for stmt in statements {
+        if let Err(e) = conn.execute(stmt, ()).await {
+            conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = conn.execute("COMMIT", ()).await {
+        conn.execute("ROLLBACK", ()).await.ok();
+        return Err(e.into());
+    }
+
+    tracing::info!("Migrated database to version 16");
+    Ok(())
+}
+
+/// Migration to version 17: Track the oldest schema able to read the database
+async fn migrate_v17(conn: &Connection) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "ALTER TABLE schema_version ADD COLUMN min_reader_version INTEGER",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (17, 16)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 17");
+    Ok(())
+}
+
+/// Migration to version 18: Pinned and archived notes
+async fn migrate_v18(conn: &Connection) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "ALTER TABLE notes ADD COLUMN is_pinned INTEGER NOT NULL DEFAULT 0",
+                Vec::new(),
+            ),
+            (
+                "ALTER TABLE notes ADD COLUMN is_archived INTEGER NOT NULL DEFAULT 0",
+                Vec::new(),
+            ),
+            (
+                "CREATE INDEX IF NOT EXISTS idx_notes_pinned ON notes(is_pinned)",
+                Vec::new(),
+            ),
+            (
+                "CREATE INDEX IF NOT EXISTS idx_notes_archived ON notes(is_archived)",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (18, 16)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 18");
+    Ok(())
+}
+
+/// Migration to version 19: Note revision history
+async fn migrate_v19(conn: &Connection) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "CREATE TABLE IF NOT EXISTS note_revisions (
+                    note_id TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+                    revision INTEGER NOT NULL,
+                    content TEXT NOT NULL,
+                    saved_at INTEGER NOT NULL,
+                    device_id TEXT,
+                    PRIMARY KEY (note_id, revision)
+                )",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (19, 16)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 19");
+    Ok(())
+}
+
+/// Migration to version 20: Passphrase parameters for encrypted notes
+async fn migrate_v20(conn: &Connection) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "CREATE TABLE IF NOT EXISTS encryption_params (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    salt TEXT NOT NULL,
+                    verifier TEXT NOT NULL,
+                    created_at INTEGER NOT NULL
+                )",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (20, 16)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 20");
+    Ok(())
+}
+
+/// Migration to version 21: search index of attachment filenames, and of
+/// text extracted from text and PDF attachments
+async fn migrate_v21(conn: &Connection) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "ALTER TABLE attachments ADD COLUMN extracted_text TEXT",
+                Vec::new(),
+            ),
+            (
+                "CREATE VIRTUAL TABLE IF NOT EXISTS attachments_fts USING fts5(
+                    filename,
+                    extracted_text,
+                    content=attachments,
+                    content_rowid=rowid
+                )",
+                Vec::new(),
+            ),
+            (
+                "CREATE TRIGGER IF NOT EXISTS attachments_ai AFTER INSERT ON attachments BEGIN
+                    INSERT INTO attachments_fts(rowid, filename, extracted_text)
+                    VALUES (NEW.rowid, NEW.filename, NEW.extracted_text);
+                END",
+                Vec::new(),
+            ),
+            (
+                "CREATE TRIGGER IF NOT EXISTS attachments_ad AFTER DELETE ON attachments BEGIN
+                    INSERT INTO attachments_fts(attachments_fts, rowid, filename, extracted_text)
+                    VALUES ('delete', OLD.rowid, OLD.filename, OLD.extracted_text);
+                END",
+                Vec::new(),
+            ),
+            (
+                "CREATE TRIGGER IF NOT EXISTS attachments_au
+                 AFTER UPDATE OF filename, extracted_text ON attachments BEGIN
+                    INSERT INTO attachments_fts(attachments_fts, rowid, filename, extracted_text)
+                    VALUES ('delete', OLD.rowid, OLD.filename, OLD.extracted_text);
+                    INSERT INTO attachments_fts(rowid, filename, extracted_text)
+                    VALUES (NEW.rowid, NEW.filename, NEW.extracted_text);
+                END",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO attachments_fts(attachments_fts) VALUES ('rebuild')",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (21, 16)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 21");
+    Ok(())
+}
+
+/// Migration to version 22: Public read-only share links for notes
+async fn migrate_v22(conn: &Connection) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "CREATE TABLE IF NOT EXISTS note_shares (
+                    id TEXT PRIMARY KEY,
+                    user_id TEXT NOT NULL,
+                    note_id TEXT NOT NULL,
+                    created_at INTEGER NOT NULL,
+                    expires_at INTEGER NOT NULL,
+                    revoked_at INTEGER
+                )",
+                Vec::new(),
+            ),
+            (
+                "CREATE INDEX IF NOT EXISTS idx_note_shares_user_note
+                 ON note_shares(user_id, note_id)",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (22, 16)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 22");
+    Ok(())
+}
+
+/// Migration to version 23: Sync outbox of notes changed since the last
+/// successful sync, kept across restarts
+async fn migrate_v23(conn: &Connection) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "CREATE TABLE IF NOT EXISTS sync_outbox (
+                    note_id TEXT PRIMARY KEY,
+                    queued_at INTEGER NOT NULL
+                )",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (23, 16)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 23");
+    Ok(())
+}
+
+/// Migration to version 24: `[[wiki links]]` between notes, for backlinks
+///
+/// Like version 12, the table commits first and the backfill resumes from a
+/// checkpoint.
+async fn migrate_v24(conn: &Connection, step: MigrationStep) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "CREATE TABLE IF NOT EXISTS note_links (
+                    note_id TEXT NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+                    target TEXT NOT NULL,
+                    position INTEGER NOT NULL,
+                    PRIMARY KEY (note_id, target)
+                )",
+                Vec::new(),
+            ),
+            (
+                "CREATE INDEX IF NOT EXISTS idx_note_links_target ON note_links(target)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    backfill_note_links(conn, step).await?;
+
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (24, 16)",
+                Vec::new(),
+            ),
+            (
+                "DELETE FROM migration_checkpoints WHERE version = 24",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 24");
+    Ok(())
+}
+
+/// Migration to version 25: Attachment storage per user, for quotas on the
+/// managed API
+async fn migrate_v25(conn: &Connection) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "CREATE TABLE IF NOT EXISTS media_usage (
+                    user_id TEXT NOT NULL,
+                    object_key TEXT NOT NULL,
+                    size_bytes INTEGER NOT NULL,
+                    recorded_at INTEGER NOT NULL,
+                    PRIMARY KEY (user_id, object_key)
+                )",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (25, 16)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 25");
+    Ok(())
+}
+
+/// Migration to version 26: reset the placeholder capture hotkey
+/// `Ctrl+Shift+D`, which the desktop app never registered, to the
+/// `CmdOrCtrl+Alt+N` shortcut it has always used
+async fn migrate_v26(conn: &Connection) -> Result<()> {
+    execute_in_transaction(
+        conn,
+        &[
+            (
+                "UPDATE settings SET value = 'CmdOrCtrl+Alt+N'
+                 WHERE key = 'capture_hotkey' AND value = 'Ctrl+Shift+D'",
+                Vec::new(),
+            ),
+            (
+                "INSERT INTO schema_version (version, min_reader_version) VALUES (26, 16)",
+                Vec::new(),
+            ),
+        ],
+    )
+    .await?;
+
+    tracing::info!("Migrated database to version 26");
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,10 +1362,11 @@ mod tests {
     #[tokio::test(flavor = "multi_thread")]
     async fn test_migrations() {
         let conn = setup().await;
-        run(&conn).await.unwrap();
+        assert_eq!(run(&conn).await.unwrap(), SchemaCompatibility::Current);
 
         let version = get_version(&conn).await.unwrap();
-        assert_eq!(version, 3);
+        assert_eq!(version, 26);
+        assert_eq!(get_min_reader_version(&conn).await.unwrap(), Some(16));
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -247,7 +1376,7 @@ mod tests {
         run(&conn).await.unwrap(); // Should not fail
 
         let version = get_version(&conn).await.unwrap();
-        assert_eq!(version, 3);
+        assert_eq!(version, 26);
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -274,4 +1403,134 @@ mod tests {
 
         assert!(exists);
     }
+
+    const BACKFILL_STEP: MigrationStep = MigrationStep {
+        version: 12,
+        completed: 0,
+        total: 1,
+    };
+
+    async fn note_urls(conn: &Connection, note_id: &str) -> Vec<String> {
+        let mut rows = conn
+            .query(
+                "SELECT url FROM note_urls WHERE note_id = ? ORDER BY position",
+                [note_id],
+            )
+            .await
+            .unwrap();
+        let mut urls = Vec::new();
+        while let Some(row) = rows.next().await.unwrap() {
+            urls.push(row.get::<String>(0).unwrap());
+        }
+        urls
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_backfill_note_urls_captures_existing_links() {
+        let conn = setup().await;
+        run(&conn).await.unwrap();
+        conn.execute(
+            "INSERT INTO notes (id, content, created_at, updated_at) VALUES ('n1', ?, 0, 0)",
+            ["Docs https://example.com/a and https://example.com/b."],
+        )
+        .await
+        .unwrap();
+
+        backfill_note_urls(&conn, BACKFILL_STEP).await.unwrap();
+
+        assert_eq!(
+            note_urls(&conn, "n1").await,
+            vec!["https://example.com/a", "https://example.com/b"]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_backfill_note_links_stores_lowercased_targets() {
+        let conn = setup().await;
+        run(&conn).await.unwrap();
+        conn.execute(
+            "INSERT INTO notes (id, content, created_at, updated_at) VALUES ('n1', ?, 0, 0)",
+            ["See [[Project Plan]], [[n2]] and [[project plan]]"],
+        )
+        .await
+        .unwrap();
+
+        backfill_note_links(
+            &conn,
+            MigrationStep {
+                version: 24,
+                ..BACKFILL_STEP
+            },
+        )
+        .await
+        .unwrap();
+
+        let mut rows = conn
+            .query(
+                "SELECT target FROM note_links WHERE note_id = 'n1' ORDER BY position",
+                (),
+            )
+            .await
+            .unwrap();
+        let mut targets = Vec::new();
+        while let Some(row) = rows.next().await.unwrap() {
+            targets.push(row.get::<String>(0).unwrap());
+        }
+        assert_eq!(targets, vec!["project plan", "n2"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_backfill_note_urls_resumes_after_checkpoint() {
+        let conn = setup().await;
+        run(&conn).await.unwrap();
+        for id in ["n1", "n2"] {
+            conn.execute(
+                "INSERT INTO notes (id, content, created_at, updated_at) VALUES (?, ?, 0, 0)",
+                [id, "See https://example.com/page"],
+            )
+            .await
+            .unwrap();
+        }
+        // A previous run committed the batch ending at n1, then stopped.
+        conn.execute(
+            "INSERT INTO migration_checkpoints (version, cursor) VALUES (12, 'n1')",
+            (),
+        )
+        .await
+        .unwrap();
+
+        backfill_note_urls(&conn, BACKFILL_STEP).await.unwrap();
+
+        assert!(note_urls(&conn, "n1").await.is_empty());
+        assert_eq!(
+            note_urls(&conn, "n2").await,
+            vec!["https://example.com/page"]
+        );
+        assert_eq!(
+            load_checkpoint(&conn, 12).await.unwrap().as_deref(),
+            Some("n2")
+        );
+    }
+
+    #[test]
+    fn migration_progress_counts_partial_steps() {
+        let progress = MigrationProgress {
+            version: 12,
+            completed: 11,
+            total: 15,
+            rows: Some((250, 500)),
+        };
+        assert_eq!(progress.percent(), 76);
+        assert_eq!(progress.description(), "Indexing links in notes");
+        assert!(!progress.is_finished());
+
+        let finished = MigrationProgress {
+            version: LATEST_VERSION,
+            completed: 15,
+            total: 15,
+            rows: None,
+        };
+        assert!(finished.is_finished());
+        assert_eq!(finished.percent(), 100);
+    }
 }
@@ -1,10 +1,41 @@
 //! Database layer for Dirt
 
+mod access_token_repository;
 mod connection;
+mod draft_repository;
+mod encryption_repository;
+mod export_exclusion_repository;
+mod export_state_repository;
+mod maintenance;
+mod media_usage_repository;
 mod migrations;
+mod note_share_repository;
+mod order_repository;
 mod repository;
+mod revision_repository;
+mod routing_rule_repository;
 mod settings_repository;
+mod statements;
+mod summary_repository;
+mod sync_history_repository;
+mod sync_outbox_repository;
 
-pub use connection::{Database, SyncConfig};
+pub use access_token_repository::{AccessTokenRepository, LibSqlAccessTokenRepository};
+pub use connection::{CompactReport, Database, DatabaseHealth, SyncConfig};
+pub use draft_repository::{DraftRepository, LibSqlDraftRepository};
+pub use encryption_repository::{EncryptionParamsRepository, LibSqlEncryptionParamsRepository};
+pub use export_exclusion_repository::{ExportExclusionRepository, LibSqlExportExclusionRepository};
+pub use export_state_repository::{ExportStateRepository, LibSqlExportStateRepository};
+pub use maintenance::{AnalyzeReport, IndexStat, QueryPlan};
+pub use media_usage_repository::{LibSqlMediaUsageRepository, MediaUsageRepository};
+pub use migrations::{subscribe_migration_progress, MigrationProgress, SchemaCompatibility};
+pub use note_share_repository::{LibSqlNoteShareRepository, NoteShareRepository};
+pub use order_repository::{LibSqlNoteOrderRepository, NoteOrderRepository};
 pub use repository::{LibSqlNoteRepository, NoteRepository};
+pub use revision_repository::{LibSqlNoteRevisionRepository, NoteRevisionRepository};
+pub use routing_rule_repository::{LibSqlRoutingRuleRepository, RoutingRuleRepository};
 pub use settings_repository::{LibSqlSettingsRepository, SettingsRepository};
+pub use statements::{StatementCache, SLOW_QUERY_ENV};
+pub use summary_repository::{LibSqlNoteSummaryRepository, NoteSummaryRepository};
+pub use sync_history_repository::{LibSqlSyncHistoryRepository, SyncHistoryRepository};
+pub use sync_outbox_repository::{LibSqlSyncOutboxRepository, SyncOutboxRepository};
@@ -0,0 +1,183 @@
+//! Note share link registry implementation
+
+use crate::error::{Error, Result};
+use crate::models::{NoteId, NoteShare};
+use libsql::Connection;
+
+/// Trait for note share link registry operations (async)
+#[allow(async_fn_in_trait)]
+pub trait NoteShareRepository {
+    /// Record a newly issued share link
+    async fn create_note_share(&self, share: &NoteShare) -> Result<()>;
+
+    /// Look up a share link by ID
+    async fn get_note_share(&self, id: &str) -> Result<Option<NoteShare>>;
+
+    /// List a user's share links for a note, newest first (including revoked
+    /// and expired ones)
+    async fn list_note_shares(&self, user_id: &str, note_id: &NoteId) -> Result<Vec<NoteShare>>;
+
+    /// Revoke one of a user's share links. Returns `false` if no active link matched.
+    async fn revoke_note_share(&self, user_id: &str, id: &str, now: i64) -> Result<bool>;
+}
+
+/// libSQL implementation of `NoteShareRepository`
+pub struct LibSqlNoteShareRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlNoteShareRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Parse a share link from a database row
+    fn parse_share(row: &libsql::Row) -> Result<NoteShare> {
+        let note_id: String = row.get(2)?;
+        Ok(NoteShare {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            note_id: note_id
+                .parse()
+                .map_err(|_| Error::InvalidInput("Invalid note ID".into()))?,
+            created_at: row.get(3)?,
+            expires_at: row.get(4)?,
+            revoked_at: row.get(5)?,
+        })
+    }
+}
+
+impl NoteShareRepository for LibSqlNoteShareRepository<'_> {
+    async fn create_note_share(&self, share: &NoteShare) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO note_shares (id, user_id, note_id, created_at, expires_at, revoked_at)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                libsql::params![
+                    share.id.as_str(),
+                    share.user_id.as_str(),
+                    share.note_id.as_str(),
+                    share.created_at,
+                    share.expires_at,
+                    share.revoked_at
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_note_share(&self, id: &str) -> Result<Option<NoteShare>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT id, user_id, note_id, created_at, expires_at, revoked_at
+                 FROM note_shares WHERE id = ?",
+                [id],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(Self::parse_share(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list_note_shares(&self, user_id: &str, note_id: &NoteId) -> Result<Vec<NoteShare>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT id, user_id, note_id, created_at, expires_at, revoked_at
+                 FROM note_shares WHERE user_id = ? AND note_id = ?
+                 ORDER BY created_at DESC, id DESC",
+                libsql::params![user_id, note_id.as_str()],
+            )
+            .await?;
+
+        let mut shares = Vec::new();
+        while let Some(row) = rows.next().await? {
+            shares.push(Self::parse_share(&row)?);
+        }
+
+        Ok(shares)
+    }
+
+    async fn revoke_note_share(&self, user_id: &str, id: &str, now: i64) -> Result<bool> {
+        let rows_affected = self
+            .conn
+            .execute(
+                "UPDATE note_shares SET revoked_at = ?
+                 WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
+                libsql::params![now, id, user_id],
+            )
+            .await?;
+        Ok(rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    async fn setup() -> Database {
+        Database::open_in_memory().await.unwrap()
+    }
+
+    fn share(id: &str, user_id: &str, note_id: NoteId, created_at: i64) -> NoteShare {
+        NoteShare {
+            id: id.to_string(),
+            user_id: user_id.to_string(),
+            note_id,
+            created_at,
+            expires_at: created_at + 3_600,
+            revoked_at: None,
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_create_get_and_list_shares() {
+        let db = setup().await;
+        let repo = LibSqlNoteShareRepository::new(db.connection());
+        let note_id = NoteId::new();
+        let other_note_id = NoteId::new();
+
+        repo.create_note_share(&share("a", "user-1", note_id, 100))
+            .await
+            .unwrap();
+        repo.create_note_share(&share("b", "user-1", note_id, 200))
+            .await
+            .unwrap();
+        repo.create_note_share(&share("c", "user-1", other_note_id, 300))
+            .await
+            .unwrap();
+        repo.create_note_share(&share("d", "user-2", note_id, 400))
+            .await
+            .unwrap();
+
+        let fetched = repo.get_note_share("a").await.unwrap().unwrap();
+        assert_eq!(fetched, share("a", "user-1", note_id, 100));
+        assert!(repo.get_note_share("missing").await.unwrap().is_none());
+
+        let listed = repo.list_note_shares("user-1", &note_id).await.unwrap();
+        let ids: Vec<_> = listed.iter().map(|share| share.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a"]);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_revoke_is_scoped_to_owner() {
+        let db = setup().await;
+        let repo = LibSqlNoteShareRepository::new(db.connection());
+        repo.create_note_share(&share("a", "user-1", NoteId::new(), 100))
+            .await
+            .unwrap();
+
+        assert!(!repo.revoke_note_share("user-2", "a", 150).await.unwrap());
+        assert!(repo.revoke_note_share("user-1", "a", 150).await.unwrap());
+        assert!(!repo.revoke_note_share("user-1", "a", 160).await.unwrap());
+
+        let revoked = repo.get_note_share("a").await.unwrap().unwrap();
+        assert_eq!(revoked.revoked_at, Some(150));
+        assert!(!revoked.is_active(150));
+    }
+}
@@ -0,0 +1,152 @@
+//! Manual note ordering repository implementation
+
+use crate::error::{Error, Result};
+use crate::models::{NoteId, SortScope};
+use libsql::Connection;
+
+/// Trait for manual note ordering within a scope (async)
+#[allow(async_fn_in_trait)]
+pub trait NoteOrderRepository {
+    /// List note IDs in a scope, in manual order
+    async fn list_order(&self, scope: &SortScope) -> Result<Vec<NoteId>>;
+
+    /// Replace the manual order of a scope
+    async fn set_order(&self, scope: &SortScope, order: &[NoteId]) -> Result<()>;
+
+    /// Forget the manual order of a scope
+    async fn clear_order(&self, scope: &SortScope) -> Result<()>;
+}
+
+/// libSQL implementation of `NoteOrderRepository`
+pub struct LibSqlNoteOrderRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlNoteOrderRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    async fn write_order(&self, scope: &str, order: &[NoteId]) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM note_positions WHERE scope = ?", [scope])
+            .await?;
+        for (position, note_id) in order.iter().enumerate() {
+            let position = i64::try_from(position)
+                .map_err(|_| Error::InvalidInput("Manual order is too long".into()))?;
+            self.conn
+                .execute(
+                    "INSERT INTO note_positions (scope, note_id, position) VALUES (?, ?, ?)",
+                    libsql::params![scope, note_id.as_str(), position],
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl NoteOrderRepository for LibSqlNoteOrderRepository<'_> {
+    async fn list_order(&self, scope: &SortScope) -> Result<Vec<NoteId>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT p.note_id
+                 FROM note_positions p
+                 JOIN notes n ON n.id = p.note_id
+                 WHERE p.scope = ? AND n.is_deleted = 0
+                 ORDER BY p.position ASC",
+                [scope.key()],
+            )
+            .await?;
+
+        let mut order = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let note_id: String = row.get(0)?;
+            order.push(
+                note_id
+                    .parse()
+                    .map_err(|_| Error::InvalidInput("Invalid note ID".into()))?,
+            );
+        }
+
+        Ok(order)
+    }
+
+    async fn set_order(&self, scope: &SortScope, order: &[NoteId]) -> Result<()> {
+        let scope = scope.key();
+        self.conn.execute("BEGIN TRANSACTION", ()).await?;
+        if let Err(e) = self.write_order(&scope, order).await {
+            self.conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e);
+        }
+        if let Err(e) = self.conn.execute("COMMIT", ()).await {
+            self.conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    async fn clear_order(&self, scope: &SortScope) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM note_positions WHERE scope = ?", [scope.key()])
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, LibSqlNoteRepository, NoteRepository};
+
+    async fn setup() -> Database {
+        Database::open_in_memory().await.unwrap()
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_order_round_trips_per_scope() {
+        let db = setup().await;
+        let notes = LibSqlNoteRepository::new(db.connection());
+        let orders = LibSqlNoteOrderRepository::new(db.connection());
+        let project = SortScope::tag("project");
+        let reading = SortScope::tag("reading");
+
+        let first = notes.create("First #project").await.unwrap();
+        let second = notes.create("Second #project").await.unwrap();
+
+        orders
+            .set_order(&project, &[second.id, first.id])
+            .await
+            .unwrap();
+        orders.set_order(&reading, &[first.id]).await.unwrap();
+
+        assert_eq!(
+            orders.list_order(&project).await.unwrap(),
+            vec![second.id, first.id]
+        );
+        assert_eq!(orders.list_order(&reading).await.unwrap(), vec![first.id]);
+
+        orders.set_order(&project, &[first.id]).await.unwrap();
+        assert_eq!(orders.list_order(&project).await.unwrap(), vec![first.id]);
+
+        orders.clear_order(&project).await.unwrap();
+        assert!(orders.list_order(&project).await.unwrap().is_empty());
+        assert_eq!(orders.list_order(&reading).await.unwrap(), vec![first.id]);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_order_skips_deleted_notes() {
+        let db = setup().await;
+        let notes = LibSqlNoteRepository::new(db.connection());
+        let orders = LibSqlNoteOrderRepository::new(db.connection());
+        let scope = SortScope::tag("project");
+
+        let kept = notes.create("Kept #project").await.unwrap();
+        let gone = notes.create("Gone #project").await.unwrap();
+        orders.set_order(&scope, &[gone.id, kept.id]).await.unwrap();
+        notes.delete(&gone.id).await.unwrap();
+
+        assert_eq!(orders.list_order(&scope).await.unwrap(), vec![kept.id]);
+    }
+}
@@ -2,11 +2,24 @@
 
 #![allow(clippy::cast_possible_wrap)] // SQLite uses i64 for LIMIT/OFFSET
 
+use crate::crypto::ContentEncryption;
 use crate::error::{Error, Result};
 use crate::models::{
-    extract_tags, Attachment, AttachmentId, Note, NoteId, SyncConflict, Tag, TagId,
+    daily_note_content, daily_note_title, extract_note_links, extract_tags, extract_urls,
+    is_daily_note, toggle_task_line, trash_cutoff, unique_prefix_len, Attachment, AttachmentId,
+    AttachmentScanStatus, Note, NoteCursor, NoteId, NoteSource, NoteUrl, SyncConflict, Tag, TagId,
+    TrashPurge, DAILY_NOTE_TAG,
 };
-use libsql::Connection;
+use crate::search::{QueryClause, QueryTerm, SearchHit, SearchQuery};
+use crate::sync::{is_sync_excluded, SyncFilter};
+use crate::tags::{content_with_renamed_tag, content_without_tag};
+use chrono::NaiveDate;
+use libsql::params::IntoParams;
+use libsql::{Connection, Rows, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use super::statements::StatementCache;
 
 /// Trait for note storage operations (async)
 #[allow(async_fn_in_trait)]
@@ -17,30 +30,139 @@ pub trait NoteRepository {
     /// Create a note with a pre-generated ID (for optimistic UI updates)
     async fn create_with_note(&self, note: &Note) -> Result<Note>;
 
+    /// Create a note with a pre-generated ID, recording where it was captured
+    async fn create_with_source(&self, note: &Note, source: NoteSource) -> Result<Note>;
+
+    /// Get the capture source recorded for a note
+    async fn get_source(&self, id: &NoteId) -> Result<Option<NoteSource>>;
+
     /// Get a note by ID
     async fn get(&self, id: &NoteId) -> Result<Option<Note>>;
 
-    /// List notes (excluding deleted), newest first
+    /// List notes (excluding deleted and archived), newest first
     async fn list(&self, limit: usize, offset: usize) -> Result<Vec<Note>>;
 
+    /// List notes (excluding deleted and archived) after `cursor`, newest
+    /// first; the first page when `cursor` is `None`
+    async fn list_after(&self, cursor: Option<&NoteCursor>, limit: usize) -> Result<Vec<Note>>;
+
+    /// List pinned notes (excluding archived), newest first
+    async fn list_pinned(&self, limit: usize) -> Result<Vec<Note>>;
+
+    /// List archived notes, newest first
+    async fn list_archived(&self, limit: usize, offset: usize) -> Result<Vec<Note>>;
+
     /// Update a note's content
     async fn update(&self, id: &NoteId, content: &str) -> Result<Note>;
 
+    /// Encrypt the content of every note still stored in plaintext, bumping
+    /// `updated_at` so sync replaces the plaintext elsewhere, and drop the
+    /// tags, URLs and links recorded from it; returns how many notes were
+    /// rewritten
+    async fn encrypt_stored_content(&self) -> Result<u64>;
+
+    /// Hold back or release every note of this device whose tags no longer
+    /// match the sync filter; returns how many notes were rewritten
+    async fn apply_sync_filter(&self) -> Result<u64>;
+
     /// Soft delete a note
     async fn delete(&self, id: &NoteId) -> Result<()>;
 
+    /// List soft-deleted notes, most recently deleted first
+    async fn list_trashed(&self, limit: usize, offset: usize) -> Result<Vec<Note>>;
+
+    /// Undo a soft delete
+    async fn restore(&self, id: &NoteId) -> Result<Note>;
+
+    /// Permanently remove notes deleted more than `days` days ago (`0`
+    /// removes every deleted note); their attachment records go with them
+    async fn purge_older_than(&self, days: u32) -> Result<TrashPurge>;
+
+    /// Pin or unpin a note
+    ///
+    /// Bumps `updated_at` so the change wins last-write-wins sync.
+    async fn set_pinned(&self, id: &NoteId, pinned: bool) -> Result<Note>;
+
+    /// Archive or restore a note
+    ///
+    /// Bumps `updated_at` so the change wins last-write-wins sync.
+    async fn set_archived(&self, id: &NoteId, archived: bool) -> Result<Note>;
+
     /// Search notes by content using FTS
+    ///
+    /// `source:<name>` terms filter by capture source instead of matching text.
     async fn search(&self, query: &str, limit: usize) -> Result<Vec<Note>>;
 
+    /// Search notes like [`search`](Self::search); with `include_attachments`,
+    /// notes whose attachment filenames or extracted text match follow the
+    /// content matches, tagged with the matching attachment
+    async fn search_hits(
+        &self,
+        query: &str,
+        limit: usize,
+        include_attachments: bool,
+    ) -> Result<Vec<SearchHit>>;
+
+    /// List notes captured from `source`, newest first
+    async fn list_by_source(
+        &self,
+        source: NoteSource,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Note>>;
+
+    /// Count non-deleted notes per capture source, largest first
+    async fn count_by_source(&self) -> Result<Vec<(NoteSource, usize)>>;
+
     /// List notes by tag
     async fn list_by_tag(&self, tag: &str, limit: usize, offset: usize) -> Result<Vec<Note>>;
 
+    /// List captured URLs with their notes, most recently updated notes
+    /// first, optionally only from notes tagged `tag`
+    async fn list_urls(&self, tag: Option<&str>, limit: usize) -> Result<Vec<NoteUrl>>;
+
+    /// List notes that link to `id` with `[[id]]` or `[[title]]` (matched
+    /// case-insensitively against its first line), most recently updated first
+    async fn backlinks(&self, id: &NoteId) -> Result<Vec<Note>>;
+
+    /// Get the daily note for `date`, creating it (recording `source`) when
+    /// there is none yet
+    async fn get_or_create_daily(&self, date: NaiveDate, source: NoteSource) -> Result<Note>;
+
     /// Get all tags with note counts
     async fn list_tags(&self) -> Result<Vec<(String, usize)>>;
 
+    /// Id prefix length that keeps every note (deleted ones included, since
+    /// synced tombstones keep their ids) distinguishable
+    async fn short_id_len(&self) -> Result<usize>;
+
+    /// Suggest tags starting with `prefix`, most frequently completed first
+    async fn suggest_tags(&self, prefix: &str, limit: usize) -> Result<Vec<(String, usize)>>;
+
+    /// Record that a tag was picked from autocomplete (boosts its ranking)
+    async fn record_tag_completion(&self, name: &str) -> Result<()>;
+
+    /// Rewrite every note tagged with one of `from` to carry `to` instead, or
+    /// to drop the tag when `to` is `None`, in one transaction. Returns each
+    /// changed note before and after.
+    async fn retag(&self, from: &[String], to: Option<&str>) -> Result<Vec<(Note, Note)>>;
+
+    /// Check or uncheck the `- [ ]` task on `line_index` of a note's content
+    /// in one transaction. Returns the note before and after.
+    async fn toggle_task(&self, id: &NoteId, line_index: usize) -> Result<(Note, Note)>;
+
     /// List recently resolved sync conflicts
     async fn list_conflicts(&self, limit: usize) -> Result<Vec<SyncConflict>>;
 
+    /// List sync conflicts resolved at or after `since` (Unix ms)
+    async fn list_conflicts_since(&self, since: i64, limit: usize) -> Result<Vec<SyncConflict>>;
+
+    /// Get one sync conflict by id
+    async fn get_conflict(&self, id: i64) -> Result<Option<SyncConflict>>;
+
+    /// Record that a sync conflict was settled again with `strategy`
+    async fn mark_conflict_resolved(&self, id: i64, strategy: &str) -> Result<()>;
+
     /// Create attachment metadata for a note
     async fn create_attachment(
         &self,
@@ -54,19 +176,118 @@ pub trait NoteRepository {
     /// List non-deleted attachments for a note
     async fn list_attachments(&self, note_id: &NoteId) -> Result<Vec<Attachment>>;
 
+    /// List non-deleted attachments of all non-deleted notes, oldest first
+    async fn list_all_attachments(&self) -> Result<Vec<Attachment>>;
+
     /// Soft delete attachment metadata by id
     async fn delete_attachment(&self, attachment_id: &AttachmentId) -> Result<()>;
+
+    /// Record the server-side scan result for an attachment
+    async fn set_attachment_scan_status(
+        &self,
+        attachment_id: &AttachmentId,
+        status: AttachmentScanStatus,
+    ) -> Result<()>;
+
+    /// Store the text extracted from an attachment for search; `None` clears it
+    async fn set_attachment_text(
+        &self,
+        attachment_id: &AttachmentId,
+        text: Option<&str>,
+    ) -> Result<()>;
 }
 
 /// libSQL implementation of `NoteRepository`
 pub struct LibSqlNoteRepository<'a> {
     conn: &'a Connection,
+    statements: Option<&'a StatementCache>,
+    encryption: &'a ContentEncryption,
+    sync_filter: Option<&'a SyncFilter>,
 }
 
 impl<'a> LibSqlNoteRepository<'a> {
     /// Create a new repository with the given connection
     pub const fn new(conn: &'a Connection) -> Self {
-        Self { conn }
+        Self {
+            conn,
+            statements: None,
+            encryption: &ContentEncryption::Off,
+            sync_filter: None,
+        }
+    }
+
+    /// Create a repository that reuses prepared statements from `statements`
+    pub const fn with_statements(conn: &'a Connection, statements: &'a StatementCache) -> Self {
+        Self {
+            conn,
+            statements: Some(statements),
+            encryption: &ContentEncryption::Off,
+            sync_filter: None,
+        }
+    }
+
+    /// Encrypt content on write and decrypt it on read per `encryption`
+    #[must_use]
+    pub const fn with_encryption(mut self, encryption: &'a ContentEncryption) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    /// Store a placeholder instead of notes `sync_filter` holds back
+    #[must_use]
+    pub const fn with_sync_filter(mut self, sync_filter: Option<&'a SyncFilter>) -> Self {
+        self.sync_filter = sync_filter;
+        self
+    }
+
+    /// Content as it syncs: the placeholder when the note is held back
+    fn synced_content<'c>(&self, content: &'c str) -> Result<Cow<'c, str>> {
+        let held_back = match self.sync_filter {
+            Some(filter) => filter.hold_back(content)?,
+            None => None,
+        };
+        Ok(held_back.map_or(Cow::Borrowed(content), Cow::Owned))
+    }
+
+    /// Stored content as it should be shown, decrypted and restored
+    fn shown_content(&self, stored: String) -> Result<String> {
+        let content = self.encryption.decrypt_content(stored)?;
+        Ok(match self.sync_filter {
+            Some(filter) => filter.restore(content),
+            None => content,
+        })
+    }
+
+    async fn query(&self, sql: &'static str, params: impl IntoParams) -> Result<Rows> {
+        match self.statements {
+            Some(statements) => statements.query(self.conn, sql, params).await,
+            None => Ok(self.conn.query(sql, params).await?),
+        }
+    }
+
+    async fn execute(&self, sql: &'static str, params: impl IntoParams) -> Result<u64> {
+        match self.statements {
+            Some(statements) => statements.execute(self.conn, sql, params).await,
+            None => Ok(self.conn.execute(sql, params).await?),
+        }
+    }
+
+    /// Whether tags, URLs and `[[links]]` are recorded in their own tables.
+    ///
+    /// Those tables would hold note text in plaintext, so encrypted notes are
+    /// left out of them and the queries that use them check decrypted content
+    /// instead.
+    const fn records_derived(&self) -> bool {
+        !self.encryption.is_enabled()
+    }
+
+    /// Refresh the tags, URLs and links recorded for a note; encrypted notes
+    /// only have theirs removed
+    async fn sync_derived(&self, note_id: &NoteId, content: &str) -> Result<()> {
+        let content = if self.records_derived() { content } else { "" };
+        self.sync_tags(note_id, content).await?;
+        self.sync_urls(note_id, content).await?;
+        self.sync_links(note_id, content).await
     }
 
     /// Sync tags for a note (create new tags, link/unlink as needed)
@@ -74,12 +295,11 @@ impl<'a> LibSqlNoteRepository<'a> {
         let tags = extract_tags(content);
 
         // Remove all existing tag links for this note
-        self.conn
-            .execute(
-                "DELETE FROM note_tags WHERE note_id = ?",
-                [note_id.as_str()],
-            )
-            .await?;
+        self.execute(
+            "DELETE FROM note_tags WHERE note_id = ?",
+            [note_id.as_str()],
+        )
+        .await?;
 
         // Add new tag links
         for tag_name in tags {
@@ -87,22 +307,177 @@ impl<'a> LibSqlNoteRepository<'a> {
             let tag_id = self.get_or_create_tag(&tag_name).await?;
 
             // Link tag to note
-            self.conn
-                .execute(
-                    "INSERT OR IGNORE INTO note_tags (note_id, tag_id) VALUES (?, ?)",
-                    [note_id.as_str(), tag_id.as_str()],
-                )
-                .await?;
+            self.execute(
+                "INSERT OR IGNORE INTO note_tags (note_id, tag_id) VALUES (?, ?)",
+                [note_id.as_str(), tag_id.as_str()],
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the URLs captured for a note
+    async fn sync_urls(&self, note_id: &NoteId, content: &str) -> Result<()> {
+        self.execute(
+            "DELETE FROM note_urls WHERE note_id = ?",
+            [note_id.as_str()],
+        )
+        .await?;
+
+        for (position, url) in extract_urls(content).into_iter().enumerate() {
+            self.execute(
+                "INSERT OR IGNORE INTO note_urls (note_id, url, position) VALUES (?, ?, ?)",
+                libsql::params![note_id.as_str(), url, position as i64],
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the `[[link]]` targets recorded for a note, lowercased
+    async fn sync_links(&self, note_id: &NoteId, content: &str) -> Result<()> {
+        self.execute(
+            "DELETE FROM note_links WHERE note_id = ?",
+            [note_id.as_str()],
+        )
+        .await?;
+
+        for (position, target) in extract_note_links(content).into_iter().enumerate() {
+            self.execute(
+                "INSERT OR IGNORE INTO note_links (note_id, target, position) VALUES (?, ?, ?)",
+                libsql::params![note_id.as_str(), target.to_lowercase(), position as i64],
+            )
+            .await?;
         }
 
         Ok(())
     }
 
+    /// Every note not in the trash, most recently updated first
+    async fn live_notes(&self) -> Result<Vec<Note>> {
+        let mut rows = self
+            .query(
+                "SELECT id, content, created_at, updated_at, is_deleted, is_pinned, is_archived
+                 FROM notes
+                 WHERE is_deleted = 0
+                 ORDER BY updated_at DESC",
+                (),
+            )
+            .await?;
+
+        let mut notes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            notes.push(self.parse_note(&row)?);
+        }
+
+        Ok(notes)
+    }
+
+    /// Live notes tagged `tag`, found from their content rather than the tag
+    /// tables
+    async fn scan_tagged(&self, tag: &str) -> Result<Vec<Note>> {
+        let mut notes = self.live_notes().await?;
+        notes.retain(|note| has_tag(note, tag));
+        Ok(notes)
+    }
+
+    /// Tags of live notes with how many notes carry each, most used first,
+    /// counted from their content rather than the tag tables
+    async fn scan_tag_counts(&self) -> Result<Vec<(String, usize)>> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for note in self.live_notes().await? {
+            for tag in note.tags() {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+
+        let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+        tags.sort_by(|(a_name, a_count), (b_name, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+        });
+        Ok(tags)
+    }
+
+    /// Notes matching one clause of a structured query, best matches first.
+    ///
+    /// Filters run in SQL. Words and phrases use the full-text index, or are
+    /// checked against decrypted content once notes are encrypted, since the
+    /// index then only holds ciphertext; tags and URLs are checked the same
+    /// way, as encrypted notes do not record them.
+    async fn search_clause(&self, clause: &QueryClause, limit: usize) -> Result<Vec<Note>> {
+        let (filters, mut params) = clause_filter_sql(clause, self.records_derived());
+        let text = clause.fts_expression();
+        let scan_content = !self.records_derived()
+            || (text.is_some() && self.sync_filter.is_some_and(SyncFilter::is_active));
+        let sql = match text.filter(|_| !scan_content) {
+            Some(text) => {
+                params.insert(0, Value::Text(text));
+                format!(
+                    "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted, n.is_pinned, n.is_archived
+                     FROM notes n
+                     JOIN notes_fts fts ON n.rowid = fts.rowid
+                     WHERE notes_fts MATCH ? AND n.is_deleted = 0{filters}
+                     ORDER BY rank"
+                )
+            }
+            None => format!(
+                "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted, n.is_pinned, n.is_archived
+                 FROM notes n
+                 WHERE n.is_deleted = 0{filters}
+                 ORDER BY n.updated_at DESC"
+            ),
+        };
+        let mut rows = self.conn.query(&sql, params).await?;
+
+        let mut notes = Vec::new();
+        while notes.len() < limit {
+            let Some(row) = rows.next().await? else {
+                break;
+            };
+            let note = self.parse_note(&row)?;
+            if !scan_content || clause.matches_content(&note) {
+                notes.push(note);
+            }
+        }
+
+        Ok(notes)
+    }
+
+    /// Notes with an attachment whose filename or extracted text matches the
+    /// words and phrases of one clause, paired with that attachment's filename
+    async fn search_clause_attachments(&self, clause: &QueryClause) -> Result<Vec<(Note, String)>> {
+        let Some(text) = clause.fts_expression() else {
+            return Ok(Vec::new());
+        };
+        let (filters, mut params) = clause_filter_sql(clause, self.records_derived());
+        params.insert(0, Value::Text(text));
+        let sql = format!(
+            "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted, n.is_pinned, n.is_archived,
+                    a.filename
+             FROM attachments a
+             JOIN attachments_fts fts ON a.rowid = fts.rowid
+             JOIN notes n ON n.id = a.note_id
+             WHERE attachments_fts MATCH ? AND a.is_deleted = 0 AND n.is_deleted = 0{filters}
+             ORDER BY rank"
+        );
+        let mut rows = self.conn.query(&sql, params).await?;
+
+        let mut matches = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let note = self.parse_note(&row)?;
+            if self.records_derived() || matches_derived_terms(clause, &note) {
+                matches.push((note, row.get(7)?));
+            }
+        }
+        Ok(matches)
+    }
+
     /// Get or create a tag by name
     async fn get_or_create_tag(&self, name: &str) -> Result<TagId> {
         // Try to find existing tag
         let mut rows = self
-            .conn
             .query("SELECT id FROM tags WHERE name = ? COLLATE NOCASE", [name])
             .await?;
 
@@ -115,32 +490,120 @@ impl<'a> LibSqlNoteRepository<'a> {
 
         // Create new tag
         let tag = Tag::new(name);
-        self.conn
-            .execute(
-                "INSERT INTO tags (id, name, created_at) VALUES (?, ?, ?)",
-                libsql::params![tag.id.as_str(), tag.name.as_str(), tag.created_at],
+        self.execute(
+            "INSERT INTO tags (id, name, created_at) VALUES (?, ?, ?)",
+            libsql::params![tag.id.as_str(), tag.name.as_str(), tag.created_at],
+        )
+        .await?;
+
+        Ok(tag.id)
+    }
+
+    /// Body of [`NoteRepository::retag`], run inside its transaction
+    async fn retag_notes(&self, from: &[String], to: Option<&str>) -> Result<Vec<(Note, Note)>> {
+        let mut tagged: Vec<Note> = Vec::new();
+        if self.records_derived() {
+            for tag in from {
+                let mut rows = self
+                    .query(
+                        "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted, n.is_pinned, n.is_archived
+                         FROM notes n
+                         JOIN note_tags nt ON n.id = nt.note_id
+                         JOIN tags t ON nt.tag_id = t.id
+                         WHERE t.name = ? COLLATE NOCASE AND n.is_deleted = 0",
+                        [tag.as_str()],
+                    )
+                    .await?;
+                while let Some(row) = rows.next().await? {
+                    let note = self.parse_note(&row)?;
+                    if !tagged.iter().any(|existing| existing.id == note.id) {
+                        tagged.push(note);
+                    }
+                }
+            }
+        } else {
+            tagged = self.live_notes().await?;
+            tagged.retain(|note| from.iter().any(|tag| has_tag(note, tag)));
+        }
+
+        let mut changed = Vec::new();
+        for note in tagged {
+            if is_sync_excluded(&note.content) {
+                continue;
+            }
+            let mut content = note.content.clone();
+            for tag in from {
+                let rewritten = match to {
+                    Some(to) => content_with_renamed_tag(&content, tag, to),
+                    None => content_without_tag(&content, tag),
+                };
+                if let Some(rewritten) = rewritten {
+                    content = rewritten;
+                }
+            }
+            if content != note.content {
+                let updated = self.update(&note.id, &content).await?;
+                changed.push((note, updated));
+            }
+        }
+
+        for tag in from {
+            if to.is_some_and(|to| to.eq_ignore_ascii_case(tag)) {
+                continue;
+            }
+            if let Some(to) = to {
+                self.execute(
+                    "UPDATE tags SET use_count = use_count
+                        + (SELECT COALESCE(SUM(use_count), 0) FROM tags WHERE name = ?1 COLLATE NOCASE)
+                     WHERE name = ?2 COLLATE NOCASE",
+                    [tag.as_str(), to],
+                )
+                .await?;
+            }
+            self.execute(
+                "DELETE FROM tags WHERE name = ? COLLATE NOCASE",
+                [tag.as_str()],
             )
             .await?;
+        }
+        Ok(changed)
+    }
 
-        Ok(tag.id)
+    /// Body of [`NoteRepository::toggle_task`], run inside its transaction
+    async fn toggle_note_task(&self, id: &NoteId, line_index: usize) -> Result<(Note, Note)> {
+        let note = self
+            .get(id)
+            .await?
+            .ok_or_else(|| Error::NotFound(id.to_string()))?;
+        let Some(content) = toggle_task_line(&note.content, line_index) else {
+            return Err(Error::InvalidInput(format!(
+                "Line {} of note {id} is not a task",
+                line_index + 1
+            )));
+        };
+        let updated = self.update(id, &content).await?;
+        Ok((note, updated))
     }
 
-    /// Parse a note from a database row
-    fn parse_note(row: &libsql::Row) -> Result<Note> {
+    /// Parse a note from a database row, decrypting its content when unlocked
+    fn parse_note(&self, row: &libsql::Row) -> Result<Note> {
         let id: String = row.get(0)?;
         Ok(Note {
             id: id
                 .parse()
                 .map_err(|_| Error::InvalidInput(format!("Invalid note ID in database: {id}")))?,
-            content: row.get(1)?,
+            content: self.shown_content(row.get(1)?)?,
             created_at: row.get(2)?,
             updated_at: row.get(3)?,
             is_deleted: row.get::<i32>(4)? != 0,
+            is_pinned: row.get::<i32>(5)? != 0,
+            is_archived: row.get::<i32>(6)? != 0,
         })
     }
 
-    /// Parse a sync conflict from a database row
-    fn parse_conflict(row: &libsql::Row) -> Result<SyncConflict> {
+    /// Parse a sync conflict from a database row, decrypting both versions
+    /// when unlocked
+    fn parse_conflict(&self, row: &libsql::Row) -> Result<SyncConflict> {
         Ok(SyncConflict {
             id: row.get(0)?,
             note_id: row.get(1)?,
@@ -148,6 +611,14 @@ impl<'a> LibSqlNoteRepository<'a> {
             incoming_updated_at: row.get(3)?,
             resolved_at: row.get(4)?,
             strategy: row.get(5)?,
+            local_content: row
+                .get::<Option<String>>(6)?
+                .map(|content| self.shown_content(content))
+                .transpose()?,
+            incoming_content: row
+                .get::<Option<String>>(7)?
+                .map(|content| self.shown_content(content))
+                .transpose()?,
         })
     }
 
@@ -168,6 +639,7 @@ impl<'a> LibSqlNoteRepository<'a> {
             r2_key: row.get(5)?,
             created_at: row.get(6)?,
             is_deleted: row.get::<i32>(7)? != 0,
+            scan_status: row.get::<String>(8)?.parse()?,
         })
     }
 }
@@ -179,35 +651,56 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
     }
 
     async fn create_with_note(&self, note: &Note) -> Result<Note> {
-        self.conn
-            .execute(
-                "INSERT INTO notes (id, content, created_at, updated_at, is_deleted) VALUES (?, ?, ?, ?, ?)",
+        self.create_with_source(note, NoteSource::Unknown).await
+    }
+
+    async fn create_with_source(&self, note: &Note, source: NoteSource) -> Result<Note> {
+        let synced_content = self.synced_content(&note.content)?;
+        let stored_content = self.encryption.encrypt_content(&synced_content)?;
+        self.execute(
+                "INSERT INTO notes (id, content, created_at, updated_at, is_deleted, is_pinned, is_archived, source)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
                 libsql::params![
                     note.id.as_str(),
-                    note.content.as_str(),
+                    stored_content,
                     note.created_at,
                     note.updated_at,
-                    i32::from(note.is_deleted)
+                    i32::from(note.is_deleted),
+                    i32::from(note.is_pinned),
+                    i32::from(note.is_archived),
+                    source.as_str()
                 ],
             )
             .await?;
 
-        self.sync_tags(&note.id, &note.content).await?;
+        self.sync_derived(&note.id, &synced_content).await?;
 
         Ok(note.clone())
     }
 
-    async fn get(&self, id: &NoteId) -> Result<Option<Note>> {
+    async fn get_source(&self, id: &NoteId) -> Result<Option<NoteSource>> {
         let mut rows = self
-            .conn
             .query(
-                "SELECT id, content, created_at, updated_at, is_deleted FROM notes WHERE id = ? AND is_deleted = 0",
+                "SELECT source FROM notes WHERE id = ? AND is_deleted = 0",
+                [id.as_str()],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(row.get::<String>(0)?.parse()?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get(&self, id: &NoteId) -> Result<Option<Note>> {
+        let mut rows = self.query(
+                "SELECT id, content, created_at, updated_at, is_deleted, is_pinned, is_archived FROM notes WHERE id = ? AND is_deleted = 0",
                 [id.as_str()],
             )
             .await?;
 
         if let Some(row) = rows.next().await? {
-            Ok(Some(Self::parse_note(&row)?))
+            Ok(Some(self.parse_note(&row)?))
         } else {
             Ok(None)
         }
@@ -215,11 +708,90 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
 
     async fn list(&self, limit: usize, offset: usize) -> Result<Vec<Note>> {
         let mut rows = self
-            .conn
             .query(
-                "SELECT id, content, created_at, updated_at, is_deleted
+                "SELECT id, content, created_at, updated_at, is_deleted, is_pinned, is_archived
                  FROM notes
-                 WHERE is_deleted = 0
+                 WHERE is_deleted = 0 AND is_archived = 0
+                 ORDER BY updated_at DESC
+                 LIMIT ? OFFSET ?",
+                libsql::params![limit as i64, offset as i64],
+            )
+            .await?;
+
+        let mut notes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            notes.push(self.parse_note(&row)?);
+        }
+
+        Ok(notes)
+    }
+
+    async fn list_after(&self, cursor: Option<&NoteCursor>, limit: usize) -> Result<Vec<Note>> {
+        let mut rows = match cursor {
+            Some(cursor) => {
+                self.query(
+                    "SELECT id, content, created_at, updated_at, is_deleted, is_pinned, is_archived
+                     FROM notes
+                     WHERE is_deleted = 0 AND is_archived = 0
+                       AND (updated_at < ? OR (updated_at = ? AND id < ?))
+                     ORDER BY updated_at DESC, id DESC
+                     LIMIT ?",
+                    libsql::params![
+                        cursor.updated_at,
+                        cursor.updated_at,
+                        cursor.id.as_str(),
+                        limit as i64
+                    ],
+                )
+                .await?
+            }
+            None => {
+                self.query(
+                    "SELECT id, content, created_at, updated_at, is_deleted, is_pinned, is_archived
+                     FROM notes
+                     WHERE is_deleted = 0 AND is_archived = 0
+                     ORDER BY updated_at DESC, id DESC
+                     LIMIT ?",
+                    libsql::params![limit as i64],
+                )
+                .await?
+            }
+        };
+
+        let mut notes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            notes.push(self.parse_note(&row)?);
+        }
+
+        Ok(notes)
+    }
+
+    async fn list_pinned(&self, limit: usize) -> Result<Vec<Note>> {
+        let mut rows = self
+            .query(
+                "SELECT id, content, created_at, updated_at, is_deleted, is_pinned, is_archived
+                 FROM notes
+                 WHERE is_deleted = 0 AND is_pinned = 1 AND is_archived = 0
+                 ORDER BY updated_at DESC, id DESC
+                 LIMIT ?",
+                libsql::params![limit as i64],
+            )
+            .await?;
+
+        let mut notes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            notes.push(self.parse_note(&row)?);
+        }
+
+        Ok(notes)
+    }
+
+    async fn list_archived(&self, limit: usize, offset: usize) -> Result<Vec<Note>> {
+        let mut rows = self
+            .query(
+                "SELECT id, content, created_at, updated_at, is_deleted, is_pinned, is_archived
+                 FROM notes
+                 WHERE is_deleted = 0 AND is_archived = 1
                  ORDER BY updated_at DESC
                  LIMIT ? OFFSET ?",
                 libsql::params![limit as i64, offset as i64],
@@ -228,7 +800,7 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
 
         let mut notes = Vec::new();
         while let Some(row) = rows.next().await? {
-            notes.push(Self::parse_note(&row)?);
+            notes.push(self.parse_note(&row)?);
         }
 
         Ok(notes)
@@ -236,12 +808,13 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
 
     async fn update(&self, id: &NoteId, content: &str) -> Result<Note> {
         let now = chrono::Utc::now().timestamp_millis();
+        let synced_content = self.synced_content(content)?;
+        let stored_content = self.encryption.encrypt_content(&synced_content)?;
 
         let rows_affected = self
-            .conn
             .execute(
                 "UPDATE notes SET content = ?, updated_at = ? WHERE id = ? AND is_deleted = 0",
-                libsql::params![content, now, id.as_str()],
+                libsql::params![stored_content, now, id.as_str()],
             )
             .await?;
 
@@ -249,18 +822,103 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
             return Err(Error::NotFound(id.to_string()));
         }
 
-        self.sync_tags(id, content).await?;
+        self.sync_derived(id, &synced_content).await?;
 
         self.get(id)
             .await?
             .ok_or_else(|| Error::NotFound(id.to_string()))
     }
 
+    async fn encrypt_stored_content(&self) -> Result<u64> {
+        if !self.encryption.is_unlocked() {
+            return Err(Error::InvalidInput(
+                "Unlock encryption before encrypting notes".into(),
+            ));
+        }
+        let mut rows = self
+            .query(
+                "SELECT id, content FROM notes WHERE content NOT LIKE 'dirt-enc:v1:%'",
+                (),
+            )
+            .await?;
+        let mut plaintext_notes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            plaintext_notes.push((row.get::<String>(0)?, row.get::<String>(1)?));
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut rewritten = 0;
+        for (id, content) in plaintext_notes {
+            rewritten += self
+                .execute(
+                    "UPDATE notes SET content = ?, updated_at = MAX(updated_at, ?) WHERE id = ?",
+                    libsql::params![self.encryption.encrypt_content(&content)?, now, id],
+                )
+                .await?;
+        }
+        // Attachment text is indexed in plaintext, so it goes with the notes,
+        // as do the tags, URLs and links recorded from them.
+        self.execute(
+            "UPDATE attachments SET extracted_text = NULL WHERE extracted_text IS NOT NULL",
+            (),
+        )
+        .await?;
+        for sql in [
+            "DELETE FROM note_tags",
+            "DELETE FROM tags",
+            "DELETE FROM note_urls",
+            "DELETE FROM note_links",
+        ] {
+            self.execute(sql, ()).await?;
+        }
+        Ok(rewritten)
+    }
+
+    async fn apply_sync_filter(&self) -> Result<u64> {
+        let Some(filter) = self.sync_filter else {
+            return Ok(0);
+        };
+        let mut rows = self
+            .query("SELECT id, content FROM notes WHERE is_deleted = 0", ())
+            .await?;
+        let mut stored_notes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            stored_notes.push((row.get::<String>(0)?, row.get::<String>(1)?));
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut rewritten = 0;
+        for (id, stored) in stored_notes {
+            let stored = self.encryption.decrypt_content(stored)?;
+            let held_back = is_sync_excluded(&stored);
+            let content = filter.restore(stored);
+            // Placeholders still present were held back by another device.
+            if is_sync_excluded(&content) || held_back == filter.excludes(&content) {
+                continue;
+            }
+            let synced_content = self.synced_content(&content)?;
+            rewritten += self
+                .execute(
+                    "UPDATE notes SET content = ?, updated_at = MAX(updated_at, ?) WHERE id = ?",
+                    libsql::params![
+                        self.encryption.encrypt_content(&synced_content)?,
+                        now,
+                        id.as_str()
+                    ],
+                )
+                .await?;
+            let note_id: NoteId = id
+                .parse()
+                .map_err(|_| Error::InvalidInput(format!("Invalid note ID in database: {id}")))?;
+            self.sync_derived(&note_id, &synced_content).await?;
+        }
+        Ok(rewritten)
+    }
+
     async fn delete(&self, id: &NoteId) -> Result<()> {
         let now = chrono::Utc::now().timestamp_millis();
 
         let rows_affected = self
-            .conn
             .execute(
                 "UPDATE notes SET is_deleted = 1, updated_at = ? WHERE id = ? AND is_deleted = 0",
                 libsql::params![now, id.as_str()],
@@ -274,37 +932,174 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
         Ok(())
     }
 
-    async fn search(&self, query: &str, limit: usize) -> Result<Vec<Note>> {
-        if query.trim().is_empty() {
-            return self.list(limit, 0).await;
-        }
-
+    async fn list_trashed(&self, limit: usize, offset: usize) -> Result<Vec<Note>> {
         let mut rows = self
-            .conn
             .query(
-                "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted
-                 FROM notes n
-                 JOIN notes_fts fts ON n.rowid = fts.rowid
-                 WHERE notes_fts MATCH ? AND n.is_deleted = 0
-                 ORDER BY rank
-                 LIMIT ?",
-                libsql::params![query, limit as i64],
+                "SELECT id, content, created_at, updated_at, is_deleted, is_pinned, is_archived
+                 FROM notes
+                 WHERE is_deleted = 1
+                 ORDER BY updated_at DESC
+                 LIMIT ? OFFSET ?",
+                libsql::params![limit as i64, offset as i64],
             )
             .await?;
 
         let mut notes = Vec::new();
         while let Some(row) = rows.next().await? {
-            notes.push(Self::parse_note(&row)?);
+            notes.push(self.parse_note(&row)?);
+        }
+
+        Ok(notes)
+    }
+
+    async fn restore(&self, id: &NoteId) -> Result<Note> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let rows_affected = self
+            .execute(
+                "UPDATE notes SET is_deleted = 0, updated_at = ? WHERE id = ? AND is_deleted = 1",
+                libsql::params![now, id.as_str()],
+            )
+            .await?;
+
+        if rows_affected == 0 {
+            return Err(Error::NotFound(id.to_string()));
+        }
+
+        self.get(id)
+            .await?
+            .ok_or_else(|| Error::NotFound(id.to_string()))
+    }
+
+    async fn purge_older_than(&self, days: u32) -> Result<TrashPurge> {
+        // A note's deletion is its last update.
+        let cutoff = trash_cutoff(chrono::Utc::now().timestamp_millis(), days);
+
+        let mut rows = self
+            .query(
+                "SELECT a.id, a.note_id, a.filename, a.mime_type, a.size_bytes, a.r2_key,
+                        a.created_at, a.is_deleted, a.scan_status
+                 FROM attachments a
+                 JOIN notes n ON n.id = a.note_id
+                 WHERE a.is_deleted = 0 AND n.is_deleted = 1 AND n.updated_at < ?
+                 ORDER BY a.created_at ASC, a.id ASC",
+                [cutoff],
+            )
+            .await?;
+        let mut attachments = Vec::new();
+        while let Some(row) = rows.next().await? {
+            attachments.push(Self::parse_attachment(&row)?);
+        }
+
+        let notes = self
+            .execute(
+                "DELETE FROM notes WHERE is_deleted = 1 AND updated_at < ?",
+                [cutoff],
+            )
+            .await?;
+
+        Ok(TrashPurge { notes, attachments })
+    }
+
+    async fn set_pinned(&self, id: &NoteId, pinned: bool) -> Result<Note> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let rows_affected = self
+            .execute(
+                "UPDATE notes SET is_pinned = ?, updated_at = ? WHERE id = ? AND is_deleted = 0",
+                libsql::params![i32::from(pinned), now, id.as_str()],
+            )
+            .await?;
+
+        if rows_affected == 0 {
+            return Err(Error::NotFound(id.to_string()));
         }
 
+        self.get(id)
+            .await?
+            .ok_or_else(|| Error::NotFound(id.to_string()))
+    }
+
+    async fn set_archived(&self, id: &NoteId, archived: bool) -> Result<Note> {
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let rows_affected = self
+            .execute(
+                "UPDATE notes SET is_archived = ?, updated_at = ? WHERE id = ? AND is_deleted = 0",
+                libsql::params![i32::from(archived), now, id.as_str()],
+            )
+            .await?;
+
+        if rows_affected == 0 {
+            return Err(Error::NotFound(id.to_string()));
+        }
+
+        self.get(id)
+            .await?
+            .ok_or_else(|| Error::NotFound(id.to_string()))
+    }
+
+    async fn search(&self, query: &str, limit: usize) -> Result<Vec<Note>> {
+        let query = SearchQuery::parse(query)?;
+        if query.is_empty() {
+            return self.list(limit, 0).await;
+        }
+
+        let mut notes: Vec<Note> = Vec::new();
+        for clause in query.clauses() {
+            for note in self.search_clause(clause, limit).await? {
+                if notes.len() < limit && notes.iter().all(|found| found.id != note.id) {
+                    notes.push(note);
+                }
+            }
+        }
         Ok(notes)
     }
 
+    async fn search_hits(
+        &self,
+        query: &str,
+        limit: usize,
+        include_attachments: bool,
+    ) -> Result<Vec<SearchHit>> {
+        let mut hits: Vec<SearchHit> = self
+            .search(query, limit)
+            .await?
+            .into_iter()
+            .map(|note| SearchHit {
+                note,
+                matched_attachment: None,
+            })
+            .collect();
+        if !include_attachments {
+            return Ok(hits);
+        }
+
+        for clause in SearchQuery::parse(query)?.clauses() {
+            if hits.len() >= limit {
+                break;
+            }
+            for (note, filename) in self.search_clause_attachments(clause).await? {
+                if hits.len() < limit && hits.iter().all(|hit| hit.note.id != note.id) {
+                    hits.push(SearchHit {
+                        note,
+                        matched_attachment: Some(filename),
+                    });
+                }
+            }
+        }
+        Ok(hits)
+    }
+
     async fn list_by_tag(&self, tag: &str, limit: usize, offset: usize) -> Result<Vec<Note>> {
+        if !self.records_derived() {
+            let tagged = self.scan_tagged(tag).await?;
+            return Ok(tagged.into_iter().skip(offset).take(limit).collect());
+        }
+
         let mut rows = self
-            .conn
             .query(
-                "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted
+                "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted, n.is_pinned, n.is_archived
                  FROM notes n
                  JOIN note_tags nt ON n.id = nt.note_id
                  JOIN tags t ON nt.tag_id = t.id
@@ -317,15 +1112,198 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
 
         let mut notes = Vec::new();
         while let Some(row) = rows.next().await? {
-            notes.push(Self::parse_note(&row)?);
+            notes.push(self.parse_note(&row)?);
+        }
+
+        Ok(notes)
+    }
+
+    async fn list_by_source(
+        &self,
+        source: NoteSource,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<Note>> {
+        let mut rows = self
+            .query(
+                "SELECT id, content, created_at, updated_at, is_deleted, is_pinned, is_archived
+                 FROM notes
+                 WHERE source = ? AND is_deleted = 0
+                 ORDER BY updated_at DESC
+                 LIMIT ? OFFSET ?",
+                libsql::params![source.as_str(), limit as i64, offset as i64],
+            )
+            .await?;
+
+        let mut notes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            notes.push(self.parse_note(&row)?);
         }
 
         Ok(notes)
     }
 
+    async fn count_by_source(&self) -> Result<Vec<(NoteSource, usize)>> {
+        let mut rows = self
+            .query(
+                "SELECT source, COUNT(*) as count
+                 FROM notes
+                 WHERE is_deleted = 0
+                 GROUP BY source
+                 ORDER BY count DESC, source ASC",
+                (),
+            )
+            .await?;
+
+        let mut counts = Vec::new();
+        while let Some(row) = rows.next().await? {
+            // Sources written by newer clients fold into `unknown`.
+            let source = row.get::<String>(0)?.parse().unwrap_or_default();
+            let count: i64 = row.get(1)?;
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            counts.push((source, count as usize));
+        }
+
+        Ok(counts)
+    }
+
+    async fn list_urls(&self, tag: Option<&str>, limit: usize) -> Result<Vec<NoteUrl>> {
+        if !self.records_derived() {
+            let notes = match tag {
+                Some(tag) => self.scan_tagged(tag).await?,
+                None => self.live_notes().await?,
+            };
+            return Ok(notes
+                .into_iter()
+                .flat_map(|note| {
+                    note.urls().into_iter().map(move |url| NoteUrl {
+                        url,
+                        note: note.clone(),
+                    })
+                })
+                .take(limit)
+                .collect());
+        }
+
+        let mut rows = self
+            .query(
+                "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted, n.is_pinned, n.is_archived, nu.url
+                 FROM note_urls nu
+                 JOIN notes n ON nu.note_id = n.id
+                 WHERE n.is_deleted = 0
+                   AND (? IS NULL OR EXISTS (
+                       SELECT 1 FROM note_tags nt
+                       JOIN tags t ON nt.tag_id = t.id
+                       WHERE nt.note_id = n.id AND t.name = ? COLLATE NOCASE
+                   ))
+                 ORDER BY n.updated_at DESC, nu.position ASC
+                 LIMIT ?",
+                libsql::params![tag, tag, limit as i64],
+            )
+            .await?;
+
+        let mut urls = Vec::new();
+        while let Some(row) = rows.next().await? {
+            urls.push(NoteUrl {
+                url: row.get(7)?,
+                note: self.parse_note(&row)?,
+            });
+        }
+
+        Ok(urls)
+    }
+
+    async fn backlinks(&self, id: &NoteId) -> Result<Vec<Note>> {
+        let note = self
+            .get(id)
+            .await?
+            .ok_or_else(|| Error::NotFound(id.to_string()))?;
+        let note_id = id.as_str().to_lowercase();
+        let title = note.title_preview(usize::MAX).trim().to_lowercase();
+        let title = if title.is_empty() {
+            note_id.clone()
+        } else {
+            title
+        };
+
+        if !self.records_derived() {
+            let mut notes = self.live_notes().await?;
+            notes.retain(|other| {
+                other.id != *id
+                    && extract_note_links(&other.content).iter().any(|target| {
+                        let target = target.to_lowercase();
+                        target == note_id || target == title
+                    })
+            });
+            return Ok(notes);
+        }
+
+        let mut rows = self
+            .query(
+                "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted, n.is_pinned, n.is_archived
+                 FROM notes n
+                 WHERE n.is_deleted = 0
+                   AND n.id != ?
+                   AND EXISTS (
+                       SELECT 1 FROM note_links nl
+                       WHERE nl.note_id = n.id AND nl.target IN (?, ?)
+                   )
+                 ORDER BY n.updated_at DESC",
+                libsql::params![id.as_str(), note_id, title],
+            )
+            .await?;
+
+        let mut notes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            notes.push(self.parse_note(&row)?);
+        }
+
+        Ok(notes)
+    }
+
+    async fn get_or_create_daily(&self, date: NaiveDate, source: NoteSource) -> Result<Note> {
+        let title = daily_note_title(date);
+        if !self.records_derived() {
+            let mut tagged = self.scan_tagged(DAILY_NOTE_TAG).await?;
+            tagged.sort_by_key(|note| note.created_at);
+            if let Some(note) = tagged.into_iter().find(|note| is_daily_note(note, &title)) {
+                return Ok(note);
+            }
+            return self
+                .create_with_source(&Note::new(&daily_note_content(date)), source)
+                .await;
+        }
+
+        // Titles are matched after decryption, so scan every daily-tagged note.
+        let mut rows = self
+            .query(
+                "SELECT n.id, n.content, n.created_at, n.updated_at, n.is_deleted, n.is_pinned, n.is_archived
+                 FROM notes n
+                 JOIN note_tags nt ON n.id = nt.note_id
+                 JOIN tags t ON nt.tag_id = t.id
+                 WHERE t.name = ? COLLATE NOCASE AND n.is_deleted = 0
+                 ORDER BY n.created_at ASC",
+                [DAILY_NOTE_TAG],
+            )
+            .await?;
+
+        while let Some(row) = rows.next().await? {
+            let note = self.parse_note(&row)?;
+            if is_daily_note(&note, &title) {
+                return Ok(note);
+            }
+        }
+
+        self.create_with_source(&Note::new(&daily_note_content(date)), source)
+            .await
+    }
+
     async fn list_tags(&self) -> Result<Vec<(String, usize)>> {
+        if !self.records_derived() {
+            return self.scan_tag_counts().await;
+        }
+
         let mut rows = self
-            .conn
             .query(
                 "SELECT t.name, COUNT(nt.note_id) as count
                  FROM tags t
@@ -346,29 +1324,163 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
             tags.push((name, count as usize));
         }
 
-        Ok(tags)
+        Ok(tags)
+    }
+
+    async fn short_id_len(&self) -> Result<usize> {
+        let mut rows = self.query("SELECT id FROM notes ORDER BY id", ()).await?;
+
+        let mut ids = Vec::new();
+        while let Some(row) = rows.next().await? {
+            ids.push(row.get::<String>(0)?);
+        }
+
+        Ok(unique_prefix_len(&ids))
+    }
+
+    async fn suggest_tags(&self, prefix: &str, limit: usize) -> Result<Vec<(String, usize)>> {
+        if !self.records_derived() {
+            let prefix = prefix.to_lowercase();
+            let mut tags = self.scan_tag_counts().await?;
+            tags.retain(|(name, _)| name.starts_with(&prefix));
+            tags.truncate(limit);
+            return Ok(tags);
+        }
+
+        let pattern = format!("{}%", escape_like(&prefix.to_lowercase()));
+        let mut rows = self
+            .query(
+                "SELECT t.name, COUNT(n.id) as count
+                 FROM tags t
+                 LEFT JOIN note_tags nt ON t.id = nt.tag_id
+                 LEFT JOIN notes n ON nt.note_id = n.id AND n.is_deleted = 0
+                 WHERE t.name LIKE ? ESCAPE '\\'
+                 GROUP BY t.id
+                 HAVING count > 0
+                 ORDER BY t.use_count DESC, count DESC, t.name ASC
+                 LIMIT ?",
+                libsql::params![pattern, limit as i64],
+            )
+            .await?;
+
+        let mut tags = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let name: String = row.get(0)?;
+            let count: i64 = row.get(1)?;
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            tags.push((name, count as usize));
+        }
+
+        Ok(tags)
+    }
+
+    async fn record_tag_completion(&self, name: &str) -> Result<()> {
+        self.execute(
+            "UPDATE tags SET use_count = use_count + 1 WHERE name = ? COLLATE NOCASE",
+            [name],
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn retag(&self, from: &[String], to: Option<&str>) -> Result<Vec<(Note, Note)>> {
+        if self.encryption.is_enabled() && !self.encryption.is_unlocked() {
+            return Err(Error::InvalidInput(
+                "Notes are encrypted; unlock them with your passphrase first".into(),
+            ));
+        }
+        self.conn.execute("BEGIN TRANSACTION", ()).await?;
+        let changed = match self.retag_notes(from, to).await {
+            Ok(changed) => changed,
+            Err(e) => {
+                self.conn.execute("ROLLBACK", ()).await.ok();
+                return Err(e);
+            }
+        };
+        if let Err(e) = self.conn.execute("COMMIT", ()).await {
+            self.conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+        Ok(changed)
+    }
+
+    async fn toggle_task(&self, id: &NoteId, line_index: usize) -> Result<(Note, Note)> {
+        if self.encryption.is_enabled() && !self.encryption.is_unlocked() {
+            return Err(Error::InvalidInput(
+                "Notes are encrypted; unlock them with your passphrase first".into(),
+            ));
+        }
+        self.conn.execute("BEGIN TRANSACTION", ()).await?;
+        let toggled = match self.toggle_note_task(id, line_index).await {
+            Ok(toggled) => toggled,
+            Err(e) => {
+                self.conn.execute("ROLLBACK", ()).await.ok();
+                return Err(e);
+            }
+        };
+        if let Err(e) = self.conn.execute("COMMIT", ()).await {
+            self.conn.execute("ROLLBACK", ()).await.ok();
+            return Err(e.into());
+        }
+        Ok(toggled)
     }
 
     async fn list_conflicts(&self, limit: usize) -> Result<Vec<SyncConflict>> {
+        self.list_conflicts_since(i64::MIN, limit).await
+    }
+
+    async fn list_conflicts_since(&self, since: i64, limit: usize) -> Result<Vec<SyncConflict>> {
         let mut rows = self
-            .conn
             .query(
-                "SELECT id, note_id, local_updated_at, incoming_updated_at, resolved_at, strategy
+                "SELECT id, note_id, local_updated_at, incoming_updated_at, resolved_at, strategy,
+                        local_content, incoming_content
                  FROM sync_conflicts
+                 WHERE resolved_at >= ?
                  ORDER BY resolved_at DESC, id DESC
                  LIMIT ?",
-                libsql::params![limit as i64],
+                libsql::params![since, limit as i64],
             )
             .await?;
 
         let mut conflicts = Vec::new();
         while let Some(row) = rows.next().await? {
-            conflicts.push(Self::parse_conflict(&row)?);
+            conflicts.push(self.parse_conflict(&row)?);
         }
 
         Ok(conflicts)
     }
 
+    async fn get_conflict(&self, id: i64) -> Result<Option<SyncConflict>> {
+        let mut rows = self
+            .query(
+                "SELECT id, note_id, local_updated_at, incoming_updated_at, resolved_at, strategy,
+                        local_content, incoming_content
+                 FROM sync_conflicts
+                 WHERE id = ?",
+                [id],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(self.parse_conflict(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn mark_conflict_resolved(&self, id: i64, strategy: &str) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let updated = self
+            .execute(
+                "UPDATE sync_conflicts SET strategy = ?, resolved_at = ? WHERE id = ?",
+                libsql::params![strategy, now, id],
+            )
+            .await?;
+        if updated == 0 {
+            return Err(Error::NotFound(format!("Sync conflict {id}")));
+        }
+        Ok(())
+    }
+
     async fn create_attachment(
         &self,
         note_id: &NoteId,
@@ -378,7 +1490,6 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
         r2_key: &str,
     ) -> Result<Attachment> {
         let mut rows = self
-            .conn
             .query(
                 "SELECT id FROM notes WHERE id = ? AND is_deleted = 0",
                 [note_id.as_str()],
@@ -390,32 +1501,32 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
 
         let attachment = Attachment::new(*note_id, filename, mime_type, size_bytes, r2_key)?;
 
-        self.conn
-            .execute(
-                "INSERT INTO attachments (
-                    id, note_id, filename, mime_type, size_bytes, r2_key, created_at, is_deleted
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-                libsql::params![
-                    attachment.id.as_str(),
-                    attachment.note_id.as_str(),
-                    attachment.filename.as_str(),
-                    attachment.mime_type.as_str(),
-                    attachment.size_bytes,
-                    attachment.r2_key.as_str(),
-                    attachment.created_at,
-                    i32::from(attachment.is_deleted),
-                ],
-            )
-            .await?;
+        self.execute(
+            "INSERT INTO attachments (
+                    id, note_id, filename, mime_type, size_bytes, r2_key, created_at, is_deleted,
+                    scan_status
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            libsql::params![
+                attachment.id.as_str(),
+                attachment.note_id.as_str(),
+                attachment.filename.as_str(),
+                attachment.mime_type.as_str(),
+                attachment.size_bytes,
+                attachment.r2_key.as_str(),
+                attachment.created_at,
+                i32::from(attachment.is_deleted),
+                attachment.scan_status.as_str(),
+            ],
+        )
+        .await?;
 
         Ok(attachment)
     }
 
     async fn list_attachments(&self, note_id: &NoteId) -> Result<Vec<Attachment>> {
-        let mut rows = self
-            .conn
-            .query(
-                "SELECT id, note_id, filename, mime_type, size_bytes, r2_key, created_at, is_deleted
+        let mut rows = self.query(
+                "SELECT id, note_id, filename, mime_type, size_bytes, r2_key, created_at, is_deleted,
+                        scan_status
                  FROM attachments
                  WHERE note_id = ? AND is_deleted = 0
                  ORDER BY created_at DESC, id DESC",
@@ -431,9 +1542,29 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
         Ok(attachments)
     }
 
+    async fn list_all_attachments(&self) -> Result<Vec<Attachment>> {
+        let mut rows = self
+            .query(
+                "SELECT a.id, a.note_id, a.filename, a.mime_type, a.size_bytes, a.r2_key,
+                        a.created_at, a.is_deleted, a.scan_status
+                 FROM attachments a
+                 JOIN notes n ON n.id = a.note_id
+                 WHERE a.is_deleted = 0 AND n.is_deleted = 0
+                 ORDER BY a.created_at ASC, a.id ASC",
+                (),
+            )
+            .await?;
+
+        let mut attachments = Vec::new();
+        while let Some(row) = rows.next().await? {
+            attachments.push(Self::parse_attachment(&row)?);
+        }
+
+        Ok(attachments)
+    }
+
     async fn delete_attachment(&self, attachment_id: &AttachmentId) -> Result<()> {
         let rows_affected = self
-            .conn
             .execute(
                 "UPDATE attachments
                  SET is_deleted = 1
@@ -448,12 +1579,139 @@ impl NoteRepository for LibSqlNoteRepository<'_> {
 
         Ok(())
     }
+
+    async fn set_attachment_scan_status(
+        &self,
+        attachment_id: &AttachmentId,
+        status: AttachmentScanStatus,
+    ) -> Result<()> {
+        let rows_affected = self
+            .execute(
+                "UPDATE attachments
+                 SET scan_status = ?
+                 WHERE id = ? AND is_deleted = 0",
+                libsql::params![status.as_str(), attachment_id.as_str()],
+            )
+            .await?;
+
+        if rows_affected == 0 {
+            return Err(Error::NotFound(attachment_id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    async fn set_attachment_text(
+        &self,
+        attachment_id: &AttachmentId,
+        text: Option<&str>,
+    ) -> Result<()> {
+        let rows_affected = self
+            .execute(
+                "UPDATE attachments
+                 SET extracted_text = ?
+                 WHERE id = ? AND is_deleted = 0",
+                libsql::params![text, attachment_id.as_str()],
+            )
+            .await?;
+
+        if rows_affected == 0 {
+            return Err(Error::NotFound(attachment_id.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// `AND` conditions and their parameters for the filters of a query clause,
+/// on notes aliased `n`; tags and URLs are left to the caller without
+/// `derived_tables`
+fn clause_filter_sql(clause: &QueryClause, derived_tables: bool) -> (String, Vec<Value>) {
+    let mut sql = String::new();
+    let mut params = Vec::new();
+    for term in &clause.terms {
+        match term {
+            QueryTerm::Tag(_) | QueryTerm::Url(_) if !derived_tables => {}
+            QueryTerm::Tag(tag) => {
+                sql.push_str(
+                    " AND EXISTS (
+                         SELECT 1 FROM note_tags nt JOIN tags t ON nt.tag_id = t.id
+                         WHERE nt.note_id = n.id AND t.name = ? COLLATE NOCASE
+                     )",
+                );
+                params.push(Value::Text(tag.clone()));
+            }
+            QueryTerm::Before(before) => {
+                sql.push_str(" AND n.created_at < ?");
+                params.push(Value::Integer(*before));
+            }
+            QueryTerm::After(after) => {
+                sql.push_str(" AND n.created_at >= ?");
+                params.push(Value::Integer(*after));
+            }
+            QueryTerm::HasAttachment => sql.push_str(
+                " AND EXISTS (
+                     SELECT 1 FROM attachments na
+                     WHERE na.note_id = n.id AND na.is_deleted = 0
+                 )",
+            ),
+            QueryTerm::Source(source) => {
+                sql.push_str(" AND n.source = ?");
+                params.push(Value::Text(source.as_str().to_string()));
+            }
+            QueryTerm::Url(url) => {
+                sql.push_str(
+                    " AND EXISTS (
+                         SELECT 1 FROM note_urls nu
+                         WHERE nu.note_id = n.id AND nu.url LIKE ? ESCAPE '\\'
+                     )",
+                );
+                params.push(Value::Text(url_like_pattern(url)));
+            }
+            QueryTerm::Word(_) | QueryTerm::Phrase(_) => {}
+        }
+    }
+    (sql, params)
+}
+
+/// Whether `note` is tagged `tag`, ignoring case
+fn has_tag(note: &Note, tag: &str) -> bool {
+    note.tags()
+        .iter()
+        .any(|note_tag| note_tag.eq_ignore_ascii_case(tag))
+}
+
+/// Whether `note` has the tags and URLs `clause` asks for
+fn matches_derived_terms(clause: &QueryClause, note: &Note) -> bool {
+    let derived = QueryClause {
+        terms: clause
+            .terms
+            .iter()
+            .filter(|term| matches!(term, QueryTerm::Tag(_) | QueryTerm::Url(_)))
+            .cloned()
+            .collect(),
+    };
+    derived.matches_content(note)
+}
+
+/// Escape `LIKE` wildcards so `value` matches literally with `ESCAPE '\'`
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Substring pattern for `url:` filters; `LIKE` ignores ASCII case
+fn url_like_pattern(url: &str) -> String {
+    format!("%{}%", escape_like(url))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::db::Database;
+    use crate::models::MIN_SHORT_ID_LEN;
 
     async fn setup() -> Database {
         Database::open_in_memory().await.unwrap()
@@ -488,6 +1746,90 @@ mod tests {
         assert!(notes[0].created_at >= notes[1].created_at);
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_list_after_pages_with_cursor() {
+        let db = setup().await;
+        let repo = db.note_repository();
+
+        for index in 0..5 {
+            let mut note = Note::new(format!("Note {index}"));
+            // Two notes share each timestamp to exercise the ID tiebreaker.
+            note.updated_at = 1_000 + index / 2;
+            repo.create_with_note(&note).await.unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = repo.list_after(cursor.as_ref(), 2).await.unwrap();
+            if page.is_empty() {
+                break;
+            }
+            cursor = page.last().map(NoteCursor::after);
+            seen.extend(page);
+        }
+
+        assert_eq!(seen.len(), 5);
+        assert!(seen
+            .windows(2)
+            .all(|pair| pair[0].updated_at >= pair[1].updated_at));
+        let ids: std::collections::HashSet<NoteId> = seen.iter().map(|note| note.id).collect();
+        assert_eq!(ids.len(), 5);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_pinned_and_archived_notes() {
+        let db = setup().await;
+        let repo = db.note_repository();
+
+        let mut pinned = Note::new("Pinned");
+        pinned.updated_at = 1_000;
+        repo.create_with_note(&pinned).await.unwrap();
+        let archived = repo.create("Archived").await.unwrap();
+        let plain = repo.create("Plain").await.unwrap();
+
+        let pinned = repo.set_pinned(&pinned.id, true).await.unwrap();
+        assert!(pinned.is_pinned);
+        // The flag change must win last-write-wins sync against older edits.
+        assert!(pinned.updated_at > 1_000);
+        let archived = repo.set_archived(&archived.id, true).await.unwrap();
+        assert!(archived.is_archived);
+
+        let listed: Vec<NoteId> = repo
+            .list(10, 0)
+            .await
+            .unwrap()
+            .iter()
+            .map(|note| note.id)
+            .collect();
+        assert_eq!(listed.len(), 2);
+        assert!(listed.contains(&pinned.id) && listed.contains(&plain.id));
+        assert_eq!(repo.list_after(None, 10).await.unwrap().len(), 2);
+        assert_eq!(repo.list_pinned(10).await.unwrap(), [pinned.clone()]);
+        assert_eq!(repo.list_archived(10, 0).await.unwrap(), [archived.clone()]);
+        assert_eq!(repo.search("Archived", 10).await.unwrap().len(), 1);
+
+        // Archiving a pinned note takes it out of the pinned list too.
+        repo.set_archived(&pinned.id, true).await.unwrap();
+        assert!(repo.list_pinned(10).await.unwrap().is_empty());
+
+        let restored = repo.set_archived(&archived.id, false).await.unwrap();
+        assert!(!restored.is_archived);
+        assert!(repo.get(&archived.id).await.unwrap().is_some());
+        assert!(repo
+            .list(10, 0)
+            .await
+            .unwrap()
+            .iter()
+            .any(|note| note.id == archived.id));
+
+        repo.delete(&plain.id).await.unwrap();
+        assert!(matches!(
+            repo.set_pinned(&plain.id, true).await,
+            Err(Error::NotFound(_))
+        ));
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn test_update() {
         let db = setup().await;
@@ -500,6 +1842,41 @@ mod tests {
         assert!(updated.updated_at >= note.updated_at);
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_toggle_task() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let note = repo
+            .create("Errands #todo\n- [ ] post office\n- [x] bank")
+            .await
+            .unwrap();
+        let (before, after) = repo.toggle_task(&note.id, 1).await.unwrap();
+        assert_eq!(before.content, note.content);
+        assert_eq!(
+            after.content,
+            "Errands #todo\n- [x] post office\n- [x] bank"
+        );
+        assert_eq!(repo.list_by_tag("todo", 10, 0).await.unwrap().len(), 1);
+
+        let (_, after) = repo.toggle_task(&note.id, 2).await.unwrap();
+        assert_eq!(
+            after.content,
+            "Errands #todo\n- [x] post office\n- [ ] bank"
+        );
+
+        assert!(matches!(
+            repo.toggle_task(&note.id, 0).await,
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            repo.toggle_task(&NoteId::new(), 1).await,
+            Err(Error::NotFound(_))
+        ));
+        let stored = repo.get(&note.id).await.unwrap().unwrap();
+        assert_eq!(stored.content, after.content);
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn test_delete() {
         let db = setup().await;
@@ -517,6 +1894,53 @@ mod tests {
         assert!(notes.is_empty());
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_trash_restore_and_purge() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let kept = repo.create("Kept").await.unwrap();
+        let recent = repo.create("Recently deleted").await.unwrap();
+        let old = repo.create("Deleted long ago").await.unwrap();
+        repo.create_attachment(&old.id, "scan.png", "image/png", 3, "notes/o/scan.png")
+            .await
+            .unwrap();
+        repo.delete(&recent.id).await.unwrap();
+        repo.delete(&old.id).await.unwrap();
+        repo.conn
+            .execute(
+                "UPDATE notes SET updated_at = 0 WHERE id = ?",
+                [old.id.as_str()],
+            )
+            .await
+            .unwrap();
+
+        let trashed = repo.list_trashed(10, 0).await.unwrap();
+        assert_eq!(
+            trashed.iter().map(|note| note.id).collect::<Vec<_>>(),
+            vec![recent.id, old.id]
+        );
+
+        let purge = repo.purge_older_than(30).await.unwrap();
+        assert_eq!(purge.notes, 1);
+        assert_eq!(purge.attachments.len(), 1);
+        assert_eq!(purge.attachments[0].r2_key, "notes/o/scan.png");
+        assert!(repo.list_attachments(&old.id).await.unwrap().is_empty());
+
+        let restored = repo.restore(&recent.id).await.unwrap();
+        assert_eq!(restored.content, "Recently deleted");
+        assert!(!restored.is_deleted);
+        assert!(matches!(
+            repo.restore(&kept.id).await,
+            Err(Error::NotFound(_))
+        ));
+
+        repo.delete(&recent.id).await.unwrap();
+        assert_eq!(repo.purge_older_than(0).await.unwrap().notes, 1);
+        assert!(repo.list_trashed(10, 0).await.unwrap().is_empty());
+        assert_eq!(repo.list(10, 0).await.unwrap().len(), 1);
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn test_search() {
         let db = setup().await;
@@ -530,6 +1954,161 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_search_structured_queries() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let mut plan = Note::new("Release plan for the launch #work");
+        plan.created_at = 1_704_067_200_000; // 2024-01-01
+        let plan = repo.create_with_note(&plan).await.unwrap();
+        let mut groceries = Note::new("Groceries: plan meals #home");
+        groceries.created_at = 1_706_745_600_000; // 2024-02-01
+        let groceries = repo.create_with_note(&groceries).await.unwrap();
+        let scan = repo.create("Scanned receipt").await.unwrap();
+        repo.create_attachment(
+            &scan.id,
+            "receipt.pdf",
+            "application/pdf",
+            3,
+            "notes/s/r.pdf",
+        )
+        .await
+        .unwrap();
+
+        let ids = |notes: Vec<Note>| notes.into_iter().map(|note| note.id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(repo.search("plan tag:work", 10).await.unwrap()),
+            vec![plan.id]
+        );
+        assert_eq!(
+            ids(repo.search("tag:home", 10).await.unwrap()),
+            vec![groceries.id]
+        );
+        assert_eq!(
+            ids(repo.search("\"plan meals\"", 10).await.unwrap()),
+            vec![groceries.id]
+        );
+        assert_eq!(
+            ids(repo.search("plan before:2024-01-15", 10).await.unwrap()),
+            vec![plan.id]
+        );
+        assert_eq!(
+            ids(repo.search("plan after:2024-01-15", 10).await.unwrap()),
+            vec![groceries.id]
+        );
+        assert_eq!(
+            ids(repo.search("has:attachment", 10).await.unwrap()),
+            vec![scan.id]
+        );
+        assert_eq!(
+            ids(repo.search("launch OR receipt", 10).await.unwrap()),
+            vec![plan.id, scan.id]
+        );
+        assert_eq!(repo.search("launch OR receipt", 1).await.unwrap().len(), 1);
+        assert_eq!(
+            repo.search("tag:work AND tag:home", 10)
+                .await
+                .unwrap()
+                .len(),
+            0
+        );
+        assert_eq!(repo.search("plan*", 10).await.unwrap().len(), 2);
+        // FTS5 syntax is searched for literally instead of failing.
+        assert!(repo.search("NEAR(", 10).await.unwrap().is_empty());
+        assert!(repo.search("before:yesterday", 10).await.is_err());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_search_hits_include_attachment_matches() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let content_match = repo.create("Quarterly budget review").await.unwrap();
+        let with_pdf = repo.create("Scanned paperwork").await.unwrap();
+        let with_text = repo.create("Meeting notes").await.unwrap();
+        repo.create_attachment(
+            &with_pdf.id,
+            "budget-2026.pdf",
+            "application/pdf",
+            10,
+            "notes/a/budget-2026.pdf",
+        )
+        .await
+        .unwrap();
+        let minutes = repo
+            .create_attachment(
+                &with_text.id,
+                "minutes.txt",
+                "text/plain",
+                10,
+                "notes/b/minutes.txt",
+            )
+            .await
+            .unwrap();
+        repo.set_attachment_text(&minutes.id, Some("Agreed the budget for Q3"))
+            .await
+            .unwrap();
+
+        let hits = repo.search_hits("budget", 10, false).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].note.id, content_match.id);
+        assert_eq!(hits[0].matched_attachment, None);
+
+        let hits = repo.search_hits("budget", 10, true).await.unwrap();
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits[0].note.id, content_match.id);
+        let attachment_matches: Vec<(NoteId, Option<&str>)> = hits[1..]
+            .iter()
+            .map(|hit| (hit.note.id, hit.matched_attachment.as_deref()))
+            .collect();
+        assert!(attachment_matches.contains(&(with_pdf.id, Some("budget-2026.pdf"))));
+        assert!(attachment_matches.contains(&(with_text.id, Some("minutes.txt"))));
+        assert_eq!(repo.search_hits("budget", 2, true).await.unwrap().len(), 2);
+
+        repo.set_attachment_text(&minutes.id, None).await.unwrap();
+        repo.delete(&with_pdf.id).await.unwrap();
+        let hits = repo.search_hits("budget", 10, true).await.unwrap();
+        assert_eq!(hits.len(), 1);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_source_filter_and_counts() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let cli = repo
+            .create_with_source(&Note::new("Buy milk"), NoteSource::Cli)
+            .await
+            .unwrap();
+        repo.create_with_source(&Note::new("Milk recipe"), NoteSource::MobileShare)
+            .await
+            .unwrap();
+        repo.create_with_source(&Note::new("Call Bo"), NoteSource::Cli)
+            .await
+            .unwrap();
+        repo.create("Legacy milk").await.unwrap();
+
+        assert_eq!(
+            repo.get_source(&cli.id).await.unwrap(),
+            Some(NoteSource::Cli)
+        );
+        assert_eq!(repo.search("source:cli", 10).await.unwrap().len(), 2);
+        let results = repo.search("milk source:cli", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, cli.id);
+        assert_eq!(repo.search("milk", 10).await.unwrap().len(), 3);
+
+        assert_eq!(
+            repo.count_by_source().await.unwrap(),
+            vec![
+                (NoteSource::Cli, 2),
+                (NoteSource::MobileShare, 1),
+                (NoteSource::Unknown, 1),
+            ]
+        );
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn test_tags() {
         let db = setup().await;
@@ -549,6 +2128,59 @@ mod tests {
         assert_eq!(rust_tag.1, 2);
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_suggest_tags_prefers_completed_tags() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        repo.create("#project one").await.unwrap();
+        repo.create("#project two").await.unwrap();
+        repo.create("#programming").await.unwrap();
+        repo.create("#rust").await.unwrap();
+
+        let tags = repo.suggest_tags("PRO", 10).await.unwrap();
+        let names: Vec<&str> = tags.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["project", "programming"]);
+
+        repo.record_tag_completion("programming").await.unwrap();
+        repo.record_tag_completion("programming").await.unwrap();
+
+        let tags = repo.suggest_tags("pro", 1).await.unwrap();
+        assert_eq!(tags, vec![("programming".to_string(), 1)]);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_suggest_tags_escapes_like_wildcards() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        repo.create("#a_b and #axb").await.unwrap();
+
+        let tags = repo.suggest_tags("a_", 10).await.unwrap();
+        assert_eq!(tags, vec![("a_b".to_string(), 1)]);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_short_id_len_covers_ids_sharing_a_prefix() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        assert_eq!(repo.short_id_len().await.unwrap(), MIN_SHORT_ID_LEN);
+
+        // Two devices capturing in the same millisecond share the timestamp bits.
+        for id in [
+            "0192a000-1111-7000-8000-00000000000a",
+            "0192a000-1111-7000-8000-00000000000b",
+        ] {
+            let note = Note {
+                id: id.parse().unwrap(),
+                ..Note::new("Synced")
+            };
+            repo.create_with_note(&note).await.unwrap();
+        }
+        assert_eq!(repo.short_id_len().await.unwrap(), 36);
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn test_list_by_tag() {
         let db = setup().await;
@@ -562,6 +2194,110 @@ mod tests {
         assert_eq!(notes.len(), 2);
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_list_urls_and_url_search_facet() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let research = repo
+            .create("Paper https://arxiv.org/abs/1 #research")
+            .await
+            .unwrap();
+        let other = repo
+            .create("Repo https://github.com/a/b and https://docs.rs/x")
+            .await
+            .unwrap();
+        repo.create("No links here").await.unwrap();
+
+        let all = repo.list_urls(None, 10).await.unwrap();
+        assert_eq!(all.len(), 3);
+        let tagged = repo.list_urls(Some("Research"), 10).await.unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].url, "https://arxiv.org/abs/1");
+        assert_eq!(tagged[0].note.id, research.id);
+
+        let by_url = repo.search("url:GITHUB.com", 10).await.unwrap();
+        assert_eq!(by_url.len(), 1);
+        assert_eq!(by_url[0].id, other.id);
+        assert_eq!(repo.search("url:", 10).await.unwrap().len(), 2);
+        assert_eq!(repo.search("repo url:docs.rs", 10).await.unwrap().len(), 1);
+        assert!(repo
+            .search("paper url:docs.rs", 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        repo.update(&other.id, "Links removed").await.unwrap();
+        assert_eq!(repo.list_urls(None, 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_backlinks_match_title_and_id() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let plan = repo.create("Project Plan\nMilestones").await.unwrap();
+        let by_title = repo.create("See [[project plan]]").await.unwrap();
+        let by_id = repo
+            .create(&format!("Details in [[{}]]", plan.id))
+            .await
+            .unwrap();
+        repo.create("Unrelated [[Other]]").await.unwrap();
+        repo.update(&plan.id, "Project Plan\nSee also [[Project Plan]]")
+            .await
+            .unwrap();
+
+        let backlinks = repo.backlinks(&plan.id).await.unwrap();
+        let mut ids = backlinks.iter().map(|note| note.id).collect::<Vec<_>>();
+        ids.sort_by_key(ToString::to_string);
+        let mut expected = vec![by_title.id, by_id.id];
+        expected.sort_by_key(ToString::to_string);
+        assert_eq!(ids, expected);
+
+        repo.update(&by_title.id, "No link any more").await.unwrap();
+        repo.delete(&by_id.id).await.unwrap();
+        assert!(repo.backlinks(&plan.id).await.unwrap().is_empty());
+        assert!(matches!(
+            repo.backlinks(&by_id.id).await,
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_get_or_create_daily_reuses_the_days_note() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+        let date = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+
+        repo.create("2026-03-14\nNot tagged").await.unwrap();
+        let daily = repo
+            .get_or_create_daily(date, NoteSource::Cli)
+            .await
+            .unwrap();
+        assert_eq!(daily.content, "2026-03-14\n#daily");
+        assert_eq!(
+            repo.get_source(&daily.id).await.unwrap(),
+            Some(NoteSource::Cli)
+        );
+
+        repo.update(&daily.id, "2026-03-14\n#daily\n\nWrote tests")
+            .await
+            .unwrap();
+        let again = repo
+            .get_or_create_daily(date, NoteSource::Desktop)
+            .await
+            .unwrap();
+        assert_eq!(again.id, daily.id);
+        assert_eq!(again.content, "2026-03-14\n#daily\n\nWrote tests");
+
+        let next_day = repo
+            .get_or_create_daily(date.succ_opt().unwrap(), NoteSource::Cli)
+            .await
+            .unwrap();
+        assert_ne!(next_day.id, daily.id);
+        assert_eq!(next_day.content, "2026-03-15\n#daily");
+    }
+
     #[tokio::test(flavor = "current_thread")]
     async fn test_lww_stale_update_is_ignored_and_logged() {
         let db = setup().await;
@@ -588,6 +2324,37 @@ mod tests {
         assert_eq!(conflicts[0].local_updated_at, note.updated_at);
         assert_eq!(conflicts[0].incoming_updated_at, stale_ts);
         assert_eq!(conflicts[0].strategy, "lww");
+        assert_eq!(conflicts[0].local_content.as_deref(), Some("Current value"));
+        assert_eq!(
+            conflicts[0].incoming_content.as_deref(),
+            Some("Stale value")
+        );
+
+        let resolved_at = conflicts[0].resolved_at;
+        let since = repo.list_conflicts_since(resolved_at, 10).await.unwrap();
+        assert_eq!(since.len(), 1);
+        let later = repo
+            .list_conflicts_since(resolved_at + 1, 10)
+            .await
+            .unwrap();
+        assert!(later.is_empty());
+
+        let conflict_id = conflicts[0].id;
+        assert_eq!(
+            repo.get_conflict(conflict_id).await.unwrap(),
+            Some(conflicts[0].clone())
+        );
+        repo.mark_conflict_resolved(conflict_id, "keep-remote")
+            .await
+            .unwrap();
+        let resolved = repo.get_conflict(conflict_id).await.unwrap().unwrap();
+        assert_eq!(resolved.strategy, "keep-remote");
+        assert!(resolved.resolved_at >= resolved_at);
+        assert!(repo.get_conflict(conflict_id + 1).await.unwrap().is_none());
+        assert!(repo
+            .mark_conflict_resolved(conflict_id + 1, "manual")
+            .await
+            .is_err());
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -652,6 +2419,48 @@ mod tests {
         let attachments = repo.list_attachments(&note.id).await.unwrap();
         assert_eq!(attachments.len(), 1);
         assert_eq!(attachments[0].id, first.id);
+        assert_eq!(attachments[0].scan_status, AttachmentScanStatus::Unscanned);
+
+        repo.set_attachment_scan_status(&first.id, AttachmentScanStatus::Quarantined)
+            .await
+            .unwrap();
+        let attachments = repo.list_attachments(&note.id).await.unwrap();
+        assert_eq!(
+            attachments[0].scan_status,
+            AttachmentScanStatus::Quarantined
+        );
+
+        let result = repo
+            .set_attachment_scan_status(&second.id, AttachmentScanStatus::Clean)
+            .await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_list_all_attachments_skips_deleted_notes() {
+        let db = setup().await;
+        let repo = LibSqlNoteRepository::new(db.connection());
+
+        let kept = repo.create("Kept").await.unwrap();
+        let trashed = repo.create("Trashed").await.unwrap();
+        let first = repo
+            .create_attachment(&kept.id, "a.png", "image/png", 10, "notes/kept/a.png")
+            .await
+            .unwrap();
+        let removed = repo
+            .create_attachment(&kept.id, "b.png", "image/png", 20, "notes/kept/b.png")
+            .await
+            .unwrap();
+        repo.create_attachment(&trashed.id, "c.png", "image/png", 30, "notes/trashed/c.png")
+            .await
+            .unwrap();
+
+        repo.delete_attachment(&removed.id).await.unwrap();
+        repo.delete(&trashed.id).await.unwrap();
+
+        let attachments = repo.list_all_attachments().await.unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].id, first.id);
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -671,4 +2480,36 @@ mod tests {
             .await;
         assert!(matches!(result, Err(Error::NotFound(_))));
     }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_sync_filter_holds_back_excluded_notes() {
+        let db = setup().await;
+        let mut filter = SyncFilter::new(
+            crate::crypto::ContentKey::generate(),
+            vec!["private".into()],
+        );
+        let private = LibSqlNoteRepository::new(db.connection())
+            .with_sync_filter(Some(&filter))
+            .create("Diary https://example.com #private")
+            .await
+            .unwrap();
+        let plain = LibSqlNoteRepository::new(db.connection());
+        let stored = plain.get(&private.id).await.unwrap().unwrap();
+        assert!(is_sync_excluded(&stored.content));
+        assert!(!stored.content.contains("Diary"));
+        assert!(plain.list_urls(None, 10).await.unwrap().is_empty());
+
+        let repo = LibSqlNoteRepository::new(db.connection()).with_sync_filter(Some(&filter));
+        let shown = repo.get(&private.id).await.unwrap().unwrap();
+        assert_eq!(shown.content, "Diary https://example.com #private");
+        assert_eq!(repo.search("diary", 10).await.unwrap().len(), 1);
+
+        filter.set_excluded_tags(Vec::new());
+        let repo = LibSqlNoteRepository::new(db.connection()).with_sync_filter(Some(&filter));
+        assert_eq!(repo.apply_sync_filter().await.unwrap(), 1);
+        let released = plain.get(&private.id).await.unwrap().unwrap();
+        assert_eq!(released.content, "Diary https://example.com #private");
+        assert_eq!(plain.list_urls(None, 10).await.unwrap().len(), 1);
+        assert_eq!(repo.apply_sync_filter().await.unwrap(), 0);
+    }
 }
@@ -0,0 +1,357 @@
+//! Note revision repository implementation
+
+use crate::crypto::ContentEncryption;
+use crate::error::{Error, Result};
+use crate::models::{NoteId, NoteRevision};
+use crate::sync::{is_sync_excluded, SyncFilter};
+use libsql::Connection;
+
+/// Trait for note revision history (async)
+#[allow(async_fn_in_trait)]
+pub trait NoteRevisionRepository {
+    /// Record `content` as the next revision of a note
+    async fn record(
+        &self,
+        note_id: &NoteId,
+        content: &str,
+        saved_at: i64,
+        device_id: Option<&str>,
+    ) -> Result<NoteRevision>;
+
+    /// List the revisions of a note, newest first
+    async fn list(&self, note_id: &NoteId) -> Result<Vec<NoteRevision>>;
+
+    /// Get one revision of a note
+    async fn get(&self, note_id: &NoteId, revision: i64) -> Result<Option<NoteRevision>>;
+
+    /// Get the newest revision of a note
+    async fn latest(&self, note_id: &NoteId) -> Result<Option<NoteRevision>>;
+
+    /// Drop all but the newest `keep` revisions of a note; returns how many
+    /// were removed
+    async fn prune(&self, note_id: &NoteId, keep: u32) -> Result<u64>;
+
+    /// Drop all but the newest `keep` revisions of every note; returns how
+    /// many were removed
+    async fn prune_all(&self, keep: u32) -> Result<u64>;
+
+    /// Encrypt every revision still stored in plaintext; returns how many
+    /// were rewritten
+    async fn encrypt_stored_content(&self) -> Result<u64>;
+
+    /// Hold back or release every revision of this device whose tags no
+    /// longer match the sync filter; returns how many were rewritten
+    async fn apply_sync_filter(&self) -> Result<u64>;
+}
+
+/// libSQL implementation of `NoteRevisionRepository`
+pub struct LibSqlNoteRevisionRepository<'a> {
+    conn: &'a Connection,
+    encryption: &'a ContentEncryption,
+    sync_filter: Option<&'a SyncFilter>,
+}
+
+impl<'a> LibSqlNoteRevisionRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self {
+            conn,
+            encryption: &ContentEncryption::Off,
+            sync_filter: None,
+        }
+    }
+
+    /// Encrypt content on write and decrypt it on read per `encryption`
+    #[must_use]
+    pub const fn with_encryption(mut self, encryption: &'a ContentEncryption) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    /// Store a placeholder instead of revisions `sync_filter` holds back
+    #[must_use]
+    pub const fn with_sync_filter(mut self, sync_filter: Option<&'a SyncFilter>) -> Self {
+        self.sync_filter = sync_filter;
+        self
+    }
+
+    /// Parse a revision from a database row
+    fn parse_revision(&self, row: &libsql::Row) -> Result<NoteRevision> {
+        let note_id: String = row.get(0)?;
+        Ok(NoteRevision {
+            note_id: note_id
+                .parse()
+                .map_err(|_| Error::InvalidInput("Invalid note ID".into()))?,
+            revision: row.get(1)?,
+            content: {
+                let content = self.encryption.decrypt_content(row.get(2)?)?;
+                match self.sync_filter {
+                    Some(filter) => filter.restore(content),
+                    None => content,
+                }
+            },
+            saved_at: row.get(3)?,
+            device_id: row.get::<Option<String>>(4)?,
+        })
+    }
+}
+
+impl NoteRevisionRepository for LibSqlNoteRevisionRepository<'_> {
+    async fn record(
+        &self,
+        note_id: &NoteId,
+        content: &str,
+        saved_at: i64,
+        device_id: Option<&str>,
+    ) -> Result<NoteRevision> {
+        let held_back = match self.sync_filter {
+            Some(filter) => filter.hold_back(content)?,
+            None => None,
+        };
+        let stored_content = self
+            .encryption
+            .encrypt_content(held_back.as_deref().unwrap_or(content))?;
+        self.conn
+            .execute(
+                "INSERT INTO note_revisions (note_id, revision, content, saved_at, device_id)
+                 VALUES (
+                    ?,
+                    (SELECT COALESCE(MAX(revision), 0) + 1 FROM note_revisions WHERE note_id = ?),
+                    ?, ?, ?
+                 )",
+                libsql::params![
+                    note_id.as_str(),
+                    note_id.as_str(),
+                    stored_content,
+                    saved_at,
+                    device_id
+                ],
+            )
+            .await?;
+
+        self.latest(note_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(note_id.to_string()))
+    }
+
+    async fn list(&self, note_id: &NoteId) -> Result<Vec<NoteRevision>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT note_id, revision, content, saved_at, device_id
+                 FROM note_revisions
+                 WHERE note_id = ?
+                 ORDER BY revision DESC",
+                [note_id.as_str()],
+            )
+            .await?;
+
+        let mut revisions = Vec::new();
+        while let Some(row) = rows.next().await? {
+            revisions.push(self.parse_revision(&row)?);
+        }
+
+        Ok(revisions)
+    }
+
+    async fn get(&self, note_id: &NoteId, revision: i64) -> Result<Option<NoteRevision>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT note_id, revision, content, saved_at, device_id
+                 FROM note_revisions
+                 WHERE note_id = ? AND revision = ?",
+                libsql::params![note_id.as_str(), revision],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(self.parse_revision(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn latest(&self, note_id: &NoteId) -> Result<Option<NoteRevision>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT note_id, revision, content, saved_at, device_id
+                 FROM note_revisions
+                 WHERE note_id = ?
+                 ORDER BY revision DESC
+                 LIMIT 1",
+                [note_id.as_str()],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(self.parse_revision(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn prune(&self, note_id: &NoteId, keep: u32) -> Result<u64> {
+        // Revisions are numbered without gaps and pruned oldest first, so the
+        // newest `keep` are the ones above `MAX(revision) - keep`.
+        let removed = self
+            .conn
+            .execute(
+                "DELETE FROM note_revisions
+                 WHERE note_id = ?
+                   AND revision <= (
+                       SELECT MAX(revision) FROM note_revisions WHERE note_id = ?
+                   ) - ?",
+                libsql::params![note_id.as_str(), note_id.as_str(), i64::from(keep)],
+            )
+            .await?;
+        Ok(removed)
+    }
+
+    async fn prune_all(&self, keep: u32) -> Result<u64> {
+        let removed = self
+            .conn
+            .execute(
+                "DELETE FROM note_revisions
+                 WHERE revision <= (
+                     SELECT MAX(newer.revision) FROM note_revisions newer
+                     WHERE newer.note_id = note_revisions.note_id
+                 ) - ?",
+                [i64::from(keep)],
+            )
+            .await?;
+        Ok(removed)
+    }
+
+    async fn encrypt_stored_content(&self) -> Result<u64> {
+        if !self.encryption.is_unlocked() {
+            return Err(Error::InvalidInput(
+                "Unlock encryption before encrypting revisions".into(),
+            ));
+        }
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT note_id, revision, content FROM note_revisions
+                 WHERE content NOT LIKE 'dirt-enc:v1:%'",
+                (),
+            )
+            .await?;
+        let mut plaintext_revisions = Vec::new();
+        while let Some(row) = rows.next().await? {
+            plaintext_revisions.push((
+                row.get::<String>(0)?,
+                row.get::<i64>(1)?,
+                row.get::<String>(2)?,
+            ));
+        }
+
+        let mut rewritten = 0;
+        for (note_id, revision, content) in plaintext_revisions {
+            rewritten += self
+                .conn
+                .execute(
+                    "UPDATE note_revisions SET content = ? WHERE note_id = ? AND revision = ?",
+                    libsql::params![
+                        self.encryption.encrypt_content(&content)?,
+                        note_id,
+                        revision
+                    ],
+                )
+                .await?;
+        }
+        Ok(rewritten)
+    }
+
+    async fn apply_sync_filter(&self) -> Result<u64> {
+        let Some(filter) = self.sync_filter else {
+            return Ok(0);
+        };
+        let mut rows = self
+            .conn
+            .query("SELECT note_id, revision, content FROM note_revisions", ())
+            .await?;
+        let mut stored_revisions = Vec::new();
+        while let Some(row) = rows.next().await? {
+            stored_revisions.push((
+                row.get::<String>(0)?,
+                row.get::<i64>(1)?,
+                row.get::<String>(2)?,
+            ));
+        }
+
+        let mut rewritten = 0;
+        for (note_id, revision, stored) in stored_revisions {
+            let stored = self.encryption.decrypt_content(stored)?;
+            let held_back = is_sync_excluded(&stored);
+            let content = filter.restore(stored);
+            if is_sync_excluded(&content) || held_back == filter.excludes(&content) {
+                continue;
+            }
+            let held_back = filter.hold_back(&content)?;
+            rewritten += self
+                .conn
+                .execute(
+                    "UPDATE note_revisions SET content = ? WHERE note_id = ? AND revision = ?",
+                    libsql::params![
+                        self.encryption
+                            .encrypt_content(held_back.as_deref().unwrap_or(&content))?,
+                        note_id,
+                        revision
+                    ],
+                )
+                .await?;
+        }
+        Ok(rewritten)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, LibSqlNoteRepository, NoteRepository};
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_record_list_and_prune_revisions() {
+        let db = Database::open_in_memory().await.unwrap();
+        let notes = LibSqlNoteRepository::new(db.connection());
+        let revisions = LibSqlNoteRevisionRepository::new(db.connection());
+
+        let note = notes.create("Draft").await.unwrap();
+        let other = notes.create("Other").await.unwrap();
+        for (index, content) in ["One", "Two", "Three", "Four"].into_iter().enumerate() {
+            let saved_at = 1_000 + i64::try_from(index).unwrap();
+            let recorded = revisions
+                .record(&note.id, content, saved_at, Some("laptop"))
+                .await
+                .unwrap();
+            assert_eq!(recorded.revision, saved_at - 999);
+        }
+        revisions.record(&other.id, "Other", 5, None).await.unwrap();
+
+        let listed = revisions.list(&note.id).await.unwrap();
+        assert_eq!(
+            listed
+                .iter()
+                .map(|revision| revision.content.as_str())
+                .collect::<Vec<_>>(),
+            ["Four", "Three", "Two", "One"]
+        );
+        assert_eq!(listed[0].device_id.as_deref(), Some("laptop"));
+        let second = revisions.get(&note.id, 2).await.unwrap().unwrap();
+        assert_eq!(second.content, "Two");
+        assert!(revisions.get(&note.id, 9).await.unwrap().is_none());
+
+        assert_eq!(revisions.prune(&note.id, 3).await.unwrap(), 1);
+        assert!(revisions.get(&note.id, 1).await.unwrap().is_none());
+        // Numbers keep counting after a prune.
+        let fifth = revisions
+            .record(&note.id, "Five", 2_000, None)
+            .await
+            .unwrap();
+        assert_eq!(fifth.revision, 5);
+
+        assert_eq!(revisions.prune_all(1).await.unwrap(), 3);
+        assert_eq!(revisions.list(&note.id).await.unwrap(), [fifth]);
+        assert_eq!(revisions.list(&other.id).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,116 @@
+//! Routing rule repository implementation
+
+use crate::error::{Error, Result};
+use crate::rules::{RoutingRule, RuleAction, RuleCondition};
+use libsql::Connection;
+
+/// Trait for routing rule storage (async)
+#[allow(async_fn_in_trait)]
+pub trait RoutingRuleRepository {
+    /// Store a rule after the existing ones
+    async fn add(&self, condition: &RuleCondition, action: &RuleAction) -> Result<RoutingRule>;
+
+    /// List rules in the order they run
+    async fn list(&self) -> Result<Vec<RoutingRule>>;
+
+    /// Remove a rule; returns whether it existed
+    async fn remove(&self, id: i64) -> Result<bool>;
+}
+
+/// libSQL implementation of `RoutingRuleRepository`
+pub struct LibSqlRoutingRuleRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlRoutingRuleRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+}
+
+impl RoutingRuleRepository for LibSqlRoutingRuleRepository<'_> {
+    async fn add(&self, condition: &RuleCondition, action: &RuleAction) -> Result<RoutingRule> {
+        let created_at = chrono::Utc::now().timestamp_millis();
+        self.conn
+            .execute(
+                "INSERT INTO routing_rules (condition, action, created_at) VALUES (?, ?, ?)",
+                libsql::params![condition.to_string(), action.to_string(), created_at],
+            )
+            .await?;
+
+        Ok(RoutingRule {
+            id: self.conn.last_insert_rowid(),
+            condition: condition.clone(),
+            action: action.clone(),
+            created_at,
+        })
+    }
+
+    async fn list(&self) -> Result<Vec<RoutingRule>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT id, condition, action, created_at FROM routing_rules ORDER BY id",
+                (),
+            )
+            .await?;
+
+        let mut rules = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let id: i64 = row.get(0)?;
+            let condition: String = row.get(1)?;
+            let action: String = row.get(2)?;
+            rules.push(RoutingRule {
+                id,
+                condition: condition.parse().map_err(|error| {
+                    Error::Database(format!("Invalid condition in routing rule {id}: {error}"))
+                })?,
+                action: action.parse().map_err(|error| {
+                    Error::Database(format!("Invalid action in routing rule {id}: {error}"))
+                })?,
+                created_at: row.get(3)?,
+            });
+        }
+
+        Ok(rules)
+    }
+
+    async fn remove(&self, id: i64) -> Result<bool> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM routing_rules WHERE id = ?", [id])
+            .await?;
+        Ok(removed > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_add_list_and_remove_rules() {
+        let db = Database::open_in_memory().await.unwrap();
+        let repo = LibSqlRoutingRuleRepository::new(db.connection());
+
+        let first = repo
+            .add(&"#work".parse().unwrap(), &"#office".parse().unwrap())
+            .await
+            .unwrap();
+        let second = repo
+            .add(
+                &"source:email-in".parse().unwrap(),
+                &"#inbox".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(repo.list().await.unwrap(), vec![first.clone(), second]);
+
+        assert!(repo.remove(first.id).await.unwrap());
+        assert!(!repo.remove(first.id).await.unwrap());
+        assert_eq!(repo.list().await.unwrap().len(), 1);
+    }
+}
@@ -1,7 +1,9 @@
 //! Settings repository implementation
 
 use crate::error::{Error, Result};
+use crate::export::parse_no_export_tags;
 use crate::models::Settings;
+use crate::util::normalize_text_option;
 use libsql::Connection;
 
 /// Trait for settings storage operations (async)
@@ -41,6 +43,14 @@ impl SettingsRepository for LibSqlSettingsRepository<'_> {
             })?;
         }
 
+        if let Some(value) = self.get_setting_optional("focus_line_width").await? {
+            settings.focus_line_width = value.parse::<u32>().map_err(|error| {
+                Error::InvalidInput(format!(
+                    "Invalid settings value for 'focus_line_width': {error}"
+                ))
+            })?;
+        }
+
         if let Some(value) = self.get_setting_optional("theme").await? {
             settings.theme = serde_json::from_str(&format!("\"{value}\"")).map_err(|error| {
                 Error::InvalidInput(format!("Invalid settings value for 'theme': {error}"))
@@ -51,6 +61,15 @@ impl SettingsRepository for LibSqlSettingsRepository<'_> {
             settings.capture_hotkey = value;
         }
 
+        if let Some(value) = self.get_setting_optional("transcription_provider").await? {
+            settings.transcription_provider = serde_json::from_str(&format!("\"{value}\""))
+                .map_err(|error| {
+                    Error::InvalidInput(format!(
+                        "Invalid settings value for 'transcription_provider': {error}"
+                    ))
+                })?;
+        }
+
         if let Some(value) = self
             .get_setting_optional("voice_memo_transcription_enabled")
             .await?
@@ -59,6 +78,92 @@ impl SettingsRepository for LibSqlSettingsRepository<'_> {
                 Self::parse_bool_setting("voice_memo_transcription_enabled", &value)?;
         }
 
+        if let Some(value) = self.get_setting_optional("weekly_digest_enabled").await? {
+            settings.weekly_digest_enabled =
+                Self::parse_bool_setting("weekly_digest_enabled", &value)?;
+        }
+
+        for (key, target) in [
+            ("notify_sync_failures", &mut settings.notify_sync_failures),
+            ("notify_sync_conflicts", &mut settings.notify_sync_conflicts),
+            (
+                "notify_export_complete",
+                &mut settings.notify_export_complete,
+            ),
+            ("notify_reminders", &mut settings.notify_reminders),
+            (
+                "open_folder_after_save",
+                &mut settings.open_folder_after_save,
+            ),
+            (
+                "capture_link_daily_note",
+                &mut settings.capture_link_daily_note,
+            ),
+            (
+                "capture_append_daily_note",
+                &mut settings.capture_append_daily_note,
+            ),
+            ("capture_copy_note_id", &mut settings.capture_copy_note_id),
+            ("image_strip_metadata", &mut settings.image_strip_metadata),
+            (
+                "os_search_index_enabled",
+                &mut settings.os_search_index_enabled,
+            ),
+            ("focus_mode", &mut settings.focus_mode),
+            (
+                "focus_typewriter_scrolling",
+                &mut settings.focus_typewriter_scrolling,
+            ),
+            ("share_target_enabled", &mut settings.share_target_enabled),
+        ] {
+            if let Some(value) = self.get_setting_optional(key).await? {
+                *target = Self::parse_bool_setting(key, &value)?;
+            }
+        }
+
+        for (key, target) in [
+            (
+                "attachment_download_dir",
+                &mut settings.attachment_download_dir,
+            ),
+            ("capture_default_tag", &mut settings.capture_default_tag),
+            ("capture_webhook_url", &mut settings.capture_webhook_url),
+            ("last_seen_version", &mut settings.last_seen_version),
+            ("whisper_model", &mut settings.whisper_model),
+        ] {
+            if let Some(value) = self.get_setting_optional(key).await? {
+                *target = normalize_text_option(Some(value));
+            }
+        }
+
+        if let Some(value) = self.get_setting_optional("no_export_tags").await? {
+            settings.no_export_tags = parse_no_export_tags(&value);
+        }
+        if let Some(value) = self.get_setting_optional("sync_excluded_tags").await? {
+            settings.sync_excluded_tags = parse_no_export_tags(&value);
+        }
+
+        for (key, target) in [
+            ("image_max_dimension", &mut settings.image_max_dimension),
+            ("focus_word_goal", &mut settings.focus_word_goal),
+            ("note_count_warning", &mut settings.note_count_warning),
+            (
+                "database_size_warning_mb",
+                &mut settings.database_size_warning_mb,
+            ),
+            ("revision_retention", &mut settings.revision_retention),
+            ("attachment_cache_mb", &mut settings.attachment_cache_mb),
+        ] {
+            if let Some(value) = self.get_setting_optional(key).await? {
+                *target = match value.trim() {
+                    "" => None,
+                    raw => Some(raw.parse::<u32>().map_err(|error| {
+                        Error::InvalidInput(format!("Invalid settings value for '{key}': {error}"))
+                    })?),
+                };
+            }
+        }
+
         Ok(settings)
     }
 
@@ -74,14 +179,69 @@ impl SettingsRepository for LibSqlSettingsRepository<'_> {
         self.set_setting("capture_hotkey", &settings.capture_hotkey)
             .await?;
         self.set_setting(
-            "voice_memo_transcription_enabled",
-            if settings.voice_memo_transcription_enabled {
-                "true"
-            } else {
-                "false"
-            },
+            "transcription_provider",
+            settings.transcription_provider.as_str(),
         )
         .await?;
+        for (key, value) in [
+            ("attachment_download_dir", &settings.attachment_download_dir),
+            ("capture_default_tag", &settings.capture_default_tag),
+            ("capture_webhook_url", &settings.capture_webhook_url),
+            ("last_seen_version", &settings.last_seen_version),
+            ("whisper_model", &settings.whisper_model),
+        ] {
+            self.set_setting(key, value.as_deref().unwrap_or(""))
+                .await?;
+        }
+        self.set_setting("no_export_tags", &settings.no_export_tags.join(","))
+            .await?;
+        self.set_setting("sync_excluded_tags", &settings.sync_excluded_tags.join(","))
+            .await?;
+        for (key, value) in [
+            ("image_max_dimension", settings.image_max_dimension),
+            ("focus_word_goal", settings.focus_word_goal),
+            ("note_count_warning", settings.note_count_warning),
+            (
+                "database_size_warning_mb",
+                settings.database_size_warning_mb,
+            ),
+            ("revision_retention", settings.revision_retention),
+            ("attachment_cache_mb", settings.attachment_cache_mb),
+        ] {
+            let raw = value.map(|value| value.to_string()).unwrap_or_default();
+            self.set_setting(key, &raw).await?;
+        }
+        self.set_setting("focus_line_width", &settings.focus_line_width.to_string())
+            .await?;
+        for (key, value) in [
+            (
+                "voice_memo_transcription_enabled",
+                settings.voice_memo_transcription_enabled,
+            ),
+            ("weekly_digest_enabled", settings.weekly_digest_enabled),
+            ("notify_sync_failures", settings.notify_sync_failures),
+            ("notify_sync_conflicts", settings.notify_sync_conflicts),
+            ("notify_export_complete", settings.notify_export_complete),
+            ("notify_reminders", settings.notify_reminders),
+            ("open_folder_after_save", settings.open_folder_after_save),
+            ("capture_link_daily_note", settings.capture_link_daily_note),
+            (
+                "capture_append_daily_note",
+                settings.capture_append_daily_note,
+            ),
+            ("capture_copy_note_id", settings.capture_copy_note_id),
+            ("image_strip_metadata", settings.image_strip_metadata),
+            ("os_search_index_enabled", settings.os_search_index_enabled),
+            ("focus_mode", settings.focus_mode),
+            (
+                "focus_typewriter_scrolling",
+                settings.focus_typewriter_scrolling,
+            ),
+            ("share_target_enabled", settings.share_target_enabled),
+        ] {
+            self.set_setting(key, if value { "true" } else { "false" })
+                .await?;
+        }
         Ok(())
     }
 }
@@ -135,6 +295,7 @@ mod tests {
     use super::*;
     use crate::db::Database;
     use crate::models::ThemeMode;
+    use crate::transcription::TranscriptionProvider;
 
     async fn setup() -> Database {
         Database::open_in_memory().await.unwrap()
@@ -161,6 +322,31 @@ mod tests {
             theme: ThemeMode::Dark,
             font_family: "JetBrains Mono".to_string(),
             voice_memo_transcription_enabled: true,
+            weekly_digest_enabled: true,
+            notify_sync_conflicts: false,
+            notify_reminders: false,
+            attachment_download_dir: Some("/home/me/Attachments".to_string()),
+            open_folder_after_save: true,
+            capture_default_tag: Some("inbox".to_string()),
+            capture_copy_note_id: true,
+            capture_append_daily_note: true,
+            capture_webhook_url: Some("https://hooks.example.com/dirt".to_string()),
+            no_export_tags: vec!["private".to_string(), "journal".to_string()],
+            sync_excluded_tags: vec!["private".to_string()],
+            image_strip_metadata: true,
+            image_max_dimension: Some(2048),
+            os_search_index_enabled: true,
+            focus_mode: true,
+            focus_line_width: 60,
+            focus_word_goal: Some(750),
+            share_target_enabled: true,
+            note_count_warning: None,
+            database_size_warning_mb: Some(64),
+            last_seen_version: Some("0.1.0".to_string()),
+            revision_retention: None,
+            attachment_cache_mb: Some(64),
+            transcription_provider: TranscriptionProvider::Whisper,
+            whisper_model: Some("base.en".to_string()),
             ..Settings::default()
         };
 
@@ -171,6 +357,50 @@ mod tests {
         assert_eq!(loaded.theme, ThemeMode::Dark);
         assert_eq!(loaded.font_family, "JetBrains Mono");
         assert!(loaded.voice_memo_transcription_enabled);
+        assert!(loaded.weekly_digest_enabled);
+        assert!(loaded.notify_sync_failures);
+        assert!(!loaded.notify_sync_conflicts);
+        assert!(!loaded.notify_reminders);
+        assert_eq!(
+            loaded.attachment_download_dir.as_deref(),
+            Some("/home/me/Attachments")
+        );
+        assert!(loaded.open_folder_after_save);
+        assert_eq!(loaded.capture_default_tag.as_deref(), Some("inbox"));
+        assert!(loaded.capture_copy_note_id);
+        assert!(!loaded.capture_link_daily_note);
+        assert!(loaded.capture_append_daily_note);
+        assert_eq!(
+            loaded.capture_webhook_url.as_deref(),
+            Some("https://hooks.example.com/dirt")
+        );
+        assert_eq!(loaded.no_export_tags, vec!["private", "journal"]);
+        assert_eq!(loaded.sync_excluded_tags, vec!["private"]);
+        assert!(loaded.image_strip_metadata);
+        assert_eq!(loaded.image_max_dimension, Some(2048));
+        assert!(loaded.os_search_index_enabled);
+        assert!(loaded.focus_mode);
+        assert_eq!(loaded.focus_line_width, 60);
+        assert!(!loaded.focus_typewriter_scrolling);
+        assert_eq!(loaded.focus_word_goal, Some(750));
+        assert!(loaded.share_target_enabled);
+        assert_eq!(loaded.note_count_warning, None);
+        assert_eq!(loaded.database_size_warning_mb, Some(64));
+        assert_eq!(loaded.last_seen_version.as_deref(), Some("0.1.0"));
+        assert_eq!(loaded.revision_retention, None);
+        assert_eq!(loaded.attachment_cache_mb, Some(64));
+        assert_eq!(
+            loaded.transcription_provider,
+            TranscriptionProvider::Whisper
+        );
+        assert_eq!(loaded.whisper_model.as_deref(), Some("base.en"));
+
+        repo.save(&Settings::default()).await.unwrap();
+        let reset = repo.load().await.unwrap();
+        assert_eq!(reset.attachment_download_dir, None);
+        assert_eq!(reset.image_max_dimension, None);
+        assert_eq!(reset.focus_word_goal, None);
+        assert_eq!(reset.note_count_warning, Some(10_000));
     }
 
     #[tokio::test(flavor = "multi_thread")]
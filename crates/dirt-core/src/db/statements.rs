@@ -0,0 +1,186 @@
+//! Prepared statement cache and opt-in query timing
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use libsql::params::IntoParams;
+use libsql::{Connection, Rows, Statement};
+
+use crate::config::{ConfigSource, EnvConfig};
+use crate::error::Result;
+
+/// Environment variable enabling the slow query log, in milliseconds
+pub const SLOW_QUERY_ENV: &str = "DIRT_SLOW_QUERY_MS";
+
+/// Statements prepared once per connection and reused across repository calls
+///
+/// A statement is checked out of the cache while it runs, so concurrent
+/// callers never share one. Rows returned by [`StatementCache::query`] must be
+/// consumed before the same SQL runs again, which holds for repository methods
+/// since each reads its rows before returning.
+#[derive(Default)]
+pub struct StatementCache {
+    statements: Mutex<HashMap<&'static str, Statement>>,
+    slow_query_threshold: Option<Duration>,
+}
+
+impl StatementCache {
+    /// Create a cache, enabling the slow query log from `DIRT_SLOW_QUERY_MS`
+    pub fn from_env() -> Self {
+        Self::from_source(&EnvConfig)
+    }
+
+    /// Create a cache, enabling the slow query log from `SLOW_QUERY_ENV` in
+    /// `source`
+    pub fn from_source(source: &impl ConfigSource) -> Self {
+        let slow_query_threshold = source
+            .text_var(SLOW_QUERY_ENV)
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_millis);
+        Self {
+            statements: Mutex::default(),
+            slow_query_threshold,
+        }
+    }
+
+    /// Log statements taking at least `threshold` (`None` disables timing)
+    pub const fn set_slow_query_threshold(&mut self, threshold: Option<Duration>) {
+        self.slow_query_threshold = threshold;
+    }
+
+    /// Number of prepared statements currently cached
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no statements have been prepared yet
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Run a cached query
+    pub async fn query(
+        &self,
+        conn: &Connection,
+        sql: &'static str,
+        params: impl IntoParams,
+    ) -> Result<Rows> {
+        let started = Instant::now();
+        let mut statement = self.checkout(conn, sql).await?;
+        let rows = statement.query(params).await;
+        self.checkin(sql, statement);
+        self.record_timing(sql, started.elapsed());
+        Ok(rows?)
+    }
+
+    /// Run a cached statement, returning the number of rows changed
+    pub async fn execute(
+        &self,
+        conn: &Connection,
+        sql: &'static str,
+        params: impl IntoParams,
+    ) -> Result<u64> {
+        let started = Instant::now();
+        let mut statement = self.checkout(conn, sql).await?;
+        let changed = statement.execute(params).await;
+        self.checkin(sql, statement);
+        self.record_timing(sql, started.elapsed());
+        Ok(u64::try_from(changed?).unwrap_or(u64::MAX))
+    }
+
+    async fn checkout(&self, conn: &Connection, sql: &'static str) -> Result<Statement> {
+        let cached = self.lock().remove(sql);
+        if let Some(mut statement) = cached {
+            statement.reset();
+            return Ok(statement);
+        }
+        Ok(conn.prepare(sql).await?)
+    }
+
+    fn checkin(&self, sql: &'static str, statement: Statement) {
+        self.lock().insert(sql, statement);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<&'static str, Statement>> {
+        self.statements
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn record_timing(&self, sql: &str, elapsed: Duration) {
+        let Some(threshold) = self.slow_query_threshold else {
+            return;
+        };
+        let statement = compact_sql(sql);
+        if elapsed >= threshold {
+            tracing::warn!("Slow query ({} ms): {}", elapsed.as_millis(), statement);
+        } else {
+            tracing::trace!("Query ({} us): {}", elapsed.as_micros(), statement);
+        }
+    }
+}
+
+/// Collapse whitespace so multi-line SQL logs on one line
+fn compact_sql(sql: &str) -> String {
+    sql.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MapConfig;
+    use crate::db::Database;
+
+    #[test]
+    fn test_compact_sql_joins_lines() {
+        assert_eq!(
+            compact_sql("SELECT id\n                 FROM notes\n  LIMIT ?"),
+            "SELECT id FROM notes LIMIT ?"
+        );
+    }
+
+    #[test]
+    fn test_slow_query_threshold_from_source() {
+        let enabled = StatementCache::from_source(&MapConfig::new().with(SLOW_QUERY_ENV, " 25 "));
+        assert_eq!(
+            enabled.slow_query_threshold,
+            Some(Duration::from_millis(25))
+        );
+
+        let invalid = StatementCache::from_source(&MapConfig::new().with(SLOW_QUERY_ENV, "fast"));
+        assert_eq!(invalid.slow_query_threshold, None);
+        assert_eq!(
+            StatementCache::from_source(&MapConfig::new()).slow_query_threshold,
+            None
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_statements_are_prepared_once() {
+        let db = Database::open_in_memory().await.unwrap();
+        let mut cache = StatementCache::default();
+        cache.set_slow_query_threshold(Some(Duration::ZERO));
+        assert!(cache.is_empty());
+
+        for value in 1..=3_i64 {
+            let mut rows = cache
+                .query(db.connection(), "SELECT ? + 1", [value])
+                .await
+                .unwrap();
+            let row = rows.next().await.unwrap().unwrap();
+            assert_eq!(row.get::<i64>(0).unwrap(), value + 1);
+        }
+        assert_eq!(cache.len(), 1);
+
+        cache
+            .execute(
+                db.connection(),
+                "UPDATE settings SET value = value WHERE key = ?",
+                ["missing"],
+            )
+            .await
+            .unwrap();
+        assert_eq!(cache.len(), 2);
+    }
+}
@@ -0,0 +1,141 @@
+//! Note summary repository implementation
+
+use crate::error::{Error, Result};
+use crate::models::{NoteId, NoteSummary, SummaryMethod};
+use libsql::Connection;
+
+/// Trait for stored note summaries (async)
+#[allow(async_fn_in_trait)]
+pub trait NoteSummaryRepository {
+    /// Store (or replace) the summary of a note
+    async fn save_summary(&self, summary: &NoteSummary) -> Result<()>;
+
+    /// Get the stored summary of a note
+    async fn get_summary(&self, note_id: &NoteId) -> Result<Option<NoteSummary>>;
+
+    /// List summaries of all notes that are not deleted
+    async fn list_summaries(&self) -> Result<Vec<NoteSummary>>;
+}
+
+/// libSQL implementation of `NoteSummaryRepository`
+pub struct LibSqlNoteSummaryRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlNoteSummaryRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Parse a summary from a database row
+    fn parse_summary(row: &libsql::Row) -> Result<NoteSummary> {
+        let note_id: String = row.get(0)?;
+        let method: String = row.get(2)?;
+        Ok(NoteSummary {
+            note_id: note_id
+                .parse()
+                .map_err(|_| Error::InvalidInput("Invalid note ID".into()))?,
+            summary: row.get(1)?,
+            method: SummaryMethod::from_db(&method),
+            note_updated_at: row.get(3)?,
+            created_at: row.get(4)?,
+        })
+    }
+}
+
+impl NoteSummaryRepository for LibSqlNoteSummaryRepository<'_> {
+    async fn save_summary(&self, summary: &NoteSummary) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO note_summaries (note_id, summary, method, note_updated_at, created_at)
+                 VALUES (?, ?, ?, ?, ?)
+                 ON CONFLICT(note_id) DO UPDATE SET
+                    summary = excluded.summary,
+                    method = excluded.method,
+                    note_updated_at = excluded.note_updated_at,
+                    created_at = excluded.created_at",
+                libsql::params![
+                    summary.note_id.as_str(),
+                    summary.summary.as_str(),
+                    summary.method.as_str(),
+                    summary.note_updated_at,
+                    summary.created_at
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_summary(&self, note_id: &NoteId) -> Result<Option<NoteSummary>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT note_id, summary, method, note_updated_at, created_at
+                 FROM note_summaries WHERE note_id = ?",
+                [note_id.as_str()],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(Self::parse_summary(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list_summaries(&self) -> Result<Vec<NoteSummary>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT s.note_id, s.summary, s.method, s.note_updated_at, s.created_at
+                 FROM note_summaries s
+                 JOIN notes n ON n.id = s.note_id
+                 WHERE n.is_deleted = 0",
+                (),
+            )
+            .await?;
+
+        let mut summaries = Vec::new();
+        while let Some(row) = rows.next().await? {
+            summaries.push(Self::parse_summary(&row)?);
+        }
+
+        Ok(summaries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, LibSqlNoteRepository, NoteRepository};
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_save_replaces_and_lists_live_summaries() {
+        let db = Database::open_in_memory().await.unwrap();
+        let notes = LibSqlNoteRepository::new(db.connection());
+        let summaries = LibSqlNoteSummaryRepository::new(db.connection());
+
+        let kept = notes.create("A long note").await.unwrap();
+        let deleted = notes.create("Another long note").await.unwrap();
+        for (note, text) in [(&kept, "First"), (&kept, "Second"), (&deleted, "Gone")] {
+            summaries
+                .save_summary(&NoteSummary {
+                    note_id: note.id,
+                    summary: text.to_string(),
+                    method: SummaryMethod::Extractive,
+                    note_updated_at: note.updated_at,
+                    created_at: note.updated_at,
+                })
+                .await
+                .unwrap();
+        }
+        notes.delete(&deleted.id).await.unwrap();
+
+        let stored = summaries.get_summary(&kept.id).await.unwrap().unwrap();
+        assert_eq!(stored.summary, "Second");
+        assert!(stored.is_current_for(&kept));
+
+        let listed = summaries.list_summaries().await.unwrap();
+        assert_eq!(listed, vec![stored]);
+    }
+}
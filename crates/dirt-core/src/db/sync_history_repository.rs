@@ -0,0 +1,161 @@
+//! Sync history repository implementation
+
+use std::collections::HashMap;
+
+use crate::error::Result;
+use crate::models::SyncHistoryEntry;
+use libsql::Connection;
+
+/// History rows kept; older entries are pruned on insert
+const SYNC_HISTORY_RETENTION: i64 = 500;
+
+/// Trait for per-sync transfer history (async)
+#[allow(async_fn_in_trait)]
+pub trait SyncHistoryRepository {
+    /// Record a completed sync; the entry's `id` is assigned by the database
+    async fn record(&self, entry: &SyncHistoryEntry) -> Result<SyncHistoryEntry>;
+
+    /// List recent syncs, newest first
+    async fn list_recent(&self, limit: usize) -> Result<Vec<SyncHistoryEntry>>;
+
+    /// Snapshot of `note id -> updated_at`, compared around a sync to count
+    /// the rows it changed
+    async fn note_versions(&self) -> Result<HashMap<String, i64>>;
+}
+
+/// libSQL implementation of `SyncHistoryRepository`
+pub struct LibSqlSyncHistoryRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlSyncHistoryRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+
+    fn parse_entry(row: &libsql::Row) -> Result<SyncHistoryEntry> {
+        Ok(SyncHistoryEntry {
+            id: row.get(0)?,
+            synced_at: row.get(1)?,
+            duration_ms: row.get(2)?,
+            frames_synced: row.get(3)?,
+            bytes_transferred: row.get(4)?,
+            rows_changed: row.get(5)?,
+        })
+    }
+}
+
+impl SyncHistoryRepository for LibSqlSyncHistoryRepository<'_> {
+    async fn record(&self, entry: &SyncHistoryEntry) -> Result<SyncHistoryEntry> {
+        self.conn
+            .execute(
+                "INSERT INTO sync_history
+                    (synced_at, duration_ms, frames_synced, bytes_transferred, rows_changed)
+                 VALUES (?, ?, ?, ?, ?)",
+                libsql::params![
+                    entry.synced_at,
+                    entry.duration_ms,
+                    entry.frames_synced,
+                    entry.bytes_transferred,
+                    entry.rows_changed
+                ],
+            )
+            .await?;
+        let id = self.conn.last_insert_rowid();
+
+        self.conn
+            .execute(
+                "DELETE FROM sync_history
+                 WHERE id NOT IN (
+                     SELECT id FROM sync_history ORDER BY synced_at DESC, id DESC LIMIT ?
+                 )",
+                libsql::params![SYNC_HISTORY_RETENTION],
+            )
+            .await?;
+
+        Ok(SyncHistoryEntry {
+            id,
+            ..entry.clone()
+        })
+    }
+
+    async fn list_recent(&self, limit: usize) -> Result<Vec<SyncHistoryEntry>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT id, synced_at, duration_ms, frames_synced, bytes_transferred, rows_changed
+                 FROM sync_history
+                 ORDER BY synced_at DESC, id DESC
+                 LIMIT ?",
+                libsql::params![limit as i64],
+            )
+            .await?;
+
+        let mut entries = Vec::new();
+        while let Some(row) = rows.next().await? {
+            entries.push(Self::parse_entry(&row)?);
+        }
+
+        Ok(entries)
+    }
+
+    async fn note_versions(&self) -> Result<HashMap<String, i64>> {
+        let mut rows = self
+            .conn
+            .query("SELECT id, updated_at FROM notes", ())
+            .await?;
+
+        let mut versions = HashMap::new();
+        while let Some(row) = rows.next().await? {
+            versions.insert(row.get::<String>(0)?, row.get::<i64>(1)?);
+        }
+
+        Ok(versions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Database, LibSqlNoteRepository, NoteRepository};
+
+    fn entry(synced_at: i64, rows_changed: i64) -> SyncHistoryEntry {
+        SyncHistoryEntry {
+            id: 0,
+            synced_at,
+            duration_ms: 120,
+            frames_synced: 3,
+            bytes_transferred: 3 * 4120,
+            rows_changed,
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_record_and_list_newest_first() {
+        let db = Database::open_in_memory().await.unwrap();
+        let history = LibSqlSyncHistoryRepository::new(db.connection());
+
+        let first = history.record(&entry(1_000, 2)).await.unwrap();
+        history.record(&entry(2_000, 40)).await.unwrap();
+        assert!(first.id > 0);
+
+        let entries = history.list_recent(10).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].synced_at, 2_000);
+        assert_eq!(entries[0].rows_changed, 40);
+        assert_eq!(entries[1], first);
+        assert_eq!(history.list_recent(1).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_note_versions_track_updates() {
+        let db = Database::open_in_memory().await.unwrap();
+        let notes = LibSqlNoteRepository::new(db.connection());
+        let history = LibSqlSyncHistoryRepository::new(db.connection());
+
+        let note = notes.create("Versioned").await.unwrap();
+        let versions = history.note_versions().await.unwrap();
+        assert_eq!(versions.get(note.id.as_str()), Some(&note.updated_at));
+    }
+}
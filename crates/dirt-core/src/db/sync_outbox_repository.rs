@@ -0,0 +1,118 @@
+//! Sync outbox repository implementation
+
+use crate::error::{Error, Result};
+use crate::models::NoteId;
+use libsql::Connection;
+
+/// Trait for the queue of notes changed since the last successful sync (async)
+#[allow(async_fn_in_trait)]
+pub trait SyncOutboxRepository {
+    /// Queue a change to `note_id` made at `queued_at` (Unix milliseconds);
+    /// a note already queued keeps its first time
+    async fn enqueue(&self, note_id: &NoteId, queued_at: i64) -> Result<()>;
+
+    /// Queued notes, oldest first
+    async fn list_pending(&self) -> Result<Vec<NoteId>>;
+
+    /// Number of queued notes
+    async fn pending_count(&self) -> Result<usize>;
+
+    /// Remove changes queued at or before `queued_at` once a sync carried
+    /// them; returns how many were removed
+    async fn ack_through(&self, queued_at: i64) -> Result<u64>;
+}
+
+/// libSQL implementation of `SyncOutboxRepository`
+pub struct LibSqlSyncOutboxRepository<'a> {
+    conn: &'a Connection,
+}
+
+impl<'a> LibSqlSyncOutboxRepository<'a> {
+    /// Create a new repository with the given connection
+    pub const fn new(conn: &'a Connection) -> Self {
+        Self { conn }
+    }
+}
+
+impl SyncOutboxRepository for LibSqlSyncOutboxRepository<'_> {
+    async fn enqueue(&self, note_id: &NoteId, queued_at: i64) -> Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO sync_outbox (note_id, queued_at) VALUES (?, ?)
+                 ON CONFLICT(note_id) DO NOTHING",
+                libsql::params![note_id.as_str(), queued_at],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn list_pending(&self) -> Result<Vec<NoteId>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT note_id FROM sync_outbox ORDER BY queued_at, note_id",
+                (),
+            )
+            .await?;
+
+        let mut note_ids = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let note_id: String = row.get(0)?;
+            note_ids.push(
+                note_id
+                    .parse()
+                    .map_err(|_| Error::InvalidInput("Invalid note ID".into()))?,
+            );
+        }
+
+        Ok(note_ids)
+    }
+
+    async fn pending_count(&self) -> Result<usize> {
+        let mut rows = self
+            .conn
+            .query("SELECT COUNT(*) FROM sync_outbox", ())
+            .await?;
+        let count = match rows.next().await? {
+            Some(row) => row.get::<i64>(0)?,
+            None => 0,
+        };
+        Ok(usize::try_from(count).unwrap_or(0))
+    }
+
+    async fn ack_through(&self, queued_at: i64) -> Result<u64> {
+        let removed = self
+            .conn
+            .execute(
+                "DELETE FROM sync_outbox WHERE queued_at <= ?",
+                libsql::params![queued_at],
+            )
+            .await?;
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_enqueue_is_idempotent_and_ack_keeps_later_changes() {
+        let db = Database::open_in_memory().await.unwrap();
+        let outbox = LibSqlSyncOutboxRepository::new(db.connection());
+        let first = NoteId::new();
+        let second = NoteId::new();
+
+        outbox.enqueue(&first, 1_000).await.unwrap();
+        outbox.enqueue(&second, 2_000).await.unwrap();
+        outbox.enqueue(&first, 3_000).await.unwrap();
+        assert_eq!(outbox.list_pending().await.unwrap(), [first, second]);
+        assert_eq!(outbox.pending_count().await.unwrap(), 2);
+
+        assert_eq!(outbox.ack_through(1_500).await.unwrap(), 1);
+        assert_eq!(outbox.list_pending().await.unwrap(), [second]);
+        assert_eq!(outbox.ack_through(2_000).await.unwrap(), 1);
+        assert_eq!(outbox.pending_count().await.unwrap(), 0);
+    }
+}
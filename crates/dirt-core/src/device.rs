@@ -0,0 +1,61 @@
+//! Identity of this installation
+//!
+//! Synced databases are shared by every device, so the id lives in a file
+//! next to the local database file instead. It is created on first use and
+//! only serves to tell devices apart in note history.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::error::Result;
+
+/// File next to the database that holds the device id
+pub const DEVICE_ID_FILE: &str = "device-id";
+
+/// Path of the device id file for the database at `db_path`.
+#[must_use]
+pub fn device_id_path(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(DEVICE_ID_FILE)
+}
+
+/// Read the id of this device, creating it on first use.
+pub fn load_or_create_device_id(db_path: &Path) -> Result<String> {
+    let path = device_id_path(db_path);
+    if let Ok(existing) = fs::read_to_string(&path) {
+        let existing = existing.trim();
+        if !existing.is_empty() {
+            return Ok(existing.to_string());
+        }
+    }
+
+    let device_id = Uuid::now_v7().to_string();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, &device_id)?;
+    Ok(device_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_id_is_created_once_per_data_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("dirt.db");
+
+        let first = load_or_create_device_id(&db_path).unwrap();
+        assert!(!first.is_empty());
+        assert_eq!(load_or_create_device_id(&db_path).unwrap(), first);
+        assert_eq!(
+            fs::read_to_string(dir.path().join(DEVICE_ID_FILE)).unwrap(),
+            first
+        );
+    }
+}
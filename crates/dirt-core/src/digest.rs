@@ -0,0 +1,339 @@
+//! Weekly digest of recent captures.
+//!
+//! Summarizes notes touched in a period into counts, top tags, notable long
+//! notes, and unchecked tasks (see [`extract_tasks`]). Clients save the rendered Markdown as
+//! a note, export it, or email it through the Dirt API.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::extract_tasks;
+use crate::Note;
+
+/// Tag added to generated digest notes; tagged notes are left out of digests.
+pub const DIGEST_TAG: &str = "digest";
+
+const DAY_MS: i64 = 86_400_000;
+const WEEK_MS: i64 = 7 * DAY_MS;
+const TOP_TAG_LIMIT: usize = 5;
+const LONG_NOTE_LIMIT: usize = 3;
+const LONG_NOTE_MIN_CHARS: usize = 500;
+const OPEN_TASK_LIMIT: usize = 20;
+const TITLE_MAX_CHARS: usize = 60;
+
+/// Half-open time range `[start_ms, end_ms)` covered by a digest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestPeriod {
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+impl DigestPeriod {
+    /// The seven days ending at `end_ms`.
+    #[must_use]
+    pub const fn week_ending(end_ms: i64) -> Self {
+        Self {
+            start_ms: end_ms.saturating_sub(WEEK_MS),
+            end_ms,
+        }
+    }
+
+    /// Whether a timestamp (unix ms) falls inside the period.
+    #[must_use]
+    pub const fn contains(&self, timestamp_ms: i64) -> bool {
+        timestamp_ms >= self.start_ms && timestamp_ms < self.end_ms
+    }
+
+    /// Human-readable date range, e.g. `2026-02-03 to 2026-02-10`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        format!(
+            "{} to {}",
+            format_date(self.start_ms),
+            format_date(self.end_ms)
+        )
+    }
+}
+
+/// Number of recent notes carrying a tag.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestTagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// A long note worth revisiting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestNote {
+    pub id: String,
+    pub title: String,
+    pub chars: usize,
+}
+
+/// An unchecked task list item found in a recent note.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestTask {
+    pub note_id: String,
+    pub text: String,
+}
+
+/// Summary of captures within a [`DigestPeriod`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeeklyDigest {
+    pub period: DigestPeriod,
+    /// Notes created during the period.
+    pub created: usize,
+    /// Older notes edited during the period.
+    pub updated: usize,
+    pub top_tags: Vec<DigestTagCount>,
+    pub long_notes: Vec<DigestNote>,
+    pub open_tasks: Vec<DigestTask>,
+}
+
+impl WeeklyDigest {
+    /// Whether nothing was captured or edited during the period.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.created == 0 && self.updated == 0
+    }
+
+    /// Title line used for digest notes and email subjects.
+    #[must_use]
+    pub fn title(&self) -> String {
+        format!("Weekly digest: {}", self.period.label())
+    }
+
+    /// Render the digest as Markdown, ending with the `#digest` tag.
+    #[must_use]
+    pub fn render_markdown(&self) -> String {
+        let mut output = String::new();
+        let _ = writeln!(output, "{}", self.title());
+        let _ = writeln!(output);
+        let _ = writeln!(output, "- Notes captured: {}", self.created);
+        let _ = writeln!(output, "- Notes updated: {}", self.updated);
+        let _ = writeln!(output, "- Open tasks: {}", self.open_tasks.len());
+
+        if !self.top_tags.is_empty() {
+            let _ = writeln!(output);
+            let _ = writeln!(output, "## Top tags");
+            for tag in &self.top_tags {
+                let _ = writeln!(output, "- {} ({})", tag.tag, tag.count);
+            }
+        }
+
+        if !self.long_notes.is_empty() {
+            let _ = writeln!(output);
+            let _ = writeln!(output, "## Notable notes");
+            for note in &self.long_notes {
+                let _ = writeln!(output, "- [[{}]] ({} chars)", note.id, note.chars);
+                let _ = writeln!(output, "  {}", note.title);
+            }
+        }
+
+        if !self.open_tasks.is_empty() {
+            let _ = writeln!(output);
+            let _ = writeln!(output, "## Unfinished tasks");
+            for task in &self.open_tasks {
+                let _ = writeln!(output, "- [ ] {} ([[{}]])", task.text, task.note_id);
+            }
+        }
+
+        let _ = writeln!(output);
+        let _ = writeln!(output, "#{DIGEST_TAG}");
+        output
+    }
+}
+
+/// Summarize non-deleted notes created or edited during `period`.
+///
+/// Notes tagged `#digest` are skipped so digests never summarize each other.
+#[must_use]
+pub fn build_weekly_digest(notes: &[Note], period: DigestPeriod) -> WeeklyDigest {
+    let mut recent: Vec<&Note> = notes
+        .iter()
+        .filter(|note| !note.is_deleted && period.contains(note.updated_at))
+        .filter(|note| !note.tags().iter().any(|tag| tag == DIGEST_TAG))
+        .collect();
+    recent.sort_by(|left, right| right.updated_at.cmp(&left.updated_at));
+
+    let created = recent
+        .iter()
+        .filter(|note| period.contains(note.created_at))
+        .count();
+
+    let mut tag_counts: HashMap<String, usize> = HashMap::new();
+    for note in &recent {
+        for tag in note.tags() {
+            *tag_counts.entry(tag).or_default() += 1;
+        }
+    }
+    let mut top_tags: Vec<DigestTagCount> = tag_counts
+        .into_iter()
+        .map(|(tag, count)| DigestTagCount { tag, count })
+        .collect();
+    top_tags.sort_by(|left, right| {
+        right
+            .count
+            .cmp(&left.count)
+            .then_with(|| left.tag.cmp(&right.tag))
+    });
+    top_tags.truncate(TOP_TAG_LIMIT);
+
+    let mut long_notes: Vec<DigestNote> = recent
+        .iter()
+        .map(|note| DigestNote {
+            id: note.id.to_string(),
+            title: note.title_preview(TITLE_MAX_CHARS).trim().to_string(),
+            chars: note.content.chars().count(),
+        })
+        .filter(|note| note.chars >= LONG_NOTE_MIN_CHARS)
+        .collect();
+    long_notes.sort_by(|left, right| right.chars.cmp(&left.chars));
+    long_notes.truncate(LONG_NOTE_LIMIT);
+
+    let open_tasks = recent
+        .iter()
+        .flat_map(|note| {
+            let note_id = note.id.to_string();
+            extract_tasks(&note.content)
+                .into_iter()
+                .filter(|task| !task.done && !task.text.is_empty())
+                .map(move |task| DigestTask {
+                    note_id: note_id.clone(),
+                    text: task.text,
+                })
+        })
+        .take(OPEN_TASK_LIMIT)
+        .collect();
+
+    WeeklyDigest {
+        period,
+        created,
+        updated: recent.len() - created,
+        top_tags,
+        long_notes,
+        open_tasks,
+    }
+}
+
+/// Whether a new weekly digest is due, i.e. no digest note was created in the
+/// seven days before `now_ms`.
+#[must_use]
+pub fn weekly_digest_due(notes: &[Note], now_ms: i64) -> bool {
+    let period = DigestPeriod::week_ending(now_ms.saturating_add(1));
+    !notes.iter().any(|note| {
+        !note.is_deleted
+            && period.contains(note.created_at)
+            && note.tags().iter().any(|tag| tag == DIGEST_TAG)
+    })
+}
+
+fn format_date(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms).map_or_else(
+        || timestamp_ms.to_string(),
+        |date_time| date_time.format("%Y-%m-%d").to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-02-10T00:00:00Z
+    const NOW_MS: i64 = 1_770_681_600_000;
+
+    fn note(content: &str, created_at: i64, updated_at: i64) -> Note {
+        let mut note = Note::new(content);
+        note.created_at = created_at;
+        note.updated_at = updated_at;
+        note
+    }
+
+    #[test]
+    fn digest_counts_recent_notes_and_collects_tasks() {
+        let period = DigestPeriod::week_ending(NOW_MS);
+        let long_body = "x".repeat(LONG_NOTE_MIN_CHARS);
+        let notes = vec![
+            note(
+                "Plan #work\n- [ ] ship it\n- [x] done\n  1. [ ] tell the team",
+                NOW_MS - DAY_MS,
+                NOW_MS - DAY_MS,
+            ),
+            note(
+                &format!("Essay #work #writing\n{long_body}"),
+                NOW_MS - 2 * DAY_MS,
+                NOW_MS - 2 * DAY_MS,
+            ),
+            note(
+                "Old idea #work\n* [ ] follow up",
+                NOW_MS - 30 * DAY_MS,
+                NOW_MS - 3 * DAY_MS,
+            ),
+            note(
+                "Ancient #archive",
+                NOW_MS - 30 * DAY_MS,
+                NOW_MS - 30 * DAY_MS,
+            ),
+            note(
+                "Last digest #digest\n- [ ] ignored",
+                NOW_MS - DAY_MS,
+                NOW_MS - DAY_MS,
+            ),
+        ];
+
+        let digest = build_weekly_digest(&notes, period);
+
+        assert_eq!(digest.created, 2);
+        assert_eq!(digest.updated, 1);
+        assert_eq!(
+            digest.top_tags[0],
+            DigestTagCount {
+                tag: "work".to_string(),
+                count: 3
+            }
+        );
+        assert!(digest.top_tags.iter().all(|tag| tag.tag != "archive"));
+        assert_eq!(digest.long_notes.len(), 1);
+        assert_eq!(digest.long_notes[0].title, "Essay #work #writing");
+        let tasks: Vec<&str> = digest
+            .open_tasks
+            .iter()
+            .map(|task| task.text.as_str())
+            .collect();
+        assert_eq!(tasks, vec!["ship it", "tell the team", "follow up"]);
+    }
+
+    #[test]
+    fn render_markdown_includes_sections_and_digest_tag() {
+        let notes = vec![note(
+            "Plan #work\n- [ ] ship it",
+            NOW_MS - DAY_MS,
+            NOW_MS - DAY_MS,
+        )];
+        let digest = build_weekly_digest(&notes, DigestPeriod::week_ending(NOW_MS));
+        let markdown = digest.render_markdown();
+
+        assert!(markdown.starts_with("Weekly digest: 2026-02-03 to 2026-02-10\n"));
+        assert!(markdown.contains("- Notes captured: 1"));
+        assert!(markdown.contains("## Top tags\n- work (1)"));
+        assert!(markdown.contains("- [ ] ship it ([["));
+        assert!(!markdown.contains("## Notable notes"));
+        assert_eq!(Note::new(markdown).tags(), vec!["digest".to_string()]);
+    }
+
+    #[test]
+    fn weekly_digest_due_checks_recent_digest_notes() {
+        let recent_digest = note("Weekly digest #digest", NOW_MS - DAY_MS, NOW_MS - DAY_MS);
+        let stale_digest = note(
+            "Weekly digest #digest",
+            NOW_MS - 8 * DAY_MS,
+            NOW_MS - DAY_MS,
+        );
+
+        assert!(weekly_digest_due(&[], NOW_MS));
+        assert!(!weekly_digest_due(&[recent_digest], NOW_MS));
+        assert!(weekly_digest_due(&[stale_digest], NOW_MS));
+    }
+}
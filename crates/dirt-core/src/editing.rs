@@ -0,0 +1,551 @@
+//! Editor behavior shared by the note editors
+//!
+//! Auto-save timing and bookkeeping, plus Markdown-aware editing shortcuts:
+//! editors intercept a key, pass the current text and selection here, and
+//! apply the returned edit. Keys without a smart behavior in context get the
+//! plain edit the textarea would have made, so callers never need a fallback.
+//! All offsets are byte offsets into the note text.
+
+use std::ops::Range;
+
+/// Idle time after the last edit before an editor auto-saves.
+pub const AUTOSAVE_IDLE_MS: u64 = 2000;
+
+/// Interval between draft-journal writes while typing.
+pub const DRAFT_JOURNAL_MS: u64 = 1000;
+
+/// Indentation added or removed per list level.
+pub const LIST_INDENT: &str = "  ";
+
+/// Edit/save version counters behind debounced auto-save.
+///
+/// Every edit bumps the edit version. A save started after edit `v` only
+/// marks the editor clean if no newer edit arrived while it was running.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AutosaveState {
+    edit_version: u64,
+    saved_version: u64,
+}
+
+impl AutosaveState {
+    /// Record an edit and return its version.
+    pub const fn record_edit(&mut self) -> u64 {
+        self.edit_version = self.edit_version.saturating_add(1);
+        self.edit_version
+    }
+
+    /// Version of the latest edit.
+    #[must_use]
+    pub const fn edit_version(self) -> u64 {
+        self.edit_version
+    }
+
+    /// Whether there are edits that have not been saved.
+    #[must_use]
+    pub const fn is_dirty(self) -> bool {
+        self.saved_version < self.edit_version
+    }
+
+    /// Whether a save scheduled after edit `version` should still run, i.e.
+    /// nothing was typed since and the edit is not saved yet.
+    #[must_use]
+    pub const fn is_pending(self, version: u64) -> bool {
+        self.is_dirty() && version == self.edit_version
+    }
+
+    /// Record that the content as of edit `version` was saved.
+    pub const fn mark_saved(&mut self, version: u64) {
+        if version > self.saved_version {
+            self.saved_version = version;
+        }
+    }
+
+    /// Treat the current content as saved (e.g. after loading or discarding).
+    pub const fn mark_clean(&mut self) {
+        self.saved_version = self.edit_version;
+    }
+}
+
+/// Keys with editing shortcuts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorKey {
+    /// Continue the list item under the caret.
+    Enter,
+    /// Indent the list items in the selection.
+    Tab,
+    /// Outdent the lines in the selection.
+    ShiftTab,
+    /// Toggle `**bold**` around the selection.
+    Bold,
+    /// A typed character; brackets and backticks are auto-paired.
+    Char(char),
+}
+
+/// Text and selection after applying a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub text: String,
+    /// Selected range; empty when only the caret is placed.
+    pub selection: Range<usize>,
+}
+
+impl TextEdit {
+    fn caret(text: String, at: usize) -> Self {
+        Self {
+            text,
+            selection: at..at,
+        }
+    }
+}
+
+/// Closing character auto-inserted after `ch`, if it opens a pair.
+#[must_use]
+pub const fn closing_pair(ch: char) -> Option<char> {
+    match ch {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        '`' => Some('`'),
+        _ => None,
+    }
+}
+
+/// Whether `key` has a shortcut that an editor should route through
+/// [`apply_editor_key`] instead of the default textarea behavior.
+#[must_use]
+pub const fn is_shortcut_key(key: EditorKey) -> bool {
+    match key {
+        EditorKey::Enter | EditorKey::Tab | EditorKey::ShiftTab | EditorKey::Bold => true,
+        EditorKey::Char(ch) => closing_pair(ch).is_some() || matches!(ch, ')' | ']' | '}'),
+    }
+}
+
+/// Apply `key` to `text` with `selection` selected.
+#[must_use]
+pub fn apply_editor_key(text: &str, selection: Range<usize>, key: EditorKey) -> TextEdit {
+    let selection = normalize_selection(text, selection);
+    match key {
+        EditorKey::Enter => continue_list(text, selection),
+        EditorKey::Tab => indent_lines(text, selection),
+        EditorKey::ShiftTab => outdent_lines(text, selection),
+        EditorKey::Bold => toggle_bold(text, selection),
+        EditorKey::Char(ch) => type_char(text, selection, ch),
+    }
+}
+
+/// Marker at the start of a list line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListMarker {
+    Bullet(char),
+    Task(char),
+    Ordered(u64, char),
+}
+
+impl ListMarker {
+    fn next(self) -> String {
+        match self {
+            Self::Bullet(bullet) => format!("{bullet} "),
+            Self::Task(bullet) => format!("{bullet} [ ] "),
+            Self::Ordered(number, delimiter) => format!("{}{delimiter} ", number + 1),
+        }
+    }
+}
+
+/// A list line split into indentation, marker, and the offset of its content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ListLine<'a> {
+    indent: &'a str,
+    marker: ListMarker,
+    content_start: usize,
+}
+
+fn parse_list_line(line: &str) -> Option<ListLine<'_>> {
+    let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+    let indent = &line[..indent_len];
+    let rest = &line[indent_len..];
+
+    let first = rest.chars().next()?;
+    if matches!(first, '-' | '*' | '+') {
+        let after = rest[1..].strip_prefix(' ')?;
+        for checkbox in ["[ ] ", "[x] ", "[X] "] {
+            if after.starts_with(checkbox) {
+                return Some(ListLine {
+                    indent,
+                    marker: ListMarker::Task(first),
+                    content_start: indent_len + 2 + checkbox.len(),
+                });
+            }
+        }
+        return Some(ListLine {
+            indent,
+            marker: ListMarker::Bullet(first),
+            content_start: indent_len + 2,
+        });
+    }
+
+    let digits = rest.len()
+        - rest
+            .trim_start_matches(|ch: char| ch.is_ascii_digit())
+            .len();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let number = rest[..digits].parse().ok()?;
+    let delimiter = rest[digits..].chars().next()?;
+    if !matches!(delimiter, '.' | ')') || !rest[digits + 1..].starts_with(' ') {
+        return None;
+    }
+    Some(ListLine {
+        indent,
+        marker: ListMarker::Ordered(number, delimiter),
+        content_start: indent_len + digits + 2,
+    })
+}
+
+fn continue_list(text: &str, selection: Range<usize>) -> TextEdit {
+    if !selection.is_empty() {
+        return replace(text, selection, "\n");
+    }
+    let caret = selection.start;
+    let line_start = line_start(text, caret);
+    let line_end = line_end(text, caret);
+    let line = &text[line_start..line_end];
+
+    let Some(item) = parse_list_line(line) else {
+        return replace(text, selection, "\n");
+    };
+    if caret < line_start + item.content_start {
+        return replace(text, selection, "\n");
+    }
+
+    // Enter on an empty item ends the list instead of adding another.
+    if line[item.content_start..].trim().is_empty() {
+        return replace(text, line_start..line_end, "");
+    }
+
+    let continuation = format!("\n{}{}", item.indent, item.marker.next());
+    replace(text, selection, &continuation)
+}
+
+fn indent_lines(text: &str, selection: Range<usize>) -> TextEdit {
+    let lines = touched_lines(text, &selection);
+    let on_list = lines
+        .iter()
+        .any(|&start| parse_list_line(&text[start..line_end(text, start)]).is_some());
+    if selection.is_empty() && !on_list {
+        return replace(text, selection, LIST_INDENT);
+    }
+
+    let mut updated = String::with_capacity(text.len() + lines.len() * LIST_INDENT.len());
+    let mut copied = 0;
+    for &start in &lines {
+        updated.push_str(&text[copied..start]);
+        updated.push_str(LIST_INDENT);
+        copied = start;
+    }
+    updated.push_str(&text[copied..]);
+
+    // A selection starting at a line start keeps covering the new indent.
+    let shift = LIST_INDENT.len();
+    let start = if !selection.is_empty() && selection.start == lines[0] {
+        selection.start
+    } else {
+        selection.start + shift
+    };
+    TextEdit {
+        text: updated,
+        selection: start..selection.end + shift * lines.len(),
+    }
+}
+
+fn outdent_lines(text: &str, selection: Range<usize>) -> TextEdit {
+    let lines = touched_lines(text, &selection);
+    let mut updated = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut removed_before_start = 0;
+    let mut removed_before_end = 0;
+    for &start in &lines {
+        let line = &text[start..line_end(text, start)];
+        let leading_spaces = line.len() - line.trim_start_matches(' ').len();
+        let removed = if line.starts_with('\t') {
+            1
+        } else {
+            leading_spaces.min(LIST_INDENT.len())
+        };
+
+        updated.push_str(&text[copied..start]);
+        copied = start + removed;
+        removed_before_start += removed.min(selection.start.saturating_sub(start));
+        removed_before_end += removed.min(selection.end.saturating_sub(start));
+    }
+    updated.push_str(&text[copied..]);
+
+    TextEdit {
+        text: updated,
+        selection: selection.start - removed_before_start..selection.end - removed_before_end,
+    }
+}
+
+fn toggle_bold(text: &str, selection: Range<usize>) -> TextEdit {
+    const MARK: &str = "**";
+    let (start, end) = (selection.start, selection.end);
+
+    if start >= MARK.len()
+        && text[..start].ends_with(MARK)
+        && text[end..].starts_with(MARK)
+        && !selection.is_empty()
+    {
+        let mut updated = String::with_capacity(text.len());
+        updated.push_str(&text[..start - MARK.len()]);
+        updated.push_str(&text[start..end]);
+        updated.push_str(&text[end + MARK.len()..]);
+        return TextEdit {
+            text: updated,
+            selection: start - MARK.len()..end - MARK.len(),
+        };
+    }
+
+    let selected = &text[start..end];
+    if selected.len() >= MARK.len() * 2 && selected.starts_with(MARK) && selected.ends_with(MARK) {
+        let inner = &selected[MARK.len()..selected.len() - MARK.len()];
+        let mut edit = replace(text, selection, inner);
+        edit.selection = start..start + inner.len();
+        return edit;
+    }
+
+    let mut edit = replace(text, selection, &format!("{MARK}{selected}{MARK}"));
+    edit.selection = start + MARK.len()..end + MARK.len();
+    edit
+}
+
+fn type_char(text: &str, selection: Range<usize>, ch: char) -> TextEdit {
+    let next = text[selection.end..].chars().next();
+    let previous = text[..selection.start].chars().next_back();
+
+    // Typing the closing character right before itself steps over it.
+    if selection.is_empty() && matches!(ch, ')' | ']' | '}' | '`') && next == Some(ch) {
+        return TextEdit::caret(text.to_string(), selection.start + ch.len_utf8());
+    }
+
+    let Some(closing) = closing_pair(ch) else {
+        return replace(text, selection, ch.encode_utf8(&mut [0; 4]));
+    };
+
+    if !selection.is_empty() {
+        let selected = &text[selection.clone()];
+        let start = selection.start + ch.len_utf8();
+        let mut edit = replace(text, selection, &format!("{ch}{selected}{closing}"));
+        edit.selection = start..start + selected.len();
+        return edit;
+    }
+
+    let pairs_here = next.map_or(true, |next| {
+        next.is_whitespace() || matches!(next, ')' | ']' | '}' | ',' | '.' | ';' | ':')
+    });
+    let backtick_run = ch == '`' && previous == Some('`');
+    if !pairs_here || backtick_run {
+        return replace(text, selection, ch.encode_utf8(&mut [0; 4]));
+    }
+
+    let mut edit = replace(text, selection, &format!("{ch}{closing}"));
+    let caret = edit.selection.start - closing.len_utf8();
+    edit.selection = caret..caret;
+    edit
+}
+
+/// Replace `range` with `insert`, leaving the caret after the insertion.
+fn replace(text: &str, range: Range<usize>, insert: &str) -> TextEdit {
+    let mut updated = String::with_capacity(text.len() + insert.len());
+    updated.push_str(&text[..range.start]);
+    updated.push_str(insert);
+    updated.push_str(&text[range.end..]);
+    TextEdit::caret(updated, range.start + insert.len())
+}
+
+fn normalize_selection(text: &str, selection: Range<usize>) -> Range<usize> {
+    let clamp = |offset: usize| {
+        let mut offset = offset.min(text.len());
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    };
+    let (start, end) = (clamp(selection.start), clamp(selection.end));
+    start.min(end)..start.max(end)
+}
+
+fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map_or(0, |index| index + 1)
+}
+
+fn line_end(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .find('\n')
+        .map_or(text.len(), |index| offset + index)
+}
+
+/// Start offsets of the lines overlapping `selection`.
+///
+/// A selection ending at the very start of a line does not include it.
+fn touched_lines(text: &str, selection: &Range<usize>) -> Vec<usize> {
+    let last = if selection.end > selection.start && text[..selection.end].ends_with('\n') {
+        selection.end - 1
+    } else {
+        selection.end
+    };
+    let mut lines = vec![line_start(text, selection.start)];
+    lines.extend(
+        text[selection.start..last]
+            .match_indices('\n')
+            .map(|(index, _)| selection.start + index + 1),
+    );
+    lines
+}
+
+/// Number of words in note text.
+///
+/// A word is a whitespace-separated run containing at least one letter or
+/// digit, so Markdown markers such as `-`, `#`, or `>` are not counted.
+#[must_use]
+pub fn word_count(text: &str) -> usize {
+    text.split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply `key` to text where `|` marks the caret (or `|...|` a selection),
+    /// returning the result in the same notation.
+    fn run(marked: &str, key: EditorKey) -> String {
+        let start = marked.find('|').expect("caret marker");
+        let rest = &marked[start + 1..];
+        let (text, selection) = match rest.find('|') {
+            Some(len) => (
+                format!("{}{}{}", &marked[..start], &rest[..len], &rest[len + 1..]),
+                start..start + len,
+            ),
+            None => (format!("{}{rest}", &marked[..start]), start..start),
+        };
+
+        let edit = apply_editor_key(&text, selection, key);
+        let TextEdit { text, selection } = edit;
+        if selection.is_empty() {
+            format!("{}|{}", &text[..selection.start], &text[selection.start..])
+        } else {
+            format!(
+                "{}|{}|{}",
+                &text[..selection.start],
+                &text[selection.clone()],
+                &text[selection.end..]
+            )
+        }
+    }
+
+    #[test]
+    fn autosave_state_tracks_unsaved_edits() {
+        let mut state = AutosaveState::default();
+        assert!(!state.is_dirty());
+
+        let first = state.record_edit();
+        assert!(state.is_pending(first));
+        let second = state.record_edit();
+        assert!(!state.is_pending(first));
+
+        // A save of an older edit leaves newer edits unsaved.
+        state.mark_saved(first);
+        assert!(state.is_dirty());
+        state.mark_saved(second);
+        assert!(!state.is_dirty());
+        assert!(!state.is_pending(second));
+
+        state.record_edit();
+        state.mark_clean();
+        assert!(!state.is_dirty());
+    }
+
+    #[test]
+    fn enter_continues_lists() {
+        assert_eq!(run("- milk|", EditorKey::Enter), "- milk\n- |");
+        assert_eq!(
+            run("  * eggs|\nnext", EditorKey::Enter),
+            "  * eggs\n  * |\nnext"
+        );
+        assert_eq!(run("- [x] done|", EditorKey::Enter), "- [x] done\n- [ ] |");
+        assert_eq!(run("9. nine|", EditorKey::Enter), "9. nine\n10. |");
+        assert_eq!(run("1) a|", EditorKey::Enter), "1) a\n2) |");
+        assert_eq!(run("- split| here", EditorKey::Enter), "- split\n- | here");
+    }
+
+    #[test]
+    fn enter_on_empty_item_ends_list() {
+        assert_eq!(run("- one\n- |", EditorKey::Enter), "- one\n|");
+        assert_eq!(run("- one\n- [ ] |", EditorKey::Enter), "- one\n|");
+    }
+
+    #[test]
+    fn enter_outside_lists_inserts_newline() {
+        assert_eq!(run("plain|", EditorKey::Enter), "plain\n|");
+        assert_eq!(run("-no space|", EditorKey::Enter), "-no space\n|");
+        assert_eq!(run("2024 was|", EditorKey::Enter), "2024 was\n|");
+        assert_eq!(run("-| item", EditorKey::Enter), "-\n| item");
+        assert_eq!(run("- |a|b", EditorKey::Enter), "- \n|b");
+    }
+
+    #[test]
+    fn tab_indents_and_shift_tab_outdents_list_items() {
+        assert_eq!(run("- a\n- b|", EditorKey::Tab), "- a\n  - b|");
+        assert_eq!(run("- |a\n- b|", EditorKey::Tab), "  - |a\n  - b|");
+        assert_eq!(run("words|", EditorKey::Tab), "words  |");
+        assert_eq!(run("  - b|", EditorKey::ShiftTab), "- b|");
+        assert_eq!(run("   - |b\n\t- c|", EditorKey::ShiftTab), " - |b\n- c|");
+        assert_eq!(run("|- a\n|- b", EditorKey::Tab), "|  - a\n|- b");
+    }
+
+    #[test]
+    fn bold_wraps_and_unwraps_selection() {
+        assert_eq!(
+            run("make |this| loud", EditorKey::Bold),
+            "make **|this|** loud"
+        );
+        assert_eq!(
+            run("make **|this|** loud", EditorKey::Bold),
+            "make |this| loud"
+        );
+        assert_eq!(
+            run("make |**this**| loud", EditorKey::Bold),
+            "make |this| loud"
+        );
+        assert_eq!(run("x |", EditorKey::Bold), "x **|**");
+    }
+
+    #[test]
+    fn brackets_and_backticks_auto_pair() {
+        assert_eq!(run("call|", EditorKey::Char('(')), "call(|)");
+        assert_eq!(run("see |", EditorKey::Char('[')), "see [|]");
+        assert_eq!(run("|word", EditorKey::Char('(')), "(|word");
+        assert_eq!(run("f(|)", EditorKey::Char(')')), "f()|");
+        assert_eq!(run("wrap |me|", EditorKey::Char('`')), "wrap `|me|`");
+        assert_eq!(run("|", EditorKey::Char('`')), "`|`");
+        assert_eq!(run("`|`", EditorKey::Char('`')), "``|");
+        assert_eq!(run("``|", EditorKey::Char('`')), "```|");
+        assert_eq!(run("a|", EditorKey::Char(']')), "a]|");
+    }
+
+    #[test]
+    fn shortcut_keys_are_detected() {
+        assert!(is_shortcut_key(EditorKey::Enter));
+        assert!(is_shortcut_key(EditorKey::Char('`')));
+        assert!(is_shortcut_key(EditorKey::Char('}')));
+        assert!(!is_shortcut_key(EditorKey::Char('a')));
+    }
+
+    #[test]
+    fn word_count_skips_markdown_markers() {
+        assert_eq!(word_count(""), 0);
+        assert_eq!(word_count("# Plan\n\n- buy milk\n- call Ana > 3pm"), 6);
+        assert_eq!(word_count("don't stop-believing #tag"), 3);
+    }
+}
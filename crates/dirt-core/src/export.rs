@@ -1,9 +1,16 @@
 //! Shared note export helpers for CLI/Desktop/Mobile parity.
 
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write as _;
+use std::io::{Cursor, Write as _};
+use std::path::Path;
 
+use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
 
+use crate::attachment_refs::{render_attachment_embed, replace_attachment_refs};
+use crate::models::{extract_tags, Attachment, NoteId};
+use crate::util::fs::{sanitize_file_name, write_atomic};
 use crate::Note;
 
 /// Export output format shared by all clients.
@@ -11,6 +18,8 @@ use crate::Note;
 pub enum ExportFormat {
     Json,
     Markdown,
+    Org,
+    PlainText,
 }
 
 impl ExportFormat {
@@ -19,8 +28,85 @@ impl ExportFormat {
         match self {
             Self::Json => "json",
             Self::Markdown => "md",
+            Self::Org => "org",
+            Self::PlainText => "txt",
         }
     }
+
+    /// Parse a file extension as returned by [`Self::extension`].
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "json" => Some(Self::Json),
+            "md" => Some(Self::Markdown),
+            "org" => Some(Self::Org),
+            "txt" => Some(Self::PlainText),
+            _ => None,
+        }
+    }
+}
+
+/// Which notes are kept out of exports, journals, digests, and shares.
+///
+/// A note is private when it is flagged `no_export` or carries one of the
+/// tags listed in [`crate::models::Settings::no_export_tags`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportPrivacy {
+    /// Notes flagged `no_export`.
+    pub excluded_note_ids: HashSet<NoteId>,
+    /// Lowercase tag names whose notes are private.
+    pub excluded_tags: Vec<String>,
+}
+
+impl ExportPrivacy {
+    /// Privacy rules that keep every note.
+    #[must_use]
+    pub fn include_all() -> Self {
+        Self::default()
+    }
+
+    /// Whether `note` may leave the app.
+    #[must_use]
+    pub fn allows(&self, note: &Note) -> bool {
+        if self.excluded_note_ids.contains(&note.id) {
+            return false;
+        }
+        self.excluded_tags.is_empty()
+            || !note
+                .tags()
+                .iter()
+                .any(|tag| self.excluded_tags.contains(tag))
+    }
+
+    /// Keep the notes that may leave the app, in their original order.
+    #[must_use]
+    pub fn filter(&self, notes: Vec<Note>) -> Vec<Note> {
+        notes.into_iter().filter(|note| self.allows(note)).collect()
+    }
+}
+
+/// Parse a comma or whitespace separated list of `no_export` tag rules.
+///
+/// Leading `#` marks are optional; invalid and duplicate tags are dropped.
+#[must_use]
+pub fn parse_no_export_tags(raw: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for item in raw.split(|c: char| c == ',' || c.is_whitespace()) {
+        let name = item.trim().trim_start_matches('#');
+        if name.is_empty() {
+            continue;
+        }
+        let Some(tag) = extract_tags(&format!("#{name}"))
+            .into_iter()
+            .find(|tag| tag.eq_ignore_ascii_case(name))
+        else {
+            continue;
+        };
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
 }
 
 /// Serializable note representation used in JSON and Markdown exports.
@@ -48,6 +134,163 @@ pub fn note_to_export_item(note: &Note) -> ExportNote {
     }
 }
 
+/// An internal `[[link]]` or `!attach[..](id)` reference that matched no
+/// exported note or attachment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenLink {
+    /// Note containing the link.
+    pub note_id: String,
+    /// Link target as written, e.g. `Project plan` for `[[Project plan]]`, or
+    /// the attachment id of an inline reference.
+    pub target: String,
+}
+
+/// Markdown export along with the links that could not be resolved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownExport {
+    pub markdown: String,
+    pub broken_links: Vec<BrokenLink>,
+}
+
+/// Stable in-document anchor for an exported note.
+#[must_use]
+pub fn note_anchor(note_id: &str) -> String {
+    format!("note-{note_id}")
+}
+
+/// Stable in-document anchor for an exported attachment.
+#[must_use]
+pub fn attachment_anchor(attachment_id: &str) -> String {
+    format!("attachment-{attachment_id}")
+}
+
+/// Link-resolution pass over the notes and attachments of one export.
+///
+/// `[[target]]` resolves to a note by id or, case-insensitively, by first
+/// line, falling back to an attachment by id or file name. `![[target]]`
+/// embeds prefer attachments. Attachment names are looked up on the linking
+/// note before the rest of the export. Inline `!attach[label](id)` references
+/// embed the attachment's media when its location is known, and link to its
+/// anchor otherwise.
+pub struct ExportLinks<'a> {
+    notes: HashMap<String, String>,
+    attachments: &'a [Attachment],
+    media_urls: HashMap<String, String>,
+    pattern: Regex,
+}
+
+impl<'a> ExportLinks<'a> {
+    #[must_use]
+    pub fn new(notes: &[Note], attachments: &'a [Attachment]) -> Self {
+        let mut targets = HashMap::new();
+        for note in notes {
+            let id = note.id.to_string();
+            let title = note.title_preview(usize::MAX).trim().to_lowercase();
+            if !title.is_empty() {
+                targets.entry(title).or_insert_with(|| id.clone());
+            }
+            targets.insert(id.clone(), id);
+        }
+
+        Self {
+            notes: targets,
+            attachments,
+            media_urls: HashMap::new(),
+            pattern: Regex::new(r"(!?)\[\[([^\[\]\n]+)\]\]").expect("Invalid regex"),
+        }
+    }
+
+    /// Embed referenced attachments from `media_urls`, keyed by attachment id
+    /// (paths of downloaded copies or signed URLs).
+    #[must_use]
+    pub fn with_media_urls(mut self, media_urls: HashMap<String, String>) -> Self {
+        self.media_urls = media_urls;
+        self
+    }
+
+    /// Rewrite a note's internal links as Markdown links to export anchors.
+    ///
+    /// Unresolved links are left as written and reported.
+    #[must_use]
+    pub fn resolve(&self, note: &Note) -> (String, Vec<BrokenLink>) {
+        let note_id = note.id.to_string();
+        let mut broken = Vec::new();
+        let content = self
+            .pattern
+            .replace_all(&note.content, |caps: &Captures<'_>| {
+                let embed = !caps[1].is_empty();
+                let target = caps[2].trim();
+                let resolved = if embed {
+                    self.attachment_link(&note_id, target)
+                        .or_else(|| self.note_link(target))
+                } else {
+                    self.note_link(target)
+                        .or_else(|| self.attachment_link(&note_id, target))
+                };
+                resolved.unwrap_or_else(|| {
+                    broken.push(BrokenLink {
+                        note_id: note_id.clone(),
+                        target: target.to_string(),
+                    });
+                    caps[0].to_string()
+                })
+            })
+            .into_owned();
+        let content = replace_attachment_refs(&content, |reference| {
+            let resolved = self.attachment_embed(&reference.label, &reference.attachment_id);
+            if resolved.is_none() {
+                broken.push(BrokenLink {
+                    note_id: note_id.clone(),
+                    target: reference.attachment_id.clone(),
+                });
+            }
+            resolved
+        });
+        (content, broken)
+    }
+
+    fn note_link(&self, target: &str) -> Option<String> {
+        let id = self
+            .notes
+            .get(&target.to_lowercase())
+            .or_else(|| self.notes.get(target))?;
+        Some(format!("[{target}](#{})", note_anchor(id)))
+    }
+
+    fn attachment_embed(&self, label: &str, attachment_id: &str) -> Option<String> {
+        let attachment = self
+            .attachments
+            .iter()
+            .find(|attachment| attachment.id.as_str() == attachment_id)?;
+        let label = if label.is_empty() {
+            attachment.filename.as_str()
+        } else {
+            label
+        };
+        Some(self.media_urls.get(attachment_id).map_or_else(
+            || format!("[{label}](#{})", attachment_anchor(attachment_id)),
+            |url| render_attachment_embed(label, &attachment.mime_type, url),
+        ))
+    }
+
+    fn attachment_link(&self, note_id: &str, target: &str) -> Option<String> {
+        let matches = |attachment: &&Attachment| {
+            attachment.id.as_str() == target || attachment.filename.eq_ignore_ascii_case(target)
+        };
+        let attachment = self
+            .attachments
+            .iter()
+            .filter(|attachment| attachment.note_id.as_str() == note_id)
+            .find(matches)
+            .or_else(|| self.attachments.iter().find(matches))?;
+        Some(format!(
+            "[{}](#{})",
+            attachment.filename,
+            attachment_anchor(&attachment.id.as_str())
+        ))
+    }
+}
+
 /// Render notes as pretty-printed JSON.
 pub fn render_json_export(notes: &[Note]) -> serde_json::Result<String> {
     let items = notes
@@ -58,8 +301,35 @@ pub fn render_json_export(notes: &[Note]) -> serde_json::Result<String> {
 }
 
 /// Render notes in Markdown with frontmatter blocks.
+///
+/// Links between notes are resolved to in-document anchors.
 #[must_use]
 pub fn render_markdown_export(notes: &[Note]) -> String {
+    render_markdown_export_with_links(notes, &[]).markdown
+}
+
+/// Render notes and their attachments in Markdown, resolving `[[links]]`.
+///
+/// Each note gets a `note-<id>` anchor and each attachment an
+/// `attachment-<id>` anchor under its note; unresolved links are reported.
+#[must_use]
+pub fn render_markdown_export_with_links(
+    notes: &[Note],
+    attachments: &[Attachment],
+) -> MarkdownExport {
+    render_markdown_export_with_media(notes, attachments, HashMap::new())
+}
+
+/// Like [`render_markdown_export_with_links`], embedding the media of inline
+/// `!attach[..](id)` references found in `media_urls` (keyed by attachment id).
+#[must_use]
+pub fn render_markdown_export_with_media(
+    notes: &[Note],
+    attachments: &[Attachment],
+    media_urls: HashMap<String, String>,
+) -> MarkdownExport {
+    let links = ExportLinks::new(notes, attachments).with_media_urls(media_urls);
+    let mut broken_links = Vec::new();
     let mut output = String::new();
 
     for (index, note) in notes.iter().enumerate() {
@@ -78,6 +348,132 @@ pub fn render_markdown_export(notes: &[Note]) -> String {
         }
         let _ = writeln!(output, "---");
         let _ = writeln!(output);
+        let _ = writeln!(output, "<a id=\"{}\"></a>", note_anchor(&export_note.id));
+        let _ = writeln!(output);
+
+        let (content, broken) = links.resolve(note);
+        broken_links.extend(broken);
+        output.push_str(&content);
+        output.push('\n');
+
+        let mut note_attachments = attachments
+            .iter()
+            .filter(|attachment| attachment.note_id == note.id && !attachment.is_deleted)
+            .peekable();
+        if note_attachments.peek().is_some() {
+            let _ = writeln!(output);
+            let _ = writeln!(output, "Attachments:");
+            for attachment in note_attachments {
+                let _ = writeln!(
+                    output,
+                    "- <a id=\"{}\"></a>{} ({}, {} bytes)",
+                    attachment_anchor(&attachment.id.as_str()),
+                    attachment.filename,
+                    attachment.mime_type,
+                    attachment.size_bytes
+                );
+            }
+        }
+    }
+
+    MarkdownExport {
+        markdown: output,
+        broken_links,
+    }
+}
+
+/// Render notes as an Org-mode document with one top-level heading per note.
+///
+/// The heading is the note's first line followed by its tags, and the id and
+/// timestamps go in a property drawer so `[[id:..]]` links keep working. Body
+/// lines starting with `*` are indented by a space so Org does not read them
+/// as headings.
+#[must_use]
+pub fn render_org_export(notes: &[Note]) -> String {
+    let mut output = String::new();
+
+    for (index, note) in notes.iter().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+
+        let export_note = note_to_export_item(note);
+        let (title, body) = export_note
+            .content
+            .split_once('\n')
+            .unwrap_or((export_note.content.as_str(), ""));
+        let title = title.trim();
+        output.push_str("* ");
+        output.push_str(if title.is_empty() { "Untitled" } else { title });
+        if !export_note.tags.is_empty() {
+            // Org tags cannot contain `-`.
+            let tags = export_note
+                .tags
+                .iter()
+                .map(|tag| tag.replace('-', "_"))
+                .collect::<Vec<_>>()
+                .join(":");
+            let _ = write!(output, " :{tags}:");
+        }
+        output.push('\n');
+        let _ = writeln!(output, ":PROPERTIES:");
+        let _ = writeln!(output, ":ID: {}", export_note.id);
+        let _ = writeln!(
+            output,
+            ":CREATED: {}",
+            format_export_time(export_note.created_at, "[%Y-%m-%d %a %H:%M]")
+        );
+        let _ = writeln!(
+            output,
+            ":UPDATED: {}",
+            format_export_time(export_note.updated_at, "[%Y-%m-%d %a %H:%M]")
+        );
+        let _ = writeln!(output, ":END:");
+
+        for line in body.lines() {
+            if line.starts_with('*') {
+                output.push(' ');
+            }
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+/// Rule printed between notes in a plain-text export.
+const PLAINTEXT_SEPARATOR: &str = "========================================";
+
+/// Render notes as plain text: a short header per note, its content as
+/// written, and a rule between notes.
+#[must_use]
+pub fn render_plaintext_export(notes: &[Note]) -> String {
+    let mut output = String::new();
+
+    for (index, note) in notes.iter().enumerate() {
+        if index > 0 {
+            let _ = writeln!(output);
+            let _ = writeln!(output, "{PLAINTEXT_SEPARATOR}");
+            let _ = writeln!(output);
+        }
+
+        let export_note = note_to_export_item(note);
+        let _ = writeln!(output, "ID: {}", export_note.id);
+        let _ = writeln!(
+            output,
+            "Created: {}",
+            format_export_time(export_note.created_at, "%Y-%m-%d %H:%M UTC")
+        );
+        let _ = writeln!(
+            output,
+            "Updated: {}",
+            format_export_time(export_note.updated_at, "%Y-%m-%d %H:%M UTC")
+        );
+        if !export_note.tags.is_empty() {
+            let _ = writeln!(output, "Tags: {}", export_note.tags.join(", "));
+        }
+        let _ = writeln!(output);
         output.push_str(&export_note.content);
         output.push('\n');
     }
@@ -85,11 +481,21 @@ pub fn render_markdown_export(notes: &[Note]) -> String {
     output
 }
 
+/// Format a Unix ms timestamp in UTC, falling back to the raw number.
+fn format_export_time(timestamp_ms: i64, format: &str) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms).map_or_else(
+        || timestamp_ms.to_string(),
+        |date_time| date_time.format(format).to_string(),
+    )
+}
+
 /// Render notes based on selected export format.
 pub fn render_notes_export(notes: &[Note], format: ExportFormat) -> serde_json::Result<String> {
     match format {
         ExportFormat::Json => render_json_export(notes),
         ExportFormat::Markdown => Ok(render_markdown_export(notes)),
+        ExportFormat::Org => Ok(render_org_export(notes)),
+        ExportFormat::PlainText => Ok(render_plaintext_export(notes)),
     }
 }
 
@@ -99,10 +505,143 @@ pub fn suggested_export_file_name(format: ExportFormat, timestamp_ms: i64) -> St
     format!("dirt-export-{timestamp_ms}.{}", format.extension())
 }
 
+/// Default file name of an export bundle, e.g. `dirt-export-<ms>.zip`.
+#[must_use]
+pub fn suggested_bundle_file_name(timestamp_ms: i64) -> String {
+    format!("dirt-export-{timestamp_ms}.zip")
+}
+
+/// Path of an attachment's file inside an export bundle,
+/// `attachments/<attachment id>/<file name>`.
+#[must_use]
+pub fn bundle_attachment_path(attachment: &Attachment) -> String {
+    format!(
+        "attachments/{}/{}",
+        attachment.id,
+        sanitize_file_name(&attachment.filename)
+    )
+}
+
+/// Write an export bundle to `path`: a zip archive holding the rendered notes
+/// as `notes.<extension>` and each attachment's bytes at
+/// [`bundle_attachment_path`].
+pub fn write_export_bundle(
+    path: &Path,
+    format: ExportFormat,
+    notes: &[u8],
+    attachments: &[(Attachment, Vec<u8>)],
+) -> crate::Result<()> {
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default();
+    let mut add = |name: &str, bytes: &[u8]| -> std::io::Result<()> {
+        archive
+            .start_file(name, options)
+            .map_err(std::io::Error::other)?;
+        archive.write_all(bytes)
+    };
+    add(&format!("notes.{}", format.extension()), notes)?;
+    for (attachment, bytes) in attachments {
+        add(&bundle_attachment_path(attachment), bytes)?;
+    }
+    let bundle = archive.finish().map_err(std::io::Error::other)?;
+    write_atomic(path, bundle.into_inner())?;
+    Ok(())
+}
+
+/// File name of a note in a mirrored export directory, e.g. `<id>.md`.
+#[must_use]
+pub fn note_export_file_name(note_id: &NoteId, format: ExportFormat) -> String {
+    format!("{note_id}.{}", format.extension())
+}
+
+/// Render a single note as the contents of its mirrored export file.
+///
+/// JSON files hold one export record; the other formats hold the note as it
+/// appears in a whole-library export.
+pub fn render_note_file(note: &Note, format: ExportFormat) -> serde_json::Result<String> {
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&note_to_export_item(note)),
+        format => render_notes_export(std::slice::from_ref(note), format),
+    }
+}
+
+/// Files an incremental export has to write and remove.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncrementalExportPlan<'a> {
+    /// Notes edited since the last export or missing from the destination.
+    pub changed: Vec<&'a Note>,
+    /// Previously exported notes that were deleted or are no longer exportable.
+    pub removed: Vec<NoteId>,
+    /// Cursor to record once the changes are written.
+    pub cursor: i64,
+}
+
+/// Work out what an incremental export into a mirror directory must do.
+///
+/// `notes` are the currently exportable notes, `previous_cursor` the highest
+/// `updated_at` written by the last run (`None` forces a full export), and
+/// `exported` the note files already present in the destination.
+#[must_use]
+pub fn plan_incremental_export<'a>(
+    notes: &'a [Note],
+    previous_cursor: Option<i64>,
+    exported: &HashSet<NoteId>,
+) -> IncrementalExportPlan<'a> {
+    let changed = notes
+        .iter()
+        .filter(|note| {
+            previous_cursor.map_or(true, |cursor| note.updated_at > cursor)
+                || !exported.contains(&note.id)
+        })
+        .collect();
+
+    let live: HashSet<NoteId> = notes.iter().map(|note| note.id).collect();
+    let mut removed: Vec<NoteId> = exported.difference(&live).copied().collect();
+    removed.sort_by_key(NoteId::to_string);
+
+    let cursor = notes
+        .iter()
+        .map(|note| note.updated_at)
+        .chain(previous_cursor)
+        .max()
+        .unwrap_or(0);
+
+    IncrementalExportPlan {
+        changed,
+        removed,
+        cursor,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn export_privacy_skips_flagged_and_tagged_notes() {
+        let flagged = Note::new("Diary entry");
+        let tagged = Note::new("Salary review #Private");
+        let public = Note::new("Release notes #work");
+        let privacy = ExportPrivacy {
+            excluded_note_ids: HashSet::from([flagged.id]),
+            excluded_tags: parse_no_export_tags("#private, secret"),
+        };
+
+        let kept = privacy.filter(vec![flagged.clone(), tagged.clone(), public.clone()]);
+        assert_eq!(kept, vec![public]);
+        assert!(ExportPrivacy::include_all().allows(&flagged));
+        assert!(ExportPrivacy::include_all().allows(&tagged));
+    }
+
+    #[test]
+    fn parse_no_export_tags_normalizes_and_dedupes() {
+        assert_eq!(
+            parse_no_export_tags(" #Private, private journal  #1bad,"),
+            vec!["private", "journal"]
+        );
+        assert!(parse_no_export_tags("").is_empty());
+    }
+
     #[test]
     fn note_to_export_item_sorts_tags() {
         let note = Note::new("#zeta test #alpha #beta");
@@ -119,6 +658,8 @@ mod tests {
             created_at: 123,
             updated_at: 456,
             is_deleted: false,
+            is_pinned: false,
+            is_archived: false,
         };
 
         let rendered = render_markdown_export(&[note]);
@@ -129,6 +670,106 @@ mod tests {
         assert!(rendered.contains("Hello export #tag"));
     }
 
+    #[test]
+    fn render_markdown_export_resolves_note_and_attachment_links() {
+        let target = Note::new("Project plan\nDetails");
+        let source = Note::new(format!(
+            "See [[project PLAN]], [[{}]] and ![[Diagram.PNG]]",
+            target.id
+        ));
+        let diagram =
+            Attachment::new(source.id, "diagram.png", "image/png", 42, "notes/diagram").unwrap();
+
+        let export = render_markdown_export_with_links(
+            &[target.clone(), source],
+            std::slice::from_ref(&diagram),
+        );
+
+        let target_anchor = note_anchor(&target.id.to_string());
+        let diagram_anchor = attachment_anchor(&diagram.id.as_str());
+        assert!(export
+            .markdown
+            .contains(&format!("<a id=\"{target_anchor}\"></a>")));
+        assert!(export
+            .markdown
+            .contains(&format!("[project PLAN](#{target_anchor})")));
+        assert!(export
+            .markdown
+            .contains(&format!("[{}](#{target_anchor})", target.id)));
+        assert!(export
+            .markdown
+            .contains(&format!("[diagram.png](#{diagram_anchor})")));
+        assert!(export.markdown.contains(&format!(
+            "- <a id=\"{diagram_anchor}\"></a>diagram.png (image/png, 42 bytes)"
+        )));
+        assert!(export.broken_links.is_empty());
+    }
+
+    #[test]
+    fn render_markdown_export_embeds_inline_attachment_references() {
+        let mut note = Note::new("Sketch");
+        let sketch =
+            Attachment::new(note.id, "sketch.png", "image/png", 42, "notes/sketch").unwrap();
+        let spec =
+            Attachment::new(note.id, "spec.pdf", "application/pdf", 7, "notes/spec").unwrap();
+        note.content = format!(
+            "Sketch\n!attach[sketch.png]({})\n!attach[Spec]({})\n!attach[old.png](gone)",
+            sketch.id, spec.id
+        );
+        let attachments = [sketch.clone(), spec.clone()];
+
+        let linked = render_markdown_export_with_links(std::slice::from_ref(&note), &attachments);
+        assert!(linked.markdown.contains(&format!(
+            "[sketch.png](#{})",
+            attachment_anchor(&sketch.id.as_str())
+        )));
+
+        let media_urls = HashMap::from([
+            (sketch.id.to_string(), "media/sketch.png".to_string()),
+            (spec.id.to_string(), "media/spec.pdf".to_string()),
+        ]);
+        let embedded = render_markdown_export_with_media(
+            std::slice::from_ref(&note),
+            &attachments,
+            media_urls,
+        );
+        assert!(embedded
+            .markdown
+            .contains("![sketch.png](media/sketch.png)"));
+        assert!(embedded.markdown.contains("[Spec](media/spec.pdf)"));
+        assert!(embedded.markdown.contains("!attach[old.png](gone)"));
+        assert_eq!(
+            embedded.broken_links,
+            [BrokenLink {
+                note_id: note.id.to_string(),
+                target: "gone".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn render_markdown_export_reports_broken_links() {
+        let note = Note::new("Todo\nAsk about [[Missing note]] and ![[gone.pdf]]");
+
+        let export = render_markdown_export_with_links(std::slice::from_ref(&note), &[]);
+
+        assert!(export.markdown.contains("[[Missing note]]"));
+        assert!(export.markdown.contains("![[gone.pdf]]"));
+        assert_eq!(
+            export.broken_links,
+            vec![
+                BrokenLink {
+                    note_id: note.id.to_string(),
+                    target: "Missing note".to_string(),
+                },
+                BrokenLink {
+                    note_id: note.id.to_string(),
+                    target: "gone.pdf".to_string(),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn suggested_export_file_name_uses_format_extension() {
         assert_eq!(
@@ -140,4 +781,143 @@ mod tests {
             "dirt-export-456.md"
         );
     }
+
+    #[test]
+    fn plan_incremental_export_writes_changed_and_removes_gone_notes() {
+        let mut unchanged = Note::new("Unchanged");
+        unchanged.updated_at = 1_000;
+        let mut edited = Note::new("Edited");
+        edited.updated_at = 3_000;
+        let mut missing = Note::new("Never written");
+        missing.updated_at = 500;
+        let deleted = NoteId::new();
+        let notes = vec![unchanged.clone(), edited.clone(), missing.clone()];
+        let exported = HashSet::from([unchanged.id, edited.id, deleted]);
+
+        let plan = plan_incremental_export(&notes, Some(2_000), &exported);
+        assert_eq!(plan.changed, vec![&edited, &missing]);
+        assert_eq!(plan.removed, vec![deleted]);
+        assert_eq!(plan.cursor, 3_000);
+
+        let full = plan_incremental_export(&notes, None, &exported);
+        assert_eq!(full.changed.len(), 3);
+        assert_eq!(
+            plan_incremental_export(&[], Some(2_000), &HashSet::new()).cursor,
+            2_000
+        );
+    }
+
+    #[test]
+    fn render_note_file_writes_one_record_per_note() {
+        let note = Note::new("Mirror me #backup");
+        let json = render_note_file(&note, ExportFormat::Json).unwrap();
+        let item: ExportNote = serde_json::from_str(&json).unwrap();
+        assert_eq!(item.id, note.id.to_string());
+        assert_eq!(item.tags, vec!["backup"]);
+
+        let markdown = render_note_file(&note, ExportFormat::Markdown).unwrap();
+        assert!(markdown.starts_with("---\nid: "));
+        assert!(markdown.contains("Mirror me #backup"));
+        assert_eq!(
+            note_export_file_name(&note.id, ExportFormat::Markdown),
+            format!("{}.md", note.id)
+        );
+        assert_eq!(
+            ExportFormat::from_extension("md"),
+            Some(ExportFormat::Markdown)
+        );
+        assert_eq!(
+            ExportFormat::from_extension("txt"),
+            Some(ExportFormat::PlainText)
+        );
+        assert_eq!(ExportFormat::from_extension("pdf"), None);
+
+        let org = render_note_file(&note, ExportFormat::Org).unwrap();
+        assert!(org.starts_with("* Mirror me #backup :backup:\n:PROPERTIES:"));
+        assert_eq!(
+            note_export_file_name(&note.id, ExportFormat::Org),
+            format!("{}.org", note.id)
+        );
+    }
+
+    #[test]
+    fn render_org_export_writes_headings_with_properties() {
+        let note = Note {
+            id: "cccccccc-cccc-7ccc-8ccc-222222222222".parse().unwrap(),
+            content: "Reading list #to-read #books\n* Dune\nSee [[Project plan]]".to_string(),
+            created_at: 1_700_000_000_000,
+            updated_at: 1_700_003_600_000,
+            is_deleted: false,
+            is_pinned: false,
+            is_archived: false,
+        };
+        let untitled = Note::new("\nBody only");
+
+        let rendered = render_org_export(&[note, untitled]);
+        assert!(rendered.starts_with(
+            "* Reading list #to-read #books :books:to_read:\n\
+             :PROPERTIES:\n\
+             :ID: cccccccc-cccc-7ccc-8ccc-222222222222\n\
+             :CREATED: [2023-11-14 Tue 22:13]\n\
+             :UPDATED: [2023-11-14 Tue 23:13]\n\
+             :END:\n \
+             * Dune\n\
+             See [[Project plan]]\n"
+        ));
+        assert!(rendered.contains("\n* Untitled\n"));
+        assert!(rendered.ends_with(":END:\nBody only\n"));
+    }
+
+    #[test]
+    fn render_plaintext_export_separates_notes() {
+        let mut first = Note::new("First note #work\nLine two");
+        first.created_at = 1_700_000_000_000;
+        first.updated_at = 1_700_000_000_000;
+        let second = Note::new("Second note");
+
+        let rendered = render_plaintext_export(&[first.clone(), second.clone()]);
+        assert!(rendered.starts_with(&format!(
+            "ID: {}\nCreated: 2023-11-14 22:13 UTC\nUpdated: 2023-11-14 22:13 UTC\n\
+             Tags: work\n\nFirst note #work\nLine two\n",
+            first.id
+        )));
+        assert!(rendered.contains(&format!("\n{PLAINTEXT_SEPARATOR}\n\nID: {}\n", second.id)));
+        assert!(!rendered.contains("Tags: \n"));
+        assert!(rendered.ends_with("\nSecond note\n"));
+    }
+
+    #[test]
+    fn write_export_bundle_packs_notes_and_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(suggested_bundle_file_name(7));
+        let note = Note::new("Trip photos");
+        let attachment = Attachment::new(
+            note.id,
+            "../Beach Day.JPG",
+            "image/jpeg",
+            3,
+            "notes/a/b.jpg",
+        )
+        .unwrap();
+
+        write_export_bundle(
+            &path,
+            ExportFormat::Markdown,
+            b"# Notes",
+            &[(attachment.clone(), b"jpg".to_vec())],
+        )
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let mut notes = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("notes.md").unwrap(), &mut notes)
+            .unwrap();
+        assert_eq!(notes, "# Notes");
+        let attachment_path = bundle_attachment_path(&attachment);
+        assert_eq!(
+            attachment_path,
+            format!("attachments/{}/beach-day.jpg", attachment.id)
+        );
+        assert_eq!(archive.by_name(&attachment_path).unwrap().size(), 3);
+    }
 }
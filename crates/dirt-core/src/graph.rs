@@ -0,0 +1,282 @@
+//! Note relationship graph.
+//!
+//! Builds a node/edge view of notes from `[[wiki links]]` between notes and
+//! the `#tags` they share. Clients render and lay out the graph themselves.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{extract_note_links, Note};
+
+/// Prefix for tag node ids, keeping them distinct from note ids.
+const TAG_NODE_PREFIX: &str = "tag:";
+/// Maximum characters used for note node labels.
+const NOTE_LABEL_MAX_CHARS: usize = 40;
+
+/// Limits and options for a graph query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphQuery {
+    /// Maximum number of notes, most recently updated first.
+    pub max_notes: usize,
+    /// Maximum number of edges; link edges are kept before tag edges.
+    pub max_edges: usize,
+    /// Whether to add tag nodes and note-to-tag edges.
+    pub include_tags: bool,
+}
+
+impl Default for GraphQuery {
+    fn default() -> Self {
+        Self {
+            max_notes: 200,
+            max_edges: 1_000,
+            include_tags: true,
+        }
+    }
+}
+
+/// Kind of graph node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphNodeKind {
+    Note,
+    Tag,
+}
+
+/// A note or tag in the graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// Note id, or `tag:<name>` for tags.
+    pub id: String,
+    pub kind: GraphNodeKind,
+    /// Note title preview or `#tag`.
+    pub label: String,
+    /// Number of edges touching this node.
+    pub degree: usize,
+}
+
+/// Kind of graph edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphEdgeKind {
+    /// `source` links to `target` with `[[...]]`.
+    Link,
+    /// `source` note carries the `target` tag.
+    Tag,
+}
+
+/// A directed relationship between two nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub kind: GraphEdgeKind,
+}
+
+/// Graph of notes and their relationships.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Whether notes or edges were dropped to honor the query limits.
+    pub truncated: bool,
+}
+
+impl NoteGraph {
+    /// Look up a node by id.
+    #[must_use]
+    pub fn node(&self, id: &str) -> Option<&GraphNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+}
+
+/// Build a graph from notes, honoring the query limits.
+///
+/// Link targets resolve to a note id or, case-insensitively, to a note's first
+/// line. Links to notes outside the selected window are dropped.
+#[must_use]
+pub fn build_note_graph(notes: &[Note], query: &GraphQuery) -> NoteGraph {
+    let mut candidates: Vec<&Note> = notes.iter().filter(|note| !note.is_deleted).collect();
+    candidates.sort_by(|left, right| right.updated_at.cmp(&left.updated_at));
+    let mut truncated = candidates.len() > query.max_notes;
+    candidates.truncate(query.max_notes);
+
+    let mut targets: HashMap<String, String> = HashMap::new();
+    for note in &candidates {
+        let id = note.id.to_string();
+        let title = note.title_preview(usize::MAX).trim().to_lowercase();
+        if !title.is_empty() {
+            targets.entry(title).or_insert_with(|| id.clone());
+        }
+        targets.insert(id.clone(), id);
+    }
+
+    let mut edges = Vec::new();
+    let mut seen_links = HashSet::new();
+    for note in &candidates {
+        let source = note.id.to_string();
+        for link in extract_note_links(&note.content) {
+            let Some(target) = targets
+                .get(&link.to_lowercase())
+                .or_else(|| targets.get(&link))
+            else {
+                continue;
+            };
+            if *target != source && seen_links.insert((source.clone(), target.clone())) {
+                edges.push(GraphEdge {
+                    source: source.clone(),
+                    target: target.clone(),
+                    kind: GraphEdgeKind::Link,
+                });
+            }
+        }
+    }
+
+    if query.include_tags {
+        for note in &candidates {
+            let mut tags = note.tags();
+            tags.sort();
+            for tag in tags {
+                edges.push(GraphEdge {
+                    source: note.id.to_string(),
+                    target: format!("{TAG_NODE_PREFIX}{tag}"),
+                    kind: GraphEdgeKind::Tag,
+                });
+            }
+        }
+    }
+
+    if edges.len() > query.max_edges {
+        truncated = true;
+        edges.truncate(query.max_edges);
+    }
+
+    let mut degrees: HashMap<&str, usize> = HashMap::new();
+    for edge in &edges {
+        *degrees.entry(edge.source.as_str()).or_default() += 1;
+        *degrees.entry(edge.target.as_str()).or_default() += 1;
+    }
+
+    let mut nodes: Vec<GraphNode> = candidates
+        .iter()
+        .map(|note| {
+            let id = note.id.to_string();
+            let degree = degrees.get(id.as_str()).copied().unwrap_or(0);
+            GraphNode {
+                label: note_label(note),
+                kind: GraphNodeKind::Note,
+                degree,
+                id,
+            }
+        })
+        .collect();
+
+    // Tag nodes only exist while at least one kept edge points at them.
+    let mut tag_ids: Vec<&str> = degrees
+        .keys()
+        .copied()
+        .filter(|id| id.starts_with(TAG_NODE_PREFIX))
+        .collect();
+    tag_ids.sort_unstable();
+    nodes.extend(tag_ids.into_iter().map(|id| GraphNode {
+        id: id.to_string(),
+        kind: GraphNodeKind::Tag,
+        label: format!("#{}", id.trim_start_matches(TAG_NODE_PREFIX)),
+        degree: degrees[id],
+    }));
+
+    NoteGraph {
+        nodes,
+        edges,
+        truncated,
+    }
+}
+
+fn note_label(note: &Note) -> String {
+    let title = note.title_preview(NOTE_LABEL_MAX_CHARS);
+    let title = title.trim();
+    if title.is_empty() {
+        "Untitled".to_string()
+    } else {
+        title.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(content: &str, updated_at: i64) -> Note {
+        let mut note = Note::new(content);
+        note.updated_at = updated_at;
+        note
+    }
+
+    #[test]
+    fn links_resolve_by_title_and_id() {
+        let target = note("Project plan\nDetails", 1);
+        let by_id = note(&format!("Ref [[{}]]", target.id), 2);
+        let by_title = note("See [[project PLAN]] and [[missing]] and [[See]]", 3);
+        let graph = build_note_graph(
+            &[target.clone(), by_id.clone(), by_title.clone()],
+            &GraphQuery {
+                include_tags: false,
+                ..GraphQuery::default()
+            },
+        );
+
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+        assert!(graph
+            .edges
+            .iter()
+            .all(|edge| edge.kind == GraphEdgeKind::Link && edge.target == target.id.to_string()));
+        assert_eq!(graph.node(&target.id.to_string()).unwrap().degree, 2);
+        assert!(!graph.truncated);
+    }
+
+    #[test]
+    fn tags_become_shared_nodes() {
+        let first = note("One #rust #graph", 1);
+        let second = note("Two #Rust", 2);
+        let graph = build_note_graph(&[first, second], &GraphQuery::default());
+
+        let rust = graph.node("tag:rust").expect("rust tag node");
+        assert_eq!(rust.kind, GraphNodeKind::Tag);
+        assert_eq!(rust.label, "#rust");
+        assert_eq!(rust.degree, 2);
+        assert_eq!(graph.node("tag:graph").unwrap().degree, 1);
+        assert_eq!(graph.edges.len(), 3);
+    }
+
+    #[test]
+    fn limits_keep_recent_notes_and_mark_truncation() {
+        let old = note("Old\n#a", 1);
+        let mid = note("Mid\n[[Old]] #b", 2);
+        let new = note("New\n[[Mid]] #c", 3);
+        let mut deleted = note("Deleted\n#d", 4);
+        deleted.is_deleted = true;
+
+        let graph = build_note_graph(
+            &[old, mid.clone(), new.clone(), deleted],
+            &GraphQuery {
+                max_notes: 2,
+                max_edges: 2,
+                include_tags: true,
+            },
+        );
+
+        assert!(graph.truncated);
+        let note_ids: Vec<&str> = graph
+            .nodes
+            .iter()
+            .filter(|node| node.kind == GraphNodeKind::Note)
+            .map(|node| node.id.as_str())
+            .collect();
+        assert_eq!(note_ids, vec![new.id.to_string(), mid.id.to_string()]);
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[0].kind, GraphEdgeKind::Link);
+        assert!(graph.node("tag:a").is_none());
+        assert!(graph.node("tag:d").is_none());
+    }
+}
@@ -0,0 +1,105 @@
+//! Shared HTTP response handling for Dirt API clients.
+//!
+//! The Dirt API reports per-user rate limits through `x-ratelimit-*` headers
+//! and `Retry-After` on HTTP 429 responses.
+
+use std::fmt;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+/// Header carrying the request budget for the current window.
+pub const RATE_LIMIT_LIMIT_HEADER: &str = "x-ratelimit-limit";
+/// Header carrying the requests left in the current window.
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+/// Header carrying seconds until the current window resets.
+pub const RATE_LIMIT_RESET_HEADER: &str = "x-ratelimit-reset";
+
+/// Fallback wait when a 429 response carries no timing headers.
+const DEFAULT_RETRY_AFTER_SECS: u64 = 5;
+
+/// Rate-limit rejection reported by the Dirt API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited {
+    /// Seconds to wait before retrying.
+    pub retry_after_secs: u64,
+    /// Requests allowed per window, if reported.
+    pub limit: Option<u32>,
+    /// Requests remaining in the window, if reported.
+    pub remaining: Option<u32>,
+}
+
+impl RateLimited {
+    /// Parse a rate-limit rejection from a response status and headers.
+    ///
+    /// Returns `None` unless the status is HTTP 429.
+    pub fn from_response(status: StatusCode, headers: &HeaderMap) -> Option<Self> {
+        if status != StatusCode::TOO_MANY_REQUESTS {
+            return None;
+        }
+
+        let retry_after_secs = header_number::<u64>(headers, RETRY_AFTER.as_str())
+            .or_else(|| header_number::<u64>(headers, RATE_LIMIT_RESET_HEADER))
+            .unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+
+        Some(Self {
+            retry_after_secs,
+            limit: header_number(headers, RATE_LIMIT_LIMIT_HEADER),
+            remaining: header_number(headers, RATE_LIMIT_REMAINING_HEADER),
+        })
+    }
+
+    /// Wait duration before the next attempt.
+    pub const fn retry_after(&self) -> Duration {
+        Duration::from_secs(self.retry_after_secs)
+    }
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rate limited; retry after {}s", self.retry_after_secs)
+    }
+}
+
+fn header_number<T: std::str::FromStr>(headers: &HeaderMap, name: &str) -> Option<T> {
+    headers.get(name)?.to_str().ok()?.trim().parse::<T>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn parses_rate_limit_headers_on_429() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("12"));
+        headers.insert(RATE_LIMIT_LIMIT_HEADER, HeaderValue::from_static("20"));
+        headers.insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from_static("0"));
+
+        let limited = RateLimited::from_response(StatusCode::TOO_MANY_REQUESTS, &headers).unwrap();
+        assert_eq!(limited.retry_after_secs, 12);
+        assert_eq!(limited.limit, Some(20));
+        assert_eq!(limited.remaining, Some(0));
+        assert_eq!(limited.retry_after(), Duration::from_secs(12));
+    }
+
+    #[test]
+    fn falls_back_to_reset_header_then_default() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RATE_LIMIT_RESET_HEADER, HeaderValue::from_static("7"));
+        let limited = RateLimited::from_response(StatusCode::TOO_MANY_REQUESTS, &headers).unwrap();
+        assert_eq!(limited.retry_after_secs, 7);
+
+        let limited =
+            RateLimited::from_response(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new()).unwrap();
+        assert_eq!(limited.retry_after_secs, DEFAULT_RETRY_AFTER_SECS);
+    }
+
+    #[test]
+    fn ignores_non_429_responses() {
+        assert!(RateLimited::from_response(StatusCode::BAD_GATEWAY, &HeaderMap::new()).is_none());
+    }
+}
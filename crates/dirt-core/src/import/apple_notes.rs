@@ -0,0 +1,161 @@
+//! Apple Notes importer.
+//!
+//! Apple Notes has no bulk export of its own; exporter tools write one text,
+//! Markdown, or HTML file per note into a folder per Notes folder, with images
+//! referenced by relative path. Folder names become tags.
+
+use regex::Regex;
+
+use super::archive::{sibling_entry, ImportArchive};
+use super::{
+    extension, guess_mime_type, now_ms, with_labels, with_title, ImportBatch, ImportSkip,
+    ImportedMedia, ImportedNote,
+};
+use crate::error::Result;
+
+const NOTE_EXTENSIONS: [&str; 5] = ["txt", "md", "markdown", "html", "htm"];
+
+pub(super) fn read(archive: &mut ImportArchive, batch: &mut ImportBatch) -> Result<()> {
+    let entries: Vec<String> = archive
+        .entries()?
+        .into_iter()
+        .filter(|entry| !entry.split('/').any(|part| part.starts_with('.')))
+        .filter(|entry| NOTE_EXTENSIONS.contains(&extension(entry).as_str()))
+        .collect();
+
+    for entry in entries {
+        let raw = match archive.read_string(&entry) {
+            Ok(raw) => raw,
+            Err(error) => {
+                batch.skipped.push(ImportSkip {
+                    entry,
+                    reason: error.to_string(),
+                });
+                continue;
+            }
+        };
+        let is_html = matches!(extension(&entry).as_str(), "html" | "htm");
+
+        let mut media = Vec::new();
+        for reference in media_references(&raw, is_html) {
+            let Some(path) = sibling_entry(&entry, &reference) else {
+                continue;
+            };
+            if !archive.contains(&path) {
+                continue;
+            }
+            let filename = path.rsplit('/').next().unwrap_or(&path).to_string();
+            media.push(ImportedMedia {
+                bytes: archive.read(&path)?,
+                mime_type: guess_mime_type(&filename),
+                filename,
+            });
+        }
+
+        let body = if is_html { html_to_text(&raw) } else { raw };
+        let content = with_title(&entry, body.trim());
+        if content.trim().is_empty() && media.is_empty() {
+            batch.skipped.push(ImportSkip {
+                entry,
+                reason: "empty note".to_string(),
+            });
+            continue;
+        }
+
+        let folder = entry.rsplit('/').nth(1);
+        let updated_at = archive.modified_ms(&entry).unwrap_or_else(now_ms);
+        batch.notes.push(ImportedNote {
+            content: with_labels(content, folder),
+            created_at: updated_at,
+            updated_at,
+            media,
+        });
+    }
+    Ok(())
+}
+
+/// Local image paths referenced by `![...](path)` or `<img src="path">`.
+pub(super) fn media_references(raw: &str, is_html: bool) -> Vec<String> {
+    let pattern = if is_html {
+        Regex::new(r#"(?i)<img[^>]*\ssrc\s*=\s*["']([^"']+)["']"#)
+    } else {
+        Regex::new(r"!\[[^\]]*\]\(([^)\s]+)\)")
+    }
+    .expect("Invalid regex");
+
+    let mut references: Vec<String> = Vec::new();
+    for capture in pattern.captures_iter(raw) {
+        let reference = decode_percent_spaces(capture[1].trim());
+        let is_remote = reference.contains("://") || reference.starts_with("data:");
+        if !is_remote && !references.contains(&reference) {
+            references.push(reference);
+        }
+    }
+    references
+}
+
+fn decode_percent_spaces(reference: &str) -> String {
+    reference.replace("%20", " ")
+}
+
+fn html_to_text(html: &str) -> String {
+    let hidden = Regex::new(r"(?is)<(head|style|script)[^>]*>.*?</(head|style|script)>")
+        .expect("Invalid regex");
+    let breaks = Regex::new(r"(?i)<br\s*/?>|</(p|div|li|h[1-6]|tr)>").expect("Invalid regex");
+    let items = Regex::new(r"(?i)<li[^>]*>").expect("Invalid regex");
+    let tags = Regex::new(r"<[^>]+>").expect("Invalid regex");
+    let blank_lines = Regex::new(r"\n{3,}").expect("Invalid regex");
+
+    let text = hidden.replace_all(html, "");
+    let text = breaks.replace_all(&text, "\n");
+    let text = items.replace_all(&text, "- ");
+    let text = tags.replace_all(&text, "");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    let text = text.lines().map(str::trim).collect::<Vec<_>>().join("\n");
+    blank_lines.replace_all(text.trim(), "\n\n").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{read_import, ImportSource};
+
+    #[test]
+    fn reads_apple_notes_folder_with_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let work = dir.path().join("Work Projects");
+        std::fs::create_dir_all(work.join("attachments")).unwrap();
+        std::fs::write(
+            work.join("Launch plan.html"),
+            r#"<html><head><title>x</title></head><body>
+                <div>Launch plan</div><div>Ship &amp; celebrate</div>
+                <ul><li>Docs</li><li>Demo</li></ul>
+                <img src="attachments/chart.png">
+            </body></html>"#,
+        )
+        .unwrap();
+        std::fs::write(work.join("attachments").join("chart.png"), b"png").unwrap();
+        std::fs::write(dir.path().join("Loose idea.txt"), "Try the ferry").unwrap();
+        std::fs::write(dir.path().join(".DS_Store"), "junk").unwrap();
+
+        let batch = read_import(ImportSource::AppleNotes, dir.path()).unwrap();
+
+        assert_eq!(batch.notes.len(), 2);
+        let loose = &batch.notes[0];
+        assert_eq!(loose.content, "Loose idea\nTry the ferry");
+        let plan = &batch.notes[1];
+        assert_eq!(
+            plan.content,
+            "Launch plan\nShip & celebrate\n\n- Docs\n- Demo\n\n#work-projects"
+        );
+        assert_eq!(plan.media.len(), 1);
+        assert_eq!(plan.media[0].filename, "chart.png");
+        assert_eq!(plan.media[0].mime_type, "image/png");
+    }
+}
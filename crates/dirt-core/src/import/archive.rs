@@ -0,0 +1,155 @@
+//! Uniform access to export folders, zip archives, and single files.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use zip::ZipArchive;
+
+use crate::error::{Error, Result};
+
+/// An export opened for reading; entries are `/`-separated relative paths.
+pub(super) enum ImportArchive {
+    Folder(PathBuf),
+    Zip(ZipArchive<File>),
+    File(PathBuf),
+}
+
+impl ImportArchive {
+    pub(super) fn open(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            return Ok(Self::Folder(path.to_path_buf()));
+        }
+        let is_zip = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"));
+        if is_zip {
+            let archive = ZipArchive::new(File::open(path)?).map_err(zip_error)?;
+            return Ok(Self::Zip(archive));
+        }
+        if path.is_file() {
+            return Ok(Self::File(path.to_path_buf()));
+        }
+        Err(Error::InvalidInput(format!(
+            "Import path not found: {}",
+            path.display()
+        )))
+    }
+
+    /// All file entries, sorted.
+    pub(super) fn entries(&self) -> Result<Vec<String>> {
+        let mut entries = match self {
+            Self::Folder(root) => {
+                let mut entries = Vec::new();
+                collect_files(root, root, &mut entries)?;
+                entries
+            }
+            Self::Zip(archive) => archive
+                .file_names()
+                .filter(|name| !name.ends_with('/'))
+                .map(str::to_string)
+                .collect(),
+            Self::File(path) => path
+                .file_name()
+                .map(|name| vec![name.to_string_lossy().into_owned()])
+                .unwrap_or_default(),
+        };
+        entries.sort();
+        Ok(entries)
+    }
+
+    pub(super) fn contains(&self, entry: &str) -> bool {
+        match self {
+            Self::Folder(root) => root.join(entry).is_file(),
+            Self::Zip(archive) => archive.index_for_name(entry).is_some(),
+            Self::File(path) => path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy() == entry),
+        }
+    }
+
+    pub(super) fn read(&mut self, entry: &str) -> Result<Vec<u8>> {
+        match self {
+            Self::Folder(root) => Ok(std::fs::read(root.join(entry))?),
+            Self::Zip(archive) => {
+                let mut file = archive.by_name(entry).map_err(zip_error)?;
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes)?;
+                Ok(bytes)
+            }
+            Self::File(path) => Ok(std::fs::read(path)?),
+        }
+    }
+
+    pub(super) fn read_string(&mut self, entry: &str) -> Result<String> {
+        let bytes = self.read(entry)?;
+        String::from_utf8(bytes)
+            .map_err(|_| Error::InvalidInput(format!("{entry} is not valid UTF-8 text")))
+    }
+
+    /// Last modification time (Unix ms) for folder and single-file exports.
+    pub(super) fn modified_ms(&self, entry: &str) -> Option<i64> {
+        let path = match self {
+            Self::Folder(root) => root.join(entry),
+            Self::File(path) => path.clone(),
+            Self::Zip(_) => return None,
+        };
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        let duration = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+        i64::try_from(duration.as_millis()).ok()
+    }
+}
+
+/// Join a relative reference onto the folder of `entry`, resolving `..`.
+pub(super) fn sibling_entry(entry: &str, reference: &str) -> Option<String> {
+    let mut parts: Vec<&str> = entry.split('/').collect();
+    parts.pop();
+    for part in reference.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn collect_files(root: &Path, dir: &Path, entries: &mut Vec<String>) -> Result<()> {
+    for item in std::fs::read_dir(dir)? {
+        let path = item?.path();
+        if path.is_dir() {
+            collect_files(root, &path, entries)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<String> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            entries.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+fn zip_error(error: zip::result::ZipError) -> Error {
+    Error::InvalidInput(format!("Failed to read zip archive: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sibling_entry_resolves_relative_references() {
+        assert_eq!(
+            sibling_entry("Notes/Work/plan.html", "plan/image.png").as_deref(),
+            Some("Notes/Work/plan/image.png")
+        );
+        assert_eq!(
+            sibling_entry("Notes/Work/plan.md", "../shared/a.png").as_deref(),
+            Some("Notes/shared/a.png")
+        );
+        assert_eq!(sibling_entry("plan.md", "../a.png"), None);
+    }
+}
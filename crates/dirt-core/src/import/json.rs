@@ -0,0 +1,107 @@
+//! Importer for Dirt's own JSON exports.
+//!
+//! `dirt export --format json` writes an array of [`ExportNote`] records; an
+//! incremental export writes one record per `<id>.json` file. Both read back
+//! with their timestamps and tags.
+
+use serde::Deserialize;
+
+use super::archive::ImportArchive;
+use super::{extension, with_labels, ImportBatch, ImportSkip, ImportedNote};
+use crate::error::{Error, Result};
+use crate::export::ExportNote;
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum JsonExport {
+    Notes(Vec<ExportNote>),
+    Note(ExportNote),
+}
+
+pub(super) fn read(archive: &mut ImportArchive, batch: &mut ImportBatch) -> Result<()> {
+    let entries: Vec<String> = archive
+        .entries()?
+        .into_iter()
+        .filter(|entry| !entry.split('/').any(|part| part.starts_with('.')))
+        .filter(|entry| extension(entry) == "json")
+        .collect();
+
+    for entry in entries {
+        let parsed = archive
+            .read_string(&entry)
+            .and_then(|raw| serde_json::from_str::<JsonExport>(&raw).map_err(Error::from));
+        let notes = match parsed {
+            Ok(JsonExport::Notes(notes)) => notes,
+            Ok(JsonExport::Note(note)) => vec![note],
+            Err(error) => {
+                batch.skipped.push(ImportSkip {
+                    entry,
+                    reason: error.to_string(),
+                });
+                continue;
+            }
+        };
+
+        for note in notes {
+            if note.content.trim().is_empty() {
+                batch.skipped.push(ImportSkip {
+                    entry: note.id,
+                    reason: "empty note".to_string(),
+                });
+                continue;
+            }
+            batch.notes.push(ImportedNote {
+                content: with_labels(note.content, note.tags.iter().map(String::as_str)),
+                created_at: note.created_at,
+                updated_at: note.updated_at,
+                media: Vec::new(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{render_json_export, render_note_file, ExportFormat};
+    use crate::import::{read_import, ImportSource};
+    use crate::Note;
+
+    #[test]
+    fn reads_notes_back_from_json_exports() {
+        let mut note = Note::new("Packing list #travel");
+        note.created_at = 1_000;
+        note.updated_at = 2_000;
+        let empty = Note::new("  ");
+        let dir = tempfile::tempdir().unwrap();
+        let export_path = dir.path().join("dirt-export.json");
+        std::fs::write(
+            &export_path,
+            render_json_export(&[note.clone(), empty]).unwrap(),
+        )
+        .unwrap();
+
+        let batch = read_import(ImportSource::Json, &export_path).unwrap();
+        assert_eq!(batch.notes.len(), 1);
+        assert_eq!(batch.notes[0].content, "Packing list #travel");
+        assert_eq!(
+            (batch.notes[0].created_at, batch.notes[0].updated_at),
+            (1_000, 2_000)
+        );
+        assert_eq!(batch.skipped.len(), 1);
+
+        let mirror = dir.path().join("mirror");
+        std::fs::create_dir_all(&mirror).unwrap();
+        std::fs::write(
+            mirror.join(format!("{}.json", note.id)),
+            render_note_file(&note, ExportFormat::Json).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(mirror.join("broken.json"), "{").unwrap();
+
+        let batch = read_import(ImportSource::Json, &mirror).unwrap();
+        assert_eq!(batch.notes.len(), 1);
+        assert_eq!(batch.skipped[0].entry, "broken.json");
+    }
+}
@@ -0,0 +1,241 @@
+//! Google Keep (Google Takeout) importer.
+//!
+//! Takeout writes one JSON file per note into `Takeout/Keep/`, next to the
+//! note's media files.
+
+use serde::Deserialize;
+
+use super::archive::{sibling_entry, ImportArchive};
+use super::{
+    guess_mime_type, now_ms, with_labels, ImportBatch, ImportSkip, ImportedMedia, ImportedNote,
+};
+use crate::error::Result;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeepNote {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    text_content: String,
+    #[serde(default)]
+    list_content: Vec<KeepListItem>,
+    #[serde(default)]
+    labels: Vec<KeepLabel>,
+    #[serde(default)]
+    attachments: Vec<KeepAttachment>,
+    #[serde(default)]
+    annotations: Vec<KeepAnnotation>,
+    #[serde(default)]
+    is_trashed: bool,
+    created_timestamp_usec: Option<i64>,
+    user_edited_timestamp_usec: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeepListItem {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    is_checked: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeepLabel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeepAttachment {
+    file_path: String,
+    mimetype: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeepAnnotation {
+    url: Option<String>,
+}
+
+pub(super) fn read(archive: &mut ImportArchive, batch: &mut ImportBatch) -> Result<()> {
+    let entries: Vec<String> = archive
+        .entries()?
+        .into_iter()
+        .filter(|entry| {
+            std::path::Path::new(entry)
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+        })
+        .collect();
+
+    for entry in entries {
+        let text = archive.read_string(&entry)?;
+        let note: KeepNote = match serde_json::from_str(&text) {
+            Ok(note) => note,
+            Err(_) => {
+                batch.skipped.push(ImportSkip {
+                    entry,
+                    reason: "not a Keep note".to_string(),
+                });
+                continue;
+            }
+        };
+        if note.is_trashed {
+            batch.skipped.push(ImportSkip {
+                entry,
+                reason: "in trash".to_string(),
+            });
+            continue;
+        }
+
+        let mut media = Vec::new();
+        for attachment in &note.attachments {
+            match read_attachment(archive, &entry, attachment)? {
+                Some(item) => media.push(item),
+                None => batch.skipped.push(ImportSkip {
+                    entry: attachment.file_path.clone(),
+                    reason: "attachment file missing from export".to_string(),
+                }),
+            }
+        }
+
+        let content = with_labels(
+            note_content(&note),
+            note.labels.iter().map(|label| label.name.as_str()),
+        );
+        if content.trim().is_empty() && media.is_empty() {
+            batch.skipped.push(ImportSkip {
+                entry,
+                reason: "empty note".to_string(),
+            });
+            continue;
+        }
+
+        let updated_at = note
+            .user_edited_timestamp_usec
+            .map_or_else(now_ms, |usec| usec / 1_000);
+        let created_at = note
+            .created_timestamp_usec
+            .map_or(updated_at, |usec| usec / 1_000);
+        batch.notes.push(ImportedNote {
+            content,
+            created_at,
+            updated_at,
+            media,
+        });
+    }
+    Ok(())
+}
+
+fn note_content(note: &KeepNote) -> String {
+    let mut lines = Vec::new();
+    let title = note.title.trim();
+    if !title.is_empty() {
+        lines.push(title.to_string());
+    }
+    let text = note.text_content.trim_end();
+    if !text.is_empty() {
+        lines.push(text.to_string());
+    }
+    for item in &note.list_content {
+        let mark = if item.is_checked { 'x' } else { ' ' };
+        lines.push(format!("- [{mark}] {}", item.text.trim()));
+    }
+    for url in note
+        .annotations
+        .iter()
+        .filter_map(|item| item.url.as_deref())
+    {
+        lines.push(url.to_string());
+    }
+    lines.join("\n")
+}
+
+/// Takeout sometimes lists `.jpeg` files that were written as `.jpg`.
+fn read_attachment(
+    archive: &mut ImportArchive,
+    entry: &str,
+    attachment: &KeepAttachment,
+) -> Result<Option<ImportedMedia>> {
+    let mut candidates = vec![attachment.file_path.clone()];
+    if let Some(stem) = attachment.file_path.strip_suffix(".jpeg") {
+        candidates.push(format!("{stem}.jpg"));
+    }
+
+    for candidate in candidates {
+        let Some(path) = sibling_entry(entry, &candidate) else {
+            continue;
+        };
+        if !archive.contains(&path) {
+            continue;
+        }
+        let filename = candidate
+            .rsplit('/')
+            .next()
+            .unwrap_or(&candidate)
+            .to_string();
+        let mime_type = attachment
+            .mimetype
+            .clone()
+            .unwrap_or_else(|| guess_mime_type(&filename));
+        return Ok(Some(ImportedMedia {
+            bytes: archive.read(&path)?,
+            filename,
+            mime_type,
+        }));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{read_import, ImportSource};
+
+    #[test]
+    fn reads_keep_takeout_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let keep = dir.path().join("Takeout").join("Keep");
+        std::fs::create_dir_all(&keep).unwrap();
+        std::fs::write(
+            keep.join("Groceries.json"),
+            r#"{
+                "title": "Groceries",
+                "listContent": [
+                    {"text": "milk", "isChecked": false},
+                    {"text": "eggs", "isChecked": true}
+                ],
+                "labels": [{"name": "Home Stuff"}],
+                "attachments": [{"filePath": "photo.jpeg", "mimetype": "image/jpeg"}],
+                "isTrashed": false,
+                "createdTimestampUsec": 1700000000000000,
+                "userEditedTimestampUsec": 1700000100000000
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(keep.join("photo.jpg"), b"jpeg-bytes").unwrap();
+        std::fs::write(
+            keep.join("Old.json"),
+            r#"{"title": "Old", "textContent": "gone", "isTrashed": true}"#,
+        )
+        .unwrap();
+        std::fs::write(keep.join("Groceries.html"), "<html></html>").unwrap();
+
+        let batch = read_import(ImportSource::Keep, dir.path()).unwrap();
+
+        assert_eq!(batch.notes.len(), 1);
+        let note = &batch.notes[0];
+        assert_eq!(
+            note.content,
+            "Groceries\n- [ ] milk\n- [x] eggs\n\n#home-stuff"
+        );
+        assert_eq!(note.created_at, 1_700_000_000_000);
+        assert_eq!(note.updated_at, 1_700_000_100_000);
+        assert_eq!(note.media.len(), 1);
+        assert_eq!(note.media[0].filename, "photo.jpg");
+        assert_eq!(note.media[0].bytes, b"jpeg-bytes");
+        assert_eq!(batch.skipped.len(), 1);
+        assert_eq!(batch.skipped[0].reason, "in trash");
+    }
+}
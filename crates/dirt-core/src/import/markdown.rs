@@ -0,0 +1,393 @@
+//! Markdown importer for Obsidian vaults and Dirt's own Markdown exports.
+//!
+//! Each `.md` file may open with a YAML frontmatter block whose timestamp and
+//! `tags` keys map back onto the note. A Dirt Markdown export holds several
+//! notes in one file, each under a frontmatter block with an `id`. Other
+//! files are titled from their file name and, as for Apple Notes, folder
+//! names become tags. Local images embedded with `![..](path)` or Obsidian's
+//! `![[name]]` are carried along as media.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use regex::Regex;
+
+use super::apple_notes::media_references;
+use super::archive::{sibling_entry, ImportArchive};
+use super::{
+    extension, guess_mime_type, now_ms, with_labels, with_title, ImportBatch, ImportSkip,
+    ImportedMedia, ImportedNote,
+};
+use crate::error::Result;
+
+const NOTE_EXTENSIONS: [&str; 2] = ["md", "markdown"];
+
+/// Note metadata read from a frontmatter block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Frontmatter {
+    /// Id written by a Dirt export
+    id: Option<String>,
+    created_at: Option<i64>,
+    updated_at: Option<i64>,
+    tags: Vec<String>,
+}
+
+pub(super) fn read(archive: &mut ImportArchive, batch: &mut ImportBatch) -> Result<()> {
+    let entries: Vec<String> = archive
+        .entries()?
+        .into_iter()
+        .filter(|entry| !entry.split('/').any(|part| part.starts_with('.')))
+        .collect();
+    let notes: Vec<&String> = entries
+        .iter()
+        .filter(|entry| NOTE_EXTENSIONS.contains(&extension(entry).as_str()))
+        .collect();
+
+    for entry in notes {
+        let raw = match archive.read_string(entry) {
+            Ok(raw) => raw,
+            Err(error) => {
+                batch.skipped.push(ImportSkip {
+                    entry: entry.clone(),
+                    reason: error.to_string(),
+                });
+                continue;
+            }
+        };
+        let modified_at = archive.modified_ms(entry).unwrap_or_else(now_ms);
+        let folder = entry.rsplit('/').nth(1);
+
+        for document in split_documents(&raw) {
+            let (frontmatter, body) = parse_frontmatter(document);
+            let body = if frontmatter.id.is_some() {
+                strip_export_markup(body)
+            } else {
+                body.to_string()
+            };
+
+            let mut media = Vec::new();
+            for path in embedded_media(&body, entry, &entries) {
+                if !archive.contains(&path) {
+                    continue;
+                }
+                let filename = path.rsplit('/').next().unwrap_or(&path).to_string();
+                media.push(ImportedMedia {
+                    bytes: archive.read(&path)?,
+                    mime_type: guess_mime_type(&filename),
+                    filename,
+                });
+            }
+
+            let content = if frontmatter.id.is_some() {
+                body.trim().to_string()
+            } else {
+                with_title(entry, body.trim())
+            };
+            if content.trim().is_empty() && media.is_empty() {
+                batch.skipped.push(ImportSkip {
+                    entry: frontmatter.id.unwrap_or_else(|| entry.clone()),
+                    reason: "empty note".to_string(),
+                });
+                continue;
+            }
+
+            let updated_at = frontmatter
+                .updated_at
+                .or(frontmatter.created_at)
+                .unwrap_or(modified_at);
+            let created_at = frontmatter.created_at.unwrap_or(updated_at);
+            let labels = frontmatter.tags.iter().map(String::as_str).chain(folder);
+            batch.notes.push(ImportedNote {
+                content: with_labels(content, labels),
+                created_at,
+                updated_at,
+                media,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Split a Dirt Markdown export into one document per note; any other file
+/// is a single document.
+fn split_documents(raw: &str) -> Vec<&str> {
+    if !raw.starts_with("---\nid: ") {
+        return vec![raw];
+    }
+    let next_note = Regex::new(r"\n\n---\nid: ").expect("Invalid regex");
+    let mut documents = Vec::new();
+    let mut start = 0;
+    for found in next_note.find_iter(raw) {
+        documents.push(&raw[start..found.start()]);
+        start = found.start() + 2;
+    }
+    documents.push(&raw[start..]);
+    documents
+}
+
+/// Split a leading `---` frontmatter block from the body.
+///
+/// Understands the YAML a note app writes: `key: value` pairs, inline
+/// `[a, b]` lists, and `- item` lists. Without a closed block the whole text
+/// is the body.
+fn parse_frontmatter(document: &str) -> (Frontmatter, &str) {
+    let mut frontmatter = Frontmatter::default();
+    let Some(rest) = document
+        .strip_prefix("---\n")
+        .or_else(|| document.strip_prefix("---\r\n"))
+    else {
+        return (frontmatter, document);
+    };
+
+    let mut block = Vec::new();
+    let mut consumed = 0;
+    let mut body = None;
+    for line in rest.split_inclusive('\n') {
+        consumed += line.len();
+        let line = line.trim_end();
+        if line == "---" {
+            body = Some(&rest[consumed..]);
+            break;
+        }
+        block.push(line);
+    }
+    let Some(body) = body else {
+        return (frontmatter, document);
+    };
+
+    let mut list_key: Option<String> = None;
+    for line in block {
+        if let Some(item) = line.trim_start().strip_prefix("- ") {
+            if let Some(key) = &list_key {
+                frontmatter.apply_list_item(key, unquote(item));
+            }
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            list_key = None;
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+        if value.is_empty() {
+            list_key = Some(key);
+            continue;
+        }
+        list_key = None;
+        let items = value
+            .strip_prefix('[')
+            .and_then(|items| items.strip_suffix(']'))
+            .unwrap_or(value);
+        for item in items.split(',') {
+            frontmatter.apply(&key, unquote(item));
+        }
+    }
+    (frontmatter, body)
+}
+
+impl Frontmatter {
+    /// Apply a `- item` line; list items are whole tags, e.g. `Work Stuff`.
+    fn apply_list_item(&mut self, key: &str, item: &str) {
+        match key {
+            "tags" | "tag" => {
+                let tag = item.trim_start_matches('#');
+                if !tag.is_empty() {
+                    self.tags.push(tag.to_string());
+                }
+            }
+            _ => self.apply(key, item),
+        }
+    }
+
+    /// Apply a scalar value; tag strings may hold several tags.
+    fn apply(&mut self, key: &str, value: &str) {
+        if value.is_empty() {
+            return;
+        }
+        match key {
+            "id" => self.id = Some(value.to_string()),
+            "created_at" | "created" | "date" => {
+                self.created_at = self.created_at.or_else(|| parse_timestamp(value));
+            }
+            "updated_at" | "updated" | "modified" => {
+                self.updated_at = self.updated_at.or_else(|| parse_timestamp(value));
+            }
+            "tags" | "tag" => {
+                for tag in value.split_whitespace() {
+                    let tag = tag.trim_start_matches('#');
+                    if !tag.is_empty() {
+                        self.tags.push(tag.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .or_else(|| {
+            value
+                .strip_prefix('\'')
+                .and_then(|inner| inner.strip_suffix('\''))
+        })
+        .unwrap_or(value)
+}
+
+/// Unix ms as written by Dirt, an RFC 3339 timestamp, or a UTC date with an
+/// optional time.
+fn parse_timestamp(value: &str) -> Option<i64> {
+    if let Ok(timestamp_ms) = value.parse::<i64>() {
+        return Some(timestamp_ms);
+    }
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Some(date_time.timestamp_millis());
+    }
+    for format in [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ] {
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(date_time.and_utc().timestamp_millis());
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date_time| date_time.and_utc().timestamp_millis())
+}
+
+/// Drop the anchors and attachment list a Dirt Markdown export adds around
+/// each note.
+fn strip_export_markup(body: &str) -> String {
+    let anchor = Regex::new(r#"(?m)^<a id="note-[^"]*"></a>\n?"#).expect("Invalid regex");
+    let attachments =
+        Regex::new(r#"\n*Attachments:\n(- <a id="attachment-[^"]*"></a>[^\n]*\n?)+$"#)
+            .expect("Invalid regex");
+    let body = anchor.replace_all(body, "");
+    attachments.replace(&body, "\n").into_owned()
+}
+
+/// Entries of images embedded in `body`, resolved against the note's folder,
+/// or anywhere in the vault for Obsidian's `![[name]]`.
+fn embedded_media(body: &str, entry: &str, entries: &[String]) -> Vec<String> {
+    let mut paths: Vec<String> = media_references(body, false)
+        .into_iter()
+        .filter_map(|reference| sibling_entry(entry, &reference))
+        .collect();
+
+    let wiki_embed = Regex::new(r"!\[\[([^\[\]|\n]+)(?:\|[^\]\n]*)?\]\]").expect("Invalid regex");
+    for capture in wiki_embed.captures_iter(body) {
+        let name = capture[1].trim();
+        let found = entries.iter().find(|candidate| {
+            candidate.as_str() == name
+                || candidate
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|file_name| file_name == name)
+        });
+        if let Some(path) = found {
+            // Embedded notes are links, not media.
+            if !NOTE_EXTENSIONS.contains(&extension(path).as_str()) && !paths.contains(path) {
+                paths.push(path.clone());
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::render_markdown_export;
+    use crate::import::{read_import, ImportSource};
+    use crate::Note;
+
+    #[test]
+    fn parse_frontmatter_reads_dates_and_tag_lists() {
+        let (frontmatter, body) = parse_frontmatter(
+            "---\ncreated: 2024-01-02\nupdated: \"2024-01-03T10:00:00Z\"\n\
+             tags:\n  - Work Stuff\n  - '#travel'\naliases: [Trip]\n---\nBody\n",
+        );
+        assert_eq!(body, "Body\n");
+        assert_eq!(frontmatter.created_at, Some(1_704_153_600_000));
+        assert_eq!(frontmatter.updated_at, Some(1_704_276_000_000));
+        assert_eq!(frontmatter.tags, ["Work Stuff", "travel"]);
+
+        let (inline, _) = parse_frontmatter("---\ntags: [a, \"b\"]\n---\n");
+        assert_eq!(inline.tags, ["a", "b"]);
+
+        let unclosed = "---\nJust a rule";
+        assert_eq!(
+            parse_frontmatter(unclosed),
+            (Frontmatter::default(), unclosed)
+        );
+    }
+
+    #[test]
+    fn reads_obsidian_vault_with_frontmatter_and_embeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let projects = dir.path().join("Projects");
+        std::fs::create_dir_all(&projects).unwrap();
+        std::fs::create_dir_all(dir.path().join("assets")).unwrap();
+        std::fs::create_dir_all(dir.path().join(".obsidian")).unwrap();
+        std::fs::write(
+            projects.join("Garden.md"),
+            "---\ncreated: 2024-03-01 08:30\ntags: [outdoors]\n---\nPlant beans\n![[bed.png]]\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("assets").join("bed.png"), b"png").unwrap();
+        std::fs::write(dir.path().join(".obsidian").join("app.md"), "config").unwrap();
+
+        let batch = read_import(ImportSource::Markdown, dir.path()).unwrap();
+
+        assert_eq!(batch.notes.len(), 1);
+        let garden = &batch.notes[0];
+        assert_eq!(
+            garden.content,
+            "Garden\nPlant beans\n![[bed.png]]\n\n#outdoors #projects"
+        );
+        assert_eq!(garden.created_at, 1_709_281_800_000);
+        assert_eq!(garden.updated_at, garden.created_at);
+        assert_eq!(garden.media.len(), 1);
+        assert_eq!(garden.media[0].filename, "bed.png");
+    }
+
+    #[test]
+    fn reads_notes_back_from_a_dirt_markdown_export() {
+        let mut first = Note::new("Reading list #books\nDune");
+        first.created_at = 1_000;
+        first.updated_at = 2_000;
+        let mut second = Note::new("Standup notes\n\n---\n\nAsk about #release");
+        second.created_at = 3_000;
+        second.updated_at = 4_000;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dirt-export.md");
+        std::fs::write(
+            &path,
+            render_markdown_export(&[first.clone(), second.clone()]),
+        )
+        .unwrap();
+
+        let batch = read_import(ImportSource::Markdown, &path).unwrap();
+
+        assert_eq!(batch.notes.len(), 2);
+        assert_eq!(batch.notes[0].content, first.content);
+        assert_eq!(
+            (batch.notes[0].created_at, batch.notes[0].updated_at),
+            (1_000, 2_000)
+        );
+        assert_eq!(batch.notes[1].content, second.content);
+        assert_eq!(
+            (batch.notes[1].created_at, batch.notes[1].updated_at),
+            (3_000, 4_000)
+        );
+    }
+}
@@ -0,0 +1,461 @@
+//! Importers for notes exported from other apps.
+//!
+//! Each adapter reads an export (a folder, a zip archive, or a single file)
+//! into an [`ImportBatch`] without touching the database, so clients can show
+//! a preview before creating notes. Labels become `#tags` appended to the note
+//! content and media files are carried along for upload as attachments.
+
+mod apple_notes;
+mod archive;
+mod json;
+mod keep;
+mod markdown;
+mod simplenote;
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::media::{media_object_key, MediaApiClient};
+use crate::models::{Note, NoteId, NoteSource};
+use crate::search::attachment_search_text;
+use crate::services::DatabaseService;
+use crate::storage::{prepare_image_upload, ImagePrivacyOptions};
+
+use self::archive::ImportArchive;
+
+/// App an export comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportSource {
+    /// Google Keep via Google Takeout (`Takeout/Keep` folder or zip).
+    Keep,
+    /// Simplenote export zip (or its `notes.json`).
+    Simplenote,
+    /// Apple Notes exported to a folder of text, Markdown, or HTML files.
+    AppleNotes,
+    /// Markdown files with optional YAML frontmatter: an Obsidian vault or a
+    /// Dirt Markdown export.
+    Markdown,
+    /// A Dirt JSON export, as one file or one file per note.
+    Json,
+}
+
+impl ImportSource {
+    /// All supported sources, in display order.
+    pub const ALL: [Self; 5] = [
+        Self::Keep,
+        Self::Simplenote,
+        Self::AppleNotes,
+        Self::Markdown,
+        Self::Json,
+    ];
+
+    /// Identifier used on the command line, e.g. `keep`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Keep => "keep",
+            Self::Simplenote => "simplenote",
+            Self::AppleNotes => "apple-notes",
+            Self::Markdown => "markdown",
+            Self::Json => "json",
+        }
+    }
+
+    /// Human-readable app name.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Keep => "Google Keep",
+            Self::Simplenote => "Simplenote",
+            Self::AppleNotes => "Apple Notes",
+            Self::Markdown => "Markdown / Obsidian",
+            Self::Json => "Dirt JSON",
+        }
+    }
+}
+
+impl fmt::Display for ImportSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ImportSource {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "keep" | "google-keep" => Ok(Self::Keep),
+            "simplenote" => Ok(Self::Simplenote),
+            "apple-notes" | "apple" => Ok(Self::AppleNotes),
+            "markdown" | "md" | "obsidian" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            other => Err(Error::InvalidInput(format!(
+                "Unknown import source '{other}' \
+                 (expected keep, simplenote, apple-notes, markdown, or json)"
+            ))),
+        }
+    }
+}
+
+/// A media file to upload as an attachment of the imported note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedMedia {
+    pub filename: String,
+    pub mime_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// A note read from an export, not yet saved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedNote {
+    /// Note content with mapped tags appended.
+    pub content: String,
+    /// Creation timestamp (Unix ms).
+    pub created_at: i64,
+    /// Last edit timestamp (Unix ms).
+    pub updated_at: i64,
+    pub media: Vec<ImportedMedia>,
+}
+
+impl ImportedNote {
+    /// Build a note that keeps the original timestamps.
+    #[must_use]
+    pub fn to_note(&self) -> Note {
+        Note {
+            id: NoteId::new(),
+            content: self.content.clone(),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            is_deleted: false,
+            is_pinned: false,
+            is_archived: false,
+        }
+    }
+}
+
+/// An entry in the export that was not imported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportSkip {
+    /// Path of the entry within the export.
+    pub entry: String,
+    pub reason: String,
+}
+
+/// Everything read from one export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportBatch {
+    pub source: ImportSource,
+    pub notes: Vec<ImportedNote>,
+    pub skipped: Vec<ImportSkip>,
+}
+
+impl ImportBatch {
+    /// Number of media files across all notes.
+    #[must_use]
+    pub fn media_count(&self) -> usize {
+        self.notes.iter().map(|note| note.media.len()).sum()
+    }
+}
+
+/// Signed-in media API used to upload imported media as attachments.
+#[derive(Clone, Copy)]
+pub struct ImportMediaTarget<'a> {
+    pub client: &'a MediaApiClient,
+    pub access_token: &'a str,
+}
+
+/// Outcome of saving an [`ImportBatch`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub notes_imported: usize,
+    pub media_uploaded: usize,
+    /// Media not uploaded, because no media target was given or upload failed.
+    pub media_skipped: usize,
+    /// Per-file upload errors.
+    pub failures: Vec<String>,
+}
+
+/// Read an export from `path` without importing it.
+pub fn read_import(source: ImportSource, path: &Path) -> Result<ImportBatch> {
+    let mut archive = ImportArchive::open(path)?;
+    let mut batch = ImportBatch {
+        source,
+        notes: Vec::new(),
+        skipped: Vec::new(),
+    };
+    match source {
+        ImportSource::Keep => keep::read(&mut archive, &mut batch)?,
+        ImportSource::Simplenote => simplenote::read(&mut archive, &mut batch)?,
+        ImportSource::AppleNotes => apple_notes::read(&mut archive, &mut batch)?,
+        ImportSource::Markdown => markdown::read(&mut archive, &mut batch)?,
+        ImportSource::Json => json::read(&mut archive, &mut batch)?,
+    }
+    if batch.notes.is_empty() && batch.skipped.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "No {} notes found in {}",
+            source.label(),
+            path.display()
+        )));
+    }
+    Ok(batch)
+}
+
+/// Save the notes of a batch, uploading media when a target is given.
+///
+/// Media failures are recorded in the summary and do not stop the import.
+pub async fn import_batch(
+    db: &DatabaseService,
+    batch: &ImportBatch,
+    media_target: Option<ImportMediaTarget<'_>>,
+) -> Result<ImportSummary> {
+    let mut summary = ImportSummary::default();
+    for imported in &batch.notes {
+        let note = db
+            .create_note_with_id_from(&imported.to_note(), NoteSource::Import)
+            .await?;
+        summary.notes_imported += 1;
+
+        for media in &imported.media {
+            let Some(target) = media_target else {
+                summary.media_skipped += 1;
+                continue;
+            };
+            match upload_media(db, target, &note.id, media).await {
+                Ok(()) => summary.media_uploaded += 1,
+                Err(error) => {
+                    summary.media_skipped += 1;
+                    summary
+                        .failures
+                        .push(format!("{}: {error}", media.filename));
+                }
+            }
+        }
+    }
+    Ok(summary)
+}
+
+async fn upload_media(
+    db: &DatabaseService,
+    target: ImportMediaTarget<'_>,
+    note_id: &NoteId,
+    media: &ImportedMedia,
+) -> std::result::Result<(), String> {
+    let settings = db
+        .load_settings()
+        .await
+        .map_err(|error| error.to_string())?;
+    let bytes = prepare_image_upload(
+        &media.bytes,
+        &media.mime_type,
+        ImagePrivacyOptions::from_settings(&settings),
+    )
+    .map_err(|error| error.to_string())?;
+    let size_bytes = i64::try_from(bytes.len()).unwrap_or(i64::MAX);
+    let search_text = attachment_search_text(&media.mime_type, &bytes);
+    let bytes = db
+        .encrypt_attachment_bytes(bytes)
+        .await
+        .map_err(|error| error.to_string())?;
+    let object_key = media_object_key(note_id, &media.filename);
+    target
+        .client
+        .upload(target.access_token, &object_key, &media.mime_type, &bytes)
+        .await?;
+    let attachment = db
+        .create_attachment(
+            note_id,
+            &media.filename,
+            &media.mime_type,
+            size_bytes,
+            &object_key,
+        )
+        .await
+        .map_err(|error| error.to_string())?;
+    if let Err(error) = db
+        .set_attachment_text(&attachment.id, search_text.as_deref())
+        .await
+    {
+        tracing::warn!("Imported attachment text not indexed: {}", error);
+    }
+
+    // Older backends without a commit endpoint leave the attachment unscanned.
+    match target.client.commit(target.access_token, &object_key).await {
+        Ok(result) => db
+            .set_attachment_scan_status(&attachment.id, result.scan_status)
+            .await
+            .map_err(|error| error.to_string()),
+        Err(error) => {
+            tracing::warn!("Imported attachment left unscanned: {}", error);
+            Ok(())
+        }
+    }
+}
+
+/// Convert an app label or folder name into a Dirt tag name.
+///
+/// `Work Stuff` becomes `work-stuff`; labels without a letter to start the
+/// tag are dropped.
+#[must_use]
+pub fn label_to_tag(label: &str) -> Option<String> {
+    let mut tag = String::with_capacity(label.len());
+    for ch in label.trim().chars().flat_map(char::to_lowercase) {
+        if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
+            tag.push(ch);
+        } else if (ch.is_whitespace() || ch == '/') && !tag.ends_with('-') {
+            tag.push('-');
+        }
+    }
+    let tag = tag
+        .trim_start_matches(|ch: char| !ch.is_ascii_alphabetic())
+        .trim_end_matches('-');
+    (!tag.is_empty()).then(|| tag.to_string())
+}
+
+/// Append `#tags` for labels the content does not already carry.
+fn with_labels<'a>(content: String, labels: impl IntoIterator<Item = &'a str>) -> String {
+    let existing = Note::new(content.as_str()).tags();
+    let mut tags: Vec<String> = Vec::new();
+    for tag in labels.into_iter().filter_map(label_to_tag) {
+        if !existing.contains(&tag) && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    if tags.is_empty() {
+        return content;
+    }
+
+    let tag_line = tags
+        .iter()
+        .map(|tag| format!("#{tag}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let content = content.trim_end();
+    if content.is_empty() {
+        tag_line
+    } else {
+        format!("{content}\n\n{tag_line}")
+    }
+}
+
+/// Use the file name as the first line unless the body already starts with it.
+fn with_title(entry: &str, body: &str) -> String {
+    let file_name = entry.rsplit('/').next().unwrap_or(entry);
+    let title = file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(stem, _)| stem)
+        .trim();
+    let first_line = body.lines().next().unwrap_or("").trim();
+    if title.is_empty() || first_line.eq_ignore_ascii_case(title) {
+        body.to_string()
+    } else if body.is_empty() {
+        title.to_string()
+    } else {
+        format!("{title}\n{body}")
+    }
+}
+
+/// Lowercase extension of an entry, or an empty string.
+fn extension(entry: &str) -> String {
+    entry
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// Guess a MIME type from a file extension, for exports that omit it.
+pub(crate) fn guess_mime_type(filename: &str) -> String {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "wav" => "audio/wav",
+        "3gp" => "audio/3gpp",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "txt" => "text/plain",
+        _ => "application/octet-stream",
+    }
+    .to_string()
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_to_tag_normalizes_labels() {
+        assert_eq!(label_to_tag("Work Stuff"), Some("work-stuff".to_string()));
+        assert_eq!(label_to_tag("Travel/2024"), Some("travel-2024".to_string()));
+        assert_eq!(label_to_tag("2024 plans"), Some("plans".to_string()));
+        assert_eq!(label_to_tag("🎉"), None);
+    }
+
+    #[test]
+    fn with_labels_appends_missing_tags_once() {
+        let content = with_labels("Groceries #food".to_string(), ["Food", "Home", "home"]);
+        assert_eq!(content, "Groceries #food\n\n#home");
+        assert_eq!(with_labels("Plain".to_string(), []), "Plain");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn import_batch_keeps_timestamps_and_skips_media_without_target() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        let batch = ImportBatch {
+            source: ImportSource::Keep,
+            notes: vec![ImportedNote {
+                content: "Imported #keep".to_string(),
+                created_at: 1_000,
+                updated_at: 2_000,
+                media: vec![ImportedMedia {
+                    filename: "photo.jpg".to_string(),
+                    mime_type: "image/jpeg".to_string(),
+                    bytes: vec![1, 2, 3],
+                }],
+            }],
+            skipped: Vec::new(),
+        };
+
+        let summary = import_batch(&db, &batch, None).await.unwrap();
+
+        assert_eq!(summary.notes_imported, 1);
+        assert_eq!(summary.media_skipped, 1);
+        let notes = db.list_notes_by_tag("keep", 10, 0).await.unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].created_at, 1_000);
+        assert_eq!(notes[0].updated_at, 2_000);
+    }
+
+    #[test]
+    fn import_source_parses_cli_names() {
+        assert_eq!("keep".parse::<ImportSource>().unwrap(), ImportSource::Keep);
+        assert_eq!(
+            "Apple-Notes".parse::<ImportSource>().unwrap(),
+            ImportSource::AppleNotes
+        );
+        assert_eq!(
+            "obsidian".parse::<ImportSource>().unwrap(),
+            ImportSource::Markdown
+        );
+        assert!("evernote".parse::<ImportSource>().is_err());
+    }
+}
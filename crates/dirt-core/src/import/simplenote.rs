@@ -0,0 +1,132 @@
+//! Simplenote importer.
+//!
+//! The Simplenote export zip stores every note in `source/notes.json`.
+
+use serde::Deserialize;
+
+use super::archive::ImportArchive;
+use super::{now_ms, with_labels, ImportBatch, ImportSkip, ImportedNote};
+use crate::error::{Error, Result};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimplenoteExport {
+    #[serde(default)]
+    active_notes: Vec<SimplenoteNote>,
+    #[serde(default)]
+    trashed_notes: Vec<SimplenoteNote>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimplenoteNote {
+    id: String,
+    #[serde(default)]
+    content: String,
+    creation_date: Option<String>,
+    last_modified: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+pub(super) fn read(archive: &mut ImportArchive, batch: &mut ImportBatch) -> Result<()> {
+    let entries = archive.entries()?;
+    // A lone JSON file is taken to be `notes.json` under another name.
+    let lone_json = match entries.as_slice() {
+        [entry] if entry.to_ascii_lowercase().ends_with(".json") => Some(entry.clone()),
+        _ => None,
+    };
+    let entry = entries
+        .into_iter()
+        .find(|entry| entry == "notes.json" || entry.ends_with("/notes.json"))
+        .or(lone_json)
+        .ok_or_else(|| Error::InvalidInput("Simplenote export has no notes.json".into()))?;
+    let export: SimplenoteExport = serde_json::from_str(&archive.read_string(&entry)?)?;
+
+    for note in export.trashed_notes {
+        batch.skipped.push(ImportSkip {
+            entry: note.id,
+            reason: "in trash".to_string(),
+        });
+    }
+
+    for note in export.active_notes {
+        if note.content.trim().is_empty() {
+            batch.skipped.push(ImportSkip {
+                entry: note.id,
+                reason: "empty note".to_string(),
+            });
+            continue;
+        }
+        let updated_at = note
+            .last_modified
+            .as_deref()
+            .and_then(parse_timestamp)
+            .unwrap_or_else(now_ms);
+        let created_at = note
+            .creation_date
+            .as_deref()
+            .and_then(parse_timestamp)
+            .unwrap_or(updated_at);
+        batch.notes.push(ImportedNote {
+            content: with_labels(note.content, note.tags.iter().map(String::as_str)),
+            created_at,
+            updated_at,
+            media: Vec::new(),
+        });
+    }
+    Ok(())
+}
+
+fn parse_timestamp(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|date_time| date_time.timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::import::{read_import, ImportSource};
+
+    #[test]
+    fn reads_simplenote_export_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("notes.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        writer
+            .start_file(
+                "source/notes.json",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer
+            .write_all(
+                br#"{
+                    "activeNotes": [{
+                        "id": "a1",
+                        "content": "Trip ideas\nLisbon",
+                        "creationDate": "2024-01-02T03:04:05.000Z",
+                        "lastModified": "2024-01-03T03:04:05.000Z",
+                        "tags": ["travel", "Someday Maybe"]
+                    }],
+                    "trashedNotes": [{"id": "t1", "content": "old"}]
+                }"#,
+            )
+            .unwrap();
+        writer.finish().unwrap();
+
+        let batch = read_import(ImportSource::Simplenote, &zip_path).unwrap();
+
+        assert_eq!(batch.notes.len(), 1);
+        assert_eq!(
+            batch.notes[0].content,
+            "Trip ideas\nLisbon\n\n#travel #someday-maybe"
+        );
+        assert_eq!(batch.notes[0].created_at, 1_704_164_645_000);
+        assert_eq!(batch.notes[0].updated_at, 1_704_251_045_000);
+        assert_eq!(batch.skipped.len(), 1);
+    }
+}
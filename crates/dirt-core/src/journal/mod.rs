@@ -0,0 +1,288 @@
+//! Print-friendly journal compiled from a range of days.
+//!
+//! Stitches every note created between two dates (inclusive, UTC) into one
+//! chronological document with a header per day and tag badges per entry,
+//! rendered as Markdown or as a simple PDF. Inline attachment references
+//! print as `[attachment: <name>]`, since the journal carries no media.
+
+mod pdf;
+
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Days, NaiveDate};
+
+use crate::attachment_refs::attachment_refs_as_labels;
+use crate::error::{Error, Result};
+use crate::Note;
+
+/// Inclusive range of calendar days (UTC) covered by a journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl JournalRange {
+    /// Range from `from` through `to`; `from` must not be after `to`.
+    pub fn new(from: NaiveDate, to: NaiveDate) -> Result<Self> {
+        if from > to {
+            return Err(Error::InvalidInput(format!(
+                "Journal start {from} is after its end {to}"
+            )));
+        }
+        Ok(Self { from, to })
+    }
+
+    /// Parse a range from two `YYYY-MM-DD` dates.
+    pub fn parse(from: &str, to: &str) -> Result<Self> {
+        Self::new(parse_journal_date(from)?, parse_journal_date(to)?)
+    }
+
+    /// The `days` days ending with `today`.
+    #[must_use]
+    pub fn last_days(today: NaiveDate, days: u32) -> Self {
+        let from = today
+            .checked_sub_days(Days::new(u64::from(days.saturating_sub(1))))
+            .unwrap_or(NaiveDate::MIN);
+        Self { from, to: today }
+    }
+
+    /// Whether a day falls inside the range.
+    #[must_use]
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from <= date && date <= self.to
+    }
+
+    /// Human-readable date range, e.g. `2024-06-01 to 2024-06-30`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        format!("{} to {}", self.from, self.to)
+    }
+}
+
+/// One note in the journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub note_id: String,
+    /// Creation time as `HH:MM` (UTC).
+    pub time: String,
+    pub content: String,
+    /// Tags in alphabetical order.
+    pub tags: Vec<String>,
+}
+
+impl JournalEntry {
+    /// Entry header: the time followed by one badge per tag.
+    #[must_use]
+    pub fn badge_line(&self) -> String {
+        let mut line = self.time.clone();
+        for tag in &self.tags {
+            let _ = write!(line, "  [#{tag}]");
+        }
+        line
+    }
+}
+
+/// Notes created on a single day, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalDay {
+    pub date: NaiveDate,
+    pub entries: Vec<JournalEntry>,
+}
+
+impl JournalDay {
+    /// Day header, e.g. `Saturday, June 1, 2024`.
+    #[must_use]
+    pub fn heading(&self) -> String {
+        self.date.format("%A, %B %-d, %Y").to_string()
+    }
+}
+
+/// Notes from a [`JournalRange`], grouped by day in chronological order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Journal {
+    pub range: JournalRange,
+    pub days: Vec<JournalDay>,
+}
+
+impl Journal {
+    /// Document title.
+    #[must_use]
+    pub fn title(&self) -> String {
+        format!("Journal: {}", self.range.label())
+    }
+
+    /// Number of notes across all days.
+    #[must_use]
+    pub fn entry_count(&self) -> usize {
+        self.days.iter().map(|day| day.entries.len()).sum()
+    }
+
+    /// Whether no notes were created in the range.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+
+    /// Render the journal as Markdown with `##` day headers and tag badges.
+    #[must_use]
+    pub fn render_markdown(&self) -> String {
+        let mut output = String::new();
+        let _ = writeln!(output, "# {}", self.title());
+
+        if self.is_empty() {
+            let _ = writeln!(output);
+            let _ = writeln!(output, "_No notes in this range._");
+        }
+
+        for day in &self.days {
+            let _ = writeln!(output);
+            let _ = writeln!(output, "## {}", day.heading());
+            for entry in &day.entries {
+                let _ = writeln!(output);
+                let mut header = format!("### {}", entry.time);
+                for tag in &entry.tags {
+                    let _ = write!(header, " `#{tag}`");
+                }
+                let _ = writeln!(output, "{header}");
+                let _ = writeln!(output);
+                let _ = writeln!(output, "{}", entry.content);
+            }
+        }
+
+        output
+    }
+
+    /// Render the journal as a paginated US Letter PDF.
+    #[must_use]
+    pub fn render_pdf(&self) -> Vec<u8> {
+        pdf::render(self)
+    }
+}
+
+/// Compile non-deleted notes created within `range` into a journal.
+#[must_use]
+pub fn compile_journal(notes: &[Note], range: JournalRange) -> Journal {
+    let mut selected: Vec<(DateTime<chrono::Utc>, &Note)> = notes
+        .iter()
+        .filter(|note| !note.is_deleted)
+        .filter_map(|note| {
+            let created = DateTime::from_timestamp_millis(note.created_at)?;
+            range
+                .contains(created.date_naive())
+                .then_some((created, note))
+        })
+        .collect();
+    selected.sort_by_key(|(created, _)| *created);
+
+    let mut days: Vec<JournalDay> = Vec::new();
+    for (created, note) in selected {
+        let date = created.date_naive();
+        let mut tags = note.tags();
+        tags.sort();
+        let entry = JournalEntry {
+            note_id: note.id.to_string(),
+            time: created.format("%H:%M").to_string(),
+            content: attachment_refs_as_labels(note.content.trim()),
+            tags,
+        };
+        match days.last_mut() {
+            Some(day) if day.date == date => day.entries.push(entry),
+            _ => days.push(JournalDay {
+                date,
+                entries: vec![entry],
+            }),
+        }
+    }
+
+    Journal { range, days }
+}
+
+/// Parse a `YYYY-MM-DD` journal date.
+pub fn parse_journal_date(input: &str) -> Result<NaiveDate> {
+    let input = input.trim();
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .map_err(|_| Error::InvalidInput(format!("Invalid date '{input}' (expected YYYY-MM-DD)")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-06-01T00:00:00Z
+    const JUNE_1_MS: i64 = 1_717_200_000_000;
+    const HOUR_MS: i64 = 3_600_000;
+    const DAY_MS: i64 = 24 * HOUR_MS;
+
+    fn note(content: &str, created_at: i64) -> Note {
+        let mut note = Note::new(content);
+        note.created_at = created_at;
+        note.updated_at = created_at;
+        note
+    }
+
+    fn june() -> JournalRange {
+        JournalRange::parse("2024-06-01", "2024-06-30").unwrap()
+    }
+
+    #[test]
+    fn compile_groups_notes_by_day_in_order() {
+        let mut deleted = note("Gone", JUNE_1_MS + HOUR_MS);
+        deleted.is_deleted = true;
+        let notes = vec![
+            note(
+                "Evening walk #outside !attach[sunset.jpg](abc)",
+                JUNE_1_MS + 20 * HOUR_MS,
+            ),
+            note("Second day", JUNE_1_MS + DAY_MS + 9 * HOUR_MS),
+            note("Morning plan #work", JUNE_1_MS + 8 * HOUR_MS),
+            note("Before the range", JUNE_1_MS - HOUR_MS),
+            note("After the range", JUNE_1_MS + 30 * DAY_MS),
+            deleted,
+        ];
+
+        let journal = compile_journal(&notes, june());
+
+        assert_eq!(journal.entry_count(), 3);
+        assert_eq!(journal.days.len(), 2);
+        assert_eq!(journal.days[0].heading(), "Saturday, June 1, 2024");
+        let first_day: Vec<&str> = journal.days[0]
+            .entries
+            .iter()
+            .map(|entry| entry.content.as_str())
+            .collect();
+        assert_eq!(
+            first_day,
+            vec![
+                "Morning plan #work",
+                "Evening walk #outside [attachment: sunset.jpg]"
+            ]
+        );
+        assert_eq!(journal.days[0].entries[0].time, "08:00");
+        assert_eq!(journal.days[0].entries[0].badge_line(), "08:00  [#work]");
+        assert_eq!(journal.days[1].heading(), "Sunday, June 2, 2024");
+    }
+
+    #[test]
+    fn render_markdown_has_day_headers_and_tag_badges() {
+        let notes = vec![note("Morning plan #work #ideas", JUNE_1_MS + 8 * HOUR_MS)];
+        let markdown = compile_journal(&notes, june()).render_markdown();
+
+        assert!(markdown.starts_with("# Journal: 2024-06-01 to 2024-06-30\n"));
+        assert!(markdown.contains("\n## Saturday, June 1, 2024\n"));
+        assert!(markdown.contains("\n### 08:00 `#ideas` `#work`\n\nMorning plan #work #ideas\n"));
+
+        let empty = compile_journal(&[], june()).render_markdown();
+        assert!(empty.contains("_No notes in this range._"));
+    }
+
+    #[test]
+    fn range_rejects_reversed_and_malformed_dates() {
+        assert!(JournalRange::parse("2024-06-30", "2024-06-01").is_err());
+        assert!(JournalRange::parse("June 1", "2024-06-30").is_err());
+
+        let today = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+        let range = JournalRange::last_days(today, 30);
+        assert_eq!(range.label(), "2024-06-01 to 2024-06-30");
+    }
+}
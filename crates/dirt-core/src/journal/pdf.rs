@@ -0,0 +1,286 @@
+//! Minimal PDF writer for compiled journals.
+//!
+//! Uses the standard Helvetica fonts so no font data has to be embedded.
+//! Text is encoded as WinAnsi; characters outside Latin-1 print as `?`.
+
+use std::fmt::Write as _;
+
+use super::Journal;
+
+const PAGE_WIDTH: u32 = 612;
+const PAGE_HEIGHT: u32 = 792;
+const MARGIN: u32 = 54;
+const BODY_WRAP_COLUMNS: usize = 90;
+
+#[derive(Debug, Clone, Copy)]
+enum Style {
+    Title,
+    Day,
+    Entry,
+    Body,
+    Gap,
+}
+
+impl Style {
+    const fn font(self) -> &'static str {
+        match self {
+            Self::Body | Self::Gap => "F1",
+            Self::Title | Self::Day | Self::Entry => "F2",
+        }
+    }
+
+    const fn size(self) -> u32 {
+        match self {
+            Self::Title => 18,
+            Self::Day => 14,
+            Self::Entry | Self::Body | Self::Gap => 11,
+        }
+    }
+
+    const fn leading(self) -> u32 {
+        match self {
+            Self::Title => 28,
+            Self::Day => 24,
+            Self::Entry => 18,
+            Self::Body => 14,
+            Self::Gap => 8,
+        }
+    }
+}
+
+struct Line {
+    style: Style,
+    text: String,
+}
+
+impl Line {
+    fn new(style: Style, text: impl Into<String>) -> Self {
+        Self {
+            style,
+            text: text.into(),
+        }
+    }
+}
+
+pub(super) fn render(journal: &Journal) -> Vec<u8> {
+    write_document(&paginate(&layout(journal)))
+}
+
+fn layout(journal: &Journal) -> Vec<Line> {
+    let mut lines = vec![
+        Line::new(Style::Title, journal.title()),
+        Line::new(Style::Gap, ""),
+    ];
+    if journal.is_empty() {
+        lines.push(Line::new(Style::Body, "No notes in this range."));
+    }
+
+    for day in &journal.days {
+        lines.push(Line::new(Style::Day, day.heading()));
+        for entry in &day.entries {
+            lines.push(Line::new(Style::Entry, entry.badge_line()));
+            for paragraph in entry.content.lines() {
+                lines.extend(
+                    wrap(paragraph, BODY_WRAP_COLUMNS)
+                        .into_iter()
+                        .map(|text| Line::new(Style::Body, text)),
+                );
+            }
+            lines.push(Line::new(Style::Gap, ""));
+        }
+    }
+
+    lines
+}
+
+/// Lay lines out top to bottom, returning one content stream per page.
+fn paginate(lines: &[Line]) -> Vec<String> {
+    let top = PAGE_HEIGHT - MARGIN;
+    let mut pages = Vec::new();
+    let mut stream = String::new();
+    let mut y = top;
+
+    for line in lines {
+        let leading = line.style.leading();
+        if y < MARGIN + leading {
+            pages.push(std::mem::take(&mut stream));
+            y = top;
+        }
+        y -= leading;
+        if !line.text.is_empty() {
+            let _ = writeln!(
+                stream,
+                "BT /{} {} Tf {MARGIN} {y} Td ({}) Tj ET",
+                line.style.font(),
+                line.style.size(),
+                escape_text(&line.text)
+            );
+        }
+    }
+
+    pages.push(stream);
+    pages
+}
+
+fn write_document(pages: &[String]) -> Vec<u8> {
+    // Objects 1-4 are the catalog, page tree and two fonts; each page then
+    // takes a page object followed by its content stream.
+    let kids = (0..pages.len())
+        .map(|index| format!("{} 0 R", 5 + 2 * index))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut objects = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!("<< /Type /Pages /Kids [{kids}] /Count {} >>", pages.len()).into_bytes(),
+        font_object("Helvetica"),
+        font_object("Helvetica-Bold"),
+    ];
+    for (index, stream) in pages.iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                6 + 2 * index
+            )
+            .into_bytes(),
+        );
+        let content = encode_win_ansi(stream);
+        let mut object = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        object.extend_from_slice(&content);
+        object.extend_from_slice(b"\nendstream");
+        objects.push(object);
+    }
+
+    let mut output = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(output.len());
+        output.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        output.extend_from_slice(object);
+        output.extend_from_slice(b"\nendobj\n");
+    }
+
+    let xref_offset = output.len();
+    let mut trailer = String::new();
+    let _ = writeln!(trailer, "xref");
+    let _ = writeln!(trailer, "0 {}", objects.len() + 1);
+    let _ = writeln!(trailer, "0000000000 65535 f ");
+    for offset in offsets {
+        let _ = writeln!(trailer, "{offset:010} 00000 n ");
+    }
+    let _ = writeln!(trailer, "trailer");
+    let _ = writeln!(trailer, "<< /Size {} /Root 1 0 R >>", objects.len() + 1);
+    let _ = writeln!(trailer, "startxref");
+    let _ = writeln!(trailer, "{xref_offset}");
+    let _ = writeln!(trailer, "%%EOF");
+    output.extend_from_slice(trailer.as_bytes());
+    output
+}
+
+fn font_object(name: &str) -> Vec<u8> {
+    format!("<< /Type /Font /Subtype /Type1 /BaseFont /{name} /Encoding /WinAnsiEncoding >>")
+        .into_bytes()
+}
+
+/// Escape a PDF literal string, replacing characters WinAnsi cannot show.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' | '(' | ')' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\t' => escaped.push(' '),
+            _ if ch.is_control() || u32::from(ch) > 0xFF => escaped.push('?'),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Encode text already limited to Latin-1 by [`escape_text`] as single bytes.
+fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|ch| u8::try_from(u32::from(ch)).unwrap_or(b'?'))
+        .collect()
+}
+
+/// Word-wrap a paragraph to `columns` characters, splitting overlong words.
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut width = 0;
+
+    for word in text.split_whitespace() {
+        let word_width = word.chars().count();
+        if width > 0 && width + 1 + word_width > columns {
+            lines.push(std::mem::take(&mut current));
+            width = 0;
+        }
+        if width > 0 {
+            current.push(' ');
+            width += 1;
+        }
+        current.push_str(word);
+        width += word_width;
+
+        while width > columns {
+            let head: String = current.chars().take(columns).collect();
+            current = current.chars().skip(columns).collect();
+            lines.push(head);
+            width -= columns;
+        }
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::{compile_journal, JournalRange};
+    use crate::Note;
+
+    #[test]
+    fn wrap_breaks_on_words_and_splits_long_words() {
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert_eq!(wrap("", 10), vec![""]);
+    }
+
+    #[test]
+    fn escape_text_handles_delimiters_and_non_latin1() {
+        assert_eq!(escape_text(r"a (b) \c"), r"a \(b\) \\c");
+        assert_eq!(escape_text("café ☕"), "café ?");
+        assert_eq!(encode_win_ansi("é"), vec![0xE9]);
+    }
+
+    #[test]
+    fn render_produces_paginated_pdf_with_valid_xref() {
+        let mut notes = Vec::new();
+        for hour in 0..80 {
+            let mut note = Note::new(format!("Entry {hour} (draft) #log"));
+            // 2024-06-01T00:00:00Z plus `hour` hours
+            note.created_at = 1_717_200_000_000 + hour * 3_600_000;
+            notes.push(note);
+        }
+        let range = JournalRange::parse("2024-06-01", "2024-06-30").unwrap();
+        let pdf = compile_journal(&notes, range).render_pdf();
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("(Saturday, June 1, 2024) Tj"));
+        assert!(text.contains(r"(Entry 0 \(draft\) #log) Tj"));
+        assert!(text.contains("(00:00  [#log]) Tj"));
+        assert!(!text.contains("/Count 1 "));
+
+        let start = text.rfind("startxref\n").unwrap() + "startxref\n".len();
+        let offset: usize = text[start..].lines().next().unwrap().parse().unwrap();
+        assert!(text[offset..].starts_with("xref\n"));
+    }
+}
@@ -4,19 +4,46 @@
 //! and platform-agnostic service clients used by all Dirt interfaces
 //! (desktop, mobile, CLI, TUI).
 
+pub mod attachment_refs;
 pub mod auth;
+pub mod capture;
+pub mod changelog;
 pub mod config;
+pub mod connectivity;
+pub mod crypto;
 pub mod db;
+pub mod device;
+pub mod digest;
+pub mod editing;
 pub mod error;
 pub mod export;
+pub mod graph;
+pub mod http;
+pub mod import;
+pub mod journal;
+pub mod logging;
+pub mod markdown;
 pub mod media;
 pub mod models;
+pub mod notifications;
+pub mod os_search;
+pub mod quota;
+pub mod rules;
+pub mod seal;
 pub mod search;
 pub mod services;
+pub mod share;
 pub mod state;
+pub mod stats;
 pub mod storage;
+pub mod summarize;
 pub mod sync;
+pub mod tags;
+pub mod transcription;
+pub mod transfer;
+pub mod translate;
 pub mod util;
+pub mod workspace;
 
 pub use error::{Error, Result};
 pub use export::ExportNote;
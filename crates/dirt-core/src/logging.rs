@@ -0,0 +1,93 @@
+//! Structured logging conventions shared by Dirt clients and the Dirt API
+//!
+//! In JSON mode every log line is one JSON object, ready for journald or
+//! `CloudWatch`. HTTP requests are logged with the same field names on both
+//! sides, so client and server logs can be joined on `request_id`:
+//!
+//! - `request_id`: value of the `x-request-id` header, sent by the client and
+//!   echoed by the API
+//! - `method`, `path`: HTTP method and URL path
+//! - `status`: HTTP status code
+//! - `latency_ms`: milliseconds until the response headers arrived
+
+use std::fmt;
+use std::str::FromStr;
+
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+
+/// Environment variable selecting the log format when no flag is given.
+pub const LOG_FORMAT_ENV: &str = "DIRT_LOG_FORMAT";
+
+/// Header carrying the id that ties a client request to its server log line.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// How log events are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event
+    Json,
+}
+
+impl LogFormat {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Json => "json",
+        }
+    }
+
+    /// Format from `DIRT_LOG_FORMAT`, falling back to text when it is unset
+    /// or unknown.
+    #[must_use]
+    pub fn from_env() -> Self {
+        std::env::var(LOG_FORMAT_ENV)
+            .ok()
+            .and_then(|raw| raw.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(Error::InvalidInput(format!(
+                "Unknown log format '{other}' (expected text or json)"
+            ))),
+        }
+    }
+}
+
+/// A fresh id for the `x-request-id` header.
+#[must_use]
+pub fn new_request_id() -> String {
+    Uuid::now_v7().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_format_parses_case_insensitively() {
+        assert_eq!(" JSON ".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert_eq!("text".parse::<LogFormat>().unwrap(), LogFormat::Text);
+        assert!("logfmt".parse::<LogFormat>().is_err());
+        assert_eq!(LogFormat::Json.to_string(), "json");
+        assert_ne!(new_request_id(), new_request_id());
+    }
+}
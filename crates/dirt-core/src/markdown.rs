@@ -0,0 +1,250 @@
+//! Markdown rendering for note previews.
+//!
+//! Renders note content to HTML that clients show beside or instead of the
+//! editor. Task list items become checkboxes, and `[[wiki links]]` become
+//! links to the notes they name; clients make those open the note. Raw HTML
+//! in a note is shown as text, so synced content cannot inject markup.
+
+use std::collections::{HashMap, HashSet};
+
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd, TextMergeStream};
+use regex::Regex;
+
+use crate::models::{extract_tasks, Note, NoteId};
+
+/// Resolves `[[wiki link]]` targets to notes, the same way the note graph
+/// does: by note id or, case-insensitively, by a note's first line.
+#[derive(Debug, Clone, Default)]
+pub struct NoteLinkResolver {
+    targets: HashMap<String, NoteId>,
+}
+
+impl NoteLinkResolver {
+    /// Resolver over `notes`; when two notes share a first line, the first
+    /// one listed wins.
+    #[must_use]
+    pub fn new(notes: &[Note]) -> Self {
+        let mut targets = HashMap::new();
+        for note in notes.iter().filter(|note| !note.is_deleted) {
+            let title = note.title_preview(usize::MAX).trim().to_lowercase();
+            if !title.is_empty() {
+                targets.entry(title).or_insert(note.id);
+            }
+            targets.insert(note.id.to_string(), note.id);
+        }
+        Self { targets }
+    }
+
+    /// Note a link target points at, if any.
+    #[must_use]
+    pub fn resolve(&self, target: &str) -> Option<NoteId> {
+        self.targets.get(&target.trim().to_lowercase()).copied()
+    }
+}
+
+/// Render note content as HTML.
+///
+/// Resolved wiki links render as `<a class="note-link" data-note-id="…">`;
+/// unresolved ones as `<span class="note-link note-link-unresolved">`.
+#[must_use]
+pub fn render_note_html(content: &str, links: &NoteLinkResolver) -> String {
+    render_html(content, links, false)
+}
+
+/// Render note content as HTML with clickable task checkboxes.
+///
+/// The checkbox of each line [`extract_tasks`] recognizes renders as
+/// `<input type="checkbox" class="task-checkbox" data-task-line="…">`, with
+/// the zero-based line to pass to
+/// [`toggle_task_line`](crate::models::toggle_task_line).
+#[must_use]
+pub fn render_note_html_with_tasks(content: &str, links: &NoteLinkResolver) -> String {
+    render_html(content, links, true)
+}
+
+fn render_html(content: &str, links: &NoteLinkResolver, interactive_tasks: bool) -> String {
+    let wiki_link = Regex::new(r"\[\[([^\[\]\n]+)\]\]").expect("Invalid regex");
+    let options =
+        Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES;
+    let task_lines: HashSet<usize> = if interactive_tasks {
+        extract_tasks(content)
+            .into_iter()
+            .map(|task| task.line_index)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    // Raw HTML becomes text before merging so links spanning it still match.
+    let parser = Parser::new_ext(content, options)
+        .into_offset_iter()
+        .map(|(event, range)| match event {
+            Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+            Event::TaskListMarker(checked) => {
+                let line_index = content[..range.start].matches('\n').count();
+                if task_lines.contains(&line_index) {
+                    Event::InlineHtml(CowStr::from(task_checkbox(line_index, checked)))
+                } else {
+                    Event::TaskListMarker(checked)
+                }
+            }
+            event => event,
+        });
+
+    let mut events = Vec::new();
+    // Link syntax is literal inside code blocks and existing links.
+    let mut literal_depth = 0usize;
+    for event in TextMergeStream::new(parser) {
+        match event {
+            Event::Start(tag @ (Tag::CodeBlock(_) | Tag::Link { .. })) => {
+                literal_depth += 1;
+                events.push(Event::Start(tag));
+            }
+            Event::End(tag @ (TagEnd::CodeBlock | TagEnd::Link)) => {
+                literal_depth = literal_depth.saturating_sub(1);
+                events.push(Event::End(tag));
+            }
+            Event::Text(text) if literal_depth == 0 => {
+                push_text_with_links(&mut events, &text, &wiki_link, links);
+            }
+            event => events.push(event),
+        }
+    }
+
+    let mut rendered = String::with_capacity(content.len() * 3 / 2);
+    html::push_html(&mut rendered, events.into_iter());
+    rendered
+}
+
+/// Push `text`, turning each `[[target]]` into an inline link element.
+fn push_text_with_links(
+    events: &mut Vec<Event<'_>>,
+    text: &str,
+    wiki_link: &Regex,
+    links: &NoteLinkResolver,
+) {
+    let mut rest_start = 0;
+    for capture in wiki_link.captures_iter(text) {
+        let (Some(whole), Some(target)) = (capture.get(0), capture.get(1)) else {
+            continue;
+        };
+        let label = target.as_str().trim();
+        if label.is_empty() {
+            continue;
+        }
+        if whole.start() > rest_start {
+            events.push(Event::Text(CowStr::from(
+                text[rest_start..whole.start()].to_string(),
+            )));
+        }
+        let element = match links.resolve(label) {
+            Some(note_id) => format!(
+                r##"<a class="note-link" href="#" data-note-id="{note_id}">{}</a>"##,
+                escape_html(label)
+            ),
+            None => format!(
+                r#"<span class="note-link note-link-unresolved">{}</span>"#,
+                escape_html(label)
+            ),
+        };
+        events.push(Event::InlineHtml(CowStr::from(element)));
+        rest_start = whole.end();
+    }
+    if rest_start < text.len() {
+        events.push(Event::Text(CowStr::from(text[rest_start..].to_string())));
+    }
+}
+
+fn task_checkbox(line_index: usize, checked: bool) -> String {
+    let checked = if checked { r#" checked="""# } else { "" };
+    format!(
+        r#"<input type="checkbox" class="task-checkbox" data-task-line="{line_index}"{checked}/>"#
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(content: &str) -> String {
+        render_note_html(content, &NoteLinkResolver::default())
+    }
+
+    #[test]
+    fn renders_markdown_and_task_lists() {
+        let rendered = render("# Plan\n\n- [x] Buy stamps\n- [ ] Call the bank\n\n**bold**");
+        assert!(rendered.contains("<h1>Plan</h1>"));
+        assert!(rendered.contains(r#"<input disabled="" type="checkbox" checked=""/>"#));
+        assert!(rendered.contains(r#"<input disabled="" type="checkbox"/>"#));
+        assert!(rendered.contains("<strong>bold</strong>"));
+    }
+
+    #[test]
+    fn task_checkboxes_carry_their_line() {
+        let rendered = render_note_html_with_tasks(
+            "Plan\n\n- [x] Buy stamps\n- [ ] Call the bank\n\n> - [ ] quoted",
+            &NoteLinkResolver::default(),
+        );
+        assert!(rendered.contains(
+            r#"<input type="checkbox" class="task-checkbox" data-task-line="2" checked=""/>"#
+        ));
+        assert!(rendered
+            .contains(r#"<input type="checkbox" class="task-checkbox" data-task-line="3"/>"#));
+        // Lines the task helpers cannot toggle stay read-only.
+        assert!(rendered.contains(r#"<input disabled="" type="checkbox"/>"#));
+    }
+
+    #[test]
+    fn raw_html_is_escaped() {
+        let rendered = render("<script>alert(1)</script>\n\nHi <b>there</b>");
+        assert!(!rendered.contains("<script>"));
+        assert!(rendered.contains("&lt;script&gt;"));
+        assert!(rendered.contains("&lt;b&gt;there&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn wiki_links_resolve_by_title_and_id() {
+        let plan = Note::new("Project plan\nDetails");
+        let other = Note::new("Other");
+        let links = NoteLinkResolver::new(&[plan.clone(), other.clone()]);
+
+        let rendered = render_note_html(
+            &format!(
+                "See [[ project PLAN ]], [[{}]] and [[Missing <note>]]",
+                other.id
+            ),
+            &links,
+        );
+        assert!(rendered.contains(&format!(
+            r##"<a class="note-link" href="#" data-note-id="{}">project PLAN</a>"##,
+            plan.id
+        )));
+        assert!(rendered.contains(&format!(r#"data-note-id="{}""#, other.id)));
+        assert!(rendered.contains(
+            r#"<span class="note-link note-link-unresolved">Missing &lt;note&gt;</span>"#
+        ));
+    }
+
+    #[test]
+    fn wiki_links_stay_literal_in_code() {
+        let rendered = render("`[[inline]]`\n\n```\n[[block]]\n```");
+        assert!(rendered.contains("<code>[[inline]]</code>"));
+        assert!(rendered.contains("[[block]]"));
+        assert!(!rendered.contains("note-link"));
+    }
+}
@@ -0,0 +1,203 @@
+//! Attachment garbage collection: compare attachment records with the objects
+//! actually stored.
+//!
+//! The Dirt API lists stored objects ([`MediaApiClient::list_objects`]); an
+//! audit then finds records whose object is gone and objects nothing refers
+//! to. Records are repaired by deleting them, and orphaned objects are purged
+//! from storage.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::{MediaApiClient, MediaResult};
+use crate::models::{Attachment, AttachmentScanStatus};
+
+/// Objects younger than this are never reported as orphaned, since another
+/// device may have uploaded them and not synced the attachment record yet.
+pub const ORPHAN_GRACE_SECS: i64 = 86_400;
+
+/// Key prefix of attachment objects in storage
+pub const ATTACHMENT_OBJECT_PREFIX: &str = "notes/";
+
+/// One object in media storage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredObject {
+    pub key: String,
+    pub size_bytes: i64,
+    /// Last write (Unix seconds), when storage reports it
+    #[serde(default)]
+    pub last_modified: Option<i64>,
+}
+
+/// One page of `GET /v1/media/objects`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredObjectPage {
+    pub objects: Vec<StoredObject>,
+    /// Pass back as `cursor` to fetch the next page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// What an attachment audit found
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AttachmentAudit {
+    /// Attachment records compared
+    pub checked_attachments: usize,
+    /// Stored objects compared
+    pub checked_objects: usize,
+    /// Records whose object is missing from storage
+    pub missing: Vec<Attachment>,
+    /// Objects no record refers to
+    pub orphaned: Vec<StoredObject>,
+}
+
+impl AttachmentAudit {
+    /// Whether records and storage agree.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.orphaned.is_empty()
+    }
+
+    /// Storage the orphaned objects take up.
+    #[must_use]
+    pub fn orphaned_bytes(&self) -> i64 {
+        self.orphaned.iter().map(|object| object.size_bytes).sum()
+    }
+}
+
+/// Compare live attachment records with the objects in storage at `now`
+/// (Unix seconds).
+///
+/// Quarantined attachments are expected to be gone from their key. Objects
+/// written within [`ORPHAN_GRACE_SECS`] of `now`, or of unknown age, are left
+/// alone.
+#[must_use]
+pub fn audit_attachments(
+    attachments: &[Attachment],
+    objects: &[StoredObject],
+    now: i64,
+) -> AttachmentAudit {
+    let stored: HashSet<&str> = objects.iter().map(|object| object.key.as_str()).collect();
+    let referenced: HashSet<&str> = attachments
+        .iter()
+        .map(|attachment| attachment.r2_key.as_str())
+        .collect();
+
+    AttachmentAudit {
+        checked_attachments: attachments.len(),
+        checked_objects: objects.len(),
+        missing: attachments
+            .iter()
+            .filter(|attachment| attachment.scan_status != AttachmentScanStatus::Quarantined)
+            .filter(|attachment| !stored.contains(attachment.r2_key.as_str()))
+            .cloned()
+            .collect(),
+        orphaned: objects
+            .iter()
+            .filter(|object| !referenced.contains(object.key.as_str()))
+            .filter(|object| {
+                object
+                    .last_modified
+                    .is_some_and(|modified| now.saturating_sub(modified) >= ORPHAN_GRACE_SECS)
+            })
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Delete the orphaned objects of `audit` from storage; returns how many were
+/// deleted.
+pub async fn purge_orphaned_objects(
+    media_api: &MediaApiClient,
+    access_token: &str,
+    audit: &AttachmentAudit,
+) -> MediaResult<usize> {
+    for object in &audit.orphaned {
+        media_api.delete(access_token, &object.key).await?;
+    }
+    Ok(audit.orphaned.len())
+}
+
+/// Delete the stored files of `attachments`, e.g. those returned when the
+/// trash is emptied; returns how many were deleted.
+pub async fn delete_attachment_objects(
+    media_api: &MediaApiClient,
+    access_token: &str,
+    attachments: &[Attachment],
+) -> MediaResult<usize> {
+    for attachment in attachments {
+        media_api.delete(access_token, &attachment.r2_key).await?;
+    }
+    Ok(attachments.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NoteId;
+
+    fn attachment(key: &str, scan_status: AttachmentScanStatus) -> Attachment {
+        let mut attachment = Attachment::new(NoteId::new(), "a.png", "image/png", 10, key).unwrap();
+        attachment.scan_status = scan_status;
+        attachment
+    }
+
+    fn object(key: &str, last_modified: Option<i64>) -> StoredObject {
+        StoredObject {
+            key: key.to_string(),
+            size_bytes: 10,
+            last_modified,
+        }
+    }
+
+    #[test]
+    fn audit_reports_missing_records_and_old_orphans() {
+        let now = 10 * ORPHAN_GRACE_SECS;
+        let attachments = [
+            attachment("notes/a/kept.png", AttachmentScanStatus::Clean),
+            attachment("notes/b/gone.png", AttachmentScanStatus::Unscanned),
+            attachment("notes/c/flagged.png", AttachmentScanStatus::Quarantined),
+        ];
+        let objects = [
+            object("notes/a/kept.png", Some(0)),
+            object("notes/d/stale.png", Some(now - ORPHAN_GRACE_SECS)),
+            object("notes/e/fresh.png", Some(now - 60)),
+            object("notes/f/undated.png", None),
+        ];
+
+        let audit = audit_attachments(&attachments, &objects, now);
+        assert_eq!(audit.checked_attachments, 3);
+        assert_eq!(audit.checked_objects, 4);
+        assert_eq!(
+            audit
+                .missing
+                .iter()
+                .map(|attachment| attachment.r2_key.as_str())
+                .collect::<Vec<_>>(),
+            ["notes/b/gone.png"]
+        );
+        assert_eq!(
+            audit.orphaned,
+            [object("notes/d/stale.png", Some(now - ORPHAN_GRACE_SECS))]
+        );
+        assert_eq!(audit.orphaned_bytes(), 10);
+        assert!(!audit.is_clean());
+        assert!(audit_attachments(&attachments[..1], &objects[..1], now).is_clean());
+    }
+
+    #[test]
+    fn object_page_omits_missing_cursor() {
+        let page: StoredObjectPage = serde_json::from_str(
+            r#"{"objects":[{"key":"notes/a/b.png","size_bytes":3}],"next_cursor":"abc"}"#,
+        )
+        .unwrap();
+        assert_eq!(page.objects[0].last_modified, None);
+        assert_eq!(page.next_cursor.as_deref(), Some("abc"));
+        let last = StoredObjectPage {
+            objects: Vec::new(),
+            next_cursor: None,
+        };
+        assert_eq!(serde_json::to_string(&last).unwrap(), r#"{"objects":[]}"#);
+    }
+}
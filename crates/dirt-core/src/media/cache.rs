@@ -0,0 +1,359 @@
+//! Local cache of downloaded attachment bytes.
+//!
+//! Previews and saves read attachments through the cache so an attachment
+//! opened twice is only downloaded from storage once. Bytes are stored
+//! content-addressed under a folder next to the database (`objects/<sha256>`),
+//! so attachments with identical bytes share one file, and `index.json` maps
+//! object keys to those files. When the cached bytes exceed the configured
+//! size, the least recently used entries are evicted.
+//!
+//! The cache holds bytes exactly as downloaded, so attachments of encrypted
+//! notes stay encrypted on disk.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{MediaApiClient, MediaResult};
+use crate::error::Result;
+use crate::util::encode_hex;
+use crate::util::fs::write_atomic;
+
+/// Folder next to the database that holds the attachment cache
+pub const ATTACHMENT_CACHE_DIR: &str = "attachment-cache";
+
+/// Cache size used unless settings say otherwise, in MB.
+pub const DEFAULT_ATTACHMENT_CACHE_MB: u32 = 256;
+
+const INDEX_FILE: &str = "index.json";
+const OBJECTS_DIR: &str = "objects";
+
+/// Path of the attachment cache for the database at `db_path`.
+#[must_use]
+pub fn attachment_cache_dir(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(ATTACHMENT_CACHE_DIR)
+}
+
+/// Size of the attachment cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// Cached object keys
+    pub entries: usize,
+    /// Files on disk; smaller than `entries` when attachments share bytes
+    pub files: usize,
+    pub bytes: u64,
+    pub max_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    /// Logical clock bumped on every use, ordering entries for eviction
+    clock: u64,
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    digest: String,
+    size_bytes: u64,
+    content_type: Option<String>,
+    last_used: u64,
+}
+
+impl CacheIndex {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Distinct files referenced by the index and their total size.
+    fn files(&self) -> BTreeMap<&str, u64> {
+        self.entries
+            .values()
+            .map(|entry| (entry.digest.as_str(), entry.size_bytes))
+            .collect()
+    }
+
+    fn bytes(&self) -> u64 {
+        self.files().values().sum()
+    }
+
+    fn is_referenced(&self, digest: &str) -> bool {
+        self.entries.values().any(|entry| entry.digest == digest)
+    }
+}
+
+/// Size-bounded, least-recently-used cache of attachment bytes.
+#[derive(Debug)]
+pub struct AttachmentCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    index: Mutex<CacheIndex>,
+}
+
+impl AttachmentCache {
+    /// Open the cache in `dir`, creating it on first use.
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join(OBJECTS_DIR))?;
+        let index = match fs::read(dir.join(INDEX_FILE)) {
+            Ok(raw) => serde_json::from_slice(&raw).unwrap_or_else(|error| {
+                tracing::warn!("Discarding corrupt attachment cache index: {error}");
+                CacheIndex::default()
+            }),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => CacheIndex::default(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self {
+            dir,
+            max_bytes,
+            index: Mutex::new(index),
+        })
+    }
+
+    /// Open the cache next to the database at `db_path`, holding at most
+    /// `max_mb` megabytes.
+    pub fn beside_database(db_path: &Path, max_mb: u32) -> Result<Self> {
+        Self::open(
+            attachment_cache_dir(db_path),
+            u64::from(max_mb) * 1024 * 1024,
+        )
+    }
+
+    /// Folder the cache lives in.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Cached bytes and content type for `object_key`, marking it as recently
+    /// used.
+    pub fn get(&self, object_key: &str) -> Option<(Vec<u8>, Option<String>)> {
+        let mut index = self.lock();
+        let entry = index.entries.get(object_key)?.clone();
+        let bytes = match fs::read(self.object_path(&entry.digest)) {
+            Ok(bytes) if digest_of(&bytes) == entry.digest => bytes,
+            _ => {
+                tracing::warn!("Dropping unreadable cached attachment {object_key}");
+                index.entries.remove(object_key);
+                self.remove_unreferenced(&index, &entry.digest);
+                self.save_index(&index);
+                return None;
+            }
+        };
+        let last_used = index.tick();
+        if let Some(cached) = index.entries.get_mut(object_key) {
+            cached.last_used = last_used;
+        }
+        self.save_index(&index);
+        Some((bytes, entry.content_type))
+    }
+
+    /// Cache `bytes` for `object_key`, evicting the least recently used
+    /// entries to stay within the size limit.
+    ///
+    /// Attachments larger than the whole cache are not stored.
+    pub fn put(&self, object_key: &str, bytes: &[u8], content_type: Option<&str>) -> Result<()> {
+        let size_bytes = u64::try_from(bytes.len()).unwrap_or(u64::MAX);
+        if size_bytes > self.max_bytes {
+            return Ok(());
+        }
+        let digest = digest_of(bytes);
+        let path = self.object_path(&digest);
+        if !path.exists() {
+            write_atomic(&path, bytes)?;
+        }
+
+        let mut index = self.lock();
+        let last_used = index.tick();
+        let previous = index.entries.insert(
+            object_key.to_string(),
+            CacheEntry {
+                digest,
+                size_bytes,
+                content_type: content_type.map(ToString::to_string),
+                last_used,
+            },
+        );
+        if let Some(previous) = previous {
+            self.remove_unreferenced(&index, &previous.digest);
+        }
+        self.evict(&mut index);
+        self.write_index(&index)
+    }
+
+    /// Drop `object_key` from the cache, e.g. after its attachment was deleted.
+    pub fn remove(&self, object_key: &str) -> Result<()> {
+        let mut index = self.lock();
+        let Some(entry) = index.entries.remove(object_key) else {
+            return Ok(());
+        };
+        self.remove_unreferenced(&index, &entry.digest);
+        self.write_index(&index)
+    }
+
+    /// Drop every cached attachment.
+    pub fn clear(&self) -> Result<()> {
+        let mut index = self.lock();
+        *index = CacheIndex::default();
+        let objects = self.dir.join(OBJECTS_DIR);
+        match fs::remove_dir_all(&objects) {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+        fs::create_dir_all(&objects)?;
+        self.write_index(&index)
+    }
+
+    /// How much the cache holds.
+    pub fn stats(&self) -> CacheStats {
+        let index = self.lock();
+        let files = index.files();
+        CacheStats {
+            entries: index.entries.len(),
+            files: files.len(),
+            bytes: files.values().sum(),
+            max_bytes: self.max_bytes,
+        }
+    }
+
+    fn evict(&self, index: &mut CacheIndex) {
+        while index.bytes() > self.max_bytes {
+            let Some(oldest) = index
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = index.entries.remove(&oldest) {
+                self.remove_unreferenced(index, &entry.digest);
+            }
+        }
+    }
+
+    /// Delete the file for `digest` unless another entry still uses it.
+    fn remove_unreferenced(&self, index: &CacheIndex, digest: &str) {
+        if index.is_referenced(digest) {
+            return;
+        }
+        let path = self.object_path(digest);
+        if let Err(error) = fs::remove_file(&path) {
+            if error.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove {}: {}", path.display(), error);
+            }
+        }
+    }
+
+    fn object_path(&self, digest: &str) -> PathBuf {
+        self.dir.join(OBJECTS_DIR).join(digest)
+    }
+
+    fn write_index(&self, index: &CacheIndex) -> Result<()> {
+        write_atomic(&self.dir.join(INDEX_FILE), serde_json::to_vec(index)?)?;
+        Ok(())
+    }
+
+    /// Like [`Self::write_index`] for reads, where a stale index only costs
+    /// recency.
+    fn save_index(&self, index: &CacheIndex) {
+        if let Err(error) = self.write_index(index) {
+            tracing::warn!("Failed to save attachment cache index: {error}");
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheIndex> {
+        self.index.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn digest_of(bytes: &[u8]) -> String {
+    encode_hex(&Sha256::digest(bytes))
+}
+
+impl MediaApiClient {
+    /// Downloads attachment bytes, reading them from `cache` when it holds
+    /// them and storing fresh downloads in it.
+    pub async fn download_cached(
+        &self,
+        cache: Option<&AttachmentCache>,
+        access_token: &str,
+        object_key: &str,
+    ) -> MediaResult<(Vec<u8>, Option<String>)> {
+        if let Some(cached) = cache.and_then(|cache| cache.get(object_key)) {
+            return Ok(cached);
+        }
+        let (bytes, content_type) = self.download(access_token, object_key).await?;
+        if let Some(cache) = cache {
+            if let Err(error) = cache.put(object_key, &bytes, content_type.as_deref()) {
+                tracing::warn!("Failed to cache attachment {object_key}: {error}");
+            }
+        }
+        Ok((bytes, content_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_entries_over_the_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AttachmentCache::open(dir.path(), 10).unwrap();
+        cache.put("a", b"aaaa", Some("text/plain")).unwrap();
+        cache.put("b", b"bbbb", None).unwrap();
+        assert_eq!(
+            cache.get("a"),
+            Some((b"aaaa".to_vec(), Some("text/plain".to_string())))
+        );
+
+        cache.put("c", b"cccc", None).unwrap();
+        assert_eq!(cache.get("b"), None);
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.stats().bytes, 8);
+
+        cache.put("huge", &[0; 11], None).unwrap();
+        assert_eq!(cache.get("huge"), None);
+        assert_eq!(cache.stats().entries, 2);
+
+        let reopened = AttachmentCache::open(dir.path(), 10).unwrap();
+        assert_eq!(reopened.get("c"), Some((b"cccc".to_vec(), None)));
+    }
+
+    #[test]
+    fn identical_bytes_share_a_file_until_both_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = AttachmentCache::open(dir.path(), 1024).unwrap();
+        cache.put("notes/1/a.png", b"same", None).unwrap();
+        cache.put("notes/2/b.png", b"same", None).unwrap();
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.files, stats.bytes), (2, 1, 4));
+
+        cache.remove("notes/1/a.png").unwrap();
+        assert_eq!(cache.get("notes/1/a.png"), None);
+        assert_eq!(cache.get("notes/2/b.png"), Some((b"same".to_vec(), None)));
+
+        cache.remove("notes/2/b.png").unwrap();
+        assert_eq!(cache.stats().files, 0);
+        assert_eq!(
+            fs::read_dir(dir.path().join(OBJECTS_DIR)).unwrap().count(),
+            0
+        );
+
+        cache.put("notes/3/c.png", b"other", None).unwrap();
+        cache.clear().unwrap();
+        assert_eq!(cache.stats().entries, 0);
+        assert_eq!(cache.get("notes/3/c.png"), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Attachment inbox: capture a file as a new note of its own.
+//!
+//! Receipts, screenshots, and similar files often need no text. Capturing
+//! one creates a note titled from the file name, tagged `#inbox`, with the
+//! file attached.
+
+use std::path::Path;
+
+use super::{media_object_key, MediaApiClient};
+use crate::error::{Error, Result};
+use crate::import::guess_mime_type;
+use crate::models::{Attachment, Note, NoteSource};
+use crate::search::attachment_search_text;
+use crate::services::DatabaseService;
+use crate::storage::{prepare_image_upload, ImagePrivacyOptions};
+
+/// Tag added to notes captured through the attachment inbox.
+pub const ATTACHMENT_INBOX_TAG: &str = "inbox";
+
+const FALLBACK_TITLE: &str = "Attachment";
+
+/// A note created around a captured file.
+#[derive(Debug, Clone)]
+pub struct CapturedAttachment {
+    pub note: Note,
+    pub attachment: Attachment,
+}
+
+/// Note title for `file_name`: the file stem with `_` read as spaces.
+///
+/// ```
+/// use dirt_core::media::attachment_note_title;
+///
+/// assert_eq!(attachment_note_title("grocery_receipt_2024-05.pdf"), "grocery receipt 2024-05");
+/// ```
+#[must_use]
+pub fn attachment_note_title(file_name: &str) -> String {
+    let file_name = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+    let stem = match file_name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => file_name,
+    };
+    let title = stem
+        .split(|ch: char| ch == '_' || ch.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if title.is_empty() {
+        FALLBACK_TITLE.to_string()
+    } else {
+        title
+    }
+}
+
+/// Content of the note created for `file_name`.
+#[must_use]
+pub fn attachment_note_content(file_name: &str) -> String {
+    format!(
+        "{}\n\n#{ATTACHMENT_INBOX_TAG}",
+        attachment_note_title(file_name)
+    )
+}
+
+/// Upload `bytes` and save them as the attachment of a new inbox note.
+///
+/// Images are first processed with the image privacy options in
+/// [`crate::models::Settings`], then encrypted when notes are. The upload
+/// happens before the note is saved, so a failed upload leaves no empty note
+/// behind.
+pub async fn capture_attachment(
+    db: &DatabaseService,
+    client: &MediaApiClient,
+    access_token: &str,
+    file_name: &str,
+    mime_type: &str,
+    bytes: &[u8],
+    source: NoteSource,
+) -> Result<CapturedAttachment> {
+    let options = ImagePrivacyOptions::from_settings(&db.load_settings().await?);
+    let bytes = prepare_image_upload(bytes, mime_type, options)?;
+    let size_bytes = i64::try_from(bytes.len()).unwrap_or(i64::MAX);
+    let search_text = attachment_search_text(mime_type, &bytes);
+    let bytes = db.encrypt_attachment_bytes(bytes).await?;
+    let note = Note::new(attachment_note_content(file_name));
+    let object_key = media_object_key(&note.id, file_name);
+    client
+        .upload(access_token, &object_key, mime_type, &bytes)
+        .await
+        .map_err(|error| Error::Storage(error.to_string()))?;
+
+    let note = db.create_note_with_id_from(&note, source).await?;
+    let mut attachment = db
+        .create_attachment(&note.id, file_name, mime_type, size_bytes, &object_key)
+        .await?;
+    if let Err(error) = db
+        .set_attachment_text(&attachment.id, search_text.as_deref())
+        .await
+    {
+        tracing::warn!("Captured attachment text not indexed: {}", error);
+    }
+
+    // Older backends without a commit endpoint leave the attachment unscanned.
+    match client.commit(access_token, &object_key).await {
+        Ok(result) => {
+            db.set_attachment_scan_status(&attachment.id, result.scan_status)
+                .await?;
+            attachment.scan_status = result.scan_status;
+        }
+        Err(error) => tracing::warn!("Captured attachment left unscanned: {}", error),
+    }
+
+    Ok(CapturedAttachment { note, attachment })
+}
+
+/// Read the file at `path` and capture it with [`capture_attachment`].
+pub async fn capture_attachment_file(
+    db: &DatabaseService,
+    client: &MediaApiClient,
+    access_token: &str,
+    path: &Path,
+    source: NoteSource,
+) -> Result<CapturedAttachment> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| !name.trim().is_empty())
+        .ok_or_else(|| Error::InvalidInput(format!("{} is not a file", path.display())))?;
+    let bytes = std::fs::read(path)?;
+    let mime_type = guess_mime_type(file_name);
+    capture_attachment(
+        db,
+        client,
+        access_token,
+        file_name,
+        &mime_type,
+        &bytes,
+        source,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachment_note_title_uses_file_stem() {
+        assert_eq!(
+            attachment_note_title("Screenshot 2024-05-01.png"),
+            "Screenshot 2024-05-01"
+        );
+        assert_eq!(attachment_note_title("/tmp/scans/tax_form.pdf"), "tax form");
+        assert_eq!(attachment_note_title("___.jpg"), "Attachment");
+        assert_eq!(attachment_note_content("receipt.jpg"), "receipt\n\n#inbox");
+    }
+}
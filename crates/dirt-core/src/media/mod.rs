@@ -3,16 +3,52 @@
 //! Platform-agnostic HTTP client that uses backend-issued presigned URLs
 //! to upload, download, and delete attachments from cloud storage.
 
-use reqwest::Method;
+mod audit;
+mod cache;
+mod inbox;
+mod multipart;
+mod transfer;
+
+pub use audit::{
+    audit_attachments, delete_attachment_objects, purge_orphaned_objects, AttachmentAudit,
+    StoredObject, StoredObjectPage, ATTACHMENT_OBJECT_PREFIX, ORPHAN_GRACE_SECS,
+};
+pub use cache::{
+    attachment_cache_dir, AttachmentCache, CacheStats, ATTACHMENT_CACHE_DIR,
+    DEFAULT_ATTACHMENT_CACHE_MB,
+};
+pub use inbox::{
+    attachment_note_content, attachment_note_title, capture_attachment, capture_attachment_file,
+    CapturedAttachment, ATTACHMENT_INBOX_TAG,
+};
+pub use multipart::{
+    MultipartUpload, MultipartUploadStart, UploadedPart, DEFAULT_UPLOAD_CHUNK_BYTES,
+    MIN_UPLOAD_CHUNK_BYTES,
+};
+pub(crate) use transfer::unless_cancelled;
+pub use transfer::{CancellationToken, DownloadProgress, UploadProgress};
+
+use chrono::NaiveDateTime;
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
+use crate::http::RateLimited;
+use crate::models::{AttachmentScanStatus, MediaUsage, NoteId};
 use crate::util::compact_text;
+use crate::util::fs::sanitize_file_name;
+use crate::util::retry::{Idempotency, RetryPolicy};
+
+/// How long before expiry a signed URL is considered due for a refresh.
+pub const SIGNED_URL_REFRESH_MARGIN_SECS: i64 = 60;
 
 /// HTTP client for managed media operations backed by the Dirt API service.
 #[derive(Debug, Clone)]
 pub struct MediaApiClient {
     base_url: String,
     client: reqwest::Client,
+    upload_chunk_size: usize,
+    retry: RetryPolicy,
 }
 
 impl MediaApiClient {
@@ -22,7 +58,28 @@ impl MediaApiClient {
         let client = reqwest::Client::builder()
             .build()
             .map_err(|error| format!("Failed to construct HTTP client: {error}"))?;
-        Ok(Self { base_url, client })
+        Ok(Self {
+            base_url,
+            client,
+            upload_chunk_size: DEFAULT_UPLOAD_CHUNK_BYTES,
+            retry: RetryPolicy::DEFAULT,
+        })
+    }
+
+    /// Sends uploads larger than `bytes` in chunks of that size; values
+    /// below [`MIN_UPLOAD_CHUNK_BYTES`] are raised to it.
+    #[must_use]
+    pub fn with_upload_chunk_size(mut self, bytes: usize) -> Self {
+        self.upload_chunk_size = bytes.max(MIN_UPLOAD_CHUNK_BYTES);
+        self
+    }
+
+    /// Retries transient API and storage failures with `policy` instead of
+    /// [`RetryPolicy::DEFAULT`].
+    #[must_use]
+    pub const fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
     }
 
     /// Returns the base URL this client was configured with.
@@ -30,47 +87,30 @@ impl MediaApiClient {
         &self.base_url
     }
 
-    /// Uploads attachment bytes using a backend-issued presigned operation.
+    /// Size above which uploads are sent in chunks.
+    #[must_use]
+    pub const fn upload_chunk_size(&self) -> usize {
+        self.upload_chunk_size
+    }
+
+    /// Uploads attachment bytes using backend-issued presigned operations,
+    /// in chunks when they exceed [`Self::upload_chunk_size`].
     pub async fn upload(
         &self,
         access_token: &str,
         object_key: &str,
         content_type: &str,
         bytes: &[u8],
-    ) -> Result<(), String> {
-        let operation = self
-            .request_presigned(
-                access_token,
-                "/v1/media/presign/upload",
-                &serde_json::json!({
-                    "object_key": object_key,
-                    "content_type": content_type,
-                }),
-            )
-            .await?;
-
-        let method = parse_method(&operation.method)?;
-        let mut request = self.client.request(method, &operation.url);
-        for (name, value) in operation.headers {
-            if name.eq_ignore_ascii_case("host") {
-                continue;
-            }
-            request = request.header(name, value);
-        }
-        let response = request
-            .body(bytes.to_vec())
-            .send()
-            .await
-            .map_err(|error| format!("Upload request failed: {error}"))?;
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(format!(
-                "Upload request failed with HTTP {status}: {}",
-                compact_text(&body)
-            ));
-        }
-        Ok(())
+    ) -> MediaResult<()> {
+        self.upload_with_progress(
+            access_token,
+            object_key,
+            content_type,
+            bytes,
+            &CancellationToken::new(),
+            |_| {},
+        )
+        .await
     }
 
     /// Downloads attachment bytes using a backend-issued presigned operation.
@@ -80,131 +120,472 @@ impl MediaApiClient {
         &self,
         access_token: &str,
         object_key: &str,
-    ) -> Result<(Vec<u8>, Option<String>), String> {
-        let encoded_object_key = urlencoding::encode(object_key);
-        let url = format!(
-            "{}/v1/media/presign/download?object_key={}",
-            self.base_url, encoded_object_key
-        );
+    ) -> MediaResult<(Vec<u8>, Option<String>)> {
+        self.download_with_progress(access_token, object_key, &CancellationToken::new(), |_| {})
+            .await
+    }
 
+    /// Deletes an attachment object using a backend-issued presigned operation.
+    pub async fn delete(&self, access_token: &str, object_key: &str) -> MediaResult<()> {
+        self.run_presigned(access_token, &PresignRequest::Delete { object_key }, None)
+            .await?;
+        Ok(())
+    }
+
+    /// Requests a fresh signed download URL for an object.
+    ///
+    /// For previews that hand the URL to a media element and may stay open
+    /// longer than the signature lives: call again once
+    /// [`SignedUrl::needs_refresh`] reports the old URL is about to expire or
+    /// storage rejects it.
+    pub async fn refresh_url(
+        &self,
+        access_token: &str,
+        object_key: &str,
+    ) -> MediaResult<SignedUrl> {
+        let operation = self
+            .presign(access_token, &PresignRequest::Download { object_key })
+            .await?;
+        Ok(SignedUrl::new(operation.url))
+    }
+
+    /// Tells the backend an upload finished so it can validate and scan the
+    /// stored object.
+    ///
+    /// Objects whose size or file signature do not match what was declared,
+    /// and objects the scanner flags, are quarantined server-side and reported
+    /// as [`AttachmentScanStatus::Quarantined`].
+    pub async fn commit(
+        &self,
+        access_token: &str,
+        object_key: &str,
+    ) -> MediaResult<MediaCommitResult> {
+        let url = format!("{}/v1/media/commit", self.base_url);
+        let body = serde_json::json!({ "object_key": object_key });
         let response = self
-            .client
-            .get(url)
-            .bearer_auth(access_token)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|error| format!("Failed to request download URL: {error}"))?;
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(format!(
-                "Download URL request failed with HTTP {status}: {}",
-                compact_text(&body)
-            ));
-        }
-        let payload = response
-            .json::<PresignResponse>()
+            .send_signing_request(Idempotency::Idempotent, || {
+                self.client
+                    .post(&url)
+                    .bearer_auth(access_token)
+                    .header("Accept", "application/json")
+                    .json(&body)
+            })
             .await
-            .map_err(|error| format!("Failed to parse download URL response: {error}"))?;
+            .map_err(|error| error.context("Failed to commit upload"))?;
+        let response = check_status(response, "Upload commit").await?;
+        response.json::<MediaCommitResult>().await.map_err(|error| {
+            MediaError::Request(format!("Failed to parse upload commit response: {error}"))
+        })
+    }
 
-        let operation = payload.operation;
-        let method = parse_method(&operation.method)?;
-        let mut request = self.client.request(method, &operation.url);
-        for (name, value) in operation.headers {
-            if name.eq_ignore_ascii_case("host") {
-                continue;
+    /// Lists every attachment object in storage, following the API's pages.
+    pub async fn list_objects(&self, access_token: &str) -> MediaResult<Vec<StoredObject>> {
+        let mut objects = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut url = format!("{}/v1/media/objects", self.base_url);
+            if let Some(cursor) = &cursor {
+                url.push_str("?cursor=");
+                url.push_str(&urlencoding::encode(cursor));
+            }
+            let response = self
+                .send_signing_request(Idempotency::Idempotent, || {
+                    self.client
+                        .get(&url)
+                        .bearer_auth(access_token)
+                        .header("Accept", "application/json")
+                })
+                .await
+                .map_err(|error| error.context("Failed to list stored objects"))?;
+            let response = check_status(response, "Object listing").await?;
+            let page = response.json::<StoredObjectPage>().await.map_err(|error| {
+                MediaError::Request(format!("Failed to parse object listing: {error}"))
+            })?;
+            objects.extend(page.objects);
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(objects),
             }
-            request = request.header(name, value);
         }
-        let response = request
-            .send()
+    }
+
+    /// Reports how much attachment storage the signed-in user has used and
+    /// their quota, for settings screens.
+    pub async fn usage(&self, access_token: &str) -> MediaResult<MediaUsage> {
+        let url = format!("{}/v1/media/usage", self.base_url);
+        let response = self
+            .send_signing_request(Idempotency::Idempotent, || {
+                self.client
+                    .get(&url)
+                    .bearer_auth(access_token)
+                    .header("Accept", "application/json")
+            })
             .await
-            .map_err(|error| format!("Download request failed: {error}"))?;
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(format!(
-                "Download request failed with HTTP {status}: {}",
-                compact_text(&body)
-            ));
-        }
-        let content_type = response
-            .headers()
-            .get(reqwest::header::CONTENT_TYPE)
-            .and_then(|value| value.to_str().ok())
-            .map(ToString::to_string);
-        let bytes = response
-            .bytes()
+            .map_err(|error| error.context("Failed to fetch storage usage"))?;
+        let response = check_status(response, "Storage usage request").await?;
+        response
+            .json::<MediaUsage>()
             .await
-            .map_err(|error| format!("Failed to read attachment bytes: {error}"))?;
-        Ok((bytes.to_vec(), content_type))
+            .map_err(|error| MediaError::Request(format!("Failed to parse storage usage: {error}")))
     }
 
-    /// Deletes an attachment object using a backend-issued presigned operation.
-    pub async fn delete(&self, access_token: &str, object_key: &str) -> Result<(), String> {
-        let operation = self
-            .request_presigned(
-                access_token,
-                "/v1/media/presign/delete",
-                &serde_json::json!({
-                    "object_key": object_key,
-                }),
-            )
-            .await?;
+    /// Signs an operation and sends it to storage.
+    ///
+    /// A signature that expired before storage saw the request (slow uploads,
+    /// clock skew) is re-requested once; a second expiry is returned as
+    /// [`MediaError::LinkExpired`].
+    async fn run_presigned(
+        &self,
+        access_token: &str,
+        request: &PresignRequest<'_>,
+        body: Option<&[u8]>,
+    ) -> MediaResult<Response> {
+        let label = request.label();
+        let mut resigned = false;
+        loop {
+            let operation = self.presign(access_token, request).await?;
+            let signed_url = SignedUrl::new(operation.url.clone());
 
-        let method = parse_method(&operation.method)?;
-        let mut request = self.client.request(method, &operation.url);
-        for (name, value) in operation.headers {
-            if name.eq_ignore_ascii_case("host") {
+            let method = parse_method(&operation.method)?;
+            let mut storage_request = self.client.request(method, &operation.url);
+            for (name, value) in operation.headers {
+                if name.eq_ignore_ascii_case("host") {
+                    continue;
+                }
+                storage_request = storage_request.header(name, value);
+            }
+            if let Some(bytes) = body {
+                storage_request = storage_request.body(bytes.to_vec());
+            }
+            // Presigned storage operations are PUTs, GETs, and DELETEs of one
+            // object, so repeating them is safe.
+            let response = self
+                .retry
+                .send(storage_request, Idempotency::Idempotent)
+                .await
+                .map_err(|error| MediaError::Network(format!("{label} request failed: {error}")))?;
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+
+            let response_body = response.text().await.unwrap_or_default();
+            if is_expired_signature(status, &response_body, &signed_url) {
+                if resigned {
+                    return Err(MediaError::LinkExpired(label));
+                }
+                resigned = true;
+                tracing::warn!("{label} link expired; requesting a fresh signature");
                 continue;
             }
-            request = request.header(name, value);
-        }
-        let response = request
-            .send()
-            .await
-            .map_err(|error| format!("Delete request failed: {error}"))?;
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(format!(
-                "Delete request failed with HTTP {status}: {}",
-                compact_text(&body)
+            return Err(MediaError::from_status(
+                status,
+                format!(
+                    "{label} request failed with HTTP {}: {}",
+                    status.as_u16(),
+                    compact_text(&response_body)
+                ),
             ));
         }
-        Ok(())
     }
 
-    async fn request_presigned(
+    /// Asks the Dirt API to sign a storage operation.
+    async fn presign(
         &self,
         access_token: &str,
-        route: &str,
-        body: &serde_json::Value,
-    ) -> Result<PresignedOperation, String> {
+        request: &PresignRequest<'_>,
+    ) -> MediaResult<PresignedOperation> {
+        let response = match request {
+            PresignRequest::Download { object_key } => {
+                let url = format!(
+                    "{}/v1/media/presign/download?object_key={}",
+                    self.base_url,
+                    urlencoding::encode(object_key)
+                );
+                self.send_signing_request(Idempotency::Idempotent, || {
+                    self.client
+                        .get(&url)
+                        .bearer_auth(access_token)
+                        .header("Accept", "application/json")
+                })
+                .await
+            }
+            PresignRequest::Upload {
+                object_key,
+                content_type,
+                size_bytes,
+            } => {
+                let url = format!("{}/v1/media/presign/upload", self.base_url);
+                let body = serde_json::json!({
+                    "object_key": object_key,
+                    "content_type": content_type,
+                    "size_bytes": size_bytes,
+                });
+                self.send_signing_request(Idempotency::Idempotent, || {
+                    self.client
+                        .post(&url)
+                        .bearer_auth(access_token)
+                        .header("Accept", "application/json")
+                        .json(&body)
+                })
+                .await
+            }
+            PresignRequest::UploadPart {
+                object_key,
+                upload_id,
+                part_number,
+            } => {
+                let url = format!("{}/v1/media/multipart/part", self.base_url);
+                let body = serde_json::json!({
+                    "object_key": object_key,
+                    "upload_id": upload_id,
+                    "part_number": part_number,
+                });
+                self.send_signing_request(Idempotency::Idempotent, || {
+                    self.client
+                        .post(&url)
+                        .bearer_auth(access_token)
+                        .header("Accept", "application/json")
+                        .json(&body)
+                })
+                .await
+            }
+            PresignRequest::Delete { object_key } => {
+                let url = format!("{}/v1/media/presign/delete", self.base_url);
+                let body = serde_json::json!({ "object_key": object_key });
+                self.send_signing_request(Idempotency::Idempotent, || {
+                    self.client
+                        .post(&url)
+                        .bearer_auth(access_token)
+                        .header("Accept", "application/json")
+                        .json(&body)
+                })
+                .await
+            }
+        }
+        .map_err(|error| error.context("Failed to request signed URL"))?;
+
+        let response = check_status(response, "Signed URL request").await?;
+        let payload = response.json::<PresignResponse>().await.map_err(|error| {
+            MediaError::Request(format!("Failed to parse signed URL response: {error}"))
+        })?;
+        Ok(payload.operation)
+    }
+
+    /// Sends a Dirt API request, retrying transient failures and short
+    /// rate-limit backoffs with the client's [`RetryPolicy`].
+    ///
+    /// A rate limit still in effect once the policy gives up is returned as
+    /// [`MediaError::Server`].
+    async fn send_signing_request(
+        &self,
+        idempotency: Idempotency,
+        build_request: impl Fn() -> RequestBuilder,
+    ) -> MediaResult<Response> {
         let response = self
-            .client
-            .post(format!("{}{}", self.base_url, route))
-            .bearer_auth(access_token)
-            .header("Accept", "application/json")
-            .json(body)
-            .send()
+            .retry
+            .send(build_request(), idempotency)
             .await
-            .map_err(|error| format!("Failed to request signed URL: {error}"))?;
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(format!(
-                "Signed URL request failed with HTTP {status}: {}",
-                compact_text(&body)
-            ));
+            .map_err(|error| MediaError::Network(error.to_string()))?;
+        if let Some(limited) = RateLimited::from_response(response.status(), response.headers()) {
+            return Err(MediaError::Server(format!("Media API {limited}")));
         }
-        let payload = response
-            .json::<PresignResponse>()
-            .await
-            .map_err(|error| format!("Failed to parse signed URL response: {error}"))?;
-        Ok(payload.operation)
+        Ok(response)
+    }
+}
+
+/// Errors from presigned media operations.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MediaError {
+    /// Storage rejected the presigned URL because its signature had expired,
+    /// even after signing it again.
+    #[error("{0} link expired before storage accepted it")]
+    LinkExpired(&'static str),
+    /// A chunked upload stopped part way because of `cause`; `resume_token`
+    /// continues it with [`MediaApiClient::resume_upload`].
+    #[error("{cause}")]
+    UploadInterrupted {
+        cause: Box<MediaError>,
+        resume_token: String,
+    },
+    /// The transfer's cancellation token fired.
+    #[error("Transfer cancelled")]
+    Cancelled,
+    /// The API rejected the access token (HTTP 401); refresh the session
+    /// and retry.
+    #[error("{0}")]
+    Unauthorized(String),
+    /// The object or endpoint does not exist (HTTP 404).
+    #[error("{0}")]
+    NotFound(String),
+    /// The upload would exceed the account's storage quota (HTTP 413).
+    #[error("{0}")]
+    Quota(String),
+    /// The API or storage could not be reached, or the connection dropped.
+    #[error("{0}")]
+    Network(String),
+    /// The API or storage failed (HTTP 5xx) or kept rate-limiting requests.
+    #[error("{0}")]
+    Server(String),
+    /// Any other rejected request or unreadable response.
+    #[error("{0}")]
+    Request(String),
+}
+
+impl MediaError {
+    /// Classifies a failed HTTP response by its status.
+    #[must_use]
+    pub fn from_status(status: StatusCode, message: String) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized(message),
+            StatusCode::NOT_FOUND => Self::NotFound(message),
+            StatusCode::PAYLOAD_TOO_LARGE | StatusCode::INSUFFICIENT_STORAGE => {
+                Self::Quota(message)
+            }
+            StatusCode::TOO_MANY_REQUESTS => Self::Server(message),
+            status if status.is_server_error() => Self::Server(message),
+            _ => Self::Request(message),
+        }
+    }
+
+    /// The failure behind an interrupted upload, or the error itself.
+    #[must_use]
+    pub fn cause(&self) -> &Self {
+        match self {
+            Self::UploadInterrupted { cause, .. } => cause.cause(),
+            other => other,
+        }
+    }
+
+    /// Whether the API rejected the access token.
+    #[must_use]
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self.cause(), Self::Unauthorized(_))
+    }
+
+    /// Whether the account's storage quota refused the upload.
+    #[must_use]
+    pub fn is_quota_exceeded(&self) -> bool {
+        matches!(self.cause(), Self::Quota(_))
     }
+
+    /// Whether retrying the same request later may succeed: network drops,
+    /// server errors, rate limits, and expired links.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self.cause(),
+            Self::Network(_) | Self::Server(_) | Self::LinkExpired(_)
+        )
+    }
+
+    /// Prefixes the message with `context`, keeping the kind of failure.
+    fn context(self, context: &str) -> Self {
+        match self {
+            Self::Unauthorized(message) => Self::Unauthorized(format!("{context}: {message}")),
+            Self::NotFound(message) => Self::NotFound(format!("{context}: {message}")),
+            Self::Quota(message) => Self::Quota(format!("{context}: {message}")),
+            Self::Network(message) => Self::Network(format!("{context}: {message}")),
+            Self::Server(message) => Self::Server(format!("{context}: {message}")),
+            Self::Request(message) => Self::Request(format!("{context}: {message}")),
+            other => other,
+        }
+    }
+
+    /// Whether the failure was an expired presigned URL.
+    #[must_use]
+    pub const fn is_link_expired(&self) -> bool {
+        matches!(self, Self::LinkExpired(_))
+    }
+
+    /// Whether the transfer was cancelled rather than failing.
+    #[must_use]
+    pub const fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled)
+    }
+
+    /// Token that resumes an interrupted chunked upload.
+    #[must_use]
+    pub fn resume_token(&self) -> Option<&str> {
+        match self {
+            Self::UploadInterrupted { resume_token, .. } => Some(resume_token),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for MediaError {
+    fn from(message: String) -> Self {
+        Self::Request(message)
+    }
+}
+
+impl From<MediaError> for String {
+    fn from(error: MediaError) -> Self {
+        error.to_string()
+    }
+}
+
+pub type MediaResult<T> = Result<T, MediaError>;
+
+/// Passes a successful Dirt API response through, or classifies a failed one
+/// as "`{label}` failed with HTTP ...".
+pub(crate) async fn check_status(response: Response, label: &str) -> MediaResult<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(MediaError::from_status(
+        status,
+        format!(
+            "{label} failed with HTTP {}: {}",
+            status.as_u16(),
+            compact_text(&body)
+        ),
+    ))
+}
+
+/// A presigned download URL and when its signature stops working.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedUrl {
+    pub url: String,
+    /// Expiry read from the URL's signing parameters (Unix seconds), when present.
+    pub expires_at: Option<i64>,
+}
+
+impl SignedUrl {
+    /// Wrap a presigned URL, reading its expiry from the query string.
+    #[must_use]
+    pub fn new(url: String) -> Self {
+        let expires_at = signed_url_expiry(&url);
+        Self { url, expires_at }
+    }
+
+    /// Whether the signature has expired at `now` (Unix seconds).
+    #[must_use]
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Whether the URL expires within [`SIGNED_URL_REFRESH_MARGIN_SECS`] of
+    /// `now`, or has an unknown expiry and should be treated as stale.
+    #[must_use]
+    pub fn needs_refresh(&self, now: i64) -> bool {
+        self.expires_at.map_or(true, |expires_at| {
+            now + SIGNED_URL_REFRESH_MARGIN_SECS >= expires_at
+        })
+    }
+}
+
+/// Backend verdict for a committed upload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaCommitResult {
+    /// Scan outcome for the object.
+    pub scan_status: AttachmentScanStatus,
+    /// Scanner-provided reason when the object was flagged.
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -219,7 +600,88 @@ struct PresignedOperation {
     headers: Vec<(String, String)>,
 }
 
-fn normalize_base_url(raw: &str) -> Result<String, String> {
+/// Storage operations the Dirt API can sign.
+enum PresignRequest<'a> {
+    Upload {
+        object_key: &'a str,
+        content_type: &'a str,
+        size_bytes: usize,
+    },
+    UploadPart {
+        object_key: &'a str,
+        upload_id: &'a str,
+        part_number: i32,
+    },
+    Download {
+        object_key: &'a str,
+    },
+    Delete {
+        object_key: &'a str,
+    },
+}
+
+impl PresignRequest<'_> {
+    const fn label(&self) -> &'static str {
+        match self {
+            Self::Upload { .. } => "Upload",
+            Self::UploadPart { .. } => "Upload part",
+            Self::Download { .. } => "Download",
+            Self::Delete { .. } => "Delete",
+        }
+    }
+}
+
+/// Expiry (Unix seconds) encoded in a presigned URL.
+///
+/// Understands SigV4-style `X-Amz-Date` + `X-Amz-Expires` (S3, R2) and
+/// `X-Goog-Date` + `X-Goog-Expires` (GCS), plus a plain `Expires` timestamp.
+fn signed_url_expiry(url: &str) -> Option<i64> {
+    let (_, query) = url.split_once('?')?;
+    let mut signed_at = None;
+    let mut lifetime = None;
+    let mut expires = None;
+    for pair in query.split('&') {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key.to_ascii_lowercase().as_str() {
+            "x-amz-date" | "x-goog-date" => {
+                signed_at = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+                    .ok()
+                    .map(|date_time| date_time.and_utc().timestamp());
+            }
+            "x-amz-expires" | "x-goog-expires" => lifetime = value.parse::<i64>().ok(),
+            "expires" => expires = value.parse::<i64>().ok(),
+            _ => {}
+        }
+    }
+    match (signed_at, lifetime) {
+        (Some(signed_at), Some(lifetime)) => Some(signed_at + lifetime),
+        _ => expires,
+    }
+}
+
+/// Whether storage rejected a request because its signature expired.
+///
+/// S3-compatible stores answer `403` with an `AccessDenied` body mentioning
+/// the expiry; the URL's own expiry covers stores with terser bodies.
+fn is_expired_signature(status: StatusCode, body: &str, signed_url: &SignedUrl) -> bool {
+    if status != StatusCode::FORBIDDEN {
+        return false;
+    }
+    body.to_ascii_lowercase().contains("expired")
+        || signed_url.is_expired(chrono::Utc::now().timestamp())
+}
+
+/// Build an object key for a new attachment upload.
+///
+/// Keys look like `notes/<note-id>/<unix-ms>-<sanitized-name>`.
+#[must_use]
+pub fn media_object_key(note_id: &NoteId, file_name: &str) -> String {
+    let safe_name = sanitize_file_name(file_name);
+    let now = chrono::Utc::now().timestamp_millis();
+    format!("{ATTACHMENT_OBJECT_PREFIX}{note_id}/{now}-{safe_name}")
+}
+
+pub(crate) fn normalize_base_url(raw: &str) -> Result<String, String> {
     let base = raw.trim().trim_end_matches('/').to_string();
     if base.is_empty() {
         return Err("API base URL must not be empty".to_string());
@@ -244,6 +706,102 @@ mod tests {
         assert!(normalize_base_url("example.com").is_err());
     }
 
+    #[test]
+    fn commit_result_parses_scan_status() {
+        let result: MediaCommitResult =
+            serde_json::from_str(r#"{"scan_status":"quarantined","reason":"EICAR"}"#).unwrap();
+        assert_eq!(result.scan_status, AttachmentScanStatus::Quarantined);
+        assert_eq!(result.reason.as_deref(), Some("EICAR"));
+    }
+
+    #[test]
+    fn media_object_key_sanitizes_file_name() {
+        let note_id = NoteId::new();
+        let key = media_object_key(&note_id, "My File (Final).PNG");
+        assert!(key.starts_with(&format!("notes/{note_id}/")));
+        assert!(key.ends_with("-my-file-final.png"));
+        assert!(media_object_key(&note_id, "...").ends_with("-file"));
+    }
+
+    #[test]
+    fn signed_url_reads_sigv4_and_plain_expiry() {
+        let sigv4 = SignedUrl::new(
+            "https://r2.example.com/notes/a.png?X-Amz-Algorithm=AWS4-HMAC-SHA256\
+             &X-Amz-Date=20240101T000000Z&X-Amz-Expires=900&X-Amz-Signature=abc"
+                .to_string(),
+        );
+        assert_eq!(sigv4.expires_at, Some(1_704_067_200 + 900));
+        assert!(!sigv4.is_expired(1_704_067_200 + 899));
+        assert!(sigv4.is_expired(1_704_067_200 + 900));
+        assert!(!sigv4.needs_refresh(1_704_067_200));
+        assert!(sigv4.needs_refresh(1_704_067_200 + 900 - SIGNED_URL_REFRESH_MARGIN_SECS));
+
+        let plain = SignedUrl::new("https://cdn.example.com/a.png?Expires=1700000000".to_string());
+        assert_eq!(plain.expires_at, Some(1_700_000_000));
+
+        let unsigned = SignedUrl::new("https://cdn.example.com/a.png".to_string());
+        assert_eq!(unsigned.expires_at, None);
+        assert!(!unsigned.is_expired(i64::MAX));
+        assert!(unsigned.needs_refresh(0));
+    }
+
+    #[test]
+    fn expired_signature_needs_forbidden_status_and_expiry_evidence() {
+        let fresh = SignedUrl::new("https://r2.example.com/a.png".to_string());
+        let expired = SignedUrl::new("https://r2.example.com/a.png?Expires=1".to_string());
+        let s3_body =
+            "<Error><Code>AccessDenied</Code><Message>Request has expired</Message></Error>";
+
+        assert!(is_expired_signature(StatusCode::FORBIDDEN, s3_body, &fresh));
+        assert!(is_expired_signature(StatusCode::FORBIDDEN, "", &expired));
+        assert!(!is_expired_signature(
+            StatusCode::FORBIDDEN,
+            "AccessDenied",
+            &fresh
+        ));
+        assert!(!is_expired_signature(
+            StatusCode::NOT_FOUND,
+            s3_body,
+            &expired
+        ));
+
+        let error = MediaError::LinkExpired("Download");
+        assert!(error.is_link_expired());
+        assert_eq!(
+            String::from(error),
+            "Download link expired before storage accepted it"
+        );
+    }
+
+    #[test]
+    fn media_error_classifies_statuses_through_interruptions() {
+        let error = |status| MediaError::from_status(status, "failed".to_string());
+        assert!(error(StatusCode::UNAUTHORIZED).is_unauthorized());
+        assert!(matches!(
+            error(StatusCode::NOT_FOUND),
+            MediaError::NotFound(_)
+        ));
+        assert!(error(StatusCode::PAYLOAD_TOO_LARGE).is_quota_exceeded());
+        assert!(error(StatusCode::BAD_GATEWAY).is_transient());
+        assert!(matches!(
+            error(StatusCode::FORBIDDEN),
+            MediaError::Request(_)
+        ));
+        assert!(!error(StatusCode::BAD_REQUEST).is_transient());
+
+        let interrupted = MediaError::UploadInterrupted {
+            cause: Box::new(MediaError::Network("connection reset".to_string())),
+            resume_token: "token".to_string(),
+        };
+        assert!(interrupted.is_transient());
+        assert_eq!(interrupted.resume_token(), Some("token"));
+        assert_eq!(interrupted.to_string(), "connection reset");
+        assert_eq!(
+            MediaError::Quota("over".to_string()).context("Upload"),
+            MediaError::Quota("Upload: over".to_string())
+        );
+    }
+
     #[test]
     fn normalize_base_url_trims_trailing_slash() {
         assert_eq!(
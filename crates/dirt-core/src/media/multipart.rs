@@ -0,0 +1,367 @@
+//! Chunked, resumable attachment uploads.
+//!
+//! Uploads larger than the client's chunk size are sent as a storage
+//! multipart upload: the Dirt API starts the upload and signs each part, so a
+//! dropped connection only costs the part in flight. The progress so far is a
+//! [`MultipartUpload`], which serializes to a resume token that can be kept
+//! across app restarts and passed back to [`MediaApiClient::resume_upload`].
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use super::transfer::{unless_cancelled, CancellationToken, UploadProgress};
+use super::{check_status, MediaApiClient, MediaError, MediaResult, PresignRequest};
+use crate::util::retry::Idempotency;
+
+/// Chunk size used unless the client is configured otherwise.
+pub const DEFAULT_UPLOAD_CHUNK_BYTES: usize = 8 * 1024 * 1024;
+
+/// Smallest chunk storage accepts for every part but the last.
+pub const MIN_UPLOAD_CHUNK_BYTES: usize = 5 * 1024 * 1024;
+
+/// Response of `POST /v1/media/multipart/start`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultipartUploadStart {
+    pub upload_id: String,
+}
+
+/// A part storage has accepted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadedPart {
+    /// 1-based part number
+    pub part_number: i32,
+    /// Storage's tag for the part, needed to complete the upload
+    pub etag: String,
+}
+
+/// A multipart upload in progress; serializes to a resume token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultipartUpload {
+    pub object_key: String,
+    pub upload_id: String,
+    pub chunk_size: usize,
+    pub total_bytes: usize,
+    /// Parts already uploaded, in order
+    pub parts: Vec<UploadedPart>,
+}
+
+impl MultipartUpload {
+    /// Number of parts `total_bytes` splits into.
+    #[must_use]
+    pub fn part_count(&self) -> i32 {
+        let count = self.total_bytes.div_ceil(self.chunk_size.max(1)).max(1);
+        i32::try_from(count).unwrap_or(i32::MAX)
+    }
+
+    /// Byte range of `part_number` within the upload.
+    #[must_use]
+    pub fn part_range(&self, part_number: i32) -> Range<usize> {
+        let index = usize::try_from(part_number.saturating_sub(1)).unwrap_or(0);
+        let start = index.saturating_mul(self.chunk_size).min(self.total_bytes);
+        let end = start.saturating_add(self.chunk_size).min(self.total_bytes);
+        start..end
+    }
+
+    /// Next part to upload, or `None` once every part is stored.
+    #[must_use]
+    pub fn next_part_number(&self) -> Option<i32> {
+        let next = i32::try_from(self.parts.len()).unwrap_or(i32::MAX) + 1;
+        (next <= self.part_count()).then_some(next)
+    }
+
+    /// Bytes of the upload storage already holds.
+    #[must_use]
+    pub fn bytes_sent(&self) -> usize {
+        self.parts
+            .iter()
+            .map(|part| self.part_range(part.part_number).len())
+            .sum()
+    }
+
+    /// Token to pass to [`MediaApiClient::resume_upload`] later.
+    #[must_use]
+    pub fn resume_token(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Read back a token from [`Self::resume_token`].
+    pub fn from_resume_token(token: &str) -> MediaResult<Self> {
+        serde_json::from_str(token)
+            .map_err(|error| MediaError::Request(format!("Invalid upload resume token: {error}")))
+    }
+
+    const fn progress(&self, bytes_sent: usize) -> UploadProgress {
+        UploadProgress {
+            bytes_sent,
+            total_bytes: self.total_bytes,
+        }
+    }
+}
+
+impl MediaApiClient {
+    /// Uploads attachment bytes, in chunks when they exceed the configured
+    /// chunk size, reporting progress after each chunk.
+    ///
+    /// An interrupted chunked upload fails with
+    /// [`MediaError::UploadInterrupted`], whose resume token continues it
+    /// with [`Self::resume_upload`]. A cancelled one is discarded along with
+    /// the parts already stored.
+    pub async fn upload_with_progress(
+        &self,
+        access_token: &str,
+        object_key: &str,
+        content_type: &str,
+        bytes: &[u8],
+        cancel: &CancellationToken,
+        mut on_progress: impl FnMut(UploadProgress),
+    ) -> MediaResult<()> {
+        if bytes.len() <= self.upload_chunk_size {
+            let request = PresignRequest::Upload {
+                object_key,
+                content_type,
+                size_bytes: bytes.len(),
+            };
+            on_progress(UploadProgress {
+                bytes_sent: 0,
+                total_bytes: bytes.len(),
+            });
+            unless_cancelled(
+                cancel,
+                self.run_presigned(access_token, &request, Some(bytes)),
+            )
+            .await
+            .ok_or(MediaError::Cancelled)??;
+            on_progress(UploadProgress {
+                bytes_sent: bytes.len(),
+                total_bytes: bytes.len(),
+            });
+            return Ok(());
+        }
+
+        let mut upload = unless_cancelled(
+            cancel,
+            self.start_multipart_upload(access_token, object_key, content_type, bytes.len()),
+        )
+        .await
+        .ok_or(MediaError::Cancelled)??;
+        self.continue_upload(access_token, &mut upload, bytes, cancel, &mut on_progress)
+            .await
+    }
+
+    /// Continues an interrupted chunked upload of the same `bytes`, sending
+    /// only the parts storage does not have yet.
+    pub async fn resume_upload(
+        &self,
+        access_token: &str,
+        resume_token: &str,
+        bytes: &[u8],
+        cancel: &CancellationToken,
+        mut on_progress: impl FnMut(UploadProgress),
+    ) -> MediaResult<()> {
+        let mut upload = MultipartUpload::from_resume_token(resume_token)?;
+        if upload.total_bytes != bytes.len() {
+            return Err(MediaError::Request(format!(
+                "Upload resume token is for {} bytes, not {}",
+                upload.total_bytes,
+                bytes.len()
+            )));
+        }
+        on_progress(upload.progress(upload.bytes_sent()));
+        self.continue_upload(access_token, &mut upload, bytes, cancel, &mut on_progress)
+            .await
+    }
+
+    /// Discards an interrupted chunked upload and the parts already stored.
+    pub async fn abort_upload(&self, access_token: &str, resume_token: &str) -> MediaResult<()> {
+        let upload = MultipartUpload::from_resume_token(resume_token)?;
+        self.abort_multipart_upload(access_token, &upload).await
+    }
+
+    async fn abort_multipart_upload(
+        &self,
+        access_token: &str,
+        upload: &MultipartUpload,
+    ) -> MediaResult<()> {
+        let body = serde_json::json!({
+            "object_key": upload.object_key,
+            "upload_id": upload.upload_id,
+        });
+        self.post_multipart(access_token, "abort", &body).await?;
+        Ok(())
+    }
+
+    async fn start_multipart_upload(
+        &self,
+        access_token: &str,
+        object_key: &str,
+        content_type: &str,
+        total_bytes: usize,
+    ) -> MediaResult<MultipartUpload> {
+        let body = serde_json::json!({
+            "object_key": object_key,
+            "content_type": content_type,
+            "size_bytes": total_bytes,
+        });
+        let response = self.post_multipart(access_token, "start", &body).await?;
+        let start = response
+            .json::<MultipartUploadStart>()
+            .await
+            .map_err(|error| {
+                MediaError::Request(format!("Failed to parse multipart upload start: {error}"))
+            })?;
+        Ok(MultipartUpload {
+            object_key: object_key.to_string(),
+            upload_id: start.upload_id,
+            chunk_size: self.upload_chunk_size,
+            total_bytes,
+            parts: Vec::new(),
+        })
+    }
+
+    /// Uploads the remaining parts of `upload` and completes it, recording
+    /// each stored part so a failure can be resumed.
+    async fn continue_upload(
+        &self,
+        access_token: &str,
+        upload: &mut MultipartUpload,
+        bytes: &[u8],
+        cancel: &CancellationToken,
+        on_progress: &mut impl FnMut(UploadProgress),
+    ) -> MediaResult<()> {
+        let result = self
+            .send_remaining_parts(access_token, upload, bytes, cancel, on_progress)
+            .await;
+        if matches!(result, Err(MediaError::Cancelled)) {
+            if let Err(error) = self.abort_multipart_upload(access_token, upload).await {
+                tracing::warn!("Failed to discard cancelled upload: {error}");
+            }
+        }
+        result
+    }
+
+    async fn send_remaining_parts(
+        &self,
+        access_token: &str,
+        upload: &mut MultipartUpload,
+        bytes: &[u8],
+        cancel: &CancellationToken,
+        on_progress: &mut impl FnMut(UploadProgress),
+    ) -> MediaResult<()> {
+        while let Some(part_number) = upload.next_part_number() {
+            let range = upload.part_range(part_number);
+            let request = PresignRequest::UploadPart {
+                object_key: &upload.object_key,
+                upload_id: &upload.upload_id,
+                part_number,
+            };
+            let response = unless_cancelled(
+                cancel,
+                self.run_presigned(access_token, &request, Some(&bytes[range.clone()])),
+            )
+            .await
+            .ok_or(MediaError::Cancelled)?
+            .map_err(|error| interrupted(upload, error))?;
+            let Some(etag) = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|value| value.to_str().ok())
+            else {
+                return Err(interrupted(
+                    upload,
+                    MediaError::Request(format!("Storage returned no tag for part {part_number}")),
+                ));
+            };
+            upload.parts.push(UploadedPart {
+                part_number,
+                etag: etag.to_string(),
+            });
+            on_progress(upload.progress(range.end));
+        }
+
+        let body = serde_json::json!({
+            "object_key": upload.object_key,
+            "upload_id": upload.upload_id,
+            "parts": upload.parts,
+        });
+        self.post_multipart(access_token, "complete", &body)
+            .await
+            .map_err(|error| interrupted(upload, error))?;
+        Ok(())
+    }
+
+    async fn post_multipart(
+        &self,
+        access_token: &str,
+        action: &str,
+        body: &serde_json::Value,
+    ) -> MediaResult<reqwest::Response> {
+        let url = format!("{}/v1/media/multipart/{action}", self.base_url);
+        // A repeated start opens a second upload and a repeated complete
+        // finds the first one already finished; only aborts are safe to repeat.
+        let idempotency = if action == "abort" {
+            Idempotency::Idempotent
+        } else {
+            Idempotency::NonIdempotent
+        };
+        let response = self
+            .send_signing_request(idempotency, || {
+                self.client
+                    .post(&url)
+                    .bearer_auth(access_token)
+                    .header("Accept", "application/json")
+                    .json(body)
+            })
+            .await
+            .map_err(|error| error.context(&format!("Failed to {action} multipart upload")))?;
+        check_status(response, &format!("Multipart upload {action}")).await
+    }
+}
+
+fn interrupted(upload: &MultipartUpload, error: MediaError) -> MediaError {
+    MediaError::UploadInterrupted {
+        cause: Box::new(error),
+        resume_token: upload.resume_token(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upload(total_bytes: usize, parts: usize) -> MultipartUpload {
+        MultipartUpload {
+            object_key: "notes/a/video.mp4".to_string(),
+            upload_id: "upload-1".to_string(),
+            chunk_size: 10,
+            total_bytes,
+            parts: (1..=parts)
+                .map(|part| UploadedPart {
+                    part_number: i32::try_from(part).unwrap(),
+                    etag: format!("\"etag-{part}\""),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parts_cover_the_upload_with_a_short_last_part() {
+        let upload = upload(25, 0);
+        assert_eq!(upload.part_count(), 3);
+        assert_eq!(upload.part_range(1), 0..10);
+        assert_eq!(upload.part_range(3), 20..25);
+        assert_eq!(upload.next_part_number(), Some(1));
+        assert_eq!(upload.bytes_sent(), 0);
+    }
+
+    #[test]
+    fn resume_token_keeps_uploaded_parts() {
+        let partial = upload(25, 2);
+        let resumed = MultipartUpload::from_resume_token(&partial.resume_token()).unwrap();
+        assert_eq!(resumed, partial);
+        assert_eq!(resumed.next_part_number(), Some(3));
+        assert_eq!(resumed.bytes_sent(), 20);
+        assert_eq!(upload(25, 3).next_part_number(), None);
+        assert!(MultipartUpload::from_resume_token("not a token").is_err());
+    }
+}
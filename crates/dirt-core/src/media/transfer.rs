@@ -0,0 +1,150 @@
+//! Progress reporting and cancellation for attachment transfers.
+//!
+//! Attachment panels pass a progress callback to show a percentage and a
+//! [`CancellationToken`] to stop a transfer part way; a cancelled transfer
+//! fails with [`MediaError::Cancelled`].
+
+use std::future::Future;
+
+pub use tokio_util::sync::CancellationToken;
+
+use super::{MediaApiClient, MediaError, MediaResult, PresignRequest};
+
+/// How far an upload has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UploadProgress {
+    pub bytes_sent: usize,
+    pub total_bytes: usize,
+}
+
+impl UploadProgress {
+    /// Share of the upload sent, from 0 to 100.
+    #[must_use]
+    pub fn percent(&self) -> u8 {
+        percent_of(self.bytes_sent, self.total_bytes)
+    }
+}
+
+/// How far a download has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub bytes_received: usize,
+    /// Size storage reported for the object, when it did
+    pub total_bytes: Option<usize>,
+}
+
+impl DownloadProgress {
+    /// Share of the download received, from 0 to 100, when the size is known.
+    #[must_use]
+    pub fn percent(&self) -> Option<u8> {
+        self.total_bytes
+            .map(|total_bytes| percent_of(self.bytes_received, total_bytes))
+    }
+}
+
+fn percent_of(done: usize, total: usize) -> u8 {
+    if total == 0 {
+        return 100;
+    }
+    u8::try_from(done.min(total).saturating_mul(100) / total).unwrap_or(100)
+}
+
+/// Runs `future` to completion, or returns `None` as soon as `cancel` fires.
+pub(crate) async fn unless_cancelled<F: Future>(
+    cancel: &CancellationToken,
+    future: F,
+) -> Option<F::Output> {
+    tokio::select! {
+        biased;
+        () = cancel.cancelled() => None,
+        output = future => Some(output),
+    }
+}
+
+impl MediaApiClient {
+    /// Downloads attachment bytes, reporting progress as they arrive.
+    ///
+    /// Returns raw bytes and an optional content type returned by the storage backend.
+    pub async fn download_with_progress(
+        &self,
+        access_token: &str,
+        object_key: &str,
+        cancel: &CancellationToken,
+        mut on_progress: impl FnMut(DownloadProgress),
+    ) -> MediaResult<(Vec<u8>, Option<String>)> {
+        let request = PresignRequest::Download { object_key };
+        let mut response =
+            unless_cancelled(cancel, self.run_presigned(access_token, &request, None))
+                .await
+                .ok_or(MediaError::Cancelled)??;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+        let total_bytes = response
+            .content_length()
+            .and_then(|length| usize::try_from(length).ok());
+
+        let mut bytes = Vec::new();
+        on_progress(DownloadProgress {
+            bytes_received: 0,
+            total_bytes,
+        });
+        while let Some(chunk) = unless_cancelled(cancel, response.chunk())
+            .await
+            .ok_or(MediaError::Cancelled)?
+            .map_err(|error| {
+                MediaError::Network(format!("Failed to read attachment bytes: {error}"))
+            })?
+        {
+            bytes.extend_from_slice(&chunk);
+            on_progress(DownloadProgress {
+                bytes_received: bytes.len(),
+                total_bytes,
+            });
+        }
+        Ok((bytes, content_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_is_clamped_and_handles_empty_transfers() {
+        let upload = UploadProgress {
+            bytes_sent: 3,
+            total_bytes: 12,
+        };
+        assert_eq!(upload.percent(), 25);
+        let empty = UploadProgress {
+            bytes_sent: 0,
+            total_bytes: 0,
+        };
+        assert_eq!(empty.percent(), 100);
+
+        let unknown = DownloadProgress {
+            bytes_received: 10,
+            total_bytes: None,
+        };
+        assert_eq!(unknown.percent(), None);
+        let overshoot = DownloadProgress {
+            bytes_received: 20,
+            total_bytes: Some(10),
+        };
+        assert_eq!(overshoot.percent(), Some(100));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn cancelled_token_wins_over_pending_work() {
+        let cancel = CancellationToken::new();
+        assert_eq!(unless_cancelled(&cancel, async { 7 }).await, Some(7));
+        cancel.cancel();
+        assert_eq!(
+            unless_cancelled(&cancel, std::future::pending::<()>()).await,
+            None
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Personal access token model
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+/// Permission granted to a personal access token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum AccessScope {
+    /// List, search, and read notes
+    #[serde(rename = "notes:read")]
+    NotesRead,
+    /// Create, update, and delete notes
+    #[serde(rename = "notes:write")]
+    NotesWrite,
+    /// Download attachments
+    #[serde(rename = "media:read")]
+    MediaRead,
+    /// Upload and delete attachments
+    #[serde(rename = "media:write")]
+    MediaWrite,
+}
+
+impl AccessScope {
+    /// Every scope, in display order
+    pub const ALL: [Self; 4] = [
+        Self::NotesRead,
+        Self::NotesWrite,
+        Self::MediaRead,
+        Self::MediaWrite,
+    ];
+
+    /// Wire name of the scope (e.g. `notes:read`)
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NotesRead => "notes:read",
+            Self::NotesWrite => "notes:write",
+            Self::MediaRead => "media:read",
+            Self::MediaWrite => "media:write",
+        }
+    }
+}
+
+impl fmt::Display for AccessScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AccessScope {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        Self::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == value)
+            .ok_or_else(|| Error::InvalidInput(format!("Unknown access token scope `{value}`")))
+    }
+}
+
+/// Metadata for an issued personal access token
+///
+/// The signed token itself is never stored; only enough to list and revoke it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessToken {
+    /// Token identifier (also embedded in the signed token)
+    pub id: String,
+    /// Owning user (Supabase subject)
+    pub user_id: String,
+    /// Human-readable label chosen at creation
+    pub name: String,
+    /// Granted scopes
+    pub scopes: Vec<AccessScope>,
+    /// Creation timestamp (Unix seconds)
+    pub created_at: i64,
+    /// Expiry timestamp (Unix seconds)
+    pub expires_at: i64,
+    /// Last successful use (Unix seconds)
+    pub last_used_at: Option<i64>,
+    /// Revocation timestamp (Unix seconds)
+    pub revoked_at: Option<i64>,
+}
+
+impl AccessToken {
+    /// Whether the token can still authenticate requests at `now` (Unix seconds)
+    #[must_use]
+    pub const fn is_active(&self, now: i64) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_round_trips_wire_name() {
+        for scope in AccessScope::ALL {
+            assert_eq!(scope.as_str().parse::<AccessScope>().unwrap(), scope);
+        }
+        assert!("admin".parse::<AccessScope>().is_err());
+    }
+
+    #[test]
+    fn token_is_inactive_when_revoked_or_expired() {
+        let token = AccessToken {
+            id: "token".to_string(),
+            user_id: "user".to_string(),
+            name: "script".to_string(),
+            scopes: vec![AccessScope::NotesRead],
+            created_at: 100,
+            expires_at: 200,
+            last_used_at: None,
+            revoked_at: None,
+        };
+        assert!(token.is_active(150));
+        assert!(!token.is_active(200));
+        assert!(!AccessToken {
+            revoked_at: Some(120),
+            ..token
+        }
+        .is_active(150));
+    }
+}
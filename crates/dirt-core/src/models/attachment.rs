@@ -47,6 +47,73 @@ impl FromStr for AttachmentId {
     }
 }
 
+/// Result of the server-side content scan for an uploaded attachment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentScanStatus {
+    /// Not scanned (scanning disabled, legacy upload, or commit not acknowledged).
+    #[default]
+    Unscanned,
+    /// Scanner found nothing objectionable.
+    Clean,
+    /// Scanner flagged the object; it was moved out of the readable namespace.
+    Quarantined,
+    /// Scanner could not produce a verdict.
+    Failed,
+}
+
+impl AttachmentScanStatus {
+    /// Wire and storage name of the status.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Unscanned => "unscanned",
+            Self::Clean => "clean",
+            Self::Quarantined => "quarantined",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Short user-facing label, or `None` when nothing needs to be shown.
+    #[must_use]
+    pub const fn label(self) -> Option<&'static str> {
+        match self {
+            Self::Unscanned => None,
+            Self::Clean => Some("Scanned"),
+            Self::Quarantined => Some("Quarantined"),
+            Self::Failed => Some("Scan failed"),
+        }
+    }
+
+    /// Whether the stored object can still be downloaded.
+    #[must_use]
+    pub const fn is_downloadable(self) -> bool {
+        !matches!(self, Self::Quarantined)
+    }
+}
+
+impl fmt::Display for AttachmentScanStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AttachmentScanStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "unscanned" => Ok(Self::Unscanned),
+            "clean" => Ok(Self::Clean),
+            "quarantined" => Ok(Self::Quarantined),
+            "failed" => Ok(Self::Failed),
+            other => Err(Error::InvalidInput(format!(
+                "Unknown attachment scan status `{other}`"
+            ))),
+        }
+    }
+}
+
 /// Attachment metadata persisted for a note.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Attachment {
@@ -66,6 +133,9 @@ pub struct Attachment {
     pub created_at: i64,
     /// Soft delete flag for sync.
     pub is_deleted: bool,
+    /// Server-side content scan result.
+    #[serde(default)]
+    pub scan_status: AttachmentScanStatus,
 }
 
 impl Attachment {
@@ -111,6 +181,7 @@ impl Attachment {
             r2_key,
             created_at: chrono::Utc::now().timestamp_millis(),
             is_deleted: false,
+            scan_status: AttachmentScanStatus::Unscanned,
         })
     }
 }
@@ -149,6 +220,24 @@ mod tests {
         assert_eq!(attachment.size_bytes, 1234);
         assert_eq!(attachment.r2_key, "notes/note/image.png");
         assert!(!attachment.is_deleted);
+        assert_eq!(attachment.scan_status, AttachmentScanStatus::Unscanned);
+    }
+
+    #[test]
+    fn test_scan_status_round_trips_storage_name() {
+        for status in [
+            AttachmentScanStatus::Unscanned,
+            AttachmentScanStatus::Clean,
+            AttachmentScanStatus::Quarantined,
+            AttachmentScanStatus::Failed,
+        ] {
+            assert_eq!(
+                status.as_str().parse::<AttachmentScanStatus>().unwrap(),
+                status
+            );
+        }
+        assert!("infected".parse::<AttachmentScanStatus>().is_err());
+        assert!(!AttachmentScanStatus::Quarantined.is_downloadable());
     }
 
     #[test]
@@ -0,0 +1,67 @@
+//! Daily notes: one journal note per calendar day
+
+use chrono::NaiveDate;
+
+use super::Note;
+
+/// Tag that marks daily notes.
+pub const DAILY_NOTE_TAG: &str = "daily";
+
+/// Title (first line) of the daily note for `date`.
+#[must_use]
+pub fn daily_note_title(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Content of a freshly created daily note for `date`.
+#[must_use]
+pub fn daily_note_content(date: NaiveDate) -> String {
+    format!("{}\n#{DAILY_NOTE_TAG}", daily_note_title(date))
+}
+
+/// Whether `note` is the daily note titled `title`.
+#[must_use]
+pub fn is_daily_note(note: &Note, title: &str) -> bool {
+    !note.is_deleted
+        && note.content.lines().next().map(str::trim) == Some(title)
+        && note.tags().iter().any(|tag| tag == DAILY_NOTE_TAG)
+}
+
+/// `content` with `entry` appended.
+///
+/// Consecutive list items stay in one list; anything else starts a new
+/// paragraph.
+#[must_use]
+pub fn daily_note_with_entry(content: &str, entry: &str) -> String {
+    let content = content.trim_end();
+    let entry = entry.trim();
+    let last_line = content.lines().last().unwrap_or_default().trim_start();
+    let separator = if last_line.starts_with("- ") && entry.starts_with("- ") {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    format!("{content}{separator}{entry}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_extend_lists_and_start_paragraphs() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 14).unwrap();
+        let content = daily_note_content(date);
+        assert_eq!(content, "2026-03-14\n#daily");
+        assert!(is_daily_note(&Note::new(&content), "2026-03-14"));
+        assert!(!is_daily_note(&Note::new("2026-03-14"), "2026-03-14"));
+
+        let content = daily_note_with_entry(&content, "- [[Call the bank]]");
+        let content = daily_note_with_entry(&content, "- [[Buy stamps]]\n");
+        let content = daily_note_with_entry(&content, "Felt productive");
+        assert_eq!(
+            content,
+            "2026-03-14\n#daily\n\n- [[Call the bank]]\n- [[Buy stamps]]\n\nFelt productive"
+        );
+    }
+}
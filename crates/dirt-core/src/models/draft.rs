@@ -0,0 +1,16 @@
+//! Note draft model
+
+use serde::{Deserialize, Serialize};
+
+use super::NoteId;
+
+/// Unsaved editor content journaled ahead of the debounced note save
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteDraft {
+    /// Note the draft belongs to
+    pub note_id: NoteId,
+    /// Editor content at the time of the last journal write
+    pub content: String,
+    /// Journal write timestamp (Unix ms)
+    pub updated_at: i64,
+}
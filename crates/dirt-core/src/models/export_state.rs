@@ -0,0 +1,18 @@
+//! Incremental export state model
+
+use serde::{Deserialize, Serialize};
+
+use crate::export::ExportFormat;
+
+/// Progress of incremental exports into one destination
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportState {
+    /// Directory the notes are mirrored into (canonical path)
+    pub destination: String,
+    /// Format of the mirrored note files
+    pub format: ExportFormat,
+    /// Highest note `updated_at` written to the destination (Unix ms)
+    pub cursor: i64,
+    /// When the last export into the destination finished (Unix ms)
+    pub exported_at: i64,
+}
@@ -0,0 +1,274 @@
+//! Global capture hotkey
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Hotkey that opens quick capture until the user records another one.
+///
+/// `CmdOrCtrl` is Cmd on macOS and Ctrl elsewhere.
+pub const DEFAULT_CAPTURE_HOTKEY: &str = "CmdOrCtrl+Alt+N";
+
+/// Shortcuts the OS or nearly every app already uses, with what they do.
+const RESERVED_HOTKEYS: &[(&str, &str)] = &[
+    ("CmdOrCtrl+A", "Select all"),
+    ("CmdOrCtrl+C", "Copy"),
+    ("CmdOrCtrl+F", "Find"),
+    ("CmdOrCtrl+Q", "Quit"),
+    ("CmdOrCtrl+S", "Save"),
+    ("CmdOrCtrl+V", "Paste"),
+    ("CmdOrCtrl+W", "Close window"),
+    ("CmdOrCtrl+X", "Cut"),
+    ("CmdOrCtrl+Z", "Undo"),
+    ("Alt+Tab", "Switch windows"),
+    ("Alt+F4", "Close window"),
+    ("Ctrl+Alt+Delete", "Security options"),
+    ("Meta+Tab", "Switch apps"),
+    ("Meta+Space", "System search"),
+    ("Meta+D", "Show desktop"),
+    ("Meta+L", "Lock screen"),
+];
+
+/// Modifier keys held with a hotkey's key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct HotkeyModifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// Cmd on macOS, the Windows/Super key elsewhere
+    pub meta: bool,
+}
+
+/// Key combination for the global capture shortcut, e.g. `Ctrl+Alt+N`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    pub modifiers: HotkeyModifiers,
+    /// Key name: an uppercase letter, a digit, `F1`-`F24`, or `Space`
+    pub key: String,
+}
+
+impl Default for Hotkey {
+    /// [`DEFAULT_CAPTURE_HOTKEY`]
+    fn default() -> Self {
+        let macos = cfg!(target_os = "macos");
+        Self {
+            modifiers: HotkeyModifiers {
+                ctrl: !macos,
+                alt: true,
+                shift: false,
+                meta: macos,
+            },
+            key: "N".to_string(),
+        }
+    }
+}
+
+impl Hotkey {
+    /// Builds a hotkey from a recorded key press, given the DOM
+    /// `KeyboardEvent.code` (`KeyN`, `Digit1`, `F5`, `Space`).
+    ///
+    /// Returns `None` for modifier keys and keys a hotkey cannot use.
+    #[must_use]
+    pub fn from_key_code(code: &str, modifiers: HotkeyModifiers) -> Option<Self> {
+        let key = code
+            .strip_prefix("Key")
+            .or_else(|| code.strip_prefix("Digit"))
+            .unwrap_or(code);
+        let key = normalize_key(key)?;
+        Some(Self { modifiers, key })
+    }
+
+    /// DOM / `keyboard-types` code of the key, e.g. `KeyN` for `N`.
+    #[must_use]
+    pub fn key_code(&self) -> String {
+        let mut chars = self.key.chars();
+        match (chars.next(), chars.next()) {
+            (Some(letter), None) if letter.is_ascii_alphabetic() => format!("Key{letter}"),
+            (Some(digit), None) if digit.is_ascii_digit() => format!("Digit{digit}"),
+            _ => self.key.clone(),
+        }
+    }
+
+    /// Why the hotkey cannot be used as a global shortcut, if anything.
+    ///
+    /// Hotkeys need Ctrl, Alt, or Cmd (function keys excepted) and must not
+    /// shadow common system or editing shortcuts. Whether another app holds
+    /// the hotkey is only known when registering it.
+    #[must_use]
+    pub fn conflict(&self) -> Option<String> {
+        let modifiers = self.modifiers;
+        if !(modifiers.ctrl || modifiers.alt || modifiers.meta) && !is_function_key(&self.key) {
+            return Some(format!(
+                "{self} would fire while typing; add {} or Alt",
+                if cfg!(target_os = "macos") {
+                    "Cmd"
+                } else {
+                    "Ctrl"
+                }
+            ));
+        }
+        RESERVED_HOTKEYS.iter().find_map(|(reserved, action)| {
+            (reserved.parse::<Self>().ok().as_ref() == Some(self))
+                .then(|| format!("{self} is already used for {action}"))
+        })
+    }
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = self.modifiers;
+        let meta = if cfg!(target_os = "macos") {
+            "Cmd"
+        } else {
+            "Super"
+        };
+        for (held, name) in [
+            (modifiers.ctrl, "Ctrl"),
+            (modifiers.alt, "Alt"),
+            (modifiers.shift, "Shift"),
+            (modifiers.meta, meta),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+impl FromStr for Hotkey {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| Error::InvalidInput(format!("Invalid hotkey '{value}': {reason}"));
+        let mut modifiers = HotkeyModifiers::default();
+        let mut key = None;
+        for token in value.split('+').map(str::trim) {
+            match token.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "cmd" | "command" | "meta" | "super" | "win" => modifiers.meta = true,
+                "cmdorctrl" => {
+                    if cfg!(target_os = "macos") {
+                        modifiers.meta = true;
+                    } else {
+                        modifiers.ctrl = true;
+                    }
+                }
+                _ if key.is_some() => return Err(invalid("more than one key")),
+                _ => key = Some(normalize_key(token).ok_or_else(|| invalid("unsupported key"))?),
+            }
+        }
+        let key = key.ok_or_else(|| invalid("no key"))?;
+        Ok(Self { modifiers, key })
+    }
+}
+
+/// Canonical name of a supported key, case-insensitively.
+fn normalize_key(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(single), None) if single.is_ascii_alphanumeric() => {
+            Some(single.to_ascii_uppercase().to_string())
+        }
+        _ if key.eq_ignore_ascii_case("space") => Some("Space".to_string()),
+        _ if is_function_key(key) => Some(key.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+fn is_function_key(key: &str) -> bool {
+    key.strip_prefix(['F', 'f'])
+        .and_then(|number| number.parse::<u8>().ok())
+        .is_some_and(|number| (1..=24).contains(&number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctrl_alt() -> HotkeyModifiers {
+        HotkeyModifiers {
+            ctrl: true,
+            alt: true,
+            ..HotkeyModifiers::default()
+        }
+    }
+
+    #[test]
+    fn parses_and_displays_hotkeys() {
+        let hotkey: Hotkey = "ctrl + alt + n".parse().unwrap();
+        assert_eq!(hotkey.modifiers, ctrl_alt());
+        assert_eq!(hotkey.key, "N");
+        assert_eq!(hotkey.to_string(), "Ctrl+Alt+N");
+        assert_eq!(hotkey.key_code(), "KeyN");
+
+        let hotkey: Hotkey = "Shift+f12".parse().unwrap();
+        assert_eq!(hotkey.to_string(), "Shift+F12");
+        assert_eq!(hotkey.key_code(), "F12");
+
+        assert!("Ctrl+Alt".parse::<Hotkey>().is_err());
+        assert!("Ctrl+A+B".parse::<Hotkey>().is_err());
+        assert!("Ctrl+Escape".parse::<Hotkey>().is_err());
+        assert!("F25".parse::<Hotkey>().is_err());
+    }
+
+    #[test]
+    fn default_hotkey_uses_the_platform_command_key() {
+        let hotkey = Hotkey::default();
+        assert_eq!(DEFAULT_CAPTURE_HOTKEY.parse::<Hotkey>().unwrap(), hotkey);
+        assert_eq!(hotkey.key, "N");
+        assert!(hotkey.modifiers.alt);
+        assert_ne!(hotkey.modifiers.ctrl, hotkey.modifiers.meta);
+        assert_eq!(hotkey.conflict(), None);
+    }
+
+    #[test]
+    fn records_hotkeys_from_key_codes() {
+        assert_eq!(
+            Hotkey::from_key_code("Digit5", ctrl_alt()).map(|hotkey| hotkey.to_string()),
+            Some("Ctrl+Alt+5".to_string())
+        );
+        assert_eq!(
+            Hotkey::from_key_code("Space", ctrl_alt()).map(|hotkey| hotkey.key_code()),
+            Some("Space".to_string())
+        );
+        assert_eq!(Hotkey::from_key_code("ControlLeft", ctrl_alt()), None);
+        assert_eq!(Hotkey::from_key_code("Escape", ctrl_alt()), None);
+    }
+
+    #[test]
+    fn detects_conflicting_hotkeys() {
+        let shift_only = HotkeyModifiers {
+            shift: true,
+            ..HotkeyModifiers::default()
+        };
+        assert!(Hotkey::from_key_code("KeyN", shift_only)
+            .unwrap()
+            .conflict()
+            .unwrap()
+            .contains("while typing"));
+        assert_eq!(
+            Hotkey::from_key_code("F9", HotkeyModifiers::default())
+                .unwrap()
+                .conflict(),
+            None
+        );
+        assert!("Alt+F4"
+            .parse::<Hotkey>()
+            .unwrap()
+            .conflict()
+            .unwrap()
+            .contains("Close window"));
+        assert!("CmdOrCtrl+C"
+            .parse::<Hotkey>()
+            .unwrap()
+            .conflict()
+            .unwrap()
+            .contains("Copy"));
+    }
+}
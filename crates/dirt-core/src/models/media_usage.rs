@@ -0,0 +1,72 @@
+//! Per-user attachment storage usage
+
+use serde::{Deserialize, Serialize};
+
+use super::format_byte_count;
+
+/// Attachment storage a user has used on the managed backend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaUsage {
+    /// Total size of the user's stored attachment objects
+    pub used_bytes: i64,
+    /// Number of stored attachment objects
+    pub object_count: i64,
+    /// Most bytes the user may store (`None` when unlimited)
+    #[serde(default)]
+    pub quota_bytes: Option<i64>,
+}
+
+impl MediaUsage {
+    /// Whether storing `additional_bytes` more would go over the quota
+    #[must_use]
+    pub fn exceeds_quota(&self, additional_bytes: i64) -> bool {
+        self.quota_bytes
+            .is_some_and(|quota| self.used_bytes.saturating_add(additional_bytes) > quota)
+    }
+
+    /// Bytes left before the quota is reached (`None` when unlimited)
+    #[must_use]
+    pub fn remaining_bytes(&self) -> Option<i64> {
+        self.quota_bytes
+            .map(|quota| quota.saturating_sub(self.used_bytes).max(0))
+    }
+
+    /// Usage for settings screens, e.g. `2.1 GiB of 5.0 GiB used`
+    #[must_use]
+    pub fn summary(&self) -> String {
+        match self.quota_bytes {
+            Some(quota) => format!(
+                "{} of {} used",
+                format_byte_count(self.used_bytes),
+                format_byte_count(quota)
+            ),
+            None => format!("{} used", format_byte_count(self.used_bytes)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quota_checks_and_summary() {
+        let usage = MediaUsage {
+            used_bytes: 2_254_857_830,
+            object_count: 12,
+            quota_bytes: Some(5_368_709_120),
+        };
+        assert_eq!(usage.summary(), "2.1 GiB of 5.0 GiB used");
+        assert!(!usage.exceeds_quota(1_024));
+        assert!(usage.exceeds_quota(4_000_000_000));
+        assert_eq!(usage.remaining_bytes(), Some(3_113_851_290));
+
+        let unlimited = MediaUsage {
+            quota_bytes: None,
+            ..usage
+        };
+        assert_eq!(unlimited.summary(), "2.1 GiB used");
+        assert!(!unlimited.exceeds_quota(i64::MAX));
+        assert_eq!(unlimited.remaining_bytes(), None);
+    }
+}
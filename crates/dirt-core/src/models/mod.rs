@@ -1,13 +1,56 @@
 //! Data models for Dirt
 
+mod access_token;
 mod attachment;
+mod daily_note;
+mod draft;
+mod export_state;
+mod hotkey;
+mod media_usage;
 mod note;
+mod note_order;
+mod note_page;
+mod note_share;
+mod note_source;
+mod note_summary;
+mod note_url;
+mod revision;
 mod settings;
 mod sync_conflict;
+mod sync_history;
 mod tag;
+mod task;
+mod template;
+mod trash;
 
-pub use attachment::{Attachment, AttachmentId};
-pub use note::{extract_tags, Note, NoteId};
+pub use access_token::{AccessScope, AccessToken};
+pub use attachment::{Attachment, AttachmentId, AttachmentScanStatus};
+pub use daily_note::{
+    daily_note_content, daily_note_title, daily_note_with_entry, is_daily_note, DAILY_NOTE_TAG,
+};
+pub use draft::NoteDraft;
+pub use export_state::ExportState;
+pub use hotkey::{Hotkey, HotkeyModifiers, DEFAULT_CAPTURE_HOTKEY};
+pub use media_usage::MediaUsage;
+pub use note::{
+    extract_note_links, extract_tags, extract_urls, short_note_id, unique_prefix_len, Note, NoteId,
+    MIN_SHORT_ID_LEN,
+};
+pub use note_order::{apply_manual_order, move_note_in_order, SortScope};
+pub use note_page::{append_note_page, merge_first_page, NoteCursor, NotePage};
+pub use note_share::NoteShare;
+pub use note_source::{NoteSource, SOURCE_FILTER_PREFIX};
+pub use note_summary::{NoteSummary, SummaryMethod};
+pub use note_url::{NoteUrl, URL_FILTER_PREFIX};
+pub use revision::{NoteRevision, DEFAULT_REVISION_RETENTION};
 pub use settings::{Settings, ThemeMode};
 pub use sync_conflict::SyncConflict;
-pub use tag::{Tag, TagId};
+pub use sync_history::{
+    count_changed_rows, format_byte_count, SyncHistoryEntry, REPLICATION_FRAME_BYTES,
+};
+pub use tag::{apply_tag_completion, tag_query_at, Tag, TagId, TagQuery};
+pub use task::{extract_tasks, toggle_task_line, NoteTask};
+pub use template::{
+    builtin_templates, find_template, parse_field_assignment, CaptureTemplate, TemplateField,
+};
+pub use trash::{trash_cutoff, TrashPurge, DEFAULT_TRASH_RETENTION_DAYS};
@@ -45,6 +45,38 @@ impl FromStr for NoteId {
     }
 }
 
+/// Fewest characters of a note id shown in listings
+pub const MIN_SHORT_ID_LEN: usize = 8;
+
+/// Shortest id prefix length that tells every note apart, like git's
+/// abbreviated hashes.
+///
+/// UUID v7 ids start with their creation time, so notes captured close
+/// together (or on two devices in the same minute) share long prefixes; the
+/// length grows past [`MIN_SHORT_ID_LEN`] just far enough to keep them
+/// distinct. `sorted_ids` must be in ascending order, so only neighboring
+/// ids need comparing.
+#[must_use]
+pub fn unique_prefix_len(sorted_ids: &[String]) -> usize {
+    sorted_ids
+        .windows(2)
+        .map(|pair| {
+            let shared = pair[0]
+                .bytes()
+                .zip(pair[1].bytes())
+                .take_while(|(left, right)| left == right)
+                .count();
+            shared + 1
+        })
+        .fold(MIN_SHORT_ID_LEN, usize::max)
+}
+
+/// First `len` characters of a note id.
+#[must_use]
+pub fn short_note_id(id: &str, len: usize) -> &str {
+    id.get(..len).unwrap_or(id)
+}
+
 /// A note in the system
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Note {
@@ -58,6 +90,12 @@ pub struct Note {
     pub updated_at: i64,
     /// Soft delete flag for sync
     pub is_deleted: bool,
+    /// Kept at the top of the note list
+    #[serde(default)]
+    pub is_pinned: bool,
+    /// Hidden from the note list but kept for search and export
+    #[serde(default)]
+    pub is_archived: bool,
 }
 
 impl Note {
@@ -71,6 +109,8 @@ impl Note {
             created_at: now,
             updated_at: now,
             is_deleted: false,
+            is_pinned: false,
+            is_archived: false,
         }
     }
 
@@ -80,6 +120,12 @@ impl Note {
         extract_tags(&self.content)
     }
 
+    /// Extract http(s) URLs from content
+    #[must_use]
+    pub fn urls(&self) -> Vec<String> {
+        extract_urls(&self.content)
+    }
+
     /// Get first line as title preview, truncated to `max_len` characters
     #[must_use]
     pub fn title_preview(&self, max_len: usize) -> String {
@@ -123,10 +169,101 @@ pub fn extract_tags(text: &str) -> Vec<String> {
         .collect()
 }
 
+/// Extract `[[wiki link]]` targets from text
+///
+/// Targets are trimmed and deduplicated case-insensitively, keeping the first
+/// spelling in order of appearance. Empty targets are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use dirt_core::models::extract_note_links;
+///
+/// let links = extract_note_links("See [[Project plan]] and [[ project PLAN ]]");
+/// assert_eq!(links, vec!["Project plan".to_string()]);
+/// ```
+#[must_use]
+pub fn extract_note_links(text: &str) -> Vec<String> {
+    let re = Regex::new(r"\[\[([^\[\]\n]+)\]\]").expect("Invalid regex");
+    let mut seen = HashSet::new();
+    re.captures_iter(text)
+        .map(|cap| cap[1].trim().to_string())
+        .filter(|target| !target.is_empty() && seen.insert(target.to_lowercase()))
+        .collect()
+}
+
+/// Extract `http://` and `https://` URLs from text
+///
+/// Trailing sentence punctuation and unbalanced closing parentheses (as in
+/// Markdown links) are not part of the URL. URLs are deduplicated, keeping
+/// the order of first appearance.
+///
+/// # Examples
+///
+/// ```
+/// use dirt_core::models::extract_urls;
+///
+/// let urls = extract_urls("Read https://example.com/a, then [b](https://example.com/b).");
+/// assert_eq!(urls, vec!["https://example.com/a", "https://example.com/b"]);
+/// ```
+#[must_use]
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let re = Regex::new(r#"(?i)https?://[^\s<>"'`\[\]{}]+"#).expect("Invalid regex");
+    let mut seen = HashSet::new();
+    re.find_iter(text)
+        .map(|found| trim_url_suffix(found.as_str()))
+        .filter(|url| {
+            url.split_once("://")
+                .is_some_and(|(_, rest)| !rest.is_empty())
+        })
+        .filter(|url| seen.insert(*url))
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn trim_url_suffix(mut url: &str) -> &str {
+    loop {
+        let mut trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        if let Some(inner) = trimmed.strip_suffix(')') {
+            if trimmed.matches('(').count() < trimmed.matches(')').count() {
+                trimmed = inner;
+            }
+        }
+        if trimmed.len() == url.len() {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn unique_prefix_len_grows_past_shared_prefixes() {
+        let ids = [
+            "0192a000-0000-7000-8000-000000000001",
+            "0192a000-0000-7000-8000-000000000002",
+            "0192b111-0000-7000-8000-000000000003",
+        ]
+        .map(String::from);
+        assert_eq!(unique_prefix_len(&ids), 36);
+        assert_eq!(
+            short_note_id(&ids[2], unique_prefix_len(&ids[1..])),
+            "0192b111"
+        );
+
+        let apart = [
+            "0192a000-0000-7000-8000-000000000001",
+            "0192a000-1000-7000-8000-000000000001",
+        ]
+        .map(String::from);
+        assert_eq!(unique_prefix_len(&apart), 10);
+        assert_eq!(unique_prefix_len(&[]), MIN_SHORT_ID_LEN);
+        assert_eq!(short_note_id("abc", 8), "abc");
+    }
+
     #[test]
     fn test_note_id_unique() {
         let id1 = NoteId::new();
@@ -193,6 +330,32 @@ mod tests {
         assert!(tags.is_empty());
     }
 
+    #[test]
+    fn test_extract_urls() {
+        let urls = extract_urls(
+            "See https://example.com/docs. Also (http://wiki.example/Rust_(lang)) \
+             and https://example.com/docs again; not ftp://x or https://",
+        );
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/docs".to_string(),
+                "http://wiki.example/Rust_(lang)".to_string(),
+            ]
+        );
+        assert_eq!(
+            extract_urls("<https://a.example/?q=1&b=2>"),
+            vec!["https://a.example/?q=1&b=2".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_extract_note_links() {
+        let links = extract_note_links("[[Alpha]] then [[ beta ]] and [[alpha]] but not [[]]");
+        assert_eq!(links, vec!["Alpha".to_string(), "beta".to_string()]);
+        assert!(extract_note_links("[single] [[unclosed").is_empty());
+    }
+
     #[test]
     fn test_title_preview() {
         let note = Note::new("First line\nSecond line\nThird line");
@@ -0,0 +1,102 @@
+//! Manual note ordering model
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Note, NoteId};
+
+/// A set of notes that can be arranged by hand
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SortScope {
+    /// Notes carrying a tag (stored lowercase, without `#`)
+    Tag(String),
+}
+
+impl SortScope {
+    /// Scope for a tag, normalized like extracted tags
+    #[must_use]
+    pub fn tag(name: &str) -> Self {
+        Self::Tag(name.trim().trim_start_matches('#').to_lowercase())
+    }
+
+    /// Stable key used to persist the scope's positions
+    #[must_use]
+    pub fn key(&self) -> String {
+        match self {
+            Self::Tag(tag) => format!("tag:{tag}"),
+        }
+    }
+}
+
+impl fmt::Display for SortScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag(tag) => write!(f, "#{tag}"),
+        }
+    }
+}
+
+/// Sort notes by a manual order
+///
+/// Notes with a stored position come first, in position order; the rest keep
+/// their existing relative order after them.
+pub fn apply_manual_order(notes: &mut [Note], order: &[NoteId]) {
+    let positions: HashMap<NoteId, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(index, id)| (*id, index))
+        .collect();
+    notes.sort_by_key(|note| positions.get(&note.id).copied().unwrap_or(usize::MAX));
+}
+
+/// Move a note to `to_index` within `order`
+///
+/// A note missing from `order` is inserted. `to_index` is clamped to the end.
+#[must_use]
+pub fn move_note_in_order(order: &[NoteId], note_id: NoteId, to_index: usize) -> Vec<NoteId> {
+    let mut reordered: Vec<NoteId> = order.iter().copied().filter(|id| *id != note_id).collect();
+    let index = to_index.min(reordered.len());
+    reordered.insert(index, note_id);
+    reordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_scope_is_normalized() {
+        assert_eq!(
+            SortScope::tag(" #Project "),
+            SortScope::Tag("project".into())
+        );
+        assert_eq!(SortScope::tag("project").key(), "tag:project");
+        assert_eq!(SortScope::tag("project").to_string(), "#project");
+    }
+
+    #[test]
+    fn apply_manual_order_puts_positioned_notes_first() {
+        let a = Note::new("a");
+        let b = Note::new("b");
+        let c = Note::new("c");
+        let mut notes = vec![a.clone(), b.clone(), c.clone()];
+
+        apply_manual_order(&mut notes, &[c.id, a.id]);
+
+        let ids: Vec<NoteId> = notes.iter().map(|note| note.id).collect();
+        assert_eq!(ids, vec![c.id, a.id, b.id]);
+    }
+
+    #[test]
+    fn move_note_in_order_reinserts_and_clamps() {
+        let a = NoteId::new();
+        let b = NoteId::new();
+        let c = NoteId::new();
+
+        assert_eq!(move_note_in_order(&[a, b, c], c, 0), vec![c, a, b]);
+        assert_eq!(move_note_in_order(&[a, b, c], a, 1), vec![b, a, c]);
+        assert_eq!(move_note_in_order(&[a, b], c, 99), vec![a, b, c]);
+    }
+}
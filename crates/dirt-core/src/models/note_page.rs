@@ -0,0 +1,185 @@
+//! Cursor pagination over the newest-first note list
+//!
+//! Clients load every pinned note together with the first page, so a loaded
+//! pinned note says nothing about how far paging got. Positions are taken
+//! from unpinned notes whenever there are any.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Note, NoteId};
+
+/// Position in the newest-first note list; a page starts after this note
+///
+/// Notes are ordered by `updated_at` descending with the ID as tiebreaker, so
+/// a cursor stays valid while newer notes are added above it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteCursor {
+    pub updated_at: i64,
+    pub id: NoteId,
+}
+
+impl NoteCursor {
+    /// Cursor resuming after `note`
+    #[must_use]
+    pub const fn after(note: &Note) -> Self {
+        Self {
+            updated_at: note.updated_at,
+            id: note.id,
+        }
+    }
+
+    /// Cursor after the oldest of `notes`, or `None` when there are none
+    #[must_use]
+    pub fn after_oldest(notes: &[Note]) -> Option<Self> {
+        oldest_position(notes).map(Self::after)
+    }
+}
+
+/// One page of notes and the cursor for the next page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotePage {
+    pub notes: Vec<Note>,
+    /// `None` once the end of the list is reached
+    pub next_cursor: Option<NoteCursor>,
+}
+
+impl NotePage {
+    /// Build a page from at most `limit` notes fetched after a cursor
+    #[must_use]
+    pub fn from_notes(notes: Vec<Note>, limit: usize) -> Self {
+        let next_cursor = if notes.len() < limit {
+            None
+        } else {
+            notes.last().map(NoteCursor::after)
+        };
+        Self { notes, next_cursor }
+    }
+}
+
+/// Append a page to the loaded notes, skipping notes already present
+pub fn append_note_page(loaded: &mut Vec<Note>, page: Vec<Note>) {
+    let known: HashSet<NoteId> = loaded.iter().map(|note| note.id).collect();
+    loaded.extend(page.into_iter().filter(|note| !known.contains(&note.id)));
+}
+
+/// Replace the first page of loaded notes with a refreshed one, keeping
+/// later pages that sort after it
+#[must_use]
+pub fn merge_first_page(first_page: Vec<Note>, loaded: &[Note]) -> Vec<Note> {
+    let Some(oldest) = oldest_position(&first_page).cloned() else {
+        return first_page;
+    };
+    let refreshed: HashSet<NoteId> = first_page.iter().map(|note| note.id).collect();
+    let mut merged = first_page;
+    merged.extend(
+        loaded
+            .iter()
+            .filter(|note| !refreshed.contains(&note.id))
+            .filter(|note| list_order(note, &oldest) == Ordering::Greater)
+            .cloned(),
+    );
+    merged
+}
+
+/// Oldest note in list order, ignoring pinned notes unless all are pinned
+fn oldest_position(notes: &[Note]) -> Option<&Note> {
+    let oldest = |pinned_too: bool| {
+        notes
+            .iter()
+            .filter(|note| pinned_too || !note.is_pinned)
+            .max_by(|left, right| list_order(left, right))
+    };
+    oldest(false).or_else(|| oldest(true))
+}
+
+/// Newest-first list order: `Less` means `left` is listed before `right`
+fn list_order(left: &Note, right: &Note) -> Ordering {
+    right
+        .updated_at
+        .cmp(&left.updated_at)
+        .then_with(|| right.id.as_str().cmp(&left.id.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(content: &str, updated_at: i64) -> Note {
+        let mut note = Note::new(content);
+        note.created_at = updated_at;
+        note.updated_at = updated_at;
+        note
+    }
+
+    #[test]
+    fn test_page_cursor_ends_on_short_page() {
+        let notes = vec![note("b", 20), note("a", 10)];
+
+        let full = NotePage::from_notes(notes.clone(), 2);
+        assert_eq!(full.next_cursor, Some(NoteCursor::after(&notes[1])));
+
+        let short = NotePage::from_notes(notes, 3);
+        assert_eq!(short.next_cursor, None);
+    }
+
+    #[test]
+    fn test_after_oldest_picks_last_in_list_order() {
+        let notes = vec![note("new", 30), note("old", 10), note("mid", 20)];
+        assert_eq!(
+            NoteCursor::after_oldest(&notes),
+            Some(NoteCursor::after(&notes[1]))
+        );
+        assert_eq!(NoteCursor::after_oldest(&[]), None);
+    }
+
+    #[test]
+    fn test_pinned_notes_do_not_move_the_cursor() {
+        let mut pinned = note("pinned", 5);
+        pinned.is_pinned = true;
+        let notes = vec![note("new", 30), note("old", 10), pinned.clone()];
+        assert_eq!(
+            NoteCursor::after_oldest(&notes),
+            Some(NoteCursor::after(&notes[1]))
+        );
+        assert_eq!(
+            NoteCursor::after_oldest(&[pinned.clone()]),
+            Some(NoteCursor::after(&pinned))
+        );
+
+        // Notes loaded past the first page survive a refresh that brings
+        // an old pinned note along.
+        let later = note("later", 8);
+        let merged = merge_first_page(notes.clone(), &[notes[0].clone(), later.clone()]);
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged[3], later);
+    }
+
+    #[test]
+    fn test_append_note_page_skips_duplicates() {
+        let first = note("first", 20);
+        let second = note("second", 10);
+        let mut loaded = vec![first.clone()];
+
+        append_note_page(&mut loaded, vec![first, second.clone()]);
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[1], second);
+    }
+
+    #[test]
+    fn test_merge_first_page_keeps_older_pages() {
+        let edited = note("edited", 50);
+        let fresh = note("fresh", 40);
+        let tail = note("tail", 10);
+        let mut stale_edited = edited.clone();
+        stale_edited.updated_at = 5;
+        let loaded = vec![fresh.clone(), tail.clone(), stale_edited];
+
+        let merged = merge_first_page(vec![edited.clone(), fresh.clone()], &loaded);
+
+        assert_eq!(merged, vec![edited, fresh, tail]);
+    }
+}
@@ -0,0 +1,57 @@
+//! Public note share link model
+
+use serde::{Deserialize, Serialize};
+
+use super::NoteId;
+
+/// Metadata for a public, read-only link to a note
+///
+/// The signed link token is never stored; only enough to validate, list, and
+/// revoke it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteShare {
+    /// Share identifier (also embedded in the signed link token)
+    pub id: String,
+    /// Owning user (Supabase subject)
+    pub user_id: String,
+    /// Shared note
+    pub note_id: NoteId,
+    /// Creation timestamp (Unix seconds)
+    pub created_at: i64,
+    /// Expiry timestamp (Unix seconds)
+    pub expires_at: i64,
+    /// Revocation timestamp (Unix seconds)
+    pub revoked_at: Option<i64>,
+}
+
+impl NoteShare {
+    /// Whether the link still opens the note at `now` (Unix seconds)
+    #[must_use]
+    pub const fn is_active(&self, now: i64) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_is_inactive_when_revoked_or_expired() {
+        let share = NoteShare {
+            id: "share".to_string(),
+            user_id: "user".to_string(),
+            note_id: NoteId::new(),
+            created_at: 100,
+            expires_at: 200,
+            revoked_at: None,
+        };
+        assert!(share.is_active(150));
+        assert!(!share.is_active(200));
+        assert!(!NoteShare {
+            revoked_at: Some(120),
+            ..share
+        }
+        .is_active(150));
+    }
+}
@@ -0,0 +1,110 @@
+//! Note source attribution
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Search filter prefix selecting notes by source, e.g. `source:cli`.
+pub const SOURCE_FILTER_PREFIX: &str = "source:";
+
+/// Interface a note was captured from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NoteSource {
+    /// Created before sources were recorded, or by an unattributed caller.
+    #[default]
+    Unknown,
+    /// `dirt` command line.
+    Cli,
+    /// Desktop app main window.
+    Desktop,
+    /// Desktop quick-capture hotkey window.
+    DesktopHotkey,
+    /// Text shared into the desktop app through the OS share / Services menu.
+    DesktopShare,
+    /// Mobile app editor.
+    Mobile,
+    /// Text shared into the mobile app from another app.
+    MobileShare,
+    /// Inbound email.
+    EmailIn,
+    /// Notes HTTP API.
+    Api,
+    /// Imported from another notes app.
+    Import,
+}
+
+impl NoteSource {
+    /// All sources, in display order.
+    pub const ALL: [Self; 10] = [
+        Self::Cli,
+        Self::Desktop,
+        Self::DesktopHotkey,
+        Self::DesktopShare,
+        Self::Mobile,
+        Self::MobileShare,
+        Self::EmailIn,
+        Self::Api,
+        Self::Import,
+        Self::Unknown,
+    ];
+
+    /// Storage and filter name of the source.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::Cli => "cli",
+            Self::Desktop => "desktop",
+            Self::DesktopHotkey => "desktop-hotkey",
+            Self::DesktopShare => "desktop-share",
+            Self::Mobile => "mobile",
+            Self::MobileShare => "mobile-share",
+            Self::EmailIn => "email-in",
+            Self::Api => "api",
+            Self::Import => "import",
+        }
+    }
+}
+
+impl fmt::Display for NoteSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NoteSource {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let name = s.trim();
+        Self::ALL
+            .into_iter()
+            .find(|source| source.as_str().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "Unknown note source `{name}` (expected one of: {})",
+                    Self::ALL.map(Self::as_str).join(", ")
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_names_round_trip() {
+        for source in NoteSource::ALL {
+            assert_eq!(source.as_str().parse::<NoteSource>().unwrap(), source);
+        }
+        assert_eq!(
+            "Desktop-Hotkey".parse::<NoteSource>().unwrap(),
+            NoteSource::DesktopHotkey
+        );
+        assert!("fax".parse::<NoteSource>().is_err());
+    }
+}
@@ -0,0 +1,58 @@
+//! Note summary model
+
+use serde::{Deserialize, Serialize};
+
+use super::{Note, NoteId};
+
+/// How a note summary was produced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryMethod {
+    /// Written by the `OpenAI` chat completions API
+    OpenAi,
+    /// Picked from the note's own sentences, without network access
+    Extractive,
+}
+
+impl SummaryMethod {
+    /// Stable identifier stored in the database
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Extractive => "extractive",
+        }
+    }
+
+    /// Parse a stored identifier; unknown values read as extractive
+    #[must_use]
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "openai" => Self::OpenAi,
+            _ => Self::Extractive,
+        }
+    }
+}
+
+/// Short summary of a long note
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteSummary {
+    /// Note the summary describes
+    pub note_id: NoteId,
+    /// Summary text
+    pub summary: String,
+    /// How the summary was produced
+    pub method: SummaryMethod,
+    /// `updated_at` of the note when it was summarized (Unix ms)
+    pub note_updated_at: i64,
+    /// Summary creation timestamp (Unix ms)
+    pub created_at: i64,
+}
+
+impl NoteSummary {
+    /// Whether the summary still describes `note` (it was not edited since)
+    #[must_use]
+    pub fn is_current_for(&self, note: &Note) -> bool {
+        self.note_id == note.id && self.note_updated_at >= note.updated_at
+    }
+}
@@ -0,0 +1,15 @@
+//! URLs captured in note content
+
+use super::Note;
+
+/// Search facet restricting results to notes that link a matching URL
+pub const URL_FILTER_PREFIX: &str = "url:";
+
+/// A URL captured in a note, paired with that note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteUrl {
+    /// The URL as written in the note
+    pub url: String,
+    /// Note the URL appears in
+    pub note: Note,
+}
@@ -0,0 +1,22 @@
+//! Saved versions of a note's content
+
+use serde::{Deserialize, Serialize};
+
+use super::NoteId;
+
+/// Revisions kept per note until the user picks another limit
+pub const DEFAULT_REVISION_RETENTION: u32 = 50;
+
+/// One saved version of a note's content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteRevision {
+    pub note_id: NoteId,
+    /// Per-note number starting at 1; numbers are not reused after pruning
+    pub revision: i64,
+    pub content: String,
+    /// When this content was saved (Unix ms)
+    pub saved_at: i64,
+    /// Device that saved the content (`None` for content saved before
+    /// history was kept)
+    pub device_id: Option<String>,
+}
@@ -2,6 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::{DEFAULT_CAPTURE_HOTKEY, DEFAULT_REVISION_RETENTION};
+use crate::media::DEFAULT_ATTACHMENT_CACHE_MB;
+use crate::transcription::TranscriptionProvider;
+
 /// Theme mode options
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -24,10 +28,70 @@ pub struct Settings {
     pub font_size: u32,
     /// Theme mode
     pub theme: ThemeMode,
-    /// Global capture hotkey (e.g., "Ctrl+Shift+D")
+    /// Global capture hotkey (e.g., "Ctrl+Alt+N"), parsed as a [`super::Hotkey`]
     pub capture_hotkey: String,
     /// Whether newly recorded voice memos should be transcribed automatically.
     pub voice_memo_transcription_enabled: bool,
+    /// Whether the desktop app saves a weekly digest note automatically.
+    pub weekly_digest_enabled: bool,
+    /// Whether to show an OS notification when sync keeps failing.
+    pub notify_sync_failures: bool,
+    /// Whether to show an OS notification when sync records new conflicts.
+    pub notify_sync_conflicts: bool,
+    /// Whether to show an OS notification when a large export finishes.
+    pub notify_export_complete: bool,
+    /// Whether to show an OS notification for reminders.
+    pub notify_reminders: bool,
+    /// Default folder for saving attachments (`None` uses the system Downloads folder)
+    pub attachment_download_dir: Option<String>,
+    /// Whether to open the containing folder after saving an attachment
+    pub open_folder_after_save: bool,
+    /// Tag added to every quick capture (`None` adds no tag)
+    pub capture_default_tag: Option<String>,
+    /// Whether quick captures are linked from today's daily note
+    pub capture_link_daily_note: bool,
+    /// Whether quick captures are appended to today's daily note instead of
+    /// becoming standalone notes
+    pub capture_append_daily_note: bool,
+    /// Whether the id of a quick capture is copied to the clipboard
+    pub capture_copy_note_id: bool,
+    /// URL that receives a JSON POST for every quick capture
+    pub capture_webhook_url: Option<String>,
+    /// Tags whose notes are left out of exports, digests, and shares
+    pub no_export_tags: Vec<String>,
+    /// Tags whose notes only this device can read; synced copies are encrypted
+    /// with a key kept beside the local database
+    pub sync_excluded_tags: Vec<String>,
+    /// Whether EXIF/GPS and other metadata is removed from uploaded images
+    pub image_strip_metadata: bool,
+    /// Longest side, in pixels, of uploaded images (`None` keeps the original size)
+    pub image_max_dimension: Option<u32>,
+    /// Whether note titles and previews are published to Spotlight / Windows Search
+    pub os_search_index_enabled: bool,
+    /// Whether the desktop editor hides everything but the open note
+    pub focus_mode: bool,
+    /// Longest editor line in focus mode, in characters
+    pub focus_line_width: u32,
+    /// Whether focus mode keeps the line being typed centered vertically
+    pub focus_typewriter_scrolling: bool,
+    /// Soft word-count goal shown in focus mode (`None` hides the indicator)
+    pub focus_word_goal: Option<u32>,
+    /// Whether the desktop app is offered in the OS share / Services menu
+    pub share_target_enabled: bool,
+    /// Note count above which a storage warning is shown (`None` never warns)
+    pub note_count_warning: Option<u32>,
+    /// Database size, in MB, above which a storage warning is shown (`None` never warns)
+    pub database_size_warning_mb: Option<u32>,
+    /// Newest Dirt version whose release notes the user was shown (`None` before the first launch)
+    pub last_seen_version: Option<String>,
+    /// Revisions kept per note (`None` keeps every revision)
+    pub revision_retention: Option<u32>,
+    /// Space, in MB, for downloaded attachments kept on this device (`None` disables the cache)
+    pub attachment_cache_mb: Option<u32>,
+    /// Service that transcribes voice memos
+    pub transcription_provider: TranscriptionProvider,
+    /// Downloaded Whisper model used for offline transcription (`None` uses the default model)
+    pub whisper_model: Option<String>,
 }
 
 impl Default for Settings {
@@ -36,8 +100,37 @@ impl Default for Settings {
             font_family: "system-ui".to_string(),
             font_size: 14,
             theme: ThemeMode::System,
-            capture_hotkey: "Ctrl+Shift+D".to_string(),
+            capture_hotkey: DEFAULT_CAPTURE_HOTKEY.to_string(),
             voice_memo_transcription_enabled: false,
+            weekly_digest_enabled: false,
+            notify_sync_failures: true,
+            notify_sync_conflicts: true,
+            notify_export_complete: true,
+            notify_reminders: true,
+            attachment_download_dir: None,
+            open_folder_after_save: false,
+            capture_default_tag: None,
+            capture_link_daily_note: false,
+            capture_append_daily_note: false,
+            capture_copy_note_id: false,
+            capture_webhook_url: None,
+            no_export_tags: Vec::new(),
+            sync_excluded_tags: Vec::new(),
+            image_strip_metadata: false,
+            image_max_dimension: None,
+            os_search_index_enabled: false,
+            focus_mode: false,
+            focus_line_width: 72,
+            focus_typewriter_scrolling: false,
+            focus_word_goal: None,
+            share_target_enabled: false,
+            note_count_warning: Some(10_000),
+            database_size_warning_mb: Some(500),
+            last_seen_version: None,
+            revision_retention: Some(DEFAULT_REVISION_RETENTION),
+            attachment_cache_mb: Some(DEFAULT_ATTACHMENT_CACHE_MB),
+            transcription_provider: TranscriptionProvider::OpenAi,
+            whisper_model: None,
         }
     }
 }
@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Error, Result};
+
 /// Recorded sync conflict resolved by strategy (e.g., LWW)
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SyncConflict {
@@ -17,4 +19,27 @@ pub struct SyncConflict {
     pub resolved_at: i64,
     /// Resolution strategy name
     pub strategy: String,
+    /// Content kept locally; `None` for conflicts recorded before contents
+    /// were retained
+    pub local_content: Option<String>,
+    /// Content of the rejected incoming row; `None` for conflicts recorded
+    /// before contents were retained
+    pub incoming_content: Option<String>,
+}
+
+impl SyncConflict {
+    /// Kept and rejected content as `(local, incoming)`; an error for
+    /// conflicts recorded before contents were retained
+    pub fn versions(&self) -> Result<(&str, &str)> {
+        match (
+            self.local_content.as_deref(),
+            self.incoming_content.as_deref(),
+        ) {
+            (Some(local), Some(incoming)) => Ok((local, incoming)),
+            _ => Err(Error::InvalidInput(format!(
+                "Sync conflict {} was recorded before note versions were kept",
+                self.id
+            ))),
+        }
+    }
 }
@@ -0,0 +1,80 @@
+//! Sync history model
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Approximate size of one replicated WAL frame (4 KiB page + 24-byte header)
+pub const REPLICATION_FRAME_BYTES: i64 = 4096 + 24;
+
+/// Transfer statistics recorded after a successful sync
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncHistoryEntry {
+    /// History row identifier
+    pub id: i64,
+    /// When the sync started (unix ms)
+    pub synced_at: i64,
+    /// How long the sync took
+    pub duration_ms: i64,
+    /// WAL frames pulled from the remote database
+    pub frames_synced: i64,
+    /// Estimated bytes transferred (frames x frame size)
+    pub bytes_transferred: i64,
+    /// Notes created, updated, or deleted by the sync
+    pub rows_changed: i64,
+}
+
+/// Count notes that are new or at a different version after a sync, given
+/// `id -> updated_at` snapshots taken before and after.
+#[must_use]
+pub fn count_changed_rows(before: &HashMap<String, i64>, after: &HashMap<String, i64>) -> usize {
+    after
+        .iter()
+        .filter(|(id, updated_at)| before.get(*id) != Some(*updated_at))
+        .count()
+}
+
+/// Human-readable byte count, e.g. `512 B`, `4.0 KiB`, `1.2 MiB`
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_byte_count(bytes: i64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+
+    if bytes.abs() < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value.abs() < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_new_and_updated_notes() {
+        let before = HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]);
+        let after = HashMap::from([
+            ("a".to_string(), 1),
+            ("b".to_string(), 5),
+            ("c".to_string(), 3),
+        ]);
+        assert_eq!(count_changed_rows(&before, &after), 2);
+        assert_eq!(count_changed_rows(&after, &after), 0);
+    }
+
+    #[test]
+    fn formats_byte_counts() {
+        assert_eq!(format_byte_count(512), "512 B");
+        assert_eq!(format_byte_count(4120), "4.0 KiB");
+        assert_eq!(format_byte_count(3 * 1024 * 1024 / 2), "1.5 MiB");
+    }
+}
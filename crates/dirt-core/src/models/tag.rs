@@ -68,6 +68,90 @@ impl Tag {
     }
 }
 
+/// A partially typed `#tag` around the editor cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagQuery {
+    /// Byte offset of the leading `#`
+    pub start: usize,
+    /// Byte offset just past the last tag character
+    pub end: usize,
+    /// Lowercased text typed after `#` up to the cursor (may be empty)
+    pub prefix: String,
+}
+
+const fn is_tag_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_' || ch == '-'
+}
+
+/// Find the tag being typed at `cursor` (a byte offset into `text`)
+///
+/// Returns `None` when the cursor is not inside a `#tag` token, or when the
+/// typed prefix can never become a valid tag (e.g. `#1`).
+#[must_use]
+pub fn tag_query_at(text: &str, cursor: usize) -> Option<TagQuery> {
+    let mut cursor = cursor.min(text.len());
+    while !text.is_char_boundary(cursor) {
+        cursor -= 1;
+    }
+
+    let before = &text[..cursor];
+    let word_start = before
+        .char_indices()
+        .rev()
+        .take_while(|(_, ch)| is_tag_char(*ch))
+        .last()
+        .map_or(cursor, |(index, _)| index);
+
+    let start = word_start.checked_sub(1)?;
+    if !before[start..].starts_with('#') {
+        return None;
+    }
+
+    let prefix = &text[word_start..cursor];
+    if prefix
+        .chars()
+        .next()
+        .is_some_and(|ch| !ch.is_ascii_alphabetic())
+    {
+        return None;
+    }
+
+    let end = cursor
+        + text[cursor..]
+            .chars()
+            .take_while(|ch| is_tag_char(*ch))
+            .map(char::len_utf8)
+            .sum::<usize>();
+
+    Some(TagQuery {
+        start,
+        end,
+        prefix: prefix.to_lowercase(),
+    })
+}
+
+/// Replace the token described by `query` with `#tag`
+///
+/// A trailing space is inserted unless whitespace already follows. Returns the
+/// updated text and the byte offset where the cursor should be placed.
+#[must_use]
+pub fn apply_tag_completion(text: &str, query: &TagQuery, tag: &str) -> (String, usize) {
+    let rest = &text[query.end..];
+    let needs_space = !rest.starts_with(char::is_whitespace);
+
+    let mut completed = String::with_capacity(text.len() + tag.len() + 2);
+    completed.push_str(&text[..query.start]);
+    completed.push('#');
+    completed.push_str(tag);
+    if needs_space {
+        completed.push(' ');
+    }
+    let cursor = completed.len() + usize::from(!needs_space);
+    completed.push_str(rest);
+
+    (completed, cursor)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,4 +168,52 @@ mod tests {
         let id2 = TagId::new();
         assert_ne!(id1, id2);
     }
+
+    #[test]
+    fn test_tag_query_at_cursor_after_hash() {
+        let query = tag_query_at("Note #", 6).unwrap();
+        assert_eq!(query.start, 5);
+        assert_eq!(query.end, 6);
+        assert_eq!(query.prefix, "");
+    }
+
+    #[test]
+    fn test_tag_query_at_partial_tag() {
+        let text = "Ideas #Pro and more";
+        let query = tag_query_at(text, 10).unwrap();
+        assert_eq!(query.start, 6);
+        assert_eq!(query.end, 10);
+        assert_eq!(query.prefix, "pro");
+
+        // Cursor in the middle of a tag still covers the whole token.
+        let query = tag_query_at(text, 8).unwrap();
+        assert_eq!(query.end, 10);
+        assert_eq!(query.prefix, "p");
+    }
+
+    #[test]
+    fn test_tag_query_at_rejects_non_tags() {
+        assert!(tag_query_at("plain words", 5).is_none());
+        assert!(tag_query_at("issue #1", 8).is_none());
+        assert!(tag_query_at("", 0).is_none());
+        assert!(tag_query_at("#tag done", 9).is_none());
+    }
+
+    #[test]
+    fn test_apply_tag_completion_inserts_space() {
+        let text = "Ideas #pro";
+        let query = tag_query_at(text, text.len()).unwrap();
+        let (completed, cursor) = apply_tag_completion(text, &query, "programming");
+        assert_eq!(completed, "Ideas #programming ");
+        assert_eq!(cursor, completed.len());
+    }
+
+    #[test]
+    fn test_apply_tag_completion_keeps_existing_whitespace() {
+        let text = "#wo today";
+        let query = tag_query_at(text, 3).unwrap();
+        let (completed, cursor) = apply_tag_completion(text, &query, "work");
+        assert_eq!(completed, "#work today");
+        assert_eq!(cursor, 6);
+    }
 }
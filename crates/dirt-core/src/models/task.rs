@@ -0,0 +1,137 @@
+//! Checkbox tasks inside notes (`- [ ]` and `- [x]` list items)
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// A task list item in note content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoteTask {
+    /// Zero-based line of the task in the note content
+    pub line_index: usize,
+    /// Whether the box is checked
+    pub done: bool,
+    /// Task text after the checkbox
+    pub text: String,
+}
+
+/// Extract `- [ ]` / `- [x]` task lines from text, in order
+///
+/// Bullets `-`, `*`, `+` and numbered items are recognized, at any
+/// indentation.
+///
+/// # Examples
+///
+/// ```
+/// use dirt_core::models::extract_tasks;
+///
+/// let tasks = extract_tasks("Groceries\n- [ ] milk\n- [x] eggs");
+/// assert_eq!(tasks.len(), 2);
+/// assert_eq!((tasks[0].line_index, tasks[0].done), (1, false));
+/// assert_eq!(tasks[1].text, "eggs");
+/// ```
+#[must_use]
+pub fn extract_tasks(text: &str) -> Vec<NoteTask> {
+    let pattern = task_pattern();
+    text.split('\n')
+        .enumerate()
+        .filter_map(|(line_index, line)| {
+            let captures = pattern.captures(line)?;
+            Some(NoteTask {
+                line_index,
+                done: &captures[1] != " ",
+                text: captures
+                    .get(2)
+                    .map_or_else(String::new, |text| text.as_str().to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Check or uncheck the task on `line_index`, leaving every other byte of
+/// `content` as it was
+///
+/// Returns `None` when that line is not a task.
+///
+/// # Examples
+///
+/// ```
+/// use dirt_core::models::toggle_task_line;
+///
+/// let content = "Groceries\n- [ ] milk";
+/// assert_eq!(toggle_task_line(content, 1).as_deref(), Some("Groceries\n- [x] milk"));
+/// assert_eq!(toggle_task_line(content, 0), None);
+/// ```
+#[must_use]
+pub fn toggle_task_line(content: &str, line_index: usize) -> Option<String> {
+    let line_start: usize = content
+        .split('\n')
+        .take(line_index)
+        .map(|line| line.len() + 1)
+        .sum();
+    let line = content.get(line_start..)?.split('\n').next()?;
+    let mark = task_pattern().captures(line)?.get(1)?;
+    let replacement = if mark.as_str() == " " { "x" } else { " " };
+
+    let mark_start = line_start + mark.start();
+    let mut toggled = String::with_capacity(content.len());
+    toggled.push_str(&content[..mark_start]);
+    toggled.push_str(replacement);
+    toggled.push_str(&content[mark_start + mark.len()..]);
+    Some(toggled)
+}
+
+/// Matches a task list item; group 1 is the box mark, group 2 the text.
+fn task_pattern() -> Regex {
+    Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+\[([ xX])\](?:\s+(.*?))?\s*$").expect("Invalid regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_tasks() {
+        let tasks = extract_tasks(
+            "Plan\n- [ ] first\r\n  * [X] nested\n1. [ ] numbered\n- [] not a task\n- [ ]\n[ ] bare",
+        );
+        assert_eq!(
+            tasks,
+            vec![
+                NoteTask {
+                    line_index: 1,
+                    done: false,
+                    text: "first".to_string(),
+                },
+                NoteTask {
+                    line_index: 2,
+                    done: true,
+                    text: "nested".to_string(),
+                },
+                NoteTask {
+                    line_index: 3,
+                    done: false,
+                    text: "numbered".to_string(),
+                },
+                NoteTask {
+                    line_index: 5,
+                    done: false,
+                    text: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toggle_task_line() {
+        let content = "Plan\r\n- [ ] first\r\n  - [X] nested";
+        let toggled = toggle_task_line(content, 1).unwrap();
+        assert_eq!(toggled, "Plan\r\n- [x] first\r\n  - [X] nested");
+        assert_eq!(
+            toggle_task_line(&toggled, 2).unwrap(),
+            "Plan\r\n- [x] first\r\n  - [ ] nested"
+        );
+
+        assert_eq!(toggle_task_line(content, 0), None);
+        assert_eq!(toggle_task_line(content, 3), None);
+    }
+}
@@ -0,0 +1,215 @@
+//! Structured capture templates
+//!
+//! A template is a named set of fields (e.g. a meeting's attendees, decisions,
+//! and actions). Filled-in fields are rendered into the note body under the
+//! template name, and the template's tag is appended so templated notes can be
+//! found by type.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// A field of a capture template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateField {
+    /// Key used on the command line, e.g. `attendees`
+    pub key: String,
+    /// Label shown in forms and rendered in the note
+    pub label: String,
+    /// Whether the field takes several lines (rendered as a list)
+    pub multiline: bool,
+}
+
+/// A structured capture template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureTemplate {
+    /// Identifier, e.g. `meeting`
+    pub id: String,
+    /// Display name, used as the note's first line
+    pub name: String,
+    /// Tag added to every note created from this template
+    pub tag: String,
+    pub fields: Vec<TemplateField>,
+}
+
+impl CaptureTemplate {
+    fn new(id: &str, name: &str, fields: &[(&str, &str, bool)]) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            tag: id.to_string(),
+            fields: fields
+                .iter()
+                .map(|(key, label, multiline)| TemplateField {
+                    key: (*key).to_string(),
+                    label: (*label).to_string(),
+                    multiline: *multiline,
+                })
+                .collect(),
+        }
+    }
+
+    /// Look up a field by key (case-insensitive)
+    #[must_use]
+    pub fn field(&self, key: &str) -> Option<&TemplateField> {
+        let key = key.trim();
+        self.fields
+            .iter()
+            .find(|field| field.key.eq_ignore_ascii_case(key))
+    }
+
+    /// Render field values into note content.
+    ///
+    /// Empty fields are left out; unknown keys are rejected. Multiline fields
+    /// become a bullet list with one item per non-empty line.
+    pub fn render(&self, values: &[(String, String)]) -> Result<String> {
+        for (key, _) in values {
+            if self.field(key).is_none() {
+                return Err(Error::InvalidInput(format!(
+                    "Template '{}' has no field '{key}' (expected one of: {})",
+                    self.id,
+                    self.field_keys().join(", ")
+                )));
+            }
+        }
+
+        let mut lines = vec![self.name.clone()];
+        for field in &self.fields {
+            let value = values
+                .iter()
+                .filter(|(key, _)| field.key.eq_ignore_ascii_case(key.trim()))
+                .map(|(_, value)| value.trim())
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if value.is_empty() {
+                continue;
+            }
+
+            if field.multiline {
+                lines.push(format!("{}:", field.label));
+                lines.extend(
+                    value
+                        .lines()
+                        .map(|line| line.trim().trim_start_matches("- ").trim())
+                        .filter(|line| !line.is_empty())
+                        .map(|line| format!("- {line}")),
+                );
+            } else {
+                lines.push(format!("{}: {}", field.label, value.replace('\n', " ")));
+            }
+        }
+        lines.push(String::new());
+        lines.push(format!("#{}", self.tag));
+        Ok(lines.join("\n"))
+    }
+
+    fn field_keys(&self) -> Vec<&str> {
+        self.fields.iter().map(|field| field.key.as_str()).collect()
+    }
+}
+
+/// Templates shipped with Dirt, in picker order
+#[must_use]
+pub fn builtin_templates() -> Vec<CaptureTemplate> {
+    vec![
+        CaptureTemplate::new(
+            "meeting",
+            "Meeting",
+            &[
+                ("attendees", "Attendees", false),
+                ("decisions", "Decisions", true),
+                ("actions", "Actions", true),
+            ],
+        ),
+        CaptureTemplate::new(
+            "standup",
+            "Standup",
+            &[
+                ("done", "Done", true),
+                ("next", "Next", true),
+                ("blockers", "Blockers", true),
+            ],
+        ),
+        CaptureTemplate::new(
+            "reading",
+            "Reading",
+            &[
+                ("title", "Title", false),
+                ("author", "Author", false),
+                ("takeaways", "Takeaways", true),
+            ],
+        ),
+    ]
+}
+
+/// Find a built-in template by id (case-insensitive)
+#[must_use]
+pub fn find_template(id: &str) -> Option<CaptureTemplate> {
+    let id = id.trim();
+    builtin_templates()
+        .into_iter()
+        .find(|template| template.id.eq_ignore_ascii_case(id))
+}
+
+/// Parse a `key=value` field assignment
+pub fn parse_field_assignment(assignment: &str) -> Result<(String, String)> {
+    let (key, value) = assignment.split_once('=').ok_or_else(|| {
+        Error::InvalidInput(format!("Invalid field '{assignment}' (expected key=value)"))
+    })?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "Invalid field '{assignment}' (missing key)"
+        )));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Note;
+
+    fn values(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn render_meeting_template() {
+        let template = find_template("Meeting").unwrap();
+        let content = template
+            .render(&values(&[
+                ("attendees", "Ana, Bo"),
+                ("actions", "Send notes\n- Book room"),
+                ("actions", "Follow up"),
+            ]))
+            .unwrap();
+
+        assert_eq!(
+            content,
+            "Meeting\nAttendees: Ana, Bo\nActions:\n- Send notes\n- Book room\n- Follow up\n\n#meeting"
+        );
+        assert_eq!(Note::new(content).tags(), vec!["meeting".to_string()]);
+    }
+
+    #[test]
+    fn render_rejects_unknown_fields() {
+        let template = find_template("standup").unwrap();
+        let error = template.render(&values(&[("mood", "good")])).unwrap_err();
+        assert!(matches!(error, Error::InvalidInput(message) if message.contains("mood")));
+    }
+
+    #[test]
+    fn parse_field_assignment_splits_on_first_equals() {
+        assert_eq!(
+            parse_field_assignment("title=a=b").unwrap(),
+            ("title".to_string(), "a=b".to_string())
+        );
+        assert!(parse_field_assignment("title").is_err());
+        assert!(parse_field_assignment("=x").is_err());
+    }
+}
@@ -0,0 +1,42 @@
+//! Deleted notes kept in the trash until they are purged
+
+use serde::Serialize;
+
+use super::Attachment;
+
+/// Days a deleted note stays restorable before `dirt trash empty` removes it
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// What purging the trash removed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TrashPurge {
+    /// Notes permanently removed
+    pub notes: u64,
+    /// Attachments of those notes, whose stored files can now be deleted
+    pub attachments: Vec<Attachment>,
+}
+
+/// Deletion time (Unix ms) before which trashed notes are older than
+/// `days` at `now` (Unix ms); `0` days covers every trashed note.
+#[must_use]
+pub fn trash_cutoff(now: i64, days: u32) -> i64 {
+    if days == 0 {
+        return i64::MAX;
+    }
+    now.saturating_sub(i64::from(days).saturating_mul(MILLIS_PER_DAY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cutoff_counts_whole_days_back() {
+        let now = 10 * MILLIS_PER_DAY;
+        assert_eq!(trash_cutoff(now, 3), 7 * MILLIS_PER_DAY);
+        assert_eq!(trash_cutoff(now, 0), i64::MAX);
+        assert!(trash_cutoff(0, u32::MAX) < 0);
+    }
+}
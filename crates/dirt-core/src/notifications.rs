@@ -0,0 +1,237 @@
+//! Notifications for events that need attention while a client runs in the
+//! background: persistent sync failures, new sync conflicts, finished
+//! exports, and reminders.
+//!
+//! Clients decide what is worth reporting with the helpers here and deliver
+//! it through a [`NotificationSink`]. Desktop shows OS notifications; mobile
+//! sends them over a [`tokio::sync::mpsc`] channel to its UI. Each
+//! [`NotificationCategory`] is toggled separately in [`Settings`].
+
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::models::{Settings, SyncConflict};
+
+/// Consecutive failed sync attempts before a failure is reported.
+pub const SYNC_FAILURE_NOTIFY_THRESHOLD: u32 = 3;
+
+/// Most recent conflicts checked for new entries after each sync.
+pub const SYNC_CONFLICT_NOTIFY_LIMIT: usize = 50;
+
+/// Exports with at least this many notes notify when they finish.
+pub const LARGE_EXPORT_NOTE_COUNT: usize = 500;
+
+/// Kinds of notifications, each toggled separately in Settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationCategory {
+    SyncFailure,
+    SyncConflict,
+    ExportComplete,
+    Reminder,
+}
+
+impl NotificationCategory {
+    /// Every category, in the order settings list them.
+    pub const ALL: [Self; 4] = [
+        Self::SyncFailure,
+        Self::SyncConflict,
+        Self::ExportComplete,
+        Self::Reminder,
+    ];
+
+    /// Short name shown next to the category's toggle.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::SyncFailure => "Sync failures",
+            Self::SyncConflict => "Conflicts",
+            Self::ExportComplete => "Large exports",
+            Self::Reminder => "Reminders",
+        }
+    }
+
+    /// Whether the user has this category enabled.
+    #[must_use]
+    pub const fn enabled_in(self, settings: &Settings) -> bool {
+        match self {
+            Self::SyncFailure => settings.notify_sync_failures,
+            Self::SyncConflict => settings.notify_sync_conflicts,
+            Self::ExportComplete => settings.notify_export_complete,
+            Self::Reminder => settings.notify_reminders,
+        }
+    }
+
+    /// Turn this category on or off in `settings`.
+    pub const fn set_enabled(self, settings: &mut Settings, enabled: bool) {
+        match self {
+            Self::SyncFailure => settings.notify_sync_failures = enabled,
+            Self::SyncConflict => settings.notify_sync_conflicts = enabled,
+            Self::ExportComplete => settings.notify_export_complete = enabled,
+            Self::Reminder => settings.notify_reminders = enabled,
+        }
+    }
+}
+
+/// A notification waiting to be shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub category: NotificationCategory,
+    pub summary: String,
+    pub body: String,
+}
+
+impl Notification {
+    #[must_use]
+    pub fn new(
+        category: NotificationCategory,
+        summary: impl Into<String>,
+        body: impl Into<String>,
+    ) -> Self {
+        Self {
+            category,
+            summary: summary.into(),
+            body: body.into(),
+        }
+    }
+}
+
+/// Where a client shows notifications.
+pub trait NotificationSink {
+    /// Show `notification`. Delivery failures are logged, not returned.
+    fn deliver(&self, notification: Notification);
+}
+
+/// Forwards notifications to a UI that drains the channel.
+impl NotificationSink for UnboundedSender<Notification> {
+    fn deliver(&self, notification: Notification) {
+        if self.send(notification).is_err() {
+            tracing::debug!("Notification dropped: no receiver");
+        }
+    }
+}
+
+/// Deliver `notification` through `sink` if its category is enabled in
+/// `settings`; returns whether it was delivered.
+pub fn notify(
+    sink: &impl NotificationSink,
+    settings: &Settings,
+    notification: Notification,
+) -> bool {
+    if !notification.category.enabled_in(settings) {
+        return false;
+    }
+    sink.deliver(notification);
+    true
+}
+
+/// Counts consecutive sync failures so only persistent ones are reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncFailureTracker {
+    consecutive_failures: u32,
+    notified: bool,
+}
+
+impl SyncFailureTracker {
+    /// Record a failed attempt; returns `true` once per failure streak, when
+    /// the streak reaches [`SYNC_FAILURE_NOTIFY_THRESHOLD`].
+    pub const fn record_failure(&mut self) -> bool {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.notified || self.consecutive_failures < SYNC_FAILURE_NOTIFY_THRESHOLD {
+            return false;
+        }
+        self.notified = true;
+        true
+    }
+
+    /// Record a successful sync, ending the current failure streak.
+    pub fn record_success(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Count conflicts recorded after the one with `last_seen_id`.
+#[must_use]
+pub fn new_conflict_count(conflicts: &[SyncConflict], last_seen_id: i64) -> usize {
+    conflicts
+        .iter()
+        .filter(|conflict| conflict.id > last_seen_id)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conflict(id: i64) -> SyncConflict {
+        SyncConflict {
+            id,
+            note_id: format!("note-{id}"),
+            local_updated_at: 1,
+            incoming_updated_at: 2,
+            resolved_at: 3,
+            strategy: "lww".to_string(),
+            local_content: None,
+            incoming_content: None,
+        }
+    }
+
+    #[test]
+    fn failure_tracker_notifies_once_per_streak() {
+        let mut tracker = SyncFailureTracker::default();
+        assert!(!tracker.record_failure());
+        assert!(!tracker.record_failure());
+        assert!(tracker.record_failure());
+        assert!(!tracker.record_failure());
+
+        tracker.record_success();
+        assert!(!tracker.record_failure());
+        assert!(!tracker.record_failure());
+        assert!(tracker.record_failure());
+    }
+
+    #[test]
+    fn counts_only_conflicts_after_last_seen() {
+        let conflicts = vec![conflict(7), conflict(5), conflict(3)];
+        assert_eq!(new_conflict_count(&conflicts, 5), 1);
+        assert_eq!(new_conflict_count(&conflicts, 7), 0);
+        assert_eq!(new_conflict_count(&conflicts, 0), 3);
+    }
+
+    #[test]
+    fn categories_follow_settings_toggles() {
+        let mut settings = Settings {
+            notify_sync_conflicts: false,
+            ..Settings::default()
+        };
+        assert!(NotificationCategory::SyncFailure.enabled_in(&settings));
+        assert!(!NotificationCategory::SyncConflict.enabled_in(&settings));
+        assert!(NotificationCategory::ExportComplete.enabled_in(&settings));
+        assert!(NotificationCategory::Reminder.enabled_in(&settings));
+
+        NotificationCategory::Reminder.set_enabled(&mut settings, false);
+        assert!(!settings.notify_reminders);
+    }
+
+    #[test]
+    fn channel_sink_receives_only_enabled_notifications() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let settings = Settings {
+            notify_export_complete: false,
+            ..Settings::default()
+        };
+
+        let failure = Notification::new(
+            NotificationCategory::SyncFailure,
+            "Dirt sync is failing",
+            "offline",
+        );
+        assert!(notify(&sender, &settings, failure.clone()));
+        assert!(!notify(
+            &sender,
+            &settings,
+            Notification::new(NotificationCategory::ExportComplete, "Export finished", ""),
+        ));
+
+        assert_eq!(receiver.try_recv().ok(), Some(failure));
+        assert!(receiver.try_recv().is_err());
+    }
+}
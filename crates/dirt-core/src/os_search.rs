@@ -0,0 +1,311 @@
+//! Publishing notes to the operating system's search index
+//!
+//! Each note becomes one small file that the platform's launcher search picks
+//! up: a Spotlight metadata file on macOS and an internet shortcut on Windows.
+//! The files carry the note title, a short preview, and a `dirt://note/<id>`
+//! link, never the full note. Which files to rewrite is decided with the same
+//! cursor-based plan as mirrored exports, so only edited notes are touched.
+
+use std::fmt::Write as _;
+
+use crate::models::{Note, NoteId};
+use crate::transfer::NOTE_TRANSFER_URI_PREFIX;
+
+/// Characters of the first line used as the entry title
+const TITLE_CHARS: usize = 80;
+
+/// Characters of the remaining content used as the entry preview
+const PREVIEW_CHARS: usize = 280;
+
+/// Name of the file holding the incremental cursor inside an index folder.
+pub const OS_SEARCH_CURSOR_FILE: &str = ".dirt-index-cursor";
+
+/// File format understood by a platform's search indexer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsSearchFormat {
+    /// Property list read by a Spotlight metadata importer (`.dirtnote`)
+    Spotlight,
+    /// Internet shortcut indexed by Windows Search (`.url`)
+    WindowsShortcut,
+}
+
+impl OsSearchFormat {
+    /// Format for the platform this binary was built for, if it has one.
+    #[must_use]
+    pub const fn current() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(Self::Spotlight)
+        } else if cfg!(target_os = "windows") {
+            Some(Self::WindowsShortcut)
+        } else {
+            None
+        }
+    }
+
+    /// File extension of index entries, without the dot.
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Spotlight => "dirtnote",
+            Self::WindowsShortcut => "url",
+        }
+    }
+}
+
+/// Title, preview, and link published for one note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OsSearchEntry {
+    pub note_id: NoteId,
+    pub title: String,
+    pub preview: String,
+    pub updated_at: i64,
+}
+
+impl OsSearchEntry {
+    /// Build the entry for a note.
+    #[must_use]
+    pub fn from_note(note: &Note) -> Self {
+        let title = note.title_preview(TITLE_CHARS).trim().to_string();
+        let preview = note
+            .content
+            .lines()
+            .skip(1)
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(PREVIEW_CHARS)
+            .collect();
+        Self {
+            note_id: note.id,
+            title: if title.is_empty() {
+                "Untitled note".to_string()
+            } else {
+                title
+            },
+            preview,
+            updated_at: note.updated_at,
+        }
+    }
+
+    /// Link that opens the note in Dirt.
+    #[must_use]
+    pub fn uri(&self) -> String {
+        format!("{NOTE_TRANSFER_URI_PREFIX}{}", self.note_id)
+    }
+
+    /// File name of the entry inside the index folder.
+    ///
+    /// Windows Search matches shortcuts by file name, so the title leads the
+    /// name there; Spotlight reads the display name from the file itself.
+    /// Both end in the note id so [`note_id_from_file_name`] can map files
+    /// back to notes.
+    #[must_use]
+    pub fn file_name(&self, format: OsSearchFormat) -> String {
+        match format {
+            OsSearchFormat::Spotlight => format!("{}.{}", self.note_id, format.extension()),
+            OsSearchFormat::WindowsShortcut => format!(
+                "{} - {}.{}",
+                safe_file_stem(&self.title),
+                self.note_id,
+                format.extension()
+            ),
+        }
+    }
+
+    /// Render the entry file contents.
+    #[must_use]
+    pub fn render(&self, format: OsSearchFormat) -> String {
+        match format {
+            OsSearchFormat::Spotlight => self.render_spotlight(),
+            OsSearchFormat::WindowsShortcut => self.render_shortcut(),
+        }
+    }
+
+    fn render_spotlight(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+            "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+            "<plist version=\"1.0\">\n<dict>\n",
+        ));
+        for (key, value) in [
+            ("kMDItemDisplayName", self.title.as_str()),
+            ("kMDItemTextContent", self.preview.as_str()),
+            ("kMDItemURL", self.uri().as_str()),
+            ("dirtNoteId", self.note_id.to_string().as_str()),
+        ] {
+            let _ = writeln!(
+                out,
+                "  <key>{key}</key>\n  <string>{}</string>",
+                escape_xml(value)
+            );
+        }
+        let _ = writeln!(
+            out,
+            "  <key>dirtUpdatedAt</key>\n  <integer>{}</integer>",
+            self.updated_at
+        );
+        out.push_str("</dict>\n</plist>\n");
+        out
+    }
+
+    fn render_shortcut(&self) -> String {
+        let mut out = format!("[InternetShortcut]\r\nURL={}\r\n", self.uri());
+        if !self.preview.is_empty() {
+            // `Comment` is shown as the tooltip and indexed as the description.
+            out.push_str("Comment=");
+            out.push_str(&self.preview);
+            out.push_str("\r\n");
+        }
+        out
+    }
+}
+
+/// Note id of an index entry file, or `None` for files Dirt did not write.
+#[must_use]
+pub fn note_id_from_file_name(file_name: &str, format: OsSearchFormat) -> Option<NoteId> {
+    let stem = file_name.strip_suffix(&format!(".{}", format.extension()))?;
+    let id = match format {
+        OsSearchFormat::Spotlight => stem,
+        OsSearchFormat::WindowsShortcut => stem.rsplit_once(" - ")?.1,
+    };
+    id.parse().ok()
+}
+
+/// Windows `.searchConnector-ms` description that adds `location` to the
+/// Start menu search scope.
+#[must_use]
+pub fn render_windows_search_connector(location: &str) -> String {
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\r\n",
+            "<searchConnectorDescription ",
+            "xmlns=\"http://schemas.microsoft.com/windows/2009/searchConnector\">\r\n",
+            "  <description>Dirt notes</description>\r\n",
+            "  <isSearchOnlyItem>false</isSearchOnlyItem>\r\n",
+            "  <includeInStartMenuScope>true</includeInStartMenuScope>\r\n",
+            "  <simpleLocation>\r\n",
+            "    <url>{}</url>\r\n",
+            "  </simpleLocation>\r\n",
+            "</searchConnectorDescription>\r\n",
+        ),
+        escape_xml(location)
+    )
+}
+
+/// Keep a title usable as a file name on every platform.
+fn safe_file_stem(title: &str) -> String {
+    let stem: String = title
+        .chars()
+        .map(|ch| match ch {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            ch if ch.is_control() => ' ',
+            ch => ch,
+        })
+        .collect();
+    let stem = stem.trim().trim_end_matches('.').to_string();
+    if stem.is_empty() {
+        "Untitled note".to_string()
+    } else {
+        stem
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            // Control characters other than tab/newline are invalid in XML 1.0.
+            ch if ch.is_control() && ch != '\t' && ch != '\n' => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(content: &str) -> Note {
+        Note {
+            id: "cccccccc-cccc-7ccc-8ccc-111111111111".parse().unwrap(),
+            content: content.to_string(),
+            created_at: 100,
+            updated_at: 200,
+            is_deleted: false,
+            is_pinned: false,
+            is_archived: false,
+        }
+    }
+
+    #[test]
+    fn entry_uses_first_line_as_title_and_rest_as_preview() {
+        let entry = OsSearchEntry::from_note(&note("Trip plan\n\n- book train\n- pack"));
+        assert_eq!(entry.title, "Trip plan");
+        assert_eq!(entry.preview, "- book train - pack");
+        assert_eq!(
+            entry.uri(),
+            "dirt://note/cccccccc-cccc-7ccc-8ccc-111111111111"
+        );
+
+        assert_eq!(
+            OsSearchEntry::from_note(&note("  \nbody")).title,
+            "Untitled note"
+        );
+    }
+
+    #[test]
+    fn file_names_round_trip_to_note_ids() {
+        let entry = OsSearchEntry::from_note(&note("Q3: plans/ideas?"));
+
+        let shortcut = entry.file_name(OsSearchFormat::WindowsShortcut);
+        assert_eq!(
+            shortcut,
+            "Q3_ plans_ideas_ - cccccccc-cccc-7ccc-8ccc-111111111111.url"
+        );
+        let spotlight = entry.file_name(OsSearchFormat::Spotlight);
+        assert_eq!(spotlight, "cccccccc-cccc-7ccc-8ccc-111111111111.dirtnote");
+
+        for (name, format) in [
+            (shortcut.as_str(), OsSearchFormat::WindowsShortcut),
+            (spotlight.as_str(), OsSearchFormat::Spotlight),
+        ] {
+            assert_eq!(note_id_from_file_name(name, format), Some(entry.note_id));
+        }
+        assert_eq!(
+            note_id_from_file_name("notes.url", OsSearchFormat::WindowsShortcut),
+            None
+        );
+        assert_eq!(
+            note_id_from_file_name(OS_SEARCH_CURSOR_FILE, OsSearchFormat::Spotlight),
+            None
+        );
+    }
+
+    #[test]
+    fn render_escapes_spotlight_values_and_writes_shortcut_links() {
+        let entry = OsSearchEntry::from_note(&note("Fish & <chips>\nwith \"salt\""));
+
+        let plist = entry.render(OsSearchFormat::Spotlight);
+        assert!(plist.contains("<string>Fish &amp; &lt;chips&gt;</string>"));
+        assert!(plist.contains("<string>with &quot;salt&quot;</string>"));
+        assert!(plist.contains("<integer>200</integer>"));
+
+        let shortcut = entry.render(OsSearchFormat::WindowsShortcut);
+        assert!(shortcut.starts_with(
+            "[InternetShortcut]\r\nURL=dirt://note/cccccccc-cccc-7ccc-8ccc-111111111111\r\n"
+        ));
+        assert!(shortcut.contains("Comment=with \"salt\"\r\n"));
+
+        let connector = render_windows_search_connector(r"C:\Users\R&D\Dirt");
+        assert!(connector.contains(r"<url>C:\Users\R&amp;D\Dirt</url>"));
+        assert!(connector.contains("<includeInStartMenuScope>true</includeInStartMenuScope>"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Soft storage quotas
+//!
+//! A local-only database grows without bound, and full-text search slows down
+//! with it. These checks warn when the database crosses the size or
+//! note-count thresholds from [`Settings`], before search gets noticeably
+//! slower. Nothing is ever blocked; the warnings only point at the
+//! maintenance actions that shrink the database again.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{format_byte_count, Settings};
+
+const BYTES_PER_MB: i64 = 1024 * 1024;
+
+/// Maintenance actions suggested alongside quota warnings.
+pub const QUOTA_MAINTENANCE_HINTS: [&str; 3] = [
+    "Archive old notes with `dirt export` and remove them with `dirt delete --tag <tag>`.",
+    "Reclaim the space of deleted notes with `dirt maintain --purge-deleted`.",
+    "Adjust or turn off these warnings with `dirt maintain --note-limit` / `--size-limit-mb`.",
+];
+
+/// Size and note counts of the local database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageUsage {
+    /// Bytes allocated by the database file
+    pub database_bytes: i64,
+    /// Notes that are not deleted
+    pub notes: i64,
+    /// Deleted notes still stored until they are purged
+    pub deleted_notes: i64,
+}
+
+/// A soft quota the database has crossed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaWarning {
+    /// More notes than the note-count threshold
+    NoteCount { notes: i64, limit: u32 },
+    /// Database file larger than the size threshold
+    DatabaseSize { bytes: i64, limit_mb: u32 },
+}
+
+impl fmt::Display for QuotaWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoteCount { notes, limit } => {
+                write!(f, "{notes} notes exceed the warning threshold of {limit}")
+            }
+            Self::DatabaseSize { bytes, limit_mb } => write!(
+                f,
+                "The database uses {}, over the warning threshold of {limit_mb} MB",
+                format_byte_count(*bytes)
+            ),
+        }
+    }
+}
+
+/// Quotas `usage` crosses under the thresholds in `settings`.
+///
+/// Deleted notes count towards the size but not the note count, since they
+/// no longer show up in lists or search results.
+#[must_use]
+pub fn quota_warnings(usage: &StorageUsage, settings: &Settings) -> Vec<QuotaWarning> {
+    let mut warnings = Vec::new();
+    if let Some(limit) = settings.note_count_warning {
+        if usage.notes > i64::from(limit) {
+            warnings.push(QuotaWarning::NoteCount {
+                notes: usage.notes,
+                limit,
+            });
+        }
+    }
+    if let Some(limit_mb) = settings.database_size_warning_mb {
+        if usage.database_bytes > i64::from(limit_mb) * BYTES_PER_MB {
+            warnings.push(QuotaWarning::DatabaseSize {
+                bytes: usage.database_bytes,
+                limit_mb,
+            });
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quota_warnings_respect_thresholds() {
+        let settings = Settings {
+            note_count_warning: Some(100),
+            database_size_warning_mb: Some(2),
+            ..Settings::default()
+        };
+        let within = StorageUsage {
+            database_bytes: 2 * BYTES_PER_MB,
+            notes: 100,
+            deleted_notes: 400,
+        };
+        assert!(quota_warnings(&within, &settings).is_empty());
+
+        let over = StorageUsage {
+            database_bytes: 3 * BYTES_PER_MB,
+            notes: 101,
+            deleted_notes: 0,
+        };
+        let warnings = quota_warnings(&over, &settings);
+        assert_eq!(
+            warnings,
+            [
+                QuotaWarning::NoteCount {
+                    notes: 101,
+                    limit: 100
+                },
+                QuotaWarning::DatabaseSize {
+                    bytes: 3 * BYTES_PER_MB,
+                    limit_mb: 2
+                },
+            ]
+        );
+        assert_eq!(
+            warnings[1].to_string(),
+            "The database uses 3.0 MiB, over the warning threshold of 2 MB"
+        );
+
+        let disabled = Settings {
+            note_count_warning: None,
+            database_size_warning_mb: None,
+            ..Settings::default()
+        };
+        assert!(quota_warnings(&over, &disabled).is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+//! Routing rules applied to new and edited notes
+//!
+//! A rule pairs a condition with an action, e.g. "notes mentioning #work also
+//! get #office" or "notes that arrived by email get #inbox". Dirt files notes
+//! by tag rather than into notebooks, so routing a note means tagging it.
+//!
+//! Rules run in the order they were added, each one seeing the tags earlier
+//! rules added, and only ever add tags that are missing. Running them again on
+//! a routed note therefore changes nothing.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::capture::{content_with_tag, normalize_capture_tag};
+use crate::error::{Error, Result};
+use crate::models::{extract_tags, NoteSource, SOURCE_FILTER_PREFIX};
+
+/// Prefix of a text condition, e.g. `contains:invoice`.
+pub const CONTAINS_PREFIX: &str = "contains:";
+
+/// When a rule applies to a note.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "value")]
+pub enum RuleCondition {
+    /// The note carries the tag (`#work`)
+    Tag(String),
+    /// The note was captured from the source (`source:email-in`)
+    Source(NoteSource),
+    /// The note contains the text, ignoring case (`contains:invoice`)
+    Contains(String),
+}
+
+impl RuleCondition {
+    /// Whether a note with `content` and `tags`, captured from `source`,
+    /// satisfies the condition.
+    #[must_use]
+    pub fn matches(&self, content: &str, tags: &[String], source: Option<NoteSource>) -> bool {
+        match self {
+            Self::Tag(tag) => tags.iter().any(|existing| existing == tag),
+            Self::Source(expected) => source == Some(*expected),
+            Self::Contains(text) => content.to_lowercase().contains(&text.to_lowercase()),
+        }
+    }
+}
+
+impl fmt::Display for RuleCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag(tag) => write!(f, "#{tag}"),
+            Self::Source(source) => write!(f, "{SOURCE_FILTER_PREFIX}{source}"),
+            Self::Contains(text) => write!(f, "{CONTAINS_PREFIX}{text}"),
+        }
+    }
+}
+
+impl FromStr for RuleCondition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let raw = s.trim();
+        if let Some(source) = strip_prefix_ignore_case(raw, SOURCE_FILTER_PREFIX) {
+            return Ok(Self::Source(source.parse()?));
+        }
+        if let Some(text) = strip_prefix_ignore_case(raw, CONTAINS_PREFIX) {
+            let text = text.trim();
+            if text.is_empty() {
+                return Err(Error::InvalidInput(
+                    "`contains:` needs the text to look for".to_string(),
+                ));
+            }
+            return Ok(Self::Contains(text.to_string()));
+        }
+        if raw.starts_with('#') {
+            return parse_tag(raw).map(Self::Tag);
+        }
+        Err(Error::InvalidInput(format!(
+            "Unknown rule condition `{raw}` (expected #tag, {SOURCE_FILTER_PREFIX}<name>, or {CONTAINS_PREFIX}<text>)"
+        )))
+    }
+}
+
+/// What a rule does to a matching note.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "value")]
+pub enum RuleAction {
+    /// Add the tag when the note does not carry it yet (`#inbox`)
+    AddTag(String),
+}
+
+impl RuleAction {
+    /// `content` after the action, or `None` when it changes nothing.
+    #[must_use]
+    pub fn apply(&self, content: &str) -> Option<String> {
+        match self {
+            Self::AddTag(tag) => content_with_tag(content, tag),
+        }
+    }
+}
+
+impl fmt::Display for RuleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AddTag(tag) => write!(f, "#{tag}"),
+        }
+    }
+}
+
+impl FromStr for RuleAction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let raw = s.trim();
+        if raw.starts_with('#') {
+            return parse_tag(raw).map(Self::AddTag);
+        }
+        Err(Error::InvalidInput(format!(
+            "Unknown rule action `{raw}` (expected a #tag to add)"
+        )))
+    }
+}
+
+/// A stored routing rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingRule {
+    pub id: i64,
+    pub condition: RuleCondition,
+    pub action: RuleAction,
+    pub created_at: i64,
+}
+
+impl fmt::Display for RoutingRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "if {} then {}", self.condition, self.action)
+    }
+}
+
+/// Result of running the rules over one note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoutingOutcome {
+    /// Note content after every matching rule ran
+    pub content: String,
+    /// Ids of the rules that changed the note, in the order they ran
+    pub applied: Vec<i64>,
+}
+
+impl RoutingOutcome {
+    /// Whether any rule changed the note.
+    #[must_use]
+    pub const fn changed(&self) -> bool {
+        !self.applied.is_empty()
+    }
+}
+
+/// Run `rules` in order over a note with `content` captured from `source`.
+#[must_use]
+pub fn route_note(
+    rules: &[RoutingRule],
+    content: &str,
+    source: Option<NoteSource>,
+) -> RoutingOutcome {
+    let mut outcome = RoutingOutcome {
+        content: content.to_string(),
+        applied: Vec::new(),
+    };
+    for rule in rules {
+        let tags = extract_tags(&outcome.content);
+        if !rule.condition.matches(&outcome.content, &tags, source) {
+            continue;
+        }
+        if let Some(routed) = rule.action.apply(&outcome.content) {
+            outcome.content = routed;
+            outcome.applied.push(rule.id);
+        }
+    }
+    outcome
+}
+
+fn parse_tag(raw: &str) -> Result<String> {
+    normalize_capture_tag(raw)
+        .ok_or_else(|| Error::InvalidInput(format!("`{raw}` is not a valid tag")))
+}
+
+fn strip_prefix_ignore_case<'a>(raw: &'a str, prefix: &str) -> Option<&'a str> {
+    raw.get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &raw[prefix.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: i64, condition: &str, action: &str) -> RoutingRule {
+        RoutingRule {
+            id,
+            condition: condition.parse().unwrap(),
+            action: action.parse().unwrap(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn conditions_and_actions_round_trip_through_text() {
+        for raw in ["#work", "source:email-in", "contains:Invoice"] {
+            assert_eq!(raw.parse::<RuleCondition>().unwrap().to_string(), raw);
+        }
+        assert_eq!(
+            "  #Work ".parse::<RuleCondition>().unwrap(),
+            RuleCondition::Tag("work".to_string())
+        );
+        assert_eq!(
+            "#inbox".parse::<RuleAction>().unwrap(),
+            RuleAction::AddTag("inbox".to_string())
+        );
+
+        assert!("work".parse::<RuleCondition>().is_err());
+        assert!("source:fax".parse::<RuleCondition>().is_err());
+        assert!("contains:  ".parse::<RuleCondition>().is_err());
+        assert!("#1abc".parse::<RuleAction>().is_err());
+        assert!("move:work".parse::<RuleAction>().is_err());
+    }
+
+    #[test]
+    fn route_note_runs_matching_rules_in_order() {
+        let rules = [
+            rule(1, "source:email-in", "#inbox"),
+            rule(2, "#inbox", "#triage"),
+            rule(3, "contains:invoice", "#finance"),
+            rule(4, "#work", "#office"),
+        ];
+
+        let outcome = route_note(&rules, "Invoice from ACME", Some(NoteSource::EmailIn));
+        assert_eq!(outcome.applied, [1, 2, 3]);
+        assert_eq!(
+            outcome.content,
+            "Invoice from ACME\n\n#inbox\n\n#triage\n\n#finance"
+        );
+
+        let again = route_note(&rules, &outcome.content, Some(NoteSource::EmailIn));
+        assert!(!again.changed());
+        assert_eq!(again.content, outcome.content);
+
+        assert!(!route_note(&rules, "Groceries", Some(NoteSource::Cli)).changed());
+    }
+}
@@ -0,0 +1,323 @@
+//! Time-locked ("sealed") notes.
+//!
+//! Sealing encrypts a note's content with a fresh ChaCha20-Poly1305 key and
+//! replaces the content with an envelope that syncs like any other note. The
+//! key is kept in a local key store beside the database, so a sealed note can
+//! only be opened on the device that sealed it, and only once its date has
+//! passed.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::error::{Error, Result};
+use crate::models::{Note, NoteId};
+use crate::services::DatabaseService;
+use crate::util::{decode_hex, encode_hex};
+
+/// Prefix of the envelope line holding the encrypted content.
+pub const SEAL_MARKER: &str = "dirt-seal:v1:";
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Encrypted content of a sealed note, as stored in the note body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedEnvelope {
+    /// Unix ms after which the note may be opened.
+    pub sealed_until: i64,
+    nonce: [u8; NONCE_LEN],
+    ciphertext: Vec<u8>,
+}
+
+impl SealedEnvelope {
+    /// Find and decode the envelope in note content.
+    #[must_use]
+    pub fn parse(content: &str) -> Option<Self> {
+        let payload = content
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(SEAL_MARKER))?;
+        let mut parts = payload.split(':');
+        let sealed_until = parts.next()?.parse().ok()?;
+        let nonce = decode_hex(parts.next()?)?.try_into().ok()?;
+        let ciphertext = decode_hex(parts.next()?)?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            sealed_until,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// Note content for the sealed note: a readable title and the envelope.
+    #[must_use]
+    pub fn render(&self) -> String {
+        format!(
+            "Sealed until {}\n\n{SEAL_MARKER}{}:{}:{}",
+            format_seal_date(self.sealed_until),
+            self.sealed_until,
+            encode_hex(&self.nonce),
+            encode_hex(&self.ciphertext)
+        )
+    }
+
+    /// Whether the seal date has passed at `now_ms`.
+    #[must_use]
+    pub const fn is_open_at(&self, now_ms: i64) -> bool {
+        now_ms >= self.sealed_until
+    }
+}
+
+/// Seal date of a note, if its content is a sealed envelope.
+#[must_use]
+pub fn sealed_until(content: &str) -> Option<i64> {
+    SealedEnvelope::parse(content).map(|envelope| envelope.sealed_until)
+}
+
+/// Parse a seal date: `YYYY-MM-DD` (start of day, UTC) or RFC 3339.
+pub fn parse_seal_date(input: &str) -> Result<i64> {
+    let input = input.trim();
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let start_of_day = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        return Ok(start_of_day.and_utc().timestamp_millis());
+    }
+    chrono::DateTime::parse_from_rfc3339(input)
+        .map(|date_time| date_time.timestamp_millis())
+        .map_err(|_| {
+            Error::InvalidInput(format!("Invalid seal date '{input}' (expected YYYY-MM-DD)"))
+        })
+}
+
+/// Format a seal date as `YYYY-MM-DD` (UTC).
+#[must_use]
+pub fn format_seal_date(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms).map_or_else(
+        || timestamp_ms.to_string(),
+        |date_time| date_time.format("%Y-%m-%d").to_string(),
+    )
+}
+
+/// Local, unsynced storage for seal keys (one file per note).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealKeyStore {
+    dir: PathBuf,
+}
+
+impl SealKeyStore {
+    /// Store keys in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Store keys in a `seal-keys` folder next to the database file.
+    #[must_use]
+    pub fn beside_database(db_path: &Path) -> Self {
+        let parent = db_path.parent().unwrap_or_else(|| Path::new("."));
+        Self::new(parent.join("seal-keys"))
+    }
+
+    fn key_path(&self, note_id: &NoteId) -> PathBuf {
+        self.dir.join(format!("{note_id}.key"))
+    }
+
+    fn store(&self, note_id: &NoteId, key: &[u8]) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(self.key_path(note_id))?;
+        file.write_all(encode_hex(key).as_bytes())?;
+        Ok(())
+    }
+
+    fn load(&self, note_id: &NoteId) -> Result<Vec<u8>> {
+        let path = self.key_path(note_id);
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Err(Error::NotFound(format!(
+                    "Seal key for note {note_id} (it was sealed on another device)"
+                )));
+            }
+            Err(error) => return Err(error.into()),
+        };
+        decode_hex(raw.trim())
+            .filter(|key| key.len() == KEY_LEN)
+            .ok_or_else(|| Error::InvalidInput(format!("Corrupt seal key at {}", path.display())))
+    }
+
+    fn remove(&self, note_id: &NoteId) -> Result<()> {
+        match fs::remove_file(self.key_path(note_id)) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Encrypt a note's content until `until_ms`.
+pub async fn seal_note(
+    db: &DatabaseService,
+    keys: &SealKeyStore,
+    note_id: &NoteId,
+    until_ms: i64,
+    now_ms: i64,
+) -> Result<Note> {
+    let note = db
+        .get_note(note_id)
+        .await?
+        .ok_or_else(|| Error::NotFound(note_id.to_string()))?;
+    if SealedEnvelope::parse(&note.content).is_some() {
+        return Err(Error::InvalidInput(format!(
+            "Note {note_id} is already sealed"
+        )));
+    }
+    if until_ms <= now_ms {
+        return Err(Error::InvalidInput(
+            "Seal date must be in the future".to_string(),
+        ));
+    }
+
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = ChaCha20Poly1305::new(&key)
+        .encrypt(&nonce, note.content.as_bytes())
+        .map_err(|_| Error::InvalidInput("Failed to encrypt note".to_string()))?;
+    let mut nonce_bytes = [0_u8; NONCE_LEN];
+    nonce_bytes.copy_from_slice(&nonce);
+    let envelope = SealedEnvelope {
+        sealed_until: until_ms,
+        nonce: nonce_bytes,
+        ciphertext,
+    };
+
+    // Persist the key before replacing the content so a failure cannot lose it.
+    keys.store(note_id, key.as_slice())?;
+    match db.update_note(note_id, &envelope.render()).await {
+        Ok(note) => Ok(note),
+        Err(error) => {
+            if let Err(remove_error) = keys.remove(note_id) {
+                tracing::warn!("Failed to discard unused seal key: {}", remove_error);
+            }
+            Err(error)
+        }
+    }
+}
+
+/// Decrypt a sealed note whose date has passed and restore its content.
+pub async fn unseal_note(
+    db: &DatabaseService,
+    keys: &SealKeyStore,
+    note_id: &NoteId,
+    now_ms: i64,
+) -> Result<Note> {
+    let note = db
+        .get_note(note_id)
+        .await?
+        .ok_or_else(|| Error::NotFound(note_id.to_string()))?;
+    let envelope = SealedEnvelope::parse(&note.content)
+        .ok_or_else(|| Error::InvalidInput(format!("Note {note_id} is not sealed")))?;
+    if !envelope.is_open_at(now_ms) {
+        return Err(Error::InvalidInput(format!(
+            "Note {note_id} is sealed until {}",
+            format_seal_date(envelope.sealed_until)
+        )));
+    }
+
+    let key = keys.load(note_id)?;
+    let plaintext = ChaCha20Poly1305::new(Key::from_slice(&key))
+        .decrypt(
+            Nonce::from_slice(&envelope.nonce),
+            envelope.ciphertext.as_slice(),
+        )
+        .map_err(|_| Error::InvalidInput(format!("Seal key does not open note {note_id}")))?;
+    let content = String::from_utf8(plaintext)
+        .map_err(|_| Error::InvalidInput("Sealed content is not valid UTF-8".to_string()))?;
+
+    let note = db.update_note(note_id, &content).await?;
+    keys.remove(note_id)?;
+    Ok(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_MS: i64 = 86_400_000;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn seal_and_unseal_round_trip() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let keys = SealKeyStore::new(dir.path());
+        let note = db.create_note("Dear future me #letters").await.unwrap();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let sealed = seal_note(&db, &keys, &note.id, now + DAY_MS, now)
+            .await
+            .unwrap();
+        assert!(!sealed.content.contains("future me"));
+        assert_eq!(sealed_until(&sealed.content), Some(now + DAY_MS));
+
+        let early = unseal_note(&db, &keys, &note.id, now).await.unwrap_err();
+        assert!(matches!(early, Error::InvalidInput(message) if message.contains("sealed until")));
+
+        let opened = unseal_note(&db, &keys, &note.id, now + DAY_MS)
+            .await
+            .unwrap();
+        assert_eq!(opened.content, "Dear future me #letters");
+        assert!(!keys.key_path(&note.id).exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn seal_discards_plaintext_revisions() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let keys = SealKeyStore::new(dir.path());
+        let note = db.create_note("Draft letter").await.unwrap();
+        db.update_note(&note.id, "Final letter").await.unwrap();
+        assert_eq!(db.list_note_revisions(&note.id).await.unwrap().len(), 2);
+        let now = chrono::Utc::now().timestamp_millis();
+
+        seal_note(&db, &keys, &note.id, now + DAY_MS, now)
+            .await
+            .unwrap();
+        assert!(db.list_note_revisions(&note.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn seal_rejects_past_dates_and_resealing() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let keys = SealKeyStore::new(dir.path());
+        let note = db.create_note("Spoilers").await.unwrap();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        assert!(seal_note(&db, &keys, &note.id, now - 1, now).await.is_err());
+        seal_note(&db, &keys, &note.id, now + DAY_MS, now)
+            .await
+            .unwrap();
+        assert!(seal_note(&db, &keys, &note.id, now + 2 * DAY_MS, now)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn parse_seal_date_accepts_dates_and_timestamps() {
+        assert_eq!(parse_seal_date("2025-12-31").unwrap(), 1_767_139_200_000);
+        assert_eq!(
+            parse_seal_date("2025-12-31T12:00:00Z").unwrap(),
+            1_767_182_400_000
+        );
+        assert!(parse_seal_date("next year").is_err());
+        assert_eq!(format_seal_date(1_767_139_200_000), "2025-12-31");
+    }
+}
@@ -1,7 +1,177 @@
 //! Search functionality for Dirt
 //!
 //! This module provides full-text search capabilities using `SQLite` FTS5.
-//! The actual FTS queries are implemented in the repository layer.
+//! Queries are parsed here (see [`SearchQuery`]) and run against FTS in the
+//! repository layer; this module also holds the client-side helpers for
+//! presenting search results and the text extraction that feeds the
+//! attachment index.
 
-// Search is currently implemented via FTS5 in the repository.
-// This module can be expanded for more advanced search features.
+mod query;
+
+pub use query::{QueryClause, QueryTerm, SearchQuery};
+
+use crate::models::Note;
+use crate::storage::{extract_pdf_text, is_pdf};
+
+/// Most characters of an attachment's text kept in the search index.
+pub const MAX_ATTACHMENT_TEXT_CHARS: usize = 100_000;
+
+/// A note found by search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    pub note: Note,
+    /// Filename of the attachment that matched, when the note's own content
+    /// did not
+    pub matched_attachment: Option<String>,
+}
+
+/// Text of a text or PDF attachment to index for search.
+///
+/// Returns `None` for other attachments, for text that is not UTF-8, and for
+/// PDFs when this build cannot extract their text. The text is truncated to
+/// [`MAX_ATTACHMENT_TEXT_CHARS`].
+#[must_use]
+pub fn attachment_search_text(mime_type: &str, bytes: &[u8]) -> Option<String> {
+    let mime_type = mime_type.to_ascii_lowercase();
+    let text = if mime_type.starts_with("text/")
+        || matches!(
+            mime_type.as_str(),
+            "application/json" | "application/xml" | "application/x-yaml"
+        ) {
+        String::from_utf8(bytes.to_vec()).ok()?
+    } else if mime_type == "application/pdf" || is_pdf(bytes) {
+        extract_pdf_text(bytes).ok()?
+    } else {
+        return None;
+    };
+
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(text.chars().take(MAX_ATTACHMENT_TEXT_CHARS).collect())
+}
+
+/// A run of note text that either matches the search query or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightSegment<'a> {
+    pub text: &'a str,
+    pub is_match: bool,
+}
+
+/// Split `text` into runs around case-insensitive occurrences of `query`.
+///
+/// Matching follows the in-memory note filter (lowercased substring), so the
+/// highlighted runs are exactly what made a note show up in the results. An
+/// empty query yields the whole text as a single unmatched run.
+#[must_use]
+pub fn highlight_matches<'a>(text: &'a str, query: &str) -> Vec<HighlightSegment<'a>> {
+    let needle: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let mut segments = Vec::new();
+    let mut plain_start = 0;
+    let mut index = 0;
+
+    while !needle.is_empty() && index < text.len() {
+        if let Some(len) = match_len_at(&text[index..], &needle) {
+            if plain_start < index {
+                segments.push(HighlightSegment {
+                    text: &text[plain_start..index],
+                    is_match: false,
+                });
+            }
+            segments.push(HighlightSegment {
+                text: &text[index..index + len],
+                is_match: true,
+            });
+            index += len;
+            plain_start = index;
+        } else {
+            index += text[index..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    if plain_start < text.len() {
+        segments.push(HighlightSegment {
+            text: &text[plain_start..],
+            is_match: false,
+        });
+    }
+    segments
+}
+
+/// Byte length of the prefix of `haystack` matching the lowercased `needle`.
+fn match_len_at(haystack: &str, needle: &[char]) -> Option<usize> {
+    let mut matched = 0;
+    for (offset, ch) in haystack.char_indices() {
+        for lower in ch.to_lowercase() {
+            if needle.get(matched) != Some(&lower) {
+                return None;
+            }
+            matched += 1;
+        }
+        if matched == needle.len() {
+            return Some(offset + ch.len_utf8());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched(segments: &[HighlightSegment<'_>]) -> Vec<&str> {
+        segments
+            .iter()
+            .filter(|segment| segment.is_match)
+            .map(|segment| segment.text)
+            .collect()
+    }
+
+    #[test]
+    fn highlight_matches_is_case_insensitive_and_keeps_all_text() {
+        let text = "Rust notes: rust is RUSTy";
+        let segments = highlight_matches(text, "rust");
+
+        assert_eq!(matched(&segments), vec!["Rust", "rust", "RUST"]);
+        let rebuilt: String = segments.iter().map(|segment| segment.text).collect();
+        assert_eq!(rebuilt, text);
+        assert!(!segments[1].is_match);
+        assert_eq!(segments.last().unwrap().text, "y");
+    }
+
+    #[test]
+    fn highlight_matches_handles_multibyte_text_and_empty_queries() {
+        let segments = highlight_matches("Café CAFÉ", "café");
+        assert_eq!(matched(&segments), vec!["Café", "CAFÉ"]);
+
+        assert_eq!(
+            highlight_matches("Plain", ""),
+            vec![HighlightSegment {
+                text: "Plain",
+                is_match: false,
+            }]
+        );
+        assert!(highlight_matches("", "x").is_empty());
+        assert!(matched(&highlight_matches("abc", "abcd")).is_empty());
+    }
+
+    #[test]
+    fn attachment_search_text_reads_text_attachments_only() {
+        assert_eq!(
+            attachment_search_text("text/plain; charset=utf-8", b"  Meeting minutes\n"),
+            Some("Meeting minutes".to_string())
+        );
+        assert_eq!(
+            attachment_search_text("application/json", b"{\"a\":1}"),
+            Some("{\"a\":1}".to_string())
+        );
+        assert_eq!(attachment_search_text("image/png", b"\x89PNG"), None);
+        assert_eq!(attachment_search_text("text/plain", b"\xff\xfe"), None);
+        assert_eq!(attachment_search_text("text/plain", b"   "), None);
+
+        let long = "a".repeat(MAX_ATTACHMENT_TEXT_CHARS + 10);
+        let text = attachment_search_text("text/markdown", long.as_bytes()).unwrap();
+        assert_eq!(text.chars().count(), MAX_ATTACHMENT_TEXT_CHARS);
+    }
+}
@@ -0,0 +1,404 @@
+//! Structured search queries.
+//!
+//! `dirt search`, the desktop search bar and the mobile note filter all parse
+//! what was typed with [`SearchQuery::parse`], so a query finds the same notes
+//! everywhere. A query is a list of words, `"quoted phrases"` and filters:
+//!
+//! - `tag:work` keeps notes tagged `#work`
+//! - `before:2024-01-01` / `after:2024-01-01` keep notes created before the
+//!   start of, or after the end of, that day (UTC)
+//! - `has:attachment` keeps notes with an attachment
+//! - `source:cli` keeps notes captured from that source
+//! - `url:github.com` keeps notes linking a URL containing the text
+//!
+//! Every term must match (`AND` may be written but is implied); `OR` separates
+//! alternatives, and binds looser than `AND`.
+
+use chrono::{Days, NaiveDate};
+
+use crate::error::{Error, Result};
+use crate::models::{Note, NoteSource, SOURCE_FILTER_PREFIX, URL_FILTER_PREFIX};
+
+const TAG_FILTER_PREFIX: &str = "tag:";
+const BEFORE_FILTER_PREFIX: &str = "before:";
+const AFTER_FILTER_PREFIX: &str = "after:";
+const HAS_FILTER_PREFIX: &str = "has:";
+
+/// One term of a [`QueryClause`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryTerm {
+    /// Word in the note text; a trailing `*` matches it as a prefix
+    Word(String),
+    /// Exact run of words in the note text
+    Phrase(String),
+    /// Tag name, without `#`
+    Tag(String),
+    /// Created before this time, in Unix milliseconds
+    Before(i64),
+    /// Created at or after this time, in Unix milliseconds
+    After(i64),
+    /// Has at least one attachment
+    HasAttachment,
+    /// Captured from this source
+    Source(NoteSource),
+    /// Links a URL containing this text; empty matches any URL
+    Url(String),
+}
+
+/// Terms that must all match
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryClause {
+    pub terms: Vec<QueryTerm>,
+}
+
+impl QueryClause {
+    /// FTS5 expression for the clause's words and phrases, or `None` when it
+    /// only has filters.
+    ///
+    /// Every word is quoted, so text that happens to be FTS5 syntax is
+    /// searched for literally.
+    #[must_use]
+    pub fn fts_expression(&self) -> Option<String> {
+        let parts: Vec<String> = self
+            .terms
+            .iter()
+            .filter_map(|term| match term {
+                QueryTerm::Word(word) => Some(match word.strip_suffix('*') {
+                    Some(prefix) if !prefix.is_empty() => format!("{}*", fts_quote(prefix)),
+                    _ => fts_quote(word),
+                }),
+                QueryTerm::Phrase(phrase) => Some(fts_quote(phrase)),
+                _ => None,
+            })
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+
+    /// Whether the note's content satisfies the words, phrases, tags and URLs.
+    ///
+    /// Words and phrases match case-insensitive substrings, like the desktop
+    /// and mobile note filters always have.
+    #[must_use]
+    pub fn matches_content(&self, note: &Note) -> bool {
+        let content = note.content.to_lowercase();
+        let tags = note.tags();
+        self.terms.iter().all(|term| match term {
+            QueryTerm::Word(word) => {
+                let word = word.strip_suffix('*').unwrap_or(word);
+                content.contains(&word.to_lowercase())
+            }
+            QueryTerm::Phrase(phrase) => content.contains(&phrase.to_lowercase()),
+            QueryTerm::Tag(tag) => tags
+                .iter()
+                .any(|note_tag| note_tag.eq_ignore_ascii_case(tag)),
+            QueryTerm::Url(url) => {
+                let url = url.to_lowercase();
+                note.urls()
+                    .iter()
+                    .any(|note_url| note_url.to_lowercase().contains(&url))
+            }
+            _ => true,
+        })
+    }
+
+    /// Whether `note` satisfies every term; `has_attachment` answers
+    /// `has:attachment`.
+    ///
+    /// `source:` cannot be checked from the note alone and never matches here.
+    #[must_use]
+    pub fn matches(&self, note: &Note, has_attachment: bool) -> bool {
+        self.matches_content(note)
+            && self.terms.iter().all(|term| match term {
+                QueryTerm::Before(before) => note.created_at < *before,
+                QueryTerm::After(after) => note.created_at >= *after,
+                QueryTerm::HasAttachment => has_attachment,
+                QueryTerm::Source(_) => false,
+                _ => true,
+            })
+    }
+}
+
+/// A parsed search query: clauses joined by `OR`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    clauses: Vec<QueryClause>,
+}
+
+impl SearchQuery {
+    /// Parse a query typed by the user.
+    ///
+    /// Fails on filters with a value that cannot be understood, such as an
+    /// unknown source or a malformed date.
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut clauses = Vec::new();
+        let mut clause = QueryClause::default();
+        for token in tokenize(query) {
+            match token {
+                Token::Phrase(phrase) => clause.terms.push(QueryTerm::Phrase(phrase)),
+                Token::Bare(word) if word == "OR" => {
+                    clauses.push(std::mem::take(&mut clause));
+                }
+                Token::Bare(word) if word == "AND" => {}
+                Token::Bare(word) => clause.terms.push(parse_term(&word)?),
+            }
+        }
+        clauses.push(clause);
+        clauses.retain(|clause| !clause.terms.is_empty());
+        Ok(Self { clauses })
+    }
+
+    /// A query matching `text` as one literal phrase, for when
+    /// [`parse`](Self::parse) rejects what was typed.
+    #[must_use]
+    pub fn literal(text: &str) -> Self {
+        let text = text.trim();
+        let clauses = if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![QueryClause {
+                terms: vec![QueryTerm::Phrase(text.to_string())],
+            }]
+        };
+        Self { clauses }
+    }
+
+    /// Parse `query`, falling back to a [`literal`](Self::literal) match
+    /// while a half-typed filter cannot be parsed yet.
+    #[must_use]
+    pub fn parse_or_literal(query: &str) -> Self {
+        Self::parse(query).unwrap_or_else(|_| Self::literal(query))
+    }
+
+    #[must_use]
+    pub const fn clauses(&self) -> &[QueryClause] {
+        &self.clauses
+    }
+
+    /// Whether the query has no terms and so matches every note.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    /// Whether answering the query needs to know which notes have attachments.
+    #[must_use]
+    pub fn uses_attachments(&self) -> bool {
+        self.clauses.iter().any(|clause| {
+            clause
+                .terms
+                .iter()
+                .any(|term| matches!(term, QueryTerm::HasAttachment))
+        })
+    }
+
+    /// First word or phrase of the query, for highlighting matches with
+    /// [`super::highlight_matches`].
+    #[must_use]
+    pub fn highlight_text(&self) -> Option<&str> {
+        self.clauses
+            .iter()
+            .flat_map(|clause| &clause.terms)
+            .find_map(|term| match term {
+                QueryTerm::Word(word) => Some(word.strip_suffix('*').unwrap_or(word)),
+                QueryTerm::Phrase(phrase) => Some(phrase.as_str()),
+                _ => None,
+            })
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Whether `note` matches any clause; see [`QueryClause::matches`].
+    #[must_use]
+    pub fn matches(&self, note: &Note, has_attachment: bool) -> bool {
+        self.is_empty()
+            || self
+                .clauses
+                .iter()
+                .any(|clause| clause.matches(note, has_attachment))
+    }
+}
+
+enum Token {
+    Bare(String),
+    Phrase(String),
+}
+
+/// Split on whitespace, keeping `"quoted phrases"` together; an unterminated
+/// quote runs to the end of the query.
+fn tokenize(query: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+        } else if ch == '"' {
+            chars.next();
+            let phrase: String = chars.by_ref().take_while(|&ch| ch != '"').collect();
+            let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                tokens.push(Token::Phrase(phrase));
+            }
+        } else {
+            let mut word = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() || ch == '"' {
+                    break;
+                }
+                word.push(ch);
+                chars.next();
+            }
+            tokens.push(Token::Bare(word));
+        }
+    }
+    tokens
+}
+
+fn parse_term(word: &str) -> Result<QueryTerm> {
+    if let Some(tag) = strip_prefix_ignore_case(word, TAG_FILTER_PREFIX) {
+        let tag = tag.trim_start_matches('#');
+        if tag.is_empty() {
+            return Err(Error::InvalidInput("`tag:` needs a tag name".into()));
+        }
+        return Ok(QueryTerm::Tag(tag.to_lowercase()));
+    }
+    if let Some(date) = strip_prefix_ignore_case(word, BEFORE_FILTER_PREFIX) {
+        return Ok(QueryTerm::Before(start_of_day(parse_date(date)?)));
+    }
+    if let Some(date) = strip_prefix_ignore_case(word, AFTER_FILTER_PREFIX) {
+        let next_day = parse_date(date)?
+            .checked_add_days(Days::new(1))
+            .ok_or_else(|| Error::InvalidInput(format!("Date `{date}` is out of range")))?;
+        return Ok(QueryTerm::After(start_of_day(next_day)));
+    }
+    if let Some(kind) = strip_prefix_ignore_case(word, HAS_FILTER_PREFIX) {
+        return match kind.to_ascii_lowercase().as_str() {
+            "attachment" | "attachments" => Ok(QueryTerm::HasAttachment),
+            _ => Err(Error::InvalidInput(format!(
+                "Unknown filter `{word}` (expected has:attachment)"
+            ))),
+        };
+    }
+    if let Some(source) = strip_prefix_ignore_case(word, SOURCE_FILTER_PREFIX) {
+        return Ok(QueryTerm::Source(source.parse()?));
+    }
+    if let Some(url) = strip_prefix_ignore_case(word, URL_FILTER_PREFIX) {
+        return Ok(QueryTerm::Url(url.to_string()));
+    }
+    Ok(QueryTerm::Word(word.to_string()))
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    value
+        .get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &value[prefix.len()..])
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| Error::InvalidInput(format!("Invalid date `{value}` (expected YYYY-MM-DD)")))
+}
+
+fn start_of_day(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+        .timestamp_millis()
+}
+
+fn fts_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(query: &str) -> Vec<Vec<QueryTerm>> {
+        SearchQuery::parse(query)
+            .unwrap()
+            .clauses()
+            .iter()
+            .map(|clause| clause.terms.clone())
+            .collect()
+    }
+
+    #[test]
+    fn parse_reads_filters_phrases_and_or() {
+        assert_eq!(
+            terms(r#"tag:#Work "release plan" has:attachment OR draft AND source:cli"#),
+            vec![
+                vec![
+                    QueryTerm::Tag("work".to_string()),
+                    QueryTerm::Phrase("release plan".to_string()),
+                    QueryTerm::HasAttachment,
+                ],
+                vec![
+                    QueryTerm::Word("draft".to_string()),
+                    QueryTerm::Source(NoteSource::Cli),
+                ],
+            ]
+        );
+        assert_eq!(
+            terms("rust URL:github.com url:"),
+            vec![vec![
+                QueryTerm::Word("rust".to_string()),
+                QueryTerm::Url("github.com".to_string()),
+                QueryTerm::Url(String::new()),
+            ]]
+        );
+        assert_eq!(
+            terms("before:2024-01-01 after:2023-12-30"),
+            vec![vec![
+                QueryTerm::Before(1_704_067_200_000),
+                QueryTerm::After(1_703_980_800_000),
+            ]]
+        );
+        assert!(SearchQuery::parse("  OR  ").unwrap().is_empty());
+        assert!(SearchQuery::parse("source:fax").is_err());
+        assert!(SearchQuery::parse("before:2024-13-01").is_err());
+        assert!(SearchQuery::parse("has:pizza").is_err());
+        assert!(SearchQuery::parse("tag:").is_err());
+    }
+
+    #[test]
+    fn fts_expression_quotes_words_and_keeps_prefixes() {
+        let query = SearchQuery::parse(r#"milk* "oat milk" say"hi tag:x"#).unwrap();
+        assert_eq!(
+            query.clauses()[0].fts_expression().as_deref(),
+            Some(r#""milk"* "oat milk" "say" "hi tag:x""#)
+        );
+        let filters_only = SearchQuery::parse("tag:x has:attachment").unwrap();
+        assert_eq!(filters_only.clauses()[0].fts_expression(), None);
+    }
+
+    #[test]
+    fn matches_checks_every_term_of_any_clause() {
+        let mut note = Note::new("Release plan for Q3 #work https://github.com/dirt");
+        note.created_at = 1_704_067_200_000 + 1;
+
+        let matches = |query: &str, has_attachment: bool| {
+            SearchQuery::parse(query)
+                .unwrap()
+                .matches(&note, has_attachment)
+        };
+        assert!(matches("", false));
+        assert_eq!(
+            SearchQuery::parse("tag:work rel* plan")
+                .unwrap()
+                .highlight_text(),
+            Some("rel")
+        );
+        assert!(matches(r#"tag:work "PLAN for""#, false));
+        assert!(!matches("tag:home plan", false));
+        assert!(matches("tag:home OR plan", false));
+        assert!(matches("after:2023-12-31 before:2024-01-02", false));
+        assert!(!matches("after:2024-01-01", false));
+        assert!(matches("has:attachment", true));
+        assert!(!matches("has:attachment", false));
+        assert!(matches("url:GitHub.com rel*", false));
+        assert!(!matches("source:cli", false));
+
+        assert!(SearchQuery::parse_or_literal("before:2024-0")
+            .matches(&Note::new("note about before:2024-0 typing"), false));
+    }
+}
@@ -2,20 +2,49 @@
 
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 
+use chrono::NaiveDate;
 use tokio::sync::Mutex;
 
+use crate::changelog::{should_record_current_version, unseen_releases, Release, CURRENT_VERSION};
+use crate::crypto::{is_encrypted, is_encrypted_bytes, ContentEncryption, EncryptionParams};
 use crate::db::{
-    Database, LibSqlNoteRepository, LibSqlSettingsRepository, NoteRepository, SettingsRepository,
-    SyncConfig,
+    AnalyzeReport, CompactReport, Database, DatabaseHealth, DraftRepository,
+    EncryptionParamsRepository, ExportExclusionRepository, ExportStateRepository,
+    LibSqlDraftRepository, LibSqlEncryptionParamsRepository, LibSqlExportExclusionRepository,
+    LibSqlExportStateRepository, LibSqlNoteOrderRepository, LibSqlNoteSummaryRepository,
+    LibSqlRoutingRuleRepository, LibSqlSettingsRepository, LibSqlSyncHistoryRepository,
+    LibSqlSyncOutboxRepository, NoteOrderRepository, NoteRepository, NoteRevisionRepository,
+    NoteSummaryRepository, RoutingRuleRepository, SettingsRepository, SyncConfig,
+    SyncHistoryRepository, SyncOutboxRepository,
 };
-use crate::models::{Attachment, AttachmentId, Note, Settings, SyncConflict};
+use crate::device::load_or_create_device_id;
+use crate::digest::{build_weekly_digest, DigestPeriod, WeeklyDigest};
+use crate::export::ExportPrivacy;
+use crate::graph::{build_note_graph, GraphQuery, NoteGraph};
+use crate::media::{audit_attachments, AttachmentAudit, StoredObject};
+use crate::models::{
+    count_changed_rows, extract_tasks, move_note_in_order, Attachment, AttachmentId,
+    AttachmentScanStatus, ExportState, Note, NoteCursor, NoteDraft, NotePage, NoteRevision,
+    NoteSource, NoteSummary, NoteTask, NoteUrl, Settings, SortScope, SyncConflict,
+    SyncHistoryEntry, TrashPurge, REPLICATION_FRAME_BYTES,
+};
+use crate::quota::{quota_warnings, QuotaWarning, StorageUsage};
+use crate::rules::{route_note, RoutingRule, RuleAction, RuleCondition};
+use crate::seal::sealed_until;
+use crate::search::SearchHit;
+use crate::stats::CaptureStats;
+use crate::sync::{ConflictResolver, SyncFilter};
+use crate::tags::parse_tag_name;
 use crate::{NoteId, Result};
 
 /// Thread-safe service for DB and repository operations.
 #[derive(Clone)]
 pub struct DatabaseService {
     db: Arc<Mutex<Database>>,
+    /// Recorded with each note revision saved through this service
+    device_id: Option<String>,
 }
 
 impl DatabaseService {
@@ -29,9 +58,26 @@ impl DatabaseService {
             std::fs::create_dir_all(parent)?;
         }
 
-        let db = Self::open_database(db_path, sync_config).await?;
+        let device_id = match load_or_create_device_id(&db_path) {
+            Ok(device_id) => Some(device_id),
+            Err(error) => {
+                tracing::warn!("Failed to load device id: {}", error);
+                None
+            }
+        };
+        let mut db = Self::open_database(db_path.clone(), sync_config).await?;
+        if db.is_sync_enabled() {
+            let settings = LibSqlSettingsRepository::new(db.connection())
+                .load()
+                .await?;
+            db.set_sync_filter(Some(SyncFilter::load_beside_database(
+                &db_path,
+                settings.sync_excluded_tags,
+            )?));
+        }
         Ok(Self {
             db: Arc::new(Mutex::new(db)),
+            device_id,
         })
     }
 
@@ -53,6 +99,7 @@ impl DatabaseService {
         let db = Database::open_in_memory().await?;
         Ok(Self {
             db: Arc::new(Mutex::new(db)),
+            device_id: None,
         })
     }
 
@@ -217,10 +264,135 @@ impl DatabaseService {
         Ok(())
     }
 
-    /// Sync with remote DB when sync is enabled.
+    /// Sync with remote DB when sync is enabled, recording how much was
+    /// transferred and how many notes changed in the sync history.
     pub async fn sync(&self) -> Result<()> {
+        let mut db = self.db.lock().await;
+        if !db.is_sync_enabled() {
+            return Ok(());
+        }
+
+        let history = LibSqlSyncHistoryRepository::new(db.connection());
+        let before = history.note_versions().await?;
+        let synced_at = chrono::Utc::now().timestamp_millis();
+        let started = Instant::now();
+        let frames = db.sync_frames().await?;
+        let duration_ms = i64::try_from(started.elapsed().as_millis()).unwrap_or(i64::MAX);
+        let after = history.note_versions().await?;
+
+        let frames_synced = i64::try_from(frames).unwrap_or(i64::MAX);
+        let entry = SyncHistoryEntry {
+            id: 0,
+            synced_at,
+            duration_ms,
+            frames_synced,
+            bytes_transferred: frames_synced.saturating_mul(REPLICATION_FRAME_BYTES),
+            rows_changed: i64::try_from(count_changed_rows(&before, &after)).unwrap_or(i64::MAX),
+        };
+        if let Err(error) = history.record(&entry).await {
+            tracing::warn!("Failed to record sync history: {}", error);
+        }
+        // Changes are queued under the same lock, so everything queued so far
+        // went out with this sync.
+        if let Err(error) = LibSqlSyncOutboxRepository::new(db.connection())
+            .ack_through(chrono::Utc::now().timestamp_millis())
+            .await
+        {
+            tracing::warn!("Failed to clear the sync outbox: {}", error);
+        }
+        // Another device may have changed which tags stay off the server.
+        if let Some(mut filter) = db.sync_filter().cloned() {
+            let settings = LibSqlSettingsRepository::new(db.connection())
+                .load()
+                .await?;
+            filter.set_excluded_tags(settings.sync_excluded_tags);
+            db.set_sync_filter(Some(filter));
+        }
+        db.detect_encryption().await
+    }
+
+    /// Queue a local change to `note_id` for the next sync; the queue
+    /// survives restarts and is cleared by a successful [`Self::sync`].
+    /// Local-only databases have nothing to sync, so nothing is queued.
+    pub async fn enqueue_sync_change(&self, note_id: &NoteId) -> Result<()> {
+        let db = self.db.lock().await;
+        if !db.is_sync_enabled() {
+            return Ok(());
+        }
+        LibSqlSyncOutboxRepository::new(db.connection())
+            .enqueue(note_id, chrono::Utc::now().timestamp_millis())
+            .await
+    }
+
+    /// Notes changed since the last successful sync, oldest first.
+    pub async fn pending_sync_changes(&self) -> Result<Vec<NoteId>> {
         let db = self.db.lock().await;
-        db.sync().await
+        LibSqlSyncOutboxRepository::new(db.connection())
+            .list_pending()
+            .await
+    }
+
+    /// Number of notes changed since the last successful sync.
+    pub async fn pending_sync_count(&self) -> Result<usize> {
+        let db = self.db.lock().await;
+        LibSqlSyncOutboxRepository::new(db.connection())
+            .pending_count()
+            .await
+    }
+
+    /// List recent syncs with their transfer sizes, newest first.
+    pub async fn list_sync_history(&self, limit: usize) -> Result<Vec<SyncHistoryEntry>> {
+        let db = self.db.lock().await;
+        let repo = LibSqlSyncHistoryRepository::new(db.connection());
+        repo.list_recent(limit).await
+    }
+
+    /// Refresh planner statistics and report index usage.
+    pub async fn analyze(&self) -> Result<AnalyzeReport> {
+        let db = self.db.lock().await;
+        db.analyze().await
+    }
+
+    /// Measure the database and count live and deleted notes.
+    pub async fn storage_usage(&self) -> Result<StorageUsage> {
+        let db = self.db.lock().await;
+        db.storage_usage().await
+    }
+
+    /// Check database integrity and full-text indexes.
+    pub async fn health_check(&self) -> Result<DatabaseHealth> {
+        let db = self.db.lock().await;
+        db.health_check().await
+    }
+
+    /// Rebuild full-text indexes, vacuum, and checkpoint the database.
+    pub async fn compact(&self) -> Result<CompactReport> {
+        let db = self.db.lock().await;
+        db.compact().await
+    }
+
+    /// Storage usage and the soft quotas it crosses under the saved settings.
+    pub async fn check_storage_quotas(&self) -> Result<(StorageUsage, Vec<QuotaWarning>)> {
+        let db = self.db.lock().await;
+        let usage = db.storage_usage().await?;
+        let settings = LibSqlSettingsRepository::new(db.connection())
+            .load()
+            .await?;
+        Ok((usage, quota_warnings(&usage, &settings)))
+    }
+
+    /// Permanently remove deleted notes and compact the database.
+    ///
+    /// Only local-only databases can be purged: synced devices need the
+    /// deleted rows to learn about the deletion.
+    pub async fn purge_deleted_notes(&self) -> Result<u64> {
+        let db = self.db.lock().await;
+        if db.is_sync_enabled() {
+            return Err(crate::Error::InvalidInput(
+                "Deleted notes can only be purged from a local-only database".to_string(),
+            ));
+        }
+        db.purge_deleted_notes().await
     }
 
     /// Returns whether sync is configured for this DB.
@@ -229,17 +401,221 @@ impl DatabaseService {
         db.is_sync_enabled()
     }
 
-    /// List notes newest-first.
+    /// Returns whether the DB was opened read-only because a newer Dirt
+    /// migrated it.
+    pub async fn is_read_only(&self) -> bool {
+        let db = self.db.lock().await;
+        db.is_read_only()
+    }
+
+    /// Whether note content is encrypted with a passphrase.
+    pub async fn is_encrypted(&self) -> bool {
+        let db = self.db.lock().await;
+        db.encryption().is_enabled()
+    }
+
+    /// Whether encrypted notes can currently be read and written.
+    pub async fn is_unlocked(&self) -> bool {
+        let db = self.db.lock().await;
+        db.encryption().is_unlocked()
+    }
+
+    /// Save the tags whose notes stay off the sync server, then hold back or
+    /// release stored notes and revisions to match; returns how many notes
+    /// were rewritten.
+    ///
+    /// Held-back notes can only be read on this device. Local-only databases
+    /// just save the tags, which apply once sync is set up.
+    pub async fn set_sync_excluded_tags(&self, tags: Vec<String>) -> Result<u64> {
+        let mut db = self.db.lock().await;
+        let settings_repo = LibSqlSettingsRepository::new(db.connection());
+        let mut settings = settings_repo.load().await?;
+        settings.sync_excluded_tags.clone_from(&tags);
+        settings_repo.save(&settings).await?;
+
+        let Some(mut filter) = db.sync_filter().cloned() else {
+            return Ok(0);
+        };
+        filter.set_excluded_tags(tags);
+        db.set_sync_filter(Some(filter));
+        let notes = db.note_repository().apply_sync_filter().await?;
+        db.revision_repository().apply_sync_filter().await?;
+        Ok(notes)
+    }
+
+    /// Turn on encryption with `passphrase` and encrypt every stored note and
+    /// revision; returns how many notes were encrypted.
+    ///
+    /// The passphrase cannot be recovered: notes encrypted with it are lost
+    /// if it is forgotten.
+    pub async fn enable_encryption(&self, passphrase: &str) -> Result<u64> {
+        let mut db = self.db.lock().await;
+        let params_repo = LibSqlEncryptionParamsRepository::new(db.connection());
+        if params_repo.load().await?.is_some() {
+            return Err(crate::Error::InvalidInput(
+                "Notes are already encrypted".into(),
+            ));
+        }
+        let (params, key) =
+            EncryptionParams::create(passphrase, chrono::Utc::now().timestamp_millis())?;
+        params_repo.save(&params).await?;
+
+        db.set_encryption(ContentEncryption::Unlocked(key));
+        encrypt_stored_content(&db).await
+    }
+
+    /// Unlock encrypted notes with `passphrase`. Notes still in plaintext,
+    /// such as ones captured before this device learned about encryption,
+    /// are encrypted; returns how many.
+    pub async fn unlock_encryption(&self, passphrase: &str) -> Result<u64> {
+        let mut db = self.db.lock().await;
+        let params = LibSqlEncryptionParamsRepository::new(db.connection())
+            .load()
+            .await?
+            .ok_or_else(|| crate::Error::InvalidInput("Notes are not encrypted".into()))?;
+        let key = params.unlock(passphrase)?;
+
+        db.set_encryption(ContentEncryption::Unlocked(key));
+        encrypt_stored_content(&db).await
+    }
+
+    /// Forget the key; encrypted notes read as envelopes until unlocked again.
+    pub async fn lock_encryption(&self) {
+        let mut db = self.db.lock().await;
+        if db.encryption().is_enabled() {
+            db.set_encryption(ContentEncryption::Locked);
+        }
+    }
+
+    /// Attachment bytes as they should be uploaded: encrypted when notes are.
+    pub async fn encrypt_attachment_bytes(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        let db = self.db.lock().await;
+        match db.encryption() {
+            ContentEncryption::Off => Ok(bytes),
+            ContentEncryption::Locked => Err(crate::Error::InvalidInput(
+                "Notes are encrypted; unlock them before adding attachments".into(),
+            )),
+            ContentEncryption::Unlocked(key) => key.encrypt_bytes(&bytes),
+        }
+    }
+
+    /// Downloaded attachment bytes, decrypted when they were encrypted.
+    pub async fn decrypt_attachment_bytes(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        if !is_encrypted_bytes(&bytes) {
+            return Ok(bytes);
+        }
+        let db = self.db.lock().await;
+        match db.encryption() {
+            ContentEncryption::Unlocked(key) => key.decrypt_bytes(&bytes),
+            _ => Err(crate::Error::InvalidInput(
+                "Attachment is encrypted; unlock your notes to open it".into(),
+            )),
+        }
+    }
+
+    /// List notes newest-first, leaving out archived notes.
     pub async fn list_notes(&self, limit: usize, offset: usize) -> Result<Vec<Note>> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.list(limit, offset).await
     }
 
+    /// List one page of notes newest-first, resuming after `cursor`.
+    pub async fn list_notes_page(
+        &self,
+        cursor: Option<&NoteCursor>,
+        limit: usize,
+    ) -> Result<NotePage> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        let notes = repo.list_after(cursor, limit).await?;
+        Ok(NotePage::from_notes(notes, limit))
+    }
+
+    /// List pinned notes newest-first, leaving out archived notes.
+    pub async fn list_pinned_notes(&self, limit: usize) -> Result<Vec<Note>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.list_pinned(limit).await
+    }
+
+    /// List archived notes newest-first.
+    pub async fn list_archived_notes(&self, limit: usize, offset: usize) -> Result<Vec<Note>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.list_archived(limit, offset).await
+    }
+
+    /// Summarize notes created or edited during `period`.
+    ///
+    /// Private notes are left out unless `include_private` is set.
+    pub async fn weekly_digest(
+        &self,
+        period: DigestPeriod,
+        include_private: bool,
+    ) -> Result<WeeklyDigest> {
+        const PAGE_SIZE: usize = 200;
+
+        // Notes are listed by `updated_at` descending, so stop at the first
+        // page that reaches past the start of the period.
+        let mut notes = Vec::new();
+        let mut offset = 0;
+        loop {
+            let batch = self.list_notes(PAGE_SIZE, offset).await?;
+            let count = batch.len();
+            let reached_start = batch
+                .last()
+                .map_or(true, |note| note.updated_at < period.start_ms);
+            notes.extend(batch);
+            if count < PAGE_SIZE || reached_start {
+                break;
+            }
+            offset += count;
+        }
+
+        let notes = self.export_privacy(include_private).await?.filter(notes);
+        Ok(build_weekly_digest(&notes, period))
+    }
+
+    /// List every non-deleted note that may be exported, archived ones
+    /// included, newest first.
+    ///
+    /// Private notes are left out unless `include_private` is set.
+    pub async fn list_exportable_notes(&self, include_private: bool) -> Result<Vec<Note>> {
+        const PAGE_SIZE: usize = 500;
+
+        let mut notes = Vec::new();
+        for archived in [false, true] {
+            let mut offset = 0;
+            loop {
+                let batch = if archived {
+                    self.list_archived_notes(PAGE_SIZE, offset).await?
+                } else {
+                    self.list_notes(PAGE_SIZE, offset).await?
+                };
+                let count = batch.len();
+                notes.extend(batch);
+                if count < PAGE_SIZE {
+                    break;
+                }
+                offset += count;
+            }
+        }
+        notes.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+        Ok(self.export_privacy(include_private).await?.filter(notes))
+    }
+
+    /// Build the note relationship graph for the most recent notes.
+    pub async fn note_graph(&self, query: &GraphQuery) -> Result<NoteGraph> {
+        let notes = self.list_notes(query.max_notes, 0).await?;
+        Ok(build_note_graph(&notes, query))
+    }
+
     /// Fetch a note by id.
     pub async fn get_note(&self, id: &NoteId) -> Result<Option<Note>> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.get(id).await
     }
 
@@ -271,38 +647,198 @@ impl DatabaseService {
     /// Create a new note.
     pub async fn create_note(&self, content: &str) -> Result<Note> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
-        repo.create(content).await
+        let repo = db.note_repository();
+        let note = repo.create(content).await?;
+        apply_routing_rules(&db, note, None).await
     }
 
     /// Create a note with a pre-generated id.
     pub async fn create_note_with_id(&self, note: &Note) -> Result<Note> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.create_with_note(note).await
     }
 
+    /// Create a new note, recording the interface it was captured from.
+    pub async fn create_note_from(&self, content: &str, source: NoteSource) -> Result<Note> {
+        self.create_note_with_id_from(&Note::new(content), source)
+            .await
+    }
+
+    /// Create a note with a pre-generated id, recording its capture source.
+    pub async fn create_note_with_id_from(&self, note: &Note, source: NoteSource) -> Result<Note> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        let note = repo.create_with_source(note, source).await?;
+        apply_routing_rules(&db, note, Some(source)).await
+    }
+
+    /// Capture source recorded for a note.
+    pub async fn note_source(&self, id: &NoteId) -> Result<Option<NoteSource>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.get_source(id).await
+    }
+
+    /// Per-source breakdown of non-deleted notes.
+    pub async fn capture_stats(&self) -> Result<CaptureStats> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        let counts = repo.count_by_source().await?;
+        Ok(CaptureStats::from_counts(&counts))
+    }
+
     /// Update a note.
     pub async fn update_note(&self, id: &NoteId, content: &str) -> Result<Note> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
-        repo.update(id, content).await
+        let repo = db.note_repository();
+        let previous = repo.get(id).await?;
+        let note = repo.update(id, content).await?;
+        let source = repo.get_source(id).await?;
+        let note = apply_routing_rules(&db, note, source).await?;
+        if let Some(previous) = previous {
+            record_revision(&db, &previous, &note, self.device_id.as_deref()).await?;
+        }
+        Ok(note)
+    }
+
+    /// List the saved revisions of a note, newest first.
+    pub async fn list_note_revisions(&self, id: &NoteId) -> Result<Vec<NoteRevision>> {
+        let db = self.db.lock().await;
+        let repo = db.revision_repository();
+        repo.list(id).await
+    }
+
+    /// Fetch one saved revision of a note.
+    pub async fn get_note_revision(
+        &self,
+        id: &NoteId,
+        revision: i64,
+    ) -> Result<Option<NoteRevision>> {
+        let db = self.db.lock().await;
+        let repo = db.revision_repository();
+        repo.get(id, revision).await
+    }
+
+    /// Put the content of an earlier revision back, saving it as a new revision.
+    pub async fn restore_note_revision(&self, id: &NoteId, revision: i64) -> Result<Note> {
+        let saved = self
+            .get_note_revision(id, revision)
+            .await?
+            .ok_or_else(|| crate::Error::NotFound(format!("Revision {revision} of note {id}")))?;
+        self.update_note(id, &saved.content).await
+    }
+
+    /// Apply the `revision_retention` setting to every note; returns how many
+    /// revisions were removed.
+    pub async fn prune_note_revisions(&self) -> Result<u64> {
+        let db = self.db.lock().await;
+        let settings = LibSqlSettingsRepository::new(db.connection())
+            .load()
+            .await?;
+        let Some(keep) = settings.revision_retention else {
+            return Ok(0);
+        };
+        db.revision_repository().prune_all(keep).await
+    }
+
+    /// List routing rules in the order they run.
+    pub async fn list_routing_rules(&self) -> Result<Vec<RoutingRule>> {
+        let db = self.db.lock().await;
+        let repo = LibSqlRoutingRuleRepository::new(db.connection());
+        repo.list().await
+    }
+
+    /// Add a routing rule that runs after the existing ones.
+    pub async fn add_routing_rule(
+        &self,
+        condition: &RuleCondition,
+        action: &RuleAction,
+    ) -> Result<RoutingRule> {
+        let db = self.db.lock().await;
+        let repo = LibSqlRoutingRuleRepository::new(db.connection());
+        repo.add(condition, action).await
+    }
+
+    /// Remove a routing rule; returns whether it existed.
+    pub async fn remove_routing_rule(&self, id: i64) -> Result<bool> {
+        let db = self.db.lock().await;
+        let repo = LibSqlRoutingRuleRepository::new(db.connection());
+        repo.remove(id).await
     }
 
     /// Soft-delete a note.
     pub async fn delete_note(&self, id: &NoteId) -> Result<()> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.delete(id).await
     }
 
+    /// List deleted notes that can still be restored, most recently deleted
+    /// first.
+    pub async fn list_trashed_notes(&self, limit: usize, offset: usize) -> Result<Vec<Note>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.list_trashed(limit, offset).await
+    }
+
+    /// Move a deleted note back to the note list.
+    pub async fn restore_note(&self, id: &NoteId) -> Result<Note> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.restore(id).await
+    }
+
+    /// Permanently remove notes deleted more than `days` days ago (`0` empties
+    /// the trash).
+    ///
+    /// The returned attachments still have files in storage for the caller to
+    /// delete. Like [`Self::purge_deleted_notes`], only local-only databases
+    /// can be purged.
+    pub async fn purge_trash(&self, days: u32) -> Result<TrashPurge> {
+        let db = self.db.lock().await;
+        if db.is_sync_enabled() {
+            return Err(crate::Error::InvalidInput(
+                "The trash can only be emptied in a local-only database".to_string(),
+            ));
+        }
+        let repo = db.note_repository();
+        repo.purge_older_than(days).await
+    }
+
+    /// Pin or unpin a note.
+    pub async fn set_note_pinned(&self, id: &NoteId, pinned: bool) -> Result<Note> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.set_pinned(id, pinned).await
+    }
+
+    /// Archive a note, or restore it to the note list.
+    pub async fn set_note_archived(&self, id: &NoteId, archived: bool) -> Result<Note> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.set_archived(id, archived).await
+    }
+
     /// Search notes by query.
     pub async fn search_notes(&self, query: &str, limit: usize) -> Result<Vec<Note>> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.search(query, limit).await
     }
 
+    /// Search notes, optionally also matching attachment filenames and text.
+    pub async fn search_note_hits(
+        &self,
+        query: &str,
+        limit: usize,
+        include_attachments: bool,
+    ) -> Result<Vec<SearchHit>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.search_hits(query, limit, include_attachments).await
+    }
+
     /// List notes by tag.
     pub async fn list_notes_by_tag(
         &self,
@@ -311,24 +847,196 @@ impl DatabaseService {
         offset: usize,
     ) -> Result<Vec<Note>> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.list_by_tag(tag, limit, offset).await
     }
 
+    /// List captured URLs with their notes, optionally from notes tagged `tag`.
+    pub async fn list_urls(&self, tag: Option<&str>, limit: usize) -> Result<Vec<NoteUrl>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.list_urls(tag, limit).await
+    }
+
+    /// List notes that link to `id` with a `[[link]]`, newest first.
+    pub async fn backlinks(&self, id: &NoteId) -> Result<Vec<Note>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.backlinks(id).await
+    }
+
+    /// Get the daily note for `date`, creating it when missing.
+    pub async fn get_or_create_daily_note(
+        &self,
+        date: NaiveDate,
+        source: NoteSource,
+    ) -> Result<Note> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.get_or_create_daily(date, source).await
+    }
+
     /// List tags and counts.
     pub async fn list_tags(&self) -> Result<Vec<(String, usize)>> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.list_tags().await
     }
 
+    /// Id prefix length that keeps every note distinguishable; see
+    /// [`crate::models::unique_prefix_len`].
+    pub async fn short_note_id_len(&self) -> Result<usize> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.short_id_len().await
+    }
+
+    /// Suggest tags for autocomplete by prefix.
+    pub async fn suggest_tags(&self, prefix: &str, limit: usize) -> Result<Vec<(String, usize)>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.suggest_tags(prefix, limit).await
+    }
+
+    /// Record an accepted tag completion for future ranking.
+    pub async fn record_tag_completion(&self, name: &str) -> Result<()> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.record_tag_completion(name).await
+    }
+
+    /// Rename `#from` to `#to` in every note; returns how many notes changed.
+    ///
+    /// Renaming onto a tag that is already in use merges the two.
+    pub async fn rename_tag(&self, from: &str, to: &str) -> Result<usize> {
+        let from = parse_tag_name(from)?;
+        let to = parse_tag_name(to)?;
+        if from == to {
+            return Err(crate::Error::InvalidInput(format!(
+                "Tag #{from} already has that name"
+            )));
+        }
+        self.retag(&[from], Some(&to)).await
+    }
+
+    /// Replace each of `sources` with `#into` in every note; returns how many
+    /// notes changed.
+    pub async fn merge_tags(&self, sources: &[String], into: &str) -> Result<usize> {
+        let into = parse_tag_name(into)?;
+        let mut from = Vec::new();
+        for source in sources {
+            let source = parse_tag_name(source)?;
+            if source != into && !from.contains(&source) {
+                from.push(source);
+            }
+        }
+        if from.is_empty() {
+            return Err(crate::Error::InvalidInput(format!(
+                "Name at least one tag other than #{into} to merge"
+            )));
+        }
+        self.retag(&from, Some(&into)).await
+    }
+
+    /// Remove `#tag` from every note; returns how many notes changed.
+    pub async fn remove_tag(&self, tag: &str) -> Result<usize> {
+        let tag = parse_tag_name(tag)?;
+        self.retag(&[tag], None).await
+    }
+
+    /// Run a tag rewrite and save a revision of every note it changed.
+    async fn retag(&self, from: &[String], to: Option<&str>) -> Result<usize> {
+        let db = self.db.lock().await;
+        let changed = db.note_repository().retag(from, to).await?;
+        for (previous, updated) in &changed {
+            record_revision(&db, previous, updated, self.device_id.as_deref()).await?;
+        }
+        Ok(changed.len())
+    }
+
+    /// Check or uncheck the task on `line_index` of a note and save a
+    /// revision of the change.
+    pub async fn toggle_note_task(&self, id: &NoteId, line_index: usize) -> Result<Note> {
+        let db = self.db.lock().await;
+        let (previous, note) = db.note_repository().toggle_task(id, line_index).await?;
+        record_revision(&db, &previous, &note, self.device_id.as_deref()).await?;
+        Ok(note)
+    }
+
+    /// Unchecked tasks of every note in the note list, newest note first.
+    pub async fn list_open_tasks(&self) -> Result<Vec<(Note, Vec<NoteTask>)>> {
+        const PAGE_SIZE: usize = 500;
+
+        let mut open = Vec::new();
+        let mut offset = 0;
+        loop {
+            let batch = self.list_notes(PAGE_SIZE, offset).await?;
+            let count = batch.len();
+            for note in batch {
+                let tasks: Vec<NoteTask> = extract_tasks(&note.content)
+                    .into_iter()
+                    .filter(|task| !task.done)
+                    .collect();
+                if !tasks.is_empty() {
+                    open.push((note, tasks));
+                }
+            }
+            if count < PAGE_SIZE {
+                break;
+            }
+            offset += count;
+        }
+        Ok(open)
+    }
+
     /// List recently resolved sync conflicts.
     pub async fn list_conflicts(&self, limit: usize) -> Result<Vec<SyncConflict>> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.list_conflicts(limit).await
     }
 
+    /// List sync conflicts resolved at or after `since` (Unix ms).
+    pub async fn list_conflicts_since(
+        &self,
+        since: i64,
+        limit: usize,
+    ) -> Result<Vec<SyncConflict>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.list_conflicts_since(since, limit).await
+    }
+
+    /// Fetch one sync conflict.
+    pub async fn get_conflict(&self, id: i64) -> Result<Option<SyncConflict>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.get_conflict(id).await
+    }
+
+    /// Settle a sync conflict again, writing what `resolver` makes of both
+    /// versions to the note. The content it replaces is kept as a revision.
+    pub async fn resolve_conflict(&self, id: i64, resolver: &dyn ConflictResolver) -> Result<Note> {
+        let conflict = self
+            .get_conflict(id)
+            .await?
+            .ok_or_else(|| crate::Error::NotFound(format!("Sync conflict {id}")))?;
+        let (local, remote) = conflict.versions()?;
+        let note_id: NoteId = conflict
+            .note_id
+            .parse()
+            .map_err(|_| crate::Error::InvalidInput("Invalid note ID".into()))?;
+
+        let note = self
+            .update_note(&note_id, &resolver.resolve(local, remote))
+            .await?;
+        let db = self.db.lock().await;
+        db.note_repository()
+            .mark_conflict_resolved(id, resolver.name())
+            .await?;
+        Ok(note)
+    }
+
     /// Create attachment metadata for a note.
     pub async fn create_attachment(
         &self,
@@ -339,25 +1047,209 @@ impl DatabaseService {
         r2_key: &str,
     ) -> Result<Attachment> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.create_attachment(note_id, filename, mime_type, size_bytes, r2_key)
             .await
     }
 
+    /// Store text extracted from an attachment (see
+    /// [`crate::search::attachment_search_text`]) so search can find it.
+    ///
+    /// Nothing is stored while encryption is on, since the index holds
+    /// plaintext.
+    pub async fn set_attachment_text(
+        &self,
+        attachment_id: &AttachmentId,
+        text: Option<&str>,
+    ) -> Result<()> {
+        let db = self.db.lock().await;
+        if text.is_some() && db.encryption().is_enabled() {
+            return Ok(());
+        }
+        let repo = db.note_repository();
+        repo.set_attachment_text(attachment_id, text).await
+    }
+
     /// List non-deleted attachment metadata for a note.
     pub async fn list_attachments(&self, note_id: &NoteId) -> Result<Vec<Attachment>> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.list_attachments(note_id).await
     }
 
+    /// List non-deleted attachment metadata across all live notes.
+    pub async fn list_all_attachments(&self) -> Result<Vec<Attachment>> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.list_all_attachments().await
+    }
+
+    /// Compare live attachment records with `objects`, as listed by
+    /// [`crate::media::MediaApiClient::list_objects`].
+    pub async fn audit_attachments(&self, objects: &[StoredObject]) -> Result<AttachmentAudit> {
+        let attachments = self.list_all_attachments().await?;
+        Ok(audit_attachments(
+            &attachments,
+            objects,
+            chrono::Utc::now().timestamp(),
+        ))
+    }
+
+    /// Delete the records of `audit` whose object is missing; returns how many
+    /// were deleted.
+    pub async fn repair_attachments(&self, audit: &AttachmentAudit) -> Result<usize> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        for attachment in &audit.missing {
+            repo.delete_attachment(&attachment.id).await?;
+        }
+        Ok(audit.missing.len())
+    }
+
     /// Soft-delete attachment metadata by id.
     pub async fn delete_attachment(&self, attachment_id: &AttachmentId) -> Result<()> {
         let db = self.db.lock().await;
-        let repo = LibSqlNoteRepository::new(db.connection());
+        let repo = db.note_repository();
         repo.delete_attachment(attachment_id).await
     }
 
+    /// Record the server-side scan result for an attachment.
+    pub async fn set_attachment_scan_status(
+        &self,
+        attachment_id: &AttachmentId,
+        status: AttachmentScanStatus,
+    ) -> Result<()> {
+        let db = self.db.lock().await;
+        let repo = db.note_repository();
+        repo.set_attachment_scan_status(attachment_id, status).await
+    }
+
+    /// Journal unsaved editor content for crash recovery.
+    pub async fn save_draft(&self, note_id: &NoteId, content: &str) -> Result<NoteDraft> {
+        let db = self.db.lock().await;
+        let repo = LibSqlDraftRepository::new(db.connection());
+        repo.save_draft(note_id, content).await
+    }
+
+    /// List journaled drafts that were never saved to their note.
+    pub async fn list_recoverable_drafts(&self) -> Result<Vec<NoteDraft>> {
+        let db = self.db.lock().await;
+        let repo = LibSqlDraftRepository::new(db.connection());
+        repo.list_recoverable_drafts().await
+    }
+
+    /// Remove the journaled draft for a note.
+    pub async fn discard_draft(&self, note_id: &NoteId) -> Result<()> {
+        let db = self.db.lock().await;
+        let repo = LibSqlDraftRepository::new(db.connection());
+        repo.discard_draft(note_id).await
+    }
+
+    /// Store (or replace) the summary of a note.
+    pub async fn save_note_summary(&self, summary: &NoteSummary) -> Result<()> {
+        let db = self.db.lock().await;
+        let repo = LibSqlNoteSummaryRepository::new(db.connection());
+        repo.save_summary(summary).await
+    }
+
+    /// Get the stored summary of a note.
+    pub async fn get_note_summary(&self, note_id: &NoteId) -> Result<Option<NoteSummary>> {
+        let db = self.db.lock().await;
+        let repo = LibSqlNoteSummaryRepository::new(db.connection());
+        repo.get_summary(note_id).await
+    }
+
+    /// List stored summaries of notes that are not deleted.
+    pub async fn list_note_summaries(&self) -> Result<Vec<NoteSummary>> {
+        let db = self.db.lock().await;
+        let repo = LibSqlNoteSummaryRepository::new(db.connection());
+        repo.list_summaries().await
+    }
+
+    /// Flag or unflag a note as `no_export`.
+    pub async fn set_note_export_excluded(&self, note_id: &NoteId, excluded: bool) -> Result<()> {
+        let db = self.db.lock().await;
+        let repo = LibSqlExportExclusionRepository::new(db.connection());
+        repo.set_excluded(note_id, excluded).await
+    }
+
+    /// Whether a note is flagged `no_export`.
+    pub async fn is_note_export_excluded(&self, note_id: &NoteId) -> Result<bool> {
+        let db = self.db.lock().await;
+        let repo = LibSqlExportExclusionRepository::new(db.connection());
+        repo.is_excluded(note_id).await
+    }
+
+    /// Privacy rules for exports, digests, and shares.
+    ///
+    /// Combines per-note `no_export` flags with the `no_export_tags` setting;
+    /// `include_private` returns rules that keep every note.
+    pub async fn export_privacy(&self, include_private: bool) -> Result<ExportPrivacy> {
+        if include_private {
+            return Ok(ExportPrivacy::include_all());
+        }
+
+        let db = self.db.lock().await;
+        let excluded_note_ids = LibSqlExportExclusionRepository::new(db.connection())
+            .list_excluded()
+            .await?
+            .into_iter()
+            .collect();
+        let settings = LibSqlSettingsRepository::new(db.connection())
+            .load()
+            .await?;
+        Ok(ExportPrivacy {
+            excluded_note_ids,
+            excluded_tags: settings.no_export_tags,
+        })
+    }
+
+    /// Get the incremental export state of a destination.
+    pub async fn get_export_state(&self, destination: &str) -> Result<Option<ExportState>> {
+        let db = self.db.lock().await;
+        let repo = LibSqlExportStateRepository::new(db.connection());
+        repo.get_state(destination).await
+    }
+
+    /// Store (or replace) the incremental export state of a destination.
+    pub async fn save_export_state(&self, state: &ExportState) -> Result<()> {
+        let db = self.db.lock().await;
+        let repo = LibSqlExportStateRepository::new(db.connection());
+        repo.save_state(state).await
+    }
+
+    /// List the manual note order of a scope.
+    pub async fn list_manual_order(&self, scope: &SortScope) -> Result<Vec<NoteId>> {
+        let db = self.db.lock().await;
+        let repo = LibSqlNoteOrderRepository::new(db.connection());
+        repo.list_order(scope).await
+    }
+
+    /// Move a note to `to_index` within the displayed order of a scope.
+    ///
+    /// `visible` is the order the notes are currently shown in; it is saved
+    /// with the move applied and returned.
+    pub async fn move_note_in_scope(
+        &self,
+        scope: &SortScope,
+        visible: &[NoteId],
+        note_id: NoteId,
+        to_index: usize,
+    ) -> Result<Vec<NoteId>> {
+        let order = move_note_in_order(visible, note_id, to_index);
+        let db = self.db.lock().await;
+        let repo = LibSqlNoteOrderRepository::new(db.connection());
+        repo.set_order(scope, &order).await?;
+        Ok(order)
+    }
+
+    /// Forget the manual note order of a scope.
+    pub async fn clear_manual_order(&self, scope: &SortScope) -> Result<()> {
+        let db = self.db.lock().await;
+        let repo = LibSqlNoteOrderRepository::new(db.connection());
+        repo.clear_order(scope).await
+    }
+
     /// Load settings.
     pub async fn load_settings(&self) -> Result<Settings> {
         let db = self.db.lock().await;
@@ -371,11 +1263,498 @@ impl DatabaseService {
         let repo = LibSqlSettingsRepository::new(db.connection());
         repo.save(settings).await
     }
+
+    /// Record the running version as seen and return the release notes the
+    /// user has not been shown yet, newest first.
+    pub async fn take_unseen_release_notes(&self) -> Result<Vec<Release>> {
+        let db = self.db.lock().await;
+        let repo = LibSqlSettingsRepository::new(db.connection());
+        let mut settings = repo.load().await?;
+        let last_seen = settings.last_seen_version.as_deref();
+        if !should_record_current_version(last_seen) {
+            return Ok(Vec::new());
+        }
+        let unseen = unseen_releases(last_seen);
+        settings.last_seen_version = Some(CURRENT_VERSION.to_string());
+        repo.save(&settings).await?;
+        Ok(unseen)
+    }
+}
+
+/// Run the routing rules over a note that was just created or edited, saving
+/// the routed content when a rule changed it.
+async fn apply_routing_rules(
+    db: &Database,
+    note: Note,
+    source: Option<NoteSource>,
+) -> Result<Note> {
+    let rules = LibSqlRoutingRuleRepository::new(db.connection())
+        .list()
+        .await?;
+    let outcome = route_note(&rules, &note.content, source);
+    if !outcome.changed() {
+        return Ok(note);
+    }
+    db.note_repository()
+        .update(&note.id, &outcome.content)
+        .await
+}
+
+/// Add an edit to the note's history and apply the retention limit.
+///
+/// Notes edited for the first time start their history with the content they
+/// had before, whose device is unknown.
+async fn encrypt_stored_content(db: &Database) -> Result<u64> {
+    let notes = db.note_repository().encrypt_stored_content().await?;
+    db.revision_repository().encrypt_stored_content().await?;
+    Ok(notes)
+}
+
+async fn record_revision(
+    db: &Database,
+    previous: &Note,
+    updated: &Note,
+    device_id: Option<&str>,
+) -> Result<()> {
+    if previous.content == updated.content {
+        return Ok(());
+    }
+    let repo = db.revision_repository();
+    // Earlier revisions would keep what a seal or encryption envelope hides.
+    if sealed_until(&updated.content).is_some() || is_encrypted(&updated.content) {
+        repo.prune(&updated.id, 0).await?;
+        return Ok(());
+    }
+    if repo.latest(&previous.id).await?.is_none() {
+        repo.record(&previous.id, &previous.content, previous.updated_at, None)
+            .await?;
+    }
+    repo.record(&updated.id, &updated.content, updated.updated_at, device_id)
+        .await?;
+
+    let settings = LibSqlSettingsRepository::new(db.connection())
+        .load()
+        .await?;
+    if let Some(keep) = settings.revision_retention {
+        repo.prune(&updated.id, keep).await?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crypto::{is_encrypted, is_encrypted_bytes};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn note_edits_are_kept_as_revisions_and_can_be_restored() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        service
+            .save_settings(&Settings {
+                revision_retention: Some(3),
+                ..Settings::default()
+            })
+            .await
+            .unwrap();
+        let note = service.create_note("First").await.unwrap();
+        for content in ["Second", "Second", "Third", "Fourth"] {
+            service.update_note(&note.id, content).await.unwrap();
+        }
+
+        // Unchanged saves add nothing, and only the newest three are kept.
+        let revisions = service.list_note_revisions(&note.id).await.unwrap();
+        assert_eq!(
+            revisions
+                .iter()
+                .map(|revision| (revision.revision, revision.content.as_str()))
+                .collect::<Vec<_>>(),
+            [(4, "Fourth"), (3, "Third"), (2, "Second")]
+        );
+
+        let restored = service.restore_note_revision(&note.id, 2).await.unwrap();
+        assert_eq!(restored.content, "Second");
+        let latest = &service.list_note_revisions(&note.id).await.unwrap()[0];
+        assert_eq!((latest.revision, latest.content.as_str()), (5, "Second"));
+        assert!(matches!(
+            service.restore_note_revision(&note.id, 1).await,
+            Err(crate::Error::NotFound(_))
+        ));
+
+        service
+            .save_settings(&Settings {
+                revision_retention: Some(1),
+                ..Settings::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(service.prune_note_revisions().await.unwrap(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn local_only_databases_queue_no_sync_changes() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let note = service.create_note("Offline edit").await.unwrap();
+        service.enqueue_sync_change(&note.id).await.unwrap();
+        assert_eq!(service.pending_sync_count().await.unwrap(), 0);
+        assert!(service.pending_sync_changes().await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn local_only_databases_save_sync_excluded_tags_as_is() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let note = service.create_note("Diary #private").await.unwrap();
+        let changed = service
+            .set_sync_excluded_tags(vec!["private".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(changed, 0);
+        assert_eq!(
+            service.load_settings().await.unwrap().sync_excluded_tags,
+            ["private"]
+        );
+        let stored = service.get_note(&note.id).await.unwrap().unwrap();
+        assert_eq!(stored.content, "Diary #private");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tags_can_be_renamed_merged_and_removed() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let standup = service.create_note("Standup #Work #todo").await.unwrap();
+        let review = service.create_note("Review #job\n\n#work").await.unwrap();
+        service.create_note("Groceries #todo").await.unwrap();
+
+        assert_eq!(service.rename_tag("#work", "office").await.unwrap(), 2);
+        let renamed = service.get_note(&standup.id).await.unwrap().unwrap();
+        assert_eq!(renamed.content, "Standup #office #todo");
+        assert_eq!(
+            service
+                .list_note_revisions(&standup.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let sources = ["job".to_string(), "office".to_string()];
+        assert_eq!(service.merge_tags(&sources, "career").await.unwrap(), 2);
+        let merged = service.get_note(&review.id).await.unwrap().unwrap();
+        assert_eq!(merged.content, "Review #career");
+
+        assert_eq!(service.remove_tag("todo").await.unwrap(), 2);
+        assert_eq!(
+            service.list_tags().await.unwrap(),
+            vec![("career".to_string(), 2)]
+        );
+        assert_eq!(service.remove_tag("todo").await.unwrap(), 0);
+        assert!(service.rename_tag("career", "#Career").await.is_err());
+        assert!(service
+            .merge_tags(&["career".to_string()], "career")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tasks_are_listed_and_toggled_with_revisions() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let errands = service
+            .create_note("Errands\n- [ ] post office\n- [x] bank")
+            .await
+            .unwrap();
+        service.create_note("No tasks here").await.unwrap();
+
+        let open = service.list_open_tasks().await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].0.id, errands.id);
+        assert_eq!(
+            open[0].1,
+            vec![NoteTask {
+                line_index: 1,
+                done: false,
+                text: "post office".to_string(),
+            }]
+        );
+
+        let toggled = service.toggle_note_task(&errands.id, 1).await.unwrap();
+        assert_eq!(toggled.content, "Errands\n- [x] post office\n- [x] bank");
+        assert!(service.list_open_tasks().await.unwrap().is_empty());
+        assert_eq!(
+            service
+                .list_note_revisions(&errands.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(service.toggle_note_task(&errands.id, 0).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn attachment_audit_repairs_records_without_objects() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let note = service.create_note("Scans").await.unwrap();
+        let kept = service
+            .create_attachment(&note.id, "kept.png", "image/png", 3, "notes/a/kept.png")
+            .await
+            .unwrap();
+        service
+            .create_attachment(&note.id, "gone.png", "image/png", 3, "notes/a/gone.png")
+            .await
+            .unwrap();
+        let objects = [StoredObject {
+            key: kept.r2_key.clone(),
+            size_bytes: 3,
+            last_modified: Some(0),
+        }];
+
+        let audit = service.audit_attachments(&objects).await.unwrap();
+        assert_eq!(audit.missing.len(), 1);
+        assert!(audit.orphaned.is_empty());
+        assert_eq!(service.repair_attachments(&audit).await.unwrap(), 1);
+        assert_eq!(service.list_all_attachments().await.unwrap(), [kept]);
+        assert!(service
+            .audit_attachments(&objects)
+            .await
+            .unwrap()
+            .is_clean());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resolving_a_conflict_restores_the_rejected_version() {
+        use crate::sync::ConflictStrategy;
+
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let note = service.create_note("Groceries\n- eggs").await.unwrap();
+        {
+            // A stale remote write loses to last-write-wins.
+            let db = service.db.lock().await;
+            db.connection()
+                .execute(
+                    "UPDATE notes SET content = ?, updated_at = ? WHERE id = ?",
+                    libsql::params![
+                        "Groceries\n- bread",
+                        note.updated_at - 10_000,
+                        note.id.as_str()
+                    ],
+                )
+                .await
+                .unwrap();
+        }
+        let conflict = service.list_conflicts(1).await.unwrap().remove(0);
+
+        let merged = service
+            .resolve_conflict(conflict.id, ConflictStrategy::MergeByLine.resolver())
+            .await
+            .unwrap();
+        assert_eq!(merged.content, "Groceries\n- eggs\n- bread");
+        let resolved = service.get_conflict(conflict.id).await.unwrap().unwrap();
+        assert_eq!(resolved.strategy, "merge-by-line");
+
+        let remote = service
+            .resolve_conflict(conflict.id, ConflictStrategy::KeepRemote.resolver())
+            .await
+            .unwrap();
+        assert_eq!(remote.content, "Groceries\n- bread");
+        // Every version the resolutions replaced is still in the history.
+        assert_eq!(
+            service.list_note_revisions(&note.id).await.unwrap().len(),
+            3
+        );
+
+        assert!(matches!(
+            service
+                .resolve_conflict(conflict.id + 1, ConflictStrategy::KeepLocal.resolver())
+                .await,
+            Err(crate::Error::NotFound(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn encrypted_notes_are_stored_as_ciphertext_and_read_transparently() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let note = service.create_note("Draft #plans").await.unwrap();
+        service
+            .update_note(&note.id, "Secret plans #plans")
+            .await
+            .unwrap();
+        assert!(!service.is_encrypted().await);
+
+        assert_eq!(service.enable_encryption("correct horse").await.unwrap(), 1);
+        assert!(service.enable_encryption("correct horse").await.is_err());
+        let stored: Vec<String> = {
+            let db = service.db.lock().await;
+            let mut rows = db
+                .connection()
+                .query(
+                    "SELECT content FROM notes UNION ALL SELECT content FROM note_revisions",
+                    (),
+                )
+                .await
+                .unwrap();
+            let mut stored = Vec::new();
+            while let Some(row) = rows.next().await.unwrap() {
+                stored.push(row.get(0).unwrap());
+            }
+            stored
+        };
+        assert_eq!(stored.len(), 3);
+        assert!(stored.iter().all(|content| is_encrypted(content)));
+
+        let read = service.get_note(&note.id).await.unwrap().unwrap();
+        assert_eq!(read.content, "Secret plans #plans");
+        assert_eq!(service.search_notes("secret", 10).await.unwrap().len(), 1);
+        assert_eq!(
+            service
+                .list_notes_by_tag("plans", 10, 0)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        let bytes = service
+            .encrypt_attachment_bytes(b"photo".to_vec())
+            .await
+            .unwrap();
+        assert!(is_encrypted_bytes(&bytes));
+
+        service.lock_encryption().await;
+        let locked = service.get_note(&note.id).await.unwrap().unwrap();
+        assert!(is_encrypted(&locked.content));
+        assert!(service.create_note("While locked").await.is_err());
+        assert!(service
+            .decrypt_attachment_bytes(bytes.clone())
+            .await
+            .is_err());
+        assert!(service.unlock_encryption("battery staple").await.is_err());
+
+        assert_eq!(service.unlock_encryption("correct horse").await.unwrap(), 0);
+        assert_eq!(
+            service.decrypt_attachment_bytes(bytes).await.unwrap(),
+            b"photo"
+        );
+        let revisions = service.list_note_revisions(&note.id).await.unwrap();
+        assert_eq!(revisions[1].content, "Draft #plans");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn encrypted_notes_leave_no_plaintext_tags_urls_or_links() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let plan = service.create_note("Plan #travel").await.unwrap();
+        service
+            .create_note("Tickets https://trains.example [[Plan]] #travel")
+            .await
+            .unwrap();
+        service.enable_encryption("correct horse").await.unwrap();
+        service
+            .create_note("Hotel https://hotel.example [[Plan]] #lodging")
+            .await
+            .unwrap();
+
+        {
+            let db = service.db.lock().await;
+            let mut rows = db
+                .connection()
+                .query(
+                    "SELECT (SELECT COUNT(*) FROM tags) + (SELECT COUNT(*) FROM note_tags)
+                        + (SELECT COUNT(*) FROM note_urls) + (SELECT COUNT(*) FROM note_links)",
+                    (),
+                )
+                .await
+                .unwrap();
+            let derived_rows: i64 = rows.next().await.unwrap().unwrap().get(0).unwrap();
+            assert_eq!(derived_rows, 0);
+        }
+
+        assert_eq!(
+            service.list_tags().await.unwrap(),
+            vec![("travel".to_string(), 2), ("lodging".to_string(), 1)]
+        );
+        let urls: Vec<String> = service
+            .list_urls(Some("travel"), 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|note_url| note_url.url)
+            .collect();
+        assert_eq!(urls, vec!["https://trains.example".to_string()]);
+        assert_eq!(service.backlinks(&plan.id).await.unwrap().len(), 2);
+        assert_eq!(
+            service
+                .search_notes("tag:lodging url:hotel", 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn routing_rules_tag_new_and_edited_notes() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        service
+            .add_routing_rule(
+                &"source:email-in".parse().unwrap(),
+                &"#inbox".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+        service
+            .add_routing_rule(&"#work".parse().unwrap(), &"#office".parse().unwrap())
+            .await
+            .unwrap();
+
+        let mailed = service
+            .create_note_from("Quarterly report", NoteSource::EmailIn)
+            .await
+            .unwrap();
+        assert_eq!(mailed.content, "Quarterly report\n\n#inbox");
+
+        let plain = service.create_note("Standup notes").await.unwrap();
+        assert_eq!(plain.content, "Standup notes");
+        let edited = service
+            .update_note(&plain.id, "Standup notes #work")
+            .await
+            .unwrap();
+        assert_eq!(edited.content, "Standup notes #work\n\n#office");
+
+        let rules = service.list_routing_rules().await.unwrap();
+        assert!(service.remove_routing_rule(rules[1].id).await.unwrap());
+        let unrouted = service.create_note("More #work").await.unwrap();
+        assert_eq!(unrouted.content, "More #work");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn release_notes_are_shown_once_after_an_upgrade() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        assert!(service
+            .take_unseen_release_notes()
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            service
+                .load_settings()
+                .await
+                .unwrap()
+                .last_seen_version
+                .as_deref(),
+            Some(CURRENT_VERSION)
+        );
+
+        service
+            .save_settings(&Settings {
+                last_seen_version: Some("0.0.1".to_string()),
+                ..Settings::default()
+            })
+            .await
+            .unwrap();
+        let unseen = service.take_unseen_release_notes().await.unwrap();
+        assert_eq!(unseen[0].version, CURRENT_VERSION);
+        assert!(service
+            .take_unseen_release_notes()
+            .await
+            .unwrap()
+            .is_empty());
+    }
 
     #[tokio::test(flavor = "multi_thread")]
     async fn in_memory_create_and_list_roundtrip() {
@@ -387,6 +1766,47 @@ mod tests {
         assert_eq!(notes[0].content, "hello core");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn exportable_notes_skip_private_notes_unless_included() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let flagged = service.create_note("Diary").await.unwrap();
+        service.create_note("Salary review #private").await.unwrap();
+        let public = service.create_note("Release notes").await.unwrap();
+        service
+            .set_note_export_excluded(&flagged.id, true)
+            .await
+            .unwrap();
+        service
+            .save_settings(&Settings {
+                no_export_tags: vec!["private".to_string()],
+                ..Settings::default()
+            })
+            .await
+            .unwrap();
+
+        let exported = service.list_exportable_notes(false).await.unwrap();
+        assert_eq!(exported, vec![public]);
+        assert!(service.is_note_export_excluded(&flagged.id).await.unwrap());
+        assert_eq!(service.list_exportable_notes(true).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn archived_notes_leave_the_list_but_are_still_exported() {
+        let service = DatabaseService::open_in_memory().await.unwrap();
+        let kept = service.create_note("Kept").await.unwrap();
+        let archived = service.create_note("Done").await.unwrap();
+        let archived = service.set_note_archived(&archived.id, true).await.unwrap();
+
+        assert_eq!(service.list_notes(10, 0).await.unwrap(), vec![kept]);
+        assert_eq!(
+            service.list_archived_notes(10, 0).await.unwrap(),
+            vec![archived.clone()]
+        );
+        let exported = service.list_exportable_notes(false).await.unwrap();
+        assert_eq!(exported.len(), 2);
+        assert!(exported.contains(&archived));
+    }
+
     #[test]
     fn detects_recoverable_local_replica_errors() {
         assert!(DatabaseService::is_recoverable_local_replica_error(
@@ -0,0 +1,261 @@
+//! Public read-only links to notes.
+//!
+//! The Dirt API signs a link for one note (`POST /v1/notes/{id}/share`) that
+//! anyone can open until it expires or is revoked; opening it returns the
+//! note and signed download URLs for its attachments. [`ShareApiClient`]
+//! wraps those endpoints for the CLI and desktop.
+
+use reqwest::Response;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::media::normalize_base_url;
+use crate::models::NoteId;
+use crate::util::compact_text;
+
+/// Link lifetime when none is requested
+pub const DEFAULT_SHARE_EXPIRY_SECS: i64 = 7 * 86_400;
+/// Longest link lifetime the API issues
+pub const MAX_SHARE_EXPIRY_SECS: i64 = 90 * 86_400;
+/// Shortest link lifetime the API issues
+pub const MIN_SHARE_EXPIRY_SECS: i64 = 60;
+
+/// Path of the public share endpoint, followed by the link token
+pub const SHARED_NOTE_PATH: &str = "/v1/shared/";
+
+/// A share link as reported to its owner
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareLink {
+    pub id: String,
+    pub note_id: NoteId,
+    /// Public URL; only returned when the link is created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Creation timestamp (Unix seconds)
+    pub created_at: i64,
+    /// Expiry timestamp (Unix seconds)
+    pub expires_at: i64,
+    /// Revocation timestamp (Unix seconds)
+    #[serde(default)]
+    pub revoked_at: Option<i64>,
+}
+
+/// What a share link opens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedNote {
+    pub note_id: NoteId,
+    pub content: String,
+    pub tags: Vec<String>,
+    /// Creation timestamp (Unix ms)
+    pub created_at: i64,
+    /// Last update timestamp (Unix ms)
+    pub updated_at: i64,
+    /// When the link stops working (Unix seconds)
+    pub expires_at: i64,
+    pub attachments: Vec<SharedAttachment>,
+}
+
+/// Attachment of a shared note
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedAttachment {
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    /// Short-lived download URL; `None` when the server has no media storage
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateShareRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_in_secs: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShareLinksResponse {
+    shares: Vec<ShareLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorBody {
+    error: String,
+}
+
+/// Parse a link lifetime such as `30m`, `12h`, `7d`, or `2w` into seconds.
+///
+/// ```
+/// use dirt_core::share::parse_share_expiry;
+///
+/// assert_eq!(parse_share_expiry("7d").unwrap(), 7 * 86_400);
+/// assert!(parse_share_expiry("7").is_err());
+/// ```
+pub fn parse_share_expiry(input: &str) -> Result<i64> {
+    let input = input.trim();
+    let invalid = || {
+        Error::InvalidInput(format!(
+            "Invalid expiry '{input}' (expected a number with m, h, d, or w, e.g. 7d)"
+        ))
+    };
+    let unit = input.chars().last().ok_or_else(invalid)?;
+    let unit_secs = match unit.to_ascii_lowercase() {
+        'm' => 60,
+        'h' => 3_600,
+        'd' => 86_400,
+        'w' => 7 * 86_400,
+        _ => return Err(invalid()),
+    };
+    let amount: i64 = input[..input.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| invalid())?;
+    let secs = amount.checked_mul(unit_secs).ok_or_else(invalid)?;
+    if !(MIN_SHARE_EXPIRY_SECS..=MAX_SHARE_EXPIRY_SECS).contains(&secs) {
+        return Err(Error::InvalidInput(format!(
+            "Share links must expire within 1 minute to {} days",
+            MAX_SHARE_EXPIRY_SECS / 86_400
+        )));
+    }
+    Ok(secs)
+}
+
+/// HTTP client for the Dirt API share endpoints.
+#[derive(Debug, Clone)]
+pub struct ShareApiClient {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl ShareApiClient {
+    /// Builds a client for an explicit API base URL.
+    pub fn new(base_url: impl Into<String>) -> std::result::Result<Self, String> {
+        let base_url = normalize_base_url(base_url.into().as_str())?;
+        let client = reqwest::Client::builder()
+            .build()
+            .map_err(|error| format!("Failed to construct HTTP client: {error}"))?;
+        Ok(Self { base_url, client })
+    }
+
+    /// Creates a link to `note_id`; `expires_in_secs` defaults to
+    /// [`DEFAULT_SHARE_EXPIRY_SECS`] on the server.
+    pub async fn create(
+        &self,
+        access_token: &str,
+        note_id: &NoteId,
+        expires_in_secs: Option<i64>,
+    ) -> std::result::Result<ShareLink, String> {
+        let response = self
+            .client
+            .post(format!("{}/v1/notes/{note_id}/share", self.base_url))
+            .bearer_auth(access_token)
+            .header("Accept", "application/json")
+            .json(&CreateShareRequest { expires_in_secs })
+            .send()
+            .await
+            .map_err(|error| format!("Failed to create share link: {error}"))?;
+        let response = check_response(response, "Share link request").await?;
+        response
+            .json::<ShareLink>()
+            .await
+            .map_err(|error| format!("Failed to parse share link response: {error}"))
+    }
+
+    /// Lists the links created for `note_id`, newest first.
+    pub async fn list(
+        &self,
+        access_token: &str,
+        note_id: &NoteId,
+    ) -> std::result::Result<Vec<ShareLink>, String> {
+        let response = self
+            .client
+            .get(format!("{}/v1/notes/{note_id}/shares", self.base_url))
+            .bearer_auth(access_token)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|error| format!("Failed to list share links: {error}"))?;
+        let response = check_response(response, "Share link listing").await?;
+        response
+            .json::<ShareLinksResponse>()
+            .await
+            .map(|body| body.shares)
+            .map_err(|error| format!("Failed to parse share links response: {error}"))
+    }
+
+    /// Revokes a link so it no longer opens the note.
+    pub async fn revoke(&self, access_token: &str, id: &str) -> std::result::Result<(), String> {
+        let response = self
+            .client
+            .delete(format!(
+                "{}/v1/shares/{}",
+                self.base_url,
+                urlencoding::encode(id)
+            ))
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|error| format!("Failed to revoke share link: {error}"))?;
+        check_response(response, "Share link revocation").await?;
+        Ok(())
+    }
+
+    /// Opens a share link without signing in, as its recipient would.
+    pub async fn open(&self, share_url: &str) -> std::result::Result<SharedNote, String> {
+        let response = self
+            .client
+            .get(share_url)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .map_err(|error| format!("Failed to open share link: {error}"))?;
+        let response = check_response(response, "Share link").await?;
+        response
+            .json::<SharedNote>()
+            .await
+            .map_err(|error| format!("Failed to parse shared note: {error}"))
+    }
+}
+
+async fn check_response(response: Response, label: &str) -> std::result::Result<Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<ApiErrorBody>(&body)
+        .map_or_else(|_| compact_text(&body), |body| body.error);
+    Err(format!(
+        "{label} failed with HTTP {}: {message}",
+        status.as_u16()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_share_expiry_accepts_units_within_limits() {
+        assert_eq!(parse_share_expiry("30m").unwrap(), 1_800);
+        assert_eq!(parse_share_expiry(" 12h ").unwrap(), 43_200);
+        assert_eq!(parse_share_expiry("2W").unwrap(), 14 * 86_400);
+        assert!(parse_share_expiry("").is_err());
+        assert!(parse_share_expiry("d").is_err());
+        assert!(parse_share_expiry("7y").is_err());
+        assert!(parse_share_expiry("0d").is_err());
+        assert!(parse_share_expiry("91d").is_err());
+    }
+
+    #[test]
+    fn share_link_omits_url_unless_created() {
+        let link = ShareLink {
+            id: "share".to_string(),
+            note_id: NoteId::new(),
+            url: None,
+            created_at: 100,
+            expires_at: 200,
+            revoked_at: None,
+        };
+        let json = serde_json::to_string(&link).unwrap();
+        assert!(!json.contains("\"url\""));
+        assert_eq!(serde_json::from_str::<ShareLink>(&json).unwrap(), link);
+    }
+}
@@ -0,0 +1,137 @@
+//! Capture statistics
+//!
+//! Summarizes how notes are captured, so users can see which interfaces they
+//! actually rely on.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::NoteSource;
+
+/// Number of notes captured from one source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceCount {
+    pub source: NoteSource,
+    pub count: usize,
+}
+
+/// Per-source breakdown of the notes in the database.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureStats {
+    /// Non-deleted notes across all sources.
+    pub total_notes: usize,
+    /// Sources with at least one note, largest first.
+    pub by_source: Vec<SourceCount>,
+}
+
+impl CaptureStats {
+    /// Build stats from per-source counts (as returned by the repository).
+    #[must_use]
+    pub fn from_counts(counts: &[(NoteSource, usize)]) -> Self {
+        let mut by_source: Vec<SourceCount> = Vec::new();
+        for &(source, count) in counts {
+            if count == 0 {
+                continue;
+            }
+            match by_source.iter_mut().find(|entry| entry.source == source) {
+                Some(entry) => entry.count += count,
+                None => by_source.push(SourceCount { source, count }),
+            }
+        }
+        by_source.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.source.as_str().cmp(b.source.as_str()))
+        });
+
+        Self {
+            total_notes: by_source.iter().map(|entry| entry.count).sum(),
+            by_source,
+        }
+    }
+
+    /// Whole-number percentage of notes captured from `source`.
+    #[must_use]
+    pub fn percent(&self, source: NoteSource) -> usize {
+        if self.total_notes == 0 {
+            return 0;
+        }
+        let count = self
+            .by_source
+            .iter()
+            .find(|entry| entry.source == source)
+            .map_or(0, |entry| entry.count);
+        (count * 100 + self.total_notes / 2) / self.total_notes
+    }
+
+    /// Plain-text table, one source per line.
+    #[must_use]
+    pub fn render_text(&self) -> String {
+        let width = self
+            .by_source
+            .iter()
+            .map(|entry| entry.source.as_str().len())
+            .max()
+            .unwrap_or(0);
+        let mut lines: Vec<String> = self
+            .by_source
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{:<width$}  {:>5}  {:>3}%",
+                    entry.source.as_str(),
+                    entry.count,
+                    self.percent(entry.source)
+                )
+            })
+            .collect();
+        lines.push(format!("{} notes total", self.total_notes));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_counts_merges_and_orders_sources() {
+        let stats = CaptureStats::from_counts(&[
+            (NoteSource::Cli, 3),
+            (NoteSource::Unknown, 1),
+            (NoteSource::DesktopHotkey, 4),
+            (NoteSource::Unknown, 2),
+            (NoteSource::Api, 0),
+        ]);
+
+        assert_eq!(stats.total_notes, 10);
+        assert_eq!(
+            stats.by_source,
+            vec![
+                SourceCount {
+                    source: NoteSource::DesktopHotkey,
+                    count: 4
+                },
+                SourceCount {
+                    source: NoteSource::Cli,
+                    count: 3
+                },
+                SourceCount {
+                    source: NoteSource::Unknown,
+                    count: 3
+                },
+            ]
+        );
+        assert_eq!(stats.percent(NoteSource::DesktopHotkey), 40);
+        assert_eq!(stats.percent(NoteSource::Api), 0);
+    }
+
+    #[test]
+    fn render_text_aligns_columns() {
+        let stats =
+            CaptureStats::from_counts(&[(NoteSource::MobileShare, 1), (NoteSource::Cli, 3)]);
+        assert_eq!(
+            stats.render_text(),
+            "cli               3   75%\nmobile-share      1   25%\n4 notes total"
+        );
+    }
+}
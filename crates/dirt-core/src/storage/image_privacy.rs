@@ -0,0 +1,385 @@
+//! Privacy processing applied to image attachments before upload.
+//!
+//! Camera photos carry EXIF metadata such as GPS coordinates, device serial
+//! numbers, and capture times. When enabled, JPEG, PNG, and WebP uploads have
+//! their metadata removed and can be downscaled to a maximum dimension. Other
+//! files are uploaded unchanged.
+
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+
+use crate::models::Settings;
+use crate::{Error, Result};
+
+/// JPEG quality used when an image has to be re-encoded.
+const REENCODE_JPEG_QUALITY: u8 = 90;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// PNG chunks that hold metadata rather than pixels or color information.
+const PNG_METADATA_CHUNKS: [&[u8; 4]; 5] = [b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+/// `VP8X` header flags announcing EXIF and XMP chunks.
+const WEBP_EXIF_FLAG: u8 = 0x08;
+const WEBP_XMP_FLAG: u8 = 0x04;
+
+/// Image privacy options, read from [`Settings`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImagePrivacyOptions {
+    /// Remove EXIF, GPS, XMP, and text metadata.
+    pub strip_metadata: bool,
+    /// Downscale images whose longer side exceeds this many pixels.
+    pub max_dimension: Option<u32>,
+}
+
+impl ImagePrivacyOptions {
+    /// Options configured in `settings`.
+    #[must_use]
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            strip_metadata: settings.image_strip_metadata,
+            max_dimension: settings.image_max_dimension.filter(|max| *max > 0),
+        }
+    }
+
+    /// Whether uploads pass through unchanged.
+    #[must_use]
+    pub const fn is_noop(&self) -> bool {
+        !self.strip_metadata && self.max_dimension.is_none()
+    }
+}
+
+/// Apply `options` to attachment bytes about to be uploaded.
+///
+/// Metadata is removed without re-encoding when the image keeps its size and
+/// orientation. Downscaled images, and images whose EXIF orientation has to be
+/// baked into the pixels before it is stripped, are re-encoded in their
+/// original format, which also drops all metadata. Bytes that are not a
+/// JPEG, PNG, or WebP image are returned unchanged.
+pub fn prepare_image_upload(
+    bytes: &[u8],
+    mime_type: &str,
+    options: ImagePrivacyOptions,
+) -> Result<Vec<u8>> {
+    let Some(format) = privacy_image_format(bytes, mime_type) else {
+        return Ok(bytes.to_vec());
+    };
+    if options.is_noop() {
+        return Ok(bytes.to_vec());
+    }
+
+    let mut decoder = ImageReader::with_format(Cursor::new(bytes), format)
+        .into_decoder()
+        .map_err(|error| decode_error(&error))?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let (width, height) = decoder.dimensions();
+    let too_large = options
+        .max_dimension
+        .is_some_and(|max| width.max(height) > max);
+
+    if !too_large {
+        if !options.strip_metadata {
+            return Ok(bytes.to_vec());
+        }
+        if orientation == Orientation::NoTransforms {
+            return strip_image_metadata(bytes, format);
+        }
+    }
+
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|error| decode_error(&error))?;
+    image.apply_orientation(orientation);
+    if let Some(max) = options.max_dimension.filter(|_| too_large) {
+        image = image.resize(max, max, FilterType::Lanczos3);
+    }
+    encode_image(&image, format)
+}
+
+/// Image formats this module can process, detected from the bytes.
+fn privacy_image_format(bytes: &[u8], mime_type: &str) -> Option<ImageFormat> {
+    if !mime_type.trim().to_ascii_lowercase().starts_with("image/") {
+        return None;
+    }
+    match image::guess_format(bytes).ok()? {
+        format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP) => Some(format),
+        _ => None,
+    }
+}
+
+fn decode_error(error: &image::ImageError) -> Error {
+    Error::InvalidInput(format!(
+        "Failed to read image for metadata stripping: {error}"
+    ))
+}
+
+fn strip_image_metadata(bytes: &[u8], format: ImageFormat) -> Result<Vec<u8>> {
+    match format {
+        ImageFormat::Jpeg => strip_jpeg_metadata(bytes),
+        ImageFormat::Png => strip_png_metadata(bytes),
+        ImageFormat::WebP => strip_webp_metadata(bytes),
+        _ => Ok(bytes.to_vec()),
+    }
+}
+
+fn malformed(kind: &str) -> Error {
+    Error::InvalidInput(format!("Failed to strip image metadata: malformed {kind}"))
+}
+
+/// Drop every APPn segment except JFIF (APP0), ICC profiles (APP2), and Adobe
+/// color transforms (APP14), plus comment segments.
+fn strip_jpeg_metadata(bytes: &[u8]) -> Result<Vec<u8>> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return Err(malformed("JPEG"));
+    }
+
+    let mut output = Vec::with_capacity(bytes.len());
+    output.extend_from_slice(&bytes[..2]);
+    let mut offset = 2;
+    loop {
+        // Markers may be preceded by any number of 0xFF fill bytes.
+        while bytes.get(offset) == Some(&0xFF) && bytes.get(offset + 1) == Some(&0xFF) {
+            offset += 1;
+        }
+        let (Some(&0xFF), Some(&marker)) = (bytes.get(offset), bytes.get(offset + 1)) else {
+            return Err(malformed("JPEG"));
+        };
+        if marker == 0xD9 {
+            output.extend_from_slice(&bytes[offset..]);
+            return Ok(output);
+        }
+        let length = bytes
+            .get(offset + 2..offset + 4)
+            .map(|length| usize::from(u16::from_be_bytes([length[0], length[1]])))
+            .filter(|length| *length >= 2)
+            .ok_or_else(|| malformed("JPEG"))?;
+        let end = offset + 2 + length;
+        if end > bytes.len() {
+            return Err(malformed("JPEG"));
+        }
+        // Entropy-coded data follows the start-of-scan header; keep the rest.
+        if marker == 0xDA {
+            output.extend_from_slice(&bytes[offset..]);
+            return Ok(output);
+        }
+        let is_metadata = matches!(marker, 0xE1 | 0xE3..=0xED | 0xEF | 0xFE);
+        if !is_metadata {
+            output.extend_from_slice(&bytes[offset..end]);
+        }
+        offset = end;
+    }
+}
+
+fn strip_png_metadata(bytes: &[u8]) -> Result<Vec<u8>> {
+    if !bytes.starts_with(PNG_SIGNATURE) {
+        return Err(malformed("PNG"));
+    }
+
+    let mut output = Vec::with_capacity(bytes.len());
+    output.extend_from_slice(PNG_SIGNATURE);
+    let mut offset = PNG_SIGNATURE.len();
+    while offset < bytes.len() {
+        let header = bytes
+            .get(offset..offset + 8)
+            .ok_or_else(|| malformed("PNG"))?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let length = usize::try_from(length).map_err(|_| malformed("PNG"))?;
+        // Length, type, data, and CRC.
+        let end = offset + 12 + length;
+        if end > bytes.len() {
+            return Err(malformed("PNG"));
+        }
+        let chunk_type = &header[4..8];
+        if !PNG_METADATA_CHUNKS
+            .iter()
+            .any(|metadata| chunk_type == metadata.as_slice())
+        {
+            output.extend_from_slice(&bytes[offset..end]);
+        }
+        offset = end;
+    }
+    Ok(output)
+}
+
+fn strip_webp_metadata(bytes: &[u8]) -> Result<Vec<u8>> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return Err(malformed("WebP"));
+    }
+
+    let mut output = Vec::with_capacity(bytes.len());
+    output.extend_from_slice(&bytes[..12]);
+    let mut offset = 12;
+    while offset < bytes.len() {
+        let header = bytes
+            .get(offset..offset + 8)
+            .ok_or_else(|| malformed("WebP"))?;
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let length = usize::try_from(length).map_err(|_| malformed("WebP"))?;
+        let data_end = offset + 8 + length;
+        if data_end > bytes.len() {
+            return Err(malformed("WebP"));
+        }
+        // Chunk data is padded to an even length.
+        let end = (data_end + (length & 1)).min(bytes.len());
+        let chunk_type = &header[..4];
+        if chunk_type != b"EXIF" && chunk_type != b"XMP " {
+            let start = output.len();
+            output.extend_from_slice(&bytes[offset..end]);
+            if chunk_type == b"VP8X" && length > 0 {
+                output[start + 8] &= !(WEBP_EXIF_FLAG | WEBP_XMP_FLAG);
+            }
+        }
+        offset = end;
+    }
+
+    let riff_size = u32::try_from(output.len() - 8).map_err(|_| malformed("WebP"))?;
+    output[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Ok(output)
+}
+
+fn encode_image(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>> {
+    let mut cursor = Cursor::new(Vec::new());
+    let result = if format == ImageFormat::Jpeg {
+        // JPEG has no alpha channel.
+        let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+        JpegEncoder::new_with_quality(&mut cursor, REENCODE_JPEG_QUALITY).encode_image(&rgb)
+    } else {
+        image.write_to(&mut cursor, format)
+    };
+    result.map_err(|error| Error::InvalidInput(format!("Failed to re-encode image: {error}")))?;
+    Ok(cursor.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, ImageBuffer, Rgb};
+
+    fn encoded(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+        let image = ImageBuffer::<Rgb<u8>, Vec<u8>>::from_fn(width, height, |x, y| {
+            Rgb([
+                u8::try_from(x % 256).unwrap(),
+                u8::try_from(y % 256).unwrap(),
+                128,
+            ])
+        });
+        let mut cursor = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut cursor, format)
+            .unwrap();
+        cursor.into_inner()
+    }
+
+    /// Insert an APP1 EXIF segment right after the JPEG SOI marker.
+    fn with_exif(jpeg: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut segment = b"Exif\0\0".to_vec();
+        segment.extend_from_slice(payload);
+        let length = u16::try_from(segment.len() + 2).unwrap();
+        let mut output = jpeg[..2].to_vec();
+        output.extend_from_slice(&[0xFF, 0xE1]);
+        output.extend_from_slice(&length.to_be_bytes());
+        output.extend_from_slice(&segment);
+        output.extend_from_slice(&jpeg[2..]);
+        output
+    }
+
+    /// CRC-32 as used by PNG chunks.
+    fn png_crc(bytes: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for byte in bytes {
+            crc ^= u32::from(*byte);
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    0xEDB8_8320 ^ (crc >> 1)
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    fn dimensions(bytes: &[u8]) -> (u32, u32) {
+        image::load_from_memory(bytes).unwrap().dimensions()
+    }
+
+    const STRIP: ImagePrivacyOptions = ImagePrivacyOptions {
+        strip_metadata: true,
+        max_dimension: None,
+    };
+
+    #[test]
+    fn strips_jpeg_exif_without_reencoding() {
+        let jpeg = encoded(40, 30, ImageFormat::Jpeg);
+        let tagged = with_exif(&jpeg, b"GPS 52.52N 13.40E");
+
+        let stripped = prepare_image_upload(&tagged, "image/jpeg", STRIP).unwrap();
+        assert_eq!(stripped, jpeg);
+        assert_eq!(dimensions(&stripped), (40, 30));
+    }
+
+    #[test]
+    fn strips_png_text_chunks() {
+        let png = encoded(8, 8, ImageFormat::Png);
+        // Insert a tEXt chunk after IHDR (signature + 25-byte IHDR chunk).
+        let mut chunk = Vec::new();
+        let data = b"Location\0Berlin";
+        chunk.extend_from_slice(&u32::try_from(data.len()).unwrap().to_be_bytes());
+        chunk.extend_from_slice(b"tEXt");
+        chunk.extend_from_slice(data);
+        chunk.extend_from_slice(&png_crc(&chunk[4..]).to_be_bytes());
+        let mut tagged = png[..33].to_vec();
+        tagged.extend_from_slice(&chunk);
+        tagged.extend_from_slice(&png[33..]);
+
+        let stripped = prepare_image_upload(&tagged, "image/png", STRIP).unwrap();
+        assert_eq!(stripped, png);
+    }
+
+    #[test]
+    fn downscales_to_max_dimension() {
+        let png = encoded(400, 100, ImageFormat::Png);
+        let options = ImagePrivacyOptions {
+            strip_metadata: false,
+            max_dimension: Some(200),
+        };
+
+        let resized = prepare_image_upload(&png, "image/png", options).unwrap();
+        assert_eq!(dimensions(&resized), (200, 50));
+        assert_eq!(image::guess_format(&resized).unwrap(), ImageFormat::Png);
+
+        let small = encoded(100, 50, ImageFormat::Png);
+        assert_eq!(
+            prepare_image_upload(&small, "image/png", options).unwrap(),
+            small
+        );
+    }
+
+    #[test]
+    fn leaves_other_files_and_disabled_options_alone() {
+        let pdf = b"%PDF-1.7 not an image".to_vec();
+        assert_eq!(
+            prepare_image_upload(&pdf, "application/pdf", STRIP).unwrap(),
+            pdf
+        );
+
+        let tagged = with_exif(&encoded(10, 10, ImageFormat::Jpeg), b"GPS");
+        assert_eq!(
+            prepare_image_upload(&tagged, "image/jpeg", ImagePrivacyOptions::default()).unwrap(),
+            tagged
+        );
+    }
+
+    #[test]
+    fn options_from_settings_ignore_zero_dimension() {
+        let settings = Settings {
+            image_strip_metadata: true,
+            image_max_dimension: Some(0),
+            ..Settings::default()
+        };
+        let options = ImagePrivacyOptions::from_settings(&settings);
+        assert!(options.strip_metadata);
+        assert_eq!(options.max_dimension, None);
+        assert!(ImagePrivacyOptions::from_settings(&Settings::default()).is_noop());
+    }
+}
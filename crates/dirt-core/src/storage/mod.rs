@@ -1,9 +1,16 @@
 //! Storage abstractions for media/object backends.
 
+mod image_privacy;
+mod pdf_preview;
 mod r2;
 mod thumbnail;
 mod voice_memo;
 
+pub use image_privacy::{prepare_image_upload, ImagePrivacyOptions};
+pub use pdf_preview::{
+    extract_pdf_text, is_pdf, pdf_preview_available, render_pdf_page, PdfPagePreview,
+    MAX_PDF_PREVIEW_BYTES,
+};
 pub use r2::{MediaStorage, R2Config, R2Storage};
 pub use thumbnail::{generate_thumbnail, ThumbnailFormat, ThumbnailImage, ThumbnailOptions};
 pub use voice_memo::{encode_voice_memo_wav, estimate_voice_memo_duration_ms, VoiceMemoOptions};
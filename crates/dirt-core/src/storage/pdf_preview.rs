@@ -0,0 +1,201 @@
+//! Page rendering for PDF attachment previews.
+//!
+//! Rendering uses pdfium and is only compiled with the `pdf-preview` feature.
+//! The pdfium shared library is loaded at runtime, first from the directory
+//! of the executable and then from the system library path.
+
+use image::DynamicImage;
+
+use super::thumbnail::{thumbnail_from_image, ThumbnailImage, ThumbnailOptions};
+use crate::{Error, Result};
+
+/// Largest PDF (in bytes) rendered for in-app preview.
+pub const MAX_PDF_PREVIEW_BYTES: usize = 16 * 1024 * 1024;
+
+/// One rendered PDF page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfPagePreview {
+    pub image: ThumbnailImage,
+    /// Zero-based index of the rendered page.
+    pub page_index: u16,
+    /// Number of pages in the document.
+    pub page_count: u16,
+}
+
+/// Whether this build can render PDF pages.
+#[must_use]
+pub const fn pdf_preview_available() -> bool {
+    cfg!(feature = "pdf-preview")
+}
+
+/// Whether `bytes` start with the PDF file signature.
+#[must_use]
+pub fn is_pdf(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"%PDF-")
+}
+
+/// Render one page of a PDF to an image fitting within `options`.
+///
+/// `page_index` is clamped to the last page.
+pub fn render_pdf_page(
+    bytes: &[u8],
+    page_index: u16,
+    options: ThumbnailOptions,
+) -> Result<PdfPagePreview> {
+    if !is_pdf(bytes) {
+        return Err(Error::InvalidInput(
+            "Attachment is not a valid PDF".to_string(),
+        ));
+    }
+    if bytes.len() > MAX_PDF_PREVIEW_BYTES {
+        return Err(Error::InvalidInput(format!(
+            "PDF is too large for in-app preview (limit: {} MB)",
+            MAX_PDF_PREVIEW_BYTES / (1024 * 1024)
+        )));
+    }
+    if options.max_width == 0 || options.max_height == 0 {
+        return Err(Error::InvalidInput(
+            "Thumbnail max dimensions must be greater than zero".to_string(),
+        ));
+    }
+
+    let (page, page_index, page_count) = rasterize_page(bytes, page_index, options)?;
+    Ok(PdfPagePreview {
+        image: thumbnail_from_image(page, options)?,
+        page_index,
+        page_count,
+    })
+}
+
+#[cfg(feature = "pdf-preview")]
+fn rasterize_page(
+    bytes: &[u8],
+    page_index: u16,
+    options: ThumbnailOptions,
+) -> Result<(DynamicImage, u16, u16)> {
+    use pdfium_render::prelude::PdfRenderConfig;
+
+    let pdfium = load_pdfium()?;
+    let document = pdfium
+        .load_pdf_from_byte_slice(bytes, None)
+        .map_err(|error| Error::InvalidInput(format!("Failed to open PDF: {error}")))?;
+
+    let pages = document.pages();
+    let page_count = pages.len();
+    if page_count == 0 {
+        return Err(Error::InvalidInput("PDF has no pages".to_string()));
+    }
+    let page_index = page_index.min(page_count - 1);
+    let page = pages
+        .get(page_index)
+        .map_err(|error| Error::InvalidInput(format!("Failed to read PDF page: {error}")))?;
+
+    let config = PdfRenderConfig::new()
+        .set_target_width(i32::try_from(options.max_width).unwrap_or(i32::MAX))
+        .set_maximum_height(i32::try_from(options.max_height).unwrap_or(i32::MAX));
+    let image = page
+        .render_with_config(&config)
+        .map_err(|error| Error::InvalidInput(format!("Failed to render PDF page: {error}")))?
+        .as_image();
+
+    Ok((image, page_index, page_count))
+}
+
+/// Extract the text of every page of a PDF, for search indexing.
+pub fn extract_pdf_text(bytes: &[u8]) -> Result<String> {
+    if !is_pdf(bytes) {
+        return Err(Error::InvalidInput(
+            "Attachment is not a valid PDF".to_string(),
+        ));
+    }
+    if bytes.len() > MAX_PDF_PREVIEW_BYTES {
+        return Err(Error::InvalidInput(format!(
+            "PDF is too large to extract text from (limit: {} MB)",
+            MAX_PDF_PREVIEW_BYTES / (1024 * 1024)
+        )));
+    }
+    page_texts(bytes).map(|pages| pages.join("\n"))
+}
+
+#[cfg(feature = "pdf-preview")]
+fn load_pdfium() -> Result<pdfium_render::prelude::Pdfium> {
+    use pdfium_render::prelude::Pdfium;
+
+    let bindings = Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path("./"))
+        .or_else(|_| Pdfium::bind_to_system_library())
+        .map_err(|error| Error::Storage(format!("pdfium library is not available: {error}")))?;
+    Ok(Pdfium::new(bindings))
+}
+
+#[cfg(feature = "pdf-preview")]
+fn page_texts(bytes: &[u8]) -> Result<Vec<String>> {
+    let pdfium = load_pdfium()?;
+    let document = pdfium
+        .load_pdf_from_byte_slice(bytes, None)
+        .map_err(|error| Error::InvalidInput(format!("Failed to open PDF: {error}")))?;
+
+    document
+        .pages()
+        .iter()
+        .map(|page| {
+            page.text()
+                .map(|text| text.all())
+                .map_err(|error| Error::InvalidInput(format!("Failed to read PDF text: {error}")))
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "pdf-preview"))]
+fn page_texts(_bytes: &[u8]) -> Result<Vec<String>> {
+    Err(Error::InvalidInput(
+        "PDF text extraction is not enabled in this build".to_string(),
+    ))
+}
+
+#[cfg(not(feature = "pdf-preview"))]
+fn rasterize_page(
+    _bytes: &[u8],
+    _page_index: u16,
+    _options: ThumbnailOptions,
+) -> Result<(DynamicImage, u16, u16)> {
+    Err(Error::InvalidInput(
+        "PDF preview is not enabled in this build".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_pdf_checks_signature() {
+        assert!(is_pdf(b"%PDF-1.7\n%\xe2\xe3"));
+        assert!(!is_pdf(b"PK\x03\x04"));
+        assert!(!is_pdf(b""));
+    }
+
+    #[test]
+    fn render_pdf_page_rejects_non_pdf_and_oversized_input() {
+        let err = render_pdf_page(b"not a pdf", 0, ThumbnailOptions::default()).unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(message) if message.contains("not a valid PDF")));
+
+        let mut oversized = b"%PDF-1.4\n".to_vec();
+        oversized.resize(MAX_PDF_PREVIEW_BYTES + 1, b' ');
+        let err = render_pdf_page(&oversized, 0, ThumbnailOptions::default()).unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(message) if message.contains("too large")));
+    }
+
+    #[cfg(not(feature = "pdf-preview"))]
+    #[test]
+    fn render_pdf_page_reports_missing_feature() {
+        assert!(!pdf_preview_available());
+        let err = render_pdf_page(b"%PDF-1.4\n", 0, ThumbnailOptions::default()).unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(message) if message.contains("not enabled")));
+    }
+
+    #[test]
+    fn extract_pdf_text_rejects_non_pdf_input() {
+        let err = extract_pdf_text(b"plain text").unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(message) if message.contains("not a valid PDF")));
+    }
+}
@@ -1,6 +1,5 @@
 //! Cloudflare R2 storage configuration and key-building helpers.
 
-use std::env;
 use std::fmt;
 
 use aws_credential_types::Credentials;
@@ -9,6 +8,9 @@ use aws_types::region::Region;
 use chrono::Utc;
 use uuid::Uuid;
 
+use crate::config::{ConfigSource, EnvConfig};
+use crate::media::{unless_cancelled, CancellationToken, DownloadProgress, UploadProgress};
+use crate::util::fs::{sanitize_file_name, sanitize_token};
 use crate::{Error, Result};
 
 const ENV_ACCOUNT_ID: &str = "R2_ACCOUNT_ID";
@@ -60,7 +62,13 @@ impl R2Config {
     /// Returns `Ok(None)` when no R2 variables are set.
     /// Returns an error when only a partial configuration is provided.
     pub fn from_env() -> Result<Option<Self>> {
-        parse_config(|key| env::var(key).ok())
+        Self::from_source(&EnvConfig)
+    }
+
+    /// Load R2 configuration from `source`, with the same rules as
+    /// [`Self::from_env`].
+    pub fn from_source(source: &impl ConfigSource) -> Result<Option<Self>> {
+        parse_config(|key| source.var(key))
     }
 
     /// Cloudflare R2 S3-compatible endpoint URL.
@@ -105,6 +113,26 @@ impl R2Storage {
         object_key: &str,
         bytes: &[u8],
         content_type: Option<&str>,
+    ) -> Result<()> {
+        self.upload_bytes_with_progress(
+            object_key,
+            bytes,
+            content_type,
+            &CancellationToken::new(),
+            |_| {},
+        )
+        .await
+    }
+
+    /// Upload object bytes, reporting progress and stopping when `cancel`
+    /// fires.
+    pub async fn upload_bytes_with_progress(
+        &self,
+        object_key: &str,
+        bytes: &[u8],
+        content_type: Option<&str>,
+        cancel: &CancellationToken,
+        mut on_progress: impl FnMut(UploadProgress),
     ) -> Result<()> {
         let object_key = normalize_object_key(object_key)?;
         let client = self.s3_client();
@@ -119,9 +147,20 @@ impl R2Storage {
             request = request.content_type(content_type);
         }
 
-        request.send().await.map_err(|error| {
-            storage_error("put_object", &self.config.bucket, Some(&object_key), error)
-        })?;
+        on_progress(UploadProgress {
+            bytes_sent: 0,
+            total_bytes: bytes.len(),
+        });
+        unless_cancelled(cancel, request.send())
+            .await
+            .ok_or_else(|| cancelled("put_object", &object_key))?
+            .map_err(|error| {
+                storage_error("put_object", &self.config.bucket, Some(&object_key), error)
+            })?;
+        on_progress(UploadProgress {
+            bytes_sent: bytes.len(),
+            total_bytes: bytes.len(),
+        });
 
         Ok(())
     }
@@ -179,15 +218,29 @@ impl R2Storage {
 
     /// Download object bytes from the configured bucket.
     pub async fn download_bytes(&self, object_key: &str) -> Result<(Vec<u8>, Option<String>)> {
+        self.download_bytes_with_progress(object_key, &CancellationToken::new(), |_| {})
+            .await
+    }
+
+    /// Download object bytes, reporting progress as they arrive and stopping
+    /// when `cancel` fires.
+    pub async fn download_bytes_with_progress(
+        &self,
+        object_key: &str,
+        cancel: &CancellationToken,
+        mut on_progress: impl FnMut(DownloadProgress),
+    ) -> Result<(Vec<u8>, Option<String>)> {
         let object_key = normalize_object_key(object_key)?;
         let client = self.s3_client();
 
-        let response = client
+        let request = client
             .get_object()
             .bucket(&self.config.bucket)
             .key(&object_key)
-            .send()
+            .send();
+        let mut response = unless_cancelled(cancel, request)
             .await
+            .ok_or_else(|| cancelled("get_object", &object_key))?
             .map_err(|error| {
                 storage_error("get_object", &self.config.bucket, Some(&object_key), error)
             })?;
@@ -198,16 +251,35 @@ impl R2Storage {
             .filter(|value| !value.is_empty())
             .map(ToOwned::to_owned);
 
-        let payload = response.body.collect().await.map_err(|error| {
-            storage_error(
-                "get_object_body",
-                &self.config.bucket,
-                Some(&object_key),
-                error,
-            )
-        })?;
+        let total_bytes = response
+            .content_length()
+            .and_then(|length| usize::try_from(length).ok());
+
+        let mut bytes = Vec::new();
+        on_progress(DownloadProgress {
+            bytes_received: 0,
+            total_bytes,
+        });
+        while let Some(chunk) = unless_cancelled(cancel, response.body.next())
+            .await
+            .ok_or_else(|| cancelled("get_object_body", &object_key))?
+        {
+            let chunk = chunk.map_err(|error| {
+                storage_error(
+                    "get_object_body",
+                    &self.config.bucket,
+                    Some(&object_key),
+                    error,
+                )
+            })?;
+            bytes.extend_from_slice(&chunk);
+            on_progress(DownloadProgress {
+                bytes_received: bytes.len(),
+                total_bytes,
+            });
+        }
 
-        Ok((payload.into_bytes().to_vec(), content_type))
+        Ok((bytes, content_type))
     }
 
     fn s3_client(&self) -> Client {
@@ -312,6 +384,10 @@ fn build_s3_client(config: &R2Config) -> Client {
     Client::from_conf(sdk_config)
 }
 
+fn cancelled(operation: &str, object_key: &str) -> Error {
+    Error::Storage(format!("R2 {operation} cancelled for {object_key}"))
+}
+
 fn storage_error(
     operation: &str,
     bucket: &str,
@@ -356,54 +432,13 @@ fn normalize_public_base_url(public_base_url: Option<String>) -> Result<Option<S
     Ok(Some(value.trim_end_matches('/').to_string()))
 }
 
-fn sanitize_file_name(file_name: &str) -> String {
-    let trimmed = file_name.trim().trim_matches('/');
-    if trimmed.is_empty() {
-        return "file".to_string();
-    }
-
-    let (stem, ext) = trimmed
-        .rsplit_once('.')
-        .map_or((trimmed, ""), |parts| parts);
-    let stem = sanitize_token(stem);
-    let stem = if stem.is_empty() {
-        "file".to_string()
-    } else {
-        stem
-    };
-    let ext = sanitize_token(ext);
-
-    if ext.is_empty() {
-        stem
-    } else {
-        format!("{stem}.{ext}")
-    }
-}
-
-fn sanitize_token(input: &str) -> String {
-    let mut out = String::with_capacity(input.len());
-    let mut last_dash = false;
-
-    for ch in input.chars().flat_map(char::to_lowercase) {
-        let keep = ch.is_ascii_alphanumeric();
-        if keep {
-            out.push(ch);
-            last_dash = false;
-        } else if !last_dash {
-            out.push('-');
-            last_dash = true;
-        }
-    }
-
-    out.trim_matches('-').to_string()
-}
-
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
     use std::time::Duration;
 
     use super::*;
+    use crate::config::MapConfig;
 
     fn parse_from_map(map: &HashMap<&str, &str>) -> Result<Option<R2Config>> {
         parse_config(|key| map.get(key).map(|value| (*value).to_string()))
@@ -451,6 +486,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_source_reads_injected_values() {
+        let source = MapConfig::new()
+            .with(ENV_ACCOUNT_ID, "account-1")
+            .with(ENV_BUCKET, "bucket-a")
+            .with(ENV_ACCESS_KEY_ID, "AKID123")
+            .with(ENV_SECRET_ACCESS_KEY, "SECRET123");
+
+        let config = R2Config::from_source(&source).unwrap().unwrap();
+        assert_eq!(config.bucket, "bucket-a");
+        assert_eq!(config.public_base_url, None);
+        assert!(R2Config::from_source(&MapConfig::new()).unwrap().is_none());
+    }
+
     #[test]
     fn r2_config_debug_redacts_secret_access_key() {
         let config = R2Config {
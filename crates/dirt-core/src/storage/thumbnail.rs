@@ -82,6 +82,14 @@ pub fn generate_thumbnail(
         ))
     })?;
 
+    thumbnail_from_image(source, options)
+}
+
+/// Resize an already decoded image to fit `options` and encode it.
+pub(crate) fn thumbnail_from_image(
+    source: image::DynamicImage,
+    options: ThumbnailOptions,
+) -> Result<ThumbnailImage> {
     let (source_width, source_height) = source.dimensions();
     let resized = if source_width <= options.max_width && source_height <= options.max_height {
         source
@@ -0,0 +1,428 @@
+//! Short summaries of long notes.
+//!
+//! With an `OpenAI` key configured, [`summarize_note`] asks the chat
+//! completions API for a summary; without one it falls back to
+//! [`extractive_summary`], which picks the note's most representative
+//! sentences locally. Summaries are stored per note and shown in list
+//! previews of long notes until the note is edited again.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config::{ConfigSource, EnvConfig};
+use crate::models::{Note, NoteSummary, SummaryMethod};
+use crate::seal::sealed_until;
+use crate::services::DatabaseService;
+use crate::util::{compact_text, is_http_url};
+use crate::{Error, Result};
+
+/// Notes at least this long (in characters) show their summary in lists.
+pub const LONG_NOTE_MIN_CHARS: usize = 1500;
+/// Longest summary kept, in characters.
+pub const SUMMARY_MAX_CHARS: usize = 400;
+
+const ENV_OPENAI_API_KEY: &str = "OPENAI_API_KEY";
+const ENV_OPENAI_BASE_URL: &str = "OPENAI_BASE_URL";
+const ENV_OPENAI_MODEL: &str = "OPENAI_SUMMARY_MODEL";
+const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com";
+const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+const EXTRACTIVE_SENTENCES: usize = 3;
+const MIN_SENTENCE_WORDS: usize = 4;
+const MIN_KEYWORD_CHARS: usize = 3;
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "also", "and", "are", "because", "been", "but", "can", "could", "for",
+    "from", "had", "has", "have", "her", "his", "how", "into", "its", "just", "more", "not", "now",
+    "our", "out", "she", "should", "some", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "was", "were", "what", "when", "which", "while", "who", "will",
+    "with", "would", "you", "your",
+];
+
+/// `OpenAI` settings for summaries.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SummaryConfig {
+    pub api_key: String,
+    /// API origin without a trailing slash.
+    pub base_url: String,
+    pub model: String,
+}
+
+impl fmt::Debug for SummaryConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SummaryConfig")
+            .field("api_key", &"[REDACTED]")
+            .field("base_url", &self.base_url)
+            .field("model", &self.model)
+            .finish()
+    }
+}
+
+impl SummaryConfig {
+    /// Load summary settings from environment variables.
+    ///
+    /// Returns `Ok(None)` when `OPENAI_API_KEY` is not set.
+    pub fn from_env() -> Result<Option<Self>> {
+        Self::resolve(None, &EnvConfig)
+    }
+
+    /// Load summary settings from `source`, with the same rules as
+    /// [`Self::from_env`].
+    pub fn from_source(source: &impl ConfigSource) -> Result<Option<Self>> {
+        Self::resolve(None, source)
+    }
+
+    /// Resolve settings from `source`, preferring `stored_api_key` (e.g.
+    /// from secure storage) over `OPENAI_API_KEY`.
+    pub fn resolve(
+        stored_api_key: Option<String>,
+        source: &impl ConfigSource,
+    ) -> Result<Option<Self>> {
+        let Some(api_key) = stored_api_key.or_else(|| source.text_var(ENV_OPENAI_API_KEY)) else {
+            return Ok(None);
+        };
+
+        let base_url = match source.text_var(ENV_OPENAI_BASE_URL) {
+            Some(value) if !is_http_url(&value) => {
+                return Err(Error::InvalidInput(format!(
+                    "{ENV_OPENAI_BASE_URL} must start with http:// or https://"
+                )));
+            }
+            Some(value) => value.trim_end_matches('/').to_string(),
+            None => DEFAULT_OPENAI_BASE_URL.to_string(),
+        };
+
+        Ok(Some(Self {
+            api_key,
+            base_url,
+            model: source
+                .text_var(ENV_OPENAI_MODEL)
+                .unwrap_or_else(|| DEFAULT_OPENAI_MODEL.to_string()),
+        }))
+    }
+}
+
+/// Whether `note` is long enough to show its summary in list previews.
+#[must_use]
+pub fn is_long_note(note: &Note) -> bool {
+    note.content.chars().count() >= LONG_NOTE_MIN_CHARS
+}
+
+/// Summary to show in place of the preview of `note`, if any.
+///
+/// Only long notes use their summary, and only while it is current.
+#[must_use]
+pub fn list_preview_summary<'a>(note: &Note, summary: Option<&'a NoteSummary>) -> Option<&'a str> {
+    summary
+        .filter(|summary| is_long_note(note) && summary.is_current_for(note))
+        .map(|summary| summary.summary.as_str())
+}
+
+/// Summarize `content` by picking its most representative sentences.
+///
+/// Sentences are scored by how often their words appear across the note;
+/// the best few are returned in their original order. Runs locally.
+///
+/// ```
+/// use dirt_core::summarize::extractive_summary;
+///
+/// let summary = extractive_summary("Trip plan\n\nWe fly to Lisbon on Friday.");
+/// assert_eq!(summary, "We fly to Lisbon on Friday.");
+/// ```
+#[must_use]
+pub fn extractive_summary(content: &str) -> String {
+    let sentences = split_sentences(content);
+    let candidates = sentences
+        .iter()
+        .filter(|sentence| sentence.split_whitespace().count() >= MIN_SENTENCE_WORDS)
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return truncate_summary(sentences.first().map_or("", String::as_str));
+    }
+
+    let mut frequencies = HashMap::<String, usize>::new();
+    for sentence in &candidates {
+        for word in keywords(sentence) {
+            *frequencies.entry(word).or_default() += 1;
+        }
+    }
+
+    let mut scored = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, sentence)| {
+            let words = keywords(sentence);
+            let unique = words.iter().collect::<HashSet<_>>();
+            let total = unique.iter().map(|word| frequencies[*word]).sum::<usize>();
+            // Compare average keyword frequency without floats.
+            (total * 1000 / unique.len().max(1), index)
+        })
+        .collect::<Vec<_>>();
+    scored.sort_by(|left, right| right.0.cmp(&left.0).then(left.1.cmp(&right.1)));
+
+    let mut picked = scored
+        .into_iter()
+        .take(EXTRACTIVE_SENTENCES)
+        .map(|(_, index)| index)
+        .collect::<Vec<_>>();
+    picked.sort_unstable();
+
+    let summary = picked
+        .into_iter()
+        .map(|index| candidates[index].as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    truncate_summary(&summary)
+}
+
+/// Summarize `note` and store the summary.
+///
+/// Uses `OpenAI` when `config` is set and [`extractive_summary`] otherwise.
+/// Sealed and empty notes are refused.
+pub async fn summarize_note(
+    db: &DatabaseService,
+    config: Option<&SummaryConfig>,
+    note: &Note,
+) -> Result<NoteSummary> {
+    if sealed_until(&note.content).is_some() {
+        return Err(Error::InvalidInput(
+            "Sealed notes cannot be summarized".to_string(),
+        ));
+    }
+    if note.is_empty() {
+        return Err(Error::InvalidInput(
+            "Note has no text to summarize".to_string(),
+        ));
+    }
+
+    let (summary, method) = match config {
+        Some(config) => (
+            openai_summary(config, &note.content).await?,
+            SummaryMethod::OpenAi,
+        ),
+        None => (extractive_summary(&note.content), SummaryMethod::Extractive),
+    };
+    let summary = NoteSummary {
+        note_id: note.id,
+        summary,
+        method,
+        note_updated_at: note.updated_at,
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    db.save_note_summary(&summary).await?;
+    Ok(summary)
+}
+
+/// Split `content` into trimmed sentences, dropping heading and list markers
+/// and lines that hold only tags.
+fn split_sentences(content: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.split_whitespace().all(|word| word.starts_with('#')) {
+            continue;
+        }
+        let line = strip_line_marker(line);
+
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+        while let Some((index, ch)) = chars.next() {
+            let at_boundary = matches!(ch, '.' | '!' | '?')
+                && chars.peek().map_or(true, |(_, next)| next.is_whitespace());
+            if at_boundary {
+                let end = index + ch.len_utf8();
+                push_sentence(&mut sentences, &line[start..end]);
+                start = end;
+            }
+        }
+        push_sentence(&mut sentences, &line[start..]);
+    }
+    sentences
+}
+
+fn push_sentence(sentences: &mut Vec<String>, sentence: &str) {
+    let sentence = sentence.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence.to_string());
+    }
+}
+
+fn strip_line_marker(line: &str) -> &str {
+    let heading = line.trim_start_matches('#');
+    let line = if heading.len() < line.len() && heading.starts_with(' ') {
+        heading.trim_start()
+    } else {
+        line
+    };
+    for marker in ["- [ ] ", "- [x] ", "- ", "* ", "> "] {
+        if let Some(rest) = line.strip_prefix(marker) {
+            return rest.trim_start();
+        }
+    }
+    line
+}
+
+fn keywords(sentence: &str) -> Vec<String> {
+    sentence
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| word.chars().count() >= MIN_KEYWORD_CHARS)
+        .map(str::to_lowercase)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+fn truncate_summary(summary: &str) -> String {
+    let summary = summary.trim();
+    if summary.chars().count() <= SUMMARY_MAX_CHARS {
+        return summary.to_string();
+    }
+    let mut truncated = summary
+        .chars()
+        .take(SUMMARY_MAX_CHARS - 3)
+        .collect::<String>();
+    truncated.push_str("...");
+    truncated
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: Option<String>,
+}
+
+async fn openai_summary(config: &SummaryConfig, content: &str) -> Result<String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|error| Error::Storage(format!("Failed to construct HTTP client: {error}")))?;
+    let response = client
+        .post(format!("{}/v1/chat/completions", config.base_url))
+        .bearer_auth(&config.api_key)
+        .json(&serde_json::json!({
+            "model": config.model,
+            "temperature": 0,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": "Summarize the user's note in at most three sentences, \
+                                in the note's language. Reply with the summary only.",
+                },
+                { "role": "user", "content": content },
+            ],
+        }))
+        .send()
+        .await
+        .map_err(|error| Error::Storage(error.to_string()))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(Error::Storage(format!(
+            "OpenAI summary failed with {status}: {}",
+            compact_text(&body)
+        )));
+    }
+
+    let payload: ChatResponse = response
+        .json()
+        .await
+        .map_err(|error| Error::Storage(error.to_string()))?;
+    payload
+        .choices
+        .into_iter()
+        .find_map(|choice| choice.message.content)
+        .map(|summary| truncate_summary(&summary))
+        .filter(|summary| !summary.is_empty())
+        .ok_or_else(|| Error::Storage("OpenAI returned no summary".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::MapConfig;
+
+    use super::*;
+
+    #[test]
+    fn extractive_summary_picks_representative_sentences_in_order() {
+        let content = "Garden plan #garden\n\n\
+            - The tomatoes need a sunny bed near the fence.\n\
+            - Water the tomatoes every morning before work.\n\
+            Buy compost. The shed door squeaks again!\n\
+            Tomatoes and basil grow well together in the sunny bed.\n\
+            Remember to call Sam about the fence paint sometime.";
+        let summary = extractive_summary(content);
+        assert_eq!(
+            summary,
+            "The tomatoes need a sunny bed near the fence. \
+             Water the tomatoes every morning before work. \
+             Tomatoes and basil grow well together in the sunny bed."
+        );
+        assert_eq!(extractive_summary("#todo\nShort"), "Short");
+        assert_eq!(extractive_summary(""), "");
+    }
+
+    #[test]
+    fn summaries_are_truncated_and_only_previewed_for_long_current_notes() {
+        let long = "word ".repeat(SUMMARY_MAX_CHARS);
+        let truncated = truncate_summary(&long);
+        assert_eq!(truncated.chars().count(), SUMMARY_MAX_CHARS);
+        assert!(truncated.ends_with("..."));
+
+        let mut note = Note::new("x".repeat(LONG_NOTE_MIN_CHARS));
+        let summary = NoteSummary {
+            note_id: note.id,
+            summary: "Gist".to_string(),
+            method: SummaryMethod::Extractive,
+            note_updated_at: note.updated_at,
+            created_at: note.updated_at,
+        };
+        assert_eq!(list_preview_summary(&note, Some(&summary)), Some("Gist"));
+        note.updated_at += 1;
+        assert_eq!(list_preview_summary(&note, Some(&summary)), None);
+        let short = Note::new("Short");
+        assert_eq!(list_preview_summary(&short, Some(&summary)), None);
+    }
+
+    #[test]
+    fn config_requires_openai_key() {
+        assert_eq!(SummaryConfig::from_source(&MapConfig::new()).unwrap(), None);
+        let config = SummaryConfig::from_source(
+            &MapConfig::new()
+                .with(ENV_OPENAI_API_KEY, "key")
+                .with(ENV_OPENAI_MODEL, "gpt-4.1-mini"),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(config.base_url, DEFAULT_OPENAI_BASE_URL);
+        assert_eq!(config.model, "gpt-4.1-mini");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn summarize_note_stores_extractive_summary_without_key() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        let note = db
+            .create_note("Standup\nShipped the importer fix today. Next up is sync retries.")
+            .await
+            .unwrap();
+
+        let summary = summarize_note(&db, None, &note).await.unwrap();
+        assert_eq!(summary.method, SummaryMethod::Extractive);
+        assert_eq!(
+            summary.summary,
+            "Shipped the importer fix today. Next up is sync retries."
+        );
+        assert_eq!(db.get_note_summary(&note.id).await.unwrap(), Some(summary));
+    }
+}
@@ -0,0 +1,180 @@
+//! Settling sync conflicts after last-write-wins picked a side.
+//!
+//! The LWW trigger keeps the newer row and records both versions in
+//! `sync_conflicts`. A [`ConflictResolver`] decides what the note should hold
+//! once someone looks at the conflict again.
+
+/// Decides the content of a note from both sides of a sync conflict
+pub trait ConflictResolver {
+    /// Strategy name recorded on the conflict once applied
+    fn name(&self) -> &'static str;
+
+    /// Content the note should hold, given the version LWW kept (`local`)
+    /// and the one it rejected (`remote`)
+    fn resolve(&self, local: &str, remote: &str) -> String;
+}
+
+/// Keep the version last-write-wins chose
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeepLocal;
+
+impl ConflictResolver for KeepLocal {
+    fn name(&self) -> &'static str {
+        "keep-local"
+    }
+
+    fn resolve(&self, local: &str, _remote: &str) -> String {
+        local.to_string()
+    }
+}
+
+/// Bring back the version last-write-wins rejected
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeepRemote;
+
+impl ConflictResolver for KeepRemote {
+    fn name(&self) -> &'static str {
+        "keep-remote"
+    }
+
+    fn resolve(&self, _local: &str, remote: &str) -> String {
+        remote.to_string()
+    }
+}
+
+/// Keep every line from both versions, in order, without duplicating the
+/// lines they share
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeByLine;
+
+impl ConflictResolver for MergeByLine {
+    fn name(&self) -> &'static str {
+        "merge-by-line"
+    }
+
+    fn resolve(&self, local: &str, remote: &str) -> String {
+        merge_lines(local, remote)
+    }
+}
+
+/// Write both versions between conflict markers for editing by hand
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Manual;
+
+impl ConflictResolver for Manual {
+    fn name(&self) -> &'static str {
+        "manual"
+    }
+
+    fn resolve(&self, local: &str, remote: &str) -> String {
+        format!("<<<<<<< local\n{local}\n=======\n{remote}\n>>>>>>> remote")
+    }
+}
+
+/// Built-in conflict resolution strategies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    KeepLocal,
+    KeepRemote,
+    MergeByLine,
+    Manual,
+}
+
+impl ConflictStrategy {
+    pub const ALL: [Self; 4] = [
+        Self::KeepLocal,
+        Self::KeepRemote,
+        Self::MergeByLine,
+        Self::Manual,
+    ];
+
+    /// Resolver implementing this strategy
+    pub const fn resolver(self) -> &'static dyn ConflictResolver {
+        match self {
+            Self::KeepLocal => &KeepLocal,
+            Self::KeepRemote => &KeepRemote,
+            Self::MergeByLine => &MergeByLine,
+            Self::Manual => &Manual,
+        }
+    }
+}
+
+/// Union of the lines of both texts along their longest common subsequence
+fn merge_lines(local: &str, remote: &str) -> String {
+    let local: Vec<&str> = local.lines().collect();
+    let remote: Vec<&str> = remote.lines().collect();
+
+    // common[i][j]: length of the longest common subsequence of
+    // local[i..] and remote[j..]
+    let mut common = vec![vec![0_usize; remote.len() + 1]; local.len() + 1];
+    for i in (0..local.len()).rev() {
+        for j in (0..remote.len()).rev() {
+            common[i][j] = if local[i] == remote[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut merged = Vec::with_capacity(local.len() + remote.len());
+    let (mut i, mut j) = (0, 0);
+    while i < local.len() && j < remote.len() {
+        if local[i] == remote[j] {
+            merged.push(local[i]);
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            merged.push(local[i]);
+            i += 1;
+        } else {
+            merged.push(remote[j]);
+            j += 1;
+        }
+    }
+    merged.extend_from_slice(&local[i..]);
+    merged.extend_from_slice(&remote[j..]);
+    merged.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategies_pick_or_combine_versions() {
+        let local = "Groceries\n- eggs\n- milk";
+        let remote = "Groceries\n- eggs\n- bread";
+
+        assert_eq!(KeepLocal.resolve(local, remote), local);
+        assert_eq!(KeepRemote.resolve(local, remote), remote);
+        assert_eq!(
+            MergeByLine.resolve(local, remote),
+            "Groceries\n- eggs\n- milk\n- bread"
+        );
+        assert_eq!(
+            Manual.resolve("a", "b"),
+            "<<<<<<< local\na\n=======\nb\n>>>>>>> remote"
+        );
+    }
+
+    #[test]
+    fn merge_by_line_keeps_the_order_of_both_sides() {
+        assert_eq!(
+            MergeByLine.resolve("one\ntwo\nfour", "one\nthree\nfour\nfive"),
+            "one\ntwo\nthree\nfour\nfive"
+        );
+        assert_eq!(MergeByLine.resolve("", "only remote"), "only remote");
+        assert_eq!(MergeByLine.resolve("same", "same"), "same");
+    }
+
+    #[test]
+    fn every_strategy_has_a_distinct_name() {
+        let names: std::collections::HashSet<_> = ConflictStrategy::ALL
+            .iter()
+            .map(|strategy| strategy.resolver().name())
+            .collect();
+        assert_eq!(names.len(), ConflictStrategy::ALL.len());
+        assert!(!names.contains("lww"));
+    }
+}
@@ -0,0 +1,208 @@
+//! Selective sync: notes carrying an excluded tag are readable on this device
+//! only.
+//!
+//! A synced database is a replica of the server, so every row syncs. A note
+//! with one of `Settings::sync_excluded_tags` is therefore stored as a short
+//! placeholder followed by an envelope encrypted with a key that never leaves
+//! this device (a file next to the local database). The server and other
+//! devices see the placeholder and the excluded tags; this device decrypts the
+//! envelope transparently when the note is read.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::crypto::{ContentKey, ENCRYPTED_MARKER};
+use crate::error::{Error, Result};
+use crate::models::extract_tags;
+use crate::util::{decode_hex, encode_hex};
+
+/// Prefix of the placeholder line holding a held-back note's content.
+pub const SYNC_EXCLUDED_MARKER: &str = "dirt-local:v1:";
+
+/// File next to the database that holds this device's sync filter key
+pub const SYNC_FILTER_KEY_FILE: &str = "sync-filter.key";
+
+/// Path of the sync filter key for the database at `db_path`.
+#[must_use]
+pub fn sync_filter_key_path(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(SYNC_FILTER_KEY_FILE)
+}
+
+/// Whether stored note content is a placeholder for a held-back note.
+#[must_use]
+pub fn is_sync_excluded(content: &str) -> bool {
+    excluded_payload(content).is_some()
+}
+
+fn excluded_payload(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(SYNC_EXCLUDED_MARKER))
+}
+
+/// Which notes this device keeps to itself, and the key that keeps them.
+#[derive(Debug, Clone)]
+pub struct SyncFilter {
+    key: ContentKey,
+    excluded_tags: Vec<String>,
+}
+
+impl SyncFilter {
+    /// Hold back notes carrying any of `excluded_tags` under `key`.
+    #[must_use]
+    pub const fn new(key: ContentKey, excluded_tags: Vec<String>) -> Self {
+        Self { key, excluded_tags }
+    }
+
+    /// Use the key stored next to the database at `db_path`, creating it on
+    /// first use.
+    pub fn load_beside_database(db_path: &Path, excluded_tags: Vec<String>) -> Result<Self> {
+        let path = sync_filter_key_path(db_path);
+        let key = match fs::read_to_string(&path) {
+            Ok(raw) => decode_hex(raw.trim())
+                .and_then(|bytes| ContentKey::from_bytes(&bytes))
+                .ok_or_else(|| {
+                    Error::InvalidInput(format!("Corrupt sync filter key at {}", path.display()))
+                })?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                let key = ContentKey::generate();
+                store_key(&path, &key)?;
+                key
+            }
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self::new(key, excluded_tags))
+    }
+
+    /// Tags whose notes are held back
+    #[must_use]
+    pub fn excluded_tags(&self) -> &[String] {
+        &self.excluded_tags
+    }
+
+    /// Replace the tags whose notes are held back.
+    pub fn set_excluded_tags(&mut self, excluded_tags: Vec<String>) {
+        self.excluded_tags = excluded_tags;
+    }
+
+    /// Whether any tag is excluded.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        !self.excluded_tags.is_empty()
+    }
+
+    /// Whether a note with `content` is held back.
+    #[must_use]
+    pub fn excludes(&self, content: &str) -> bool {
+        extract_tags(content)
+            .iter()
+            .any(|tag| self.excluded_tags.contains(tag))
+    }
+
+    /// Placeholder to store instead of `content`, or `None` when the note
+    /// syncs as it is.
+    ///
+    /// Placeholders of notes held back by another device cannot be edited,
+    /// since saving one would replace the note on that device as well.
+    pub fn hold_back(&self, content: &str) -> Result<Option<String>> {
+        if is_sync_excluded(content) {
+            return Err(Error::InvalidInput(
+                "This note is kept on another device and can only be edited there".into(),
+            ));
+        }
+        let mut tags: Vec<String> = extract_tags(content)
+            .into_iter()
+            .filter(|tag| self.excluded_tags.contains(tag))
+            .collect();
+        if tags.is_empty() {
+            return Ok(None);
+        }
+        tags.sort();
+
+        let envelope = self.key.encrypt_text(content)?;
+        let payload = envelope.strip_prefix(ENCRYPTED_MARKER).unwrap_or(&envelope);
+        let tags = tags
+            .iter()
+            .map(|tag| format!("#{tag}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(Some(format!(
+            "Kept on another device {tags}\n\n{SYNC_EXCLUDED_MARKER}{payload}"
+        )))
+    }
+
+    /// Content to show for `stored`: the original note when this device held
+    /// it back, otherwise `stored` unchanged.
+    #[must_use]
+    pub fn restore(&self, stored: String) -> String {
+        let Some(payload) = excluded_payload(&stored) else {
+            return stored;
+        };
+        self.key
+            .decrypt_text(&format!("{ENCRYPTED_MARKER}{payload}"))
+            .unwrap_or(stored)
+    }
+}
+
+fn store_key(path: &Path, key: &ContentKey) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(encode_hex(key.as_bytes()).as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_back_excluded_notes_for_this_device_only() {
+        let filter = SyncFilter::new(ContentKey::generate(), vec!["private".to_string()]);
+        assert_eq!(filter.hold_back("Groceries #home").unwrap(), None);
+
+        let stored = filter
+            .hold_back("Diary #Private #journal\nDear diary")
+            .unwrap()
+            .unwrap();
+        assert!(stored.starts_with("Kept on another device #private\n"));
+        assert!(!stored.contains("Dear diary"));
+        assert!(is_sync_excluded(&stored));
+        assert_eq!(
+            filter.restore(stored.clone()),
+            "Diary #Private #journal\nDear diary"
+        );
+
+        let other_device = SyncFilter::new(ContentKey::generate(), vec!["private".to_string()]);
+        assert_eq!(other_device.restore(stored.clone()), stored);
+        assert!(other_device.hold_back(&stored).is_err());
+    }
+
+    #[test]
+    fn key_is_created_once_per_data_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("dirt.db");
+
+        let first = SyncFilter::load_beside_database(&db_path, vec!["private".to_string()])
+            .unwrap()
+            .hold_back("#private")
+            .unwrap()
+            .unwrap();
+        let second = SyncFilter::load_beside_database(&db_path, Vec::new()).unwrap();
+        assert!(!second.is_active());
+        assert_eq!(second.restore(first), "#private");
+        assert!(dir.path().join(SYNC_FILTER_KEY_FILE).exists());
+    }
+}
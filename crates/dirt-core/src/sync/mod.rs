@@ -3,12 +3,26 @@
 //! Exchanges a Supabase access token for short-lived Turso database
 //! credentials via the Dirt API backend.
 
+mod conflict;
+mod filter;
+mod scheduler;
+
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::http::RateLimited;
+use crate::util::retry::{Idempotency, RetryPolicy};
 use crate::util::{compact_text, is_http_url, normalize_text_option, unix_timestamp_now};
 
+pub use conflict::{
+    ConflictResolver, ConflictStrategy, KeepLocal, KeepRemote, Manual, MergeByLine,
+};
+pub use filter::{
+    is_sync_excluded, sync_filter_key_path, SyncFilter, SYNC_EXCLUDED_MARKER, SYNC_FILTER_KEY_FILE,
+};
+pub use scheduler::{CredentialRefreshReason, SyncAction, SyncRotationEvent, SyncScheduler};
+
 /// Short-lived Turso sync credentials minted by backend auth exchange.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SyncToken {
@@ -18,6 +32,11 @@ pub struct SyncToken {
     pub expires_at: i64,
     /// Turso database URL to pair with the token.
     pub database_url: String,
+    /// Backend credential generation; a change means credentials were rotated.
+    ///
+    /// `None` when the backend predates credential rotation.
+    #[serde(default)]
+    pub credential_epoch: Option<u64>,
 }
 
 impl std::fmt::Debug for SyncToken {
@@ -27,6 +46,7 @@ impl std::fmt::Debug for SyncToken {
             .field("token", &"[REDACTED]")
             .field("expires_at", &self.expires_at)
             .field("database_url", &self.database_url)
+            .field("credential_epoch", &self.credential_epoch)
             .finish()
     }
 }
@@ -40,10 +60,22 @@ pub enum SyncAuthError {
     Http(#[from] reqwest::Error),
     #[error("Sync API error: {0}")]
     Api(String),
+    #[error("Sync API {0}")]
+    RateLimited(RateLimited),
     #[error("Invalid sync token payload: {0}")]
     InvalidPayload(String),
 }
 
+impl SyncAuthError {
+    /// Returns the backoff requested by the API when this is a rate-limit error.
+    pub const fn rate_limited(&self) -> Option<RateLimited> {
+        match self {
+            Self::RateLimited(limited) => Some(*limited),
+            _ => None,
+        }
+    }
+}
+
 pub type SyncAuthResult<T> = Result<T, SyncAuthError>;
 
 /// Backend token exchange client.
@@ -51,6 +83,7 @@ pub type SyncAuthResult<T> = Result<T, SyncAuthError>;
 pub struct TursoSyncAuthClient {
     endpoint: String,
     client: Client,
+    retry: RetryPolicy,
 }
 
 impl TursoSyncAuthClient {
@@ -60,9 +93,18 @@ impl TursoSyncAuthClient {
         Ok(Self {
             endpoint,
             client: Client::new(),
+            retry: RetryPolicy::DEFAULT,
         })
     }
 
+    /// Retries transient exchange failures with `policy` instead of
+    /// [`RetryPolicy::DEFAULT`].
+    #[must_use]
+    pub const fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
     /// Returns the endpoint URL this client was configured with.
     pub fn endpoint(&self) -> &str {
         &self.endpoint
@@ -77,13 +119,19 @@ impl TursoSyncAuthClient {
             ));
         }
 
-        let response = self
+        // Minting another short-lived token is harmless, so the exchange is
+        // safe to repeat.
+        let request = self
             .client
             .post(&self.endpoint)
             .bearer_auth(access_token)
-            .header("Accept", "application/json")
-            .send()
-            .await?;
+            .header("Accept", "application/json");
+        let response = self.retry.send(request, Idempotency::Idempotent).await?;
+
+        if let Some(limited) = RateLimited::from_response(response.status(), response.headers()) {
+            tracing::warn!("Sync token exchange {}", limited);
+            return Err(SyncAuthError::RateLimited(limited));
+        }
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -108,6 +156,7 @@ struct SyncTokenResponse {
     /// Relative expiry in seconds — used as fallback when `expires_at` is absent.
     expires_in: Option<i64>,
     database_url: Option<String>,
+    credential_epoch: Option<u64>,
 }
 
 impl TryFrom<SyncTokenResponse> for SyncToken {
@@ -142,6 +191,7 @@ impl TryFrom<SyncTokenResponse> for SyncToken {
             token,
             expires_at,
             database_url,
+            credential_epoch: value.credential_epoch,
         })
     }
 }
@@ -201,9 +251,27 @@ mod tests {
             token: "secret".to_string(),
             expires_at: 123,
             database_url: "libsql://example.turso.io".to_string(),
+            credential_epoch: Some(1),
         };
         let debug = format!("{token:?}");
         assert!(!debug.contains("secret"));
         assert!(debug.contains("[REDACTED]"));
     }
+
+    #[test]
+    fn sync_token_response_reads_optional_credential_epoch() {
+        let payload: SyncTokenResponse = serde_json::from_str(
+            r#"{"auth_token":"t","expires_at":10,"database_url":"libsql://db","credential_epoch":4}"#,
+        )
+        .unwrap();
+        let token = SyncToken::try_from(payload).unwrap();
+        assert_eq!(token.credential_epoch, Some(4));
+
+        let payload: SyncTokenResponse = serde_json::from_str(
+            r#"{"auth_token":"t","expires_at":10,"database_url":"libsql://db"}"#,
+        )
+        .unwrap();
+        let token = SyncToken::try_from(payload).unwrap();
+        assert_eq!(token.credential_epoch, None);
+    }
 }
@@ -0,0 +1,274 @@
+//! Credential refresh and rotation scheduling for managed sync.
+//!
+//! libSQL bakes sync credentials into the replica connection, so clients
+//! reopen the replica whenever the token nears expiry or the backend reports
+//! rotated credentials. The scheduler only decides *when*; callers perform
+//! the token exchange and reconnect.
+
+use std::fmt;
+
+use super::SyncToken;
+
+/// Reopen the replica this many seconds before its token expires.
+const DEFAULT_REFRESH_MARGIN_SECS: i64 = 120;
+/// How often to ask the backend whether credentials were rotated.
+const DEFAULT_ROTATION_CHECK_INTERVAL_SECS: i64 = 300;
+
+/// Why the replica should be reopened with fresh credentials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialRefreshReason {
+    /// The current token is about to expire.
+    Expiring,
+    /// The backend rotated sync credentials.
+    Rotated,
+}
+
+impl fmt::Display for CredentialRefreshReason {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Expiring => "token expiring",
+            Self::Rotated => "credentials rotated",
+        })
+    }
+}
+
+/// Next step for a client's periodic sync loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    /// Sync the open replica with its current credentials.
+    Sync,
+    /// Exchange a token to learn whether the backend rotated credentials.
+    CheckRotation,
+    /// Reopen the replica with freshly exchanged credentials.
+    Reopen(CredentialRefreshReason),
+}
+
+/// Credential rotation observed during a token exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncRotationEvent {
+    /// Unix timestamp (seconds) when the rotation was observed.
+    pub observed_at: i64,
+    /// Epoch of the credentials the replica was opened with.
+    pub previous_epoch: Option<u64>,
+    /// Epoch reported by the backend.
+    pub epoch: u64,
+}
+
+impl fmt::Display for SyncRotationEvent {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.previous_epoch {
+            Some(previous) => write!(
+                formatter,
+                "Sync credentials rotated (epoch {previous} -> {})",
+                self.epoch
+            ),
+            None => write!(formatter, "Sync credentials rotated (epoch {})", self.epoch),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ActiveCredentials {
+    expires_at: i64,
+    epoch: Option<u64>,
+    checked_at: i64,
+}
+
+/// Tracks the credentials behind the open replica and schedules refreshes.
+#[derive(Debug, Clone)]
+pub struct SyncScheduler {
+    refresh_margin_secs: i64,
+    rotation_check_interval_secs: i64,
+    active: Option<ActiveCredentials>,
+    pending: Option<SyncToken>,
+    last_rotation: Option<SyncRotationEvent>,
+}
+
+impl Default for SyncScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncScheduler {
+    /// Creates a scheduler with the default refresh margin and check interval.
+    pub const fn new() -> Self {
+        Self::with_intervals(
+            DEFAULT_REFRESH_MARGIN_SECS,
+            DEFAULT_ROTATION_CHECK_INTERVAL_SECS,
+        )
+    }
+
+    /// Creates a scheduler with explicit timings, in seconds.
+    pub const fn with_intervals(
+        refresh_margin_secs: i64,
+        rotation_check_interval_secs: i64,
+    ) -> Self {
+        Self {
+            refresh_margin_secs,
+            rotation_check_interval_secs,
+            active: None,
+            pending: None,
+            last_rotation: None,
+        }
+    }
+
+    /// Records the token the replica was just opened with.
+    pub fn credentials_opened(&mut self, token: &SyncToken, now: i64) {
+        self.active = Some(ActiveCredentials {
+            expires_at: token.expires_at,
+            epoch: token.credential_epoch,
+            checked_at: now,
+        });
+        self.pending = None;
+    }
+
+    /// Forgets the current credentials, e.g. after sign-out or a failed open.
+    pub fn clear(&mut self) {
+        self.active = None;
+        self.pending = None;
+    }
+
+    /// Returns whether a replica is currently open with managed credentials.
+    pub const fn has_credentials(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Returns the most recent rotation observed by this scheduler.
+    pub const fn last_rotation(&self) -> Option<SyncRotationEvent> {
+        self.last_rotation
+    }
+
+    /// Decides what the periodic sync loop should do at `now`.
+    pub fn next_action(&self, now: i64) -> SyncAction {
+        let Some(active) = self.active else {
+            return SyncAction::Sync;
+        };
+        if self.pending.is_some() {
+            return SyncAction::Reopen(CredentialRefreshReason::Rotated);
+        }
+        if now >= active.expires_at.saturating_sub(self.refresh_margin_secs) {
+            return SyncAction::Reopen(CredentialRefreshReason::Expiring);
+        }
+        // Backends without credential epochs cannot signal rotation.
+        if active.epoch.is_some()
+            && now.saturating_sub(active.checked_at) >= self.rotation_check_interval_secs
+        {
+            return SyncAction::CheckRotation;
+        }
+        SyncAction::Sync
+    }
+
+    /// Marks a rotation check as started so failures are not retried every tick.
+    pub fn begin_rotation_check(&mut self, now: i64) {
+        if let Some(active) = self.active.as_mut() {
+            active.checked_at = now;
+        }
+    }
+
+    /// Compares a freshly exchanged token with the open replica's credentials.
+    ///
+    /// Returns the rotation event when the backend reports a new credential
+    /// epoch; the token is then held until [`Self::take_pending_token`].
+    pub fn observe_exchange(&mut self, token: SyncToken, now: i64) -> Option<SyncRotationEvent> {
+        let active = self.active?;
+        let epoch = token.credential_epoch?;
+        if active.epoch == Some(epoch) {
+            return None;
+        }
+
+        let event = SyncRotationEvent {
+            observed_at: now,
+            previous_epoch: active.epoch,
+            epoch,
+        };
+        self.last_rotation = Some(event);
+        self.pending = Some(token);
+        Some(event)
+    }
+
+    /// Takes the token stashed by a rotation check if it is still usable.
+    pub fn take_pending_token(&mut self, now: i64) -> Option<SyncToken> {
+        self.pending
+            .take()
+            .filter(|token| now < token.expires_at.saturating_sub(self.refresh_margin_secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(expires_at: i64, credential_epoch: Option<u64>) -> SyncToken {
+        SyncToken {
+            token: "token".to_string(),
+            expires_at,
+            database_url: "libsql://example.turso.io".to_string(),
+            credential_epoch,
+        }
+    }
+
+    #[test]
+    fn scheduler_refreshes_before_expiry() {
+        let mut scheduler = SyncScheduler::new();
+        assert_eq!(scheduler.next_action(0), SyncAction::Sync);
+
+        scheduler.credentials_opened(&token(1_000, None), 0);
+        assert_eq!(scheduler.next_action(500), SyncAction::Sync);
+        assert_eq!(
+            scheduler.next_action(880),
+            SyncAction::Reopen(CredentialRefreshReason::Expiring)
+        );
+    }
+
+    #[test]
+    fn scheduler_checks_rotation_only_when_backend_reports_epochs() {
+        let mut scheduler = SyncScheduler::new();
+        scheduler.credentials_opened(&token(10_000, None), 0);
+        assert_eq!(scheduler.next_action(600), SyncAction::Sync);
+
+        scheduler.credentials_opened(&token(10_000, Some(1)), 0);
+        assert_eq!(scheduler.next_action(299), SyncAction::Sync);
+        assert_eq!(scheduler.next_action(300), SyncAction::CheckRotation);
+
+        scheduler.begin_rotation_check(300);
+        assert_eq!(scheduler.next_action(400), SyncAction::Sync);
+    }
+
+    #[test]
+    fn scheduler_reopens_with_pending_token_after_rotation() {
+        let mut scheduler = SyncScheduler::new();
+        scheduler.credentials_opened(&token(10_000, Some(1)), 0);
+
+        assert_eq!(
+            scheduler.observe_exchange(token(10_300, Some(1)), 300),
+            None
+        );
+        assert_eq!(scheduler.next_action(310), SyncAction::Sync);
+
+        let event = scheduler
+            .observe_exchange(token(10_600, Some(2)), 600)
+            .expect("rotation event");
+        assert_eq!(event.previous_epoch, Some(1));
+        assert_eq!(event.epoch, 2);
+        assert_eq!(scheduler.last_rotation(), Some(event));
+        assert_eq!(
+            scheduler.next_action(601),
+            SyncAction::Reopen(CredentialRefreshReason::Rotated)
+        );
+
+        let pending = scheduler.take_pending_token(601).expect("pending token");
+        assert_eq!(pending.credential_epoch, Some(2));
+        scheduler.credentials_opened(&pending, 602);
+        assert_eq!(scheduler.next_action(603), SyncAction::Sync);
+        assert_eq!(scheduler.last_rotation(), Some(event));
+    }
+
+    #[test]
+    fn take_pending_token_drops_tokens_near_expiry() {
+        let mut scheduler = SyncScheduler::new();
+        scheduler.credentials_opened(&token(10_000, Some(1)), 0);
+        scheduler.observe_exchange(token(1_000, Some(2)), 300);
+        assert!(scheduler.take_pending_token(900).is_none());
+    }
+}
@@ -0,0 +1,160 @@
+//! Renaming, merging, and removing tags.
+//!
+//! Tags only exist as `#hashtags` in note content, so every operation here
+//! rewrites content; the `tags` table follows when the note is saved. The
+//! database side runs all notes of one operation in a single transaction (see
+//! `DatabaseService::rename_tag`).
+
+use regex::Regex;
+
+use crate::capture::normalize_capture_tag;
+use crate::error::{Error, Result};
+use crate::models::extract_tags;
+
+/// Normalize a tag given on the command line or in a form (`#Work` → `work`).
+pub fn parse_tag_name(raw: &str) -> Result<String> {
+    normalize_capture_tag(raw)
+        .ok_or_else(|| Error::InvalidInput(format!("`{}` is not a valid tag", raw.trim())))
+}
+
+/// `content` with every `#from` replaced by `#to`, or `None` when it does not
+/// carry `from`.
+///
+/// Notes that already carry `to` just lose `#from`, so merging never leaves
+/// the same tag twice.
+///
+/// ```
+/// use dirt_core::tags::content_with_renamed_tag;
+///
+/// assert_eq!(
+///     content_with_renamed_tag("Standup #Work", "work", "job").as_deref(),
+///     Some("Standup #job")
+/// );
+/// assert_eq!(content_with_renamed_tag("#workshop", "work", "job"), None);
+/// ```
+#[must_use]
+pub fn content_with_renamed_tag(content: &str, from: &str, to: &str) -> Option<String> {
+    if from != to && extract_tags(content).iter().any(|tag| tag == to) {
+        return content_without_tag(content, from);
+    }
+    let mut changed = false;
+    let renamed = tag_pattern().replace_all(content, |caps: &regex::Captures<'_>| {
+        if caps[1].eq_ignore_ascii_case(from) {
+            changed = true;
+            format!("#{to}")
+        } else {
+            caps[0].to_string()
+        }
+    });
+    changed.then(|| renamed.into_owned())
+}
+
+/// `content` without any `#tag`, or `None` when it does not carry `tag`.
+///
+/// Lines left empty once their tags are gone are dropped.
+///
+/// ```
+/// use dirt_core::tags::content_without_tag;
+///
+/// assert_eq!(
+///     content_without_tag("Call Sam #todo today\n\n#todo", "todo").as_deref(),
+///     Some("Call Sam today")
+/// );
+/// ```
+#[must_use]
+pub fn content_without_tag(content: &str, tag: &str) -> Option<String> {
+    let pattern = tag_pattern();
+    let mut changed = false;
+    let mut lines = Vec::new();
+    for line in content.split('\n') {
+        let Some(stripped) = line_without_tag(&pattern, line, tag) else {
+            lines.push(line.to_string());
+            continue;
+        };
+        changed = true;
+        if !stripped.trim().is_empty() {
+            lines.push(stripped);
+        }
+    }
+    if !changed {
+        return None;
+    }
+    let rewritten = lines.join("\n");
+    Some(if content.ends_with(char::is_whitespace) {
+        rewritten
+    } else {
+        rewritten.trim_end().to_string()
+    })
+}
+
+fn line_without_tag(pattern: &Regex, line: &str, tag: &str) -> Option<String> {
+    let mut stripped = String::with_capacity(line.len());
+    let mut kept_from = 0;
+    for caps in pattern.captures_iter(line) {
+        if !caps[1].eq_ignore_ascii_case(tag) {
+            continue;
+        }
+        let found = caps.get(0).expect("match has a whole group");
+        let mut start = found.start();
+        let mut end = found.end();
+        // Take one neighbouring space with the tag so words stay single-spaced.
+        if start > kept_from && line[..start].ends_with(' ') {
+            start -= 1;
+        } else if line[end..].starts_with(' ') {
+            end += 1;
+        }
+        stripped.push_str(&line[kept_from..start]);
+        kept_from = end;
+    }
+    if kept_from == 0 {
+        return None;
+    }
+    stripped.push_str(&line[kept_from..]);
+    Some(stripped)
+}
+
+fn tag_pattern() -> Regex {
+    Regex::new(r"#([a-zA-Z][a-zA-Z0-9_-]*)").expect("Invalid regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renames_whole_tags_only() {
+        assert_eq!(
+            content_with_renamed_tag("#work and #WORK, not #work-log", "work", "job").as_deref(),
+            Some("#job and #job, not #work-log")
+        );
+        assert_eq!(
+            content_with_renamed_tag("Plan #work #job", "work", "job").as_deref(),
+            Some("Plan #job")
+        );
+        assert_eq!(content_with_renamed_tag("No tags", "work", "job"), None);
+    }
+
+    #[test]
+    fn removes_tags_and_empty_tag_lines() {
+        assert_eq!(
+            content_without_tag("#todo buy milk", "todo").as_deref(),
+            Some("buy milk")
+        );
+        assert_eq!(
+            content_without_tag("Title\n#todo #home\nBody\n", "todo").as_deref(),
+            Some("Title\n#home\nBody\n")
+        );
+        assert_eq!(
+            content_without_tag("Title\n\n#todo", "todo").as_deref(),
+            Some("Title")
+        );
+        assert_eq!(content_without_tag("#todos", "todo"), None);
+    }
+
+    #[test]
+    fn parse_tag_name_normalizes_input() {
+        assert_eq!(parse_tag_name(" #Work ").unwrap(), "work");
+        assert!(parse_tag_name("#1st").is_err());
+        assert!(parse_tag_name("two words").is_err());
+    }
+}
@@ -0,0 +1,755 @@
+//! Voice memo transcription through speech-to-text providers.
+//!
+//! A [`Transcriber`] turns one audio clip into text. [`transcribe_audio`]
+//! checks the clip, splits WAV recordings larger than the provider accepts in
+//! one request, and retries rate-limited or failed requests. The transcript is
+//! stored with [`append_voice_memo_transcript`] as a
+//! `[Voice memo transcript: <file>]` block at the end of the note.
+//!
+//! Settings pick the provider ([`configured_transcriber`]): the `OpenAI` API,
+//! or whisper.cpp on this device when built with the `transcribe-whisper`
+//! feature, using a model downloaded into a [`WhisperModelStore`]. Where the
+//! `OpenAI` key comes from is up to each client: the desktop and mobile apps
+//! keep it in secure storage, the CLI reads `OPENAI_API_KEY`.
+
+mod openai;
+mod pcm;
+#[cfg(feature = "transcribe-whisper")]
+mod whisper;
+mod whisper_models;
+
+pub use openai::OpenAiTranscriber;
+pub use pcm::{wav_to_whisper_samples, WHISPER_SAMPLE_RATE};
+#[cfg(feature = "transcribe-whisper")]
+pub use whisper::WhisperTranscriber;
+pub use whisper_models::{
+    find_whisper_model, InstalledWhisperModel, WhisperModel, WhisperModelStore,
+    DEFAULT_WHISPER_MODEL, WHISPER_MODELS, WHISPER_MODELS_DIR,
+};
+
+use std::fmt;
+use std::io::Cursor;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::{ConfigSource, EnvConfig};
+use crate::models::{Note, NoteId, Settings};
+use crate::services::DatabaseService;
+use crate::util::is_http_url;
+use crate::{Error, Result};
+
+/// Environment variable holding the `OpenAI` API key
+pub const ENV_OPENAI_API_KEY: &str = "OPENAI_API_KEY";
+const ENV_OPENAI_BASE_URL: &str = "OPENAI_BASE_URL";
+const ENV_OPENAI_TRANSCRIPTION_MODEL: &str = "OPENAI_TRANSCRIPTION_MODEL";
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com";
+const DEFAULT_MODEL: &str = "gpt-4o-mini-transcribe";
+
+/// Largest clip sent in one request unless a provider says otherwise.
+pub const MAX_TRANSCRIPTION_UPLOAD_BYTES: usize = 25 * 1024 * 1024;
+
+/// Requests made for one clip before a retryable failure is returned.
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Room left for the header when sizing WAV chunks; hound writes at most 68
+/// bytes.
+const WAV_HEADER_BYTES: usize = 80;
+
+/// Errors from transcription setup and requests.
+#[derive(Debug, Error)]
+pub enum TranscriptionError {
+    #[error("Transcription is not configured. Add an OpenAI API key in Settings.")]
+    NotConfigured,
+    #[error("Invalid transcription configuration: {0}")]
+    InvalidConfiguration(String),
+    #[error("Secure storage error: {0}")]
+    SecureStorage(String),
+    #[error("Invalid audio: {0}")]
+    InvalidAudio(String),
+    #[error("Unauthorized transcription request (check configured OpenAI API key)")]
+    Unauthorized,
+    /// The provider answered with an error status.
+    #[error("Transcription request failed with HTTP {status}: {message}")]
+    Api { status: u16, message: String },
+    /// The request did not get an answer.
+    #[error("HTTP request failed: {0}")]
+    Request(String),
+    #[error("Whisper model storage error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl TranscriptionError {
+    /// Whether sending the same request again may succeed.
+    #[must_use]
+    pub const fn is_retryable(&self) -> bool {
+        match self {
+            Self::Request(_) => true,
+            Self::Api { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+pub type TranscriptionResult<T> = std::result::Result<T, TranscriptionError>;
+
+/// Speech-to-text service behind a [`Transcriber`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionProvider {
+    /// The `OpenAI` audio transcriptions API
+    #[default]
+    OpenAi,
+    /// whisper.cpp on this device
+    Whisper,
+}
+
+impl TranscriptionProvider {
+    /// Stable identifier, as stored in settings.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Whisper => "whisper",
+        }
+    }
+
+    /// Name shown to users.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::OpenAi => "OpenAI",
+            Self::Whisper => "Whisper (offline)",
+        }
+    }
+
+    /// Whether this build includes the provider.
+    #[must_use]
+    pub const fn is_enabled(self) -> bool {
+        match self {
+            Self::OpenAi => true,
+            Self::Whisper => cfg!(feature = "transcribe-whisper"),
+        }
+    }
+
+    /// Parse a provider identifier (case-insensitive).
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "openai" => Some(Self::OpenAi),
+            "whisper" => Some(Self::Whisper),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TranscriptionProvider {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.label())
+    }
+}
+
+/// Provider settings for `OpenAI` speech-to-text.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TranscriptionConfig {
+    pub api_key: String,
+    /// API origin without a trailing slash.
+    pub base_url: String,
+    pub model: String,
+}
+
+impl fmt::Debug for TranscriptionConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TranscriptionConfig")
+            .field("api_key", &"[REDACTED]")
+            .field("base_url", &self.base_url)
+            .field("model", &self.model)
+            .finish()
+    }
+}
+
+impl TranscriptionConfig {
+    /// Load settings from environment variables, taking the key from
+    /// `OPENAI_API_KEY`.
+    ///
+    /// Returns `Ok(None)` when no key is set.
+    pub fn from_env() -> TranscriptionResult<Option<Self>> {
+        Self::from_source(&EnvConfig)
+    }
+
+    /// Load settings from `source`, with the same rules as [`Self::from_env`].
+    pub fn from_source(source: &impl ConfigSource) -> TranscriptionResult<Option<Self>> {
+        Self::resolve(source.text_var(ENV_OPENAI_API_KEY), source)
+    }
+
+    /// Settings for `api_key` (e.g. from secure storage), with the
+    /// `OPENAI_BASE_URL` and `OPENAI_TRANSCRIPTION_MODEL` overrides in
+    /// `source`.
+    ///
+    /// Returns `Ok(None)` without a key.
+    pub fn resolve(
+        api_key: Option<String>,
+        source: &impl ConfigSource,
+    ) -> TranscriptionResult<Option<Self>> {
+        let Some(api_key) = api_key
+            .map(|key| key.trim().to_string())
+            .filter(|key| !key.is_empty())
+        else {
+            return Ok(None);
+        };
+
+        let base_url = match source.text_var(ENV_OPENAI_BASE_URL) {
+            Some(value) if !is_http_url(&value) => {
+                return Err(TranscriptionError::InvalidConfiguration(format!(
+                    "{ENV_OPENAI_BASE_URL} must start with http:// or https://"
+                )));
+            }
+            Some(value) => value.trim_end_matches('/').to_string(),
+            None => DEFAULT_BASE_URL.to_string(),
+        };
+        let model = source
+            .text_var(ENV_OPENAI_TRANSCRIPTION_MODEL)
+            .unwrap_or_else(|| DEFAULT_MODEL.to_string());
+
+        Ok(Some(Self {
+            api_key,
+            base_url,
+            model,
+        }))
+    }
+}
+
+/// Audio to transcribe
+#[derive(Debug, Clone, Copy)]
+pub struct AudioClip<'a> {
+    pub file_name: &'a str,
+    /// An `audio/*` type
+    pub mime_type: &'a str,
+    pub bytes: &'a [u8],
+}
+
+impl AudioClip<'_> {
+    fn validate(&self) -> TranscriptionResult<()> {
+        let problem = if self.file_name.trim().is_empty() {
+            "file name must not be empty"
+        } else if !self
+            .mime_type
+            .trim()
+            .to_ascii_lowercase()
+            .starts_with("audio/")
+        {
+            "type must start with audio/"
+        } else if self.bytes.is_empty() {
+            "audio payload must not be empty"
+        } else {
+            return Ok(());
+        };
+        Err(TranscriptionError::InvalidAudio(problem.to_string()))
+    }
+
+    fn is_wav(&self) -> bool {
+        matches!(
+            self.mime_type.trim().to_ascii_lowercase().as_str(),
+            "audio/wav" | "audio/x-wav" | "audio/wave" | "audio/vnd.wave"
+        ) || self.file_name.to_ascii_lowercase().ends_with(".wav")
+    }
+}
+
+/// Turns audio into text.
+#[allow(async_fn_in_trait)]
+pub trait Transcriber {
+    /// Stable provider identifier, e.g. `openai`.
+    fn provider(&self) -> &'static str;
+
+    /// Model the provider is asked to use.
+    fn model(&self) -> &str;
+
+    /// Largest clip the provider accepts in one request.
+    fn max_upload_bytes(&self) -> usize {
+        MAX_TRANSCRIPTION_UPLOAD_BYTES
+    }
+
+    /// Transcribe one clip no larger than [`Self::max_upload_bytes`].
+    async fn transcribe(&self, clip: &AudioClip<'_>) -> TranscriptionResult<String>;
+}
+
+/// The transcriber chosen in settings.
+#[derive(Debug, Clone)]
+pub enum ConfiguredTranscriber {
+    OpenAi(OpenAiTranscriber),
+    #[cfg(feature = "transcribe-whisper")]
+    Whisper(WhisperTranscriber),
+}
+
+impl Transcriber for ConfiguredTranscriber {
+    fn provider(&self) -> &'static str {
+        match self {
+            Self::OpenAi(transcriber) => transcriber.provider(),
+            #[cfg(feature = "transcribe-whisper")]
+            Self::Whisper(transcriber) => transcriber.provider(),
+        }
+    }
+
+    fn model(&self) -> &str {
+        match self {
+            Self::OpenAi(transcriber) => transcriber.model(),
+            #[cfg(feature = "transcribe-whisper")]
+            Self::Whisper(transcriber) => transcriber.model(),
+        }
+    }
+
+    fn max_upload_bytes(&self) -> usize {
+        match self {
+            Self::OpenAi(transcriber) => transcriber.max_upload_bytes(),
+            #[cfg(feature = "transcribe-whisper")]
+            Self::Whisper(transcriber) => transcriber.max_upload_bytes(),
+        }
+    }
+
+    async fn transcribe(&self, clip: &AudioClip<'_>) -> TranscriptionResult<String> {
+        match self {
+            Self::OpenAi(transcriber) => transcriber.transcribe(clip).await,
+            #[cfg(feature = "transcribe-whisper")]
+            Self::Whisper(transcriber) => transcriber.transcribe(clip).await,
+        }
+    }
+}
+
+/// Build the transcriber `settings` select: `OpenAI` with `api_key` and the
+/// overrides in `source`, or Whisper with the chosen model from `models`.
+///
+/// Returns `Ok(None)` when `OpenAI` is selected but no key is set.
+pub fn configured_transcriber(
+    settings: &Settings,
+    api_key: Option<String>,
+    source: &impl ConfigSource,
+    models: &WhisperModelStore,
+) -> TranscriptionResult<Option<ConfiguredTranscriber>> {
+    match settings.transcription_provider {
+        TranscriptionProvider::OpenAi => Ok(TranscriptionConfig::resolve(api_key, source)?
+            .map(OpenAiTranscriber::new)
+            .transpose()?
+            .map(ConfiguredTranscriber::OpenAi)),
+        TranscriptionProvider::Whisper => whisper_transcriber(settings, models).map(Some),
+    }
+}
+
+#[cfg(feature = "transcribe-whisper")]
+fn whisper_transcriber(
+    settings: &Settings,
+    models: &WhisperModelStore,
+) -> TranscriptionResult<ConfiguredTranscriber> {
+    let name = settings
+        .whisper_model
+        .as_deref()
+        .unwrap_or(DEFAULT_WHISPER_MODEL);
+    let path = models.installed_path(name)?.ok_or_else(|| {
+        TranscriptionError::InvalidConfiguration(format!(
+            "Whisper model {name} is not downloaded yet"
+        ))
+    })?;
+    WhisperTranscriber::new(name, &path).map(ConfiguredTranscriber::Whisper)
+}
+
+#[cfg(not(feature = "transcribe-whisper"))]
+fn whisper_transcriber(
+    _settings: &Settings,
+    _models: &WhisperModelStore,
+) -> TranscriptionResult<ConfiguredTranscriber> {
+    Err(TranscriptionError::InvalidConfiguration(
+        "This build does not include offline Whisper transcription".to_string(),
+    ))
+}
+
+/// Transcribe `clip`, splitting WAV audio the provider cannot take in one
+/// request and retrying transient failures.
+///
+/// Other formats larger than the provider's limit are refused.
+pub async fn transcribe_audio<T: Transcriber>(
+    transcriber: &T,
+    clip: &AudioClip<'_>,
+) -> TranscriptionResult<String> {
+    clip.validate()?;
+    let max_bytes = transcriber.max_upload_bytes();
+    if clip.bytes.len() <= max_bytes {
+        return transcribe_with_retry(transcriber, clip).await;
+    }
+    if !clip.is_wav() {
+        return Err(TranscriptionError::InvalidAudio(format!(
+            "{} is larger than the {} MB the provider accepts",
+            clip.file_name,
+            max_bytes / (1024 * 1024)
+        )));
+    }
+
+    let mut parts = Vec::new();
+    for chunk in split_wav(clip.bytes, max_bytes)? {
+        let chunk_clip = AudioClip {
+            bytes: &chunk,
+            ..*clip
+        };
+        let text = transcribe_with_retry(transcriber, &chunk_clip).await?;
+        if !text.trim().is_empty() {
+            parts.push(text.trim().to_string());
+        }
+    }
+    Ok(parts.join(" "))
+}
+
+async fn transcribe_with_retry<T: Transcriber>(
+    transcriber: &T,
+    clip: &AudioClip<'_>,
+) -> TranscriptionResult<String> {
+    let mut attempt = 0;
+    loop {
+        match transcriber.transcribe(clip).await {
+            Err(error) if error.is_retryable() && attempt + 1 < MAX_ATTEMPTS => {
+                tracing::warn!("Transcription attempt {} failed: {}", attempt + 1, error);
+                tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt)).await;
+                attempt += 1;
+            }
+            result => return result.map(|text| text.trim().to_string()),
+        }
+    }
+}
+
+/// Split a WAV file into standalone WAV files of at most `max_bytes` each,
+/// cut on frame boundaries.
+pub fn split_wav(bytes: &[u8], max_bytes: usize) -> TranscriptionResult<Vec<Vec<u8>>> {
+    let reader = hound::WavReader::new(Cursor::new(bytes)).map_err(invalid_wav)?;
+    let spec = reader.spec();
+    let frame_bytes = usize::from(spec.channels) * usize::from(spec.bits_per_sample.div_ceil(8));
+    let frames_per_chunk = max_bytes.saturating_sub(WAV_HEADER_BYTES) / frame_bytes.max(1);
+    if frames_per_chunk == 0 {
+        return Err(TranscriptionError::InvalidAudio(format!(
+            "{max_bytes} bytes cannot hold a single audio frame"
+        )));
+    }
+    let samples_per_chunk = frames_per_chunk * usize::from(spec.channels);
+    match spec.sample_format {
+        hound::SampleFormat::Int => write_wav_chunks::<i32>(reader, spec, samples_per_chunk),
+        hound::SampleFormat::Float => write_wav_chunks::<f32>(reader, spec, samples_per_chunk),
+    }
+}
+
+fn write_wav_chunks<S: hound::Sample + Copy>(
+    mut reader: hound::WavReader<Cursor<&[u8]>>,
+    spec: hound::WavSpec,
+    samples_per_chunk: usize,
+) -> TranscriptionResult<Vec<Vec<u8>>> {
+    let samples = reader
+        .samples::<S>()
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(invalid_wav)?;
+    samples
+        .chunks(samples_per_chunk)
+        .map(|chunk| {
+            let mut cursor = Cursor::new(Vec::new());
+            let mut writer = hound::WavWriter::new(&mut cursor, spec).map_err(invalid_wav)?;
+            for &sample in chunk {
+                writer.write_sample(sample).map_err(invalid_wav)?;
+            }
+            writer.finalize().map_err(invalid_wav)?;
+            Ok(cursor.into_inner())
+        })
+        .collect()
+}
+
+fn invalid_wav(error: hound::Error) -> TranscriptionError {
+    TranscriptionError::InvalidAudio(format!("Unreadable WAV audio: {error}"))
+}
+
+/// `existing` with a transcript block for `file_name` appended; `None` when
+/// the transcript is empty.
+#[must_use]
+pub fn content_with_voice_memo_transcript(
+    existing: &str,
+    file_name: &str,
+    transcript: &str,
+) -> Option<String> {
+    let transcript = transcript.trim();
+    if transcript.is_empty() {
+        return None;
+    }
+    let file_name = match file_name.trim() {
+        "" => "voice memo",
+        name => name,
+    };
+    let block = format!("[Voice memo transcript: {file_name}]\n{transcript}");
+    let existing = existing.trim_end();
+    if existing.is_empty() {
+        Some(block)
+    } else {
+        Some(format!("{existing}\n\n{block}"))
+    }
+}
+
+/// Append `transcript` to the note, starting from `latest_content` when the
+/// caller has unsaved edits, and save it.
+///
+/// Returns the updated note, or `None` when the transcript was empty.
+pub async fn append_voice_memo_transcript(
+    db: &DatabaseService,
+    note_id: &NoteId,
+    file_name: &str,
+    transcript: &str,
+    latest_content: Option<String>,
+) -> Result<Option<Note>> {
+    let existing = match latest_content {
+        Some(content) => content,
+        None => {
+            db.get_note(note_id)
+                .await?
+                .ok_or_else(|| Error::NotFound(note_id.to_string()))?
+                .content
+        }
+    };
+    let Some(content) = content_with_voice_memo_transcript(&existing, file_name, transcript) else {
+        return Ok(None);
+    };
+    db.update_note(note_id, &content).await.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use crate::config::MapConfig;
+    use crate::storage::{encode_voice_memo_wav, VoiceMemoOptions};
+
+    use super::*;
+
+    /// Fails with each queued error before answering with the clip size.
+    struct ScriptedTranscriber {
+        failures: RefCell<Vec<TranscriptionError>>,
+        calls: RefCell<usize>,
+        max_upload_bytes: usize,
+    }
+
+    impl ScriptedTranscriber {
+        fn new(failures: Vec<TranscriptionError>, max_upload_bytes: usize) -> Self {
+            Self {
+                failures: RefCell::new(failures),
+                calls: RefCell::new(0),
+                max_upload_bytes,
+            }
+        }
+    }
+
+    impl Transcriber for ScriptedTranscriber {
+        fn provider(&self) -> &'static str {
+            "scripted"
+        }
+
+        fn model(&self) -> &str {
+            "test"
+        }
+
+        fn max_upload_bytes(&self) -> usize {
+            self.max_upload_bytes
+        }
+
+        async fn transcribe(&self, clip: &AudioClip<'_>) -> TranscriptionResult<String> {
+            *self.calls.borrow_mut() += 1;
+            if let Some(error) = self.failures.borrow_mut().pop() {
+                return Err(error);
+            }
+            Ok(format!(" {} bytes ", clip.bytes.len()))
+        }
+    }
+
+    fn wav_clip(bytes: &[u8]) -> AudioClip<'_> {
+        AudioClip {
+            file_name: "memo.wav",
+            mime_type: "audio/wav",
+            bytes,
+        }
+    }
+
+    #[test]
+    fn config_reads_injected_overrides() {
+        let source = MapConfig::new()
+            .with(ENV_OPENAI_BASE_URL, "https://proxy.example.com/")
+            .with(ENV_OPENAI_TRANSCRIPTION_MODEL, " whisper-1 ");
+        assert_eq!(
+            TranscriptionConfig::resolve(Some("stored-key".to_string()), &source).unwrap(),
+            Some(TranscriptionConfig {
+                api_key: "stored-key".to_string(),
+                base_url: "https://proxy.example.com".to_string(),
+                model: "whisper-1".to_string(),
+            })
+        );
+        assert_eq!(
+            TranscriptionConfig::from_source(&MapConfig::new()).unwrap(),
+            None
+        );
+        let from_env = MapConfig::new().with(ENV_OPENAI_API_KEY, "env-key");
+        assert_eq!(
+            TranscriptionConfig::from_source(&from_env)
+                .unwrap()
+                .unwrap()
+                .model,
+            DEFAULT_MODEL
+        );
+
+        let invalid = MapConfig::new().with(ENV_OPENAI_BASE_URL, "proxy.example.com");
+        assert!(matches!(
+            TranscriptionConfig::resolve(Some("stored-key".to_string()), &invalid),
+            Err(TranscriptionError::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn settings_select_the_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let models = WhisperModelStore::new(dir.path());
+        let source = MapConfig::new();
+        let openai = configured_transcriber(
+            &Settings::default(),
+            Some("stored-key".to_string()),
+            &source,
+            &models,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(openai.provider(), "openai");
+        assert!(
+            configured_transcriber(&Settings::default(), None, &source, &models)
+                .unwrap()
+                .is_none()
+        );
+
+        let whisper = Settings {
+            transcription_provider: TranscriptionProvider::Whisper,
+            whisper_model: Some("tiny".to_string()),
+            ..Settings::default()
+        };
+        assert!(matches!(
+            configured_transcriber(&whisper, None, &source, &models),
+            Err(TranscriptionError::InvalidConfiguration(_))
+        ));
+        assert_eq!(
+            TranscriptionProvider::parse(" Whisper "),
+            Some(TranscriptionProvider::Whisper)
+        );
+        assert_eq!(TranscriptionProvider::OpenAi.as_str(), "openai");
+    }
+
+    #[test]
+    fn split_wav_keeps_every_sample_in_valid_chunks() {
+        let samples = (0..1_000).collect::<Vec<i16>>();
+        let wav = encode_voice_memo_wav(&samples, VoiceMemoOptions::default()).unwrap();
+
+        let chunks = split_wav(&wav, WAV_HEADER_BYTES + 600).unwrap();
+        assert_eq!(chunks.len(), 4);
+        let mut decoded = Vec::new();
+        for chunk in &chunks {
+            assert!(chunk.len() <= WAV_HEADER_BYTES + 600);
+            let mut reader = hound::WavReader::new(Cursor::new(chunk.as_slice())).unwrap();
+            assert_eq!(reader.spec().sample_rate, 16_000);
+            decoded.extend(reader.samples::<i16>().map(std::result::Result::unwrap));
+        }
+        assert_eq!(decoded, samples);
+
+        assert!(split_wav(b"not audio", 1_000).is_err());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn large_wav_is_transcribed_in_chunks_and_joined() {
+        let wav = encode_voice_memo_wav(&[0; 1_000], VoiceMemoOptions::default()).unwrap();
+        let transcriber = ScriptedTranscriber::new(Vec::new(), WAV_HEADER_BYTES + 1_000);
+
+        let text = transcribe_audio(&transcriber, &wav_clip(&wav))
+            .await
+            .unwrap();
+        assert_eq!(text, "1044 bytes 1044 bytes");
+        assert_eq!(*transcriber.calls.borrow(), 2);
+
+        let webm = AudioClip {
+            file_name: "memo.webm",
+            mime_type: "audio/webm",
+            bytes: &wav,
+        };
+        assert!(matches!(
+            transcribe_audio(&transcriber, &webm).await,
+            Err(TranscriptionError::InvalidAudio(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn retries_transient_failures_only() {
+        let transcriber = ScriptedTranscriber::new(
+            vec![
+                TranscriptionError::Api {
+                    status: 503,
+                    message: "busy".to_string(),
+                },
+                TranscriptionError::Request("connection reset".to_string()),
+            ],
+            MAX_TRANSCRIPTION_UPLOAD_BYTES,
+        );
+        let text = transcribe_audio(&transcriber, &wav_clip(&[1, 2, 3]))
+            .await
+            .unwrap();
+        assert_eq!(text, "3 bytes");
+        assert_eq!(*transcriber.calls.borrow(), 3);
+
+        let unauthorized = ScriptedTranscriber::new(vec![TranscriptionError::Unauthorized], 1_000);
+        assert!(matches!(
+            transcribe_audio(&unauthorized, &wav_clip(&[1, 2, 3])).await,
+            Err(TranscriptionError::Unauthorized)
+        ));
+        assert_eq!(*unauthorized.calls.borrow(), 1);
+
+        let empty = ScriptedTranscriber::new(Vec::new(), 1_000);
+        assert!(matches!(
+            transcribe_audio(&empty, &wav_clip(&[])).await,
+            Err(TranscriptionError::InvalidAudio(_))
+        ));
+        assert_eq!(*empty.calls.borrow(), 0);
+    }
+
+    #[test]
+    fn transcript_block_is_appended_after_a_blank_line() {
+        assert_eq!(
+            content_with_voice_memo_transcript(
+                "Existing note body\n",
+                "memo.webm",
+                "  Hello from transcript.  "
+            )
+            .as_deref(),
+            Some(
+                "Existing note body\n\n[Voice memo transcript: memo.webm]\nHello from transcript."
+            )
+        );
+        assert_eq!(
+            content_with_voice_memo_transcript("", " ", "Hi").as_deref(),
+            Some("[Voice memo transcript: voice memo]\nHi")
+        );
+        assert!(content_with_voice_memo_transcript("Body", "memo.webm", "   ").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn transcript_is_saved_into_the_note() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        let note = db.create_note("Standup").await.unwrap();
+
+        let updated = append_voice_memo_transcript(&db, &note.id, "memo.wav", "All good.", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            updated.content,
+            "Standup\n\n[Voice memo transcript: memo.wav]\nAll good."
+        );
+        assert!(
+            append_voice_memo_transcript(&db, &note.id, "memo.wav", " ", None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! `OpenAI` audio transcription (Whisper / `gpt-4o-*-transcribe`).
+
+use std::time::Duration;
+
+use reqwest::{multipart, Client, Request, StatusCode};
+use serde::Deserialize;
+
+use super::{AudioClip, Transcriber, TranscriptionConfig, TranscriptionError, TranscriptionResult};
+use crate::util::compact_text;
+
+/// Long enough for a full-size upload on a slow connection.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Transcribes with the `OpenAI` audio transcriptions API.
+#[derive(Debug, Clone)]
+pub struct OpenAiTranscriber {
+    client: Client,
+    config: TranscriptionConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+impl OpenAiTranscriber {
+    pub fn new(config: TranscriptionConfig) -> TranscriptionResult<Self> {
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|error| {
+                TranscriptionError::InvalidConfiguration(format!(
+                    "Failed to construct HTTP client: {error}"
+                ))
+            })?;
+        Ok(Self { client, config })
+    }
+
+    fn build_request(&self, clip: &AudioClip<'_>) -> TranscriptionResult<Request> {
+        let file_part = multipart::Part::bytes(clip.bytes.to_vec())
+            .file_name(clip.file_name.to_string())
+            .mime_str(clip.mime_type.trim())
+            .map_err(|error| TranscriptionError::InvalidAudio(error.to_string()))?;
+        let form = multipart::Form::new()
+            .text("model", self.config.model.clone())
+            .part("file", file_part);
+
+        self.client
+            .post(format!("{}/v1/audio/transcriptions", self.config.base_url))
+            .bearer_auth(&self.config.api_key)
+            .multipart(form)
+            .build()
+            .map_err(|error| TranscriptionError::Request(error.to_string()))
+    }
+}
+
+impl Transcriber for OpenAiTranscriber {
+    fn provider(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    async fn transcribe(&self, clip: &AudioClip<'_>) -> TranscriptionResult<String> {
+        let request = self.build_request(clip)?;
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .map_err(|error| TranscriptionError::Request(error.to_string()))?;
+        let status = response.status();
+        if status == StatusCode::UNAUTHORIZED {
+            return Err(TranscriptionError::Unauthorized);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(TranscriptionError::Api {
+                status: status.as_u16(),
+                message: compact_text(&body),
+            });
+        }
+
+        let payload: TranscriptionResponse = response
+            .json()
+            .await
+            .map_err(|error| TranscriptionError::Request(error.to_string()))?;
+        Ok(payload.text.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transcriber() -> OpenAiTranscriber {
+        OpenAiTranscriber::new(TranscriptionConfig {
+            api_key: "test-key".to_string(),
+            base_url: "https://api.openai.com".to_string(),
+            model: "gpt-4o-mini-transcribe".to_string(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn request_targets_audio_transcriptions() {
+        let request = transcriber()
+            .build_request(&AudioClip {
+                file_name: "memo.wav",
+                mime_type: "audio/wav",
+                bytes: &[0, 1, 2, 3],
+            })
+            .unwrap();
+
+        assert_eq!(request.method(), reqwest::Method::POST);
+        assert_eq!(
+            request.url().as_str(),
+            "https://api.openai.com/v1/audio/transcriptions"
+        );
+        let auth = request
+            .headers()
+            .get(reqwest::header::AUTHORIZATION)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert_eq!(auth, "Bearer test-key");
+    }
+
+    #[test]
+    fn request_supports_non_wav_audio_mime() {
+        let request = transcriber()
+            .build_request(&AudioClip {
+                file_name: "memo.webm",
+                mime_type: "audio/webm",
+                bytes: &[0, 1, 2, 3],
+            })
+            .unwrap();
+
+        let content_type = request
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(content_type.starts_with("multipart/form-data"));
+    }
+
+    #[test]
+    fn parse_transcription_response() {
+        let payload: TranscriptionResponse =
+            serde_json::from_str(r#"{"text":"hello world"}"#).unwrap();
+        assert_eq!(payload.text, "hello world");
+    }
+}
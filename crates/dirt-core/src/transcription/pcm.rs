@@ -0,0 +1,111 @@
+//! Audio decoding for local speech-to-text models.
+
+use std::io::Cursor;
+
+use super::{TranscriptionError, TranscriptionResult};
+
+/// Sample rate Whisper models expect.
+pub const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+/// Decode WAV audio into mono 32-bit float samples at 16 kHz, the input
+/// Whisper models take.
+///
+/// Channels are averaged and other sample rates are linearly resampled.
+pub fn wav_to_whisper_samples(bytes: &[u8]) -> TranscriptionResult<Vec<f32>> {
+    let mut reader = hound::WavReader::new(Cursor::new(bytes)).map_err(|error| {
+        TranscriptionError::InvalidAudio(format!("Local transcription needs WAV audio: {error}"))
+    })?;
+    let spec = reader.spec();
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<Vec<_>, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = 2f32.powi(i32::from(spec.bits_per_sample) - 1);
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| int_to_f32(sample) / scale))
+                .collect::<Result<Vec<_>, _>>()
+        }
+    }
+    .map_err(|error| TranscriptionError::InvalidAudio(format!("Unreadable WAV audio: {error}")))?;
+
+    let channels = spec.channels.max(1);
+    let mono = samples
+        .chunks(usize::from(channels))
+        .map(|frame| frame.iter().sum::<f32>() / f32::from(channels))
+        .collect::<Vec<_>>();
+    Ok(resample(&mono, spec.sample_rate, WHISPER_SAMPLE_RATE))
+}
+
+fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() || from_rate == 0 {
+        return samples.to_vec();
+    }
+    let step = f64::from(from_rate) / f64::from(to_rate);
+    let last = samples.len() - 1;
+    let mut output = Vec::new();
+    let mut position = 0f64;
+    loop {
+        // Positions are non-negative and bounded by the input length.
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let index = position.floor() as usize;
+        if index > last {
+            break;
+        }
+        let current = samples[index];
+        let following = samples[(index + 1).min(last)];
+        #[allow(clippy::cast_possible_truncation)]
+        let fraction = (position - position.floor()) as f32;
+        output.push(current + (following - current) * fraction);
+        position += step;
+    }
+    output
+}
+
+/// Whisper only needs approximate amplitudes, so rounding the low bits of
+/// 32-bit samples is harmless.
+#[allow(clippy::cast_precision_loss)]
+const fn int_to_f32(value: i32) -> f32 {
+    value as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav(samples: &[i16], sample_rate: u32, channels: u16) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut cursor = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for &sample in samples {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn converts_stereo_int_audio_to_mono_floats() {
+        let samples =
+            wav_to_whisper_samples(&wav(&[16_384, 0, -32_768, -32_768], 16_000, 2)).unwrap();
+        assert_eq!(samples, vec![0.25, -1.0]);
+    }
+
+    #[test]
+    fn resamples_to_sixteen_kilohertz() {
+        let samples = wav_to_whisper_samples(&wav(&[0; 48_000], 48_000, 1)).unwrap();
+        assert_eq!(samples.len(), 16_000);
+
+        let upsampled = resample(&[0.0, 1.0], 8_000, 16_000);
+        assert_eq!(upsampled, vec![0.0, 0.5, 1.0, 1.0]);
+
+        assert!(matches!(
+            wav_to_whisper_samples(b"OggS"),
+            Err(TranscriptionError::InvalidAudio(_))
+        ));
+    }
+}
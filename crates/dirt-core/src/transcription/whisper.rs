@@ -0,0 +1,92 @@
+//! Offline transcription with whisper.cpp.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+use super::pcm::wav_to_whisper_samples;
+use super::{AudioClip, Transcriber, TranscriptionError, TranscriptionResult};
+
+/// Transcribes on this device with a downloaded ggml Whisper model.
+#[derive(Clone)]
+pub struct WhisperTranscriber {
+    context: Arc<WhisperContext>,
+    model: String,
+}
+
+impl std::fmt::Debug for WhisperTranscriber {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter
+            .debug_struct("WhisperTranscriber")
+            .field("model", &self.model)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WhisperTranscriber {
+    /// Load the model `name` from `model_path`.
+    pub fn new(name: &str, model_path: &Path) -> TranscriptionResult<Self> {
+        let path = model_path.to_str().ok_or_else(|| {
+            TranscriptionError::InvalidConfiguration(format!(
+                "Whisper model path is not valid UTF-8: {}",
+                model_path.display()
+            ))
+        })?;
+        let context = WhisperContext::new_with_params(path, WhisperContextParameters::default())
+            .map_err(|error| {
+                TranscriptionError::InvalidConfiguration(format!(
+                    "Failed to load Whisper model {name}: {error}"
+                ))
+            })?;
+        Ok(Self {
+            context: Arc::new(context),
+            model: name.to_string(),
+        })
+    }
+}
+
+impl Transcriber for WhisperTranscriber {
+    fn provider(&self) -> &'static str {
+        "whisper"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Whole recordings are decoded locally, so nothing needs splitting.
+    fn max_upload_bytes(&self) -> usize {
+        usize::MAX
+    }
+
+    async fn transcribe(&self, clip: &AudioClip<'_>) -> TranscriptionResult<String> {
+        let samples = wav_to_whisper_samples(clip.bytes)?;
+        let context = Arc::clone(&self.context);
+        tokio::task::spawn_blocking(move || run_whisper(&context, &samples))
+            .await
+            .map_err(|error| {
+                TranscriptionError::InvalidAudio(format!("Whisper transcription stopped: {error}"))
+            })?
+    }
+}
+
+fn run_whisper(context: &WhisperContext, samples: &[f32]) -> TranscriptionResult<String> {
+    let failed = |error: whisper_rs::WhisperError| {
+        TranscriptionError::InvalidAudio(format!("Whisper transcription failed: {error}"))
+    };
+    let mut state = context.create_state().map_err(failed)?;
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some("auto"));
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_special(false);
+    params.set_print_timestamps(false);
+    state.full(params, samples).map_err(failed)?;
+
+    let mut text = String::new();
+    for segment in 0..state.full_n_segments().map_err(failed)? {
+        text.push_str(&state.full_get_segment_text(segment).map_err(failed)?);
+    }
+    Ok(text.trim().to_string())
+}
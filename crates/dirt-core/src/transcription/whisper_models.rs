@@ -0,0 +1,289 @@
+//! Download and manage ggml Whisper models for offline transcription.
+//!
+//! Models are single `ggml-<name>.bin` files published with whisper.cpp. A
+//! [`WhisperModelStore`] keeps them in one folder; downloads are written to a
+//! `.part` file first so an interrupted download never looks installed.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::{TranscriptionError, TranscriptionResult};
+
+/// Folder, next to the database or data directory, that holds Whisper models
+pub const WHISPER_MODELS_DIR: &str = "whisper-models";
+
+/// Model used when settings do not name one
+pub const DEFAULT_WHISPER_MODEL: &str = "base";
+
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A model that can be downloaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WhisperModel {
+    /// Name as used in settings, e.g. `base.en`
+    pub name: &'static str,
+    /// Approximate download size in MB
+    pub size_mb: u32,
+    /// Whether the model only understands English
+    pub english_only: bool,
+}
+
+/// Models offered for download, smallest first.
+pub const WHISPER_MODELS: &[WhisperModel] = &[
+    WhisperModel {
+        name: "tiny",
+        size_mb: 75,
+        english_only: false,
+    },
+    WhisperModel {
+        name: "tiny.en",
+        size_mb: 75,
+        english_only: true,
+    },
+    WhisperModel {
+        name: "base",
+        size_mb: 142,
+        english_only: false,
+    },
+    WhisperModel {
+        name: "base.en",
+        size_mb: 142,
+        english_only: true,
+    },
+    WhisperModel {
+        name: "small",
+        size_mb: 466,
+        english_only: false,
+    },
+    WhisperModel {
+        name: "small.en",
+        size_mb: 466,
+        english_only: true,
+    },
+    WhisperModel {
+        name: "medium",
+        size_mb: 1_500,
+        english_only: false,
+    },
+    WhisperModel {
+        name: "medium.en",
+        size_mb: 1_500,
+        english_only: true,
+    },
+    WhisperModel {
+        name: "large-v3-turbo",
+        size_mb: 1_620,
+        english_only: false,
+    },
+];
+
+/// Catalog entry for `name` (case-insensitive).
+#[must_use]
+pub fn find_whisper_model(name: &str) -> Option<&'static WhisperModel> {
+    let name = name.trim();
+    WHISPER_MODELS
+        .iter()
+        .find(|model| model.name.eq_ignore_ascii_case(name))
+}
+
+/// A model present in a [`WhisperModelStore`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InstalledWhisperModel {
+    pub name: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+/// Folder of downloaded Whisper models.
+#[derive(Debug, Clone)]
+pub struct WhisperModelStore {
+    dir: PathBuf,
+}
+
+impl WhisperModelStore {
+    /// Models kept in `dir`; the folder is created on the first download.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Models kept in [`WHISPER_MODELS_DIR`] under `parent`.
+    pub fn in_dir(parent: &Path) -> Self {
+        Self::new(parent.join(WHISPER_MODELS_DIR))
+    }
+
+    /// Models kept next to the database file at `db_path`.
+    pub fn beside_database(db_path: &Path) -> Self {
+        Self::in_dir(db_path.parent().unwrap_or_else(|| Path::new(".")))
+    }
+
+    /// Folder the models live in.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the model `name` is (or would be) stored.
+    pub fn model_path(&self, name: &str) -> TranscriptionResult<PathBuf> {
+        let model = known_model(name)?;
+        Ok(self.dir.join(format!("ggml-{}.bin", model.name)))
+    }
+
+    /// Path of `name` when it has been downloaded.
+    pub fn installed_path(&self, name: &str) -> TranscriptionResult<Option<PathBuf>> {
+        let path = self.model_path(name)?;
+        Ok(path.is_file().then_some(path))
+    }
+
+    /// Downloaded catalog models, in catalog order.
+    pub fn installed(&self) -> TranscriptionResult<Vec<InstalledWhisperModel>> {
+        let mut installed = Vec::new();
+        for model in WHISPER_MODELS {
+            let path = self.model_path(model.name)?;
+            match fs::metadata(&path) {
+                Ok(metadata) if metadata.is_file() => installed.push(InstalledWhisperModel {
+                    name: model.name.to_string(),
+                    path,
+                    size_bytes: metadata.len(),
+                }),
+                Ok(_) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+        Ok(installed)
+    }
+
+    /// Download `name`, calling `on_progress` with the bytes received and the
+    /// total when the server reports it. Returns the installed model path.
+    pub async fn download(
+        &self,
+        name: &str,
+        mut on_progress: impl FnMut(u64, Option<u64>),
+    ) -> TranscriptionResult<PathBuf> {
+        let path = self.model_path(name)?;
+        let model = known_model(name)?;
+        fs::create_dir_all(&self.dir)?;
+
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(|error| TranscriptionError::Request(error.to_string()))?;
+        let mut response = client
+            .get(model_url(model.name))
+            .send()
+            .await
+            .map_err(|error| TranscriptionError::Request(error.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(TranscriptionError::Api {
+                status: status.as_u16(),
+                message: format!("Failed to download Whisper model {}", model.name),
+            });
+        }
+
+        let total = response.content_length();
+        let partial = path.with_extension("bin.part");
+        let mut file = File::create(&partial)?;
+        let mut received = 0u64;
+        on_progress(received, total);
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|error| TranscriptionError::Request(error.to_string()))?
+        {
+            file.write_all(&chunk)?;
+            received += u64::try_from(chunk.len()).unwrap_or(u64::MAX);
+            on_progress(received, total);
+        }
+        file.sync_all()?;
+        drop(file);
+
+        if total.is_some_and(|total| total != received) {
+            fs::remove_file(&partial)?;
+            return Err(TranscriptionError::Request(format!(
+                "Download of Whisper model {} ended early",
+                model.name
+            )));
+        }
+        fs::rename(&partial, &path)?;
+        Ok(path)
+    }
+
+    /// Delete the downloaded model `name`; returns whether it was present.
+    pub fn remove(&self, name: &str) -> TranscriptionResult<bool> {
+        match fs::remove_file(self.model_path(name)?) {
+            Ok(()) => Ok(true),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+fn known_model(name: &str) -> TranscriptionResult<&'static WhisperModel> {
+    find_whisper_model(name).ok_or_else(|| {
+        let available = WHISPER_MODELS
+            .iter()
+            .map(|model| model.name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        TranscriptionError::InvalidConfiguration(format!(
+            "Unknown Whisper model '{}' (available: {available})",
+            name.trim()
+        ))
+    })
+}
+
+fn model_url(name: &str) -> String {
+    format!("{MODEL_BASE_URL}/ggml-{name}.bin")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_lookup_and_paths() {
+        assert_eq!(find_whisper_model(" Base.EN ").unwrap().name, "base.en");
+        assert!(find_whisper_model(DEFAULT_WHISPER_MODEL).is_some());
+        assert!(find_whisper_model("huge").is_none());
+        assert_eq!(
+            model_url("tiny"),
+            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin"
+        );
+
+        let store = WhisperModelStore::beside_database(Path::new("/data/dirt.db"));
+        assert_eq!(
+            store.model_path("small").unwrap(),
+            Path::new("/data/whisper-models/ggml-small.bin")
+        );
+        assert!(matches!(
+            store.model_path("../secrets"),
+            Err(TranscriptionError::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn lists_and_removes_installed_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = WhisperModelStore::new(dir.path());
+        assert!(store.installed().unwrap().is_empty());
+        assert_eq!(store.installed_path("tiny").unwrap(), None);
+
+        fs::write(store.model_path("tiny").unwrap(), b"ggml").unwrap();
+        fs::write(dir.path().join("ggml-tiny.bin.part"), b"gg").unwrap();
+        let installed = store.installed().unwrap();
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].name, "tiny");
+        assert_eq!(installed[0].size_bytes, 4);
+        assert!(store.installed_path("tiny").unwrap().is_some());
+
+        assert!(store.remove("tiny").unwrap());
+        assert!(!store.remove("tiny").unwrap());
+        assert!(store.installed().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+//! Device-to-device note hand-off
+//!
+//! A note is packed into a `dirt://note/<id>?content=<text>` link that the
+//! desktop renders as a QR code and the mobile app opens after a scan. Short
+//! notes carry their content so the receiver does not have to wait for a
+//! sync cycle; notes too long for a scannable code are sent by id only, which
+//! works once both devices share a synced account.
+
+use std::str::FromStr;
+
+use crate::models::{Note, NoteId, NoteSource};
+use crate::services::DatabaseService;
+use crate::{Error, Result};
+
+/// Scheme and host prefix of note transfer links.
+pub const NOTE_TRANSFER_URI_PREFIX: &str = "dirt://note/";
+
+/// Longest link packed into a QR code; larger codes get hard to scan from a
+/// screen at medium error correction.
+pub const MAX_TRANSFER_URI_BYTES: usize = 1_200;
+
+const CONTENT_PARAM: &str = "content";
+
+/// A note (or a reference to it) travelling between devices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteTransfer {
+    pub note_id: NoteId,
+    /// Full note text, absent when the receiver should find the note by id.
+    pub content: Option<String>,
+}
+
+/// What receiving a transfer did on this device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferReceipt {
+    /// The note already existed locally and was left untouched.
+    Opened(Note),
+    /// The note was created from the transferred content.
+    Imported(Note),
+}
+
+impl TransferReceipt {
+    /// The note to show after receiving.
+    #[must_use]
+    pub const fn note(&self) -> &Note {
+        match self {
+            Self::Opened(note) | Self::Imported(note) => note,
+        }
+    }
+}
+
+impl NoteTransfer {
+    /// Build the transfer for a note, embedding its content when it fits.
+    ///
+    /// Content that would overflow [`MAX_TRANSFER_URI_BYTES`] falls back to an
+    /// id-only transfer for synced accounts and is rejected for local-only
+    /// users, whose other device has no way to look the note up.
+    pub fn for_note(note_id: NoteId, content: &str, synced: bool) -> Result<Self> {
+        let with_content = Self {
+            note_id,
+            content: Some(content.to_string()),
+        };
+        let uri_len = with_content.to_uri().len();
+        if uri_len <= MAX_TRANSFER_URI_BYTES {
+            return Ok(with_content);
+        }
+        if synced {
+            return Ok(Self {
+                note_id,
+                content: None,
+            });
+        }
+        Err(Error::InvalidInput(format!(
+            "Note is too long for a QR code ({uri_len} of {MAX_TRANSFER_URI_BYTES} bytes); \
+             sign in to sync so it can be sent by id"
+        )))
+    }
+
+    /// Encode the transfer as a `dirt://note/...` link.
+    #[must_use]
+    pub fn to_uri(&self) -> String {
+        let mut uri = format!("{NOTE_TRANSFER_URI_PREFIX}{}", self.note_id);
+        if let Some(content) = &self.content {
+            uri.push('?');
+            uri.push_str(CONTENT_PARAM);
+            uri.push('=');
+            uri.push_str(&urlencoding::encode(content));
+        }
+        uri
+    }
+}
+
+impl FromStr for NoteTransfer {
+    type Err = Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        let rest = raw
+            .get(..NOTE_TRANSFER_URI_PREFIX.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(NOTE_TRANSFER_URI_PREFIX))
+            .map(|_| &raw[NOTE_TRANSFER_URI_PREFIX.len()..])
+            .ok_or_else(|| Error::InvalidInput(format!("Not a Dirt note link: {raw}")))?;
+
+        let (id, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let note_id = NoteId::from_str(id.trim_end_matches('/'))
+            .map_err(|error| Error::InvalidInput(format!("Invalid note id in link: {error}")))?;
+
+        let mut content = None;
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            if key == CONTENT_PARAM {
+                let decoded = urlencoding::decode(value).map_err(|error| {
+                    Error::InvalidInput(format!("Invalid note content in link: {error}"))
+                })?;
+                content = Some(decoded.into_owned());
+            }
+        }
+
+        Ok(Self { note_id, content })
+    }
+}
+
+/// Whether `text` looks like a note transfer link.
+#[must_use]
+pub fn is_note_transfer_uri(text: &str) -> bool {
+    text.trim()
+        .get(..NOTE_TRANSFER_URI_PREFIX.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(NOTE_TRANSFER_URI_PREFIX))
+}
+
+/// Open or import a received note.
+///
+/// A note already on this device wins over the transferred copy, since it may
+/// carry newer edits. Otherwise the note is created with its original id so a
+/// later sync merges the two copies instead of duplicating them.
+pub async fn receive_note_transfer(
+    db: &DatabaseService,
+    transfer: &NoteTransfer,
+) -> Result<TransferReceipt> {
+    if let Some(note) = db.get_note(&transfer.note_id).await? {
+        return Ok(TransferReceipt::Opened(note));
+    }
+
+    let Some(content) = transfer.content.as_deref() else {
+        return Err(Error::NotFound(format!(
+            "Note {} is not on this device yet; sync and scan again",
+            transfer.note_id
+        )));
+    };
+    let note = Note {
+        id: transfer.note_id,
+        ..Note::new(content)
+    };
+    let created = db
+        .create_note_with_id_from(&note, NoteSource::Import)
+        .await?;
+    Ok(TransferReceipt::Imported(created))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_uri_round_trips_content() {
+        let note_id = NoteId::new();
+        let transfer =
+            NoteTransfer::for_note(note_id, "Buy milk & eggs?\n#errands 100%", false).unwrap();
+
+        let uri = transfer.to_uri();
+        assert!(uri.starts_with(NOTE_TRANSFER_URI_PREFIX));
+        assert!(!uri.contains('\n'));
+        assert!(is_note_transfer_uri(&uri));
+
+        let parsed: NoteTransfer = uri.parse().unwrap();
+        assert_eq!(parsed, transfer);
+        assert_eq!(
+            parsed.content.as_deref(),
+            Some("Buy milk & eggs?\n#errands 100%")
+        );
+    }
+
+    #[test]
+    fn long_notes_fall_back_to_id_only_when_synced() {
+        let note_id = NoteId::new();
+        let long = "x".repeat(MAX_TRANSFER_URI_BYTES);
+
+        let synced = NoteTransfer::for_note(note_id, &long, true).unwrap();
+        assert_eq!(synced.content, None);
+        assert_eq!(synced.to_uri(), format!("dirt://note/{note_id}"));
+
+        assert!(NoteTransfer::for_note(note_id, &long, false).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_foreign_links_and_bad_ids() {
+        assert!("https://example.com/note/1"
+            .parse::<NoteTransfer>()
+            .is_err());
+        assert!("dirt://note/not-a-uuid".parse::<NoteTransfer>().is_err());
+        assert!(!is_note_transfer_uri("just some text"));
+
+        let note_id = NoteId::new();
+        let parsed: NoteTransfer = format!(" DIRT://note/{note_id}/ ").parse().unwrap();
+        assert_eq!(parsed.note_id, note_id);
+        assert_eq!(parsed.content, None);
+    }
+
+    #[tokio::test]
+    async fn receive_imports_new_notes_and_opens_existing_ones() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        let transfer = NoteTransfer::for_note(NoteId::new(), "From the desktop", false).unwrap();
+
+        let imported = receive_note_transfer(&db, &transfer).await.unwrap();
+        assert!(matches!(imported, TransferReceipt::Imported(_)));
+        assert_eq!(imported.note().id, transfer.note_id);
+        assert_eq!(
+            db.note_source(&transfer.note_id).await.unwrap(),
+            Some(NoteSource::Import)
+        );
+
+        db.update_note(&transfer.note_id, "Edited here")
+            .await
+            .unwrap();
+        let opened = receive_note_transfer(&db, &transfer).await.unwrap();
+        assert!(matches!(opened, TransferReceipt::Opened(_)));
+        assert_eq!(opened.note().content, "Edited here");
+
+        let missing = NoteTransfer {
+            note_id: NoteId::new(),
+            content: None,
+        };
+        assert!(receive_note_transfer(&db, &missing).await.is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! `DeepL` REST translator.
+
+use reqwest::{Client, Request};
+use serde::{Deserialize, Serialize};
+
+use super::{http_client, response_error, TranslationConfig, TranslationProvider, Translator};
+use crate::{Error, Result};
+
+/// Translates with the `DeepL` `/v2/translate` API.
+#[derive(Debug, Clone)]
+pub struct DeepLTranslator {
+    client: Client,
+    config: TranslationConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct TranslateRequest<'a> {
+    text: [&'a str; 1],
+    target_lang: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranslateResponse {
+    translations: Vec<Translation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Translation {
+    text: String,
+}
+
+impl DeepLTranslator {
+    pub fn new(config: TranslationConfig) -> Result<Self> {
+        Ok(Self {
+            client: http_client()?,
+            config,
+        })
+    }
+
+    fn build_request(&self, text: &str, language: &str) -> Result<Request> {
+        let body = TranslateRequest {
+            text: [text],
+            target_lang: target_lang(language),
+        };
+        self.client
+            .post(format!("{}/v2/translate", self.config.base_url))
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("DeepL-Auth-Key {}", self.config.api_key),
+            )
+            .json(&body)
+            .build()
+            .map_err(|error| Error::Storage(error.to_string()))
+    }
+}
+
+impl Translator for DeepLTranslator {
+    fn provider(&self) -> TranslationProvider {
+        TranslationProvider::DeepL
+    }
+
+    async fn translate(&self, text: &str, language: &str) -> Result<String> {
+        let request = self.build_request(text, language)?;
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .map_err(|error| Error::Storage(error.to_string()))?;
+        if !response.status().is_success() {
+            return Err(response_error(self.provider(), response).await);
+        }
+
+        let payload: TranslateResponse = response
+            .json()
+            .await
+            .map_err(|error| Error::Storage(error.to_string()))?;
+        payload
+            .translations
+            .into_iter()
+            .next()
+            .map(|translation| translation.text)
+            .ok_or_else(|| Error::Storage("DeepL returned no translation".to_string()))
+    }
+}
+
+/// `DeepL` language codes are upper case: `ja` becomes `JA`, `pt-BR` `PT-BR`.
+fn target_lang(language: &str) -> String {
+    language.to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_uses_deepl_auth_and_upper_case_language() {
+        let translator = DeepLTranslator::new(TranslationConfig {
+            provider: TranslationProvider::DeepL,
+            api_key: "secret:fx".to_string(),
+            base_url: "https://api-free.deepl.com".to_string(),
+            model: String::new(),
+        })
+        .unwrap();
+        let request = translator.build_request("Hello", "pt-BR").unwrap();
+
+        assert_eq!(
+            request.url().as_str(),
+            "https://api-free.deepl.com/v2/translate"
+        );
+        assert_eq!(
+            request.headers()[reqwest::header::AUTHORIZATION],
+            "DeepL-Auth-Key secret:fx"
+        );
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body["target_lang"], "PT-BR");
+        assert_eq!(body["text"][0], "Hello");
+    }
+
+    #[test]
+    fn parse_translate_response() {
+        let payload: TranslateResponse = serde_json::from_str(
+            r#"{"translations":[{"detected_source_language":"EN","text":"Olá"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(payload.translations[0].text, "Olá");
+    }
+}
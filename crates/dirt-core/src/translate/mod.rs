@@ -0,0 +1,546 @@
+//! Note translation through external providers.
+//!
+//! A [`Translator`] turns note text into another language. Providers are
+//! compiled in with the `translate-openai` and `translate-deepl` features and
+//! picked at runtime from [`TranslationConfig`]. [`translate_note`] stores the
+//! result either as a new note linking back to the original or as a
+//! `## Translation (<lang>)` section appended to it.
+
+#[cfg(feature = "translate-deepl")]
+mod deepl;
+#[cfg(feature = "translate-openai")]
+mod openai;
+
+#[cfg(feature = "translate-deepl")]
+pub use deepl::DeepLTranslator;
+#[cfg(feature = "translate-openai")]
+pub use openai::OpenAiTranslator;
+
+use std::fmt;
+
+use crate::config::{ConfigSource, EnvConfig};
+use crate::models::{Note, NoteSource};
+use crate::seal::sealed_until;
+use crate::services::DatabaseService;
+use crate::util::is_http_url;
+use crate::{Error, Result};
+
+const ENV_PROVIDER: &str = "DIRT_TRANSLATION_PROVIDER";
+const ENV_OPENAI_API_KEY: &str = "OPENAI_API_KEY";
+const ENV_OPENAI_BASE_URL: &str = "OPENAI_BASE_URL";
+const ENV_OPENAI_MODEL: &str = "OPENAI_TRANSLATION_MODEL";
+const ENV_DEEPL_API_KEY: &str = "DEEPL_API_KEY";
+const ENV_DEEPL_BASE_URL: &str = "DEEPL_BASE_URL";
+
+const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com";
+const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini";
+const DEEPL_BASE_URL: &str = "https://api.deepl.com";
+const DEEPL_FREE_BASE_URL: &str = "https://api-free.deepl.com";
+/// `DeepL` API keys for the free plan end with this suffix.
+const DEEPL_FREE_KEY_SUFFIX: &str = ":fx";
+
+#[cfg(any(feature = "translate-openai", feature = "translate-deepl"))]
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+const SECTION_HEADING_PREFIX: &str = "## Translation (";
+
+/// Translates text into a target language.
+#[allow(async_fn_in_trait)]
+pub trait Translator {
+    /// Provider used for the translation.
+    fn provider(&self) -> TranslationProvider;
+
+    /// Translate `text` into `language`, a normalized tag such as `ja` or
+    /// `pt-BR` (see [`normalize_language`]).
+    async fn translate(&self, text: &str, language: &str) -> Result<String>;
+}
+
+/// Translation service behind a [`Translator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranslationProvider {
+    OpenAi,
+    DeepL,
+}
+
+impl TranslationProvider {
+    /// Stable identifier, as accepted by `DIRT_TRANSLATION_PROVIDER`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::DeepL => "deepl",
+        }
+    }
+
+    /// Name shown to users.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::OpenAi => "OpenAI",
+            Self::DeepL => "DeepL",
+        }
+    }
+
+    /// Whether this build includes the provider.
+    #[must_use]
+    pub const fn is_enabled(self) -> bool {
+        match self {
+            Self::OpenAi => cfg!(feature = "translate-openai"),
+            Self::DeepL => cfg!(feature = "translate-deepl"),
+        }
+    }
+
+    /// Parse a provider identifier (case-insensitive).
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "openai" => Some(Self::OpenAi),
+            "deepl" => Some(Self::DeepL),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TranslationProvider {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.label())
+    }
+}
+
+/// Provider settings resolved from the environment.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TranslationConfig {
+    pub provider: TranslationProvider,
+    pub api_key: String,
+    /// API origin without a trailing slash.
+    pub base_url: String,
+    /// Model name; only used by `OpenAI`.
+    pub model: String,
+}
+
+impl fmt::Debug for TranslationConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TranslationConfig")
+            .field("provider", &self.provider)
+            .field("api_key", &"[REDACTED]")
+            .field("base_url", &self.base_url)
+            .field("model", &self.model)
+            .finish()
+    }
+}
+
+impl TranslationConfig {
+    /// Load translation settings from environment variables.
+    ///
+    /// Returns `Ok(None)` when no provider key is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        Self::resolve(None, &EnvConfig)
+    }
+
+    /// Load translation settings from `source`, with the same rules as
+    /// [`Self::from_env`].
+    pub fn from_source(source: &impl ConfigSource) -> Result<Option<Self>> {
+        Self::resolve(None, source)
+    }
+
+    /// Resolve settings from `source`, preferring `stored_openai_key` (e.g.
+    /// from secure storage) over `OPENAI_API_KEY`.
+    ///
+    /// `DIRT_TRANSLATION_PROVIDER` picks the provider; without it, `OpenAI`
+    /// is used when it has a key and `DeepL` otherwise.
+    pub fn resolve(
+        stored_openai_key: Option<String>,
+        source: &impl ConfigSource,
+    ) -> Result<Option<Self>> {
+        let openai_key = stored_openai_key.or_else(|| source.text_var(ENV_OPENAI_API_KEY));
+        let deepl_key = source.text_var(ENV_DEEPL_API_KEY);
+
+        let provider = match source.text_var(ENV_PROVIDER) {
+            Some(value) => TranslationProvider::parse(&value).ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "{ENV_PROVIDER} must be 'openai' or 'deepl', got '{value}'"
+                ))
+            })?,
+            None if openai_key.is_some() => TranslationProvider::OpenAi,
+            None if deepl_key.is_some() => TranslationProvider::DeepL,
+            None => return Ok(None),
+        };
+
+        let config = match provider {
+            TranslationProvider::OpenAi => Self {
+                provider,
+                api_key: openai_key.ok_or_else(|| missing_key(ENV_OPENAI_API_KEY, provider))?,
+                base_url: base_url_var(source, ENV_OPENAI_BASE_URL)?
+                    .unwrap_or_else(|| DEFAULT_OPENAI_BASE_URL.to_string()),
+                model: source
+                    .text_var(ENV_OPENAI_MODEL)
+                    .unwrap_or_else(|| DEFAULT_OPENAI_MODEL.to_string()),
+            },
+            TranslationProvider::DeepL => {
+                let api_key = deepl_key.ok_or_else(|| missing_key(ENV_DEEPL_API_KEY, provider))?;
+                let default_base_url = if api_key.ends_with(DEEPL_FREE_KEY_SUFFIX) {
+                    DEEPL_FREE_BASE_URL
+                } else {
+                    DEEPL_BASE_URL
+                };
+                Self {
+                    provider,
+                    base_url: base_url_var(source, ENV_DEEPL_BASE_URL)?
+                        .unwrap_or_else(|| default_base_url.to_string()),
+                    api_key,
+                    model: String::new(),
+                }
+            }
+        };
+        Ok(Some(config))
+    }
+}
+
+fn missing_key(variable: &str, provider: TranslationProvider) -> Error {
+    Error::InvalidInput(format!("{variable} is required for {provider} translation"))
+}
+
+fn base_url_var(source: &impl ConfigSource, key: &str) -> Result<Option<String>> {
+    let Some(value) = source.text_var(key) else {
+        return Ok(None);
+    };
+    if !is_http_url(&value) {
+        return Err(Error::InvalidInput(format!(
+            "{key} must start with http:// or https://"
+        )));
+    }
+    Ok(Some(value.trim_end_matches('/').to_string()))
+}
+
+/// Where [`translate_note`] stores a translation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TranslationOutput {
+    /// A new note that links back to the original.
+    #[default]
+    LinkedNote,
+    /// A `## Translation (<lang>)` section at the end of the original note,
+    /// replacing an earlier translation into the same language.
+    AppendSection,
+}
+
+/// Normalize a language tag: `JA` becomes `ja`, `pt_br` becomes `pt-BR`.
+///
+/// Returns `None` unless `raw` looks like a BCP 47 tag (a two- or
+/// three-letter language, optionally followed by a script or region).
+#[must_use]
+pub fn normalize_language(raw: &str) -> Option<String> {
+    let raw = raw.trim().replace('_', "-");
+    let mut parts = raw.split('-');
+    let language = parts.next()?;
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|ch| ch.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let mut normalized = language.to_ascii_lowercase();
+    for part in parts {
+        if !(2..=4).contains(&part.len()) || !part.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+            return None;
+        }
+        normalized.push('-');
+        if part.len() == 4 {
+            // Script subtag, e.g. `Hant`.
+            normalized.push_str(&part[..1].to_ascii_uppercase());
+            normalized.push_str(&part[1..].to_ascii_lowercase());
+        } else {
+            normalized.push_str(&part.to_ascii_uppercase());
+        }
+    }
+    Some(normalized)
+}
+
+/// Heading of the section holding a translation into `language`.
+#[must_use]
+pub fn translation_section_heading(language: &str) -> String {
+    format!("{SECTION_HEADING_PREFIX}{language})")
+}
+
+/// Text of `content` that should be translated: everything before the first
+/// translation section.
+#[must_use]
+pub fn translatable_text(content: &str) -> &str {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if line.trim_end().starts_with(SECTION_HEADING_PREFIX) {
+            return content[..offset].trim_end();
+        }
+        offset += line.len();
+    }
+    content.trim_end()
+}
+
+/// `content` with `translation` stored in its `language` section.
+///
+/// An existing section for the same language is replaced in place; other
+/// translation sections are kept.
+#[must_use]
+pub fn content_with_translation_section(
+    content: &str,
+    language: &str,
+    translation: &str,
+) -> String {
+    let heading = translation_section_heading(language);
+    let section = format!("{heading}\n\n{}", translation.trim());
+
+    let lines = content.lines().collect::<Vec<_>>();
+    let Some(start) = lines.iter().position(|line| line.trim_end() == heading) else {
+        return format!("{}\n\n{section}", content.trim_end());
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.trim_end().starts_with(SECTION_HEADING_PREFIX))
+        .map_or(lines.len(), |index| start + 1 + index);
+
+    let before = lines[..start].join("\n");
+    let after = lines[end..].join("\n");
+    let mut updated = before.trim_end().to_string();
+    if !updated.is_empty() {
+        updated.push_str("\n\n");
+    }
+    updated.push_str(&section);
+    if !after.is_empty() {
+        updated.push_str("\n\n");
+        updated.push_str(&after);
+    }
+    updated
+}
+
+/// Content of a translation note for `original`.
+#[must_use]
+pub fn translation_note_content(original: &Note, language: &str, translation: &str) -> String {
+    format!(
+        "{}\n\n---\nTranslated ({language}) from [[{}]]",
+        translation.trim(),
+        original.id
+    )
+}
+
+/// Translate `note` into `language` and store the result as `output` says.
+///
+/// Returns the note holding the translation: the new linked note, or the
+/// updated original. Sealed notes are refused.
+pub async fn translate_note<T: Translator>(
+    db: &DatabaseService,
+    translator: &T,
+    note: &Note,
+    language: &str,
+    output: TranslationOutput,
+    source: NoteSource,
+) -> Result<Note> {
+    let language = normalize_language(language)
+        .ok_or_else(|| Error::InvalidInput(format!("'{language}' is not a language code")))?;
+    if sealed_until(&note.content).is_some() {
+        return Err(Error::InvalidInput(
+            "Sealed notes cannot be translated".to_string(),
+        ));
+    }
+    let text = translatable_text(&note.content);
+    if text.trim().is_empty() {
+        return Err(Error::InvalidInput(
+            "Note has no text to translate".to_string(),
+        ));
+    }
+
+    let translation = translator.translate(text, &language).await?;
+    if translation.trim().is_empty() {
+        return Err(Error::Storage(format!(
+            "{} returned an empty translation",
+            translator.provider()
+        )));
+    }
+
+    match output {
+        TranslationOutput::LinkedNote => {
+            let content = translation_note_content(note, &language, &translation);
+            db.create_note_from(&content, source).await
+        }
+        TranslationOutput::AppendSection => {
+            let content = content_with_translation_section(&note.content, &language, &translation);
+            db.update_note(&note.id, &content).await
+        }
+    }
+}
+
+/// [`translate_note`] with the provider picked by `config`.
+///
+/// Fails when this build does not include the configured provider.
+#[cfg_attr(
+    not(any(feature = "translate-openai", feature = "translate-deepl")),
+    allow(unused_variables, clippy::unused_async)
+)]
+pub async fn translate_note_with_config(
+    db: &DatabaseService,
+    config: &TranslationConfig,
+    note: &Note,
+    language: &str,
+    output: TranslationOutput,
+    source: NoteSource,
+) -> Result<Note> {
+    match config.provider {
+        #[cfg(feature = "translate-openai")]
+        TranslationProvider::OpenAi => {
+            let translator = OpenAiTranslator::new(config.clone())?;
+            translate_note(db, &translator, note, language, output, source).await
+        }
+        #[cfg(feature = "translate-deepl")]
+        TranslationProvider::DeepL => {
+            let translator = DeepLTranslator::new(config.clone())?;
+            translate_note(db, &translator, note, language, output, source).await
+        }
+        #[allow(unreachable_patterns)]
+        provider => Err(Error::InvalidInput(format!(
+            "{provider} translation is not enabled in this build"
+        ))),
+    }
+}
+
+#[cfg(any(feature = "translate-openai", feature = "translate-deepl"))]
+fn http_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|error| Error::Storage(format!("Failed to construct HTTP client: {error}")))
+}
+
+#[cfg(any(feature = "translate-openai", feature = "translate-deepl"))]
+async fn response_error(provider: TranslationProvider, response: reqwest::Response) -> Error {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    Error::Storage(format!(
+        "{provider} translation failed with {status}: {}",
+        crate::util::compact_text(&body)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::MapConfig;
+
+    use super::*;
+
+    struct UppercaseTranslator;
+
+    impl Translator for UppercaseTranslator {
+        fn provider(&self) -> TranslationProvider {
+            TranslationProvider::OpenAi
+        }
+
+        async fn translate(&self, text: &str, language: &str) -> Result<String> {
+            Ok(format!("[{language}] {}", text.to_uppercase()))
+        }
+    }
+
+    #[test]
+    fn normalize_language_accepts_bcp47_tags() {
+        assert_eq!(normalize_language(" JA ").as_deref(), Some("ja"));
+        assert_eq!(normalize_language("pt_br").as_deref(), Some("pt-BR"));
+        assert_eq!(normalize_language("zh-hant").as_deref(), Some("zh-Hant"));
+        assert_eq!(normalize_language("Japanese"), None);
+        assert_eq!(normalize_language("e"), None);
+        assert_eq!(normalize_language("en-"), None);
+    }
+
+    #[test]
+    fn config_picks_provider_from_available_keys() {
+        assert_eq!(
+            TranslationConfig::from_source(&MapConfig::new()).unwrap(),
+            None
+        );
+
+        let deepl =
+            TranslationConfig::from_source(&MapConfig::new().with(ENV_DEEPL_API_KEY, "secret:fx"))
+                .unwrap()
+                .unwrap();
+        assert_eq!(deepl.provider, TranslationProvider::DeepL);
+        assert_eq!(deepl.base_url, DEEPL_FREE_BASE_URL);
+
+        let source = MapConfig::new()
+            .with(ENV_DEEPL_API_KEY, "secret")
+            .with(ENV_OPENAI_BASE_URL, "https://proxy.example.com/");
+        let openai = TranslationConfig::resolve(Some("stored".to_string()), &source)
+            .unwrap()
+            .unwrap();
+        assert_eq!(openai.provider, TranslationProvider::OpenAi);
+        assert_eq!(openai.api_key, "stored");
+        assert_eq!(openai.base_url, "https://proxy.example.com");
+        assert_eq!(openai.model, DEFAULT_OPENAI_MODEL);
+
+        let missing_key = MapConfig::new().with(ENV_PROVIDER, "deepl");
+        assert!(matches!(
+            TranslationConfig::from_source(&missing_key),
+            Err(Error::InvalidInput(message)) if message.contains(ENV_DEEPL_API_KEY)
+        ));
+    }
+
+    #[test]
+    fn translation_sections_replace_same_language() {
+        let content = "Good morning";
+        let with_ja = content_with_translation_section(content, "ja", "おはよう");
+        assert_eq!(with_ja, "Good morning\n\n## Translation (ja)\n\nおはよう");
+        assert_eq!(translatable_text(&with_ja), "Good morning");
+
+        let with_fr = content_with_translation_section(&with_ja, "fr", "Bonjour");
+        let updated = content_with_translation_section(&with_fr, "ja", "おはようございます");
+        assert_eq!(
+            updated,
+            "Good morning\n\n## Translation (ja)\n\nおはようございます\n\n## Translation (fr)\n\nBonjour"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn translate_note_creates_linked_note_or_appends_section() {
+        let db = DatabaseService::open_in_memory().await.unwrap();
+        let note = db.create_note("Buy milk #errands").await.unwrap();
+
+        let linked = translate_note(
+            &db,
+            &UppercaseTranslator,
+            &note,
+            "JA",
+            TranslationOutput::LinkedNote,
+            NoteSource::Cli,
+        )
+        .await
+        .unwrap();
+        assert_ne!(linked.id, note.id);
+        assert_eq!(
+            linked.content,
+            format!(
+                "[ja] BUY MILK #ERRANDS\n\n---\nTranslated (ja) from [[{}]]",
+                note.id
+            )
+        );
+
+        let appended = translate_note(
+            &db,
+            &UppercaseTranslator,
+            &note,
+            "ja",
+            TranslationOutput::AppendSection,
+            NoteSource::Cli,
+        )
+        .await
+        .unwrap();
+        assert_eq!(appended.id, note.id);
+        assert_eq!(
+            appended.content,
+            "Buy milk #errands\n\n## Translation (ja)\n\n[ja] BUY MILK #ERRANDS"
+        );
+
+        let err = translate_note(
+            &db,
+            &UppercaseTranslator,
+            &note,
+            "klingon!",
+            TranslationOutput::LinkedNote,
+            NoteSource::Cli,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)));
+    }
+}
@@ -0,0 +1,155 @@
+//! `OpenAI` chat-completions translator.
+
+use reqwest::{Client, Request};
+use serde::{Deserialize, Serialize};
+
+use super::{http_client, response_error, TranslationConfig, TranslationProvider, Translator};
+use crate::{Error, Result};
+
+/// Translates with the `OpenAI` chat completions API.
+#[derive(Debug, Clone)]
+pub struct OpenAiTranslator {
+    client: Client,
+    config: TranslationConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    temperature: f32,
+    messages: [ChatMessage<'a>; 2],
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    content: Option<String>,
+}
+
+impl OpenAiTranslator {
+    pub fn new(config: TranslationConfig) -> Result<Self> {
+        Ok(Self {
+            client: http_client()?,
+            config,
+        })
+    }
+
+    fn build_request(&self, text: &str, language: &str) -> Result<Request> {
+        let instructions = system_prompt(language);
+        let body = ChatRequest {
+            model: &self.config.model,
+            temperature: 0.0,
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: &instructions,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: text,
+                },
+            ],
+        };
+        self.client
+            .post(format!("{}/v1/chat/completions", self.config.base_url))
+            .bearer_auth(&self.config.api_key)
+            .json(&body)
+            .build()
+            .map_err(|error| Error::Storage(error.to_string()))
+    }
+}
+
+impl Translator for OpenAiTranslator {
+    fn provider(&self) -> TranslationProvider {
+        TranslationProvider::OpenAi
+    }
+
+    async fn translate(&self, text: &str, language: &str) -> Result<String> {
+        let request = self.build_request(text, language)?;
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .map_err(|error| Error::Storage(error.to_string()))?;
+        if !response.status().is_success() {
+            return Err(response_error(self.provider(), response).await);
+        }
+
+        let payload: ChatResponse = response
+            .json()
+            .await
+            .map_err(|error| Error::Storage(error.to_string()))?;
+        payload
+            .choices
+            .into_iter()
+            .find_map(|choice| choice.message.content)
+            .map(|content| content.trim().to_string())
+            .ok_or_else(|| Error::Storage("OpenAI returned no translation".to_string()))
+    }
+}
+
+fn system_prompt(language: &str) -> String {
+    format!(
+        "Translate the user's note into the language with BCP 47 tag '{language}'. \
+         Keep Markdown formatting, #tags, [[links]], and URLs unchanged. \
+         Reply with the translation only."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_targets_chat_completions() {
+        let translator = OpenAiTranslator::new(TranslationConfig {
+            provider: TranslationProvider::OpenAi,
+            api_key: "test-key".to_string(),
+            base_url: "https://api.openai.com".to_string(),
+            model: "gpt-4o-mini".to_string(),
+        })
+        .unwrap();
+        let request = translator.build_request("Hello", "ja").unwrap();
+
+        assert_eq!(request.method(), reqwest::Method::POST);
+        assert_eq!(
+            request.url().as_str(),
+            "https://api.openai.com/v1/chat/completions"
+        );
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body["model"], "gpt-4o-mini");
+        assert_eq!(body["messages"][1]["content"], "Hello");
+        assert!(body["messages"][0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("'ja'"));
+    }
+
+    #[test]
+    fn parse_chat_response() {
+        let payload: ChatResponse = serde_json::from_str(
+            r#"{"choices":[{"message":{"role":"assistant","content":"こんにちは"}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            payload.choices[0].message.content.as_deref(),
+            Some("こんにちは")
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Hand notes off to the user's external editor (`$VISUAL` / `$EDITOR`).
+//!
+//! The CLI blocks on [`launch_editor`]; the desktop app spawns the editor with
+//! [`spawn_editor`], watches the temp file, and decides how to apply each
+//! change with [`classify_external_change`].
+
+use std::io;
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus};
+
+use crate::config::{ConfigSource, EnvConfig};
+
+/// Editor configured by `$VISUAL`, then `$EDITOR`, then the platform default.
+pub fn preferred_editor() -> String {
+    preferred_editor_from(&EnvConfig)
+}
+
+/// [`preferred_editor`] with the variables read from `source`.
+pub fn preferred_editor_from(source: &impl ConfigSource) -> String {
+    source
+        .var("VISUAL")
+        .or_else(|| source.var("EDITOR"))
+        .unwrap_or_else(|| default_editor().to_string())
+}
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+pub const fn default_editor() -> &'static str {
+    if cfg!(windows) {
+        "notepad"
+    } else {
+        "vi"
+    }
+}
+
+/// Start `editor` on `file_path` without waiting for it.
+///
+/// The command is first run as a single program name so paths containing
+/// spaces work; if that is not found it is split on whitespace, which
+/// supports values like `code --wait`.
+pub fn spawn_editor(editor: &str, file_path: &Path) -> io::Result<Child> {
+    match Command::new(editor).arg(file_path).spawn() {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let mut parts = editor.split_whitespace();
+            let Some(program) = parts.next() else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "empty EDITOR command",
+                ));
+            };
+            Command::new(program).args(parts).arg(file_path).spawn()
+        }
+        result => result,
+    }
+}
+
+/// Run `editor` on `file_path` and wait for it to exit successfully.
+pub fn launch_editor(editor: &str, file_path: &Path) -> io::Result<()> {
+    let status = spawn_editor(editor, file_path)?.wait()?;
+    check_editor_status(editor, status)
+}
+
+/// Turn a non-zero editor exit status into an error.
+pub fn check_editor_status(editor: &str, status: ExitStatus) -> io::Result<()> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "`{editor}` exited with status {status}"
+        )))
+    }
+}
+
+/// Drop the final newline an editor appended on save.
+///
+/// Most terminal editors end files with a newline; when the handed-off text
+/// had none, the newline is not treated as an edit.
+pub fn strip_editor_newline<'a>(handed_off: &str, edited: &'a str) -> &'a str {
+    if handed_off.ends_with('\n') {
+        return edited;
+    }
+    edited
+        .strip_suffix("\r\n")
+        .or_else(|| edited.strip_suffix('\n'))
+        .unwrap_or(edited)
+}
+
+/// How a change read back from an external editor applies to the note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalChange {
+    /// The file matches the note; nothing to save.
+    Unchanged,
+    /// The note is as it was handed off; save the edited text.
+    Apply,
+    /// The note was changed elsewhere since the handoff; saving would lose
+    /// that change.
+    Conflict,
+}
+
+/// Compare the editor's text against the note.
+///
+/// `base` is the content last handed off or synced back, `current` is the
+/// note as stored now, and `edited` is the editor's text.
+pub fn classify_external_change(base: &str, current: &str, edited: &str) -> ExternalChange {
+    if edited == current || edited == base {
+        ExternalChange::Unchanged
+    } else if current == base {
+        ExternalChange::Apply
+    } else {
+        ExternalChange::Conflict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MapConfig;
+
+    #[test]
+    fn default_editor_is_defined() {
+        assert!(!default_editor().is_empty());
+    }
+
+    #[test]
+    fn preferred_editor_prefers_visual_over_editor() {
+        let both = MapConfig::new()
+            .with("VISUAL", "code --wait")
+            .with("EDITOR", "vim");
+        assert_eq!(preferred_editor_from(&both), "code --wait");
+        let editor_only = MapConfig::new().with("EDITOR", "vim");
+        assert_eq!(preferred_editor_from(&editor_only), "vim");
+        assert_eq!(preferred_editor_from(&MapConfig::new()), default_editor());
+    }
+
+    #[test]
+    fn strip_editor_newline_only_drops_added_newline() {
+        assert_eq!(
+            strip_editor_newline("draft", "draft edited\n"),
+            "draft edited"
+        );
+        assert_eq!(strip_editor_newline("draft", "draft\r\n"), "draft");
+        assert_eq!(strip_editor_newline("draft\n", "draft\n"), "draft\n");
+        assert_eq!(strip_editor_newline("draft", "draft\n\n"), "draft\n");
+    }
+
+    #[test]
+    fn classify_external_change_detects_conflicts() {
+        assert_eq!(
+            classify_external_change("base", "base", "base"),
+            ExternalChange::Unchanged
+        );
+        assert_eq!(
+            classify_external_change("base", "base", "edited"),
+            ExternalChange::Apply
+        );
+        assert_eq!(
+            classify_external_change("base", "in app", "edited"),
+            ExternalChange::Conflict
+        );
+        assert_eq!(
+            classify_external_change("base", "same", "same"),
+            ExternalChange::Unchanged
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn launch_editor_reports_failed_exit() {
+        let file = std::env::temp_dir().join("dirt-editor-test.md");
+        assert!(launch_editor("true", &file).is_ok());
+        let error = launch_editor("false", &file).unwrap_err();
+        assert!(error.to_string().starts_with("`false` exited with status"));
+    }
+}
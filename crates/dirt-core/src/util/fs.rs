@@ -0,0 +1,164 @@
+//! File helpers shared by exports, backups, attachments, and temp files.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Fallback name when sanitizing leaves nothing usable.
+const FALLBACK_FILE_NAME: &str = "file";
+
+static TEMP_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Reduce text to lowercase ASCII alphanumerics joined by single dashes.
+///
+/// `" My  File__Name "` becomes `my-file-name`; input without any
+/// alphanumerics becomes an empty string.
+#[must_use]
+pub fn sanitize_token(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut last_dash = false;
+
+    for ch in input.chars().flat_map(char::to_lowercase) {
+        if ch.is_ascii_alphanumeric() {
+            out.push(ch);
+            last_dash = false;
+        } else if !last_dash {
+            out.push('-');
+            last_dash = true;
+        }
+    }
+
+    out.trim_matches('-').to_string()
+}
+
+/// Turn an arbitrary (possibly user-supplied) name into a safe file name.
+///
+/// Stem and extension are sanitized separately, so path separators and
+/// traversal segments cannot survive: `../My File (Final).PNG` becomes
+/// `my-file-final.png`.
+#[must_use]
+pub fn sanitize_file_name(file_name: &str) -> String {
+    let file_name = file_name.trim();
+    let (stem, ext) = file_name.rsplit_once('.').unwrap_or((file_name, ""));
+    let stem = sanitize_token(stem);
+    let stem = if stem.is_empty() {
+        FALLBACK_FILE_NAME.to_string()
+    } else {
+        stem
+    };
+    let ext = sanitize_token(ext);
+
+    if ext.is_empty() {
+        stem
+    } else {
+        format!("{stem}.{ext}")
+    }
+}
+
+/// Path for `file_name` in `dir` that does not exist yet.
+///
+/// Returns `dir/file_name` when it is free, otherwise the first free
+/// `dir/<stem>-<n>.<ext>`.
+#[must_use]
+pub fn unique_path(dir: &Path, file_name: &str) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+
+    let (stem, ext) = match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, Some(ext)),
+        _ => (file_name, None),
+    };
+    (1_u64..)
+        .map(|index| {
+            dir.join(ext.map_or_else(
+                || format!("{stem}-{index}"),
+                |ext| format!("{stem}-{index}.{ext}"),
+            ))
+        })
+        .find(|path| !path.exists())
+        .unwrap_or(candidate)
+}
+
+/// Write a file atomically: write a sibling temp file, then rename it over
+/// `path`, so readers never see partial content. Missing parent folders are
+/// created.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} has no file name", path.display()),
+        )
+    })?;
+    let dir = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    fs::create_dir_all(dir)?;
+
+    let temp_path = dir.join(format!(
+        ".{}.{}-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        TEMP_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(contents.as_ref())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Unique path for a new temp file under `<system temp>/dirt/<subdir>`.
+///
+/// The folder is created; the name is sanitized and made collision-free.
+pub fn temp_file_path(subdir: &str, file_name: &str) -> io::Result<PathBuf> {
+    let dir = std::env::temp_dir()
+        .join("dirt")
+        .join(sanitize_token(subdir));
+    fs::create_dir_all(&dir)?;
+    Ok(unique_path(&dir, &sanitize_file_name(file_name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_file_name_strips_separators_and_punctuation() {
+        assert_eq!(sanitize_token(" My  File__Name "), "my-file-name");
+        assert_eq!(sanitize_token("..."), "");
+        assert_eq!(
+            sanitize_file_name("../My File (Final).PNG"),
+            "my-file-final.png"
+        );
+        assert_eq!(sanitize_file_name("notes/a\\b"), "notes-a-b");
+        assert_eq!(sanitize_file_name("..."), "file");
+    }
+
+    #[test]
+    fn unique_path_and_atomic_write_avoid_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = unique_path(dir.path(), "export.md");
+        assert_eq!(first, dir.path().join("export.md"));
+
+        write_atomic(&first, "one").unwrap();
+        let second = unique_path(dir.path(), "export.md");
+        assert_eq!(second, dir.path().join("export-1.md"));
+
+        write_atomic(&first, "two").unwrap();
+        assert_eq!(fs::read_to_string(&first).unwrap(), "two");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let nested = dir.path().join("a").join("b.json");
+        write_atomic(&nested, "{}").unwrap();
+        assert_eq!(fs::read_to_string(nested).unwrap(), "{}");
+    }
+}
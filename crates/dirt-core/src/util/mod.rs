@@ -1,5 +1,11 @@
 //! Shared utility functions used across multiple modules.
 
+use std::fmt::Write as _;
+
+pub mod editor;
+pub mod fs;
+pub mod retry;
+
 /// Normalize optional text by trimming whitespace and removing empties.
 ///
 /// Returns `None` when the input is `None` or the trimmed value is empty.
@@ -28,6 +34,26 @@ pub fn unix_timestamp_now() -> i64 {
     chrono::Utc::now().timestamp()
 }
 
+/// Lowercase hex encoding of `bytes`.
+pub fn encode_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(out, "{byte:02x}");
+    }
+    out
+}
+
+/// Decode hex produced by [`encode_hex`]; `None` when it is malformed.
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,235 @@
+//! Retry policy shared by the HTTP clients.
+//!
+//! Dropped connections, gateway errors, and rate limits are retried with
+//! exponential backoff and jitter, within a total wait budget. Requests that
+//! are not idempotent are only retried when the server cannot have acted on
+//! them: the connection never opened, or the API rate-limited the request.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::{RequestBuilder, Response, StatusCode};
+
+use crate::http::RateLimited;
+
+/// Whether sending a request twice has the same effect as sending it once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Idempotency {
+    /// Safe to repeat after any transient failure: reads, URL signing,
+    /// deletes, and token exchanges.
+    Idempotent,
+    /// Repeated only when the server cannot have received it, e.g. sign-up
+    /// or starting a multipart upload.
+    NonIdempotent,
+}
+
+/// How often and how long an HTTP client retries transient failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including the first; `1` disables retries.
+    pub max_attempts: u32,
+    /// Backoff before the first retry, doubled for each later one.
+    pub base_delay: Duration,
+    /// Longest backoff between two attempts.
+    pub max_delay: Duration,
+    /// Longest total time spent waiting between attempts, including
+    /// `Retry-After` waits asked for by rate limits.
+    pub budget: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl RetryPolicy {
+    /// Three attempts, backing off from 250 ms, waiting 30 s at most.
+    pub const DEFAULT: Self = Self {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(250),
+        max_delay: Duration::from_secs(5),
+        budget: Duration::from_secs(30),
+    };
+
+    /// A single attempt.
+    pub const NONE: Self = Self {
+        max_attempts: 1,
+        ..Self::DEFAULT
+    };
+
+    /// Sends `request`, retrying transient failures.
+    ///
+    /// Returns the last response or transport error; callers check the
+    /// status as they would for a single attempt. Requests with streaming
+    /// bodies cannot be replayed and are sent once.
+    pub async fn send(
+        &self,
+        request: RequestBuilder,
+        idempotency: Idempotency,
+    ) -> reqwest::Result<Response> {
+        let mut attempt = 1;
+        let mut waited = Duration::ZERO;
+        loop {
+            let replay = if attempt < self.max_attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+            let Some(replay) = replay else {
+                return request.send().await;
+            };
+
+            let outcome = replay.send().await;
+            let delay = match &outcome {
+                Ok(response) => {
+                    self.response_delay(response.status(), response.headers(), attempt, idempotency)
+                }
+                Err(error) => self.error_delay(error, attempt, idempotency),
+            };
+            let Some(delay) = delay.filter(|delay| waited + *delay <= self.budget) else {
+                return outcome;
+            };
+
+            match &outcome {
+                Ok(response) => tracing::debug!(
+                    "HTTP {} on attempt {attempt}; retrying in {delay:?}",
+                    response.status().as_u16()
+                ),
+                Err(error) => {
+                    tracing::debug!(
+                        "Request failed on attempt {attempt}: {error}; retrying in {delay:?}"
+                    );
+                }
+            }
+            tokio::time::sleep(delay).await;
+            waited += delay;
+            attempt += 1;
+        }
+    }
+
+    /// Wait before retrying a response, or `None` when it should be returned.
+    fn response_delay(
+        &self,
+        status: StatusCode,
+        headers: &HeaderMap,
+        attempt: u32,
+        idempotency: Idempotency,
+    ) -> Option<Duration> {
+        // A rate-limited request was rejected before the API acted on it.
+        if let Some(limited) = RateLimited::from_response(status, headers) {
+            return Some(limited.retry_after());
+        }
+        let transient = matches!(
+            status,
+            StatusCode::REQUEST_TIMEOUT
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT
+        );
+        (transient && idempotency == Idempotency::Idempotent).then(|| self.backoff(attempt))
+    }
+
+    /// Wait before retrying a transport error, or `None` when it should be
+    /// returned.
+    fn error_delay(
+        &self,
+        error: &reqwest::Error,
+        attempt: u32,
+        idempotency: Idempotency,
+    ) -> Option<Duration> {
+        let retryable = error.is_connect()
+            || (idempotency == Idempotency::Idempotent
+                && (error.is_timeout() || error.is_request()));
+        retryable.then(|| self.backoff(attempt))
+    }
+
+    /// Backoff after `attempt` failed attempts: doubling from
+    /// [`Self::base_delay`] up to [`Self::max_delay`], with the upper half
+    /// randomized so clients that failed together do not retry together.
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)));
+        let half = exponential.min(self.max_delay) / 2;
+        let span = u64::try_from(half.as_nanos()).unwrap_or(u64::MAX).max(1);
+        half + Duration::from_nanos(random_u64() % span)
+    }
+}
+
+/// Randomness for jitter; each `RandomState` is seeded differently.
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap_with_jitter() {
+        let policy = RetryPolicy::DEFAULT;
+        for attempt in 1..=8 {
+            let capped = policy
+                .base_delay
+                .saturating_mul(2_u32.pow(attempt - 1))
+                .min(policy.max_delay);
+            let delay = policy.backoff(attempt);
+            assert!(delay >= capped / 2, "attempt {attempt}: {delay:?}");
+            assert!(delay <= capped, "attempt {attempt}: {delay:?}");
+        }
+    }
+
+    #[test]
+    fn gateway_errors_are_retried_only_when_idempotent() {
+        let policy = RetryPolicy::DEFAULT;
+        let headers = HeaderMap::new();
+        assert!(policy
+            .response_delay(
+                StatusCode::SERVICE_UNAVAILABLE,
+                &headers,
+                1,
+                Idempotency::Idempotent
+            )
+            .is_some());
+        assert!(policy
+            .response_delay(
+                StatusCode::SERVICE_UNAVAILABLE,
+                &headers,
+                1,
+                Idempotency::NonIdempotent
+            )
+            .is_none());
+        assert!(policy
+            .response_delay(
+                StatusCode::BAD_REQUEST,
+                &headers,
+                1,
+                Idempotency::Idempotent
+            )
+            .is_none());
+        assert!(policy
+            .response_delay(StatusCode::OK, &headers, 1, Idempotency::Idempotent)
+            .is_none());
+    }
+
+    #[test]
+    fn rate_limits_wait_for_retry_after_even_when_not_idempotent() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+        assert_eq!(
+            RetryPolicy::DEFAULT.response_delay(
+                StatusCode::TOO_MANY_REQUESTS,
+                &headers,
+                1,
+                Idempotency::NonIdempotent
+            ),
+            Some(Duration::from_secs(2))
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Workspaces: separate vaults on one device
+//!
+//! A workspace binds a local database, the backend profile it signs in with,
+//! and the stored sign-in session, so a work and a personal vault never mix
+//! notes or accounts. The workspaces and the active one are listed in
+//! [`WORKSPACES_FILE`] in the app data directory. The [`DEFAULT_WORKSPACE`]
+//! keeps the database path and session key used before workspaces existed,
+//! so upgrading changes nothing until a second workspace is added.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::util::fs::{sanitize_token, write_atomic};
+
+/// File in the app data directory that lists the workspaces
+pub const WORKSPACES_FILE: &str = "workspaces.json";
+
+/// Workspace that exists on every device
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+const DB_FILE_NAME: &str = "dirt.db";
+
+/// A local database with the profile and session it syncs with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workspace {
+    /// Name shown in workspace pickers, e.g. `work`
+    pub name: String,
+    /// Database that holds the workspace's notes
+    pub db_path: PathBuf,
+    /// Backend profile the workspace signs in with (the CLI profile of the
+    /// same name)
+    pub profile: String,
+}
+
+impl Workspace {
+    /// Whether this is the [`DEFAULT_WORKSPACE`].
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_WORKSPACE
+    }
+
+    /// Key the workspace's sign-in session is stored under, given the key
+    /// `base_key` used before workspaces existed. The default workspace keeps
+    /// `base_key` so existing sign-ins carry over.
+    #[must_use]
+    pub fn session_key(&self, base_key: &str) -> String {
+        if self.is_default() {
+            base_key.to_string()
+        } else {
+            format!("{base_key}:{}", sanitize_token(&self.name))
+        }
+    }
+}
+
+/// Database path for the workspace `name` under `root`: `<root>/<name>/dirt.db`,
+/// the same layout as CLI profiles. The name is sanitized so it cannot escape
+/// `root`.
+#[must_use]
+pub fn workspace_db_path(root: &Path, name: &str) -> PathBuf {
+    let dir_name = sanitize_token(name);
+    let dir_name = if dir_name.is_empty() {
+        DEFAULT_WORKSPACE
+    } else {
+        dir_name.as_str()
+    };
+    root.join(dir_name).join(DB_FILE_NAME)
+}
+
+/// The workspaces on this device and which one is active
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workspaces {
+    active: String,
+    workspaces: Vec<Workspace>,
+    #[serde(skip)]
+    root: PathBuf,
+}
+
+impl Workspaces {
+    /// Only the default workspace, using `default_db_path`, with new
+    /// workspaces created under `root`.
+    #[must_use]
+    pub fn with_default(root: &Path, default_db_path: PathBuf) -> Self {
+        Self {
+            active: DEFAULT_WORKSPACE.to_string(),
+            workspaces: vec![default_workspace(default_db_path)],
+            root: root.to_path_buf(),
+        }
+    }
+
+    /// Read the workspaces listed in `root`. Without a list yet, only the
+    /// default workspace exists, using `default_db_path`.
+    pub fn load(root: &Path, default_db_path: PathBuf) -> Result<Self> {
+        let raw = match fs::read_to_string(root.join(WORKSPACES_FILE)) {
+            Ok(raw) => raw,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::with_default(root, default_db_path));
+            }
+            Err(error) => return Err(error.into()),
+        };
+        let mut workspaces = serde_json::from_str::<Self>(&raw)?;
+        workspaces.root = root.to_path_buf();
+        if !workspaces.workspaces.iter().any(Workspace::is_default) {
+            workspaces
+                .workspaces
+                .insert(0, default_workspace(default_db_path));
+        }
+        if workspaces.get(&workspaces.active).is_none() {
+            workspaces.active = DEFAULT_WORKSPACE.to_string();
+        }
+        Ok(workspaces)
+    }
+
+    /// Write the list back to the data directory.
+    pub fn save(&self) -> Result<()> {
+        write_atomic(
+            &self.root.join(WORKSPACES_FILE),
+            serde_json::to_vec_pretty(self)?,
+        )?;
+        Ok(())
+    }
+
+    /// The workspace in use.
+    #[must_use]
+    pub fn active(&self) -> &Workspace {
+        self.get(&self.active)
+            .unwrap_or_else(|| &self.workspaces[0])
+    }
+
+    /// All workspaces, the default one first.
+    #[must_use]
+    pub fn list(&self) -> &[Workspace] {
+        &self.workspaces
+    }
+
+    /// The workspace called `name` (case-insensitive).
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Workspace> {
+        let name = name.trim();
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Add a workspace with its own database, signing in with `profile`
+    /// (the workspace name when `None`). Call [`Self::save`] to keep it.
+    pub fn add(&mut self, name: &str, profile: Option<&str>) -> Result<&Workspace> {
+        let name = name.trim();
+        let token = sanitize_token(name);
+        if token.is_empty() {
+            return Err(Error::InvalidInput(
+                "Workspace name needs at least one letter or digit".to_string(),
+            ));
+        }
+        if self
+            .workspaces
+            .iter()
+            .any(|workspace| sanitize_token(&workspace.name) == token)
+        {
+            return Err(Error::InvalidInput(format!(
+                "A workspace named '{name}' already exists"
+            )));
+        }
+
+        let profile = profile
+            .map(str::trim)
+            .filter(|profile| !profile.is_empty())
+            .unwrap_or(name);
+        self.workspaces.push(Workspace {
+            name: name.to_string(),
+            db_path: workspace_db_path(&self.root, name),
+            profile: profile.to_string(),
+        });
+        Ok(&self.workspaces[self.workspaces.len() - 1])
+    }
+
+    /// Make `name` the active workspace. Call [`Self::save`] to keep it.
+    pub fn switch(&mut self, name: &str) -> Result<&Workspace> {
+        let workspace = self.get(name).ok_or_else(|| not_found(name))?;
+        self.active = workspace.name.clone();
+        Ok(self.active())
+    }
+
+    /// Drop `name` from the list. Its database stays on disk. The default
+    /// and the active workspace cannot be removed.
+    pub fn remove(&mut self, name: &str) -> Result<Workspace> {
+        let index = self
+            .workspaces
+            .iter()
+            .position(|workspace| workspace.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| not_found(name))?;
+        let workspace = &self.workspaces[index];
+        if workspace.is_default() {
+            return Err(Error::InvalidInput(
+                "The default workspace cannot be removed".to_string(),
+            ));
+        }
+        if workspace.name == self.active {
+            return Err(Error::InvalidInput(format!(
+                "Switch to another workspace before removing '{}'",
+                workspace.name
+            )));
+        }
+        Ok(self.workspaces.remove(index))
+    }
+}
+
+fn default_workspace(db_path: PathBuf) -> Workspace {
+    Workspace {
+        name: DEFAULT_WORKSPACE.to_string(),
+        db_path,
+        profile: DEFAULT_WORKSPACE.to_string(),
+    }
+}
+
+fn not_found(name: &str) -> Error {
+    Error::NotFound(format!("Workspace '{}'", name.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_workspace_keeps_legacy_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let legacy = dir.path().join("dirt.db");
+        let workspaces = Workspaces::load(dir.path(), legacy.clone()).unwrap();
+
+        let active = workspaces.active();
+        assert!(active.is_default());
+        assert_eq!(active.db_path, legacy);
+        assert_eq!(active.session_key("supabase_session"), "supabase_session");
+        assert_eq!(workspaces.list().len(), 1);
+        assert_eq!(
+            workspace_db_path(Path::new("/data"), "../Work Vault"),
+            Path::new("/data/work-vault/dirt.db")
+        );
+    }
+
+    #[test]
+    fn add_switch_and_remove_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut workspaces = Workspaces::load(dir.path(), dir.path().join("dirt.db")).unwrap();
+
+        let work = workspaces.add("Work", None).unwrap().clone();
+        assert_eq!(work.db_path, dir.path().join("work").join("dirt.db"));
+        assert_eq!(work.profile, "Work");
+        assert_eq!(
+            work.session_key("supabase_session"),
+            "supabase_session:work"
+        );
+        assert!(matches!(
+            workspaces.add(" work ", None),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            workspaces.add("!!", None),
+            Err(Error::InvalidInput(_))
+        ));
+
+        assert_eq!(workspaces.switch("work").unwrap().name, "Work");
+        assert!(matches!(
+            workspaces.remove("Work"),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            workspaces.switch("missing"),
+            Err(Error::NotFound(_))
+        ));
+        workspaces.save().unwrap();
+
+        let mut reloaded = Workspaces::load(dir.path(), dir.path().join("dirt.db")).unwrap();
+        assert_eq!(reloaded.active().name, "Work");
+        assert_eq!(reloaded.list().len(), 2);
+
+        reloaded.switch(DEFAULT_WORKSPACE).unwrap();
+        assert!(matches!(
+            reloaded.remove(DEFAULT_WORKSPACE),
+            Err(Error::InvalidInput(_))
+        ));
+        assert_eq!(reloaded.remove("work").unwrap().name, "Work");
+        assert_eq!(reloaded.list().len(), 1);
+    }
+}
@@ -1,25 +1,45 @@
 //! Main application component
 
+use std::collections::HashSet;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use dioxus::desktop::{window, LogicalPosition, LogicalSize};
+use dioxus::desktop::{window, DesktopContext};
 use dioxus::prelude::*;
+use dirt_core::capture::PostCaptureActions;
+use dirt_core::changelog::{should_record_current_version, unseen_releases, CURRENT_VERSION};
+use dirt_core::connectivity::{Connectivity, ConnectivityMonitor};
 use dirt_core::db::SyncConfig;
-use dirt_core::models::Note;
-
-use crate::bootstrap_config::{load_bootstrap_config, resolve_bootstrap_config};
-use crate::components::{QuickCapture, SettingsPanel};
-use crate::queries::use_notes_query;
+use dirt_core::digest::{weekly_digest_due, DigestPeriod, DIGEST_TAG};
+use dirt_core::models::{merge_first_page, Note, NoteSource};
+use dirt_core::os_search::OsSearchFormat;
+use dirt_core::sync::{SyncAction, SyncRotationEvent, SyncScheduler};
+use dirt_core::transcription::TranscriptionProvider;
+
+use crate::bootstrap_config::{
+    desktop_manifest_target, load_bootstrap_config, resolve_bootstrap_config_for,
+};
+use crate::components::{
+    capture_file_to_inbox, capture_text, show_quick_capture_window, DraftRecovery, ExportDialog,
+    ImportWizard, MigrationProgressDialog, ReauthDialog, SettingsPanel, WhatsNewDialog,
+};
+use crate::hotkey::apply_capture_hotkey_setting;
+use crate::queries::{invalidate_notes_query, use_notes_query};
 use crate::services::{
-    auth_service_from_bootstrap, media_client_from_bootstrap, sync_auth_from_bootstrap,
-    AuthSession, DatabaseService, DesktopAuthService, MediaApiClient, TranscriptionService,
-    TursoSyncAuthClient,
+    active_workspace, auth_service_from_bootstrap, install_share_target,
+    media_client_from_bootstrap, new_conflict_count, notify, os_search_index_dir,
+    remove_os_search_index, remove_share_target, share_inbox_dir, sync_auth_from_bootstrap,
+    sync_os_search_index, take_shared_texts, AuthSession, AuthUser, DatabaseService,
+    DesktopAuthService, MediaApiClient, NotificationCategory, SessionEvent, ShareTargetKind,
+    SyncFailureTracker, TranscriptionService, TursoSyncAuthClient, SYNC_CONFLICT_NOTIFY_LIMIT,
 };
 use crate::state::{AppState, SyncStatus};
 use crate::theme::{resolve_theme, ResolvedTheme};
-use crate::tray::{process_tray_events, QUIT_REQUESTED, SHOW_MAIN_WINDOW};
+use crate::tray::{
+    process_tray_events, take_open_note_request, update_tray_menu, CAPTURE_FILE_REQUESTED,
+    QUIT_REQUESTED, SHOW_MAIN_WINDOW, SYNC_NOW_REQUESTED,
+};
 use crate::views::Home;
 use crate::{HOTKEY_TRIGGERED, TRAY_ENABLED};
 
@@ -28,49 +48,73 @@ use crate::{HOTKEY_TRIGGERED, TRAY_ENABLED};
 pub fn App() -> Element {
     // State signals
     let mut notes = use_signal(Vec::new);
-    let current_note_id = use_signal(|| None);
+    let mut current_note_id = use_signal(|| None);
     let search_query = use_signal(String::new);
     let active_tag_filter = use_signal(|| None::<String>);
+    let manual_sort_enabled = use_signal(|| false);
+    let manual_order = use_signal(Vec::new);
     let mut settings = use_signal(dirt_core::models::Settings::default);
     let mut theme = use_signal(|| resolve_theme(dirt_core::models::ThemeMode::System));
     let settings_open = use_signal(|| false);
-    let mut quick_capture_open = use_signal(|| false);
-    let mut saved_window_geometry: Signal<Option<(f64, f64, f64, f64)>> = use_signal(|| None);
+    let import_open = use_signal(|| false);
+    let export_open = use_signal(|| false);
+    let graph_open = use_signal(|| false);
+    let links_open = use_signal(|| false);
+    let quick_capture_open = use_signal(|| false);
+    let quick_capture_window = use_signal(|| None::<DesktopContext>);
     let mut db_service: Signal<Option<Arc<DatabaseService>>> = use_signal(|| None);
     let mut auth_service: Signal<Option<Arc<DesktopAuthService>>> = use_signal(|| None);
     let mut sync_auth_client: Signal<Option<Arc<TursoSyncAuthClient>>> = use_signal(|| None);
     let mut media_api_client: Signal<Option<Arc<MediaApiClient>>> = use_signal(|| None);
-    let transcription_service: Signal<Option<Arc<TranscriptionService>>> =
-        use_signal(|| match TranscriptionService::new() {
-            Ok(service) => Some(Arc::new(service)),
-            Err(error) => {
-                tracing::warn!("Voice transcription service unavailable: {}", error);
-                None
-            }
-        });
+    let mut transcription_service: Signal<Option<Arc<TranscriptionService>>> =
+        use_signal(|| TranscriptionService::load(&dirt_core::models::Settings::default()));
     let mut auth_session: Signal<Option<AuthSession>> = use_signal(|| None);
     let mut auth_error: Signal<Option<String>> = use_signal(|| None);
+    let mut expired_session: Signal<Option<AuthUser>> = use_signal(|| None);
+    let reauth_open = use_signal(|| false);
     let mut db_reconnect_version = use_signal(|| 0u64);
-    let mut auth_initialized = use_signal(|| false);
+    let mut database_read_only = use_signal(|| false);
+    let mut update_required = use_signal(|| None::<String>);
+    let workspace = use_signal(active_workspace);
+    let mut auth_workspace = use_signal(|| None::<String>);
     let mut bootstrap_ready = use_signal(|| false);
+    let connectivity = use_signal(ConnectivityMonitor::default);
     let mut sync_status = use_signal(|| SyncStatus::Offline);
     let mut sync_issue = use_signal(|| None::<String>);
     let mut last_sync_at = use_signal(|| None::<i64>);
     let mut pending_sync_count = use_signal(|| 0usize);
     let mut pending_sync_note_ids = use_signal(Vec::new);
-    let mut sync_token_expires_at = use_signal(|| None::<i64>);
+    let mut sync_scheduler = use_signal(SyncScheduler::new);
+    let mut last_sync_rotation = use_signal(|| None::<SyncRotationEvent>);
+    let mut sync_retry_at = use_signal(|| None::<i64>);
+    let mut recovered_drafts = use_signal(Vec::new);
+    let mut whats_new = use_signal(Vec::new);
+    let attachment_note_ids = use_signal(HashSet::new);
     let embedded_bootstrap_config = load_bootstrap_config();
 
-    // Initialize authentication service and restore persisted session.
+    // Initialize authentication service and restore the active workspace's
+    // persisted session; reruns when another workspace is switched to.
     use_effect(move || {
-        if auth_initialized() {
+        let workspace_name = workspace().name;
+        if auth_workspace.peek().as_deref() == Some(workspace_name.as_str()) {
             return;
         }
-        auth_initialized.set(true);
+        let switched = auth_workspace.peek().is_some();
+        auth_workspace.set(Some(workspace_name));
+        if switched {
+            auth_session.set(None);
+            expired_session.set(None);
+            sync_scheduler.write().clear();
+        }
         let fallback_bootstrap = embedded_bootstrap_config.clone();
 
         spawn(async move {
-            let bootstrap = match resolve_bootstrap_config(fallback_bootstrap.clone()).await {
+            let bootstrap = match resolve_bootstrap_config_for(
+                fallback_bootstrap.clone(),
+                desktop_manifest_target(),
+            )
+            .await
+            {
                 Ok(config) => config,
                 Err(error) => {
                     tracing::warn!(
@@ -80,6 +124,12 @@ pub fn App() -> Element {
                     fallback_bootstrap
                 }
             };
+            update_required.set(
+                bootstrap
+                    .update_required(CURRENT_VERSION)
+                    .map(str::to_string),
+            );
+            connectivity.peek().set_probe_url_from_bootstrap(&bootstrap);
 
             match sync_auth_from_bootstrap(&bootstrap) {
                 Ok(Some(client)) => sync_auth_client.set(Some(Arc::new(client))),
@@ -104,9 +154,12 @@ pub fn App() -> Element {
             match service_result {
                 Ok(Some(service)) => {
                     let service = Arc::new(service);
-                    match service.restore_session().await {
-                        Ok(session) => {
-                            auth_session.set(session);
+                    match service.restore_session_event().await {
+                        Ok(event) => {
+                            if let SessionEvent::Expired(user) = &event {
+                                expired_session.set(Some(user.clone()));
+                            }
+                            auth_session.set(event.into_session());
                             auth_error.set(None);
                             db_reconnect_version.set(db_reconnect_version().saturating_add(1));
                         }
@@ -127,6 +180,9 @@ pub fn App() -> Element {
                 }
             }
 
+            if switched {
+                db_reconnect_version.set(db_reconnect_version().saturating_add(1));
+            }
             bootstrap_ready.set(true);
         });
     });
@@ -157,21 +213,41 @@ pub fn App() -> Element {
 
         let db_result =
             if let (Some(client), Some(session)) = (managed_sync_client, current_session) {
-                match client.exchange_token(&session.access_token).await {
+                // Reuse credentials already fetched by a rotation check.
+                let now = chrono::Utc::now().timestamp();
+                let pending_token = sync_scheduler.write().take_pending_token(now);
+                let token_result = match pending_token {
+                    Some(token) => Ok(token),
+                    None => client.exchange_token(&session.access_token).await,
+                };
+                match token_result {
                     Ok(token) => {
-                        sync_token_expires_at.set(Some(token.expires_at));
-                        let sync_config = SyncConfig::new(token.database_url, token.token);
-                        DatabaseService::new_with_sync(sync_config).await
+                        sync_retry_at.set(None);
+                        let sync_config =
+                            SyncConfig::new(token.database_url.clone(), token.token.clone());
+                        let result = DatabaseService::new_with_sync(sync_config).await;
+                        if result.is_ok() {
+                            sync_scheduler.write().credentials_opened(&token, now);
+                        } else {
+                            sync_scheduler.write().clear();
+                        }
+                        result
                     }
                     Err(error) => {
-                        sync_token_expires_at.set(None);
+                        sync_scheduler.write().clear();
+                        if let Some(limited) = error.rate_limited() {
+                            let retry_after =
+                                i64::try_from(limited.retry_after_secs).unwrap_or(i64::MAX);
+                            let now = chrono::Utc::now().timestamp();
+                            sync_retry_at.set(Some(now.saturating_add(retry_after)));
+                        }
                         let message = format!("Managed sync token exchange failed: {error}");
                         sync_issue.set(Some(message.clone()));
                         Err(dirt_core::Error::Storage(message))
                     }
                 }
             } else {
-                sync_token_expires_at.set(None);
+                sync_scheduler.write().clear();
                 DatabaseService::new().await
             };
 
@@ -179,7 +255,7 @@ pub fn App() -> Element {
             Ok(db) => {
                 let db = Arc::new(db);
 
-                let loaded_settings = match db.load_settings_with_large_stack().await {
+                let mut loaded_settings = match db.load_settings_with_large_stack().await {
                     Ok(settings) => settings,
                     Err(error) => {
                         let message = format!("Failed to load desktop settings: {error}");
@@ -190,10 +266,44 @@ pub fn App() -> Element {
                         return;
                     }
                 };
+                let last_seen = loaded_settings.last_seen_version.clone();
+                if should_record_current_version(last_seen.as_deref()) {
+                    whats_new.set(unseen_releases(last_seen.as_deref()));
+                    loaded_settings.last_seen_version = Some(CURRENT_VERSION.to_string());
+                    if let Err(error) = db.save_settings(&loaded_settings).await {
+                        tracing::warn!("Failed to record the last seen version: {}", error);
+                    }
+                }
                 let resolved_theme = resolve_theme(loaded_settings.theme);
+                if loaded_settings.transcription_provider != TranscriptionProvider::OpenAi {
+                    transcription_service.set(TranscriptionService::load(&loaded_settings));
+                }
+                apply_capture_hotkey_setting(&loaded_settings.capture_hotkey);
                 settings.set(loaded_settings);
                 theme.set(resolved_theme);
 
+                match db.list_recoverable_drafts().await {
+                    Ok(drafts) => {
+                        if !drafts.is_empty() {
+                            tracing::info!("Found {} unsaved drafts to recover", drafts.len());
+                        }
+                        recovered_drafts.set(drafts);
+                    }
+                    Err(error) => {
+                        tracing::warn!("Failed to load unsaved drafts: {}", error);
+                    }
+                }
+
+                match db.pending_sync_changes().await {
+                    Ok(note_ids) => {
+                        pending_sync_count.set(note_ids.len());
+                        pending_sync_note_ids.set(note_ids);
+                    }
+                    Err(error) => {
+                        tracing::warn!("Failed to load pending sync changes: {}", error);
+                    }
+                }
+
                 if db.is_sync_enabled().await {
                     sync_status.set(SyncStatus::Syncing);
                     match db.sync_with_large_stack().await {
@@ -221,6 +331,7 @@ pub fn App() -> Element {
                     sync_status.set(SyncStatus::Offline);
                 }
 
+                database_read_only.set(db.is_read_only().await);
                 db_service.set(Some(db));
             }
             Err(error) => {
@@ -235,24 +346,76 @@ pub fn App() -> Element {
 
     // Periodically sync and update sync status metadata.
     use_future(move || async move {
+        let mut failure_tracker = SyncFailureTracker::default();
+        let mut last_conflict_id = None::<i64>;
         loop {
-            tokio::time::sleep(Duration::from_secs(30)).await;
+            // Wait out the interval, waking early for "Sync Now" in the tray.
+            let wake_at = Instant::now() + Duration::from_secs(30);
+            let mut sync_requested = false;
+            while !sync_requested && Instant::now() < wake_at {
+                tokio::time::sleep(Duration::from_millis(250)).await;
+                sync_requested = SYNC_NOW_REQUESTED.swap(false, Ordering::SeqCst);
+            }
+
+            // While offline, only probe once per backoff interval instead of
+            // failing a sync every tick; a requested sync probes right away.
+            let monitor = connectivity.peek().clone();
+            if monitor.is_offline() {
+                let now = chrono::Utc::now().timestamp();
+                if !(sync_requested || monitor.should_attempt(now))
+                    || monitor.probe(now).await == Connectivity::Offline
+                {
+                    continue;
+                }
+            }
             let cloud_sync_expected = sync_auth_client.read().is_some() && auth_session().is_some();
 
-            // Proactively refresh the sync token before it expires.
+            // Honor the API's rate-limit backoff before exchanging a token again.
+            if let Some(retry_at) = sync_retry_at() {
+                if chrono::Utc::now().timestamp() < retry_at {
+                    continue;
+                }
+                tracing::info!("Sync rate-limit backoff elapsed, retrying token exchange");
+                sync_retry_at.set(None);
+                db_reconnect_version.set(db_reconnect_version() + 1);
+                continue;
+            }
+
             // Turso tokens are short-lived (~15min) and libSQL bakes them
-            // into the connection at construction time, so we must re-open
-            // the database with fresh credentials before the token lapses.
-            if let Some(expires_at) = sync_token_expires_at() {
-                let now = chrono::Utc::now().timestamp();
-                if now >= expires_at - 120 {
-                    tracing::info!(
-                        "Sync token expires in {}s, triggering credential refresh",
-                        expires_at - now
-                    );
+            // into the connection at construction time, so the database is
+            // re-opened before the token lapses or after the API rotates
+            // credentials.
+            let now = chrono::Utc::now().timestamp();
+            let action = sync_scheduler.peek().next_action(now);
+            match action {
+                SyncAction::Reopen(reason) => {
+                    tracing::info!("Reopening sync database with fresh credentials ({reason})");
                     db_reconnect_version.set(db_reconnect_version() + 1);
                     continue;
                 }
+                SyncAction::CheckRotation => {
+                    sync_scheduler.write().begin_rotation_check(now);
+                    let client = sync_auth_client.peek().clone();
+                    let session = auth_session.peek().clone();
+                    if let (Some(client), Some(session)) = (client, session) {
+                        match client.exchange_token(&session.access_token).await {
+                            Ok(token) => {
+                                let rotation = sync_scheduler.write().observe_exchange(token, now);
+                                if let Some(event) = rotation {
+                                    tracing::info!("{event}; reopening sync database");
+                                    last_sync_rotation.set(Some(event));
+                                    db_reconnect_version.set(db_reconnect_version() + 1);
+                                    continue;
+                                }
+                            }
+                            Err(error) => {
+                                // The current credentials still work; try again next interval.
+                                tracing::warn!("Sync credential rotation check failed: {error}");
+                            }
+                        }
+                    }
+                }
+                SyncAction::Sync => {}
             }
 
             let db = db_service.read().clone();
@@ -292,9 +455,33 @@ pub fn App() -> Element {
                 Ok(()) => {
                     sync_status.set(SyncStatus::Synced);
                     sync_issue.set(None);
+                    monitor.record_online();
                     last_sync_at.set(Some(chrono::Utc::now().timestamp_millis()));
                     pending_sync_count.set(0);
                     pending_sync_note_ids.write().clear();
+                    failure_tracker.record_success();
+
+                    match db.list_conflicts(SYNC_CONFLICT_NOTIFY_LIMIT).await {
+                        Ok(conflicts) => {
+                            // The first check after launch only records a baseline.
+                            if let Some(last_seen) = last_conflict_id {
+                                let new_conflicts = new_conflict_count(&conflicts, last_seen);
+                                if new_conflicts > 0 {
+                                    notify(
+                                        &settings.peek(),
+                                        NotificationCategory::SyncConflict,
+                                        "Sync conflicts resolved",
+                                        &format!(
+                                            "{new_conflicts} note edits conflicted during sync. Review them in Settings > Sync."
+                                        ),
+                                    );
+                                }
+                            }
+                            let newest = conflicts.iter().map(|conflict| conflict.id).max();
+                            last_conflict_id = newest.max(last_conflict_id).or(Some(0));
+                        }
+                        Err(error) => tracing::warn!("Failed to check sync conflicts: {error}"),
+                    }
                 }
                 Err(error) => {
                     let message = format!("{error}");
@@ -326,8 +513,27 @@ pub fn App() -> Element {
                         continue;
                     }
 
+                    if monitor.probe(chrono::Utc::now().timestamp()).await == Connectivity::Offline
+                    {
+                        tracing::info!("Sync paused while offline: {error}");
+                        sync_issue.set(Some(
+                            "No network connection. Sync resumes when you're back online."
+                                .to_string(),
+                        ));
+                        sync_status.set(SyncStatus::Offline);
+                        continue;
+                    }
+
                     let message = format!("Periodic sync failed: {error}");
                     tracing::error!("{message}");
+                    if failure_tracker.record_failure() {
+                        notify(
+                            &settings.peek(),
+                            NotificationCategory::SyncFailure,
+                            "Dirt sync is failing",
+                            &message,
+                        );
+                    }
                     sync_issue.set(Some(message));
                     sync_status.set(SyncStatus::Error);
                 }
@@ -335,6 +541,188 @@ pub fn App() -> Element {
         }
     });
 
+    // Refresh the auth session before it lapses. A rejected refresh leaves
+    // sync paused behind the session-expired banner until the user signs in.
+    // The check is skipped while offline rather than failing every minute.
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            let service = auth_service.peek().clone();
+            let session = auth_session.peek().clone();
+            let (Some(service), Some(session)) = (service, session) else {
+                continue;
+            };
+            if !session.is_expired() || connectivity.peek().is_offline() {
+                continue;
+            }
+
+            match service.check_session(session).await {
+                Ok(SessionEvent::Active(refreshed)) => auth_session.set(Some(refreshed)),
+                Ok(SessionEvent::Expired(user)) => {
+                    tracing::warn!("Auth session expired; sync paused until sign-in");
+                    expired_session.set(Some(user));
+                    auth_session.set(None);
+                    db_reconnect_version.set(db_reconnect_version() + 1);
+                }
+                Ok(SessionEvent::SignedOut) => auth_session.set(None),
+                Err(error) => tracing::warn!("Auth session check failed: {error}"),
+            }
+        }
+    });
+
+    // Save a weekly digest note when enabled and none was created this week.
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(60 * 60)).await;
+            if !settings.peek().weekly_digest_enabled {
+                continue;
+            }
+            let db = db_service.peek().clone();
+            let Some(db) = db else {
+                continue;
+            };
+
+            let now = chrono::Utc::now().timestamp_millis();
+            match db.list_notes_by_tag(DIGEST_TAG, 10, 0).await {
+                Ok(digests) if weekly_digest_due(&digests, now) => {}
+                Ok(_) => continue,
+                Err(error) => {
+                    tracing::warn!("Failed to check for recent digest notes: {error}");
+                    continue;
+                }
+            }
+
+            let digest = match db
+                .weekly_digest(DigestPeriod::week_ending(now), false)
+                .await
+            {
+                Ok(digest) if !digest.is_empty() => digest,
+                Ok(_) => continue,
+                Err(error) => {
+                    tracing::warn!("Failed to build weekly digest: {error}");
+                    continue;
+                }
+            };
+            match db.create_note(&digest.render_markdown()).await {
+                Ok(note) => {
+                    tracing::info!("Saved weekly digest note {}", note.id);
+                    invalidate_notes_query().await;
+                }
+                Err(error) => tracing::warn!("Failed to save weekly digest note: {error}"),
+            }
+        }
+    });
+
+    // Keep the Spotlight / Windows Search entries in step with note edits.
+    use_future(move || async move {
+        let Some(format) = OsSearchFormat::current() else {
+            return;
+        };
+        let Some(index_dir) = os_search_index_dir(format) else {
+            return;
+        };
+        let mut published = None::<(usize, i64)>;
+        let mut last_refresh = Instant::now();
+        loop {
+            tokio::time::sleep(Duration::from_secs(15)).await;
+            if !settings.peek().os_search_index_enabled {
+                if published.take().is_some() || index_dir.exists() {
+                    if let Err(error) = remove_os_search_index(format, &index_dir) {
+                        tracing::warn!("Failed to remove OS search index: {error}");
+                    }
+                }
+                continue;
+            }
+            let db = db_service.peek().clone();
+            let Some(db) = db else {
+                continue;
+            };
+
+            // Note count and latest edit change whenever a note is added,
+            // edited, or deleted. Privacy changes do not touch either, so the
+            // index is also refreshed every few minutes regardless.
+            let fingerprint = {
+                let notes = notes.peek();
+                let latest_edit = notes.iter().map(|note| note.updated_at).max();
+                (notes.len(), latest_edit.unwrap_or(0))
+            };
+            if published == Some(fingerprint)
+                && last_refresh.elapsed() < Duration::from_secs(5 * 60)
+            {
+                continue;
+            }
+            last_refresh = Instant::now();
+            match sync_os_search_index(&db, format, &index_dir).await {
+                Ok(summary) => {
+                    if summary.written + summary.removed > 0 {
+                        tracing::info!(
+                            "Updated OS search index: {} written, {} removed",
+                            summary.written,
+                            summary.removed
+                        );
+                    }
+                    published = Some(fingerprint);
+                }
+                Err(error) => tracing::warn!("Failed to update OS search index: {error}"),
+            }
+        }
+    });
+
+    // Keep the share menu entry registered while enabled, and capture text
+    // other apps shared through it (or `dirt-desktop --share`).
+    use_future(move || async move {
+        let Some(inbox_dir) = share_inbox_dir() else {
+            return;
+        };
+        let share_target = ShareTargetKind::current();
+        let mut registered = None::<bool>;
+        loop {
+            if let Some(kind) = share_target {
+                let enabled = settings.peek().share_target_enabled;
+                if registered != Some(enabled) {
+                    let result = if enabled {
+                        std::env::current_exe().and_then(|exe| install_share_target(kind, &exe))
+                    } else {
+                        remove_share_target(kind)
+                    };
+                    if let Err(error) = result {
+                        tracing::warn!("Failed to update share menu entry: {error}");
+                    }
+                    registered = Some(enabled);
+                }
+            }
+
+            let db = db_service.peek().clone();
+            if let Some(db) = db {
+                match take_shared_texts(&inbox_dir) {
+                    Ok(texts) => {
+                        let actions = PostCaptureActions::from_settings(&settings.peek());
+                        for text in texts {
+                            capture_text(
+                                db.clone(),
+                                text,
+                                NoteSource::DesktopShare,
+                                actions.clone(),
+                            )
+                            .await;
+                        }
+                    }
+                    Err(error) => tracing::warn!("Failed to read shared text: {error}"),
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(750)).await;
+        }
+    });
+
+    // Mirror recent notes, sync state, and the capture hotkey in the tray menu.
+    use_effect(move || {
+        update_tray_menu(
+            &notes.read(),
+            sync_status(),
+            &settings.read().capture_hotkey,
+        );
+    });
+
     // Use dioxus-query for reactive notes fetching (called unconditionally - rules of hooks)
     let notes_query = use_notes_query(db_service.read().clone());
 
@@ -349,24 +737,27 @@ pub fn App() -> Element {
             if tray_enabled {
                 process_tray_events();
 
-                // Check for show window request
+                // Check for show window request, opening a recent note if one was picked
                 if SHOW_MAIN_WINDOW.swap(false, Ordering::SeqCst) {
                     tracing::info!("Showing main window from tray");
-                    let win = window();
-                    let tao_win = &win.window;
-
-                    // Restore pre-capture geometry before showing.
-                    if let Some((w, h, x, y)) = saved_window_geometry() {
-                        tao_win.set_outer_position(LogicalPosition::new(x, y));
-                        tao_win.set_inner_size(LogicalSize::new(w, h));
-                        saved_window_geometry.set(None);
+                    if let Some(note_id) = take_open_note_request() {
+                        current_note_id.set(Some(note_id));
                     }
-
-                    quick_capture_open.set(false);
+                    let win = window();
                     win.set_visible(true);
                     win.set_focus();
                 }
 
+                // Capture a picked file into a new inbox note
+                if CAPTURE_FILE_REQUESTED.swap(false, Ordering::SeqCst) {
+                    spawn(capture_file_to_inbox(
+                        db_service.read().clone(),
+                        media_api_client.read().clone(),
+                        auth_session(),
+                        current_note_id,
+                    ));
+                }
+
                 // Check for quit request
                 if QUIT_REQUESTED.swap(false, Ordering::SeqCst) {
                     tracing::info!("Quit requested from tray");
@@ -377,43 +768,8 @@ pub fn App() -> Element {
             // Check if hotkey was triggered
             if HOTKEY_TRIGGERED.swap(false, Ordering::SeqCst) {
                 tracing::info!("Opening quick capture");
-                let win = window();
-                let tao_win = &win.window;
-
-                // Save main-window geometry once; keep it across repeated captures
-                // until the main window is explicitly reopened.
-                if saved_window_geometry().is_none() {
-                    let scale = tao_win.current_monitor().map_or(1.0, |m| m.scale_factor());
-                    let phys_size = tao_win.inner_size();
-                    let phys_pos = tao_win.outer_position().unwrap_or_default();
-                    saved_window_geometry.set(Some((
-                        f64::from(phys_size.width) / scale,
-                        f64::from(phys_size.height) / scale,
-                        f64::from(phys_pos.x) / scale,
-                        f64::from(phys_pos.y) / scale,
-                    )));
-                }
-
-                // Resize to compact quick capture size
-                let capture_w = 420.0;
-                let capture_h = 200.0;
-                tao_win.set_inner_size(LogicalSize::new(capture_w, capture_h));
-
-                // Center on current monitor
-                if let Some(monitor) = tao_win.current_monitor() {
-                    let mon_size = monitor.size();
-                    let mon_pos = monitor.position();
-                    let mon_scale = monitor.scale_factor();
-                    let cx = f64::from(mon_pos.x) / mon_scale
-                        + (f64::from(mon_size.width) / mon_scale - capture_w) / 2.0;
-                    let cy = f64::from(mon_pos.y) / mon_scale
-                        + (f64::from(mon_size.height) / mon_scale - capture_h) / 2.0;
-                    tao_win.set_outer_position(LogicalPosition::new(cx, cy));
-                }
-
-                win.set_visible(true);
-                win.set_focus();
-                quick_capture_open.set(true);
+                show_quick_capture_window(quick_capture_window, consume_context::<AppState>())
+                    .await;
             }
 
             // Sync query result to notes signal only when the query itself changes.
@@ -430,7 +786,9 @@ pub fn App() -> Element {
                     if changed {
                         tracing::debug!("Notes query returned {} notes", fetched_notes.len());
                         last_query_result = Some(fetched_notes.clone());
-                        notes.set(fetched_notes);
+                        // Keep pages the note list loaded beyond the first one.
+                        let merged = merge_first_page(fetched_notes, &notes.peek());
+                        notes.set(merged);
                     }
                 }
             }
@@ -444,7 +802,10 @@ pub fn App() -> Element {
         notes,
         current_note_id,
         search_query,
+        attachment_note_ids,
         active_tag_filter,
+        manual_sort_enabled,
+        manual_order,
         settings,
         theme,
         db_service,
@@ -453,14 +814,27 @@ pub fn App() -> Element {
         transcription_service,
         auth_session,
         auth_error,
+        expired_session,
+        reauth_open,
+        database_read_only,
+        update_required,
         db_reconnect_version,
+        workspace,
+        connectivity,
         sync_status,
         sync_issue,
         last_sync_at,
+        last_sync_rotation,
         pending_sync_count,
         pending_sync_note_ids,
         settings_open,
+        import_open,
+        export_open,
+        graph_open,
+        links_open,
         quick_capture_open,
+        recovered_drafts,
+        whats_new,
     });
 
     let current_theme = theme();
@@ -489,15 +863,23 @@ pub fn App() -> Element {
                 background: {colors.bg_primary};
                 color: {colors.text_primary};
             ",
-            if quick_capture_open() {
-                QuickCapture {}
-            } else {
-                Home {}
-
-                if settings_open() {
-                    SettingsPanel {}
-                }
+            Home {}
+
+            if settings_open() {
+                SettingsPanel {}
+            } else if import_open() {
+                ImportWizard {}
+            } else if export_open() {
+                ExportDialog {}
+            } else if reauth_open() {
+                ReauthDialog {}
+            } else if !recovered_drafts.read().is_empty() {
+                DraftRecovery {}
+            } else if !whats_new.read().is_empty() {
+                WhatsNewDialog {}
             }
+
+            MigrationProgressDialog {}
         }
     }
 }
@@ -2,16 +2,37 @@
 //!
 //! Re-exports the shared `BootstrapConfig` from dirt-core and provides
 //! the desktop-specific `load_bootstrap_config` function that reads the
-//! embedded build-time JSON.
+//! embedded build-time JSON and any packager-embedded bootstrap manifest.
 
-pub use dirt_core::config::{resolve_bootstrap_config, BootstrapConfig};
+use dirt_core::config::{parse_embedded_bootstrap_manifest, ClientPlatform, ManifestTarget};
+pub use dirt_core::config::{resolve_bootstrap_config_for, BootstrapConfig};
 
 /// Loads the generated desktop bootstrap JSON from `OUT_DIR`.
+///
+/// Values from build-time environment variables win; fields they leave unset
+/// come from the manifest embedded via `DIRT_EMBEDDED_BOOTSTRAP_MANIFEST`.
 pub fn load_bootstrap_config() -> BootstrapConfig {
     let raw = include_str!(concat!(env!("OUT_DIR"), "/desktop-bootstrap.json"));
     let parsed: BootstrapConfig = serde_json::from_str(raw)
         .unwrap_or_else(|error| panic!("Failed to parse desktop bootstrap config: {error}"));
-    normalize_desktop_bootstrap(parsed)
+    let embedded = parse_embedded_bootstrap_manifest(
+        include_str!(concat!(
+            env!("OUT_DIR"),
+            "/embedded-bootstrap-manifest.json"
+        )),
+        desktop_manifest_target(),
+    )
+    .unwrap_or_else(|error| panic!("Failed to parse embedded bootstrap manifest: {error}"));
+    let config = match embedded {
+        Some(embedded) => parsed.or_fallback(embedded),
+        None => parsed,
+    };
+    normalize_desktop_bootstrap(config)
+}
+
+/// Channel and platform overrides desktop reads from bootstrap manifests.
+pub fn desktop_manifest_target() -> ManifestTarget {
+    ManifestTarget::current(ClientPlatform::Desktop)
 }
 
 fn normalize_desktop_bootstrap(mut config: BootstrapConfig) -> BootstrapConfig {
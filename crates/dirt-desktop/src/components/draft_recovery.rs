@@ -0,0 +1,177 @@
+//! Startup prompt for restoring unsaved editor drafts
+
+use dioxus::prelude::*;
+
+use dirt_core::models::NoteDraft;
+use dirt_core::NoteId;
+
+use super::button::{Button, ButtonVariant};
+use super::dialog::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
+use crate::queries::invalidate_notes_query;
+use crate::state::AppState;
+
+/// Characters of draft content shown per row
+const DRAFT_PREVIEW_CHARS: usize = 80;
+
+/// Dialog offering to restore or discard drafts left behind by a crash
+#[component]
+pub fn DraftRecovery() -> Element {
+    let mut state = use_context::<AppState>();
+    let mut recovered_drafts = state.recovered_drafts;
+    let colors = (state.theme)().palette();
+    let mut busy = use_signal(|| false);
+
+    let mut remove_from_list = move |note_id: NoteId| {
+        recovered_drafts
+            .write()
+            .retain(|draft| draft.note_id != note_id);
+    };
+
+    let mut restore_draft = move |draft: NoteDraft| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        busy.set(true);
+        state.enqueue_pending_change(draft.note_id);
+
+        spawn(async move {
+            match db.update_note(&draft.note_id, &draft.content).await {
+                Ok(_) => {
+                    tracing::info!("Restored unsaved draft for note {}", draft.note_id);
+                    if let Err(error) = db.discard_draft(&draft.note_id).await {
+                        tracing::warn!("Failed to clear restored draft: {}", error);
+                    }
+                    remove_from_list(draft.note_id);
+                    invalidate_notes_query().await;
+                }
+                Err(error) => {
+                    tracing::error!("Failed to restore draft: {}", error);
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    let mut discard_draft = move |note_id: NoteId| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        busy.set(true);
+
+        spawn(async move {
+            match db.discard_draft(&note_id).await {
+                Ok(()) => remove_from_list(note_id),
+                Err(error) => tracing::error!("Failed to discard draft: {}", error),
+            }
+            busy.set(false);
+        });
+    };
+
+    let drafts = recovered_drafts();
+    let draft_count = drafts.len();
+
+    rsx! {
+        DialogRoot {
+            open: true,
+            on_open_change: move |open: bool| {
+                if !open {
+                    // Keep journaled drafts so the prompt returns on next launch.
+                    recovered_drafts.write().clear();
+                }
+            },
+
+            DialogContent {
+                style: "width: 440px; max-width: 90vw; text-align: left;",
+
+                DialogTitle { "Recover unsaved changes" }
+                DialogDescription {
+                    if draft_count == 1 {
+                        "Dirt closed before 1 note was saved."
+                    } else {
+                        "Dirt closed before {draft_count} notes were saved."
+                    }
+                }
+
+                div {
+                    style: "display: flex; flex-direction: column; gap: 8px; margin: 12px 0;",
+                    for draft in drafts {
+                        {
+                            let note_id = draft.note_id;
+                            let preview = draft_preview(&draft.content);
+                            let saved_at = format_draft_timestamp(draft.updated_at);
+                            let draft_for_restore = draft.clone();
+                            rsx! {
+                                div {
+                                    key: "{note_id}",
+                                    style: "
+                                        display: flex;
+                                        align-items: center;
+                                        gap: 8px;
+                                        padding: 8px;
+                                        border: 1px solid {colors.border};
+                                        border-radius: 6px;
+                                    ",
+                                    div {
+                                        style: "flex: 1; min-width: 0;",
+                                        div {
+                                            style: "
+                                                overflow: hidden;
+                                                text-overflow: ellipsis;
+                                                white-space: nowrap;
+                                                color: {colors.text_primary};
+                                            ",
+                                            "{preview}"
+                                        }
+                                        div {
+                                            style: "font-size: 12px; color: {colors.text_muted};",
+                                            "Edited {saved_at}"
+                                        }
+                                    }
+                                    Button {
+                                        variant: ButtonVariant::Primary,
+                                        disabled: busy(),
+                                        onclick: move |_| restore_draft(draft_for_restore.clone()),
+                                        "Restore"
+                                    }
+                                    Button {
+                                        variant: ButtonVariant::Ghost,
+                                        disabled: busy(),
+                                        onclick: move |_| discard_draft(note_id),
+                                        "Discard"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    style: "display: flex; justify-content: flex-end;",
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: move |_| recovered_drafts.write().clear(),
+                        "Decide later"
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn draft_preview(content: &str) -> String {
+    content
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("Untitled")
+        .trim()
+        .chars()
+        .take(DRAFT_PREVIEW_CHARS)
+        .collect()
+}
+
+fn format_draft_timestamp(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms).map_or_else(
+        || timestamp_ms.to_string(),
+        |date_time| date_time.format("%Y-%m-%d %H:%M UTC").to_string(),
+    )
+}
@@ -0,0 +1,273 @@
+//! Export of all notes: pick a format and destination, optionally bundling
+//! attachments into a zip archive
+
+use dioxus::prelude::*;
+use rfd::AsyncFileDialog;
+
+use dirt_core::export::suggested_bundle_file_name;
+use dirt_core::models::Attachment;
+
+use super::button::{Button, ButtonVariant};
+use super::dialog::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
+use crate::services::{
+    export_notes_bundle_to_path, export_notes_to_path, notify, suggested_export_file_name,
+    BundleExportSummary, ExportProgress, NotesExportFormat, NotificationCategory,
+    LARGE_EXPORT_NOTE_COUNT,
+};
+use crate::state::AppState;
+
+/// Dialog that exports every note to a file or an attachment bundle
+#[component]
+pub fn ExportDialog() -> Element {
+    let state = use_context::<AppState>();
+    let mut export_open = state.export_open;
+    let settings = state.settings;
+    let colors = (state.theme)().palette();
+    let mut format = use_signal(|| NotesExportFormat::Markdown);
+    let mut include_private = use_signal(|| false);
+    let mut bundle_attachments = use_signal(|| false);
+    let mut busy = use_signal(|| false);
+    let mut progress = use_signal(|| None::<ExportProgress>);
+    let mut message = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+    let media_available =
+        state.media_api_client.read().is_some() && state.auth_session.read().is_some();
+
+    let run_export = move |_: MouseEvent| {
+        if busy() {
+            return;
+        }
+        let Some(db) = state.db_service.read().clone() else {
+            error.set(Some("Database service is not available.".to_string()));
+            return;
+        };
+        let format = format();
+        let include_private = include_private();
+        let media_api = state.media_api_client.read().clone();
+        let access_token = state
+            .auth_session
+            .read()
+            .as_ref()
+            .map(|session| session.access_token.clone())
+            .filter(|token| !token.trim().is_empty());
+        let media = media_api.zip(access_token).filter(|_| bundle_attachments());
+        busy.set(true);
+        progress.set(None);
+        message.set(None);
+        error.set(None);
+
+        spawn(async move {
+            let now = chrono::Utc::now().timestamp_millis();
+            let default_name = if media.is_some() {
+                suggested_bundle_file_name(now)
+            } else {
+                suggested_export_file_name(format, now)
+            };
+            let Some(file) = AsyncFileDialog::new()
+                .set_title("Export notes")
+                .set_file_name(&default_name)
+                .save_file()
+                .await
+            else {
+                busy.set(false);
+                return;
+            };
+            let path = file.path().to_path_buf();
+
+            let result = match media {
+                Some((media_api, access_token)) => {
+                    let download_db = db.clone();
+                    export_notes_bundle_to_path(
+                        db.as_ref(),
+                        format,
+                        include_private,
+                        &path,
+                        move |attachment: Attachment| {
+                            let media_api = media_api.clone();
+                            let access_token = access_token.clone();
+                            let db = download_db.clone();
+                            async move {
+                                let (bytes, _) = media_api
+                                    .download(&access_token, &attachment.r2_key)
+                                    .await
+                                    .map_err(|error| error.to_string())?;
+                                db.decrypt_attachment_bytes(bytes)
+                                    .await
+                                    .map_err(|error| error.to_string())
+                            }
+                        },
+                        |update| progress.set(Some(update)),
+                    )
+                    .await
+                    .map(|summary| (summary.notes, bundle_message(&summary)))
+                }
+                None => export_notes_to_path(db.as_ref(), format, include_private, &path)
+                    .await
+                    .map(|count| (count, format!("Exported {count} notes."))),
+            };
+
+            match result {
+                Ok((count, summary)) => {
+                    let summary = format!("{summary} Saved to {}", path.display());
+                    if count >= LARGE_EXPORT_NOTE_COUNT {
+                        notify(
+                            &settings.peek(),
+                            NotificationCategory::ExportComplete,
+                            "Export finished",
+                            &summary,
+                        );
+                    }
+                    message.set(Some(summary));
+                }
+                Err(export_error) => {
+                    tracing::error!("Export failed: {}", export_error);
+                    error.set(Some(format!("Export failed: {export_error}")));
+                }
+            }
+            progress.set(None);
+            busy.set(false);
+        });
+    };
+
+    let status = match progress() {
+        Some(update) if busy() => Some(format!(
+            "Downloading attachments {} of {}...",
+            update.attachments_done, update.attachments_total
+        )),
+        _ if busy() => Some("Exporting...".to_string()),
+        _ => None,
+    };
+
+    rsx! {
+        DialogRoot {
+            open: true,
+            on_open_change: move |open: bool| {
+                if !open && !busy() {
+                    export_open.set(false);
+                }
+            },
+
+            DialogContent {
+                style: "width: 480px; max-width: 90vw; text-align: left;",
+
+                DialogTitle { "Export notes" }
+
+                DialogDescription {
+                    "Save every note in one file, or as a zip archive with its attachments."
+                }
+
+                div {
+                    style: "display: flex; flex-direction: column; gap: 12px; margin: 12px 0;",
+
+                    div {
+                        style: "display: flex; gap: 8px; flex-wrap: wrap;",
+                        for option in NotesExportFormat::ALL {
+                            Button {
+                                key: "{option.label()}",
+                                variant: if format() == option {
+                                    ButtonVariant::Secondary
+                                } else {
+                                    ButtonVariant::Ghost
+                                },
+                                disabled: busy(),
+                                onclick: move |_| format.set(option),
+                                "{option.label()}"
+                            }
+                        }
+                    }
+
+                    div {
+                        style: "display: flex; align-items: center; gap: 8px;",
+                        span { style: "flex: 1; color: {colors.text_primary};", "Include private notes" }
+                        Button {
+                            variant: if include_private() {
+                                ButtonVariant::Secondary
+                            } else {
+                                ButtonVariant::Ghost
+                            },
+                            disabled: busy(),
+                            onclick: move |_| include_private.set(!include_private()),
+                            if include_private() {
+                                "Included"
+                            } else {
+                                "Skipped"
+                            }
+                        }
+                    }
+
+                    div {
+                        style: "display: flex; align-items: center; gap: 8px;",
+                        span { style: "flex: 1; color: {colors.text_primary};", "Bundle attachments" }
+                        Button {
+                            variant: if bundle_attachments() && media_available {
+                                ButtonVariant::Secondary
+                            } else {
+                                ButtonVariant::Ghost
+                            },
+                            disabled: busy() || !media_available,
+                            onclick: move |_| bundle_attachments.set(!bundle_attachments()),
+                            if bundle_attachments() && media_available {
+                                "Zip archive"
+                            } else {
+                                "Notes only"
+                            }
+                        }
+                    }
+                    if !media_available {
+                        div {
+                            style: "font-size: 12px; color: {colors.text_muted};",
+                            "Sign in to bundle attachments; they are downloaded from cloud storage."
+                        }
+                    }
+                }
+
+                if let Some(status) = status {
+                    div { style: "font-size: 12px; color: {colors.text_muted};", "{status}" }
+                }
+                if let Some(summary) = message() {
+                    div { style: "font-size: 12px; color: {colors.text_muted};", "{summary}" }
+                }
+                if let Some(failure) = error() {
+                    div {
+                        style: "font-size: 12px; color: {colors.error}; margin-top: 8px;",
+                        "{failure}"
+                    }
+                }
+
+                div {
+                    style: "display: flex; justify-content: flex-end; gap: 8px; margin-top: 12px;",
+                    Button {
+                        variant: ButtonVariant::Ghost,
+                        disabled: busy(),
+                        onclick: move |_| export_open.set(false),
+                        if message().is_some() {
+                            "Done"
+                        } else {
+                            "Cancel"
+                        }
+                    }
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        disabled: busy(),
+                        onclick: run_export,
+                        "Export..."
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn bundle_message(summary: &BundleExportSummary) -> String {
+    let mut message = format!(
+        "Exported {} notes and {} attachments.",
+        summary.notes, summary.attachments
+    );
+    if summary.skipped_attachments > 0 {
+        message.push_str(&format!(
+            " {} attachments could not be downloaded.",
+            summary.skipped_attachments
+        ));
+    }
+    message
+}
@@ -0,0 +1,361 @@
+//! Note graph view: notes and tags laid out as a force-directed graph
+
+use std::collections::HashMap;
+
+use dioxus::prelude::*;
+
+use dirt_core::graph::{GraphEdgeKind, GraphNodeKind, GraphQuery, NoteGraph};
+use dirt_core::models::NoteId;
+
+use super::button::{Button, ButtonVariant};
+use crate::state::AppState;
+
+/// Side length of the square the layout is computed in
+const LAYOUT_SIZE: f64 = 1_000.0;
+const LAYOUT_ITERATIONS: usize = 150;
+const MIN_ZOOM: f64 = 0.25;
+const MAX_ZOOM: f64 = 4.0;
+const ZOOM_STEP: f64 = 1.25;
+
+/// Graph of note links and shared tags; clicking a note opens it
+#[component]
+pub fn GraphView() -> Element {
+    let mut state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut zoom = use_signal(|| 1.0_f64);
+
+    let graph = use_resource(move || {
+        let db = state.db_service.read().clone();
+        // Reload whenever notes change so new notes and links show up
+        let _ = state.notes.read().len();
+        async move {
+            let db = db.ok_or_else(|| "Database not initialized".to_string())?;
+            db.note_graph(&GraphQuery::default())
+                .await
+                .map_err(|error| error.to_string())
+        }
+    });
+    // The layout is quadratic in the node count, so run it once per loaded
+    // graph rather than on every render
+    let layout = use_memo(move || match &*graph.read() {
+        Some(Ok(graph)) => layout_graph(graph),
+        _ => HashMap::new(),
+    });
+
+    let close_graph = move |_: MouseEvent| {
+        state.graph_open.set(false);
+    };
+
+    let zoom_in = move |_: MouseEvent| {
+        zoom.set((zoom() * ZOOM_STEP).min(MAX_ZOOM));
+    };
+
+    let zoom_out = move |_: MouseEvent| {
+        zoom.set((zoom() / ZOOM_STEP).max(MIN_ZOOM));
+    };
+
+    let on_wheel = move |evt: Event<WheelData>| {
+        evt.prevent_default();
+        let delta = evt.delta().strip_units().y;
+        if delta < 0.0 {
+            zoom.set((zoom() * ZOOM_STEP).min(MAX_ZOOM));
+        } else if delta > 0.0 {
+            zoom.set((zoom() / ZOOM_STEP).max(MIN_ZOOM));
+        }
+    };
+
+    let extent = LAYOUT_SIZE / zoom();
+    let view_box = format!("{} {} {extent} {extent}", -extent / 2.0, -extent / 2.0);
+    let zoom_label = format!("{:.0}%", zoom() * 100.0);
+
+    let body = match &*graph.read() {
+        None => rsx! {
+            div {
+                style: "padding: 24px; color: {colors.text_muted};",
+                "Building graph..."
+            }
+        },
+        Some(Err(error)) => rsx! {
+            div {
+                style: "padding: 24px; color: {colors.error};",
+                "Failed to load graph: {error}"
+            }
+        },
+        Some(Ok(graph)) if graph.nodes.is_empty() => rsx! {
+            div {
+                style: "padding: 24px; color: {colors.text_muted};",
+                "No notes to graph yet."
+            }
+        },
+        Some(Ok(graph)) => {
+            let positions = layout.read();
+            let edges = graph
+                .edges
+                .iter()
+                .enumerate()
+                .filter_map(|(index, edge)| {
+                    let (x1, y1) = *positions.get(edge.source.as_str())?;
+                    let (x2, y2) = *positions.get(edge.target.as_str())?;
+                    let dash = match edge.kind {
+                        GraphEdgeKind::Link => "none",
+                        GraphEdgeKind::Tag => "6 4",
+                    };
+                    Some((index, x1, y1, x2, y2, dash))
+                })
+                .collect::<Vec<_>>();
+            let nodes = graph
+                .nodes
+                .iter()
+                .filter_map(|node| {
+                    let (x, y) = *positions.get(node.id.as_str())?;
+                    Some((node.clone(), x, y, node_radius(node.degree)))
+                })
+                .collect::<Vec<_>>();
+            let truncated = graph.truncated;
+
+            rsx! {
+                if truncated {
+                    div {
+                        style: "padding: 4px 12px; font-size: 12px; color: {colors.text_muted};",
+                        "Showing the most recent notes only."
+                    }
+                }
+                svg {
+                    view_box: "{view_box}",
+                    style: "flex: 1; width: 100%; height: 100%;",
+                    onwheel: on_wheel,
+
+                    for (index, x1, y1, x2, y2, dash) in edges {
+                        line {
+                            key: "edge-{index}",
+                            x1: "{x1}",
+                            y1: "{y1}",
+                            x2: "{x2}",
+                            y2: "{y2}",
+                            stroke: "{colors.border}",
+                            stroke_width: "1.5",
+                            stroke_dasharray: "{dash}",
+                        }
+                    }
+
+                    for (node, x, y, radius) in nodes {
+                        g {
+                            key: "{node.id}",
+                            style: if node.kind == GraphNodeKind::Note { "cursor: pointer;" } else { "" },
+                            onclick: {
+                                let node_id = node.id.clone();
+                                let kind = node.kind;
+                                move |_| {
+                                    if kind != GraphNodeKind::Note {
+                                        return;
+                                    }
+                                    if let Ok(id) = node_id.parse::<NoteId>() {
+                                        state.current_note_id.set(Some(id));
+                                        state.graph_open.set(false);
+                                    }
+                                }
+                            },
+                            circle {
+                                cx: "{x}",
+                                cy: "{y}",
+                                r: "{radius}",
+                                fill: if node.kind == GraphNodeKind::Note { colors.accent } else { colors.bg_tertiary },
+                                stroke: "{colors.border}",
+                                stroke_width: "1",
+                            }
+                            text {
+                                x: "{x}",
+                                y: "{y + radius + 14.0}",
+                                text_anchor: "middle",
+                                font_size: "12",
+                                fill: "{colors.text_secondary}",
+                                "{node.label}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: "graph-view",
+            style: "
+                flex: 1;
+                display: flex;
+                flex-direction: column;
+                overflow: hidden;
+                background: {colors.bg_secondary};
+            ",
+
+            div {
+                style: "
+                    display: flex;
+                    align-items: center;
+                    gap: 8px;
+                    padding: 8px 12px;
+                    border-bottom: 1px solid {colors.border_light};
+                ",
+                span { style: "font-weight: 600;", "Note graph" }
+                div { style: "flex: 1;" }
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    onclick: zoom_out,
+                    "−"
+                }
+                span { style: "font-size: 12px; min-width: 40px; text-align: center;", "{zoom_label}" }
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    onclick: zoom_in,
+                    "+"
+                }
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    onclick: close_graph,
+                    "Close"
+                }
+            }
+
+            {body}
+        }
+    }
+}
+
+fn node_radius(degree: usize) -> f64 {
+    let degree = f64::from(u32::try_from(degree.min(20)).unwrap_or(20));
+    6.0 + degree.sqrt() * 3.0
+}
+
+/// Fruchterman-Reingold layout centered on the origin.
+///
+/// Nodes start on a circle so the result is deterministic for a given graph.
+#[allow(clippy::cast_precision_loss)]
+fn layout_graph(graph: &NoteGraph) -> HashMap<String, (f64, f64)> {
+    let count = graph.nodes.len();
+    if count == 0 {
+        return HashMap::new();
+    }
+
+    let index_of: HashMap<&str, usize> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.id.as_str(), index))
+        .collect();
+    let edges: Vec<(usize, usize)> = graph
+        .edges
+        .iter()
+        .filter_map(|edge| {
+            Some((
+                *index_of.get(edge.source.as_str())?,
+                *index_of.get(edge.target.as_str())?,
+            ))
+        })
+        .collect();
+
+    let radius = LAYOUT_SIZE * 0.35;
+    let mut positions: Vec<(f64, f64)> = (0..count)
+        .map(|index| {
+            let angle = std::f64::consts::TAU * index as f64 / count as f64;
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect();
+
+    let ideal = (LAYOUT_SIZE * LAYOUT_SIZE / count as f64).sqrt() * 0.5;
+    let mut temperature = LAYOUT_SIZE / 10.0;
+    let cooling = temperature / LAYOUT_ITERATIONS as f64;
+
+    for _ in 0..LAYOUT_ITERATIONS {
+        let mut displacement = vec![(0.0_f64, 0.0_f64); count];
+
+        for i in 0..count {
+            for j in (i + 1)..count {
+                let dx = positions[i].0 - positions[j].0;
+                let dy = positions[i].1 - positions[j].1;
+                let distance = dx.hypot(dy).max(0.01);
+                let force = ideal * ideal / distance;
+                let (fx, fy) = (dx / distance * force, dy / distance * force);
+                displacement[i].0 += fx;
+                displacement[i].1 += fy;
+                displacement[j].0 -= fx;
+                displacement[j].1 -= fy;
+            }
+        }
+
+        for &(source, target) in &edges {
+            let dx = positions[source].0 - positions[target].0;
+            let dy = positions[source].1 - positions[target].1;
+            let distance = dx.hypot(dy).max(0.01);
+            let force = distance * distance / ideal;
+            let (fx, fy) = (dx / distance * force, dy / distance * force);
+            displacement[source].0 -= fx;
+            displacement[source].1 -= fy;
+            displacement[target].0 += fx;
+            displacement[target].1 += fy;
+        }
+
+        let bound = LAYOUT_SIZE / 2.0 - 20.0;
+        for (position, (dx, dy)) in positions.iter_mut().zip(displacement) {
+            let length = dx.hypot(dy).max(0.01);
+            let step = length.min(temperature);
+            position.0 = (position.0 + dx / length * step).clamp(-bound, bound);
+            position.1 = (position.1 + dy / length * step).clamp(-bound, bound);
+        }
+
+        temperature = (temperature - cooling).max(1.0);
+    }
+
+    graph
+        .nodes
+        .iter()
+        .map(|node| node.id.clone())
+        .zip(positions)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirt_core::graph::build_note_graph;
+    use dirt_core::Note;
+
+    #[test]
+    fn layout_places_every_node_inside_bounds() {
+        let notes = vec![
+            Note::new("Alpha\n[[Beta]] #shared"),
+            Note::new("Beta\n#shared"),
+            Note::new("Gamma"),
+        ];
+        let graph = build_note_graph(&notes, &GraphQuery::default());
+        let positions = layout_graph(&graph);
+
+        assert_eq!(positions.len(), graph.nodes.len());
+        for (x, y) in positions.values() {
+            assert!(x.abs() <= LAYOUT_SIZE / 2.0 && y.abs() <= LAYOUT_SIZE / 2.0);
+        }
+    }
+
+    #[test]
+    fn linked_nodes_end_up_closer_than_unlinked_ones() {
+        let notes = vec![
+            Note::new("Alpha\n[[Beta]]"),
+            Note::new("Beta"),
+            Note::new("Gamma"),
+        ];
+        let graph = build_note_graph(
+            &notes,
+            &GraphQuery {
+                include_tags: false,
+                ..GraphQuery::default()
+            },
+        );
+        let positions = layout_graph(&graph);
+        let position = |note: &Note| positions[note.id.to_string().as_str()];
+        let distance = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).hypot(a.1 - b.1);
+
+        let linked = distance(position(&notes[0]), position(&notes[1]));
+        let unlinked = distance(position(&notes[0]), position(&notes[2]));
+        assert!(linked < unlinked);
+    }
+}
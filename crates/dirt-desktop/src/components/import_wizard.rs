@@ -0,0 +1,317 @@
+//! Guided import of notes exported from other apps
+
+use std::path::PathBuf;
+
+use dioxus::prelude::*;
+use rfd::AsyncFileDialog;
+
+use dirt_core::import::{
+    import_batch, read_import, ImportBatch, ImportMediaTarget, ImportSource, ImportSummary,
+};
+
+use super::button::{Button, ButtonVariant};
+use super::dialog::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
+use crate::queries::invalidate_notes_query;
+use crate::state::AppState;
+
+/// Notes listed in the preview step
+const PREVIEW_LIMIT: usize = 8;
+/// Characters of note content shown per preview row
+const PREVIEW_TITLE_CHARS: usize = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+enum ImportStep {
+    ChooseSource,
+    Preview(ImportBatch),
+    Done(ImportSummary),
+}
+
+/// Wizard that reads an export, previews it, then imports the notes
+#[component]
+pub fn ImportWizard() -> Element {
+    let state = use_context::<AppState>();
+    let mut import_open = state.import_open;
+    let colors = (state.theme)().palette();
+    let mut step = use_signal(|| ImportStep::ChooseSource);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let mut read_export = move |source: ImportSource, pick_folder: bool| {
+        busy.set(true);
+        error.set(None);
+
+        spawn(async move {
+            let dialog =
+                AsyncFileDialog::new().set_title(format!("Import from {}", source.label()));
+            let picked = if pick_folder {
+                dialog.pick_folder().await
+            } else {
+                dialog
+                    .add_filter("Export archive", file_extensions(source))
+                    .pick_file()
+                    .await
+            };
+            let Some(handle) = picked else {
+                busy.set(false);
+                return;
+            };
+            let path: PathBuf = handle.path().to_path_buf();
+
+            match tokio::task::spawn_blocking(move || read_import(source, &path)).await {
+                Ok(Ok(batch)) => step.set(ImportStep::Preview(batch)),
+                Ok(Err(read_error)) => error.set(Some(read_error.to_string())),
+                Err(join_error) => error.set(Some(join_error.to_string())),
+            }
+            busy.set(false);
+        });
+    };
+
+    let mut run_import = move |batch: ImportBatch| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        let media_api = state.media_api_client.read().clone();
+        let access_token = state
+            .auth_session
+            .read()
+            .as_ref()
+            .map(|session| session.access_token.clone())
+            .filter(|token| !token.trim().is_empty());
+        busy.set(true);
+        error.set(None);
+
+        spawn(async move {
+            let media_target = match (media_api.as_deref(), access_token.as_deref()) {
+                (Some(client), Some(access_token)) => Some(ImportMediaTarget {
+                    client,
+                    access_token,
+                }),
+                _ => None,
+            };
+            match import_batch(&db, &batch, media_target).await {
+                Ok(summary) => {
+                    tracing::info!(
+                        "Imported {} notes from {}",
+                        summary.notes_imported,
+                        batch.source.label()
+                    );
+                    invalidate_notes_query().await;
+                    step.set(ImportStep::Done(summary));
+                }
+                Err(import_error) => {
+                    tracing::error!("Import failed: {}", import_error);
+                    error.set(Some(import_error.to_string()));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    let body = match step() {
+        ImportStep::ChooseSource => rsx! {
+            DialogDescription {
+                "Pick the app your notes come from, then select its export."
+            }
+            div {
+                style: "display: flex; flex-direction: column; gap: 8px; margin: 12px 0;",
+                for source in ImportSource::ALL {
+                    div {
+                        key: "{source}",
+                        style: "
+                            display: flex;
+                            align-items: center;
+                            gap: 8px;
+                            padding: 8px;
+                            border: 1px solid {colors.border};
+                            border-radius: 6px;
+                        ",
+                        div {
+                            style: "flex: 1; min-width: 0;",
+                            div { style: "color: {colors.text_primary};", "{source.label()}" }
+                            div {
+                                style: "font-size: 12px; color: {colors.text_muted};",
+                                "{source_hint(source)}"
+                            }
+                        }
+                        Button {
+                            variant: ButtonVariant::Secondary,
+                            disabled: busy(),
+                            onclick: move |_| read_export(source, true),
+                            "Folder..."
+                        }
+                        if source != ImportSource::AppleNotes {
+                            Button {
+                                variant: ButtonVariant::Ghost,
+                                disabled: busy(),
+                                onclick: move |_| read_export(source, false),
+                                "File..."
+                            }
+                        }
+                    }
+                }
+            }
+            if busy() {
+                div { style: "font-size: 12px; color: {colors.text_muted};", "Reading export..." }
+            }
+        },
+        ImportStep::Preview(batch) => {
+            let note_count = batch.notes.len();
+            let media_count = batch.media_count();
+            let source_label = batch.source.label();
+            let titles: Vec<String> = batch
+                .notes
+                .iter()
+                .take(PREVIEW_LIMIT)
+                .map(|note| preview_title(&note.content))
+                .collect();
+            let remaining = note_count.saturating_sub(PREVIEW_LIMIT);
+            let skipped = batch.skipped.clone();
+            let media_available =
+                state.media_api_client.read().is_some() && state.auth_session.read().is_some();
+            let batch_for_import = batch.clone();
+            rsx! {
+                DialogDescription {
+                    "Found {note_count} notes and {media_count} media files in the {source_label} export."
+                }
+                div {
+                    style: "
+                        display: flex;
+                        flex-direction: column;
+                        gap: 4px;
+                        margin: 12px 0;
+                        max-height: 240px;
+                        overflow-y: auto;
+                    ",
+                    for (index, title) in titles.into_iter().enumerate() {
+                        div {
+                            key: "{index}",
+                            style: "
+                                overflow: hidden;
+                                text-overflow: ellipsis;
+                                white-space: nowrap;
+                                color: {colors.text_primary};
+                            ",
+                            "{title}"
+                        }
+                    }
+                    if remaining > 0 {
+                        div { style: "color: {colors.text_muted};", "...and {remaining} more" }
+                    }
+                    for (index, skip) in skipped.into_iter().enumerate() {
+                        div {
+                            key: "skip-{index}",
+                            style: "font-size: 12px; color: {colors.text_muted};",
+                            "Skipping {skip.entry}: {skip.reason}"
+                        }
+                    }
+                }
+                if media_count > 0 && !media_available {
+                    div {
+                        style: "font-size: 12px; color: {colors.text_muted}; margin-bottom: 8px;",
+                        "Sign in to upload media as attachments; notes will be imported without it."
+                    }
+                }
+                div {
+                    style: "display: flex; justify-content: flex-end; gap: 8px;",
+                    Button {
+                        variant: ButtonVariant::Ghost,
+                        disabled: busy(),
+                        onclick: move |_| step.set(ImportStep::ChooseSource),
+                        "Back"
+                    }
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        disabled: busy() || note_count == 0,
+                        onclick: move |_| run_import(batch_for_import.clone()),
+                        if busy() {
+                            "Importing..."
+                        } else {
+                            "Import {note_count} notes"
+                        }
+                    }
+                }
+            }
+        }
+        ImportStep::Done(summary) => rsx! {
+            DialogDescription {
+                "Imported {summary.notes_imported} notes and uploaded {summary.media_uploaded} attachments."
+            }
+            if summary.media_skipped > 0 {
+                div {
+                    style: "font-size: 12px; color: {colors.text_muted}; margin: 8px 0;",
+                    "{summary.media_skipped} media files were not uploaded."
+                }
+            }
+            for (index, failure) in summary.failures.into_iter().enumerate() {
+                div {
+                    key: "{index}",
+                    style: "font-size: 12px; color: {colors.text_muted};",
+                    "{failure}"
+                }
+            }
+            div {
+                style: "display: flex; justify-content: flex-end; margin-top: 12px;",
+                Button {
+                    variant: ButtonVariant::Primary,
+                    onclick: move |_| import_open.set(false),
+                    "Done"
+                }
+            }
+        },
+    };
+
+    rsx! {
+        DialogRoot {
+            open: true,
+            on_open_change: move |open: bool| {
+                if !open && !busy() {
+                    import_open.set(false);
+                }
+            },
+
+            DialogContent {
+                style: "width: 480px; max-width: 90vw; text-align: left;",
+
+                DialogTitle { "Import notes" }
+
+                {body}
+
+                if let Some(message) = error() {
+                    div {
+                        style: "font-size: 12px; color: {colors.error}; margin-top: 8px;",
+                        "{message}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+const fn source_hint(source: ImportSource) -> &'static str {
+    match source {
+        ImportSource::Keep => "Google Takeout Keep folder or zip",
+        ImportSource::Simplenote => "Simplenote export zip or notes.json",
+        ImportSource::AppleNotes => "Folder of exported text, Markdown, or HTML notes",
+        ImportSource::Markdown => "Obsidian vault, Markdown folder, or Dirt Markdown export",
+        ImportSource::Json => "Dirt JSON export file or per-note export folder",
+    }
+}
+
+/// File types offered by the file picker for `source`
+const fn file_extensions(source: ImportSource) -> &'static [&'static str] {
+    match source {
+        ImportSource::Markdown => &["md", "markdown", "zip"],
+        _ => &["zip", "json"],
+    }
+}
+
+fn preview_title(content: &str) -> String {
+    content
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("Untitled")
+        .trim()
+        .chars()
+        .take(PREVIEW_TITLE_CHARS)
+        .collect()
+}
@@ -0,0 +1,130 @@
+//! Links view: every URL captured in notes, newest first
+
+use dioxus::prelude::*;
+
+use super::button::{Button, ButtonVariant};
+use crate::state::AppState;
+
+/// Maximum number of links loaded into the view
+const LINKS_LIMIT: usize = 500;
+const PREVIEW_LENGTH: usize = 60;
+
+/// List of captured links; clicking a note preview opens the note
+#[component]
+pub fn LinksView() -> Element {
+    let mut state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+
+    let links = use_resource(move || {
+        let db = state.db_service.read().clone();
+        // Reload whenever notes change so new links show up immediately
+        let _ = state.notes.read().len();
+        async move {
+            let db = db.ok_or_else(|| "Database not initialized".to_string())?;
+            db.list_urls(None, LINKS_LIMIT)
+                .await
+                .map_err(|error| error.to_string())
+        }
+    });
+
+    let close_links = move |_: MouseEvent| {
+        state.links_open.set(false);
+    };
+
+    let body = match &*links.read() {
+        None => rsx! {
+            div {
+                style: "padding: 24px; color: {colors.text_muted};",
+                "Loading links..."
+            }
+        },
+        Some(Err(error)) => rsx! {
+            div {
+                style: "padding: 24px; color: {colors.error};",
+                "Failed to load links: {error}"
+            }
+        },
+        Some(Ok(links)) if links.is_empty() => rsx! {
+            div {
+                style: "padding: 24px; color: {colors.text_muted};",
+                "No links captured yet. URLs in your notes show up here."
+            }
+        },
+        Some(Ok(links)) => {
+            let rows = links
+                .iter()
+                .map(|link| {
+                    (
+                        link.url.clone(),
+                        link.note.id,
+                        link.note.title_preview(PREVIEW_LENGTH),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            rsx! {
+                div {
+                    style: "flex: 1; overflow-y: auto;",
+                    for (index, (url, note_id, preview)) in rows.into_iter().enumerate() {
+                        div {
+                            key: "link-{index}",
+                            style: "
+                                display: flex;
+                                flex-direction: column;
+                                gap: 2px;
+                                padding: 8px 12px;
+                                border-bottom: 1px solid {colors.border_light};
+                            ",
+                            a {
+                                href: "{url}",
+                                target: "_blank",
+                                style: "color: {colors.accent}; font-size: 13px; word-break: break-all;",
+                                "{url}"
+                            }
+                            span {
+                                style: "font-size: 12px; color: {colors.text_muted}; cursor: pointer;",
+                                onclick: move |_| {
+                                    state.current_note_id.set(Some(note_id));
+                                    state.links_open.set(false);
+                                },
+                                "{preview}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: "links-view",
+            style: "
+                flex: 1;
+                display: flex;
+                flex-direction: column;
+                overflow: hidden;
+                background: {colors.bg_secondary};
+            ",
+
+            div {
+                style: "
+                    display: flex;
+                    align-items: center;
+                    gap: 8px;
+                    padding: 8px 12px;
+                    border-bottom: 1px solid {colors.border_light};
+                ",
+                span { style: "font-weight: 600;", "Links" }
+                div { style: "flex: 1;" }
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    onclick: close_links,
+                    "Close"
+                }
+            }
+
+            {body}
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Startup dialog shown while database migrations run
+
+use dioxus::prelude::*;
+
+use dirt_core::db::{subscribe_migration_progress, MigrationProgress};
+
+use super::dialog::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
+use crate::state::AppState;
+
+/// Modal progress bar for long schema migrations
+///
+/// Migrations run while the database opens, before any note is shown. The
+/// dialog follows the progress reported by `dirt-core` and closes itself once
+/// the schema is current; interrupted steps resume on the next launch.
+#[component]
+pub fn MigrationProgressDialog() -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut progress = use_signal(|| None::<MigrationProgress>);
+
+    use_future(move || async move {
+        let mut receiver = subscribe_migration_progress();
+        loop {
+            let latest = *receiver.borrow_and_update();
+            progress.set(latest.filter(|progress| !progress.is_finished()));
+            if receiver.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let Some(current) = progress() else {
+        return rsx! {};
+    };
+    let percent = current.percent();
+    let description = current.description();
+
+    rsx! {
+        DialogRoot {
+            open: true,
+            DialogContent {
+                style: "width: 400px; max-width: 90vw; text-align: left;",
+
+                DialogTitle { "Updating your notes database" }
+                DialogDescription {
+                    "Dirt is upgrading its storage. This runs once and picks up where it left off if Dirt is closed."
+                }
+
+                div {
+                    style: "display: flex; flex-direction: column; gap: 6px; margin-top: 12px;",
+                    div {
+                        style: "
+                            height: 8px;
+                            border-radius: 4px;
+                            background: {colors.bg_tertiary};
+                            overflow: hidden;
+                        ",
+                        div {
+                            style: "
+                                width: {percent}%;
+                                height: 100%;
+                                background: {colors.accent};
+                                transition: width 0.2s ease;
+                            ",
+                        }
+                    }
+                    div {
+                        style: "display: flex; justify-content: space-between; font-size: 12px; color: {colors.text_secondary};",
+                        span { "{description}" }
+                        if let Some((done, total)) = current.rows {
+                            span { "{done} / {total} notes" }
+                        } else {
+                            span { "{percent}%" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -2,25 +2,45 @@
 //!
 //! Reusable UI components for the desktop application.
 
+mod draft_recovery;
+mod export_dialog;
+mod graph_view;
+mod import_wizard;
+mod links_view;
+mod migration_progress;
 mod note_actions;
 mod note_card;
 mod note_editor;
 mod note_list;
 mod quick_capture;
+mod read_only_banner;
 mod search_bar;
+mod session_expired;
 mod settings;
 mod sidebar;
 mod toolbar;
+mod update_required_banner;
+mod whats_new;
 
-pub use note_actions::create_note_optimistic;
+pub use draft_recovery::DraftRecovery;
+pub use export_dialog::ExportDialog;
+pub use graph_view::GraphView;
+pub use import_wizard::ImportWizard;
+pub use links_view::LinksView;
+pub use migration_progress::MigrationProgressDialog;
+pub use note_actions::{capture_file_to_inbox, capture_text, create_note_optimistic};
 pub use note_card::NoteCard;
 pub use note_editor::NoteEditor;
 pub use note_list::NoteList;
-pub use quick_capture::QuickCapture;
+pub use quick_capture::show_quick_capture_window;
+pub use read_only_banner::ReadOnlyDatabaseBanner;
 pub use search_bar::SearchBar;
+pub use session_expired::{ReauthDialog, SessionExpiredBanner};
 pub use settings::SettingsPanel;
 pub use sidebar::Sidebar;
 pub use toolbar::Toolbar;
+pub use update_required_banner::UpdateRequiredBanner;
+pub use whats_new::WhatsNewDialog;
 pub mod button;
 pub mod card;
 pub mod dialog;
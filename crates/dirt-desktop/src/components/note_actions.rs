@@ -1,15 +1,39 @@
 //! Shared note actions used by UI components.
 
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
 use dioxus::prelude::*;
-use dirt_core::models::Note;
+use dirt_core::capture::{append_to_daily_note, run_post_capture, PostCaptureActions};
+use dirt_core::media::capture_attachment;
+use dirt_core::models::{Note, NoteId, NoteSource};
+use rfd::{AsyncFileDialog, AsyncMessageDialog, MessageLevel};
 
 use crate::queries::invalidate_notes_query;
+use crate::services::{AuthSession, DatabaseService, MediaApiClient, SystemClipboard};
 use crate::state::AppState;
+use crate::tray::SHOW_MAIN_WINDOW;
 
 /// Create a new note with optimistic UI update and background persistence.
 pub fn create_note_optimistic(state: &mut AppState) {
+    create_note_optimistic_from(
+        state,
+        "",
+        NoteSource::Desktop,
+        PostCaptureActions::default(),
+    );
+}
+
+/// Create a note holding `content` with an optimistic UI update, then persist
+/// it and run the post-capture `actions` in the background.
+pub fn create_note_optimistic_from(
+    state: &mut AppState,
+    content: &str,
+    source: NoteSource,
+    actions: PostCaptureActions,
+) {
     // Create optimistic note with client-generated ID (UUID v7)
-    let optimistic_note = Note::new("");
+    let optimistic_note = Note::new(content);
     let note_id = optimistic_note.id;
 
     // Update UI immediately (optimistic)
@@ -23,13 +47,149 @@ pub fn create_note_optimistic(state: &mut AppState) {
     let db = state.db_service.read().clone();
     spawn(async move {
         if let Some(db) = db {
-            if let Err(e) = db.create_note_with_id(&optimistic_note).await {
-                tracing::error!("Failed to persist note: {}", e);
-                // Note: Don't rollback - user can continue editing
-            } else {
-                // Invalidate query to sync state
-                invalidate_notes_query().await;
+            match db.create_note_with_id_from(&optimistic_note, source).await {
+                Ok(note) => {
+                    run_capture_actions(&db, &actions, note, source).await;
+                    // Invalidate query to sync state
+                    invalidate_notes_query().await;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to persist note: {}", e);
+                    // Note: Don't rollback - user can continue editing
+                }
             }
         }
     });
 }
+
+/// Save captured text as a new note and run the configured post-capture actions.
+///
+/// Used by quick capture and by text shared from other apps, neither of which
+/// has a window to report failures in, so errors are only logged.
+pub async fn capture_text(
+    db: Arc<DatabaseService>,
+    text: String,
+    source: NoteSource,
+    actions: PostCaptureActions,
+) {
+    match db.create_note_from(&text, source).await {
+        Ok(note) => {
+            tracing::info!("Captured note {} from {}", note.id, source.as_str());
+            run_capture_actions(&db, &actions, note, source).await;
+            invalidate_notes_query().await;
+        }
+        Err(e) => {
+            tracing::error!("Failed to create note: {}", e);
+        }
+    }
+}
+
+/// Run the post-capture `actions` on a newly saved capture, logging failures.
+async fn run_capture_actions(
+    db: &DatabaseService,
+    actions: &PostCaptureActions,
+    note: Note,
+    source: NoteSource,
+) {
+    if actions.is_empty() {
+        return;
+    }
+    let today = chrono::Local::now().date_naive();
+    let report =
+        run_post_capture(db, actions, note, source, today, Some(&mut SystemClipboard)).await;
+    for failure in &report.failures {
+        tracing::warn!("Post-capture action failed: {}", failure);
+    }
+}
+
+/// Append captured text to today's daily note instead of creating a note.
+///
+/// Like [`capture_text`], failures are only logged.
+pub async fn capture_text_to_daily_note(
+    db: Arc<DatabaseService>,
+    text: String,
+    source: NoteSource,
+) {
+    let today = chrono::Local::now().date_naive();
+    match append_to_daily_note(&db, &text, source, today).await {
+        Ok(note) => {
+            tracing::info!("Appended capture to daily note {}", note.id);
+            invalidate_notes_query().await;
+        }
+        Err(e) => {
+            tracing::error!("Failed to append to daily note: {}", e);
+        }
+    }
+}
+
+/// Pick a file and capture it as a new `#inbox` note with the file attached.
+///
+/// On success the main window is shown with the new note selected; failures
+/// are reported in a message dialog since the window may be hidden.
+pub async fn capture_file_to_inbox(
+    db: Option<Arc<DatabaseService>>,
+    media_api: Option<Arc<MediaApiClient>>,
+    auth_session: Option<AuthSession>,
+    mut current_note_id: Signal<Option<NoteId>>,
+) {
+    let Some(file) = AsyncFileDialog::new()
+        .set_title("Capture file to inbox")
+        .pick_file()
+        .await
+    else {
+        return;
+    };
+    let file_name = file.file_name();
+    let bytes = file.read().await;
+
+    match upload_to_inbox(db, media_api, auth_session, &file_name, &bytes).await {
+        Ok(note_id) => {
+            tracing::info!("Captured {} into inbox note {}", file_name, note_id);
+            invalidate_notes_query().await;
+            current_note_id.set(Some(note_id));
+            SHOW_MAIN_WINDOW.store(true, Ordering::SeqCst);
+        }
+        Err(error) => {
+            tracing::error!("Failed to capture {} into inbox: {}", file_name, error);
+            AsyncMessageDialog::new()
+                .set_level(MessageLevel::Error)
+                .set_title("Capture failed")
+                .set_description(format!("Could not capture {file_name}: {error}"))
+                .show()
+                .await;
+        }
+    }
+}
+
+async fn upload_to_inbox(
+    db: Option<Arc<DatabaseService>>,
+    media_api: Option<Arc<MediaApiClient>>,
+    auth_session: Option<AuthSession>,
+    file_name: &str,
+    bytes: &[u8],
+) -> Result<NoteId, String> {
+    let db = db.ok_or_else(|| "Database service is not available.".to_string())?;
+    let media_api =
+        media_api.ok_or_else(|| "Cloud media is not configured for this build.".to_string())?;
+    let access_token = auth_session
+        .map(|session| session.access_token)
+        .filter(|token| !token.trim().is_empty())
+        .ok_or_else(|| "Sign in is required for cloud attachment operations.".to_string())?;
+    let mime_type = mime_guess::from_path(file_name)
+        .first_or_octet_stream()
+        .essence_str()
+        .to_string();
+
+    let captured = capture_attachment(
+        &db,
+        &media_api,
+        &access_token,
+        file_name,
+        &mime_type,
+        bytes,
+        NoteSource::Desktop,
+    )
+    .await
+    .map_err(|error| error.to_string())?;
+    Ok(captured.note.id)
+}
@@ -6,14 +6,18 @@ use dioxus::html::HasFileData;
 use dioxus::prelude::*;
 use rfd::AsyncFileDialog;
 
+use dirt_core::attachment_refs::parse_attachment_refs;
+use dirt_core::media::{CancellationToken, UploadProgress};
 use dirt_core::models::{Attachment, AttachmentId};
 use dirt_core::NoteId;
 
 use super::attachment_preview::{
-    attachment_kind_label, format_attachment_size, render_preview_content, AttachmentPreview,
+    attachment_kind_label, build_pdf_preview, format_attachment_size, render_preview_content,
+    AttachmentPreview,
 };
 use super::attachment_utils::{
-    delete_remote_attachment, list_attachments_with_retry, load_attachment_preview,
+    content_with_attachment_ref, delete_remote_attachment, list_attachments_with_retry,
+    load_attachment_preview, missing_attachment_refs, refresh_stream_url, save_attachment_as,
     upload_attachment, UploadContext, UploadSignals,
 };
 use super::transcription::{
@@ -41,18 +45,23 @@ pub(super) fn AttachmentPanel(
     let mut last_note_id = use_signal(|| None::<NoteId>);
     let mut attachment_upload_error = use_signal(|| None::<String>);
     let attachment_uploading = use_signal(|| false);
+    let attachment_upload_progress = use_signal(|| None::<UploadProgress>);
+    let attachment_upload_cancel = use_signal(|| None::<CancellationToken>);
     let attachments = use_signal(Vec::<Attachment>::new);
     let mut attachments_error = use_signal(|| None::<String>);
     let attachments_loading = use_signal(|| false);
     let attachment_refresh_version = use_signal(|| 0u64);
     let attachment_load_request_id = use_hook(|| Arc::new(AtomicU64::new(0)));
     let mut deleting_attachment_id = use_signal(|| None::<AttachmentId>);
+    let mut saving_attachment_id = use_signal(|| None::<AttachmentId>);
+    let mut attachment_saved_message = use_signal(|| None::<String>);
     let mut drag_over = use_signal(|| false);
     let mut preview_open = use_signal(|| false);
     let mut preview_loading = use_signal(|| false);
     let mut preview_title = use_signal(String::new);
     let mut preview_error = use_signal(|| None::<String>);
     let mut preview_content = use_signal(AttachmentPreview::default);
+    let mut preview_attachment = use_signal(|| None::<Attachment>);
     let mut voice_memo_state = use_signal(VoiceMemoRecorderState::default);
     let mut voice_memo_started_at = use_signal(|| None::<Instant>);
 
@@ -62,6 +71,7 @@ pub(super) fn AttachmentPanel(
     if note_id != *last_note_id.peek() {
         last_note_id.set(note_id);
         deleting_attachment_id.set(None);
+        attachment_saved_message.set(None);
         let recorder_state = *voice_memo_state.peek();
         let should_discard = matches!(
             recorder_state,
@@ -192,6 +202,8 @@ pub(super) fn AttachmentPanel(
         let mut upload_error = attachment_upload_error;
         let signals = UploadSignals {
             uploading: attachment_uploading,
+            progress: attachment_upload_progress,
+            cancel: attachment_upload_cancel,
             upload_error,
             attachment_refresh_signal: attachment_refresh_version,
         };
@@ -199,6 +211,7 @@ pub(super) fn AttachmentPanel(
             db: state.db_service.read().clone(),
             media_api: state.media_api_client.read().clone(),
             auth_session: (state.auth_session)(),
+            connectivity: state.connectivity.read().clone(),
             signals,
         };
 
@@ -238,6 +251,8 @@ pub(super) fn AttachmentPanel(
         let mut upload_error = attachment_upload_error;
         let signals = UploadSignals {
             uploading: attachment_uploading,
+            progress: attachment_upload_progress,
+            cancel: attachment_upload_cancel,
             upload_error,
             attachment_refresh_signal: attachment_refresh_version,
         };
@@ -245,6 +260,7 @@ pub(super) fn AttachmentPanel(
             db: state.db_service.read().clone(),
             media_api: state.media_api_client.read().clone(),
             auth_session: (state.auth_session)(),
+            connectivity: state.connectivity.read().clone(),
             signals,
         };
 
@@ -333,6 +349,8 @@ pub(super) fn AttachmentPanel(
 
         let signals = UploadSignals {
             uploading: attachment_uploading,
+            progress: attachment_upload_progress,
+            cancel: attachment_upload_cancel,
             upload_error: attachment_upload_error,
             attachment_refresh_signal: attachment_refresh_version,
         };
@@ -340,6 +358,7 @@ pub(super) fn AttachmentPanel(
             db: state.db_service.read().clone(),
             media_api: state.media_api_client.read().clone(),
             auth_session: (state.auth_session)(),
+            connectivity: state.connectivity.read().clone(),
             signals,
         };
         let transcription_enabled = (state.settings)().voice_memo_transcription_enabled;
@@ -427,7 +446,17 @@ pub(super) fn AttachmentPanel(
         "transparent"
     };
     let attachment_items = attachments();
+    let referenced_ids: Vec<String> = parse_attachment_refs(&editor_content)
+        .into_iter()
+        .map(|reference| reference.attachment_id)
+        .collect();
+    let missing_refs = if attachments_loading() {
+        String::new()
+    } else {
+        missing_attachment_refs(&editor_content, &attachment_items).join(", ")
+    };
     let active_deleting_attachment = deleting_attachment_id();
+    let active_saving_attachment = saving_attachment_id();
     let voice_memo_state_value = voice_memo_state();
     let voice_memo_status = match voice_memo_state_value {
         VoiceMemoRecorderState::Idle => None,
@@ -442,12 +471,63 @@ pub(super) fn AttachmentPanel(
         VoiceMemoRecorderState::Stopping => Some("Finalizing voice memo...".to_string()),
     };
 
+    let on_pdf_page = move |page_index: u16| {
+        let AttachmentPreview::PdfPage { bytes, .. } = preview_content() else {
+            return;
+        };
+        preview_loading.set(true);
+        spawn(async move {
+            match tokio::task::spawn_blocking(move || build_pdf_preview(bytes, page_index)).await {
+                Ok(preview) => preview_content.set(preview),
+                Err(error) => preview_error.set(Some(format!("Failed to render page: {error}"))),
+            }
+            preview_loading.set(false);
+        });
+    };
+
+    // Streamed previews can outlive their signed link; renew it when playback
+    // fails because the link expired.
+    let on_stream_error = move |()| {
+        let AttachmentPreview::MediaStream { mime_type, url } = preview_content() else {
+            return;
+        };
+        let now = chrono::Utc::now().timestamp();
+        if url.expires_at.is_none() || !url.needs_refresh(now) {
+            preview_error.set(Some(
+                "Playback failed. The file may use a format this app cannot play.".to_string(),
+            ));
+            return;
+        }
+        let Some(attachment) = preview_attachment() else {
+            return;
+        };
+        let media_api = state.media_api_client.read().clone();
+        let auth_session = (state.auth_session)();
+        spawn(async move {
+            match refresh_stream_url(&attachment, media_api, auth_session).await {
+                Ok(url) => preview_content.set(AttachmentPreview::MediaStream { mime_type, url }),
+                Err(error) => preview_error.set(Some(error)),
+            }
+        });
+    };
+
     rsx! {
         if note_id.is_some() {
             if attachment_uploading() {
                 div {
-                    style: "margin-bottom: 8px; color: {colors.text_muted}; font-size: 12px;",
-                    "Uploading attachment..."
+                    style: "margin-bottom: 8px; display: flex; align-items: center; gap: 8px; color: {colors.text_muted}; font-size: 12px;",
+                    if let Some(progress) = attachment_upload_progress() {
+                        "Uploading attachment... {progress.percent()}%"
+                    } else {
+                        "Uploading attachment..."
+                    }
+                    if let Some(cancel) = attachment_upload_cancel() {
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: move |_| cancel.cancel(),
+                            "Cancel"
+                        }
+                    }
                 }
             }
 
@@ -472,6 +552,20 @@ pub(super) fn AttachmentPanel(
                 }
             }
 
+            if !missing_refs.is_empty() {
+                div {
+                    style: "margin-bottom: 8px; color: {colors.error}; font-size: 12px;",
+                    "Inline references to missing attachments: {missing_refs}"
+                }
+            }
+
+            if let Some(message) = attachment_saved_message() {
+                div {
+                    style: "margin-bottom: 8px; color: {colors.text_muted}; font-size: 12px;",
+                    "{message}"
+                }
+            }
+
             div {
                 style: "
                     margin-top: 8px;
@@ -572,6 +666,25 @@ pub(super) fn AttachmentPanel(
                                     style: "color: {colors.text_muted}; white-space: nowrap; flex-shrink: 0;",
                                     "{attachment_kind_label(&attachment.filename, &attachment.mime_type)}"
                                 }
+                                if referenced_ids.contains(&attachment.id.to_string()) {
+                                    span {
+                                        style: "color: {colors.text_muted}; white-space: nowrap; flex-shrink: 0;",
+                                        "inline"
+                                    }
+                                }
+                                if let Some(scan_label) = attachment.scan_status.label() {
+                                    span {
+                                        style: format!(
+                                            "color: {}; white-space: nowrap; flex-shrink: 0;",
+                                            if attachment.scan_status.is_downloadable() {
+                                                colors.text_muted
+                                            } else {
+                                                colors.error
+                                            }
+                                        ),
+                                        "{scan_label}"
+                                    }
+                                }
                             }
                             span {
                                 style: "color: {colors.text_muted}; white-space: nowrap; flex-shrink: 0;",
@@ -582,6 +695,18 @@ pub(super) fn AttachmentPanel(
                                 Button {
                                     variant: ButtonVariant::Ghost,
                                     style: "padding: 2px 8px; font-size: 11px;",
+                                    title: "Insert an inline reference at the end of the note",
+                                    onclick: {
+                                        let updated_content =
+                                            content_with_attachment_ref(&editor_content, &attachment);
+                                        move |_| on_editor_content_change.call(updated_content.clone())
+                                    },
+                                    "Insert"
+                                }
+                                Button {
+                                    variant: ButtonVariant::Ghost,
+                                    style: "padding: 2px 8px; font-size: 11px;",
+                                    disabled: !attachment.scan_status.is_downloadable(),
                                     onclick: {
                                         let attachment = attachment.clone();
                                         move |_| {
@@ -591,16 +716,19 @@ pub(super) fn AttachmentPanel(
                                             preview_error.set(None);
                                             preview_title.set(attachment.filename.clone());
                                             preview_content.set(AttachmentPreview::None);
+                                            preview_attachment.set(Some(attachment.clone()));
 
                                             let mut preview_loading_signal = preview_loading;
                                             let mut preview_error_signal = preview_error;
                                             let mut preview_content_signal = preview_content;
                                             let attachment = attachment.clone();
+                                            let db = state.db_service.read().clone();
                                             let media_api = state.media_api_client.read().clone();
                                             let auth_session = (state.auth_session)();
+                                            let settings = (state.settings)();
 
                                             spawn(async move {
-                                                match load_attachment_preview(&attachment, media_api, auth_session).await {
+                                                match load_attachment_preview(&attachment, db, media_api, auth_session, &settings).await {
                                                     Ok(preview) => preview_content_signal.set(preview),
                                                     Err(error) => preview_error_signal.set(Some(error)),
                                                 }
@@ -610,6 +738,48 @@ pub(super) fn AttachmentPanel(
                                     },
                                     "Open"
                                 }
+                                Button {
+                                    variant: ButtonVariant::Ghost,
+                                    style: "padding: 2px 8px; font-size: 11px;",
+                                    disabled: !attachment.scan_status.is_downloadable()
+                                        || active_saving_attachment == Some(attachment.id),
+                                    onclick: {
+                                        let attachment = attachment.clone();
+                                        move |_| {
+                                            let mut saving_signal = saving_attachment_id;
+                                            let mut attachment_error_signal = attachments_error;
+                                            let mut saved_message_signal = attachment_saved_message;
+                                            let attachment = attachment.clone();
+                                            let db = state.db_service.read().clone();
+                                            let media_api = state.media_api_client.read().clone();
+                                            let auth_session = (state.auth_session)();
+                                            let settings = (state.settings)();
+
+                                            spawn(async move {
+                                                attachment_error_signal.set(None);
+                                                saved_message_signal.set(None);
+                                                saving_signal.set(Some(attachment.id));
+
+                                                match save_attachment_as(&attachment, db, media_api, auth_session, &settings).await {
+                                                    Ok(Some(path)) => saved_message_signal.set(Some(format!(
+                                                        "Saved {} to {}",
+                                                        attachment.filename,
+                                                        path.display()
+                                                    ))),
+                                                    Ok(None) => {}
+                                                    Err(error) => attachment_error_signal.set(Some(error)),
+                                                }
+
+                                                saving_signal.set(None);
+                                            });
+                                        }
+                                    },
+                                    if active_saving_attachment == Some(attachment.id) {
+                                        "Saving..."
+                                    } else {
+                                        "Save as..."
+                                    }
+                                }
                                 Button {
                                     variant: ButtonVariant::Ghost,
                                     style: "padding: 2px 8px; font-size: 11px;",
@@ -711,7 +881,7 @@ pub(super) fn AttachmentPanel(
                                 "{error}"
                             }
                         } else {
-                            {render_preview_content(preview_content(), &preview_title(), colors)}
+                            {render_preview_content(preview_content(), &preview_title(), colors, EventHandler::new(on_pdf_page), EventHandler::new(on_stream_error))}
                         }
                     }
                 }
@@ -1,8 +1,13 @@
+use std::sync::Arc;
+
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine as _;
 use dioxus::prelude::*;
+use dirt_core::media::SignedUrl;
+use dirt_core::storage::{render_pdf_page, ThumbnailFormat, ThumbnailOptions};
 
 use super::attachment_utils::{file_size_i64, infer_attachment_mime_type};
+use crate::components::button::{Button, ButtonVariant};
 use crate::theme::ColorPalette;
 
 const KIB_BYTES: u64 = 1024;
@@ -10,6 +15,12 @@ const MIB_BYTES: u64 = KIB_BYTES * 1024;
 const GIB_BYTES: u64 = MIB_BYTES * 1024;
 const MAX_TEXT_PREVIEW_BYTES: usize = 256 * 1024;
 const MAX_MEDIA_PREVIEW_BYTES: usize = 8 * 1024 * 1024;
+const PDF_PAGE_OPTIONS: ThumbnailOptions = ThumbnailOptions {
+    max_width: 1200,
+    max_height: 1600,
+    format: ThumbnailFormat::Png,
+    jpeg_quality: 80,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(super) enum AttachmentKind {
@@ -17,6 +28,7 @@ pub(super) enum AttachmentKind {
     Video,
     Audio,
     Text,
+    Pdf,
     File,
 }
 
@@ -32,6 +44,18 @@ pub(super) enum AttachmentPreview {
         mime_type: String,
         data_uri: String,
     },
+    /// Large audio/video played straight from a signed storage URL
+    MediaStream {
+        mime_type: String,
+        url: SignedUrl,
+    },
+    PdfPage {
+        /// Source PDF, kept to render other pages on navigation
+        bytes: Arc<[u8]>,
+        page_index: u16,
+        page_count: u16,
+        data_uri: String,
+    },
     Unsupported {
         mime_type: String,
         reason: String,
@@ -42,6 +66,8 @@ pub(super) fn render_preview_content(
     preview: AttachmentPreview,
     preview_title: &str,
     colors: &ColorPalette,
+    on_pdf_page: EventHandler<u16>,
+    on_stream_error: EventHandler<()>,
 ) -> Element {
     match preview {
         AttachmentPreview::None => rsx! {
@@ -88,6 +114,61 @@ pub(super) fn render_preview_content(
                 }
             }
         },
+        AttachmentPreview::MediaStream { mime_type, url } => rsx! {
+            if mime_type.starts_with("video/") {
+                video {
+                    src: "{url.url}",
+                    controls: true,
+                    preload: "metadata",
+                    onerror: move |_| on_stream_error.call(()),
+                    style: "display: block; width: 100%; max-height: 56vh; border-radius: 6px;",
+                }
+            } else {
+                audio {
+                    src: "{url.url}",
+                    controls: true,
+                    preload: "metadata",
+                    onerror: move |_| on_stream_error.call(()),
+                    style: "width: 100%;",
+                }
+            }
+        },
+        AttachmentPreview::PdfPage {
+            page_index,
+            page_count,
+            data_uri,
+            ..
+        } => {
+            let page_number = page_index + 1;
+            rsx! {
+                div {
+                    style: "display: flex; flex-direction: column; gap: 8px;",
+                    img {
+                        src: "{data_uri}",
+                        alt: "{preview_title}, page {page_number}",
+                        style: "display: block; max-width: 100%; max-height: 52vh; margin: 0 auto; border-radius: 6px; background: white;",
+                    }
+                    if page_count > 1 {
+                        div {
+                            style: "display: flex; align-items: center; justify-content: center; gap: 12px; color: {colors.text_secondary};",
+                            Button {
+                                variant: ButtonVariant::Ghost,
+                                disabled: page_index == 0,
+                                onclick: move |_| on_pdf_page.call(page_index.saturating_sub(1)),
+                                "Previous"
+                            }
+                            span { "Page {page_number} of {page_count}" }
+                            Button {
+                                variant: ButtonVariant::Ghost,
+                                disabled: page_number >= page_count,
+                                onclick: move |_| on_pdf_page.call(page_number),
+                                "Next"
+                            }
+                        }
+                    }
+                }
+            }
+        }
         AttachmentPreview::Unsupported { mime_type, reason } => rsx! {
             div {
                 style: "display: flex; flex-direction: column; gap: 6px; color: {colors.text_secondary};",
@@ -125,6 +206,7 @@ pub(super) fn build_attachment_preview(
                 data_uri: format!("data:{mime_type};base64,{encoded}"),
             }
         }
+        AttachmentKind::Pdf => build_pdf_preview(Arc::from(bytes), 0),
         AttachmentKind::File => AttachmentPreview::Unsupported {
             mime_type: mime_type.to_string(),
             reason: "This file type does not have an in-app preview yet.".to_string(),
@@ -132,6 +214,34 @@ pub(super) fn build_attachment_preview(
     }
 }
 
+/// Whether an attachment is too large to inline and should be streamed from
+/// a signed URL instead.
+pub(super) fn streams_from_signed_url(file_name: &str, mime_type: &str, size_bytes: i64) -> bool {
+    matches!(
+        attachment_kind(file_name, mime_type),
+        AttachmentKind::Video | AttachmentKind::Audio
+    ) && usize::try_from(size_bytes).map_or(true, |size| size > MAX_MEDIA_PREVIEW_BYTES)
+}
+
+/// Render one page of a PDF; runs pdfium, so call off the UI thread.
+pub(super) fn build_pdf_preview(bytes: Arc<[u8]>, page_index: u16) -> AttachmentPreview {
+    match render_pdf_page(&bytes, page_index, PDF_PAGE_OPTIONS) {
+        Ok(page) => {
+            let encoded = BASE64_STANDARD.encode(&page.image.bytes);
+            AttachmentPreview::PdfPage {
+                bytes,
+                page_index: page.page_index,
+                page_count: page.page_count,
+                data_uri: format!("data:image/png;base64,{encoded}"),
+            }
+        }
+        Err(error) => AttachmentPreview::Unsupported {
+            mime_type: "application/pdf".to_string(),
+            reason: error.to_string(),
+        },
+    }
+}
+
 pub(super) fn decode_text_preview(bytes: &[u8]) -> (String, bool) {
     if bytes.len() <= MAX_TEXT_PREVIEW_BYTES {
         return (String::from_utf8_lossy(bytes).to_string(), false);
@@ -151,6 +261,8 @@ pub(super) fn attachment_kind(file_name: &str, mime_type: &str) -> AttachmentKin
         AttachmentKind::Audio
     } else if normalized_mime.starts_with("text/") {
         AttachmentKind::Text
+    } else if normalized_mime == "application/pdf" {
+        AttachmentKind::Pdf
     } else {
         AttachmentKind::File
     }
@@ -162,6 +274,7 @@ pub(super) fn attachment_kind_label(file_name: &str, mime_type: &str) -> &'stati
         AttachmentKind::Video => "video",
         AttachmentKind::Audio => "audio",
         AttachmentKind::Text => "text",
+        AttachmentKind::Pdf => "pdf",
         AttachmentKind::File => "file",
     }
 }
@@ -1,13 +1,27 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use dioxus::prelude::*;
-
-use dirt_core::models::Attachment;
+use rfd::AsyncFileDialog;
+
+use dirt_core::attachment_refs::{format_attachment_ref, parse_attachment_refs};
+use dirt_core::connectivity::ConnectivityMonitor;
+use dirt_core::media::{
+    media_object_key, CancellationToken, MediaError, SignedUrl, UploadProgress,
+};
+use dirt_core::models::{Attachment, AttachmentScanStatus, Settings};
+use dirt_core::search::attachment_search_text;
+use dirt_core::storage::{prepare_image_upload, ImagePrivacyOptions};
+use dirt_core::util::fs::{sanitize_file_name, write_atomic};
 use dirt_core::NoteId;
 
-use super::attachment_preview::{build_attachment_preview, AttachmentPreview};
-use crate::services::{AuthSession, DatabaseService, MediaApiClient};
+use super::attachment_preview::{
+    build_attachment_preview, streams_from_signed_url, AttachmentPreview,
+};
+use crate::services::{
+    attachment_download_dir, reveal_in_file_manager, AuthSession, DatabaseService, MediaApiClient,
+};
 
 const ATTACHMENT_LIST_MAX_ATTEMPTS: usize = 3;
 const ATTACHMENT_LIST_RETRY_DELAY_MS: u64 = 120;
@@ -15,6 +29,8 @@ const ATTACHMENT_LIST_RETRY_DELAY_MS: u64 = 120;
 #[derive(Clone, Copy)]
 pub(super) struct UploadSignals {
     pub uploading: Signal<bool>,
+    pub progress: Signal<Option<UploadProgress>>,
+    pub cancel: Signal<Option<CancellationToken>>,
     pub upload_error: Signal<Option<String>>,
     pub attachment_refresh_signal: Signal<u64>,
 }
@@ -24,6 +40,7 @@ pub(super) struct UploadContext {
     pub db: Option<Arc<DatabaseService>>,
     pub media_api: Option<Arc<MediaApiClient>>,
     pub auth_session: Option<AuthSession>,
+    pub connectivity: ConnectivityMonitor,
     pub signals: UploadSignals,
 }
 
@@ -37,6 +54,8 @@ pub(super) async fn upload_attachment(
     let mut uploading = context.signals.uploading;
     let mut upload_error = context.signals.upload_error;
     let mut attachment_refresh_signal = context.signals.attachment_refresh_signal;
+    let mut progress = context.signals.progress;
+    let mut cancel_signal = context.signals.cancel;
 
     uploading.set(true);
 
@@ -61,64 +80,316 @@ pub(super) async fn upload_attachment(
             return false;
         }
     };
-
-    let object_key = build_media_object_key(&note_id, &file_name);
-    let mime_type = infer_attachment_mime_type(file_content_type.as_deref(), &file_name);
-
-    if let Err(error) = media_api
-        .upload(&access_token, &object_key, &mime_type, file_bytes.as_ref())
+    if !context
+        .connectivity
+        .ensure_online(chrono::Utc::now().timestamp())
         .await
     {
-        upload_error.set(Some(format!("Failed to upload attachment: {error}")));
+        upload_error.set(Some(
+            "You're offline. Try the upload again once you're connected.".to_string(),
+        ));
         uploading.set(false);
         return false;
     }
 
-    if let Err(error) = db
-        .create_attachment(
-            &note_id,
-            &file_name,
-            &mime_type,
-            file_size_i64(file_bytes.len()),
+    let object_key = media_object_key(&note_id, &file_name);
+    let mime_type = infer_attachment_mime_type(file_content_type.as_deref(), &file_name);
+    let file_bytes = match prepare_attachment_bytes(&db, &file_bytes, &mime_type).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            upload_error.set(Some(format!("Failed to prepare attachment: {error}")));
+            uploading.set(false);
+            return false;
+        }
+    };
+    let size_bytes = file_size_i64(file_bytes.len());
+    let search_text = attachment_search_text(&mime_type, &file_bytes);
+    let file_bytes = match db.encrypt_attachment_bytes(file_bytes).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            upload_error.set(Some(format!("Failed to encrypt attachment: {error}")));
+            uploading.set(false);
+            return false;
+        }
+    };
+
+    let cancel = CancellationToken::new();
+    cancel_signal.set(Some(cancel.clone()));
+    let uploaded = media_api
+        .upload_with_progress(
+            &access_token,
             &object_key,
+            &mime_type,
+            file_bytes.as_ref(),
+            &cancel,
+            |update| progress.set(Some(update)),
         )
-        .await
-    {
-        upload_error.set(Some(format!("Failed to save attachment metadata: {error}")));
+        .await;
+    cancel_signal.set(None);
+    progress.set(None);
+    if let Err(error) = uploaded {
+        if matches!(error.cause(), MediaError::Network(_)) {
+            context
+                .connectivity
+                .probe(chrono::Utc::now().timestamp())
+                .await;
+        }
+        upload_error.set(Some(if error.is_cancelled() {
+            "Upload cancelled.".to_string()
+        } else {
+            media_error_message("Failed to upload attachment", &error)
+        }));
         uploading.set(false);
         return false;
     }
 
+    let attachment = match db
+        .create_attachment(&note_id, &file_name, &mime_type, size_bytes, &object_key)
+        .await
+    {
+        Ok(attachment) => attachment,
+        Err(error) => {
+            upload_error.set(Some(format!("Failed to save attachment metadata: {error}")));
+            uploading.set(false);
+            return false;
+        }
+    };
+    if let Err(error) = db
+        .set_attachment_text(&attachment.id, search_text.as_deref())
+        .await
+    {
+        tracing::warn!("Attachment text not indexed for search: {error}");
+    }
+
+    let scan_status = commit_uploaded_attachment(&db, &media_api, &access_token, &attachment).await;
     attachment_refresh_signal.set(attachment_refresh_signal() + 1);
     uploading.set(false);
+
+    if let Err(message) = scan_status {
+        upload_error.set(Some(message));
+        return false;
+    }
     true
 }
 
+/// Applies the image privacy settings to attachment bytes before upload.
+async fn prepare_attachment_bytes(
+    db: &DatabaseService,
+    bytes: &[u8],
+    mime_type: &str,
+) -> Result<Vec<u8>, dirt_core::Error> {
+    let settings = db.load_settings().await?;
+    prepare_image_upload(
+        bytes,
+        mime_type,
+        ImagePrivacyOptions::from_settings(&settings),
+    )
+}
+
+/// Decrypts downloaded attachment bytes that were uploaded encrypted.
+async fn decrypt_downloaded_bytes(
+    db: Option<&DatabaseService>,
+    bytes: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+    match db {
+        Some(db) => db
+            .decrypt_attachment_bytes(bytes)
+            .await
+            .map_err(|error| format!("Failed to decrypt attachment: {error}")),
+        None => Ok(bytes),
+    }
+}
+
+/// Asks the backend to scan a finished upload and records the verdict.
+///
+/// Older backends without a commit endpoint leave the attachment unscanned.
+/// Returns an error message when the scanner quarantined the object.
+async fn commit_uploaded_attachment(
+    db: &DatabaseService,
+    media_api: &MediaApiClient,
+    access_token: &str,
+    attachment: &Attachment,
+) -> Result<(), String> {
+    let result = match media_api.commit(access_token, &attachment.r2_key).await {
+        Ok(result) => result,
+        Err(MediaError::NotFound(_)) => {
+            tracing::debug!(
+                attachment_id = %attachment.id,
+                "Backend has no upload commit endpoint; leaving unscanned"
+            );
+            return Ok(());
+        }
+        Err(error) => {
+            tracing::warn!(
+                attachment_id = %attachment.id,
+                "Attachment upload commit failed; leaving unscanned: {}",
+                error
+            );
+            return Ok(());
+        }
+    };
+
+    if let Err(error) = db
+        .set_attachment_scan_status(&attachment.id, result.scan_status)
+        .await
+    {
+        tracing::warn!(
+            attachment_id = %attachment.id,
+            "Failed to record attachment scan status: {}",
+            error
+        );
+    }
+
+    if result.scan_status == AttachmentScanStatus::Quarantined {
+        return Err(quarantine_message(
+            &attachment.filename,
+            result.reason.as_deref(),
+        ));
+    }
+    Ok(())
+}
+
+/// User-facing message for a failed media operation, explaining failures the
+/// user can act on (an expired sign-in, a full quota, a dropped connection).
+fn media_error_message(failed_to: &str, error: &MediaError) -> String {
+    match error.cause() {
+        MediaError::Unauthorized(_) => {
+            format!("{failed_to}: your session has expired. Sign in again and retry.")
+        }
+        MediaError::NotFound(_) => format!("{failed_to}: the file is no longer in cloud storage."),
+        MediaError::Quota(_) => {
+            format!("{failed_to}: your attachment storage is full. See Settings > Media for usage.")
+        }
+        _ if error.is_transient() => format!("{failed_to}, try again shortly: {error}"),
+        _ => format!("{failed_to}: {error}"),
+    }
+}
+
+fn quarantine_message(file_name: &str, reason: Option<&str>) -> String {
+    match reason.map(str::trim).filter(|reason| !reason.is_empty()) {
+        Some(reason) => format!("'{file_name}' was quarantined by the content scanner: {reason}"),
+        None => format!("'{file_name}' was quarantined by the content scanner."),
+    }
+}
+
 pub(super) async fn load_attachment_preview(
     attachment: &Attachment,
+    db: Option<Arc<DatabaseService>>,
     media_api: Option<Arc<MediaApiClient>>,
     auth_session: Option<AuthSession>,
+    settings: &Settings,
 ) -> Result<AttachmentPreview, String> {
+    if !attachment.scan_status.is_downloadable() {
+        return Err(quarantine_message(&attachment.filename, None));
+    }
     let Some(media_api) = media_api else {
         return Err("Cloud media is not configured for this build.".to_string());
     };
     let access_token = require_media_access_token(auth_session)?;
+    // A signed link would stream ciphertext, so encrypted notes always
+    // download and decrypt their attachments.
+    let encrypted = match db.as_deref() {
+        Some(db) => db.is_encrypted().await,
+        None => false,
+    };
 
+    if !encrypted
+        && streams_from_signed_url(
+            &attachment.filename,
+            &attachment.mime_type,
+            attachment.size_bytes,
+        )
+    {
+        let url = media_api
+            .refresh_url(&access_token, &attachment.r2_key)
+            .await
+            .map_err(|error| media_error_message("Failed to get a playback link", &error))?;
+        return Ok(AttachmentPreview::MediaStream {
+            mime_type: infer_attachment_mime_type(
+                Some(attachment.mime_type.as_str()),
+                &attachment.filename,
+            ),
+            url,
+        });
+    }
+
+    let cache = DatabaseService::attachment_cache(settings);
     let (bytes, downloaded_content_type) = media_api
-        .download(&access_token, &attachment.r2_key)
+        .download_cached(cache.as_ref(), &access_token, &attachment.r2_key)
         .await
-        .map_err(|error| format!("Failed to download attachment: {error}"))?;
+        .map_err(|error| media_error_message("Failed to download attachment", &error))?;
+    let bytes = decrypt_downloaded_bytes(db.as_deref(), bytes).await?;
 
     let content_type_hint = downloaded_content_type
         .as_deref()
         .or(Some(attachment.mime_type.as_str()));
     let mime_type = infer_attachment_mime_type(content_type_hint, &attachment.filename);
 
-    Ok(build_attachment_preview(
-        &attachment.filename,
-        &mime_type,
-        &bytes,
-    ))
+    // PDF pages are rasterized by pdfium, which blocks.
+    let file_name = attachment.filename.clone();
+    tokio::task::spawn_blocking(move || build_attachment_preview(&file_name, &mime_type, &bytes))
+        .await
+        .map_err(|error| format!("Failed to build attachment preview: {error}"))
+}
+
+/// Sign a fresh playback link for a streamed preview whose link expired.
+pub(super) async fn refresh_stream_url(
+    attachment: &Attachment,
+    media_api: Option<Arc<MediaApiClient>>,
+    auth_session: Option<AuthSession>,
+) -> Result<SignedUrl, String> {
+    let Some(media_api) = media_api else {
+        return Err("Cloud media is not configured for this build.".to_string());
+    };
+    let access_token = require_media_access_token(auth_session)?;
+    media_api
+        .refresh_url(&access_token, &attachment.r2_key)
+        .await
+        .map_err(|error| media_error_message("Failed to renew the playback link", &error))
+}
+
+/// Download an attachment to a path picked in a native save dialog.
+///
+/// Returns `Ok(None)` when the dialog is cancelled.
+pub(super) async fn save_attachment_as(
+    attachment: &Attachment,
+    db: Option<Arc<DatabaseService>>,
+    media_api: Option<Arc<MediaApiClient>>,
+    auth_session: Option<AuthSession>,
+    settings: &Settings,
+) -> Result<Option<PathBuf>, String> {
+    if !attachment.scan_status.is_downloadable() {
+        return Err(quarantine_message(&attachment.filename, None));
+    }
+    let Some(media_api) = media_api else {
+        return Err("Cloud media is not configured for this build.".to_string());
+    };
+    let access_token = require_media_access_token(auth_session)?;
+
+    let mut dialog = AsyncFileDialog::new().set_file_name(sanitize_file_name(&attachment.filename));
+    if let Some(dir) = attachment_download_dir(settings) {
+        dialog = dialog.set_directory(dir);
+    }
+    let Some(file) = dialog.save_file().await else {
+        return Ok(None);
+    };
+    let path = file.path().to_path_buf();
+
+    let cache = DatabaseService::attachment_cache(settings);
+    let (bytes, _) = media_api
+        .download_cached(cache.as_ref(), &access_token, &attachment.r2_key)
+        .await
+        .map_err(|error| media_error_message("Failed to download attachment", &error))?;
+    let bytes = decrypt_downloaded_bytes(db.as_deref(), bytes).await?;
+    write_atomic(&path, &bytes)
+        .map_err(|error| format!("Failed to save {}: {error}", path.display()))?;
+
+    if settings.open_folder_after_save {
+        if let Err(error) = reveal_in_file_manager(&path) {
+            tracing::warn!("Failed to open folder for {}: {}", path.display(), error);
+        }
+    }
+    Ok(Some(path))
 }
 
 pub(super) async fn delete_remote_attachment(
@@ -126,11 +397,15 @@ pub(super) async fn delete_remote_attachment(
     media_api: Option<Arc<MediaApiClient>>,
     auth_session: Option<AuthSession>,
 ) -> Result<(), String> {
+    DatabaseService::forget_cached_attachment(object_key);
     let Some(media_api) = media_api else {
         return Ok(());
     };
     let access_token = require_media_access_token(auth_session)?;
-    media_api.delete(&access_token, object_key).await
+    media_api
+        .delete(&access_token, object_key)
+        .await
+        .map_err(String::from)
 }
 
 pub(super) fn require_media_access_token(
@@ -142,47 +417,28 @@ pub(super) fn require_media_access_token(
         .ok_or_else(|| "Sign in is required for cloud attachment operations.".to_string())
 }
 
-pub(super) fn build_media_object_key(note_id: &NoteId, file_name: &str) -> String {
-    let stem = file_name
-        .trim()
-        .rsplit_once('.')
-        .map_or_else(|| file_name.trim(), |(left, _)| left);
-    let ext = file_name
-        .trim()
-        .rsplit_once('.')
-        .map_or("", |(_, right)| right);
-
-    let safe_stem = sanitize_media_token(stem);
-    let safe_stem = if safe_stem.is_empty() {
-        "file".to_string()
+/// `content` with an inline reference to `attachment` on a new last line.
+pub(super) fn content_with_attachment_ref(content: &str, attachment: &Attachment) -> String {
+    let reference = format_attachment_ref(attachment);
+    let content = content.trim_end();
+    if content.is_empty() {
+        reference
     } else {
-        safe_stem
-    };
-    let safe_ext = sanitize_media_token(ext);
-    let safe_name = if safe_ext.is_empty() {
-        safe_stem
-    } else {
-        format!("{safe_stem}.{safe_ext}")
-    };
-    let now = chrono::Utc::now().timestamp_millis();
-    format!("notes/{note_id}/{now}-{safe_name}")
-}
-
-pub(super) fn sanitize_media_token(input: &str) -> String {
-    let mut out = String::with_capacity(input.len());
-    let mut last_dash = false;
-
-    for ch in input.chars().flat_map(char::to_lowercase) {
-        if ch.is_ascii_alphanumeric() {
-            out.push(ch);
-            last_dash = false;
-        } else if !last_dash {
-            out.push('-');
-            last_dash = true;
-        }
+        format!("{content}\n{reference}")
     }
+}
 
-    out.trim_matches('-').to_string()
+/// Labels of inline references in `content` that match none of `attachments`.
+pub(super) fn missing_attachment_refs(content: &str, attachments: &[Attachment]) -> Vec<String> {
+    parse_attachment_refs(content)
+        .into_iter()
+        .filter(|reference| {
+            !attachments
+                .iter()
+                .any(|attachment| attachment.id.to_string() == reference.attachment_id)
+        })
+        .map(|reference| reference.label)
+        .collect()
 }
 
 pub(super) async fn list_attachments_with_retry(
@@ -0,0 +1,57 @@
+//! Backlinks for the editor: notes linking here with `[[...]]`
+
+use dioxus::prelude::*;
+
+use dirt_core::NoteId;
+
+use crate::state::AppState;
+
+const PREVIEW_LENGTH: usize = 60;
+
+/// Footer list of notes that link to the current note; clicking one opens it
+#[component]
+pub(super) fn BacklinksPanel(note_id: NoteId) -> Element {
+    let mut state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+
+    let backlinks = use_resource(use_reactive!(|note_id| {
+        let db = state.db_service.read().clone();
+        // Reload whenever notes change so new links show up immediately
+        let _ = state.notes.read().len();
+        async move {
+            let db = db.ok_or_else(|| "Database not initialized".to_string())?;
+            db.backlinks(&note_id)
+                .await
+                .map_err(|error| error.to_string())
+        }
+    }));
+
+    let rows = match &*backlinks.read() {
+        Some(Ok(notes)) => notes
+            .iter()
+            .map(|note| (note.id, note.title_preview(PREVIEW_LENGTH)))
+            .collect::<Vec<_>>(),
+        Some(Err(error)) => {
+            tracing::warn!("Failed to load backlinks: {}", error);
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+
+    rsx! {
+        if !rows.is_empty() {
+            div {
+                style: "display: flex; flex-wrap: wrap; align-items: center; gap: 8px; padding-top: 8px; font-size: 12px;",
+                span { style: "color: {colors.text_muted};", "Linked from:" }
+                for (linking_id, preview) in rows {
+                    span {
+                        key: "{linking_id}",
+                        style: "color: {colors.accent}; cursor: pointer;",
+                        onclick: move |_| state.current_note_id.set(Some(linking_id)),
+                        "{preview}"
+                    }
+                }
+            }
+        }
+    }
+}
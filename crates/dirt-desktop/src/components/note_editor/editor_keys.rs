@@ -0,0 +1,62 @@
+use std::ops::Range;
+
+use dioxus::prelude::*;
+
+use dirt_core::editing::{is_shortcut_key, EditorKey};
+
+use super::tag_autocomplete::utf16_to_byte_offset;
+
+const READ_SELECTION_SCRIPT: &str = r"
+const el = document.querySelector('.editor-textarea');
+return el ? [el.selectionStart, el.selectionEnd] : null;
+";
+
+/// Map a keydown to an editing shortcut, if it has one.
+pub(super) fn editor_key(evt: &KeyboardData) -> Option<EditorKey> {
+    if evt.is_composing() {
+        return None;
+    }
+    let modifiers = evt.modifiers();
+    let command = modifiers.ctrl() || modifiers.meta();
+
+    let key = match evt.key() {
+        Key::Enter if modifiers.is_empty() => EditorKey::Enter,
+        Key::Tab if !command && !modifiers.alt() => {
+            if modifiers.shift() {
+                EditorKey::ShiftTab
+            } else {
+                EditorKey::Tab
+            }
+        }
+        Key::Character(text) if command && !modifiers.alt() && text.eq_ignore_ascii_case("b") => {
+            EditorKey::Bold
+        }
+        Key::Character(text) if !command && !modifiers.alt() => {
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => EditorKey::Char(ch),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    is_shortcut_key(key).then_some(key)
+}
+
+/// Read the editor selection as a byte range into `text`.
+pub(super) async fn read_editor_selection(text: &str) -> Option<Range<usize>> {
+    let offsets: Option<(usize, usize)> =
+        document::eval(READ_SELECTION_SCRIPT).join().await.ok()?;
+    offsets.map(|(start, end)| utf16_to_byte_offset(text, start)..utf16_to_byte_offset(text, end))
+}
+
+/// Select a byte range of `text` in the editor.
+pub(super) fn set_editor_selection(text: &str, selection: Range<usize>) {
+    let to_utf16 = |offset: usize| text[..offset.min(text.len())].encode_utf16().count();
+    let (start, end) = (to_utf16(selection.start), to_utf16(selection.end));
+    let script = format!(
+        "const el = document.querySelector('.editor-textarea');
+         if (el) {{ el.focus(); el.setSelectionRange({start}, {end}); }}"
+    );
+    let _ = document::eval(&script);
+}
@@ -0,0 +1,97 @@
+//! Word-count goal and exit control shown in focus mode
+
+use dioxus::prelude::*;
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::state::AppState;
+
+/// Scroll the editor so the caret line sits mid-screen.
+///
+/// Wrapped lines make the caret position impossible to derive from the text
+/// alone, so a hidden mirror of the textarea measures where the caret lands.
+pub(super) const TYPEWRITER_SCROLL_SCRIPT: &str = r"
+(() => {
+  const el = document.querySelector('.editor-textarea');
+  if (!el) { return; }
+  const style = getComputedStyle(el);
+  const mirror = document.createElement('div');
+  for (const prop of ['fontFamily', 'fontSize', 'lineHeight', 'letterSpacing',
+                      'paddingTop', 'paddingLeft', 'paddingRight', 'boxSizing']) {
+    mirror.style[prop] = style[prop];
+  }
+  mirror.style.position = 'absolute';
+  mirror.style.visibility = 'hidden';
+  mirror.style.whiteSpace = 'pre-wrap';
+  mirror.style.overflowWrap = 'break-word';
+  mirror.style.width = el.clientWidth + 'px';
+  mirror.textContent = el.value.slice(0, el.selectionStart);
+  const caret = document.createElement('span');
+  caret.textContent = '​';
+  mirror.appendChild(caret);
+  document.body.appendChild(mirror);
+  const caretTop = caret.offsetTop;
+  mirror.remove();
+  el.scrollTop = Math.max(0, caretTop - el.clientHeight / 2);
+})();
+";
+
+/// Footer of the focus-mode editor: word count against the goal, and a way out
+#[component]
+pub(super) fn FocusModeBar(word_count: usize, word_goal: Option<u32>) -> Element {
+    let mut state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+
+    let goal = word_goal
+        .and_then(|goal| usize::try_from(goal).ok())
+        .filter(|goal| *goal > 0);
+    let reached = goal.is_some_and(|goal| word_count >= goal);
+    let percent = goal.map_or(0, |goal| (word_count.min(goal) * 100) / goal);
+    let label = match goal {
+        Some(goal) if reached => format!("{word_count} / {goal} words, goal reached"),
+        Some(goal) => format!("{word_count} / {goal} words"),
+        None if word_count == 1 => "1 word".to_string(),
+        None => format!("{word_count} words"),
+    };
+    let bar_color = if reached {
+        colors.success
+    } else {
+        colors.accent
+    };
+
+    rsx! {
+        div {
+            class: "focus-mode-bar",
+            style: "
+                display: flex;
+                align-items: center;
+                gap: 12px;
+                padding-top: 8px;
+                font-size: 12px;
+                color: {colors.text_muted};
+            ",
+            span { "{label}" }
+            if goal.is_some() {
+                div {
+                    style: "
+                        flex: 1;
+                        max-width: 160px;
+                        height: 4px;
+                        border-radius: 2px;
+                        background: {colors.bg_tertiary};
+                        overflow: hidden;
+                    ",
+                    div {
+                        style: "width: {percent}%; height: 100%; background: {bar_color};",
+                    }
+                }
+            }
+            div { style: "flex: 1;" }
+            Button {
+                variant: ButtonVariant::Ghost,
+                title: "Leave focus mode (Esc)",
+                onclick: move |_| state.toggle_focus_mode(),
+                "Exit focus"
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Rendered Markdown view of the note being edited
+
+use dioxus::prelude::*;
+
+use dirt_core::markdown::{render_note_html_with_tasks, NoteLinkResolver};
+use dirt_core::NoteId;
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::state::AppState;
+
+/// Send `note:<id>` for each clicked `[[wiki link]]` in the preview and
+/// `task:<line>` for each clicked task checkbox.
+const PREVIEW_CLICK_SCRIPT: &str = r"
+const preview = document.getElementById('markdown-preview');
+if (preview) {
+  preview.addEventListener('click', (event) => {
+    const checkbox = event.target.closest('input.task-checkbox');
+    if (checkbox) {
+      dioxus.send('task:' + checkbox.dataset.taskLine);
+      return;
+    }
+    const link = event.target.closest('a.note-link');
+    if (!link) { return; }
+    event.preventDefault();
+    dioxus.send('note:' + link.dataset.noteId);
+  });
+}
+";
+
+/// What the editor area shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) enum EditorView {
+    #[default]
+    Edit,
+    Split,
+    Preview,
+}
+
+impl EditorView {
+    const ALL: [Self; 3] = [Self::Edit, Self::Split, Self::Preview];
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Edit => "Edit",
+            Self::Split => "Split",
+            Self::Preview => "Preview",
+        }
+    }
+
+    pub(super) const fn shows_editor(self) -> bool {
+        !matches!(self, Self::Preview)
+    }
+
+    pub(super) const fn shows_preview(self) -> bool {
+        !matches!(self, Self::Edit)
+    }
+}
+
+/// Edit / Split / Preview switch above the editor
+#[component]
+pub(super) fn EditorViewToggle(view: EditorView, on_change: EventHandler<EditorView>) -> Element {
+    rsx! {
+        div {
+            style: "display: flex; justify-content: flex-end; gap: 4px; padding-bottom: 8px;",
+            for option in EditorView::ALL {
+                Button {
+                    key: "{option.label()}",
+                    variant: if view == option { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                    onclick: move |_| on_change.call(option),
+                    "{option.label()}"
+                }
+            }
+        }
+    }
+}
+
+/// Note content rendered as Markdown; `[[wiki links]]` to loaded notes open
+/// them, and clicking a task checkbox passes its line to `on_toggle_task`.
+#[component]
+pub(super) fn MarkdownPreview(content: String, on_toggle_task: EventHandler<usize>) -> Element {
+    let mut state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+
+    let html = render_note_html_with_tasks(&content, &NoteLinkResolver::new(&state.notes.read()));
+
+    let listen_for_clicks = move |_: MountedEvent| {
+        spawn(async move {
+            let mut clicks = document::eval(PREVIEW_CLICK_SCRIPT);
+            while let Ok(click) = clicks.recv::<String>().await {
+                if let Some(line) = click.strip_prefix("task:") {
+                    match line.parse::<usize>() {
+                        Ok(line_index) => on_toggle_task.call(line_index),
+                        Err(error) => {
+                            tracing::warn!("Ignoring invalid task line '{line}': {error}")
+                        }
+                    }
+                } else if let Some(note_id) = click.strip_prefix("note:") {
+                    match note_id.parse::<NoteId>() {
+                        Ok(note_id) => state.current_note_id.set(Some(note_id)),
+                        Err(error) => {
+                            tracing::warn!("Ignoring invalid note link '{note_id}': {error}");
+                        }
+                    }
+                }
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            id: "markdown-preview",
+            class: "markdown-preview",
+            style: "
+                flex: 1;
+                min-width: 0;
+                overflow-y: auto;
+                line-height: 1.6;
+                color: {colors.text_primary};
+            ",
+            onmounted: listen_for_clicks,
+            dangerous_inner_html: "{html}",
+        }
+    }
+}
@@ -4,19 +4,54 @@ use std::time::Duration;
 
 use dioxus::prelude::*;
 
+use dirt_core::editing::{
+    apply_editor_key, word_count, EditorKey, AUTOSAVE_IDLE_MS, DRAFT_JOURNAL_MS,
+};
+use dirt_core::models::{apply_tag_completion, tag_query_at, toggle_task_line, TagQuery};
+use dirt_core::seal::sealed_until;
 use dirt_core::NoteId;
 
 use self::attachment_panel::AttachmentPanel;
+use self::backlinks_bar::BacklinksPanel;
+use self::editor_keys::{editor_key, read_editor_selection, set_editor_selection};
+use self::focus_bar::{FocusModeBar, TYPEWRITER_SCROLL_SCRIPT};
+use self::markdown_preview::{EditorView, EditorViewToggle, MarkdownPreview};
+use self::no_export_bar::NoExportAction;
+use self::pin_bar::PinAction;
+use self::seal_bar::{SealAction, SealedNoteBanner};
+use self::send_to_device_bar::SendToDeviceAction;
+use self::share_bar::ShareLinkAction;
+use self::summary_bar::SummarizeAction;
+use self::tag_autocomplete::{
+    read_editor_cursor, set_editor_cursor, TagSuggestions, TAG_SUGGESTION_LIMIT,
+};
+use self::translate_bar::TranslateAction;
 use crate::queries::invalidate_notes_query;
+use crate::services::DatabaseService;
 use crate::state::AppState;
 
 mod attachment_panel;
 mod attachment_preview;
 mod attachment_utils;
+mod backlinks_bar;
+mod editor_keys;
+mod focus_bar;
+mod markdown_preview;
+mod no_export_bar;
+mod pin_bar;
+mod seal_bar;
+mod send_to_device_bar;
+mod share_bar;
+mod summary_bar;
+mod tag_autocomplete;
 mod transcription;
+mod translate_bar;
 
-/// Idle save delay - save after 2 seconds of no typing
-const IDLE_SAVE_MS: u64 = 2000;
+async fn discard_draft(db: &DatabaseService, note_id: &NoteId) {
+    if let Err(error) = db.discard_draft(note_id).await {
+        tracing::warn!("Failed to discard draft for note {}: {}", note_id, error);
+    }
+}
 
 /// Plain text note editor with auto-save
 #[component]
@@ -24,15 +59,31 @@ pub fn NoteEditor() -> Element {
     let mut state = use_context::<AppState>();
     let current_note = state.current_note();
     let colors = (state.theme)().palette();
+    let settings = (state.settings)();
+    let focus_mode = settings.focus_mode;
+    let typewriter_scrolling = focus_mode && settings.focus_typewriter_scrolling;
 
     // Local editor state for the selected note.
     let mut content = use_signal(String::new);
     let mut current_note_id = use_signal(|| None::<NoteId>);
+    // `updated_at` of the stored note the editor content reflects.
+    let mut shown_updated_at = use_signal(|| 0i64);
 
     // Version-based save tracking to debounce writes.
     let mut save_version = use_signal(|| 0u64);
     let mut last_saved_version = use_signal(|| 0u64);
 
+    // Throttled draft journal so a crash before the debounced save loses nothing.
+    let mut draft_journal_pending = use_signal(|| false);
+
+    // Tag autocomplete state for the `#tag` under the caret.
+    let mut tag_query = use_signal(|| None::<TagQuery>);
+    let mut tag_suggestions = use_signal(Vec::<String>::new);
+    let mut selected_suggestion = use_signal(|| 0usize);
+
+    // Plain editor, editor beside its Markdown preview, or preview only.
+    let mut editor_view = use_signal(EditorView::default);
+
     // Sync content when selected note changes.
     use_effect(move || {
         let selected = state.current_note();
@@ -40,13 +91,27 @@ pub fn NoteEditor() -> Element {
 
         if selected_id != current_note_id() {
             if let Some(note) = selected {
+                shown_updated_at.set(note.updated_at);
                 content.set(note.content);
             } else {
+                shown_updated_at.set(0);
                 content.set(String::new());
             }
             current_note_id.set(selected_id);
             save_version.set(0);
             last_saved_version.set(0);
+        } else if let Some(note) = selected {
+            // Pick up newer changes saved outside this editor (e.g. an
+            // external editor) while there are no unsaved local edits.
+            let newer = note.updated_at > *shown_updated_at.peek();
+            if *content.peek() == note.content {
+                if newer {
+                    shown_updated_at.set(note.updated_at);
+                }
+            } else if newer && *save_version.peek() == *last_saved_version.peek() {
+                shown_updated_at.set(note.updated_at);
+                content.set(note.content);
+            }
         }
     });
 
@@ -65,7 +130,7 @@ pub fn NoteEditor() -> Element {
         }
 
         spawn(async move {
-            tokio::time::sleep(Duration::from_millis(IDLE_SAVE_MS)).await;
+            tokio::time::sleep(Duration::from_millis(AUTOSAVE_IDLE_MS)).await;
 
             if save_version() != current_version {
                 return;
@@ -78,6 +143,7 @@ pub fn NoteEditor() -> Element {
                         Ok(_) => {
                             tracing::debug!("Auto-saved note: {}", id);
                             last_saved_version.set(current_version);
+                            discard_draft(&db, &id).await;
                             invalidate_notes_query().await;
                         }
                         Err(error) => {
@@ -110,6 +176,7 @@ pub fn NoteEditor() -> Element {
                         Ok(_) => {
                             tracing::debug!("Saved note on blur/shortcut: {}", id);
                             last_saved_version.set(current_version);
+                            discard_draft(&db, &id).await;
                             invalidate_notes_query().await;
                         }
                         Err(error) => {
@@ -121,10 +188,116 @@ pub fn NoteEditor() -> Element {
         });
     };
 
-    let on_input = move |evt: Event<FormData>| {
-        let new_content = evt.value();
+    let mut close_tag_suggestions = move || {
+        tag_query.set(None);
+        tag_suggestions.write().clear();
+        selected_suggestion.set(0);
+    };
+
+    let mut refresh_tag_suggestions = move |text: String| {
+        spawn(async move {
+            let query = match read_editor_cursor(&text).await {
+                Some(cursor) => tag_query_at(&text, cursor),
+                None => None,
+            };
+            let Some(query) = query else {
+                close_tag_suggestions();
+                return;
+            };
+            let Some(db) = state.db_service.read().clone() else {
+                return;
+            };
+
+            match db.suggest_tags(&query.prefix, TAG_SUGGESTION_LIMIT).await {
+                Ok(tags) if content() == text => {
+                    let names: Vec<String> = tags
+                        .into_iter()
+                        .map(|(name, _)| name)
+                        .filter(|name| *name != query.prefix)
+                        .collect();
+                    if names.is_empty() {
+                        close_tag_suggestions();
+                    } else {
+                        selected_suggestion.set(0);
+                        tag_suggestions.set(names);
+                        tag_query.set(Some(query));
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    tracing::warn!("Failed to load tag suggestions: {}", error);
+                }
+            }
+        });
+    };
+
+    let mut accept_tag_suggestion = move |tag: String| {
+        let Some(query) = tag_query() else {
+            return;
+        };
+        let current = content();
+        if query.end > current.len() {
+            close_tag_suggestions();
+            return;
+        }
+
+        let (updated, cursor) = apply_tag_completion(&current, &query, &tag);
+        content.set(updated.clone());
+        save_version.set(save_version() + 1);
+        close_tag_suggestions();
+
+        if let Some(id) = current_note_id() {
+            let mut notes = state.notes.write();
+            if let Some(note) = notes.iter_mut().find(|note| note.id == id) {
+                note.content.clone_from(&updated);
+                note.updated_at = chrono::Utc::now().timestamp_millis();
+            }
+        }
+
+        spawn(async move {
+            // Let the textarea re-render with the new value before moving the caret.
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            set_editor_cursor(&updated, cursor);
+
+            let db = state.db_service.read().clone();
+            if let Some(db) = db {
+                if let Err(error) = db.record_tag_completion(&tag).await {
+                    tracing::warn!("Failed to record tag completion: {}", error);
+                }
+            }
+        });
+    };
+
+    let mut journal_draft = move || {
+        if draft_journal_pending() {
+            return;
+        }
+        draft_journal_pending.set(true);
+
+        spawn(async move {
+            tokio::time::sleep(Duration::from_millis(DRAFT_JOURNAL_MS)).await;
+            draft_journal_pending.set(false);
+
+            if save_version() == last_saved_version() {
+                return;
+            }
+            let Some(id) = current_note_id() else {
+                return;
+            };
+            let db = state.db_service.read().clone();
+            if let Some(db) = db {
+                if let Err(error) = db.save_draft(&id, &content()).await {
+                    tracing::warn!("Failed to journal draft for note {}: {}", id, error);
+                }
+            }
+        });
+    };
+
+    let mut edit_content = move |new_content: String| {
         content.set(new_content.clone());
         save_version.set(save_version() + 1);
+        journal_draft();
+        refresh_tag_suggestions(new_content.clone());
 
         // Optimistically reflect the latest content in local list state.
         if let Some(id) = current_note_id() {
@@ -136,17 +309,128 @@ pub fn NoteEditor() -> Element {
         }
     };
 
+    // Keep the caret line mid-screen while typewriter scrolling is on.
+    let scroll_to_caret = move || {
+        if typewriter_scrolling {
+            let _ = document::eval(TYPEWRITER_SCROLL_SCRIPT);
+        }
+    };
+
+    let on_input = move |evt: Event<FormData>| {
+        edit_content(evt.value());
+        scroll_to_caret();
+    };
+
+    // Checking a box in the preview edits the content like typing would.
+    let toggle_task = move |line_index: usize| {
+        if let Some(updated) = toggle_task_line(&content(), line_index) {
+            edit_content(updated);
+        }
+    };
+
+    let mut apply_shortcut = move |key: EditorKey| {
+        let text = content();
+        spawn(async move {
+            let Some(selection) = read_editor_selection(&text).await else {
+                return;
+            };
+            // Drop the shortcut if the text changed while reading the selection.
+            if content() != text {
+                return;
+            }
+            let edit = apply_editor_key(&text, selection, key);
+            edit_content(edit.text.clone());
+
+            // Let the textarea re-render with the new value before selecting.
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            set_editor_selection(&edit.text, edit.selection);
+        });
+    };
+
     let on_blur = move |_| {
+        close_tag_suggestions();
         perform_save_now();
     };
 
+    // Sealing, unsealing, and appended translations write the note directly;
+    // adopt the stored content as saved so autosave and the draft journal
+    // cannot overwrite it.
+    let on_seal_change = move |updated_content: String| {
+        // Bumping the version also cancels any debounced save still in flight.
+        let version = save_version() + 1;
+        content.set(updated_content.clone());
+        save_version.set(version);
+        last_saved_version.set(version);
+        close_tag_suggestions();
+        if let Some(id) = current_note_id() {
+            let mut notes = state.notes.write();
+            if let Some(note) = notes.iter_mut().find(|note| note.id == id) {
+                note.content = updated_content;
+                note.updated_at = chrono::Utc::now().timestamp_millis();
+            }
+        }
+    };
+
+    let sealed_until_ms = sealed_until(&content());
+
     let on_keydown = move |evt: Event<KeyboardData>| {
+        let suggestion_count = tag_suggestions.read().len();
+        if suggestion_count > 0 {
+            match evt.key() {
+                Key::ArrowDown => {
+                    evt.prevent_default();
+                    selected_suggestion.set((selected_suggestion() + 1) % suggestion_count);
+                    return;
+                }
+                Key::ArrowUp => {
+                    evt.prevent_default();
+                    selected_suggestion
+                        .set((selected_suggestion() + suggestion_count - 1) % suggestion_count);
+                    return;
+                }
+                Key::Enter | Key::Tab => {
+                    evt.prevent_default();
+                    let picked = tag_suggestions.read().get(selected_suggestion()).cloned();
+                    if let Some(tag) = picked {
+                        accept_tag_suggestion(tag);
+                    }
+                    return;
+                }
+                Key::Escape => {
+                    // Dismiss the popup without deselecting the note.
+                    evt.prevent_default();
+                    evt.stop_propagation();
+                    close_tag_suggestions();
+                    return;
+                }
+                _ => {}
+            }
+        }
+
         if evt.modifiers().ctrl() && evt.key() == Key::Character("s".to_string()) {
             evt.prevent_default();
             perform_save_now();
+            return;
+        }
+
+        if let Some(key) = editor_key(&evt) {
+            evt.prevent_default();
+            apply_shortcut(key);
         }
     };
 
+    // Focus mode narrows the text to a readable column; typewriter scrolling
+    // needs room below the last line to keep it centered.
+    let textarea_layout = if focus_mode {
+        let bottom_padding = if typewriter_scrolling { "50vh" } else { "0" };
+        format!(
+            "max-width: {}ch; margin: 0 auto; padding-bottom: {bottom_padding}; font-size: 1.1em;",
+            settings.focus_line_width
+        )
+    } else {
+        String::new()
+    };
+
     rsx! {
         div {
             class: "note-editor",
@@ -158,41 +442,107 @@ pub fn NoteEditor() -> Element {
                 background: {colors.bg_primary};
             ",
 
-            if current_note.is_some() {
-                textarea {
-                    class: "editor-textarea",
-                    style: "
-                        flex: 1;
-                        width: 100%;
-                        border: none;
-                        outline: none;
-                        resize: none;
-                        font-family: inherit;
-                        font-size: inherit;
-                        line-height: 1.6;
-                        background: transparent;
-                        color: {colors.text_primary};
-                    ",
-                    value: "{content}",
-                    placeholder: "Start typing...",
-                    oninput: on_input,
-                    onblur: on_blur,
-                    onkeydown: on_keydown,
-                }
-
-                AttachmentPanel {
-                    note_id: current_note_id(),
-                    editor_content: content(),
-                    on_editor_content_change: move |updated_content: String| {
-                        content.set(updated_content.clone());
-                        if let Some(id) = current_note_id() {
-                            let mut notes = state.notes.write();
-                            if let Some(note) = notes.iter_mut().find(|note| note.id == id) {
-                                note.content = updated_content;
-                                note.updated_at = chrono::Utc::now().timestamp_millis();
+            if let (Some(note_id), Some(sealed_until_ms)) = (current_note_id(), sealed_until_ms) {
+                SealedNoteBanner {
+                    note_id,
+                    sealed_until_ms,
+                    on_editor_content_change: on_seal_change,
+                }
+            } else if current_note.is_some() {
+                EditorViewToggle {
+                    view: editor_view(),
+                    on_change: move |view| editor_view.set(view),
+                }
+
+                div {
+                    style: "flex: 1; display: flex; gap: 16px; min-height: 0;",
+                    if editor_view().shows_editor() {
+                        textarea {
+                            class: "editor-textarea",
+                            style: "
+                                flex: 1;
+                                width: 100%;
+                                border: none;
+                                outline: none;
+                                resize: none;
+                                font-family: inherit;
+                                font-size: inherit;
+                                line-height: 1.6;
+                                background: transparent;
+                                color: {colors.text_primary};
+                                {textarea_layout}
+                            ",
+                            value: "{content}",
+                            placeholder: "Start typing...",
+                            oninput: on_input,
+                            onblur: on_blur,
+                            onkeydown: on_keydown,
+                            // Arrow keys and clicks move the caret without an input event.
+                            onkeyup: move |_| scroll_to_caret(),
+                            onclick: move |_| scroll_to_caret(),
+                        }
+                    }
+                    if editor_view().shows_preview() {
+                        MarkdownPreview {
+                            content: content(),
+                            on_toggle_task: toggle_task,
+                        }
+                    }
+                }
+
+                if !tag_suggestions.read().is_empty() {
+                    TagSuggestions {
+                        suggestions: tag_suggestions(),
+                        selected: selected_suggestion(),
+                        on_pick: move |tag: String| accept_tag_suggestion(tag),
+                    }
+                }
+
+                if focus_mode {
+                    FocusModeBar {
+                        word_count: word_count(&content.read()),
+                        word_goal: settings.focus_word_goal,
+                    }
+                } else {
+                    AttachmentPanel {
+                        note_id: current_note_id(),
+                        editor_content: content(),
+                        on_editor_content_change: move |updated_content: String| {
+                            content.set(updated_content.clone());
+                            if let Some(id) = current_note_id() {
+                                let mut notes = state.notes.write();
+                                if let Some(note) = notes.iter_mut().find(|note| note.id == id) {
+                                    note.content = updated_content;
+                                    note.updated_at = chrono::Utc::now().timestamp_millis();
+                                }
                             }
+                        },
+                    }
+
+                    if let Some(note_id) = current_note_id() {
+                        SealAction {
+                            note_id,
+                            editor_content: content(),
+                            on_editor_content_change: on_seal_change,
+                        }
+                        TranslateAction {
+                            note_id,
+                            editor_content: content(),
+                            on_editor_content_change: on_seal_change,
                         }
-                    },
+                        SummarizeAction {
+                            note_id,
+                            editor_content: content(),
+                        }
+                        PinAction { note_id }
+                        NoExportAction { note_id }
+                        SendToDeviceAction {
+                            note_id,
+                            editor_content: content(),
+                        }
+                        ShareLinkAction { note_id }
+                        BacklinksPanel { note_id }
+                    }
                 }
             } else {
                 div {
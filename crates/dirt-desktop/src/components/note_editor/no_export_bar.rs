@@ -0,0 +1,69 @@
+//! No-export toggle for the editor
+
+use dioxus::prelude::*;
+
+use dirt_core::NoteId;
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::state::AppState;
+
+/// Footer action that keeps the current note out of exports, digests, and shares
+#[component]
+pub(super) fn NoExportAction(note_id: NoteId) -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut excluded = use_signal(|| None::<(NoteId, bool)>);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    use_effect(use_reactive!(|note_id| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        error.set(None);
+        spawn(async move {
+            match db.is_note_export_excluded(&note_id).await {
+                Ok(flag) => excluded.set(Some((note_id, flag))),
+                Err(load_error) => tracing::warn!("Failed to load no-export flag: {}", load_error),
+            }
+        });
+    }));
+
+    let is_excluded = excluded()
+        .filter(|(flag_note_id, _)| *flag_note_id == note_id)
+        .is_some_and(|(_, flag)| flag);
+
+    let toggle = move |_| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        busy.set(true);
+        error.set(None);
+        spawn(async move {
+            match db.set_note_export_excluded(&note_id, !is_excluded).await {
+                Ok(()) => excluded.set(Some((note_id, !is_excluded))),
+                Err(save_error) => {
+                    tracing::error!("Failed to update no-export flag: {}", save_error);
+                    error.set(Some(save_error.to_string()));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            style: "display: flex; align-items: center; gap: 8px; padding-top: 8px; font-size: 12px;",
+            Button {
+                variant: if is_excluded { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                disabled: busy(),
+                title: "Private notes are left out of exports, journals, digests, and webhooks",
+                onclick: toggle,
+                if is_excluded { "Private" } else { "Mark private" }
+            }
+            if let Some(message) = error() {
+                span { style: "color: {colors.error};", "{message}" }
+            }
+        }
+    }
+}
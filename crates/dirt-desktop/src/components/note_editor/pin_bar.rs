@@ -0,0 +1,83 @@
+//! Pin and archive toggles for the editor
+
+use dioxus::prelude::*;
+
+use dirt_core::NoteId;
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::queries::invalidate_notes_query;
+use crate::state::AppState;
+
+#[derive(Clone, Copy)]
+enum NoteFlag {
+    Pinned(bool),
+    Archived(bool),
+}
+
+/// Footer actions that pin the current note to the top of the list or archive it
+#[component]
+pub(super) fn PinAction(note_id: NoteId) -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let (is_pinned, is_archived) = state
+        .notes
+        .read()
+        .iter()
+        .find(|note| note.id == note_id)
+        .map_or((false, false), |note| (note.is_pinned, note.is_archived));
+
+    let mut apply = move |flag: NoteFlag| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        busy.set(true);
+        error.set(None);
+        spawn(async move {
+            let result = match flag {
+                NoteFlag::Pinned(pinned) => db.set_note_pinned(&note_id, pinned).await,
+                NoteFlag::Archived(archived) => db.set_note_archived(&note_id, archived).await,
+            };
+            match result {
+                Ok(updated) => {
+                    let mut notes = state.notes.write();
+                    if let Some(note) = notes.iter_mut().find(|note| note.id == note_id) {
+                        *note = updated;
+                    }
+                    drop(notes);
+                    invalidate_notes_query().await;
+                }
+                Err(save_error) => {
+                    tracing::error!("Failed to update note flags: {}", save_error);
+                    error.set(Some(save_error.to_string()));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            style: "display: flex; align-items: center; gap: 8px; padding-top: 8px; font-size: 12px;",
+            Button {
+                variant: if is_pinned { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                disabled: busy() || is_archived,
+                title: "Pinned notes stay at the top of the note list",
+                onclick: move |_| apply(NoteFlag::Pinned(!is_pinned)),
+                if is_pinned { "Pinned" } else { "Pin" }
+            }
+            Button {
+                variant: if is_archived { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                disabled: busy(),
+                title: "Archived notes leave the note list but are still exported",
+                onclick: move |_| apply(NoteFlag::Archived(!is_archived)),
+                if is_archived { "Archived" } else { "Archive" }
+            }
+            if let Some(message) = error() {
+                span { style: "color: {colors.error};", "{message}" }
+            }
+        }
+    }
+}
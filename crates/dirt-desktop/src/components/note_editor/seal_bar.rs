@@ -0,0 +1,181 @@
+//! Seal-until action and sealed-note banner for the editor
+
+use dioxus::prelude::*;
+
+use dirt_core::seal::{format_seal_date, parse_seal_date, seal_note, unseal_note};
+use dirt_core::NoteId;
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::queries::invalidate_notes_query;
+use crate::services::DatabaseService;
+use crate::state::AppState;
+
+/// Banner shown instead of the editor while a note is sealed
+#[component]
+pub(super) fn SealedNoteBanner(
+    note_id: NoteId,
+    sealed_until_ms: i64,
+    on_editor_content_change: EventHandler<String>,
+) -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let can_open = now >= sealed_until_ms;
+    let date = format_seal_date(sealed_until_ms);
+
+    let open_note = move |_| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        busy.set(true);
+        error.set(None);
+
+        spawn(async move {
+            let keys = DatabaseService::seal_key_store();
+            let now = chrono::Utc::now().timestamp_millis();
+            match unseal_note(&db, &keys, &note_id, now).await {
+                Ok(note) => {
+                    on_editor_content_change.call(note.content);
+                    invalidate_notes_query().await;
+                }
+                Err(unseal_error) => {
+                    tracing::error!("Failed to open sealed note: {}", unseal_error);
+                    error.set(Some(unseal_error.to_string()));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            style: "
+                flex: 1;
+                display: flex;
+                flex-direction: column;
+                align-items: center;
+                justify-content: center;
+                gap: 12px;
+                color: {colors.text_secondary};
+            ",
+            div { style: "font-size: 16px; color: {colors.text_primary};", "Sealed until {date}" }
+            if can_open {
+                div { "The seal date has passed." }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    disabled: busy(),
+                    onclick: open_note,
+                    if busy() { "Opening..." } else { "Open note" }
+                }
+            } else {
+                div { style: "color: {colors.text_muted};", "This note can be opened on or after {date}." }
+            }
+            if let Some(message) = error() {
+                div { style: "font-size: 12px; color: {colors.error};", "{message}" }
+            }
+        }
+    }
+}
+
+/// Footer action that seals the current note until a chosen date
+#[component]
+pub(super) fn SealAction(
+    note_id: NoteId,
+    editor_content: String,
+    on_editor_content_change: EventHandler<String>,
+) -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut picking = use_signal(|| false);
+    let mut date_input = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let seal = move |_| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        let until_ms = match parse_seal_date(&date_input()) {
+            Ok(until_ms) => until_ms,
+            Err(parse_error) => {
+                error.set(Some(parse_error.to_string()));
+                return;
+            }
+        };
+        let latest_content = editor_content.clone();
+        busy.set(true);
+        error.set(None);
+
+        spawn(async move {
+            // Flush unsaved edits so the sealed copy matches the editor.
+            let result = match db.update_note(&note_id, &latest_content).await {
+                Ok(_) => {
+                    let keys = DatabaseService::seal_key_store();
+                    let now = chrono::Utc::now().timestamp_millis();
+                    seal_note(&db, &keys, &note_id, until_ms, now).await
+                }
+                Err(save_error) => Err(save_error),
+            };
+            match result {
+                Ok(note) => {
+                    // Drop the plaintext draft journal along with the content.
+                    if let Err(draft_error) = db.discard_draft(&note_id).await {
+                        tracing::warn!("Failed to discard draft for sealed note: {}", draft_error);
+                    }
+                    picking.set(false);
+                    date_input.set(String::new());
+                    on_editor_content_change.call(note.content);
+                    invalidate_notes_query().await;
+                }
+                Err(seal_error) => {
+                    tracing::error!("Failed to seal note: {}", seal_error);
+                    error.set(Some(seal_error.to_string()));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            style: "display: flex; align-items: center; gap: 8px; padding-top: 8px; font-size: 12px;",
+            if picking() {
+                span { style: "color: {colors.text_secondary};", "Seal until" }
+                input {
+                    class: "input",
+                    r#type: "date",
+                    value: "{date_input}",
+                    oninput: move |evt| date_input.set(evt.value()),
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    disabled: busy() || date_input().is_empty(),
+                    onclick: seal,
+                    if busy() { "Sealing..." } else { "Seal" }
+                }
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    disabled: busy(),
+                    onclick: move |_| {
+                        picking.set(false);
+                        error.set(None);
+                    },
+                    "Cancel"
+                }
+            } else {
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    title: "Encrypt this note until a date; the key stays on this device",
+                    onclick: move |_| picking.set(true),
+                    "Seal until..."
+                }
+            }
+            if let Some(message) = error() {
+                span { style: "color: {colors.error};", "{message}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Send-to-device QR code for the editor
+
+use dioxus::prelude::*;
+
+use dirt_core::capture::Clipboard;
+use dirt_core::transfer::NoteTransfer;
+use dirt_core::NoteId;
+use qrcode::render::svg;
+use qrcode::{EcLevel, QrCode};
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::services::SystemClipboard;
+use crate::state::AppState;
+
+/// Minimum edge length of the rendered QR code, in pixels
+const QR_SIZE_PX: u32 = 220;
+
+/// Footer action that shows the current note as a QR code for the mobile app
+#[component]
+pub(super) fn SendToDeviceAction(note_id: NoteId, editor_content: String) -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut open_for = use_signal(|| None::<NoteId>);
+    let mut copied = use_signal(|| false);
+
+    let is_open = open_for() == Some(note_id);
+    let synced = (state.auth_session)().is_some();
+    let transfer = is_open.then(|| NoteTransfer::for_note(note_id, &editor_content, synced));
+
+    let toggle = move |_| {
+        copied.set(false);
+        open_for.set(if is_open { None } else { Some(note_id) });
+    };
+
+    rsx! {
+        div {
+            style: "display: flex; flex-direction: column; gap: 8px; padding-top: 8px; font-size: 12px;",
+            div {
+                Button {
+                    variant: if is_open { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                    title: "Show a QR code to open this note on your phone",
+                    onclick: toggle,
+                    if is_open { "Hide QR code" } else { "Send to device" }
+                }
+            }
+            match transfer {
+                Some(Ok(transfer)) => {
+                    let uri = transfer.to_uri();
+                    let hint = if transfer.content.is_some() {
+                        "Scan with the Dirt mobile app or your phone's camera."
+                    } else {
+                        "Too long to fit in the code: it is sent by id and opens once your phone has synced."
+                    };
+                    match render_qr_svg(&uri) {
+                        Ok(qr_svg) => rsx! {
+                            div {
+                                style: "display: flex; align-items: flex-start; gap: 12px;",
+                                div {
+                                    style: "
+                                        flex-shrink: 0;
+                                        line-height: 0;
+                                        border: 1px solid {colors.border_light};
+                                        border-radius: 6px;
+                                        overflow: hidden;
+                                    ",
+                                    dangerous_inner_html: "{qr_svg}",
+                                }
+                                div {
+                                    style: "display: flex; flex-direction: column; gap: 8px; color: {colors.text_secondary};",
+                                    span { "{hint}" }
+                                    div {
+                                        Button {
+                                            variant: ButtonVariant::Ghost,
+                                            onclick: move |_| {
+                                                match SystemClipboard.set_text(&uri) {
+                                                    Ok(()) => copied.set(true),
+                                                    Err(copy_error) => {
+                                                        tracing::warn!("Failed to copy note link: {}", copy_error);
+                                                    }
+                                                }
+                                            },
+                                            if copied() { "Link copied" } else { "Copy link" }
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                        Err(render_error) => rsx! {
+                            span { style: "color: {colors.error};", "{render_error}" }
+                        },
+                    }
+                }
+                Some(Err(transfer_error)) => rsx! {
+                    span { style: "color: {colors.error};", "{transfer_error}" }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
+
+/// Render `uri` as an SVG QR code at least [`QR_SIZE_PX`] wide.
+fn render_qr_svg(uri: &str) -> Result<String, String> {
+    let code = QrCode::with_error_correction_level(uri.as_bytes(), EcLevel::M)
+        .map_err(|error| format!("Failed to build QR code: {error}"))?;
+    Ok(code
+        .render::<svg::Color<'_>>()
+        .min_dimensions(QR_SIZE_PX, QR_SIZE_PX)
+        .dark_color(svg::Color("#000000"))
+        .light_color(svg::Color("#ffffff"))
+        .build())
+}
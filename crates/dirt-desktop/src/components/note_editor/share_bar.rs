@@ -0,0 +1,187 @@
+//! Public share links for the editor
+
+use std::sync::Arc;
+
+use dioxus::prelude::*;
+use rfd::{AsyncMessageDialog, MessageButtons, MessageDialogResult, MessageLevel};
+
+use dirt_core::capture::Clipboard;
+use dirt_core::share::{ShareApiClient, ShareLink};
+use dirt_core::NoteId;
+
+use super::attachment_utils::require_media_access_token;
+use crate::components::button::{Button, ButtonVariant};
+use crate::services::{AuthSession, DatabaseService, MediaApiClient, SystemClipboard};
+use crate::state::AppState;
+
+/// Footer action that copies a public read-only link to the current note
+#[component]
+pub(super) fn ShareLinkAction(note_id: NoteId) -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut busy = use_signal(|| false);
+    let mut shared = use_signal(|| None::<(NoteId, ShareLink)>);
+    let mut message = use_signal(|| None::<Result<String, String>>);
+
+    if state.media_api_client.read().is_none() {
+        return rsx! {};
+    }
+    let current = shared().filter(|(shared_id, _)| *shared_id == note_id);
+
+    let share = move |_| {
+        let db = state.db_service.read().clone();
+        let media_api = state.media_api_client.read().clone();
+        let auth_session = (state.auth_session)();
+        busy.set(true);
+        message.set(None);
+        spawn(async move {
+            let created = match confirm_share(&note_id, db).await {
+                Ok(true) => create_share_link(&note_id, media_api, auth_session)
+                    .await
+                    .map(Some),
+                Ok(false) => Ok(None),
+                Err(error) => Err(error),
+            };
+            match created {
+                Ok(None) => {}
+                Ok(Some(link)) => {
+                    let url = link.url.clone().unwrap_or_default();
+                    message.set(Some(match SystemClipboard.set_text(&url) {
+                        Ok(()) => Ok(format!(
+                            "Link copied. Anyone with it can read this note until {}.",
+                            format_expiry(link.expires_at)
+                        )),
+                        Err(copy_error) => Ok(format!("Share link: {url} ({copy_error})")),
+                    }));
+                    shared.set(Some((note_id, link)));
+                }
+                Err(share_error) => message.set(Some(Err(share_error))),
+            }
+            busy.set(false);
+        });
+    };
+
+    let revoke = move |_| {
+        let media_api = state.media_api_client.read().clone();
+        let auth_session = (state.auth_session)();
+        busy.set(true);
+        message.set(None);
+        spawn(async move {
+            match revoke_share_links(&note_id, media_api, auth_session).await {
+                Ok(count) => {
+                    shared.set(None);
+                    message.set(Some(Ok(format!("Revoked {count} share link(s)."))));
+                }
+                Err(revoke_error) => message.set(Some(Err(revoke_error))),
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            style: "display: flex; align-items: center; gap: 8px; padding-top: 8px; font-size: 12px;",
+            Button {
+                variant: ButtonVariant::Ghost,
+                disabled: busy(),
+                title: "Copy a public read-only link to this note and its attachments",
+                onclick: share,
+                if current.is_some() { "New share link" } else { "Share link" }
+            }
+            Button {
+                variant: ButtonVariant::Ghost,
+                disabled: busy(),
+                title: "Stop every public link to this note from working",
+                onclick: revoke,
+                "Revoke links"
+            }
+            match message() {
+                Some(Ok(text)) => rsx! {
+                    span { style: "color: {colors.text_secondary};", "{text}" }
+                },
+                Some(Err(text)) => rsx! {
+                    span { style: "color: {colors.error};", "{text}" }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
+
+/// Whether `note_id` may be shared; notes marked no-export are only shared
+/// once the user confirms.
+async fn confirm_share(note_id: &NoteId, db: Option<Arc<DatabaseService>>) -> Result<bool, String> {
+    let db = db.ok_or_else(|| "Database not initialized".to_string())?;
+    let note = db
+        .get_note(note_id)
+        .await
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| format!("Note {note_id} not found"))?;
+    let privacy = db
+        .export_privacy(false)
+        .await
+        .map_err(|error| error.to_string())?;
+    if privacy.allows(&note) {
+        return Ok(true);
+    }
+
+    let answer = AsyncMessageDialog::new()
+        .set_level(MessageLevel::Warning)
+        .set_title("Share a private note?")
+        .set_description(
+            "This note is marked no-export. Anyone with the link will be able to read it.",
+        )
+        .set_buttons(MessageButtons::OkCancel)
+        .show()
+        .await;
+    Ok(matches!(answer, MessageDialogResult::Ok))
+}
+
+/// Create a link to `note_id` with the server's default lifetime.
+pub(super) async fn create_share_link(
+    note_id: &NoteId,
+    media_api: Option<Arc<MediaApiClient>>,
+    auth_session: Option<AuthSession>,
+) -> Result<ShareLink, String> {
+    let (client, access_token) = share_client(media_api, auth_session)?;
+    client.create(&access_token, note_id, None).await
+}
+
+/// Revoke every active link to `note_id`, returning how many were revoked.
+pub(super) async fn revoke_share_links(
+    note_id: &NoteId,
+    media_api: Option<Arc<MediaApiClient>>,
+    auth_session: Option<AuthSession>,
+) -> Result<usize, String> {
+    let (client, access_token) = share_client(media_api, auth_session)?;
+    let now = chrono::Utc::now().timestamp();
+    let mut revoked = 0;
+    for link in client.list(&access_token, note_id).await? {
+        if link.revoked_at.is_none() && link.expires_at > now {
+            client.revoke(&access_token, &link.id).await?;
+            revoked += 1;
+        }
+    }
+    Ok(revoked)
+}
+
+/// Share links go to the same Dirt API that serves attachments.
+fn share_client(
+    media_api: Option<Arc<MediaApiClient>>,
+    auth_session: Option<AuthSession>,
+) -> Result<(ShareApiClient, String), String> {
+    let Some(media_api) = media_api else {
+        return Err("Share links need the Dirt API, which is not configured.".to_string());
+    };
+    let access_token = require_media_access_token(auth_session)?;
+    Ok((ShareApiClient::new(media_api.base_url())?, access_token))
+}
+
+fn format_expiry(expires_at: i64) -> String {
+    chrono::DateTime::from_timestamp(expires_at, 0)
+        .map(|date_time| date_time.with_timezone(&chrono::Local))
+        .map_or_else(
+            || expires_at.to_string(),
+            |date_time| date_time.format("%Y-%m-%d %H:%M").to_string(),
+        )
+}
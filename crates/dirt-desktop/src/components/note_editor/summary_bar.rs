@@ -0,0 +1,109 @@
+//! Summarize action for the editor
+
+use dioxus::prelude::*;
+
+use dirt_core::models::SummaryMethod;
+use dirt_core::summarize::summarize_note;
+use dirt_core::NoteId;
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::queries::invalidate_notes_query;
+use crate::services::summary_config;
+use crate::state::AppState;
+
+/// Footer action that summarizes the current note and shows the summary
+#[component]
+pub(super) fn SummarizeAction(note_id: NoteId, editor_content: String) -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+    let mut summary = use_signal(|| None::<(NoteId, String, SummaryMethod)>);
+
+    // Show the stored summary of the selected note, if it has one.
+    use_effect(use_reactive!(|note_id| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        error.set(None);
+        spawn(async move {
+            match db.get_note_summary(&note_id).await {
+                Ok(stored) => summary
+                    .set(stored.map(|stored| (stored.note_id, stored.summary, stored.method))),
+                Err(load_error) => tracing::warn!("Failed to load note summary: {}", load_error),
+            }
+        });
+    }));
+
+    let summarize = move |_| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        let config = match summary_config() {
+            Ok(config) => config,
+            Err(config_error) => {
+                error.set(Some(config_error));
+                return;
+            }
+        };
+        let latest_content = editor_content.clone();
+        busy.set(true);
+        error.set(None);
+
+        spawn(async move {
+            // Flush unsaved edits so the summary matches the editor.
+            let result = match db.update_note(&note_id, &latest_content).await {
+                Ok(note) => summarize_note(&db, config.as_ref(), &note).await,
+                Err(save_error) => Err(save_error),
+            };
+            match result {
+                Ok(stored) => {
+                    summary.set(Some((stored.note_id, stored.summary, stored.method)));
+                    invalidate_notes_query().await;
+                }
+                Err(summary_error) => {
+                    tracing::error!("Failed to summarize note: {}", summary_error);
+                    error.set(Some(summary_error.to_string()));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    let shown = summary().filter(|(summary_note_id, _, _)| *summary_note_id == note_id);
+
+    rsx! {
+        div {
+            style: "display: flex; flex-direction: column; gap: 4px; padding-top: 8px; font-size: 12px;",
+            div {
+                style: "display: flex; align-items: center; gap: 8px;",
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    disabled: busy(),
+                    title: "Summarize with OpenAI when an API key is set, otherwise on this device",
+                    onclick: summarize,
+                    if busy() {
+                        "Summarizing..."
+                    } else if shown.is_some() {
+                        "Resummarize"
+                    } else {
+                        "Summarize"
+                    }
+                }
+                if let Some(message) = error() {
+                    span { style: "color: {colors.error};", "{message}" }
+                }
+            }
+            if let Some((_, text, method)) = shown {
+                div {
+                    style: "color: {colors.text_secondary}; line-height: 1.5;",
+                    span {
+                        style: "color: {colors.text_muted};",
+                        if method == SummaryMethod::OpenAi { "Summary (OpenAI): " } else { "Summary: " }
+                    }
+                    "{text}"
+                }
+            }
+        }
+    }
+}
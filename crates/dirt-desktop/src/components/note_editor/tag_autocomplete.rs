@@ -0,0 +1,101 @@
+use dioxus::prelude::*;
+
+use crate::state::AppState;
+
+/// Maximum number of tag suggestions shown in the popup
+pub(super) const TAG_SUGGESTION_LIMIT: usize = 6;
+
+const READ_CURSOR_SCRIPT: &str = r"
+const el = document.querySelector('.editor-textarea');
+return el ? el.selectionStart : null;
+";
+
+/// Read the editor caret position as a byte offset into `text`.
+pub(super) async fn read_editor_cursor(text: &str) -> Option<usize> {
+    let offset: Option<usize> = document::eval(READ_CURSOR_SCRIPT).join().await.ok()?;
+    offset.map(|utf16_offset| utf16_to_byte_offset(text, utf16_offset))
+}
+
+/// Move the editor caret to a byte offset into `text`.
+pub(super) fn set_editor_cursor(text: &str, byte_offset: usize) {
+    let utf16_offset = text[..byte_offset.min(text.len())].encode_utf16().count();
+    let script = format!(
+        "const el = document.querySelector('.editor-textarea');
+         if (el) {{ el.focus(); el.setSelectionRange({utf16_offset}, {utf16_offset}); }}"
+    );
+    let _ = document::eval(&script);
+}
+
+/// Convert a JS `selectionStart` (UTF-16 code units) into a byte offset.
+pub(super) fn utf16_to_byte_offset(text: &str, utf16_offset: usize) -> usize {
+    let mut units = 0;
+    for (index, ch) in text.char_indices() {
+        if units >= utf16_offset {
+            return index;
+        }
+        units += ch.len_utf16();
+    }
+    text.len()
+}
+
+/// Popup listing tag completions below the editor
+#[component]
+pub(super) fn TagSuggestions(
+    suggestions: Vec<String>,
+    selected: usize,
+    on_pick: EventHandler<String>,
+) -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+
+    rsx! {
+        div {
+            class: "tag-suggestions",
+            style: "
+                display: flex;
+                flex-wrap: wrap;
+                gap: 6px;
+                padding: 6px 0;
+            ",
+            for (index, tag) in suggestions.into_iter().enumerate() {
+                {
+                    let is_selected = index == selected;
+                    let background = if is_selected { colors.accent } else { colors.bg_secondary };
+                    let text_color = if is_selected { colors.accent_text } else { colors.text_secondary };
+                    let tag_for_pick = tag.clone();
+                    rsx! {
+                        button {
+                            key: "{tag}",
+                            style: "
+                                border: 1px solid {colors.border};
+                                border-radius: 12px;
+                                padding: 2px 10px;
+                                font-size: 12px;
+                                cursor: pointer;
+                                background: {background};
+                                color: {text_color};
+                            ",
+                            // Keep focus in the textarea so the caret survives the pick.
+                            onmousedown: move |evt: Event<MouseData>| evt.prevent_default(),
+                            onclick: move |_| on_pick.call(tag_for_pick.clone()),
+                            "#{tag}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::utf16_to_byte_offset;
+
+    #[test]
+    fn converts_utf16_offsets_to_byte_offsets() {
+        assert_eq!(utf16_to_byte_offset("abc", 2), 2);
+        assert_eq!(utf16_to_byte_offset("héllo #t", 8), 9);
+        assert_eq!(utf16_to_byte_offset("😀 #t", 5), 7);
+        assert_eq!(utf16_to_byte_offset("abc", 10), 3);
+    }
+}
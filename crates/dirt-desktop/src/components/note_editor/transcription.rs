@@ -3,6 +3,7 @@ use std::time::Instant;
 
 use dioxus::prelude::*;
 
+use dirt_core::transcription::append_voice_memo_transcript;
 use dirt_core::NoteId;
 
 use crate::queries::invalidate_notes_query;
@@ -84,7 +85,7 @@ async fn transcribe_voice_memo(
     audio_bytes: Vec<u8>,
 ) -> Result<String, String> {
     transcription_service
-        .transcribe_audio_bytes(file_name, mime_type, audio_bytes)
+        .transcribe_audio_bytes(file_name, mime_type, &audio_bytes)
         .await
         .map_err(|error| {
             tracing::warn!("Voice memo transcription failed: {}", error);
@@ -99,60 +100,18 @@ async fn append_voice_memo_transcription_to_note(
     transcript: &str,
     latest_editor_content: Option<String>,
 ) -> Result<Option<String>, String> {
-    let existing_note = db
-        .get_note(note_id)
-        .await
-        .map_err(|error| {
-            tracing::warn!("Failed to load note for transcription append: {}", error);
-            format!("Voice memo uploaded, but transcription could not update the note: {error}")
-        })?
-        .ok_or_else(|| "Voice memo uploaded, but note was no longer available.".to_string())?;
-
-    let base_content = latest_editor_content.unwrap_or(existing_note.content);
-    let Some(updated_content) =
-        append_voice_memo_transcription(&base_content, file_name, transcript)
-    else {
-        return Ok(None);
-    };
-
-    let updated_note = db
-        .update_note(note_id, &updated_content)
-        .await
-        .map_err(|error| {
-            tracing::warn!(
-                "Failed to save voice memo transcription into note: {}",
-                error
-            );
-            format!("Voice memo uploaded, but transcription could not be saved: {error}")
-        })?;
-
-    Ok(Some(updated_note.content))
-}
-
-fn append_voice_memo_transcription(
-    existing_content: &str,
-    file_name: &str,
-    transcription: &str,
-) -> Option<String> {
-    let normalized_transcription = transcription.trim();
-    if normalized_transcription.is_empty() {
-        return None;
-    }
-
-    let normalized_file_name = if file_name.trim().is_empty() {
-        "voice memo"
-    } else {
-        file_name.trim()
-    };
-    let transcription_block =
-        format!("[Voice memo transcript: {normalized_file_name}]\n{normalized_transcription}");
-    let normalized_existing = existing_content.trim_end();
-
-    if normalized_existing.is_empty() {
-        Some(transcription_block)
-    } else {
-        Some(format!("{normalized_existing}\n\n{transcription_block}"))
-    }
+    let updated_note =
+        append_voice_memo_transcript(db, note_id, file_name, transcript, latest_editor_content)
+            .await
+            .map_err(|error| {
+                tracing::warn!(
+                    "Failed to save voice memo transcription into note: {}",
+                    error
+                );
+                format!("Voice memo uploaded, but transcription could not be saved: {error}")
+            })?;
+
+    Ok(updated_note.map(|note| note.content))
 }
 
 pub(super) fn format_recording_duration(duration_ms: u64) -> String {
@@ -176,26 +135,4 @@ mod tests {
         assert_eq!(format_recording_duration(12_345), "00:12");
         assert_eq!(format_recording_duration(120_000), "02:00");
     }
-
-    #[test]
-    fn appends_voice_memo_transcription_with_separator() {
-        let updated = append_voice_memo_transcription(
-            "Existing note body",
-            "memo.webm",
-            "  Hello from transcript.  ",
-        )
-        .unwrap();
-
-        assert_eq!(
-            updated,
-            "Existing note body\n\n[Voice memo transcript: memo.webm]\nHello from transcript."
-        );
-    }
-
-    #[test]
-    fn ignores_empty_voice_memo_transcription() {
-        assert!(
-            append_voice_memo_transcription("Existing note body", "memo.webm", "   ").is_none()
-        );
-    }
 }
@@ -0,0 +1,140 @@
+//! Translate action for the editor
+
+use dioxus::prelude::*;
+
+use dirt_core::models::NoteSource;
+use dirt_core::translate::{normalize_language, translate_note_with_config, TranslationOutput};
+use dirt_core::NoteId;
+
+use crate::components::button::{Button, ButtonVariant};
+use crate::queries::invalidate_notes_query;
+use crate::services::translation_config;
+use crate::state::AppState;
+
+/// Footer action that translates the current note into another language
+#[component]
+pub(super) fn TranslateAction(
+    note_id: NoteId,
+    editor_content: String,
+    on_editor_content_change: EventHandler<String>,
+) -> Element {
+    let mut state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+    let mut picking = use_signal(|| false);
+    let mut language_input = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let mut translate = move |output: TranslationOutput, latest_content: String| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        let Some(language) = normalize_language(&language_input()) else {
+            error.set(Some(
+                "Enter a language code such as ja, de, or pt-BR.".to_string(),
+            ));
+            return;
+        };
+        let config = match translation_config() {
+            Ok(config) => config,
+            Err(config_error) => {
+                error.set(Some(config_error));
+                return;
+            }
+        };
+        busy.set(true);
+        error.set(None);
+
+        spawn(async move {
+            // Flush unsaved edits so the translation matches the editor.
+            let result = match db.update_note(&note_id, &latest_content).await {
+                Ok(note) => {
+                    translate_note_with_config(
+                        &db,
+                        &config,
+                        &note,
+                        &language,
+                        output,
+                        NoteSource::Desktop,
+                    )
+                    .await
+                }
+                Err(save_error) => Err(save_error),
+            };
+            match result {
+                Ok(translated) => {
+                    picking.set(false);
+                    language_input.set(String::new());
+                    if translated.id == note_id {
+                        on_editor_content_change.call(translated.content);
+                        invalidate_notes_query().await;
+                    } else {
+                        // Reload first so the new note is listed when it opens.
+                        invalidate_notes_query().await;
+                        state.current_note_id.set(Some(translated.id));
+                    }
+                }
+                Err(translate_error) => {
+                    tracing::error!("Failed to translate note: {}", translate_error);
+                    error.set(Some(translate_error.to_string()));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div {
+            style: "display: flex; align-items: center; gap: 8px; padding-top: 8px; font-size: 12px;",
+            if picking() {
+                span { style: "color: {colors.text_secondary};", "Translate to" }
+                input {
+                    class: "input",
+                    style: "width: 80px;",
+                    placeholder: "ja",
+                    value: "{language_input}",
+                    oninput: move |evt| language_input.set(evt.value()),
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    disabled: busy() || language_input().trim().is_empty(),
+                    title: "Save the translation as a new note linked to this one",
+                    onclick: {
+                        let content = editor_content.clone();
+                        move |_| translate(TranslationOutput::LinkedNote, content.clone())
+                    },
+                    if busy() { "Translating..." } else { "New note" }
+                }
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    disabled: busy() || language_input().trim().is_empty(),
+                    title: "Add the translation as a section at the end of this note",
+                    onclick: {
+                        let content = editor_content.clone();
+                        move |_| translate(TranslationOutput::AppendSection, content.clone())
+                    },
+                    "Append"
+                }
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    disabled: busy(),
+                    onclick: move |_| {
+                        picking.set(false);
+                        error.set(None);
+                    },
+                    "Cancel"
+                }
+            } else {
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    title: "Translate this note with OpenAI or DeepL",
+                    onclick: move |_| picking.set(true),
+                    "Translate..."
+                }
+            }
+            if let Some(message) = error() {
+                span { style: "color: {colors.error};", "{message}" }
+            }
+        }
+    }
+}
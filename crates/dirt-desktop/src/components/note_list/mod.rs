@@ -0,0 +1,330 @@
+//! Note list component
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use dioxus::prelude::*;
+
+use dirt_core::models::{append_note_page, move_note_in_order, NoteCursor, NoteId};
+use dirt_core::summarize::list_preview_summary;
+
+use super::button::{Button, ButtonVariant};
+use super::NoteCard;
+use crate::queries::NOTE_PAGE_SIZE;
+use crate::state::AppState;
+use window::{RowWindow, DEFAULT_VIEWPORT_HEIGHT_PX, NOTE_ROW_HEIGHT_PX};
+
+mod window;
+
+/// List of notes with previews
+///
+/// While a tag is selected the list can switch to manual order, where notes
+/// are dragged into place and the order is saved per tag.
+///
+/// Only rows near the viewport are mounted, with spacers standing in for the
+/// rest, and older pages of notes are fetched as the list scrolls.
+#[component]
+pub fn NoteList() -> Element {
+    let mut state = use_context::<AppState>();
+    let mut timestamp_tick = use_signal(|| 0_u64);
+    let mut dragged_note = use_signal(|| None::<NoteId>);
+    let mut list_element = use_signal(|| None::<Rc<MountedData>>);
+    let mut scroll_top = use_signal(|| 0.0_f64);
+    let mut viewport_height = use_signal(|| DEFAULT_VIEWPORT_HEIGHT_PX);
+    let mut loading_more = use_signal(|| false);
+    let mut notes_exhausted = use_signal(|| false);
+
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            timestamp_tick.set(timestamp_tick().wrapping_add(1));
+        }
+    });
+
+    // Reload the stored order whenever the manual-sort scope changes.
+    use_effect(move || {
+        let scope = state.manual_sort_scope();
+        let db = state.db_service.read().clone();
+        let (Some(scope), Some(db)) = (scope, db) else {
+            state.manual_order.set(Vec::new());
+            return;
+        };
+        spawn(async move {
+            match db.list_manual_order(&scope).await {
+                Ok(order) => state.manual_order.set(order),
+                Err(error) => tracing::error!("Failed to load manual order: {}", error),
+            }
+        });
+    });
+
+    // Stored summaries stand in for the preview line of long notes.
+    let note_summaries = use_resource(move || {
+        let db = state.db_service.read().clone();
+        // Reload whenever notes change so new and stale summaries show up
+        let _ = state.notes.read().len();
+        async move {
+            let Some(db) = db else {
+                return HashMap::new();
+            };
+            match db.list_note_summaries().await {
+                Ok(summaries) => summaries
+                    .into_iter()
+                    .map(|summary| (summary.note_id, summary))
+                    .collect(),
+                Err(error) => {
+                    tracing::error!("Failed to load note summaries: {}", error);
+                    HashMap::new()
+                }
+            }
+        }
+    });
+
+    let measure_viewport = move || async move {
+        let Some(element) = list_element.peek().clone() else {
+            return;
+        };
+        if let Ok(offset) = element.get_scroll_offset().await {
+            scroll_top.set(offset.y);
+        }
+        if let Ok(rect) = element.get_client_rect().await {
+            viewport_height.set(rect.height());
+        }
+    };
+
+    let mut load_more = move || {
+        if *loading_more.peek() || *notes_exhausted.peek() {
+            return;
+        }
+        let Some(db) = state.db_service.peek().clone() else {
+            return;
+        };
+        let cursor = NoteCursor::after_oldest(&state.notes.peek());
+        loading_more.set(true);
+        spawn(async move {
+            match db.list_notes_page(cursor.as_ref(), NOTE_PAGE_SIZE).await {
+                Ok(page) => {
+                    if page.next_cursor.is_none() {
+                        notes_exhausted.set(true);
+                    }
+                    append_note_page(&mut state.notes.write(), page.notes);
+                }
+                Err(error) => tracing::error!("Failed to load more notes: {}", error),
+            }
+            loading_more.set(false);
+        });
+    };
+
+    // A reconnected database starts over from its first page.
+    use_effect(move || {
+        let _ = state.db_service.read();
+        notes_exhausted.set(false);
+    });
+
+    // Fetch the next page once the window nears the last loaded note. While
+    // searching or filtering by tag, pages load from a button instead so a
+    // narrow filter does not pull in the whole database.
+    use_effect(move || {
+        let filtering = !(state.search_query)().is_empty() || (state.active_tag_filter)().is_some();
+        let total = state
+            .notes
+            .read()
+            .iter()
+            .filter(|note| !note.is_deleted)
+            .count();
+        let window = RowWindow::visible(scroll_top(), viewport_height(), total);
+        if !filtering && total > 0 && window.near_end() {
+            load_more();
+        }
+    });
+
+    // Force periodic rerender so relative timestamps stay fresh.
+    _ = timestamp_tick();
+
+    let filtered_notes = state.filtered_notes();
+    let summaries = note_summaries().unwrap_or_default();
+    let current_id = (state.current_note_id)();
+    let colors = (state.theme)().palette();
+    let active_tag = (state.active_tag_filter)();
+    let manual_sort_enabled = (state.manual_sort_enabled)();
+    // Reordering a search subset would drop the hidden notes' positions.
+    let can_reorder = state.manual_sort_scope().is_some() && (state.search_query)().is_empty();
+    let filtering = !(state.search_query)().is_empty() || active_tag.is_some();
+    let row_window = RowWindow::visible(scroll_top(), viewport_height(), filtered_notes.len());
+    let top_spacer = row_window.top_spacer_px();
+    let bottom_spacer = row_window.bottom_spacer_px();
+    let show_load_more = filtering && !notes_exhausted();
+
+    let toggle_manual_sort = move |_: MouseEvent| {
+        state.manual_sort_enabled.set(!manual_sort_enabled);
+    };
+
+    let reset_order = move |_: MouseEvent| {
+        let (Some(scope), Some(db)) = (state.manual_sort_scope(), state.db_service.read().clone())
+        else {
+            return;
+        };
+        state.manual_order.set(Vec::new());
+        spawn(async move {
+            if let Err(error) = db.clear_manual_order(&scope).await {
+                tracing::error!("Failed to reset manual order: {}", error);
+            }
+        });
+    };
+
+    let mut drop_on = move |target_index: usize| {
+        let Some(note_id) = dragged_note() else {
+            return;
+        };
+        dragged_note.set(None);
+        let (Some(scope), Some(db)) = (state.manual_sort_scope(), state.db_service.read().clone())
+        else {
+            return;
+        };
+        let visible: Vec<NoteId> = state.filtered_notes().iter().map(|note| note.id).collect();
+        // Show the new order immediately; the saved order replaces it below.
+        state
+            .manual_order
+            .set(move_note_in_order(&visible, note_id, target_index));
+        spawn(async move {
+            match db
+                .move_note_in_scope(&scope, &visible, note_id, target_index)
+                .await
+            {
+                Ok(order) => state.manual_order.set(order),
+                Err(error) => tracing::error!("Failed to save manual order: {}", error),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "note-list",
+            style: "
+                width: 280px;
+                border-right: 1px solid {colors.border};
+                display: flex;
+                flex-direction: column;
+                min-height: 0;
+                background: {colors.bg_primary};
+            ",
+
+            if let Some(tag) = active_tag {
+                div {
+                    style: "
+                        display: flex;
+                        align-items: center;
+                        gap: 6px;
+                        padding: 6px 10px;
+                        border-bottom: 1px solid {colors.border_light};
+                        font-size: 12px;
+                        color: {colors.text_muted};
+                    ",
+                    span { style: "flex: 1;", "#{tag}" }
+                    Button {
+                        variant: if manual_sort_enabled { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                        onclick: toggle_manual_sort,
+                        "Manual order"
+                    }
+                    if manual_sort_enabled {
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: reset_order,
+                            "Reset"
+                        }
+                    }
+                }
+            }
+
+            div {
+                class: "note-list-rows",
+                style: "flex: 1; min-height: 0; overflow-y: auto;",
+                onmounted: move |evt: MountedEvent| async move {
+                    list_element.set(Some(evt.data()));
+                    measure_viewport().await;
+                },
+                onscroll: move |_| measure_viewport(),
+
+                if filtered_notes.is_empty() {
+                    div {
+                        style: "
+                            padding: 20px;
+                            text-align: center;
+                            color: {colors.text_muted};
+                        ",
+                        "No notes yet"
+                    }
+                } else {
+                    div { style: "height: {top_spacer}px;" }
+
+                    for (index, note) in filtered_notes
+                        .into_iter()
+                        .enumerate()
+                        .skip(row_window.start)
+                        .take(row_window.len())
+                    {
+                        {
+                            let note_id = note.id;
+                            let is_selected = current_id == Some(note_id);
+                            let title = note.title_preview(40);
+                            let preview = list_preview_summary(&note, summaries.get(&note_id))
+                                .map_or_else(|| note.title_preview(60), str::to_string);
+                            let updated_at_ms = note.updated_at;
+                            let opacity = if dragged_note() == Some(note_id) { 0.5 } else { 1.0 };
+
+                            rsx! {
+                                div {
+                                    key: "{note_id}",
+                                    draggable: can_reorder,
+                                    style: "
+                                        height: {NOTE_ROW_HEIGHT_PX}px;
+                                        overflow: hidden;
+                                        opacity: {opacity};
+                                    ",
+                                    ondragstart: move |_| {
+                                        if can_reorder {
+                                            dragged_note.set(Some(note_id));
+                                        }
+                                    },
+                                    ondragover: move |evt: DragEvent| {
+                                        if can_reorder {
+                                            evt.prevent_default();
+                                        }
+                                    },
+                                    ondragend: move |_| dragged_note.set(None),
+                                    ondrop: move |evt: DragEvent| {
+                                        evt.prevent_default();
+                                        drop_on(index);
+                                    },
+                                    NoteCard {
+                                        title,
+                                        preview,
+                                        updated_at_ms,
+                                        is_selected,
+                                        onclick: move |_| {
+                                            state.current_note_id.set(Some(note_id));
+                                        },
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div { style: "height: {bottom_spacer}px;" }
+                }
+
+                if show_load_more {
+                    div {
+                        style: "padding: 8px; text-align: center;",
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            disabled: loading_more(),
+                            onclick: move |_| load_more(),
+                            if loading_more() { "Loading..." } else { "Load more notes" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Row window math for the virtualized note list
+
+/// Fixed height of one note row, in CSS pixels
+pub(super) const NOTE_ROW_HEIGHT_PX: f64 = 88.0;
+/// Rows mounted above and below the viewport to hide blank flashes
+const OVERSCAN_ROWS: usize = 8;
+/// Load the next page once the window is this close to the last loaded row
+const LOAD_MORE_THRESHOLD_ROWS: usize = 40;
+/// Viewport height assumed until the list has been measured
+pub(super) const DEFAULT_VIEWPORT_HEIGHT_PX: f64 = 900.0;
+
+/// Rows `start..end` are mounted; the rest are replaced by spacers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct RowWindow {
+    pub start: usize,
+    pub end: usize,
+    pub total: usize,
+}
+
+impl RowWindow {
+    /// Window of rows visible at `scroll_top` in a viewport `viewport_height`
+    /// tall, padded by a few overscan rows on each side
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn visible(scroll_top: f64, viewport_height: f64, total: usize) -> Self {
+        let first_visible = (scroll_top.max(0.0) / NOTE_ROW_HEIGHT_PX).floor() as usize;
+        let visible_rows = (viewport_height.max(0.0) / NOTE_ROW_HEIGHT_PX).ceil() as usize + 1;
+
+        let start = first_visible.saturating_sub(OVERSCAN_ROWS).min(total);
+        let end = first_visible
+            .saturating_add(visible_rows)
+            .saturating_add(OVERSCAN_ROWS)
+            .min(total);
+        Self { start, end, total }
+    }
+
+    /// Number of mounted rows
+    pub const fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Spacer height standing in for the rows above the window
+    #[allow(clippy::cast_precision_loss)]
+    pub const fn top_spacer_px(&self) -> f64 {
+        self.start as f64 * NOTE_ROW_HEIGHT_PX
+    }
+
+    /// Spacer height standing in for the rows below the window
+    #[allow(clippy::cast_precision_loss)]
+    pub const fn bottom_spacer_px(&self) -> f64 {
+        (self.total - self.end) as f64 * NOTE_ROW_HEIGHT_PX
+    }
+
+    /// Whether the window is close enough to the end to fetch another page
+    pub const fn near_end(&self) -> bool {
+        self.end.saturating_add(LOAD_MORE_THRESHOLD_ROWS) >= self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_at_top_mounts_viewport_and_overscan() {
+        let window = RowWindow::visible(0.0, NOTE_ROW_HEIGHT_PX * 10.0, 20_000);
+
+        assert_eq!(window.start, 0);
+        assert_eq!(window.end, 11 + OVERSCAN_ROWS);
+        assert!(window.top_spacer_px().abs() < f64::EPSILON);
+        assert!(!window.near_end());
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn window_in_middle_keeps_spacers_consistent() {
+        let total = 20_000;
+        let window = RowWindow::visible(NOTE_ROW_HEIGHT_PX * 5_000.5, 900.0, total);
+
+        assert_eq!(window.start, 5_000 - OVERSCAN_ROWS);
+        assert!(window.len() < 40);
+        let covered = window.top_spacer_px()
+            + window.len() as f64 * NOTE_ROW_HEIGHT_PX
+            + window.bottom_spacer_px();
+        assert!((covered - total as f64 * NOTE_ROW_HEIGHT_PX).abs() < 1.0);
+    }
+
+    #[test]
+    fn window_clamps_to_short_lists_and_requests_more() {
+        let window = RowWindow::visible(10_000.0, 900.0, 3);
+
+        assert_eq!((window.start, window.end), (3, 3));
+        assert!(window.bottom_spacer_px().abs() < f64::EPSILON);
+        assert!(window.near_end());
+        assert!(RowWindow::visible(0.0, 900.0, 0).near_end());
+    }
+}
@@ -1,16 +1,90 @@
-//! Quick capture component
+//! Quick capture window
 //!
-//! When triggered via global hotkey, the main window resizes to a compact
-//! capture box (420x200). This component fills that window entirely.
-//! On save/cancel it hides immediately; main-window geometry is restored when
-//! the user explicitly reopens the full app from tray.
+//! The global hotkey opens a frameless, always-on-top capture box (420x200)
+//! in its own window, so the main window can stay hidden in the tray. The
+//! window is created on first use and only hidden on save/cancel, which keeps
+//! it ready for the next capture and lets saves started from it finish.
+//!
+//! A template picker swaps the textarea for a small form of template fields;
+//! the window grows to fit the form.
 
-use dioxus::desktop::window;
+use dioxus::desktop::{
+    window, Config, DesktopContext, LogicalPosition, LogicalSize, WindowBuilder,
+    WindowCloseBehaviour,
+};
 use dioxus::prelude::*;
+use dirt_core::capture::PostCaptureActions;
+use dirt_core::models::{builtin_templates, CaptureTemplate, NoteSource};
 
 use super::button::{Button, ButtonVariant};
-use crate::queries::invalidate_notes_query;
+use super::note_actions::{capture_text_to_daily_note, create_note_optimistic_from};
 use crate::state::AppState;
+use crate::theme::ResolvedTheme;
+
+/// Quick capture window width (matches the size set when opening it)
+const CAPTURE_WIDTH: f64 = 420.0;
+/// Quick capture window height without a template
+const CAPTURE_HEIGHT: f64 = 200.0;
+/// Extra window height per template field
+const TEMPLATE_FIELD_HEIGHT: f64 = 64.0;
+
+/// Focuses the capture input when the reused window is shown again.
+const FOCUS_INPUT_SCRIPT: &str = r#"
+    const input = document.querySelector(".quick-capture textarea, .quick-capture input");
+    if (input) { input.focus(); }
+"#;
+
+/// Show the quick capture window, creating it on first use.
+///
+/// Must be called from the main window, whose [`AppState`] the capture window
+/// shares.
+pub async fn show_quick_capture_window(
+    mut capture_window: Signal<Option<DesktopContext>>,
+    mut state: AppState,
+) {
+    let existing = capture_window.peek().clone();
+    let capture = match existing {
+        Some(capture) => capture,
+        None => {
+            let dom = VirtualDom::new(QuickCaptureWindow).with_root_context(state);
+            let config = Config::new()
+                .with_window(
+                    WindowBuilder::new()
+                        .with_title("Dirt Quick Capture")
+                        .with_decorations(false)
+                        .with_always_on_top(true)
+                        .with_resizable(false)
+                        .with_visible(false)
+                        .with_inner_size(LogicalSize::new(CAPTURE_WIDTH, CAPTURE_HEIGHT)),
+                )
+                .with_menu(None)
+                .with_close_behaviour(WindowCloseBehaviour::WindowHides);
+            let capture = window().new_window(dom, config).resolve().await;
+            capture_window.set(Some(capture.clone()));
+            capture
+        }
+    };
+
+    center_on_main_monitor(&capture);
+    capture.set_visible(true);
+    capture.set_focus();
+    state.quick_capture_open.set(true);
+}
+
+/// Center the capture window on the monitor of the (possibly hidden) main window.
+fn center_on_main_monitor(capture: &DesktopContext) {
+    let Some(monitor) = window().window.current_monitor() else {
+        return;
+    };
+    let size = monitor.size();
+    let position = monitor.position();
+    let scale = monitor.scale_factor();
+    let x = f64::from(position.x) / scale + (f64::from(size.width) / scale - CAPTURE_WIDTH) / 2.0;
+    let y = f64::from(position.y) / scale + (f64::from(size.height) / scale - CAPTURE_HEIGHT) / 2.0;
+    capture
+        .window
+        .set_outer_position(LogicalPosition::new(x, y));
+}
 
 /// Hide quick-capture window immediately.
 fn hide_window() {
@@ -18,23 +92,101 @@ fn hide_window() {
     win.set_visible(false);
 }
 
-/// Quick capture — fills the entire (resized) window
+/// Resize the capture window to fit `field_count` template fields.
+fn resize_for_fields(field_count: usize) {
+    let extra_rows = u32::try_from(field_count.saturating_sub(1)).unwrap_or(u32::MAX);
+    let height = CAPTURE_HEIGHT + TEMPLATE_FIELD_HEIGHT * f64::from(extra_rows);
+    window().set_inner_size(LogicalSize::new(CAPTURE_WIDTH, height));
+}
+
+/// Root of the quick capture window, sharing the main window's [`AppState`]
 #[component]
-pub fn QuickCapture() -> Element {
+fn QuickCaptureWindow() -> Element {
+    let state = use_context::<AppState>();
+    let theme_attr = match (state.theme)() {
+        ResolvedTheme::Light => "light",
+        ResolvedTheme::Dark => "dark",
+    };
+
+    rsx! {
+        document::Link {
+            rel: "stylesheet",
+            href: asset!("/assets/dx-components-theme.css"),
+        }
+        document::Link { rel: "stylesheet", href: asset!("/assets/theme-overrides.css") }
+
+        div {
+            class: "app-container quick-capture",
+            "data-theme": "{theme_attr}",
+            style: "height: 100vh;",
+            QuickCapture {}
+        }
+    }
+}
+
+/// Quick capture — fills the entire capture window
+#[component]
+fn QuickCapture() -> Element {
     let mut state = use_context::<AppState>();
     let mut content = use_signal(String::new);
     let mut is_saving = use_signal(|| false);
+    let templates = use_hook(builtin_templates);
+    let mut selected_template = use_signal(|| None::<CaptureTemplate>);
+    let mut field_values = use_signal(Vec::<(String, String)>::new);
 
     let colors = (state.theme)().palette();
 
+    // The window is reused between captures, so focus the input each time it opens.
+    use_effect(move || {
+        if (state.quick_capture_open)() {
+            let _ = document::eval(FOCUS_INPUT_SCRIPT);
+        }
+    });
+
     let mut close = move || {
         hide_window();
         state.quick_capture_open.set(false);
         content.set(String::new());
+        selected_template.set(None);
+        field_values.set(Vec::new());
+        resize_for_fields(1);
+    };
+
+    let mut select_template = move |template: Option<CaptureTemplate>| {
+        let field_count = template
+            .as_ref()
+            .map_or(1, |template| template.fields.len());
+        field_values.set(Vec::new());
+        selected_template.set(template);
+        resize_for_fields(field_count);
+    };
+
+    let mut set_field = move |key: String, value: String| {
+        let mut values = field_values.write();
+        match values.iter_mut().find(|(existing, _)| *existing == key) {
+            Some(entry) => entry.1 = value,
+            None => values.push((key, value)),
+        }
     };
 
     let mut submit = move || {
-        let text = content.read().trim().to_string();
+        let text = match selected_template() {
+            Some(template) => {
+                let values = field_values();
+                if values.iter().all(|(_, value)| value.trim().is_empty()) {
+                    String::new()
+                } else {
+                    match template.render(&values) {
+                        Ok(text) => text,
+                        Err(error) => {
+                            tracing::error!("Failed to render template: {}", error);
+                            return;
+                        }
+                    }
+                }
+            }
+            None => content.read().trim().to_string(),
+        };
         if text.is_empty() {
             close();
             return;
@@ -42,21 +194,19 @@ pub fn QuickCapture() -> Element {
         if *is_saving.read() {
             return;
         }
-        is_saving.set(true);
         // Hide immediately; persist in the background.
         close();
+        let settings = state.settings.peek().clone();
+        if !settings.capture_append_daily_note {
+            let actions = PostCaptureActions::from_settings(&settings);
+            create_note_optimistic_from(&mut state, &text, NoteSource::DesktopHotkey, actions);
+            return;
+        }
+        is_saving.set(true);
         let db = state.db_service.read().clone();
         spawn(async move {
             if let Some(db) = db {
-                match db.create_note(&text).await {
-                    Ok(note) => {
-                        tracing::info!("Quick captured note: {}", note.id);
-                        invalidate_notes_query().await;
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to create note: {}", e);
-                    }
-                }
+                capture_text_to_daily_note(db, text, NoteSource::DesktopHotkey).await;
             }
             is_saving.set(false);
         });
@@ -70,6 +220,9 @@ pub fn QuickCapture() -> Element {
         close();
     };
 
+    let active_template = selected_template();
+    let active_template_id = active_template.as_ref().map(|template| template.id.clone());
+
     let handle_keydown = move |evt: Event<KeyboardData>| {
         if evt.key() == Key::Escape {
             close();
@@ -94,35 +247,107 @@ pub fn QuickCapture() -> Element {
                 flex-direction: column;
             ",
 
-            h3 {
-                style: "margin: 0 0 12px 0; font-size: 13px; color: {colors.text_secondary}; font-weight: 500;",
-                "Quick Capture"
+            div {
+                style: "display: flex; align-items: center; gap: 6px; margin: 0 0 12px 0;",
+                h3 {
+                    style: "margin: 0; margin-right: auto; font-size: 13px; color: {colors.text_secondary}; font-weight: 500;",
+                    "Quick Capture"
+                }
+                Button {
+                    variant: if active_template_id.is_none() { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                    onclick: move |_| select_template(None),
+                    style: "padding: 2px 8px; font-size: 12px;",
+                    "Note"
+                }
+                for template in templates.clone() {
+                    Button {
+                        key: "{template.id}",
+                        variant: if active_template_id.as_deref() == Some(template.id.as_str()) {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Ghost
+                        },
+                        onclick: {
+                            let template = template.clone();
+                            move |_| select_template(Some(template.clone()))
+                        },
+                        style: "padding: 2px 8px; font-size: 12px;",
+                        "{template.name}"
+                    }
+                }
             }
 
-            textarea {
-                class: "input",
-                style: "
-                    flex: 1;
-                    width: 100%;
-                    border: 1px solid {colors.border};
-                    border-radius: 8px;
-                    padding: 12px;
-                    font-size: 14px;
-                    resize: none;
-                    outline: none;
-                    font-family: inherit;
-                    box-sizing: border-box;
-                    background: {colors.bg_secondary};
-                    color: {colors.text_primary};
-                ",
-                value: "{content}",
-                placeholder: "Capture a thought... (Ctrl+Enter to save, Esc to cancel)",
-                autofocus: true,
-                onmounted: move |evt: MountedEvent| async move {
-                    _ = evt.set_focus(true).await;
-                },
-                oninput: move |evt| content.set(evt.value()),
-                onkeydown: handle_keydown,
+            if let Some(template) = active_template {
+                div {
+                    style: "flex: 1; display: flex; flex-direction: column; gap: 8px; overflow-y: auto;",
+                    onkeydown: handle_keydown,
+                    for (index, field) in template.fields.into_iter().enumerate() {
+                        {
+                            let key = field.key.clone();
+                            let value = field_values
+                                .read()
+                                .iter()
+                                .find(|(existing, _)| *existing == field.key)
+                                .map(|(_, value)| value.clone())
+                                .unwrap_or_default();
+                            let field_style = format!(
+                                "width: 100%; border: 1px solid {}; border-radius: 6px; padding: 6px 8px; font-size: 13px; outline: none; font-family: inherit; box-sizing: border-box; background: {}; color: {};",
+                                colors.border, colors.bg_secondary, colors.text_primary
+                            );
+                            rsx! {
+                                label {
+                                    key: "{field.key}",
+                                    style: "display: flex; flex-direction: column; gap: 4px; font-size: 12px; color: {colors.text_secondary};",
+                                    "{field.label}"
+                                    if field.multiline {
+                                        textarea {
+                                            class: "input",
+                                            style: "{field_style} resize: none; height: 44px;",
+                                            value: "{value}",
+                                            placeholder: "One item per line",
+                                            autofocus: index == 0,
+                                            oninput: move |evt| set_field(key.clone(), evt.value()),
+                                        }
+                                    } else {
+                                        input {
+                                            class: "input",
+                                            style: "{field_style}",
+                                            value: "{value}",
+                                            autofocus: index == 0,
+                                            oninput: move |evt| set_field(key.clone(), evt.value()),
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            } else {
+                textarea {
+                    class: "input",
+                    style: "
+                        flex: 1;
+                        width: 100%;
+                        border: 1px solid {colors.border};
+                        border-radius: 8px;
+                        padding: 12px;
+                        font-size: 14px;
+                        resize: none;
+                        outline: none;
+                        font-family: inherit;
+                        box-sizing: border-box;
+                        background: {colors.bg_secondary};
+                        color: {colors.text_primary};
+                    ",
+                    value: "{content}",
+                    placeholder: "Capture a thought... (Ctrl+Enter to save, Esc to cancel)",
+                    autofocus: true,
+                    onmounted: move |evt: MountedEvent| async move {
+                        _ = evt.set_focus(true).await;
+                    },
+                    oninput: move |evt| content.set(evt.value()),
+                    onkeydown: handle_keydown,
+                }
             }
 
             div {
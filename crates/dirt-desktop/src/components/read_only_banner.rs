@@ -0,0 +1,31 @@
+//! Banner for databases opened read-only after a newer client upgraded them
+
+use dioxus::prelude::*;
+
+use crate::state::AppState;
+
+/// Persistent banner shown while the database schema is newer than this build
+#[component]
+pub fn ReadOnlyDatabaseBanner() -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+
+    if !(state.database_read_only)() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "read-only-banner",
+            role: "status",
+            style: "
+                padding: 8px 16px;
+                border-bottom: 1px solid {colors.border};
+                background: {colors.bg_secondary};
+                color: {colors.text_primary};
+                font-size: 13px;
+            ",
+            "A newer version of Dirt upgraded this database. Notes are read-only until you update."
+        }
+    }
+}
@@ -2,17 +2,153 @@
 
 use dioxus::prelude::*;
 
+use dirt_core::search::{highlight_matches, SearchHit, SearchQuery};
+use dirt_core::{Note, NoteId};
+
 use super::input::Input;
 use crate::state::AppState;
 
+/// Maximum number of results listed under the search bar
+const SEARCH_RESULT_LIMIT: usize = 8;
+
+/// Focus the editor once the selected note has rendered.
+const FOCUS_EDITOR_SCRIPT: &str = r"
+setTimeout(() => {
+  const el = document.querySelector('.editor-textarea');
+  if (el) { el.focus(); }
+}, 0);
+";
+
 /// Search bar for filtering notes
+///
+/// While a query is typed, matching notes are listed below the input with a
+/// preview of the highlighted one: arrow keys move the highlight, Enter opens
+/// the note, and Escape hands focus back to the editor. Notes found only
+/// through an attachment's filename or text follow, marked with a paperclip.
 #[component]
 pub fn SearchBar() -> Element {
     let mut state = use_context::<AppState>();
+    let mut selected = use_signal(|| 0_usize);
+    let mut results_open = use_signal(|| false);
+
+    // `has:attachment` needs to know which notes have attachments.
+    use_effect(move || {
+        let db = state.db_service.read().clone();
+        let _ = state.notes.read().len();
+        if !SearchQuery::parse_or_literal(&(state.search_query)()).uses_attachments() {
+            return;
+        }
+        spawn(async move {
+            let Some(db) = db else {
+                return;
+            };
+            match db.list_all_attachments().await {
+                Ok(attachments) => state.attachment_note_ids.set(
+                    attachments
+                        .iter()
+                        .map(|attachment| attachment.note_id)
+                        .collect(),
+                ),
+                Err(error) => tracing::warn!("Failed to load attachments for search: {}", error),
+            }
+        });
+    });
+
+    let attachment_hits = use_resource(move || {
+        let db = state.db_service.read().clone();
+        let query = attachment_search_query(&(state.search_query)());
+        async move {
+            let (Some(db), Some(query)) = (db, query) else {
+                return Vec::new();
+            };
+            // Half-typed filters fail to parse; keep the content results alone.
+            db.search_note_hits(&query, SEARCH_RESULT_LIMIT, true)
+                .await
+                .map(|hits| {
+                    hits.into_iter()
+                        .filter(|hit| hit.matched_attachment.is_some() && !hit.note.is_archived)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        }
+    });
+
+    let query = (state.search_query)();
+    let results: Vec<SearchHit> = if query.is_empty() {
+        Vec::new()
+    } else {
+        let mut results: Vec<SearchHit> = state
+            .filtered_notes()
+            .into_iter()
+            .take(SEARCH_RESULT_LIMIT)
+            .map(|note| SearchHit {
+                note,
+                matched_attachment: None,
+            })
+            .collect();
+        for hit in attachment_hits.read().iter().flatten() {
+            if results.len() >= SEARCH_RESULT_LIMIT {
+                break;
+            }
+            if results.iter().all(|result| result.note.id != hit.note.id) {
+                results.push(hit.clone());
+            }
+        }
+        results
+    };
+    let result_count = results.len();
+    let selected_index = selected().min(result_count.saturating_sub(1));
+    let result_ids: Vec<NoteId> = results.iter().map(|hit| hit.note.id).collect();
+
+    let mut open_note = move |note_id: NoteId| {
+        state.current_note_id.set(Some(note_id));
+        results_open.set(false);
+        let _ = document::eval(FOCUS_EDITOR_SCRIPT);
+    };
+
+    let handle_keydown = move |evt: Event<KeyboardData>| {
+        if evt.is_composing() {
+            return;
+        }
+        match evt.key() {
+            Key::ArrowDown if result_count > 0 => {
+                evt.prevent_default();
+                results_open.set(true);
+                selected.set((selected_index + 1) % result_count);
+            }
+            Key::ArrowUp if result_count > 0 => {
+                evt.prevent_default();
+                results_open.set(true);
+                selected.set((selected_index + result_count - 1) % result_count);
+            }
+            Key::Enter => {
+                if let Some(note_id) = result_ids.get(selected_index).copied() {
+                    evt.prevent_default();
+                    open_note(note_id);
+                }
+            }
+            Key::Escape => {
+                // Keep the open note; only leave the search field.
+                evt.prevent_default();
+                evt.stop_propagation();
+                results_open.set(false);
+                let _ = document::eval(FOCUS_EDITOR_SCRIPT);
+            }
+            _ => {}
+        }
+    };
+
+    let preview = results.get(selected_index).map(|hit| hit.note.clone());
+    let highlight = SearchQuery::parse_or_literal(&query)
+        .highlight_text()
+        .unwrap_or_default()
+        .to_string();
+    let show_results = results_open() && result_count > 0;
 
     rsx! {
         div {
             class: "search-bar",
+            style: "position: relative;",
 
             Input {
                 r#type: "text",
@@ -20,8 +156,183 @@ pub fn SearchBar() -> Element {
                 value: "{state.search_query}",
                 oninput: move |evt: FormEvent| {
                     state.search_query.set(evt.value());
+                    selected.set(0);
+                    results_open.set(true);
                 },
+                onfocus: move |_| results_open.set(true),
+                onblur: move |_| results_open.set(false),
+                onkeydown: handle_keydown,
+            }
+
+            if show_results {
+                SearchResults {
+                    results,
+                    highlight,
+                    selected: selected_index,
+                    preview,
+                    on_hover: move |index: usize| selected.set(index),
+                    on_open: move |note_id: NoteId| open_note(note_id),
+                }
             }
         }
     }
 }
+
+/// Result list with a live preview of the highlighted note
+#[component]
+fn SearchResults(
+    results: Vec<SearchHit>,
+    highlight: String,
+    selected: usize,
+    preview: Option<Note>,
+    on_hover: EventHandler<usize>,
+    on_open: EventHandler<NoteId>,
+) -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+
+    rsx! {
+        div {
+            class: "search-results",
+            style: "
+                position: absolute;
+                top: 100%;
+                left: 16px;
+                right: 16px;
+                z-index: 20;
+                display: flex;
+                max-height: 360px;
+                border: 1px solid {colors.border};
+                border-radius: 6px;
+                background: {colors.bg_primary};
+                box-shadow: 0 8px 24px rgba(0, 0, 0, 0.2);
+                overflow: hidden;
+            ",
+            // Keep focus in the input so arrow keys keep working after a click.
+            onmousedown: move |evt: Event<MouseData>| evt.prevent_default(),
+
+            div {
+                style: "
+                    width: 240px;
+                    flex-shrink: 0;
+                    overflow-y: auto;
+                    border-right: 1px solid {colors.border_light};
+                ",
+                for (index, hit) in results.into_iter().enumerate() {
+                    {
+                        let note_id = hit.note.id;
+                        let is_selected = index == selected;
+                        let background = if is_selected { colors.accent } else { colors.bg_primary };
+                        let text_color = if is_selected { colors.accent_text } else { colors.text_primary };
+                        let title = hit.note.title_preview(40);
+                        rsx! {
+                            div {
+                                key: "{note_id}",
+                                style: "
+                                    padding: 8px 10px;
+                                    font-size: 13px;
+                                    cursor: pointer;
+                                    white-space: nowrap;
+                                    overflow: hidden;
+                                    text-overflow: ellipsis;
+                                    background: {background};
+                                    color: {text_color};
+                                ",
+                                onmouseenter: move |_| on_hover.call(index),
+                                onclick: move |_| on_open.call(note_id),
+                                "{title}"
+                                if let Some(filename) = hit.matched_attachment {
+                                    div {
+                                        style: "
+                                            font-size: 11px;
+                                            overflow: hidden;
+                                            text-overflow: ellipsis;
+                                            opacity: 0.75;
+                                        ",
+                                        title: "Matched in attachment",
+                                        "📎 {filename}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(note) = preview {
+                div {
+                    class: "search-preview",
+                    style: "
+                        flex: 1;
+                        min-width: 0;
+                        padding: 10px 12px;
+                        overflow-y: auto;
+                        white-space: pre-wrap;
+                        font-size: 13px;
+                        line-height: 1.5;
+                        color: {colors.text_secondary};
+                    ",
+                    for (index, segment) in highlight_matches(&note.content, &highlight).into_iter().enumerate() {
+                        if segment.is_match {
+                            mark {
+                                key: "{index}",
+                                style: "
+                                    background: {colors.accent};
+                                    color: {colors.accent_text};
+                                    border-radius: 2px;
+                                ",
+                                "{segment.text}"
+                            }
+                        } else {
+                            span { key: "{index}", "{segment.text}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Query for attachment matches in which the word still being typed matches
+/// as a prefix; `None` for a blank query.
+fn attachment_search_query(query: &str) -> Option<String> {
+    let query = query.trim_start();
+    if query.is_empty() {
+        return None;
+    }
+    let last_word = query.rsplit(char::is_whitespace).next().unwrap_or_default();
+    let finished = query.ends_with(char::is_whitespace)
+        || last_word.contains([':', '"', '*'])
+        || matches!(last_word, "OR" | "AND");
+    Some(if finished {
+        query.to_string()
+    } else {
+        format!("{query}*")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachment_search_query_prefixes_the_word_being_typed() {
+        assert_eq!(
+            attachment_search_query("  q3 budg"),
+            Some("q3 budg*".to_string())
+        );
+        assert_eq!(
+            attachment_search_query("budget "),
+            Some("budget ".to_string())
+        );
+        assert_eq!(
+            attachment_search_query("budget tag:work"),
+            Some("budget tag:work".to_string())
+        );
+        assert_eq!(
+            attachment_search_query("\"q3 plan\""),
+            Some("\"q3 plan\"".to_string())
+        );
+        assert_eq!(attachment_search_query("   "), None);
+    }
+}
@@ -0,0 +1,161 @@
+//! Session expiry banner and re-login dialog
+
+use dioxus::prelude::*;
+
+use super::button::{Button, ButtonVariant};
+use super::dialog::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
+use super::input::Input;
+use super::settings::format_auth_error_message;
+use crate::state::AppState;
+
+/// Persistent banner shown while sync is paused by an expired session
+#[component]
+pub fn SessionExpiredBanner() -> Element {
+    let mut state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+
+    if (state.expired_session)().is_none() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "session-expired-banner",
+            role: "alert",
+            style: "
+                display: flex;
+                align-items: center;
+                gap: 12px;
+                padding: 8px 16px;
+                border-bottom: 1px solid {colors.error};
+                background: {colors.bg_secondary};
+                color: {colors.text_primary};
+                font-size: 13px;
+            ",
+            span {
+                style: "flex: 1;",
+                "Session expired — sign in to resume sync"
+            }
+            Button {
+                variant: ButtonVariant::Primary,
+                onclick: move |_| state.reauth_open.set(true),
+                "Sign in"
+            }
+        }
+    }
+}
+
+/// Dialog to sign in again after the saved session expired
+#[component]
+pub fn ReauthDialog() -> Element {
+    let state = use_context::<AppState>();
+    let mut reauth_open = state.reauth_open;
+    let colors = (state.theme)().palette();
+    let expired_email = (state.expired_session)()
+        .and_then(|user| user.email)
+        .unwrap_or_default();
+    let mut email = use_signal(move || expired_email);
+    let mut password = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+    let mut error_message = use_signal(|| None::<String>);
+
+    let sign_in = move |_: MouseEvent| {
+        let Some(service) = state.auth_service.read().clone() else {
+            error_message.set(Some(
+                "Authentication is not available in this build.".to_string(),
+            ));
+            return;
+        };
+        let email_value = email().trim().to_string();
+        let password_value = password();
+        if email_value.is_empty() || password_value.trim().is_empty() {
+            error_message.set(Some("Email and password are required.".to_string()));
+            return;
+        }
+
+        busy.set(true);
+        error_message.set(None);
+
+        let mut auth_session = state.auth_session;
+        let mut auth_error = state.auth_error;
+        let mut expired_session = state.expired_session;
+        let mut db_reconnect_version = state.db_reconnect_version;
+        spawn(async move {
+            match service.sign_in(&email_value, &password_value).await {
+                Ok(session) => {
+                    auth_session.set(Some(session));
+                    auth_error.set(None);
+                    expired_session.set(None);
+                    password.set(String::new());
+                    reauth_open.set(false);
+                    db_reconnect_version.set(db_reconnect_version().saturating_add(1));
+                }
+                Err(error) => {
+                    tracing::error!("Re-login failed: {}", error);
+                    error_message.set(Some(format_auth_error_message(&error.to_string())));
+                }
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        DialogRoot {
+            open: true,
+            on_open_change: move |open: bool| {
+                if !open {
+                    reauth_open.set(false);
+                }
+            },
+
+            DialogContent {
+                style: "width: 380px; max-width: 90vw; text-align: left;",
+
+                DialogTitle { "Session expired" }
+                DialogDescription {
+                    "Sign in again to resume sync. Notes stay available locally in the meantime."
+                }
+
+                div {
+                    style: "display: flex; flex-direction: column; gap: 8px; margin: 12px 0;",
+                    Input {
+                        r#type: "email",
+                        placeholder: "Email",
+                        value: "{email}",
+                        disabled: busy(),
+                        oninput: move |event: FormEvent| email.set(event.value()),
+                    }
+                    Input {
+                        r#type: "password",
+                        placeholder: "Password",
+                        value: "{password}",
+                        disabled: busy(),
+                        oninput: move |event: FormEvent| password.set(event.value()),
+                    }
+                    if let Some(message) = error_message() {
+                        div {
+                            style: "font-size: 12px; color: {colors.error};",
+                            "{message}"
+                        }
+                    }
+                }
+
+                div {
+                    style: "display: flex; justify-content: flex-end; gap: 8px;",
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        disabled: busy(),
+                        onclick: move |_| reauth_open.set(false),
+                        "Not now"
+                    }
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        disabled: busy(),
+                        onclick: sign_in,
+                        if busy() { "Signing in..." } else { "Sign in" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use dioxus::prelude::*;
+
+use dirt_core::capture::normalize_capture_tag;
+use dirt_core::models::Settings;
+use dirt_core::util::{is_http_url, normalize_text_option};
+
+use super::row::SettingRow;
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::input::Input;
+use crate::services::ShareTargetKind;
+
+#[component]
+pub(super) fn CaptureSettingsTab(
+    current_settings: Settings,
+    on_save: EventHandler<Settings>,
+) -> Element {
+    let initial_tag = current_settings
+        .capture_default_tag
+        .clone()
+        .unwrap_or_default();
+    let initial_webhook = current_settings
+        .capture_webhook_url
+        .clone()
+        .unwrap_or_default();
+    let mut tag_input = use_signal(move || initial_tag);
+    let mut webhook_input = use_signal(move || initial_webhook);
+    let mut message = use_signal(|| None::<String>);
+
+    let save_tag = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            let raw = tag_input();
+            let tag = if raw.trim().is_empty() {
+                None
+            } else if let Some(tag) = normalize_capture_tag(&raw) {
+                Some(tag)
+            } else {
+                message.set(Some(format!("'{}' is not a valid tag.", raw.trim())));
+                return;
+            };
+            tag_input.set(tag.clone().unwrap_or_default());
+            message.set(None);
+            on_save.call(Settings {
+                capture_default_tag: tag,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    let save_webhook = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            let url = normalize_text_option(Some(webhook_input()));
+            if url.as_deref().is_some_and(|url| !is_http_url(url)) {
+                message.set(Some(
+                    "Webhook URL must start with http:// or https://.".to_string(),
+                ));
+                return;
+            }
+            message.set(None);
+            on_save.call(Settings {
+                capture_webhook_url: url,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    let toggle_daily_note = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            on_save.call(Settings {
+                capture_link_daily_note: !current_settings.capture_link_daily_note,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    let toggle_append_daily_note = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            on_save.call(Settings {
+                capture_append_daily_note: !current_settings.capture_append_daily_note,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    let toggle_copy_id = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            on_save.call(Settings {
+                capture_copy_note_id: !current_settings.capture_copy_note_id,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    let toggle_share_target = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            on_save.call(Settings {
+                share_target_enabled: !current_settings.share_target_enabled,
+                ..current_settings.clone()
+            });
+        }
+    };
+    let share_menu = ShareTargetKind::current().map(|kind| match kind {
+        ShareTargetKind::MacService => "the Services menu when text is selected",
+        ShareTargetKind::WindowsSendTo => "the Explorer \"Send to\" menu for text files",
+    });
+
+    rsx! {
+        SettingRow {
+            label: "Default Tag",
+            description: "Added to every quick capture and `dirt add` note",
+
+            div {
+                class: "auth-actions",
+                Input {
+                    class: "auth-input",
+                    placeholder: "inbox",
+                    value: "{tag_input}",
+                    oninput: move |event: FormEvent| tag_input.set(event.value()),
+                }
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    onclick: save_tag,
+                    "Save"
+                }
+            }
+        }
+
+        SettingRow {
+            label: "Daily Note",
+            description: "Link each capture from today's #daily note",
+
+            div {
+                class: "auth-actions",
+                Button {
+                    variant: if current_settings.capture_link_daily_note {
+                        ButtonVariant::Secondary
+                    } else {
+                        ButtonVariant::Ghost
+                    },
+                    onclick: toggle_daily_note,
+                    if current_settings.capture_link_daily_note {
+                        "Enabled"
+                    } else {
+                        "Disabled"
+                    }
+                }
+            }
+        }
+
+        SettingRow {
+            label: "Journal Mode",
+            description: "Append quick captures to today's #daily note instead of creating notes",
+
+            div {
+                class: "auth-actions",
+                Button {
+                    variant: if current_settings.capture_append_daily_note {
+                        ButtonVariant::Secondary
+                    } else {
+                        ButtonVariant::Ghost
+                    },
+                    onclick: toggle_append_daily_note,
+                    if current_settings.capture_append_daily_note {
+                        "Enabled"
+                    } else {
+                        "Disabled"
+                    }
+                }
+            }
+        }
+
+        SettingRow {
+            label: "Copy Note ID",
+            description: "Copy the id of a quick capture to the clipboard",
+
+            div {
+                class: "auth-actions",
+                Button {
+                    variant: if current_settings.capture_copy_note_id {
+                        ButtonVariant::Secondary
+                    } else {
+                        ButtonVariant::Ghost
+                    },
+                    onclick: toggle_copy_id,
+                    if current_settings.capture_copy_note_id {
+                        "Enabled"
+                    } else {
+                        "Disabled"
+                    }
+                }
+            }
+        }
+
+        if let Some(share_menu) = share_menu {
+            SettingRow {
+                label: "Share Menu",
+                description: "Offer \"Send to Dirt\" in {share_menu}",
+
+                div {
+                    class: "auth-actions",
+                    Button {
+                        variant: if current_settings.share_target_enabled {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Ghost
+                        },
+                        onclick: toggle_share_target,
+                        if current_settings.share_target_enabled {
+                            "Enabled"
+                        } else {
+                            "Disabled"
+                        }
+                    }
+                }
+            }
+        }
+
+        SettingRow {
+            label: "Webhook",
+            description: "POST each capture as JSON to this URL",
+
+            div {
+                class: "auth-actions",
+                Input {
+                    class: "auth-input",
+                    r#type: "url",
+                    placeholder: "https://example.com/hooks/dirt",
+                    value: "{webhook_input}",
+                    oninput: move |event: FormEvent| webhook_input.set(event.value()),
+                }
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    onclick: save_webhook,
+                    "Save"
+                }
+            }
+        }
+
+        if let Some(message) = message() {
+            div {
+                class: "auth-message",
+                "{message}"
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use dioxus::prelude::*;
+
+use dirt_core::models::Settings;
+
+use super::row::SettingRow;
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::input::Input;
+
+/// Narrowest and widest focus-mode line, in characters
+const LINE_WIDTH_RANGE: (u32, u32) = (40, 160);
+
+#[component]
+pub(super) fn FocusModeSettings(
+    current_settings: Settings,
+    on_save: EventHandler<Settings>,
+) -> Element {
+    let initial_width = current_settings.focus_line_width.to_string();
+    let initial_goal = current_settings
+        .focus_word_goal
+        .map(|goal| goal.to_string())
+        .unwrap_or_default();
+    let mut width_input = use_signal(move || initial_width);
+    let mut goal_input = use_signal(move || initial_goal);
+    let mut message = use_signal(|| None::<String>);
+
+    let toggle_typewriter = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            on_save.call(Settings {
+                focus_typewriter_scrolling: !current_settings.focus_typewriter_scrolling,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    let save_layout = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            let (min_width, max_width) = LINE_WIDTH_RANGE;
+            let width = match width_input().trim().parse::<u32>() {
+                Ok(width) if (min_width..=max_width).contains(&width) => width,
+                _ => {
+                    message.set(Some(format!(
+                        "Line width must be between {min_width} and {max_width} characters."
+                    )));
+                    return;
+                }
+            };
+            let raw_goal = goal_input();
+            let goal = match raw_goal.trim() {
+                "" | "0" => None,
+                value => match value.parse::<u32>() {
+                    Ok(goal) => Some(goal),
+                    Err(_) => {
+                        message.set(Some(format!(
+                            "'{value}' is not a word count; leave it empty for no goal."
+                        )));
+                        return;
+                    }
+                },
+            };
+            message.set(None);
+            on_save.call(Settings {
+                focus_line_width: width,
+                focus_word_goal: goal,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    rsx! {
+        SettingRow {
+            label: "Focus Mode",
+            description: "Hide everything but the note (Ctrl+Shift+F). Set the line width and an optional word goal",
+
+            div {
+                class: "auth-panel",
+                div {
+                    class: "auth-actions",
+                    span { class: "auth-hint", "Typewriter scrolling" }
+                    Button {
+                        variant: if current_settings.focus_typewriter_scrolling {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Ghost
+                        },
+                        onclick: toggle_typewriter,
+                        if current_settings.focus_typewriter_scrolling {
+                            "Enabled"
+                        } else {
+                            "Disabled"
+                        }
+                    }
+                }
+                div {
+                    class: "auth-actions",
+                    Input {
+                        class: "auth-input",
+                        r#type: "number",
+                        placeholder: "Line width (characters)",
+                        value: "{width_input}",
+                        oninput: move |event: FormEvent| width_input.set(event.value()),
+                    }
+                    Input {
+                        class: "auth-input",
+                        r#type: "number",
+                        placeholder: "Word goal (e.g. 500)",
+                        value: "{goal_input}",
+                        oninput: move |event: FormEvent| goal_input.set(event.value()),
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: save_layout,
+                        "Save"
+                    }
+                }
+                if let Some(message) = message() {
+                    div {
+                        class: "auth-message",
+                        "{message}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use dioxus::prelude::*;
+
+use dirt_core::models::{Hotkey, HotkeyModifiers, Settings};
+
+use super::row::SettingRow;
+use crate::components::button::{Button, ButtonVariant};
+use crate::hotkey::set_capture_hotkey;
+
+/// Records and registers the global quick-capture hotkey.
+///
+/// A recorded hotkey is checked against common system shortcuts, then
+/// registered right away; it is only saved once registration succeeds.
+#[component]
+pub(super) fn HotkeySettings(
+    hotkey_bg: &'static str,
+    hotkey_border: &'static str,
+    current_settings: Settings,
+    on_save: EventHandler<Settings>,
+) -> Element {
+    let mut recording = use_signal(|| false);
+    let mut message = use_signal(|| None::<String>);
+
+    let current_hotkey = current_settings.capture_hotkey.parse::<Hotkey>().ok();
+    let current_label = current_hotkey.as_ref().map_or_else(
+        || current_settings.capture_hotkey.clone(),
+        ToString::to_string,
+    );
+    let is_default = current_hotkey == Some(Hotkey::default());
+
+    let mut save_hotkey = move |hotkey: Hotkey, settings: Settings| {
+        let registered = match hotkey.conflict() {
+            Some(conflict) => Err(format!("{conflict}. Choose another hotkey.")),
+            None => set_capture_hotkey(&hotkey),
+        };
+        match registered {
+            Ok(()) => {
+                message.set(None);
+                on_save.call(Settings {
+                    capture_hotkey: hotkey.to_string(),
+                    ..settings
+                });
+            }
+            Err(error) => message.set(Some(error)),
+        }
+    };
+
+    let record_key = {
+        let current_settings = current_settings.clone();
+        move |event: KeyboardEvent| {
+            if !recording() {
+                return;
+            }
+            event.prevent_default();
+            if event.key() == Key::Escape {
+                recording.set(false);
+                return;
+            }
+            let modifiers = event.modifiers();
+            let held = HotkeyModifiers {
+                ctrl: modifiers.ctrl(),
+                alt: modifiers.alt(),
+                shift: modifiers.shift(),
+                meta: modifiers.meta(),
+            };
+            // Modifier presses keep recording until a key completes the hotkey.
+            let Some(hotkey) = Hotkey::from_key_code(&event.code().to_string(), held) else {
+                return;
+            };
+            recording.set(false);
+            save_hotkey(hotkey, current_settings.clone());
+        }
+    };
+
+    let reset = move |_: MouseEvent| {
+        recording.set(false);
+        save_hotkey(Hotkey::default(), current_settings.clone());
+    };
+
+    rsx! {
+        SettingRow {
+            label: "Capture Hotkey",
+            description: "Global shortcut for quick capture. Click Record, then press the new keys (Esc cancels)",
+
+            div {
+                class: "auth-panel",
+                div {
+                    class: "auth-actions",
+                    // Key presses bubble here from the focused Record button.
+                    onkeydown: record_key,
+                    div {
+                        class: "hotkey-display",
+                        style: "
+                            background: {hotkey_bg};
+                            border: 1px solid {hotkey_border};
+                        ",
+                        if recording() {
+                            "Press keys…"
+                        } else {
+                            "{current_label}"
+                        }
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: move |_| {
+                            message.set(None);
+                            recording.set(!recording());
+                        },
+                        if recording() { "Cancel" } else { "Record" }
+                    }
+                    if !is_default {
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: reset,
+                            "Reset"
+                        }
+                    }
+                }
+                if let Some(message) = message() {
+                    div {
+                        class: "auth-message",
+                        "{message}"
+                    }
+                }
+            }
+        }
+    }
+}
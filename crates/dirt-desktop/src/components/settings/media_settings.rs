@@ -1,10 +1,14 @@
 use dioxus::prelude::*;
 
 use dirt_core::models::Settings;
+use dirt_core::transcription::TranscriptionProvider;
 
 use super::row::SettingRow;
 use crate::components::button::{Button, ButtonVariant};
 use crate::components::input::Input;
+use crate::components::select::{
+    Select, SelectItemIndicator, SelectList, SelectOption, SelectTrigger, SelectValue,
+};
 
 #[component]
 pub(super) fn MediaSettingsTab(
@@ -12,6 +16,15 @@ pub(super) fn MediaSettingsTab(
     transcription_status_text: String,
     transcription_toggle_disabled: bool,
     on_toggle_transcription: EventHandler<MouseEvent>,
+    whisper_available: bool,
+    on_transcription_provider_change: EventHandler<String>,
+    whisper_model: String,
+    whisper_model_options: Vec<(String, String)>,
+    on_whisper_model_change: EventHandler<String>,
+    whisper_model_downloaded: bool,
+    whisper_download_busy: bool,
+    on_download_whisper_model: EventHandler<MouseEvent>,
+    whisper_message: Option<String>,
     openai_api_key_input: String,
     on_openai_api_key_input: EventHandler<String>,
     on_save_openai_api_key: EventHandler<MouseEvent>,
@@ -19,9 +32,22 @@ pub(super) fn MediaSettingsTab(
     openai_api_key_configured: bool,
     openai_api_key_message: Option<String>,
     export_busy: bool,
-    on_export_json: EventHandler<MouseEvent>,
-    on_export_markdown: EventHandler<MouseEvent>,
+    on_open_export: EventHandler<MouseEvent>,
+    export_include_private: bool,
+    on_toggle_export_include_private: EventHandler<MouseEvent>,
     export_message: Option<String>,
+    journal_from: String,
+    on_journal_from_input: EventHandler<String>,
+    journal_to: String,
+    on_journal_to_input: EventHandler<String>,
+    on_export_journal_pdf: EventHandler<MouseEvent>,
+    on_export_journal_markdown: EventHandler<MouseEvent>,
+    on_open_import: EventHandler<MouseEvent>,
+    on_toggle_weekly_digest: EventHandler<MouseEvent>,
+    download_dir_label: String,
+    on_choose_download_dir: EventHandler<MouseEvent>,
+    on_reset_download_dir: EventHandler<MouseEvent>,
+    on_toggle_open_folder_after_save: EventHandler<MouseEvent>,
 ) -> Element {
     rsx! {
         SettingRow {
@@ -47,6 +73,95 @@ pub(super) fn MediaSettingsTab(
             }
         }
 
+        if whisper_available {
+            SettingRow {
+                label: "Transcription Provider",
+                description: "Whisper transcribes WAV voice memos on this device without sending audio to OpenAI",
+
+                div {
+                    class: "auth-panel",
+
+                    Select::<String> {
+                        default_value: current_settings.transcription_provider.as_str().to_string(),
+                        on_value_change: move |value: Option<String>| {
+                            if let Some(value) = value {
+                                on_transcription_provider_change.call(value);
+                            }
+                        },
+
+                        SelectTrigger {
+                            style: "width: 170px;",
+                            SelectValue {}
+                        }
+
+                        SelectList {
+                            for (index, provider) in [TranscriptionProvider::OpenAi, TranscriptionProvider::Whisper]
+                                .into_iter()
+                                .enumerate()
+                            {
+                                SelectOption::<String> {
+                                    key: "{provider.as_str()}",
+                                    index,
+                                    value: provider.as_str().to_string(),
+                                    text_value: provider.label(),
+                                    "{provider.label()}"
+                                    SelectItemIndicator {}
+                                }
+                            }
+                        }
+                    }
+
+                    if current_settings.transcription_provider == TranscriptionProvider::Whisper {
+                        div {
+                            class: "auth-actions",
+                            Select::<String> {
+                                default_value: whisper_model.clone(),
+                                on_value_change: move |value: Option<String>| {
+                                    if let Some(value) = value {
+                                        on_whisper_model_change.call(value);
+                                    }
+                                },
+
+                                SelectTrigger {
+                                    style: "width: 220px;",
+                                    SelectValue {}
+                                }
+
+                                SelectList {
+                                    for (index, (value, label)) in whisper_model_options.into_iter().enumerate() {
+                                        SelectOption::<String> {
+                                            key: "{value}",
+                                            index,
+                                            value: value.clone(),
+                                            text_value: label.clone(),
+                                            "{label}"
+                                            SelectItemIndicator {}
+                                        }
+                                    }
+                                }
+                            }
+
+                            if !whisper_model_downloaded {
+                                Button {
+                                    variant: ButtonVariant::Secondary,
+                                    disabled: whisper_download_busy,
+                                    onclick: move |event| on_download_whisper_model.call(event),
+                                    "Download Model"
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some(message) = whisper_message {
+                        div {
+                            class: "auth-message",
+                            "{message}"
+                        }
+                    }
+                }
+            }
+        }
+
         SettingRow {
             label: "API Keys",
             description: "Store user-provided API keys in the OS keychain.",
@@ -98,7 +213,7 @@ pub(super) fn MediaSettingsTab(
 
         SettingRow {
             label: "Export",
-            description: "Export all notes as JSON or Markdown",
+            description: "Export all notes as JSON, Markdown, Org, or plain text, with attachments bundled, or compile a journal",
 
             div {
                 class: "auth-panel",
@@ -107,14 +222,68 @@ pub(super) fn MediaSettingsTab(
                     Button {
                         variant: ButtonVariant::Secondary,
                         disabled: export_busy,
-                        onclick: move |event| on_export_json.call(event),
-                        "Export JSON"
+                        onclick: move |event| on_open_export.call(event),
+                        "Export notes..."
+                    }
+                }
+
+                div {
+                    class: "auth-actions",
+                    span { class: "auth-hint", "Include private notes" }
+                    Button {
+                        variant: if export_include_private {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Ghost
+                        },
+                        disabled: export_busy,
+                        onclick: move |event| on_toggle_export_include_private.call(event),
+                        if export_include_private {
+                            "Included"
+                        } else {
+                            "Skipped"
+                        }
+                    }
+                }
+
+                div {
+                    class: "auth-hint",
+                    "Compile notes from a date range into a printable journal"
+                }
+
+                div {
+                    class: "auth-actions",
+                    Input {
+                        class: "auth-input",
+                        r#type: "date",
+                        value: "{journal_from}",
+                        oninput: move |event: FormEvent| {
+                            on_journal_from_input.call(event.value());
+                        },
+                    }
+                    Input {
+                        class: "auth-input",
+                        r#type: "date",
+                        value: "{journal_to}",
+                        oninput: move |event: FormEvent| {
+                            on_journal_to_input.call(event.value());
+                        },
+                    }
+                }
+
+                div {
+                    class: "auth-actions",
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        disabled: export_busy,
+                        onclick: move |event| on_export_journal_pdf.call(event),
+                        "Journal PDF"
                     }
                     Button {
                         variant: ButtonVariant::Secondary,
                         disabled: export_busy,
-                        onclick: move |event| on_export_markdown.call(event),
-                        "Export Markdown"
+                        onclick: move |event| on_export_journal_markdown.call(event),
+                        "Journal Markdown"
                     }
                 }
 
@@ -133,5 +302,82 @@ pub(super) fn MediaSettingsTab(
                 }
             }
         }
+
+        SettingRow {
+            label: "Attachment Downloads",
+            description: "Save as... starts in {download_dir_label}",
+
+            div {
+                class: "auth-panel",
+                div {
+                    class: "auth-actions",
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: move |event| on_choose_download_dir.call(event),
+                        "Choose Folder..."
+                    }
+                    if current_settings.attachment_download_dir.is_some() {
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: move |event| on_reset_download_dir.call(event),
+                            "Use Downloads"
+                        }
+                    }
+                }
+                div {
+                    class: "auth-actions",
+                    span { class: "auth-hint", "Open folder after saving" }
+                    Button {
+                        variant: if current_settings.open_folder_after_save {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Ghost
+                        },
+                        onclick: move |event| on_toggle_open_folder_after_save.call(event),
+                        if current_settings.open_folder_after_save {
+                            "Enabled"
+                        } else {
+                            "Disabled"
+                        }
+                    }
+                }
+            }
+        }
+
+        SettingRow {
+            label: "Import",
+            description: "Bring notes over from Google Keep, Simplenote, or Apple Notes",
+
+            div {
+                class: "auth-actions",
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    onclick: move |event| on_open_import.call(event),
+                    "Import Notes..."
+                }
+            }
+        }
+
+        SettingRow {
+            label: "Weekly Digest",
+            description: "Save a #digest note summarizing the past week",
+
+            div {
+                class: "auth-actions",
+                Button {
+                    variant: if current_settings.weekly_digest_enabled {
+                        ButtonVariant::Secondary
+                    } else {
+                        ButtonVariant::Ghost
+                    },
+                    onclick: move |event| on_toggle_weekly_digest.call(event),
+                    if current_settings.weekly_digest_enabled {
+                        "Enabled"
+                    } else {
+                        "Disabled"
+                    }
+                }
+            }
+        }
     }
 }
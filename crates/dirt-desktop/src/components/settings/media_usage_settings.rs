@@ -0,0 +1,64 @@
+use dioxus::prelude::*;
+
+use dirt_core::media::MediaError;
+use dirt_core::models::MediaUsage;
+
+use super::row::SettingRow;
+use crate::state::AppState;
+
+/// Attachment storage used on the managed backend against the account's
+/// quota, e.g. "2.1 GiB of 5.0 GiB used". Hidden without managed media or
+/// a sign-in.
+#[component]
+pub(super) fn MediaUsageSettings() -> Element {
+    let state = use_context::<AppState>();
+    let mut usage = use_signal(|| None::<Result<MediaUsage, MediaError>>);
+
+    use_effect(move || {
+        let media_api = state.media_api_client.read().clone();
+        let access_token = state
+            .auth_session
+            .read()
+            .as_ref()
+            .map(|session| session.access_token.clone())
+            .filter(|token| !token.trim().is_empty());
+        let (Some(media_api), Some(access_token)) = (media_api, access_token) else {
+            usage.set(None);
+            return;
+        };
+        spawn(async move {
+            usage.set(Some(media_api.usage(&access_token).await));
+        });
+    });
+
+    let Some(result) = usage() else {
+        return rsx! {};
+    };
+    let (description, detail) = match result {
+        Ok(usage) => (
+            usage.summary(),
+            format!("{} stored attachment file(s)", usage.object_count),
+        ),
+        Err(error) if error.is_unauthorized() => (
+            "Sign in again to see storage usage".to_string(),
+            error.to_string(),
+        ),
+        Err(MediaError::NotFound(_)) => (
+            "This server does not report storage usage".to_string(),
+            String::new(),
+        ),
+        Err(error) => (
+            "Storage usage is unavailable".to_string(),
+            error.to_string(),
+        ),
+    };
+
+    rsx! {
+        SettingRow {
+            label: "Attachment Storage",
+            description: "{description}",
+
+            span { class: "auth-hint", "{detail}" }
+        }
+    }
+}
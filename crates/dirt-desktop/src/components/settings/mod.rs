@@ -1,36 +1,64 @@
 //! Settings panel component
 
-use std::sync::Arc;
-
 use dioxus::prelude::*;
 use rfd::AsyncFileDialog;
 
-use dirt_core::models::{NoteId, Settings, SyncConflict, ThemeMode};
+use dirt_core::journal::JournalRange;
+use dirt_core::models::{
+    short_note_id, NoteId, Settings, SyncConflict, SyncHistoryEntry, ThemeMode, MIN_SHORT_ID_LEN,
+};
+use dirt_core::transcription::{
+    InstalledWhisperModel, TranscriptionProvider, DEFAULT_WHISPER_MODEL, WHISPER_MODELS,
+};
 
 use super::button::{Button, ButtonVariant};
 use super::dialog::{DialogContent, DialogRoot, DialogTitle};
 use crate::services::{
-    export_notes_to_path, suggested_export_file_name, AuthConfigStatus, NotesExportFormat,
-    SignUpOutcome, TranscriptionConfigStatus, TranscriptionService,
+    attachment_download_dir, attachment_download_dir_label, export_journal_to_path,
+    suggested_journal_file_name, AuthConfigStatus, DatabaseService, JournalExportFormat,
+    NotificationCategory, SignUpOutcome, TranscriptionConfigStatus, TranscriptionService,
 };
 use crate::state::AppState;
 use crate::theme::resolve_theme;
 use auth_settings::AuthSettingsTab;
+use capture_settings::CaptureSettingsTab;
+use focus_settings::FocusModeSettings;
+use hotkey_settings::HotkeySettings;
 use media_settings::MediaSettingsTab;
+use media_usage_settings::MediaUsageSettings;
+use privacy_settings::{
+    EncryptionSetting, ImagePrivacySetting, OsSearchIndexSetting, PrivateTagsSetting,
+};
+use rules_settings::RoutingRulesSettings;
+use storage_settings::StorageSettings;
 use sync_settings::{SyncConflictView, SyncSettingsTab};
 use theme_settings::ThemeSettingsTab;
+use trash_settings::TrashSettings;
+use workspace_settings::WorkspaceSettings;
 
 mod auth_settings;
+mod capture_settings;
+mod focus_settings;
+mod hotkey_settings;
 mod media_settings;
+mod media_usage_settings;
+mod privacy_settings;
 mod row;
+mod rules_settings;
+mod storage_settings;
 mod sync_settings;
 mod theme_settings;
+mod trash_settings;
+mod workspace_settings;
 
 const SYNC_CONFLICT_LIMIT: usize = 10;
+const SYNC_HISTORY_LIMIT: usize = 30;
+const JOURNAL_DEFAULT_DAYS: u32 = 30;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum SettingsTab {
     Appearance,
+    Capture,
     Media,
     Sync,
     Auth,
@@ -69,7 +97,36 @@ pub fn SettingsPanel() -> Element {
         settings_open.set(false);
     };
 
+    let mut import_open = state.import_open;
+    let open_import = move |_: MouseEvent| {
+        settings_open.set(false);
+        import_open.set(true);
+    };
+
+    let mut export_open = state.export_open;
+    let open_export = move |_: MouseEvent| {
+        settings_open.set(false);
+        export_open.set(true);
+    };
+
+    let choose_download_dir = move |_: MouseEvent| {
+        let mut save = save_settings;
+        spawn(async move {
+            let mut dialog = AsyncFileDialog::new();
+            if let Some(dir) = attachment_download_dir(&settings.peek()) {
+                dialog = dialog.set_directory(dir);
+            }
+            let Some(folder) = dialog.pick_folder().await else {
+                return;
+            };
+            let mut new_settings = settings();
+            new_settings.attachment_download_dir = Some(folder.path().display().to_string());
+            save(new_settings);
+        });
+    };
+
     let current_settings = settings();
+    let download_dir_label = attachment_download_dir_label(&current_settings);
     let current_theme_value = match current_settings.theme {
         ThemeMode::Light => "light",
         ThemeMode::Dark => "dark",
@@ -86,10 +143,32 @@ pub fn SettingsPanel() -> Element {
         .is_some_and(|status| status.enabled);
     let transcription_status_text = transcription_status_text(
         transcription_config_status.as_ref(),
+        current_settings.transcription_provider,
         current_settings.voice_memo_transcription_enabled,
     );
     let transcription_toggle_disabled =
         !transcription_available && !current_settings.voice_memo_transcription_enabled;
+    let whisper_available = TranscriptionProvider::Whisper.is_enabled();
+    let installed_whisper_models = if whisper_available {
+        DatabaseService::whisper_models()
+            .installed()
+            .unwrap_or_else(|error| {
+                tracing::warn!("Failed to list Whisper models: {}", error);
+                Vec::new()
+            })
+    } else {
+        Vec::new()
+    };
+    let whisper_model = current_settings
+        .whisper_model
+        .clone()
+        .unwrap_or_else(|| DEFAULT_WHISPER_MODEL.to_string());
+    let whisper_model_downloaded = installed_whisper_models
+        .iter()
+        .any(|installed| installed.name == whisper_model);
+    let whisper_model_options = whisper_model_options(&installed_whisper_models);
+    let mut whisper_message = use_signal(|| None::<String>);
+    let mut whisper_download_busy = use_signal(|| false);
     let mut openai_api_key_input = use_signal(String::new);
     let mut openai_api_key_message = use_signal(|| None::<String>);
     let mut openai_api_key_configured = use_signal(|| {
@@ -101,6 +180,12 @@ pub fn SettingsPanel() -> Element {
     let active_session = (state.auth_session)();
     let sync_status = (state.sync_status)();
     let sync_issue = (state.sync_issue)();
+    let sync_rotation_summary = (state.last_sync_rotation)().map(|event| {
+        format!(
+            "{event} at {}",
+            format_sync_conflict_timestamp(event.observed_at.saturating_mul(1000))
+        )
+    });
     let pending_sync_count = (state.pending_sync_count)();
     let pending_sync_note_ids = (state.pending_sync_note_ids)();
     let pending_sync_preview = format_pending_sync_preview(&pending_sync_note_ids);
@@ -122,10 +207,17 @@ pub fn SettingsPanel() -> Element {
     let auth_service_for_preflight = auth_service.clone();
     let mut export_busy = use_signal(|| false);
     let mut export_message = use_signal(|| None::<String>);
+    let mut export_include_private = use_signal(|| false);
+    let default_journal_range =
+        JournalRange::last_days(chrono::Utc::now().date_naive(), JOURNAL_DEFAULT_DAYS);
+    let mut journal_from = use_signal(|| default_journal_range.from.to_string());
+    let mut journal_to = use_signal(|| default_journal_range.to.to_string());
     let sync_conflicts = use_signal(Vec::<SyncConflict>::new);
+    let sync_conflict_id_len = use_signal(|| MIN_SHORT_ID_LEN);
     let mut sync_conflicts_loading = use_signal(|| false);
     let mut sync_conflicts_error = use_signal(|| None::<String>);
     let mut sync_conflicts_refresh_version = use_signal(|| 0u64);
+    let sync_history = use_signal(Vec::<SyncHistoryEntry>::new);
 
     use_effect(move || {
         if auth_config_checked() || auth_service_for_preflight.is_none() {
@@ -172,6 +264,8 @@ pub fn SettingsPanel() -> Element {
         let mut conflicts_signal = sync_conflicts;
         let mut loading_signal = sync_conflicts_loading;
         let mut error_signal = sync_conflicts_error;
+        let mut history_signal = sync_history;
+        let mut id_len_signal = sync_conflict_id_len;
         spawn(async move {
             let Some(db) = db else {
                 conflicts_signal.set(Vec::new());
@@ -190,6 +284,16 @@ pub fn SettingsPanel() -> Element {
                 }
             }
 
+            match db.short_note_id_len().await {
+                Ok(id_len) => id_len_signal.set(id_len),
+                Err(error) => tracing::warn!("Failed to measure note id prefixes: {}", error),
+            }
+
+            match db.list_sync_history(SYNC_HISTORY_LIMIT).await {
+                Ok(history) => history_signal.set(history),
+                Err(error) => tracing::warn!("Failed to load sync history: {}", error),
+            }
+
             loading_signal.set(false);
         });
     });
@@ -216,12 +320,14 @@ pub fn SettingsPanel() -> Element {
         let mut auth_message_signal = auth_message;
         let mut auth_password_signal = auth_password;
         let mut auth_busy_signal = auth_busy;
+        let mut expired_session_signal = state.expired_session;
         let mut db_reconnect_signal = state.db_reconnect_version;
         spawn(async move {
             match service.sign_in(&email, &password).await {
                 Ok(session) => {
                     auth_session_signal.set(Some(session));
                     auth_error_signal.set(None);
+                    expired_session_signal.set(None);
                     auth_password_signal.set(String::new());
                     auth_message_signal.set(Some("Signed in.".to_string()));
                     db_reconnect_signal.set(db_reconnect_signal().saturating_add(1));
@@ -271,12 +377,14 @@ pub fn SettingsPanel() -> Element {
         let mut auth_error_signal = state.auth_error;
         let mut auth_message_signal = auth_message;
         let mut auth_busy_signal = auth_busy;
+        let mut expired_session_signal = state.expired_session;
         let mut db_reconnect_signal = state.db_reconnect_version;
         spawn(async move {
             match service.sign_up(&email, &password).await {
                 Ok(SignUpOutcome::SignedIn(session)) => {
                     auth_session_signal.set(Some(session));
                     auth_error_signal.set(None);
+                    expired_session_signal.set(None);
                     auth_message_signal.set(Some("Account created and signed in.".to_string()));
                     db_reconnect_signal.set(db_reconnect_signal().saturating_add(1));
                 }
@@ -370,61 +478,24 @@ pub fn SettingsPanel() -> Element {
         });
     };
 
-    let export_json = move |_: MouseEvent| {
+    let mut export_journal = move |format: JournalExportFormat| {
         if export_busy() {
             return;
         }
 
-        export_busy.set(true);
-        export_message.set(None);
-
-        let db = state.db_service.read().clone();
-        let mut export_busy_signal = export_busy;
-        let mut export_message_signal = export_message;
-        spawn(async move {
-            let Some(db) = db else {
-                export_message_signal.set(Some("Database service is not available.".to_string()));
-                export_busy_signal.set(false);
-                return;
-            };
-
-            let default_name = suggested_export_file_name(
-                NotesExportFormat::Json,
-                chrono::Utc::now().timestamp_millis(),
-            );
-            let Some(file) = AsyncFileDialog::new()
-                .set_file_name(&default_name)
-                .save_file()
-                .await
-            else {
-                export_busy_signal.set(false);
+        let range = match JournalRange::parse(&journal_from(), &journal_to()) {
+            Ok(range) => range,
+            Err(error) => {
+                export_message.set(Some(format!("Journal export failed: {error}")));
                 return;
-            };
-
-            match export_notes_to_path(db.as_ref(), NotesExportFormat::Json, file.path()).await {
-                Ok(count) => {
-                    export_message_signal.set(Some(format!(
-                        "Exported {count} notes to {}",
-                        file.path().display()
-                    )));
-                }
-                Err(error) => {
-                    export_message_signal.set(Some(format!("Export failed: {error}")));
-                }
             }
-            export_busy_signal.set(false);
-        });
-    };
-
-    let export_markdown = move |_: MouseEvent| {
-        if export_busy() {
-            return;
-        }
+        };
 
         export_busy.set(true);
         export_message.set(None);
 
         let db = state.db_service.read().clone();
+        let include_private = export_include_private();
         let mut export_busy_signal = export_busy;
         let mut export_message_signal = export_message;
         spawn(async move {
@@ -434,10 +505,7 @@ pub fn SettingsPanel() -> Element {
                 return;
             };
 
-            let default_name = suggested_export_file_name(
-                NotesExportFormat::Markdown,
-                chrono::Utc::now().timestamp_millis(),
-            );
+            let default_name = suggested_journal_file_name(range, format);
             let Some(file) = AsyncFileDialog::new()
                 .set_file_name(&default_name)
                 .save_file()
@@ -447,16 +515,18 @@ pub fn SettingsPanel() -> Element {
                 return;
             };
 
-            match export_notes_to_path(db.as_ref(), NotesExportFormat::Markdown, file.path()).await
+            match export_journal_to_path(db.as_ref(), range, format, include_private, file.path())
+                .await
             {
                 Ok(count) => {
                     export_message_signal.set(Some(format!(
-                        "Exported {count} notes to {}",
+                        "Compiled {count} notes from {} into {}",
+                        range.label(),
                         file.path().display()
                     )));
                 }
                 Err(error) => {
-                    export_message_signal.set(Some(format!("Export failed: {error}")));
+                    export_message_signal.set(Some(format!("Journal export failed: {error}")));
                 }
             }
             export_busy_signal.set(false);
@@ -487,13 +557,7 @@ pub fn SettingsPanel() -> Element {
             }
         }
 
-        match TranscriptionService::new() {
-            Ok(service) => transcription_service_signal.set(Some(Arc::new(service))),
-            Err(error) => {
-                tracing::warn!("Voice transcription service unavailable: {}", error);
-                transcription_service_signal.set(None);
-            }
-        }
+        transcription_service_signal.set(TranscriptionService::load(&settings.peek()));
     };
 
     let clear_openai_api_key = move |_: MouseEvent| {
@@ -508,13 +572,49 @@ pub fn SettingsPanel() -> Element {
             }
         }
 
-        match TranscriptionService::new() {
-            Ok(service) => transcription_service_signal.set(Some(Arc::new(service))),
-            Err(error) => {
-                tracing::warn!("Voice transcription service unavailable: {}", error);
-                transcription_service_signal.set(None);
-            }
+        transcription_service_signal.set(TranscriptionService::load(&settings.peek()));
+    };
+
+    let download_whisper_model = move |_: MouseEvent| {
+        if whisper_download_busy() {
+            return;
         }
+        let name = settings
+            .peek()
+            .whisper_model
+            .clone()
+            .unwrap_or_else(|| DEFAULT_WHISPER_MODEL.to_string());
+        whisper_download_busy.set(true);
+        whisper_message.set(Some(format!("Downloading Whisper model {name}...")));
+        spawn(async move {
+            let mut last_percent = None;
+            let result = DatabaseService::whisper_models()
+                .download(&name, |received, total| {
+                    let percent = total
+                        .filter(|total| *total > 0)
+                        .map(|total| received.saturating_mul(100) / total);
+                    if let Some(percent) = percent.filter(|percent| last_percent != Some(*percent))
+                    {
+                        last_percent = Some(percent);
+                        whisper_message.set(Some(format!(
+                            "Downloading Whisper model {name}... {percent}%"
+                        )));
+                    }
+                })
+                .await;
+            match result {
+                Ok(_) => {
+                    whisper_message.set(Some(format!("Whisper model {name} downloaded.")));
+                    transcription_service_signal.set(TranscriptionService::load(&settings.peek()));
+                }
+                Err(error) => {
+                    whisper_message.set(Some(format!(
+                        "Failed to download Whisper model {name}: {error}"
+                    )));
+                }
+            }
+            whisper_download_busy.set(false);
+        });
     };
 
     let auth_working = auth_busy() || auth_verifying();
@@ -525,7 +625,7 @@ pub fn SettingsPanel() -> Element {
         .into_iter()
         .map(|conflict| SyncConflictView {
             id: conflict.id,
-            note_id: conflict.note_id.clone(),
+            note_id: short_note_id(&conflict.note_id, sync_conflict_id_len()).to_string(),
             resolved_at: format_sync_conflict_timestamp(conflict.resolved_at),
             details: format!(
                 "Local ts: {}, incoming ts: {}, strategy: {}",
@@ -608,6 +708,15 @@ pub fn SettingsPanel() -> Element {
                         onclick: move |_| active_tab.set(SettingsTab::Appearance),
                         "Appearance"
                     }
+                    Button {
+                        variant: if active_tab() == SettingsTab::Capture {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Ghost
+                        },
+                        onclick: move |_| active_tab.set(SettingsTab::Capture),
+                        "Capture"
+                    }
                     Button {
                         variant: if active_tab() == SettingsTab::Media {
                             ButtonVariant::Secondary
@@ -640,18 +749,42 @@ pub fn SettingsPanel() -> Element {
                 match active_tab() {
                     SettingsTab::Appearance => rsx! {
                         ThemeSettingsTab {
-                            hotkey_bg: colors.bg_tertiary,
-                            hotkey_border: colors.border,
-                            current_settings: current_settings,
+                            current_settings: current_settings.clone(),
                             current_theme_value: current_theme_value.to_string(),
                             on_theme_change: on_theme_change,
                             on_font_family_change: on_font_family_change,
                             on_font_size_change: on_font_size_change,
                         }
+                        HotkeySettings {
+                            hotkey_bg: colors.bg_tertiary,
+                            hotkey_border: colors.border,
+                            current_settings: current_settings.clone(),
+                            on_save: {
+                                let mut save = save_settings;
+                                move |new_settings: Settings| save(new_settings)
+                            },
+                        }
+                        FocusModeSettings {
+                            current_settings: current_settings,
+                            on_save: {
+                                let mut save = save_settings;
+                                move |new_settings: Settings| save(new_settings)
+                            },
+                        }
+                    },
+                    SettingsTab::Capture => rsx! {
+                        CaptureSettingsTab {
+                            current_settings: current_settings,
+                            on_save: {
+                                let mut save = save_settings;
+                                move |new_settings: Settings| save(new_settings)
+                            },
+                        }
+                        RoutingRulesSettings {}
                     },
                     SettingsTab::Media => rsx! {
                         MediaSettingsTab {
-                            current_settings: current_settings,
+                            current_settings: current_settings.clone(),
                             transcription_status_text: transcription_status_text,
                             transcription_toggle_disabled: transcription_toggle_disabled,
                             on_toggle_transcription: {
@@ -663,6 +796,36 @@ pub fn SettingsPanel() -> Element {
                                     save(new_settings);
                                 }
                             },
+                            whisper_available: whisper_available,
+                            on_transcription_provider_change: {
+                                let mut save = save_settings;
+                                move |value: String| {
+                                    let Some(provider) = TranscriptionProvider::parse(&value) else {
+                                        return;
+                                    };
+                                    let mut new_settings = settings();
+                                    new_settings.transcription_provider = provider;
+                                    transcription_service_signal
+                                        .set(TranscriptionService::load(&new_settings));
+                                    save(new_settings);
+                                }
+                            },
+                            whisper_model: whisper_model,
+                            whisper_model_options: whisper_model_options,
+                            on_whisper_model_change: {
+                                let mut save = save_settings;
+                                move |value: String| {
+                                    let mut new_settings = settings();
+                                    new_settings.whisper_model = Some(value);
+                                    transcription_service_signal
+                                        .set(TranscriptionService::load(&new_settings));
+                                    save(new_settings);
+                                }
+                            },
+                            whisper_model_downloaded: whisper_model_downloaded,
+                            whisper_download_busy: whisper_download_busy(),
+                            on_download_whisper_model: download_whisper_model,
+                            whisper_message: whisper_message(),
                             openai_api_key_input: openai_api_key_input(),
                             on_openai_api_key_input: move |value: String| {
                                 openai_api_key_input.set(value);
@@ -672,21 +835,108 @@ pub fn SettingsPanel() -> Element {
                             openai_api_key_configured: openai_api_key_configured(),
                             openai_api_key_message: openai_api_key_message(),
                             export_busy: export_busy(),
-                            on_export_json: export_json,
-                            on_export_markdown: export_markdown,
+                            on_open_export: open_export,
+                            export_include_private: export_include_private(),
+                            on_toggle_export_include_private: move |_| {
+                                export_include_private.set(!export_include_private());
+                            },
                             export_message: export_message(),
+                            journal_from: journal_from(),
+                            on_journal_from_input: move |value: String| {
+                                journal_from.set(value);
+                            },
+                            journal_to: journal_to(),
+                            on_journal_to_input: move |value: String| {
+                                journal_to.set(value);
+                            },
+                            on_export_journal_pdf: move |_| export_journal(JournalExportFormat::Pdf),
+                            on_export_journal_markdown: move |_| {
+                                export_journal(JournalExportFormat::Markdown);
+                            },
+                            on_open_import: open_import,
+                            on_toggle_weekly_digest: {
+                                let mut save = save_settings;
+                                move |_| {
+                                    let mut new_settings = settings();
+                                    new_settings.weekly_digest_enabled =
+                                        !new_settings.weekly_digest_enabled;
+                                    save(new_settings);
+                                }
+                            },
+                            download_dir_label: download_dir_label,
+                            on_choose_download_dir: choose_download_dir,
+                            on_reset_download_dir: {
+                                let mut save = save_settings;
+                                move |_| {
+                                    let mut new_settings = settings();
+                                    new_settings.attachment_download_dir = None;
+                                    save(new_settings);
+                                }
+                            },
+                            on_toggle_open_folder_after_save: {
+                                let mut save = save_settings;
+                                move |_| {
+                                    let mut new_settings = settings();
+                                    new_settings.open_folder_after_save =
+                                        !new_settings.open_folder_after_save;
+                                    save(new_settings);
+                                }
+                            },
+                        }
+                        ImagePrivacySetting {
+                            current_settings: current_settings.clone(),
+                            on_save: {
+                                let mut save = save_settings;
+                                move |new_settings: Settings| save(new_settings)
+                            },
+                        }
+                        PrivateTagsSetting {
+                            current_settings: current_settings.clone(),
+                            on_save: {
+                                let mut save = save_settings;
+                                move |new_settings: Settings| save(new_settings)
+                            },
+                        }
+                        EncryptionSetting {}
+                        OsSearchIndexSetting {
+                            current_settings: current_settings.clone(),
+                            on_save: {
+                                let mut save = save_settings;
+                                move |new_settings: Settings| save(new_settings)
+                            },
+                        }
+                        StorageSettings {
+                            current_settings: current_settings,
+                            on_save: {
+                                let mut save = save_settings;
+                                move |new_settings: Settings| save(new_settings)
+                            },
                         }
+                        MediaUsageSettings {}
+                        TrashSettings {}
                     },
                     SettingsTab::Sync => rsx! {
                         SyncSettingsTab {
                             sync_status: sync_status,
                             sync_issue: sync_issue,
+                            sync_rotation_summary: sync_rotation_summary,
                             pending_sync_count: pending_sync_count,
                             pending_sync_preview: pending_sync_preview,
                             sync_conflicts: sync_conflict_items,
                             sync_conflicts_loading: sync_conflicts_loading(),
                             sync_conflicts_error: sync_conflicts_error(),
                             on_refresh_sync_conflicts: refresh_sync_conflicts,
+                            sync_history: sync_history(),
+                            current_settings: current_settings,
+                            on_toggle_notification: {
+                                let mut save = save_settings;
+                                move |category: NotificationCategory| {
+                                    let mut new_settings = settings();
+                                    let enabled = category.enabled_in(&new_settings);
+                                    category.set_enabled(&mut new_settings, !enabled);
+                                    save(new_settings);
+                                }
+                            },
                         }
                     },
                     SettingsTab::Auth => rsx! {
@@ -712,6 +962,7 @@ pub fn SettingsPanel() -> Element {
                             on_sign_out: sign_out,
                             on_verify_config: verify_config,
                         }
+                        WorkspaceSettings {}
                     },
                 }
             }
@@ -719,7 +970,11 @@ pub fn SettingsPanel() -> Element {
     }
 }
 
-fn transcription_status_text(status: Option<&TranscriptionConfigStatus>, enabled: bool) -> String {
+fn transcription_status_text(
+    status: Option<&TranscriptionConfigStatus>,
+    provider: TranscriptionProvider,
+    enabled: bool,
+) -> String {
     let toggle = if enabled { "enabled" } else { "disabled" };
 
     match status {
@@ -733,12 +988,32 @@ fn transcription_status_text(status: Option<&TranscriptionConfigStatus>, enabled
         Some(_) => {
             format!("Optional transcription is {toggle}. Add an OpenAI API key in API Keys.")
         }
+        None if provider == TranscriptionProvider::Whisper => {
+            format!("Optional transcription is {toggle}. Download the selected Whisper model.")
+        }
         None => {
             format!("Optional transcription is {toggle}. Service failed to initialize.")
         }
     }
 }
 
+/// Whisper models offered in settings as `(name, label)`, marking downloaded ones.
+fn whisper_model_options(installed: &[InstalledWhisperModel]) -> Vec<(String, String)> {
+    WHISPER_MODELS
+        .iter()
+        .map(|model| {
+            let downloaded = installed.iter().any(|entry| entry.name == model.name);
+            let label = format!(
+                "{} ({} MB{})",
+                model.name,
+                model.size_mb,
+                if downloaded { ", downloaded" } else { "" }
+            );
+            (model.name.to_string(), label)
+        })
+        .collect()
+}
+
 fn sign_up_block_reason(status: Option<AuthConfigStatus>) -> Option<String> {
     let status = status?;
 
@@ -762,7 +1037,7 @@ fn sign_up_block_reason(status: Option<AuthConfigStatus>) -> Option<String> {
     None
 }
 
-fn format_auth_error_message(raw: &str) -> String {
+pub(super) fn format_auth_error_message(raw: &str) -> String {
     let normalized = raw.to_lowercase();
     if normalized.contains("over_email_send_rate_limit")
         || normalized.contains("email rate limit exceeded")
@@ -901,6 +1176,29 @@ mod tests {
         assert!(sign_up_block_reason(Some(status)).is_none());
     }
 
+    #[test]
+    fn sync_history_bars_scale_to_largest_sync_oldest_first() {
+        use super::sync_settings::sync_history_bars;
+
+        let entry = |id: i64, bytes_transferred: i64| SyncHistoryEntry {
+            id,
+            synced_at: id * 1000,
+            duration_ms: 10,
+            frames_synced: 0,
+            bytes_transferred,
+            rows_changed: 1,
+        };
+        let bars = sync_history_bars(&[entry(3, 0), entry(2, 4096), entry(1, 1024)]);
+
+        let heights = bars
+            .iter()
+            .map(|bar| bar.height_percent)
+            .collect::<Vec<_>>();
+        assert_eq!(heights, vec![25, 100, 2]);
+        assert_eq!(bars[0].id, 1);
+        assert!(bars[1].tooltip.contains("4.0 KiB"));
+    }
+
     #[test]
     fn format_sync_conflict_timestamp_uses_utc_display() {
         let formatted = format_sync_conflict_timestamp(0);
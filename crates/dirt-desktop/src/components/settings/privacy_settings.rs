@@ -0,0 +1,305 @@
+use dioxus::prelude::*;
+
+use dirt_core::export::parse_no_export_tags;
+use dirt_core::models::Settings;
+use dirt_core::os_search::OsSearchFormat;
+
+use super::row::SettingRow;
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::input::Input;
+use crate::queries::invalidate_notes_query;
+use crate::state::AppState;
+
+#[component]
+pub(super) fn PrivateTagsSetting(
+    current_settings: Settings,
+    on_save: EventHandler<Settings>,
+) -> Element {
+    let initial_tags = current_settings.no_export_tags.join(", ");
+    let mut tags_input = use_signal(move || initial_tags);
+
+    let save_tags = move |_: MouseEvent| {
+        let tags = parse_no_export_tags(&tags_input());
+        tags_input.set(tags.join(", "));
+        on_save.call(Settings {
+            no_export_tags: tags,
+            ..current_settings.clone()
+        });
+    };
+
+    rsx! {
+        SettingRow {
+            label: "Private Tags",
+            description: "Notes with these tags are left out of exports, digests, and webhooks",
+
+            div {
+                class: "auth-actions",
+                Input {
+                    class: "auth-input",
+                    placeholder: "private, journal",
+                    value: "{tags_input}",
+                    oninput: move |event: FormEvent| tags_input.set(event.value()),
+                }
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    onclick: save_tags,
+                    "Save"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub(super) fn ImagePrivacySetting(
+    current_settings: Settings,
+    on_save: EventHandler<Settings>,
+) -> Element {
+    let initial_max = current_settings
+        .image_max_dimension
+        .map(|max| max.to_string())
+        .unwrap_or_default();
+    let mut max_input = use_signal(move || initial_max);
+    let mut message = use_signal(|| None::<String>);
+
+    let toggle_strip = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            on_save.call(Settings {
+                image_strip_metadata: !current_settings.image_strip_metadata,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    let save_max = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            let raw = max_input();
+            let max_dimension = match raw.trim() {
+                "" => None,
+                value => match value.parse::<u32>() {
+                    Ok(max) if max > 0 => Some(max),
+                    _ => {
+                        message.set(Some(format!(
+                            "'{value}' is not a size in pixels; leave it empty to keep originals."
+                        )));
+                        return;
+                    }
+                },
+            };
+            message.set(None);
+            on_save.call(Settings {
+                image_max_dimension: max_dimension,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    rsx! {
+        SettingRow {
+            label: "Image Privacy",
+            description: "Remove EXIF/GPS metadata and shrink large images before upload",
+
+            div {
+                class: "auth-panel",
+                div {
+                    class: "auth-actions",
+                    span { class: "auth-hint", "Strip metadata" }
+                    Button {
+                        variant: if current_settings.image_strip_metadata {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Ghost
+                        },
+                        onclick: toggle_strip,
+                        if current_settings.image_strip_metadata {
+                            "Enabled"
+                        } else {
+                            "Disabled"
+                        }
+                    }
+                }
+                div {
+                    class: "auth-actions",
+                    Input {
+                        class: "auth-input",
+                        r#type: "number",
+                        placeholder: "Max size in pixels (e.g. 2048)",
+                        value: "{max_input}",
+                        oninput: move |event: FormEvent| max_input.set(event.value()),
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: save_max,
+                        "Save"
+                    }
+                }
+                if let Some(message) = message() {
+                    div {
+                        class: "auth-message",
+                        "{message}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EncryptionStatus {
+    Off,
+    Locked,
+    Unlocked,
+}
+
+/// Turns on passphrase encryption of note content and attachments, and
+/// unlocks or locks it for this session.
+#[component]
+pub(super) fn EncryptionSetting() -> Element {
+    let state = use_context::<AppState>();
+    let mut status = use_signal(|| EncryptionStatus::Off);
+    let mut passphrase_input = use_signal(String::new);
+    let mut message = use_signal(|| None::<String>);
+    let mut working = use_signal(|| false);
+    let mut refresh_version = use_signal(|| 0u64);
+
+    use_effect(move || {
+        let _refresh_version = refresh_version();
+        let db = state.db_service.read().clone();
+        spawn(async move {
+            let Some(db) = db else {
+                return;
+            };
+            status.set(if !db.is_encrypted().await {
+                EncryptionStatus::Off
+            } else if db.is_unlocked().await {
+                EncryptionStatus::Unlocked
+            } else {
+                EncryptionStatus::Locked
+            });
+        });
+    });
+
+    let submit = move |_: MouseEvent| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        let current = status();
+        let passphrase = passphrase_input();
+        working.set(true);
+        spawn(async move {
+            let result = match current {
+                EncryptionStatus::Off => db.enable_encryption(&passphrase).await.map(|count| {
+                    format!(
+                        "Encrypted {count} note(s). Keep your passphrase safe: it cannot be recovered."
+                    )
+                }),
+                EncryptionStatus::Locked => db
+                    .unlock_encryption(&passphrase)
+                    .await
+                    .map(|_| "Notes unlocked.".to_string()),
+                EncryptionStatus::Unlocked => {
+                    db.lock_encryption().await;
+                    Ok("Notes locked.".to_string())
+                }
+            };
+            match result {
+                Ok(done) => {
+                    passphrase_input.set(String::new());
+                    message.set(Some(done));
+                }
+                Err(error) => message.set(Some(error.to_string())),
+            }
+            working.set(false);
+            refresh_version.set(refresh_version() + 1);
+            invalidate_notes_query().await;
+        });
+    };
+
+    let (description, action) = match status() {
+        EncryptionStatus::Off => (
+            "Encrypt note content and attachments with a passphrase before they are saved or synced",
+            "Encrypt",
+        ),
+        EncryptionStatus::Locked => (
+            "Notes are encrypted. Enter your passphrase to read and edit them",
+            "Unlock",
+        ),
+        EncryptionStatus::Unlocked => (
+            "Notes are encrypted and unlocked for this session",
+            "Lock",
+        ),
+    };
+
+    rsx! {
+        SettingRow {
+            label: "Encryption",
+            description: "{description}",
+
+            div {
+                class: "auth-panel",
+                div {
+                    class: "auth-actions",
+                    if status() != EncryptionStatus::Unlocked {
+                        Input {
+                            class: "auth-input",
+                            r#type: "password",
+                            placeholder: "Passphrase",
+                            value: "{passphrase_input}",
+                            disabled: working(),
+                            oninput: move |event: FormEvent| passphrase_input.set(event.value()),
+                        }
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        disabled: working(),
+                        onclick: submit,
+                        "{action}"
+                    }
+                }
+                if let Some(message) = message() {
+                    div {
+                        class: "auth-message",
+                        "{message}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub(super) fn OsSearchIndexSetting(
+    current_settings: Settings,
+    on_save: EventHandler<Settings>,
+) -> Element {
+    let Some(format) = OsSearchFormat::current() else {
+        return rsx! {};
+    };
+    let launcher = match format {
+        OsSearchFormat::Spotlight => "Spotlight",
+        OsSearchFormat::WindowsShortcut => "Windows Search",
+    };
+    let enabled = current_settings.os_search_index_enabled;
+
+    let toggle = move |_: MouseEvent| {
+        on_save.call(Settings {
+            os_search_index_enabled: !enabled,
+            ..current_settings.clone()
+        });
+    };
+
+    rsx! {
+        SettingRow {
+            label: "System Search",
+            description: "Let {launcher} find notes by title and preview; private notes are never published",
+
+            Button {
+                variant: if enabled { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                onclick: toggle,
+                if enabled { "Enabled" } else { "Disabled" }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use dioxus::prelude::*;
+
+use dirt_core::rules::{RoutingRule, RuleAction, RuleCondition};
+
+use super::row::SettingRow;
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::input::Input;
+use crate::state::AppState;
+
+/// Editor for the routing rules that tag new and edited notes.
+#[component]
+pub(super) fn RoutingRulesSettings() -> Element {
+    let state = use_context::<AppState>();
+    let mut rules = use_signal(Vec::<RoutingRule>::new);
+    let mut condition_input = use_signal(String::new);
+    let mut action_input = use_signal(String::new);
+    let mut message = use_signal(|| None::<String>);
+    let mut refresh_version = use_signal(|| 0u64);
+
+    use_effect(move || {
+        let _refresh_version = refresh_version();
+        let db = state.db_service.read().clone();
+        spawn(async move {
+            let Some(db) = db else {
+                return;
+            };
+            match db.list_routing_rules().await {
+                Ok(loaded) => rules.set(loaded),
+                Err(error) => tracing::warn!("Failed to load routing rules: {}", error),
+            }
+        });
+    });
+
+    let add_rule = move |_: MouseEvent| {
+        let parsed = condition_input()
+            .parse::<RuleCondition>()
+            .and_then(|condition| Ok((condition, action_input().parse::<RuleAction>()?)));
+        let (condition, action) = match parsed {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                message.set(Some(error.to_string()));
+                return;
+            }
+        };
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        spawn(async move {
+            match db.add_routing_rule(&condition, &action).await {
+                Ok(_) => {
+                    condition_input.set(String::new());
+                    action_input.set(String::new());
+                    message.set(None);
+                }
+                Err(error) => message.set(Some(format!("Failed to add rule: {error}"))),
+            }
+            refresh_version.set(refresh_version() + 1);
+        });
+    };
+
+    let mut remove_rule = move |id: i64| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        spawn(async move {
+            if let Err(error) = db.remove_routing_rule(id).await {
+                message.set(Some(format!("Failed to remove rule: {error}")));
+            }
+            refresh_version.set(refresh_version() + 1);
+        });
+    };
+
+    rsx! {
+        SettingRow {
+            label: "Routing Rules",
+            description: "Tag new and edited notes automatically. Conditions: #tag, source:<name>, or contains:<text>; the action is a #tag to add",
+
+            div {
+                class: "auth-panel",
+                for rule in rules() {
+                    div {
+                        key: "{rule.id}",
+                        class: "auth-actions",
+                        span { class: "auth-hint", "{rule}" }
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: move |_| remove_rule(rule.id),
+                            "Remove"
+                        }
+                    }
+                }
+                div {
+                    class: "auth-actions",
+                    Input {
+                        class: "auth-input",
+                        placeholder: "If (e.g. source:email-in)",
+                        value: "{condition_input}",
+                        oninput: move |event: FormEvent| condition_input.set(event.value()),
+                    }
+                    Input {
+                        class: "auth-input",
+                        placeholder: "Then add (e.g. #inbox)",
+                        value: "{action_input}",
+                        oninput: move |event: FormEvent| action_input.set(event.value()),
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: add_rule,
+                        "Add"
+                    }
+                }
+                if let Some(message) = message() {
+                    div {
+                        class: "auth-message",
+                        "{message}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::num::ParseIntError;
+
+use dioxus::prelude::*;
+
+use dirt_core::media::CacheStats;
+use dirt_core::models::{format_byte_count, Settings};
+use dirt_core::quota::{quota_warnings, StorageUsage};
+
+use super::row::SettingRow;
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::input::Input;
+use crate::queries::{invalidate_notes_query, invalidate_trash_query};
+use crate::services::DatabaseService;
+use crate::state::AppState;
+
+/// Database size and note count against the soft storage quotas, with the
+/// thresholds and a purge of deleted notes for local-only databases, plus the
+/// size of the local attachment cache.
+#[component]
+pub(super) fn StorageSettings(
+    current_settings: Settings,
+    on_save: EventHandler<Settings>,
+) -> Element {
+    let state = use_context::<AppState>();
+    let initial_notes = limit_text(current_settings.note_count_warning);
+    let initial_size = limit_text(current_settings.database_size_warning_mb);
+    let mut notes_input = use_signal(move || initial_notes);
+    let mut size_input = use_signal(move || initial_size);
+    let initial_cache = limit_text(current_settings.attachment_cache_mb);
+    let mut cache_input = use_signal(move || initial_cache);
+    let mut cache_stats = use_signal(|| None::<CacheStats>);
+    let mut message = use_signal(|| None::<String>);
+    let mut usage = use_signal(|| None::<StorageUsage>);
+    let mut local_only = use_signal(|| false);
+    let mut purge_armed = use_signal(|| false);
+    let mut purging = use_signal(|| false);
+    let mut refresh_version = use_signal(|| 0u64);
+
+    use_effect(move || {
+        let _refresh_version = refresh_version();
+        let db = state.db_service.read().clone();
+        spawn(async move {
+            let Some(db) = db else {
+                return;
+            };
+            local_only.set(!db.is_sync_enabled().await);
+            match db.storage_usage().await {
+                Ok(measured) => usage.set(Some(measured)),
+                Err(error) => tracing::warn!("Failed to measure database storage: {}", error),
+            }
+        });
+    });
+
+    use_effect(move || {
+        let _refresh_version = refresh_version();
+        let settings = (state.settings)();
+        cache_stats.set(DatabaseService::attachment_cache(&settings).map(|cache| cache.stats()));
+    });
+
+    let save_limits = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            let (Ok(note_count_warning), Ok(database_size_warning_mb)) =
+                (parse_limit(&notes_input()), parse_limit(&size_input()))
+            else {
+                message.set(Some(
+                    "Limits must be whole numbers; leave a field empty for no warning.".to_string(),
+                ));
+                return;
+            };
+            message.set(None);
+            on_save.call(Settings {
+                note_count_warning,
+                database_size_warning_mb,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    let save_cache_limit = {
+        let current_settings = current_settings.clone();
+        move |_: MouseEvent| {
+            let Ok(attachment_cache_mb) = parse_limit(&cache_input()) else {
+                message.set(Some(
+                    "The cache size must be a whole number of MB; leave it empty to turn the cache off."
+                        .to_string(),
+                ));
+                return;
+            };
+            message.set(None);
+            on_save.call(Settings {
+                attachment_cache_mb,
+                ..current_settings.clone()
+            });
+        }
+    };
+
+    let clear_cache = move |_: MouseEvent| {
+        let Some(cache) = DatabaseService::attachment_cache(&state.settings.peek()) else {
+            return;
+        };
+        match cache.clear() {
+            Ok(()) => message.set(Some("Cleared the attachment cache.".to_string())),
+            Err(error) => message.set(Some(format!(
+                "Failed to clear the attachment cache: {error}"
+            ))),
+        }
+        refresh_version.set(refresh_version() + 1);
+    };
+
+    let purge = move |_: MouseEvent| {
+        if !purge_armed() {
+            purge_armed.set(true);
+            return;
+        }
+        purge_armed.set(false);
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        purging.set(true);
+        spawn(async move {
+            match db.purge_deleted_notes().await {
+                Ok(purged) => {
+                    message.set(Some(format!("Purged {purged} deleted notes.")));
+                    invalidate_notes_query().await;
+                    invalidate_trash_query().await;
+                }
+                Err(error) => message.set(Some(format!("Purge failed: {error}"))),
+            }
+            purging.set(false);
+            refresh_version.set(refresh_version() + 1);
+        });
+    };
+
+    let measured = usage();
+    let warnings = measured
+        .map(|measured| quota_warnings(&measured, &current_settings))
+        .unwrap_or_default();
+    let deleted_notes = measured.map_or(0, |measured| measured.deleted_notes);
+    let summary = measured.map(|measured| {
+        format!(
+            "{} · {} notes · {} deleted",
+            format_byte_count(measured.database_bytes),
+            measured.notes,
+            measured.deleted_notes
+        )
+    });
+    let cache_summary = cache_stats().map_or_else(
+        || "Off; every preview downloads the attachment again".to_string(),
+        |stats| {
+            format!(
+                "{} attachments · {} of {}",
+                stats.entries,
+                format_byte_count(i64::try_from(stats.bytes).unwrap_or(i64::MAX)),
+                format_byte_count(i64::try_from(stats.max_bytes).unwrap_or(i64::MAX))
+            )
+        },
+    );
+
+    rsx! {
+        SettingRow {
+            label: "Storage",
+            description: "Warn before a large database slows down search. Leave a limit empty to turn its warning off",
+
+            div {
+                class: "auth-panel",
+                if let Some(summary) = summary {
+                    span { class: "auth-hint", "{summary}" }
+                }
+                for warning in warnings.iter() {
+                    div {
+                        class: "auth-message",
+                        "{warning}. Export old notes, delete them, then purge deleted notes."
+                    }
+                }
+                div {
+                    class: "auth-actions",
+                    Input {
+                        class: "auth-input",
+                        r#type: "number",
+                        placeholder: "Note limit (e.g. 10000)",
+                        value: "{notes_input}",
+                        oninput: move |event: FormEvent| notes_input.set(event.value()),
+                    }
+                    Input {
+                        class: "auth-input",
+                        r#type: "number",
+                        placeholder: "Size limit in MB (e.g. 500)",
+                        value: "{size_input}",
+                        oninput: move |event: FormEvent| size_input.set(event.value()),
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: save_limits,
+                        "Save"
+                    }
+                }
+                if local_only() && deleted_notes > 0 {
+                    div {
+                        class: "auth-actions",
+                        Button {
+                            variant: if purge_armed() { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                            disabled: purging(),
+                            onclick: purge,
+                            if purging() {
+                                "Purging..."
+                            } else if purge_armed() {
+                                "Click again to purge permanently"
+                            } else {
+                                "Purge {deleted_notes} deleted notes"
+                            }
+                        }
+                    }
+                }
+                if let Some(message) = message() {
+                    div {
+                        class: "auth-message",
+                        "{message}"
+                    }
+                }
+            }
+        }
+
+        SettingRow {
+            label: "Attachment Cache",
+            description: "Opened attachments are kept on this device so they open without downloading again. The least recently used are dropped once the cache is full",
+
+            div {
+                class: "auth-panel",
+                span { class: "auth-hint", "{cache_summary}" }
+                div {
+                    class: "auth-actions",
+                    Input {
+                        class: "auth-input",
+                        r#type: "number",
+                        placeholder: "Cache size in MB (e.g. 256)",
+                        value: "{cache_input}",
+                        oninput: move |event: FormEvent| cache_input.set(event.value()),
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: save_cache_limit,
+                        "Save"
+                    }
+                    Button {
+                        variant: ButtonVariant::Ghost,
+                        disabled: !cache_stats().is_some_and(|stats| stats.entries > 0),
+                        onclick: clear_cache,
+                        "Clear Cache"
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn limit_text(limit: Option<u32>) -> String {
+    limit.map(|limit| limit.to_string()).unwrap_or_default()
+}
+
+/// Parse a limit input; empty or `0` turns the warning off.
+fn parse_limit(raw: &str) -> Result<Option<u32>, ParseIntError> {
+    match raw.trim() {
+        "" | "0" => Ok(None),
+        value => value.parse::<u32>().map(Some),
+    }
+}
@@ -1,7 +1,11 @@
 use dioxus::prelude::*;
 
+use dirt_core::models::{format_byte_count, Settings, SyncHistoryEntry};
+
+use super::format_sync_conflict_timestamp;
 use super::row::SettingRow;
 use crate::components::button::{Button, ButtonVariant};
+use crate::services::NotificationCategory;
 use crate::state::SyncStatus;
 
 #[derive(Clone, PartialEq, Eq)]
@@ -12,17 +16,65 @@ pub(super) struct SyncConflictView {
     pub details: String,
 }
 
+/// One bar of the sync history chart
+#[derive(Clone, PartialEq, Eq)]
+pub(super) struct SyncHistoryBar {
+    pub id: i64,
+    /// Height relative to the largest transfer shown, 2-100
+    pub height_percent: i64,
+    pub tooltip: String,
+}
+
+/// Chart bars for recent syncs (given newest first), oldest on the left.
+pub(super) fn sync_history_bars(entries: &[SyncHistoryEntry]) -> Vec<SyncHistoryBar> {
+    let max_bytes = entries
+        .iter()
+        .map(|entry| entry.bytes_transferred)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+
+    entries
+        .iter()
+        .rev()
+        .map(|entry| SyncHistoryBar {
+            id: entry.id,
+            height_percent: (entry.bytes_transferred.saturating_mul(100) / max_bytes).clamp(2, 100),
+            tooltip: format!(
+                "{}: {}, {} notes changed",
+                format_sync_conflict_timestamp(entry.synced_at),
+                format_byte_count(entry.bytes_transferred),
+                entry.rows_changed
+            ),
+        })
+        .collect()
+}
+
 #[component]
 pub(super) fn SyncSettingsTab(
     sync_status: SyncStatus,
     sync_issue: Option<String>,
+    sync_rotation_summary: Option<String>,
     pending_sync_count: usize,
     pending_sync_preview: String,
     sync_conflicts: Vec<SyncConflictView>,
     sync_conflicts_loading: bool,
     sync_conflicts_error: Option<String>,
     on_refresh_sync_conflicts: EventHandler<MouseEvent>,
+    sync_history: Vec<SyncHistoryEntry>,
+    current_settings: Settings,
+    on_toggle_notification: EventHandler<NotificationCategory>,
 ) -> Element {
+    let history_bars = sync_history_bars(&sync_history);
+    let latest_sync_summary = sync_history.first().map(|latest| {
+        format!(
+            "Last sync: {} transferred, {} notes changed, {} ms",
+            format_byte_count(latest.bytes_transferred),
+            latest.rows_changed,
+            latest.duration_ms
+        )
+    });
+
     rsx! {
         SettingRow {
             label: "Sync Health",
@@ -34,6 +86,12 @@ pub(super) fn SyncSettingsTab(
                     class: "auth-hint",
                     "Status: {sync_status_label(sync_status)}"
                 }
+                if let Some(rotation) = sync_rotation_summary {
+                    div {
+                        class: "auth-hint",
+                        "{rotation}"
+                    }
+                }
                 if let Some(issue) = sync_issue {
                     div {
                         class: "auth-error",
@@ -62,6 +120,63 @@ pub(super) fn SyncSettingsTab(
             }
         }
 
+        SettingRow {
+            label: "Sync History",
+            description: "Data pulled by recent syncs; tall bars are heavy syncs",
+
+            div {
+                class: "auth-panel",
+                if history_bars.is_empty() {
+                    div {
+                        class: "auth-hint",
+                        "No syncs recorded yet."
+                    }
+                } else {
+                    div {
+                        style: "display: flex; align-items: flex-end; gap: 3px; height: 64px;",
+                        for bar in history_bars {
+                            div {
+                                key: "{bar.id}",
+                                title: "{bar.tooltip}",
+                                style: "flex: 1; max-width: 14px; height: {bar.height_percent}%; background: currentColor; opacity: 0.55; border-radius: 2px 2px 0 0;",
+                            }
+                        }
+                    }
+                }
+                if let Some(summary) = latest_sync_summary {
+                    div {
+                        class: "auth-hint",
+                        "{summary}"
+                    }
+                }
+            }
+        }
+
+        SettingRow {
+            label: "Notifications",
+            description: "Show system notifications, even when the window is closed",
+
+            div {
+                class: "auth-actions",
+                for category in NotificationCategory::ALL {
+                    Button {
+                        key: "{category.label()}",
+                        variant: if category.enabled_in(&current_settings) {
+                            ButtonVariant::Secondary
+                        } else {
+                            ButtonVariant::Ghost
+                        },
+                        onclick: move |_| on_toggle_notification.call(category),
+                        if category.enabled_in(&current_settings) {
+                            "{category.label()}: On"
+                        } else {
+                            "{category.label()}: Off"
+                        }
+                    }
+                }
+            }
+        }
+
         SettingRow {
             label: "Sync Conflicts",
             description: "Recent LWW conflict resolutions",
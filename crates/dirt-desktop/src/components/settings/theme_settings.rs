@@ -21,8 +21,6 @@ const FONT_FAMILIES: &[(&str, &str)] = &[
 
 #[component]
 pub(super) fn ThemeSettingsTab(
-    hotkey_bg: &'static str,
-    hotkey_border: &'static str,
     current_settings: Settings,
     current_theme_value: String,
     on_theme_change: EventHandler<String>,
@@ -135,19 +133,5 @@ pub(super) fn ThemeSettingsTab(
                 }
             }
         }
-
-        SettingRow {
-            label: "Capture Hotkey",
-            description: "Global shortcut for quick capture",
-
-            div {
-                class: "hotkey-display",
-                style: "
-                    background: {hotkey_bg};
-                    border: 1px solid {hotkey_border};
-                ",
-                "Ctrl + Alt + N"
-            }
-        }
     }
 }
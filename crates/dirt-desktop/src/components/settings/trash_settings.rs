@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use dioxus::prelude::*;
+
+use dirt_core::media::delete_attachment_objects;
+use dirt_core::models::{NoteId, TrashPurge};
+
+use super::row::SettingRow;
+use crate::components::button::{Button, ButtonVariant};
+use crate::queries::{invalidate_notes_query, invalidate_trash_query, use_trash_query};
+use crate::services::{AuthSession, DatabaseService, MediaApiClient};
+use crate::state::AppState;
+
+const PREVIEW_CHARS: usize = 60;
+
+/// Deleted notes with a restore button each, and emptying the trash of
+/// local-only databases, which also deletes the notes' attachments.
+#[component]
+pub(super) fn TrashSettings() -> Element {
+    let state = use_context::<AppState>();
+    let trash_query = use_trash_query(state.db_service.read().clone());
+    let mut message = use_signal(|| None::<String>);
+    let mut local_only = use_signal(|| false);
+    let mut empty_armed = use_signal(|| false);
+    let mut busy = use_signal(|| false);
+
+    use_effect(move || {
+        let db = state.db_service.read().clone();
+        spawn(async move {
+            if let Some(db) = db {
+                local_only.set(!db.is_sync_enabled().await);
+            }
+        });
+    });
+
+    let mut restore = move |id: NoteId| {
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        busy.set(true);
+        spawn(async move {
+            match db.restore_note(&id).await {
+                Ok(note) => {
+                    message.set(Some(format!(
+                        "Restored \"{}\".",
+                        note.title_preview(PREVIEW_CHARS)
+                    )));
+                    invalidate_notes_query().await;
+                    invalidate_trash_query().await;
+                }
+                Err(error) => message.set(Some(format!("Restore failed: {error}"))),
+            }
+            busy.set(false);
+        });
+    };
+
+    let empty_trash = move |_: MouseEvent| {
+        if !empty_armed() {
+            empty_armed.set(true);
+            return;
+        }
+        empty_armed.set(false);
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        let media_api = state.media_api_client.read().clone();
+        let auth_session = state.auth_session.read().clone();
+        busy.set(true);
+        spawn(async move {
+            match db.purge_trash(0).await {
+                Ok(purge) => {
+                    let mut text = format!("Removed {} notes from the trash.", purge.notes);
+                    if let Err(error) =
+                        delete_purged_attachments(&purge, media_api, auth_session).await
+                    {
+                        text.push_str(&format!(
+                            " {} attachment file(s) were not deleted from storage: {error}",
+                            purge.attachments.len()
+                        ));
+                    }
+                    message.set(Some(text));
+                    invalidate_trash_query().await;
+                }
+                Err(error) => message.set(Some(format!("Emptying the trash failed: {error}"))),
+            }
+            busy.set(false);
+        });
+    };
+
+    let rows = trash_query
+        .read()
+        .state()
+        .ok()
+        .map(|notes| {
+            notes
+                .iter()
+                .map(|note| (note.id, note.title_preview(PREVIEW_CHARS)))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let has_notes = !rows.is_empty();
+
+    rsx! {
+        SettingRow {
+            label: "Trash",
+            description: "Deleted notes stay here until the trash is emptied",
+
+            div {
+                class: "auth-panel",
+                if !has_notes {
+                    span { class: "auth-hint", "The trash is empty." }
+                }
+                for (note_id, preview) in rows {
+                    div {
+                        key: "{note_id}",
+                        class: "auth-actions",
+                        span { class: "auth-hint", "{preview}" }
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            disabled: busy(),
+                            onclick: move |_| restore(note_id),
+                            "Restore"
+                        }
+                    }
+                }
+                if local_only() && has_notes {
+                    div {
+                        class: "auth-actions",
+                        Button {
+                            variant: if empty_armed() { ButtonVariant::Secondary } else { ButtonVariant::Ghost },
+                            disabled: busy(),
+                            onclick: empty_trash,
+                            if empty_armed() {
+                                "Click again to remove permanently"
+                            } else {
+                                "Empty Trash"
+                            }
+                        }
+                    }
+                }
+                if let Some(message) = message() {
+                    div {
+                        class: "auth-message",
+                        "{message}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Drop purged attachments from the cache and delete their stored files.
+async fn delete_purged_attachments(
+    purge: &TrashPurge,
+    media_api: Option<Arc<MediaApiClient>>,
+    auth_session: Option<AuthSession>,
+) -> Result<usize, String> {
+    for attachment in &purge.attachments {
+        DatabaseService::forget_cached_attachment(&attachment.r2_key);
+    }
+    let Some(media_api) = media_api else {
+        return Ok(0);
+    };
+    if purge.attachments.is_empty() {
+        return Ok(0);
+    }
+    let access_token = auth_session
+        .map(|session| session.access_token)
+        .filter(|token| !token.trim().is_empty())
+        .ok_or_else(|| "sign in to delete them".to_string())?;
+    delete_attachment_objects(&media_api, &access_token, &purge.attachments)
+        .await
+        .map_err(String::from)
+}
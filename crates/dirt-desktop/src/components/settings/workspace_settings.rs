@@ -0,0 +1,102 @@
+use dioxus::prelude::*;
+
+use super::row::SettingRow;
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::input::Input;
+use crate::services::{add_workspace, load_workspaces, switch_workspace};
+use crate::state::AppState;
+
+/// Workspaces on this device, each with its own notes and sign-in, with
+/// buttons to switch between them and a field to add one.
+#[component]
+pub(super) fn WorkspaceSettings() -> Element {
+    let state = use_context::<AppState>();
+    let mut names = use_signal(workspace_names);
+    let mut new_name = use_signal(String::new);
+    let mut message = use_signal(|| None::<String>);
+
+    let mut switch_to = move |name: String| match switch_workspace(&name) {
+        Ok(workspace) => {
+            message.set(Some(format!("Switched to {}.", workspace.name)));
+            let mut active = state.workspace;
+            active.set(workspace);
+        }
+        Err(error) => message.set(Some(format!("Switching workspace failed: {error}"))),
+    };
+
+    let add = move |_: MouseEvent| match add_workspace(&new_name()) {
+        Ok(workspace) => {
+            message.set(Some(format!(
+                "Added {}. Switch to it to sign in and start writing.",
+                workspace.name
+            )));
+            new_name.set(String::new());
+            names.set(workspace_names());
+        }
+        Err(error) => message.set(Some(format!("Adding workspace failed: {error}"))),
+    };
+
+    let active_name = state.workspace.read().name.clone();
+
+    rsx! {
+        SettingRow {
+            label: "Workspaces",
+            description: "Separate vaults, each with its own notes and account",
+
+            div {
+                class: "auth-panel",
+                for name in names() {
+                    div {
+                        key: "{name}",
+                        class: "auth-actions",
+                        span { class: "auth-hint", "{name}" }
+                        if name == active_name {
+                            span { class: "auth-hint", "Active" }
+                        } else {
+                            Button {
+                                variant: ButtonVariant::Ghost,
+                                onclick: move |_| switch_to(name.clone()),
+                                "Switch"
+                            }
+                        }
+                    }
+                }
+                div {
+                    class: "auth-actions",
+                    Input {
+                        class: "auth-input",
+                        placeholder: "work",
+                        value: "{new_name}",
+                        oninput: move |event: FormEvent| new_name.set(event.value()),
+                    }
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        disabled: new_name().trim().is_empty(),
+                        onclick: add,
+                        "Add Workspace"
+                    }
+                }
+                if let Some(message) = message() {
+                    div {
+                        class: "auth-message",
+                        "{message}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn workspace_names() -> Vec<String> {
+    match load_workspaces() {
+        Ok(workspaces) => workspaces
+            .list()
+            .iter()
+            .map(|workspace| workspace.name.clone())
+            .collect(),
+        Err(error) => {
+            tracing::warn!("Failed to load workspaces: {error}");
+            Vec::new()
+        }
+    }
+}
@@ -31,6 +31,12 @@ pub fn Sidebar() -> Element {
 
     let active_tag = (state.active_tag_filter)();
     let total_notes = (state.notes)().iter().filter(|n| !n.is_deleted).count();
+    let total_links: usize = (state.notes)()
+        .iter()
+        .filter(|n| !n.is_deleted)
+        .map(|n| n.urls().len())
+        .sum();
+    let links_open = (state.links_open)();
 
     rsx! {
         aside {
@@ -63,6 +69,17 @@ pub fn Sidebar() -> Element {
                 },
             }
 
+            // Smart view of every captured URL
+            TagItem {
+                label: "Links",
+                count: total_links,
+                is_active: links_open,
+                onclick: move |_| {
+                    state.graph_open.set(false);
+                    state.links_open.set(true);
+                },
+            }
+
             // Tag list
             for (tag, count) in sorted_tags {
                 {
@@ -2,21 +2,30 @@
 
 use chrono::Utc;
 use dioxus::prelude::*;
+use dirt_core::seal::sealed_until;
 
 use super::button::{Button, ButtonVariant};
 use super::create_note_optimistic;
-use crate::queries::invalidate_notes_query;
+use crate::queries::{invalidate_notes_query, invalidate_trash_query};
+use crate::services::edit_in_external_editor;
 use crate::state::{AppState, SyncStatus};
 
 /// Toolbar with action buttons
 #[component]
 pub fn Toolbar() -> Element {
     let mut state = use_context::<AppState>();
-    let has_selected_note = state.current_note().is_some();
+    let current_note = state.current_note();
+    let has_selected_note = current_note.is_some();
+    let can_edit_externally = current_note
+        .as_ref()
+        .is_some_and(|note| sealed_until(&note.content).is_none());
     let sync_status = (state.sync_status)();
     let last_sync_at = (state.last_sync_at)();
     let pending_sync_count = (state.pending_sync_count)();
     let pending_sync_note_ids = (state.pending_sync_note_ids)();
+    let graph_open = (state.graph_open)();
+    let mut external_edit_active = use_signal(|| false);
+    let mut external_edit_error = use_signal(|| None::<String>);
 
     let sync_status_text = format_sync_status_text(sync_status, last_sync_at);
     let sync_status_class = sync_status_class(sync_status);
@@ -45,12 +54,46 @@ pub fn Toolbar() -> Element {
                     } else {
                         // Invalidate query to sync state
                         invalidate_notes_query().await;
+                        invalidate_trash_query().await;
                     }
                 }
             });
         }
     };
 
+    let edit_externally = move |_| {
+        let Some(note) = state.current_note() else {
+            return;
+        };
+        let Some(db) = state.db_service.read().clone() else {
+            return;
+        };
+        external_edit_active.set(true);
+        external_edit_error.set(None);
+
+        spawn(async move {
+            let result = edit_in_external_editor(&db, &note, |updated| {
+                let mut notes = state.notes.write();
+                if let Some(existing) = notes.iter_mut().find(|n| n.id == updated.id) {
+                    *existing = updated;
+                }
+            })
+            .await;
+            if let Err(error) = result {
+                tracing::error!("External edit of note {} failed: {}", note.id, error);
+                external_edit_error.set(Some(error.to_string()));
+            }
+            external_edit_active.set(false);
+            invalidate_notes_query().await;
+        });
+    };
+
+    let toggle_graph = move |_| {
+        let open = (state.graph_open)();
+        state.graph_open.set(!open);
+        state.links_open.set(false);
+    };
+
     let open_settings = move |_| {
         state.settings_open.set(true);
     };
@@ -73,6 +116,33 @@ pub fn Toolbar() -> Element {
                 }
             }
 
+            if can_edit_externally {
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    disabled: external_edit_active(),
+                    title: "Open this note in $VISUAL or $EDITOR and sync saves back",
+                    onclick: edit_externally,
+                    if external_edit_active() { "Editing externally..." } else { "Edit in external editor" }
+                }
+            }
+
+            if has_selected_note {
+                Button {
+                    variant: ButtonVariant::Secondary,
+                    title: "Hide everything but this note (Ctrl+Shift+F)",
+                    onclick: move |_| state.toggle_focus_mode(),
+                    "Focus"
+                }
+            }
+
+            if let Some(message) = external_edit_error() {
+                span {
+                    class: "external-edit-error",
+                    title: "{message}",
+                    "{message}"
+                }
+            }
+
             // Spacer
             div { style: "flex: 1;" }
 
@@ -91,6 +161,12 @@ pub fn Toolbar() -> Element {
                 }
             }
 
+            Button {
+                variant: if graph_open { ButtonVariant::Primary } else { ButtonVariant::Secondary },
+                onclick: toggle_graph,
+                "Graph"
+            }
+
             // Settings button
             Button {
                 variant: ButtonVariant::Secondary,
@@ -0,0 +1,32 @@
+//! Banner for builds older than the backend's minimum supported version
+
+use dioxus::prelude::*;
+use dirt_core::changelog::CURRENT_VERSION;
+
+use crate::state::AppState;
+
+/// Persistent banner shown while the bootstrap manifest requires a newer build
+#[component]
+pub fn UpdateRequiredBanner() -> Element {
+    let state = use_context::<AppState>();
+    let colors = (state.theme)().palette();
+
+    let Some(minimum) = (state.update_required)() else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div {
+            class: "update-required-banner",
+            role: "alert",
+            style: "
+                padding: 8px 16px;
+                border-bottom: 1px solid {colors.border};
+                background: {colors.bg_secondary};
+                color: {colors.text_primary};
+                font-size: 13px;
+            ",
+            "Update required: the Dirt service needs version {minimum} or newer (this is {CURRENT_VERSION}). Sync may stop working until you update."
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! One-time "What's new" dialog shown after an upgrade
+
+use dioxus::prelude::*;
+
+use super::button::{Button, ButtonVariant};
+use super::dialog::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
+use crate::state::AppState;
+
+/// Dialog listing the release notes since the version the user last ran
+#[component]
+pub fn WhatsNewDialog() -> Element {
+    let state = use_context::<AppState>();
+    let mut whats_new = state.whats_new;
+    let colors = (state.theme)().palette();
+    let releases = whats_new();
+    let latest_version = releases
+        .first()
+        .map(|release| release.version.clone())
+        .unwrap_or_default();
+
+    rsx! {
+        DialogRoot {
+            open: true,
+            on_open_change: move |open: bool| {
+                if !open {
+                    whats_new.write().clear();
+                }
+            },
+
+            DialogContent {
+                style: "width: 480px; max-width: 90vw; text-align: left;",
+
+                DialogTitle { "What's new in Dirt {latest_version}" }
+                DialogDescription {
+                    "Here is what changed since you last opened Dirt."
+                }
+
+                div {
+                    style: "display: flex; flex-direction: column; gap: 12px; margin: 12px 0; max-height: 50vh; overflow-y: auto;",
+                    for release in releases {
+                        {
+                            let heading = release.date.as_ref().map_or_else(
+                                || release.version.clone(),
+                                |date| format!("{} ({date})", release.version),
+                            );
+                            let version = release.version;
+                            let notes = release.notes;
+                            rsx! {
+                                div {
+                                    key: "{version}",
+                                    div {
+                                        style: "font-weight: 600; color: {colors.text_primary};",
+                                        "{heading}"
+                                    }
+                                    ul {
+                                        style: "margin: 4px 0 0; padding-left: 20px; color: {colors.text_secondary};",
+                                        for note in notes {
+                                            li { "{note}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    style: "display: flex; justify-content: flex-end;",
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        onclick: move |_| whats_new.write().clear(),
+                        "Got it"
+                    }
+                }
+            }
+        }
+    }
+}
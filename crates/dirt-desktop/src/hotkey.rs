@@ -1,10 +1,20 @@
 //! Global hotkey registration and handling
 
+use std::cell::RefCell;
+use std::str::FromStr;
+
+use dirt_core::models::Hotkey;
 use global_hotkey::{
     hotkey::{Code, HotKey, Modifiers},
     GlobalHotKeyManager,
 };
 
+thread_local! {
+    /// Manager created on the main thread before launch; the settings UI runs
+    /// on the same thread and re-registers the hotkey through it.
+    static MANAGER: RefCell<Option<HotkeyManager>> = const { RefCell::new(None) };
+}
+
 /// Default hotkey: Ctrl+Alt+N (Windows/Linux) or Cmd+Option+N (macOS)
 /// N for "Note" - a quick way to capture a thought
 pub fn default_hotkey() -> HotKey {
@@ -16,25 +26,95 @@ pub fn default_hotkey() -> HotKey {
     HotKey::new(Some(modifiers), Code::KeyN)
 }
 
+/// Convert a capture hotkey setting into a registrable global hotkey
+fn to_global_hotkey(hotkey: &Hotkey) -> Result<HotKey, String> {
+    let code = Code::from_str(&hotkey.key_code())
+        .map_err(|_| format!("{hotkey} cannot be used as a global hotkey"))?;
+    let mut modifiers = Modifiers::empty();
+    for (held, modifier) in [
+        (hotkey.modifiers.ctrl, Modifiers::CONTROL),
+        (hotkey.modifiers.alt, Modifiers::ALT),
+        (hotkey.modifiers.shift, Modifiers::SHIFT),
+        (hotkey.modifiers.meta, Modifiers::META),
+    ] {
+        if held {
+            modifiers |= modifier;
+        }
+    }
+    Ok(HotKey::new(Some(modifiers), code))
+}
+
 /// Manages global hotkey registration
 pub struct HotkeyManager {
-    _manager: GlobalHotKeyManager,
-    #[allow(dead_code)]
-    pub hotkey: HotKey,
+    manager: GlobalHotKeyManager,
+    hotkey: HotKey,
 }
 
 impl HotkeyManager {
-    /// Create and register the global hotkey
+    /// Create and register the default global hotkey
     pub fn new() -> Result<Self, global_hotkey::Error> {
         let manager = GlobalHotKeyManager::new()?;
         let hotkey = default_hotkey();
 
         manager.register(hotkey)?;
-        tracing::info!("Registered global hotkey: Ctrl+Alt+N");
+        tracing::info!("Registered global hotkey: {}", Hotkey::default());
+
+        Ok(Self { manager, hotkey })
+    }
+
+    /// Register `hotkey` in place of the current one, keeping the current
+    /// one registered when the new one is refused.
+    fn replace(&mut self, hotkey: &Hotkey) -> Result<(), String> {
+        let next = to_global_hotkey(hotkey)?;
+        if next.id() == self.hotkey.id() {
+            return Ok(());
+        }
+
+        if let Err(error) = self.manager.unregister(self.hotkey) {
+            tracing::warn!("Failed to unregister previous global hotkey: {}", error);
+        }
+        if let Err(error) = self.manager.register(next) {
+            if let Err(restore_error) = self.manager.register(self.hotkey) {
+                tracing::error!(
+                    "Failed to restore previous global hotkey: {}",
+                    restore_error
+                );
+            }
+            return Err(format!(
+                "{hotkey} could not be registered; another application may be using it ({error})"
+            ));
+        }
+
+        self.hotkey = next;
+        tracing::info!("Registered global hotkey: {hotkey}");
+        Ok(())
+    }
+}
+
+/// Keep `manager` alive for the rest of the process so the hotkey can be
+/// changed at runtime. Must be called on the main thread.
+pub fn install(manager: HotkeyManager) {
+    MANAGER.with(|slot| *slot.borrow_mut() = Some(manager));
+}
+
+/// Register `hotkey` as the quick capture shortcut without restarting.
+///
+/// On failure the previous hotkey stays registered.
+pub fn set_capture_hotkey(hotkey: &Hotkey) -> Result<(), String> {
+    MANAGER.with(|slot| match slot.borrow_mut().as_mut() {
+        Some(manager) => manager.replace(hotkey),
+        None => Err("Global hotkeys are not available on this system.".to_string()),
+    })
+}
 
-        Ok(Self {
-            _manager: manager,
-            hotkey,
-        })
+/// Register the capture hotkey stored in settings, logging failures so the
+/// app keeps the previous hotkey.
+pub fn apply_capture_hotkey_setting(value: &str) {
+    let result = value
+        .parse::<Hotkey>()
+        .map_err(|error| error.to_string())
+        .and_then(|hotkey| set_capture_hotkey(&hotkey));
+    if let Err(error) = result {
+        tracing::warn!("Capture hotkey '{}' not registered: {}", value, error);
     }
 }
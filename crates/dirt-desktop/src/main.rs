@@ -25,6 +25,7 @@ use std::time::Duration;
 use dioxus::desktop::{Config, WindowCloseBehaviour};
 use global_hotkey::{GlobalHotKeyEvent, HotKeyState};
 use hotkey::HotkeyManager;
+use services::{share_inbox_dir, spool_shared_text, ShareRequest};
 use single_instance::SingleInstance;
 use tray::TrayManager;
 
@@ -46,8 +47,17 @@ fn main() {
 
     tracing::info!("Starting Dirt...");
 
+    // Text shared from another app is queued before anything else, so the
+    // running instance (or this one, once started) can pick it up.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let shared = ShareRequest::from_args(&args).is_some_and(spool_share);
+
     let _single_instance = match SingleInstance::new("dirt-desktop-single-instance") {
         Ok(instance) if instance.is_single() => instance,
+        Ok(_) if shared => {
+            tracing::info!("Handed shared text to the running Dirt instance.");
+            return;
+        }
         Ok(_) => {
             tracing::error!("Another Dirt desktop instance is already running.");
             return;
@@ -59,29 +69,29 @@ fn main() {
     };
 
     // Initialize system tray BEFORE Dioxus (must be on main thread)
-    let _tray_manager = match TrayManager::new() {
+    // The tray is kept on the main thread, where the app refreshes its menu.
+    match TrayManager::new() {
         Ok(manager) => {
             tracing::info!("System tray initialized");
             TRAY_ENABLED.store(true, Ordering::SeqCst);
-            Some(manager)
+            tray::install(manager);
         }
         Err(e) => {
             tracing::error!("Failed to initialize system tray: {}", e);
-            None
         }
-    };
+    }
 
     // Initialize global hotkey BEFORE launching Dioxus.
-    // The manager must be kept alive and stay on the main thread.
+    // The manager must be kept alive and stay on the main thread, where the
+    // app re-registers it when the user records another hotkey.
     // Retry briefly to tolerate OS-level release delay from a previous instance.
-    let _hotkey_manager = match initialize_hotkey_manager() {
-        Ok(manager) => Some(manager),
+    match initialize_hotkey_manager() {
+        Ok(manager) => hotkey::install(manager),
         Err(e) => {
             tracing::warn!("Failed to register hotkey after retries: {}", e);
             tracing::warn!("Continuing desktop startup without global hotkey support.");
-            None
         }
-    };
+    }
 
     // Configure Dioxus to hide window on close instead of exiting
     // Hide window instead of exiting when closed - keeps app running in tray
@@ -93,6 +103,24 @@ fn main() {
         .launch(app::App);
 }
 
+/// Queue the text of a `--share` launch; returns whether anything was queued.
+fn spool_share(request: ShareRequest) -> bool {
+    let Some(inbox_dir) = share_inbox_dir() else {
+        tracing::error!("No local data folder to queue shared text in.");
+        return false;
+    };
+    match request
+        .read()
+        .and_then(|text| spool_shared_text(&inbox_dir, &text))
+    {
+        Ok(queued) => queued,
+        Err(error) => {
+            tracing::error!("Failed to queue shared text: {}", error);
+            false
+        }
+    }
+}
+
 fn initialize_hotkey_manager() -> Result<HotkeyManager, String> {
     const HOTKEY_RETRY_ATTEMPTS: usize = 6;
     const HOTKEY_RETRY_DELAY_MS: u64 = 300;
@@ -103,7 +131,7 @@ fn initialize_hotkey_manager() -> Result<HotkeyManager, String> {
                 GlobalHotKeyEvent::set_event_handler(Some(|event: GlobalHotKeyEvent| {
                     tracing::debug!("GlobalHotKeyEvent received: state={:?}", event.state);
                     if event.state == HotKeyState::Pressed {
-                        tracing::info!("Hotkey pressed (Ctrl+Alt+N) - setting flag");
+                        tracing::info!("Capture hotkey pressed - setting flag");
                         HOTKEY_TRIGGERED.store(true, Ordering::SeqCst);
                     }
                 }));
@@ -5,21 +5,28 @@ use std::sync::Arc;
 
 use dioxus_query::prelude::*;
 
-use dirt_core::models::Note;
+use dirt_core::models::{append_note_page, Note};
 
 use crate::services::DatabaseService;
 
-/// Query capability for fetching all notes
+/// Notes fetched per page; the notes query loads the first page and the note
+/// list fetches later pages as it scrolls
+pub const NOTE_PAGE_SIZE: usize = 200;
+
+/// Most pinned notes loaded alongside the first page
+const PINNED_NOTES_LIMIT: usize = 500;
+
+/// Most deleted notes listed in the trash
+const TRASH_PAGE_SIZE: usize = 200;
+
+/// Query capability for fetching the first page of notes, plus every pinned
+/// note so pinned notes sit at the top however old they are
 #[derive(Clone)]
 pub struct NotesQuery(pub Option<Arc<DatabaseService>>);
 
 impl PartialEq for NotesQuery {
     fn eq(&self, other: &Self) -> bool {
-        match (&self.0, &other.0) {
-            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
-            (None, None) => true,
-            _ => false,
-        }
+        same_database(self.0.as_ref(), other.0.as_ref())
     }
 }
 
@@ -27,10 +34,7 @@ impl Eq for NotesQuery {}
 
 impl Hash for NotesQuery {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0
-            .as_ref()
-            .map(|arc| Arc::as_ptr(arc) as usize)
-            .hash(state);
+        hash_database(self.0.as_ref(), state);
     }
 }
 
@@ -42,7 +46,17 @@ impl QueryCapability for NotesQuery {
     async fn run(&self, _keys: &Self::Keys) -> Result<Self::Ok, Self::Err> {
         let db = self.0.as_ref().ok_or("Database not initialized")?;
         tracing::debug!("NotesQuery: fetching notes from database");
-        db.list_notes(100, 0).await.map_err(|e| e.to_string())
+        let mut notes = db
+            .list_notes_page(None, NOTE_PAGE_SIZE)
+            .await
+            .map(|page| page.notes)
+            .map_err(|e| e.to_string())?;
+        let pinned = db
+            .list_pinned_notes(PINNED_NOTES_LIMIT)
+            .await
+            .map_err(|e| e.to_string())?;
+        append_note_page(&mut notes, pinned);
+        Ok(notes)
     }
 }
 
@@ -57,3 +71,59 @@ pub fn use_notes_query(db: Option<Arc<DatabaseService>>) -> UseQuery<NotesQuery>
     let enabled = db.is_some();
     use_query(Query::new((), NotesQuery(db)).enable(enabled))
 }
+
+/// Query capability for fetching the trash: deleted notes that can still be
+/// restored, most recently deleted first
+#[derive(Clone)]
+pub struct TrashQuery(pub Option<Arc<DatabaseService>>);
+
+impl PartialEq for TrashQuery {
+    fn eq(&self, other: &Self) -> bool {
+        same_database(self.0.as_ref(), other.0.as_ref())
+    }
+}
+
+impl Eq for TrashQuery {}
+
+impl Hash for TrashQuery {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_database(self.0.as_ref(), state);
+    }
+}
+
+impl QueryCapability for TrashQuery {
+    type Ok = Vec<Note>;
+    type Err = String;
+    type Keys = ();
+
+    async fn run(&self, _keys: &Self::Keys) -> Result<Self::Ok, Self::Err> {
+        let db = self.0.as_ref().ok_or("Database not initialized")?;
+        db.list_trashed_notes(TRASH_PAGE_SIZE, 0)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Invalidate the trash query (call after deleting, restoring, or purging notes)
+pub async fn invalidate_trash_query() {
+    QueriesStorage::<TrashQuery>::invalidate_matching(()).await;
+}
+
+/// Hook to use the trash query (always call unconditionally - uses enable flag)
+pub fn use_trash_query(db: Option<Arc<DatabaseService>>) -> UseQuery<TrashQuery> {
+    let enabled = db.is_some();
+    use_query(Query::new((), TrashQuery(db)).enable(enabled))
+}
+
+/// Queries are keyed by the database they read, compared by identity
+fn same_database(a: Option<&Arc<DatabaseService>>, b: Option<&Arc<DatabaseService>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn hash_database<H: Hasher>(db: Option<&Arc<DatabaseService>>, state: &mut H) {
+    db.map(|arc| Arc::as_ptr(arc) as usize).hash(state);
+}
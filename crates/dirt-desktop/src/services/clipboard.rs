@@ -0,0 +1,28 @@
+//! System clipboard for the copy-note-id post-capture action.
+
+use std::sync::Mutex;
+
+use dirt_core::capture::Clipboard;
+
+/// Clipboard handle kept for the app's lifetime; on X11 the copied text is
+/// only served while the handle that set it is alive.
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// The OS clipboard.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClipboard;
+
+impl Clipboard for SystemClipboard {
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        let mut slot = CLIPBOARD
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let clipboard = match slot.take() {
+            Some(clipboard) => clipboard,
+            None => arboard::Clipboard::new().map_err(|error| error.to_string())?,
+        };
+        slot.insert(clipboard)
+            .set_text(text)
+            .map_err(|error| error.to_string())
+    }
+}
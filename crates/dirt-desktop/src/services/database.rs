@@ -7,10 +7,15 @@ use std::path::PathBuf;
 use std::thread;
 
 use dirt_core::db::SyncConfig;
+use dirt_core::media::AttachmentCache;
 use dirt_core::models::Settings;
+use dirt_core::seal::SealKeyStore;
 use dirt_core::services::DatabaseService as CoreDatabaseService;
+use dirt_core::transcription::WhisperModelStore;
 use dirt_core::Result;
 
+use super::active_workspace;
+
 /// Desktop database service preserving desktop path defaults.
 #[derive(Clone)]
 pub struct DatabaseService {
@@ -81,11 +86,39 @@ impl DatabaseService {
         .map_err(|error| dirt_core::Error::Database(error.to_string()))?
     }
 
+    /// Local key store for sealed notes, kept beside the database file.
+    pub fn seal_key_store() -> SealKeyStore {
+        SealKeyStore::beside_database(&Self::default_db_path())
+    }
+
+    /// Local cache of downloaded attachments, kept beside the database file.
+    ///
+    /// `None` when the cache is turned off in settings or cannot be opened.
+    pub fn attachment_cache(settings: &Settings) -> Option<AttachmentCache> {
+        let max_mb = settings.attachment_cache_mb.filter(|max_mb| *max_mb > 0)?;
+        AttachmentCache::beside_database(&Self::default_db_path(), max_mb)
+            .map_err(|error| tracing::warn!("Failed to open the attachment cache: {error}"))
+            .ok()
+    }
+
+    /// Downloaded Whisper models for offline transcription, kept beside the
+    /// database file.
+    pub fn whisper_models() -> WhisperModelStore {
+        WhisperModelStore::beside_database(&Self::default_db_path())
+    }
+
+    /// Drop a deleted attachment from the local cache, even while the cache
+    /// is turned off.
+    pub fn forget_cached_attachment(object_key: &str) {
+        let removed = AttachmentCache::beside_database(&Self::default_db_path(), 0)
+            .and_then(|cache| cache.remove(object_key));
+        if let Err(error) = removed {
+            tracing::warn!("Failed to remove {object_key} from the attachment cache: {error}");
+        }
+    }
+
     fn default_db_path() -> PathBuf {
-        dirs::data_dir()
-            .unwrap_or_else(|| panic!("Failed to resolve desktop data directory"))
-            .join("dirt")
-            .join("dirt.db")
+        active_workspace().db_path
     }
 }
 
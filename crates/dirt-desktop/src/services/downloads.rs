@@ -0,0 +1,72 @@
+//! Saving attachments to disk and revealing them in the file manager.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use dirt_core::models::Settings;
+
+/// Folder the attachment "Save as" dialog starts in.
+///
+/// Uses the configured download folder when it still exists, otherwise the
+/// system Downloads folder.
+pub fn attachment_download_dir(settings: &Settings) -> Option<PathBuf> {
+    settings
+        .attachment_download_dir
+        .as_deref()
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .or_else(dirs::download_dir)
+}
+
+/// Label for the download folder setting.
+pub fn attachment_download_dir_label(settings: &Settings) -> String {
+    settings.attachment_download_dir.as_deref().map_or_else(
+        || "System Downloads folder".to_string(),
+        ToString::to_string,
+    )
+}
+
+/// Open the folder containing `path`, selecting the file where supported.
+pub fn reveal_in_file_manager(path: &Path) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("explorer");
+        command.arg(format!("/select,{}", path.display()));
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    } else {
+        let folder = path.parent().unwrap_or(path);
+        let mut command = Command::new("xdg-open");
+        command.arg(folder);
+        command
+    };
+    command.spawn().map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn download_dir_falls_back_when_configured_folder_is_missing() {
+        let existing = std::env::temp_dir();
+        let settings = Settings {
+            attachment_download_dir: Some(existing.display().to_string()),
+            ..Settings::default()
+        };
+        assert_eq!(attachment_download_dir(&settings), Some(existing));
+
+        let missing = Settings {
+            attachment_download_dir: Some("/definitely/not/a/dirt/folder".to_string()),
+            ..Settings::default()
+        };
+        assert_eq!(attachment_download_dir(&missing), dirs::download_dir());
+        assert_eq!(
+            attachment_download_dir_label(&Settings::default()),
+            "System Downloads folder"
+        );
+    }
+}
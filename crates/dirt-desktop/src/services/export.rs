@@ -1,23 +1,44 @@
 //! Note export service for desktop UI parity with CLI exports.
 
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::Path;
 
 use dirt_core::export::{
-    render_notes_export, suggested_export_file_name as core_suggested_export_file_name,
+    bundle_attachment_path, render_json_export, render_markdown_export_with_media,
+    render_org_export, render_plaintext_export,
+    suggested_export_file_name as core_suggested_export_file_name, write_export_bundle,
     ExportFormat,
 };
-use dirt_core::Note;
+use dirt_core::journal::{compile_journal, JournalRange};
+use dirt_core::models::{Attachment, Note};
+use dirt_core::util::fs::write_atomic;
 use thiserror::Error;
 
 use super::DatabaseService;
 
-const PAGE_SIZE: usize = 500;
-
 /// Export output format.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum NotesExportFormat {
     Json,
     Markdown,
+    Org,
+    PlainText,
+}
+
+impl NotesExportFormat {
+    /// Every format, in the order the export dialog offers them.
+    pub const ALL: [Self; 4] = [Self::Markdown, Self::Json, Self::Org, Self::PlainText];
+
+    /// Name shown in the export dialog.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Markdown => "Markdown",
+            Self::Org => "Org",
+            Self::PlainText => "Plain text",
+        }
+    }
 }
 
 impl From<NotesExportFormat> for ExportFormat {
@@ -25,6 +46,24 @@ impl From<NotesExportFormat> for ExportFormat {
         match value {
             NotesExportFormat::Json => Self::Json,
             NotesExportFormat::Markdown => Self::Markdown,
+            NotesExportFormat::Org => Self::Org,
+            NotesExportFormat::PlainText => Self::PlainText,
+        }
+    }
+}
+
+/// Compiled journal output format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JournalExportFormat {
+    Markdown,
+    Pdf,
+}
+
+impl JournalExportFormat {
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Pdf => "pdf",
         }
     }
 }
@@ -40,41 +79,166 @@ pub enum NotesExportError {
     Io(#[from] std::io::Error),
 }
 
-/// Export all non-deleted notes to the destination path.
+/// Progress of a bundle export, reported after each attachment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExportProgress {
+    /// Attachments fetched or skipped so far
+    pub attachments_done: usize,
+    pub attachments_total: usize,
+}
+
+/// What a bundle export wrote.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BundleExportSummary {
+    pub notes: usize,
+    pub attachments: usize,
+    /// Attachments left out because they were quarantined or failed to download
+    pub skipped_attachments: usize,
+}
+
+/// Export all non-deleted notes to the destination path, leaving out private
+/// notes unless `include_private` is set.
 pub async fn export_notes_to_path(
     db: &DatabaseService,
     format: NotesExportFormat,
+    include_private: bool,
     output_path: &Path,
 ) -> Result<usize, NotesExportError> {
-    let notes = list_all_notes(db).await?;
-    let rendered = render_notes_export(&notes, format.into())?;
+    let notes = db.list_exportable_notes(include_private).await?;
+    let attachments = if format == NotesExportFormat::Markdown {
+        list_note_attachments(db, &notes).await?
+    } else {
+        Vec::new()
+    };
+    let rendered = render_notes(&notes, &attachments, format, HashMap::new())?;
 
-    std::fs::write(output_path, rendered)?;
+    write_atomic(output_path, rendered)?;
     Ok(notes.len())
 }
 
-/// Build a deterministic default file name for save dialogs.
-#[must_use]
-pub fn suggested_export_file_name(format: NotesExportFormat, timestamp_ms: i64) -> String {
-    core_suggested_export_file_name(format.into(), timestamp_ms)
-}
+/// Export notes like [`export_notes_to_path`] into a zip bundle that also
+/// holds their attachments, fetched one at a time with `download`.
+///
+/// Quarantined attachments and failed downloads are skipped; Markdown
+/// exports embed the bundled copies.
+pub async fn export_notes_bundle_to_path<F, Fut>(
+    db: &DatabaseService,
+    format: NotesExportFormat,
+    include_private: bool,
+    output_path: &Path,
+    mut download: F,
+    mut on_progress: impl FnMut(ExportProgress),
+) -> Result<BundleExportSummary, NotesExportError>
+where
+    F: FnMut(Attachment) -> Fut,
+    Fut: Future<Output = Result<Vec<u8>, String>>,
+{
+    let notes = db.list_exportable_notes(include_private).await?;
+    let attachments = list_note_attachments(db, &notes).await?;
+    let attachments_total = attachments.len();
 
-async fn list_all_notes(db: &DatabaseService) -> Result<Vec<Note>, dirt_core::Error> {
-    let mut notes = Vec::new();
-    let mut offset = 0usize;
+    let mut bundled = Vec::with_capacity(attachments_total);
+    for (index, attachment) in attachments.iter().enumerate() {
+        if attachment.scan_status.is_downloadable() {
+            match download(attachment.clone()).await {
+                Ok(bytes) => bundled.push((attachment.clone(), bytes)),
+                Err(error) => tracing::warn!(
+                    "Export: leaving out attachment {} ({}): {}",
+                    attachment.id,
+                    attachment.filename,
+                    error
+                ),
+            }
+        }
+        on_progress(ExportProgress {
+            attachments_done: index + 1,
+            attachments_total,
+        });
+    }
 
-    loop {
-        let batch = db.list_notes(PAGE_SIZE, offset).await?;
-        let count = batch.len();
-        notes.extend(batch);
+    let media_paths = bundled
+        .iter()
+        .map(|(attachment, _)| (attachment.id.as_str(), bundle_attachment_path(attachment)))
+        .collect();
+    let rendered = render_notes(&notes, &attachments, format, media_paths)?;
+    write_export_bundle(output_path, format.into(), rendered.as_bytes(), &bundled)?;
+    Ok(BundleExportSummary {
+        notes: notes.len(),
+        attachments: bundled.len(),
+        skipped_attachments: attachments_total - bundled.len(),
+    })
+}
 
-        if count < PAGE_SIZE {
-            break;
-        }
-        offset += count;
+async fn list_note_attachments(
+    db: &DatabaseService,
+    notes: &[Note],
+) -> Result<Vec<Attachment>, dirt_core::Error> {
+    let mut attachments = Vec::new();
+    for note in notes {
+        attachments.extend(db.list_attachments(&note.id).await?);
     }
+    Ok(attachments)
+}
+
+fn render_notes(
+    notes: &[Note],
+    attachments: &[Attachment],
+    format: NotesExportFormat,
+    media_paths: HashMap<String, String>,
+) -> Result<String, NotesExportError> {
+    Ok(match format {
+        NotesExportFormat::Json => render_json_export(notes)?,
+        NotesExportFormat::Markdown => {
+            let export = render_markdown_export_with_media(notes, attachments, media_paths);
+            for link in &export.broken_links {
+                tracing::warn!(
+                    "Export: note {} links to missing [[{}]]",
+                    link.note_id,
+                    link.target
+                );
+            }
+            export.markdown
+        }
+        NotesExportFormat::Org => render_org_export(notes),
+        NotesExportFormat::PlainText => render_plaintext_export(notes),
+    })
+}
+
+/// Compile notes created within `range` into a journal at the destination
+/// path, returning the number of entries.
+pub async fn export_journal_to_path(
+    db: &DatabaseService,
+    range: JournalRange,
+    format: JournalExportFormat,
+    include_private: bool,
+    output_path: &Path,
+) -> Result<usize, NotesExportError> {
+    let notes = db.list_exportable_notes(include_private).await?;
+    let journal = compile_journal(&notes, range);
+    let rendered = match format {
+        JournalExportFormat::Markdown => journal.render_markdown().into_bytes(),
+        JournalExportFormat::Pdf => journal.render_pdf(),
+    };
 
-    Ok(notes)
+    write_atomic(output_path, rendered)?;
+    Ok(journal.entry_count())
+}
+
+/// Default save-dialog file name for a compiled journal.
+#[must_use]
+pub fn suggested_journal_file_name(range: JournalRange, format: JournalExportFormat) -> String {
+    format!(
+        "dirt-journal-{}-to-{}.{}",
+        range.from,
+        range.to,
+        format.extension()
+    )
+}
+
+/// Build a deterministic default file name for save dialogs.
+#[must_use]
+pub fn suggested_export_file_name(format: NotesExportFormat, timestamp_ms: i64) -> String {
+    core_suggested_export_file_name(format.into(), timestamp_ms)
 }
 
 #[cfg(test)]
@@ -92,6 +256,23 @@ mod tests {
             suggested_export_file_name(NotesExportFormat::Markdown, 456),
             "dirt-export-456.md"
         );
+        assert_eq!(
+            suggested_export_file_name(NotesExportFormat::Org, 789),
+            "dirt-export-789.org"
+        );
+        assert_eq!(
+            suggested_export_file_name(NotesExportFormat::PlainText, 789),
+            "dirt-export-789.txt"
+        );
+    }
+
+    #[test]
+    fn suggested_journal_file_name_uses_range_and_extension() {
+        let range = JournalRange::parse("2024-06-01", "2024-06-30").unwrap();
+        assert_eq!(
+            suggested_journal_file_name(range, JournalExportFormat::Pdf),
+            "dirt-journal-2024-06-01-to-2024-06-30.pdf"
+        );
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -105,9 +286,10 @@ mod tests {
             chrono::Utc::now().timestamp_millis()
         ));
 
-        let exported_count = export_notes_to_path(&db, NotesExportFormat::Markdown, &output_path)
-            .await
-            .unwrap();
+        let exported_count =
+            export_notes_to_path(&db, NotesExportFormat::Markdown, false, &output_path)
+                .await
+                .unwrap();
         assert_eq!(exported_count, 2);
 
         let exported = std::fs::read_to_string(&output_path).unwrap();
@@ -116,4 +298,82 @@ mod tests {
 
         let _ = std::fs::remove_file(output_path);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn export_notes_bundle_to_path_packs_downloaded_attachments() {
+        let db = DatabaseService::in_memory().await.unwrap();
+        let note = db.create_note("Trip photos").await.unwrap();
+        db.create_attachment(&note.id, "beach.jpg", "image/jpeg", 3, "notes/a/beach.jpg")
+            .await
+            .unwrap();
+        db.create_attachment(&note.id, "gone.jpg", "image/jpeg", 3, "notes/a/gone.jpg")
+            .await
+            .unwrap();
+
+        let output_path = std::env::temp_dir().join(format!(
+            "dirt-desktop-bundle-test-{}.zip",
+            chrono::Utc::now().timestamp_millis()
+        ));
+        let mut progress = Vec::new();
+        let summary = export_notes_bundle_to_path(
+            &db,
+            NotesExportFormat::Markdown,
+            false,
+            &output_path,
+            |attachment: Attachment| async move {
+                if attachment.filename == "beach.jpg" {
+                    Ok(b"jpg".to_vec())
+                } else {
+                    Err("not found".to_string())
+                }
+            },
+            |update| progress.push(update.attachments_done),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            summary,
+            BundleExportSummary {
+                notes: 1,
+                attachments: 1,
+                skipped_attachments: 1,
+            }
+        );
+        assert_eq!(progress, [1, 2]);
+        assert!(std::fs::metadata(&output_path).unwrap().len() > 0);
+
+        let _ = std::fs::remove_file(output_path);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn export_notes_to_path_skips_private_notes_by_default() {
+        let db = DatabaseService::in_memory().await.unwrap();
+        db.create_note("Shared plan").await.unwrap();
+        let private = db.create_note("Private diary").await.unwrap();
+        db.set_note_export_excluded(&private.id, true)
+            .await
+            .unwrap();
+
+        let output_path = std::env::temp_dir().join(format!(
+            "dirt-desktop-private-export-test-{}.json",
+            chrono::Utc::now().timestamp_millis()
+        ));
+
+        let exported_count =
+            export_notes_to_path(&db, NotesExportFormat::Json, false, &output_path)
+                .await
+                .unwrap();
+        assert_eq!(exported_count, 1);
+        assert!(!std::fs::read_to_string(&output_path)
+            .unwrap()
+            .contains("Private diary"));
+
+        let exported_count = export_notes_to_path(&db, NotesExportFormat::Json, true, &output_path)
+            .await
+            .unwrap();
+        assert_eq!(exported_count, 2);
+
+        let _ = std::fs::remove_file(output_path);
+    }
 }
@@ -0,0 +1,135 @@
+//! "Edit in external editor" handoff for desktop notes.
+//!
+//! The note is written to a temp file and opened in `$VISUAL` / `$EDITOR`.
+//! While the editor runs the file is polled and each save is synced back into
+//! the note, unless the note was changed in Dirt meanwhile.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use dirt_core::util::editor::{
+    check_editor_status, classify_external_change, preferred_editor, spawn_editor,
+    strip_editor_newline, ExternalChange,
+};
+use dirt_core::util::fs::{temp_file_path, write_atomic};
+use dirt_core::Note;
+use thiserror::Error;
+
+use super::DatabaseService;
+
+/// How often the temp file is checked for saves while the editor runs.
+pub const EXTERNAL_EDIT_POLL_MS: u64 = 500;
+
+/// Errors emitted by the external editor handoff.
+#[derive(Debug, Error)]
+pub enum ExternalEditError {
+    #[error(transparent)]
+    Database(#[from] dirt_core::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(
+        "The note changed in Dirt while it was open in the external editor. \
+         Your external edits were not applied and are kept in {}",
+        .0.display()
+    )]
+    Conflict(PathBuf),
+}
+
+/// Open `note` in the external editor and sync saves back until it exits.
+///
+/// `on_saved` runs with the stored note after every applied change. The temp
+/// file is removed afterwards, except on conflict so no edits are lost.
+pub async fn edit_in_external_editor(
+    db: &DatabaseService,
+    note: &Note,
+    mut on_saved: impl FnMut(Note),
+) -> Result<(), ExternalEditError> {
+    let editor = preferred_editor();
+    let path = temp_file_path("external-edit", &format!("{}.md", note.id))?;
+    write_atomic(&path, &note.content)?;
+
+    let mut session = ExternalEditSession {
+        note,
+        path: &path,
+        base: note.content.clone(),
+        last_read: note.content.clone(),
+    };
+    let result = watch_editor(db, &editor, &mut session, &mut on_saved).await;
+    if !matches!(result, Err(ExternalEditError::Conflict(_))) {
+        let _ = std::fs::remove_file(&path);
+    }
+    result
+}
+
+struct ExternalEditSession<'a> {
+    note: &'a Note,
+    path: &'a Path,
+    /// Note content as last handed off or synced back.
+    base: String,
+    /// File content seen on the previous poll.
+    last_read: String,
+}
+
+async fn watch_editor(
+    db: &DatabaseService,
+    editor: &str,
+    session: &mut ExternalEditSession<'_>,
+    on_saved: &mut impl FnMut(Note),
+) -> Result<(), ExternalEditError> {
+    let mut child = spawn_editor(editor, session.path)?;
+    tracing::info!("Opened note {} in `{}`", session.note.id, editor);
+
+    loop {
+        let exit_status = child.try_wait()?;
+        if let Err(error) = sync_back(db, session, on_saved).await {
+            if matches!(error, ExternalEditError::Conflict(_)) {
+                tracing::warn!("External edit of note {} conflicted", session.note.id);
+            }
+            return Err(error);
+        }
+        if let Some(status) = exit_status {
+            return Ok(check_editor_status(editor, status)?);
+        }
+        tokio::time::sleep(Duration::from_millis(EXTERNAL_EDIT_POLL_MS)).await;
+    }
+}
+
+/// Apply the temp file to the note if it was saved since the last poll.
+async fn sync_back(
+    db: &DatabaseService,
+    session: &mut ExternalEditSession<'_>,
+    on_saved: &mut impl FnMut(Note),
+) -> Result<(), ExternalEditError> {
+    // Editors that save by renaming can leave the file briefly missing.
+    let Ok(text) = std::fs::read_to_string(session.path) else {
+        return Ok(());
+    };
+    if text == session.last_read {
+        return Ok(());
+    }
+    session.last_read.clone_from(&text);
+
+    let edited = strip_editor_newline(&session.note.content, &text);
+    let current = db
+        .get_note(&session.note.id)
+        .await?
+        .ok_or_else(|| dirt_core::Error::NotFound(session.note.id.to_string()))?;
+
+    match classify_external_change(&session.base, &current.content, edited) {
+        ExternalChange::Unchanged => {
+            if current.content == edited {
+                session.base = current.content;
+            }
+            Ok(())
+        }
+        ExternalChange::Apply => {
+            let updated = db.update_note(&session.note.id, edited).await?;
+            tracing::debug!("Synced external edit of note {}", updated.id);
+            session.base.clone_from(&updated.content);
+            on_saved(updated);
+            Ok(())
+        }
+        ExternalChange::Conflict => Err(ExternalEditError::Conflict(session.path.to_path_buf())),
+    }
+}
@@ -4,15 +4,25 @@
 //! Auth, sync, and media clients are shared from dirt-core;
 //! only platform-specific wiring (session store, database wrapper) lives here.
 
+mod clipboard;
 mod database;
+mod downloads;
 mod export;
+mod external_editor;
+mod notifications;
+mod os_search;
 mod session_store;
+mod share_target;
+mod summary;
 mod transcription;
+mod translation;
 mod voice_memo;
+mod workspace;
 
 // Re-export shared types from dirt-core
 pub use dirt_core::auth::{
-    AuthConfigStatus, AuthError, AuthResult, AuthSession, SignUpOutcome, SupabaseAuthService,
+    AuthConfigStatus, AuthError, AuthResult, AuthSession, AuthUser, SessionEvent, SignUpOutcome,
+    SupabaseAuthService,
 };
 pub use dirt_core::config::BootstrapConfig;
 pub use dirt_core::media::MediaApiClient;
@@ -35,7 +45,7 @@ pub fn auth_service_from_bootstrap(
             let service = SupabaseAuthService::with_session_store(
                 url,
                 anon_key,
-                KeyringSessionStore::default(),
+                KeyringSessionStore::for_workspace(&active_workspace()),
             )?;
             Ok(Some(service))
         }
@@ -69,12 +79,35 @@ pub fn media_client_from_bootstrap(
 }
 
 // Re-export desktop-specific services
+pub use clipboard::SystemClipboard;
 pub use database::DatabaseService;
-pub use export::{export_notes_to_path, suggested_export_file_name, NotesExportFormat};
+pub use downloads::{
+    attachment_download_dir, attachment_download_dir_label, reveal_in_file_manager,
+};
+pub use export::{
+    export_journal_to_path, export_notes_bundle_to_path, export_notes_to_path,
+    suggested_export_file_name, suggested_journal_file_name, BundleExportSummary, ExportProgress,
+    JournalExportFormat, NotesExportFormat,
+};
+pub use external_editor::{edit_in_external_editor, ExternalEditError};
+pub use notifications::{
+    new_conflict_count, notify, NotificationCategory, SyncFailureTracker, LARGE_EXPORT_NOTE_COUNT,
+    SYNC_CONFLICT_NOTIFY_LIMIT,
+};
+pub use os_search::{
+    os_search_index_dir, remove_os_search_index, sync_os_search_index, OsSearchIndexSummary,
+};
 pub use session_store::KeyringSessionStore;
+pub use share_target::{
+    install_share_target, remove_share_target, share_inbox_dir, spool_shared_text,
+    take_shared_texts, ShareRequest, ShareTargetKind,
+};
+pub use summary::summary_config;
 pub use transcription::{TranscriptionConfigStatus, TranscriptionService};
+pub use translation::translation_config;
 pub use voice_memo::{
     cleanup_temp_voice_memo, discard_voice_memo_recording, start_voice_memo_recording,
     stop_voice_memo_recording, transition_voice_memo_state, VoiceMemoRecorderEvent,
     VoiceMemoRecorderState,
 };
+pub use workspace::{active_workspace, add_workspace, load_workspaces, switch_workspace};
@@ -0,0 +1,44 @@
+//! OS-level notifications for events that need attention while Dirt runs in
+//! the background (sync failures, new sync conflicts, finished exports,
+//! reminders).
+
+use dirt_core::models::Settings;
+use dirt_core::notifications::{Notification, NotificationSink};
+use notify_rust::Notification as OsNotification;
+
+pub use dirt_core::notifications::{
+    new_conflict_count, NotificationCategory, SyncFailureTracker, LARGE_EXPORT_NOTE_COUNT,
+    SYNC_CONFLICT_NOTIFY_LIMIT,
+};
+
+const APP_NAME: &str = "Dirt";
+
+/// Shows notifications through the OS notification center.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsNotifications;
+
+impl NotificationSink for OsNotifications {
+    /// Delivery happens on a background thread since some platforms block on
+    /// the notification daemon; failures are only logged.
+    fn deliver(&self, notification: Notification) {
+        std::thread::spawn(move || {
+            if let Err(error) = OsNotification::new()
+                .appname(APP_NAME)
+                .summary(&notification.summary)
+                .body(&notification.body)
+                .show()
+            {
+                tracing::warn!("Failed to show desktop notification: {}", error);
+            }
+        });
+    }
+}
+
+/// Show a desktop notification if its category is enabled.
+pub fn notify(settings: &Settings, category: NotificationCategory, summary: &str, body: &str) {
+    dirt_core::notifications::notify(
+        &OsNotifications,
+        settings,
+        Notification::new(category, summary, body),
+    );
+}
@@ -0,0 +1,192 @@
+//! Publishing note titles and previews to Spotlight / Windows Search.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use dirt_core::export::plan_incremental_export;
+use dirt_core::os_search::{
+    note_id_from_file_name, render_windows_search_connector, OsSearchEntry, OsSearchFormat,
+    OS_SEARCH_CURSOR_FILE,
+};
+use dirt_core::util::fs::write_atomic;
+use dirt_core::NoteId;
+
+use super::export::NotesExportError;
+use super::DatabaseService;
+
+/// Name of the Windows search connector added to the user's `Searches` folder
+const SEARCH_CONNECTOR_FILE: &str = "Dirt notes.searchConnector-ms";
+
+/// Result of one index refresh.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OsSearchIndexSummary {
+    pub written: usize,
+    pub removed: usize,
+}
+
+/// Folder the platform indexer reads Dirt entries from.
+///
+/// macOS picks up files under `~/Library/Caches/Metadata`, the location used
+/// for Spotlight metadata importers; on Windows the folder is made searchable
+/// through a search connector.
+pub fn os_search_index_dir(format: OsSearchFormat) -> Option<PathBuf> {
+    match format {
+        OsSearchFormat::Spotlight => {
+            dirs::home_dir().map(|home| home.join("Library/Caches/Metadata/Dirt"))
+        }
+        OsSearchFormat::WindowsShortcut => {
+            dirs::data_local_dir().map(|dir| dir.join("Dirt").join("SearchIndex"))
+        }
+    }
+}
+
+fn search_connector_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("Searches").join(SEARCH_CONNECTOR_FILE))
+}
+
+/// Bring the OS search index up to date with the exportable notes.
+///
+/// Only notes edited since the last refresh are rewritten. Private notes are
+/// never published, and entries for notes that were deleted or marked private
+/// are removed.
+pub async fn sync_os_search_index(
+    db: &DatabaseService,
+    format: OsSearchFormat,
+    dir: &Path,
+) -> Result<OsSearchIndexSummary, NotesExportError> {
+    let notes = db.list_exportable_notes(false).await?;
+    let indexed = indexed_files(dir, format)?;
+    let previous_cursor = fs::read_to_string(dir.join(OS_SEARCH_CURSOR_FILE))
+        .ok()
+        .and_then(|raw| raw.trim().parse::<i64>().ok());
+    let indexed_ids: HashSet<NoteId> = indexed.keys().copied().collect();
+    let plan = plan_incremental_export(&notes, previous_cursor, &indexed_ids);
+
+    let mut summary = OsSearchIndexSummary::default();
+    for note in &plan.changed {
+        let entry = OsSearchEntry::from_note(note);
+        let path = dir.join(entry.file_name(format));
+        // Windows entries are named after the title, so a renamed note leaves
+        // its old shortcut behind.
+        for stale in indexed.get(&note.id).into_iter().flatten() {
+            if *stale != path {
+                remove_if_present(stale)?;
+            }
+        }
+        write_atomic(&path, entry.render(format))?;
+        summary.written += 1;
+    }
+    for note_id in &plan.removed {
+        for path in indexed.get(note_id).into_iter().flatten() {
+            remove_if_present(path)?;
+        }
+        summary.removed += 1;
+    }
+    write_atomic(&dir.join(OS_SEARCH_CURSOR_FILE), plan.cursor.to_string())?;
+
+    if format == OsSearchFormat::WindowsShortcut {
+        if let Some(connector) = search_connector_path() {
+            if !connector.exists() {
+                write_atomic(
+                    &connector,
+                    render_windows_search_connector(&dir.display().to_string()),
+                )?;
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// Delete every published entry, e.g. after the index is turned off.
+pub fn remove_os_search_index(format: OsSearchFormat, dir: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(dir) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error),
+    }
+    if format == OsSearchFormat::WindowsShortcut {
+        if let Some(connector) = search_connector_path() {
+            remove_if_present(&connector)?;
+        }
+    }
+    Ok(())
+}
+
+/// Entry files in `dir`, grouped by the note they belong to.
+fn indexed_files(dir: &Path, format: OsSearchFormat) -> io::Result<HashMap<NoteId, Vec<PathBuf>>> {
+    let mut files: HashMap<NoteId, Vec<PathBuf>> = HashMap::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(files),
+        Err(error) => return Err(error),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        let note_id = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| note_id_from_file_name(name, format));
+        if let Some(note_id) = note_id {
+            files.entry(note_id).or_default().push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn remove_if_present(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.ends_with(".dirtnote"))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sync_os_search_index_writes_changes_and_drops_private_notes() {
+        let db = DatabaseService::in_memory().await.unwrap();
+        let shared = db.create_note("Shared plan\nmilestones").await.unwrap();
+        let private = db.create_note("Private diary").await.unwrap();
+        let dir = std::env::temp_dir().join(format!(
+            "dirt-desktop-os-search-test-{}",
+            chrono::Utc::now().timestamp_millis()
+        ));
+
+        let first = sync_os_search_index(&db, OsSearchFormat::Spotlight, &dir)
+            .await
+            .unwrap();
+        assert_eq!(first.written, 2);
+        assert_eq!(entry_names(&dir).len(), 2);
+
+        let unchanged = sync_os_search_index(&db, OsSearchFormat::Spotlight, &dir)
+            .await
+            .unwrap();
+        assert_eq!(unchanged, OsSearchIndexSummary::default());
+
+        db.set_note_export_excluded(&private.id, true)
+            .await
+            .unwrap();
+        let after_private = sync_os_search_index(&db, OsSearchFormat::Spotlight, &dir)
+            .await
+            .unwrap();
+        assert_eq!(after_private.removed, 1);
+        assert_eq!(entry_names(&dir), vec![format!("{}.dirtnote", shared.id)]);
+
+        remove_os_search_index(OsSearchFormat::Spotlight, &dir).unwrap();
+        assert!(!dir.exists());
+    }
+}
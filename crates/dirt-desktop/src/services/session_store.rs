@@ -1,6 +1,7 @@
 //! Desktop session persistence using the OS keyring.
 
 use dirt_core::auth::{AuthError, AuthResult, AuthSession, SessionPersistence};
+use dirt_core::workspace::Workspace;
 use keyring::Entry;
 
 const KEYRING_SERVICE_NAME: &str = "dirt";
@@ -24,6 +25,14 @@ impl Default for KeyringSessionStore {
 }
 
 impl KeyringSessionStore {
+    /// Store for the sign-in session of `workspace`.
+    pub fn for_workspace(workspace: &Workspace) -> Self {
+        Self {
+            username: workspace.session_key(KEYRING_SESSION_USERNAME),
+            ..Self::default()
+        }
+    }
+
     fn entry(&self) -> AuthResult<Entry> {
         Entry::new(&self.service_name, &self.username)
             .map_err(|error| AuthError::SecureStorage(error.to_string()))
@@ -0,0 +1,343 @@
+//! Receiving text shared from other apps.
+//!
+//! Dirt registers itself with the OS share surface it can reach without an
+//! app package: a Quick Action in the macOS Services menu for selected text,
+//! and a "Send to" entry for text files in Windows Explorer. Both launch
+//! `dirt-desktop --share`, which drops the text into a spool folder and exits
+//! when Dirt is already running; the running app picks the text up and saves
+//! it through the quick-capture pipeline.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use dirt_core::util::fs::write_atomic;
+
+/// Argument carrying shared text; `-` (or no value) reads it from stdin.
+pub const SHARE_TEXT_ARG: &str = "--share";
+
+/// Argument followed by text files whose contents are shared.
+pub const SHARE_FILES_ARG: &str = "--share-file";
+
+/// Menu label of the macOS service and the Windows "Send to" entry
+const SHARE_TARGET_NAME: &str = "Send to Dirt";
+
+/// Extension of spooled texts waiting to be captured
+const SPOOL_EXTENSION: &str = "txt";
+
+/// OS share surface Dirt can register with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareTargetKind {
+    /// Automator Quick Action listed in the Services menu for selected text
+    MacService,
+    /// Script in the Explorer "Send to" menu for files
+    WindowsSendTo,
+}
+
+impl ShareTargetKind {
+    /// Share surface for the platform this binary was built for, if any.
+    pub const fn current() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(Self::MacService)
+        } else if cfg!(target_os = "windows") {
+            Some(Self::WindowsSendTo)
+        } else {
+            None
+        }
+    }
+
+    /// Where the registration lives once installed.
+    pub fn install_path(self) -> Option<PathBuf> {
+        match self {
+            Self::MacService => dirs::home_dir().map(|home| {
+                home.join("Library/Services")
+                    .join(format!("{SHARE_TARGET_NAME}.workflow"))
+            }),
+            Self::WindowsSendTo => dirs::config_dir().map(|roaming| {
+                roaming
+                    .join("Microsoft/Windows/SendTo")
+                    .join(format!("{SHARE_TARGET_NAME}.cmd"))
+            }),
+        }
+    }
+}
+
+/// What a `--share` launch asked Dirt to capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShareRequest {
+    Text(String),
+    Stdin,
+    Files(Vec<PathBuf>),
+}
+
+impl ShareRequest {
+    /// Parse the process arguments (without the program name).
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let position = args
+            .iter()
+            .position(|arg| arg == SHARE_TEXT_ARG || arg == SHARE_FILES_ARG)?;
+        let rest = &args[position + 1..];
+        if args[position] == SHARE_FILES_ARG {
+            return Some(Self::Files(rest.iter().map(PathBuf::from).collect()));
+        }
+        match rest.first().map(String::as_str) {
+            None | Some("-") => Some(Self::Stdin),
+            Some(_) => Some(Self::Text(rest.join(" "))),
+        }
+    }
+
+    /// Read the shared text; files are joined with blank lines.
+    pub fn read(self) -> io::Result<String> {
+        match self {
+            Self::Text(text) => Ok(text),
+            Self::Stdin => {
+                let mut text = String::new();
+                io::stdin().read_to_string(&mut text)?;
+                Ok(text)
+            }
+            Self::Files(paths) => {
+                let mut parts = Vec::with_capacity(paths.len());
+                for path in paths {
+                    parts.push(fs::read_to_string(&path).map_err(|error| {
+                        io::Error::new(error.kind(), format!("{}: {error}", path.display()))
+                    })?);
+                }
+                Ok(parts.join("\n\n"))
+            }
+        }
+    }
+}
+
+/// Folder shared texts wait in until the running app captures them.
+pub fn share_inbox_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("Dirt").join("ShareInbox"))
+}
+
+/// Queue shared text for capture; blank text is ignored.
+///
+/// Returns whether anything was queued.
+pub fn spool_shared_text(dir: &Path, text: &str) -> io::Result<bool> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(false);
+    }
+    // Millisecond timestamps keep captures in share order; the pid keeps two
+    // shares in the same millisecond apart.
+    let file_name = format!(
+        "{}-{}.{SPOOL_EXTENSION}",
+        chrono::Utc::now().timestamp_millis(),
+        std::process::id()
+    );
+    write_atomic(&dir.join(file_name), text)?;
+    Ok(true)
+}
+
+/// Remove and return every queued text, oldest first.
+pub fn take_shared_texts(dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == SPOOL_EXTENSION) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut texts = Vec::with_capacity(paths.len());
+    for path in paths {
+        let text = fs::read_to_string(&path)?;
+        fs::remove_file(&path)?;
+        if !text.trim().is_empty() {
+            texts.push(text);
+        }
+    }
+    Ok(texts)
+}
+
+/// Register Dirt with the OS share surface, pointing it at `exe`.
+pub fn install_share_target(kind: ShareTargetKind, exe: &Path) -> io::Result<()> {
+    let path = kind
+        .install_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No home folder"))?;
+    let exe = exe.display().to_string();
+    match kind {
+        ShareTargetKind::MacService => {
+            let contents = path.join("Contents");
+            write_atomic(&contents.join("Info.plist"), render_service_info_plist())?;
+            write_atomic(
+                &contents.join("document.wflow"),
+                render_service_workflow(&exe),
+            )
+        }
+        ShareTargetKind::WindowsSendTo => write_atomic(&path, render_send_to_script(&exe)),
+    }
+}
+
+/// Undo [`install_share_target`]; a missing registration is not an error.
+pub fn remove_share_target(kind: ShareTargetKind) -> io::Result<()> {
+    let Some(path) = kind.install_path() else {
+        return Ok(());
+    };
+    let result = match kind {
+        ShareTargetKind::MacService => fs::remove_dir_all(&path),
+        ShareTargetKind::WindowsSendTo => fs::remove_file(&path),
+    };
+    match result {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// `Info.plist` advertising the Quick Action for selected text.
+fn render_service_info_plist() -> String {
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+            "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+            "<plist version=\"1.0\">\n<dict>\n",
+            "  <key>NSServices</key>\n  <array>\n    <dict>\n",
+            "      <key>NSMenuItem</key>\n",
+            "      <dict>\n        <key>default</key>\n        <string>{}</string>\n      </dict>\n",
+            "      <key>NSMessage</key>\n      <string>runWorkflowAsService</string>\n",
+            "      <key>NSSendTypes</key>\n",
+            "      <array>\n        <string>public.utf8-plain-text</string>\n      </array>\n",
+            "    </dict>\n  </array>\n</dict>\n</plist>\n",
+        ),
+        SHARE_TARGET_NAME
+    )
+}
+
+/// Automator workflow that pipes the selection into `dirt-desktop --share -`.
+fn render_service_workflow(exe: &str) -> String {
+    let command = format!("{} {SHARE_TEXT_ARG} -", shell_quote(exe));
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+            "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+            "<plist version=\"1.0\">\n<dict>\n",
+            "  <key>AMApplicationBuild</key>\n  <string>523</string>\n",
+            "  <key>AMApplicationVersion</key>\n  <string>2.10</string>\n",
+            "  <key>AMDocumentVersion</key>\n  <string>2</string>\n",
+            "  <key>actions</key>\n  <array>\n    <dict>\n",
+            "      <key>action</key>\n      <dict>\n",
+            "        <key>AMAccepts</key>\n        <dict>\n",
+            "          <key>Container</key>\n          <string>List</string>\n",
+            "          <key>Optional</key>\n          <true/>\n",
+            "          <key>Types</key>\n",
+            "          <array>\n            <string>com.apple.cocoa.string</string>\n          </array>\n",
+            "        </dict>\n",
+            "        <key>ActionBundlePath</key>\n",
+            "        <string>/System/Library/Automator/Run Shell Script.action</string>\n",
+            "        <key>ActionName</key>\n        <string>Run Shell Script</string>\n",
+            "        <key>ActionParameters</key>\n        <dict>\n",
+            "          <key>COMMAND_STRING</key>\n          <string>{}</string>\n",
+            "          <key>CheckedForUserDefaultShell</key>\n          <true/>\n",
+            "          <key>inputMethod</key>\n          <integer>0</integer>\n",
+            "          <key>shell</key>\n          <string>/bin/sh</string>\n",
+            "          <key>source</key>\n          <string></string>\n",
+            "        </dict>\n",
+            "        <key>BundleIdentifier</key>\n        <string>com.apple.RunShellScript</string>\n",
+            "        <key>Class Name</key>\n        <string>RunShellScriptAction</string>\n",
+            "      </dict>\n",
+            "    </dict>\n  </array>\n",
+            "  <key>connectors</key>\n  <dict/>\n",
+            "  <key>workflowMetaData</key>\n  <dict>\n",
+            "    <key>serviceInputTypeIdentifier</key>\n",
+            "    <string>com.apple.Automator.text</string>\n",
+            "    <key>serviceOutputTypeIdentifier</key>\n",
+            "    <string>com.apple.Automator.nothing</string>\n",
+            "    <key>serviceProcessesInput</key>\n    <integer>0</integer>\n",
+            "    <key>workflowTypeIdentifier</key>\n",
+            "    <string>com.apple.Automator.servicesMenu</string>\n",
+            "  </dict>\n</dict>\n</plist>\n",
+        ),
+        escape_xml(&command)
+    )
+}
+
+/// Batch script for the Explorer "Send to" menu; Explorer passes the picked
+/// files as arguments.
+fn render_send_to_script(exe: &str) -> String {
+    format!("@echo off\r\nstart \"\" \"{exe}\" {SHARE_FILES_ARG} %*\r\n")
+}
+
+/// Quote `value` for a POSIX shell.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn share_request_parses_text_stdin_and_files() {
+        assert_eq!(ShareRequest::from_args(&args(&[])), None);
+        assert_eq!(
+            ShareRequest::from_args(&args(&["--share", "buy", "milk"])),
+            Some(ShareRequest::Text("buy milk".to_string()))
+        );
+        assert_eq!(
+            ShareRequest::from_args(&args(&["--share", "-"])),
+            Some(ShareRequest::Stdin)
+        );
+        assert_eq!(
+            ShareRequest::from_args(&args(&["--share-file", "a.txt", "b.md"])),
+            Some(ShareRequest::Files(vec![
+                PathBuf::from("a.txt"),
+                PathBuf::from("b.md")
+            ]))
+        );
+    }
+
+    #[test]
+    fn spooled_texts_are_taken_once_in_order() {
+        let dir = std::env::temp_dir().join(format!(
+            "dirt-desktop-share-inbox-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+
+        assert!(!spool_shared_text(&dir, "  \n").unwrap());
+        assert!(spool_shared_text(&dir, "first").unwrap());
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        assert!(spool_shared_text(&dir, " second \n").unwrap());
+
+        assert_eq!(take_shared_texts(&dir).unwrap(), ["first", "second"]);
+        assert!(take_shared_texts(&dir).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn registrations_launch_the_share_entry_point() {
+        let workflow = render_service_workflow("/Applications/Dirt's.app/Contents/MacOS/dirt");
+        assert!(workflow.contains(
+            "<string>'/Applications/Dirt'\\''s.app/Contents/MacOS/dirt' --share -</string>"
+        ));
+        assert!(workflow.contains("com.apple.Automator.servicesMenu"));
+        assert!(render_service_info_plist().contains("<string>Send to Dirt</string>"));
+
+        assert_eq!(
+            render_send_to_script(r"C:\Program Files\Dirt\dirt-desktop.exe"),
+            "@echo off\r\nstart \"\" \"C:\\Program Files\\Dirt\\dirt-desktop.exe\" --share-file %*\r\n"
+        );
+    }
+}
@@ -0,0 +1,16 @@
+//! Note summary configuration for the desktop app.
+
+use dirt_core::config::EnvConfig;
+use dirt_core::summarize::SummaryConfig;
+
+use super::TranscriptionService;
+
+/// Resolve `OpenAI` summary settings from the key saved in Settings or
+/// `OPENAI_API_KEY`; `Ok(None)` selects the local extractive summary.
+pub fn summary_config() -> Result<Option<SummaryConfig>, String> {
+    let stored_key = TranscriptionService::stored_api_key().unwrap_or_else(|error| {
+        tracing::warn!("Failed to read stored OpenAI API key: {}", error);
+        None
+    });
+    SummaryConfig::resolve(stored_key, &EnvConfig).map_err(|error| error.to_string())
+}
@@ -1,30 +1,25 @@
-//! Optional audio transcription service foundation.
-#![allow(dead_code)] // Foundation module; full wiring lands in follow-up issue.
-
+//! Desktop voice memo transcription: the `OpenAI` key in secure OS storage
+//! feeding the shared `dirt_core::transcription` client, or a Whisper model
+//! downloaded next to the database for offline transcription.
+
+use std::sync::Arc;
+
+use dirt_core::config::{ConfigSource, EnvConfig};
+use dirt_core::models::Settings;
+use dirt_core::transcription::{
+    configured_transcriber, transcribe_audio, AudioClip, ConfiguredTranscriber, OpenAiTranscriber,
+    Transcriber, TranscriptionConfig, TranscriptionError, TranscriptionProvider,
+    TranscriptionResult,
+};
 use keyring::Entry;
-use reqwest::{multipart, Client, Request, StatusCode};
-use serde::Deserialize;
-use thiserror::Error;
+
+use super::DatabaseService;
 
 const ENV_OPENAI_API_KEY: &str = "OPENAI_API_KEY";
-const ENV_OPENAI_TRANSCRIPTION_MODEL: &str = "OPENAI_TRANSCRIPTION_MODEL";
-const ENV_OPENAI_BASE_URL: &str = "OPENAI_BASE_URL";
 
-const DEFAULT_MODEL: &str = "gpt-4o-mini-transcribe";
-const DEFAULT_BASE_URL: &str = "https://api.openai.com";
 const KEYRING_SERVICE_NAME: &str = "dirt";
 const KEYRING_OPENAI_API_KEY_USERNAME: &str = "openai_api_key";
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-enum TranscriptionMode {
-    Disabled,
-    OpenAi {
-        base_url: String,
-        api_key: String,
-        model: String,
-    },
-}
-
 /// Basic configuration status for transcription.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TranscriptionConfigStatus {
@@ -33,27 +28,9 @@ pub struct TranscriptionConfigStatus {
     pub model: Option<String>,
 }
 
-/// Errors from transcription service setup and requests.
-#[derive(Debug, Error)]
-pub enum TranscriptionError {
-    #[error("Transcription is not configured. Add an OpenAI API key in Settings.")]
-    NotConfigured,
-    #[error("Invalid transcription configuration: {0}")]
-    InvalidConfiguration(&'static str),
-    #[error("Secure storage error: {0}")]
-    SecureStorage(String),
-    #[error("HTTP request failed: {0}")]
-    Http(#[from] reqwest::Error),
-    #[error("Transcription API error: {0}")]
-    Api(String),
-}
-
-type TranscriptionResult<T> = Result<T, TranscriptionError>;
-
 #[derive(Clone)]
 pub struct TranscriptionService {
-    client: Client,
-    mode: TranscriptionMode,
+    transcriber: Option<ConfiguredTranscriber>,
 }
 
 #[derive(Debug, Clone)]
@@ -109,53 +86,35 @@ impl OpenAiApiKeyStore {
 }
 
 impl TranscriptionService {
-    /// Build transcription service from secure storage.
+    /// Build the transcription service for the provider chosen in `settings`:
+    /// `OpenAI` with the key from secure storage, or a downloaded Whisper model.
     ///
     /// In debug builds, `OPENAI_API_KEY` is allowed as a local fallback.
-    pub fn new() -> TranscriptionResult<Self> {
-        let key_store = OpenAiApiKeyStore::default();
-        let mut api_key = key_store.load()?;
-
-        #[cfg(debug_assertions)]
-        if api_key.is_none() {
-            api_key = std::env::var(ENV_OPENAI_API_KEY)
-                .ok()
-                .map(|value| value.trim().to_string())
-                .filter(|value| !value.is_empty());
-        }
-
-        let mode = if let Some(api_key) = api_key {
-            let base_url = std::env::var(ENV_OPENAI_BASE_URL)
-                .ok()
-                .map(|value| value.trim().trim_end_matches('/').to_string())
-                .filter(|value| !value.is_empty())
-                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
-
-            if !(base_url.starts_with("https://") || base_url.starts_with("http://")) {
-                return Err(TranscriptionError::InvalidConfiguration(
-                    "OPENAI_BASE_URL must start with http:// or https://",
-                ));
+    pub fn new(settings: &Settings) -> TranscriptionResult<Self> {
+        let transcriber = match settings.transcription_provider {
+            TranscriptionProvider::OpenAi => {
+                let api_key = OpenAiApiKeyStore::default().load()?;
+                transcription_config(api_key, &EnvConfig)?
+                    .map(OpenAiTranscriber::new)
+                    .transpose()?
+                    .map(ConfiguredTranscriber::OpenAi)
             }
-
-            let model = std::env::var(ENV_OPENAI_TRANSCRIPTION_MODEL)
-                .ok()
-                .map(|value| value.trim().to_string())
-                .filter(|value| !value.is_empty())
-                .unwrap_or_else(|| DEFAULT_MODEL.to_string());
-
-            TranscriptionMode::OpenAi {
-                base_url,
-                api_key,
-                model,
-            }
-        } else {
-            TranscriptionMode::Disabled
+            TranscriptionProvider::Whisper => configured_transcriber(
+                settings,
+                None,
+                &EnvConfig,
+                &DatabaseService::whisper_models(),
+            )?,
         };
+        Ok(Self { transcriber })
+    }
 
-        Ok(Self {
-            client: Client::builder().build()?,
-            mode,
-        })
+    /// [`Self::new`], logging why the service is unavailable instead of failing.
+    pub fn load(settings: &Settings) -> Option<Arc<Self>> {
+        Self::new(settings)
+            .map(Arc::new)
+            .map_err(|error| tracing::warn!("Voice transcription service unavailable: {}", error))
+            .ok()
     }
 
     /// Persist `OpenAI` API key into secure storage.
@@ -163,7 +122,7 @@ impl TranscriptionService {
         let api_key = raw_api_key.trim();
         if api_key.is_empty() {
             return Err(TranscriptionError::InvalidConfiguration(
-                "OpenAI API key must not be empty",
+                "OpenAI API key must not be empty".to_string(),
             ));
         }
         OpenAiApiKeyStore::default().save(api_key)
@@ -179,141 +138,90 @@ impl TranscriptionService {
         Ok(OpenAiApiKeyStore::default().load()?.is_some())
     }
 
+    /// Stored `OpenAI` API key, also used for note translation.
+    pub fn stored_api_key() -> TranscriptionResult<Option<String>> {
+        OpenAiApiKeyStore::default().load()
+    }
+
     #[must_use]
     pub fn config_status(&self) -> TranscriptionConfigStatus {
-        match &self.mode {
-            TranscriptionMode::Disabled => TranscriptionConfigStatus {
+        match &self.transcriber {
+            None => TranscriptionConfigStatus {
                 enabled: false,
                 provider: "none",
                 model: None,
             },
-            TranscriptionMode::OpenAi { model, .. } => TranscriptionConfigStatus {
+            Some(transcriber) => TranscriptionConfigStatus {
                 enabled: true,
-                provider: "openai",
-                model: Some(model.clone()),
+                provider: transcriber.provider(),
+                model: Some(transcriber.model().to_string()),
             },
         }
     }
 
-    /// Transcribe WAV bytes into text (when configured).
-    pub async fn transcribe_wav_bytes(
-        &self,
-        file_name: &str,
-        wav_bytes: Vec<u8>,
-    ) -> TranscriptionResult<String> {
-        self.transcribe_audio_bytes(file_name, "audio/wav", wav_bytes)
-            .await
-    }
-
     /// Transcribe arbitrary audio bytes into text (when configured).
     pub async fn transcribe_audio_bytes(
         &self,
         file_name: &str,
         mime_type: &str,
-        audio_bytes: Vec<u8>,
+        audio_bytes: &[u8],
     ) -> TranscriptionResult<String> {
-        if file_name.trim().is_empty() {
-            return Err(TranscriptionError::InvalidConfiguration(
-                "file_name must not be empty",
-            ));
-        }
-        if mime_type.trim().is_empty() {
-            return Err(TranscriptionError::InvalidConfiguration(
-                "mime_type must not be empty",
-            ));
-        }
-        if !mime_type.trim().to_ascii_lowercase().starts_with("audio/") {
-            return Err(TranscriptionError::InvalidConfiguration(
-                "mime_type must start with audio/",
-            ));
-        }
-        if audio_bytes.is_empty() {
-            return Err(TranscriptionError::InvalidConfiguration(
-                "audio payload must not be empty",
-            ));
-        }
-
-        let request = self.build_transcription_request(file_name, mime_type, audio_bytes)?;
-        let response = self.client.execute(request).await?;
-
-        if response.status() == StatusCode::UNAUTHORIZED {
-            return Err(TranscriptionError::Api(
-                "Unauthorized transcription request (check configured OpenAI API key)".to_string(),
-            ));
-        }
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(TranscriptionError::Api(format!(
-                "Transcription request failed with {status}: {body}"
-            )));
-        }
-
-        let payload: OpenAiTranscriptionResponse = response.json().await?;
-        Ok(payload.text.trim().to_string())
-    }
-
-    fn build_transcription_request(
-        &self,
-        file_name: &str,
-        mime_type: &str,
-        audio_bytes: Vec<u8>,
-    ) -> TranscriptionResult<Request> {
-        let (base_url, api_key, model) = match &self.mode {
-            TranscriptionMode::Disabled => return Err(TranscriptionError::NotConfigured),
-            TranscriptionMode::OpenAi {
-                base_url,
-                api_key,
-                model,
-            } => (base_url, api_key, model),
-        };
-
-        let endpoint = format!("{base_url}/v1/audio/transcriptions");
-        let file_part = multipart::Part::bytes(audio_bytes)
-            .file_name(file_name.to_string())
-            .mime_str(mime_type)
-            .map_err(TranscriptionError::Http)?;
-
-        let form = multipart::Form::new()
-            .text("model", model.clone())
-            .part("file", file_part);
-
-        self.client
-            .post(endpoint)
-            .bearer_auth(api_key)
-            .multipart(form)
-            .build()
-            .map_err(TranscriptionError::Http)
+        let transcriber = self
+            .transcriber
+            .as_ref()
+            .ok_or(TranscriptionError::NotConfigured)?;
+        transcribe_audio(
+            transcriber,
+            &AudioClip {
+                file_name,
+                mime_type,
+                bytes: audio_bytes,
+            },
+        )
+        .await
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct OpenAiTranscriptionResponse {
-    text: String,
+/// Resolve transcription settings from the stored key and `OPENAI_*`
+/// overrides in `source`.
+///
+/// In debug builds, `OPENAI_API_KEY` is allowed as a fallback key.
+fn transcription_config(
+    stored_api_key: Option<String>,
+    source: &impl ConfigSource,
+) -> TranscriptionResult<Option<TranscriptionConfig>> {
+    let api_key = stored_api_key.or_else(|| {
+        if cfg!(debug_assertions) {
+            source.text_var(ENV_OPENAI_API_KEY)
+        } else {
+            None
+        }
+    });
+    TranscriptionConfig::resolve(api_key, source)
 }
 
 #[cfg(test)]
 mod tests {
+    use dirt_core::config::MapConfig;
+
     use super::*;
 
-    fn configured_service() -> TranscriptionService {
-        TranscriptionService {
-            client: Client::builder().build().unwrap(),
-            mode: TranscriptionMode::OpenAi {
-                base_url: "https://api.openai.com".to_string(),
-                api_key: "test-key".to_string(),
-                model: "gpt-4o-mini-transcribe".to_string(),
-            },
-        }
+    #[test]
+    fn stored_key_takes_precedence_over_environment() {
+        let source = MapConfig::new()
+            .with(ENV_OPENAI_API_KEY, "env-key")
+            .with("OPENAI_TRANSCRIPTION_MODEL", "whisper-1");
+        let config = transcription_config(Some("stored-key".to_string()), &source)
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.api_key, "stored-key");
+        assert_eq!(config.model, "whisper-1");
+        assert_eq!(transcription_config(None, &MapConfig::new()).unwrap(), None);
     }
 
     #[test]
     fn disabled_status_when_not_configured() {
-        let service = TranscriptionService {
-            client: Client::builder().build().unwrap(),
-            mode: TranscriptionMode::Disabled,
-        };
+        let service = TranscriptionService { transcriber: None };
 
         let status = service.config_status();
         assert!(!status.enabled);
@@ -321,58 +229,13 @@ mod tests {
         assert_eq!(status.model, None);
     }
 
-    #[test]
-    fn openai_request_shape_is_correct() {
-        let service = configured_service();
-        let request = service
-            .build_transcription_request("memo.wav", "audio/wav", vec![0, 1, 2, 3])
-            .unwrap();
-
-        assert_eq!(request.method(), reqwest::Method::POST);
-        assert_eq!(
-            request.url().as_str(),
-            "https://api.openai.com/v1/audio/transcriptions"
-        );
-
-        let auth = request
-            .headers()
-            .get(reqwest::header::AUTHORIZATION)
-            .unwrap()
-            .to_str()
-            .unwrap();
-        assert!(auth.starts_with("Bearer "));
-    }
-
-    #[test]
-    fn request_fails_when_disabled() {
-        let service = TranscriptionService {
-            client: Client::builder().build().unwrap(),
-            mode: TranscriptionMode::Disabled,
-        };
-        let err = service
-            .build_transcription_request("memo.wav", "audio/wav", vec![1, 2, 3])
+    #[tokio::test(flavor = "current_thread")]
+    async fn transcribing_fails_when_disabled() {
+        let service = TranscriptionService { transcriber: None };
+        let error = service
+            .transcribe_audio_bytes("memo.wav", "audio/wav", &[1, 2, 3])
+            .await
             .unwrap_err();
-        assert!(matches!(err, TranscriptionError::NotConfigured));
-    }
-
-    #[test]
-    fn request_supports_non_wav_audio_mime() {
-        let service = configured_service();
-        let request = service
-            .build_transcription_request("memo.webm", "audio/webm", vec![0, 1, 2, 3])
-            .unwrap();
-
-        assert_eq!(request.method(), reqwest::Method::POST);
-        assert_eq!(
-            request.url().as_str(),
-            "https://api.openai.com/v1/audio/transcriptions"
-        );
-    }
-
-    #[test]
-    fn parse_openai_response_text() {
-        let payload: OpenAiTranscriptionResponse =
-            serde_json::from_str(r#"{"text":"hello world"}"#).unwrap();
-        assert_eq!(payload.text, "hello world");
+        assert!(matches!(error, TranscriptionError::NotConfigured));
     }
 }
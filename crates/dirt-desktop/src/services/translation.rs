@@ -0,0 +1,21 @@
+//! Translation provider configuration for the desktop app.
+
+use dirt_core::config::EnvConfig;
+use dirt_core::translate::TranslationConfig;
+
+use super::TranscriptionService;
+
+/// Resolve the translation provider, reusing the `OpenAI` key saved in
+/// Settings before falling back to `OPENAI_API_KEY` / `DEEPL_API_KEY`.
+pub fn translation_config() -> Result<TranslationConfig, String> {
+    let stored_key = TranscriptionService::stored_api_key().unwrap_or_else(|error| {
+        tracing::warn!("Failed to read stored OpenAI API key: {}", error);
+        None
+    });
+    TranslationConfig::resolve(stored_key, &EnvConfig)
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| {
+            "Translation is not configured. Add an OpenAI API key in Settings or set DEEPL_API_KEY."
+                .to_string()
+        })
+}
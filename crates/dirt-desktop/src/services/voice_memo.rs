@@ -6,6 +6,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine as _;
 use dioxus::document;
+use dirt_core::util::fs::{temp_file_path, write_atomic};
 use serde::Deserialize;
 
 const START_RECORDING_SCRIPT: &str = r#"
@@ -342,12 +343,9 @@ fn build_voice_memo_file_name(mime_type: &str) -> String {
 }
 
 fn persist_temp_voice_memo(file_name: &str, bytes: &[u8]) -> Result<PathBuf, String> {
-    let temp_dir = std::env::temp_dir().join("dirt").join("voice-memos");
-    std::fs::create_dir_all(&temp_dir)
+    let path = temp_file_path("voice-memos", file_name)
         .map_err(|error| format!("Failed to create temp voice memo directory: {error}"))?;
-
-    let path = temp_dir.join(file_name);
-    std::fs::write(&path, bytes)
+    write_atomic(&path, bytes)
         .map_err(|error| format!("Failed to persist temp voice memo file: {error}"))?;
     Ok(path)
 }
@@ -0,0 +1,73 @@
+//! The desktop's active workspace.
+//!
+//! The database path and keyring session both come from the active
+//! workspace. It is read from disk once and cached, so the many
+//! `DatabaseService` helpers that need the database path stay cheap.
+
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use dirt_core::workspace::{Workspace, Workspaces};
+use dirt_core::Result;
+
+static ACTIVE_WORKSPACE: RwLock<Option<Workspace>> = RwLock::new(None);
+
+/// Desktop data directory, which holds the workspace list.
+fn data_root() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| panic!("Failed to resolve desktop data directory"))
+        .join("dirt")
+}
+
+/// Workspaces on this device; the default one uses the database desktop
+/// used before workspaces existed.
+pub fn load_workspaces() -> Result<Workspaces> {
+    let root = data_root();
+    Workspaces::load(&root, root.join("dirt.db"))
+}
+
+/// The workspace the app is using.
+pub fn active_workspace() -> Workspace {
+    if let Some(workspace) = ACTIVE_WORKSPACE
+        .read()
+        .ok()
+        .and_then(|active| active.clone())
+    {
+        return workspace;
+    }
+
+    let workspace = match load_workspaces() {
+        Ok(workspaces) => workspaces.active().clone(),
+        Err(error) => {
+            tracing::warn!("Failed to load workspaces, using the default one: {error}");
+            let root = data_root();
+            Workspaces::with_default(&root, root.join("dirt.db"))
+                .active()
+                .clone()
+        }
+    };
+    if let Ok(mut active) = ACTIVE_WORKSPACE.write() {
+        *active = Some(workspace.clone());
+    }
+    workspace
+}
+
+/// Make `name` the active workspace and remember it for the next launch.
+/// The caller reconnects the database and restores the workspace's session.
+pub fn switch_workspace(name: &str) -> Result<Workspace> {
+    let mut workspaces = load_workspaces()?;
+    let workspace = workspaces.switch(name)?.clone();
+    workspaces.save()?;
+    if let Ok(mut active) = ACTIVE_WORKSPACE.write() {
+        *active = Some(workspace.clone());
+    }
+    Ok(workspace)
+}
+
+/// Add a workspace with its own database and sign-in.
+pub fn add_workspace(name: &str) -> Result<Workspace> {
+    let mut workspaces = load_workspaces()?;
+    let workspace = workspaces.add(name, None)?.clone();
+    workspaces.save()?;
+    Ok(workspace)
+}
@@ -2,15 +2,22 @@
 //!
 //! Global state accessible via Dioxus context providers.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use dioxus::prelude::*;
 
-use dirt_core::models::{Note, NoteId, Settings};
+use dirt_core::changelog::Release;
+use dirt_core::connectivity::ConnectivityMonitor;
+use dirt_core::models::{apply_manual_order, Note, NoteDraft, NoteId, Settings, SortScope};
+use dirt_core::search::SearchQuery;
 pub use dirt_core::state::SyncState as SyncStatus;
+use dirt_core::sync::SyncRotationEvent;
+use dirt_core::workspace::Workspace;
 
 use crate::services::{
-    AuthSession, DatabaseService, DesktopAuthService, MediaApiClient, TranscriptionService,
+    AuthSession, AuthUser, DatabaseService, DesktopAuthService, MediaApiClient,
+    TranscriptionService,
 };
 use crate::theme::ResolvedTheme;
 
@@ -23,8 +30,14 @@ pub struct AppState {
     pub current_note_id: Signal<Option<NoteId>>,
     /// Current search query
     pub search_query: Signal<String>,
+    /// Notes with at least one attachment, loaded for `has:attachment` searches
+    pub attachment_note_ids: Signal<HashSet<NoteId>>,
     /// Active tag filter
     pub active_tag_filter: Signal<Option<String>>,
+    /// Whether notes under the active tag are shown in manual order
+    pub manual_sort_enabled: Signal<bool>,
+    /// Manual note order for the active tag scope
+    pub manual_order: Signal<Vec<NoteId>>,
     /// Application settings
     pub settings: Signal<Settings>,
     /// Resolved theme (light/dark based on settings and system preference)
@@ -41,22 +54,48 @@ pub struct AppState {
     pub auth_session: Signal<Option<AuthSession>>,
     /// Last auth initialization/sign-in error for UI display
     pub auth_error: Signal<Option<String>>,
+    /// User whose session could not be refreshed; cleared on sign-in
+    pub expired_session: Signal<Option<AuthUser>>,
+    /// Whether the re-login dialog for an expired session is open
+    pub reauth_open: Signal<bool>,
+    /// Whether a newer client upgraded the database, leaving it read-only
+    pub database_read_only: Signal<bool>,
+    /// Minimum version the backend requires, while this build is older
+    pub update_required: Signal<Option<String>>,
     /// Monotonic reconnect trigger for db reinitialization flows.
     pub db_reconnect_version: Signal<u64>,
+    /// Workspace whose database and sign-in are in use
+    pub workspace: Signal<Workspace>,
+    /// Whether the backend is reachable; network work pauses while offline
+    pub connectivity: Signal<ConnectivityMonitor>,
     /// Current sync status
     pub sync_status: Signal<SyncStatus>,
     /// Last sync subsystem error shown in settings diagnostics
     pub sync_issue: Signal<Option<String>>,
     /// Timestamp (unix ms) of the most recent successful sync
     pub last_sync_at: Signal<Option<i64>>,
+    /// Most recent sync credential rotation reported by the API
+    pub last_sync_rotation: Signal<Option<SyncRotationEvent>>,
     /// Count of local changes pending cloud sync
     pub pending_sync_count: Signal<usize>,
     /// Unique note IDs currently represented in pending changes
     pub pending_sync_note_ids: Signal<Vec<NoteId>>,
     /// Whether settings panel is open
     pub settings_open: Signal<bool>,
-    /// Whether quick capture overlay is active
+    /// Whether the import wizard is open
+    pub import_open: Signal<bool>,
+    /// Whether the export dialog is open
+    pub export_open: Signal<bool>,
+    /// Whether the note graph replaces the list and editor
+    pub graph_open: Signal<bool>,
+    /// Whether the captured links view replaces the list and editor
+    pub links_open: Signal<bool>,
+    /// Whether the quick capture window is showing
     pub quick_capture_open: Signal<bool>,
+    /// Unsaved drafts found on startup, pending a restore/discard decision
+    pub recovered_drafts: Signal<Vec<NoteDraft>>,
+    /// Release notes not seen before this launch, shown once in a dialog
+    pub whats_new: Signal<Vec<Release>>,
 }
 
 impl AppState {
@@ -67,37 +106,85 @@ impl AppState {
         current_id.and_then(|id| (self.notes)().into_iter().find(|note| note.id == id))
     }
 
-    /// Get filtered notes based on search query and tag filter
+    /// Scope whose manual order applies to the note list, if any
+    #[must_use]
+    pub fn manual_sort_scope(&self) -> Option<SortScope> {
+        if !(self.manual_sort_enabled)() {
+            return None;
+        }
+        (self.active_tag_filter)().map(|tag| SortScope::tag(&tag))
+    }
+
+    /// Get filtered notes based on search query and tag filter, in manual
+    /// order when a manual-sort scope is active
+    ///
+    /// The query is parsed like `dirt search` queries; see [`SearchQuery`].
     #[must_use]
     pub fn filtered_notes(&self) -> Vec<Note> {
         let notes = (self.notes)();
-        let query = (self.search_query)().to_lowercase();
+        let query = SearchQuery::parse_or_literal(&(self.search_query)());
+        let attachment_note_ids = (self.attachment_note_ids)();
         let tag_filter = (self.active_tag_filter)();
 
-        notes
+        let mut notes = notes
             .into_iter()
-            .filter(|note| !note.is_deleted)
-            .filter(|note| {
-                if query.is_empty() {
-                    true
-                } else {
-                    note.content.to_lowercase().contains(&query)
-                }
-            })
+            .filter(|note| !note.is_deleted && !note.is_archived)
+            .filter(|note| query.matches(note, attachment_note_ids.contains(&note.id)))
             .filter(|note| {
                 tag_filter
                     .as_ref()
                     .map_or(true, |tag| note.tags().iter().any(|t| t == tag))
             })
-            .collect()
+            .collect::<Vec<_>>();
+
+        if self.manual_sort_scope().is_some() {
+            apply_manual_order(&mut notes, &(self.manual_order)());
+        } else {
+            // Stable, so pinned and unpinned notes each keep newest-first order.
+            notes.sort_by_key(|note| !note.is_pinned);
+        }
+        notes
+    }
+
+    /// Change settings outside the settings panel and save them in the background.
+    pub fn update_settings(&mut self, update: impl FnOnce(&mut Settings)) {
+        let mut settings = self.settings.peek().clone();
+        update(&mut settings);
+        self.settings.set(settings.clone());
+
+        let db = self.db_service.peek().clone();
+        spawn(async move {
+            if let Some(db) = db {
+                if let Err(error) = db.save_settings(&settings).await {
+                    tracing::error!("Failed to save settings: {}", error);
+                }
+            }
+        });
+    }
+
+    /// Turn the distraction-free editor on or off.
+    pub fn toggle_focus_mode(&mut self) {
+        self.update_settings(|settings| settings.focus_mode = !settings.focus_mode);
     }
 
     /// Track a pending change for a note until the next successful sync.
+    ///
+    /// The change is also queued in the database's sync outbox so it is
+    /// still pending after a restart.
     pub fn enqueue_pending_change(&mut self, note_id: NoteId) {
         let mut pending_notes = self.pending_sync_note_ids.write();
         if !pending_notes.contains(&note_id) {
             pending_notes.push(note_id);
             self.pending_sync_count.set(pending_notes.len());
         }
+
+        let db = self.db_service.peek().clone();
+        spawn(async move {
+            if let Some(db) = db {
+                if let Err(error) = db.enqueue_sync_change(&note_id).await {
+                    tracing::warn!("Failed to queue note {} for sync: {}", note_id, error);
+                }
+            }
+        });
     }
 }
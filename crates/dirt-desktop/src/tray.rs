@@ -1,51 +1,101 @@
 //! System tray integration
 //!
-//! Provides system tray icon with menu for quick access to Dirt features.
+//! Provides system tray icon with menu for quick access to Dirt features:
+//! quick capture, the most recent notes, and the sync state with a
+//! "Sync now" action.
 
+use std::cell::RefCell;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    OnceLock,
+    Mutex, OnceLock, PoisonError,
 };
 
+use dirt_core::models::{Hotkey, Note, NoteId};
 use tray_icon::{
-    menu::{Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem},
+    menu::{Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem, Submenu},
     Icon, TrayIcon, TrayIconBuilder, TrayIconEvent,
 };
 
+use crate::state::SyncStatus;
+
 /// Atomic flags for tray events - shared with UI
 pub static SHOW_MAIN_WINDOW: AtomicBool = AtomicBool::new(false);
 pub static QUIT_REQUESTED: AtomicBool = AtomicBool::new(false);
+pub static CAPTURE_FILE_REQUESTED: AtomicBool = AtomicBool::new(false);
+pub static SYNC_NOW_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Recent note picked in the tray, waiting for the UI to open it
+static OPEN_NOTE_REQUESTED: Mutex<Option<NoteId>> = Mutex::new(None);
 
 /// Static menu item IDs (set during initialization)
 static NEW_NOTE_ID: OnceLock<MenuId> = OnceLock::new();
+static CAPTURE_FILE_ID: OnceLock<MenuId> = OnceLock::new();
 static OPEN_DIRT_ID: OnceLock<MenuId> = OnceLock::new();
+static SYNC_NOW_ID: OnceLock<MenuId> = OnceLock::new();
 static QUIT_ID: OnceLock<MenuId> = OnceLock::new();
 
+/// Number of notes listed under "Recent Notes"
+const RECENT_NOTE_COUNT: usize = 5;
+/// Longest note title shown in the menu
+const RECENT_NOTE_TITLE_CHARS: usize = 40;
+
+thread_local! {
+    /// Tray created on the main thread before launch; the UI runs on the same
+    /// thread and refreshes the menu as notes and sync state change.
+    static TRAY: RefCell<Option<TrayManager>> = const { RefCell::new(None) };
+}
+
 /// System tray manager
 pub struct TrayManager {
-    #[allow(dead_code)]
     tray_icon: TrayIcon,
+    new_note_item: MenuItem,
+    sync_status_item: MenuItem,
+    recent_menu: Submenu,
+    /// Menu entries currently under "Recent Notes", with the note each opens
+    recent_items: Vec<(MenuItem, NoteId)>,
+    /// What the menu last showed, to skip rebuilding it when nothing changed
+    shown: Option<TrayMenuState>,
+}
+
+/// Dynamic part of the tray menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrayMenuState {
+    recent_notes: Vec<(NoteId, String)>,
+    sync_status: SyncStatus,
+    capture_hotkey: String,
 }
 
 impl TrayManager {
     /// Create and initialize the system tray
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
         // Create menu items
-        let new_note_item = MenuItem::new("New Note\tCtrl+Alt+N", true, None);
+        let new_note_item = MenuItem::new("Quick Capture", true, None);
+        let capture_file_item = MenuItem::new("Capture File...", true, None);
         let open_item = MenuItem::new("Open Dirt", true, None);
+        let recent_menu = Submenu::new("Recent Notes", false);
+        let sync_status_item = MenuItem::new(sync_status_label(SyncStatus::Offline), false, None);
+        let sync_now_item = MenuItem::new("Sync Now", true, None);
         let quit_item = MenuItem::new("Quit", true, None);
 
         // Store IDs in statics for event handling
         let _ = NEW_NOTE_ID.set(new_note_item.id().clone());
+        let _ = CAPTURE_FILE_ID.set(capture_file_item.id().clone());
         let _ = OPEN_DIRT_ID.set(open_item.id().clone());
+        let _ = SYNC_NOW_ID.set(sync_now_item.id().clone());
         let _ = QUIT_ID.set(quit_item.id().clone());
 
         // Build menu
         let menu = Menu::new();
         menu.append_items(&[
             &new_note_item,
+            &capture_file_item,
             &open_item,
             &PredefinedMenuItem::separator(),
+            &recent_menu,
+            &PredefinedMenuItem::separator(),
+            &sync_status_item,
+            &sync_now_item,
+            &PredefinedMenuItem::separator(),
             &quit_item,
         ])?;
 
@@ -62,7 +112,125 @@ impl TrayManager {
 
         tracing::info!("System tray initialized");
 
-        Ok(Self { tray_icon })
+        Ok(Self {
+            tray_icon,
+            new_note_item,
+            sync_status_item,
+            recent_menu,
+            recent_items: Vec::new(),
+            shown: None,
+        })
+    }
+
+    /// Bring the menu and tooltip in line with `state`
+    fn refresh(&mut self, state: TrayMenuState) {
+        if self.shown.as_ref() == Some(&state) {
+            return;
+        }
+
+        self.new_note_item
+            .set_text(format!("Quick Capture\t{}", state.capture_hotkey));
+        self.sync_status_item
+            .set_text(sync_status_label(state.sync_status));
+        if let Err(error) = self.tray_icon.set_tooltip(Some(format!(
+            "Dirt - {}",
+            sync_status_label(state.sync_status)
+        ))) {
+            tracing::warn!("Failed to update tray tooltip: {}", error);
+        }
+
+        let recent_changed = self
+            .shown
+            .as_ref()
+            .map_or(true, |shown| shown.recent_notes != state.recent_notes);
+        if recent_changed {
+            for (item, _) in self.recent_items.drain(..) {
+                if let Err(error) = self.recent_menu.remove(&item) {
+                    tracing::warn!("Failed to remove recent note from tray: {}", error);
+                }
+            }
+            for (note_id, title) in &state.recent_notes {
+                let item = MenuItem::new(title, true, None);
+                match self.recent_menu.append(&item) {
+                    Ok(()) => self.recent_items.push((item, *note_id)),
+                    Err(error) => tracing::warn!("Failed to add recent note to tray: {}", error),
+                }
+            }
+            self.recent_menu.set_enabled(!self.recent_items.is_empty());
+        }
+
+        self.shown = Some(state);
+    }
+
+    /// Note opened by the recent note entry `id`, if it is one
+    fn recent_note_for(&self, id: &MenuId) -> Option<NoteId> {
+        self.recent_items
+            .iter()
+            .find(|(item, _)| item.id() == id)
+            .map(|(_, note_id)| *note_id)
+    }
+}
+
+/// Keep `manager` alive for the rest of the process so the menu can be
+/// updated. Must be called on the main thread.
+pub fn install(manager: TrayManager) {
+    TRAY.with(|slot| *slot.borrow_mut() = Some(manager));
+}
+
+/// Show the most recently edited `notes`, the sync state, and the current
+/// capture hotkey in the tray menu. Does nothing without a tray.
+pub fn update_tray_menu(notes: &[Note], sync_status: SyncStatus, capture_hotkey: &str) {
+    let state = TrayMenuState {
+        recent_notes: recent_note_entries(notes),
+        sync_status,
+        capture_hotkey: capture_hotkey
+            .parse::<Hotkey>()
+            .map_or_else(|_| capture_hotkey.to_string(), |hotkey| hotkey.to_string()),
+    };
+    TRAY.with(|slot| {
+        if let Some(manager) = slot.borrow_mut().as_mut() {
+            manager.refresh(state);
+        }
+    });
+}
+
+/// Take the note picked from the tray's recent notes, if any
+pub fn take_open_note_request() -> Option<NoteId> {
+    OPEN_NOTE_REQUESTED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+}
+
+/// Most recently edited notes with their menu titles, newest first
+fn recent_note_entries(notes: &[Note]) -> Vec<(NoteId, String)> {
+    let mut recent: Vec<&Note> = notes
+        .iter()
+        .filter(|note| !note.is_deleted && !note.is_archived)
+        .collect();
+    recent.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    recent
+        .into_iter()
+        .take(RECENT_NOTE_COUNT)
+        .map(|note| {
+            let title = note.title_preview(RECENT_NOTE_TITLE_CHARS);
+            let title = title.trim();
+            let title = if title.is_empty() {
+                "Untitled".to_string()
+            } else {
+                title.to_string()
+            };
+            (note.id, title)
+        })
+        .collect()
+}
+
+const fn sync_status_label(status: SyncStatus) -> &'static str {
+    match status {
+        SyncStatus::Synced => "Synced",
+        SyncStatus::Syncing => "Syncing...",
+        SyncStatus::Offline => "Offline",
+        SyncStatus::Error => "Sync error",
     }
 }
 
@@ -77,12 +245,28 @@ pub fn process_tray_events() {
         if NEW_NOTE_ID.get().is_some_and(|nid| nid == id) {
             tracing::info!("Tray: New Note clicked");
             crate::HOTKEY_TRIGGERED.store(true, Ordering::SeqCst);
+        } else if CAPTURE_FILE_ID.get().is_some_and(|cid| cid == id) {
+            tracing::info!("Tray: Capture File clicked");
+            CAPTURE_FILE_REQUESTED.store(true, Ordering::SeqCst);
         } else if OPEN_DIRT_ID.get().is_some_and(|oid| oid == id) {
             tracing::info!("Tray: Open Dirt clicked");
             SHOW_MAIN_WINDOW.store(true, Ordering::SeqCst);
+        } else if SYNC_NOW_ID.get().is_some_and(|sid| sid == id) {
+            tracing::info!("Tray: Sync Now clicked");
+            SYNC_NOW_REQUESTED.store(true, Ordering::SeqCst);
         } else if QUIT_ID.get().is_some_and(|qid| qid == id) {
             tracing::info!("Tray: Quit clicked");
             QUIT_REQUESTED.store(true, Ordering::SeqCst);
+        } else if let Some(note_id) = TRAY.with(|slot| {
+            slot.borrow()
+                .as_ref()
+                .and_then(|manager| manager.recent_note_for(id))
+        }) {
+            tracing::info!("Tray: Recent note clicked");
+            *OPEN_NOTE_REQUESTED
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) = Some(note_id);
+            SHOW_MAIN_WINDOW.store(true, Ordering::SeqCst);
         }
     }
 
@@ -129,3 +313,31 @@ fn create_default_icon() -> Result<Icon, Box<dyn std::error::Error>> {
 
     Ok(Icon::from_rgba(rgba, SIZE, SIZE)?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(content: &str, updated_at: i64) -> Note {
+        Note {
+            updated_at,
+            ..Note::new(content)
+        }
+    }
+
+    #[test]
+    fn recent_notes_are_newest_first_and_skip_hidden_notes() {
+        let mut deleted = note("Deleted", 90);
+        deleted.is_deleted = true;
+        let mut archived = note("Archived", 80);
+        archived.is_archived = true;
+        let mut notes = vec![deleted, archived, note("  \nbody", 70)];
+        notes.extend((1..=5).map(|index| note(&format!("Note {index}\nbody"), index)));
+
+        let titles: Vec<String> = recent_note_entries(&notes)
+            .into_iter()
+            .map(|(_, title)| title)
+            .collect();
+        assert_eq!(titles, ["Untitled", "Note 5", "Note 4", "Note 3", "Note 2"]);
+    }
+}
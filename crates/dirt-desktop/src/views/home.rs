@@ -3,7 +3,8 @@
 use dioxus::prelude::*;
 
 use crate::components::{
-    create_note_optimistic, NoteEditor, NoteList, SearchBar, Sidebar, Toolbar,
+    create_note_optimistic, GraphView, LinksView, NoteEditor, NoteList, ReadOnlyDatabaseBanner,
+    SearchBar, SessionExpiredBanner, Sidebar, Toolbar, UpdateRequiredBanner,
 };
 use crate::state::AppState;
 
@@ -25,17 +26,60 @@ pub fn Home() -> Element {
             return;
         }
 
+        let is_focus_shortcut = (evt.modifiers().ctrl() || evt.modifiers().meta())
+            && evt.modifiers().shift()
+            && matches!(
+                evt.key(),
+                Key::Character(ch) if ch.eq_ignore_ascii_case("f")
+            );
+        if is_focus_shortcut {
+            evt.prevent_default();
+            state.toggle_focus_mode();
+            return;
+        }
+
         if evt.key() == Key::Escape {
             if (state.settings_open)() {
                 state.settings_open.set(false);
                 return;
             }
+            if state.settings.peek().focus_mode {
+                state.toggle_focus_mode();
+                return;
+            }
+            if (state.graph_open)() {
+                state.graph_open.set(false);
+                return;
+            }
+            if (state.links_open)() {
+                state.links_open.set(false);
+                return;
+            }
             if (state.current_note_id)().is_some() {
                 state.current_note_id.set(None);
             }
         }
     };
 
+    if (state.settings)().focus_mode {
+        return rsx! {
+            div {
+                class: "home-container focus-mode",
+                style: "display: flex; flex-direction: column; height: 100vh;",
+                onkeydown: handle_keydown,
+
+                SessionExpiredBanner {}
+                ReadOnlyDatabaseBanner {}
+                UpdateRequiredBanner {}
+                div {
+                    class: "content-area",
+                    style: "flex: 1; display: flex; overflow: hidden;",
+                    NoteEditor {}
+                }
+            }
+        };
+    }
+
     rsx! {
         div {
             class: "home-container",
@@ -48,6 +92,9 @@ pub fn Home() -> Element {
                 class: "main-content",
                 style: "flex: 1; display: flex; flex-direction: column;",
 
+                SessionExpiredBanner {}
+                ReadOnlyDatabaseBanner {}
+                UpdateRequiredBanner {}
                 Toolbar {}
                 SearchBar {}
 
@@ -55,8 +102,14 @@ pub fn Home() -> Element {
                     class: "content-area",
                     style: "flex: 1; display: flex; overflow: hidden;",
 
-                    NoteList {}
-                    NoteEditor {}
+                    if (state.graph_open)() {
+                        GraphView {}
+                    } else if (state.links_open)() {
+                        LinksView {}
+                    } else {
+                        NoteList {}
+                        NoteEditor {}
+                    }
                 }
             }
         }
@@ -0,0 +1,256 @@
+//! dirt-integration-tests - Cross-client tests for the shared `dirt-core` flows
+//!
+//! The tests in `tests/` walk the path every client takes, from bootstrap
+//! config to export, through the same `dirt-core` calls the CLI, desktop, and
+//! mobile apps make. An API change in `dirt-core` that one client would only
+//! notice at runtime fails here instead.
+//!
+//! [`MockBackend`] stands in for the bootstrap manifest, Supabase auth, and the
+//! sync token exchange on one local port, so nothing leaves the machine.
+//! [`Client`] captures how each app opens its database and exports notes.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use dirt_core::export::{render_notes_export, ExportFormat};
+use dirt_core::services::DatabaseService;
+use dirt_core::Result;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Anon key published in the mock bootstrap manifest.
+pub const MOCK_ANON_KEY: &str = "mock-anon-key";
+/// Supabase access token returned by a successful mock sign-in.
+pub const MOCK_ACCESS_TOKEN: &str = "mock-access-token";
+/// Turso token returned by the mock sync token exchange.
+pub const MOCK_SYNC_TOKEN: &str = "mock-sync-token";
+/// Turso database URL returned by the mock sync token exchange.
+pub const MOCK_DATABASE_URL: &str = "libsql://dirt-mock.turso.io";
+/// Id of the user the mock backend signs in.
+pub const MOCK_USER_ID: &str = "00000000-0000-4000-8000-000000000001";
+
+/// One request the mock backend answered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    /// Value of the `Authorization` header, if any
+    pub authorization: Option<String>,
+}
+
+/// Local HTTP server answering the managed-mode endpoints clients call.
+///
+/// - `GET /v1/bootstrap`: manifest pointing auth and the API at this server
+/// - `POST /auth/v1/token`: a session for [`MOCK_ACCESS_TOKEN`]
+/// - `POST /v1/sync/token`: Turso credentials, only for [`MOCK_ACCESS_TOKEN`]
+pub struct MockBackend {
+    base_url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockBackend {
+    /// Bind a free local port and serve until the test runtime shuts down.
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let served_url = base_url.clone();
+        let recorded = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let base_url = served_url.clone();
+                let recorded = Arc::clone(&recorded);
+                tokio::spawn(async move {
+                    if let Err(error) = serve_connection(socket, &base_url, &recorded).await {
+                        eprintln!("mock backend connection failed: {error}");
+                    }
+                });
+            }
+        });
+
+        Ok(Self { base_url, requests })
+    }
+
+    /// Base URL, used as both the Supabase URL and the API base URL.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// URL of the bootstrap manifest, as stored in a client profile.
+    pub fn bootstrap_url(&self) -> String {
+        format!("{}/v1/bootstrap", self.base_url)
+    }
+
+    /// Requests answered so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .map(|requests| requests.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// A Dirt client whose calls into `dirt-core` the tests replay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Client {
+    Cli,
+    Desktop,
+    Mobile,
+}
+
+impl Client {
+    pub const ALL: [Self; 3] = [Self::Cli, Self::Desktop, Self::Mobile];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Cli => "cli",
+            Self::Desktop => "desktop",
+            Self::Mobile => "mobile",
+        }
+    }
+
+    /// Open the database at `path` the way the client does without sync;
+    /// a sync replica needs a real Turso database.
+    pub async fn open_database(self, path: &Path) -> Result<DatabaseService> {
+        match self {
+            Self::Cli | Self::Mobile => DatabaseService::open_path(path.to_path_buf(), None).await,
+            Self::Desktop => DatabaseService::open_local_path(path.to_path_buf()).await,
+        }
+    }
+
+    /// Render a full export the way the client does, leaving out private notes.
+    pub async fn export_notes(self, db: &DatabaseService, format: ExportFormat) -> Result<String> {
+        let notes = match self {
+            Self::Cli | Self::Desktop => db.list_exportable_notes(false).await?,
+            Self::Mobile => {
+                let notes = db.list_exportable_notes(true).await?;
+                db.export_privacy(false).await?.filter(notes)
+            }
+        };
+        Ok(render_notes_export(&notes, format)?)
+    }
+}
+
+async fn serve_connection(
+    mut socket: TcpStream,
+    base_url: &str,
+    recorded: &Mutex<Vec<RecordedRequest>>,
+) -> std::io::Result<()> {
+    let Some(request) = read_request(&mut socket).await? else {
+        return Ok(());
+    };
+    let (status, body) = respond(&request, base_url);
+    if let Ok(mut requests) = recorded.lock() {
+        requests.push(request);
+    }
+    let response = format!(
+        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    socket.write_all(response.as_bytes()).await?;
+    socket.shutdown().await
+}
+
+/// Read one request's head and body; `None` when the peer closed early.
+async fn read_request(socket: &mut TcpStream) -> std::io::Result<Option<RecordedRequest>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0_u8; 4096];
+    let head_end = loop {
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default().to_string();
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.trim().to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+
+    // Drain the body so the client never sees a reset mid-upload.
+    let mut body_read = buffer.len() - head_end;
+    while body_read < content_length {
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        body_read += read;
+    }
+
+    Ok(Some(RecordedRequest {
+        method,
+        path,
+        authorization,
+    }))
+}
+
+fn respond(request: &RecordedRequest, base_url: &str) -> (&'static str, String) {
+    let authorized =
+        request.authorization.as_deref() == Some(format!("Bearer {MOCK_ACCESS_TOKEN}").as_str());
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/v1/bootstrap") => (
+            "200 OK",
+            serde_json::json!({
+                "schema_version": 1,
+                "manifest_version": "mock",
+                "supabase_url": base_url,
+                "supabase_anon_key": MOCK_ANON_KEY,
+                "api_base_url": base_url,
+                "feature_flags": {
+                    "managed_sync": true,
+                    "managed_media": false,
+                },
+            })
+            .to_string(),
+        ),
+        ("POST", "/auth/v1/token") => (
+            "200 OK",
+            serde_json::json!({
+                "access_token": MOCK_ACCESS_TOKEN,
+                "refresh_token": "mock-refresh-token",
+                "expires_in": 3600,
+                "user": { "id": MOCK_USER_ID, "email": "mock@example.com" },
+            })
+            .to_string(),
+        ),
+        ("POST", "/v1/sync/token") if authorized => (
+            "200 OK",
+            serde_json::json!({
+                "auth_token": MOCK_SYNC_TOKEN,
+                "expires_in": 900,
+                "database_url": MOCK_DATABASE_URL,
+                "credential_epoch": 1,
+            })
+            .to_string(),
+        ),
+        ("POST", "/v1/sync/token") => (
+            "401 Unauthorized",
+            serde_json::json!({ "error": "invalid access token" }).to_string(),
+        ),
+        _ => (
+            "404 Not Found",
+            serde_json::json!({ "error": "not found" }).to_string(),
+        ),
+    }
+}
@@ -0,0 +1,154 @@
+//! Profile config → auth → token exchange → database → CRUD → export, once
+//! per client.
+
+use dirt_core::auth::SupabaseAuthService;
+use dirt_core::config::{resolve_bootstrap_config, BootstrapConfig};
+use dirt_core::db::SyncConfig;
+use dirt_core::export::ExportFormat;
+use dirt_core::sync::{SyncAuthError, TursoSyncAuthClient};
+use dirt_integration_tests::{
+    Client, MockBackend, MOCK_ACCESS_TOKEN, MOCK_DATABASE_URL, MOCK_SYNC_TOKEN, MOCK_USER_ID,
+};
+
+async fn run_client_flow(client: Client) {
+    let backend = MockBackend::start().await.expect("start mock backend");
+    let temp_dir = tempfile::tempdir().expect("temp dir");
+
+    // A profile stores only the bootstrap URL; everything else is fetched.
+    let profile = BootstrapConfig {
+        bootstrap_manifest_url: Some(backend.bootstrap_url()),
+        ..BootstrapConfig::default()
+    };
+    let config = resolve_bootstrap_config(profile)
+        .await
+        .expect("resolve bootstrap config");
+    assert_eq!(config.supabase_url.as_deref(), Some(backend.base_url()));
+    assert_eq!(
+        config.managed_api_base_url().as_deref(),
+        Some(backend.base_url())
+    );
+
+    let auth = SupabaseAuthService::new(
+        config.supabase_url.as_deref().expect("supabase url"),
+        config.supabase_anon_key.clone().expect("anon key"),
+    )
+    .expect("auth service");
+    let session = auth
+        .sign_in("mock@example.com", "correct horse")
+        .await
+        .expect("sign in");
+    assert_eq!(session.access_token, MOCK_ACCESS_TOKEN);
+    assert_eq!(session.user.id, MOCK_USER_ID);
+    assert!(!session.is_expired());
+
+    let sync_auth = TursoSyncAuthClient::new(
+        config
+            .turso_sync_token_endpoint
+            .clone()
+            .expect("sync endpoint"),
+    )
+    .expect("sync auth client");
+    let token = sync_auth
+        .exchange_token(&session.access_token)
+        .await
+        .expect("exchange token");
+    assert_eq!(token.token, MOCK_SYNC_TOKEN);
+    assert_eq!(token.credential_epoch, Some(1));
+    let sync_config = SyncConfig::new(token.database_url.clone(), token.token.clone());
+    assert_eq!(sync_config.url.as_deref(), Some(MOCK_DATABASE_URL));
+
+    let requests: Vec<_> = backend
+        .requests()
+        .into_iter()
+        .map(|request| (request.method, request.path))
+        .collect();
+    assert_eq!(
+        requests,
+        [
+            ("GET".to_string(), "/v1/bootstrap".to_string()),
+            ("POST".to_string(), "/auth/v1/token".to_string()),
+            ("POST".to_string(), "/v1/sync/token".to_string()),
+        ]
+    );
+
+    let db_path = temp_dir.path().join(format!("{}.db", client.name()));
+    let db = client.open_database(&db_path).await.expect("open database");
+    assert!(!db.is_read_only().await);
+
+    let groceries = db.create_note("Groceries #home").await.expect("create");
+    let diary = db.create_note("Diary #journal").await.expect("create");
+    let scratch = db.create_note("Scratch").await.expect("create");
+    db.update_note(&groceries.id, "Groceries: eggs, milk #home")
+        .await
+        .expect("update");
+    db.set_note_export_excluded(&diary.id, true)
+        .await
+        .expect("exclude from export");
+    db.delete_note(&scratch.id).await.expect("delete");
+
+    let updated = db
+        .get_note(&groceries.id)
+        .await
+        .expect("get")
+        .expect("note exists");
+    assert_eq!(updated.content, "Groceries: eggs, milk #home");
+    assert_eq!(db.list_notes(10, 0).await.expect("list").len(), 2);
+    assert_eq!(db.search_notes("milk", 10).await.expect("search").len(), 1);
+    assert_eq!(
+        db.list_tags().await.expect("tags"),
+        [("home".to_string(), 1), ("journal".to_string(), 1)]
+    );
+
+    let json = client
+        .export_notes(&db, ExportFormat::Json)
+        .await
+        .expect("json export");
+    let exported: serde_json::Value = serde_json::from_str(&json).expect("export is JSON");
+    let exported = exported.as_array().expect("export is a list");
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0]["id"], groceries.id.to_string());
+    assert_eq!(exported[0]["content"], "Groceries: eggs, milk #home");
+    assert_eq!(exported[0]["tags"], serde_json::json!(["home"]));
+
+    let markdown = client
+        .export_notes(&db, ExportFormat::Markdown)
+        .await
+        .expect("markdown export");
+    assert!(markdown.contains("Groceries: eggs, milk #home"));
+    assert!(!markdown.contains("Diary"));
+    assert!(!markdown.contains("Scratch"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cli_flow_from_profile_to_export() {
+    run_client_flow(Client::Cli).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn desktop_flow_from_profile_to_export() {
+    run_client_flow(Client::Desktop).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn mobile_flow_from_profile_to_export() {
+    run_client_flow(Client::Mobile).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn token_exchange_rejects_unknown_access_tokens() {
+    let backend = MockBackend::start().await.expect("start mock backend");
+    let sync_auth = TursoSyncAuthClient::new(format!("{}/v1/sync/token", backend.base_url()))
+        .expect("sync auth client");
+
+    let error = sync_auth
+        .exchange_token("someone-elses-token")
+        .await
+        .expect_err("exchange must fail");
+    assert!(matches!(error, SyncAuthError::Api(message) if message.contains("401")));
+
+    let requests = backend.requests();
+    assert_eq!(
+        requests[0].authorization.as_deref(),
+        Some("Bearer someone-elses-token")
+    );
+}
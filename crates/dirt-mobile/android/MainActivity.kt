@@ -6,8 +6,11 @@ import android.appwidget.AppWidgetProvider
 import android.content.Context
 import android.content.Intent
 import android.os.Bundle
+import android.provider.MediaStore
 import android.system.Os
 import android.widget.RemoteViews
+import androidx.core.content.FileProvider
+import java.io.File
 
 private const val ACTION_QUICK_CAPTURE = "dev.dioxus.main.action.QUICK_CAPTURE"
 private const val EXTRA_QUICK_CAPTURE_CONTENT = "dev.dioxus.main.extra.QUICK_CAPTURE_CONTENT"
@@ -15,6 +18,8 @@ private const val EXTRA_QUICK_CAPTURE_CONTENT = "dev.dioxus.main.extra.QUICK_CAP
 private const val ENV_QUICK_CAPTURE = "DIRT_QUICK_CAPTURE"
 private const val ENV_QUICK_CAPTURE_CONTENT = "DIRT_QUICK_CAPTURE_CONTENT"
 private const val ENV_SHARE_TEXT = "DIRT_SHARE_TEXT"
+private const val ENV_NOTE_LINK = "DIRT_NOTE_LINK"
+private const val NOTE_LINK_SCHEME = "dirt"
 
 object BuildConfig {
     val DEBUG: Boolean by lazy {
@@ -56,10 +61,50 @@ class MainActivity : WryActivity() {
             ""
         }
         val quickCaptureEnabled = action == ACTION_QUICK_CAPTURE
+        val noteLink = intent?.data
+            ?.takeIf { action == Intent.ACTION_VIEW && it.scheme == NOTE_LINK_SCHEME }
+            ?.toString()
+            .orEmpty()
 
         setEnvValue(ENV_SHARE_TEXT, sharedText)
         setEnvValue(ENV_QUICK_CAPTURE_CONTENT, quickCaptureText)
         setEnvValue(ENV_QUICK_CAPTURE, if (quickCaptureEnabled) "true" else "")
+        setEnvValue(ENV_NOTE_LINK, noteLink)
+    }
+
+    /** Called from Rust to open the camera for scanning a note QR code. */
+    @Suppress("unused")
+    fun openNoteScanner() {
+        runOnUiThread {
+            // Camera apps decode QR codes themselves and open the dirt:// link,
+            // which comes back through the VIEW intent filter.
+            startActivity(Intent(MediaStore.INTENT_ACTION_STILL_IMAGE_CAMERA))
+        }
+    }
+
+    /** Called from Rust to open the share sheet for a file in app storage. */
+    @Suppress("unused")
+    fun shareFile(path: String, mimeType: String, title: String) {
+        runOnUiThread {
+            val file = File(path)
+            val send = Intent(Intent.ACTION_SEND).apply {
+                type = mimeType
+                putExtra(Intent.EXTRA_SUBJECT, title)
+                try {
+                    val uri = FileProvider.getUriForFile(
+                        this@MainActivity,
+                        "$packageName.fileprovider",
+                        file,
+                    )
+                    putExtra(Intent.EXTRA_STREAM, uri)
+                    addFlags(Intent.FLAG_GRANT_READ_URI_PERMISSION)
+                } catch (_: IllegalArgumentException) {
+                    // Outside the provider paths: share the contents as text.
+                    putExtra(Intent.EXTRA_TEXT, file.readText())
+                }
+            }
+            startActivity(Intent.createChooser(send, title))
+        }
     }
 
     private fun setEnvValue(name: String, value: String) {
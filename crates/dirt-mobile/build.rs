@@ -16,6 +16,12 @@ const QUICK_CAPTURE_WIDGET_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
     android:widgetCategory="home_screen" />
 "#;
 
+const FILE_PROVIDER_PATHS_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<paths>
+    <files-path name="app_files" path="." />
+</paths>
+"#;
+
 #[derive(Debug, Default, Serialize)]
 struct MobileBootstrapConfig {
     bootstrap_manifest_url: Option<String>,
@@ -41,12 +47,12 @@ fn main() {
         println!("cargo:warning=failed to generate mobile bootstrap config: {error}");
     }
 
-    if let Err(error) = write_android_widget_resources() {
-        println!("cargo:warning=failed to generate Android widget metadata: {error}");
+    if let Err(error) = write_android_xml_resources() {
+        println!("cargo:warning=failed to generate Android XML resources: {error}");
     }
 }
 
-fn write_android_widget_resources() -> io::Result<()> {
+fn write_android_xml_resources() -> io::Result<()> {
     let Some(kotlin_out_dir) = env::var_os("WRY_ANDROID_KOTLIN_FILES_OUT_DIR") else {
         return Ok(());
     };
@@ -61,6 +67,7 @@ fn write_android_widget_resources() -> io::Result<()> {
         xml_dir.join("dirt_quick_capture_widget_info.xml"),
         QUICK_CAPTURE_WIDGET_XML,
     )?;
+    fs::write(xml_dir.join("dirt_file_paths.xml"), FILE_PROVIDER_PATHS_XML)?;
 
     Ok(())
 }
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -6,10 +7,31 @@ use dioxus_primitives::label::Label;
 use dioxus_primitives::scroll_area::{ScrollArea, ScrollDirection, ScrollType};
 use dioxus_primitives::separator::Separator;
 use dioxus_primitives::toast::{use_toast, ToastOptions};
+use dirt_core::changelog::CURRENT_VERSION;
+use dirt_core::config::EnvConfig;
+use dirt_core::connectivity::{Connectivity, ConnectivityMonitor};
+use dirt_core::db::{subscribe_migration_progress, MigrationProgress};
+use dirt_core::editing::{AutosaveState, AUTOSAVE_IDLE_MS};
+use dirt_core::media::{media_object_key, CancellationToken, MediaError, UploadProgress};
+use dirt_core::models::{
+    apply_tag_completion, extract_tasks, tag_query_at, toggle_task_line, AttachmentScanStatus,
+    NoteSource, Settings,
+};
+use dirt_core::notifications::{
+    new_conflict_count, notify, Notification, NotificationCategory, SyncFailureTracker,
+    SYNC_CONFLICT_NOTIFY_LIMIT,
+};
+use dirt_core::search::{attachment_search_text, SearchQuery};
+use dirt_core::sync::{SyncAction, SyncRotationEvent, SyncScheduler};
+use dirt_core::transcription::{
+    transcribe_audio, AudioClip, OpenAiTranscriber, TranscriptionConfig, TranscriptionError,
+};
+use dirt_core::transfer::{NoteTransfer, TransferReceipt};
 use dirt_core::{Attachment, AttachmentId, Note, NoteId, SyncConflict, SyncState};
 
 use crate::attachments::{
-    attachment_kind_label, build_attachment_preview, infer_attachment_mime_type, AttachmentPreview,
+    attachment_kind_label, build_attachment_preview, build_pdf_preview, compress_captured_photo,
+    infer_attachment_mime_type, AttachmentPreview, PHOTO_MAX_DIMENSION_CHOICES,
 };
 use crate::auth::{AuthConfigStatus, AuthSession, SignUpOutcome, SupabaseAuthService};
 use crate::bootstrap_config::{
@@ -20,6 +42,10 @@ use crate::config::{
     ResolvedSyncConfig, SecretStatus, SyncConfigSource,
 };
 use crate::data::MobileNoteStore;
+use crate::diagnostics::{
+    diagnostics_directory, push_sync_error, write_diagnostics_report, DiagnosticsReport, LogBuffer,
+    SyncErrorRecord,
+};
 use crate::export::{
     default_export_directory, export_notes_to_path, suggested_export_file_name, MobileExportFormat,
 };
@@ -34,6 +60,7 @@ use crate::voice_memo::{
     stop_voice_memo_recording, transition_voice_memo_state, VoiceMemoRecorderEvent,
     VoiceMemoRecorderState,
 };
+use crate::workspace::{active_workspace, add_workspace, switch_workspace, workspace_names};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum MobileView {
@@ -42,6 +69,17 @@ enum MobileView {
     Settings,
 }
 
+/// Why the editor draft is being saved.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DraftSave {
+    /// Idle or blur auto-save; silent unless it fails.
+    Auto,
+    /// The Save button.
+    Manual,
+    /// Leaving the editor; returns to the list once saved.
+    Leave,
+}
+
 type MobileSyncState = SyncState;
 
 struct MobileConfigDiagnostics {
@@ -124,16 +162,21 @@ pub(crate) fn AppShell() -> Element {
     let mut store = use_signal(|| None::<Arc<MobileNoteStore>>);
     let mut notes = use_signal(Vec::<Note>::new);
     let mut search_query = use_signal(String::new);
+    let mut attachment_note_ids = use_signal(HashSet::<NoteId>::new);
     let mut active_tag_filter = use_signal(|| None::<String>);
     let mut selected_note_id = use_signal(|| None::<NoteId>);
     let mut draft_content = use_signal(String::new);
-    let mut draft_dirty = use_signal(|| false);
-    let mut draft_edit_version = use_signal(|| 0u64);
+    let mut autosave = use_signal(AutosaveState::default);
+    let mut leave_after_save = use_signal(|| false);
+    let mut discard_confirm_open = use_signal(|| false);
+    let mut draft_source = use_signal(|| NoteSource::Mobile);
+    let mut tag_suggestions = use_signal(Vec::<String>::new);
     let mut view = use_signal(|| MobileView::List);
     let mut status_message = use_signal(|| None::<String>);
     let mut loading = use_signal(|| true);
     let mut saving = use_signal(|| false);
     let mut deleting = use_signal(|| false);
+    let connectivity = use_signal(ConnectivityMonitor::default);
     let mut sync_state = use_signal(|| MobileSyncState::Offline);
     let mut last_sync_at = use_signal(|| None::<i64>);
     let mut sync_scheduler_active = use_signal(|| false);
@@ -150,6 +193,8 @@ pub(crate) fn AppShell() -> Element {
     let mut attachments_error = use_signal(|| None::<String>);
     let mut attachment_uploading = use_signal(|| false);
     let mut attachment_upload_error = use_signal(|| None::<String>);
+    let attachment_upload_progress = use_signal(|| None::<UploadProgress>);
+    let attachment_upload_cancel = use_signal(|| None::<CancellationToken>);
     let mut deleting_attachment_id = use_signal(|| None::<AttachmentId>);
     let mut attachment_preview_open = use_signal(|| false);
     let mut attachment_preview_loading = use_signal(|| false);
@@ -162,22 +207,62 @@ pub(crate) fn AppShell() -> Element {
     let mut db_init_retry_version = use_signal(|| 0u64);
     let mut openai_api_key_input = use_signal(String::new);
     let mut openai_api_key_configured = use_signal(|| false);
+    let mut voice_memo_transcription_enabled = use_signal(|| false);
+    let mut notification_settings = use_signal(Settings::default);
     let mut active_sync_source = use_signal(|| SyncConfigSource::None);
     let mut auth_service = use_signal(|| None::<Arc<SupabaseAuthService>>);
     let mut auth_session = use_signal(|| None::<AuthSession>);
     let mut media_api_client = use_signal(|| None::<Arc<MediaApiClient>>);
+    let mut media_usage_summary = use_signal(|| None::<String>);
     let mut sync_auth_client = use_signal(|| None::<Arc<TursoSyncAuthClient>>);
-    let mut sync_token_expires_at = use_signal(|| None::<i64>);
+    let mut sync_credentials = use_signal(SyncScheduler::new);
+    let mut last_sync_rotation = use_signal(|| None::<SyncRotationEvent>);
+    let mut sync_retry_at = use_signal(|| None::<i64>);
     let mut auth_email_input = use_signal(String::new);
     let mut auth_password_input = use_signal(String::new);
     let mut auth_config_status = use_signal(|| None::<AuthConfigStatus>);
     let mut auth_loading = use_signal(|| false);
     let mut export_busy = use_signal(|| false);
+    let mut recent_sync_errors = use_signal(Vec::<SyncErrorRecord>::new);
+    let mut diagnostics_busy = use_signal(|| false);
+    let mut photo_max_dimension = use_signal(|| load_runtime_config().photo_max_dimension());
     let launch: Signal<LaunchIntent> = use_signal(crate::launch::detect_launch_intent_from_runtime);
     let mut launch_applied = use_signal(|| false);
+    let mut pending_note_link = use_signal(|| None::<String>);
+    let mut migration_progress = use_signal(|| None::<MigrationProgress>);
     let mut bootstrap_config_state = use_signal(load_bootstrap_config);
+    let mut update_required = use_signal(|| None::<String>);
+    let mut workspaces = use_signal(workspace_names);
+    let mut active_workspace_name = use_signal(|| active_workspace().name);
+    let mut workspace_name_input = use_signal(String::new);
     let toasts = use_toast();
 
+    // Background work raises notifications on this channel; they show as
+    // toasts while the app is open.
+    let notifications = use_signal(|| {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<Notification>();
+        spawn(async move {
+            while let Some(notification) = receiver.recv().await {
+                toasts.info(
+                    notification.summary,
+                    ToastOptions::new().description(notification.body),
+                );
+            }
+        });
+        sender
+    });
+
+    use_future(move || async move {
+        let mut receiver = subscribe_migration_progress();
+        loop {
+            let latest = *receiver.borrow_and_update();
+            migration_progress.set(latest.filter(|progress| !progress.is_finished()));
+            if receiver.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+
     let mut bootstrap_config_for_init = bootstrap_config_state;
     use_future(move || async move {
         let bootstrap_config_fallback = bootstrap_config_for_init();
@@ -185,6 +270,8 @@ pub(crate) fn AppShell() -> Element {
             bootstrap_config_fallback.bootstrap_manifest_url.is_some();
         let bootstrap_config = match resolve_bootstrap_config(bootstrap_config_fallback).await {
             Ok(config) => {
+                update_required.set(config.update_required(CURRENT_VERSION).map(str::to_string));
+                connectivity.peek().set_probe_url_from_bootstrap(&config);
                 bootstrap_config_for_init.set(config.clone());
                 Some(config)
             }
@@ -223,7 +310,7 @@ pub(crate) fn AppShell() -> Element {
         auth_config_status.set(None);
         media_api_client.set(None);
         sync_auth_client.set(None);
-        sync_token_expires_at.set(None);
+        sync_credentials.write().clear();
 
         if let Some(bootstrap_config) = bootstrap_config.as_ref() {
             match MediaApiClient::new_from_bootstrap(bootstrap_config) {
@@ -304,11 +391,14 @@ pub(crate) fn AppShell() -> Element {
             sync_auth_client.read().clone(),
             auth_session(),
             &mut status_message,
+            &mut sync_retry_at,
         )
         .await
         {
             Ok(Some(token)) => {
-                sync_token_expires_at.set(Some(token.expires_at));
+                sync_credentials
+                    .write()
+                    .credentials_opened(&token, chrono::Utc::now().timestamp());
                 resolved_sync_config = match resolve_sync_config() {
                     Ok(config) => Some(config),
                     Err(error) => {
@@ -346,6 +436,25 @@ pub(crate) fn AppShell() -> Element {
                 initialized = true;
 
                 store.set(Some(note_store.clone()));
+                match note_store.settings().await {
+                    Ok(settings) => {
+                        voice_memo_transcription_enabled
+                            .set(settings.voice_memo_transcription_enabled);
+                        notification_settings.set(settings);
+                    }
+                    Err(error) => {
+                        tracing::warn!("Failed to load mobile settings: {}", error);
+                    }
+                }
+                match note_store.pending_sync_changes().await {
+                    Ok(note_ids) => {
+                        pending_sync_count.set(note_ids.len());
+                        pending_sync_note_ids.set(note_ids);
+                    }
+                    Err(error) => {
+                        tracing::warn!("Failed to load pending sync changes: {}", error);
+                    }
+                }
 
                 if note_store.is_sync_enabled().await {
                     sync_scheduler_active.set(true);
@@ -369,6 +478,13 @@ pub(crate) fn AppShell() -> Element {
                         Err(error) => {
                             tracing::error!("Initial mobile sync failed: {}", error);
                             sync_state.set(MobileSyncState::Error);
+                            push_sync_error(
+                                &mut recent_sync_errors.write(),
+                                SyncErrorRecord {
+                                    occurred_at_ms: chrono::Utc::now().timestamp_millis(),
+                                    message: format!("Initial sync: {error}"),
+                                },
+                            );
                             consecutive_sync_failures.set(1);
                             status_message.set(Some(format!(
                                 "Initial sync failed; retrying in background: {error}"
@@ -407,13 +523,16 @@ pub(crate) fn AppShell() -> Element {
         }
 
         if initialized && !launch_applied() {
-            if let Some(shared_text) = launch.share_text {
+            if let Some(note_link) = launch.note_link {
+                pending_note_link.set(Some(note_link));
+                launch_applied.set(true);
+            } else if let Some(shared_text) = launch.share_text {
                 apply_share_intent(
                     shared_text,
                     &mut selected_note_id,
+                    &mut draft_source,
                     &mut draft_content,
-                    &mut draft_dirty,
-                    &mut draft_edit_version,
+                    &mut autosave,
                     &mut status_message,
                 );
                 view.set(MobileView::Editor);
@@ -422,8 +541,7 @@ pub(crate) fn AppShell() -> Element {
                 apply_quick_capture_launch(
                     launch.quick_capture.seed_text,
                     &mut draft_content,
-                    &mut draft_dirty,
-                    &mut draft_edit_version,
+                    &mut autosave,
                 );
                 selected_note_id.set(None);
                 status_message.set(Some("Quick capture ready".to_string()));
@@ -436,24 +554,66 @@ pub(crate) fn AppShell() -> Element {
     });
 
     use_future(move || async move {
+        let mut failure_tracker = SyncFailureTracker::default();
+        let mut last_conflict_id = None::<i64>;
         loop {
             tokio::time::sleep(Duration::from_secs(SYNC_INTERVAL_SECS)).await;
 
+            // While offline, only probe once per backoff interval instead of
+            // exchanging tokens and failing a sync every tick.
+            let monitor = connectivity.peek().clone();
+            if monitor.is_offline() {
+                let now = chrono::Utc::now().timestamp();
+                if !monitor.should_attempt(now) || monitor.probe(now).await == Connectivity::Offline
+                {
+                    continue;
+                }
+            }
+
             let managed_sync_enabled = sync_auth_client.read().is_some();
-            if managed_sync_enabled
-                && sync_token_expires_at()
-                    .map(|expires_at| expires_at <= chrono::Utc::now().timestamp() + 60)
-                    .unwrap_or(true)
-            {
+            let now = chrono::Utc::now().timestamp();
+            let credential_action = sync_credentials.peek().next_action(now);
+            if managed_sync_enabled && credential_action == SyncAction::CheckRotation {
+                sync_credentials.write().begin_rotation_check(now);
                 match refresh_managed_sync_token(
                     sync_auth_client.read().clone(),
                     auth_session(),
                     &mut status_message,
+                    &mut sync_retry_at,
                 )
                 .await
                 {
                     Ok(Some(token)) => {
-                        sync_token_expires_at.set(Some(token.expires_at));
+                        let rotation = sync_credentials.write().observe_exchange(token, now);
+                        if let Some(event) = rotation {
+                            tracing::info!("{event}; reinitializing mobile database");
+                            last_sync_rotation.set(Some(event));
+                            db_init_retry_version.set(db_init_retry_version() + 1);
+                            continue;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(error) => {
+                        tracing::warn!("Sync credential rotation check failed: {error}");
+                    }
+                }
+            }
+
+            let needs_credentials = matches!(credential_action, SyncAction::Reopen(_))
+                || !sync_credentials.peek().has_credentials();
+            if managed_sync_enabled && needs_credentials {
+                match refresh_managed_sync_token(
+                    sync_auth_client.read().clone(),
+                    auth_session(),
+                    &mut status_message,
+                    &mut sync_retry_at,
+                )
+                .await
+                {
+                    Ok(Some(token)) => {
+                        sync_credentials
+                            .write()
+                            .credentials_opened(&token, chrono::Utc::now().timestamp());
                         status_message.set(Some(
                             "Refreshed sync credentials. Reinitializing database connection..."
                                 .to_string(),
@@ -494,10 +654,37 @@ pub(crate) fn AppShell() -> Element {
             match note_store.sync().await {
                 Ok(()) => {
                     sync_state.set(MobileSyncState::Synced);
+                    monitor.record_online();
                     last_sync_at.set(Some(chrono::Utc::now().timestamp_millis()));
                     consecutive_sync_failures.set(0);
+                    failure_tracker.record_success();
                     clear_pending_sync_queue(&mut pending_sync_note_ids, &mut pending_sync_count);
 
+                    match note_store.list_conflicts(SYNC_CONFLICT_NOTIFY_LIMIT).await {
+                        Ok(conflicts) => {
+                            // The first check after launch only records a baseline.
+                            if let Some(last_seen) = last_conflict_id {
+                                let new_conflicts = new_conflict_count(&conflicts, last_seen);
+                                if new_conflicts > 0 {
+                                    notify(
+                                        &*notifications.peek(),
+                                        &notification_settings.peek(),
+                                        Notification::new(
+                                            NotificationCategory::SyncConflict,
+                                            "Sync conflicts resolved",
+                                            format!(
+                                                "{new_conflicts} note edits conflicted during sync. Review them in Settings."
+                                            ),
+                                        ),
+                                    );
+                                }
+                            }
+                            let newest = conflicts.iter().map(|conflict| conflict.id).max();
+                            last_conflict_id = newest.max(last_conflict_id).or(Some(0));
+                        }
+                        Err(error) => tracing::warn!("Failed to check sync conflicts: {error}"),
+                    }
+
                     if previous_sync_state == MobileSyncState::Error {
                         toasts.success(
                             "Sync restored".to_string(),
@@ -511,9 +698,38 @@ pub(crate) fn AppShell() -> Element {
                     }
                 }
                 Err(error) => {
+                    if monitor.probe(chrono::Utc::now().timestamp()).await == Connectivity::Offline
+                    {
+                        tracing::info!("Mobile sync paused while offline: {error}");
+                        sync_state.set(MobileSyncState::Offline);
+                        status_message.set(Some(
+                            "No network connection. Sync resumes when you're back online."
+                                .to_string(),
+                        ));
+                        continue;
+                    }
+
                     tracing::error!("Periodic mobile sync failed: {}", error);
                     sync_state.set(MobileSyncState::Error);
+                    push_sync_error(
+                        &mut recent_sync_errors.write(),
+                        SyncErrorRecord {
+                            occurred_at_ms: sync_attempt_timestamp,
+                            message: error.to_string(),
+                        },
+                    );
                     consecutive_sync_failures.set(consecutive_sync_failures().saturating_add(1));
+                    if failure_tracker.record_failure() {
+                        notify(
+                            &*notifications.peek(),
+                            &notification_settings.peek(),
+                            Notification::new(
+                                NotificationCategory::SyncFailure,
+                                "Dirt sync is failing",
+                                format!("Periodic mobile sync failed: {error}"),
+                            ),
+                        );
+                    }
 
                     if managed_sync_enabled && should_refresh_managed_token_after_sync_error(&error)
                     {
@@ -521,11 +737,14 @@ pub(crate) fn AppShell() -> Element {
                             sync_auth_client.read().clone(),
                             auth_session(),
                             &mut status_message,
+                            &mut sync_retry_at,
                         )
                         .await
                         {
                             Ok(Some(token)) => {
-                                sync_token_expires_at.set(Some(token.expires_at));
+                                sync_credentials
+                                    .write()
+                                    .credentials_opened(&token, chrono::Utc::now().timestamp());
                                 status_message.set(Some(
                                     "Recovered sync credentials after auth error. Reinitializing database connection..."
                                         .to_string(),
@@ -587,6 +806,28 @@ pub(crate) fn AppShell() -> Element {
         attachments_loading.set(false);
     });
 
+    // `has:attachment` searches need to know which notes have attachments.
+    use_future(move || async move {
+        let query = search_query();
+        let _attachment_refresh_version = attachment_refresh_version();
+        let _note_count = notes.read().len();
+        if !SearchQuery::parse_or_literal(&query).uses_attachments() {
+            return;
+        }
+        let Some(note_store) = store.read().clone() else {
+            return;
+        };
+        match note_store.list_all_attachments().await {
+            Ok(attachments) => attachment_note_ids.set(
+                attachments
+                    .iter()
+                    .map(|attachment| attachment.note_id)
+                    .collect(),
+            ),
+            Err(error) => tracing::warn!("Failed to load attachments for search: {error}"),
+        }
+    });
+
     use_future(move || async move {
         let current_view = view();
         let current_note_id = selected_note_id();
@@ -644,6 +885,83 @@ pub(crate) fn AppShell() -> Element {
         sync_conflicts_loading.set(false);
     });
 
+    use_future(move || async move {
+        if view() != MobileView::Settings {
+            return;
+        }
+        let media_api = media_api_client.read().clone();
+        let access_token = auth_session
+            .read()
+            .as_ref()
+            .map(|session| session.access_token.clone())
+            .filter(|token| !token.trim().is_empty());
+        let (Some(media_api), Some(access_token)) = (media_api, access_token) else {
+            media_usage_summary.set(None);
+            return;
+        };
+        let summary = match media_api.usage(&access_token).await {
+            Ok(usage) => usage.summary(),
+            Err(error) if error.is_unauthorized() => {
+                "Sign in again to see storage usage.".to_string()
+            }
+            Err(MediaError::NotFound(_)) => {
+                "This server does not report storage usage.".to_string()
+            }
+            Err(error) => format!("Storage usage is unavailable: {error}"),
+        };
+        media_usage_summary.set(Some(summary));
+    });
+
+    // Links scanned while the app is already running arrive via `onNewIntent`.
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            if let Some(link) = crate::launch::take_note_link_from_runtime() {
+                pending_note_link.set(Some(link));
+            }
+        }
+    });
+
+    use_future(move || async move {
+        let Some(link) = pending_note_link() else {
+            return;
+        };
+        let Some(note_store) = store.read().clone() else {
+            return;
+        };
+        pending_note_link.set(None);
+
+        let receipt = match link.parse::<NoteTransfer>() {
+            Ok(transfer) => note_store.receive_transfer(&transfer).await,
+            Err(error) => Err(error),
+        };
+        match receipt {
+            Ok(receipt) => {
+                let note = receipt.note().clone();
+                selected_note_id.set(Some(note.id));
+                draft_content.set(note.content);
+                autosave.write().mark_clean();
+                discard_confirm_open.set(false);
+                attachment_upload_error.set(None);
+                attachment_preview_open.set(false);
+                view.set(MobileView::Editor);
+                status_message.set(Some(transfer_receipt_message(&receipt).to_string()));
+                if let Ok(loaded_notes) = note_store.list_notes().await {
+                    notes.set(loaded_notes);
+                }
+            }
+            Err(error) => {
+                status_message.set(Some(format!("Could not open scanned note: {error}")));
+            }
+        }
+    });
+
+    let on_scan_note_code = move |_| {
+        if let Err(error) = crate::scanner::open_note_scanner() {
+            status_message.set(Some(format!("Failed to open the camera: {error}")));
+        }
+    };
+
     let on_new_note = move |_| {
         if store.read().is_none() {
             status_message.set(Some(
@@ -652,8 +970,10 @@ pub(crate) fn AppShell() -> Element {
             return;
         }
         selected_note_id.set(None);
+        draft_source.set(NoteSource::Mobile);
         draft_content.set(String::new());
-        draft_dirty.set(false);
+        autosave.write().mark_clean();
+        discard_confirm_open.set(false);
         status_message.set(None);
         attachment_upload_error.set(None);
         attachment_preview_open.set(false);
@@ -668,13 +988,16 @@ pub(crate) fn AppShell() -> Element {
         db_init_retry_version.set(db_init_retry_version() + 1);
     };
 
-    let on_back_to_list = move |_| {
+    let mut show_note_list = move || {
+        discard_confirm_open.set(false);
         view.set(MobileView::List);
         attachment_preview_open.set(false);
         attachment_preview_loading.set(false);
         attachment_preview_error.set(None);
     };
 
+    let on_show_notes = move |_| show_note_list();
+
     let on_open_settings = move |_| {
         view.set(MobileView::Settings);
         sync_conflicts_refresh_version.set(sync_conflicts_refresh_version().saturating_add(1));
@@ -720,6 +1043,46 @@ pub(crate) fn AppShell() -> Element {
             }
         };
 
+    let on_toggle_voice_memo_transcription = move |_| {
+        let Some(note_store) = store.read().clone() else {
+            return;
+        };
+        let enabled = !voice_memo_transcription_enabled();
+        spawn(async move {
+            match note_store
+                .set_voice_memo_transcription_enabled(enabled)
+                .await
+            {
+                Ok(()) => {
+                    voice_memo_transcription_enabled.set(enabled);
+                    status_message.set(Some(if enabled {
+                        "Voice memos will be transcribed.".to_string()
+                    } else {
+                        "Voice memo transcription turned off.".to_string()
+                    }));
+                }
+                Err(error) => status_message.set(Some(format!(
+                    "Failed to save voice memo transcription setting: {error}"
+                ))),
+            }
+        });
+    };
+
+    let on_toggle_notification = move |category: NotificationCategory| {
+        let Some(note_store) = store.read().clone() else {
+            return;
+        };
+        let enabled = !category.enabled_in(&notification_settings.read());
+        spawn(async move {
+            match note_store.set_notification_enabled(category, enabled).await {
+                Ok(settings) => notification_settings.set(settings),
+                Err(error) => status_message.set(Some(format!(
+                    "Failed to save notification setting: {error}"
+                ))),
+            }
+        });
+    };
+
     let on_auth_sign_in = move |_| {
         if auth_loading() {
             return;
@@ -757,11 +1120,14 @@ pub(crate) fn AppShell() -> Element {
                         sync_auth_client.read().clone(),
                         Some(session),
                         &mut status_message,
+                        &mut sync_retry_at,
                     )
                     .await
                     {
                         Ok(Some(token)) => {
-                            sync_token_expires_at.set(Some(token.expires_at));
+                            sync_credentials
+                                .write()
+                                .credentials_opened(&token, chrono::Utc::now().timestamp());
                             status_message.set(Some(
                                 "Signed in and refreshed sync credentials.".to_string(),
                             ));
@@ -824,11 +1190,14 @@ pub(crate) fn AppShell() -> Element {
                         sync_auth_client.read().clone(),
                         Some(session),
                         &mut status_message,
+                        &mut sync_retry_at,
                     )
                     .await
                     {
                         Ok(Some(token)) => {
-                            sync_token_expires_at.set(Some(token.expires_at));
+                            sync_credentials
+                                .write()
+                                .credentials_opened(&token, chrono::Utc::now().timestamp());
                             status_message.set(Some(
                                 "Signed up and refreshed sync credentials.".to_string(),
                             ));
@@ -876,7 +1245,7 @@ pub(crate) fn AppShell() -> Element {
                 Ok(()) => {
                     auth_session.set(None);
                     auth_password_input.set(String::new());
-                    sync_token_expires_at.set(None);
+                    sync_credentials.write().clear();
                     active_sync_source.set(SyncConfigSource::None);
                     if let Err(error) = clear_runtime_sync_credentials() {
                         status_message.set(Some(format!(
@@ -898,7 +1267,34 @@ pub(crate) fn AppShell() -> Element {
         });
     };
 
-    let on_export_json = move |_| {
+    let mut open_workspace = move |name: String| match switch_workspace(&name) {
+        Ok(workspace) => {
+            active_workspace_name.set(workspace.name.clone());
+            auth_session.set(None);
+            sync_credentials.write().clear();
+            status_message.set(Some(format!("Opening workspace {}...", workspace.name)));
+            db_init_retry_version.set(db_init_retry_version() + 1);
+        }
+        Err(error) => {
+            status_message.set(Some(format!("Switching workspace failed: {error}")));
+        }
+    };
+
+    let on_add_workspace = move |_| match add_workspace(&workspace_name_input()) {
+        Ok(workspace) => {
+            workspace_name_input.set(String::new());
+            workspaces.set(workspace_names());
+            status_message.set(Some(format!(
+                "Added workspace {}. Switch to it to sign in.",
+                workspace.name
+            )));
+        }
+        Err(error) => {
+            status_message.set(Some(format!("Adding workspace failed: {error}")));
+        }
+    };
+
+    let mut export_notes = move |format: MobileExportFormat| {
         if export_busy() {
             return;
         }
@@ -910,15 +1306,15 @@ pub(crate) fn AppShell() -> Element {
         };
 
         export_busy.set(true);
-        status_message.set(Some("Exporting notes as JSON...".to_string()));
+        status_message.set(Some(format!("Exporting notes as {}...", format.label())));
 
         let output_path = default_export_directory().join(suggested_export_file_name(
-            MobileExportFormat::Json,
+            format,
             chrono::Utc::now().timestamp_millis(),
         ));
 
         spawn(async move {
-            match export_notes_to_path(note_store, MobileExportFormat::Json, &output_path).await {
+            match export_notes_to_path(note_store, format, &output_path).await {
                 Ok(note_count) => {
                     status_message.set(Some(format!(
                         "Exported {note_count} notes to {}",
@@ -926,111 +1322,269 @@ pub(crate) fn AppShell() -> Element {
                     )));
                 }
                 Err(error) => {
-                    status_message.set(Some(format!("JSON export failed: {error}")));
+                    status_message.set(Some(format!("{} export failed: {error}", format.label())));
                 }
             }
             export_busy.set(false);
         });
     };
 
-    let on_export_markdown = move |_| {
-        if export_busy() {
+    let on_export_diagnostics = move |_| {
+        if diagnostics_busy() {
             return;
         }
-        let Some(note_store) = store.read().clone() else {
-            status_message.set(Some(
-                "Still initializing your notes. Please try again in a moment.".to_string(),
-            ));
-            return;
-        };
-
-        export_busy.set(true);
-        status_message.set(Some("Exporting notes as Markdown...".to_string()));
+        diagnostics_busy.set(true);
+        status_message.set(Some("Preparing diagnostics...".to_string()));
 
-        let output_path = default_export_directory().join(suggested_export_file_name(
-            MobileExportFormat::Markdown,
-            chrono::Utc::now().timestamp_millis(),
-        ));
+        let diagnostics = mobile_config_diagnostics(
+            active_sync_source(),
+            auth_config_status(),
+            &bootstrap_config_state(),
+        );
+        let provisioning = mobile_provisioning_status(
+            &diagnostics,
+            auth_session().as_ref(),
+            sync_state(),
+            sync_scheduler_active(),
+        );
+        let report = DiagnosticsReport {
+            generated_at_ms: chrono::Utc::now().timestamp_millis(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            config: diagnostics_config_lines(
+                &diagnostics,
+                &provisioning,
+                sync_state_label(sync_state(), last_sync_at())
+                    .trim_start_matches("Sync: ")
+                    .to_string(),
+            ),
+            sync_errors: recent_sync_errors(),
+            log_lines: LogBuffer::global().recent_lines(),
+        };
 
         spawn(async move {
-            match export_notes_to_path(note_store, MobileExportFormat::Markdown, &output_path).await
-            {
-                Ok(note_count) => {
-                    status_message.set(Some(format!(
-                        "Exported {note_count} notes to {}",
-                        output_path.display()
-                    )));
+            let written = tokio::task::spawn_blocking(move || {
+                write_diagnostics_report(&report, &diagnostics_directory())
+            })
+            .await;
+            match written {
+                Ok(Ok(path)) => {
+                    match crate::share::share_file(&path, "text/plain", "Dirt diagnostics") {
+                        Ok(()) => status_message.set(None),
+                        Err(error) => status_message.set(Some(format!(
+                            "Saved diagnostics to {}, but sharing failed: {error}",
+                            path.display()
+                        ))),
+                    }
+                }
+                Ok(Err(error)) => {
+                    status_message.set(Some(format!("Diagnostics export failed: {error}")));
                 }
                 Err(error) => {
-                    status_message.set(Some(format!("Markdown export failed: {error}")));
+                    status_message.set(Some(format!("Diagnostics export failed: {error}")));
                 }
             }
-            export_busy.set(false);
+            diagnostics_busy.set(false);
         });
     };
 
-    use_future(move || async move {
-        let current_revision = draft_edit_version();
-        if !draft_dirty() {
+    let mut save_draft = move |trigger: DraftSave| {
+        if !autosave().is_dirty() && trigger != DraftSave::Manual {
+            if trigger == DraftSave::Leave {
+                show_note_list();
+            }
             return;
         }
-
-        tokio::time::sleep(Duration::from_millis(650)).await;
-        if current_revision != draft_edit_version() || !draft_dirty() {
+        if saving() {
+            if trigger == DraftSave::Leave {
+                leave_after_save.set(true);
+            }
             return;
         }
 
         let Some(note_store) = store.read().clone() else {
+            if trigger != DraftSave::Auto {
+                status_message.set(Some(
+                    "Still initializing your notes. Please try again in a moment.".to_string(),
+                ));
+            }
             return;
         };
-        if saving() {
-            return;
-        }
 
         let content = draft_content().trim().to_string();
         if content.is_empty() {
+            match trigger {
+                DraftSave::Auto => {}
+                DraftSave::Manual => {
+                    status_message.set(Some("Note content cannot be empty".to_string()));
+                }
+                DraftSave::Leave if selected_note_id().is_none() => {
+                    // A blank new note has nothing worth keeping.
+                    autosave.write().mark_clean();
+                    show_note_list();
+                }
+                DraftSave::Leave => discard_confirm_open.set(true),
+            }
             return;
         }
 
+        let version = autosave().edit_version();
         let current_note_id = selected_note_id();
         saving.set(true);
+        if trigger == DraftSave::Manual {
+            status_message.set(Some("Saving note...".to_string()));
+        }
 
-        let save_result = if let Some(note_id) = current_note_id {
-            note_store.update_note(&note_id, &content).await
-        } else {
-            note_store.create_note(&content).await
-        };
+        spawn(async move {
+            let save_result = if let Some(note_id) = current_note_id {
+                note_store.update_note(&note_id, &content).await
+            } else {
+                note_store.create_note_from(&content, draft_source()).await
+            };
 
-        match save_result {
-            Ok(saved_note) => {
-                selected_note_id.set(Some(saved_note.id));
-                draft_content.set(saved_note.content);
-                if current_revision == draft_edit_version() {
-                    draft_dirty.set(false);
-                }
-                enqueue_pending_sync_change(
-                    saved_note.id,
-                    &mut pending_sync_note_ids,
-                    &mut pending_sync_count,
-                );
+            let saved = match save_result {
+                Ok(saved_note) => {
+                    selected_note_id.set(Some(saved_note.id));
+                    draft_source.set(NoteSource::Mobile);
+                    autosave.write().mark_saved(version);
+                    if trigger == DraftSave::Manual && !autosave().is_dirty() {
+                        draft_content.set(saved_note.content);
+                    }
+                    enqueue_pending_sync_change(
+                        saved_note.id,
+                        Some(note_store.clone()),
+                        &mut pending_sync_note_ids,
+                        &mut pending_sync_count,
+                    );
 
-                match note_store.list_notes().await {
-                    Ok(fresh_notes) => notes.set(fresh_notes),
-                    Err(error) => {
-                        status_message.set(Some(format!(
-                            "Saved, but failed to refresh note list: {error}"
-                        )));
+                    match note_store.list_notes().await {
+                        Ok(fresh_notes) => {
+                            notes.set(fresh_notes);
+                            if trigger == DraftSave::Manual {
+                                status_message.set(Some("Note saved".to_string()));
+                            }
+                        }
+                        Err(error) => {
+                            status_message.set(Some(format!(
+                                "Saved, but failed to refresh note list: {error}"
+                            )));
+                        }
                     }
+                    true
+                }
+                Err(error) => {
+                    status_message.set(Some(format!("Failed to save note: {error}")));
+                    false
+                }
+            };
+
+            saving.set(false);
+            if trigger == DraftSave::Leave || leave_after_save() {
+                leave_after_save.set(false);
+                if saved && !autosave().is_dirty() {
+                    show_note_list();
+                } else {
+                    discard_confirm_open.set(true);
                 }
             }
-            Err(error) => {
-                status_message.set(Some(format!("Failed to save note: {error}")));
-            }
+        });
+    };
+
+    // Debounced auto-save: save once the draft has been idle for a while.
+    use_effect(move || {
+        let state = autosave();
+        if !state.is_dirty() {
+            return;
         }
 
-        saving.set(false);
+        let version = state.edit_version();
+        spawn(async move {
+            tokio::time::sleep(Duration::from_millis(AUTOSAVE_IDLE_MS)).await;
+            if autosave().is_pending(version) {
+                save_draft(DraftSave::Auto);
+            }
+        });
     });
 
+    let on_back_to_list = move |_| save_draft(DraftSave::Leave);
+
+    let on_save_note = move |_| save_draft(DraftSave::Manual);
+
+    let on_request_discard = move |_| discard_confirm_open.set(true);
+
+    let on_keep_editing = move |_| discard_confirm_open.set(false);
+
+    let on_discard_changes = move |_| {
+        let saved_content = selected_note_id()
+            .and_then(|note_id| {
+                notes
+                    .read()
+                    .iter()
+                    .find(|note| note.id == note_id)
+                    .map(|note| note.content.clone())
+            })
+            .unwrap_or_default();
+        draft_content.set(saved_content);
+        draft_source.set(NoteSource::Mobile);
+        autosave.write().mark_clean();
+        tag_suggestions.write().clear();
+        status_message.set(Some("Changes discarded".to_string()));
+        show_note_list();
+    };
+
+    let mut refresh_tag_suggestions = move |content: String| {
+        let Some(query) = tag_query_at(&content, content.len()) else {
+            tag_suggestions.write().clear();
+            return;
+        };
+        let Some(note_store) = store.read().clone() else {
+            return;
+        };
+
+        spawn(async move {
+            match note_store.suggest_tags(&query.prefix).await {
+                Ok(tags) if draft_content() == content => {
+                    tag_suggestions.set(
+                        tags.into_iter()
+                            .filter(|name| *name != query.prefix)
+                            .collect(),
+                    );
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    tracing::warn!("Failed to load tag suggestions: {}", error);
+                }
+            }
+        });
+    };
+
+    let mut accept_tag_suggestion = move |tag: String| {
+        let content = draft_content();
+        let Some(query) = tag_query_at(&content, content.len()) else {
+            tag_suggestions.write().clear();
+            return;
+        };
+
+        let (updated, _) = apply_tag_completion(&content, &query, &tag);
+        draft_content.set(updated);
+        autosave.write().record_edit();
+        tag_suggestions.write().clear();
+
+        if let Some(note_store) = store.read().clone() {
+            spawn(async move {
+                if let Err(error) = note_store.record_tag_completion(&tag).await {
+                    tracing::warn!("Failed to record tag completion: {}", error);
+                }
+            });
+        }
+    };
+
+    let mut toggle_task = move |line_index: usize| {
+        if let Some(updated) = toggle_task_line(&draft_content(), line_index) {
+            draft_content.set(updated);
+            autosave.write().record_edit();
+        }
+    };
+
     let on_delete_note = move |_| {
         if deleting() {
             return;
@@ -1055,11 +1609,13 @@ pub(crate) fn AppShell() -> Element {
                 Ok(()) => {
                     enqueue_pending_sync_change(
                         note_id,
+                        Some(note_store.clone()),
                         &mut pending_sync_note_ids,
                         &mut pending_sync_count,
                     );
                     selected_note_id.set(None);
                     draft_content.set(String::new());
+                    autosave.write().mark_clean();
                     view.set(MobileView::List);
 
                     match note_store.list_notes().await {
@@ -1083,69 +1639,90 @@ pub(crate) fn AppShell() -> Element {
         });
     };
 
-    let on_save_note = move |_| {
-        if saving() {
+    let on_pick_attachment = move |event: Event<FormData>| {
+        if attachment_uploading() {
             return;
         }
 
+        let Some(note_id) = selected_note_id() else {
+            attachment_upload_error.set(Some(
+                "Save this note before uploading attachments.".to_string(),
+            ));
+            return;
+        };
         let Some(note_store) = store.read().clone() else {
-            status_message.set(Some(
+            attachment_upload_error.set(Some(
                 "Still initializing your notes. Please try again in a moment.".to_string(),
             ));
             return;
         };
 
-        let content = draft_content().trim().to_string();
-        if content.is_empty() {
-            status_message.set(Some("Note content cannot be empty".to_string()));
+        let mut files = event.files();
+        let Some(file) = files.pop() else {
+            return;
+        };
+
+        let file_name = file.name();
+        if file_name.trim().is_empty() {
+            attachment_upload_error.set(Some("Selected file has no name.".to_string()));
             return;
         }
+        let file_content_type = file.content_type();
 
-        let current_note_id = selected_note_id();
-        saving.set(true);
-        status_message.set(Some("Saving note...".to_string()));
+        attachment_upload_error.set(None);
+        attachment_uploading.set(true);
+        status_message.set(Some(format!("Uploading {file_name}...")));
 
+        let media_api = media_api_client.read().clone();
+        let auth_session_value = auth_session();
         spawn(async move {
-            let save_result = if let Some(note_id) = current_note_id {
-                note_store.update_note(&note_id, &content).await
-            } else {
-                note_store.create_note(&content).await
+            let file_bytes = match file.read_bytes().await {
+                Ok(bytes) => bytes.to_vec(),
+                Err(error) => {
+                    attachment_upload_error
+                        .set(Some(format!("Failed to read selected file: {error}")));
+                    attachment_uploading.set(false);
+                    return;
+                }
             };
 
-            match save_result {
-                Ok(saved_note) => {
-                    selected_note_id.set(Some(saved_note.id));
-                    draft_content.set(saved_note.content);
-                    draft_dirty.set(false);
-                    draft_edit_version.set(draft_edit_version().saturating_add(1));
+            match upload_attachment_to_r2(
+                note_store,
+                note_id,
+                file_name,
+                file_content_type,
+                file_bytes,
+                UploadTarget {
+                    media_api,
+                    auth_session: auth_session_value,
+                    connectivity: connectivity.peek().clone(),
+                    progress: attachment_upload_progress,
+                    cancel: attachment_upload_cancel,
+                },
+            )
+            .await
+            {
+                Ok(()) => {
                     enqueue_pending_sync_change(
-                        saved_note.id,
+                        note_id,
+                        store.peek().clone(),
                         &mut pending_sync_note_ids,
                         &mut pending_sync_count,
                     );
-
-                    match note_store.list_notes().await {
-                        Ok(fresh_notes) => {
-                            notes.set(fresh_notes);
-                            status_message.set(Some("Note saved".to_string()));
-                        }
-                        Err(error) => {
-                            status_message.set(Some(format!(
-                                "Saved, but failed to refresh note list: {error}"
-                            )));
-                        }
-                    }
+                    attachment_refresh_version.set(attachment_refresh_version() + 1);
+                    status_message.set(Some("Attachment uploaded.".to_string()));
                 }
                 Err(error) => {
-                    status_message.set(Some(format!("Failed to save note: {error}")));
+                    attachment_upload_error.set(Some(error.clone()));
+                    status_message.set(Some(error));
                 }
             }
 
-            saving.set(false);
+            attachment_uploading.set(false);
         });
     };
 
-    let on_pick_attachment = move |event: Event<FormData>| {
+    let on_capture_photo = move |event: Event<FormData>| {
         if attachment_uploading() {
             return;
         }
@@ -1168,49 +1745,68 @@ pub(crate) fn AppShell() -> Element {
             return;
         };
 
-        let file_name = file.name();
-        if file_name.trim().is_empty() {
-            attachment_upload_error.set(Some("Selected file has no name.".to_string()));
-            return;
-        }
-        let file_content_type = file.content_type();
-
         attachment_upload_error.set(None);
         attachment_uploading.set(true);
-        status_message.set(Some(format!("Uploading {file_name}...")));
+        status_message.set(Some("Processing photo...".to_string()));
 
+        let max_dimension = photo_max_dimension();
         let media_api = media_api_client.read().clone();
         let auth_session_value = auth_session();
         spawn(async move {
             let file_bytes = match file.read_bytes().await {
                 Ok(bytes) => bytes.to_vec(),
                 Err(error) => {
-                    attachment_upload_error
-                        .set(Some(format!("Failed to read selected file: {error}")));
+                    attachment_upload_error.set(Some(format!("Failed to read photo: {error}")));
                     attachment_uploading.set(false);
                     return;
                 }
             };
 
+            let captured_at_ms = chrono::Utc::now().timestamp_millis();
+            let photo = match tokio::task::spawn_blocking(move || {
+                compress_captured_photo(&file_bytes, max_dimension, captured_at_ms)
+            })
+            .await
+            {
+                Ok(result) => result,
+                Err(error) => Err(format!("Failed to process photo: {error}")),
+            };
+            let photo = match photo {
+                Ok(photo) => photo,
+                Err(error) => {
+                    attachment_upload_error.set(Some(error.clone()));
+                    status_message.set(Some(error));
+                    attachment_uploading.set(false);
+                    return;
+                }
+            };
+
+            status_message.set(Some(format!("Uploading {}...", photo.file_name)));
             match upload_attachment_to_r2(
                 note_store,
                 note_id,
-                file_name,
-                file_content_type,
-                file_bytes,
-                media_api,
-                auth_session_value,
+                photo.file_name,
+                Some(photo.mime_type.to_string()),
+                photo.bytes,
+                UploadTarget {
+                    media_api,
+                    auth_session: auth_session_value,
+                    connectivity: connectivity.peek().clone(),
+                    progress: attachment_upload_progress,
+                    cancel: attachment_upload_cancel,
+                },
             )
             .await
             {
                 Ok(()) => {
                     enqueue_pending_sync_change(
                         note_id,
+                        store.peek().clone(),
                         &mut pending_sync_note_ids,
                         &mut pending_sync_count,
                     );
                     attachment_refresh_version.set(attachment_refresh_version() + 1);
-                    status_message.set(Some("Attachment uploaded.".to_string()));
+                    status_message.set(Some("Photo attached.".to_string()));
                 }
                 Err(error) => {
                     attachment_upload_error.set(Some(error.clone()));
@@ -1222,6 +1818,17 @@ pub(crate) fn AppShell() -> Element {
         });
     };
 
+    let mut on_select_photo_max_dimension = move |dimension: u32| {
+        let mut runtime_config = load_runtime_config();
+        runtime_config.photo_max_dimension = Some(dimension);
+        match save_runtime_config(&runtime_config) {
+            Ok(()) => photo_max_dimension.set(dimension),
+            Err(error) => {
+                status_message.set(Some(format!("Failed to save photo size: {error}")));
+            }
+        }
+    };
+
     let on_start_voice_memo = move |_| {
         attachment_upload_error.set(None);
 
@@ -1292,17 +1899,24 @@ pub(crate) fn AppShell() -> Element {
 
         let media_api = media_api_client.read().clone();
         let auth_session_value = auth_session();
+        let transcribe = voice_memo_transcription_enabled() && openai_api_key_configured();
         spawn(async move {
             match stop_voice_memo_recording().await {
                 Ok(recorded) => {
+                    let audio_bytes = transcribe.then(|| recorded.bytes.clone());
                     let upload_result = upload_attachment_to_r2(
                         note_store,
                         note_id,
                         recorded.file_name.clone(),
                         Some(recorded.mime_type.clone()),
                         recorded.bytes,
-                        media_api,
-                        auth_session_value,
+                        UploadTarget {
+                            media_api,
+                            auth_session: auth_session_value,
+                            connectivity: connectivity.peek().clone(),
+                            progress: attachment_upload_progress,
+                            cancel: attachment_upload_cancel,
+                        },
                     )
                     .await;
 
@@ -1311,6 +1925,7 @@ pub(crate) fn AppShell() -> Element {
                         Ok(()) => {
                             enqueue_pending_sync_change(
                                 note_id,
+                                store.peek().clone(),
                                 &mut pending_sync_note_ids,
                                 &mut pending_sync_count,
                             );
@@ -1322,6 +1937,60 @@ pub(crate) fn AppShell() -> Element {
                                 VoiceMemoRecorderEvent::StopSucceeded,
                             ));
                             voice_memo_started_at.set(None);
+
+                            let Some(audio_bytes) = audio_bytes else {
+                                return;
+                            };
+                            let Some(note_store) = store.peek().clone() else {
+                                return;
+                            };
+                            status_message
+                                .set(Some("Voice memo attached. Transcribing...".to_string()));
+                            let latest_content = if selected_note_id() == Some(note_id) {
+                                Some(draft_content())
+                            } else {
+                                None
+                            };
+                            let transcribed = transcribe_voice_memo_into_note(
+                                &note_store,
+                                note_id,
+                                &recorded.file_name,
+                                &recorded.mime_type,
+                                &audio_bytes,
+                                latest_content.clone(),
+                            )
+                            .await;
+                            match transcribed {
+                                Ok(Some(note)) => {
+                                    if latest_content.is_some()
+                                        && selected_note_id() == Some(note_id)
+                                        && latest_content == Some(draft_content())
+                                    {
+                                        draft_content.set(note.content);
+                                        autosave.write().mark_clean();
+                                    }
+                                    enqueue_pending_sync_change(
+                                        note_id,
+                                        Some(note_store.clone()),
+                                        &mut pending_sync_note_ids,
+                                        &mut pending_sync_count,
+                                    );
+                                    if let Ok(fresh_notes) = note_store.list_notes().await {
+                                        notes.set(fresh_notes);
+                                    }
+                                    status_message.set(Some("Voice memo transcribed.".to_string()));
+                                }
+                                Ok(None) => {
+                                    status_message.set(Some(
+                                        "Voice memo attached; no speech was transcribed."
+                                            .to_string(),
+                                    ));
+                                }
+                                Err(error) => {
+                                    tracing::warn!("Voice memo transcription failed: {}", error);
+                                    status_message.set(Some(error));
+                                }
+                            }
                         }
                         Err(error) => {
                             attachment_upload_error.set(Some(error.clone()));
@@ -1378,6 +2047,22 @@ pub(crate) fn AppShell() -> Element {
         attachment_preview_content.set(AttachmentPreview::None);
     };
 
+    let on_attachment_pdf_page = move |page_index: u16| {
+        let AttachmentPreview::PdfPage { bytes, .. } = attachment_preview_content() else {
+            return;
+        };
+        attachment_preview_loading.set(true);
+        spawn(async move {
+            match tokio::task::spawn_blocking(move || build_pdf_preview(bytes, page_index)).await {
+                Ok(preview) => attachment_preview_content.set(preview),
+                Err(error) => {
+                    attachment_preview_error.set(Some(format!("Failed to render page: {error}")));
+                }
+            }
+            attachment_preview_loading.set(false);
+        });
+    };
+
     let bootstrap_config = bootstrap_config_state();
     let diagnostics = mobile_config_diagnostics(
         active_sync_source(),
@@ -1403,6 +2088,12 @@ pub(crate) fn AppShell() -> Element {
     let last_sync_attempt_text = last_sync_attempt_at()
         .map(relative_time)
         .unwrap_or_else(|| "never".to_string());
+    let sync_rotation_text = last_sync_rotation().map(|event| {
+        format!(
+            "{event}, {}",
+            relative_time(event.observed_at.saturating_mul(1000))
+        )
+    });
     let sync_scheduler_text = if sync_scheduler_active() {
         format!("active (every {SYNC_INTERVAL_SECS}s)")
     } else {
@@ -1431,6 +2122,7 @@ pub(crate) fn AppShell() -> Element {
         &all_notes,
         &search_query_value,
         active_tag_filter_value.as_deref(),
+        &attachment_note_ids.read(),
     );
     let total_note_count = all_notes.len();
     let filtered_note_count = filtered_notes.len();
@@ -1494,7 +2186,7 @@ pub(crate) fn AppShell() -> Element {
                             type: "button",
                             variant: ButtonVariant::Outline,
                             style: "padding: 6px 10px; font-size: 12px;",
-                            onclick: on_back_to_list,
+                            onclick: on_show_notes,
                             "Notes"
                         }
                     } else {
@@ -1514,6 +2206,18 @@ pub(crate) fn AppShell() -> Element {
                 style: "height: 1px; background: #e5e7eb;",
             }
 
+            if let Some(minimum) = update_required() {
+                p {
+                    role: "alert",
+                    style: "margin: 0; padding: 10px 16px; font-size: 13px; color: #b91c1c;",
+                    "Update required: the Dirt service needs version {minimum} or newer (this is {CURRENT_VERSION})."
+                }
+                Separator {
+                    decorative: true,
+                    style: "height: 1px; background: #e5e7eb;",
+                }
+            }
+
             if let Some(message) = status_message() {
                 p {
                     style: "margin: 0; padding: 10px 16px; font-size: 13px; color: #374151;",
@@ -1534,7 +2238,28 @@ pub(crate) fn AppShell() -> Element {
                         justify-content: center;
                         color: #6b7280;
                     ",
-                    "Loading notes..."
+                    if let Some(progress) = migration_progress() {
+                        div {
+                            style: "width: 70%; display: flex; flex-direction: column; gap: 8px; font-size: 14px;",
+                            span { "{progress.description()}" }
+                            div {
+                                style: "height: 6px; border-radius: 3px; background: #e5e7eb; overflow: hidden;",
+                                div {
+                                    style: "width: {progress.percent()}%; height: 100%; background: #2563eb;",
+                                }
+                            }
+                            span {
+                                style: "font-size: 12px;",
+                                if let Some((done, total)) = progress.rows {
+                                    "Updating database: {done} / {total} notes"
+                                } else {
+                                    "Updating database: {progress.percent()}%"
+                                }
+                            }
+                        }
+                    } else {
+                        "Loading notes..."
+                    }
                 }
             } else if view() == MobileView::List {
                 {include!("views/list.rs")}
@@ -1550,87 +2275,236 @@ pub(crate) fn AppShell() -> Element {
 fn apply_quick_capture_launch(
     seed_text: Option<String>,
     draft_content: &mut Signal<String>,
-    draft_dirty: &mut Signal<bool>,
-    draft_edit_version: &mut Signal<u64>,
+    autosave: &mut Signal<AutosaveState>,
 ) {
     draft_content.set(seed_text.unwrap_or_default());
-    draft_dirty.set(true);
-    draft_edit_version.set(draft_edit_version().saturating_add(1));
+    autosave.write().record_edit();
+}
+
+const fn transfer_receipt_message(receipt: &TransferReceipt) -> &'static str {
+    match receipt {
+        TransferReceipt::Opened(_) => "Opened note sent from another device",
+        TransferReceipt::Imported(_) => "Imported note from another device",
+    }
 }
 
 fn apply_share_intent(
     shared_text: String,
     selected_note_id: &mut Signal<Option<NoteId>>,
+    draft_source: &mut Signal<NoteSource>,
     draft_content: &mut Signal<String>,
-    draft_dirty: &mut Signal<bool>,
-    draft_edit_version: &mut Signal<u64>,
+    autosave: &mut Signal<AutosaveState>,
     status_message: &mut Signal<Option<String>>,
 ) {
     selected_note_id.set(None);
+    draft_source.set(NoteSource::MobileShare);
     draft_content.set(shared_text);
-    draft_dirty.set(true);
-    draft_edit_version.set(draft_edit_version().saturating_add(1));
+    autosave.write().record_edit();
     status_message.set(Some("Shared text ready".to_string()));
 }
 
+/// Where an attachment upload goes, and the signals that show its progress
+/// and let it be cancelled
+struct UploadTarget {
+    media_api: Option<Arc<MediaApiClient>>,
+    auth_session: Option<AuthSession>,
+    connectivity: ConnectivityMonitor,
+    progress: Signal<Option<UploadProgress>>,
+    cancel: Signal<Option<CancellationToken>>,
+}
+
+/// Transcribe an attached voice memo with the stored `OpenAI` key and append
+/// the transcript to its note.
+async fn transcribe_voice_memo_into_note(
+    note_store: &MobileNoteStore,
+    note_id: NoteId,
+    file_name: &str,
+    mime_type: &str,
+    audio_bytes: &[u8],
+    latest_content: Option<String>,
+) -> Result<Option<Note>, String> {
+    let api_key = secret_store::read_secret(secret_store::SECRET_OPENAI_API_KEY)
+        .map_err(|error| format!("Failed to read OpenAI API key: {error}"))?;
+    let config = TranscriptionConfig::resolve(api_key, &EnvConfig)
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| TranscriptionError::NotConfigured.to_string())?;
+    let transcriber = OpenAiTranscriber::new(config).map_err(|error| error.to_string())?;
+    let clip = AudioClip {
+        file_name,
+        mime_type,
+        bytes: audio_bytes,
+    };
+    let transcript = transcribe_audio(&transcriber, &clip)
+        .await
+        .map_err(|error| format!("Voice memo attached, but transcription failed: {error}"))?;
+    note_store
+        .append_voice_memo_transcript(&note_id, file_name, &transcript, latest_content)
+        .await
+        .map_err(|error| {
+            format!("Voice memo attached, but the transcript could not be saved: {error}")
+        })
+}
+
 async fn upload_attachment_to_r2(
     note_store: Arc<MobileNoteStore>,
     note_id: NoteId,
     file_name: String,
     content_type: Option<String>,
     file_bytes: Vec<u8>,
-    media_api: Option<Arc<MediaApiClient>>,
-    auth_session: Option<AuthSession>,
+    target: UploadTarget,
 ) -> Result<(), String> {
+    let UploadTarget {
+        media_api,
+        auth_session,
+        connectivity,
+        mut progress,
+        cancel: mut cancel_signal,
+    } = target;
     let media_api = media_api.ok_or_else(|| {
         "Managed media API is not configured in bootstrap. Set DIRT_API_BASE_URL.".to_string()
     })?;
     let access_token = require_media_access_token(auth_session)?;
-    let object_key = build_media_object_key(&note_id, &file_name);
+    if !connectivity
+        .ensure_online(chrono::Utc::now().timestamp())
+        .await
+    {
+        return Err("You're offline. Try the upload again once you're connected.".to_string());
+    }
+    let object_key = media_object_key(&note_id, &file_name);
     let mime_type = infer_attachment_mime_type(content_type.as_deref(), &file_name);
-
-    media_api
-        .upload(&access_token, &object_key, &mime_type, file_bytes.as_ref())
+    let file_bytes = note_store
+        .prepare_attachment_bytes(&file_bytes, &mime_type)
+        .await
+        .map_err(|error| format!("Failed to prepare attachment: {error}"))?;
+    let size_bytes = file_size_i64(file_bytes.len());
+    let search_text = attachment_search_text(&mime_type, &file_bytes);
+    let file_bytes = note_store
+        .encrypt_attachment_bytes(file_bytes)
         .await
-        .map_err(|error| format!("Failed to upload attachment via media API: {error}"))?;
+        .map_err(|error| format!("Failed to encrypt attachment: {error}"))?;
 
-    note_store
-        .create_attachment(
-            &note_id,
-            &file_name,
-            &mime_type,
-            file_size_i64(file_bytes.len()),
+    let cancel = CancellationToken::new();
+    cancel_signal.set(Some(cancel.clone()));
+    let uploaded = media_api
+        .upload_with_progress(
+            &access_token,
             &object_key,
+            &mime_type,
+            file_bytes.as_ref(),
+            &cancel,
+            |update| progress.set(Some(update)),
         )
+        .await;
+    cancel_signal.set(None);
+    progress.set(None);
+    if let Err(error) = &uploaded {
+        if matches!(error.cause(), MediaError::Network(_)) {
+            connectivity.probe(chrono::Utc::now().timestamp()).await;
+        }
+    }
+    uploaded.map_err(|error| {
+        if error.is_cancelled() {
+            format!("Upload of '{file_name}' cancelled.")
+        } else {
+            media_error_message("Failed to upload attachment via media API", &error)
+        }
+    })?;
+
+    let attachment = note_store
+        .create_attachment(&note_id, &file_name, &mime_type, size_bytes, &object_key)
+        .await
+        .map_err(|error| format!("Failed to save attachment metadata: {error}"))?;
+    if let Err(error) = note_store
+        .set_attachment_text(&attachment.id, search_text.as_deref())
+        .await
+    {
+        tracing::warn!("Attachment text not indexed for search: {error}");
+    }
+
+    // Older backends have no commit endpoint; the attachment then stays unscanned.
+    let result = match media_api.commit(&access_token, &object_key).await {
+        Ok(result) => result,
+        Err(MediaError::NotFound(_)) => return Ok(()),
+        Err(error) => {
+            tracing::warn!("Attachment upload commit failed; leaving unscanned: {error}");
+            return Ok(());
+        }
+    };
+    if let Err(error) = note_store
+        .set_attachment_scan_status(&attachment.id, result.scan_status)
         .await
-        .map(|_| ())
-        .map_err(|error| format!("Failed to save attachment metadata: {error}"))
+    {
+        tracing::warn!("Failed to record attachment scan status: {error}");
+    }
+    if result.scan_status == AttachmentScanStatus::Quarantined {
+        return Err(quarantine_message(&file_name, result.reason.as_deref()));
+    }
+    Ok(())
+}
+
+/// User-facing message for a failed media operation, explaining failures the
+/// user can act on (an expired sign-in, a full quota, a dropped connection).
+fn media_error_message(failed_to: &str, error: &MediaError) -> String {
+    match error.cause() {
+        MediaError::Unauthorized(_) => {
+            format!("{failed_to}: your session has expired. Sign in again and retry.")
+        }
+        MediaError::NotFound(_) => format!("{failed_to}: the file is no longer in cloud storage."),
+        MediaError::Quota(_) => {
+            format!("{failed_to}: your attachment storage is full. See Settings for usage.")
+        }
+        _ if error.is_transient() => format!("{failed_to}, try again shortly: {error}"),
+        _ => format!("{failed_to}: {error}"),
+    }
+}
+
+fn quarantine_message(file_name: &str, reason: Option<&str>) -> String {
+    match reason.map(str::trim).filter(|reason| !reason.is_empty()) {
+        Some(reason) => format!("'{file_name}' was quarantined by the content scanner: {reason}"),
+        None => format!("'{file_name}' was quarantined by the content scanner."),
+    }
 }
 
 async fn load_attachment_preview_from_r2(
     attachment: &Attachment,
+    note_store: Option<Arc<MobileNoteStore>>,
     media_api: Option<Arc<MediaApiClient>>,
     auth_session: Option<AuthSession>,
 ) -> Result<AttachmentPreview, String> {
+    if !attachment.scan_status.is_downloadable() {
+        return Err(quarantine_message(&attachment.filename, None));
+    }
     let media_api = media_api.ok_or_else(|| {
         "Managed media API is not configured in bootstrap. Set DIRT_API_BASE_URL.".to_string()
     })?;
     let access_token = require_media_access_token(auth_session)?;
+    let cache = note_store
+        .as_deref()
+        .and_then(MobileNoteStore::attachment_cache);
     let (bytes, downloaded_content_type) = media_api
-        .download(&access_token, &attachment.r2_key)
+        .download_cached(cache, &access_token, &attachment.r2_key)
         .await
-        .map_err(|error| format!("Failed to download attachment via media API: {error}"))?;
+        .map_err(|error| {
+            media_error_message("Failed to download attachment via media API", &error)
+        })?;
+    let bytes = match note_store {
+        Some(note_store) => note_store
+            .decrypt_attachment_bytes(bytes)
+            .await
+            .map_err(|error| format!("Failed to decrypt attachment: {error}"))?,
+        None => bytes,
+    };
 
     let content_type_hint = downloaded_content_type
         .as_deref()
         .or(Some(attachment.mime_type.as_str()));
     let mime_type = infer_attachment_mime_type(content_type_hint, &attachment.filename);
 
-    Ok(build_attachment_preview(
-        &attachment.filename,
-        &mime_type,
-        &bytes,
-    ))
+    // PDF pages are rasterized by pdfium, which blocks.
+    let file_name = attachment.filename.clone();
+    tokio::task::spawn_blocking(move || build_attachment_preview(&file_name, &mime_type, &bytes))
+        .await
+        .map_err(|error| format!("Failed to build attachment preview: {error}"))
 }
 
 async fn delete_attachment_object_from_r2(
@@ -1655,50 +2529,11 @@ fn require_media_access_token(auth_session: Option<AuthSession>) -> Result<Strin
         .ok_or_else(|| "Sign in is required for managed attachment operations.".to_string())
 }
 
-fn build_media_object_key(note_id: &NoteId, file_name: &str) -> String {
-    let stem = file_name
-        .trim()
-        .rsplit_once('.')
-        .map_or(file_name.trim(), |(left, _)| left);
-    let ext = file_name
-        .trim()
-        .rsplit_once('.')
-        .map_or("", |(_, right)| right);
-
-    let safe_stem = sanitize_media_token(stem);
-    let safe_stem = if safe_stem.is_empty() {
-        "file".to_string()
-    } else {
-        safe_stem
-    };
-    let safe_ext = sanitize_media_token(ext);
-    let safe_name = if safe_ext.is_empty() {
-        safe_stem
-    } else {
-        format!("{safe_stem}.{safe_ext}")
-    };
-    let now = chrono::Utc::now().timestamp_millis();
-    format!("notes/{note_id}/{now}-{safe_name}")
-}
-
-fn sanitize_media_token(input: &str) -> String {
-    let mut out = String::with_capacity(input.len());
-    let mut last_dash = false;
-
-    for ch in input.chars().flat_map(char::to_lowercase) {
-        if ch.is_ascii_alphanumeric() {
-            out.push(ch);
-            last_dash = false;
-        } else if !last_dash {
-            out.push('-');
-            last_dash = true;
-        }
-    }
-
-    out.trim_matches('-').to_string()
-}
-
-fn render_attachment_preview(preview: AttachmentPreview, preview_title: &str) -> Element {
+fn render_attachment_preview(
+    preview: AttachmentPreview,
+    preview_title: &str,
+    on_pdf_page: EventHandler<u16>,
+) -> Element {
     match preview {
         AttachmentPreview::None => rsx! {
             p {
@@ -1744,6 +2579,46 @@ fn render_attachment_preview(preview: AttachmentPreview, preview_title: &str) ->
                 }
             }
         },
+        AttachmentPreview::PdfPage {
+            page_index,
+            page_count,
+            data_uri,
+            ..
+        } => {
+            let page_number = page_index + 1;
+            rsx! {
+                div {
+                    style: "display: flex; flex-direction: column; gap: 8px;",
+                    img {
+                        src: "{data_uri}",
+                        alt: "{preview_title}, page {page_number}",
+                        style: "display: block; max-width: 100%; max-height: 56vh; margin: 0 auto; border-radius: 8px; background: #ffffff;",
+                    }
+                    if page_count > 1 {
+                        div {
+                            style: "display: flex; align-items: center; justify-content: center; gap: 10px; font-size: 12px; color: #4b5563;",
+                            UiButton {
+                                type: "button",
+                                variant: ButtonVariant::Outline,
+                                style: "padding: 6px 10px; font-size: 12px;",
+                                disabled: page_index == 0,
+                                onclick: move |_| on_pdf_page.call(page_index.saturating_sub(1)),
+                                "Previous"
+                            }
+                            span { "Page {page_number} of {page_count}" }
+                            UiButton {
+                                type: "button",
+                                variant: ButtonVariant::Outline,
+                                style: "padding: 6px 10px; font-size: 12px;",
+                                disabled: page_number >= page_count,
+                                onclick: move |_| on_pdf_page.call(page_number),
+                                "Next"
+                            }
+                        }
+                    }
+                }
+            }
+        }
         AttachmentPreview::Unsupported { mime_type, reason } => rsx! {
             div {
                 style: "display: flex; flex-direction: column; gap: 6px;",
@@ -1760,8 +2635,11 @@ fn render_attachment_preview(preview: AttachmentPreview, preview_title: &str) ->
     }
 }
 
+/// Track a pending change in the UI and queue it in the store's sync outbox,
+/// which keeps it across app restarts.
 fn enqueue_pending_sync_change(
     note_id: NoteId,
+    note_store: Option<Arc<MobileNoteStore>>,
     pending_sync_note_ids: &mut Signal<Vec<NoteId>>,
     pending_sync_count: &mut Signal<usize>,
 ) {
@@ -1770,6 +2648,14 @@ fn enqueue_pending_sync_change(
         pending_notes.push(note_id);
         pending_sync_count.set(pending_notes.len());
     }
+
+    if let Some(note_store) = note_store {
+        spawn(async move {
+            if let Err(error) = note_store.enqueue_sync_change(&note_id).await {
+                tracing::warn!("Failed to queue note {} for sync: {}", note_id, error);
+            }
+        });
+    }
 }
 
 fn clear_pending_sync_queue(
@@ -1882,6 +2768,48 @@ fn mobile_config_diagnostics(
     }
 }
 
+/// Masked settings and provisioning lines for the diagnostics bundle.
+fn diagnostics_config_lines(
+    diagnostics: &MobileConfigDiagnostics,
+    provisioning: &MobileProvisioningStatus,
+    sync_state_text: String,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("Sync state", sync_state_text),
+        ("Auth provisioning", provisioning.auth_status.clone()),
+        ("Sync provisioning", provisioning.sync_status.clone()),
+        ("Media provisioning", provisioning.media_status.clone()),
+        ("Config source", diagnostics.turso_active_source.clone()),
+        (
+            "Turso runtime endpoint",
+            diagnostics.turso_runtime_endpoint.clone(),
+        ),
+        (
+            "Turso runtime token",
+            diagnostics.turso_runtime_token_status.clone(),
+        ),
+        (
+            "Managed token endpoint",
+            diagnostics.turso_managed_auth_endpoint.clone(),
+        ),
+        ("Supabase URL", diagnostics.supabase_url.clone()),
+        (
+            "Supabase anon key",
+            diagnostics.supabase_anon_key_status.clone(),
+        ),
+        (
+            "Supabase auth config",
+            diagnostics.supabase_auth_status.clone(),
+        ),
+        ("Media bucket status", diagnostics.r2_bucket.clone()),
+        ("Media endpoint", diagnostics.r2_endpoint.clone()),
+        (
+            "Media credentials",
+            diagnostics.r2_credentials_status.clone(),
+        ),
+    ]
+}
+
 fn mobile_provisioning_status(
     diagnostics: &MobileConfigDiagnostics,
     auth_session: Option<&AuthSession>,
@@ -1997,6 +2925,7 @@ async fn refresh_managed_sync_token(
     sync_auth_client: Option<Arc<TursoSyncAuthClient>>,
     auth_session: Option<AuthSession>,
     _status_message: &mut Signal<Option<String>>,
+    sync_retry_at: &mut Signal<Option<i64>>,
 ) -> Result<Option<SyncToken>, String> {
     let Some(client) = sync_auth_client else {
         return Ok(None);
@@ -2005,10 +2934,28 @@ async fn refresh_managed_sync_token(
         return Ok(None);
     };
 
-    let token = client
-        .exchange_token(&session.access_token)
-        .await
-        .map_err(|error| error.to_string())?;
+    // Honor the API's rate-limit backoff instead of hammering the token endpoint.
+    let now = chrono::Utc::now().timestamp();
+    if let Some(retry_at) = sync_retry_at().filter(|retry_at| *retry_at > now) {
+        return Err(format!(
+            "rate limited; retrying in {}s",
+            retry_at.saturating_sub(now)
+        ));
+    }
+
+    let token = match client.exchange_token(&session.access_token).await {
+        Ok(token) => {
+            sync_retry_at.set(None);
+            token
+        }
+        Err(error) => {
+            if let Some(limited) = error.rate_limited() {
+                let retry_after = i64::try_from(limited.retry_after_secs).unwrap_or(i64::MAX);
+                sync_retry_at.set(Some(now.saturating_add(retry_after)));
+            }
+            return Err(error.to_string());
+        }
+    };
     let mut runtime_config = load_runtime_config();
     runtime_config.turso_database_url = Some(token.database_url.clone());
     save_runtime_config(&runtime_config)
@@ -2214,7 +3161,7 @@ mod tests {
         assert_eq!(attachment_kind_label("notes.txt", "text/plain"), "text");
         assert_eq!(
             attachment_kind_label("report.pdf", "application/pdf"),
-            "file"
+            "pdf"
         );
     }
 
@@ -2269,20 +3216,6 @@ mod tests {
         assert!(title.contains("+1"));
     }
 
-    #[test]
-    fn builds_managed_media_object_key() {
-        let note_id = NoteId::new();
-        let key = build_media_object_key(&note_id, "My File (Final).PNG");
-        assert!(key.starts_with(&format!("notes/{note_id}/")));
-        assert!(key.ends_with("-my-file-final.png"));
-    }
-
-    #[test]
-    fn sanitizes_media_token() {
-        assert_eq!(sanitize_media_token(" My  File__Name "), "my-file-name");
-        assert_eq!(sanitize_media_token("..."), "");
-    }
-
     #[test]
     fn provisioning_status_prompts_for_sign_in() {
         let diagnostics = diagnostics_fixture();
@@ -1,9 +1,32 @@
 //! Attachment helpers for mobile upload/preview UX.
 
+use std::sync::Arc;
+
 use base64::prelude::{Engine as _, BASE64_STANDARD};
+use chrono::{DateTime, Utc};
+use dirt_core::storage::{generate_thumbnail, render_pdf_page, ThumbnailFormat, ThumbnailOptions};
 
 pub const MAX_TEXT_PREVIEW_BYTES: usize = 256 * 1024;
 pub const MAX_MEDIA_PREVIEW_BYTES: usize = 8 * 1024 * 1024;
+const PDF_PAGE_OPTIONS: ThumbnailOptions = ThumbnailOptions {
+    max_width: 900,
+    max_height: 1200,
+    format: ThumbnailFormat::Png,
+    jpeg_quality: 80,
+};
+
+/// Longest-edge limits offered for camera photos, in pixels.
+pub const PHOTO_MAX_DIMENSION_CHOICES: [u32; 3] = [1280, 2048, 4096];
+pub const DEFAULT_PHOTO_MAX_DIMENSION: u32 = 2048;
+const PHOTO_JPEG_QUALITY: u8 = 85;
+
+/// Camera photo re-encoded for upload.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CapturedPhoto {
+    pub file_name: String,
+    pub mime_type: &'static str,
+    pub bytes: Vec<u8>,
+}
 
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub enum AttachmentPreview {
@@ -17,6 +40,13 @@ pub enum AttachmentPreview {
         mime_type: String,
         data_uri: String,
     },
+    PdfPage {
+        /// Source PDF, kept to render other pages on navigation.
+        bytes: Arc<[u8]>,
+        page_index: u16,
+        page_count: u16,
+        data_uri: String,
+    },
     Unsupported {
         mime_type: String,
         reason: String,
@@ -29,6 +59,7 @@ enum AttachmentKind {
     Audio,
     Video,
     Text,
+    Pdf,
     File,
 }
 
@@ -67,6 +98,7 @@ pub fn attachment_kind_label(file_name: &str, mime_type: &str) -> &'static str {
         AttachmentKind::Audio => "audio",
         AttachmentKind::Video => "video",
         AttachmentKind::Text => "text",
+        AttachmentKind::Pdf => "pdf",
         AttachmentKind::File => "file",
     }
 }
@@ -96,6 +128,7 @@ pub fn build_attachment_preview(
                 data_uri: format!("data:{mime_type};base64,{encoded}"),
             }
         }
+        AttachmentKind::Pdf => build_pdf_preview(Arc::from(bytes), 0),
         AttachmentKind::File => AttachmentPreview::Unsupported {
             mime_type: mime_type.to_string(),
             reason: "This file type does not have an in-app preview yet.".to_string(),
@@ -103,6 +136,57 @@ pub fn build_attachment_preview(
     }
 }
 
+/// Render one page of a PDF. Rasterizing blocks, so call off the UI thread.
+#[must_use]
+pub fn build_pdf_preview(bytes: Arc<[u8]>, page_index: u16) -> AttachmentPreview {
+    match render_pdf_page(&bytes, page_index, PDF_PAGE_OPTIONS) {
+        Ok(page) => {
+            let encoded = BASE64_STANDARD.encode(&page.image.bytes);
+            AttachmentPreview::PdfPage {
+                bytes,
+                page_index: page.page_index,
+                page_count: page.page_count,
+                data_uri: format!("data:image/png;base64,{encoded}"),
+            }
+        }
+        Err(error) => AttachmentPreview::Unsupported {
+            mime_type: "application/pdf".to_string(),
+            reason: error.to_string(),
+        },
+    }
+}
+
+/// Downscale a camera photo to fit `max_dimension` and re-encode it as JPEG.
+///
+/// Decoding and encoding block, so call off the UI thread.
+pub fn compress_captured_photo(
+    bytes: &[u8],
+    max_dimension: u32,
+    captured_at_ms: i64,
+) -> Result<CapturedPhoto, String> {
+    let photo = generate_thumbnail(
+        bytes,
+        ThumbnailOptions {
+            max_width: max_dimension,
+            max_height: max_dimension,
+            format: ThumbnailFormat::Jpeg,
+            jpeg_quality: PHOTO_JPEG_QUALITY,
+        },
+    )
+    .map_err(|error| format!("Failed to process photo: {error}"))?;
+
+    Ok(CapturedPhoto {
+        file_name: captured_photo_file_name(captured_at_ms),
+        mime_type: "image/jpeg",
+        bytes: photo.bytes,
+    })
+}
+
+fn captured_photo_file_name(captured_at_ms: i64) -> String {
+    let captured_at = DateTime::<Utc>::from_timestamp_millis(captured_at_ms).unwrap_or_default();
+    format!("photo-{}.jpg", captured_at.format("%Y%m%d-%H%M%S"))
+}
+
 fn decode_text_preview(bytes: &[u8]) -> (String, bool) {
     if bytes.len() <= MAX_TEXT_PREVIEW_BYTES {
         return (String::from_utf8_lossy(bytes).to_string(), false);
@@ -122,6 +206,8 @@ fn attachment_kind(file_name: &str, mime_type: &str) -> AttachmentKind {
         AttachmentKind::Video
     } else if normalized_mime.starts_with("text/") {
         AttachmentKind::Text
+    } else if normalized_mime == "application/pdf" {
+        AttachmentKind::Pdf
     } else {
         AttachmentKind::File
     }
@@ -155,6 +241,10 @@ mod tests {
         assert_eq!(attachment_kind_label("voice.wav", "audio/wav"), "audio");
         assert_eq!(attachment_kind_label("clip.mp4", "video/mp4"), "video");
         assert_eq!(attachment_kind_label("readme.md", "text/plain"), "text");
+        assert_eq!(
+            attachment_kind_label("report.pdf", "application/pdf"),
+            "pdf"
+        );
         assert_eq!(
             attachment_kind_label("archive.zip", "application/zip"),
             "file"
@@ -176,6 +266,47 @@ mod tests {
         assert!(matches!(image, AttachmentPreview::MediaDataUri { .. }));
     }
 
+    #[cfg(not(feature = "pdf-preview"))]
+    #[test]
+    fn pdf_preview_falls_back_without_renderer() {
+        let preview = build_attachment_preview("report.pdf", "application/pdf", b"%PDF-1.4\n");
+        assert!(matches!(
+            preview,
+            AttachmentPreview::Unsupported { reason, .. } if reason.contains("not enabled")
+        ));
+    }
+
+    /// Uncompressed 24-bit BMP of a single colour.
+    fn bmp(width: u32, height: u32) -> Vec<u8> {
+        let row = (width * 3).div_ceil(4) * 4;
+        let pixels = row * height;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"BM");
+        bytes.extend_from_slice(&(54 + pixels).to_le_bytes());
+        bytes.extend_from_slice(&[0; 4]);
+        bytes.extend_from_slice(&54_u32.to_le_bytes());
+        bytes.extend_from_slice(&40_u32.to_le_bytes());
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.extend_from_slice(&1_u16.to_le_bytes());
+        bytes.extend_from_slice(&24_u16.to_le_bytes());
+        bytes.extend_from_slice(&[0; 24]);
+        bytes.resize(bytes.len() + usize::try_from(pixels).unwrap(), 0x80);
+        bytes
+    }
+
+    #[test]
+    fn compresses_captured_photo_to_max_dimension() {
+        // 2024-06-01T08:30:00Z
+        let photo = compress_captured_photo(&bmp(400, 200), 100, 1_717_230_600_000).unwrap();
+
+        assert_eq!(photo.file_name, "photo-20240601-083000.jpg");
+        assert_eq!(photo.mime_type, "image/jpeg");
+        assert_eq!(&photo.bytes[..2], &[0xFF, 0xD8]);
+        assert!(compress_captured_photo(b"not a photo", 100, 0).is_err());
+        assert!(PHOTO_MAX_DIMENSION_CHOICES.contains(&DEFAULT_PHOTO_MAX_DIMENSION));
+    }
+
     #[test]
     fn marks_large_media_as_unsupported() {
         let bytes = vec![0_u8; MAX_MEDIA_PREVIEW_BYTES + 1];
@@ -8,13 +8,25 @@ use dirt_core::auth::{SessionPersistence, SupabaseAuthService as CoreSupabaseAut
 
 use crate::bootstrap_config::MobileBootstrapConfig;
 use crate::secret_store;
+use crate::workspace::active_workspace;
 
-#[derive(Debug, Clone, Copy, Default)]
-struct SessionStore;
+/// Session of the active workspace in secure storage.
+#[derive(Debug, Clone)]
+struct SessionStore {
+    key: String,
+}
+
+impl SessionStore {
+    fn for_active_workspace() -> Self {
+        Self {
+            key: active_workspace().session_key(secret_store::SECRET_SUPABASE_SESSION),
+        }
+    }
+}
 
 impl SessionPersistence for SessionStore {
     fn load(&self) -> AuthResult<Option<AuthSession>> {
-        match secret_store::read_secret(secret_store::SECRET_SUPABASE_SESSION) {
+        match secret_store::read_secret(&self.key) {
             Ok(Some(raw)) => Ok(Some(serde_json::from_str(&raw)?)),
             Ok(None) => Ok(None),
             Err(error) => Err(AuthError::SecureStorage(error)),
@@ -23,13 +35,11 @@ impl SessionPersistence for SessionStore {
 
     fn save(&self, session: &AuthSession) -> AuthResult<()> {
         let serialized = serde_json::to_string(session)?;
-        secret_store::write_secret(secret_store::SECRET_SUPABASE_SESSION, &serialized)
-            .map_err(AuthError::SecureStorage)
+        secret_store::write_secret(&self.key, &serialized).map_err(AuthError::SecureStorage)
     }
 
     fn clear(&self) -> AuthResult<()> {
-        secret_store::delete_secret(secret_store::SECRET_SUPABASE_SESSION)
-            .map_err(AuthError::SecureStorage)
+        secret_store::delete_secret(&self.key).map_err(AuthError::SecureStorage)
     }
 }
 
@@ -57,7 +67,11 @@ impl SupabaseAuthService {
 
     /// Create a service with explicit Supabase project URL and anon key.
     pub fn new(url: impl AsRef<str>, anon_key: impl Into<String>) -> AuthResult<Self> {
-        let inner = CoreSupabaseAuthService::with_session_store(url, anon_key, SessionStore)?;
+        let inner = CoreSupabaseAuthService::with_session_store(
+            url,
+            anon_key,
+            SessionStore::for_active_workspace(),
+        )?;
         Ok(Self { inner })
     }
 
@@ -2,6 +2,7 @@
 #![cfg_attr(not(target_os = "android"), allow(dead_code))]
 
 pub use dirt_core::config::BootstrapConfig as MobileBootstrapConfig;
+use dirt_core::config::{ClientPlatform, ManifestTarget};
 pub use dirt_core::util::normalize_text_option;
 
 /// Loads the generated mobile bootstrap JSON from `OUT_DIR`.
@@ -11,11 +12,15 @@ pub fn load_bootstrap_config() -> MobileBootstrapConfig {
         .unwrap_or_else(|error| panic!("Failed to parse mobile bootstrap config: {error}"))
 }
 
-/// Resolves runtime bootstrap config.
+/// Resolves runtime bootstrap config with the manifest's mobile overrides.
 pub async fn resolve_bootstrap_config(
     fallback: MobileBootstrapConfig,
 ) -> Result<MobileBootstrapConfig, String> {
-    dirt_core::config::resolve_bootstrap_config(fallback).await
+    dirt_core::config::resolve_bootstrap_config_for(
+        fallback,
+        ManifestTarget::current(ClientPlatform::Mobile),
+    )
+    .await
 }
 
 #[cfg(test)]
@@ -77,7 +82,7 @@ mod tests {
     fn parse_manifest_rejects_invalid_schema_version() {
         let payload = r#"
         {
-          "schema_version": 2,
+          "schema_version": 9,
           "manifest_version": "v1",
           "supabase_url": "https://project.supabase.co",
           "supabase_anon_key": "anon",
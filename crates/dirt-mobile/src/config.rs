@@ -8,6 +8,7 @@ use dirt_core::util::normalize_text_option;
 use dirt_core::Result;
 use serde::{Deserialize, Serialize};
 
+use crate::attachments::DEFAULT_PHOTO_MAX_DIMENSION;
 use crate::secret_store;
 
 const RUNTIME_CONFIG_FILE: &str = "mobile-config.json";
@@ -39,15 +40,25 @@ pub struct ResolvedSyncConfig {
 pub struct MobileRuntimeConfig {
     #[serde(default)]
     pub turso_database_url: Option<String>,
+    /// Longest edge camera photos are downscaled to before upload.
+    #[serde(default)]
+    pub photo_max_dimension: Option<u32>,
 }
 
 impl MobileRuntimeConfig {
     pub fn from_raw(url: Option<String>) -> Self {
         Self {
             turso_database_url: normalize_text_option(url),
+            photo_max_dimension: None,
         }
     }
 
+    pub fn photo_max_dimension(&self) -> u32 {
+        self.photo_max_dimension
+            .filter(|dimension| *dimension > 0)
+            .unwrap_or(DEFAULT_PHOTO_MAX_DIMENSION)
+    }
+
     pub const fn has_sync_url(&self) -> bool {
         self.turso_database_url.is_some()
     }
@@ -114,7 +125,10 @@ pub fn save_runtime_config_to_path(config: &MobileRuntimeConfig, path: &Path) ->
         std::fs::create_dir_all(parent)?;
     }
 
-    let normalized = MobileRuntimeConfig::from_raw(config.turso_database_url.clone());
+    let normalized = MobileRuntimeConfig {
+        photo_max_dimension: config.photo_max_dimension,
+        ..MobileRuntimeConfig::from_raw(config.turso_database_url.clone())
+    };
     let content = serde_json::to_string_pretty(&normalized)?;
     std::fs::write(path, content)?;
     Ok(())
@@ -253,7 +267,10 @@ mod tests {
         ));
         let config_path = test_dir.join("mobile-config.json");
 
-        let config = MobileRuntimeConfig::from_raw(Some(" libsql://runtime.turso.io ".to_string()));
+        let config = MobileRuntimeConfig {
+            photo_max_dimension: Some(1280),
+            ..MobileRuntimeConfig::from_raw(Some(" libsql://runtime.turso.io ".to_string()))
+        };
         save_runtime_config_to_path(&config, &config_path).unwrap();
 
         let loaded = load_runtime_config_from_path(&config_path);
@@ -261,6 +278,11 @@ mod tests {
             loaded.turso_database_url.as_deref(),
             Some("libsql://runtime.turso.io")
         );
+        assert_eq!(loaded.photo_max_dimension(), 1280);
+        assert_eq!(
+            MobileRuntimeConfig::default().photo_max_dimension(),
+            DEFAULT_PHOTO_MAX_DIMENSION
+        );
 
         let _ = std::fs::remove_file(config_path);
         let _ = std::fs::remove_dir_all(test_dir);
@@ -2,21 +2,34 @@
 
 #[cfg(target_os = "android")]
 use std::path::PathBuf;
-
-use dirt_core::models::{Attachment, AttachmentId, Note, NoteId, SyncConflict};
+use std::sync::Arc;
+
+use dirt_core::media::AttachmentCache;
+use dirt_core::models::{
+    Attachment, AttachmentId, AttachmentScanStatus, Note, NoteId, NoteSource, Settings,
+    SyncConflict,
+};
+use dirt_core::notifications::NotificationCategory;
 use dirt_core::services::DatabaseService as CoreDatabaseService;
+use dirt_core::storage::{prepare_image_upload, ImagePrivacyOptions};
+use dirt_core::transcription::append_voice_memo_transcript;
+use dirt_core::transfer::{receive_note_transfer, NoteTransfer, TransferReceipt};
 use dirt_core::{Error, Result};
 
 #[cfg(target_os = "android")]
-use crate::config::{default_mobile_data_directory, resolve_sync_config};
+use crate::config::resolve_sync_config;
+#[cfg(target_os = "android")]
+use crate::workspace::active_workspace;
 
 const DEFAULT_NOTES_LIMIT: usize = 100;
-const EXPORT_NOTES_PAGE_SIZE: usize = 500;
+const TAG_SUGGESTION_LIMIT: usize = 5;
 
 /// Thin async wrapper around shared core database service APIs.
 #[derive(Clone)]
 pub struct MobileNoteStore {
     db: CoreDatabaseService,
+    /// Downloaded attachments kept beside the database (`None` when turned off)
+    attachment_cache: Option<Arc<AttachmentCache>>,
 }
 
 impl MobileNoteStore {
@@ -30,15 +43,48 @@ impl MobileNoteStore {
         let resolved_sync_config =
             resolve_sync_config().map_err(|error| Error::InvalidInput(error.to_string()))?;
 
-        let db = CoreDatabaseService::open_path(db_path, resolved_sync_config.sync_config).await?;
-        Ok(Self { db })
+        let db = CoreDatabaseService::open_path(db_path.clone(), resolved_sync_config.sync_config)
+            .await?;
+        let attachment_cache = db
+            .load_settings()
+            .await?
+            .attachment_cache_mb
+            .filter(|max_mb| *max_mb > 0)
+            .and_then(|max_mb| {
+                AttachmentCache::beside_database(&db_path, max_mb)
+                    .map_err(|error| tracing::warn!("Failed to open the attachment cache: {error}"))
+                    .ok()
+            })
+            .map(Arc::new);
+        Ok(Self {
+            db,
+            attachment_cache,
+        })
     }
 
     /// Open an in-memory database for tests.
     #[cfg(test)]
     pub async fn open_in_memory() -> Result<Self> {
         let db = CoreDatabaseService::open_in_memory().await?;
-        Ok(Self { db })
+        Ok(Self {
+            db,
+            attachment_cache: None,
+        })
+    }
+
+    /// Cache that attachment previews read through, when it is turned on.
+    pub fn attachment_cache(&self) -> Option<&AttachmentCache> {
+        self.attachment_cache.as_deref()
+    }
+
+    /// Drop a deleted attachment from the attachment cache.
+    pub fn forget_cached_attachment(&self, object_key: &str) {
+        let Some(cache) = self.attachment_cache() else {
+            return;
+        };
+        if let Err(error) = cache.remove(object_key) {
+            tracing::warn!("Failed to remove {object_key} from the attachment cache: {error}");
+        }
     }
 
     /// List notes newest-first.
@@ -46,29 +92,51 @@ impl MobileNoteStore {
         self.db.list_notes(DEFAULT_NOTES_LIMIT, 0).await
     }
 
-    /// List all notes for full export operations.
+    /// List all notes, archived ones included, for full export operations.
     pub async fn list_all_notes(&self) -> Result<Vec<Note>> {
-        let mut notes = Vec::new();
-        let mut offset = 0usize;
-
-        loop {
-            let batch = self.db.list_notes(EXPORT_NOTES_PAGE_SIZE, offset).await?;
-            let count = batch.len();
-            notes.extend(batch);
-
-            if count < EXPORT_NOTES_PAGE_SIZE {
-                break;
-            }
-            offset += count;
-        }
+        self.db.list_exportable_notes(true).await
+    }
 
-        Ok(notes)
+    /// List all notes that may be exported, leaving out private notes.
+    pub async fn list_exportable_notes(&self) -> Result<Vec<Note>> {
+        let notes = self.list_all_notes().await?;
+        Ok(self.db.export_privacy(false).await?.filter(notes))
     }
 
-    /// Create a note.
+    /// Apply the stored image privacy settings to attachment bytes.
+    pub async fn prepare_attachment_bytes(&self, bytes: &[u8], mime_type: &str) -> Result<Vec<u8>> {
+        let settings = self.db.load_settings().await?;
+        prepare_image_upload(
+            bytes,
+            mime_type,
+            ImagePrivacyOptions::from_settings(&settings),
+        )
+    }
+
+    /// Encrypt attachment bytes for upload when notes are encrypted.
+    pub async fn encrypt_attachment_bytes(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        self.db.encrypt_attachment_bytes(bytes).await
+    }
+
+    /// Decrypt downloaded attachment bytes that were uploaded encrypted.
+    pub async fn decrypt_attachment_bytes(&self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        self.db.decrypt_attachment_bytes(bytes).await
+    }
+
+    /// Create a note from the mobile editor.
     pub async fn create_note(&self, content: &str) -> Result<Note> {
+        self.create_note_from(content, NoteSource::Mobile).await
+    }
+
+    /// Create a note, recording the interface it was captured from.
+    pub async fn create_note_from(&self, content: &str, source: NoteSource) -> Result<Note> {
         let normalized = normalize_content(content)?;
-        self.db.create_note(&normalized).await
+        self.db.create_note_from(&normalized, source).await
+    }
+
+    /// Open or import a note sent from another device.
+    pub async fn receive_transfer(&self, transfer: &NoteTransfer) -> Result<TransferReceipt> {
+        receive_note_transfer(&self.db, transfer).await
     }
 
     /// Update an existing note.
@@ -96,16 +164,52 @@ impl MobileNoteStore {
             .await
     }
 
+    /// Store text extracted from an attachment for search.
+    pub async fn set_attachment_text(
+        &self,
+        attachment_id: &AttachmentId,
+        text: Option<&str>,
+    ) -> Result<()> {
+        self.db.set_attachment_text(attachment_id, text).await
+    }
+
     /// List attachment metadata for a note.
     pub async fn list_attachments(&self, note_id: &NoteId) -> Result<Vec<Attachment>> {
         self.db.list_attachments(note_id).await
     }
 
+    /// List attachment metadata across all notes.
+    pub async fn list_all_attachments(&self) -> Result<Vec<Attachment>> {
+        self.db.list_all_attachments().await
+    }
+
     /// Soft delete attachment metadata by id.
     pub async fn delete_attachment(&self, attachment_id: &AttachmentId) -> Result<()> {
         self.db.delete_attachment(attachment_id).await
     }
 
+    /// Record the server-side scan result for an attachment.
+    pub async fn set_attachment_scan_status(
+        &self,
+        attachment_id: &AttachmentId,
+        status: AttachmentScanStatus,
+    ) -> Result<()> {
+        self.db
+            .set_attachment_scan_status(attachment_id, status)
+            .await
+    }
+
+    /// Suggest existing tags matching a typed prefix.
+    pub async fn suggest_tags(&self, prefix: &str) -> Result<Vec<String>> {
+        let tags = self.db.suggest_tags(prefix, TAG_SUGGESTION_LIMIT).await?;
+        Ok(tags.into_iter().map(|(name, _)| name).collect())
+    }
+
+    /// Record an accepted tag completion.
+    pub async fn record_tag_completion(&self, name: &str) -> Result<()> {
+        self.db.record_tag_completion(name).await
+    }
+
     /// List recently resolved sync conflicts.
     pub async fn list_conflicts(&self, limit: usize) -> Result<Vec<SyncConflict>> {
         self.db.list_conflicts(limit).await
@@ -116,6 +220,65 @@ impl MobileNoteStore {
         self.db.sync().await
     }
 
+    /// Queue a note change for the next sync, surviving app restarts.
+    pub async fn enqueue_sync_change(&self, note_id: &NoteId) -> Result<()> {
+        self.db.enqueue_sync_change(note_id).await
+    }
+
+    /// Notes changed since the last successful sync, oldest first.
+    pub async fn pending_sync_changes(&self) -> Result<Vec<NoteId>> {
+        self.db.pending_sync_changes().await
+    }
+
+    /// Whether voice memos are transcribed after they are attached.
+    pub async fn voice_memo_transcription_enabled(&self) -> Result<bool> {
+        Ok(self
+            .db
+            .load_settings()
+            .await?
+            .voice_memo_transcription_enabled)
+    }
+
+    /// Turn voice memo transcription on or off.
+    pub async fn set_voice_memo_transcription_enabled(&self, enabled: bool) -> Result<()> {
+        let settings = self.db.load_settings().await?;
+        self.db
+            .save_settings(&Settings {
+                voice_memo_transcription_enabled: enabled,
+                ..settings
+            })
+            .await
+    }
+
+    /// Stored settings, including which notifications are turned on.
+    pub async fn settings(&self) -> Result<Settings> {
+        self.db.load_settings().await
+    }
+
+    /// Turn a notification category on or off, returning the saved settings.
+    pub async fn set_notification_enabled(
+        &self,
+        category: NotificationCategory,
+        enabled: bool,
+    ) -> Result<Settings> {
+        let mut settings = self.db.load_settings().await?;
+        category.set_enabled(&mut settings, enabled);
+        self.db.save_settings(&settings).await?;
+        Ok(settings)
+    }
+
+    /// Append a voice memo transcript to its note, starting from
+    /// `latest_content` when the editor holds unsaved changes.
+    pub async fn append_voice_memo_transcript(
+        &self,
+        note_id: &NoteId,
+        file_name: &str,
+        transcript: &str,
+        latest_content: Option<String>,
+    ) -> Result<Option<Note>> {
+        append_voice_memo_transcript(&self.db, note_id, file_name, transcript, latest_content).await
+    }
+
     /// Check whether remote sync is enabled.
     pub async fn is_sync_enabled(&self) -> bool {
         self.db.is_sync_enabled().await
@@ -132,10 +295,10 @@ fn normalize_content(content: &str) -> Result<String> {
     Ok(normalized.to_string())
 }
 
-/// Build a mobile-friendly local DB path.
+/// Local DB path of the active workspace.
 #[cfg(target_os = "android")]
 pub fn default_db_path() -> PathBuf {
-    default_mobile_data_directory().join("dirt-mobile.db")
+    active_workspace().db_path
 }
 
 #[cfg(test)]
@@ -183,6 +346,16 @@ mod tests {
         assert_eq!(notes.len(), 3);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn suggests_tags_by_prefix() {
+        let store = MobileNoteStore::open_in_memory().await.unwrap();
+        store.create_note("Plan #work and #weekend").await.unwrap();
+        store.create_note("More #work").await.unwrap();
+
+        let tags = store.suggest_tags("w").await.unwrap();
+        assert_eq!(tags, vec!["work".to_string(), "weekend".to_string()]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn in_memory_store_sync_is_disabled() {
         let store = MobileNoteStore::open_in_memory().await.unwrap();
@@ -202,6 +375,33 @@ mod tests {
         assert!(conflicts.is_empty());
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn voice_memo_transcript_is_appended_once_enabled() {
+        let store = MobileNoteStore::open_in_memory().await.unwrap();
+        assert!(!store.voice_memo_transcription_enabled().await.unwrap());
+        store
+            .set_voice_memo_transcription_enabled(true)
+            .await
+            .unwrap();
+        assert!(store.voice_memo_transcription_enabled().await.unwrap());
+
+        let note = store.create_note("Groceries").await.unwrap();
+        let updated = store
+            .append_voice_memo_transcript(
+                &note.id,
+                "memo.m4a",
+                "Milk and eggs.",
+                Some("Groceries list".to_string()),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            updated.content,
+            "Groceries list\n\n[Voice memo transcript: memo.m4a]\nMilk and eggs."
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn attachment_metadata_roundtrip() {
         let store = MobileNoteStore::open_in_memory().await.unwrap();
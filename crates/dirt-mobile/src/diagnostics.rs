@@ -0,0 +1,303 @@
+//! Shareable diagnostics bundle for mobile bug reports.
+//!
+//! The bundle is a plain-text file with the app version, masked config
+//! diagnostics, recent sync errors, and the tail of the app log. Log lines
+//! are captured in memory by [`LogBuffer`], installed as a tracing writer at
+//! startup.
+#![cfg_attr(not(target_os = "android"), allow(dead_code))]
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use dirt_core::util::fs::write_atomic;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::config::default_mobile_data_directory;
+
+/// Log lines kept for the diagnostics bundle.
+pub const LOG_BUFFER_LINES: usize = 200;
+/// Sync errors kept for the diagnostics bundle.
+pub const RECENT_SYNC_ERROR_LIMIT: usize = 20;
+
+const DIAGNOSTICS_DIR_NAME: &str = "diagnostics";
+const REDACTED: &str = "[redacted]";
+
+/// Ring buffer of the most recent formatted log lines.
+#[derive(Debug)]
+pub struct LogBuffer {
+    capacity: usize,
+    lines: Mutex<VecDeque<String>>,
+}
+
+impl LogBuffer {
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lines: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Buffer shared by the tracing subscriber and the diagnostics export.
+    pub fn global() -> &'static Self {
+        static BUFFER: OnceLock<LogBuffer> = OnceLock::new();
+        BUFFER.get_or_init(|| Self::new(LOG_BUFFER_LINES))
+    }
+
+    /// Append the lines in `text`, dropping the oldest beyond capacity.
+    pub fn push_text(&self, text: &str) {
+        let mut lines = self
+            .lines
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            if lines.len() == self.capacity {
+                lines.pop_front();
+            }
+            lines.push_back(line.to_string());
+        }
+    }
+
+    /// Buffered lines, oldest first.
+    pub fn recent_lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+impl<'a> MakeWriter<'a> for &'static LogBuffer {
+    type Writer = LogBufferWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogBufferWriter { buffer: self }
+    }
+}
+
+/// Writer handed to the tracing formatter for one event.
+pub struct LogBufferWriter {
+    buffer: &'static LogBuffer,
+}
+
+impl io::Write for LogBufferWriter {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.buffer.push_text(&String::from_utf8_lossy(bytes));
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A failed sync attempt shown in the diagnostics bundle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncErrorRecord {
+    pub occurred_at_ms: i64,
+    pub message: String,
+}
+
+/// Record a sync error, keeping the newest [`RECENT_SYNC_ERROR_LIMIT`].
+pub fn push_sync_error(errors: &mut Vec<SyncErrorRecord>, record: SyncErrorRecord) {
+    errors.push(record);
+    let overflow = errors.len().saturating_sub(RECENT_SYNC_ERROR_LIMIT);
+    errors.drain(..overflow);
+}
+
+/// Everything that goes into a diagnostics bundle.
+#[derive(Clone, Debug, Default)]
+pub struct DiagnosticsReport {
+    pub generated_at_ms: i64,
+    pub app_version: String,
+    /// Already-masked `(label, value)` config and sync status pairs.
+    pub config: Vec<(&'static str, String)>,
+    pub sync_errors: Vec<SyncErrorRecord>,
+    pub log_lines: Vec<String>,
+}
+
+impl DiagnosticsReport {
+    /// Render the bundle as plain text; tokens in errors and logs are masked.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Dirt mobile diagnostics");
+        let _ = writeln!(out, "Generated: {}", format_timestamp(self.generated_at_ms));
+        let _ = writeln!(out, "App version: {}", self.app_version);
+        let _ = writeln!(
+            out,
+            "Target: {}/{}",
+            std::env::consts::ARCH,
+            std::env::consts::OS
+        );
+
+        let _ = writeln!(out, "\n== Configuration ==");
+        for (label, value) in &self.config {
+            let _ = writeln!(out, "{label}: {value}");
+        }
+
+        let _ = writeln!(
+            out,
+            "\n== Recent sync errors ({}) ==",
+            self.sync_errors.len()
+        );
+        if self.sync_errors.is_empty() {
+            let _ = writeln!(out, "None");
+        }
+        for error in &self.sync_errors {
+            let _ = writeln!(
+                out,
+                "{}  {}",
+                format_timestamp(error.occurred_at_ms),
+                mask_secrets(&error.message)
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "\n== Recent log (last {} lines) ==",
+            self.log_lines.len()
+        );
+        for line in &self.log_lines {
+            let _ = writeln!(out, "{}", mask_secrets(line));
+        }
+        out
+    }
+}
+
+/// Mask bearer tokens, JWTs, and `token=`/`key=` style values in `line`.
+pub fn mask_secrets(line: &str) -> String {
+    let mut masked = Vec::new();
+    let mut mask_next = false;
+    for word in line.split(' ') {
+        if mask_next && !word.is_empty() {
+            masked.push(REDACTED.to_string());
+            mask_next = false;
+            continue;
+        }
+        if word.eq_ignore_ascii_case("bearer") {
+            mask_next = true;
+            masked.push(word.to_string());
+        } else if word.starts_with("eyJ") {
+            masked.push(REDACTED.to_string());
+        } else if let Some((key, _)) = word.split_once('=').filter(|(key, _)| is_secret_key(key)) {
+            masked.push(format!("{key}={REDACTED}"));
+        } else {
+            masked.push(word.to_string());
+        }
+    }
+    masked.join(" ")
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    ["token", "key", "secret", "password"]
+        .iter()
+        .any(|secret| key.ends_with(secret))
+}
+
+/// File name for a bundle generated at `timestamp_ms`.
+pub fn diagnostics_file_name(timestamp_ms: i64) -> String {
+    let stamp = chrono::DateTime::from_timestamp_millis(timestamp_ms).map_or_else(
+        || timestamp_ms.to_string(),
+        |date_time| date_time.format("%Y%m%d-%H%M%S").to_string(),
+    );
+    format!("dirt-diagnostics-{stamp}.txt")
+}
+
+/// Folder bundles are written to before sharing; inside app storage so the
+/// share sheet can grant read access to it.
+pub fn diagnostics_directory() -> PathBuf {
+    default_mobile_data_directory().join(DIAGNOSTICS_DIR_NAME)
+}
+
+/// Write `report` into `directory` and return the file path.
+pub fn write_diagnostics_report(
+    report: &DiagnosticsReport,
+    directory: &Path,
+) -> io::Result<PathBuf> {
+    let path = directory.join(diagnostics_file_name(report.generated_at_ms));
+    write_atomic(&path, report.render())?;
+    Ok(path)
+}
+
+fn format_timestamp(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms).map_or_else(
+        || timestamp_ms.to_string(),
+        |date_time| date_time.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_buffer_keeps_newest_lines() {
+        let buffer = LogBuffer::new(3);
+        buffer.push_text("one\ntwo\n");
+        buffer.push_text("three\n\nfour\n");
+        assert_eq!(buffer.recent_lines(), vec!["two", "three", "four"]);
+    }
+
+    #[test]
+    fn push_sync_error_caps_history() {
+        let mut errors = Vec::new();
+        for index in 0..(RECENT_SYNC_ERROR_LIMIT as i64 + 5) {
+            push_sync_error(
+                &mut errors,
+                SyncErrorRecord {
+                    occurred_at_ms: index,
+                    message: format!("error {index}"),
+                },
+            );
+        }
+        assert_eq!(errors.len(), RECENT_SYNC_ERROR_LIMIT);
+        assert_eq!(errors[0].occurred_at_ms, 5);
+    }
+
+    #[test]
+    fn mask_secrets_hides_tokens() {
+        assert_eq!(
+            mask_secrets("Authorization: Bearer abc123 sent"),
+            "Authorization: Bearer [redacted] sent"
+        );
+        assert_eq!(
+            mask_secrets("got eyJhbGciOi.payload.sig back"),
+            "got [redacted] back"
+        );
+        assert_eq!(
+            mask_secrets("url=https://x.example auth_token=abc"),
+            "url=https://x.example auth_token=[redacted]"
+        );
+    }
+
+    #[test]
+    fn report_renders_all_sections() {
+        let report = DiagnosticsReport {
+            generated_at_ms: 0,
+            app_version: "1.2.3".to_string(),
+            config: vec![("Sync", "managed".to_string())],
+            sync_errors: vec![SyncErrorRecord {
+                occurred_at_ms: 1_000,
+                message: "401 with Bearer secret".to_string(),
+            }],
+            log_lines: vec!["INFO started".to_string()],
+        };
+
+        let rendered = report.render();
+        assert!(rendered.contains("Generated: 1970-01-01 00:00:00 UTC"));
+        assert!(rendered.contains("App version: 1.2.3"));
+        assert!(rendered.contains("Sync: managed"));
+        assert!(rendered.contains("1970-01-01 00:00:01 UTC  401 with Bearer [redacted]"));
+        assert!(!rendered.contains("secret"));
+        assert!(rendered.contains("== Recent log (last 1 lines) ==\nINFO started\n"));
+        assert_eq!(
+            diagnostics_file_name(0),
+            "dirt-diagnostics-19700101-000000.txt"
+        );
+    }
+}
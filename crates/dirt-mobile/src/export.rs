@@ -8,6 +8,7 @@ use dirt_core::export::{
     render_notes_export, suggested_export_file_name as core_suggested_export_file_name,
     ExportFormat,
 };
+use dirt_core::util::fs::write_atomic;
 use thiserror::Error;
 
 use crate::config::default_mobile_data_directory;
@@ -19,6 +20,21 @@ const EXPORT_DIR_NAME: &str = "dirt-exports";
 pub enum MobileExportFormat {
     Json,
     Markdown,
+    Org,
+    PlainText,
+}
+
+impl MobileExportFormat {
+    /// Name shown in export status messages.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Markdown => "Markdown",
+            Self::Org => "Org",
+            Self::PlainText => "plain text",
+        }
+    }
 }
 
 impl From<MobileExportFormat> for ExportFormat {
@@ -26,6 +42,8 @@ impl From<MobileExportFormat> for ExportFormat {
         match value {
             MobileExportFormat::Json => Self::Json,
             MobileExportFormat::Markdown => Self::Markdown,
+            MobileExportFormat::Org => Self::Org,
+            MobileExportFormat::PlainText => Self::PlainText,
         }
     }
 }
@@ -45,13 +63,10 @@ pub async fn export_notes_to_path(
     format: MobileExportFormat,
     output_path: &Path,
 ) -> Result<usize, MobileExportError> {
-    let notes = note_store.list_all_notes().await?;
+    let notes = note_store.list_exportable_notes().await?;
     let rendered = render_notes_export(&notes, format.into())?;
 
-    if let Some(parent) = output_path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    std::fs::write(output_path, rendered)?;
+    write_atomic(output_path, rendered)?;
     Ok(notes.len())
 }
 
@@ -84,6 +99,14 @@ mod tests {
             suggested_export_file_name(MobileExportFormat::Markdown, 456),
             "dirt-export-456.md"
         );
+        assert_eq!(
+            suggested_export_file_name(MobileExportFormat::Org, 789),
+            "dirt-export-789.org"
+        );
+        assert_eq!(
+            suggested_export_file_name(MobileExportFormat::PlainText, 789),
+            "dirt-export-789.txt"
+        );
     }
 
     #[tokio::test(flavor = "multi_thread")]
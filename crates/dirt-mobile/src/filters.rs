@@ -1,8 +1,9 @@
 //! Mobile note list filtering helpers (search + tag filtering).
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
 
-use dirt_core::Note;
+use dirt_core::search::SearchQuery;
+use dirt_core::{Note, NoteId};
 
 /// Return a sorted, deduplicated tag list discovered across notes.
 #[must_use]
@@ -16,17 +17,25 @@ pub fn collect_note_tags(notes: &[Note]) -> Vec<String> {
     tags.into_iter().collect()
 }
 
-/// Filter notes by case-insensitive text query and optional exact tag filter.
+/// Filter notes by search query and optional exact tag filter.
+///
+/// The query is parsed like `dirt search` queries (see [`SearchQuery`]);
+/// `attachment_note_ids` answers `has:attachment`.
 #[must_use]
-pub fn filter_notes(notes: &[Note], search_query: &str, tag_filter: Option<&str>) -> Vec<Note> {
-    let normalized_query = normalize_query(search_query);
+pub fn filter_notes(
+    notes: &[Note],
+    search_query: &str,
+    tag_filter: Option<&str>,
+    attachment_note_ids: &HashSet<NoteId>,
+) -> Vec<Note> {
+    let query = SearchQuery::parse_or_literal(search_query);
     let normalized_tag_filter = tag_filter
         .map(normalize_query)
         .filter(|value| !value.is_empty());
 
     notes
         .iter()
-        .filter(|note| note_matches_query(note, &normalized_query))
+        .filter(|note| query.matches(note, attachment_note_ids.contains(&note.id)))
         .filter(|note| note_matches_tag_filter(note, normalized_tag_filter.as_deref()))
         .cloned()
         .collect()
@@ -36,13 +45,6 @@ fn normalize_query(raw: &str) -> String {
     raw.trim().to_lowercase()
 }
 
-fn note_matches_query(note: &Note, query: &str) -> bool {
-    if query.is_empty() {
-        return true;
-    }
-    note.content.to_lowercase().contains(query)
-}
-
 fn note_matches_tag_filter(note: &Note, tag_filter: Option<&str>) -> bool {
     let Some(tag_filter) = tag_filter else {
         return true;
@@ -80,7 +82,7 @@ mod tests {
             Note::new("Standup notes #work"),
         ];
 
-        let filtered = filter_notes(&notes, "project", Some("work"));
+        let filtered = filter_notes(&notes, "project", Some("work"), &HashSet::new());
         assert_eq!(filtered.len(), 1);
         assert!(filtered[0].content.contains("kickoff"));
     }
@@ -92,8 +94,26 @@ mod tests {
             Note::new("Read a book #personal"),
         ];
 
-        let filtered = filter_notes(&notes, "DEBUG", Some("WORK"));
+        let filtered = filter_notes(&notes, "DEBUG", Some("WORK"), &HashSet::new());
         assert_eq!(filtered.len(), 1);
         assert!(filtered[0].content.contains("Debug"));
     }
+
+    #[test]
+    fn parses_structured_queries_like_dirt_search() {
+        let notes = vec![
+            Note::new("Project kickoff tomorrow #work"),
+            Note::new("Project movie night #personal"),
+            Note::new("Scanned receipt"),
+        ];
+        let with_attachment = HashSet::from([notes[2].id]);
+
+        let filtered = filter_notes(&notes, "tag:personal OR kickoff", None, &with_attachment);
+        assert_eq!(filtered.len(), 2);
+        let filtered = filter_notes(&notes, "has:attachment", None, &with_attachment);
+        assert_eq!(filtered.len(), 1);
+        assert!(filtered[0].content.contains("receipt"));
+        let filtered = filter_notes(&notes, "\"movie night\"", None, &with_attachment);
+        assert_eq!(filtered.len(), 1);
+    }
 }
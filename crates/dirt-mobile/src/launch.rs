@@ -1,4 +1,7 @@
-//! Launch argument parsing for mobile quick-capture and share-intent flows.
+//! Launch argument parsing for mobile quick-capture, share-intent, and
+//! scanned note-link flows.
+
+use dirt_core::transfer::is_note_transfer_uri;
 
 const QUICK_CAPTURE_FLAG: &str = "--quick-capture";
 const SHARE_TEXT_FLAG: &str = "--share-text";
@@ -9,6 +12,8 @@ const QUICK_CAPTURE_ENV_ENABLED: &str = "DIRT_QUICK_CAPTURE";
 const QUICK_CAPTURE_ENV_CONTENT: &str = "DIRT_QUICK_CAPTURE_CONTENT";
 #[cfg(target_os = "android")]
 const SHARE_TEXT_ENV_CONTENT: &str = "DIRT_SHARE_TEXT";
+#[cfg(target_os = "android")]
+const NOTE_LINK_ENV_CONTENT: &str = "DIRT_NOTE_LINK";
 
 /// Parsed quick-capture launch state.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -26,6 +31,8 @@ pub struct LaunchIntent {
     pub quick_capture: QuickCaptureLaunch,
     /// Shared text payload from share-intent style launches.
     pub share_text: Option<String>,
+    /// `dirt://note/...` link opened from a scanned QR code or shared as text.
+    pub note_link: Option<String>,
 }
 
 /// Detect launch intent settings from process arguments and environment.
@@ -34,6 +41,8 @@ pub fn detect_launch_intent_from_runtime() -> LaunchIntent {
     let env_quick_content = std::env::var(QUICK_CAPTURE_ENV_CONTENT).ok();
     let env_quick_enabled = std::env::var(QUICK_CAPTURE_ENV_ENABLED).ok();
     let env_share_text = std::env::var(SHARE_TEXT_ENV_CONTENT).ok();
+    // Taken rather than read so the running-app poll does not open it again.
+    let env_note_link = take_note_link_from_runtime();
 
     // Android NativeActivity launches do not provide reliable process argv.
     // Reading std::env::args() can crash on some devices/emulators, so use
@@ -43,25 +52,49 @@ pub fn detect_launch_intent_from_runtime() -> LaunchIntent {
         env_quick_content.as_deref(),
         env_quick_enabled.as_deref(),
         env_share_text.as_deref(),
+        env_note_link.as_deref(),
     )
 }
 
+/// Take a note link delivered to the running app, clearing it so it is
+/// handled once.
+///
+/// `MainActivity.onNewIntent` publishes scanned links through the environment
+/// after launch, so the app polls for them.
+#[cfg(target_os = "android")]
+pub fn take_note_link_from_runtime() -> Option<String> {
+    let link = std::env::var(NOTE_LINK_ENV_CONTENT)
+        .ok()
+        .and_then(|value| normalize_text(&value))?;
+    std::env::remove_var(NOTE_LINK_ENV_CONTENT);
+    Some(link)
+}
+
 /// Parse full launch intent state from explicit args/env inputs.
 pub fn parse_launch_intent<'a>(
     args: impl IntoIterator<Item = &'a str>,
     env_quick_content: Option<&str>,
     env_quick_enabled: Option<&str>,
     env_share_text: Option<&str>,
+    env_note_link: Option<&str>,
 ) -> LaunchIntent {
     let args: Vec<&str> = args.into_iter().collect();
 
     let quick_capture =
         parse_quick_capture_launch(args.iter().copied(), env_quick_content, env_quick_enabled);
-    let share_text = parse_share_text(args.iter().copied(), env_share_text);
+    let mut share_text = parse_share_text(args.iter().copied(), env_share_text);
+    let mut note_link = env_note_link
+        .and_then(normalize_text)
+        .filter(|link| is_note_transfer_uri(link));
+    // A link scanned by another app and shared as text opens the note too.
+    if note_link.is_none() && share_text.as_deref().is_some_and(is_note_transfer_uri) {
+        note_link = share_text.take();
+    }
 
     LaunchIntent {
         quick_capture,
         share_text,
+        note_link,
     }
 }
 
@@ -199,6 +232,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         );
 
         assert_eq!(parsed.share_text.as_deref(), Some("shared content"));
@@ -211,6 +245,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         );
 
         assert_eq!(parsed.share_text.as_deref(), Some("shared content"));
@@ -218,7 +253,8 @@ mod tests {
 
     #[test]
     fn parse_share_text_uses_env_payload_as_fallback() {
-        let parsed = parse_launch_intent(["dirt-mobile"], None, None, Some(" from share env "));
+        let parsed =
+            parse_launch_intent(["dirt-mobile"], None, None, Some(" from share env "), None);
 
         assert_eq!(parsed.share_text.as_deref(), Some("from share env"));
     }
@@ -236,6 +272,7 @@ mod tests {
             None,
             None,
             None,
+            None,
         );
 
         assert!(parsed.quick_capture.enabled);
@@ -245,4 +282,19 @@ mod tests {
         );
         assert_eq!(parsed.share_text.as_deref(), Some("shared text"));
     }
+
+    #[test]
+    fn parse_note_link_from_env_or_shared_text() {
+        let link = "dirt://note/01890a5d-ac96-774b-bcce-b302099a8057";
+
+        let parsed = parse_launch_intent(["dirt-mobile"], None, None, None, Some(link));
+        assert_eq!(parsed.note_link.as_deref(), Some(link));
+
+        let shared = parse_launch_intent(["dirt-mobile"], None, None, Some(link), None);
+        assert_eq!(shared.note_link.as_deref(), Some(link));
+        assert_eq!(shared.share_text, None);
+
+        let ignored = parse_launch_intent(["dirt-mobile"], None, None, None, Some("https://x"));
+        assert_eq!(ignored.note_link, None);
+    }
 }
@@ -15,6 +15,8 @@ mod config;
 #[cfg(any(target_os = "android", test))]
 mod data;
 #[cfg(any(target_os = "android", test))]
+mod diagnostics;
+#[cfg(any(target_os = "android", test))]
 mod export;
 #[cfg(any(target_os = "android", test))]
 mod filters;
@@ -22,22 +24,32 @@ mod filters;
 mod launch;
 #[cfg(any(target_os = "android", test))]
 mod media_api;
+#[cfg(target_os = "android")]
+mod scanner;
 #[cfg(any(target_os = "android", test))]
 mod secret_store;
+#[cfg(target_os = "android")]
+mod share;
 #[cfg(any(target_os = "android", test))]
 mod sync_auth;
 #[cfg(target_os = "android")]
 mod ui;
 #[cfg(any(target_os = "android", test))]
 mod voice_memo;
+#[cfg(any(target_os = "android", test))]
+mod workspace;
 
 #[cfg(target_os = "android")]
 fn main() {
+    use tracing_subscriber::fmt::writer::MakeWriterExt;
+
     tracing_subscriber::fmt()
         .with_env_filter(
             tracing_subscriber::EnvFilter::from_default_env()
                 .add_directive("dirt=info".parse().unwrap()),
         )
+        .with_ansi(false)
+        .with_writer(std::io::stdout.and(diagnostics::LogBuffer::global()))
         .init();
 
     tracing::info!("Starting Dirt mobile shell...");
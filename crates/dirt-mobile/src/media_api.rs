@@ -3,10 +3,16 @@
 
 use std::ops::Deref;
 
-use dirt_core::media::MediaApiClient as CoreMediaApiClient;
+use dirt_core::media::{
+    CancellationToken, MediaApiClient as CoreMediaApiClient, MediaCommitResult, MediaError,
+    MediaResult, UploadProgress,
+};
 
 use crate::bootstrap_config::MobileBootstrapConfig;
 
+/// Times an interrupted chunked upload is resumed before giving up.
+const UPLOAD_RESUME_ATTEMPTS: usize = 3;
+
 /// HTTP client for managed media operations backed by the Dirt API service.
 #[derive(Debug, Clone)]
 pub struct MediaApiClient {
@@ -35,17 +41,78 @@ impl MediaApiClient {
         self.inner.base_url()
     }
 
-    /// Uploads attachment bytes using a backend-issued presigned operation.
+    /// Uploads attachment bytes using backend-issued presigned operations.
+    ///
+    /// Large files go up in chunks; when a mobile connection drops part way,
+    /// the upload resumes from the last stored chunk. Interruptions a retry
+    /// cannot fix, such as an expired sign-in or a full quota, are returned
+    /// without resuming.
     pub async fn upload(
         &self,
         access_token: &str,
         object_key: &str,
         content_type: &str,
         bytes: &[u8],
-    ) -> Result<(), String> {
-        self.inner
-            .upload(access_token, object_key, content_type, bytes)
-            .await
+    ) -> MediaResult<()> {
+        self.upload_with_progress(
+            access_token,
+            object_key,
+            content_type,
+            bytes,
+            &CancellationToken::new(),
+            |_| {},
+        )
+        .await
+    }
+
+    /// Like [`Self::upload`], reporting progress after each chunk and
+    /// stopping when `cancel` fires.
+    pub async fn upload_with_progress(
+        &self,
+        access_token: &str,
+        object_key: &str,
+        content_type: &str,
+        bytes: &[u8],
+        cancel: &CancellationToken,
+        mut on_progress: impl FnMut(UploadProgress),
+    ) -> MediaResult<()> {
+        let mut result = self
+            .inner
+            .upload_with_progress(
+                access_token,
+                object_key,
+                content_type,
+                bytes,
+                cancel,
+                &mut on_progress,
+            )
+            .await;
+        for _ in 0..UPLOAD_RESUME_ATTEMPTS {
+            let Some(resume_token) = result
+                .as_ref()
+                .err()
+                .filter(|error| error.is_transient())
+                .and_then(MediaError::resume_token)
+            else {
+                break;
+            };
+            let resume_token = resume_token.to_string();
+            tracing::warn!("Attachment upload interrupted; resuming");
+            result = self
+                .inner
+                .resume_upload(access_token, &resume_token, bytes, cancel, &mut on_progress)
+                .await;
+        }
+        result
+    }
+
+    /// Asks the backend to validate and scan a finished upload.
+    pub async fn commit(
+        &self,
+        access_token: &str,
+        object_key: &str,
+    ) -> MediaResult<MediaCommitResult> {
+        self.inner.commit(access_token, object_key).await
     }
 
     /// Downloads attachment bytes using a backend-issued presigned operation.
@@ -53,12 +120,12 @@ impl MediaApiClient {
         &self,
         access_token: &str,
         object_key: &str,
-    ) -> Result<(Vec<u8>, Option<String>), String> {
+    ) -> MediaResult<(Vec<u8>, Option<String>)> {
         self.inner.download(access_token, object_key).await
     }
 
     /// Deletes an attachment object using a backend-issued presigned operation.
-    pub async fn delete(&self, access_token: &str, object_key: &str) -> Result<(), String> {
+    pub async fn delete(&self, access_token: &str, object_key: &str) -> MediaResult<()> {
         self.inner.delete(access_token, object_key).await
     }
 }
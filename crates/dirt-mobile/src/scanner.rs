@@ -0,0 +1,26 @@
+//! Android camera bridge for scanning note QR codes.
+//!
+//! Calls `MainActivity.openNoteScanner`, which opens the system camera. Camera
+//! apps that recognise QR codes hand the `dirt://note/...` link back to the
+//! app through its intent filter.
+
+use jni::objects::JObject;
+use jni::JavaVM;
+
+/// Open the camera so the user can scan a note shared from another device.
+#[allow(unsafe_code)]
+pub fn open_note_scanner() -> Result<(), String> {
+    let context = ndk_context::android_context();
+    // SAFETY: ndk-context holds the process-wide JavaVM pointer, valid for
+    // the lifetime of the app.
+    let vm = unsafe { JavaVM::from_raw(context.vm().cast()) }.map_err(|error| error.to_string())?;
+    // SAFETY: the context is a global reference to the running activity.
+    let activity = unsafe { JObject::from_raw(context.context().cast()) };
+    let mut env = vm
+        .attach_current_thread()
+        .map_err(|error| error.to_string())?;
+
+    env.call_method(&activity, "openNoteScanner", "()V", &[])
+        .map_err(|error| error.to_string())?;
+    Ok(())
+}
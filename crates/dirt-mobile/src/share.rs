@@ -0,0 +1,43 @@
+//! Android share sheet bridge.
+//!
+//! Calls `MainActivity.shareFile`, which wraps the file in a `FileProvider`
+//! URI and opens the system chooser.
+
+use std::path::Path;
+
+use jni::objects::{JObject, JValue};
+use jni::JavaVM;
+
+/// Open the share sheet for `path`, a file inside app storage.
+#[allow(unsafe_code)]
+pub fn share_file(path: &Path, mime_type: &str, title: &str) -> Result<(), String> {
+    let context = ndk_context::android_context();
+    // SAFETY: ndk-context holds the process-wide JavaVM pointer, valid for
+    // the lifetime of the app.
+    let vm = unsafe { JavaVM::from_raw(context.vm().cast()) }.map_err(|error| error.to_string())?;
+    // SAFETY: the context is a global reference to the running activity.
+    let activity = unsafe { JObject::from_raw(context.context().cast()) };
+    let mut env = vm
+        .attach_current_thread()
+        .map_err(|error| error.to_string())?;
+
+    let path = env
+        .new_string(path.to_string_lossy())
+        .map_err(|error| error.to_string())?;
+    let mime_type = env
+        .new_string(mime_type)
+        .map_err(|error| error.to_string())?;
+    let title = env.new_string(title).map_err(|error| error.to_string())?;
+    env.call_method(
+        &activity,
+        "shareFile",
+        "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;)V",
+        &[
+            JValue::Object(&path),
+            JValue::Object(&mime_type),
+            JValue::Object(&title),
+        ],
+    )
+    .map_err(|error| error.to_string())?;
+    Ok(())
+}
@@ -73,6 +73,7 @@ mod tests {
             token: "sensitive-token".to_string(),
             expires_at: 1_700_000_000,
             database_url: "libsql://example.turso.io".to_string(),
+            credential_epoch: None,
         };
         let debug_output = format!("{token:?}");
         assert!(!debug_output.contains("sensitive-token"));
@@ -142,6 +142,7 @@ pub fn UiInput(
             class: "ui-input",
             oninput: move |event| _ = oninput.map(|handler| handler(event)),
             onchange: move |event| _ = onchange.map(|handler| handler(event)),
+            onblur: move |event| _ = onblur.map(|handler| handler(event)),
             ..attributes,
             {children}
         }
@@ -152,6 +153,7 @@ pub fn UiInput(
 pub fn UiTextarea(
     oninput: Option<EventHandler<FormEvent>>,
     onchange: Option<EventHandler<FormEvent>>,
+    onblur: Option<EventHandler<FocusEvent>>,
     #[props(extends = GlobalAttributes)]
     #[props(extends = textarea)]
     attributes: Vec<Attribute>,
@@ -162,6 +164,7 @@ pub fn UiTextarea(
             class: "ui-textarea",
             oninput: move |event| _ = oninput.map(|handler| handler(event)),
             onchange: move |event| _ = onchange.map(|handler| handler(event)),
+            onblur: move |event| _ = onblur.map(|handler| handler(event)),
             ..attributes,
             {children}
         }
@@ -19,10 +19,22 @@ rsx! {
                         onclick: on_save_note,
                         if saving() { "Saving..." } else { "Save" }
                     }
-                    if saving() {
-                        p {
-                            style: "margin: 0; align-self: center; font-size: 12px; color: #6b7280;",
+                    p {
+                        style: "margin: 0; align-self: center; font-size: 12px; color: #6b7280;",
+                        if saving() {
                             "Saving..."
+                        } else if autosave().is_dirty() {
+                            "Unsaved changes"
+                        } else if selected_note_id().is_some() {
+                            "Saved"
+                        }
+                    }
+                    if autosave().is_dirty() && !discard_confirm_open() {
+                        UiButton {
+                            type: "button",
+                            variant: ButtonVariant::Outline,
+                            onclick: on_request_discard,
+                            "Discard"
                         }
                     }
                     if selected_note_id().is_some() {
@@ -37,6 +49,35 @@ rsx! {
                     }
                 }
 
+                if discard_confirm_open() {
+                    div {
+                        style: "
+                            padding: 10px 12px;
+                            display: flex;
+                            gap: 8px;
+                            align-items: center;
+                            background: #fef2f2;
+                        ",
+                        p {
+                            style: "margin: 0; flex: 1; font-size: 13px; color: #991b1b;",
+                            "Discard unsaved changes?"
+                        }
+                        UiButton {
+                            type: "button",
+                            variant: ButtonVariant::Outline,
+                            onclick: on_keep_editing,
+                            "Keep editing"
+                        }
+                        UiButton {
+                            type: "button",
+                            variant: ButtonVariant::Danger,
+                            disabled: saving(),
+                            onclick: on_discard_changes,
+                            "Discard"
+                        }
+                    }
+                }
+
                 Separator {
                     decorative: true,
                     style: "height: 1px; background: #e5e7eb;",
@@ -54,10 +95,77 @@ rsx! {
                     value: "{draft_content}",
                     placeholder: "Write your note...",
                     oninput: move |event: Event<FormData>| {
-                        draft_content.set(event.value());
-                        draft_dirty.set(true);
-                        draft_edit_version.set(draft_edit_version().saturating_add(1));
+                        let value = event.value();
+                        draft_content.set(value.clone());
+                        autosave.write().record_edit();
+                        refresh_tag_suggestions(value);
                     },
+                    onblur: move |_| save_draft(DraftSave::Auto),
+                }
+
+                if !tag_suggestions.read().is_empty() {
+                    div {
+                        style: "
+                            margin: 0 12px 12px 12px;
+                            display: flex;
+                            gap: 8px;
+                            flex-wrap: wrap;
+                        ",
+                        for tag in tag_suggestions() {
+                            UiButton {
+                                key: "{tag}",
+                                type: "button",
+                                variant: ButtonVariant::Outline,
+                                style: "padding: 6px 10px; font-size: 12px;",
+                                onclick: {
+                                    let tag = tag.clone();
+                                    move |_| accept_tag_suggestion(tag.clone())
+                                },
+                                "#{tag}"
+                            }
+                        }
+                    }
+                }
+
+                if !extract_tasks(&draft_content.read()).is_empty() {
+                    div {
+                        style: "
+                            margin: 0 12px 12px 12px;
+                            padding: 10px;
+                            border: 1px solid #e5e7eb;
+                            border-radius: 10px;
+                            background: #ffffff;
+                            display: flex;
+                            flex-direction: column;
+                            gap: 6px;
+                        ",
+                        p {
+                            style: "
+                                margin: 0;
+                                font-size: 12px;
+                                font-weight: 700;
+                                color: #6b7280;
+                                text-transform: uppercase;
+                                letter-spacing: 0.04em;
+                            ",
+                            "Tasks"
+                        }
+                        for task in extract_tasks(&draft_content.read()) {
+                            label {
+                                key: "{task.line_index}",
+                                style: "display: flex; gap: 8px; align-items: center; font-size: 14px; color: #111827;",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: task.done,
+                                    onchange: move |_| toggle_task(task.line_index),
+                                }
+                                span {
+                                    style: if task.done { "text-decoration: line-through; color: #6b7280;" } else { "" },
+                                    "{task.text}"
+                                }
+                            }
+                        }
+                    }
                 }
 
                 div {
@@ -95,6 +203,19 @@ rsx! {
                             div {
                                 style: "display: flex; gap: 8px; flex-wrap: wrap;",
                                 if voice_memo_state_value == VoiceMemoRecorderState::Idle {
+                                    label {
+                                        class: "ui-button ui-button--outline",
+                                        style: "padding: 6px 10px; font-size: 12px;",
+                                        input {
+                                            r#type: "file",
+                                            accept: "image/*",
+                                            "capture": "environment",
+                                            style: "display: none;",
+                                            disabled: attachment_uploading(),
+                                            onchange: on_capture_photo,
+                                        }
+                                        "Take photo"
+                                    }
                                     UiButton {
                                         type: "button",
                                         variant: ButtonVariant::Outline,
@@ -149,9 +270,24 @@ rsx! {
                     }
 
                     if attachment_uploading() {
-                        p {
-                            style: "margin: 0; font-size: 12px; color: #6b7280;",
-                            "Uploading attachment..."
+                        div {
+                            style: "display: flex; gap: 8px; align-items: center;",
+                            p {
+                                style: "margin: 0; font-size: 12px; color: #6b7280;",
+                                if let Some(progress) = attachment_upload_progress() {
+                                    "Uploading attachment... {progress.percent()}%"
+                                } else {
+                                    "Uploading attachment..."
+                                }
+                            }
+                            if let Some(cancel) = attachment_upload_cancel() {
+                                UiButton {
+                                    type: "button",
+                                    variant: ButtonVariant::Outline,
+                                    onclick: move |_| cancel.cancel(),
+                                    "Cancel"
+                                }
+                            }
                         }
                     }
                     if let Some(error) = attachment_upload_error() {
@@ -212,6 +348,15 @@ rsx! {
                                         style: "margin: 0; color: #6b7280; white-space: nowrap;",
                                         "{attachment_kind_label(&attachment.filename, &attachment.mime_type)}"
                                     }
+                                    if let Some(scan_label) = attachment.scan_status.label() {
+                                        p {
+                                            style: format!(
+                                                "margin: 0; white-space: nowrap; color: {};",
+                                                if attachment.scan_status.is_downloadable() { "#6b7280" } else { "#b91c1c" }
+                                            ),
+                                            "{scan_label}"
+                                        }
+                                    }
                                     p {
                                         style: "margin: 0; color: #6b7280; white-space: nowrap;",
                                         "{format_attachment_size(attachment.size_bytes)}"
@@ -224,13 +369,14 @@ rsx! {
                                         let attachment_for_preview = attachment.clone();
                                         let attachment_for_delete = attachment.clone();
                                         let deleting_now = deleting_attachment_id() == Some(attachment_id);
+                                        let downloadable = attachment.scan_status.is_downloadable();
 
                                         rsx! {
                                             UiButton {
                                                 type: "button",
                                                 variant: ButtonVariant::Outline,
                                                 style: "padding: 6px 10px; font-size: 12px;",
-                                                disabled: deleting_now,
+                                                disabled: deleting_now || !downloadable,
                                                 onclick: move |_| {
                                                     let attachment_for_preview =
                                                         attachment_for_preview.clone();
@@ -240,11 +386,13 @@ rsx! {
                                                     attachment_preview_content.set(AttachmentPreview::None);
                                                     attachment_preview_title.set(attachment_for_preview.filename.clone());
 
+                                                    let note_store = store.read().clone();
                                                     let media_api = media_api_client.read().clone();
                                                     let auth_session_value = auth_session();
                                                     spawn(async move {
                                                         match load_attachment_preview_from_r2(
                                                             &attachment_for_preview,
+                                                            note_store,
                                                             media_api,
                                                             auth_session_value,
                                                         )
@@ -283,9 +431,11 @@ rsx! {
                                                             Ok(()) => {
                                                                 enqueue_pending_sync_change(
                                                                     attachment_for_delete.note_id,
+                                                                    Some(note_store.clone()),
                                                                     &mut pending_sync_note_ids,
                                                                     &mut pending_sync_count,
                                                                 );
+                                                                note_store.forget_cached_attachment(&attachment_for_delete.r2_key);
                                                                 if let Err(error) = delete_attachment_object_from_r2(
                                                                     &attachment_for_delete.r2_key,
                                                                     media_api,
@@ -368,7 +518,7 @@ rsx! {
                                         "{error}"
                                     }
                                 } else {
-                                    {render_attachment_preview(attachment_preview_content(), &attachment_preview_title())}
+                                    {render_attachment_preview(attachment_preview_content(), &attachment_preview_title(), EventHandler::new(on_attachment_pdf_page))}
                                 }
                             }
                         }
@@ -49,6 +49,13 @@ rsx! {
                             onclick: on_new_note,
                             "New note"
                         }
+                        UiButton {
+                            type: "button",
+                            variant: ButtonVariant::Outline,
+                            style: "font-size: 14px; padding: 12px;",
+                            onclick: on_scan_note_code,
+                            "Scan QR"
+                        }
                     }
 
                     div {
@@ -190,7 +197,7 @@ rsx! {
                                             onclick: move |_| {
                                                 selected_note_id.set(Some(note_id));
                                                 draft_content.set(note_content.clone());
-                                                draft_dirty.set(false);
+                                                autosave.write().mark_clean();
                                                 status_message.set(None);
                                                 attachment_upload_error.set(None);
                                                 attachment_preview_open.set(false);
@@ -47,6 +47,12 @@ rsx! {
                             style: "margin: 0; font-size: 12px; color: #6b7280;",
                             "Consecutive sync failures: {consecutive_sync_failures}"
                         }
+                        if let Some(sync_rotation_text) = sync_rotation_text.as_ref() {
+                            p {
+                                style: "margin: 0; font-size: 12px; color: #6b7280;",
+                                "{sync_rotation_text}"
+                            }
+                        }
                         p {
                             style: "margin: 0; font-size: 12px; color: #6b7280;",
                             "Pending local changes: {pending_sync_count_value}"
@@ -130,6 +136,59 @@ rsx! {
                                 "Clear key"
                             }
                         }
+                        UiButton {
+                            type: "button",
+                            block: true,
+                            variant: ButtonVariant::Outline,
+                            onclick: on_toggle_voice_memo_transcription,
+                            if voice_memo_transcription_enabled() {
+                                "Transcribe voice memos: On"
+                            } else {
+                                "Transcribe voice memos: Off"
+                            }
+                        }
+                        p {
+                            style: "margin: 0; font-size: 12px; color: #6b7280;",
+                            "Sends voice memos to OpenAI and adds the transcript to the note."
+                        }
+                    }
+
+                    div {
+                        style: "
+                            padding: 12px;
+                            border: 1px solid #e5e7eb;
+                            border-radius: 12px;
+                            background: #ffffff;
+                            display: flex;
+                            flex-direction: column;
+                            gap: 8px;
+                            margin-bottom: 10px;
+                        ",
+                        p {
+                            style: "
+                                margin: 0;
+                                font-size: 12px;
+                                font-weight: 700;
+                                color: #6b7280;
+                                text-transform: uppercase;
+                                letter-spacing: 0.04em;
+                            ",
+                            "Notifications"
+                        }
+                        for category in NotificationCategory::ALL {
+                            UiButton {
+                                key: "{category.label()}",
+                                type: "button",
+                                block: true,
+                                variant: ButtonVariant::Outline,
+                                onclick: move |_| on_toggle_notification(category),
+                                if category.enabled_in(&notification_settings.read()) {
+                                    "{category.label()}: On"
+                                } else {
+                                    "{category.label()}: Off"
+                                }
+                            }
+                        }
                     }
 
                     div {
@@ -206,6 +265,107 @@ rsx! {
                         }
                     }
 
+                    div {
+                        style: "
+                            padding: 12px;
+                            border: 1px solid #e5e7eb;
+                            border-radius: 12px;
+                            background: #ffffff;
+                            display: flex;
+                            flex-direction: column;
+                            gap: 8px;
+                            margin-bottom: 10px;
+                        ",
+                        p {
+                            style: "
+                                margin: 0;
+                                font-size: 12px;
+                                font-weight: 700;
+                                color: #6b7280;
+                                text-transform: uppercase;
+                                letter-spacing: 0.04em;
+                            ",
+                            "Workspaces"
+                        }
+                        p {
+                            style: "margin: 0; font-size: 12px; color: #6b7280;",
+                            "Each workspace keeps its own notes and sign-in."
+                        }
+                        for name in workspaces() {
+                            div {
+                                key: "{name}",
+                                style: "display: flex; gap: 8px; align-items: center;",
+                                p {
+                                    style: "margin: 0; flex: 1; font-size: 14px; color: #111827;",
+                                    "{name}"
+                                }
+                                if name == active_workspace_name() {
+                                    p {
+                                        style: "margin: 0; font-size: 12px; color: #6b7280;",
+                                        "Active"
+                                    }
+                                } else {
+                                    UiButton {
+                                        type: "button",
+                                        variant: ButtonVariant::Outline,
+                                        disabled: loading(),
+                                        onclick: move |_| open_workspace(name.clone()),
+                                        "Switch"
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            style: "display: flex; gap: 8px;",
+                            UiInput {
+                                id: "workspace-name",
+                                r#type: "text",
+                                placeholder: "New workspace",
+                                value: "{workspace_name_input}",
+                                oninput: move |event: Event<FormData>| {
+                                    workspace_name_input.set(event.value());
+                                },
+                            }
+                            UiButton {
+                                type: "button",
+                                variant: ButtonVariant::Outline,
+                                disabled: workspace_name_input().trim().is_empty(),
+                                onclick: on_add_workspace,
+                                "Add"
+                            }
+                        }
+                    }
+
+                    if let Some(summary) = media_usage_summary() {
+                        div {
+                            style: "
+                                padding: 12px;
+                                border: 1px solid #e5e7eb;
+                                border-radius: 12px;
+                                background: #ffffff;
+                                display: flex;
+                                flex-direction: column;
+                                gap: 8px;
+                                margin-bottom: 10px;
+                            ",
+                            p {
+                                style: "
+                                    margin: 0;
+                                    font-size: 12px;
+                                    font-weight: 700;
+                                    color: #6b7280;
+                                    text-transform: uppercase;
+                                    letter-spacing: 0.04em;
+                                ",
+                                "Attachment Storage"
+                            }
+                            p {
+                                style: "margin: 0; font-size: 14px; color: #111827;",
+                                "{summary}"
+                            }
+                        }
+                    }
+
                     div {
                         style: "
                             padding: 12px;
@@ -293,6 +453,51 @@ rsx! {
                         }
                     }
 
+                    div {
+                        style: "
+                            padding: 12px;
+                            border: 1px solid #e5e7eb;
+                            border-radius: 12px;
+                            background: #ffffff;
+                            display: flex;
+                            flex-direction: column;
+                            gap: 8px;
+                            margin-bottom: 10px;
+                        ",
+                        p {
+                            style: "
+                                margin: 0;
+                                font-size: 12px;
+                                font-weight: 700;
+                                color: #6b7280;
+                                text-transform: uppercase;
+                                letter-spacing: 0.04em;
+                            ",
+                            "Camera photos"
+                        }
+                        p {
+                            style: "margin: 0; font-size: 12px; color: #6b7280;",
+                            "Photos are resized so the longest edge is at most {photo_max_dimension} px before upload."
+                        }
+                        div {
+                            style: "display: flex; gap: 8px;",
+                            for dimension in PHOTO_MAX_DIMENSION_CHOICES {
+                                UiButton {
+                                    key: "{dimension}",
+                                    type: "button",
+                                    block: true,
+                                    variant: if photo_max_dimension() == dimension {
+                                        ButtonVariant::Secondary
+                                    } else {
+                                        ButtonVariant::Outline
+                                    },
+                                    onclick: move |_| on_select_photo_max_dimension(dimension),
+                                    "{dimension} px"
+                                }
+                            }
+                        }
+                    }
+
                     div {
                         style: "
                             padding: 12px;
@@ -326,7 +531,7 @@ rsx! {
                                 block: true,
                                 variant: ButtonVariant::Outline,
                                 disabled: export_busy(),
-                                onclick: on_export_json,
+                                onclick: move |_| export_notes(MobileExportFormat::Json),
                                 if export_busy() { "Exporting..." } else { "Export JSON" }
                             }
                             UiButton {
@@ -334,10 +539,29 @@ rsx! {
                                 block: true,
                                 variant: ButtonVariant::Outline,
                                 disabled: export_busy(),
-                                onclick: on_export_markdown,
+                                onclick: move |_| export_notes(MobileExportFormat::Markdown),
                                 "Export Markdown"
                             }
                         }
+                        div {
+                            style: "display: flex; gap: 8px;",
+                            UiButton {
+                                type: "button",
+                                block: true,
+                                variant: ButtonVariant::Outline,
+                                disabled: export_busy(),
+                                onclick: move |_| export_notes(MobileExportFormat::Org),
+                                "Export Org"
+                            }
+                            UiButton {
+                                type: "button",
+                                block: true,
+                                variant: ButtonVariant::Outline,
+                                disabled: export_busy(),
+                                onclick: move |_| export_notes(MobileExportFormat::PlainText),
+                                "Export Text"
+                            }
+                        }
                     }
 
                     div {
@@ -370,6 +594,14 @@ rsx! {
                             style: "margin: 0; font-size: 12px; color: #6b7280;",
                             "Target: {std::env::consts::ARCH}/{std::env::consts::OS}"
                         }
+                        UiButton {
+                            type: "button",
+                            block: true,
+                            variant: ButtonVariant::Outline,
+                            disabled: diagnostics_busy(),
+                            onclick: on_export_diagnostics,
+                            if diagnostics_busy() { "Preparing..." } else { "Export diagnostics" }
+                        }
                     }
 
                     div {
@@ -0,0 +1,60 @@
+//! Workspaces on the phone: each has its own database and sign-in.
+#![cfg_attr(not(target_os = "android"), allow(dead_code))]
+
+use dirt_core::workspace::{Workspace, Workspaces};
+use dirt_core::Result;
+
+use crate::config::default_mobile_data_directory;
+
+/// Workspaces on this device; the default one uses the database the app
+/// used before workspaces existed.
+pub fn load_workspaces() -> Result<Workspaces> {
+    let root = default_mobile_data_directory();
+    Workspaces::load(&root, root.join("dirt-mobile.db"))
+}
+
+/// The workspace the app is using.
+pub fn active_workspace() -> Workspace {
+    match load_workspaces() {
+        Ok(workspaces) => workspaces.active().clone(),
+        Err(error) => {
+            tracing::warn!("Failed to load workspaces, using the default one: {error}");
+            let root = default_mobile_data_directory();
+            Workspaces::with_default(&root, root.join("dirt-mobile.db"))
+                .active()
+                .clone()
+        }
+    }
+}
+
+/// Make `name` the active workspace; the caller reinitializes the database
+/// and session.
+pub fn switch_workspace(name: &str) -> Result<Workspace> {
+    let mut workspaces = load_workspaces()?;
+    let workspace = workspaces.switch(name)?.clone();
+    workspaces.save()?;
+    Ok(workspace)
+}
+
+/// Add a workspace with its own database and sign-in.
+pub fn add_workspace(name: &str) -> Result<Workspace> {
+    let mut workspaces = load_workspaces()?;
+    let workspace = workspaces.add(name, None)?.clone();
+    workspaces.save()?;
+    Ok(workspace)
+}
+
+/// Names of all workspaces, the default one first.
+pub fn workspace_names() -> Vec<String> {
+    match load_workspaces() {
+        Ok(workspaces) => workspaces
+            .list()
+            .iter()
+            .map(|workspace| workspace.name.clone())
+            .collect(),
+        Err(error) => {
+            tracing::warn!("Failed to load workspaces: {error}");
+            Vec::new()
+        }
+    }
+}